@@ -39,7 +39,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let mut ao1 = AnalogOutput::new(1, "Damper Position".to_string());
     ao1.units = EngineeringUnits::Percent;
-    ao1.relinquish_default = 0.0;
+    ao1.commandable.set_relinquish_default(0.0);
     ao1.description = "VAV damper position control".to_string();
 
     let mut av1 = AnalogValue::new(1, "Setpoint".to_string());
@@ -55,7 +55,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mut bo1 = BinaryOutput::new(1, "Fan Control".to_string());
     bo1.active_text = "ON".to_string();
     bo1.inactive_text = "OFF".to_string();
-    bo1.relinquish_default = BinaryPV::Inactive;
+    bo1.commandable.set_relinquish_default(BinaryPV::Inactive);
 
     let mut bv1 = BinaryValue::new(1, "Override Switch".to_string());
     bv1.active_text = "OVERRIDE".to_string();
@@ -117,15 +117,15 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let ao_id = ObjectIdentifier::new(ObjectType::AnalogOutput, 1);
 
     let ai_props = vec![
-        PropertyReference::new(PropertyIdentifier::ObjectName as u32),
-        PropertyReference::new(PropertyIdentifier::PresentValue as u32),
-        PropertyReference::new(PropertyIdentifier::OutOfService as u32),
+        PropertyReference::new(u32::from(PropertyIdentifier::ObjectName)),
+        PropertyReference::new(u32::from(PropertyIdentifier::PresentValue)),
+        PropertyReference::new(u32::from(PropertyIdentifier::OutOfService)),
     ];
 
     let ao_props = vec![
-        PropertyReference::new(PropertyIdentifier::ObjectName as u32),
-        PropertyReference::new(PropertyIdentifier::PresentValue as u32),
-        PropertyReference::new(PropertyIdentifier::PriorityArray as u32),
+        PropertyReference::new(u32::from(PropertyIdentifier::ObjectName)),
+        PropertyReference::new(u32::from(PropertyIdentifier::PresentValue)),
+        PropertyReference::new(u32::from(PropertyIdentifier::PriorityArray)),
     ];
 
     let ai_spec = ReadAccessSpecification::new(ai_id, ai_props);
@@ -197,7 +197,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Write Property APDU
     let write_prop = WritePropertyRequest::with_priority(
         ao_id,
-        PropertyIdentifier::PresentValue as u32,
+        u32::from(PropertyIdentifier::PresentValue),
         vec![0x44, 0x42, 0x96, 0x00, 0x00], // Real 75.0 encoded
         8,
     );
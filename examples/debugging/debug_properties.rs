@@ -396,7 +396,7 @@ fn encode_rpm_request(
 
     for spec in &request.read_access_specifications {
         let object_id = encode_object_id(
-            spec.object_identifier.object_type as u16,
+            u16::from(spec.object_identifier.object_type),
             spec.object_identifier.instance,
         );
         buffer.push(0x0C);
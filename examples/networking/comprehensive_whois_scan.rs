@@ -332,7 +332,9 @@ fn analyze_device(
     // Read basic device properties
     println!("   📋 Reading device properties...");
 
-    if let Ok(name) = read_device_property(socket, device, PropertyIdentifier::ObjectName as u32) {
+    if let Ok(name) =
+        read_device_property(socket, device, u32::from(PropertyIdentifier::ObjectName))
+    {
         if let Ok(parsed_name) = parse_string_from_response(&name) {
             // Clean up device names with null bytes and control characters
             let cleaned_name = parsed_name
@@ -348,15 +350,19 @@ fn analyze_device(
         }
     }
 
-    if let Ok(model) = read_device_property(socket, device, PropertyIdentifier::ModelName as u32) {
+    if let Ok(model) =
+        read_device_property(socket, device, u32::from(PropertyIdentifier::ModelName))
+    {
         if let Ok(parsed_model) = parse_string_from_response(&model) {
             device.model_name = Some(parsed_model);
         }
     }
 
-    if let Ok(firmware) =
-        read_device_property(socket, device, PropertyIdentifier::FirmwareRevision as u32)
-    {
+    if let Ok(firmware) = read_device_property(
+        socket,
+        device,
+        u32::from(PropertyIdentifier::FirmwareRevision),
+    ) {
         if let Ok(parsed_firmware) = parse_string_from_response(&firmware) {
             device.firmware_revision = Some(parsed_firmware);
         }
@@ -380,7 +386,7 @@ fn analyze_device(
                     socket,
                     device,
                     &device.objects[i],
-                    PropertyIdentifier::ObjectName as u32,
+                    u32::from(PropertyIdentifier::ObjectName),
                 ) {
                     if let Ok(parsed_name) = parse_string_from_response(&name) {
                         // Clean up object names - remove null bytes and control characters
@@ -424,7 +430,7 @@ fn analyze_device(
                         socket,
                         device,
                         &device.objects[i],
-                        PropertyIdentifier::PresentValue as u32,
+                        u32::from(PropertyIdentifier::PresentValue),
                     ) {
                         // Special handling for binary objects
                         if let Ok(parsed_value) = parse_value_from_response(&value) {
@@ -443,7 +449,7 @@ fn analyze_device(
                             socket,
                             device,
                             &device.objects[i],
-                            PropertyIdentifier::OutputUnits as u32,
+                            u32::from(PropertyIdentifier::OutputUnits),
                         ) {
                             if let Ok(parsed_units) = parse_units_from_response(&units) {
                                 device.objects[i].description =
@@ -509,7 +515,7 @@ fn read_device_property(
 
     // Context tag 0: Object Identifier (BACnetObjectIdentifier)
     // Encode as 4-byte object identifier: (object_type << 22) | instance
-    let object_type = ObjectType::Device as u32; // 8
+    let object_type = u32::from(ObjectType::Device); // 8
     let object_id = (object_type << 22) | (device.device_id & 0x3FFFFF);
     apdu.push(0x0C); // Context tag [0], length 4
     apdu.extend_from_slice(&object_id.to_be_bytes());
@@ -535,7 +541,12 @@ fn try_read_object_list_multiple_approaches(
     device: &mut BACnetDevice,
 ) -> Result<usize, Box<dyn std::error::Error>> {
     // First try to read the array length (index 0)
-    match read_property_with_array_index(socket, device, PropertyIdentifier::ObjectList as u32, 0) {
+    match read_property_with_array_index(
+        socket,
+        device,
+        u32::from(PropertyIdentifier::ObjectList),
+        0,
+    ) {
         Ok(length_response) => {
             // Parse the length - it might be a number like "84" or an encoded value
             let length = length_response.parse::<u32>().unwrap_or(100);
@@ -546,7 +557,7 @@ fn try_read_object_list_multiple_approaches(
                 match read_property_with_array_index(
                     socket,
                     device,
-                    PropertyIdentifier::ObjectList as u32,
+                    u32::from(PropertyIdentifier::ObjectList),
                     i,
                 ) {
                     Ok(obj_response) => {
@@ -655,7 +666,7 @@ fn read_device_property_simple(
     ];
 
     // Object ID for device
-    let obj_id = ((ObjectType::Device as u32) << 22) | (device.device_id & 0x3FFFFF);
+    let obj_id = (u32::from(ObjectType::Device) << 22) | (device.device_id & 0x3FFFFF);
     apdu.push(0x0C); // Context tag 0, length 4
     apdu.extend_from_slice(&obj_id.to_be_bytes());
 
@@ -684,7 +695,7 @@ fn read_device_property_alternative(
     ];
 
     // Object ID for device
-    let obj_id = ((ObjectType::Device as u32) << 22) | (device.device_id & 0x3FFFFF);
+    let obj_id = (u32::from(ObjectType::Device) << 22) | (device.device_id & 0x3FFFFF);
     apdu.push(0x0C); // Context tag 0, length 4
     apdu.extend_from_slice(&obj_id.to_be_bytes());
 
@@ -701,7 +712,7 @@ fn read_object_list(
 ) -> Result<usize, Box<dyn std::error::Error>> {
     // First try to read the entire object list at once
     println!("   🔍 Attempting to read entire object list at once...");
-    match read_device_property(socket, device, PropertyIdentifier::ObjectList as u32) {
+    match read_device_property(socket, device, u32::from(PropertyIdentifier::ObjectList)) {
         Ok(obj_list_data) => {
             println!(
                 "   ✅ Received object list response: {} bytes",
@@ -737,7 +748,12 @@ fn read_object_list(
 
     // Fallback to reading array length first
     println!("   🔄 Falling back to reading object list by array indices...");
-    match read_property_with_array_index(socket, device, PropertyIdentifier::ObjectList as u32, 0) {
+    match read_property_with_array_index(
+        socket,
+        device,
+        u32::from(PropertyIdentifier::ObjectList),
+        0,
+    ) {
         Ok(length_str) => {
             if let Ok(length) = length_str.parse::<u32>() {
                 println!("   📊 Object list has {} items", length);
@@ -753,7 +769,7 @@ fn read_object_list(
                     match read_property_with_array_index(
                         socket,
                         device,
-                        PropertyIdentifier::ObjectList as u32,
+                        u32::from(PropertyIdentifier::ObjectList),
                         i,
                     ) {
                         Ok(obj_data) => {
@@ -796,7 +812,7 @@ fn read_object_list(
                 match read_property_with_array_index(
                     socket,
                     device,
-                    PropertyIdentifier::ObjectList as u32,
+                    u32::from(PropertyIdentifier::ObjectList),
                     i,
                 ) {
                     Ok(obj_data) => {
@@ -846,7 +862,7 @@ fn read_property_with_array_index(
 
     // Context tag 0: Object Identifier (BACnetObjectIdentifier)
     // Encode as 4-byte object identifier: (object_type << 22) | instance
-    let object_type = ObjectType::Device as u32; // 8
+    let object_type = u32::from(ObjectType::Device); // 8
     let object_id = (object_type << 22) | (device.device_id & 0x3FFFFF);
     apdu.push(0x0C); // Context tag [0], length 4
     apdu.extend_from_slice(&object_id.to_be_bytes());
@@ -897,7 +913,7 @@ fn read_object_property_simple(
     ];
 
     // Object ID
-    let obj_id = ((object.object_type as u32) << 22) | (object.instance & 0x3FFFFF);
+    let obj_id = (u32::from(object.object_type) << 22) | (object.instance & 0x3FFFFF);
     apdu.push(0x0C); // Context tag 0, length 4
     apdu.extend_from_slice(&obj_id.to_be_bytes());
 
@@ -931,7 +947,7 @@ fn read_object_property(
 
     // Context tag 0: Object Identifier (BACnetObjectIdentifier)
     // Encode as 4-byte object identifier: (object_type << 22) | instance
-    let object_id = ((object.object_type as u32) << 22) | (object.instance & 0x3FFFFF);
+    let object_id = (u32::from(object.object_type) << 22) | (object.instance & 0x3FFFFF);
     apdu.push(0x0C); // Context tag [0], length 4
     apdu.extend_from_slice(&object_id.to_be_bytes());
 
@@ -382,11 +382,11 @@ fn read_device_properties(socket: &UdpSocket, device: &mut RemoteDevice) {
 
     // First read basic device properties
     let basic_properties = vec![
-        (PropertyIdentifier::ObjectName as u32, "Object Name"),
-        (PropertyIdentifier::ModelName as u32, "Model Name"),
-        (PropertyIdentifier::VendorName as u32, "Vendor Name"),
+        (u32::from(PropertyIdentifier::ObjectName), "Object Name"),
+        (u32::from(PropertyIdentifier::ModelName), "Model Name"),
+        (u32::from(PropertyIdentifier::VendorName), "Vendor Name"),
         (
-            PropertyIdentifier::FirmwareRevision as u32,
+            u32::from(PropertyIdentifier::FirmwareRevision),
             "Firmware Revision",
         ),
     ];
@@ -445,7 +445,7 @@ fn read_device_properties(socket: &UdpSocket, device: &mut RemoteDevice) {
                     socket,
                     device,
                     obj_id,
-                    PropertyIdentifier::ObjectName as u32,
+                    u32::from(PropertyIdentifier::ObjectName),
                 ) {
                     Ok(name) => println!("      Name: {}", name),
                     Err(_) => println!("      Name: <unavailable>"),
@@ -457,7 +457,7 @@ fn read_device_properties(socket: &UdpSocket, device: &mut RemoteDevice) {
                         socket,
                         device,
                         obj_id,
-                        PropertyIdentifier::PresentValue as u32,
+                        u32::from(PropertyIdentifier::PresentValue),
                     ) {
                         println!("      Present Value: {}", value);
                     }
@@ -497,7 +497,7 @@ fn read_property(
 
     // Context tag 0: Object Identifier (BACnetObjectIdentifier)
     // Encode as 4-byte object identifier: (object_type << 22) | instance
-    let object_type = ObjectType::Device as u32; // 8
+    let object_type = u32::from(ObjectType::Device); // 8
     let object_id = (object_type << 22) | (device.device_id & 0x3FFFFF);
     apdu.push(0x0C); // Context tag [0], length 4
     apdu.extend_from_slice(&object_id.to_be_bytes());
@@ -575,24 +575,18 @@ fn read_property(
 
                             if resp_invoke_id == (invoke_id & 0x0F) {
                                 match pdu_type {
-                                    0x3 => {
-                                        // PDU_TYPE_COMPLEX_ACK
-                                        // Complex ACK format: [PDU_TYPE+invoke_id] [service_choice] [service_data...]
-                                        if apdu_data.len() >= 2 && apdu_data[1] == 0x0C {
-                                            // SERVICE_CONFIRMED_READ_PROPERTY
-                                            // Parse ReadProperty-ACK service data starting at byte 2
-                                            if let Ok(response) =
-                                                ReadPropertyResponse::decode(&apdu_data[2..])
-                                            {
-                                                return extract_string_value(
-                                                    &response.property_value,
-                                                );
-                                            } else {
-                                                // Try manual parsing if decode fails
-                                                return parse_read_property_ack_manual(
-                                                    &apdu_data[2..],
-                                                );
-                                            }
+                                    // PDU_TYPE_COMPLEX_ACK
+                                    // Complex ACK format: [PDU_TYPE+invoke_id] [service_choice] [service_data...]
+                                    0x3 if apdu_data.len() >= 2 && apdu_data[1] == 0x0C => {
+                                        // SERVICE_CONFIRMED_READ_PROPERTY
+                                        // Parse ReadProperty-ACK service data starting at byte 2
+                                        if let Ok(response) =
+                                            ReadPropertyResponse::decode(&apdu_data[2..])
+                                        {
+                                            return extract_string_value(&response.property_value);
+                                        } else {
+                                            // Try manual parsing if decode fails
+                                            return parse_read_property_ack_manual(&apdu_data[2..]);
                                         }
                                     }
                                     0x5 => {
@@ -778,7 +772,7 @@ fn read_object_list(
     device: &RemoteDevice,
 ) -> Result<Vec<BACnetObjectId>, Box<dyn std::error::Error>> {
     // Read Object-List property (property ID 76)
-    match read_property(socket, device, PropertyIdentifier::ObjectList as u32) {
+    match read_property(socket, device, u32::from(PropertyIdentifier::ObjectList)) {
         Ok(_raw_data) => {
             // The Object-List is typically too large to read at once, so we might get an error
             // Let's try reading it with array indices
@@ -799,7 +793,12 @@ fn read_object_list_with_indices(
     let mut objects = Vec::new();
 
     // First try to read index 0 to get the array length
-    match read_property_with_array_index(socket, device, PropertyIdentifier::ObjectList as u32, 0) {
+    match read_property_with_array_index(
+        socket,
+        device,
+        u32::from(PropertyIdentifier::ObjectList),
+        0,
+    ) {
         Ok(length_str) => {
             if let Ok(length) = length_str.parse::<u32>() {
                 println!("    Object-List has {} objects", length);
@@ -810,7 +809,7 @@ fn read_object_list_with_indices(
                     match read_property_with_array_index(
                         socket,
                         device,
-                        PropertyIdentifier::ObjectList as u32,
+                        u32::from(PropertyIdentifier::ObjectList),
                         i,
                     ) {
                         Ok(obj_data) => {
@@ -831,7 +830,7 @@ fn read_object_list_with_indices(
                 match read_property_with_array_index(
                     socket,
                     device,
-                    PropertyIdentifier::ObjectList as u32,
+                    u32::from(PropertyIdentifier::ObjectList),
                     i,
                 ) {
                     Ok(obj_data) => {
@@ -871,7 +870,7 @@ fn read_object_property(
 
     // ReadProperty Service Data
     // Context tag 0: Object Identifier
-    let object_type = object_id.object_type as u32;
+    let object_type = u32::from(object_id.object_type);
     let obj_id = (object_type << 22) | (object_id.instance & 0x3FFFFF);
     apdu.push(0x0C); // Context tag [0], length 4
     apdu.extend_from_slice(&obj_id.to_be_bytes());
@@ -972,7 +971,7 @@ fn read_property_with_array_index(
 
     // ReadProperty Service Data
     // Context tag 0: Object Identifier (Device)
-    let object_type = ObjectType::Device as u32;
+    let object_type = u32::from(ObjectType::Device);
     let obj_id = (object_type << 22) | (device.device_id & 0x3FFFFF);
     apdu.push(0x0C); // Context tag [0], length 4
     apdu.extend_from_slice(&obj_id.to_be_bytes());
@@ -78,7 +78,35 @@ impl ObjectInfo {
             ObjectType::LoadControl => "Load Control",
             ObjectType::StructuredView => "Structured View",
             ObjectType::AccessDoor => "Access Door",
+            ObjectType::AccessPoint => "Access Point",
+            ObjectType::AccessZone => "Access Zone",
+            ObjectType::CredentialDataInput => "Credential Data Input",
+            ObjectType::CharacterStringValue => "CharacterString Value",
+            ObjectType::IntegerValue => "Integer Value",
+            ObjectType::PositiveIntegerValue => "Positive Integer Value",
+            ObjectType::LargeAnalogValue => "Large Analog Value",
+            ObjectType::DatePatternValue => "Date Pattern Value",
+            ObjectType::DateValue => "Date Value",
+            ObjectType::DateTimePatternValue => "DateTime Pattern Value",
+            ObjectType::DateTimeValue => "DateTime Value",
+            ObjectType::TimePatternValue => "Time Pattern Value",
+            ObjectType::TimeValue => "Time Value",
+            ObjectType::BitStringValue => "BitString Value",
+            ObjectType::Channel => "Channel",
+            ObjectType::LightingOutput => "Lighting Output",
+            ObjectType::BinaryLightingOutput => "Binary Lighting Output",
+            ObjectType::NetworkPort => "Network Port",
+            ObjectType::ElevatorGroup => "Elevator Group",
+            ObjectType::Escalator => "Escalator",
+            ObjectType::Lift => "Lift",
+            ObjectType::AuditLog => "Audit Log",
+            ObjectType::AuditReporter => "Audit Reporter",
+            ObjectType::Timer => "Timer",
+            ObjectType::NotificationForwarder => "Notification Forwarder",
+            ObjectType::AlertEnrollment => "Alert Enrollment",
+            ObjectType::Staging => "Staging",
             ObjectType::OctetString => "Octet String",
+            ObjectType::Proprietary(_) => "Proprietary",
         }
         .to_string();
 
@@ -1031,7 +1059,7 @@ fn encode_rpm_request(
     for spec in &request.read_access_specifications {
         // Object identifier - context tag 0
         let object_id = encode_object_id(
-            spec.object_identifier.object_type as u16,
+            u16::from(spec.object_identifier.object_type),
             spec.object_identifier.instance,
         );
         buffer.push(0x0C); // Context tag 0, length 4
@@ -1173,36 +1201,36 @@ fn parse_rpm_response(
             .object_identifier
             .object_type
         {
-            ObjectType::AnalogInput | ObjectType::AnalogOutput | ObjectType::AnalogValue => {
-                if pos < data.len() && data[pos] == 0x44 {
-                    // Real value tag
-                    if let Some((value, consumed)) = extract_present_value(
-                        &data[pos..],
-                        objects_info[current_obj_index]
-                            .object_identifier
-                            .object_type,
-                    ) {
-                        // Debug: comment out for clean output
-                        // println!("Debug: Extracted present value: '{}'", value);
-                        objects_info[current_obj_index].present_value = Some(value);
-                        pos += consumed;
-                    }
+            ObjectType::AnalogInput | ObjectType::AnalogOutput | ObjectType::AnalogValue
+                if pos < data.len() && data[pos] == 0x44 =>
+            {
+                // Real value tag
+                if let Some((value, consumed)) = extract_present_value(
+                    &data[pos..],
+                    objects_info[current_obj_index]
+                        .object_identifier
+                        .object_type,
+                ) {
+                    // Debug: comment out for clean output
+                    // println!("Debug: Extracted present value: '{}'", value);
+                    objects_info[current_obj_index].present_value = Some(value);
+                    pos += consumed;
                 }
             }
-            ObjectType::BinaryInput | ObjectType::BinaryOutput | ObjectType::BinaryValue => {
-                if pos < data.len() && data[pos] == 0x11 {
-                    // Boolean value tag
-                    if let Some((value, consumed)) = extract_present_value(
-                        &data[pos..],
-                        objects_info[current_obj_index]
-                            .object_identifier
-                            .object_type,
-                    ) {
-                        // Debug: comment out for clean output
-                        // println!("Debug: Extracted present value: '{}'", value);
-                        objects_info[current_obj_index].present_value = Some(value);
-                        pos += consumed;
-                    }
+            ObjectType::BinaryInput | ObjectType::BinaryOutput | ObjectType::BinaryValue
+                if pos < data.len() && data[pos] == 0x11 =>
+            {
+                // Boolean value tag
+                if let Some((value, consumed)) = extract_present_value(
+                    &data[pos..],
+                    objects_info[current_obj_index]
+                        .object_identifier
+                        .object_type,
+                ) {
+                    // Debug: comment out for clean output
+                    // println!("Debug: Extracted present value: '{}'", value);
+                    objects_info[current_obj_index].present_value = Some(value);
+                    pos += consumed;
                 }
             }
             _ => {}
@@ -1388,6 +1416,34 @@ fn get_object_type_name(object_type: ObjectType) -> &'static str {
         ObjectType::LoadControl => "Load Control",
         ObjectType::StructuredView => "Structured View",
         ObjectType::AccessDoor => "Access Door",
+        ObjectType::AccessPoint => "Access Point",
+        ObjectType::AccessZone => "Access Zone",
+        ObjectType::CredentialDataInput => "Credential Data Input",
+        ObjectType::CharacterStringValue => "CharacterString Value",
+        ObjectType::IntegerValue => "Integer Value",
+        ObjectType::PositiveIntegerValue => "Positive Integer Value",
+        ObjectType::LargeAnalogValue => "Large Analog Value",
+        ObjectType::DatePatternValue => "Date Pattern Value",
+        ObjectType::DateValue => "Date Value",
+        ObjectType::DateTimePatternValue => "DateTime Pattern Value",
+        ObjectType::DateTimeValue => "DateTime Value",
+        ObjectType::TimePatternValue => "Time Pattern Value",
+        ObjectType::TimeValue => "Time Value",
+        ObjectType::BitStringValue => "BitString Value",
+        ObjectType::Channel => "Channel",
+        ObjectType::LightingOutput => "Lighting Output",
+        ObjectType::BinaryLightingOutput => "Binary Lighting Output",
+        ObjectType::NetworkPort => "Network Port",
+        ObjectType::ElevatorGroup => "Elevator Group",
+        ObjectType::Escalator => "Escalator",
+        ObjectType::Lift => "Lift",
+        ObjectType::AuditLog => "Audit Log",
+        ObjectType::AuditReporter => "Audit Reporter",
+        ObjectType::Timer => "Timer",
+        ObjectType::NotificationForwarder => "Notification Forwarder",
+        ObjectType::AlertEnrollment => "Alert Enrollment",
+        ObjectType::Staging => "Staging",
         ObjectType::OctetString => "Octet String",
+        ObjectType::Proprietary(_) => "Proprietary",
     }
 }
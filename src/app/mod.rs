@@ -37,6 +37,13 @@
 //! };
 //! ```
 
+/// Segmented transmission and reception of confirmed requests and
+/// ComplexACKs
+pub mod segmentation;
+
+/// Client and server transaction state machines
+pub mod tsm;
+
 #[cfg(feature = "std")]
 use std::error::Error;
 
@@ -52,8 +59,17 @@ use alloc::{string::String, vec::Vec};
 #[cfg(not(feature = "std"))]
 use core::time::Duration;
 
+use crate::app::tsm::{RequestOutcome, ServerTsm};
+use crate::encoding::{decode_enumerated, encode_enumerated};
 use crate::object::Segmentation;
-use crate::service::{AbortReason, ConfirmedServiceChoice, RejectReason, UnconfirmedServiceChoice};
+use crate::service::{
+    communication_control::handle_device_communication_control,
+    private_transfer::{handle_confirmed_private_transfer, handle_unconfirmed_private_transfer},
+    reinitialize_device::{handle_reinitialize_device, SERVICE_REQUEST_DENIED},
+    AbortReason, CommunicationControl, ConfirmedServiceChoice, PrivateTransferRegistry,
+    PropertyAccessError, ReinitializedState, RejectReason, Result as ServiceResult,
+    UnconfirmedServiceChoice,
+};
 
 /// Result type for application layer operations
 #[cfg(feature = "std")]
@@ -98,6 +114,34 @@ impl fmt::Display for ApplicationError {
 #[cfg(feature = "std")]
 impl Error for ApplicationError {}
 
+impl ApplicationError {
+    /// The PDU a server answers the confirmed request `invoke_id` with when
+    /// processing it fails with this error
+    ///
+    /// Requests that cannot be decoded are rejected; failures of the
+    /// transaction itself abort it.
+    pub fn to_apdu(&self, invoke_id: u8) -> Apdu {
+        match self {
+            ApplicationError::InvalidApdu(_) => Apdu::reject(invoke_id, RejectReason::InvalidTag),
+            ApplicationError::UnsupportedApduType => {
+                Apdu::abort(true, invoke_id, AbortReason::InvalidApduInThisState)
+            }
+            ApplicationError::SegmentationError(_) => {
+                Apdu::abort(true, invoke_id, AbortReason::SegmentationNotSupported)
+            }
+            ApplicationError::Timeout => {
+                Apdu::abort(true, invoke_id, AbortReason::ApplicationExceededReplyTime)
+            }
+            ApplicationError::MaxApduLengthExceeded => {
+                Apdu::abort(true, invoke_id, AbortReason::ApduTooLong)
+            }
+            ApplicationError::TransactionError(_) | ApplicationError::ServiceError(_) => {
+                Apdu::abort(true, invoke_id, AbortReason::Other)
+            }
+        }
+    }
+}
+
 /// APDU types
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
@@ -113,7 +157,7 @@ pub enum ApduType {
 }
 
 /// Application Protocol Data Unit
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Apdu {
     /// Confirmed service request
     ConfirmedRequest {
@@ -162,8 +206,11 @@ pub enum Apdu {
     Error {
         invoke_id: u8,
         service_choice: u8,
-        error_class: u8,
-        error_code: u8,
+        error_class: u32,
+        error_code: u32,
+        /// Service-specific parameters following the error type, empty for
+        /// services whose error is only a class and code
+        error_parameters: Vec<u8>,
     },
 
     /// Reject PDU
@@ -190,6 +237,37 @@ pub enum MaxSegments {
     GreaterThan64 = 7,
 }
 
+impl MaxSegments {
+    /// The number of segments, `None` when unspecified or more than 64
+    pub fn count(&self) -> Option<usize> {
+        match self {
+            MaxSegments::Unspecified | MaxSegments::GreaterThan64 => None,
+            MaxSegments::Two => Some(2),
+            MaxSegments::Four => Some(4),
+            MaxSegments::Eight => Some(8),
+            MaxSegments::Sixteen => Some(16),
+            MaxSegments::ThirtyTwo => Some(32),
+            MaxSegments::SixtyFour => Some(64),
+        }
+    }
+
+    /// The largest value not exceeding `count` segments, `Unspecified` for
+    /// `None`
+    pub fn from_count(count: Option<usize>) -> Self {
+        match count {
+            None => MaxSegments::Unspecified,
+            Some(count) if count > 64 => MaxSegments::GreaterThan64,
+            Some(count) if count >= 64 => MaxSegments::SixtyFour,
+            Some(count) if count >= 32 => MaxSegments::ThirtyTwo,
+            Some(count) if count >= 16 => MaxSegments::Sixteen,
+            Some(count) if count >= 8 => MaxSegments::Eight,
+            Some(count) if count >= 4 => MaxSegments::Four,
+            Some(count) if count >= 2 => MaxSegments::Two,
+            Some(_) => MaxSegments::Unspecified,
+        }
+    }
+}
+
 /// Maximum APDU size that can be accepted
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MaxApduSize {
@@ -213,6 +291,20 @@ impl MaxApduSize {
             MaxApduSize::Up1476 => 1476,
         }
     }
+
+    /// The largest size not exceeding `size` octets
+    pub fn from_size(size: usize) -> Self {
+        [
+            MaxApduSize::Up1476,
+            MaxApduSize::Up1024,
+            MaxApduSize::Up480,
+            MaxApduSize::Up206,
+            MaxApduSize::Up128,
+        ]
+        .into_iter()
+        .find(|max| max.size() <= size)
+        .unwrap_or(MaxApduSize::Up50)
+    }
 }
 
 /// Transaction state for confirmed services
@@ -246,6 +338,46 @@ pub enum TransactionState {
 }
 
 impl Apdu {
+    /// A Reject PDU for the request `invoke_id`
+    pub fn reject(invoke_id: u8, reason: RejectReason) -> Self {
+        Apdu::Reject {
+            invoke_id,
+            reject_reason: reason as u8,
+        }
+    }
+
+    /// An Abort PDU for the transaction `invoke_id`, sent by the server or
+    /// the client
+    pub fn abort(server: bool, invoke_id: u8, reason: AbortReason) -> Self {
+        Apdu::Abort {
+            server,
+            invoke_id,
+            abort_reason: reason as u8,
+        }
+    }
+
+    /// An Error PDU answering the request `invoke_id` for `service_choice`
+    pub fn error(invoke_id: u8, service_choice: u8, error: PropertyAccessError) -> Self {
+        Self::error_with_parameters(invoke_id, service_choice, error, Vec::new())
+    }
+
+    /// An Error PDU carrying service-specific parameters after the error
+    /// type, such as the first failed write of a WritePropertyMultiple
+    pub fn error_with_parameters(
+        invoke_id: u8,
+        service_choice: u8,
+        error: PropertyAccessError,
+        error_parameters: Vec<u8>,
+    ) -> Self {
+        Apdu::Error {
+            invoke_id,
+            service_choice,
+            error_class: error.error_class,
+            error_code: error.error_code,
+            error_parameters,
+        }
+    }
+
     /// Encode APDU to bytes
     pub fn encode(&self) -> Vec<u8> {
         let mut buffer = Vec::new();
@@ -393,6 +525,7 @@ impl Apdu {
                 service_choice,
                 error_class,
                 error_code,
+                error_parameters,
             } => {
                 // PDU Type
                 buffer.push((ApduType::Error as u8) << 4);
@@ -400,10 +533,19 @@ impl Apdu {
                 buffer.push(*invoke_id);
                 // Service choice
                 buffer.push(*service_choice);
-                // Error class
-                buffer.push(*error_class);
-                // Error code
-                buffer.push(*error_code);
+                // Error class and code, enumerated; an enumerated value of at
+                // most four octets always encodes. With parameters the error
+                // type is wrapped in context tag 0.
+                if error_parameters.is_empty() {
+                    let _ = encode_enumerated(&mut buffer, *error_class);
+                    let _ = encode_enumerated(&mut buffer, *error_code);
+                } else {
+                    buffer.push(0x0E);
+                    let _ = encode_enumerated(&mut buffer, *error_class);
+                    let _ = encode_enumerated(&mut buffer, *error_code);
+                    buffer.push(0x0F);
+                    buffer.extend_from_slice(error_parameters);
+                }
             }
 
             Apdu::Reject {
@@ -675,14 +817,33 @@ impl Apdu {
 
                 let invoke_id = data[1];
                 let service_choice = data[2];
-                let error_class = data[3];
-                let error_code = data[4];
+                let invalid =
+                    |_| ApplicationError::InvalidApdu("Invalid error class or code".into());
+                // The error type of a service-specific error is in context tag 0
+                let complex = data[3] == 0x0E;
+                let mut pos = if complex { 4 } else { 3 };
+                let (error_class, consumed) = decode_enumerated(&data[pos..]).map_err(invalid)?;
+                pos += consumed;
+                let (error_code, consumed) = decode_enumerated(&data[pos..]).map_err(invalid)?;
+                pos += consumed;
+
+                let error_parameters = if complex {
+                    if data.get(pos) != Some(&0x0F) {
+                        return Err(ApplicationError::InvalidApdu(
+                            "Unterminated error type".to_string(),
+                        ));
+                    }
+                    data[pos + 1..].to_vec()
+                } else {
+                    Vec::new()
+                };
 
                 Ok(Apdu::Error {
                     invoke_id,
                     service_choice,
                     error_class,
                     error_code,
+                    error_parameters,
                 })
             }
 
@@ -724,7 +885,7 @@ impl Apdu {
 }
 
 /// Invoke ID manager for handling transaction IDs
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InvokeIdManager {
     next_id: u8,
     active_ids: Vec<u8>,
@@ -1108,6 +1269,19 @@ pub struct ApplicationLayerHandler {
     transaction_manager: TransactionManager,
     /// Service processors
     service_processors: ServiceProcessors,
+    /// Communication state set by DeviceCommunicationControl
+    communication_control: CommunicationControl,
+    /// Confirmed requests answered, by source address, for duplicate
+    /// detection and segmented responses
+    server_tsm: ServerTsm<Vec<u8>>,
+    /// APDUs to send beyond the replies to received APDUs, with the station
+    /// each goes to
+    sends: Vec<(Vec<u8>, Apdu)>,
+    /// Password required by DeviceCommunicationControl and ReinitializeDevice
+    /// requests
+    password: Option<String>,
+    /// Handlers for vendor-defined private transfer services
+    private_transfer: PrivateTransferRegistry,
     /// Application statistics
     pub stats: ApplicationStatistics,
 }
@@ -1129,6 +1303,8 @@ impl Default for SupportedServices {
                 ConfirmedServiceChoice::WriteProperty,
                 ConfirmedServiceChoice::ReadPropertyMultiple,
                 ConfirmedServiceChoice::SubscribeCOV,
+                ConfirmedServiceChoice::DeviceCommunicationControl,
+                ConfirmedServiceChoice::ReinitializeDevice,
             ],
             unconfirmed: vec![
                 UnconfirmedServiceChoice::WhoIs,
@@ -1142,18 +1318,34 @@ impl Default for SupportedServices {
 /// Type alias for service processor function
 type ServiceProcessor = Box<dyn Fn(&[u8]) -> Result<Vec<u8>> + Send + Sync>;
 
+/// Type alias for the ReadProperty processor, whose errors are answered
+/// with the Error, Reject or Abort PDU they map to
+type ReadPropertyProcessor = Box<dyn Fn(&[u8]) -> ServiceResult<Vec<u8>> + Send + Sync>;
+
+/// Type alias for a confirmed service processor answering with the whole
+/// reply APDU for an invoke ID
+type ConfirmedServiceProcessor = Box<dyn Fn(u8, &[u8]) -> Apdu + Send + Sync>;
+
 /// Type alias for optional service processor function
 type OptionalServiceProcessor = Box<dyn Fn(&[u8]) -> Result<Option<Vec<u8>>> + Send + Sync>;
 
+/// Type alias for the host's ReinitializeDevice callback
+type ReinitializeProcessor =
+    Box<dyn Fn(ReinitializedState) -> core::result::Result<(), PropertyAccessError> + Send + Sync>;
+
 /// Service processors for handling different service types
 #[derive(Default)]
 struct ServiceProcessors {
     /// Read property processor
-    read_property: Option<ServiceProcessor>,
+    read_property: Option<ReadPropertyProcessor>,
     /// Write property processor
     write_property: Option<ServiceProcessor>,
+    /// WritePropertyMultiple processor
+    write_property_multiple: Option<ConfirmedServiceProcessor>,
     /// Who-Is processor
     who_is: Option<OptionalServiceProcessor>,
+    /// ReinitializeDevice processor
+    reinitialize_device: Option<ReinitializeProcessor>,
 }
 
 impl fmt::Debug for ServiceProcessors {
@@ -1161,7 +1353,12 @@ impl fmt::Debug for ServiceProcessors {
         f.debug_struct("ServiceProcessors")
             .field("read_property", &self.read_property.is_some())
             .field("write_property", &self.write_property.is_some())
+            .field(
+                "write_property_multiple",
+                &self.write_property_multiple.is_some(),
+            )
             .field("who_is", &self.who_is.is_some())
+            .field("reinitialize_device", &self.reinitialize_device.is_some())
             .finish()
     }
 }
@@ -1174,33 +1371,84 @@ impl ApplicationLayerHandler {
             supported_services: SupportedServices::default(),
             transaction_manager: TransactionManager::new(),
             service_processors: ServiceProcessors::default(),
+            communication_control: CommunicationControl::new(),
+            server_tsm: ServerTsm::new(&ApplicationConfig::default()),
+            sends: Vec::new(),
+            password: None,
+            private_transfer: PrivateTransferRegistry::new(),
             stats: ApplicationStatistics::default(),
         }
     }
 
-    /// Process an incoming APDU
-    pub fn process_apdu(&mut self, apdu: &Apdu, _source: &[u8]) -> Result<Option<Apdu>> {
+    /// Process an incoming APDU from the station `source`
+    ///
+    /// A confirmed request `source` sends again is answered with the
+    /// response to the first one without executing the service again; one
+    /// whose processing fails is answered with the Reject or Abort PDU for
+    /// the error. The segments of a segmented request are answered with
+    /// SegmentACKs, and the request executes once the last one arrives, its
+    /// response left for [`take_sends`](Self::take_sends) after the final
+    /// SegmentACK. A response longer than the client accepts is answered
+    /// with its first segment, and the segments its SegmentACKs ask for are
+    /// left for [`take_sends`](Self::take_sends) too.
+    pub fn process_apdu(&mut self, apdu: &Apdu, source: &[u8]) -> Result<Option<Apdu>> {
         self.stats.apdus_received += 1;
 
+        if !self.communication_control.accepts(apdu) {
+            self.stats.dropped_apdus += 1;
+            return Ok(None);
+        }
+
         match apdu {
-            Apdu::ConfirmedRequest {
-                segmented,
-                more_follows,
-                segmented_response_accepted,
-                max_segments: _,
-                max_response_size: _,
-                invoke_id,
-                sequence_number: _,
-                proposed_window_size: _,
-                service_choice,
-                service_data,
-            } => {
+            Apdu::ConfirmedRequest { .. } => {
+                let source = source.to_vec();
+                let reassembled;
+                let (ack, request) = match self.server_tsm.request(&source, apdu) {
+                    RequestOutcome::Execute => (None, apdu),
+                    RequestOutcome::InProgress => {
+                        self.stats.duplicate_requests += 1;
+                        return Ok(None);
+                    }
+                    RequestOutcome::Resend(response) => {
+                        self.stats.duplicate_requests += 1;
+                        return Ok(Some(response));
+                    }
+                    RequestOutcome::Segment(reply) => return Ok(reply),
+                    RequestOutcome::Reassembled { ack, request } => {
+                        reassembled = request;
+                        (Some(ack), &reassembled)
+                    }
+                };
+                let Apdu::ConfirmedRequest {
+                    segmented,
+                    more_follows,
+                    segmented_response_accepted,
+                    invoke_id,
+                    service_choice,
+                    service_data,
+                    ..
+                } = request
+                else {
+                    return Err(ApplicationError::UnsupportedApduType);
+                };
                 let pdu_flags = PduFlags {
                     segmented: *segmented,
                     more_follows: *more_follows,
                     segmented_response_accepted: *segmented_response_accepted,
                 };
-                self.process_confirmed_request(pdu_flags, *invoke_id, *service_choice, service_data)
+                let response = self
+                    .process_confirmed_request(pdu_flags, *invoke_id, *service_choice, service_data)
+                    .unwrap_or_else(|error| Some(error.to_apdu(*invoke_id)));
+                let response = self.server_tsm.respond(&source, *invoke_id, response);
+                // The SegmentACK for the last segment goes before the response
+                match ack {
+                    Some(ack) => {
+                        self.sends
+                            .extend(response.map(|response| (source, response)));
+                        Ok(Some(ack))
+                    }
+                    None => Ok(response),
+                }
             }
             Apdu::UnconfirmedRequest {
                 service_choice,
@@ -1231,16 +1479,32 @@ impl ApplicationLayerHandler {
                 service_choice,
                 error_class,
                 error_code,
+                ..
             } => self.process_error(*invoke_id, *service_choice, *error_class, *error_code),
             Apdu::Reject {
                 invoke_id,
                 reject_reason,
             } => self.process_reject(*invoke_id, *reject_reason),
+            Apdu::SegmentAck { server: false, .. } => {
+                let source = source.to_vec();
+                let segments = self.server_tsm.segment_ack(&source, apdu);
+                self.sends.extend(
+                    segments
+                        .into_iter()
+                        .map(|segment| (source.clone(), segment)),
+                );
+                Ok(None)
+            }
             Apdu::Abort {
                 server,
                 invoke_id,
                 abort_reason,
-            } => self.process_abort(*server, *invoke_id, *abort_reason),
+            } => {
+                if !*server {
+                    self.server_tsm.forget(&source.to_vec(), *invoke_id);
+                }
+                self.process_abort(*server, *invoke_id, *abort_reason)
+            }
             _ => {
                 self.stats.unknown_apdus += 1;
                 Err(ApplicationError::UnsupportedApduType)
@@ -1259,10 +1523,10 @@ impl ApplicationLayerHandler {
         self.stats.confirmed_requests += 1;
 
         if !self.supported_services.confirmed.contains(&service_choice) {
-            return Ok(Some(Apdu::Reject {
+            return Ok(Some(Apdu::reject(
                 invoke_id,
-                reject_reason: RejectReason::UnrecognizedService as u8,
-            }));
+                RejectReason::UnrecognizedService,
+            )));
         }
 
         // Process based on service type
@@ -1279,27 +1543,45 @@ impl ApplicationLayerHandler {
                             service_choice: service_choice as u8,
                             service_data: response_data,
                         })),
-                        Err(_) => {
-                            Ok(Some(Apdu::Error {
-                                invoke_id,
-                                service_choice: service_choice as u8,
-                                error_class: 0, // Object
-                                error_code: 0,  // Unknown object
-                            }))
-                        }
+                        Err(error) => Ok(Some(error.to_apdu(invoke_id, service_choice as u8))),
                     }
                 } else {
-                    Ok(Some(Apdu::Abort {
-                        server: true,
+                    Ok(Some(Apdu::abort(true, invoke_id, AbortReason::Other)))
+                }
+            }
+            ConfirmedServiceChoice::WritePropertyMultiple => {
+                match self.service_processors.write_property_multiple {
+                    Some(ref processor) => Ok(Some(processor(invoke_id, service_data))),
+                    None => Ok(Some(Apdu::reject(
                         invoke_id,
-                        abort_reason: AbortReason::Other as u8,
-                    }))
+                        RejectReason::UnrecognizedService,
+                    ))),
                 }
             }
-            _ => Ok(Some(Apdu::Reject {
+            ConfirmedServiceChoice::DeviceCommunicationControl => {
+                Ok(Some(handle_device_communication_control(
+                    &mut self.communication_control,
+                    self.password.as_deref(),
+                    invoke_id,
+                    service_data,
+                )))
+            }
+            ConfirmedServiceChoice::ReinitializeDevice => Ok(Some(handle_reinitialize_device(
+                self.password.as_deref(),
                 invoke_id,
-                reject_reason: RejectReason::UnrecognizedService as u8,
-            })),
+                service_data,
+                |state| match self.service_processors.reinitialize_device {
+                    Some(ref processor) => processor(state),
+                    None => Err(SERVICE_REQUEST_DENIED),
+                },
+            ))),
+            ConfirmedServiceChoice::ConfirmedPrivateTransfer => Ok(Some(
+                handle_confirmed_private_transfer(&self.private_transfer, invoke_id, service_data),
+            )),
+            _ => Ok(Some(Apdu::reject(
+                invoke_id,
+                RejectReason::UnrecognizedService,
+            ))),
         }
     }
 
@@ -1311,6 +1593,11 @@ impl ApplicationLayerHandler {
     ) -> Result<Option<Apdu>> {
         self.stats.unconfirmed_requests += 1;
 
+        if service_choice == UnconfirmedServiceChoice::UnconfirmedPrivateTransfer {
+            let _ = handle_unconfirmed_private_transfer(&self.private_transfer, service_data);
+            return Ok(None);
+        }
+
         // Unconfirmed requests don't get responses unless it's I-Am for Who-Is
         if service_choice == UnconfirmedServiceChoice::WhoIs {
             if let Some(ref processor) = self.service_processors.who_is {
@@ -1351,8 +1638,8 @@ impl ApplicationLayerHandler {
         &mut self,
         invoke_id: u8,
         _service_choice: u8,
-        error_class: u8,
-        error_code: u8,
+        error_class: u32,
+        error_code: u32,
     ) -> Result<Option<Apdu>> {
         self.stats.errors += 1;
         self.transaction_manager
@@ -1381,14 +1668,32 @@ impl ApplicationLayerHandler {
         Ok(None)
     }
 
-    /// Set a service processor
+    /// Set the ReadProperty processor, returning the encoded ReadProperty-ACK
+    /// or the error to answer with
     pub fn set_read_property_handler<F>(&mut self, handler: F)
     where
-        F: Fn(&[u8]) -> Result<Vec<u8>> + Send + Sync + 'static,
+        F: Fn(&[u8]) -> ServiceResult<Vec<u8>> + Send + Sync + 'static,
     {
         self.service_processors.read_property = Some(Box::new(handler));
     }
 
+    /// Set the WritePropertyMultiple processor, answering with the reply APDU
+    /// for the invoke ID and service data it is given
+    ///
+    /// [`handle_write_property_multiple`](crate::service::write_property_multiple::handle_write_property_multiple)
+    /// answers from an object database. Setting the processor adds the
+    /// service to the supported services.
+    pub fn set_write_property_multiple_handler<F>(&mut self, handler: F)
+    where
+        F: Fn(u8, &[u8]) -> Apdu + Send + Sync + 'static,
+    {
+        self.service_processors.write_property_multiple = Some(Box::new(handler));
+        let confirmed = &mut self.supported_services.confirmed;
+        if !confirmed.contains(&ConfirmedServiceChoice::WritePropertyMultiple) {
+            confirmed.push(ConfirmedServiceChoice::WritePropertyMultiple);
+        }
+    }
+
     /// Set Who-Is processor
     pub fn set_who_is_handler<F>(&mut self, handler: F)
     where
@@ -1396,6 +1701,99 @@ impl ApplicationLayerHandler {
     {
         self.service_processors.who_is = Some(Box::new(handler));
     }
+
+    /// Set ReinitializeDevice processor
+    ///
+    /// The processor performs the restart, backup or restore step once the
+    /// password has been checked. Without one, requests are denied.
+    pub fn set_reinitialize_device_handler<F>(&mut self, handler: F)
+    where
+        F: Fn(ReinitializedState) -> core::result::Result<(), PropertyAccessError>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.service_processors.reinitialize_device = Some(Box::new(handler));
+    }
+
+    /// Register the handler for a vendor's private transfer service
+    ///
+    /// The handler receives the encoded service parameters and returns the
+    /// encoded result block. Registering the first handler adds the private
+    /// transfer services to the supported services.
+    pub fn register_private_transfer<F>(&mut self, vendor_id: u16, service_number: u32, handler: F)
+    where
+        F: Fn(Option<&[u8]>) -> core::result::Result<Option<Vec<u8>>, PropertyAccessError>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.private_transfer
+            .register(vendor_id, service_number, handler);
+        let services = &mut self.supported_services;
+        if !services
+            .confirmed
+            .contains(&ConfirmedServiceChoice::ConfirmedPrivateTransfer)
+        {
+            services
+                .confirmed
+                .push(ConfirmedServiceChoice::ConfirmedPrivateTransfer);
+        }
+        if !services
+            .unconfirmed
+            .contains(&UnconfirmedServiceChoice::UnconfirmedPrivateTransfer)
+        {
+            services
+                .unconfirmed
+                .push(UnconfirmedServiceChoice::UnconfirmedPrivateTransfer);
+        }
+    }
+
+    /// Set the password DeviceCommunicationControl and ReinitializeDevice
+    /// requests must carry, or `None` to accept requests without one
+    pub fn set_password(&mut self, password: Option<String>) {
+        self.password = password;
+    }
+
+    /// Communication state set by DeviceCommunicationControl
+    pub fn communication_control(&self) -> &CommunicationControl {
+        &self.communication_control
+    }
+
+    /// Count down a timed DeviceCommunicationControl state, the responses
+    /// remembered for duplicate requests and the segment timers of segmented
+    /// responses by `elapsed`
+    ///
+    /// Segments sent again are left for [`take_sends`](Self::take_sends).
+    pub fn advance_time(&mut self, elapsed: Duration) {
+        self.communication_control.advance_time(elapsed);
+        let mut sends = self.server_tsm.advance_time(elapsed);
+        self.sends.append(&mut sends);
+    }
+
+    /// Take the APDUs to send beyond the replies
+    /// [`process_apdu`](Self::process_apdu) returns, with the station each
+    /// goes to: the responses to reassembled segmented requests, the further
+    /// segments of segmented responses, and those sent again after a segment
+    /// timeout
+    pub fn take_sends(&mut self) -> Vec<(Vec<u8>, Apdu)> {
+        core::mem::take(&mut self.sends)
+    }
+
+    /// Check whether an APDU may go out under the current
+    /// DeviceCommunicationControl state, counting it as sent if so
+    ///
+    /// `answering_who_is` marks an I-Am sent in answer to a Who-Is, the one
+    /// request allowed while initiation is disabled.
+    pub fn may_send(&mut self, apdu: &Apdu, answering_who_is: bool) -> bool {
+        if self.communication_control.may_send(apdu, answering_who_is) {
+            self.stats.apdus_sent += 1;
+            true
+        } else {
+            self.stats.dropped_apdus += 1;
+            false
+        }
+    }
 }
 
 /// Transaction manager for tracking active transactions
@@ -1463,7 +1861,7 @@ impl TransactionManager {
     }
 
     /// Mark transaction as error
-    pub fn error_transaction(&mut self, invoke_id: u8, _error_class: u8, _error_code: u8) {
+    pub fn error_transaction(&mut self, invoke_id: u8, _error_class: u32, _error_code: u32) {
         if let Some(transaction) = self
             .transactions
             .iter_mut()
@@ -1541,6 +1939,10 @@ pub struct ApplicationStatistics {
     pub unknown_apdus: u64,
     /// Segmentation errors
     pub segmentation_errors: u64,
+    /// APDUs dropped while DeviceCommunicationControl disabled communication
+    pub dropped_apdus: u64,
+    /// Retransmitted confirmed requests answered without executing them
+    pub duplicate_requests: u64,
 }
 
 /// Priority queue for application messages
@@ -1736,6 +2138,64 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_error_encode_decode_wide_code() {
+        // Proprietary error codes run past 255
+        let apdu = Apdu::error(
+            3,
+            15,
+            PropertyAccessError {
+                error_class: 2,
+                error_code: 300,
+            },
+        );
+
+        let decoded = Apdu::decode(&apdu.encode()).unwrap();
+        assert_eq!(decoded, apdu);
+        assert!(matches!(
+            decoded,
+            Apdu::Error {
+                error_class: 2,
+                error_code: 300,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_read_property_processor_error() {
+        let mut handler = ApplicationLayerHandler::new(1);
+        handler.set_read_property_handler(|_| {
+            Err(crate::service::ServiceError::Failed(PropertyAccessError {
+                error_class: 1,
+                error_code: 31,
+            }))
+        });
+        let request = Apdu::ConfirmedRequest {
+            segmented: false,
+            more_follows: false,
+            segmented_response_accepted: false,
+            max_segments: MaxSegments::Unspecified,
+            max_response_size: MaxApduSize::Up1476,
+            invoke_id: 4,
+            sequence_number: None,
+            proposed_window_size: None,
+            service_choice: ConfirmedServiceChoice::ReadProperty,
+            service_data: vec![0x0C, 0x02, 0x00, 0x00, 0x01, 0x19, 0x55],
+        };
+
+        assert!(matches!(
+            handler.process_apdu(&request, &[]).unwrap(),
+            Some(Apdu::Error {
+                invoke_id: 4,
+                service_choice: 12,
+                error_class: 1,
+                error_code: 31,
+                ..
+            })
+        ));
+    }
+
     #[test]
     fn test_confirmed_request_encode_decode() {
         let apdu = Apdu::ConfirmedRequest {
@@ -1957,4 +2417,345 @@ mod tests {
         let reassembled = buffer.reassemble().unwrap();
         assert_eq!(reassembled, vec![1, 2, 3, 7, 8, 9]);
     }
+
+    #[test]
+    fn test_device_communication_control_gates_traffic() {
+        use crate::service::{DeviceCommunicationControlRequest, EnableDisable};
+
+        let mut handler = ApplicationLayerHandler::new(1);
+        handler.set_password(Some(String::from("secret")));
+        handler.set_who_is_handler(|_| Ok(Some(vec![0xC4, 0x02, 0x00, 0x00, 0x01])));
+        let confirmed = |invoke_id, service_choice, service_data| Apdu::ConfirmedRequest {
+            segmented: false,
+            more_follows: false,
+            segmented_response_accepted: false,
+            max_segments: MaxSegments::Unspecified,
+            max_response_size: MaxApduSize::Up1476,
+            invoke_id,
+            sequence_number: None,
+            proposed_window_size: None,
+            service_choice,
+            service_data,
+        };
+        let who_is = Apdu::UnconfirmedRequest {
+            service_choice: UnconfirmedServiceChoice::WhoIs,
+            service_data: Vec::new(),
+        };
+        let dcc = |request: DeviceCommunicationControlRequest| {
+            let mut service_data = Vec::new();
+            request.encode(&mut service_data).unwrap();
+            confirmed(
+                1,
+                ConfirmedServiceChoice::DeviceCommunicationControl,
+                service_data,
+            )
+        };
+
+        let disable = DeviceCommunicationControlRequest::new(EnableDisable::Disable)
+            .with_duration(1)
+            .with_password("secret");
+        assert!(matches!(
+            handler.process_apdu(&dcc(disable), &[]).unwrap(),
+            Some(Apdu::SimpleAck {
+                service_choice: 17,
+                ..
+            })
+        ));
+        assert!(handler.process_apdu(&who_is, &[]).unwrap().is_none());
+        assert!(handler
+            .process_apdu(
+                &confirmed(2, ConfirmedServiceChoice::ReadProperty, Vec::new()),
+                &[]
+            )
+            .unwrap()
+            .is_none());
+        assert_eq!(handler.stats.dropped_apdus, 2);
+
+        // A wrong password is refused even while disabled
+        let enable = DeviceCommunicationControlRequest::new(EnableDisable::Enable);
+        assert!(matches!(
+            handler.process_apdu(&dcc(enable.clone()), &[]).unwrap(),
+            Some(Apdu::Error {
+                error_class: 4,
+                error_code: 26,
+                ..
+            })
+        ));
+
+        handler.advance_time(Duration::from_secs(60));
+        assert_eq!(
+            handler.communication_control().state(),
+            EnableDisable::Enable
+        );
+        assert!(matches!(
+            handler.process_apdu(&who_is, &[]).unwrap(),
+            Some(Apdu::UnconfirmedRequest {
+                service_choice: UnconfirmedServiceChoice::IAm,
+                ..
+            })
+        ));
+
+        let disable_initiation =
+            DeviceCommunicationControlRequest::new(EnableDisable::DisableInitiation)
+                .with_password("secret");
+        handler.process_apdu(&dcc(disable_initiation), &[]).unwrap();
+        let i_am = handler.process_apdu(&who_is, &[]).unwrap().unwrap();
+        assert!(handler.may_send(&i_am, true));
+        assert!(!handler.may_send(&i_am, false));
+        assert!(!handler.may_send(&who_is, false));
+        handler
+            .process_apdu(&dcc(enable.with_password("secret")), &[])
+            .unwrap();
+        assert!(handler.may_send(&who_is, false));
+    }
+
+    #[test]
+    fn test_segmented_read_property_response() {
+        let mut handler = ApplicationLayerHandler::new(1);
+        handler.set_read_property_handler(|_| Ok(vec![7; 200]));
+        let request = Apdu::ConfirmedRequest {
+            segmented: false,
+            more_follows: false,
+            segmented_response_accepted: true,
+            max_segments: MaxSegments::Unspecified,
+            max_response_size: MaxApduSize::Up50,
+            invoke_id: 3,
+            sequence_number: None,
+            proposed_window_size: None,
+            service_choice: ConfirmedServiceChoice::ReadProperty,
+            service_data: Vec::new(),
+        };
+        let source = [10, 0, 0, 1, 0xBA, 0xC0];
+
+        assert!(matches!(
+            handler.process_apdu(&request, &source).unwrap(),
+            Some(Apdu::ComplexAck {
+                segmented: true,
+                more_follows: true,
+                sequence_number: Some(0),
+                ..
+            })
+        ));
+        assert!(handler.take_sends().is_empty());
+
+        let ack = Apdu::SegmentAck {
+            negative: false,
+            server: false,
+            invoke_id: 3,
+            sequence_number: 0,
+            window_size: 4,
+        };
+        assert!(handler.process_apdu(&ack, &source).unwrap().is_none());
+        let sends = handler.take_sends();
+        assert_eq!(sends.len(), 4);
+        assert!(sends.iter().all(|(station, _)| station == &source));
+        assert!(matches!(
+            sends[3].1,
+            Apdu::ComplexAck {
+                more_follows: false,
+                sequence_number: Some(4),
+                ..
+            }
+        ));
+
+        // Segments not acknowledged in time go again
+        handler.advance_time(Duration::from_secs(2));
+        assert_eq!(handler.take_sends(), sends);
+    }
+
+    #[test]
+    fn test_segmented_write_property_multiple_request() {
+        use crate::app::segmentation::{SegmentedTransmit, TransmitOutcome};
+        use crate::object::{ObjectIdentifier, ObjectType, PropertyIdentifier, PropertyValue};
+        use crate::service::{
+            BacnetPropertyValue, WriteAccessSpecification, WritePropertyMultipleRequest,
+        };
+        use std::sync::{Arc, Mutex};
+
+        let mut handler = ApplicationLayerHandler::new(1);
+        let executed = Arc::new(Mutex::new(Vec::new()));
+        let requests = Arc::clone(&executed);
+        handler.set_write_property_multiple_handler(move |invoke_id, service_data| {
+            requests.lock().unwrap().push(service_data.to_vec());
+            Apdu::SimpleAck {
+                invoke_id,
+                service_choice: ConfirmedServiceChoice::WritePropertyMultiple as u8,
+            }
+        });
+
+        let properties = (0..4)
+            .map(|n| {
+                BacnetPropertyValue::new(
+                    u32::from(PropertyIdentifier::Description),
+                    PropertyValue::CharacterString(format!("{} {}", n, "x".repeat(40))),
+                )
+            })
+            .collect();
+        let mut service_data = Vec::new();
+        WritePropertyMultipleRequest::new(vec![WriteAccessSpecification::new(
+            ObjectIdentifier::new(ObjectType::AnalogValue, 1),
+            properties,
+        )])
+        .encode(&mut service_data)
+        .unwrap();
+        let request = Apdu::ConfirmedRequest {
+            segmented: false,
+            more_follows: false,
+            segmented_response_accepted: false,
+            max_segments: MaxSegments::Unspecified,
+            max_response_size: MaxApduSize::Up1476,
+            invoke_id: 9,
+            sequence_number: None,
+            proposed_window_size: None,
+            service_choice: ConfirmedServiceChoice::WritePropertyMultiple,
+            service_data: service_data.clone(),
+        };
+        let mut transmit = SegmentedTransmit::new(request, 50, None, 2).unwrap();
+        assert_eq!(transmit.segment_count(), 5);
+        let source = [10, 0, 0, 1, 0xBA, 0xC0];
+
+        let mut acks = Vec::new();
+        let mut window = transmit.start();
+        while !window.is_empty() {
+            let mut replies = Vec::new();
+            for segment in &window {
+                replies.extend(handler.process_apdu(segment, &source).unwrap());
+            }
+            // Only the last segment of each window is acknowledged
+            assert_eq!(replies.len(), 1);
+            let ack = replies.pop().unwrap();
+            if let Apdu::SegmentAck {
+                negative: false,
+                server: true,
+                invoke_id: 9,
+                sequence_number,
+                ..
+            } = ack
+            {
+                acks.push(sequence_number);
+            } else {
+                panic!("expected a SegmentACK, got {:?}", ack);
+            }
+            window = match transmit.segment_ack(&ack) {
+                TransmitOutcome::Send(segments) => segments,
+                TransmitOutcome::Complete => Vec::new(),
+                outcome => panic!("unexpected {:?}", outcome),
+            };
+        }
+
+        assert_eq!(acks, vec![0, 2, 4]);
+        assert_eq!(*executed.lock().unwrap(), vec![service_data]);
+        assert_eq!(
+            handler.take_sends(),
+            vec![(
+                source.to_vec(),
+                Apdu::SimpleAck {
+                    invoke_id: 9,
+                    service_choice: ConfirmedServiceChoice::WritePropertyMultiple as u8,
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn test_reinitialize_device_dispatch() {
+        use crate::service::ReinitializeDeviceRequest;
+        use std::sync::{Arc, Mutex};
+
+        let mut handler = ApplicationLayerHandler::new(1);
+        let mut service_data = Vec::new();
+        ReinitializeDeviceRequest::new(ReinitializedState::Warmstart)
+            .encode(&mut service_data)
+            .unwrap();
+        let request = |invoke_id| Apdu::ConfirmedRequest {
+            segmented: false,
+            more_follows: false,
+            segmented_response_accepted: false,
+            max_segments: MaxSegments::Unspecified,
+            max_response_size: MaxApduSize::Up1476,
+            invoke_id,
+            sequence_number: None,
+            proposed_window_size: None,
+            service_choice: ConfirmedServiceChoice::ReinitializeDevice,
+            service_data: service_data.clone(),
+        };
+
+        // Denied until the host can act on it
+        assert!(matches!(
+            handler.process_apdu(&request(9), &[]).unwrap(),
+            Some(Apdu::Error {
+                error_class: 5,
+                error_code: 29,
+                ..
+            })
+        ));
+
+        let requested = Arc::new(Mutex::new(Vec::new()));
+        let log = Arc::clone(&requested);
+        handler.set_reinitialize_device_handler(move |state| {
+            log.lock().unwrap().push(state);
+            Ok(())
+        });
+        assert!(matches!(
+            handler.process_apdu(&request(10), &[]).unwrap(),
+            Some(Apdu::SimpleAck {
+                invoke_id: 10,
+                service_choice: 20,
+            })
+        ));
+        assert_eq!(*requested.lock().unwrap(), [ReinitializedState::Warmstart]);
+
+        // A retransmission is answered again without reinitializing again,
+        // and the same invoke ID from another station is a new request
+        assert!(matches!(
+            handler.process_apdu(&request(10), &[]).unwrap(),
+            Some(Apdu::SimpleAck { invoke_id: 10, .. })
+        ));
+        assert_eq!(handler.stats.duplicate_requests, 1);
+        handler.process_apdu(&request(10), &[1]).unwrap();
+        assert_eq!(requested.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_private_transfer_dispatch() {
+        use crate::service::{PrivateTransferAck, PrivateTransferRequest};
+
+        let mut handler = ApplicationLayerHandler::new(1);
+        let mut service_data = Vec::new();
+        PrivateTransferRequest::new(260, 1)
+            .encode(&mut service_data)
+            .unwrap();
+        let request = |invoke_id| Apdu::ConfirmedRequest {
+            segmented: false,
+            more_follows: false,
+            segmented_response_accepted: false,
+            max_segments: MaxSegments::Unspecified,
+            max_response_size: MaxApduSize::Up1476,
+            invoke_id,
+            sequence_number: None,
+            proposed_window_size: None,
+            service_choice: ConfirmedServiceChoice::ConfirmedPrivateTransfer,
+            service_data: service_data.clone(),
+        };
+
+        assert!(matches!(
+            handler.process_apdu(&request(6), &[]).unwrap(),
+            Some(Apdu::Reject { invoke_id: 6, .. })
+        ));
+
+        handler.register_private_transfer(260, 1, |_| Ok(Some(vec![0x91, 0x01])));
+        let Some(Apdu::ComplexAck { service_data, .. }) =
+            handler.process_apdu(&request(7), &[]).unwrap()
+        else {
+            panic!("expected a ComplexAck");
+        };
+        assert_eq!(
+            PrivateTransferAck::decode(&service_data).unwrap(),
+            PrivateTransferAck {
+                vendor_id: 260,
+                service_number: 1,
+                result_block: Some(vec![0x91, 0x01]),
+            }
+        );
+    }
 }
@@ -0,0 +1,886 @@
+//! APDU segmentation (Clause 5.2, 5.4)
+//!
+//! A confirmed request or ComplexACK longer than the receiver accepts is
+//! sent in segments that each fit its maximum APDU length. The sender sends
+//! the first segment alone, with the window size it proposes; the
+//! receiver's SegmentACK sets the actual window size, and each SegmentACK
+//! after that acknowledges the segments up to its sequence number and asks
+//! for the next window. A window that is not acknowledged within the
+//! segment timeout is sent again, up to the retry limit, and a negative
+//! SegmentACK has the segments after the ones it acknowledges sent again at
+//! once. Sequence numbers run modulo 256.
+//!
+//! The receiver acknowledges the first segment with the smaller of the
+//! proposed window size and its own, and after that the last segment of
+//! each window. A segment out of order is discarded and answered with a
+//! negative SegmentACK for the last segment received in order; a duplicate
+//! is answered with a positive one, in case the SegmentACK for it was lost.
+//! More segments than the receiver accepts abort the transaction with
+//! buffer-overflow, and a receiver that hears nothing for four segment
+//! timeouts gives up.
+//!
+//! [`SegmentedTransmit`] and [`SegmentedReceive`] are the two sides as pure
+//! state: the caller sends the APDUs they return, and feeds them the APDUs
+//! received and the time passed.
+
+#[cfg(feature = "std")]
+use std::time::Duration;
+
+#[cfg(not(feature = "std"))]
+use core::time::Duration;
+
+#[cfg(not(feature = "std"))]
+use alloc::{format, string::ToString, vec, vec::Vec};
+
+use crate::app::{Apdu, ApplicationError, Result};
+use crate::service::AbortReason;
+
+/// Default APDU_Segment_Timeout
+pub const DEFAULT_SEGMENT_TIMEOUT: Duration = Duration::from_millis(2000);
+
+/// Default Number_Of_APDU_Retries
+pub const DEFAULT_RETRIES: u8 = 3;
+
+/// Largest window size (Clause 20.1.2.8)
+pub const MAX_WINDOW_SIZE: u8 = 127;
+
+/// What to do next in a segmented transmission
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransmitOutcome {
+    /// Send these segments, then wait for a SegmentACK
+    Send(Vec<Apdu>),
+    /// Nothing to send; keep waiting for a SegmentACK
+    Wait,
+    /// Every segment is acknowledged
+    Complete,
+    /// No SegmentACK came within the retries; the transmission failed
+    TimedOut,
+}
+
+/// The sending side of a segmented confirmed request or ComplexACK
+#[derive(Debug, Clone)]
+pub struct SegmentedTransmit {
+    /// The APDU being sent, without its service data
+    template: Apdu,
+    /// Service data of each segment
+    segments: Vec<Vec<u8>>,
+    /// Window size proposed in each segment
+    proposed_window_size: u8,
+    /// Window size the receiver asked for
+    actual_window_size: u8,
+    /// Index of the first segment not acknowledged
+    window_start: usize,
+    /// Index after the last segment sent
+    sent: usize,
+    /// Times the current window was sent again
+    retries: u8,
+    /// Number_Of_APDU_Retries
+    max_retries: u8,
+    /// APDU_Segment_Timeout
+    segment_timeout: Duration,
+    /// Time since the last send
+    elapsed: Duration,
+}
+
+impl SegmentedTransmit {
+    /// Whether `apdu` is too long for a receiver accepting APDUs of
+    /// `max_apdu` octets
+    pub fn needs_segmentation(apdu: &Apdu, max_apdu: usize) -> bool {
+        apdu.encode().len() > max_apdu
+    }
+
+    /// Prepare `apdu`, a confirmed request or ComplexACK, to be sent in
+    /// segments of at most `max_apdu` octets to a receiver accepting
+    /// `max_segments` of them (`None` when unspecified)
+    ///
+    /// The segmentation fields of `apdu` are replaced.
+    pub fn new(
+        apdu: Apdu,
+        max_apdu: usize,
+        max_segments: Option<usize>,
+        proposed_window_size: u8,
+    ) -> Result<Self> {
+        let mut template = apdu;
+        let data = match &mut template {
+            Apdu::ConfirmedRequest { service_data, .. } | Apdu::ComplexAck { service_data, .. } => {
+                core::mem::take(service_data)
+            }
+            _ => {
+                return Err(ApplicationError::SegmentationError(
+                    "Only confirmed requests and ComplexACKs are segmented".to_string(),
+                ))
+            }
+        };
+
+        let header = segment(&template, 0, true, 1, Vec::new()).encode().len();
+        let size = max_apdu.saturating_sub(header);
+        if size == 0 {
+            return Err(ApplicationError::SegmentationError(format!(
+                "No room for service data in a {} octet APDU",
+                max_apdu
+            )));
+        }
+        let segments: Vec<Vec<u8>> = if data.is_empty() {
+            vec![Vec::new()]
+        } else {
+            data.chunks(size).map(<[u8]>::to_vec).collect()
+        };
+        if let Some(max_segments) = max_segments.filter(|max| segments.len() > *max) {
+            return Err(ApplicationError::SegmentationError(format!(
+                "{} segments needed, {} accepted",
+                segments.len(),
+                max_segments
+            )));
+        }
+
+        Ok(Self {
+            template,
+            segments,
+            proposed_window_size: proposed_window_size.clamp(1, MAX_WINDOW_SIZE),
+            actual_window_size: 1,
+            window_start: 0,
+            sent: 0,
+            retries: 0,
+            max_retries: DEFAULT_RETRIES,
+            segment_timeout: DEFAULT_SEGMENT_TIMEOUT,
+            elapsed: Duration::ZERO,
+        })
+    }
+
+    /// Set APDU_Segment_Timeout
+    pub fn set_segment_timeout(&mut self, timeout: Duration) {
+        self.segment_timeout = timeout;
+    }
+
+    /// Set Number_Of_APDU_Retries
+    pub fn set_retries(&mut self, retries: u8) {
+        self.max_retries = retries;
+    }
+
+    /// Invoke ID of the APDU
+    pub fn invoke_id(&self) -> u8 {
+        match self.template {
+            Apdu::ConfirmedRequest { invoke_id, .. } | Apdu::ComplexAck { invoke_id, .. } => {
+                invoke_id
+            }
+            _ => 0,
+        }
+    }
+
+    /// Whether this is the server, sending a ComplexACK
+    pub fn is_server(&self) -> bool {
+        matches!(self.template, Apdu::ComplexAck { .. })
+    }
+
+    /// Number of segments
+    pub fn segment_count(&self) -> usize {
+        self.segments.len()
+    }
+
+    /// Window size the receiver asked for, 1 until its first SegmentACK
+    pub fn actual_window_size(&self) -> u8 {
+        self.actual_window_size
+    }
+
+    /// Whether every segment is acknowledged
+    pub fn is_complete(&self) -> bool {
+        self.window_start == self.segments.len()
+    }
+
+    /// Start over with the first segment, to be sent alone
+    pub fn start(&mut self) -> Vec<Apdu> {
+        self.window_start = 0;
+        self.sent = 0;
+        self.actual_window_size = 1;
+        self.retries = 0;
+        self.fill_window()
+    }
+
+    /// Take a SegmentACK from the receiver
+    ///
+    /// SegmentACKs for other transactions, and positive ones acknowledging
+    /// nothing new, are ignored.
+    pub fn segment_ack(&mut self, ack: &Apdu) -> TransmitOutcome {
+        let Apdu::SegmentAck {
+            negative,
+            server,
+            invoke_id,
+            sequence_number,
+            window_size,
+        } = *ack
+        else {
+            return TransmitOutcome::Wait;
+        };
+        if invoke_id != self.invoke_id() || server == self.is_server() || self.sent == 0 {
+            return TransmitOutcome::Wait;
+        }
+        if self.is_complete() {
+            return TransmitOutcome::Complete;
+        }
+
+        let in_flight = self.sent - self.window_start;
+        let acknowledged = sequence_number
+            .wrapping_add(1)
+            .wrapping_sub(self.window_start as u8) as usize;
+        if acknowledged > in_flight || (acknowledged == 0 && !negative) {
+            return TransmitOutcome::Wait;
+        }
+        self.window_start += acknowledged;
+        self.actual_window_size = window_size.clamp(1, MAX_WINDOW_SIZE);
+        self.retries = 0;
+        if self.is_complete() {
+            return TransmitOutcome::Complete;
+        }
+        // Whatever followed the acknowledged segments goes again
+        self.sent = self.window_start;
+        TransmitOutcome::Send(self.fill_window())
+    }
+
+    /// Count `elapsed` against the segment timeout, sending the window
+    /// again once it runs out
+    pub fn advance_time(&mut self, elapsed: Duration) -> TransmitOutcome {
+        if self.sent == 0 || self.is_complete() {
+            return TransmitOutcome::Wait;
+        }
+        self.elapsed += elapsed;
+        if self.elapsed < self.segment_timeout {
+            return TransmitOutcome::Wait;
+        }
+        if self.retries >= self.max_retries {
+            return TransmitOutcome::TimedOut;
+        }
+        self.retries += 1;
+        self.sent = self.window_start;
+        TransmitOutcome::Send(self.fill_window())
+    }
+
+    /// The segments from the first one not sent to the end of the window
+    fn fill_window(&mut self) -> Vec<Apdu> {
+        let end = (self.window_start + self.actual_window_size as usize).min(self.segments.len());
+        let segments = (self.sent..end)
+            .map(|index| {
+                segment(
+                    &self.template,
+                    index as u8,
+                    index + 1 < self.segments.len(),
+                    self.proposed_window_size,
+                    self.segments[index].clone(),
+                )
+            })
+            .collect();
+        self.sent = end;
+        self.elapsed = Duration::ZERO;
+        segments
+    }
+}
+
+/// What to do with a received segment
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReceiveOutcome {
+    /// Send this SegmentACK and wait for more segments
+    Ack(Apdu),
+    /// Wait for more segments
+    Wait,
+    /// The last segment arrived: send the SegmentACK, and take the APDU with
+    /// the service data of all the segments
+    Complete { ack: Apdu, apdu: Apdu },
+    /// Send this Abort; the transaction is over
+    Abort(Apdu),
+    /// No segment came in time; the transaction is abandoned
+    TimedOut,
+}
+
+/// The receiving side of a segmented confirmed request or ComplexACK
+#[derive(Debug, Clone)]
+pub struct SegmentedReceive {
+    /// The first segment, without its service data, once it has arrived
+    first: Option<Apdu>,
+    /// Service data of the segments received in order
+    data: Vec<u8>,
+    /// Segments received in order
+    count: usize,
+    /// Most segments accepted, `None` for no limit
+    max_segments: Option<usize>,
+    /// Largest window size accepted
+    window_size: u8,
+    /// Window size in use
+    actual_window_size: u8,
+    /// Sequence number of the segment that started the current window
+    initial_sequence: u8,
+    /// Sequence number of the last segment received in order
+    last_sequence: u8,
+    /// Whether the last segment arrived
+    complete: bool,
+    /// APDU_Segment_Timeout
+    segment_timeout: Duration,
+    /// Time since the last segment
+    elapsed: Duration,
+}
+
+impl SegmentedReceive {
+    /// Prepare to receive a segmented APDU of at most `max_segments`
+    /// segments (`None` for no limit), in windows of at most `window_size`
+    pub fn new(max_segments: Option<usize>, window_size: u8) -> Self {
+        Self {
+            first: None,
+            data: Vec::new(),
+            count: 0,
+            max_segments,
+            window_size: window_size.clamp(1, MAX_WINDOW_SIZE),
+            actual_window_size: 1,
+            initial_sequence: 0,
+            last_sequence: 0,
+            complete: false,
+            segment_timeout: DEFAULT_SEGMENT_TIMEOUT,
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    /// Set APDU_Segment_Timeout; the receiver gives up after four of them
+    pub fn set_segment_timeout(&mut self, timeout: Duration) {
+        self.segment_timeout = timeout;
+    }
+
+    /// Window size in use, once the first segment has arrived
+    pub fn actual_window_size(&self) -> u8 {
+        self.actual_window_size
+    }
+
+    /// Segments received in order so far
+    pub fn segment_count(&self) -> usize {
+        self.count
+    }
+
+    /// Whether the last segment arrived
+    pub fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    /// Take a segment from the sender
+    ///
+    /// APDUs of other transactions are ignored; a first segment with a
+    /// sequence number other than 0 is aborted.
+    pub fn segment(&mut self, apdu: &Apdu) -> ReceiveOutcome {
+        let Some((invoke_id, sequence, window, more, data)) = segment_fields(apdu) else {
+            return ReceiveOutcome::Wait;
+        };
+        // The receiver of a request is the server
+        let server = matches!(apdu, Apdu::ConfirmedRequest { .. });
+
+        let Some(first) = &self.first else {
+            if sequence != 0 {
+                return ReceiveOutcome::Abort(abort(
+                    server,
+                    invoke_id,
+                    AbortReason::InvalidApduInThisState,
+                ));
+            }
+            self.actual_window_size = window.clamp(1, self.window_size);
+            let mut first = apdu.clone();
+            unsegment(&mut first, Vec::new());
+            self.first = Some(first);
+            self.complete = false;
+            self.count = 0;
+            self.data.clear();
+            self.last_sequence = 0;
+            self.initial_sequence = 0;
+            return self.accept(server, invoke_id, 0, more, data);
+        };
+        let same_transaction = core::mem::discriminant(first) == core::mem::discriminant(apdu)
+            && matches!(
+                first,
+                Apdu::ConfirmedRequest { invoke_id: id, .. } | Apdu::ComplexAck { invoke_id: id, .. }
+                    if *id == invoke_id
+            );
+        if !same_transaction {
+            return ReceiveOutcome::Wait;
+        }
+        self.elapsed = Duration::ZERO;
+
+        if !self.complete && sequence == self.last_sequence.wrapping_add(1) {
+            self.accept(server, invoke_id, sequence, more, data)
+        } else if self.last_sequence.wrapping_sub(sequence) < self.actual_window_size {
+            // Already received: the SegmentACK may have been lost
+            ReceiveOutcome::Ack(self.ack(false, server, invoke_id))
+        } else {
+            self.initial_sequence = self.last_sequence;
+            ReceiveOutcome::Ack(self.ack(true, server, invoke_id))
+        }
+    }
+
+    /// Count `elapsed` against four segment timeouts
+    pub fn advance_time(&mut self, elapsed: Duration) -> ReceiveOutcome {
+        if self.first.is_none() || self.complete {
+            return ReceiveOutcome::Wait;
+        }
+        self.elapsed += elapsed;
+        if self.elapsed >= self.segment_timeout * 4 {
+            self.first = None;
+            return ReceiveOutcome::TimedOut;
+        }
+        ReceiveOutcome::Wait
+    }
+
+    /// Take the segment after the last one received in order
+    fn accept(
+        &mut self,
+        server: bool,
+        invoke_id: u8,
+        sequence: u8,
+        more: bool,
+        data: &[u8],
+    ) -> ReceiveOutcome {
+        self.count += 1;
+        if self.max_segments.is_some_and(|max| self.count > max) {
+            self.first = None;
+            return ReceiveOutcome::Abort(abort(server, invoke_id, AbortReason::BufferOverflow));
+        }
+        self.data.extend_from_slice(data);
+        self.last_sequence = sequence;
+
+        if !more {
+            let Some(mut apdu) = self.first.clone() else {
+                return ReceiveOutcome::Wait;
+            };
+            self.complete = true;
+            unsegment(&mut apdu, core::mem::take(&mut self.data));
+            return ReceiveOutcome::Complete {
+                ack: self.ack(false, server, invoke_id),
+                apdu,
+            };
+        }
+        let window_full = sequence == self.initial_sequence.wrapping_add(self.actual_window_size);
+        if sequence == 0 || window_full {
+            self.initial_sequence = sequence;
+            ReceiveOutcome::Ack(self.ack(false, server, invoke_id))
+        } else {
+            ReceiveOutcome::Wait
+        }
+    }
+
+    /// A SegmentACK for the last segment received in order
+    fn ack(&self, negative: bool, server: bool, invoke_id: u8) -> Apdu {
+        Apdu::SegmentAck {
+            negative,
+            server,
+            invoke_id,
+            sequence_number: self.last_sequence,
+            window_size: self.actual_window_size,
+        }
+    }
+}
+
+/// Invoke ID, sequence number, proposed window size, more-follows and
+/// service data of a segment
+fn segment_fields(apdu: &Apdu) -> Option<(u8, u8, u8, bool, &[u8])> {
+    match apdu {
+        Apdu::ConfirmedRequest {
+            segmented: true,
+            more_follows,
+            invoke_id,
+            sequence_number,
+            proposed_window_size,
+            service_data,
+            ..
+        }
+        | Apdu::ComplexAck {
+            segmented: true,
+            more_follows,
+            invoke_id,
+            sequence_number,
+            proposed_window_size,
+            service_data,
+            ..
+        } => Some((
+            *invoke_id,
+            sequence_number.unwrap_or(0),
+            proposed_window_size.unwrap_or(1),
+            *more_follows,
+            service_data,
+        )),
+        _ => None,
+    }
+}
+
+fn abort(server: bool, invoke_id: u8, reason: AbortReason) -> Apdu {
+    Apdu::Abort {
+        server,
+        invoke_id,
+        abort_reason: reason as u8,
+    }
+}
+
+/// Clear the segmentation fields of `apdu` and give it `data`
+fn unsegment(apdu: &mut Apdu, data: Vec<u8>) {
+    if let Apdu::ConfirmedRequest {
+        segmented,
+        more_follows,
+        sequence_number,
+        proposed_window_size,
+        service_data,
+        ..
+    }
+    | Apdu::ComplexAck {
+        segmented,
+        more_follows,
+        sequence_number,
+        proposed_window_size,
+        service_data,
+        ..
+    } = apdu
+    {
+        *segmented = false;
+        *more_follows = false;
+        *sequence_number = None;
+        *proposed_window_size = None;
+        *service_data = data;
+    }
+}
+
+/// One segment of `template`
+fn segment(template: &Apdu, sequence: u8, more: bool, window_size: u8, data: Vec<u8>) -> Apdu {
+    let mut apdu = template.clone();
+    match &mut apdu {
+        Apdu::ConfirmedRequest {
+            segmented,
+            more_follows,
+            sequence_number,
+            proposed_window_size,
+            service_data,
+            ..
+        }
+        | Apdu::ComplexAck {
+            segmented,
+            more_follows,
+            sequence_number,
+            proposed_window_size,
+            service_data,
+            ..
+        } => {
+            *segmented = true;
+            *more_follows = more;
+            *sequence_number = Some(sequence);
+            *proposed_window_size = Some(window_size);
+            *service_data = data;
+        }
+        _ => {}
+    }
+    apdu
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{MaxApduSize, MaxSegments};
+    use crate::service::ConfirmedServiceChoice;
+
+    fn request(length: usize) -> Apdu {
+        Apdu::ConfirmedRequest {
+            segmented: false,
+            more_follows: false,
+            segmented_response_accepted: true,
+            max_segments: MaxSegments::Sixteen,
+            max_response_size: MaxApduSize::Up206,
+            invoke_id: 7,
+            sequence_number: None,
+            proposed_window_size: None,
+            service_choice: ConfirmedServiceChoice::WriteProperty,
+            service_data: (0..length).map(|i| i as u8).collect(),
+        }
+    }
+
+    fn ack(negative: bool, sequence_number: u8, window_size: u8) -> Apdu {
+        Apdu::SegmentAck {
+            negative,
+            server: true,
+            invoke_id: 7,
+            sequence_number,
+            window_size,
+        }
+    }
+
+    fn sequence_numbers(outcome: &TransmitOutcome) -> Vec<u8> {
+        let TransmitOutcome::Send(segments) = outcome else {
+            panic!("expected segments, got {:?}", outcome);
+        };
+        segments
+            .iter()
+            .map(|apdu| match apdu {
+                Apdu::ConfirmedRequest {
+                    sequence_number, ..
+                } => sequence_number.unwrap(),
+                _ => panic!("expected a confirmed request, got {:?}", apdu),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_windows_and_segment_acks() {
+        // 6 octets of header leave 200 for data in a 206 octet APDU
+        let original = request(1000);
+        assert!(SegmentedTransmit::needs_segmentation(&original, 206));
+        let mut transmit = SegmentedTransmit::new(original.clone(), 206, Some(16), 3).unwrap();
+        assert_eq!(transmit.segment_count(), 5);
+
+        let first = transmit.start();
+        assert_eq!(first.len(), 1);
+        let Apdu::ConfirmedRequest {
+            segmented,
+            more_follows,
+            proposed_window_size,
+            ref service_data,
+            ..
+        } = first[0]
+        else {
+            panic!("expected a confirmed request");
+        };
+        assert!(segmented && more_follows);
+        assert_eq!(proposed_window_size, Some(3));
+        assert_eq!(first[0].encode().len(), 206);
+        assert_eq!(
+            service_data[..],
+            (0..200).map(|i| i as u8).collect::<Vec<_>>()[..]
+        );
+
+        // The receiver asks for windows of 2
+        let outcome = transmit.segment_ack(&ack(false, 0, 2));
+        assert_eq!(sequence_numbers(&outcome), [1, 2]);
+        assert_eq!(transmit.actual_window_size(), 2);
+        // A SegmentACK for a client, or another transaction, is not ours
+        let mut strange = ack(false, 2, 2);
+        if let Apdu::SegmentAck { server, .. } = &mut strange {
+            *server = false;
+        }
+        assert_eq!(transmit.segment_ack(&strange), TransmitOutcome::Wait);
+
+        let outcome = transmit.segment_ack(&ack(false, 2, 2));
+        assert_eq!(sequence_numbers(&outcome), [3, 4]);
+        let TransmitOutcome::Send(last) = outcome else {
+            unreachable!()
+        };
+        assert!(matches!(
+            last[1],
+            Apdu::ConfirmedRequest {
+                more_follows: false,
+                ..
+            }
+        ));
+        assert_eq!(
+            transmit.segment_ack(&ack(false, 4, 2)),
+            TransmitOutcome::Complete
+        );
+        assert!(transmit.is_complete());
+
+        // The segments' service data is the original's
+        let mut transmit = SegmentedTransmit::new(original.clone(), 206, None, 127).unwrap();
+        let mut segments = transmit.start();
+        if let TransmitOutcome::Send(rest) = transmit.segment_ack(&ack(false, 0, 127)) {
+            segments.extend(rest);
+        }
+        let data: Vec<u8> = segments
+            .iter()
+            .flat_map(|apdu| match apdu {
+                Apdu::ConfirmedRequest { service_data, .. } => service_data.clone(),
+                _ => Vec::new(),
+            })
+            .collect();
+        let Apdu::ConfirmedRequest { service_data, .. } = original else {
+            unreachable!()
+        };
+        assert_eq!(data, service_data);
+    }
+
+    #[test]
+    fn test_retransmission() {
+        let mut transmit = SegmentedTransmit::new(request(1000), 206, None, 3).unwrap();
+        transmit.set_segment_timeout(Duration::from_millis(100));
+        transmit.set_retries(1);
+        transmit.start();
+        assert_eq!(
+            transmit.advance_time(Duration::from_millis(60)),
+            TransmitOutcome::Wait
+        );
+        // The unacknowledged first segment goes again
+        let outcome = transmit.advance_time(Duration::from_millis(60));
+        assert_eq!(sequence_numbers(&outcome), [0]);
+
+        let outcome = transmit.segment_ack(&ack(false, 0, 3));
+        assert_eq!(sequence_numbers(&outcome), [1, 2, 3]);
+        // A duplicate SegmentACK changes nothing
+        assert_eq!(
+            transmit.segment_ack(&ack(false, 0, 3)),
+            TransmitOutcome::Wait
+        );
+
+        // Segment 2 was lost: the rest of the window goes again at once
+        let outcome = transmit.segment_ack(&ack(true, 1, 3));
+        assert_eq!(sequence_numbers(&outcome), [2, 3, 4]);
+        // A negative SegmentACK for nothing new resends the whole window
+        let outcome = transmit.segment_ack(&ack(true, 1, 3));
+        assert_eq!(sequence_numbers(&outcome), [2, 3, 4]);
+
+        let outcome = transmit.advance_time(Duration::from_millis(100));
+        assert_eq!(sequence_numbers(&outcome), [2, 3, 4]);
+        assert_eq!(
+            transmit.advance_time(Duration::from_millis(100)),
+            TransmitOutcome::TimedOut
+        );
+    }
+
+    /// Segment `sequence` of a request of `count` segments
+    fn request_segment(sequence: u8, count: u8) -> Apdu {
+        Apdu::ConfirmedRequest {
+            segmented: true,
+            more_follows: sequence + 1 < count,
+            segmented_response_accepted: true,
+            max_segments: MaxSegments::Sixteen,
+            max_response_size: MaxApduSize::Up206,
+            invoke_id: 7,
+            sequence_number: Some(sequence),
+            proposed_window_size: Some(4),
+            service_choice: ConfirmedServiceChoice::WriteProperty,
+            service_data: vec![sequence],
+        }
+    }
+
+    #[test]
+    fn test_transmit_to_receive() {
+        let original = request(1000);
+        let mut transmit = SegmentedTransmit::new(original.clone(), 128, None, 4).unwrap();
+        let mut receive = SegmentedReceive::new(Some(16), 3);
+        let mut in_flight = transmit.start();
+        let mut received = None;
+        while received.is_none() {
+            let mut acks = Vec::new();
+            for segment in in_flight.drain(..) {
+                match receive.segment(&segment) {
+                    ReceiveOutcome::Ack(ack) => acks.push(ack),
+                    ReceiveOutcome::Wait => {}
+                    ReceiveOutcome::Complete { ack, apdu } => {
+                        acks.push(ack);
+                        received = Some(apdu);
+                    }
+                    outcome => panic!("unexpected {:?}", outcome),
+                }
+            }
+            assert_eq!(acks.len(), 1);
+            match transmit.segment_ack(&acks[0]) {
+                TransmitOutcome::Send(segments) => in_flight = segments,
+                TransmitOutcome::Complete => {}
+                outcome => panic!("unexpected {:?}", outcome),
+            }
+        }
+        // The smaller window size wins
+        assert_eq!(receive.actual_window_size(), 3);
+        assert!(transmit.is_complete() && receive.is_complete());
+        assert_eq!(received, Some(original));
+    }
+
+    #[test]
+    fn test_receive_out_of_order_and_limits() {
+        let mut receive = SegmentedReceive::new(Some(4), 2);
+        let ack = |negative: bool, sequence_number: u8| Apdu::SegmentAck {
+            negative,
+            server: true,
+            invoke_id: 7,
+            sequence_number,
+            window_size: 2,
+        };
+        assert_eq!(
+            receive.segment(&request_segment(0, 5)),
+            ReceiveOutcome::Ack(ack(false, 0))
+        );
+        assert_eq!(
+            receive.segment(&request_segment(1, 5)),
+            ReceiveOutcome::Wait
+        );
+        // Segment 2 went missing
+        assert_eq!(
+            receive.segment(&request_segment(3, 5)),
+            ReceiveOutcome::Ack(ack(true, 1))
+        );
+        // A duplicate is acknowledged again
+        assert_eq!(
+            receive.segment(&request_segment(1, 5)),
+            ReceiveOutcome::Ack(ack(false, 1))
+        );
+        assert_eq!(
+            receive.segment(&request_segment(2, 5)),
+            ReceiveOutcome::Wait
+        );
+        assert_eq!(
+            receive.segment(&request_segment(3, 5)),
+            ReceiveOutcome::Ack(ack(false, 3))
+        );
+        assert_eq!(receive.segment_count(), 4);
+
+        // The fifth segment is one more than accepted
+        assert_eq!(
+            receive.segment(&request_segment(4, 5)),
+            ReceiveOutcome::Abort(Apdu::Abort {
+                server: true,
+                invoke_id: 7,
+                abort_reason: AbortReason::BufferOverflow as u8,
+            })
+        );
+
+        // A transaction must start with segment 0, and is abandoned after
+        // four segment timeouts
+        let mut receive = SegmentedReceive::new(None, 2);
+        assert!(matches!(
+            receive.segment(&request_segment(1, 5)),
+            ReceiveOutcome::Abort(Apdu::Abort { abort_reason, .. })
+                if abort_reason == AbortReason::InvalidApduInThisState as u8
+        ));
+        receive.set_segment_timeout(Duration::from_millis(100));
+        receive.segment(&request_segment(0, 5));
+        assert_eq!(
+            receive.advance_time(Duration::from_millis(300)),
+            ReceiveOutcome::Wait
+        );
+        assert_eq!(
+            receive.advance_time(Duration::from_millis(100)),
+            ReceiveOutcome::TimedOut
+        );
+    }
+
+    #[test]
+    fn test_complex_ack_and_limits() {
+        let complex_ack = Apdu::ComplexAck {
+            segmented: false,
+            more_follows: false,
+            invoke_id: 9,
+            sequence_number: None,
+            proposed_window_size: None,
+            service_choice: ConfirmedServiceChoice::ReadProperty as u8,
+            service_data: vec![0xAA; 120],
+        };
+        // 5 octets of header in a segmented ComplexACK
+        let mut transmit = SegmentedTransmit::new(complex_ack, 50, Some(4), 2).unwrap();
+        assert!(transmit.is_server());
+        assert_eq!(transmit.segment_count(), 3);
+        let first = transmit.start();
+        assert_eq!(first[0].encode().len(), 50);
+        let client_ack = Apdu::SegmentAck {
+            negative: false,
+            server: false,
+            invoke_id: 9,
+            sequence_number: 0,
+            window_size: 4,
+        };
+        assert!(
+            matches!(transmit.segment_ack(&client_ack), TransmitOutcome::Send(s) if s.len() == 2)
+        );
+
+        // More segments than the receiver accepts, no room for data, and
+        // APDUs that are never segmented
+        assert!(SegmentedTransmit::new(request(1000), 206, Some(4), 1).is_err());
+        assert!(SegmentedTransmit::new(request(10), 6, None, 1).is_err());
+        let simple_ack = Apdu::SimpleAck {
+            invoke_id: 1,
+            service_choice: 15,
+        };
+        assert!(SegmentedTransmit::new(simple_ack, 50, None, 1).is_err());
+        assert!(!SegmentedTransmit::needs_segmentation(&request(10), 50));
+    }
+}
@@ -0,0 +1,1307 @@
+//! Transaction state machines (Clause 5.4)
+//!
+//! The client side of a confirmed request runs from the request to the
+//! reply that ends it. [`ClientTsm`] allocates the request's invoke ID,
+//! sends it in segments when it is longer than the server accepts, and
+//! matches what comes back by invoke ID and peer: a SimpleACK, ComplexACK
+//! (segmented or not), Error, Reject or Abort ends the transaction with a
+//! [`Confirmation`]. A request with no reply within APDU_Timeout is sent
+//! again, Number_Of_APDU_Retries times, before it ends with
+//! [`Confirmation::Timeout`].
+//!
+//! On the server side, a client that hears no reply sends its request
+//! again with the same invoke ID. [`ServerTsm`] remembers each confirmed
+//! request by source address and invoke ID along with the response sent,
+//! so a retransmitted request is answered with that response instead of
+//! executing the service a second time. A segmented request is reassembled,
+//! with SegmentACKs for its windows, before it executes. A ComplexACK longer
+//! than the client
+//! accepts is sent in segments as the client's SegmentACKs ask for them, or
+//! aborted when the client cannot take it in segments.
+//!
+//! Like the segmentation machines they drive, the TSMs are pure state: the
+//! caller sends the APDUs of each [`ClientOutcome`] to their peers, and
+//! feeds the TSMs the APDUs received and the time passed.
+
+#[cfg(feature = "std")]
+use std::{collections::BTreeMap, fmt, time::Duration};
+
+#[cfg(not(feature = "std"))]
+use alloc::{collections::BTreeMap, string::ToString, vec, vec::Vec};
+
+#[cfg(not(feature = "std"))]
+use core::{fmt, time::Duration};
+
+use crate::app::segmentation::{
+    ReceiveOutcome, SegmentedReceive, SegmentedTransmit, TransmitOutcome, DEFAULT_SEGMENT_TIMEOUT,
+};
+use crate::app::{
+    Apdu, ApplicationConfig, ApplicationError, InvokeIdManager, MaxApduSize, MaxSegments, Result,
+};
+use crate::object::Segmentation;
+use crate::service::{
+    AbortReason, ConfirmedServiceChoice, PropertyAccessError, RejectReason, ServiceError,
+};
+
+/// Window size proposed for segmented transfers
+pub const DEFAULT_WINDOW_SIZE: u8 = 4;
+
+/// How a confirmed request ended
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Confirmation {
+    /// The service succeeded with no result
+    SimpleAck { service_choice: u8 },
+    /// The service succeeded with a result, reassembled if it was segmented
+    ComplexAck {
+        service_choice: u8,
+        service_data: Vec<u8>,
+    },
+    /// The service failed
+    Error {
+        service_choice: u8,
+        error: PropertyAccessError,
+    },
+    /// The server rejected the request
+    Reject { reason: RejectReason },
+    /// The transaction was aborted, by the server or by this client
+    Abort { server: bool, reason: AbortReason },
+    /// No reply came within the retries
+    Timeout,
+}
+
+impl Confirmation {
+    /// The result of a SimpleACK (empty) or ComplexACK, or the error the
+    /// request failed with
+    pub fn into_result(self) -> core::result::Result<Vec<u8>, ServiceError> {
+        match self {
+            Confirmation::SimpleAck { .. } => Ok(Vec::new()),
+            Confirmation::ComplexAck { service_data, .. } => Ok(service_data),
+            Confirmation::Error { error, .. } => Err(ServiceError::Failed(error)),
+            Confirmation::Reject { reason } => Err(ServiceError::Rejected(reason)),
+            Confirmation::Abort { reason, .. } => Err(ServiceError::Aborted(reason)),
+            Confirmation::Timeout => Err(ServiceError::Timeout),
+        }
+    }
+
+    /// The confirmation a reply APDU is, if it ends a transaction
+    fn of_reply(apdu: &Apdu) -> Option<Self> {
+        Some(match *apdu {
+            Apdu::SimpleAck { service_choice, .. } => Confirmation::SimpleAck { service_choice },
+            Apdu::ComplexAck {
+                service_choice,
+                ref service_data,
+                ..
+            } => Confirmation::ComplexAck {
+                service_choice,
+                service_data: service_data.clone(),
+            },
+            Apdu::Error {
+                service_choice,
+                error_class,
+                error_code,
+                ..
+            } => Confirmation::Error {
+                service_choice,
+                error: PropertyAccessError {
+                    error_class,
+                    error_code,
+                },
+            },
+            Apdu::Reject { reject_reason, .. } => Confirmation::Reject {
+                reason: RejectReason::from_u8(reject_reason),
+            },
+            Apdu::Abort {
+                server,
+                abort_reason,
+                ..
+            } => Confirmation::Abort {
+                server,
+                reason: AbortReason::from_u8(abort_reason),
+            },
+            _ => return None,
+        })
+    }
+}
+
+impl fmt::Display for Confirmation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Confirmation::SimpleAck { .. } => write!(f, "SimpleACK"),
+            Confirmation::ComplexAck { .. } => write!(f, "ComplexACK"),
+            Confirmation::Error { error, .. } => write!(
+                f,
+                "Error: class {} code {}",
+                error.error_class, error.error_code
+            ),
+            Confirmation::Reject { reason } => write!(f, "Rejected: {}", reason),
+            Confirmation::Abort { server, reason } => write!(
+                f,
+                "Aborted by the {}: {}",
+                if *server { "server" } else { "client" },
+                reason
+            ),
+            Confirmation::Timeout => write!(f, "No reply"),
+        }
+    }
+}
+
+/// What the client TSM does with a request, a received APDU or the time
+/// passed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientOutcome<A> {
+    /// APDUs to send, with their peer
+    pub sends: Vec<(A, Apdu)>,
+    /// Transactions that ended, by invoke ID
+    pub confirmations: Vec<(u8, Confirmation)>,
+}
+
+impl<A> Default for ClientOutcome<A> {
+    fn default() -> Self {
+        Self {
+            sends: Vec::new(),
+            confirmations: Vec::new(),
+        }
+    }
+}
+
+impl<A> ClientOutcome<A> {
+    /// Add the sends and confirmations of `other`
+    pub fn append(&mut self, other: &mut Self) {
+        self.sends.append(&mut other.sends);
+        self.confirmations.append(&mut other.confirmations);
+    }
+}
+
+/// States of a transaction between request and reply
+#[derive(Debug, Clone)]
+enum ClientState {
+    /// The request went out whole, or all its segments were acknowledged
+    AwaitConfirmation,
+    /// Sending the request's segments
+    SegmentedRequest(SegmentedTransmit),
+    /// Receiving the segments of the ComplexACK
+    SegmentedConfirmation(SegmentedReceive),
+}
+
+/// One confirmed request in progress
+#[derive(Debug, Clone)]
+struct ClientTransaction<A> {
+    /// The server
+    peer: A,
+    /// The request, unsegmented
+    request: Apdu,
+    /// Longest APDU the server accepts
+    max_apdu: usize,
+    /// Most segments the server accepts, `None` if not known
+    max_segments: Option<usize>,
+    state: ClientState,
+    /// Times the request was sent again
+    retries: u8,
+    /// Time since the request went out whole or its last segment was
+    /// acknowledged
+    elapsed: Duration,
+}
+
+/// The client side of confirmed requests
+#[derive(Debug, Clone)]
+pub struct ClientTsm<A> {
+    invoke_ids: InvokeIdManager,
+    transactions: BTreeMap<u8, ClientTransaction<A>>,
+    /// APDU_Timeout
+    apdu_timeout: Duration,
+    /// Number_Of_APDU_Retries
+    retries: u8,
+    /// APDU_Segment_Timeout
+    segment_timeout: Duration,
+    /// Window size proposed, and accepted, for segmented transfers
+    window_size: u8,
+    /// Whether segmented ComplexACKs are accepted
+    segmented_response_accepted: bool,
+    /// Most segments accepted in a ComplexACK, `None` for no limit
+    max_segments: Option<usize>,
+    /// Longest APDU accepted
+    max_apdu: usize,
+}
+
+impl<A: Clone + PartialEq> ClientTsm<A> {
+    /// Create a TSM with the timeouts, retries and segmentation of `config`
+    pub fn new(config: &ApplicationConfig) -> Self {
+        Self {
+            invoke_ids: InvokeIdManager::new(),
+            transactions: BTreeMap::new(),
+            apdu_timeout: Duration::from_millis(config.apdu_timeout as u64),
+            retries: config.apdu_retries,
+            segment_timeout: DEFAULT_SEGMENT_TIMEOUT,
+            window_size: DEFAULT_WINDOW_SIZE,
+            segmented_response_accepted: matches!(
+                config.segmentation,
+                Segmentation::Both | Segmentation::Receive
+            ),
+            max_segments: (config.max_segments > 0).then_some(config.max_segments as usize),
+            max_apdu: config.max_apdu_length as usize,
+        }
+    }
+
+    /// Set APDU_Timeout
+    pub fn set_apdu_timeout(&mut self, timeout: Duration) {
+        self.apdu_timeout = timeout;
+    }
+
+    /// Set Number_Of_APDU_Retries
+    pub fn set_apdu_retries(&mut self, retries: u8) {
+        self.retries = retries;
+    }
+
+    /// Set APDU_Segment_Timeout
+    pub fn set_segment_timeout(&mut self, timeout: Duration) {
+        self.segment_timeout = timeout;
+    }
+
+    /// Set the window size for segmented transfers
+    pub fn set_window_size(&mut self, window_size: u8) {
+        self.window_size = window_size;
+    }
+
+    /// Number of transactions in progress
+    pub fn active(&self) -> usize {
+        self.transactions.len()
+    }
+
+    /// Whether the transaction `invoke_id` is in progress
+    pub fn is_pending(&self, invoke_id: u8) -> bool {
+        self.transactions.contains_key(&invoke_id)
+    }
+
+    /// Start a confirmed request to `peer`, a server accepting APDUs of
+    /// `max_apdu` octets in up to `max_segments` segments (`None` if not
+    /// known), returning its invoke ID and what to send
+    pub fn request(
+        &mut self,
+        peer: A,
+        service_choice: ConfirmedServiceChoice,
+        service_data: Vec<u8>,
+        max_apdu: usize,
+        max_segments: Option<usize>,
+    ) -> Result<(u8, ClientOutcome<A>)> {
+        let invoke_id = self
+            .invoke_ids
+            .next_id()
+            .ok_or_else(|| ApplicationError::TransactionError("No free invoke ID".to_string()))?;
+        let request = Apdu::ConfirmedRequest {
+            segmented: false,
+            more_follows: false,
+            segmented_response_accepted: self.segmented_response_accepted,
+            max_segments: MaxSegments::from_count(self.max_segments),
+            max_response_size: MaxApduSize::from_size(self.max_apdu),
+            invoke_id,
+            sequence_number: None,
+            proposed_window_size: None,
+            service_choice,
+            service_data,
+        };
+        let mut transaction = ClientTransaction {
+            peer,
+            request,
+            max_apdu,
+            max_segments,
+            state: ClientState::AwaitConfirmation,
+            retries: 0,
+            elapsed: Duration::ZERO,
+        };
+        let sends = match self.send_request(&mut transaction) {
+            Ok(sends) => sends,
+            Err(e) => {
+                self.invoke_ids.release_id(invoke_id);
+                return Err(e);
+            }
+        };
+
+        let outcome = ClientOutcome {
+            sends: sends
+                .into_iter()
+                .map(|apdu| (transaction.peer.clone(), apdu))
+                .collect(),
+            confirmations: Vec::new(),
+        };
+        self.transactions.insert(invoke_id, transaction);
+        Ok((invoke_id, outcome))
+    }
+
+    /// Take an APDU received from `source`
+    ///
+    /// APDUs that belong to no transaction with `source` are ignored.
+    pub fn receive(&mut self, source: &A, apdu: &Apdu) -> ClientOutcome<A> {
+        let mut outcome = ClientOutcome::default();
+        let Some(invoke_id) = reply_invoke_id(apdu) else {
+            return outcome;
+        };
+        let segmented_response_accepted = self.segmented_response_accepted;
+        let (max_segments, window_size) = (self.max_segments, self.window_size);
+        let segment_timeout = self.segment_timeout;
+        let Some(transaction) = self
+            .transactions
+            .get_mut(&invoke_id)
+            .filter(|transaction| transaction.peer == *source)
+        else {
+            return outcome;
+        };
+
+        let mut sends = Vec::new();
+        let confirmation = match (&mut transaction.state, apdu) {
+            (ClientState::SegmentedRequest(transmit), Apdu::SegmentAck { .. }) => {
+                match transmit.segment_ack(apdu) {
+                    TransmitOutcome::Send(segments) => {
+                        sends = segments;
+                        None
+                    }
+                    TransmitOutcome::Complete => {
+                        transaction.state = ClientState::AwaitConfirmation;
+                        transaction.elapsed = Duration::ZERO;
+                        None
+                    }
+                    TransmitOutcome::Wait => None,
+                    TransmitOutcome::TimedOut => Some(Confirmation::Timeout),
+                }
+            }
+            (_, Apdu::SegmentAck { .. }) => None,
+            (ClientState::SegmentedConfirmation(receive), Apdu::ComplexAck { segmented, .. })
+                if *segmented =>
+            {
+                receive_segment(receive, apdu, &mut sends)
+            }
+            (_, Apdu::ComplexAck { segmented, .. }) if *segmented => {
+                if segmented_response_accepted {
+                    let mut receive = SegmentedReceive::new(max_segments, window_size);
+                    receive.set_segment_timeout(segment_timeout);
+                    let confirmation = receive_segment(&mut receive, apdu, &mut sends);
+                    transaction.state = ClientState::SegmentedConfirmation(receive);
+                    confirmation
+                } else {
+                    let reason = AbortReason::SegmentationNotSupported;
+                    sends.push(Apdu::abort(false, invoke_id, reason));
+                    Some(Confirmation::Abort {
+                        server: false,
+                        reason,
+                    })
+                }
+            }
+            (_, reply) => Confirmation::of_reply(reply),
+        };
+
+        outcome.sends = sends
+            .into_iter()
+            .map(|apdu| (transaction.peer.clone(), apdu))
+            .collect();
+        if let Some(confirmation) = confirmation {
+            self.finish(invoke_id);
+            outcome.confirmations.push((invoke_id, confirmation));
+        }
+        outcome
+    }
+
+    /// Count `elapsed` against the timers of every transaction, sending
+    /// requests and segments again and ending the transactions out of
+    /// retries
+    pub fn advance_time(&mut self, elapsed: Duration) -> ClientOutcome<A> {
+        let mut outcome = ClientOutcome::default();
+        let mut transactions = core::mem::take(&mut self.transactions);
+        for (invoke_id, transaction) in transactions.iter_mut() {
+            let mut sends = Vec::new();
+            let timed_out = match &mut transaction.state {
+                ClientState::AwaitConfirmation => {
+                    transaction.elapsed += elapsed;
+                    if transaction.elapsed < self.apdu_timeout {
+                        false
+                    } else if transaction.retries < self.retries {
+                        transaction.retries += 1;
+                        match self.send_request(transaction) {
+                            Ok(segments) => {
+                                sends = segments;
+                                false
+                            }
+                            Err(_) => true,
+                        }
+                    } else {
+                        true
+                    }
+                }
+                ClientState::SegmentedRequest(transmit) => match transmit.advance_time(elapsed) {
+                    TransmitOutcome::Send(segments) => {
+                        sends = segments;
+                        false
+                    }
+                    TransmitOutcome::TimedOut => true,
+                    _ => false,
+                },
+                ClientState::SegmentedConfirmation(receive) => {
+                    receive.advance_time(elapsed) == ReceiveOutcome::TimedOut
+                }
+            };
+            outcome.sends.extend(
+                sends
+                    .into_iter()
+                    .map(|apdu| (transaction.peer.clone(), apdu)),
+            );
+            if timed_out {
+                outcome
+                    .confirmations
+                    .push((*invoke_id, Confirmation::Timeout));
+            }
+        }
+        self.transactions = transactions;
+        for (invoke_id, _) in &outcome.confirmations {
+            self.finish(*invoke_id);
+        }
+        outcome
+    }
+
+    /// Give up on the transaction `invoke_id` without a confirmation
+    pub fn cancel(&mut self, invoke_id: u8) -> bool {
+        self.finish(invoke_id)
+    }
+
+    /// Send the request of `transaction` from the start, in segments if the
+    /// server needs them
+    fn send_request(&self, transaction: &mut ClientTransaction<A>) -> Result<Vec<Apdu>> {
+        transaction.elapsed = Duration::ZERO;
+        if !SegmentedTransmit::needs_segmentation(&transaction.request, transaction.max_apdu) {
+            transaction.state = ClientState::AwaitConfirmation;
+            return Ok(vec![transaction.request.clone()]);
+        }
+        let mut transmit = SegmentedTransmit::new(
+            transaction.request.clone(),
+            transaction.max_apdu,
+            transaction.max_segments,
+            self.window_size,
+        )?;
+        transmit.set_segment_timeout(self.segment_timeout);
+        transmit.set_retries(self.retries);
+        let first = transmit.start();
+        transaction.state = ClientState::SegmentedRequest(transmit);
+        Ok(first)
+    }
+
+    fn finish(&mut self, invoke_id: u8) -> bool {
+        self.invoke_ids.release_id(invoke_id);
+        self.transactions.remove(&invoke_id).is_some()
+    }
+}
+
+/// Invoke ID of an APDU a server sends a client
+fn reply_invoke_id(apdu: &Apdu) -> Option<u8> {
+    match *apdu {
+        Apdu::SimpleAck { invoke_id, .. }
+        | Apdu::ComplexAck { invoke_id, .. }
+        | Apdu::Error { invoke_id, .. }
+        | Apdu::Reject { invoke_id, .. }
+        | Apdu::SegmentAck {
+            server: true,
+            invoke_id,
+            ..
+        }
+        | Apdu::Abort {
+            server: true,
+            invoke_id,
+            ..
+        } => Some(invoke_id),
+        _ => None,
+    }
+}
+
+/// Pass a segment of the ComplexACK to `receive`, collecting what to send
+fn receive_segment(
+    receive: &mut SegmentedReceive,
+    apdu: &Apdu,
+    sends: &mut Vec<Apdu>,
+) -> Option<Confirmation> {
+    match receive.segment(apdu) {
+        ReceiveOutcome::Ack(ack) => {
+            sends.push(ack);
+            None
+        }
+        ReceiveOutcome::Wait => None,
+        ReceiveOutcome::Complete { ack, apdu } => {
+            sends.push(ack);
+            Confirmation::of_reply(&apdu)
+        }
+        ReceiveOutcome::Abort(abort) => {
+            let confirmation = Confirmation::of_reply(&abort);
+            sends.push(abort);
+            confirmation
+        }
+        ReceiveOutcome::TimedOut => Some(Confirmation::Timeout),
+    }
+}
+
+/// What a server does with a confirmed request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RequestOutcome {
+    /// A new request: execute the service and pass the response to
+    /// [`ServerTsm::respond`]
+    Execute,
+    /// A retransmission of a request still being executed: drop it
+    InProgress,
+    /// A retransmission of a request already answered: send the response
+    /// again
+    Resend(Apdu),
+    /// A segment of a segmented request: send the SegmentACK or Abort, if
+    /// any, and wait for the rest
+    Segment(Option<Apdu>),
+    /// The last segment of a segmented request: send the SegmentACK, then
+    /// execute the reassembled request
+    Reassembled { ack: Apdu, request: Apdu },
+}
+
+/// States of a confirmed request a server received
+#[derive(Debug, Clone)]
+enum ServerState {
+    /// Receiving the segments of the request
+    SegmentedRequest(SegmentedReceive),
+    /// The service executes
+    AwaitResponse,
+    /// The response went out whole
+    Responded,
+    /// Sending the response's segments, or sent them all
+    SegmentedResponse(SegmentedTransmit),
+}
+
+/// A confirmed request a server received
+#[derive(Debug, Clone)]
+struct ServerTransaction {
+    /// The request, or the first segment of a segmented one, to tell a
+    /// retransmission from a new request reusing the invoke ID
+    request: Apdu,
+    /// The response sent, `None` while the service executes
+    response: Option<Apdu>,
+    state: ServerState,
+    /// Time since the request arrived or the response was sent
+    elapsed: Duration,
+}
+
+/// The server side of confirmed requests, detecting duplicates,
+/// reassembling segmented requests and sending responses longer than the
+/// client accepts in segments
+#[derive(Debug, Clone)]
+pub struct ServerTsm<A> {
+    transactions: BTreeMap<(A, u8), ServerTransaction>,
+    /// How long a request and its response are remembered
+    retention: Duration,
+    /// APDU_Segment_Timeout
+    segment_timeout: Duration,
+    /// Number_Of_APDU_Retries, for the windows of a segmented response
+    retries: u8,
+    /// Window size proposed, and accepted, for segmented transfers
+    window_size: u8,
+    /// Whether segmented requests are accepted
+    segmented_request_accepted: bool,
+    /// Most segments accepted in a request, `None` for no limit
+    max_segments: Option<usize>,
+    /// Whether responses may be sent in segments
+    segmented_response_supported: bool,
+    /// Longest APDU sent
+    max_apdu: usize,
+}
+
+impl<A: Ord + Clone> ServerTsm<A> {
+    /// Create a TSM remembering requests for as long as a client with the
+    /// APDU_Timeout and Number_Of_APDU_Retries of `config` retransmits, and
+    /// segmenting responses as `config` allows
+    pub fn new(config: &ApplicationConfig) -> Self {
+        Self {
+            transactions: BTreeMap::new(),
+            retention: Duration::from_millis(config.apdu_timeout as u64)
+                * (config.apdu_retries as u32 + 1),
+            segment_timeout: DEFAULT_SEGMENT_TIMEOUT,
+            retries: config.apdu_retries,
+            window_size: DEFAULT_WINDOW_SIZE,
+            segmented_request_accepted: matches!(
+                config.segmentation,
+                Segmentation::Both | Segmentation::Receive
+            ),
+            max_segments: (config.max_segments > 0).then_some(config.max_segments as usize),
+            segmented_response_supported: matches!(
+                config.segmentation,
+                Segmentation::Both | Segmentation::Transmit
+            ),
+            max_apdu: config.max_apdu_length as usize,
+        }
+    }
+
+    /// Set how long a request and its response are remembered
+    pub fn set_retention(&mut self, retention: Duration) {
+        self.retention = retention;
+    }
+
+    /// Set APDU_Segment_Timeout
+    pub fn set_segment_timeout(&mut self, timeout: Duration) {
+        self.segment_timeout = timeout;
+    }
+
+    /// Number of requests remembered
+    pub fn active(&self) -> usize {
+        self.transactions.len()
+    }
+
+    /// Take a confirmed request received from `source`
+    ///
+    /// A request with the invoke ID of a remembered one from the same
+    /// source is a retransmission if it is the same request; otherwise it
+    /// starts a new transaction. A retransmission of a request answered in
+    /// segments starts the segments over. The segments of a segmented
+    /// request are collected until the last one arrives; the request is
+    /// aborted with buffer-overflow when it has more segments than accepted,
+    /// or with segmentation-not-supported when segmented requests are not
+    /// accepted at all. APDUs other than confirmed requests are always
+    /// executed.
+    pub fn request(&mut self, source: &A, apdu: &Apdu) -> RequestOutcome {
+        let Apdu::ConfirmedRequest {
+            invoke_id,
+            segmented,
+            sequence_number,
+            ..
+        } = *apdu
+        else {
+            return RequestOutcome::Execute;
+        };
+        let key = (source.clone(), invoke_id);
+        if segmented {
+            match self
+                .transactions
+                .get(&key)
+                .map(|transaction| &transaction.state)
+            {
+                Some(ServerState::SegmentedRequest(_)) => return self.segment(key, apdu),
+                // Segments repeated after the request was reassembled
+                Some(_) if sequence_number != Some(0) => return RequestOutcome::Segment(None),
+                _ => {}
+            }
+        }
+        if let Some(transaction) = self
+            .transactions
+            .get_mut(&key)
+            .filter(|transaction| transaction.request == *apdu)
+        {
+            return match (&mut transaction.state, &transaction.response) {
+                (ServerState::SegmentedResponse(transmit), _) => {
+                    transaction.elapsed = Duration::ZERO;
+                    match transmit.start().pop() {
+                        Some(first) => RequestOutcome::Resend(first),
+                        None => RequestOutcome::InProgress,
+                    }
+                }
+                (_, Some(response)) => RequestOutcome::Resend(response.clone()),
+                (_, None) => RequestOutcome::InProgress,
+            };
+        }
+
+        let state = if !segmented {
+            ServerState::AwaitResponse
+        } else if self.segmented_request_accepted {
+            let mut receive = SegmentedReceive::new(self.max_segments, self.window_size);
+            receive.set_segment_timeout(self.segment_timeout);
+            ServerState::SegmentedRequest(receive)
+        } else {
+            let reason = AbortReason::SegmentationNotSupported;
+            return RequestOutcome::Segment(Some(Apdu::abort(true, invoke_id, reason)));
+        };
+        self.transactions.insert(
+            key.clone(),
+            ServerTransaction {
+                request: apdu.clone(),
+                response: None,
+                state,
+                elapsed: Duration::ZERO,
+            },
+        );
+        if segmented {
+            return self.segment(key, apdu);
+        }
+        RequestOutcome::Execute
+    }
+
+    /// Pass a segment to the transaction `key` reassembling its request
+    fn segment(&mut self, key: (A, u8), apdu: &Apdu) -> RequestOutcome {
+        let Some(transaction) = self.transactions.get_mut(&key) else {
+            return RequestOutcome::Segment(None);
+        };
+        let ServerState::SegmentedRequest(receive) = &mut transaction.state else {
+            return RequestOutcome::Segment(None);
+        };
+        match receive.segment(apdu) {
+            ReceiveOutcome::Ack(ack) => RequestOutcome::Segment(Some(ack)),
+            ReceiveOutcome::Wait => RequestOutcome::Segment(None),
+            ReceiveOutcome::Complete { ack, apdu } => {
+                transaction.state = ServerState::AwaitResponse;
+                transaction.elapsed = Duration::ZERO;
+                RequestOutcome::Reassembled { ack, request: apdu }
+            }
+            ReceiveOutcome::Abort(abort) => {
+                self.transactions.remove(&key);
+                RequestOutcome::Segment(Some(abort))
+            }
+            ReceiveOutcome::TimedOut => {
+                self.transactions.remove(&key);
+                RequestOutcome::Segment(None)
+            }
+        }
+    }
+
+    /// Record the response to the request `invoke_id` from `source`, `None`
+    /// if the service sent none, and return what to send
+    ///
+    /// A ComplexACK longer than the client accepts goes out in segments,
+    /// starting with the first one returned here; the rest follow its
+    /// SegmentACKs. A client that does not accept segmented responses, or
+    /// not that many segments, gets an Abort with segmentation-not-supported
+    /// instead.
+    pub fn respond(&mut self, source: &A, invoke_id: u8, response: Option<Apdu>) -> Option<Apdu> {
+        let key = (source.clone(), invoke_id);
+        let Some(response) = response else {
+            self.transactions.remove(&key);
+            return None;
+        };
+        let Some(transaction) = self.transactions.get_mut(&key) else {
+            return Some(response);
+        };
+        transaction.elapsed = Duration::ZERO;
+        let Apdu::ConfirmedRequest {
+            segmented_response_accepted,
+            max_segments,
+            max_response_size,
+            ..
+        } = transaction.request
+        else {
+            return Some(response);
+        };
+        let max_apdu = max_response_size.size().min(self.max_apdu);
+        if !matches!(response, Apdu::ComplexAck { .. })
+            || !SegmentedTransmit::needs_segmentation(&response, max_apdu)
+        {
+            transaction.state = ServerState::Responded;
+            transaction.response = Some(response.clone());
+            return Some(response);
+        }
+
+        let transmit = (segmented_response_accepted && self.segmented_response_supported)
+            .then(|| {
+                SegmentedTransmit::new(
+                    response.clone(),
+                    max_apdu,
+                    max_segments.count(),
+                    self.window_size,
+                )
+                .ok()
+            })
+            .flatten();
+        let Some(mut transmit) = transmit else {
+            let abort = Apdu::abort(true, invoke_id, AbortReason::SegmentationNotSupported);
+            transaction.state = ServerState::Responded;
+            transaction.response = Some(abort.clone());
+            return Some(abort);
+        };
+        transmit.set_segment_timeout(self.segment_timeout);
+        transmit.set_retries(self.retries);
+        let first = transmit.start().pop();
+        transaction.state = ServerState::SegmentedResponse(transmit);
+        transaction.response = Some(response);
+        first
+    }
+
+    /// Take a SegmentACK `source` sent for a segmented response, returning
+    /// the segments to send next
+    ///
+    /// SegmentACKs for no response being sent in segments are ignored.
+    pub fn segment_ack(&mut self, source: &A, apdu: &Apdu) -> Vec<Apdu> {
+        let Apdu::SegmentAck {
+            server: false,
+            invoke_id,
+            ..
+        } = *apdu
+        else {
+            return Vec::new();
+        };
+        let key = (source.clone(), invoke_id);
+        let Some(transaction) = self.transactions.get_mut(&key) else {
+            return Vec::new();
+        };
+        let ServerState::SegmentedResponse(transmit) = &mut transaction.state else {
+            return Vec::new();
+        };
+        match transmit.segment_ack(apdu) {
+            TransmitOutcome::Send(segments) => segments,
+            TransmitOutcome::Complete => {
+                transaction.elapsed = Duration::ZERO;
+                Vec::new()
+            }
+            TransmitOutcome::Wait => Vec::new(),
+            TransmitOutcome::TimedOut => {
+                self.transactions.remove(&key);
+                Vec::new()
+            }
+        }
+    }
+
+    /// Forget the request `invoke_id` from `source`, as when the client
+    /// aborts it
+    pub fn forget(&mut self, source: &A, invoke_id: u8) -> bool {
+        self.transactions
+            .remove(&(source.clone(), invoke_id))
+            .is_some()
+    }
+
+    /// Count `elapsed` against the requests remembered, forgetting those
+    /// older than the retention, and return the segments to send again to
+    /// clients that did not acknowledge them in time
+    ///
+    /// A segmented request whose client stays silent for four segment
+    /// timeouts, and a segmented response whose client stays silent through
+    /// every retry, are given up.
+    pub fn advance_time(&mut self, elapsed: Duration) -> Vec<(A, Apdu)> {
+        let retention = self.retention;
+        let mut sends = Vec::new();
+        self.transactions
+            .retain(|(source, _), transaction| match &mut transaction.state {
+                ServerState::SegmentedRequest(receive) => {
+                    receive.advance_time(elapsed) != ReceiveOutcome::TimedOut
+                }
+                ServerState::SegmentedResponse(transmit) if !transmit.is_complete() => {
+                    match transmit.advance_time(elapsed) {
+                        TransmitOutcome::Send(segments) => {
+                            sends.extend(segments.into_iter().map(|apdu| (source.clone(), apdu)));
+                            true
+                        }
+                        TransmitOutcome::TimedOut => false,
+                        _ => true,
+                    }
+                }
+                _ => {
+                    transaction.elapsed += elapsed;
+                    transaction.elapsed < retention
+                }
+            });
+        sends
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SERVER: u8 = 5;
+
+    fn tsm() -> ClientTsm<u8> {
+        ClientTsm::new(&ApplicationConfig {
+            apdu_timeout: 1000,
+            apdu_retries: 2,
+            ..ApplicationConfig::default()
+        })
+    }
+
+    fn read_property(tsm: &mut ClientTsm<u8>, length: usize, max_apdu: usize) -> (u8, Vec<Apdu>) {
+        let (invoke_id, outcome) = tsm
+            .request(
+                SERVER,
+                ConfirmedServiceChoice::ReadProperty,
+                vec![0x0C; length],
+                max_apdu,
+                None,
+            )
+            .unwrap();
+        assert!(outcome.sends.iter().all(|(peer, _)| *peer == SERVER));
+        (
+            invoke_id,
+            outcome.sends.into_iter().map(|(_, apdu)| apdu).collect(),
+        )
+    }
+
+    #[test]
+    fn test_replies_end_transactions() {
+        let mut tsm = tsm();
+        let (first, sends) = read_property(&mut tsm, 8, 1476);
+        let (second, _) = read_property(&mut tsm, 8, 1476);
+        let (third, _) = read_property(&mut tsm, 8, 1476);
+        assert_ne!(first, second);
+        assert!(matches!(
+            sends[..],
+            [Apdu::ConfirmedRequest {
+                segmented: false,
+                segmented_response_accepted: true,
+                ..
+            }]
+        ));
+        assert_eq!(tsm.active(), 3);
+
+        // A reply from another peer, or with no transaction, is not ours
+        let ack = Apdu::ComplexAck {
+            segmented: false,
+            more_follows: false,
+            invoke_id: first,
+            sequence_number: None,
+            proposed_window_size: None,
+            service_choice: 12,
+            service_data: vec![0x3E],
+        };
+        assert!(tsm.receive(&9, &ack).confirmations.is_empty());
+        let outcome = tsm.receive(&SERVER, &ack);
+        assert_eq!(
+            outcome.confirmations,
+            [(
+                first,
+                Confirmation::ComplexAck {
+                    service_choice: 12,
+                    service_data: vec![0x3E]
+                }
+            )]
+        );
+        assert!(tsm.receive(&SERVER, &ack).confirmations.is_empty());
+
+        let error = Apdu::Error {
+            invoke_id: second,
+            service_choice: 12,
+            error_class: 1,
+            error_code: 31,
+            error_parameters: Vec::new(),
+        };
+        let (_, confirmation) = tsm.receive(&SERVER, &error).confirmations.remove(0);
+        assert_eq!(confirmation.to_string(), "Error: class 1 code 31");
+        assert!(matches!(
+            confirmation.into_result(),
+            Err(ServiceError::Failed(PropertyAccessError {
+                error_class: 1,
+                error_code: 31
+            }))
+        ));
+
+        let reject = Apdu::Reject {
+            invoke_id: third,
+            reject_reason: 9,
+        };
+        assert_eq!(
+            tsm.receive(&SERVER, &reject).confirmations,
+            [(
+                third,
+                Confirmation::Reject {
+                    reason: RejectReason::UnrecognizedService
+                }
+            )]
+        );
+        assert_eq!(tsm.active(), 0);
+    }
+
+    #[test]
+    fn test_timeout_and_retries() {
+        let mut tsm = tsm();
+        let (invoke_id, _) = read_property(&mut tsm, 8, 1476);
+        assert_eq!(
+            tsm.advance_time(Duration::from_millis(999)),
+            ClientOutcome::default()
+        );
+        // Sent again twice, then given up
+        for _ in 0..2 {
+            let outcome = tsm.advance_time(Duration::from_millis(1));
+            assert!(matches!(
+                outcome.sends[..],
+                [(SERVER, Apdu::ConfirmedRequest { invoke_id: id, .. })] if id == invoke_id
+            ));
+            tsm.advance_time(Duration::from_millis(999));
+        }
+        let outcome = tsm.advance_time(Duration::from_millis(1));
+        assert_eq!(outcome.confirmations, [(invoke_id, Confirmation::Timeout)]);
+        assert!(!tsm.is_pending(invoke_id));
+    }
+
+    #[test]
+    fn test_segmented_request_and_response() {
+        let mut tsm = tsm();
+        let (invoke_id, sends) = read_property(&mut tsm, 500, 206);
+        assert!(matches!(
+            sends[..],
+            [Apdu::ConfirmedRequest {
+                segmented: true,
+                more_follows: true,
+                ..
+            }]
+        ));
+        let segment_ack = |sequence_number| Apdu::SegmentAck {
+            negative: false,
+            server: true,
+            invoke_id,
+            sequence_number,
+            window_size: 4,
+        };
+        let outcome = tsm.receive(&SERVER, &segment_ack(0));
+        assert_eq!(outcome.sends.len(), 2);
+        assert!(tsm.receive(&SERVER, &segment_ack(2)).sends.is_empty());
+
+        // The ComplexACK comes back in two segments
+        let segment = |sequence_number: u8, more_follows| Apdu::ComplexAck {
+            segmented: true,
+            more_follows,
+            invoke_id,
+            sequence_number: Some(sequence_number),
+            proposed_window_size: Some(2),
+            service_choice: 12,
+            service_data: vec![sequence_number; 3],
+        };
+        let outcome = tsm.receive(&SERVER, &segment(0, true));
+        assert!(matches!(
+            outcome.sends[..],
+            [(
+                SERVER,
+                Apdu::SegmentAck {
+                    server: false,
+                    sequence_number: 0,
+                    ..
+                }
+            )]
+        ));
+        let outcome = tsm.receive(&SERVER, &segment(1, false));
+        assert_eq!(outcome.sends.len(), 1);
+        assert_eq!(
+            outcome.confirmations,
+            [(
+                invoke_id,
+                Confirmation::ComplexAck {
+                    service_choice: 12,
+                    service_data: vec![0, 0, 0, 1, 1, 1]
+                }
+            )]
+        );
+
+        // Without segmented responses, a segmented ComplexACK is aborted
+        let mut tsm = ClientTsm::new(&ApplicationConfig {
+            segmentation: Segmentation::NoSegmentation,
+            ..ApplicationConfig::default()
+        });
+        let (invoke_id, _) = read_property(&mut tsm, 8, 1476);
+        let outcome = tsm.receive(&SERVER, &segment(0, true));
+        let reason = AbortReason::SegmentationNotSupported;
+        assert_eq!(
+            outcome.sends,
+            [(SERVER, Apdu::abort(false, invoke_id, reason))]
+        );
+        assert_eq!(
+            outcome.confirmations,
+            [(
+                invoke_id,
+                Confirmation::Abort {
+                    server: false,
+                    reason
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn test_server_duplicate_requests() {
+        let mut tsm = ServerTsm::new(&ApplicationConfig {
+            apdu_timeout: 1000,
+            apdu_retries: 2,
+            ..ApplicationConfig::default()
+        });
+        let request = |invoke_id, service_data| Apdu::ConfirmedRequest {
+            segmented: false,
+            more_follows: false,
+            segmented_response_accepted: true,
+            max_segments: MaxSegments::Unspecified,
+            max_response_size: MaxApduSize::Up1476,
+            invoke_id,
+            sequence_number: None,
+            proposed_window_size: None,
+            service_choice: ConfirmedServiceChoice::ReadProperty,
+            service_data,
+        };
+        let ack = Apdu::SimpleAck {
+            invoke_id: 1,
+            service_choice: 12,
+        };
+
+        assert_eq!(
+            tsm.request(&SERVER, &request(1, vec![1])),
+            RequestOutcome::Execute
+        );
+        assert_eq!(
+            tsm.request(&SERVER, &request(1, vec![1])),
+            RequestOutcome::InProgress
+        );
+        tsm.respond(&SERVER, 1, Some(ack.clone()));
+        assert_eq!(
+            tsm.request(&SERVER, &request(1, vec![1])),
+            RequestOutcome::Resend(ack.clone())
+        );
+
+        // Another source, or another request with the invoke ID, is new
+        assert_eq!(
+            tsm.request(&9, &request(1, vec![1])),
+            RequestOutcome::Execute
+        );
+        assert_eq!(
+            tsm.request(&SERVER, &request(1, vec![2])),
+            RequestOutcome::Execute
+        );
+        assert_eq!(tsm.active(), 2);
+
+        // Responses are remembered while the client could retransmit
+        tsm.respond(&SERVER, 1, Some(ack.clone()));
+        tsm.advance_time(Duration::from_millis(2999));
+        assert_eq!(
+            tsm.request(&SERVER, &request(1, vec![2])),
+            RequestOutcome::Resend(ack)
+        );
+        tsm.advance_time(Duration::from_millis(1));
+        assert_eq!(tsm.active(), 0);
+        assert_eq!(
+            tsm.request(&SERVER, &request(1, vec![2])),
+            RequestOutcome::Execute
+        );
+        assert!(tsm.forget(&SERVER, 1));
+    }
+
+    #[test]
+    fn test_server_segmented_request_limits() {
+        let segment = |sequence_number, more_follows| Apdu::ConfirmedRequest {
+            segmented: true,
+            more_follows,
+            segmented_response_accepted: false,
+            max_segments: MaxSegments::Unspecified,
+            max_response_size: MaxApduSize::Up1476,
+            invoke_id: 4,
+            sequence_number: Some(sequence_number),
+            proposed_window_size: Some(4),
+            service_choice: ConfirmedServiceChoice::WritePropertyMultiple,
+            service_data: vec![sequence_number; 10],
+        };
+        let abort =
+            |reason: AbortReason| RequestOutcome::Segment(Some(Apdu::abort(true, 4, reason)));
+
+        // More segments than accepted overflow the buffer
+        let mut tsm = ServerTsm::new(&ApplicationConfig {
+            max_segments: 2,
+            ..ApplicationConfig::default()
+        });
+        assert!(matches!(
+            tsm.request(&1, &segment(0, true)),
+            RequestOutcome::Segment(Some(Apdu::SegmentAck {
+                sequence_number: 0,
+                ..
+            }))
+        ));
+        assert_eq!(
+            tsm.request(&1, &segment(1, true)),
+            RequestOutcome::Segment(None)
+        );
+        assert_eq!(
+            tsm.request(&1, &segment(2, false)),
+            abort(AbortReason::BufferOverflow)
+        );
+        assert_eq!(tsm.active(), 0);
+
+        // A server only transmitting segments takes no segmented requests
+        let mut tsm = ServerTsm::new(&ApplicationConfig {
+            segmentation: Segmentation::Transmit,
+            ..ApplicationConfig::default()
+        });
+        assert_eq!(
+            tsm.request(&1, &segment(0, true)),
+            abort(AbortReason::SegmentationNotSupported)
+        );
+        assert_eq!(tsm.active(), 0);
+    }
+
+    #[test]
+    fn test_server_segmented_response() {
+        let mut tsm = ServerTsm::new(&ApplicationConfig::default());
+        let request =
+            |invoke_id, segmented_response_accepted, max_segments| Apdu::ConfirmedRequest {
+                segmented: false,
+                more_follows: false,
+                segmented_response_accepted,
+                max_segments,
+                max_response_size: MaxApduSize::Up50,
+                invoke_id,
+                sequence_number: None,
+                proposed_window_size: None,
+                service_choice: ConfirmedServiceChoice::ReadPropertyMultiple,
+                service_data: vec![1],
+            };
+        let response = |invoke_id| Apdu::ComplexAck {
+            segmented: false,
+            more_follows: false,
+            invoke_id,
+            sequence_number: None,
+            proposed_window_size: None,
+            service_choice: 14,
+            service_data: vec![7; 200],
+        };
+        let segment_ack = |sequence_number, window_size| Apdu::SegmentAck {
+            negative: false,
+            server: false,
+            invoke_id: 1,
+            sequence_number,
+            window_size,
+        };
+        let sequence = |segments: &[Apdu]| -> Vec<(u8, bool)> {
+            segments
+                .iter()
+                .map(|segment| match segment {
+                    Apdu::ComplexAck {
+                        segmented: true,
+                        more_follows,
+                        sequence_number: Some(sequence),
+                        ..
+                    } => (*sequence, *more_follows),
+                    other => panic!("Expected a segment, got {:?}", other),
+                })
+                .collect()
+        };
+
+        // 200 octets go in five segments of 45
+        let unspecified = MaxSegments::Unspecified;
+        assert_eq!(
+            tsm.request(&SERVER, &request(1, true, unspecified)),
+            RequestOutcome::Execute
+        );
+        let first = tsm.respond(&SERVER, 1, Some(response(1))).unwrap();
+        assert_eq!(sequence(core::slice::from_ref(&first)), [(0, true)]);
+        let segments = tsm.segment_ack(&SERVER, &segment_ack(0, 2));
+        assert_eq!(sequence(&segments), [(1, true), (2, true)]);
+
+        // An unacknowledged window goes again
+        assert!(tsm.advance_time(Duration::from_millis(1999)).is_empty());
+        let resent = tsm.advance_time(Duration::from_millis(1));
+        assert_eq!(
+            resent,
+            [(SERVER, segments[0].clone()), (SERVER, segments[1].clone())]
+        );
+
+        let segments = tsm.segment_ack(&SERVER, &segment_ack(2, 2));
+        assert_eq!(sequence(&segments), [(3, true), (4, false)]);
+        assert!(tsm.segment_ack(&SERVER, &segment_ack(4, 2)).is_empty());
+        assert!(tsm.advance_time(DEFAULT_SEGMENT_TIMEOUT).is_empty());
+
+        // A retransmitted request has the segments start over
+        assert_eq!(
+            tsm.request(&SERVER, &request(1, true, unspecified)),
+            RequestOutcome::Resend(first)
+        );
+
+        // A client that cannot take the segments has the response aborted
+        let abort = Apdu::abort(true, 2, AbortReason::SegmentationNotSupported);
+        tsm.request(&SERVER, &request(2, false, unspecified));
+        assert_eq!(tsm.respond(&SERVER, 2, Some(response(2))), Some(abort));
+        let abort = Apdu::abort(true, 3, AbortReason::SegmentationNotSupported);
+        tsm.request(&SERVER, &request(3, true, MaxSegments::Four));
+        assert_eq!(tsm.respond(&SERVER, 3, Some(response(3))), Some(abort));
+
+        // A client that stays silent is given up after the retries
+        let mut tsm = ServerTsm::new(&ApplicationConfig {
+            apdu_retries: 1,
+            ..ApplicationConfig::default()
+        });
+        tsm.request(&SERVER, &request(1, true, unspecified));
+        tsm.respond(&SERVER, 1, Some(response(1)));
+        assert_eq!(tsm.advance_time(DEFAULT_SEGMENT_TIMEOUT).len(), 1);
+        assert!(tsm.advance_time(DEFAULT_SEGMENT_TIMEOUT).is_empty());
+        assert_eq!(tsm.active(), 0);
+    }
+}
@@ -0,0 +1,1106 @@
+//! Asynchronous BACnet client and server
+//!
+//! [`AsyncBacnetClient`] sends requests over any [`AsyncDataLink`]. Its
+//! confirmed requests run on a [`ClientTsm`]: a task owned by the client
+//! reads the link and passes each reply to the TSM, which matches it to its
+//! transaction, so many requests can be outstanding at once. Each confirmed
+//! request resolves when its ACK, Error, Reject or Abort arrives, a segmented
+//! ComplexACK once every segment is in, or fails once every retry has timed
+//! out. Unconfirmed requests heard on the link, such as I-Am or COV
+//! notifications, go to the receivers returned by
+//! [`subscribe`](AsyncBacnetClient::subscribe).
+//!
+//! [`serve`] is the server side: a loop, typically spawned as a task, that
+//! passes incoming requests to an [`ApplicationLayerHandler`] and sends its
+//! replies back.
+//!
+//! Requests go to a [`BacnetAddress`]: a station on the local network, or
+//! through a router a station on another network. The client keeps the
+//! router to each remote network, learned from the SNET of replies and from
+//! I-Am-Router-To-Network, and looks for an unknown one with
+//! Who-Is-Router-To-Network before sending; a data link address stands for a
+//! station on the local network.
+//!
+//! A link carries either a client or a server, since both read every frame
+//! from it.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    error::Error,
+    fmt,
+    io::ErrorKind,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use tokio::{
+    sync::{broadcast, oneshot, Notify},
+    task::JoinHandle,
+};
+
+use crate::{
+    app::{
+        tsm::{ClientOutcome, ClientTsm, Confirmation},
+        Apdu, ApplicationConfig, ApplicationError, ApplicationLayerHandler, MaxApduSize,
+    },
+    datalink::{AsyncDataLink, DataLinkAddress, DataLinkError, DataLinkType},
+    encoding::EncodingError,
+    network::{message::NetworkMessage, BacnetAddress, NetworkAddress, Npdu},
+    object::ObjectIdentifier,
+    service::{
+        AbortReason, ConfirmedServiceChoice, IAmRequest, ReadPropertyAck, ReadPropertyRequest,
+        RejectReason, UnconfirmedServiceChoice, WhoIsRequest, WritePropertyRequest,
+    },
+};
+
+/// Default time to wait for an acknowledgement, APDU_Timeout
+pub const DEFAULT_APDU_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Default number of times a request is sent again, Number_Of_APDU_Retries
+pub const DEFAULT_APDU_RETRIES: u8 = 3;
+
+/// Number of unconfirmed requests buffered for each subscriber
+const UNCONFIRMED_CAPACITY: usize = 64;
+
+/// How often a request waiting for its confirmation counts the time passed
+/// against the transaction timers
+const TICK: Duration = Duration::from_millis(10);
+
+/// Result type for asynchronous requests
+pub type Result<T> = std::result::Result<T, RequestError>;
+
+/// Ways a request can fail
+#[derive(Debug)]
+pub enum RequestError {
+    /// The data link failed to send the request
+    DataLink(DataLinkError),
+    /// The request or the acknowledgement could not be encoded or decoded
+    Encoding(EncodingError),
+    /// No acknowledgement arrived after every retry
+    Timeout,
+    /// All 256 invoke IDs are waiting for acknowledgements
+    NoInvokeId,
+    /// The request could not be started, such as one too long to segment
+    Application(ApplicationError),
+    /// The device answered with an Error PDU
+    Error {
+        /// Error class
+        error_class: u32,
+        /// Error code
+        error_code: u32,
+    },
+    /// The device rejected the request, with the reject reason
+    Reject(RejectReason),
+    /// The transaction was aborted, with the abort reason
+    Abort(AbortReason),
+    /// DeviceCommunicationControl holds back requests from the device
+    CommunicationDisabled,
+}
+
+impl fmt::Display for RequestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RequestError::DataLink(e) => write!(f, "Data link error: {}", e),
+            RequestError::Encoding(e) => write!(f, "Encoding error: {}", e),
+            RequestError::Timeout => write!(f, "Request timeout"),
+            RequestError::NoInvokeId => write!(f, "No invoke ID free"),
+            RequestError::Application(e) => write!(f, "Application error: {}", e),
+            RequestError::Error {
+                error_class,
+                error_code,
+            } => write!(f, "Error class {} code {}", error_class, error_code),
+            RequestError::Reject(reason) => write!(f, "Rejected: {}", reason),
+            RequestError::Abort(reason) => write!(f, "Aborted: {}", reason),
+            RequestError::CommunicationDisabled => write!(f, "Communication disabled"),
+        }
+    }
+}
+
+impl Error for RequestError {}
+
+impl From<DataLinkError> for RequestError {
+    fn from(e: DataLinkError) -> Self {
+        RequestError::DataLink(e)
+    }
+}
+
+impl From<EncodingError> for RequestError {
+    fn from(e: EncodingError) -> Self {
+        RequestError::Encoding(e)
+    }
+}
+
+impl From<ApplicationError> for RequestError {
+    fn from(e: ApplicationError) -> Self {
+        match e {
+            ApplicationError::TransactionError(_) => RequestError::NoInvokeId,
+            e => RequestError::Application(e),
+        }
+    }
+}
+
+impl From<Confirmation> for Result<Vec<u8>> {
+    /// The service data of a ComplexACK, empty for a SimpleACK, or the error
+    /// the request ended with
+    fn from(confirmation: Confirmation) -> Self {
+        match confirmation {
+            Confirmation::SimpleAck { .. } => Ok(Vec::new()),
+            Confirmation::ComplexAck { service_data, .. } => Ok(service_data),
+            Confirmation::Error { error, .. } => Err(RequestError::Error {
+                error_class: error.error_class,
+                error_code: error.error_code,
+            }),
+            Confirmation::Reject { reason } => Err(RequestError::Reject(reason)),
+            Confirmation::Abort { reason, .. } => Err(RequestError::Abort(reason)),
+            Confirmation::Timeout => Err(RequestError::Timeout),
+        }
+    }
+}
+
+/// An unconfirmed request heard on the link
+#[derive(Debug, Clone)]
+pub struct UnconfirmedRequest {
+    /// The service
+    pub service_choice: UnconfirmedServiceChoice,
+    /// Encoded service parameters
+    pub service_data: Vec<u8>,
+    /// Address on the link the request came from, the router for a request
+    /// from another network
+    pub source: DataLinkAddress,
+    /// Network and MAC address of the station that sent the request
+    pub address: BacnetAddress,
+}
+
+/// Confirmed requests in progress
+struct Transactions {
+    tsm: ClientTsm<BacnetAddress>,
+    /// Where the confirmation of each request goes, by invoke ID
+    waiting: HashMap<u8, oneshot::Sender<Confirmation>>,
+    /// When time was last counted against the TSM
+    counted: Instant,
+}
+
+impl Transactions {
+    /// Hand the confirmations of `outcome` to their requests, returning what
+    /// to send
+    fn deliver(&mut self, outcome: ClientOutcome<BacnetAddress>) -> Vec<(BacnetAddress, Apdu)> {
+        for (invoke_id, confirmation) in outcome.confirmations {
+            if let Some(waiting) = self.waiting.remove(&invoke_id) {
+                // The request may have been dropped
+                let _ = waiting.send(confirmation);
+            }
+        }
+        outcome.sends
+    }
+
+    /// Count the time passed since it was last counted against the TSM
+    fn tick(&mut self) -> Vec<(BacnetAddress, Apdu)> {
+        let now = Instant::now();
+        let outcome = self.tsm.advance_time(now - self.counted);
+        self.counted = now;
+        self.deliver(outcome)
+    }
+}
+
+/// Transactions shared between the requests and the receive task
+type SharedTransactions = Arc<Mutex<Transactions>>;
+
+/// Ends the transaction of a request dropped before its confirmation
+struct Unfinished<'a> {
+    transactions: &'a SharedTransactions,
+    invoke_id: u8,
+    confirmed: bool,
+}
+
+impl Drop for Unfinished<'_> {
+    fn drop(&mut self) {
+        if !self.confirmed {
+            let mut transactions = self.transactions.lock().unwrap();
+            transactions.waiting.remove(&self.invoke_id);
+            transactions.tsm.cancel(self.invoke_id);
+        }
+    }
+}
+
+/// Routers to remote networks, by network number, with a notification for
+/// each router learned
+type Routers = Arc<(Mutex<HashMap<u16, DataLinkAddress>>, Notify)>;
+
+/// Asynchronous BACnet client on one data link
+///
+/// Must be created within a Tokio runtime; dropping the client stops its
+/// receive task.
+pub struct AsyncBacnetClient {
+    link: Arc<dyn AsyncDataLink>,
+    transactions: SharedTransactions,
+    unconfirmed: broadcast::Sender<UnconfirmedRequest>,
+    routers: Routers,
+    timeout: Duration,
+    /// Handler of the device the client speaks for, whose
+    /// DeviceCommunicationControl state gates the requests
+    handler: Option<Arc<Mutex<ApplicationLayerHandler>>>,
+    task: JoinHandle<()>,
+}
+
+impl AsyncBacnetClient {
+    /// Create a client on `link` and start reading it
+    pub fn new(link: Arc<dyn AsyncDataLink>) -> Self {
+        let config = ApplicationConfig {
+            apdu_timeout: DEFAULT_APDU_TIMEOUT.as_millis() as u16,
+            apdu_retries: DEFAULT_APDU_RETRIES,
+            ..ApplicationConfig::default()
+        };
+        let transactions = Arc::new(Mutex::new(Transactions {
+            tsm: ClientTsm::new(&config),
+            waiting: HashMap::new(),
+            counted: Instant::now(),
+        }));
+        let routers = Routers::default();
+        let (unconfirmed, _) = broadcast::channel(UNCONFIRMED_CAPACITY);
+        let task = tokio::spawn(run_client(
+            link.clone(),
+            transactions.clone(),
+            unconfirmed.clone(),
+            routers.clone(),
+        ));
+        Self {
+            link,
+            transactions,
+            unconfirmed,
+            routers,
+            timeout: DEFAULT_APDU_TIMEOUT,
+            handler: None,
+            task,
+        }
+    }
+
+    /// The data link the client runs on
+    pub fn link(&self) -> &Arc<dyn AsyncDataLink> {
+        &self.link
+    }
+
+    /// Set how long each attempt waits for an acknowledgement
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+        self.transactions
+            .lock()
+            .unwrap()
+            .tsm
+            .set_apdu_timeout(timeout);
+    }
+
+    /// Set how many times an unacknowledged request is sent again
+    pub fn set_retries(&mut self, retries: u8) {
+        self.transactions
+            .lock()
+            .unwrap()
+            .tsm
+            .set_apdu_retries(retries);
+    }
+
+    /// Speak for the device `handler` serves, holding back the requests its
+    /// DeviceCommunicationControl state disables
+    pub fn set_handler(&mut self, handler: Arc<Mutex<ApplicationLayerHandler>>) {
+        self.handler = Some(handler);
+    }
+
+    /// Receive the unconfirmed requests heard from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<UnconfirmedRequest> {
+        self.unconfirmed.subscribe()
+    }
+
+    /// Send requests for `network` through the router at `router`
+    pub fn add_router(&self, network: u16, router: DataLinkAddress) {
+        self.routers.0.lock().unwrap().insert(network, router);
+    }
+
+    /// The router known to reach `network`
+    pub fn router(&self, network: u16) -> Option<DataLinkAddress> {
+        self.routers.0.lock().unwrap().get(&network).cloned()
+    }
+
+    /// The router to `network`, asking for it with Who-Is-Router-To-Network
+    /// and waiting up to `wait` for the answer if it is not known
+    pub async fn find_router(
+        &self,
+        network: u16,
+        wait: Duration,
+    ) -> Result<Option<DataLinkAddress>> {
+        if let Some(router) = self.router(network) {
+            return Ok(Some(router));
+        }
+        let who_is = NetworkMessage::WhoIsRouterToNetwork(Some(network))
+            .encode_npdu(&Npdu::network_message(None));
+        self.link
+            .send_frame(&who_is, &DataLinkAddress::Broadcast)
+            .await?;
+
+        let deadline = tokio::time::Instant::now() + wait;
+        loop {
+            let learned = self.routers.1.notified();
+            if let Some(router) = self.router(network) {
+                return Ok(Some(router));
+            }
+            if tokio::time::timeout_at(deadline, learned).await.is_err() {
+                return Ok(None);
+            }
+        }
+    }
+
+    /// Send a confirmed request and wait for its acknowledgement
+    ///
+    /// Resolves to the service data of a ComplexACK, reassembled if it came
+    /// in segments, or to empty service data for a SimpleACK.
+    pub async fn confirmed_request(
+        &self,
+        destination: impl Into<BacnetAddress>,
+        service_choice: ConfirmedServiceChoice,
+        service_data: Vec<u8>,
+    ) -> Result<Vec<u8>> {
+        let destination = destination.into();
+        // Find the route first; the TSM's sends follow it
+        self.resolve(&destination).await?;
+        let (invoke_id, sends, mut confirmation) = {
+            let mut transactions = self.transactions.lock().unwrap();
+            let (invoke_id, outcome) = transactions.tsm.request(
+                destination,
+                service_choice,
+                service_data,
+                MaxApduSize::Up1476.size(),
+                None,
+            )?;
+            if !outcome.sends.iter().all(|(_, apdu)| self.may_send(apdu)) {
+                transactions.tsm.cancel(invoke_id);
+                return Err(RequestError::CommunicationDisabled);
+            }
+            let (sender, receiver) = oneshot::channel();
+            transactions.waiting.insert(invoke_id, sender);
+            (invoke_id, outcome.sends, receiver)
+        };
+        let mut unfinished = Unfinished {
+            transactions: &self.transactions,
+            invoke_id,
+            confirmed: false,
+        };
+        send_apdus(&*self.link, &self.routers, sends).await?;
+
+        loop {
+            match tokio::time::timeout(TICK, &mut confirmation).await {
+                Ok(Ok(confirmation)) => {
+                    unfinished.confirmed = true;
+                    return confirmation.into();
+                }
+                // The sender goes only with its confirmation
+                Ok(Err(_)) => return Err(RequestError::Timeout),
+                Err(_) => {
+                    let sends = self.transactions.lock().unwrap().tick();
+                    // A send that fails is repeated with the retries
+                    let _ = send_apdus(&*self.link, &self.routers, sends).await;
+                }
+            }
+        }
+    }
+
+    /// Send an unconfirmed request
+    pub async fn unconfirmed_request(
+        &self,
+        destination: impl Into<BacnetAddress>,
+        service_choice: UnconfirmedServiceChoice,
+        service_data: Vec<u8>,
+    ) -> Result<()> {
+        let (link_destination, npdu) = self.resolve(&destination.into()).await?;
+        let apdu = Apdu::UnconfirmedRequest {
+            service_choice,
+            service_data,
+        };
+        if !self.may_send(&apdu) {
+            return Err(RequestError::CommunicationDisabled);
+        }
+        let mut message = npdu.encode();
+        message.extend_from_slice(&apdu.encode());
+        Ok(self.link.send_frame(&message, &link_destination).await?)
+    }
+
+    /// Send a Who-Is to `destination` (typically a broadcast address) and
+    /// collect the I-Am answers that arrive within `wait`
+    ///
+    /// Answers from devices outside the requested range are ignored; a
+    /// device that answers more than once is listed once, in order of device
+    /// instance, with its network and MAC address.
+    pub async fn who_is(
+        &self,
+        destination: impl Into<BacnetAddress>,
+        request: &WhoIsRequest,
+        wait: Duration,
+    ) -> Result<Vec<(IAmRequest, BacnetAddress)>> {
+        let mut service_data = Vec::new();
+        request.encode(&mut service_data)?;
+        let mut heard = self.subscribe();
+        self.unconfirmed_request(destination, UnconfirmedServiceChoice::WhoIs, service_data)
+            .await?;
+
+        let mut devices = BTreeMap::new();
+        let deadline = tokio::time::Instant::now() + wait;
+        while let Ok(received) = tokio::time::timeout_at(deadline, heard.recv()).await {
+            let unconfirmed = match received {
+                Ok(unconfirmed) => unconfirmed,
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            };
+            if unconfirmed.service_choice != UnconfirmedServiceChoice::IAm {
+                continue;
+            }
+            if let Ok(i_am) = IAmRequest::decode(&unconfirmed.service_data) {
+                if request.matches(i_am.device_identifier.instance) {
+                    devices.insert(i_am.device_identifier.instance, (i_am, unconfirmed.address));
+                }
+            }
+        }
+        Ok(devices.into_values().collect())
+    }
+
+    /// Read a property, or one element of an array property
+    pub async fn read_property(
+        &self,
+        destination: impl Into<BacnetAddress>,
+        object_identifier: ObjectIdentifier,
+        property_identifier: u32,
+        property_array_index: Option<u32>,
+    ) -> Result<ReadPropertyAck> {
+        let request = ReadPropertyRequest {
+            object_identifier,
+            property_identifier,
+            property_array_index,
+        };
+        let mut service_data = Vec::new();
+        request.encode(&mut service_data)?;
+        let ack = self
+            .confirmed_request(
+                destination,
+                ConfirmedServiceChoice::ReadProperty,
+                service_data,
+            )
+            .await?;
+        Ok(ReadPropertyAck::decode(&ack)?)
+    }
+
+    /// Write a property
+    pub async fn write_property(
+        &self,
+        destination: impl Into<BacnetAddress>,
+        request: &WritePropertyRequest,
+    ) -> Result<()> {
+        let mut service_data = Vec::new();
+        request.encode(&mut service_data)?;
+        self.confirmed_request(
+            destination,
+            ConfirmedServiceChoice::WriteProperty,
+            service_data,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Whether the device's DeviceCommunicationControl state lets `apdu` go
+    /// out
+    fn may_send(&self, apdu: &Apdu) -> bool {
+        self.handler
+            .as_ref()
+            .is_none_or(|handler| handler.lock().unwrap().may_send(apdu, false))
+    }
+
+    /// The data link address to send to for `destination`, the router for a
+    /// remote network, and the NPCI naming it, asking for the router to a
+    /// remote network if it is not known
+    async fn resolve(&self, destination: &BacnetAddress) -> Result<(DataLinkAddress, Npdu)> {
+        if !destination.is_local() && !destination.is_broadcast() {
+            self.find_router(destination.network, self.timeout).await?;
+        }
+        route(self.link.link_type(), &self.routers, destination)
+    }
+}
+
+impl Drop for AsyncBacnetClient {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// The data link address to send to for `destination`, the router for a
+/// remote network, and the NPCI naming it
+///
+/// For a network with no known router the NPDU is broadcast for any router
+/// on the local network to pick up.
+fn route(
+    link_type: DataLinkType,
+    routers: &Routers,
+    destination: &BacnetAddress,
+) -> Result<(DataLinkAddress, Npdu)> {
+    if destination.is_local() {
+        let address =
+            DataLinkAddress::from_mac(link_type, &destination.address).ok_or_else(|| {
+                DataLinkError::AddressError(format!(
+                    "{:02X?} is not a {:?} address",
+                    destination.address, link_type
+                ))
+            })?;
+        return Ok((address, Npdu::new()));
+    }
+    let router = if destination.is_broadcast() {
+        None
+    } else {
+        routers.0.lock().unwrap().get(&destination.network).cloned()
+    };
+    let mut npdu = Npdu::new();
+    npdu.set_destination(Some(destination.clone()));
+    Ok((router.unwrap_or(DataLinkAddress::Broadcast), npdu))
+}
+
+/// Send the APDUs of confirmed request transactions to their peers
+async fn send_apdus(
+    link: &dyn AsyncDataLink,
+    routers: &Routers,
+    sends: Vec<(BacnetAddress, Apdu)>,
+) -> Result<()> {
+    for (peer, apdu) in sends {
+        let (link_destination, mut npdu) = route(link.link_type(), routers, &peer)?;
+        npdu.control.expecting_reply = matches!(apdu, Apdu::ConfirmedRequest { .. });
+        let mut message = npdu.encode();
+        message.extend_from_slice(&apdu.encode());
+        link.send_frame(&message, &link_destination).await?;
+    }
+    Ok(())
+}
+
+/// Decode the APDU carried by an NPDU, with the NPDU header
+///
+/// Network layer messages carry no APDU and yield `None`.
+fn decode_apdu(frame: &[u8]) -> Option<(Npdu, Apdu)> {
+    let (npdu, npdu_len) = Npdu::decode(frame).ok()?;
+    if npdu.is_network_message() {
+        return None;
+    }
+    let apdu = Apdu::decode(&frame[npdu_len..]).ok()?;
+    Some((npdu, apdu))
+}
+
+/// Note the routers a received NPDU shows: the sender of an
+/// I-Am-Router-To-Network, or the station an NPDU from another network came
+/// through
+fn learn_routers(routers: &Routers, frame: &[u8], source: &DataLinkAddress) {
+    let networks = match NetworkMessage::decode_npdu(frame) {
+        Ok((_, NetworkMessage::IAmRouterToNetwork(networks))) => networks,
+        Ok(_) => return,
+        Err(_) => match Npdu::decode(frame) {
+            Ok((
+                Npdu {
+                    source: Some(snet), ..
+                },
+                _,
+            )) => vec![snet.network],
+            _ => return,
+        },
+    };
+    let (table, learned) = &**routers;
+    let mut table = table.lock().unwrap();
+    for network in networks {
+        table.insert(network, source.clone());
+    }
+    learned.notify_waiters();
+}
+
+/// Body of the client's receive task
+async fn run_client(
+    link: Arc<dyn AsyncDataLink>,
+    transactions: SharedTransactions,
+    unconfirmed: broadcast::Sender<UnconfirmedRequest>,
+    routers: Routers,
+) {
+    loop {
+        let (frame, source) = match link.receive_frame().await {
+            Ok(received) => received,
+            Err(DataLinkError::IoError(e)) if e.kind() == ErrorKind::BrokenPipe => return,
+            Err(_) => continue,
+        };
+        learn_routers(&routers, &frame, &source);
+        let Some((npdu, apdu)) = decode_apdu(&frame) else {
+            continue;
+        };
+        let address = npdu.source.unwrap_or_else(|| BacnetAddress::from(&source));
+        if let Apdu::UnconfirmedRequest {
+            service_choice,
+            service_data,
+        } = apdu
+        {
+            // No subscriber is no error
+            let _ = unconfirmed.send(UnconfirmedRequest {
+                service_choice,
+                service_data,
+                source,
+                address,
+            });
+            continue;
+        }
+
+        let sends = {
+            let mut transactions = transactions.lock().unwrap();
+            let outcome = transactions.tsm.receive(&address, &apdu);
+            transactions.deliver(outcome)
+        };
+        // SegmentACKs that fail to go out are repeated by the server
+        let _ = send_apdus(&*link, &routers, sends).await;
+    }
+}
+
+/// Stations on other networks the server heard from, by the MAC address of
+/// the router their requests came through
+type Routes = Arc<Mutex<HashMap<Vec<u8>, NetworkAddress>>>;
+
+/// Stops a task when dropped
+struct AbortOnDrop(JoinHandle<()>);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Serve requests arriving on `link` with `handler` until the link fails
+///
+/// Each request is passed to
+/// [`process_apdu`](ApplicationLayerHandler::process_apdu) and any reply is
+/// sent back to where the request came from, through the router it came
+/// through when it came from another network, followed by whatever the
+/// handler leaves for [`take_sends`](ApplicationLayerHandler::take_sends).
+/// The time passed is counted on the handler with
+/// [`advance_time`](ApplicationLayerHandler::advance_time), so segments
+/// are sent again on time. The handler stays shared, so the application can
+/// keep changing it while this runs:
+///
+/// ```no_run
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// use std::sync::{Arc, Mutex};
+/// use bacnet_rs::app::ApplicationLayerHandler;
+/// use bacnet_rs::async_client::serve;
+/// use bacnet_rs::datalink::async_link::AsyncBacnetIpDataLink;
+///
+/// let link = Arc::new(AsyncBacnetIpDataLink::bind("0.0.0.0:47808").await?);
+/// let handler = Arc::new(Mutex::new(ApplicationLayerHandler::new(1234)));
+/// let server = tokio::spawn(serve(link, handler.clone()));
+/// # server.await??;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn serve(
+    link: Arc<dyn AsyncDataLink>,
+    handler: Arc<Mutex<ApplicationLayerHandler>>,
+) -> std::result::Result<(), DataLinkError> {
+    let routes = Routes::default();
+    let _timer = AbortOnDrop(tokio::spawn(run_server_timer(
+        link.clone(),
+        handler.clone(),
+        routes.clone(),
+    )));
+    loop {
+        let (frame, source) = match link.receive_frame().await {
+            Ok(received) => received,
+            Err(DataLinkError::IoError(e)) if e.kind() == ErrorKind::BrokenPipe => {
+                return Err(DataLinkError::IoError(e))
+            }
+            Err(_) => continue,
+        };
+        let Some((npdu, apdu)) = decode_apdu(&frame) else {
+            continue;
+        };
+        let station = source.to_mac();
+        {
+            let mut routes = routes.lock().unwrap();
+            match npdu.source {
+                Some(remote) => routes.insert(station.clone(), remote),
+                None => routes.remove(&station),
+            };
+        }
+        let answering_who_is = matches!(
+            apdu,
+            Apdu::UnconfirmedRequest {
+                service_choice: UnconfirmedServiceChoice::WhoIs,
+                ..
+            }
+        );
+        let (reply, sends) = {
+            let mut handler = handler.lock().unwrap();
+            // Replies DeviceCommunicationControl holds back are dropped here
+            let reply = match handler.process_apdu(&apdu, &station) {
+                Ok(Some(reply)) if handler.may_send(&reply, answering_who_is) => Some(reply),
+                _ => None,
+            };
+            (reply, take_sends(&mut handler))
+        };
+
+        if let Some(reply) = reply {
+            send_reply(&*link, &routes, &station, &source, &reply).await?;
+        }
+        for (station, apdu) in sends {
+            if let Some(destination) = DataLinkAddress::from_mac(link.link_type(), &station) {
+                send_reply(&*link, &routes, &station, &destination, &apdu).await?;
+            }
+        }
+    }
+}
+
+/// Body of the server's timer task, counting the time passed on the handler
+/// and sending the segments it sends again
+async fn run_server_timer(
+    link: Arc<dyn AsyncDataLink>,
+    handler: Arc<Mutex<ApplicationLayerHandler>>,
+    routes: Routes,
+) {
+    let mut counted = Instant::now();
+    loop {
+        tokio::time::sleep(TICK).await;
+        let sends = {
+            let mut handler = handler.lock().unwrap();
+            let now = Instant::now();
+            handler.advance_time(now - counted);
+            counted = now;
+            take_sends(&mut handler)
+        };
+        for (station, apdu) in sends {
+            if let Some(destination) = DataLinkAddress::from_mac(link.link_type(), &station) {
+                // Segments that fail to go out are sent again on the next timeout
+                let _ = send_reply(&*link, &routes, &station, &destination, &apdu).await;
+            }
+        }
+    }
+}
+
+/// The APDUs the handler left to send that DeviceCommunicationControl lets
+/// go out
+fn take_sends(handler: &mut ApplicationLayerHandler) -> Vec<(Vec<u8>, Apdu)> {
+    let mut sends = handler.take_sends();
+    sends.retain(|(_, apdu)| handler.may_send(apdu, false));
+    sends
+}
+
+/// Send `apdu` to `station`, at `destination` on the link, naming the
+/// station behind it when its requests came through a router
+async fn send_reply(
+    link: &dyn AsyncDataLink,
+    routes: &Routes,
+    station: &[u8],
+    destination: &DataLinkAddress,
+    apdu: &Apdu,
+) -> std::result::Result<(), DataLinkError> {
+    let mut npdu = Npdu::new();
+    if let Some(NetworkAddress { network, address }) = routes.lock().unwrap().get(station) {
+        npdu.control.destination_present = true;
+        npdu.destination = Some(NetworkAddress::new(*network, address.clone()));
+        npdu.hop_count = Some(255);
+    }
+    let mut message = npdu.encode();
+    message.extend_from_slice(&apdu.encode());
+    link.send_frame(&message, destination).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datalink::async_link::AsyncBacnetIpDataLink;
+    use crate::object::{ObjectType, PropertyValue};
+    use crate::service::{PropertyAccessError, ServiceError};
+
+    async fn link() -> Arc<dyn AsyncDataLink> {
+        Arc::new(AsyncBacnetIpDataLink::bind("127.0.0.1:0").await.unwrap())
+    }
+
+    /// A server whose ReadProperty handler answers with the Present_Value of
+    /// Analog Value 1 and fails for anything else
+    fn handler() -> Arc<Mutex<ApplicationLayerHandler>> {
+        let mut handler = ApplicationLayerHandler::new(1234);
+        handler.set_read_property_handler(|service_data| {
+            let request = ReadPropertyRequest::decode(service_data)
+                .map_err(|e| ServiceError::Rejected(RejectReason::for_decode_error(&e)))?;
+            if request.object_identifier.instance != 1 {
+                return Err(ServiceError::Failed(PropertyAccessError {
+                    error_class: 1,
+                    error_code: 31,
+                }));
+            }
+            let ack = ReadPropertyAck::new(
+                request.object_identifier,
+                request.property_identifier,
+                PropertyValue::Real(21.5),
+            );
+            let mut buffer = Vec::new();
+            ack.encode(&mut buffer).unwrap();
+            Ok(buffer)
+        });
+        handler.set_who_is_handler(|service_data| {
+            let i_am = IAmRequest::new(
+                ObjectIdentifier::new(ObjectType::Device, 1234),
+                1476,
+                3,
+                260,
+            );
+            let request = WhoIsRequest::decode(service_data)
+                .map_err(|e| crate::app::ApplicationError::ServiceError(e.to_string()))?;
+            if !request.matches(1234) {
+                return Ok(None);
+            }
+            let mut buffer = Vec::new();
+            i_am.encode(&mut buffer).unwrap();
+            Ok(Some(buffer))
+        });
+        Arc::new(Mutex::new(handler))
+    }
+
+    #[tokio::test]
+    async fn test_read_property_and_error() {
+        let server_link = link().await;
+        let server_address = server_link.local_address();
+        let server = tokio::spawn(serve(server_link, handler()));
+        let client = AsyncBacnetClient::new(link().await);
+
+        let object = ObjectIdentifier::new(ObjectType::AnalogValue, 1);
+        let ack = client
+            .read_property(&server_address, object, 85, None)
+            .await
+            .unwrap();
+        assert_eq!(ack.object_identifier, object);
+        assert_eq!(ack.property_value, PropertyValue::Real(21.5));
+
+        // Concurrent requests each get their own answer
+        let missing = ObjectIdentifier::new(ObjectType::AnalogValue, 2);
+        let (found, failed) = tokio::join!(
+            client.read_property(&server_address, object, 85, None),
+            client.read_property(&server_address, missing, 85, None),
+        );
+        assert!(found.is_ok());
+        assert!(matches!(
+            failed,
+            Err(RequestError::Error {
+                error_class: 1,
+                error_code: 31
+            })
+        ));
+
+        // The handler does not carry out WriteProperty
+        let write = WritePropertyRequest::new(object, 85, vec![0x44, 0, 0, 0, 0]);
+        assert!(matches!(
+            client.write_property(&server_address, &write).await,
+            Err(RequestError::Reject(RejectReason::UnrecognizedService))
+        ));
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn test_who_is() {
+        let server_link = link().await;
+        let server_address = server_link.local_address();
+        let server = tokio::spawn(serve(server_link, handler()));
+        let client = AsyncBacnetClient::new(link().await);
+
+        let devices = client
+            .who_is(
+                &server_address,
+                &WhoIsRequest::new(),
+                Duration::from_millis(300),
+            )
+            .await
+            .unwrap();
+        assert_eq!(devices.len(), 1);
+        assert_eq!(devices[0].0.device_identifier.instance, 1234);
+        assert_eq!(devices[0].1, BacnetAddress::from(&server_address));
+
+        let devices = client
+            .who_is(
+                &server_address,
+                &WhoIsRequest::for_range(1, 100),
+                Duration::from_millis(200),
+            )
+            .await
+            .unwrap();
+        assert!(devices.is_empty());
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn test_remote_device_through_router() {
+        use crate::datalink::bip::BacnetIpDataLink;
+        use crate::datalink::DataLink;
+        use crate::network::{port::PortDispatcher, router::Router};
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        // Network 10 holds the client, network 20 the server
+        let mut dispatcher = PortDispatcher::new();
+        let near = BacnetIpDataLink::new("127.0.0.1:0").unwrap();
+        let router_address = near.local_address();
+        dispatcher.add_port(1, 10, Box::new(near)).unwrap();
+        let far = BacnetIpDataLink::new("127.0.0.1:0").unwrap();
+        dispatcher.add_port(2, 20, Box::new(far)).unwrap();
+
+        let server_link = link().await;
+        let server = BacnetAddress::new(20, server_link.local_address().to_mac());
+        let server_task = tokio::spawn(serve(server_link, handler()));
+        let client = AsyncBacnetClient::new(link().await);
+
+        // The client learns the router from I-Am-Router-To-Network
+        let i_am_router =
+            NetworkMessage::IAmRouterToNetwork(vec![20]).encode_npdu(&Npdu::network_message(None));
+        dispatcher
+            .send(1, &i_am_router, &client.link().local_address())
+            .unwrap();
+        let learned = tokio::time::timeout(Duration::from_secs(2), async {
+            while client.router(20).is_none() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        });
+        learned.await.unwrap();
+        assert_eq!(client.router(20), Some(router_address));
+
+        let running = Arc::new(AtomicBool::new(true));
+        let routing = {
+            let running = running.clone();
+            std::thread::spawn(move || {
+                let mut router = Router::for_dispatcher(&dispatcher);
+                while running.load(Ordering::Relaxed) {
+                    router.poll(&mut dispatcher, Duration::from_millis(20));
+                }
+            })
+        };
+
+        let object = ObjectIdentifier::new(ObjectType::AnalogValue, 1);
+        let ack = client
+            .read_property(server.clone(), object, 85, None)
+            .await
+            .unwrap();
+        assert_eq!(ack.property_value, PropertyValue::Real(21.5));
+
+        // The I-Am names the device by its network and MAC
+        let devices = client
+            .who_is(server.clone(), &WhoIsRequest::new(), Duration::from_secs(1))
+            .await
+            .unwrap();
+        assert_eq!(devices.len(), 1);
+        assert_eq!(devices[0].1, server);
+
+        running.store(false, Ordering::Relaxed);
+        routing.join().unwrap();
+        server_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_timeout_after_retries() {
+        // Nothing answers at this address
+        let silent = link().await;
+        let mut client = AsyncBacnetClient::new(link().await);
+        client.set_timeout(Duration::from_millis(50));
+        client.set_retries(2);
+
+        let started = std::time::Instant::now();
+        let result = client
+            .read_property(
+                &silent.local_address(),
+                ObjectIdentifier::new(ObjectType::Device, 1),
+                77,
+                None,
+            )
+            .await;
+        assert!(matches!(result, Err(RequestError::Timeout)));
+        assert!(started.elapsed() >= Duration::from_millis(150));
+
+        // The request was sent three times
+        let mut sent = 0;
+        while tokio::time::timeout(Duration::from_millis(50), silent.receive_frame())
+            .await
+            .is_ok()
+        {
+            sent += 1;
+        }
+        assert_eq!(sent, 3);
+        // The invoke ID is free again
+        assert_eq!(client.transactions.lock().unwrap().tsm.active(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_segmented_complex_ack() {
+        use crate::app::segmentation::{SegmentedTransmit, TransmitOutcome};
+
+        // A server whose ReadPropertyACK takes several segments
+        let server_link = link().await;
+        let server_address = server_link.local_address();
+        let name = "x".repeat(3000);
+        let server = {
+            let name = name.clone();
+            tokio::spawn(async move {
+                let (frame, client_address) = server_link.receive_frame().await.unwrap();
+                let Some((
+                    _,
+                    Apdu::ConfirmedRequest {
+                        segmented_response_accepted,
+                        invoke_id,
+                        service_data,
+                        ..
+                    },
+                )) = decode_apdu(&frame)
+                else {
+                    panic!("expected a confirmed request");
+                };
+                assert!(segmented_response_accepted);
+                let request = ReadPropertyRequest::decode(&service_data).unwrap();
+                let ack = ReadPropertyAck::new(
+                    request.object_identifier,
+                    request.property_identifier,
+                    PropertyValue::CharacterString(name),
+                );
+                let mut buffer = Vec::new();
+                ack.encode(&mut buffer).unwrap();
+                let apdu = Apdu::ComplexAck {
+                    segmented: false,
+                    more_follows: false,
+                    invoke_id,
+                    sequence_number: None,
+                    proposed_window_size: None,
+                    service_choice: ConfirmedServiceChoice::ReadProperty as u8,
+                    service_data: buffer,
+                };
+                let mut transmit = SegmentedTransmit::new(apdu, 480, None, 4).unwrap();
+                let send = |segments: Vec<Apdu>| {
+                    let link = server_link.clone();
+                    let client_address = client_address.clone();
+                    async move {
+                        for segment in segments {
+                            let mut message = Npdu::new().encode();
+                            message.extend_from_slice(&segment.encode());
+                            link.send_frame(&message, &client_address).await.unwrap();
+                        }
+                    }
+                };
+                send(transmit.start()).await;
+                loop {
+                    let (frame, _) = server_link.receive_frame().await.unwrap();
+                    let Some((_, ack)) = decode_apdu(&frame) else {
+                        continue;
+                    };
+                    match transmit.segment_ack(&ack) {
+                        TransmitOutcome::Send(segments) => send(segments).await,
+                        TransmitOutcome::Wait => {}
+                        TransmitOutcome::Complete => break,
+                        TransmitOutcome::TimedOut => panic!("segments not acknowledged"),
+                    }
+                }
+                transmit.segment_count()
+            })
+        };
+        let client = AsyncBacnetClient::new(link().await);
+
+        let object = ObjectIdentifier::new(ObjectType::Device, 1234);
+        let ack = client
+            .read_property(&server_address, object, 77, None)
+            .await
+            .unwrap();
+        assert_eq!(ack.property_value, PropertyValue::CharacterString(name));
+        assert!(server.await.unwrap() > 1);
+        assert_eq!(client.transactions.lock().unwrap().tsm.active(), 0);
+    }
+}
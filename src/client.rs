@@ -5,7 +5,10 @@
 
 #[cfg(feature = "std")]
 use std::{
+    collections::BTreeMap,
+    fmt,
     net::{SocketAddr, UdpSocket},
+    sync::{Arc, Mutex},
     time::{Duration, Instant},
 };
 
@@ -13,20 +16,34 @@ use std::{
 use alloc::{collections::BTreeMap as HashMap, string::String, vec::Vec};
 
 use crate::{
-    app::{Apdu, MaxApduSize, MaxSegments},
+    app::{tsm::ClientTsm, Apdu, ApplicationConfig, ApplicationLayerHandler, MaxApduSize},
     network::Npdu,
     object::{ObjectIdentifier, ObjectType},
     service::{
-        ConfirmedServiceChoice, IAmRequest, PropertyReference, ReadAccessSpecification,
-        ReadPropertyMultipleRequest, UnconfirmedServiceChoice, WhoIsRequest,
+        max_stream_chunk, AtomicReadFileRequest, AtomicReadFileResponse, AtomicWriteFileRequest,
+        AtomicWriteFileResponse, AuditLogQueryAck, AuditLogQueryRequest, AuditNotificationRequest,
+        ConfirmedServiceChoice, CovNotificationRequest, DeviceCommissioner, FileAccessMethodResult,
+        IAmRequest, IHaveRequest, PendingCovNotification, PendingEventNotification,
+        PrivateTransferAck, PrivateTransferRequest, PropertyReference, ReadAccessSpecification,
+        ReadPropertyMultipleRequest, RejectReason, TextMessageReceiver, TextMessageRequest,
+        UnconfirmedServiceChoice, WhoHasRequest, WhoIsRequest,
     },
 };
 
+/// Error a send fails with when DeviceCommunicationControl holds it back
+#[cfg(feature = "std")]
+const COMMUNICATION_DISABLED: &str = "Communication disabled by DeviceCommunicationControl";
+
 /// High-level BACnet client for device communication
 #[cfg(feature = "std")]
 pub struct BacnetClient {
     socket: UdpSocket,
     timeout: Duration,
+    /// Confirmed requests in progress
+    tsm: Mutex<ClientTsm<SocketAddr>>,
+    /// Handler of the device the client speaks for, whose
+    /// DeviceCommunicationControl state gates what it sends
+    handler: Option<Arc<Mutex<ApplicationLayerHandler>>>,
 }
 
 /// Discovered BACnet device information
@@ -40,6 +57,170 @@ pub struct DeviceInfo {
     pub segmentation: u32,
 }
 
+impl DeviceInfo {
+    /// Build the device information announced by an I-Am from `address`
+    pub fn from_i_am(iam: &IAmRequest, address: SocketAddr) -> Self {
+        let vendor_name = crate::vendor::get_vendor_name(iam.vendor_identifier as u16)
+            .unwrap_or("Unknown Vendor")
+            .to_string();
+
+        Self {
+            device_id: iam.device_identifier.instance,
+            address,
+            vendor_id: iam.vendor_identifier,
+            vendor_name,
+            max_apdu: iam.max_apdu_length_accepted,
+            segmentation: iam.segmentation_supported,
+        }
+    }
+}
+
+/// Devices that answered a Who-Is, keyed by device instance
+#[derive(Debug, Clone, Default)]
+pub struct DiscoveryResults {
+    devices: BTreeMap<u32, DeviceInfo>,
+}
+
+impl DiscoveryResults {
+    /// Create an empty result set
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record an I-Am received from `address`
+    ///
+    /// A device that answers more than once keeps its latest address. Returns
+    /// true if the device was not already known.
+    pub fn record(&mut self, iam: &IAmRequest, address: SocketAddr) -> bool {
+        self.devices
+            .insert(
+                iam.device_identifier.instance,
+                DeviceInfo::from_i_am(iam, address),
+            )
+            .is_none()
+    }
+
+    /// The device with the given instance, if it answered
+    pub fn get(&self, device_id: u32) -> Option<&DeviceInfo> {
+        self.devices.get(&device_id)
+    }
+
+    /// The discovered devices in order of device instance
+    pub fn devices(&self) -> impl Iterator<Item = &DeviceInfo> {
+        self.devices.values()
+    }
+
+    /// Number of devices discovered
+    pub fn len(&self) -> usize {
+        self.devices.len()
+    }
+
+    /// Whether no device answered
+    pub fn is_empty(&self) -> bool {
+        self.devices.is_empty()
+    }
+}
+
+/// Callback invoked with the COV notifications of one subscriber process
+pub type CovCallback = Box<dyn FnMut(&CovNotificationRequest) + Send>;
+
+/// Routes incoming COV notifications to callbacks registered by subscriber
+/// process identifier
+///
+/// The process identifier is the one given in the SubscribeCOV request, so
+/// each subscription, or group of subscriptions, can have its own handler.
+#[derive(Default)]
+pub struct CovNotificationDispatcher {
+    callbacks: BTreeMap<u32, CovCallback>,
+}
+
+impl CovNotificationDispatcher {
+    /// Create a dispatcher with no callbacks
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the callback for a subscriber process, replacing any earlier
+    /// one
+    pub fn register<F>(&mut self, subscriber_process_identifier: u32, callback: F)
+    where
+        F: FnMut(&CovNotificationRequest) + Send + 'static,
+    {
+        self.callbacks
+            .insert(subscriber_process_identifier, Box::new(callback));
+    }
+
+    /// Remove the callback of a subscriber process; returns true if there was
+    /// one
+    pub fn unregister(&mut self, subscriber_process_identifier: u32) -> bool {
+        self.callbacks
+            .remove(&subscriber_process_identifier)
+            .is_some()
+    }
+
+    /// Pass a notification to the callback of its subscriber process
+    ///
+    /// Returns false if no callback is registered for the process.
+    pub fn dispatch(&mut self, notification: &CovNotificationRequest) -> bool {
+        match self
+            .callbacks
+            .get_mut(&notification.subscriber_process_identifier)
+        {
+            Some(callback) => {
+                callback(notification);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Handle a received APDU
+    ///
+    /// COV notifications are dispatched; other APDUs are ignored. Returns the
+    /// reply to send: a SimpleAck for a confirmed notification, whether or
+    /// not a callback took it, or a Reject if it cannot be decoded.
+    pub fn handle_apdu(&mut self, apdu: &Apdu) -> Option<Apdu> {
+        match apdu {
+            Apdu::UnconfirmedRequest {
+                service_choice: UnconfirmedServiceChoice::UnconfirmedCOVNotification,
+                service_data,
+            } => {
+                if let Ok(notification) = CovNotificationRequest::decode(service_data) {
+                    self.dispatch(&notification);
+                }
+                None
+            }
+            Apdu::ConfirmedRequest {
+                invoke_id,
+                service_choice: ConfirmedServiceChoice::ConfirmedCOVNotification,
+                service_data,
+                ..
+            } => Some(match CovNotificationRequest::decode(service_data) {
+                Ok(notification) => {
+                    self.dispatch(&notification);
+                    Apdu::SimpleAck {
+                        invoke_id: *invoke_id,
+                        service_choice: ConfirmedServiceChoice::ConfirmedCOVNotification as u8,
+                    }
+                }
+                Err(error) => Apdu::reject(*invoke_id, RejectReason::for_decode_error(&error)),
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Debug for CovNotificationDispatcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CovNotificationDispatcher")
+            .field(
+                "subscriber_processes",
+                &self.callbacks.keys().collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
 /// Object information with common properties
 #[derive(Debug, Clone)]
 pub struct ObjectInfo {
@@ -69,13 +250,27 @@ impl BacnetClient {
     pub fn new() -> Result<Self, Box<dyn std::error::Error>> {
         let socket = UdpSocket::bind("0.0.0.0:0")?;
         socket.set_read_timeout(Some(Duration::from_secs(5)))?;
+        let timeout = Duration::from_secs(5);
 
         Ok(Self {
             socket,
-            timeout: Duration::from_secs(5),
+            timeout,
+            tsm: Mutex::new(ClientTsm::new(&ApplicationConfig {
+                apdu_timeout: timeout.as_millis() as u16,
+                ..ApplicationConfig::default()
+            })),
+            handler: None,
         })
     }
 
+    /// Speak for the device `handler` serves, holding back what its
+    /// DeviceCommunicationControl state disables
+    ///
+    /// Requests held back fail without being sent.
+    pub fn set_handler(&mut self, handler: Arc<Mutex<ApplicationLayerHandler>>) {
+        self.handler = Some(handler);
+    }
+
     /// Discover a device by IP address
     pub fn discover_device(
         &self,
@@ -86,10 +281,7 @@ impl BacnetClient {
         let mut buffer = Vec::new();
         whois.encode(&mut buffer)?;
 
-        // Create and send message
-        let message =
-            self.create_unconfirmed_message(UnconfirmedServiceChoice::WhoIs as u8, &buffer);
-        self.socket.send_to(&message, target_addr)?;
+        self.send_unconfirmed(target_addr, UnconfirmedServiceChoice::WhoIs, buffer)?;
 
         // Wait for I-Am response
         let mut recv_buffer = [0u8; 1500];
@@ -114,6 +306,327 @@ impl BacnetClient {
         Err("Device discovery timeout".into())
     }
 
+    /// Send a Who-Is to `target_addr` (typically a broadcast address) and
+    /// collect the I-Am answers that arrive before the timeout
+    ///
+    /// Answers from devices outside the requested instance range are ignored.
+    pub fn who_is(
+        &self,
+        target_addr: SocketAddr,
+        request: &WhoIsRequest,
+    ) -> Result<DiscoveryResults, Box<dyn std::error::Error>> {
+        let mut buffer = Vec::new();
+        request.encode(&mut buffer)?;
+
+        self.socket.set_broadcast(true)?;
+        self.send_unconfirmed(target_addr, UnconfirmedServiceChoice::WhoIs, buffer)?;
+
+        let mut results = DiscoveryResults::new();
+        let mut recv_buffer = [0u8; 1500];
+        let start_time = Instant::now();
+
+        while start_time.elapsed() < self.timeout {
+            match self.socket.recv_from(&mut recv_buffer) {
+                Ok((len, source)) => {
+                    if let Some(iam) = self.decode_iam(&recv_buffer[..len]) {
+                        if request.matches(iam.device_identifier.instance) {
+                            results.record(&iam, source);
+                        }
+                    }
+                }
+                Err(e)
+                    if matches!(
+                        e.kind(),
+                        std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut
+                    ) =>
+                {
+                    continue
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// Send a Who-Has to `target_addr` (typically a broadcast address) and
+    /// collect the I-Have answers that arrive before the timeout, with the
+    /// address each came from
+    pub fn who_has(
+        &self,
+        target_addr: SocketAddr,
+        request: &WhoHasRequest,
+    ) -> Result<Vec<(IHaveRequest, SocketAddr)>, Box<dyn std::error::Error>> {
+        let mut buffer = Vec::new();
+        request.encode(&mut buffer)?;
+
+        self.socket.set_broadcast(true)?;
+        self.send_unconfirmed(target_addr, UnconfirmedServiceChoice::WhoHas, buffer)?;
+
+        let mut answers = Vec::new();
+        let mut recv_buffer = [0u8; 1500];
+        let start_time = Instant::now();
+
+        while start_time.elapsed() < self.timeout {
+            match self.socket.recv_from(&mut recv_buffer) {
+                Ok((len, source)) => {
+                    let i_have = self
+                        .unconfirmed_service_data(
+                            &recv_buffer[..len],
+                            UnconfirmedServiceChoice::IHave,
+                        )
+                        .and_then(|data| IHaveRequest::decode(data).ok());
+                    if let Some(i_have) = i_have {
+                        answers.push((i_have, source));
+                    }
+                }
+                Err(e)
+                    if matches!(
+                        e.kind(),
+                        std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut
+                    ) =>
+                {
+                    continue
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        Ok(answers)
+    }
+
+    /// Receive COV notifications for `duration`, passing each to the
+    /// dispatcher and acknowledging the confirmed ones
+    pub fn receive_cov_notifications(
+        &self,
+        dispatcher: &mut CovNotificationDispatcher,
+        duration: Duration,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.receive_requests(duration, |apdu| dispatcher.handle_apdu(apdu))
+    }
+
+    /// Send an operator text message to a workstation or other device
+    ///
+    /// A confirmed message waits for the receiver's acknowledgement; an
+    /// unconfirmed one returns as soon as it is sent.
+    pub fn send_text_message(
+        &self,
+        target_addr: SocketAddr,
+        request: &TextMessageRequest,
+        confirmed: bool,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut service_data = Vec::new();
+        request.encode(&mut service_data)?;
+        if confirmed {
+            self.send_confirmed_request(
+                target_addr,
+                ConfirmedServiceChoice::ConfirmedTextMessage,
+                &service_data,
+            )?;
+        } else {
+            self.send_unconfirmed(
+                target_addr,
+                UnconfirmedServiceChoice::UnconfirmedTextMessage,
+                service_data,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Invoke a vendor's private service with a ConfirmedPrivateTransfer and
+    /// return the device's acknowledgement
+    pub fn private_transfer(
+        &self,
+        target_addr: SocketAddr,
+        request: &PrivateTransferRequest,
+    ) -> Result<PrivateTransferAck, Box<dyn std::error::Error>> {
+        let mut service_data = Vec::new();
+        request.encode(&mut service_data)?;
+        let response_data = self.send_confirmed_request(
+            target_addr,
+            ConfirmedServiceChoice::ConfirmedPrivateTransfer,
+            &service_data,
+        )?;
+        Ok(PrivateTransferAck::decode(&response_data)?)
+    }
+
+    /// Send an UnconfirmedPrivateTransfer
+    pub fn send_private_transfer(
+        &self,
+        target_addr: SocketAddr,
+        request: &PrivateTransferRequest,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut service_data = Vec::new();
+        request.encode(&mut service_data)?;
+        self.send_unconfirmed(
+            target_addr,
+            UnconfirmedServiceChoice::UnconfirmedPrivateTransfer,
+            service_data,
+        )
+    }
+
+    /// Forward audit notifications to an audit log device
+    ///
+    /// Confirmed notifications wait for the device's acknowledgement.
+    pub fn send_audit_notification(
+        &self,
+        target_addr: SocketAddr,
+        request: &AuditNotificationRequest,
+        confirmed: bool,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut service_data = Vec::new();
+        request.encode(&mut service_data)?;
+        if confirmed {
+            self.send_confirmed_request(
+                target_addr,
+                ConfirmedServiceChoice::ConfirmedAuditNotification,
+                &service_data,
+            )?;
+        } else {
+            self.send_unconfirmed(
+                target_addr,
+                UnconfirmedServiceChoice::UnconfirmedAuditNotification,
+                service_data,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Send a COV notification the object database has pending to the
+    /// subscriber at `target_addr`
+    ///
+    /// A confirmed notification waits for the subscriber's acknowledgement.
+    pub fn send_cov_notification(
+        &self,
+        target_addr: SocketAddr,
+        pending: &PendingCovNotification,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut service_data = Vec::new();
+        pending.notification.encode(&mut service_data)?;
+        if pending.issue_confirmed_notifications {
+            self.send_confirmed_request(
+                target_addr,
+                ConfirmedServiceChoice::ConfirmedCOVNotification,
+                &service_data,
+            )?;
+        } else {
+            self.send_unconfirmed(
+                target_addr,
+                UnconfirmedServiceChoice::UnconfirmedCOVNotification,
+                service_data,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Send an event notification the object database has pending to its
+    /// recipient at `target_addr`
+    ///
+    /// A confirmed notification waits for the recipient's acknowledgement.
+    pub fn send_event_notification(
+        &self,
+        target_addr: SocketAddr,
+        pending: &PendingEventNotification,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut service_data = Vec::new();
+        pending.notification.encode(&mut service_data)?;
+        if pending.issue_confirmed_notifications {
+            self.send_confirmed_request(
+                target_addr,
+                ConfirmedServiceChoice::ConfirmedEventNotification,
+                &service_data,
+            )?;
+        } else {
+            self.send_unconfirmed(
+                target_addr,
+                UnconfirmedServiceChoice::UnconfirmedEventNotification,
+                service_data,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Query a remote Audit Log for the records of one source or target
+    pub fn audit_log_query(
+        &self,
+        target_addr: SocketAddr,
+        request: &AuditLogQueryRequest,
+    ) -> Result<AuditLogQueryAck, Box<dyn std::error::Error>> {
+        let mut service_data = Vec::new();
+        request.encode(&mut service_data)?;
+        let response_data = self.send_confirmed_request(
+            target_addr,
+            ConfirmedServiceChoice::AuditLogQuery,
+            &service_data,
+        )?;
+        Ok(AuditLogQueryAck::decode(&response_data)?)
+    }
+
+    /// Receive text messages for `duration`, passing each to the receiver
+    /// and acknowledging the confirmed ones
+    pub fn receive_text_messages(
+        &self,
+        receiver: &mut TextMessageReceiver,
+        duration: Duration,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.receive_requests(duration, |apdu| receiver.handle_apdu(apdu))
+    }
+
+    /// Answer the Who-Am-I requests that arrive within `duration` with the
+    /// commissioner's You-Are assignments
+    pub fn commission_devices(
+        &self,
+        commissioner: &DeviceCommissioner,
+        duration: Duration,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.receive_requests(duration, |apdu| commissioner.handle_apdu(apdu))
+    }
+
+    /// Receive requests for `duration`, sending back whatever reply `handle`
+    /// returns for each
+    fn receive_requests(
+        &self,
+        duration: Duration,
+        mut handle: impl FnMut(&Apdu) -> Option<Apdu>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut recv_buffer = [0u8; 1500];
+        let start_time = Instant::now();
+
+        while start_time.elapsed() < duration {
+            match self.socket.recv_from(&mut recv_buffer) {
+                Ok((len, source)) => {
+                    let Some(apdu) = self.decode_apdu(&recv_buffer[..len]) else {
+                        continue;
+                    };
+                    let answering_who_is = matches!(
+                        apdu,
+                        Apdu::UnconfirmedRequest {
+                            service_choice: UnconfirmedServiceChoice::WhoIs,
+                            ..
+                        }
+                    );
+                    match handle(&apdu) {
+                        Some(reply) if self.may_send(&reply, answering_who_is) => {
+                            self.socket.send_to(&self.create_message(&reply), source)?;
+                        }
+                        _ => {}
+                    }
+                }
+                Err(e)
+                    if matches!(
+                        e.kind(),
+                        std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut
+                    ) =>
+                {
+                    continue
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        Ok(())
+    }
+
     /// Read the device's object list
     pub fn read_object_list(
         &self,
@@ -125,10 +638,8 @@ impl BacnetClient {
         let read_spec = ReadAccessSpecification::new(device_object, vec![property_ref]);
         let rpm_request = ReadPropertyMultipleRequest::new(vec![read_spec]);
 
-        let invoke_id = 1;
         let response_data = self.send_confirmed_request(
             target_addr,
-            invoke_id,
             ConfirmedServiceChoice::ReadPropertyMultiple,
             &self.encode_rpm_request(&rpm_request)?,
         )?;
@@ -145,7 +656,7 @@ impl BacnetClient {
         let mut objects_info = Vec::new();
         let batch_size = 5;
 
-        for (batch_idx, chunk) in objects.chunks(batch_size).enumerate() {
+        for chunk in objects.chunks(batch_size) {
             let mut read_specs = Vec::new();
 
             for obj in chunk {
@@ -186,11 +697,9 @@ impl BacnetClient {
             }
 
             let rpm_request = ReadPropertyMultipleRequest::new(read_specs);
-            let invoke_id = (batch_idx + 2) as u8;
 
             match self.send_confirmed_request(
                 target_addr,
-                invoke_id,
                 ConfirmedServiceChoice::ReadPropertyMultiple,
                 &self.encode_rpm_request(&rpm_request)?,
             ) {
@@ -234,28 +743,113 @@ impl BacnetClient {
         Ok(objects_info)
     }
 
-    /// Create an unconfirmed message
-    fn create_unconfirmed_message(&self, service_choice: u8, service_data: &[u8]) -> Vec<u8> {
-        // Create NPDU
-        let mut npdu = Npdu::new();
-        npdu.control.expecting_reply = false;
-        npdu.control.priority = 0;
-        let npdu_buffer = npdu.encode();
+    /// Read the whole contents of a stream-access File object
+    ///
+    /// Issues AtomicReadFile requests sized to fit a 1476-octet response until
+    /// the device reports the end of the file.
+    pub fn read_file(
+        &self,
+        target_addr: SocketAddr,
+        file_identifier: ObjectIdentifier,
+    ) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        let chunk_size = max_stream_chunk(MaxApduSize::Up1476.size()) as u32;
+        let mut contents = Vec::new();
+
+        loop {
+            let request = AtomicReadFileRequest::new_stream_access(
+                file_identifier,
+                contents.len() as i32,
+                chunk_size,
+            );
+            let mut service_data = Vec::new();
+            request.encode(&mut service_data)?;
+            let response_data = self.send_confirmed_request(
+                target_addr,
+                ConfirmedServiceChoice::AtomicReadFile,
+                &service_data,
+            )?;
+
+            let response = AtomicReadFileResponse::decode(&response_data)?;
+            let FileAccessMethodResult::StreamAccess { file_data, .. } =
+                response.access_method_result
+            else {
+                return Err("Expected a stream access response".into());
+            };
+            let done = response.end_of_file || file_data.is_empty();
+            contents.extend_from_slice(&file_data);
+            if done {
+                break;
+            }
+        }
+
+        Ok(contents)
+    }
+
+    /// Write `data` into a stream-access File object at `start_position`
+    ///
+    /// Splits the data into AtomicWriteFile requests that fit the device's
+    /// `max_apdu` (from its I-Am) and sends them in order. A start position of
+    /// -1 appends the data.
+    pub fn write_file(
+        &self,
+        target_addr: SocketAddr,
+        file_identifier: ObjectIdentifier,
+        start_position: i32,
+        data: &[u8],
+        max_apdu: usize,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let requests =
+            AtomicWriteFileRequest::stream_chunks(file_identifier, start_position, data, max_apdu);
+        for request in &requests {
+            let mut service_data = Vec::new();
+            request.encode(&mut service_data)?;
+            let response_data = self.send_confirmed_request(
+                target_addr,
+                ConfirmedServiceChoice::AtomicWriteFile,
+                &service_data,
+            )?;
+            AtomicWriteFileResponse::decode(&response_data)?;
+        }
+        Ok(())
+    }
+
+    /// Send an unconfirmed request, unless DeviceCommunicationControl holds
+    /// it back
+    fn send_unconfirmed(
+        &self,
+        target_addr: SocketAddr,
+        service_choice: UnconfirmedServiceChoice,
+        service_data: Vec<u8>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let apdu = Apdu::UnconfirmedRequest {
+            service_choice,
+            service_data,
+        };
+        if !self.may_send(&apdu, false) {
+            return Err(COMMUNICATION_DISABLED.into());
+        }
+        self.socket
+            .send_to(&self.create_message(&apdu), target_addr)?;
+        Ok(())
+    }
 
-        // Create unconfirmed service request APDU
-        let mut apdu = vec![0x10]; // Unconfirmed-Request PDU type
-        apdu.push(service_choice);
-        apdu.extend_from_slice(service_data);
+    /// Whether the device's DeviceCommunicationControl state lets `apdu` go
+    /// out
+    fn may_send(&self, apdu: &Apdu, answering_who_is: bool) -> bool {
+        self.handler
+            .as_ref()
+            .is_none_or(|handler| handler.lock().unwrap().may_send(apdu, answering_who_is))
+    }
 
-        // Combine NPDU and APDU
-        let mut message = npdu_buffer;
-        message.extend_from_slice(&apdu);
+    /// Wrap an APDU that expects no reply in NPDU and BVLC headers
+    fn create_message(&self, apdu: &Apdu) -> Vec<u8> {
+        let npdu = Npdu::new();
+        let mut message = npdu.encode();
+        message.extend_from_slice(&apdu.encode());
 
-        // Wrap in BVLC header for BACnet/IP (unicast)
         let mut bvlc_message = vec![0x81, 0x0A, 0x00, 0x00];
         bvlc_message.extend_from_slice(&message);
 
-        // Update BVLC length
         let total_len = bvlc_message.len() as u16;
         bvlc_message[2] = (total_len >> 8) as u8;
         bvlc_message[3] = (total_len & 0xFF) as u8;
@@ -264,34 +858,92 @@ impl BacnetClient {
     }
 
     /// Send a confirmed request and wait for response
+    ///
+    /// The request is sent again after each timeout without a reply, up to
+    /// the default number of APDU retries. Segmented ComplexACKs are
+    /// reassembled; an Error, Reject, Abort or timeout is returned as a
+    /// [`ServiceError`](crate::service::ServiceError).
     fn send_confirmed_request(
         &self,
         target_addr: SocketAddr,
-        invoke_id: u8,
         service_choice: ConfirmedServiceChoice,
         service_data: &[u8],
     ) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
-        let apdu = Apdu::ConfirmedRequest {
-            segmented: false,
-            more_follows: false,
-            segmented_response_accepted: true,
-            max_segments: MaxSegments::Unspecified,
-            max_response_size: MaxApduSize::Up1476,
-            invoke_id,
-            sequence_number: None,
-            proposed_window_size: None,
+        let mut tsm = self.tsm.lock().map_err(|_| "Client TSM poisoned")?;
+        let (invoke_id, outcome) = tsm.request(
+            target_addr,
             service_choice,
-            service_data: service_data.to_vec(),
-        };
+            service_data.to_vec(),
+            MaxApduSize::Up1476.size(),
+            None,
+        )?;
+        if !outcome
+            .sends
+            .iter()
+            .all(|(_, apdu)| self.may_send(apdu, false))
+        {
+            tsm.cancel(invoke_id);
+            return Err(COMMUNICATION_DISABLED.into());
+        }
+        let mut sends = outcome.sends;
 
-        let apdu_data = apdu.encode();
-        let mut npdu = Npdu::new();
-        npdu.control.expecting_reply = true;
-        npdu.control.priority = 0;
-        let npdu_data = npdu.encode();
+        let mut recv_buffer = [0u8; 1500];
+        let mut last_tick = Instant::now();
+        loop {
+            for (peer, apdu) in sends.drain(..) {
+                if let Err(e) = self
+                    .socket
+                    .send_to(&self.create_request_message(&apdu), peer)
+                {
+                    tsm.cancel(invoke_id);
+                    return Err(e.into());
+                }
+            }
+
+            let mut outcome = match self.socket.recv_from(&mut recv_buffer) {
+                Ok((len, source)) => match self.decode_apdu(&recv_buffer[..len]) {
+                    Some(apdu) => tsm.receive(&source, &apdu),
+                    None => Default::default(),
+                },
+                Err(e)
+                    if matches!(
+                        e.kind(),
+                        std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut
+                    ) =>
+                {
+                    Default::default()
+                }
+                Err(e) => {
+                    tsm.cancel(invoke_id);
+                    return Err(e.into());
+                }
+            };
+            outcome.append(&mut tsm.advance_time(last_tick.elapsed()));
+            last_tick = Instant::now();
+
+            sends = outcome.sends;
+            if let Some((_, confirmation)) = outcome
+                .confirmations
+                .into_iter()
+                .find(|(id, _)| *id == invoke_id)
+            {
+                for (peer, apdu) in sends.drain(..) {
+                    let _ = self
+                        .socket
+                        .send_to(&self.create_request_message(&apdu), peer);
+                }
+                return Ok(confirmation.into_result()?);
+            }
+        }
+    }
 
-        let mut message = npdu_data;
-        message.extend_from_slice(&apdu_data);
+    /// Wrap an APDU of a confirmed request transaction in NPDU and BVLC
+    /// headers
+    fn create_request_message(&self, apdu: &Apdu) -> Vec<u8> {
+        let mut npdu = Npdu::new();
+        npdu.control.expecting_reply = matches!(apdu, Apdu::ConfirmedRequest { .. });
+        let mut message = npdu.encode();
+        message.extend_from_slice(&apdu.encode());
 
         let mut bvlc_message = vec![0x81, 0x0A, 0x00, 0x00];
         bvlc_message.extend_from_slice(&message);
@@ -300,33 +952,28 @@ impl BacnetClient {
         bvlc_message[2] = (total_len >> 8) as u8;
         bvlc_message[3] = (total_len & 0xFF) as u8;
 
-        self.socket.send_to(&bvlc_message, target_addr)?;
-
-        // Wait for response
-        let mut recv_buffer = [0u8; 1500];
-        let start_time = Instant::now();
-
-        while start_time.elapsed() < self.timeout {
-            match self.socket.recv_from(&mut recv_buffer) {
-                Ok((len, source)) => {
-                    if source == target_addr {
-                        if let Some(response_data) =
-                            self.process_confirmed_response(&recv_buffer[..len], invoke_id)
-                        {
-                            return Ok(response_data);
-                        }
-                    }
-                }
-                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => continue,
-                Err(e) => return Err(e.into()),
-            }
-        }
-
-        Err("Request timeout".into())
+        bvlc_message
     }
 
     /// Parse I-Am response
     fn parse_iam_response(&self, data: &[u8], source: SocketAddr) -> Option<DeviceInfo> {
+        let iam = self.decode_iam(data)?;
+        Some(DeviceInfo::from_i_am(&iam, source))
+    }
+
+    /// Decode the I-Am carried by a BACnet/IP message
+    fn decode_iam(&self, data: &[u8]) -> Option<IAmRequest> {
+        let service_data = self.unconfirmed_service_data(data, UnconfirmedServiceChoice::IAm)?;
+        IAmRequest::decode(service_data).ok()
+    }
+
+    /// Extract the service data of an unconfirmed request carried by a
+    /// BACnet/IP message, if it is the expected service
+    fn unconfirmed_service_data<'a>(
+        &self,
+        data: &'a [u8],
+        service_choice: UnconfirmedServiceChoice,
+    ) -> Option<&'a [u8]> {
         // Check BVLC header
         if data.len() < 4 || data[0] != 0x81 {
             return None;
@@ -345,31 +992,15 @@ impl BacnetClient {
         let apdu_start = npdu_start + npdu_len;
         let apdu = &data[apdu_start..];
 
-        if apdu.len() < 2 || apdu[0] != 0x10 || apdu[1] != UnconfirmedServiceChoice::IAm as u8 {
+        if apdu.len() < 2 || apdu[0] != 0x10 || apdu[1] != service_choice as u8 {
             return None;
         }
 
-        match IAmRequest::decode(&apdu[2..]) {
-            Ok(iam) => {
-                let vendor_name = crate::vendor::get_vendor_name(iam.vendor_identifier as u16)
-                    .unwrap_or("Unknown Vendor")
-                    .to_string();
-
-                Some(DeviceInfo {
-                    device_id: iam.device_identifier.instance,
-                    address: source,
-                    vendor_id: iam.vendor_identifier,
-                    vendor_name,
-                    max_apdu: iam.max_apdu_length_accepted,
-                    segmentation: iam.segmentation_supported,
-                })
-            }
-            Err(_) => None,
-        }
+        Some(&apdu[2..])
     }
 
-    /// Process confirmed response
-    fn process_confirmed_response(&self, data: &[u8], expected_invoke_id: u8) -> Option<Vec<u8>> {
+    /// Decode the APDU carried by a BACnet/IP message
+    fn decode_apdu(&self, data: &[u8]) -> Option<Apdu> {
         // Check BVLC header
         if data.len() < 4 || data[0] != 0x81 {
             return None;
@@ -385,22 +1016,7 @@ impl BacnetClient {
         let (_npdu, npdu_len) = Npdu::decode(&data[npdu_start..]).ok()?;
 
         let apdu_start = npdu_start + npdu_len;
-        let apdu = Apdu::decode(&data[apdu_start..]).ok()?;
-
-        match apdu {
-            Apdu::ComplexAck {
-                invoke_id,
-                service_data,
-                ..
-            } => {
-                if invoke_id == expected_invoke_id {
-                    Some(service_data)
-                } else {
-                    None
-                }
-            }
-            _ => None,
-        }
+        Apdu::decode(&data[apdu_start..]).ok()
     }
 
     /// Encode ReadPropertyMultiple request
@@ -413,7 +1029,7 @@ impl BacnetClient {
         for spec in &request.read_access_specifications {
             // Object identifier - context tag 0
             let object_id = encode_object_id(
-                spec.object_identifier.object_type as u16,
+                u16::from(spec.object_identifier.object_type),
                 spec.object_identifier.instance,
             );
             buffer.push(0x0C);
@@ -560,7 +1176,35 @@ pub fn get_object_type_name(object_type: ObjectType) -> &'static str {
         ObjectType::LoadControl => "Load Control",
         ObjectType::StructuredView => "Structured View",
         ObjectType::AccessDoor => "Access Door",
+        ObjectType::AccessPoint => "Access Point",
+        ObjectType::AccessZone => "Access Zone",
+        ObjectType::CredentialDataInput => "Credential Data Input",
+        ObjectType::CharacterStringValue => "CharacterString Value",
+        ObjectType::IntegerValue => "Integer Value",
+        ObjectType::PositiveIntegerValue => "Positive Integer Value",
+        ObjectType::LargeAnalogValue => "Large Analog Value",
+        ObjectType::DatePatternValue => "Date Pattern Value",
+        ObjectType::DateValue => "Date Value",
+        ObjectType::DateTimePatternValue => "DateTime Pattern Value",
+        ObjectType::DateTimeValue => "DateTime Value",
+        ObjectType::TimePatternValue => "Time Pattern Value",
+        ObjectType::TimeValue => "Time Value",
+        ObjectType::BitStringValue => "BitString Value",
+        ObjectType::Channel => "Channel",
+        ObjectType::LightingOutput => "Lighting Output",
+        ObjectType::BinaryLightingOutput => "Binary Lighting Output",
+        ObjectType::NetworkPort => "Network Port",
+        ObjectType::ElevatorGroup => "Elevator Group",
+        ObjectType::Escalator => "Escalator",
+        ObjectType::Lift => "Lift",
+        ObjectType::AuditLog => "Audit Log",
+        ObjectType::AuditReporter => "Audit Reporter",
+        ObjectType::Timer => "Timer",
+        ObjectType::NotificationForwarder => "Notification Forwarder",
+        ObjectType::AlertEnrollment => "Alert Enrollment",
+        ObjectType::Staging => "Staging",
         ObjectType::OctetString => "Octet String",
+        ObjectType::Proprietary(_) => "Proprietary",
     }
 }
 
@@ -593,4 +1237,118 @@ mod tests {
         assert_eq!(obj_type, 8);
         assert_eq!(instance, 5047);
     }
+
+    #[test]
+    fn test_communication_control_holds_back_requests() {
+        use crate::app::{MaxApduSize, MaxSegments};
+        use crate::service::{DeviceCommunicationControlRequest, EnableDisable};
+
+        let handler = Arc::new(Mutex::new(ApplicationLayerHandler::new(1234)));
+        let mut client = BacnetClient::new().unwrap();
+        client.set_handler(handler.clone());
+        let receiver = UdpSocket::bind("127.0.0.1:0").unwrap();
+        receiver
+            .set_read_timeout(Some(Duration::from_millis(200)))
+            .unwrap();
+        let target = receiver.local_addr().unwrap();
+        let request = PrivateTransferRequest::new(260, 1);
+        let mut buffer = [0u8; 1500];
+
+        client.send_private_transfer(target, &request).unwrap();
+        assert!(receiver.recv_from(&mut buffer).is_ok());
+
+        let mut service_data = Vec::new();
+        DeviceCommunicationControlRequest::new(EnableDisable::DisableInitiation)
+            .encode(&mut service_data)
+            .unwrap();
+        let dcc = Apdu::ConfirmedRequest {
+            segmented: false,
+            more_follows: false,
+            segmented_response_accepted: false,
+            max_segments: MaxSegments::Unspecified,
+            max_response_size: MaxApduSize::Up1476,
+            invoke_id: 1,
+            sequence_number: None,
+            proposed_window_size: None,
+            service_choice: ConfirmedServiceChoice::DeviceCommunicationControl,
+            service_data,
+        };
+        handler.lock().unwrap().process_apdu(&dcc, &[]).unwrap();
+
+        assert!(client.send_private_transfer(target, &request).is_err());
+        assert!(client.private_transfer(target, &request).is_err());
+        assert!(receiver.recv_from(&mut buffer).is_err());
+        assert_eq!(handler.lock().unwrap().stats.dropped_apdus, 2);
+    }
+
+    #[test]
+    fn test_discovery_results() {
+        let address: SocketAddr = "192.168.1.10:47808".parse().unwrap();
+        let iam = |instance| {
+            IAmRequest::new(
+                ObjectIdentifier::new(ObjectType::Device, instance),
+                1476,
+                3,
+                260,
+            )
+        };
+
+        let mut results = DiscoveryResults::new();
+        assert!(results.record(&iam(200), address));
+        assert!(results.record(&iam(100), address));
+        // A repeated answer updates the entry rather than adding one
+        let moved: SocketAddr = "192.168.1.11:47808".parse().unwrap();
+        assert!(!results.record(&iam(200), moved));
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results.get(200).unwrap().address, moved);
+        let ids: Vec<u32> = results.devices().map(|device| device.device_id).collect();
+        assert_eq!(ids, vec![100, 200]);
+    }
+
+    #[test]
+    fn test_cov_notification_dispatcher() {
+        use crate::service::{BacnetPropertyValue, PendingCovNotification};
+        use std::sync::mpsc;
+
+        let (sender, receiver) = mpsc::channel();
+        let mut dispatcher = CovNotificationDispatcher::new();
+        dispatcher.register(7, move |notification| {
+            sender.send(notification.time_remaining).unwrap();
+        });
+
+        let pending = |subscriber_process_identifier, confirmed| PendingCovNotification {
+            subscriber_device_identifier: ObjectIdentifier::new(ObjectType::Device, 2),
+            issue_confirmed_notifications: confirmed,
+            notification: CovNotificationRequest::new(
+                subscriber_process_identifier,
+                ObjectIdentifier::new(ObjectType::Device, 1),
+                ObjectIdentifier::new(ObjectType::AnalogInput, 1),
+                300,
+                vec![BacnetPropertyValue::new(
+                    85,
+                    crate::object::PropertyValue::Real(21.5),
+                )],
+            ),
+        };
+
+        // Unconfirmed notifications need no reply
+        let apdu = pending(7, false).to_apdu(0).unwrap();
+        assert!(dispatcher.handle_apdu(&apdu).is_none());
+        assert_eq!(receiver.try_recv(), Ok(300));
+
+        // Confirmed ones are acknowledged even without a callback
+        let apdu = pending(8, true).to_apdu(4).unwrap();
+        assert!(matches!(
+            dispatcher.handle_apdu(&apdu),
+            Some(Apdu::SimpleAck {
+                invoke_id: 4,
+                service_choice: 1,
+            })
+        ));
+        assert!(receiver.try_recv().is_err());
+
+        assert!(dispatcher.unregister(7));
+        assert!(!dispatcher.dispatch(&pending(7, false).notification));
+    }
 }
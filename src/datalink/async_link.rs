@@ -0,0 +1,1485 @@
+//! Asynchronous data links on Tokio.
+//!
+//! [`AsyncDataLink`] is the `async` counterpart of [`DataLink`]: sending and
+//! receiving are futures, and every method takes `&self`, so one link can be
+//! shared between tasks behind an `Arc`. Receiving waits for as long as it
+//! takes; bound it with [`tokio::time::timeout`] where needed.
+//!
+//! - [`AsyncBacnetIpDataLink`] runs BACnet/IP on a Tokio UDP socket.
+//! - [`AsyncMstpDataLink`] and [`AsyncPtpDataLink`] run the MS/TP master node
+//!   and the PTP connection in a task on any async byte stream, such as a
+//!   serial device opened with [`open`](AsyncMstpDataLink::open).
+//! - [`AsyncScDataLink`] runs the BACnet/SC node in a task on the async TLS
+//!   sessions of an [`AsyncScConnector`].
+//! - [`AsyncEthernetDataLink`] runs BACnet/Ethernet on a raw socket waited
+//!   on by Tokio (Linux only).
+//! - [`BlockingDataLink`] runs an application's own [`DataLink`] on Tokio's
+//!   blocking thread pool.
+//!
+//! The state machines are the ones the blocking links use; only the I/O
+//! around them differs.
+
+use std::{
+    io::{self, ErrorKind},
+    net::{Ipv4Addr, SocketAddr},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    net::{ToSocketAddrs, UdpSocket},
+    sync::mpsc,
+    task::JoinHandle,
+};
+
+use crate::datalink::bip::{BvlcMessage, BACNET_IP_PORT, BIP_MAX_NPDU_LENGTH};
+use crate::datalink::bsc::{
+    not_connected, ConnectInfo, Hub, HubAction, HubConnectionStatus, HubConnector,
+    ScConnectionState, ScFunction, ScMessage, ScNodeConfig, ScPayload, Vmac,
+    ERROR_CODE_NODE_DUPLICATE_VMAC, HUB_SUBPROTOCOL,
+};
+#[cfg(target_os = "linux")]
+use crate::datalink::ethernet::{
+    EthernetFrame, RawSocket, ETHERNET_BROADCAST_MAC, MAX_ETHERNET_FRAME_SIZE,
+    MAX_ETHERNET_NPDU_LENGTH,
+};
+use crate::datalink::mstp::{
+    FrameReceiver, MasterNode, MstpConfig, MstpOutcome, MstpState, MSTP_BROADCAST_ADDRESS,
+    MSTP_MAX_EXTENDED_DATA_LENGTH, MSTP_MAX_FRAME_SIZE,
+};
+use crate::datalink::ptp::{
+    PtpConfig, PtpConnection, PtpOutcome, PtpReceiver, PtpState, PTP_HEADER_SIZE,
+    PTP_MAX_DATA_LENGTH,
+};
+use crate::datalink::websocket::{
+    check_handshake_response, handshake_request, stream_ended, Framing, WebSocketUri,
+};
+use crate::datalink::{DataLink, DataLinkAddress, DataLinkError, DataLinkType, Result};
+
+/// How long a port task waits for octets before running the timers
+const PORT_POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+/// How long the BACnet/SC node task waits for traffic before running the
+/// hub connection timers, which count in seconds
+const SC_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Queue of NPDUs received by a port task
+type ReceiveQueue = tokio::sync::Mutex<mpsc::UnboundedReceiver<(Vec<u8>, DataLinkAddress)>>;
+
+/// Asynchronous BACnet data link.
+///
+/// Implementations must be shareable between tasks; links with mutable state
+/// keep it behind their own locks.
+#[async_trait]
+pub trait AsyncDataLink: Send + Sync {
+    /// Send an NPDU to `dest`.
+    async fn send_frame(&self, frame: &[u8], dest: &DataLinkAddress) -> Result<()>;
+
+    /// Wait for the next NPDU, returning it with the address it came from.
+    async fn receive_frame(&self) -> Result<(Vec<u8>, DataLinkAddress)>;
+
+    /// The type of data link.
+    fn link_type(&self) -> DataLinkType;
+
+    /// This station's address on the link.
+    fn local_address(&self) -> DataLinkAddress;
+
+    /// The address that reaches every station on the link.
+    fn broadcast_address(&self) -> DataLinkAddress {
+        DataLinkAddress::Broadcast
+    }
+
+    /// The longest NPDU the link carries.
+    fn max_npdu_length(&self) -> usize;
+}
+
+/// BACnet/IP on a Tokio UDP socket.
+///
+/// This is a plain B/IP node: it sends and receives Original-Unicast-NPDU and
+/// Original-Broadcast-NPDU messages and accepts Forwarded-NPDUs, but takes no
+/// BBMD or foreign device role; use [`BacnetIpDataLink`] through
+/// [`BlockingDataLink`] for those.
+///
+/// [`BacnetIpDataLink`]: crate::datalink::bip::BacnetIpDataLink
+#[derive(Debug)]
+pub struct AsyncBacnetIpDataLink {
+    /// UDP socket
+    socket: UdpSocket,
+    /// Local IP address and port
+    local_addr: SocketAddr,
+    /// Local broadcast address, the /24 broadcast address unless set
+    broadcast_addr: SocketAddr,
+}
+
+impl AsyncBacnetIpDataLink {
+    /// Bind a UDP socket to `bind_addr`, with broadcast enabled
+    pub async fn bind<A: ToSocketAddrs>(bind_addr: A) -> Result<Self> {
+        let socket = UdpSocket::bind(bind_addr)
+            .await
+            .map_err(DataLinkError::IoError)?;
+        let local_addr = socket.local_addr().map_err(DataLinkError::IoError)?;
+        socket.set_broadcast(true).map_err(DataLinkError::IoError)?;
+
+        let broadcast_addr = match local_addr {
+            SocketAddr::V4(addr) => {
+                let ip = addr.ip().octets();
+                SocketAddr::new(
+                    Ipv4Addr::new(ip[0], ip[1], ip[2], 255).into(),
+                    BACNET_IP_PORT,
+                )
+            }
+            SocketAddr::V6(_) => return Err(DataLinkError::UnsupportedType),
+        };
+
+        Ok(Self {
+            socket,
+            local_addr,
+            broadcast_addr,
+        })
+    }
+
+    /// Set the local broadcast address
+    pub fn set_broadcast_address(&mut self, address: SocketAddr) {
+        self.broadcast_addr = address;
+    }
+
+    async fn send_message(&self, message: &BvlcMessage, dest: SocketAddr) -> Result<()> {
+        self.socket
+            .send_to(&message.encode()?, dest)
+            .await
+            .map_err(DataLinkError::IoError)?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl AsyncDataLink for AsyncBacnetIpDataLink {
+    async fn send_frame(&self, frame: &[u8], dest: &DataLinkAddress) -> Result<()> {
+        match dest {
+            // The local broadcast address is a broadcast however it is named
+            DataLinkAddress::Ip(addr) if *addr != self.broadcast_addr => {
+                let message = BvlcMessage::OriginalUnicastNpdu(frame.to_vec());
+                self.send_message(&message, *addr).await
+            }
+            DataLinkAddress::Ip(_) | DataLinkAddress::Broadcast => {
+                let message = BvlcMessage::OriginalBroadcastNpdu(frame.to_vec());
+                self.send_message(&message, self.broadcast_addr).await
+            }
+            _ => Err(DataLinkError::UnsupportedType),
+        }
+    }
+
+    /// BVLC messages other than NPDUs are dropped on the way
+    async fn receive_frame(&self) -> Result<(Vec<u8>, DataLinkAddress)> {
+        let mut buffer = [0u8; 1500];
+        loop {
+            let (len, source) = self
+                .socket
+                .recv_from(&mut buffer)
+                .await
+                .map_err(DataLinkError::IoError)?;
+            if source == self.local_addr {
+                continue;
+            }
+            match BvlcMessage::decode(&buffer[..len]) {
+                Ok(BvlcMessage::OriginalUnicastNpdu(npdu))
+                | Ok(BvlcMessage::OriginalBroadcastNpdu(npdu)) => {
+                    return Ok((npdu, DataLinkAddress::Ip(source)))
+                }
+                Ok(BvlcMessage::ForwardedNpdu { source, npdu }) => {
+                    return Ok((npdu, DataLinkAddress::Ip(source)))
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn link_type(&self) -> DataLinkType {
+        DataLinkType::BacnetIp
+    }
+
+    fn local_address(&self) -> DataLinkAddress {
+        DataLinkAddress::Ip(self.local_addr)
+    }
+
+    fn max_npdu_length(&self) -> usize {
+        BIP_MAX_NPDU_LENGTH
+    }
+}
+
+/// Open a serial device for reading and writing
+async fn open_port(path: &str) -> Result<tokio::fs::File> {
+    tokio::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(path)
+        .await
+        .map_err(DataLinkError::IoError)
+}
+
+/// Wait for an NPDU from a port task
+async fn next_received(queue: &ReceiveQueue) -> Result<(Vec<u8>, DataLinkAddress)> {
+    queue.lock().await.recv().await.ok_or_else(task_ended)
+}
+
+fn task_ended() -> DataLinkError {
+    DataLinkError::IoError(io::Error::new(ErrorKind::BrokenPipe, "Port task ended"))
+}
+
+/// MS/TP master node in a Tokio task.
+///
+/// The task owns the port and runs the frame receiver and the
+/// [`MasterNode`], as the thread of [`MstpDataLink`] does. The task stops
+/// when the link is dropped.
+///
+/// [`MstpDataLink`]: crate::datalink::mstp::MstpDataLink
+#[derive(Debug)]
+pub struct AsyncMstpDataLink {
+    /// Master node state machine, shared with the port task
+    node: Arc<Mutex<MasterNode>>,
+    /// NPDUs received
+    receive_queue: ReceiveQueue,
+    /// Port task
+    task: JoinHandle<()>,
+}
+
+impl AsyncMstpDataLink {
+    /// Open the serial device at `path` and start the node on it
+    ///
+    /// The device must already be set up as for
+    /// [`MstpDataLink::open`](crate::datalink::mstp::MstpDataLink::open).
+    pub async fn open(path: &str, config: MstpConfig) -> Result<Self> {
+        Self::new(open_port(path).await?, config)
+    }
+
+    /// Start the node on a byte stream to the line
+    ///
+    /// Must be called within a Tokio runtime.
+    pub fn new<P>(port: P, config: MstpConfig) -> Result<Self>
+    where
+        P: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    {
+        if config.station_address == MSTP_BROADCAST_ADDRESS && config.zero_config.is_none() {
+            return Err(DataLinkError::AddressError(
+                "MS/TP station address 255 is the broadcast address".into(),
+            ));
+        }
+        let node = Arc::new(Mutex::new(MasterNode::new(config.clone(), Instant::now())));
+        let (received, receive_queue) = mpsc::unbounded_channel();
+        let task = tokio::spawn(run_mstp_port(port, config, node.clone(), received));
+        Ok(Self {
+            node,
+            receive_queue: tokio::sync::Mutex::new(receive_queue),
+            task,
+        })
+    }
+
+    /// The current state of the master node
+    pub fn state(&self) -> MstpState {
+        self.node.lock().unwrap().state()
+    }
+
+    /// Whether this station found no other master
+    pub fn is_sole_master(&self) -> bool {
+        self.node.lock().unwrap().is_sole_master()
+    }
+}
+
+impl Drop for AsyncMstpDataLink {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Body of the MS/TP port task
+async fn run_mstp_port<P: AsyncRead + AsyncWrite + Unpin>(
+    mut port: P,
+    config: MstpConfig,
+    node: Arc<Mutex<MasterNode>>,
+    received: mpsc::UnboundedSender<(Vec<u8>, DataLinkAddress)>,
+) {
+    let mut receiver = FrameReceiver::new(Duration::from_millis(config.frame_abort));
+    let mut buffer = [0u8; MSTP_MAX_FRAME_SIZE];
+    let mut last_octet = Instant::now();
+    loop {
+        let read = tokio::time::timeout(PORT_POLL_INTERVAL, port.read(&mut buffer)).await;
+        let now = Instant::now();
+        let mut outcome = MstpOutcome::default();
+        let mut idle = false;
+        {
+            let mut master = node.lock().unwrap();
+            match read {
+                Ok(Ok(0)) => idle = true,
+                Ok(Ok(count)) => {
+                    master.octets_received(count, now);
+                    last_octet = now;
+                    for &octet in &buffer[..count] {
+                        if let Some(event) = receiver.receive(octet, now) {
+                            outcome.extend(master.receive(event, now));
+                        }
+                    }
+                }
+                Ok(Err(e)) if is_transient(&e) => {}
+                Ok(Err(_)) => return,
+                Err(_) => {}
+            }
+            if let Some(event) = receiver.timeout(now) {
+                outcome.extend(master.receive(event, now));
+            }
+            outcome.extend(master.poll(now));
+        }
+
+        if !outcome.transmit.is_empty() {
+            // Tturnaround
+            tokio::time::sleep(config.turnaround().saturating_sub(last_octet.elapsed())).await;
+            let mut octets = 0;
+            for frame in &outcome.transmit {
+                let encoded = frame.encode();
+                octets += encoded.len();
+                if port.write_all(&encoded).await.is_err() {
+                    return;
+                }
+            }
+            let _ = port.flush().await;
+            let end = Instant::now() + config.transmission_time(octets);
+            node.lock().unwrap().transmission_ended(end);
+        } else if idle {
+            // A read of nothing returns at once; give the line a moment
+            tokio::time::sleep(PORT_POLL_INTERVAL).await;
+        }
+
+        for (npdu, source) in outcome.received {
+            if received
+                .send((npdu, DataLinkAddress::MsTP(source)))
+                .is_err()
+            {
+                return;
+            }
+        }
+    }
+}
+
+/// Whether a port error only means no data was waiting
+fn is_transient(error: &io::Error) -> bool {
+    matches!(
+        error.kind(),
+        ErrorKind::WouldBlock | ErrorKind::TimedOut | ErrorKind::Interrupted
+    )
+}
+
+#[async_trait]
+impl AsyncDataLink for AsyncMstpDataLink {
+    async fn send_frame(&self, frame: &[u8], dest: &DataLinkAddress) -> Result<()> {
+        let dest_addr = match dest {
+            DataLinkAddress::MsTP(addr) => *addr,
+            DataLinkAddress::Broadcast => MSTP_BROADCAST_ADDRESS,
+            _ => {
+                return Err(DataLinkError::AddressError(
+                    "Invalid address type for MS/TP".into(),
+                ))
+            }
+        };
+        let expecting_reply = frame.len() > 1 && frame[1] & 0x04 != 0;
+        self.node
+            .lock()
+            .unwrap()
+            .queue(frame.to_vec(), dest_addr, expecting_reply)
+    }
+
+    async fn receive_frame(&self) -> Result<(Vec<u8>, DataLinkAddress)> {
+        next_received(&self.receive_queue).await
+    }
+
+    fn link_type(&self) -> DataLinkType {
+        DataLinkType::MsTP
+    }
+
+    fn local_address(&self) -> DataLinkAddress {
+        DataLinkAddress::MsTP(self.node.lock().unwrap().config().station_address)
+    }
+
+    fn broadcast_address(&self) -> DataLinkAddress {
+        DataLinkAddress::MsTP(MSTP_BROADCAST_ADDRESS)
+    }
+
+    fn max_npdu_length(&self) -> usize {
+        MSTP_MAX_EXTENDED_DATA_LENGTH
+    }
+}
+
+/// PTP connection in a Tokio task.
+///
+/// The task owns the port and runs the frame receiver and the
+/// [`PtpConnection`], as the thread of [`PtpDataLink`] does. The task stops
+/// when the link is dropped.
+///
+/// [`PtpDataLink`]: crate::datalink::ptp::PtpDataLink
+#[derive(Debug)]
+pub struct AsyncPtpDataLink {
+    /// Connection state machine, shared with the port task
+    connection: Arc<Mutex<PtpConnection>>,
+    /// Octets for the port task to transmit
+    transmit: Arc<Mutex<Vec<Vec<u8>>>>,
+    /// NPDUs received
+    receive_queue: ReceiveQueue,
+    /// Port task
+    task: JoinHandle<()>,
+}
+
+impl AsyncPtpDataLink {
+    /// Open the serial device at `path` and wait for calls on it
+    ///
+    /// The device must already be set up as for
+    /// [`PtpDataLink::open`](crate::datalink::ptp::PtpDataLink::open).
+    pub async fn open(path: &str, config: PtpConfig) -> Result<Self> {
+        Ok(Self::new(open_port(path).await?, config))
+    }
+
+    /// Run the connection on a byte stream to the peer
+    ///
+    /// Must be called within a Tokio runtime.
+    pub fn new<P>(port: P, config: PtpConfig) -> Self
+    where
+        P: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    {
+        let receiver = PtpReceiver::new(Duration::from_millis(config.frame_abort));
+        let connection = Arc::new(Mutex::new(PtpConnection::new(config, Instant::now())));
+        let transmit = Arc::new(Mutex::new(Vec::new()));
+        let (received, receive_queue) = mpsc::unbounded_channel();
+        let task = tokio::spawn(run_ptp_port(
+            port,
+            receiver,
+            connection.clone(),
+            transmit.clone(),
+            received,
+        ));
+        Self {
+            connection,
+            transmit,
+            receive_queue: tokio::sync::Mutex::new(receive_queue),
+            task,
+        }
+    }
+
+    /// The current state of the connection
+    pub fn state(&self) -> PtpState {
+        self.connection.lock().unwrap().state()
+    }
+
+    /// Call the peer
+    pub fn connect(&self) {
+        let outcome = self.connection.lock().unwrap().connect(Instant::now());
+        self.transmit.lock().unwrap().extend(outcome.transmit);
+    }
+
+    /// Close the connection
+    pub fn disconnect(&self) {
+        let outcome = self.connection.lock().unwrap().disconnect(Instant::now());
+        self.transmit.lock().unwrap().extend(outcome.transmit);
+    }
+}
+
+impl Drop for AsyncPtpDataLink {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Body of the PTP port task
+async fn run_ptp_port<P: AsyncRead + AsyncWrite + Unpin>(
+    mut port: P,
+    mut receiver: PtpReceiver,
+    connection: Arc<Mutex<PtpConnection>>,
+    transmit: Arc<Mutex<Vec<Vec<u8>>>>,
+    received: mpsc::UnboundedSender<(Vec<u8>, DataLinkAddress)>,
+) {
+    let mut buffer = [0u8; 2 * (PTP_HEADER_SIZE + PTP_MAX_DATA_LENGTH + 2)];
+    loop {
+        let read = tokio::time::timeout(PORT_POLL_INTERVAL, port.read(&mut buffer)).await;
+        let now = Instant::now();
+        let mut outcome = PtpOutcome {
+            transmit: core::mem::take(&mut *transmit.lock().unwrap()),
+            received: Vec::new(),
+        };
+        let mut idle = false;
+        {
+            let mut ptp = connection.lock().unwrap();
+            match read {
+                Ok(Ok(0)) => idle = true,
+                Ok(Ok(count)) => {
+                    for &octet in &buffer[..count] {
+                        if let Some(event) = receiver.receive(octet, now) {
+                            outcome.extend(ptp.receive(event, now));
+                        }
+                    }
+                }
+                Ok(Err(e)) if is_transient(&e) => {}
+                Ok(Err(_)) => return,
+                Err(_) => {}
+            }
+            if let Some(event) = receiver.timeout(now) {
+                outcome.extend(ptp.receive(event, now));
+            }
+            outcome.extend(ptp.poll(now));
+        }
+
+        for octets in &outcome.transmit {
+            if port.write_all(octets).await.is_err() {
+                return;
+            }
+        }
+        if !outcome.transmit.is_empty() {
+            let _ = port.flush().await;
+        } else if idle {
+            tokio::time::sleep(PORT_POLL_INTERVAL).await;
+        }
+
+        for npdu in outcome.received {
+            if received
+                .send((npdu, DataLinkAddress::PointToPoint))
+                .is_err()
+            {
+                return;
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl AsyncDataLink for AsyncPtpDataLink {
+    async fn send_frame(&self, frame: &[u8], dest: &DataLinkAddress) -> Result<()> {
+        match dest {
+            DataLinkAddress::PointToPoint | DataLinkAddress::Broadcast => {}
+            _ => {
+                return Err(DataLinkError::AddressError(
+                    "Invalid address type for PTP".into(),
+                ))
+            }
+        }
+        self.connection.lock().unwrap().queue(frame.to_vec())
+    }
+
+    async fn receive_frame(&self) -> Result<(Vec<u8>, DataLinkAddress)> {
+        next_received(&self.receive_queue).await
+    }
+
+    fn link_type(&self) -> DataLinkType {
+        DataLinkType::PointToPoint
+    }
+
+    fn local_address(&self) -> DataLinkAddress {
+        DataLinkAddress::PointToPoint
+    }
+
+    fn broadcast_address(&self) -> DataLinkAddress {
+        DataLinkAddress::PointToPoint
+    }
+
+    fn max_npdu_length(&self) -> usize {
+        PTP_MAX_DATA_LENGTH
+    }
+}
+
+/// Opens the TLS sessions an [`AsyncScDataLink`] runs its WebSocket over.
+///
+/// The async counterpart of [`ScConnector`]: the connector authenticates the
+/// hub and presents the device's operational certificate.
+///
+/// [`ScConnector`]: crate::datalink::bsc::ScConnector
+#[async_trait]
+pub trait AsyncScConnector: Send + 'static {
+    /// The TLS session type.
+    type Stream: AsyncRead + AsyncWrite + Unpin + Send + 'static;
+
+    /// Open a TLS session to the host and port of `uri`.
+    async fn connect(&mut self, uri: &WebSocketUri) -> io::Result<Self::Stream>;
+}
+
+/// The hub connection, as last reported by the node task
+#[derive(Debug, Clone)]
+struct ScStatus {
+    vmac: Vmac,
+    state: ScConnectionState,
+    hub_status: HubConnectionStatus,
+    hub_info: Option<ConnectInfo>,
+}
+
+/// What the link asks of its node task
+#[derive(Debug)]
+enum ScCommand {
+    /// Send an NPDU through the hub
+    Send(Vec<u8>, Vmac),
+    /// Start connecting again after a disconnect
+    Connect,
+    /// Disconnect from the hub gracefully
+    Disconnect,
+}
+
+/// BACnet/SC node in a Tokio task.
+///
+/// The task owns the WebSocket to the hub and runs the [`HubConnector`],
+/// answering the control messages as [`ScDataLink`] does, so only NPDUs
+/// reach [`receive_frame`](AsyncDataLink::receive_frame). Sends are queued
+/// to the task and never wait behind a receive. The node talks through the
+/// hub only: it opens and accepts no direct connections, and answers
+/// Address-Resolution with no URIs. The task stops when the link is
+/// dropped.
+///
+/// [`ScDataLink`]: crate::datalink::bsc::ScDataLink
+#[derive(Debug)]
+pub struct AsyncScDataLink {
+    /// Hub connection, updated by the node task
+    status: Arc<Mutex<ScStatus>>,
+    /// Requests for the node task
+    commands: mpsc::UnboundedSender<ScCommand>,
+    /// NPDUs received
+    receive_queue: ReceiveQueue,
+    /// Largest NPDU sent or accepted
+    max_npdu_length: usize,
+    /// Node task
+    task: JoinHandle<()>,
+}
+
+impl AsyncScDataLink {
+    /// Start a node that connects to its hub at once
+    ///
+    /// Must be called within a Tokio runtime.
+    pub fn new<C: AsyncScConnector>(config: ScNodeConfig, connector: C) -> Self {
+        let status = Arc::new(Mutex::new(ScStatus {
+            vmac: config.vmac,
+            state: ScConnectionState::Idle,
+            hub_status: HubConnectionStatus::NoHubConnection,
+            hub_info: None,
+        }));
+        let (commands, command_queue) = mpsc::unbounded_channel();
+        let (received, receive_queue) = mpsc::unbounded_channel();
+        let max_npdu_length = config.max_npdu_length as usize;
+        let node = ScNode {
+            hub: HubConnector::new(&config, Instant::now()),
+            config,
+            connector,
+            socket: None,
+            hub_info: None,
+            message_id: 0,
+            status: status.clone(),
+            received,
+        };
+        let task = tokio::spawn(node.run(command_queue));
+        Self {
+            status,
+            commands,
+            receive_queue: tokio::sync::Mutex::new(receive_queue),
+            max_npdu_length,
+            task,
+        }
+    }
+
+    /// The VMAC of this node
+    ///
+    /// A new one is picked when the hub reports the VMAC as a duplicate.
+    pub fn vmac(&self) -> Vmac {
+        self.status.lock().unwrap().vmac
+    }
+
+    /// The hub connection state
+    pub fn state(&self) -> ScConnectionState {
+        self.status.lock().unwrap().state
+    }
+
+    /// The hub connection status
+    pub fn hub_status(&self) -> HubConnectionStatus {
+        self.status.lock().unwrap().hub_status
+    }
+
+    /// The Connect-Accept payload of the hub connected to
+    pub fn hub_info(&self) -> Option<ConnectInfo> {
+        self.status.lock().unwrap().hub_info
+    }
+
+    /// Reconnect after a [`disconnect`](Self::disconnect), starting with the
+    /// primary hub
+    pub fn connect(&self) {
+        let _ = self.commands.send(ScCommand::Connect);
+    }
+
+    /// Disconnect from the hub gracefully
+    ///
+    /// The node sends a Disconnect-Request and closes the WebSocket when the
+    /// Disconnect-ACK arrives or the disconnect wait timeout runs out. It
+    /// stays disconnected until [`connect`](Self::connect).
+    pub fn disconnect(&self) {
+        let _ = self.commands.send(ScCommand::Disconnect);
+    }
+}
+
+impl Drop for AsyncScDataLink {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+#[async_trait]
+impl AsyncDataLink for AsyncScDataLink {
+    async fn send_frame(&self, frame: &[u8], dest: &DataLinkAddress) -> Result<()> {
+        let destination = match dest {
+            DataLinkAddress::SecureConnect(vmac) => Vmac(*vmac),
+            DataLinkAddress::Broadcast => Vmac::BROADCAST,
+            _ => {
+                return Err(DataLinkError::AddressError(
+                    "Invalid address type for BACnet/SC".into(),
+                ))
+            }
+        };
+        if frame.len() > self.max_npdu_length {
+            return Err(DataLinkError::InvalidFrame);
+        }
+        if self.state() != ScConnectionState::Connected {
+            return Err(not_connected());
+        }
+        self.commands
+            .send(ScCommand::Send(frame.to_vec(), destination))
+            .map_err(|_| task_ended())
+    }
+
+    async fn receive_frame(&self) -> Result<(Vec<u8>, DataLinkAddress)> {
+        next_received(&self.receive_queue).await
+    }
+
+    fn link_type(&self) -> DataLinkType {
+        DataLinkType::SecureConnect
+    }
+
+    fn local_address(&self) -> DataLinkAddress {
+        DataLinkAddress::SecureConnect(self.vmac().0)
+    }
+
+    fn broadcast_address(&self) -> DataLinkAddress {
+        DataLinkAddress::SecureConnect(Vmac::BROADCAST.0)
+    }
+
+    fn max_npdu_length(&self) -> usize {
+        self.max_npdu_length
+    }
+}
+
+/// The BACnet/SC node run by the task of an [`AsyncScDataLink`]
+struct ScNode<C: AsyncScConnector> {
+    config: ScNodeConfig,
+    connector: C,
+    hub: HubConnector,
+    /// WebSocket to the hub, while one is open
+    socket: Option<(C::Stream, Framing)>,
+    /// Connect-Accept payload of the current hub
+    hub_info: Option<ConnectInfo>,
+    /// Message ID of the last message originated
+    message_id: u16,
+    status: Arc<Mutex<ScStatus>>,
+    received: mpsc::UnboundedSender<(Vec<u8>, DataLinkAddress)>,
+}
+
+impl<C: AsyncScConnector> ScNode<C> {
+    /// Body of the node task
+    async fn run(mut self, mut commands: mpsc::UnboundedReceiver<ScCommand>) {
+        let mut buffer = [0u8; 4096];
+        loop {
+            self.maintain().await;
+            self.publish();
+            tokio::select! {
+                read = read_socket(&mut self.socket, &mut buffer) => match read {
+                    Ok(0) => self.connection_lost(),
+                    Ok(count) => self.receive(&buffer[..count]).await,
+                    Err(e) if is_transient(&e) => {}
+                    Err(_) => self.connection_lost(),
+                },
+                command = commands.recv() => match command {
+                    Some(command) => self.command(command).await,
+                    None => return,
+                },
+                _ = tokio::time::sleep(SC_POLL_INTERVAL) => {}
+            }
+        }
+    }
+
+    /// Carry out whatever the connection timing asks for
+    async fn maintain(&mut self) {
+        match self.hub.poll(Instant::now()) {
+            Some(HubAction::Connect(hub)) => match self.open(hub).await {
+                Ok(socket) => {
+                    self.socket = Some(socket);
+                    self.hub_info = None;
+                    self.hub.attempt_started(Instant::now());
+                    let request = ScMessage::new(
+                        self.next_message_id(),
+                        ScPayload::ConnectRequest(self.config.connect_info()),
+                    );
+                    self.send_message(&request).await;
+                }
+                Err(_) => self.hub.attempt_failed(Instant::now()),
+            },
+            Some(HubAction::SendHeartbeat) => {
+                let message = ScMessage::new(self.next_message_id(), ScPayload::HeartbeatRequest);
+                self.send_message(&message).await;
+            }
+            Some(HubAction::Close) => self.close_socket().await,
+            None => {}
+        }
+    }
+
+    /// Open a WebSocket to `hub`, within the connect wait timeout
+    async fn open(&mut self, hub: Hub) -> io::Result<(C::Stream, Framing)> {
+        let uri: WebSocketUri = self.config.hub_uri(hub).parse()?;
+        let connector = &mut self.connector;
+        let handshake = async {
+            let mut stream = connector.connect(&uri).await?;
+            let framing = websocket_connect(&mut stream, &uri, HUB_SUBPROTOCOL).await?;
+            Ok((stream, framing))
+        };
+        tokio::time::timeout(self.config.connect_wait_timeout, handshake)
+            .await
+            .map_err(|_| io::Error::from(ErrorKind::TimedOut))?
+    }
+
+    async fn command(&mut self, command: ScCommand) {
+        let now = Instant::now();
+        match command {
+            ScCommand::Send(npdu, destination) => {
+                if self.hub.state() == ScConnectionState::Connected {
+                    let message =
+                        ScMessage::new(self.next_message_id(), ScPayload::EncapsulatedNpdu(npdu))
+                            .with_destination(destination);
+                    self.send_message(&message).await;
+                }
+            }
+            ScCommand::Connect => {
+                if self.hub.state() == ScConnectionState::Idle {
+                    self.hub.start(now);
+                }
+            }
+            ScCommand::Disconnect if self.socket.is_none() => self.hub.stopped(now),
+            ScCommand::Disconnect => {
+                self.hub.disconnect_started(now);
+                let request = ScMessage::new(self.next_message_id(), ScPayload::DisconnectRequest);
+                self.send_message(&request).await;
+            }
+        }
+    }
+
+    /// Handle octets from the hub
+    async fn receive(&mut self, data: &[u8]) {
+        if let Some((_, framing)) = self.socket.as_mut() {
+            framing.extend(data);
+        }
+        while let Some((_, framing)) = self.socket.as_mut() {
+            let mut replies = Vec::new();
+            let message = framing.take_message(&mut replies);
+            for reply in replies {
+                self.write(&reply).await;
+            }
+            match message {
+                Ok(Some(data)) => {
+                    if let Ok(message) = ScMessage::decode(&data) {
+                        self.process_message(message).await;
+                    }
+                }
+                Ok(None) => return,
+                Err(_) => return self.connection_lost(),
+            }
+        }
+    }
+
+    /// Handle a message from the hub
+    async fn process_message(&mut self, message: ScMessage) {
+        let now = Instant::now();
+        self.hub.received(now);
+        match (&message.payload, self.hub.state()) {
+            (ScPayload::ConnectAccept(info), ScConnectionState::AwaitingAccept) => {
+                self.hub_info = Some(*info);
+                self.hub.accepted(now);
+            }
+            (
+                ScPayload::Result {
+                    function,
+                    nak: Some(nak),
+                },
+                ScConnectionState::AwaitingAccept,
+            ) if *function == ScFunction::ConnectRequest as u8 => {
+                // Another node holds the VMAC: pick a new one for the next attempt
+                if nak.error_code == ERROR_CODE_NODE_DUPLICATE_VMAC {
+                    self.config.vmac = Vmac::random();
+                }
+                self.close_socket().await;
+                self.hub.attempt_failed(now);
+            }
+            (ScPayload::EncapsulatedNpdu(npdu), ScConnectionState::Connected) => {
+                if let Some(source) = message.originating {
+                    let _ = self.received.send((npdu.clone(), source.into()));
+                }
+            }
+            (ScPayload::HeartbeatRequest, _) => {
+                self.send_message(&message.reply(ScPayload::HeartbeatAck))
+                    .await;
+            }
+            (ScPayload::DisconnectRequest, _) => {
+                self.send_message(&message.reply(ScPayload::DisconnectAck))
+                    .await;
+                self.close_socket().await;
+                self.hub.closed(now);
+            }
+            (ScPayload::DisconnectAck, ScConnectionState::Disconnecting) => {
+                self.close_socket().await;
+                self.hub.stopped(now);
+            }
+            (ScPayload::AdvertisementSolicitation, _) => {
+                let mut advertisement = message.reply(ScPayload::Advertisement {
+                    hub_status: self.hub.status(),
+                    accepts_direct_connections: false,
+                    max_bvlc_length: self.config.max_bvlc_length,
+                    max_npdu_length: self.config.max_npdu_length,
+                });
+                advertisement.destination = message.originating;
+                self.send_message(&advertisement).await;
+            }
+            (ScPayload::AddressResolution, _) => {
+                if let Some(origin) = message.originating {
+                    let ack = message
+                        .reply(ScPayload::AddressResolutionAck(Vec::new()))
+                        .with_destination(origin);
+                    self.send_message(&ack).await;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Send a BVLC-SC message to the hub, if a WebSocket is open
+    async fn send_message(&mut self, message: &ScMessage) {
+        let Some((_, framing)) = self.socket.as_ref() else {
+            return;
+        };
+        let frame = message
+            .encode()
+            .and_then(|data| framing.binary(&data).map_err(DataLinkError::IoError));
+        if let Ok(frame) = frame {
+            self.write(&frame).await;
+        }
+    }
+
+    /// Write a WebSocket frame to the hub; a failure loses the connection
+    async fn write(&mut self, frame: &[u8]) {
+        let Some((stream, _)) = self.socket.as_mut() else {
+            return;
+        };
+        let written = match stream.write_all(frame).await {
+            Ok(()) => stream.flush().await,
+            Err(e) => Err(e),
+        };
+        if written.is_err() {
+            self.connection_lost();
+        }
+    }
+
+    /// Start the closing handshake and drop the WebSocket
+    async fn close_socket(&mut self) {
+        if let Some((mut stream, mut framing)) = self.socket.take() {
+            if let Some(frame) = framing.close() {
+                let _ = stream.write_all(&frame).await;
+                let _ = stream.flush().await;
+            }
+        }
+        self.hub_info = None;
+    }
+
+    fn connection_lost(&mut self) {
+        let now = Instant::now();
+        self.socket = None;
+        self.hub_info = None;
+        match self.hub.state() {
+            ScConnectionState::AwaitingAccept => self.hub.attempt_failed(now),
+            ScConnectionState::Disconnecting => self.hub.stopped(now),
+            _ => self.hub.closed(now),
+        }
+    }
+
+    fn next_message_id(&mut self) -> u16 {
+        self.message_id = self.message_id.wrapping_add(1);
+        self.message_id
+    }
+
+    /// Report the hub connection to the link
+    fn publish(&self) {
+        *self.status.lock().unwrap() = ScStatus {
+            vmac: self.config.vmac,
+            state: self.hub.state(),
+            hub_status: self.hub.status(),
+            hub_info: self.hub_info,
+        };
+    }
+}
+
+/// Read from the WebSocket to the hub, waiting for good while there is none
+async fn read_socket<S: AsyncRead + Unpin>(
+    socket: &mut Option<(S, Framing)>,
+    buffer: &mut [u8],
+) -> io::Result<usize> {
+    match socket {
+        Some((stream, _)) => stream.read(buffer).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Open a WebSocket connection to `uri` as a client, returning the framing
+/// to run it with
+async fn websocket_connect<S: AsyncRead + AsyncWrite + Unpin>(
+    stream: &mut S,
+    uri: &WebSocketUri,
+    protocol: &str,
+) -> io::Result<Framing> {
+    let mut framing = Framing::new(true);
+    let (request, key) = handshake_request(&uri.host_header(), &uri.path, protocol);
+    stream.write_all(request.as_bytes()).await?;
+    stream.flush().await?;
+    let mut buffer = [0u8; 4096];
+    let response = loop {
+        if let Some(head) = framing.take_http_head()? {
+            break head;
+        }
+        match stream.read(&mut buffer).await? {
+            0 => return Err(stream_ended()),
+            count => framing.extend(&buffer[..count]),
+        }
+    };
+    check_handshake_response(&response, &key, protocol)?;
+    Ok(framing)
+}
+
+/// BACnet/Ethernet on a raw socket registered with Tokio.
+///
+/// Frames are built and filtered as by [`EthernetDataLink`]; the socket is
+/// non-blocking and waited on by Tokio's reactor instead of `poll`. Opening
+/// it requires the CAP_NET_RAW capability or root access.
+///
+/// [`EthernetDataLink`]: crate::datalink::ethernet::EthernetDataLink
+#[cfg(target_os = "linux")]
+#[derive(Debug)]
+pub struct AsyncEthernetDataLink {
+    /// The socket
+    socket: tokio::io::unix::AsyncFd<RawSocket>,
+    /// Local MAC address of this device
+    local_mac: [u8; 6],
+}
+
+#[cfg(target_os = "linux")]
+impl AsyncEthernetDataLink {
+    /// Open a raw socket on `interface` (e.g. "eth0"), sending from
+    /// `local_mac`
+    ///
+    /// Must be called within a Tokio runtime.
+    pub fn new(interface: &str, local_mac: [u8; 6]) -> Result<Self> {
+        let socket = RawSocket::open(interface).map_err(DataLinkError::IoError)?;
+        socket
+            .set_nonblocking(true)
+            .map_err(DataLinkError::IoError)?;
+        let socket = tokio::io::unix::AsyncFd::new(socket).map_err(DataLinkError::IoError)?;
+        Ok(Self { socket, local_mac })
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[async_trait]
+impl AsyncDataLink for AsyncEthernetDataLink {
+    async fn send_frame(&self, frame: &[u8], dest: &DataLinkAddress) -> Result<()> {
+        let dest_mac = match dest {
+            DataLinkAddress::Ethernet(mac) => *mac,
+            DataLinkAddress::Broadcast => ETHERNET_BROADCAST_MAC,
+            _ => {
+                return Err(DataLinkError::AddressError(
+                    "Invalid address type for Ethernet".into(),
+                ))
+            }
+        };
+        if frame.len() > MAX_ETHERNET_NPDU_LENGTH {
+            return Err(DataLinkError::InvalidFrame);
+        }
+        let encoded = EthernetFrame::new(dest_mac, self.local_mac, frame.to_vec()).encode();
+        self.socket
+            .async_io(tokio::io::Interest::WRITABLE, |socket| {
+                socket.send_now(&encoded)
+            })
+            .await
+            .map_err(DataLinkError::IoError)
+    }
+
+    async fn receive_frame(&self) -> Result<(Vec<u8>, DataLinkAddress)> {
+        let mut buffer = [0u8; MAX_ETHERNET_FRAME_SIZE];
+        // Other traffic on the interface is skipped
+        loop {
+            let length = self
+                .socket
+                .async_io(tokio::io::Interest::READABLE, |socket| {
+                    socket.receive_now(&mut buffer)
+                })
+                .await
+                .map_err(DataLinkError::IoError)?;
+            if let Ok(frame) = EthernetFrame::decode(&buffer[..length.min(buffer.len())]) {
+                if frame.is_for(&self.local_mac) {
+                    return Ok((frame.payload, DataLinkAddress::Ethernet(frame.src_mac)));
+                }
+            }
+        }
+    }
+
+    fn link_type(&self) -> DataLinkType {
+        DataLinkType::Ethernet
+    }
+
+    fn local_address(&self) -> DataLinkAddress {
+        DataLinkAddress::Ethernet(self.local_mac)
+    }
+
+    fn broadcast_address(&self) -> DataLinkAddress {
+        DataLinkAddress::Ethernet(ETHERNET_BROADCAST_MAC)
+    }
+
+    fn max_npdu_length(&self) -> usize {
+        MAX_ETHERNET_NPDU_LENGTH
+    }
+}
+
+/// An application's own blocking [`DataLink`] run on Tokio's blocking
+/// thread pool.
+///
+/// Each send and each receive attempt runs on a pool thread with the link
+/// locked, so a send waits for at most one receive timeout of the wrapped
+/// link. Receive timeouts are retried until an NPDU arrives. The link's
+/// address and limits are read once, when it is wrapped.
+///
+/// This is a fallback for links with no async form; the links in this
+/// crate each have their own above, which neither hold a pool thread nor
+/// make sends wait.
+#[derive(Debug)]
+pub struct BlockingDataLink<D> {
+    /// The wrapped link
+    link: Arc<Mutex<D>>,
+    /// Type of the wrapped link
+    link_type: DataLinkType,
+    /// Address of the wrapped link
+    local_address: DataLinkAddress,
+    /// Broadcast address of the wrapped link
+    broadcast_address: DataLinkAddress,
+    /// NPDU limit of the wrapped link
+    max_npdu_length: usize,
+}
+
+impl<D: DataLink + Send + 'static> BlockingDataLink<D> {
+    /// Wrap `link`
+    pub fn new(link: D) -> Self {
+        Self {
+            link_type: link.link_type(),
+            local_address: link.local_address(),
+            broadcast_address: link.broadcast_address(),
+            max_npdu_length: link.max_npdu_length(),
+            link: Arc::new(Mutex::new(link)),
+        }
+    }
+
+    /// Run `f` on the wrapped link on a pool thread
+    async fn run<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut D) -> Result<T> + Send + 'static,
+    {
+        let link = self.link.clone();
+        tokio::task::spawn_blocking(move || f(&mut link.lock().unwrap()))
+            .await
+            .map_err(|e| DataLinkError::IoError(io::Error::other(e)))?
+    }
+}
+
+#[async_trait]
+impl<D: DataLink + Send + 'static> AsyncDataLink for BlockingDataLink<D> {
+    async fn send_frame(&self, frame: &[u8], dest: &DataLinkAddress) -> Result<()> {
+        let (frame, dest) = (frame.to_vec(), dest.clone());
+        self.run(move |link| link.send_frame(&frame, &dest)).await
+    }
+
+    async fn receive_frame(&self) -> Result<(Vec<u8>, DataLinkAddress)> {
+        loop {
+            match self.run(|link| link.receive_frame()).await {
+                Err(DataLinkError::IoError(e)) if is_transient(&e) => continue,
+                result => return result,
+            }
+        }
+    }
+
+    fn link_type(&self) -> DataLinkType {
+        self.link_type
+    }
+
+    fn local_address(&self) -> DataLinkAddress {
+        self.local_address.clone()
+    }
+
+    fn broadcast_address(&self) -> DataLinkAddress {
+        self.broadcast_address.clone()
+    }
+
+    fn max_npdu_length(&self) -> usize {
+        self.max_npdu_length
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::time::timeout;
+
+    #[tokio::test]
+    async fn test_async_bip_exchange() {
+        let a = AsyncBacnetIpDataLink::bind("127.0.0.1:0").await.unwrap();
+        let b = AsyncBacnetIpDataLink::bind("127.0.0.1:0").await.unwrap();
+        assert_eq!(a.link_type(), DataLinkType::BacnetIp);
+        assert_eq!(a.max_npdu_length(), BIP_MAX_NPDU_LENGTH);
+
+        let npdu = [0x01, 0x00, 0x10, 0x08];
+        a.send_frame(&npdu, &b.local_address()).await.unwrap();
+        let (received, source) = timeout(Duration::from_secs(2), b.receive_frame())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(received, npdu);
+        assert_eq!(source, a.local_address());
+
+        // BVLC control messages never reach the caller
+        let result = BvlcMessage::Result(crate::datalink::bip::BvlcResultCode::Successful);
+        let DataLinkAddress::Ip(b_addr) = b.local_address() else {
+            unreachable!()
+        };
+        a.send_message(&result, b_addr).await.unwrap();
+        assert!(timeout(Duration::from_millis(100), b.receive_frame())
+            .await
+            .is_err());
+
+        // The broadcast address sends an Original-Broadcast-NPDU
+        let mut a = a;
+        let peer = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        a.set_broadcast_address(peer.local_addr().unwrap());
+        a.send_frame(&npdu, &a.broadcast_address()).await.unwrap();
+        let mut buffer = [0u8; 1500];
+        let (len, _) = timeout(Duration::from_secs(2), peer.recv_from(&mut buffer))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(&buffer[..2], [0x81, 0x0B]);
+        assert_eq!(&buffer[4..len], npdu);
+    }
+
+    #[tokio::test]
+    async fn test_async_ptp_link() {
+        let (a_port, b_port) = tokio::io::duplex(4096);
+        let a = AsyncPtpDataLink::new(a_port, PtpConfig::default());
+        let b = AsyncPtpDataLink::new(b_port, PtpConfig::default());
+        a.connect();
+
+        let deadline = Instant::now() + Duration::from_secs(2);
+        while !(a.state() == PtpState::Connected && b.state() == PtpState::Connected) {
+            assert!(Instant::now() < deadline, "connection not established");
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+
+        a.send_frame(&[0x01, 0x00, 0xAA], &DataLinkAddress::PointToPoint)
+            .await
+            .unwrap();
+        let (npdu, source) = timeout(Duration::from_secs(2), b.receive_frame())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(npdu, [0x01, 0x00, 0xAA]);
+        assert_eq!(source, DataLinkAddress::PointToPoint);
+        assert!(a
+            .send_frame(&[0x01], &DataLinkAddress::MsTP(1))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_async_mstp_sole_master() {
+        // Nothing answers on the other end of the line
+        let (port, _line) = tokio::io::duplex(4096);
+        let config = MstpConfig {
+            station_address: 5,
+            max_master: 8,
+            ..Default::default()
+        };
+        let link = AsyncMstpDataLink::new(port, config).unwrap();
+        assert_eq!(link.local_address(), DataLinkAddress::MsTP(5));
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !link.is_sole_master() {
+            assert!(Instant::now() < deadline, "sole master not found");
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert!(AsyncMstpDataLink::new(
+            tokio::io::duplex(64).0,
+            MstpConfig {
+                station_address: MSTP_BROADCAST_ADDRESS,
+                ..Default::default()
+            }
+        )
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_async_sc_node() {
+        use crate::datalink::websocket::WebSocket;
+
+        struct PlainConnector;
+
+        #[async_trait]
+        impl AsyncScConnector for PlainConnector {
+            type Stream = tokio::net::TcpStream;
+
+            async fn connect(&mut self, uri: &WebSocketUri) -> io::Result<Self::Stream> {
+                tokio::net::TcpStream::connect((uri.host.as_str(), uri.port)).await
+            }
+        }
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let peer = Vmac([0x02, 0x00, 0x00, 0x00, 0x00, 0x09]);
+        let hub = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut socket =
+                WebSocket::accept(stream, &[HUB_SUBPROTOCOL], Duration::from_secs(5)).unwrap();
+            let request = ScMessage::decode(&socket.receive().unwrap()).unwrap();
+            let ScPayload::ConnectRequest(node) = request.payload else {
+                panic!("expected Connect-Request");
+            };
+            let npdu = request.reply(ScPayload::EncapsulatedNpdu(vec![0x01, 0x00, 0x10, 0x08]));
+            let replies = [
+                request.reply(ScPayload::ConnectAccept(ConnectInfo {
+                    vmac: Vmac::random(),
+                    ..node
+                })),
+                ScMessage::new(40, ScPayload::HeartbeatRequest),
+                npdu.with_originating(peer),
+            ];
+            for reply in replies {
+                socket.send_binary(&reply.encode().unwrap()).unwrap();
+            }
+
+            let heartbeat_ack = ScMessage::decode(&socket.receive().unwrap()).unwrap();
+            assert_eq!(heartbeat_ack, ScMessage::new(40, ScPayload::HeartbeatAck));
+            let unicast = ScMessage::decode(&socket.receive().unwrap()).unwrap();
+            assert_eq!(unicast.destination, Some(peer));
+            assert_eq!(
+                unicast.payload,
+                ScPayload::EncapsulatedNpdu(vec![0x01, 0x00])
+            );
+
+            let disconnect = ScMessage::decode(&socket.receive().unwrap()).unwrap();
+            assert_eq!(disconnect.payload, ScPayload::DisconnectRequest);
+            let ack = disconnect.reply(ScPayload::DisconnectAck);
+            socket.send_binary(&ack.encode().unwrap()).unwrap();
+        });
+
+        let config = ScNodeConfig::new(&format!("ws://{}/", address));
+        let vmac = config.vmac;
+        let link = AsyncScDataLink::new(config, PlainConnector);
+        assert_eq!(link.local_address(), DataLinkAddress::SecureConnect(vmac.0));
+
+        let (npdu, source) = timeout(Duration::from_secs(5), link.receive_frame())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(npdu, [0x01, 0x00, 0x10, 0x08]);
+        assert_eq!(source, DataLinkAddress::SecureConnect(peer.0));
+        assert_eq!(link.state(), ScConnectionState::Connected);
+        assert_eq!(link.hub_status(), HubConnectionStatus::ConnectedToPrimary);
+        assert!(link.hub_info().is_some());
+
+        link.send_frame(&[0x01, 0x00], &source).await.unwrap();
+        link.disconnect();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while link.state() != ScConnectionState::Idle {
+            assert!(Instant::now() < deadline, "no Disconnect-ACK");
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert!(link
+            .send_frame(&[0x01, 0x20], &DataLinkAddress::Broadcast)
+            .await
+            .is_err());
+        hub.join().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_blocking_data_link() {
+        use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
+
+        /// An application's link: one end of a pair of channels
+        struct Loopback {
+            address: u8,
+            sender: Sender<(Vec<u8>, u8)>,
+            receiver: Mutex<Receiver<(Vec<u8>, u8)>>,
+        }
+
+        impl DataLink for Loopback {
+            fn send_frame(&mut self, frame: &[u8], _dest: &DataLinkAddress) -> Result<()> {
+                self.sender
+                    .send((frame.to_vec(), self.address))
+                    .map_err(|_| DataLinkError::InvalidFrame)
+            }
+
+            fn receive_frame(&mut self) -> Result<(Vec<u8>, DataLinkAddress)> {
+                let receiver = self.receiver.lock().unwrap();
+                match receiver.recv_timeout(Duration::from_millis(100)) {
+                    Ok((frame, source)) => Ok((frame, DataLinkAddress::MsTP(source))),
+                    Err(RecvTimeoutError::Timeout) => {
+                        Err(DataLinkError::IoError(ErrorKind::TimedOut.into()))
+                    }
+                    Err(RecvTimeoutError::Disconnected) => Err(DataLinkError::InvalidFrame),
+                }
+            }
+
+            fn link_type(&self) -> DataLinkType {
+                DataLinkType::MsTP
+            }
+
+            fn local_address(&self) -> DataLinkAddress {
+                DataLinkAddress::MsTP(self.address)
+            }
+
+            fn max_npdu_length(&self) -> usize {
+                MSTP_MAX_EXTENDED_DATA_LENGTH
+            }
+        }
+
+        let (to_b, from_a) = channel();
+        let (to_a, from_b) = channel();
+        let a = BlockingDataLink::new(Loopback {
+            address: 1,
+            sender: to_b,
+            receiver: Mutex::new(from_b),
+        });
+        let b = BlockingDataLink::new(Loopback {
+            address: 2,
+            sender: to_a,
+            receiver: Mutex::new(from_a),
+        });
+        assert_eq!(b.link_type(), DataLinkType::MsTP);
+        let b_address = b.local_address();
+
+        // The receive waits through several timeouts of the wrapped link
+        let receive = tokio::spawn(async move { b.receive_frame().await });
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        a.send_frame(&[0x01, 0x00], &b_address).await.unwrap();
+        let (npdu, source) = timeout(Duration::from_secs(2), receive)
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert_eq!(npdu, [0x01, 0x00]);
+        assert_eq!(source, a.local_address());
+    }
+}
@@ -0,0 +1,714 @@
+//! BACnet Broadcast Management Device (ASHRAE 135 Annex J.4 and J.5).
+//!
+//! IP routers do not pass broadcasts, so a BACnet/IP network spanning several
+//! subnets needs a BBMD on each of them. BBMDs forward every local broadcast
+//! to each other and to the foreign devices registered with them, which lets
+//! Who-Is, I-Am and the other broadcast services reach the whole network.
+//!
+//! # Broadcast Distribution Table
+//!
+//! Each BDT entry names a BBMD, this one included, and the broadcast
+//! distribution mask used to reach its subnet:
+//!
+//! - **Two-hop** (mask `255.255.255.255`): Forwarded-NPDUs are unicast to the
+//!   peer BBMD, which broadcasts them on its own subnet.
+//! - **One-hop** (any other mask): Forwarded-NPDUs are sent straight to the
+//!   peer subnet's directed broadcast address, `address | !mask`. This only
+//!   works when the routers in between pass directed broadcasts.
+//!
+//! # Foreign Device Table
+//!
+//! Foreign devices register with a time-to-live. The BBMD keeps each
+//! registration for the TTL plus a fixed [grace period](FDT_GRACE_PERIOD) so
+//! that a re-registration delayed in transit does not drop the device.
+//!
+//! # NAT traversal
+//!
+//! A BBMD behind a NAT router (Annex J.7.8) is known to its peers and
+//! foreign devices by the router's public address, not its own. Configured
+//! with that [global address](Bbmd::set_global_address), the BBMD:
+//!
+//! - takes the BDT entry naming its global address as its own entry;
+//! - puts its global address in place of private ones as the originating
+//!   device of the Forwarded-NPDUs it sends off the subnet, since a private
+//!   address means nothing beyond the NAT router;
+//! - drops its own broadcasts when they come back through the NAT router.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use bacnet_rs::datalink::bbmd::Bbmd;
+//! use bacnet_rs::datalink::bip::BacnetIpDataLink;
+//!
+//! # fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut bbmd = Bbmd::new("192.168.1.10:47808".parse()?, [255, 255, 255, 0]);
+//! bbmd.add_bdt_entry("192.168.1.10:47808".parse()?, [255, 255, 255, 255]);
+//! bbmd.add_bdt_entry("192.168.2.10:47808".parse()?, [255, 255, 255, 255]);
+//!
+//! let mut data_link = BacnetIpDataLink::new("192.168.1.10:47808")?;
+//! data_link.set_bbmd(Some(bbmd));
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+    net::{Ipv4Addr, SocketAddr, SocketAddrV4},
+    time::{Duration, Instant},
+};
+
+use crate::datalink::bip::{BdtEntry, BvlcMessage, BvlcResultCode, FdtEntry, FdtRecord};
+use crate::object::{
+    BacnetIpMode, BdtTableEntry, FdtTableEntry, HostNPort, IpPortSettings, NetworkPort,
+};
+
+/// Time a BBMD keeps a foreign device registration beyond its TTL (Annex J.5.2.1).
+pub const FDT_GRACE_PERIOD: Duration = Duration::from_secs(30);
+
+/// What a BBMD does with a received BVLC message.
+///
+/// The BBMD only decides; the datalink sends the messages and passes the NPDU
+/// to the network layer.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BbmdOutcome {
+    /// Reply to send back to the sender.
+    pub reply: Option<BvlcMessage>,
+
+    /// Messages to forward, with their destinations.
+    pub forwards: Vec<(BvlcMessage, SocketAddr)>,
+
+    /// NPDU for the network layer, with the address of the device that
+    /// originally sent it.
+    pub npdu: Option<(Vec<u8>, SocketAddr)>,
+}
+
+/// BBMD state: the Broadcast Distribution Table, the Foreign Device Table and
+/// the forwarding rules between them.
+///
+/// The BBMD is independent of any socket. [`BacnetIpDataLink`] hands it every
+/// received message through [`process`](Self::process) and carries out the
+/// returned [`BbmdOutcome`].
+///
+/// [`BacnetIpDataLink`]: crate::datalink::bip::BacnetIpDataLink
+#[derive(Debug, Clone)]
+pub struct Bbmd {
+    /// B/IP address of this BBMD.
+    address: SocketAddr,
+
+    /// Directed broadcast address of the local subnet.
+    broadcast_address: SocketAddr,
+
+    /// Broadcast Distribution Table, normally including this BBMD.
+    bdt: Vec<BdtEntry>,
+
+    /// Foreign Device Table.
+    fdt: Vec<FdtEntry>,
+
+    /// Whether Register-Foreign-Device requests are accepted.
+    accept_fd_registrations: bool,
+
+    /// Public B/IP address of the NAT router in front of this BBMD.
+    global_address: Option<SocketAddr>,
+}
+
+impl Bbmd {
+    /// Create a BBMD with an empty BDT that accepts foreign devices.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - The BBMD's own B/IP address, which must be IPv4
+    /// * `subnet_mask` - Mask of the local subnet, used for local broadcasts
+    pub fn new(address: SocketAddr, subnet_mask: [u8; 4]) -> Self {
+        Self {
+            address,
+            broadcast_address: directed_broadcast(address, subnet_mask),
+            bdt: Vec::new(),
+            fdt: Vec::new(),
+            accept_fd_registrations: true,
+            global_address: None,
+        }
+    }
+
+    /// Create a BBMD from the settings of a BACnet/IP Network Port.
+    ///
+    /// Returns `None` unless the port is in BBMD mode.
+    pub fn from_settings(settings: &IpPortSettings) -> Option<Self> {
+        if settings.mode != BacnetIpMode::Bbmd {
+            return None;
+        }
+        let address = SocketAddrV4::new(settings.ip_address.into(), settings.udp_port).into();
+        let mut bbmd = Self::new(address, settings.subnet_mask);
+        bbmd.apply_settings(settings);
+        Some(bbmd)
+    }
+
+    /// Take over the BDT, registration policy and NAT traversal settings of
+    /// a Network Port.
+    ///
+    /// Registered foreign devices are kept.
+    pub fn apply_settings(&mut self, settings: &IpPortSettings) {
+        self.accept_fd_registrations = settings.accept_fd_registrations;
+        self.global_address = settings
+            .global_address
+            .filter(|_| settings.nat_traversal)
+            .map(host_n_port_address);
+        self.bdt = settings
+            .broadcast_distribution_table
+            .iter()
+            .map(|entry| BdtEntry {
+                address: host_n_port_address(entry.bbmd_address),
+                mask: entry.broadcast_mask,
+            })
+            .collect();
+    }
+
+    /// Copy the Foreign Device Table into a Network Port's
+    /// BBMD_Foreign_Device_Table.
+    ///
+    /// IPv6 registrations cannot be represented and are left out.
+    pub fn update_network_port(&self, port: &mut NetworkPort, now: Instant) {
+        port.foreign_device_table = self
+            .fdt_records(now)
+            .into_iter()
+            .filter_map(|record| match record.address {
+                SocketAddr::V4(address) => Some(FdtTableEntry {
+                    address: HostNPort::new(address.ip().octets(), address.port()),
+                    time_to_live: record.ttl,
+                    remaining_time_to_live: record.time_remaining,
+                }),
+                SocketAddr::V6(_) => None,
+            })
+            .collect();
+    }
+
+    /// The BBMD's own B/IP address.
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// The directed broadcast address of the local subnet.
+    pub fn broadcast_address(&self) -> SocketAddr {
+        self.broadcast_address
+    }
+
+    /// The public address this BBMD is reached at through a NAT router.
+    pub fn global_address(&self) -> Option<SocketAddr> {
+        self.global_address
+    }
+
+    /// Set the public address of the NAT router in front of this BBMD, or
+    /// `None` when it is reached directly.
+    pub fn set_global_address(&mut self, address: Option<SocketAddr>) {
+        self.global_address = address;
+    }
+
+    /// The Broadcast Distribution Table.
+    pub fn bdt(&self) -> &[BdtEntry] {
+        &self.bdt
+    }
+
+    /// Replace the Broadcast Distribution Table.
+    pub fn set_bdt(&mut self, entries: Vec<BdtEntry>) {
+        self.bdt = entries;
+    }
+
+    /// Add a BBMD to the BDT, replacing any entry with the same address.
+    pub fn add_bdt_entry(&mut self, address: SocketAddr, mask: [u8; 4]) {
+        self.bdt.retain(|entry| entry.address != address);
+        self.bdt.push(BdtEntry { address, mask });
+    }
+
+    /// Remove a BBMD from the BDT, returning whether it was present.
+    pub fn remove_bdt_entry(&mut self, address: SocketAddr) -> bool {
+        let count = self.bdt.len();
+        self.bdt.retain(|entry| entry.address != address);
+        self.bdt.len() < count
+    }
+
+    /// The BDT as BBMD_Broadcast_Distribution_Table entries, for writing
+    /// back to a Network Port after a Write-Broadcast-Distribution-Table.
+    ///
+    /// IPv6 entries cannot be represented and are left out.
+    pub fn bdt_table(&self) -> Vec<BdtTableEntry> {
+        self.bdt
+            .iter()
+            .filter_map(|entry| match entry.address {
+                SocketAddr::V4(address) => Some(BdtTableEntry {
+                    bbmd_address: HostNPort::new(address.ip().octets(), address.port()),
+                    broadcast_mask: entry.mask,
+                }),
+                SocketAddr::V6(_) => None,
+            })
+            .collect()
+    }
+
+    /// The BDT entries this BBMD only reaches in two hops (all-ones mask).
+    pub fn two_hop_peers(&self) -> impl Iterator<Item = &BdtEntry> {
+        self.peers().filter(|entry| entry.mask == [255; 4])
+    }
+
+    /// The Foreign Device Table.
+    pub fn fdt(&self) -> &[FdtEntry] {
+        &self.fdt
+    }
+
+    /// The Foreign Device Table as reported in Read-Foreign-Device-Table-Ack,
+    /// with the grace period included in the remaining time.
+    pub fn fdt_records(&self, now: Instant) -> Vec<FdtRecord> {
+        self.fdt.iter().map(|entry| entry.record(now)).collect()
+    }
+
+    /// Whether foreign device registrations are accepted.
+    pub fn accepts_fd_registrations(&self) -> bool {
+        self.accept_fd_registrations
+    }
+
+    /// Accept or refuse foreign device registrations.
+    ///
+    /// Refusing does not drop devices already registered.
+    pub fn set_accept_fd_registrations(&mut self, accept: bool) {
+        self.accept_fd_registrations = accept;
+    }
+
+    /// Register or re-register a foreign device.
+    ///
+    /// A TTL of zero is accepted and expires after the grace period.
+    pub fn register_foreign_device(
+        &mut self,
+        address: SocketAddr,
+        ttl: u16,
+        now: Instant,
+    ) -> BvlcResultCode {
+        if !self.accept_fd_registrations {
+            return BvlcResultCode::RegisterForeignDeviceNak;
+        }
+        self.fdt.retain(|entry| entry.address != address);
+        self.fdt.push(FdtEntry {
+            address,
+            ttl,
+            registration_time: now,
+        });
+        BvlcResultCode::Successful
+    }
+
+    /// Remove a foreign device registration.
+    pub fn delete_foreign_device(&mut self, address: SocketAddr) -> BvlcResultCode {
+        let count = self.fdt.len();
+        self.fdt.retain(|entry| entry.address != address);
+        if self.fdt.len() < count {
+            BvlcResultCode::Successful
+        } else {
+            BvlcResultCode::DeleteForeignDeviceTableEntryNak
+        }
+    }
+
+    /// Drop registrations whose TTL and grace period have run out.
+    pub fn expire(&mut self, now: Instant) {
+        self.fdt.retain(|entry| !entry.is_expired(now));
+    }
+
+    /// Whether `address` is a registered foreign device.
+    pub fn is_foreign_device(&self, address: SocketAddr) -> bool {
+        self.fdt.iter().any(|entry| entry.address == address)
+    }
+
+    /// The Forwarded-NPDUs that distribute a broadcast originated by
+    /// `source`, a device on the local subnet or this BBMD itself.
+    ///
+    /// Behind a NAT router the Forwarded-NPDUs name the global address as
+    /// their source.
+    pub fn originate(&self, npdu: &[u8], source: SocketAddr) -> Vec<(BvlcMessage, SocketAddr)> {
+        self.distribute(npdu, self.global_address.unwrap_or(source), true, false)
+    }
+
+    /// Handle a received BVLC message.
+    ///
+    /// Messages sent by this BBMD itself, such as its own local broadcasts,
+    /// are ignored.
+    pub fn process(
+        &mut self,
+        message: BvlcMessage,
+        source: SocketAddr,
+        now: Instant,
+    ) -> BbmdOutcome {
+        let mut outcome = BbmdOutcome::default();
+        if self.is_own_address(source) {
+            return outcome;
+        }
+        self.expire(now);
+
+        let result = match message {
+            BvlcMessage::OriginalUnicastNpdu(npdu) => {
+                outcome.npdu = Some((npdu, source));
+                return outcome;
+            }
+            BvlcMessage::OriginalBroadcastNpdu(npdu) => {
+                outcome.forwards = self.originate(&npdu, source);
+                outcome.npdu = Some((npdu, source));
+                return outcome;
+            }
+            BvlcMessage::ForwardedNpdu {
+                source: original,
+                npdu,
+            } => {
+                // Our own broadcast, passed back by a peer
+                if self.global_address == Some(original) {
+                    return outcome;
+                }
+                // Only forward what peers send; local echoes of our own
+                // two-hop rebroadcasts come from our address and were
+                // dropped above.
+                if self.peers().any(|entry| entry.address == source) {
+                    let local = self.own_entry().is_some_and(|entry| entry.mask == [255; 4]);
+                    outcome.forwards = self.distribute(&npdu, original, false, local);
+                }
+                outcome.npdu = Some((npdu, original));
+                return outcome;
+            }
+            BvlcMessage::DistributeBroadcastToNetwork(npdu) => {
+                if !self.is_foreign_device(source) {
+                    BvlcResultCode::DistributeBroadcastToNetworkNak
+                } else {
+                    outcome.forwards = self.distribute(&npdu, source, true, true);
+                    outcome.npdu = Some((npdu, source));
+                    return outcome;
+                }
+            }
+            BvlcMessage::RegisterForeignDevice { ttl } => {
+                self.register_foreign_device(source, ttl, now)
+            }
+            BvlcMessage::ReadBroadcastDistributionTable => {
+                outcome.reply = Some(BvlcMessage::ReadBroadcastDistributionTableAck(
+                    self.bdt.clone(),
+                ));
+                return outcome;
+            }
+            BvlcMessage::WriteBroadcastDistributionTable(entries) => {
+                self.bdt = entries;
+                BvlcResultCode::Successful
+            }
+            BvlcMessage::ReadForeignDeviceTable => {
+                outcome.reply = Some(BvlcMessage::ReadForeignDeviceTableAck(
+                    self.fdt_records(now),
+                ));
+                return outcome;
+            }
+            BvlcMessage::DeleteForeignDeviceTableEntry(address) => {
+                self.delete_foreign_device(address)
+            }
+            BvlcMessage::Result(_)
+            | BvlcMessage::ReadBroadcastDistributionTableAck(_)
+            | BvlcMessage::ReadForeignDeviceTableAck(_)
+            | BvlcMessage::SecureBvll(_) => return outcome,
+        };
+
+        outcome.reply = Some(BvlcMessage::Result(result));
+        outcome
+    }
+
+    /// Whether `address` is this BBMD's, locally or through the NAT router.
+    fn is_own_address(&self, address: SocketAddr) -> bool {
+        address == self.address || self.global_address == Some(address)
+    }
+
+    /// BDT entries other than this BBMD's own.
+    fn peers(&self) -> impl Iterator<Item = &BdtEntry> {
+        self.bdt
+            .iter()
+            .filter(|entry| !self.is_own_address(entry.address))
+    }
+
+    /// This BBMD's own BDT entry.
+    fn own_entry(&self) -> Option<&BdtEntry> {
+        self.bdt
+            .iter()
+            .find(|entry| self.is_own_address(entry.address))
+    }
+
+    /// Forwarded-NPDUs from `source` to the registered foreign devices other
+    /// than `source` and, if asked, to the peer BBMDs and the local subnet.
+    fn distribute(
+        &self,
+        npdu: &[u8],
+        source: SocketAddr,
+        peers: bool,
+        local: bool,
+    ) -> Vec<(BvlcMessage, SocketAddr)> {
+        let message = BvlcMessage::ForwardedNpdu {
+            source,
+            npdu: npdu.to_vec(),
+        };
+        let mut destinations = Vec::new();
+        if local {
+            destinations.push(self.broadcast_address);
+        }
+        if peers {
+            destinations.extend(self.peers().map(forward_address));
+        }
+        destinations.extend(
+            self.fdt
+                .iter()
+                .map(|entry| entry.address)
+                .filter(|address| *address != source),
+        );
+        destinations
+            .into_iter()
+            .map(|destination| (message.clone(), destination))
+            .collect()
+    }
+}
+
+/// Where Forwarded-NPDUs for a BDT entry are sent: the peer itself for
+/// two-hop entries, its subnet's directed broadcast address for one-hop ones.
+pub fn forward_address(entry: &BdtEntry) -> SocketAddr {
+    directed_broadcast(entry.address, entry.mask)
+}
+
+fn directed_broadcast(address: SocketAddr, mask: [u8; 4]) -> SocketAddr {
+    match address {
+        SocketAddr::V4(address) => {
+            let ip = u32::from(*address.ip()) | !u32::from_be_bytes(mask);
+            SocketAddrV4::new(Ipv4Addr::from(ip), address.port()).into()
+        }
+        SocketAddr::V6(_) => address,
+    }
+}
+
+fn host_n_port_address(host: HostNPort) -> SocketAddr {
+    SocketAddrV4::new(host.address.into(), host.port).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addr(s: &str) -> SocketAddr {
+        s.parse().unwrap()
+    }
+
+    fn destinations(forwards: &[(BvlcMessage, SocketAddr)]) -> Vec<SocketAddr> {
+        forwards
+            .iter()
+            .map(|(_, destination)| *destination)
+            .collect()
+    }
+
+    #[test]
+    fn test_forwarding_masks() {
+        let mut bbmd = Bbmd::new(addr("192.168.1.10:47808"), [255, 255, 255, 0]);
+        bbmd.add_bdt_entry(addr("192.168.1.10:47808"), [255; 4]);
+        bbmd.add_bdt_entry(addr("192.168.2.10:47808"), [255; 4]);
+        bbmd.add_bdt_entry(addr("10.0.4.1:47808"), [255, 255, 252, 0]);
+        let now = Instant::now();
+        bbmd.register_foreign_device(addr("172.16.0.5:47808"), 60, now);
+
+        // A local broadcast goes to both peers and the foreign device
+        let local = addr("192.168.1.20:47808");
+        let outcome = bbmd.process(BvlcMessage::OriginalBroadcastNpdu(vec![1, 0]), local, now);
+        assert_eq!(outcome.npdu, Some((vec![1, 0], local)));
+        assert_eq!(
+            destinations(&outcome.forwards),
+            [
+                addr("192.168.2.10:47808"),
+                addr("10.0.7.255:47808"),
+                addr("172.16.0.5:47808")
+            ]
+        );
+        assert_eq!(
+            outcome.forwards[0].0,
+            BvlcMessage::ForwardedNpdu {
+                source: local,
+                npdu: vec![1, 0]
+            }
+        );
+
+        // Our own entry is two-hop, so peer traffic is rebroadcast locally
+        let forwarded = BvlcMessage::ForwardedNpdu {
+            source: addr("192.168.2.30:47808"),
+            npdu: vec![1, 0],
+        };
+        let outcome = bbmd.process(forwarded.clone(), addr("192.168.2.10:47808"), now);
+        assert_eq!(
+            destinations(&outcome.forwards),
+            [addr("192.168.1.255:47808"), addr("172.16.0.5:47808")]
+        );
+        assert_eq!(outcome.npdu.unwrap().1, addr("192.168.2.30:47808"));
+
+        // The local echo of that rebroadcast is ignored
+        let outcome = bbmd.process(forwarded, bbmd.address(), now);
+        assert_eq!(outcome, BbmdOutcome::default());
+    }
+
+    #[test]
+    fn test_foreign_device_table() {
+        let mut bbmd = Bbmd::new(addr("192.168.1.10:47808"), [255, 255, 255, 0]);
+        bbmd.add_bdt_entry(bbmd.address(), [255; 4]);
+        let device = addr("172.16.0.5:47808");
+        let start = Instant::now();
+
+        // Broadcasts from unregistered devices are refused
+        let outcome = bbmd.process(
+            BvlcMessage::DistributeBroadcastToNetwork(vec![1, 0]),
+            device,
+            start,
+        );
+        assert_eq!(
+            outcome.reply,
+            Some(BvlcMessage::Result(
+                BvlcResultCode::DistributeBroadcastToNetworkNak
+            ))
+        );
+
+        let outcome = bbmd.process(
+            BvlcMessage::RegisterForeignDevice { ttl: 60 },
+            device,
+            start,
+        );
+        assert_eq!(
+            outcome.reply,
+            Some(BvlcMessage::Result(BvlcResultCode::Successful))
+        );
+        assert_eq!(
+            bbmd.fdt_records(start),
+            [FdtRecord {
+                address: device,
+                ttl: 60,
+                time_remaining: 90,
+            }]
+        );
+        let outcome = bbmd.process(
+            BvlcMessage::DistributeBroadcastToNetwork(vec![1, 0]),
+            device,
+            start,
+        );
+        assert_eq!(
+            destinations(&outcome.forwards),
+            [addr("192.168.1.255:47808")]
+        );
+
+        // Registrations survive their TTL for the grace period only
+        bbmd.expire(start + Duration::from_secs(80));
+        assert_eq!(bbmd.fdt().len(), 1);
+        bbmd.expire(start + Duration::from_secs(90));
+        assert!(bbmd.fdt().is_empty());
+
+        bbmd.set_accept_fd_registrations(false);
+        assert_eq!(
+            bbmd.register_foreign_device(device, 60, start),
+            BvlcResultCode::RegisterForeignDeviceNak
+        );
+    }
+
+    #[test]
+    fn test_behind_nat() {
+        let mut bbmd = Bbmd::new(addr("192.168.1.10:47808"), [255, 255, 255, 0]);
+        let global = addr("203.0.113.7:47808");
+        bbmd.set_global_address(Some(global));
+        // Peers know this BBMD by its global address
+        bbmd.add_bdt_entry(global, [255; 4]);
+        bbmd.add_bdt_entry(addr("198.51.100.20:47808"), [255; 4]);
+        let now = Instant::now();
+        bbmd.register_foreign_device(addr("198.51.100.99:50000"), 60, now);
+
+        // A local broadcast leaves the subnet under the global address
+        let local = addr("192.168.1.20:47808");
+        let outcome = bbmd.process(BvlcMessage::OriginalBroadcastNpdu(vec![1, 0]), local, now);
+        assert_eq!(outcome.npdu, Some((vec![1, 0], local)));
+        assert_eq!(
+            destinations(&outcome.forwards),
+            [addr("198.51.100.20:47808"), addr("198.51.100.99:50000")]
+        );
+        assert!(outcome.forwards.iter().all(|(message, _)| matches!(
+            message,
+            BvlcMessage::ForwardedNpdu { source, .. } if *source == global
+        )));
+
+        // Peer traffic is rebroadcast locally with its real source
+        let remote = addr("10.1.1.5:47808");
+        let forwarded = BvlcMessage::ForwardedNpdu {
+            source: remote,
+            npdu: vec![1, 0],
+        };
+        let outcome = bbmd.process(forwarded, addr("198.51.100.20:47808"), now);
+        assert_eq!(
+            outcome.forwards[0],
+            (
+                BvlcMessage::ForwardedNpdu {
+                    source: remote,
+                    npdu: vec![1, 0]
+                },
+                addr("192.168.1.255:47808")
+            )
+        );
+
+        // Our own broadcast coming back is dropped, as is hairpinned traffic
+        let echo = BvlcMessage::ForwardedNpdu {
+            source: global,
+            npdu: vec![1, 0],
+        };
+        let outcome = bbmd.process(echo.clone(), addr("198.51.100.20:47808"), now);
+        assert_eq!(outcome, BbmdOutcome::default());
+        assert_eq!(bbmd.process(echo, global, now), BbmdOutcome::default());
+
+        // The global address comes from the Network Port when NAT traversal
+        // is on
+        let mut settings = IpPortSettings {
+            ip_address: [192, 168, 1, 10],
+            mode: BacnetIpMode::Bbmd,
+            global_address: Some(HostNPort::new([203, 0, 113, 7], 0xBAC0)),
+            ..Default::default()
+        };
+        assert_eq!(
+            Bbmd::from_settings(&settings).unwrap().global_address(),
+            None
+        );
+        settings.nat_traversal = true;
+        assert_eq!(
+            Bbmd::from_settings(&settings).unwrap().global_address(),
+            Some(global)
+        );
+    }
+
+    #[test]
+    fn test_network_port_configuration() {
+        let mut settings = IpPortSettings {
+            ip_address: [192, 168, 1, 10],
+            ..Default::default()
+        };
+        assert!(Bbmd::from_settings(&settings).is_none());
+
+        settings.mode = BacnetIpMode::Bbmd;
+        settings.accept_fd_registrations = true;
+        settings.broadcast_distribution_table = vec![BdtTableEntry {
+            bbmd_address: HostNPort::new([192, 168, 2, 10], 0xBAC0),
+            broadcast_mask: [255; 4],
+        }];
+        let mut bbmd = Bbmd::from_settings(&settings).unwrap();
+        assert_eq!(bbmd.address(), addr("192.168.1.10:47808"));
+        assert_eq!(bbmd.broadcast_address(), addr("192.168.1.255:47808"));
+        assert_eq!(
+            bbmd.two_hop_peers().next().unwrap().address,
+            addr("192.168.2.10:47808")
+        );
+        assert_eq!(bbmd.bdt_table(), settings.broadcast_distribution_table);
+
+        let now = Instant::now();
+        bbmd.register_foreign_device(addr("172.16.0.5:47809"), 120, now);
+        let mut port = NetworkPort::new(
+            1,
+            "BACnet/IP Port".to_string(),
+            crate::object::NetworkPortConfig {
+                network_number: 1,
+                settings: crate::object::DatalinkSettings::Ipv4(settings),
+            },
+        );
+        bbmd.update_network_port(&mut port, now);
+        assert_eq!(
+            port.foreign_device_table,
+            [FdtTableEntry {
+                address: HostNPort::new([172, 16, 0, 5], 47809),
+                time_to_live: 120,
+                remaining_time_to_live: 150,
+            }]
+        );
+    }
+}
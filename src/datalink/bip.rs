@@ -20,16 +20,22 @@
 //!
 //! The BVLC layer provides these message types:
 //!
+//! - **BVLC-Result**: Success or NAK reply to a management request
+//! - **Write-Broadcast-Distribution-Table**: Replace a BBMD's peer list
+//! - **Read-Broadcast-Distribution-Table**: Query BBMD's peer list
 //! - **Original-Unicast-NPDU**: Direct unicast message to a specific device
 //! - **Original-Broadcast-NPDU**: Broadcast message originating from this device
 //! - **Forwarded-NPDU**: Message forwarded by a BBMD
 //! - **Register-Foreign-Device**: Request to join a remote network
-//! - **Read-Broadcast-Distribution-Table**: Query BBMD's peer list
 //! - **Read-Foreign-Device-Table**: Query registered foreign devices
 //! - **Delete-Foreign-Device-Table-Entry**: Remove a foreign device
-//! - **Distribute-Broadcast-To-Network**: BBMD-to-BBMD broadcast distribution
+//! - **Distribute-Broadcast-To-Network**: Foreign device broadcast through a BBMD
 //! - **Secure-BVLL**: Encrypted BACnet communication (BACnet/SC)
 //!
+//! [`BvlcMessage`] encodes and decodes every function. [`BacnetIpDataLink`]
+//! answers the management functions itself, so [`DataLink::receive_frame`]
+//! only ever hands NPDUs to the layers above.
+//!
 //! # Examples
 //!
 //! ## Basic BACnet/IP Communication
@@ -67,11 +73,32 @@
 //! # Ok(())
 //! # }
 //! ```
+//!
+//! ## BBMD Management
+//!
+//! ```no_run
+//! use bacnet_rs::datalink::bip::BacnetIpDataLink;
+//!
+//! # fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut data_link = BacnetIpDataLink::new("0.0.0.0:47808")?;
+//!
+//! // Inspect a BBMD's tables
+//! let bbmd_addr = "192.168.1.10:47808".parse()?;
+//! for entry in data_link.read_broadcast_distribution_table(bbmd_addr)? {
+//!     println!("Peer {} mask {:?}", entry.address, entry.mask);
+//! }
+//! for record in data_link.read_foreign_device_table(bbmd_addr)? {
+//!     println!("Foreign device {} ({}s left)", record.address, record.time_remaining);
+//! }
+//! # Ok(())
+//! # }
+//! ```
 
 #[cfg(feature = "std")]
 use std::{
+    collections::VecDeque,
     io::ErrorKind,
-    net::{SocketAddr, ToSocketAddrs, UdpSocket},
+    net::{Ipv4Addr, SocketAddr, SocketAddrV4, ToSocketAddrs, UdpSocket},
     time::{Duration, Instant},
 };
 
@@ -80,6 +107,13 @@ use alloc::{string::String, vec::Vec};
 
 use crate::datalink::{DataLink, DataLinkAddress, DataLinkError, DataLinkType, Result};
 
+#[cfg(feature = "std")]
+use crate::{
+    datalink::bbmd::{Bbmd, FDT_GRACE_PERIOD},
+    datalink::foreign_device::ForeignDevice,
+    object::{BacnetIpMode, IpPortSettings},
+};
+
 /// BACnet/IP well-known UDP port number.
 ///
 /// This is the standard port (0xBAC0 = 47808) defined by ASHRAE 135 for BACnet/IP
@@ -96,6 +130,17 @@ use crate::datalink::{DataLink, DataLinkAddress, DataLinkError, DataLinkType, Re
 /// ```
 pub const BACNET_IP_PORT: u16 = 47808;
 
+/// Largest NPDU carried in one BVLC message (Annex J).
+///
+/// The limit keeps a Forwarded-NPDU, with its 10-octet header, inside an
+/// unfragmented Ethernet frame.
+pub const BIP_MAX_NPDU_LENGTH: usize = 1497;
+
+/// How long [`BacnetIpDataLink`] waits for a BBMD to answer a management
+/// request before giving up.
+#[cfg(feature = "std")]
+pub const BVLC_REQUEST_TIMEOUT: Duration = Duration::from_secs(3);
+
 /// BVLC (BACnet Virtual Link Control) message types.
 ///
 /// These message types define the various operations supported by the BVLC protocol,
@@ -125,6 +170,16 @@ pub const BACNET_IP_PORT: u16 = 47808;
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
 pub enum BvlcFunction {
+    /// BVLC-Result (0x00).
+    ///
+    /// Reply to a management request carrying a [`BvlcResultCode`].
+    Result = 0x00,
+
+    /// Write-Broadcast-Distribution-Table (0x01).
+    ///
+    /// Request to replace a BBMD's Broadcast Distribution Table.
+    WriteBroadcastDistributionTable = 0x01,
+
     /// Original-Unicast-NPDU (0x0A).
     ///
     /// Encapsulates an NPDU for unicast delivery to a specific BACnet/IP device.
@@ -179,19 +234,100 @@ pub enum BvlcFunction {
 
     /// Distribute-Broadcast-To-Network (0x09).
     ///
-    /// Used between BBMDs to distribute broadcast NPDUs to all devices on their
-    /// respective networks.
+    /// Sent by a registered foreign device to have its BBMD broadcast an NPDU
+    /// on the BBMD's network and forward it to the other networks in the BDT.
     DistributeBroadcastToNetwork = 0x09,
 
-    /// Forwarded-NPDU-From-Device (0x0C).
-    ///
-    /// Alternative forwarding mechanism that preserves the original device address.
-    ForwardedNpduFromDevice = 0x0C,
-
-    /// Secure-BVLL (0x0D).
+    /// Secure-BVLL (0x0C).
     ///
     /// Used for BACnet Secure Connect (BACnet/SC) encrypted communication.
-    SecureBvll = 0x0D,
+    SecureBvll = 0x0C,
+}
+
+impl TryFrom<u8> for BvlcFunction {
+    type Error = DataLinkError;
+
+    fn try_from(value: u8) -> Result<Self> {
+        match value {
+            0x00 => Ok(BvlcFunction::Result),
+            0x01 => Ok(BvlcFunction::WriteBroadcastDistributionTable),
+            0x02 => Ok(BvlcFunction::ReadBroadcastDistributionTable),
+            0x03 => Ok(BvlcFunction::ReadBroadcastDistributionTableAck),
+            0x04 => Ok(BvlcFunction::ForwardedNpdu),
+            0x05 => Ok(BvlcFunction::RegisterForeignDevice),
+            0x06 => Ok(BvlcFunction::ReadForeignDeviceTable),
+            0x07 => Ok(BvlcFunction::ReadForeignDeviceTableAck),
+            0x08 => Ok(BvlcFunction::DeleteForeignDeviceTableEntry),
+            0x09 => Ok(BvlcFunction::DistributeBroadcastToNetwork),
+            0x0A => Ok(BvlcFunction::OriginalUnicastNpdu),
+            0x0B => Ok(BvlcFunction::OriginalBroadcastNpdu),
+            0x0C => Ok(BvlcFunction::SecureBvll),
+            _ => Err(DataLinkError::InvalidFrame),
+        }
+    }
+}
+
+/// BVLC-Result codes (Annex J.2.1).
+///
+/// A BBMD answers every management request with one of these codes. Each
+/// request has its own NAK; devices that are not BBMDs NAK them all.
+///
+/// # Examples
+///
+/// ```
+/// use bacnet_rs::datalink::bip::BvlcResultCode;
+/// use bacnet_rs::datalink::DataLinkError;
+///
+/// assert!(BvlcResultCode::Successful.into_result().is_ok());
+/// assert!(matches!(
+///     BvlcResultCode::RegisterForeignDeviceNak.into_result(),
+///     Err(DataLinkError::Nak(0x0030))
+/// ));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u16)]
+pub enum BvlcResultCode {
+    /// The request was carried out.
+    Successful = 0x0000,
+    /// Write-Broadcast-Distribution-Table was refused.
+    WriteBroadcastDistributionTableNak = 0x0010,
+    /// Read-Broadcast-Distribution-Table was refused.
+    ReadBroadcastDistributionTableNak = 0x0020,
+    /// Register-Foreign-Device was refused.
+    RegisterForeignDeviceNak = 0x0030,
+    /// Read-Foreign-Device-Table was refused.
+    ReadForeignDeviceTableNak = 0x0040,
+    /// Delete-Foreign-Device-Table-Entry was refused.
+    DeleteForeignDeviceTableEntryNak = 0x0050,
+    /// Distribute-Broadcast-To-Network was refused.
+    DistributeBroadcastToNetworkNak = 0x0060,
+}
+
+impl BvlcResultCode {
+    /// Turn the code into a result, with NAKs as [`DataLinkError::Nak`].
+    pub fn into_result(self) -> Result<()> {
+        match self {
+            BvlcResultCode::Successful => Ok(()),
+            nak => Err(DataLinkError::Nak(nak as u16)),
+        }
+    }
+}
+
+impl TryFrom<u16> for BvlcResultCode {
+    type Error = DataLinkError;
+
+    fn try_from(value: u16) -> Result<Self> {
+        match value {
+            0x0000 => Ok(BvlcResultCode::Successful),
+            0x0010 => Ok(BvlcResultCode::WriteBroadcastDistributionTableNak),
+            0x0020 => Ok(BvlcResultCode::ReadBroadcastDistributionTableNak),
+            0x0030 => Ok(BvlcResultCode::RegisterForeignDeviceNak),
+            0x0040 => Ok(BvlcResultCode::ReadForeignDeviceTableNak),
+            0x0050 => Ok(BvlcResultCode::DeleteForeignDeviceTableEntryNak),
+            0x0060 => Ok(BvlcResultCode::DistributeBroadcastToNetworkNak),
+            _ => Err(DataLinkError::InvalidFrame),
+        }
+    }
 }
 
 /// BVLC header structure for BACnet/IP messages.
@@ -330,21 +466,7 @@ impl BvlcHeader {
             return Err(DataLinkError::InvalidFrame);
         }
 
-        let function = match data[1] {
-            0x0A => BvlcFunction::OriginalUnicastNpdu,
-            0x0B => BvlcFunction::OriginalBroadcastNpdu,
-            0x04 => BvlcFunction::ForwardedNpdu,
-            0x05 => BvlcFunction::RegisterForeignDevice,
-            0x02 => BvlcFunction::ReadBroadcastDistributionTable,
-            0x03 => BvlcFunction::ReadBroadcastDistributionTableAck,
-            0x06 => BvlcFunction::ReadForeignDeviceTable,
-            0x07 => BvlcFunction::ReadForeignDeviceTableAck,
-            0x08 => BvlcFunction::DeleteForeignDeviceTableEntry,
-            0x09 => BvlcFunction::DistributeBroadcastToNetwork,
-            0x0C => BvlcFunction::ForwardedNpduFromDevice,
-            0x0D => BvlcFunction::SecureBvll,
-            _ => return Err(DataLinkError::InvalidFrame),
-        };
+        let function = BvlcFunction::try_from(data[1])?;
 
         let length = ((data[2] as u16) << 8) | (data[3] as u16);
 
@@ -377,7 +499,7 @@ impl BvlcHeader {
 /// };
 /// # }
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg(feature = "std")]
 pub struct BdtEntry {
     /// IP address and port of the peer BBMD.
@@ -451,6 +573,288 @@ pub struct FdtEntry {
     pub registration_time: Instant,
 }
 
+#[cfg(feature = "std")]
+impl FdtEntry {
+    /// Seconds left before the registration expires, counting the
+    /// [grace period](FDT_GRACE_PERIOD) a BBMD allows beyond the TTL.
+    pub fn time_remaining(&self, now: Instant) -> u16 {
+        let lifetime = self.ttl as u64 + FDT_GRACE_PERIOD.as_secs();
+        let elapsed = now.duration_since(self.registration_time).as_secs();
+        lifetime.saturating_sub(elapsed).min(u16::MAX as u64) as u16
+    }
+
+    /// Whether the registration has expired.
+    pub fn is_expired(&self, now: Instant) -> bool {
+        self.time_remaining(now) == 0
+    }
+
+    /// The entry as reported in a Read-Foreign-Device-Table-Ack.
+    pub fn record(&self, now: Instant) -> FdtRecord {
+        FdtRecord {
+            address: self.address,
+            ttl: self.ttl,
+            time_remaining: self.time_remaining(now),
+        }
+    }
+}
+
+/// Foreign Device Table entry as carried on the wire.
+///
+/// A Read-Foreign-Device-Table-Ack reports each registration with the TTL
+/// the device asked for and the seconds left before the BBMD drops it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg(feature = "std")]
+pub struct FdtRecord {
+    /// IP address and port of the foreign device.
+    pub address: SocketAddr,
+
+    /// Time-to-live the device registered with, in seconds.
+    pub ttl: u16,
+
+    /// Seconds left before the registration expires.
+    pub time_remaining: u16,
+}
+
+/// A complete BVLC message (Annex J.2).
+///
+/// Each variant carries the data that follows the 4-byte [`BvlcHeader`] for
+/// its function. B/IP addresses travel as 4 octets of IPv4 address followed
+/// by a 2-octet port, so only IPv4 socket addresses can be encoded.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "std")] {
+/// use bacnet_rs::datalink::bip::BvlcMessage;
+///
+/// let message = BvlcMessage::ForwardedNpdu {
+///     source: "192.168.1.20:47808".parse().unwrap(),
+///     npdu: vec![0x01, 0x00],
+/// };
+/// let bytes = message.encode().unwrap();
+/// assert_eq!(bytes[..4], [0x81, 0x04, 0x00, 0x0C]);
+/// assert_eq!(BvlcMessage::decode(&bytes).unwrap(), message);
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg(feature = "std")]
+pub enum BvlcMessage {
+    /// BVLC-Result
+    Result(BvlcResultCode),
+    /// Write-Broadcast-Distribution-Table with the new table
+    WriteBroadcastDistributionTable(Vec<BdtEntry>),
+    /// Read-Broadcast-Distribution-Table
+    ReadBroadcastDistributionTable,
+    /// Read-Broadcast-Distribution-Table-Ack with the table
+    ReadBroadcastDistributionTableAck(Vec<BdtEntry>),
+    /// Forwarded-NPDU with the address of the device that sent it
+    ForwardedNpdu {
+        /// Original source of the NPDU
+        source: SocketAddr,
+        /// Forwarded NPDU
+        npdu: Vec<u8>,
+    },
+    /// Register-Foreign-Device with the requested time-to-live in seconds
+    RegisterForeignDevice {
+        /// Time-to-live in seconds
+        ttl: u16,
+    },
+    /// Read-Foreign-Device-Table
+    ReadForeignDeviceTable,
+    /// Read-Foreign-Device-Table-Ack with the table
+    ReadForeignDeviceTableAck(Vec<FdtRecord>),
+    /// Delete-Foreign-Device-Table-Entry naming the registration to drop
+    DeleteForeignDeviceTableEntry(SocketAddr),
+    /// Distribute-Broadcast-To-Network with the NPDU to broadcast
+    DistributeBroadcastToNetwork(Vec<u8>),
+    /// Original-Unicast-NPDU
+    OriginalUnicastNpdu(Vec<u8>),
+    /// Original-Broadcast-NPDU
+    OriginalBroadcastNpdu(Vec<u8>),
+    /// Secure-BVLL, left undecoded
+    SecureBvll(Vec<u8>),
+}
+
+#[cfg(feature = "std")]
+impl BvlcMessage {
+    /// The BVLC function of this message.
+    pub fn function(&self) -> BvlcFunction {
+        match self {
+            BvlcMessage::Result(_) => BvlcFunction::Result,
+            BvlcMessage::WriteBroadcastDistributionTable(_) => {
+                BvlcFunction::WriteBroadcastDistributionTable
+            }
+            BvlcMessage::ReadBroadcastDistributionTable => {
+                BvlcFunction::ReadBroadcastDistributionTable
+            }
+            BvlcMessage::ReadBroadcastDistributionTableAck(_) => {
+                BvlcFunction::ReadBroadcastDistributionTableAck
+            }
+            BvlcMessage::ForwardedNpdu { .. } => BvlcFunction::ForwardedNpdu,
+            BvlcMessage::RegisterForeignDevice { .. } => BvlcFunction::RegisterForeignDevice,
+            BvlcMessage::ReadForeignDeviceTable => BvlcFunction::ReadForeignDeviceTable,
+            BvlcMessage::ReadForeignDeviceTableAck(_) => BvlcFunction::ReadForeignDeviceTableAck,
+            BvlcMessage::DeleteForeignDeviceTableEntry(_) => {
+                BvlcFunction::DeleteForeignDeviceTableEntry
+            }
+            BvlcMessage::DistributeBroadcastToNetwork(_) => {
+                BvlcFunction::DistributeBroadcastToNetwork
+            }
+            BvlcMessage::OriginalUnicastNpdu(_) => BvlcFunction::OriginalUnicastNpdu,
+            BvlcMessage::OriginalBroadcastNpdu(_) => BvlcFunction::OriginalBroadcastNpdu,
+            BvlcMessage::SecureBvll(_) => BvlcFunction::SecureBvll,
+        }
+    }
+
+    /// Encode the message, header included.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DataLinkError::AddressError`] for IPv6 addresses and
+    /// [`DataLinkError::InvalidFrame`] if the message exceeds 65535 bytes.
+    pub fn encode(&self) -> Result<Vec<u8>> {
+        let mut body = Vec::new();
+        match self {
+            BvlcMessage::Result(code) => body.extend_from_slice(&(*code as u16).to_be_bytes()),
+            BvlcMessage::WriteBroadcastDistributionTable(entries)
+            | BvlcMessage::ReadBroadcastDistributionTableAck(entries) => {
+                for entry in entries {
+                    encode_bip_address(&mut body, &entry.address)?;
+                    body.extend_from_slice(&entry.mask);
+                }
+            }
+            BvlcMessage::ReadBroadcastDistributionTable | BvlcMessage::ReadForeignDeviceTable => {}
+            BvlcMessage::ForwardedNpdu { source, npdu } => {
+                encode_bip_address(&mut body, source)?;
+                body.extend_from_slice(npdu);
+            }
+            BvlcMessage::RegisterForeignDevice { ttl } => {
+                body.extend_from_slice(&ttl.to_be_bytes())
+            }
+            BvlcMessage::ReadForeignDeviceTableAck(records) => {
+                for record in records {
+                    encode_bip_address(&mut body, &record.address)?;
+                    body.extend_from_slice(&record.ttl.to_be_bytes());
+                    body.extend_from_slice(&record.time_remaining.to_be_bytes());
+                }
+            }
+            BvlcMessage::DeleteForeignDeviceTableEntry(address) => {
+                encode_bip_address(&mut body, address)?
+            }
+            BvlcMessage::DistributeBroadcastToNetwork(npdu)
+            | BvlcMessage::OriginalUnicastNpdu(npdu)
+            | BvlcMessage::OriginalBroadcastNpdu(npdu)
+            | BvlcMessage::SecureBvll(npdu) => body.extend_from_slice(npdu),
+        }
+
+        let length = u16::try_from(4 + body.len()).map_err(|_| DataLinkError::InvalidFrame)?;
+        let mut frame = BvlcHeader::new(self.function(), length).encode();
+        frame.extend_from_slice(&body);
+        Ok(frame)
+    }
+
+    /// Decode a message, header included.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DataLinkError::InvalidFrame`] if the header is invalid, its
+    /// length does not match the buffer, or the data does not fit the
+    /// function.
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        let header = BvlcHeader::decode(data)?;
+        if data.len() != header.length as usize {
+            return Err(DataLinkError::InvalidFrame);
+        }
+
+        let body = &data[4..];
+        let message = match header.function {
+            BvlcFunction::Result if body.len() == 2 => {
+                BvlcMessage::Result(BvlcResultCode::try_from(u16::from_be_bytes([
+                    body[0], body[1],
+                ]))?)
+            }
+            BvlcFunction::WriteBroadcastDistributionTable if body.len().is_multiple_of(10) => {
+                BvlcMessage::WriteBroadcastDistributionTable(decode_bdt(body))
+            }
+            BvlcFunction::ReadBroadcastDistributionTable if body.is_empty() => {
+                BvlcMessage::ReadBroadcastDistributionTable
+            }
+            BvlcFunction::ReadBroadcastDistributionTableAck if body.len().is_multiple_of(10) => {
+                BvlcMessage::ReadBroadcastDistributionTableAck(decode_bdt(body))
+            }
+            BvlcFunction::ForwardedNpdu if body.len() > 6 => BvlcMessage::ForwardedNpdu {
+                source: decode_bip_address(body),
+                npdu: body[6..].to_vec(),
+            },
+            BvlcFunction::RegisterForeignDevice if body.len() == 2 => {
+                BvlcMessage::RegisterForeignDevice {
+                    ttl: u16::from_be_bytes([body[0], body[1]]),
+                }
+            }
+            BvlcFunction::ReadForeignDeviceTable if body.is_empty() => {
+                BvlcMessage::ReadForeignDeviceTable
+            }
+            BvlcFunction::ReadForeignDeviceTableAck if body.len().is_multiple_of(10) => {
+                BvlcMessage::ReadForeignDeviceTableAck(
+                    body.chunks(10)
+                        .map(|chunk| FdtRecord {
+                            address: decode_bip_address(chunk),
+                            ttl: u16::from_be_bytes([chunk[6], chunk[7]]),
+                            time_remaining: u16::from_be_bytes([chunk[8], chunk[9]]),
+                        })
+                        .collect(),
+                )
+            }
+            BvlcFunction::DeleteForeignDeviceTableEntry if body.len() == 6 => {
+                BvlcMessage::DeleteForeignDeviceTableEntry(decode_bip_address(body))
+            }
+            BvlcFunction::DistributeBroadcastToNetwork if !body.is_empty() => {
+                BvlcMessage::DistributeBroadcastToNetwork(body.to_vec())
+            }
+            BvlcFunction::OriginalUnicastNpdu if !body.is_empty() => {
+                BvlcMessage::OriginalUnicastNpdu(body.to_vec())
+            }
+            BvlcFunction::OriginalBroadcastNpdu if !body.is_empty() => {
+                BvlcMessage::OriginalBroadcastNpdu(body.to_vec())
+            }
+            BvlcFunction::SecureBvll => BvlcMessage::SecureBvll(body.to_vec()),
+            _ => return Err(DataLinkError::InvalidFrame),
+        };
+        Ok(message)
+    }
+}
+
+/// Append a 6-byte B/IP address.
+#[cfg(feature = "std")]
+fn encode_bip_address(buffer: &mut Vec<u8>, address: &SocketAddr) -> Result<()> {
+    let SocketAddr::V4(address) = address else {
+        return Err(DataLinkError::AddressError(
+            "BACnet/IP addresses must be IPv4".into(),
+        ));
+    };
+    buffer.extend_from_slice(&address.ip().octets());
+    buffer.extend_from_slice(&address.port().to_be_bytes());
+    Ok(())
+}
+
+/// Read a 6-byte B/IP address from the start of `data`.
+#[cfg(feature = "std")]
+fn decode_bip_address(data: &[u8]) -> SocketAddr {
+    let ip = Ipv4Addr::new(data[0], data[1], data[2], data[3]);
+    SocketAddrV4::new(ip, u16::from_be_bytes([data[4], data[5]])).into()
+}
+
+/// Read a list of 10-byte BDT entries.
+#[cfg(feature = "std")]
+fn decode_bdt(data: &[u8]) -> Vec<BdtEntry> {
+    data.chunks(10)
+        .map(|chunk| BdtEntry {
+            address: decode_bip_address(chunk),
+            mask: [chunk[6], chunk[7], chunk[8], chunk[9]],
+        })
+        .collect()
+}
+
 /// BACnet/IP data link implementation.
 ///
 /// Provides complete BACnet/IP communication including BVLC protocol support,
@@ -517,23 +921,31 @@ pub struct BacnetIpDataLink {
     /// Local IP address and port.
     local_addr: SocketAddr,
 
-    /// Broadcast Distribution Table.
+    /// BBMD role, holding the BDT and FDT.
     ///
-    /// Contains peer BBMDs for broadcast forwarding. Only used when this
-    /// device is configured as a BBMD.
-    bdt: Vec<BdtEntry>,
+    /// `None` unless this device is configured as a BBMD.
+    bbmd: Option<Bbmd>,
 
-    /// Foreign Device Table.
+    /// Registration with a remote BBMD.
     ///
-    /// Contains registered foreign devices that should receive broadcasts.
-    /// Only used when this device is configured as a BBMD.
-    fdt: Vec<FdtEntry>,
+    /// While set, broadcasts go to that BBMD as
+    /// Distribute-Broadcast-To-Network messages.
+    foreign_device: Option<ForeignDevice>,
 
     /// Local broadcast address for this subnet.
     ///
-    /// Calculated based on the local IP address and subnet mask.
+    /// Defaults to the /24 broadcast address of the local IP; see
+    /// [`set_broadcast_address`](Self::set_broadcast_address).
     /// Used for Original-Broadcast-NPDU messages.
     broadcast_addr: SocketAddr,
+
+    /// NPDUs received while waiting for a management reply.
+    ///
+    /// Handed out by [`DataLink::receive_frame`] before reading the socket.
+    pending: VecDeque<(Vec<u8>, SocketAddr)>,
+
+    /// How long management requests wait for a reply.
+    request_timeout: Duration,
 }
 
 #[cfg(feature = "std")]
@@ -595,8 +1007,8 @@ impl BacnetIpDataLink {
         let broadcast_addr = match local_addr {
             SocketAddr::V4(addr) => {
                 let ip = addr.ip().octets();
-                // Simple broadcast calculation - in production, use proper subnet mask
-                let broadcast_ip = std::net::Ipv4Addr::new(ip[0], ip[1], ip[2], 255);
+                // Assume a /24 subnet until told otherwise
+                let broadcast_ip = Ipv4Addr::new(ip[0], ip[1], ip[2], 255);
                 SocketAddr::new(broadcast_ip.into(), BACNET_IP_PORT)
             }
             SocketAddr::V6(_) => {
@@ -608,12 +1020,170 @@ impl BacnetIpDataLink {
         Ok(Self {
             socket,
             local_addr,
-            bdt: Vec::new(),
-            fdt: Vec::new(),
+            bbmd: None,
+            foreign_device: None,
             broadcast_addr,
+            pending: VecDeque::new(),
+            request_timeout: BVLC_REQUEST_TIMEOUT,
         })
     }
 
+    /// The address Original-Broadcast-NPDUs are sent to.
+    pub fn broadcast_address(&self) -> SocketAddr {
+        self.broadcast_addr
+    }
+
+    /// Set the local broadcast address.
+    ///
+    /// Use the directed broadcast address of the actual subnet, such as
+    /// `192.168.16.255:47808` for `192.168.16.0/22`.
+    pub fn set_broadcast_address(&mut self, address: SocketAddr) {
+        self.broadcast_addr = address;
+    }
+
+    /// Set how long management requests wait for the BBMD's reply.
+    ///
+    /// Defaults to [`BVLC_REQUEST_TIMEOUT`].
+    pub fn set_request_timeout(&mut self, timeout: Duration) {
+        self.request_timeout = timeout;
+    }
+
+    /// Whether this device acts as a BBMD.
+    pub fn is_bbmd(&self) -> bool {
+        self.bbmd.is_some()
+    }
+
+    /// The BBMD role, if this device is a BBMD.
+    pub fn bbmd(&self) -> Option<&Bbmd> {
+        self.bbmd.as_ref()
+    }
+
+    /// The BBMD role, for changing its tables at runtime.
+    pub fn bbmd_mut(&mut self) -> Option<&mut Bbmd> {
+        self.bbmd.as_mut()
+    }
+
+    /// Make this device a BBMD, or an ordinary device with `None`.
+    ///
+    /// The BBMD's address should be the one this data link is bound to.
+    pub fn set_bbmd(&mut self, bbmd: Option<Bbmd>) {
+        self.bbmd = bbmd;
+    }
+
+    /// Apply the settings of a BACnet/IP Network Port.
+    ///
+    /// Sets the local broadcast address from IP_Address and IP_Subnet_Mask,
+    /// and takes on or drops the BBMD role according to BACnet_IP_Mode. A
+    /// BBMD that stays one keeps its foreign device registrations. Call this
+    /// with the configuration returned by
+    /// [`NetworkPort::take_activated_config`](crate::object::NetworkPort::take_activated_config).
+    pub fn apply_settings(&mut self, settings: &IpPortSettings) {
+        let broadcast_ip =
+            u32::from_be_bytes(settings.ip_address) | !u32::from_be_bytes(settings.subnet_mask);
+        self.broadcast_addr =
+            SocketAddrV4::new(Ipv4Addr::from(broadcast_ip), settings.udp_port).into();
+
+        match (&mut self.bbmd, settings.mode) {
+            (Some(bbmd), BacnetIpMode::Bbmd) => bbmd.apply_settings(settings),
+            (bbmd, _) => *bbmd = Bbmd::from_settings(settings),
+        }
+
+        let foreign_device = ForeignDevice::from_settings(settings);
+        let unchanged = match (&self.foreign_device, &foreign_device) {
+            (Some(current), Some(new)) => {
+                current.bbmd() == new.bbmd() && current.ttl() == new.ttl()
+            }
+            _ => false,
+        };
+        if !unchanged {
+            self.foreign_device = foreign_device;
+        }
+    }
+
+    /// The foreign device registration, if this device is a foreign device.
+    pub fn foreign_device(&self) -> Option<&ForeignDevice> {
+        self.foreign_device.as_ref()
+    }
+
+    /// Make this device a foreign device, or stop being one with `None`.
+    ///
+    /// Stopping does not cancel the registration at the BBMD, which lapses
+    /// once its TTL and grace period run out.
+    pub fn set_foreign_device(&mut self, foreign_device: Option<ForeignDevice>) {
+        self.foreign_device = foreign_device;
+    }
+
+    /// Register as a foreign device with a BBMD and keep the registration
+    /// alive.
+    ///
+    /// The first Register-Foreign-Device is sent at once. From then on
+    /// [`receive_frame`](DataLink::receive_frame) renews it before the TTL
+    /// runs out, retries when the BBMD stays silent or NAKs, and broadcasts
+    /// are sent through the BBMD. This replaces any earlier registration.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the registration cannot be sent.
+    pub fn join_as_foreign_device(&mut self, bbmd_addr: SocketAddr, ttl: u16) -> Result<()> {
+        self.foreign_device = Some(ForeignDevice::new(bbmd_addr, ttl));
+        self.maintain_registration()
+    }
+
+    /// Register again at once, as the RENEW_FD_REGISTRATION Network Port
+    /// command asks. Does nothing unless this device is a foreign device.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the registration cannot be sent.
+    pub fn renew_registration(&mut self) -> Result<()> {
+        if let Some(foreign_device) = &mut self.foreign_device {
+            foreign_device.renew();
+        }
+        self.maintain_registration()
+    }
+
+    /// Send the Register-Foreign-Device that is due, if any.
+    ///
+    /// [`receive_frame`](DataLink::receive_frame) and broadcasts call this;
+    /// an application that neither receives nor broadcasts for longer than
+    /// half the TTL should call it itself.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the registration cannot be sent.
+    pub fn maintain_registration(&mut self) -> Result<()> {
+        let Some(foreign_device) = &mut self.foreign_device else {
+            return Ok(());
+        };
+        let bbmd_addr = foreign_device.bbmd();
+        match foreign_device.poll(Instant::now()) {
+            Some(message) => self.send_message(&message, bbmd_addr),
+            None => Ok(()),
+        }
+    }
+
+    /// The Broadcast Distribution Table, empty unless this device is a BBMD.
+    pub fn bdt(&self) -> &[BdtEntry] {
+        self.bbmd.as_ref().map_or(&[], |bbmd| bbmd.bdt())
+    }
+
+    /// The Foreign Device Table, empty unless this device is a BBMD.
+    pub fn fdt(&self) -> &[FdtEntry] {
+        self.bbmd.as_ref().map_or(&[], |bbmd| bbmd.fdt())
+    }
+
+    /// Send a BVLC message to `dest`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the message cannot be encoded or sent.
+    pub fn send_message(&self, message: &BvlcMessage, dest: SocketAddr) -> Result<()> {
+        self.socket
+            .send_to(&message.encode()?, dest)
+            .map_err(DataLinkError::IoError)?;
+        Ok(())
+    }
+
     /// Send a unicast NPDU to a specific device.
     ///
     /// Wraps the NPDU in a BVLC Original-Unicast-NPDU message and sends it
@@ -643,24 +1213,19 @@ impl BacnetIpDataLink {
     /// # }
     /// ```
     pub fn send_unicast_npdu(&mut self, npdu: &[u8], dest: SocketAddr) -> Result<()> {
-        let header = BvlcHeader::new(BvlcFunction::OriginalUnicastNpdu, 4 + npdu.len() as u16);
-
-        let mut frame = header.encode();
-        frame.extend_from_slice(npdu);
-
-        self.socket
-            .send_to(&frame, dest)
-            .map_err(DataLinkError::IoError)?;
-
-        Ok(())
+        self.send_message(&BvlcMessage::OriginalUnicastNpdu(npdu.to_vec()), dest)
     }
 
     /// Send a broadcast NPDU to all devices.
     ///
-    /// Wraps the NPDU in a BVLC Original-Broadcast-NPDU message and sends it to:
-    /// 1. The local subnet broadcast address
-    /// 2. All peer BBMDs in the BDT (if configured as BBMD)
-    /// 3. All registered foreign devices in the FDT (if configured as BBMD)
+    /// Wraps the NPDU in a BVLC Original-Broadcast-NPDU message and sends it
+    /// to the local subnet broadcast address. A BBMD also sends it as a
+    /// Forwarded-NPDU to:
+    /// 1. All peer BBMDs in the BDT
+    /// 2. All registered foreign devices in the FDT
+    ///
+    /// A foreign device instead sends it to its BBMD as a
+    /// Distribute-Broadcast-To-Network message.
     ///
     /// # Arguments
     ///
@@ -686,24 +1251,46 @@ impl BacnetIpDataLink {
     /// # }
     /// ```
     pub fn send_broadcast_npdu(&mut self, npdu: &[u8]) -> Result<()> {
-        let header = BvlcHeader::new(BvlcFunction::OriginalBroadcastNpdu, 4 + npdu.len() as u16);
-
-        let mut frame = header.encode();
-        frame.extend_from_slice(npdu);
+        if let Some(foreign_device) = &self.foreign_device {
+            let bbmd_addr = foreign_device.bbmd();
+            self.maintain_registration()?;
+            return self.distribute_broadcast_to_network(npdu, bbmd_addr);
+        }
 
-        // Send to local broadcast address
-        self.socket
-            .send_to(&frame, self.broadcast_addr)
-            .map_err(DataLinkError::IoError)?;
+        self.send_message(
+            &BvlcMessage::OriginalBroadcastNpdu(npdu.to_vec()),
+            self.broadcast_addr,
+        )?;
 
-        // Send to all BDT entries
-        for entry in &self.bdt {
-            let _ = self.socket.send_to(&frame, entry.address);
+        if let Some(bbmd) = &self.bbmd {
+            for (message, dest) in bbmd.originate(npdu, bbmd.address()) {
+                let _ = self.send_message(&message, dest);
+            }
         }
-
         Ok(())
     }
 
+    /// Ask a BBMD to broadcast an NPDU on its network.
+    ///
+    /// Foreign devices cannot reach the remote network's broadcast address,
+    /// so they send broadcasts to the BBMD they registered with in a
+    /// Distribute-Broadcast-To-Network message. A BBMD that refuses answers
+    /// with a NAK, which [`receive_frame`](DataLink::receive_frame) drops.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the message cannot be sent.
+    pub fn distribute_broadcast_to_network(
+        &mut self,
+        npdu: &[u8],
+        bbmd_addr: SocketAddr,
+    ) -> Result<()> {
+        self.send_message(
+            &BvlcMessage::DistributeBroadcastToNetwork(npdu.to_vec()),
+            bbmd_addr,
+        )
+    }
+
     /// Register this device as a foreign device with a BBMD.
     ///
     /// Foreign device registration allows a device on a different IP subnet to
@@ -721,7 +1308,7 @@ impl BacnetIpDataLink {
     ///
     /// # Notes
     ///
-    /// - The BBMD may reject the registration (no response is provided)
+    /// - The BBMD answers with a BVLC-Result, which this call does not wait for
     /// - Re-registration should occur at intervals less than the TTL
     /// - A TTL of 0 cancels the registration
     ///
@@ -743,14 +1330,81 @@ impl BacnetIpDataLink {
     /// # }
     /// ```
     pub fn register_foreign_device(&mut self, bbmd_addr: SocketAddr, ttl: u16) -> Result<()> {
-        let header = BvlcHeader::new(BvlcFunction::RegisterForeignDevice, 6);
-        let mut frame = header.encode();
-        frame.extend_from_slice(&ttl.to_be_bytes());
+        self.send_message(&BvlcMessage::RegisterForeignDevice { ttl }, bbmd_addr)
+    }
 
-        self.socket
-            .send_to(&frame, bbmd_addr)
-            .map_err(DataLinkError::IoError)?;
+    /// Read a BBMD's Broadcast Distribution Table.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DataLinkError::Nak`] if the device is not a BBMD, or an
+    /// I/O timeout if no reply arrives in time.
+    pub fn read_broadcast_distribution_table(
+        &mut self,
+        bbmd_addr: SocketAddr,
+    ) -> Result<Vec<BdtEntry>> {
+        match self.request(
+            bbmd_addr,
+            &BvlcMessage::ReadBroadcastDistributionTable,
+            BvlcResultCode::ReadBroadcastDistributionTableNak,
+        )? {
+            BvlcMessage::ReadBroadcastDistributionTableAck(entries) => Ok(entries),
+            _ => Err(DataLinkError::InvalidFrame),
+        }
+    }
 
+    /// Replace a BBMD's Broadcast Distribution Table.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DataLinkError::Nak`] if the BBMD refuses, or an I/O timeout
+    /// if no reply arrives in time.
+    pub fn write_broadcast_distribution_table(
+        &mut self,
+        bbmd_addr: SocketAddr,
+        entries: &[BdtEntry],
+    ) -> Result<()> {
+        self.request(
+            bbmd_addr,
+            &BvlcMessage::WriteBroadcastDistributionTable(entries.to_vec()),
+            BvlcResultCode::WriteBroadcastDistributionTableNak,
+        )?;
+        Ok(())
+    }
+
+    /// Read a BBMD's Foreign Device Table.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DataLinkError::Nak`] if the device is not a BBMD, or an
+    /// I/O timeout if no reply arrives in time.
+    pub fn read_foreign_device_table(&mut self, bbmd_addr: SocketAddr) -> Result<Vec<FdtRecord>> {
+        match self.request(
+            bbmd_addr,
+            &BvlcMessage::ReadForeignDeviceTable,
+            BvlcResultCode::ReadForeignDeviceTableNak,
+        )? {
+            BvlcMessage::ReadForeignDeviceTableAck(records) => Ok(records),
+            _ => Err(DataLinkError::InvalidFrame),
+        }
+    }
+
+    /// Remove a registration from a BBMD's Foreign Device Table.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DataLinkError::Nak`] if the BBMD has no such entry, or an
+    /// I/O timeout if no reply arrives in time.
+    pub fn delete_foreign_device_table_entry(
+        &mut self,
+        bbmd_addr: SocketAddr,
+        entry: SocketAddr,
+    ) -> Result<()> {
+        self.request(
+            bbmd_addr,
+            &BvlcMessage::DeleteForeignDeviceTableEntry(entry),
+            BvlcResultCode::DeleteForeignDeviceTableEntryNak,
+        )?;
         Ok(())
     }
 
@@ -758,7 +1412,10 @@ impl BacnetIpDataLink {
     ///
     /// When configured as a BBMD, this device will forward broadcast messages
     /// to all peers in the BDT. Each peer BBMD is responsible for distributing
-    /// broadcasts to devices on its local subnet.
+    /// broadcasts to devices on its local subnet. The BDT of a BBMD lists the
+    /// BBMD itself too; that entry is never forwarded to. A device that is not
+    /// yet a BBMD becomes one, at its bound address with a /24 subnet; use
+    /// [`set_bbmd`](Self::set_bbmd) for anything else.
     ///
     /// # Arguments
     ///
@@ -780,14 +1437,18 @@ impl BacnetIpDataLink {
     /// # }
     /// ```
     pub fn add_bdt_entry(&mut self, address: SocketAddr, mask: [u8; 4]) {
-        self.bdt.push(BdtEntry { address, mask });
+        let local_addr = self.local_addr;
+        self.bbmd
+            .get_or_insert_with(|| Bbmd::new(local_addr, [255, 255, 255, 0]))
+            .add_bdt_entry(address, mask);
     }
 
     /// Remove expired entries from the Foreign Device Table.
     ///
     /// This method should be called periodically to remove foreign devices
     /// whose registration has expired. Devices that fail to re-register
-    /// within their TTL period will no longer receive broadcasts.
+    /// within their TTL and grace period will no longer receive broadcasts.
+    /// Received frames also trigger a cleanup.
     ///
     /// # Examples
     ///
@@ -807,71 +1468,168 @@ impl BacnetIpDataLink {
     /// # }
     /// ```
     pub fn cleanup_fdt(&mut self) {
-        let now = Instant::now();
-        self.fdt.retain(|entry| {
-            now.duration_since(entry.registration_time).as_secs() < entry.ttl as u64
-        });
+        if let Some(bbmd) = &mut self.bbmd {
+            bbmd.expire(Instant::now());
+        }
+    }
+
+    /// Send a management request and wait for the BBMD's reply.
+    ///
+    /// BVLC replies carry no invoke ID, so the first acknowledgement from
+    /// `bbmd_addr` that fits the request is taken as the answer. Anything
+    /// else received meanwhile is processed as usual and its NPDUs queued.
+    fn request(
+        &mut self,
+        bbmd_addr: SocketAddr,
+        message: &BvlcMessage,
+        nak: BvlcResultCode,
+    ) -> Result<BvlcMessage> {
+        self.send_message(message, bbmd_addr)?;
+
+        let deadline = Instant::now() + self.request_timeout;
+        while Instant::now() < deadline {
+            let (reply, source) = match self.receive_message() {
+                Ok(received) => received,
+                Err(DataLinkError::InvalidFrame) => continue,
+                Err(DataLinkError::IoError(e))
+                    if e.kind() == ErrorKind::WouldBlock || e.kind() == ErrorKind::TimedOut =>
+                {
+                    continue
+                }
+                Err(e) => return Err(e),
+            };
+
+            if source == bbmd_addr {
+                let answers = match (&reply, message) {
+                    (BvlcMessage::Result(code), _) if *code == nak => {
+                        return Err(DataLinkError::Nak(nak as u16))
+                    }
+                    (
+                        BvlcMessage::ReadBroadcastDistributionTableAck(_),
+                        BvlcMessage::ReadBroadcastDistributionTable,
+                    )
+                    | (
+                        BvlcMessage::ReadForeignDeviceTableAck(_),
+                        BvlcMessage::ReadForeignDeviceTable,
+                    ) => true,
+                    (
+                        BvlcMessage::Result(BvlcResultCode::Successful),
+                        BvlcMessage::WriteBroadcastDistributionTable(_)
+                        | BvlcMessage::DeleteForeignDeviceTableEntry(_),
+                    ) => true,
+                    _ => false,
+                };
+                if answers {
+                    return Ok(reply);
+                }
+            }
+
+            if let Some(npdu) = self.process_message(reply, source)? {
+                self.pending.push_back(npdu);
+            }
+        }
+
+        Err(DataLinkError::IoError(std::io::Error::new(
+            ErrorKind::TimedOut,
+            "no BVLC reply",
+        )))
+    }
+
+    /// Read one BVLC message from the socket.
+    fn receive_message(&mut self) -> Result<(BvlcMessage, SocketAddr)> {
+        let mut buffer = [0u8; 1500]; // MTU size
+        let (len, source) = self
+            .socket
+            .recv_from(&mut buffer)
+            .map_err(DataLinkError::IoError)?;
+        Ok((BvlcMessage::decode(&buffer[..len])?, source))
     }
 
     /// Process a received BVLC message.
     ///
     /// Handles all BVLC message types according to the BACnet/IP specification.
-    /// Returns the encapsulated NPDU for data messages, or None for control messages.
+    /// A BBMD leaves the message to its [`Bbmd`] and carries out the outcome;
+    /// any other device delivers NPDUs and NAKs the BBMD management requests.
+    /// Messages this device sent itself, such as its own broadcasts, are
+    /// dropped; a BBMD should therefore bind to its interface address rather
+    /// than `0.0.0.0`.
     ///
     /// # Arguments
     ///
-    /// * `data` - The complete BVLC message including header
+    /// * `message` - The decoded BVLC message
     /// * `source` - The source IP address and port
     ///
     /// # Returns
     ///
-    /// - `Some(npdu)` - For data messages (Original-Unicast-NPDU, etc.)
+    /// - `Some((npdu, source))` - For data messages, with the original source
+    ///   of forwarded NPDUs
     /// - `None` - For control messages (Register-Foreign-Device, etc.)
     ///
     /// # Errors
     ///
-    /// Returns an error if the message format is invalid.
-    fn process_bvlc_message(&mut self, data: &[u8], source: SocketAddr) -> Result<Option<Vec<u8>>> {
-        let header = BvlcHeader::decode(data)?;
+    /// Returns an error if a reply cannot be sent.
+    fn process_message(
+        &mut self,
+        message: BvlcMessage,
+        source: SocketAddr,
+    ) -> Result<Option<(Vec<u8>, SocketAddr)>> {
+        if source == self.local_addr {
+            return Ok(None);
+        }
 
-        if data.len() != header.length as usize {
-            return Err(DataLinkError::InvalidFrame);
+        if let (Some(foreign_device), BvlcMessage::Result(code)) =
+            (&mut self.foreign_device, &message)
+        {
+            if source == foreign_device.bbmd()
+                && foreign_device.handle_result(*code, Instant::now())
+            {
+                self.maintain_registration()?;
+                return Ok(None);
+            }
         }
 
-        match header.function {
-            BvlcFunction::OriginalUnicastNpdu | BvlcFunction::OriginalBroadcastNpdu => {
-                // Return the NPDU portion (skip 4-byte BVLC header)
-                if data.len() > 4 {
-                    Ok(Some(data[4..].to_vec()))
-                } else {
-                    Err(DataLinkError::InvalidFrame)
-                }
+        if let Some(bbmd) = &mut self.bbmd {
+            let outcome = bbmd.process(message, source, Instant::now());
+            for (message, dest) in &outcome.forwards {
+                let _ = self.send_message(message, *dest);
             }
-            BvlcFunction::ForwardedNpdu => {
-                // Forwarded NPDU has original source address after header
-                if data.len() > 10 {
-                    Ok(Some(data[10..].to_vec()))
-                } else {
-                    Err(DataLinkError::InvalidFrame)
-                }
+            if let Some(reply) = &outcome.reply {
+                self.send_message(reply, source)?;
             }
-            BvlcFunction::RegisterForeignDevice => {
-                // Handle foreign device registration
-                if data.len() == 6 {
-                    let ttl = u16::from_be_bytes([data[4], data[5]]);
-                    self.fdt.push(FdtEntry {
-                        address: source,
-                        ttl,
-                        registration_time: Instant::now(),
-                    });
-                }
-                Ok(None)
+            return Ok(outcome.npdu);
+        }
+
+        let nak = match message {
+            BvlcMessage::OriginalUnicastNpdu(npdu) | BvlcMessage::OriginalBroadcastNpdu(npdu) => {
+                return Ok(Some((npdu, source)))
             }
-            _ => {
-                // Other BVLC functions not yet implemented
-                Ok(None)
+            BvlcMessage::ForwardedNpdu {
+                source: original,
+                npdu,
+            } => return Ok(Some((npdu, original))),
+            BvlcMessage::DistributeBroadcastToNetwork(_) => {
+                BvlcResultCode::DistributeBroadcastToNetworkNak
             }
-        }
+            BvlcMessage::RegisterForeignDevice { .. } => BvlcResultCode::RegisterForeignDeviceNak,
+            BvlcMessage::ReadBroadcastDistributionTable => {
+                BvlcResultCode::ReadBroadcastDistributionTableNak
+            }
+            BvlcMessage::WriteBroadcastDistributionTable(_) => {
+                BvlcResultCode::WriteBroadcastDistributionTableNak
+            }
+            BvlcMessage::ReadForeignDeviceTable => BvlcResultCode::ReadForeignDeviceTableNak,
+            BvlcMessage::DeleteForeignDeviceTableEntry(_) => {
+                BvlcResultCode::DeleteForeignDeviceTableEntryNak
+            }
+            // Replies outside a request, and BACnet/SC traffic, need no answer
+            BvlcMessage::Result(_)
+            | BvlcMessage::ReadBroadcastDistributionTableAck(_)
+            | BvlcMessage::ReadForeignDeviceTableAck(_)
+            | BvlcMessage::SecureBvll(_) => return Ok(None),
+        };
+
+        self.send_message(&BvlcMessage::Result(nak), source)?;
+        Ok(None)
     }
 }
 
@@ -879,30 +1637,32 @@ impl BacnetIpDataLink {
 impl DataLink for BacnetIpDataLink {
     fn send_frame(&mut self, frame: &[u8], dest: &DataLinkAddress) -> Result<()> {
         match dest {
+            // The local broadcast address is a broadcast however it is named
+            DataLinkAddress::Ip(addr) if *addr == self.broadcast_addr => {
+                self.send_broadcast_npdu(frame)
+            }
             DataLinkAddress::Ip(addr) => self.send_unicast_npdu(frame, *addr),
             DataLinkAddress::Broadcast => self.send_broadcast_npdu(frame),
             _ => Err(DataLinkError::UnsupportedType),
         }
     }
 
+    /// Receive the next NPDU.
+    ///
+    /// BVLC control messages are handled on the way and never returned; the
+    /// call keeps reading until an NPDU arrives or the socket times out. A
+    /// foreign device's registration is renewed here when due.
     fn receive_frame(&mut self) -> Result<(Vec<u8>, DataLinkAddress)> {
-        let mut buffer = [0u8; 1500]; // MTU size
-
-        match self.socket.recv_from(&mut buffer) {
-            Ok((len, source)) => {
-                let data = &buffer[..len];
+        self.maintain_registration()?;
+        if let Some((npdu, source)) = self.pending.pop_front() {
+            return Ok((npdu, DataLinkAddress::Ip(source)));
+        }
 
-                if let Some(npdu) = self.process_bvlc_message(data, source)? {
-                    Ok((npdu, DataLinkAddress::Ip(source)))
-                } else {
-                    // No NPDU to return, try again
-                    Err(DataLinkError::InvalidFrame)
-                }
+        loop {
+            let (message, source) = self.receive_message()?;
+            if let Some((npdu, source)) = self.process_message(message, source)? {
+                return Ok((npdu, DataLinkAddress::Ip(source)));
             }
-            Err(e) if e.kind() == ErrorKind::WouldBlock || e.kind() == ErrorKind::TimedOut => {
-                Err(DataLinkError::IoError(e))
-            }
-            Err(e) => Err(DataLinkError::IoError(e)),
         }
     }
 
@@ -913,6 +1673,14 @@ impl DataLink for BacnetIpDataLink {
     fn local_address(&self) -> DataLinkAddress {
         DataLinkAddress::Ip(self.local_addr)
     }
+
+    fn broadcast_address(&self) -> DataLinkAddress {
+        DataLinkAddress::Broadcast
+    }
+
+    fn max_npdu_length(&self) -> usize {
+        BIP_MAX_NPDU_LENGTH
+    }
 }
 
 #[cfg(test)]
@@ -942,7 +1710,147 @@ mod tests {
         let result = BacnetIpDataLink::new("127.0.0.1:0");
         assert!(result.is_ok());
 
-        let datalink = result.unwrap();
+        // Upper layers see it through the trait alone
+        let datalink: Box<dyn DataLink> = Box::new(result.unwrap());
         assert_eq!(datalink.link_type(), DataLinkType::BacnetIp);
+        assert_eq!(datalink.max_npdu_length(), BIP_MAX_NPDU_LENGTH);
+        assert_eq!(datalink.broadcast_address(), DataLinkAddress::Broadcast);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_broadcast_address_sends_broadcast() {
+        let mut link = BacnetIpDataLink::new("127.0.0.1:0").unwrap();
+        let peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+        peer.set_read_timeout(Some(Duration::from_secs(2))).unwrap();
+        let peer_addr = peer.local_addr().unwrap();
+        link.set_broadcast_address(peer_addr);
+        let mut buffer = [0u8; 1500];
+
+        let broadcast = DataLink::broadcast_address(&link);
+        link.send_frame(&[0x01, 0x00], &broadcast).unwrap();
+        let (len, _) = peer.recv_from(&mut buffer).unwrap();
+        assert_eq!(&buffer[..2], [0x81, 0x0B]);
+        assert_eq!(&buffer[4..len], [0x01, 0x00]);
+
+        // Naming the broadcast address itself is a broadcast too
+        link.send_frame(&[0x01, 0x00], &DataLinkAddress::Ip(peer_addr))
+            .unwrap();
+        peer.recv_from(&mut buffer).unwrap();
+        assert_eq!(buffer[1], 0x0B);
+
+        // A foreign device hands broadcasts to its BBMD, after registering
+        link.set_foreign_device(Some(ForeignDevice::new(peer_addr, 60)));
+        link.send_frame(&[0x01, 0x00], &broadcast).unwrap();
+        peer.recv_from(&mut buffer).unwrap();
+        assert_eq!(buffer[1], 0x05);
+        let (len, _) = peer.recv_from(&mut buffer).unwrap();
+        assert_eq!(buffer[1], 0x09);
+        assert_eq!(&buffer[4..len], [0x01, 0x00]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_bvlc_message_codec() {
+        let peer: SocketAddr = "192.168.1.10:47808".parse().unwrap();
+        let bytes = BvlcMessage::Result(BvlcResultCode::RegisterForeignDeviceNak)
+            .encode()
+            .unwrap();
+        assert_eq!(bytes, [0x81, 0x00, 0x00, 0x06, 0x00, 0x30]);
+        let bytes = BvlcMessage::ReadBroadcastDistributionTableAck(vec![BdtEntry {
+            address: peer,
+            mask: [255, 255, 255, 255],
+        }])
+        .encode()
+        .unwrap();
+        assert_eq!(
+            bytes,
+            [0x81, 0x03, 0x00, 0x0E, 192, 168, 1, 10, 0xBA, 0xC0, 0xFF, 0xFF, 0xFF, 0xFF]
+        );
+
+        for message in [
+            BvlcMessage::WriteBroadcastDistributionTable(vec![]),
+            BvlcMessage::ReadBroadcastDistributionTable,
+            BvlcMessage::ForwardedNpdu {
+                source: peer,
+                npdu: vec![0x01, 0x20],
+            },
+            BvlcMessage::RegisterForeignDevice { ttl: 300 },
+            BvlcMessage::ReadForeignDeviceTable,
+            BvlcMessage::ReadForeignDeviceTableAck(vec![FdtRecord {
+                address: peer,
+                ttl: 60,
+                time_remaining: 85,
+            }]),
+            BvlcMessage::DeleteForeignDeviceTableEntry(peer),
+            BvlcMessage::DistributeBroadcastToNetwork(vec![0x01, 0x00]),
+            BvlcMessage::OriginalUnicastNpdu(vec![0x01, 0x00]),
+            BvlcMessage::OriginalBroadcastNpdu(vec![0x01, 0x00]),
+        ] {
+            let bytes = message.encode().unwrap();
+            assert_eq!(bytes[1], message.function() as u8);
+            assert_eq!(BvlcMessage::decode(&bytes).unwrap(), message);
+        }
+
+        // Truncated tables and length mismatches are rejected
+        assert!(BvlcMessage::decode(&[0x81, 0x03, 0x00, 0x07, 192, 168, 1]).is_err());
+        assert!(BvlcMessage::decode(&[0x81, 0x0A, 0x00, 0x08, 0x01, 0x00]).is_err());
+        assert!("[::1]:47808"
+            .parse::<SocketAddr>()
+            .map(BvlcMessage::DeleteForeignDeviceTableEntry)
+            .unwrap()
+            .encode()
+            .is_err());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_bbmd_management() {
+        let mut bbmd = BacnetIpDataLink::new("127.0.0.1:0").unwrap();
+        let bbmd_addr = bbmd.local_addr;
+        bbmd.add_bdt_entry(bbmd_addr, [255, 255, 255, 255]);
+        let mut client = BacnetIpDataLink::new("127.0.0.1:0").unwrap();
+        let client_addr = client.local_addr;
+
+        let server = std::thread::spawn(move || {
+            let deadline = Instant::now() + Duration::from_secs(10);
+            while Instant::now() < deadline {
+                if let Ok((npdu, source)) = bbmd.receive_frame() {
+                    return Some((npdu, source));
+                }
+            }
+            None
+        });
+
+        client.register_foreign_device(bbmd_addr, 60).unwrap();
+        let records = client.read_foreign_device_table(bbmd_addr).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].address, client_addr);
+        assert_eq!(records[0].ttl, 60);
+        assert_eq!(
+            client.read_broadcast_distribution_table(bbmd_addr).unwrap(),
+            vec![BdtEntry {
+                address: bbmd_addr,
+                mask: [255, 255, 255, 255],
+            }]
+        );
+
+        assert!(matches!(
+            client.delete_foreign_device_table_entry(bbmd_addr, bbmd_addr),
+            Err(DataLinkError::Nak(0x0050))
+        ));
+
+        // Registered foreign devices broadcast through the BBMD
+        client.join_as_foreign_device(bbmd_addr, 60).unwrap();
+        client
+            .send_frame(&[0x01, 0x00], &DataLinkAddress::Broadcast)
+            .unwrap();
+        let (npdu, source) = server.join().unwrap().unwrap();
+        assert_eq!(npdu, [0x01, 0x00]);
+        assert_eq!(source, DataLinkAddress::Ip(client_addr));
+
+        // The BBMD's answer to the registration is picked up on receive
+        assert!(client.receive_frame().is_err());
+        assert!(client.foreign_device().unwrap().is_registered());
     }
 }
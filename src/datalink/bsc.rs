@@ -0,0 +1,1814 @@
+//! BACnet Secure Connect (ASHRAE 135 Annex AB) node implementation.
+//!
+//! BACnet/SC replaces UDP broadcasts with a hub-and-spoke topology of
+//! TLS-secured WebSocket connections. Every node connects to a hub, primary
+//! or failover, which forwards unicast messages by virtual MAC address (VMAC)
+//! and replicates broadcasts to all connected nodes.
+//!
+//! # BVLC-SC Messages
+//!
+//! Each WebSocket binary message holds one BVLC-SC message: a function code,
+//! control flags, a message ID, optional originating and destination VMACs,
+//! optional header options and a function-specific payload. [`ScMessage`]
+//! encodes and decodes all of them.
+//!
+//! # Hub Connection
+//!
+//! [`HubConnector`] decides when to connect, to which hub, when to send
+//! heartbeats and when a connection has failed. It tries the primary hub
+//! first and the failover hub next, then backs off between the minimum and
+//! maximum reconnect times. [`ScDataLink`] drives it, answers the control
+//! messages itself, and hands only NPDUs to the layers above.
+//!
+//! The TLS session comes from an [`ScConnector`] supplied by the
+//! application, which also holds the operational certificate used to
+//! authenticate with the hub.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use bacnet_rs::datalink::bsc::{ScConnector, ScDataLink, ScNodeConfig};
+//! use bacnet_rs::datalink::websocket::WebSocketUri;
+//! use bacnet_rs::datalink::{DataLink, DataLinkAddress};
+//! use std::net::TcpStream;
+//!
+//! // A real connector would wrap the stream in a TLS session
+//! struct Connector;
+//!
+//! impl ScConnector for Connector {
+//!     type Stream = TcpStream;
+//!
+//!     fn connect(&mut self, uri: &WebSocketUri) -> std::io::Result<TcpStream> {
+//!         let stream = TcpStream::connect((uri.host.as_str(), uri.port))?;
+//!         stream.set_read_timeout(Some(std::time::Duration::from_millis(100)))?;
+//!         Ok(stream)
+//!     }
+//! }
+//!
+//! # fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let config = ScNodeConfig::new("wss://hub.example.com/");
+//! let mut data_link = ScDataLink::new(config, Connector);
+//!
+//! let who_is_npdu = vec![0x01, 0x20, 0xFF, 0xFF, 0x00, 0xFF, 0x10, 0x08];
+//! data_link.send_frame(&who_is_npdu, &DataLinkAddress::Broadcast)?;
+//! let (npdu, source) = data_link.receive_frame()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+    collections::VecDeque,
+    fmt,
+    io::{self, ErrorKind, Read, Write},
+    net::{SocketAddr, TcpListener, ToSocketAddrs},
+    time::{Duration, Instant},
+};
+
+use crate::datalink::sc_direct::{DirectLink, NoDirectAcceptor, DIRECT_SUBPROTOCOL};
+use crate::datalink::sc_hub::ScAcceptor;
+use crate::datalink::websocket::{random_bytes, WebSocket, WebSocketUri};
+use crate::datalink::{DataLink, DataLinkAddress, DataLinkError, DataLinkType, Result};
+use crate::object::ScPortSettings;
+
+/// WebSocket subprotocol for node-to-hub connections.
+pub const HUB_SUBPROTOCOL: &str = "hub.bsc.bacnet.org";
+
+/// Default maximum BVLC message length accepted by a node.
+pub const DEFAULT_MAX_BVLC_LENGTH: u16 = 1600;
+
+/// Default maximum NPDU length accepted by a node.
+pub const DEFAULT_MAX_NPDU_LENGTH: u16 = 1497;
+
+/// Header option type for the Secure Path option.
+pub const SECURE_PATH_OPTION: u8 = 1;
+
+/// Header option type for proprietary options.
+pub const PROPRIETARY_OPTION: u8 = 31;
+
+/// BACnet error class COMMUNICATION, used by BVLC-SC NAKs.
+pub const ERROR_CLASS_COMMUNICATION: u16 = 7;
+
+/// BACnet error code NODE_DUPLICATE_VMAC, sent by a hub refusing a
+/// Connect-Request for a VMAC already in use.
+pub const ERROR_CODE_NODE_DUPLICATE_VMAC: u16 = 151;
+
+/// A BACnet/SC virtual MAC address.
+///
+/// # Examples
+///
+/// ```
+/// use bacnet_rs::datalink::bsc::Vmac;
+///
+/// let vmac = Vmac::random();
+/// assert!(!vmac.is_broadcast());
+/// assert_eq!(Vmac::BROADCAST.to_string(), "FF:FF:FF:FF:FF:FF");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Vmac(pub [u8; 6]);
+
+impl Vmac {
+    /// The broadcast VMAC.
+    pub const BROADCAST: Vmac = Vmac([0xFF; 6]);
+
+    /// Generate a random VMAC.
+    ///
+    /// The address is locally administered and unicast, as Annex AB asks
+    /// for automatically chosen VMACs.
+    pub fn random() -> Self {
+        let mut vmac = [0u8; 6];
+        random_bytes(&mut vmac);
+        vmac[0] = (vmac[0] & 0xF0) | 0x02;
+        Vmac(vmac)
+    }
+
+    /// Whether this is the broadcast VMAC.
+    pub fn is_broadcast(&self) -> bool {
+        *self == Self::BROADCAST
+    }
+}
+
+impl fmt::Display for Vmac {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [a, b, c, d, e, g] = self.0;
+        write!(
+            f,
+            "{:02X}:{:02X}:{:02X}:{:02X}:{:02X}:{:02X}",
+            a, b, c, d, e, g
+        )
+    }
+}
+
+impl From<Vmac> for DataLinkAddress {
+    fn from(vmac: Vmac) -> Self {
+        if vmac.is_broadcast() {
+            DataLinkAddress::Broadcast
+        } else {
+            DataLinkAddress::SecureConnect(vmac.0)
+        }
+    }
+}
+
+/// Generate a random (version 4) device UUID.
+pub fn random_uuid() -> [u8; 16] {
+    let mut uuid = [0u8; 16];
+    random_bytes(&mut uuid);
+    uuid[6] = (uuid[6] & 0x0F) | 0x40;
+    uuid[8] = (uuid[8] & 0x3F) | 0x80;
+    uuid
+}
+
+/// BVLC-SC function codes (Annex AB.2).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ScFunction {
+    /// BVLC-Result (0x00)
+    Result = 0x00,
+    /// Encapsulated-NPDU (0x01)
+    EncapsulatedNpdu = 0x01,
+    /// Address-Resolution (0x02)
+    AddressResolution = 0x02,
+    /// Address-Resolution-ACK (0x03)
+    AddressResolutionAck = 0x03,
+    /// Advertisement (0x04)
+    Advertisement = 0x04,
+    /// Advertisement-Solicitation (0x05)
+    AdvertisementSolicitation = 0x05,
+    /// Connect-Request (0x06)
+    ConnectRequest = 0x06,
+    /// Connect-Accept (0x07)
+    ConnectAccept = 0x07,
+    /// Disconnect-Request (0x08)
+    DisconnectRequest = 0x08,
+    /// Disconnect-ACK (0x09)
+    DisconnectAck = 0x09,
+    /// Heartbeat-Request (0x0A)
+    HeartbeatRequest = 0x0A,
+    /// Heartbeat-ACK (0x0B)
+    HeartbeatAck = 0x0B,
+    /// Proprietary-Message (0x0C)
+    Proprietary = 0x0C,
+}
+
+impl TryFrom<u8> for ScFunction {
+    type Error = DataLinkError;
+
+    fn try_from(value: u8) -> Result<Self> {
+        match value {
+            0x00 => Ok(ScFunction::Result),
+            0x01 => Ok(ScFunction::EncapsulatedNpdu),
+            0x02 => Ok(ScFunction::AddressResolution),
+            0x03 => Ok(ScFunction::AddressResolutionAck),
+            0x04 => Ok(ScFunction::Advertisement),
+            0x05 => Ok(ScFunction::AdvertisementSolicitation),
+            0x06 => Ok(ScFunction::ConnectRequest),
+            0x07 => Ok(ScFunction::ConnectAccept),
+            0x08 => Ok(ScFunction::DisconnectRequest),
+            0x09 => Ok(ScFunction::DisconnectAck),
+            0x0A => Ok(ScFunction::HeartbeatRequest),
+            0x0B => Ok(ScFunction::HeartbeatAck),
+            0x0C => Ok(ScFunction::Proprietary),
+            _ => Err(DataLinkError::InvalidFrame),
+        }
+    }
+}
+
+/// A destination or data header option.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeaderOption {
+    /// Option type, such as [`SECURE_PATH_OPTION`].
+    pub option_type: u8,
+    /// Whether a receiver that does not know the option must reject the
+    /// message.
+    pub must_understand: bool,
+    /// Option data, if the option carries any.
+    pub data: Option<Vec<u8>>,
+}
+
+impl HeaderOption {
+    /// The Secure Path option, marking a message that only crossed secure
+    /// connections.
+    pub fn secure_path() -> Self {
+        Self {
+            option_type: SECURE_PATH_OPTION,
+            must_understand: true,
+            data: None,
+        }
+    }
+}
+
+/// The error part of a BVLC-Result NAK.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScNak {
+    /// Marker of the header option that caused the error, or zero.
+    pub header_marker: u8,
+    /// BACnet error class.
+    pub error_class: u16,
+    /// BACnet error code.
+    pub error_code: u16,
+    /// Human-readable details.
+    pub details: String,
+}
+
+/// The payload of Connect-Request and Connect-Accept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConnectInfo {
+    /// VMAC of the sender.
+    pub vmac: Vmac,
+    /// Device UUID of the sender.
+    pub device_uuid: [u8; 16],
+    /// Largest BVLC message the sender accepts.
+    pub max_bvlc_length: u16,
+    /// Largest NPDU the sender accepts.
+    pub max_npdu_length: u16,
+}
+
+/// Hub connection status reported in an Advertisement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum HubConnectionStatus {
+    /// Not connected to a hub.
+    NoHubConnection = 0,
+    /// Connected to the primary hub.
+    ConnectedToPrimary = 1,
+    /// Connected to the failover hub.
+    ConnectedToFailover = 2,
+}
+
+impl TryFrom<u8> for HubConnectionStatus {
+    type Error = DataLinkError;
+
+    fn try_from(value: u8) -> Result<Self> {
+        match value {
+            0 => Ok(HubConnectionStatus::NoHubConnection),
+            1 => Ok(HubConnectionStatus::ConnectedToPrimary),
+            2 => Ok(HubConnectionStatus::ConnectedToFailover),
+            _ => Err(DataLinkError::InvalidFrame),
+        }
+    }
+}
+
+/// Function-specific payload of a BVLC-SC message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScPayload {
+    /// BVLC-Result for a function, with the error for a NAK
+    Result {
+        /// Function code of the message answered
+        function: u8,
+        /// `None` for an ACK
+        nak: Option<ScNak>,
+    },
+    /// Encapsulated-NPDU
+    EncapsulatedNpdu(Vec<u8>),
+    /// Address-Resolution
+    AddressResolution,
+    /// Address-Resolution-ACK with the node's direct-connect URIs
+    AddressResolutionAck(Vec<String>),
+    /// Advertisement
+    Advertisement {
+        /// Hub connection status of the sender
+        hub_status: HubConnectionStatus,
+        /// Whether the sender accepts direct connections
+        accepts_direct_connections: bool,
+        /// Largest BVLC message the sender accepts
+        max_bvlc_length: u16,
+        /// Largest NPDU the sender accepts
+        max_npdu_length: u16,
+    },
+    /// Advertisement-Solicitation
+    AdvertisementSolicitation,
+    /// Connect-Request
+    ConnectRequest(ConnectInfo),
+    /// Connect-Accept
+    ConnectAccept(ConnectInfo),
+    /// Disconnect-Request
+    DisconnectRequest,
+    /// Disconnect-ACK
+    DisconnectAck,
+    /// Heartbeat-Request
+    HeartbeatRequest,
+    /// Heartbeat-ACK
+    HeartbeatAck,
+    /// Proprietary-Message
+    Proprietary {
+        /// Vendor identifier
+        vendor_id: u16,
+        /// Vendor-defined function
+        function: u8,
+        /// Vendor-defined data
+        data: Vec<u8>,
+    },
+}
+
+impl ScPayload {
+    /// The BVLC-SC function carrying this payload.
+    pub fn function(&self) -> ScFunction {
+        match self {
+            ScPayload::Result { .. } => ScFunction::Result,
+            ScPayload::EncapsulatedNpdu(_) => ScFunction::EncapsulatedNpdu,
+            ScPayload::AddressResolution => ScFunction::AddressResolution,
+            ScPayload::AddressResolutionAck(_) => ScFunction::AddressResolutionAck,
+            ScPayload::Advertisement { .. } => ScFunction::Advertisement,
+            ScPayload::AdvertisementSolicitation => ScFunction::AdvertisementSolicitation,
+            ScPayload::ConnectRequest(_) => ScFunction::ConnectRequest,
+            ScPayload::ConnectAccept(_) => ScFunction::ConnectAccept,
+            ScPayload::DisconnectRequest => ScFunction::DisconnectRequest,
+            ScPayload::DisconnectAck => ScFunction::DisconnectAck,
+            ScPayload::HeartbeatRequest => ScFunction::HeartbeatRequest,
+            ScPayload::HeartbeatAck => ScFunction::HeartbeatAck,
+            ScPayload::Proprietary { .. } => ScFunction::Proprietary,
+        }
+    }
+}
+
+/// A BVLC-SC message.
+///
+/// # Examples
+///
+/// ```
+/// use bacnet_rs::datalink::bsc::{ScMessage, ScPayload, Vmac};
+///
+/// let message = ScMessage::new(7, ScPayload::EncapsulatedNpdu(vec![0x01, 0x00]))
+///     .with_destination(Vmac::BROADCAST);
+/// let encoded = message.encode().unwrap();
+/// assert_eq!(ScMessage::decode(&encoded).unwrap(), message);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScMessage {
+    /// Message ID, echoed by the response to a request.
+    pub message_id: u16,
+    /// VMAC of the node that sent the message, added by hubs when forwarding.
+    pub originating: Option<Vmac>,
+    /// VMAC of the node the message is for.
+    pub destination: Option<Vmac>,
+    /// Destination options.
+    pub destination_options: Vec<HeaderOption>,
+    /// Data options, only used with Encapsulated-NPDU.
+    pub data_options: Vec<HeaderOption>,
+    /// Function-specific payload.
+    pub payload: ScPayload,
+}
+
+impl ScMessage {
+    /// Create a message without addresses or options.
+    pub fn new(message_id: u16, payload: ScPayload) -> Self {
+        Self {
+            message_id,
+            originating: None,
+            destination: None,
+            destination_options: Vec::new(),
+            data_options: Vec::new(),
+            payload,
+        }
+    }
+
+    /// Set the originating VMAC.
+    pub fn with_originating(mut self, vmac: Vmac) -> Self {
+        self.originating = Some(vmac);
+        self
+    }
+
+    /// Set the destination VMAC.
+    pub fn with_destination(mut self, vmac: Vmac) -> Self {
+        self.destination = Some(vmac);
+        self
+    }
+
+    /// The BVLC-SC function of this message.
+    pub fn function(&self) -> ScFunction {
+        self.payload.function()
+    }
+
+    /// Encode the message.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DataLinkError::InvalidFrame`] if a header option holds more
+    /// than 65535 bytes of data.
+    pub fn encode(&self) -> Result<Vec<u8>> {
+        let mut flags = 0u8;
+        if self.originating.is_some() {
+            flags |= 0x08;
+        }
+        if self.destination.is_some() {
+            flags |= 0x04;
+        }
+        if !self.destination_options.is_empty() {
+            flags |= 0x02;
+        }
+        if !self.data_options.is_empty() {
+            flags |= 0x01;
+        }
+
+        let mut frame = vec![self.function() as u8, flags];
+        frame.extend_from_slice(&self.message_id.to_be_bytes());
+        for vmac in [self.originating, self.destination].into_iter().flatten() {
+            frame.extend_from_slice(&vmac.0);
+        }
+        encode_options(&mut frame, &self.destination_options)?;
+        encode_options(&mut frame, &self.data_options)?;
+
+        match &self.payload {
+            ScPayload::Result { function, nak } => {
+                frame.push(*function);
+                match nak {
+                    None => frame.push(0x00),
+                    Some(nak) => {
+                        frame.push(0x01);
+                        frame.push(nak.header_marker);
+                        frame.extend_from_slice(&nak.error_class.to_be_bytes());
+                        frame.extend_from_slice(&nak.error_code.to_be_bytes());
+                        frame.extend_from_slice(nak.details.as_bytes());
+                    }
+                }
+            }
+            ScPayload::EncapsulatedNpdu(npdu) => frame.extend_from_slice(npdu),
+            ScPayload::AddressResolutionAck(uris) => {
+                frame.extend_from_slice(uris.join(" ").as_bytes())
+            }
+            ScPayload::Advertisement {
+                hub_status,
+                accepts_direct_connections,
+                max_bvlc_length,
+                max_npdu_length,
+            } => {
+                frame.push(*hub_status as u8);
+                frame.push(*accepts_direct_connections as u8);
+                frame.extend_from_slice(&max_bvlc_length.to_be_bytes());
+                frame.extend_from_slice(&max_npdu_length.to_be_bytes());
+            }
+            ScPayload::ConnectRequest(info) | ScPayload::ConnectAccept(info) => {
+                frame.extend_from_slice(&info.vmac.0);
+                frame.extend_from_slice(&info.device_uuid);
+                frame.extend_from_slice(&info.max_bvlc_length.to_be_bytes());
+                frame.extend_from_slice(&info.max_npdu_length.to_be_bytes());
+            }
+            ScPayload::Proprietary {
+                vendor_id,
+                function,
+                data,
+            } => {
+                frame.extend_from_slice(&vendor_id.to_be_bytes());
+                frame.push(*function);
+                frame.extend_from_slice(data);
+            }
+            ScPayload::AddressResolution
+            | ScPayload::AdvertisementSolicitation
+            | ScPayload::DisconnectRequest
+            | ScPayload::DisconnectAck
+            | ScPayload::HeartbeatRequest
+            | ScPayload::HeartbeatAck => {}
+        }
+        Ok(frame)
+    }
+
+    /// Decode a message.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DataLinkError::InvalidFrame`] for unknown functions and
+    /// truncated or malformed messages.
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        if data.len() < 4 {
+            return Err(DataLinkError::InvalidFrame);
+        }
+        let function = ScFunction::try_from(data[0])?;
+        let flags = data[1];
+        let message_id = u16::from_be_bytes([data[2], data[3]]);
+        let mut offset = 4;
+
+        let mut read_vmac = |present: bool| -> Result<Option<Vmac>> {
+            if !present {
+                return Ok(None);
+            }
+            let bytes = data
+                .get(offset..offset + 6)
+                .ok_or(DataLinkError::InvalidFrame)?;
+            offset += 6;
+            let mut vmac = [0u8; 6];
+            vmac.copy_from_slice(bytes);
+            Ok(Some(Vmac(vmac)))
+        };
+        let originating = read_vmac(flags & 0x08 != 0)?;
+        let destination = read_vmac(flags & 0x04 != 0)?;
+        let destination_options = if flags & 0x02 != 0 {
+            decode_options(data, &mut offset)?
+        } else {
+            Vec::new()
+        };
+        let data_options = if flags & 0x01 != 0 {
+            decode_options(data, &mut offset)?
+        } else {
+            Vec::new()
+        };
+
+        let body = &data[offset..];
+        let u16_at = |index: usize| -> Result<u16> {
+            body.get(index..index + 2)
+                .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+                .ok_or(DataLinkError::InvalidFrame)
+        };
+        let payload = match function {
+            ScFunction::Result => {
+                let (&for_function, rest) =
+                    body.split_first().ok_or(DataLinkError::InvalidFrame)?;
+                let nak = match rest.first() {
+                    Some(0x00) => None,
+                    Some(0x01) if rest.len() >= 6 => Some(ScNak {
+                        header_marker: rest[1],
+                        error_class: u16_at(3)?,
+                        error_code: u16_at(5)?,
+                        details: String::from_utf8(rest[6..].to_vec())
+                            .map_err(|_| DataLinkError::InvalidFrame)?,
+                    }),
+                    _ => return Err(DataLinkError::InvalidFrame),
+                };
+                ScPayload::Result {
+                    function: for_function,
+                    nak,
+                }
+            }
+            ScFunction::EncapsulatedNpdu => ScPayload::EncapsulatedNpdu(body.to_vec()),
+            ScFunction::AddressResolution => ScPayload::AddressResolution,
+            ScFunction::AddressResolutionAck => {
+                let uris = core::str::from_utf8(body).map_err(|_| DataLinkError::InvalidFrame)?;
+                ScPayload::AddressResolutionAck(
+                    uris.split_whitespace().map(str::to_string).collect(),
+                )
+            }
+            ScFunction::Advertisement => {
+                if body.len() < 6 {
+                    return Err(DataLinkError::InvalidFrame);
+                }
+                ScPayload::Advertisement {
+                    hub_status: HubConnectionStatus::try_from(body[0])?,
+                    accepts_direct_connections: body[1] != 0,
+                    max_bvlc_length: u16_at(2)?,
+                    max_npdu_length: u16_at(4)?,
+                }
+            }
+            ScFunction::AdvertisementSolicitation => ScPayload::AdvertisementSolicitation,
+            ScFunction::ConnectRequest | ScFunction::ConnectAccept => {
+                if body.len() < 26 {
+                    return Err(DataLinkError::InvalidFrame);
+                }
+                let mut vmac = [0u8; 6];
+                vmac.copy_from_slice(&body[..6]);
+                let mut device_uuid = [0u8; 16];
+                device_uuid.copy_from_slice(&body[6..22]);
+                let info = ConnectInfo {
+                    vmac: Vmac(vmac),
+                    device_uuid,
+                    max_bvlc_length: u16_at(22)?,
+                    max_npdu_length: u16_at(24)?,
+                };
+                if function == ScFunction::ConnectRequest {
+                    ScPayload::ConnectRequest(info)
+                } else {
+                    ScPayload::ConnectAccept(info)
+                }
+            }
+            ScFunction::DisconnectRequest => ScPayload::DisconnectRequest,
+            ScFunction::DisconnectAck => ScPayload::DisconnectAck,
+            ScFunction::HeartbeatRequest => ScPayload::HeartbeatRequest,
+            ScFunction::HeartbeatAck => ScPayload::HeartbeatAck,
+            ScFunction::Proprietary => {
+                if body.len() < 3 {
+                    return Err(DataLinkError::InvalidFrame);
+                }
+                ScPayload::Proprietary {
+                    vendor_id: u16_at(0)?,
+                    function: body[2],
+                    data: body[3..].to_vec(),
+                }
+            }
+        };
+
+        Ok(Self {
+            message_id,
+            originating,
+            destination,
+            destination_options,
+            data_options,
+            payload,
+        })
+    }
+
+    /// A reply to this message with `payload`, echoing its message ID.
+    pub fn reply(&self, payload: ScPayload) -> Self {
+        Self::new(self.message_id, payload)
+    }
+}
+
+fn encode_options(frame: &mut Vec<u8>, options: &[HeaderOption]) -> Result<()> {
+    for (index, option) in options.iter().enumerate() {
+        let mut marker = option.option_type & 0x1F;
+        if index + 1 < options.len() {
+            marker |= 0x80;
+        }
+        if option.must_understand {
+            marker |= 0x40;
+        }
+        if option.data.is_some() {
+            marker |= 0x20;
+        }
+        frame.push(marker);
+        if let Some(data) = &option.data {
+            let length = u16::try_from(data.len()).map_err(|_| DataLinkError::InvalidFrame)?;
+            frame.extend_from_slice(&length.to_be_bytes());
+            frame.extend_from_slice(data);
+        }
+    }
+    Ok(())
+}
+
+fn decode_options(data: &[u8], offset: &mut usize) -> Result<Vec<HeaderOption>> {
+    let mut options = Vec::new();
+    loop {
+        let marker = *data.get(*offset).ok_or(DataLinkError::InvalidFrame)?;
+        *offset += 1;
+        let option_data = if marker & 0x20 != 0 {
+            let length = data
+                .get(*offset..*offset + 2)
+                .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]) as usize)
+                .ok_or(DataLinkError::InvalidFrame)?;
+            *offset += 2;
+            let bytes = data
+                .get(*offset..*offset + length)
+                .ok_or(DataLinkError::InvalidFrame)?;
+            *offset += length;
+            Some(bytes.to_vec())
+        } else {
+            None
+        };
+        options.push(HeaderOption {
+            option_type: marker & 0x1F,
+            must_understand: marker & 0x40 != 0,
+            data: option_data,
+        });
+        if marker & 0x80 == 0 {
+            return Ok(options);
+        }
+    }
+}
+
+/// Configuration of a BACnet/SC node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScNodeConfig {
+    /// URI of the primary hub.
+    pub primary_hub_uri: String,
+    /// URI of the failover hub, empty when there is none.
+    pub failover_hub_uri: String,
+    /// VMAC of this node.
+    pub vmac: Vmac,
+    /// Device UUID of this node, kept across restarts.
+    pub device_uuid: [u8; 16],
+    /// Largest BVLC message this node accepts.
+    pub max_bvlc_length: u16,
+    /// Largest NPDU this node accepts.
+    pub max_npdu_length: u16,
+    /// Delay before the first reconnect attempt.
+    pub minimum_reconnect_time: Duration,
+    /// Longest delay between reconnect attempts.
+    pub maximum_reconnect_time: Duration,
+    /// How long to wait for a Connect-Accept.
+    pub connect_wait_timeout: Duration,
+    /// How long to wait for a Disconnect-ACK.
+    pub disconnect_wait_timeout: Duration,
+    /// Idle time before a Heartbeat-Request is sent.
+    pub heartbeat_timeout: Duration,
+}
+
+impl ScNodeConfig {
+    /// Create a configuration for `primary_hub_uri` with a random VMAC and
+    /// UUID and the default Network Port timings.
+    pub fn new(primary_hub_uri: &str) -> Self {
+        Self::from_settings(&ScPortSettings {
+            primary_hub_uri: primary_hub_uri.to_string(),
+            ..Default::default()
+        })
+    }
+
+    /// Create a configuration from the settings of a BACnet/SC Network Port,
+    /// with a random VMAC and UUID.
+    pub fn from_settings(settings: &ScPortSettings) -> Self {
+        let seconds = |value: u16| Duration::from_secs(value as u64);
+        Self {
+            primary_hub_uri: settings.primary_hub_uri.clone(),
+            failover_hub_uri: settings.failover_hub_uri.clone(),
+            vmac: Vmac::random(),
+            device_uuid: random_uuid(),
+            max_bvlc_length: DEFAULT_MAX_BVLC_LENGTH,
+            max_npdu_length: DEFAULT_MAX_NPDU_LENGTH,
+            minimum_reconnect_time: seconds(settings.minimum_reconnect_time),
+            maximum_reconnect_time: seconds(settings.maximum_reconnect_time),
+            connect_wait_timeout: seconds(settings.connect_wait_timeout),
+            disconnect_wait_timeout: seconds(settings.disconnect_wait_timeout),
+            heartbeat_timeout: seconds(settings.heartbeat_timeout),
+        }
+    }
+
+    /// The Connect-Request payload for this node.
+    pub fn connect_info(&self) -> ConnectInfo {
+        ConnectInfo {
+            vmac: self.vmac,
+            device_uuid: self.device_uuid,
+            max_bvlc_length: self.max_bvlc_length,
+            max_npdu_length: self.max_npdu_length,
+        }
+    }
+
+    /// The URI of `hub`.
+    pub fn hub_uri(&self, hub: Hub) -> &str {
+        match hub {
+            Hub::Primary => &self.primary_hub_uri,
+            Hub::Failover => &self.failover_hub_uri,
+        }
+    }
+}
+
+/// One of the two hubs a node may connect to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hub {
+    /// The primary hub.
+    Primary,
+    /// The failover hub.
+    Failover,
+}
+
+/// State of a node's hub connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScConnectionState {
+    /// No connection; one may be scheduled.
+    Idle,
+    /// Connect-Request sent, waiting for Connect-Accept.
+    AwaitingAccept,
+    /// Connected to a hub.
+    Connected,
+    /// Disconnect-Request sent, waiting for Disconnect-ACK.
+    Disconnecting,
+}
+
+/// What [`HubConnector::poll`] asks the datalink to do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HubAction {
+    /// Open a WebSocket to the hub and send a Connect-Request.
+    Connect(Hub),
+    /// Send a Heartbeat-Request.
+    SendHeartbeat,
+    /// Close the WebSocket; the connection failed or ended.
+    Close,
+}
+
+/// Hub connection timing for a BACnet/SC node (Annex AB.6).
+///
+/// The connector tries the primary hub first and the failover hub straight
+/// after a failed attempt. When both fail, it waits the minimum reconnect
+/// time before starting over, doubling the wait after each failed round up
+/// to the maximum reconnect time. A node on the failover hub stays there
+/// until that connection ends.
+#[derive(Debug, Clone)]
+pub struct HubConnector {
+    /// Whether a failover hub is configured.
+    has_failover: bool,
+
+    /// Timing parameters.
+    minimum_reconnect_time: Duration,
+    maximum_reconnect_time: Duration,
+    connect_wait_timeout: Duration,
+    disconnect_wait_timeout: Duration,
+    heartbeat_timeout: Duration,
+
+    /// Current state.
+    state: ScConnectionState,
+
+    /// Hub connected to, or attempted next.
+    hub: Hub,
+
+    /// When the state last changed.
+    since: Instant,
+
+    /// When the next connection attempt is due, `None` when stopped.
+    next_attempt: Option<Instant>,
+
+    /// Wait after the next failed round.
+    reconnect_delay: Duration,
+
+    /// When a message was last received on the connection.
+    last_received: Instant,
+
+    /// When a Heartbeat-Request was last sent.
+    last_heartbeat: Option<Instant>,
+}
+
+impl HubConnector {
+    /// Create a connector for `config`, with an attempt on the primary hub
+    /// due at once.
+    pub fn new(config: &ScNodeConfig, now: Instant) -> Self {
+        Self {
+            has_failover: !config.failover_hub_uri.is_empty(),
+            minimum_reconnect_time: config.minimum_reconnect_time,
+            maximum_reconnect_time: config.maximum_reconnect_time,
+            connect_wait_timeout: config.connect_wait_timeout,
+            disconnect_wait_timeout: config.disconnect_wait_timeout,
+            heartbeat_timeout: config.heartbeat_timeout,
+            state: ScConnectionState::Idle,
+            hub: Hub::Primary,
+            since: now,
+            next_attempt: Some(now),
+            reconnect_delay: config.minimum_reconnect_time,
+            last_received: now,
+            last_heartbeat: None,
+        }
+    }
+
+    /// The current state.
+    pub fn state(&self) -> ScConnectionState {
+        self.state
+    }
+
+    /// The hub connected to, or attempted next.
+    pub fn hub(&self) -> Hub {
+        self.hub
+    }
+
+    /// The hub connection status, as reported in Advertisements.
+    pub fn status(&self) -> HubConnectionStatus {
+        match (self.state, self.hub) {
+            (ScConnectionState::Connected, Hub::Primary) => HubConnectionStatus::ConnectedToPrimary,
+            (ScConnectionState::Connected, Hub::Failover) => {
+                HubConnectionStatus::ConnectedToFailover
+            }
+            _ => HubConnectionStatus::NoHubConnection,
+        }
+    }
+
+    /// Schedule an attempt on the primary hub at once.
+    pub fn start(&mut self, now: Instant) {
+        self.hub = Hub::Primary;
+        self.reconnect_delay = self.minimum_reconnect_time;
+        self.next_attempt = Some(now);
+    }
+
+    /// The action due now, if any.
+    pub fn poll(&mut self, now: Instant) -> Option<HubAction> {
+        let elapsed = now.duration_since(self.since);
+        match self.state {
+            ScConnectionState::Idle => {
+                let due = self.next_attempt.is_some_and(|at| now >= at);
+                due.then_some(HubAction::Connect(self.hub))
+            }
+            ScConnectionState::AwaitingAccept if elapsed >= self.connect_wait_timeout => {
+                self.attempt_failed(now);
+                Some(HubAction::Close)
+            }
+            ScConnectionState::Connected => {
+                let silent = now.duration_since(self.last_received);
+                if silent >= self.heartbeat_timeout * 2 {
+                    self.closed(now);
+                    return Some(HubAction::Close);
+                }
+                let heartbeat_due = self
+                    .last_heartbeat
+                    .is_none_or(|sent| now.duration_since(sent) >= self.heartbeat_timeout);
+                if silent >= self.heartbeat_timeout && heartbeat_due {
+                    self.last_heartbeat = Some(now);
+                    return Some(HubAction::SendHeartbeat);
+                }
+                None
+            }
+            ScConnectionState::Disconnecting if elapsed >= self.disconnect_wait_timeout => {
+                self.stopped(now);
+                Some(HubAction::Close)
+            }
+            _ => None,
+        }
+    }
+
+    /// Record that the WebSocket opened and the Connect-Request went out.
+    pub fn attempt_started(&mut self, now: Instant) {
+        self.enter(ScConnectionState::AwaitingAccept, now);
+    }
+
+    /// Record that the attempt on the current hub failed.
+    pub fn attempt_failed(&mut self, now: Instant) {
+        self.enter(ScConnectionState::Idle, now);
+        if self.hub == Hub::Primary && self.has_failover {
+            self.hub = Hub::Failover;
+            self.next_attempt = Some(now);
+        } else {
+            self.hub = Hub::Primary;
+            self.next_attempt = Some(now + self.reconnect_delay);
+            self.reconnect_delay = (self.reconnect_delay * 2).min(self.maximum_reconnect_time);
+        }
+    }
+
+    /// Record that the hub accepted the connection.
+    pub fn accepted(&mut self, now: Instant) {
+        self.enter(ScConnectionState::Connected, now);
+        self.reconnect_delay = self.minimum_reconnect_time;
+        self.last_received = now;
+        self.last_heartbeat = None;
+    }
+
+    /// Record that a message arrived from the hub.
+    pub fn received(&mut self, now: Instant) {
+        self.last_received = now;
+        self.last_heartbeat = None;
+    }
+
+    /// Record that the Disconnect-Request went out.
+    pub fn disconnect_started(&mut self, now: Instant) {
+        self.enter(ScConnectionState::Disconnecting, now);
+    }
+
+    /// Record that the connection ended without this node asking; an
+    /// attempt on the primary hub follows after the minimum reconnect time.
+    pub fn closed(&mut self, now: Instant) {
+        self.enter(ScConnectionState::Idle, now);
+        self.hub = Hub::Primary;
+        self.next_attempt = Some(now + self.minimum_reconnect_time);
+    }
+
+    /// Record that this node closed the connection; no attempt follows
+    /// until [`start`](Self::start).
+    pub fn stopped(&mut self, now: Instant) {
+        self.enter(ScConnectionState::Idle, now);
+        self.next_attempt = None;
+    }
+
+    fn enter(&mut self, state: ScConnectionState, now: Instant) {
+        self.state = state;
+        self.since = now;
+    }
+}
+
+/// Opens the TLS sessions a BACnet/SC node runs its WebSockets over.
+///
+/// The connector authenticates the hub and presents the device's
+/// operational certificate. Streams should have a read timeout so that
+/// [`DataLink::receive_frame`] returns when nothing arrives.
+pub trait ScConnector: Send + Sync {
+    /// The TLS session type.
+    type Stream: Read + Write + Send + Sync;
+
+    /// Open a TLS session to the host and port of `uri`.
+    fn connect(&mut self, uri: &WebSocketUri) -> io::Result<Self::Stream>;
+}
+
+/// BACnet/SC node datalink connected through a hub.
+///
+/// Unicast NPDUs to a peer with an established direct connection go over
+/// that connection; everything else goes through the hub.
+pub struct ScDataLink<C: ScConnector, A: ScAcceptor = NoDirectAcceptor> {
+    /// Node configuration.
+    config: ScNodeConfig,
+
+    /// Opens TLS sessions.
+    connector: C,
+
+    /// Connection timing.
+    hub: HubConnector,
+
+    /// WebSocket to the hub, while one is open.
+    socket: Option<WebSocket<C::Stream>>,
+
+    /// Connect-Accept payload of the current hub.
+    hub_info: Option<ConnectInfo>,
+
+    /// Message ID of the last message originated.
+    message_id: u16,
+
+    /// Accepts direct connections, with the non-blocking listening socket.
+    direct_acceptor: Option<(A, TcpListener)>,
+
+    /// URIs offered in Address-Resolution-ACKs.
+    direct_uris: Vec<String>,
+
+    /// Direct connections this node initiated.
+    outbound: Vec<DirectLink<C::Stream>>,
+
+    /// Direct connections peers initiated.
+    inbound: Vec<DirectLink<A::Stream>>,
+
+    /// Peers asked for their direct-connect URIs.
+    resolving: Vec<Vmac>,
+
+    /// NPDUs received on direct connections, not yet returned.
+    pending: VecDeque<(Vec<u8>, DataLinkAddress)>,
+}
+
+impl<C: ScConnector> ScDataLink<C> {
+    /// Create a node that connects to its hub on first use.
+    pub fn new(config: ScNodeConfig, connector: C) -> Self {
+        let hub = HubConnector::new(&config, Instant::now());
+        Self {
+            config,
+            connector,
+            hub,
+            socket: None,
+            hub_info: None,
+            message_id: 0,
+            direct_acceptor: None,
+            direct_uris: Vec::new(),
+            outbound: Vec::new(),
+            inbound: Vec::new(),
+            resolving: Vec::new(),
+            pending: VecDeque::new(),
+        }
+    }
+}
+
+impl<C: ScConnector, A: ScAcceptor> ScDataLink<C, A> {
+    /// Accept direct connections from peers on `addr`.
+    ///
+    /// `uris` are the WebSocket URIs peers reach the listener at, offered
+    /// in answers to Address-Resolution.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the address cannot be bound.
+    pub fn accept_direct_connections<B: ScAcceptor, T: ToSocketAddrs>(
+        self,
+        addr: T,
+        acceptor: B,
+        uris: Vec<String>,
+    ) -> Result<ScDataLink<C, B>> {
+        let listener = TcpListener::bind(addr).map_err(DataLinkError::IoError)?;
+        listener
+            .set_nonblocking(true)
+            .map_err(DataLinkError::IoError)?;
+        for mut link in self.inbound {
+            link.close();
+        }
+        Ok(ScDataLink {
+            config: self.config,
+            connector: self.connector,
+            hub: self.hub,
+            socket: self.socket,
+            hub_info: self.hub_info,
+            message_id: self.message_id,
+            direct_acceptor: Some((acceptor, listener)),
+            direct_uris: uris,
+            outbound: self.outbound,
+            inbound: Vec::new(),
+            resolving: self.resolving,
+            pending: self.pending,
+        })
+    }
+
+    /// The node configuration.
+    pub fn config(&self) -> &ScNodeConfig {
+        &self.config
+    }
+
+    /// The VMAC of this node.
+    pub fn vmac(&self) -> Vmac {
+        self.config.vmac
+    }
+
+    /// The hub connection state.
+    pub fn state(&self) -> ScConnectionState {
+        self.hub.state()
+    }
+
+    /// The hub connection status.
+    pub fn hub_status(&self) -> HubConnectionStatus {
+        self.hub.status()
+    }
+
+    /// The Connect-Accept payload of the hub connected to.
+    pub fn hub_info(&self) -> Option<&ConnectInfo> {
+        self.hub_info.as_ref()
+    }
+
+    /// Set the URIs offered in answers to Address-Resolution, as when they
+    /// depend on the port the listener was bound to.
+    pub fn set_direct_uris(&mut self, uris: Vec<String>) {
+        self.direct_uris = uris;
+    }
+
+    /// Whether this node accepts direct connections.
+    pub fn accepts_direct_connections(&self) -> bool {
+        self.direct_acceptor.is_some()
+    }
+
+    /// The address the direct-connection listener is bound to.
+    pub fn direct_listen_address(&self) -> Option<SocketAddr> {
+        let (_, listener) = self.direct_acceptor.as_ref()?;
+        listener.local_addr().ok()
+    }
+
+    /// Peers with an established direct connection.
+    pub fn direct_peers(&self) -> Vec<Vmac> {
+        let outbound = self.outbound.iter().filter_map(DirectLink::peer_vmac);
+        let inbound = self.inbound.iter().filter_map(DirectLink::peer_vmac);
+        outbound.chain(inbound).collect()
+    }
+
+    /// Start a direct connection to `peer`.
+    ///
+    /// Sends an Address-Resolution through the hub; the connection opens
+    /// when the peer's Address-Resolution-ACK arrives, during a later
+    /// [`DataLink::receive_frame`]. Until then, and if the peer offers no
+    /// usable URI, traffic to the peer keeps going through the hub.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the hub is not connected.
+    pub fn connect_direct(&mut self, peer: Vmac) -> Result<()> {
+        if self.direct_peers().contains(&peer) || self.resolving.contains(&peer) {
+            return Ok(());
+        }
+        let request = ScMessage::new(self.next_message_id(), ScPayload::AddressResolution)
+            .with_destination(peer);
+        self.send_message(&request)?;
+        self.resolving.push(peer);
+        Ok(())
+    }
+
+    /// Close the direct connection to `peer`, if there is one.
+    pub fn disconnect_direct(&mut self, peer: Vmac) {
+        self.resolving.retain(|vmac| *vmac != peer);
+        for link in &mut self.outbound {
+            if link.peer_vmac() == Some(peer) {
+                link.close();
+            }
+        }
+        for link in &mut self.inbound {
+            if link.peer_vmac() == Some(peer) {
+                link.close();
+            }
+        }
+        self.outbound.retain(|link| link.peer_vmac() != Some(peer));
+        self.inbound.retain(|link| link.peer_vmac() != Some(peer));
+    }
+
+    /// Reconnect after a [`disconnect`](Self::disconnect), starting with the
+    /// primary hub.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the connection cannot be opened; further attempts
+    /// follow on schedule.
+    pub fn connect(&mut self) -> Result<()> {
+        if self.state() == ScConnectionState::Idle {
+            self.hub.start(Instant::now());
+        }
+        self.maintain()
+    }
+
+    /// Carry out whatever the connection timing asks for: connection
+    /// attempts, heartbeats and closing failed connections, for the hub as
+    /// well as for direct connections, and accept new direct connections.
+    ///
+    /// Called by [`DataLink::send_frame`] and [`DataLink::receive_frame`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of a failed hub connection attempt or heartbeat.
+    pub fn maintain(&mut self) -> Result<()> {
+        let now = Instant::now();
+        self.accept_direct(now);
+        let config = &self.config;
+        self.outbound.retain_mut(|link| link.maintain(config, now));
+        self.inbound.retain_mut(|link| link.maintain(config, now));
+
+        match self.hub.poll(now) {
+            Some(HubAction::Connect(hub)) => {
+                if let Err(e) = self.open(hub) {
+                    self.socket = None;
+                    self.hub.attempt_failed(now);
+                    return Err(e);
+                }
+                self.hub.attempt_started(now);
+            }
+            Some(HubAction::SendHeartbeat) => {
+                let message = ScMessage::new(self.next_message_id(), ScPayload::HeartbeatRequest);
+                self.send_message(&message)?;
+            }
+            Some(HubAction::Close) => self.drop_socket(),
+            None => {}
+        }
+        Ok(())
+    }
+
+    /// Disconnect from the hub gracefully.
+    ///
+    /// Sends a Disconnect-Request and waits up to the disconnect wait
+    /// timeout for the Disconnect-ACK. The node stays disconnected until
+    /// [`connect`](Self::connect).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the Disconnect-Request cannot be sent.
+    pub fn disconnect(&mut self) -> Result<()> {
+        let now = Instant::now();
+        if self.socket.is_none() {
+            self.hub.stopped(now);
+            return Ok(());
+        }
+
+        let request = ScMessage::new(self.next_message_id(), ScPayload::DisconnectRequest);
+        let sent = self.send_message(&request);
+        self.hub.disconnect_started(now);
+        if sent.is_ok() {
+            let deadline = now + self.config.disconnect_wait_timeout;
+            while let Some(socket) = self.socket.as_mut() {
+                match socket.receive() {
+                    Ok(data) => {
+                        if let Ok(message) = ScMessage::decode(&data) {
+                            if message.payload == ScPayload::DisconnectAck {
+                                break;
+                            }
+                        }
+                    }
+                    Err(e)
+                        if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut)
+                            && Instant::now() < deadline => {}
+                    Err(_) => break,
+                }
+            }
+        }
+        self.drop_socket();
+        self.hub.stopped(Instant::now());
+        sent
+    }
+
+    /// Send a BVLC-SC message to the hub.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no WebSocket is open or sending fails.
+    pub fn send_message(&mut self, message: &ScMessage) -> Result<()> {
+        let frame = message.encode()?;
+        let socket = self.socket.as_mut().ok_or_else(not_connected)?;
+        if let Err(e) = socket.send_binary(&frame) {
+            self.connection_lost();
+            return Err(DataLinkError::IoError(e));
+        }
+        Ok(())
+    }
+
+    fn open(&mut self, hub: Hub) -> Result<()> {
+        let uri: WebSocketUri = self
+            .config
+            .hub_uri(hub)
+            .parse()
+            .map_err(|e: io::Error| DataLinkError::AddressError(e.to_string()))?;
+        let stream = self
+            .connector
+            .connect(&uri)
+            .map_err(DataLinkError::IoError)?;
+        let socket = WebSocket::connect(
+            stream,
+            &uri.host_header(),
+            &uri.path,
+            HUB_SUBPROTOCOL,
+            self.config.connect_wait_timeout,
+        )
+        .map_err(DataLinkError::IoError)?;
+        self.socket = Some(socket);
+        self.hub_info = None;
+
+        let request = ScMessage::new(
+            self.next_message_id(),
+            ScPayload::ConnectRequest(self.config.connect_info()),
+        );
+        self.send_message(&request)
+    }
+
+    fn next_message_id(&mut self) -> u16 {
+        self.message_id = self.message_id.wrapping_add(1);
+        self.message_id
+    }
+
+    fn drop_socket(&mut self) {
+        if let Some(mut socket) = self.socket.take() {
+            let _ = socket.close();
+        }
+        self.hub_info = None;
+    }
+
+    fn connection_lost(&mut self) {
+        let now = Instant::now();
+        self.drop_socket();
+        match self.hub.state() {
+            ScConnectionState::AwaitingAccept => self.hub.attempt_failed(now),
+            ScConnectionState::Disconnecting => self.hub.stopped(now),
+            _ => self.hub.closed(now),
+        }
+    }
+
+    /// Accept waiting direct connections; refused handshakes only lose that
+    /// connection.
+    fn accept_direct(&mut self, now: Instant) {
+        let Some((acceptor, listener)) = self.direct_acceptor.as_mut() else {
+            return;
+        };
+        while let Ok((stream, _)) = listener.accept() {
+            let accepted = stream
+                .set_nonblocking(false)
+                .and_then(|_| acceptor.accept(stream));
+            let Ok((stream, certificate)) = accepted else {
+                continue;
+            };
+            if certificate.is_empty() {
+                continue;
+            }
+            let timeout = self.config.connect_wait_timeout;
+            if let Ok(socket) = WebSocket::accept(stream, &[DIRECT_SUBPROTOCOL], timeout) {
+                self.inbound
+                    .push(DirectLink::accept(socket, certificate, now));
+            }
+        }
+    }
+
+    /// Open a direct connection to `peer` at the first of `uris` that works.
+    fn open_direct(&mut self, peer: Vmac, uris: &[String]) {
+        for uri in uris {
+            let Ok(uri) = uri.parse::<WebSocketUri>() else {
+                continue;
+            };
+            let Ok(stream) = self.connector.connect(&uri) else {
+                continue;
+            };
+            let opened = WebSocket::connect(
+                stream,
+                &uri.host_header(),
+                &uri.path,
+                DIRECT_SUBPROTOCOL,
+                self.config.connect_wait_timeout,
+            )
+            .and_then(|socket| DirectLink::initiate(socket, peer, &self.config, Instant::now()));
+            if let Ok(link) = opened {
+                self.outbound.push(link);
+                return;
+            }
+        }
+    }
+
+    /// Handle every message waiting on the direct connections.
+    fn service_direct(&mut self) {
+        let now = Instant::now();
+        let (config, pending) = (&self.config, &mut self.pending);
+        self.outbound
+            .retain_mut(|link| link.service(config, now, |_, _| true, pending));
+        if let Some((acceptor, _)) = &self.direct_acceptor {
+            let authorize =
+                |certificate: &[u8], node: &ConnectInfo| acceptor.authorize(certificate, node);
+            self.inbound
+                .retain_mut(|link| link.service(config, now, authorize, pending));
+        }
+    }
+
+    /// Send an NPDU over the direct connection to `peer`, if one is up.
+    ///
+    /// Returns `false` when there is none or it just failed, so the NPDU
+    /// should go through the hub.
+    fn send_direct(&mut self, peer: Vmac, npdu: &[u8]) -> bool {
+        fn send<S: Read + Write>(links: &mut Vec<DirectLink<S>>, peer: Vmac, npdu: &[u8]) -> bool {
+            let Some(index) = links.iter().position(|link| link.peer_vmac() == Some(peer)) else {
+                return false;
+            };
+            if links[index].send_npdu(npdu).is_ok() {
+                return true;
+            }
+            links.remove(index);
+            false
+        }
+        send(&mut self.outbound, peer, npdu) || send(&mut self.inbound, peer, npdu)
+    }
+
+    /// Handle a message from the hub, returning any NPDU it delivers.
+    fn process_message(
+        &mut self,
+        message: ScMessage,
+    ) -> Result<Option<(Vec<u8>, DataLinkAddress)>> {
+        let now = Instant::now();
+        self.hub.received(now);
+        match (&message.payload, self.hub.state()) {
+            (ScPayload::ConnectAccept(info), ScConnectionState::AwaitingAccept) => {
+                self.hub_info = Some(*info);
+                self.hub.accepted(now);
+            }
+            (
+                ScPayload::Result {
+                    function,
+                    nak: Some(nak),
+                },
+                ScConnectionState::AwaitingAccept,
+            ) if *function == ScFunction::ConnectRequest as u8 => {
+                // Another node holds the VMAC: pick a new one for the next attempt
+                if nak.error_code == ERROR_CODE_NODE_DUPLICATE_VMAC {
+                    self.config.vmac = Vmac::random();
+                }
+                self.drop_socket();
+                self.hub.attempt_failed(now);
+            }
+            (ScPayload::EncapsulatedNpdu(npdu), ScConnectionState::Connected) => {
+                if let Some(source) = message.originating {
+                    return Ok(Some((npdu.clone(), source.into())));
+                }
+            }
+            (ScPayload::HeartbeatRequest, _) => {
+                self.send_message(&message.reply(ScPayload::HeartbeatAck))?
+            }
+            (ScPayload::DisconnectRequest, _) => {
+                let _ = self.send_message(&message.reply(ScPayload::DisconnectAck));
+                self.drop_socket();
+                self.hub.closed(now);
+            }
+            (ScPayload::AdvertisementSolicitation, _) => {
+                let mut advertisement = message.reply(ScPayload::Advertisement {
+                    hub_status: self.hub.status(),
+                    accepts_direct_connections: self.accepts_direct_connections(),
+                    max_bvlc_length: self.config.max_bvlc_length,
+                    max_npdu_length: self.config.max_npdu_length,
+                });
+                advertisement.destination = message.originating;
+                self.send_message(&advertisement)?;
+            }
+            (ScPayload::AddressResolution, _) => {
+                let Some(origin) = message.originating else {
+                    return Ok(None);
+                };
+                // Nodes that do not accept direct connections offer no URIs
+                let uris = if self.accepts_direct_connections() {
+                    self.direct_uris.clone()
+                } else {
+                    Vec::new()
+                };
+                let ack = message
+                    .reply(ScPayload::AddressResolutionAck(uris))
+                    .with_destination(origin);
+                self.send_message(&ack)?;
+            }
+            (ScPayload::AddressResolutionAck(uris), _) => {
+                let Some(peer) = message.originating else {
+                    return Ok(None);
+                };
+                if let Some(index) = self.resolving.iter().position(|vmac| *vmac == peer) {
+                    self.resolving.remove(index);
+                    self.open_direct(peer, uris);
+                }
+            }
+            (ScPayload::Result { function, .. }, _)
+                if *function == ScFunction::AddressResolution as u8 =>
+            {
+                self.resolving
+                    .retain(|vmac| Some(*vmac) != message.originating);
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+}
+
+impl<C: ScConnector, A: ScAcceptor> fmt::Debug for ScDataLink<C, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScDataLink")
+            .field("vmac", &self.config.vmac)
+            .field("state", &self.hub.state())
+            .field("hub", &self.hub.hub())
+            .field("direct_peers", &self.direct_peers())
+            .finish()
+    }
+}
+
+pub(crate) fn not_connected() -> DataLinkError {
+    DataLinkError::IoError(io::Error::new(
+        ErrorKind::NotConnected,
+        "Not connected to a BACnet/SC hub",
+    ))
+}
+
+impl<C: ScConnector, A: ScAcceptor> DataLink for ScDataLink<C, A> {
+    fn send_frame(&mut self, frame: &[u8], dest: &DataLinkAddress) -> Result<()> {
+        let destination = match dest {
+            DataLinkAddress::SecureConnect(vmac) => Vmac(*vmac),
+            DataLinkAddress::Broadcast => Vmac::BROADCAST,
+            _ => {
+                return Err(DataLinkError::AddressError(
+                    "Invalid address type for BACnet/SC".into(),
+                ))
+            }
+        };
+        if frame.len() > self.config.max_npdu_length as usize {
+            return Err(DataLinkError::InvalidFrame);
+        }
+
+        // A hub failure need not stop traffic on a direct connection
+        let maintained = self.maintain();
+        if !destination.is_broadcast() && self.send_direct(destination, frame) {
+            return Ok(());
+        }
+        maintained?;
+        if self.state() != ScConnectionState::Connected {
+            return Err(not_connected());
+        }
+        let message = ScMessage::new(
+            self.next_message_id(),
+            ScPayload::EncapsulatedNpdu(frame.to_vec()),
+        )
+        .with_destination(destination);
+        self.send_message(&message)
+    }
+
+    fn receive_frame(&mut self) -> Result<(Vec<u8>, DataLinkAddress)> {
+        let maintained = self.maintain();
+        if self.pending.is_empty() {
+            self.service_direct();
+        }
+        if let Some(frame) = self.pending.pop_front() {
+            return Ok(frame);
+        }
+        maintained?;
+        loop {
+            let socket = self.socket.as_mut().ok_or_else(not_connected)?;
+            let data = match socket.receive() {
+                Ok(data) => data,
+                Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                    return Err(DataLinkError::IoError(e))
+                }
+                Err(e) => {
+                    self.connection_lost();
+                    return Err(DataLinkError::IoError(e));
+                }
+            };
+            let Ok(message) = ScMessage::decode(&data) else {
+                continue;
+            };
+            if let Some(frame) = self.process_message(message)? {
+                return Ok(frame);
+            }
+        }
+    }
+
+    fn link_type(&self) -> DataLinkType {
+        DataLinkType::SecureConnect
+    }
+
+    fn local_address(&self) -> DataLinkAddress {
+        DataLinkAddress::SecureConnect(self.config.vmac.0)
+    }
+
+    fn broadcast_address(&self) -> DataLinkAddress {
+        DataLinkAddress::SecureConnect(Vmac::BROADCAST.0)
+    }
+
+    fn max_npdu_length(&self) -> usize {
+        self.config.max_npdu_length as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::{TcpListener, TcpStream};
+
+    struct PlainConnector;
+
+    impl ScConnector for PlainConnector {
+        type Stream = TcpStream;
+
+        fn connect(&mut self, uri: &WebSocketUri) -> io::Result<TcpStream> {
+            let stream = TcpStream::connect((uri.host.as_str(), uri.port))?;
+            stream.set_read_timeout(Some(Duration::from_millis(50)))?;
+            Ok(stream)
+        }
+    }
+
+    #[test]
+    fn test_message_codec() {
+        let info = ConnectInfo {
+            vmac: Vmac([0x02, 0x11, 0x22, 0x33, 0x44, 0x55]),
+            device_uuid: [0xAB; 16],
+            max_bvlc_length: 1600,
+            max_npdu_length: 1497,
+        };
+        let encoded = ScMessage::new(0x1234, ScPayload::ConnectRequest(info))
+            .encode()
+            .unwrap();
+        assert_eq!(
+            &encoded[..10],
+            &[0x06, 0x00, 0x12, 0x34, 0x02, 0x11, 0x22, 0x33, 0x44, 0x55]
+        );
+        assert_eq!(&encoded[26..], &[0x06, 0x40, 0x05, 0xD9]);
+
+        let messages = [
+            ScMessage::new(1, ScPayload::EncapsulatedNpdu(vec![0x01, 0x20]))
+                .with_originating(info.vmac)
+                .with_destination(Vmac::BROADCAST),
+            ScMessage {
+                destination_options: vec![
+                    HeaderOption::secure_path(),
+                    HeaderOption {
+                        option_type: PROPRIETARY_OPTION,
+                        must_understand: false,
+                        data: Some(vec![0x01, 0x04, 0x99]),
+                    },
+                ],
+                ..ScMessage::new(2, ScPayload::HeartbeatRequest)
+            },
+            ScMessage::new(
+                3,
+                ScPayload::Result {
+                    function: ScFunction::ConnectRequest as u8,
+                    nak: Some(ScNak {
+                        header_marker: 0,
+                        error_class: 7,
+                        error_code: 123,
+                        details: "duplicate".to_string(),
+                    }),
+                },
+            ),
+            ScMessage::new(
+                4,
+                ScPayload::AddressResolutionAck(vec![
+                    "wss://10.0.0.1/".to_string(),
+                    "wss://node.example.com/".to_string(),
+                ]),
+            ),
+            ScMessage::new(
+                5,
+                ScPayload::Advertisement {
+                    hub_status: HubConnectionStatus::ConnectedToFailover,
+                    accepts_direct_connections: true,
+                    max_bvlc_length: 1600,
+                    max_npdu_length: 1497,
+                },
+            ),
+        ];
+        for message in messages {
+            let encoded = message.encode().unwrap();
+            assert_eq!(ScMessage::decode(&encoded).unwrap(), message);
+        }
+
+        assert!(ScMessage::decode(&[0x0D, 0x00, 0x00, 0x01]).is_err());
+        assert!(ScMessage::decode(&[0x01, 0x08, 0x00, 0x01, 0x02]).is_err());
+    }
+
+    #[test]
+    fn test_hub_connector_schedule() {
+        let start = Instant::now();
+        let mut config = ScNodeConfig::new("wss://primary.example.com/");
+        config.failover_hub_uri = "wss://failover.example.com/".to_string();
+        config.maximum_reconnect_time = Duration::from_secs(15);
+        let mut connector = HubConnector::new(&config, start);
+
+        assert_eq!(
+            connector.poll(start),
+            Some(HubAction::Connect(Hub::Primary))
+        );
+        connector.attempt_started(start);
+        assert_eq!(connector.poll(start + Duration::from_secs(9)), None);
+
+        // No Connect-Accept: the failover hub is tried at once
+        let timeout = start + config.connect_wait_timeout;
+        assert_eq!(connector.poll(timeout), Some(HubAction::Close));
+        assert_eq!(
+            connector.poll(timeout),
+            Some(HubAction::Connect(Hub::Failover))
+        );
+
+        // Both failed: back off from the minimum reconnect time
+        connector.attempt_failed(timeout);
+        assert_eq!(connector.poll(timeout + Duration::from_secs(4)), None);
+        let retry = timeout + Duration::from_secs(5);
+        assert_eq!(
+            connector.poll(retry),
+            Some(HubAction::Connect(Hub::Primary))
+        );
+        connector.attempt_failed(retry);
+        connector.attempt_failed(retry);
+        assert_eq!(connector.poll(retry + Duration::from_secs(9)), None);
+        let retry = retry + Duration::from_secs(10);
+        connector.attempt_failed(retry);
+        connector.attempt_failed(retry);
+        assert_eq!(connector.poll(retry + Duration::from_secs(14)), None);
+        let retry = retry + Duration::from_secs(15);
+        assert!(connector.poll(retry).is_some());
+
+        // Heartbeats on an idle connection, then give up after two timeouts
+        connector.attempt_started(retry);
+        connector.accepted(retry);
+        assert_eq!(connector.status(), HubConnectionStatus::ConnectedToPrimary);
+        let idle = retry + config.heartbeat_timeout;
+        assert_eq!(connector.poll(idle), Some(HubAction::SendHeartbeat));
+        assert_eq!(connector.poll(idle), None);
+        assert_eq!(
+            connector.poll(idle + config.heartbeat_timeout),
+            Some(HubAction::Close)
+        );
+        assert_eq!(connector.state(), ScConnectionState::Idle);
+
+        connector.stopped(idle);
+        assert_eq!(connector.poll(idle + Duration::from_secs(3600)), None);
+    }
+
+    #[test]
+    fn test_node_connection() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let peer = Vmac([0x02, 0x00, 0x00, 0x00, 0x00, 0x09]);
+        let hub = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut socket =
+                WebSocket::accept(stream, &[HUB_SUBPROTOCOL], Duration::from_secs(5)).unwrap();
+            let request = ScMessage::decode(&socket.receive().unwrap()).unwrap();
+            let ScPayload::ConnectRequest(node) = request.payload else {
+                panic!("expected Connect-Request");
+            };
+            let npdu = request.reply(ScPayload::EncapsulatedNpdu(vec![0x01, 0x00, 0x10, 0x08]));
+            let replies = [
+                request.reply(ScPayload::ConnectAccept(ConnectInfo {
+                    vmac: Vmac::random(),
+                    ..node
+                })),
+                ScMessage::new(40, ScPayload::HeartbeatRequest),
+                npdu.with_originating(peer),
+            ];
+            for reply in replies {
+                socket.send_binary(&reply.encode().unwrap()).unwrap();
+            }
+
+            let heartbeat_ack = ScMessage::decode(&socket.receive().unwrap()).unwrap();
+            assert_eq!(heartbeat_ack, ScMessage::new(40, ScPayload::HeartbeatAck));
+            let broadcast = ScMessage::decode(&socket.receive().unwrap()).unwrap();
+            assert_eq!(broadcast.destination, Some(Vmac::BROADCAST));
+            assert_eq!(
+                broadcast.payload,
+                ScPayload::EncapsulatedNpdu(vec![0x01, 0x20])
+            );
+
+            let disconnect = ScMessage::decode(&socket.receive().unwrap()).unwrap();
+            assert_eq!(disconnect.payload, ScPayload::DisconnectRequest);
+            let ack = disconnect.reply(ScPayload::DisconnectAck);
+            socket.send_binary(&ack.encode().unwrap()).unwrap();
+        });
+
+        let config = ScNodeConfig::new(&format!("ws://{}/", address));
+        let mut node = ScDataLink::new(config, PlainConnector);
+        let frame = (0..100)
+            .find_map(|_| node.receive_frame().ok())
+            .expect("no NPDU from the hub");
+        assert_eq!(
+            frame,
+            (
+                vec![0x01, 0x00, 0x10, 0x08],
+                DataLinkAddress::SecureConnect(peer.0)
+            )
+        );
+        assert_eq!(node.state(), ScConnectionState::Connected);
+        assert!(node.hub_info().is_some());
+
+        node.send_frame(&[0x01, 0x20], &DataLinkAddress::Broadcast)
+            .unwrap();
+        node.disconnect().unwrap();
+        assert_eq!(node.state(), ScConnectionState::Idle);
+        assert!(node
+            .send_frame(&[0x01, 0x20], &DataLinkAddress::Broadcast)
+            .is_err());
+        hub.join().unwrap();
+    }
+}
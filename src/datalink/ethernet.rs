@@ -6,8 +6,8 @@
 //!
 //! # Overview
 //!
-//! BACnet/Ethernet uses standard IEEE 802.3 Ethernet frames with a specific Ethernet type
-//! (0x82DC) and LLC header to identify BACnet traffic. Key features:
+//! BACnet/Ethernet uses IEEE 802.3 frames carrying an ISO 8802-2 (802.2) LLC header whose
+//! DSAP and SSAP of 0x82 identify BACnet traffic. Key features:
 //!
 //! - **Direct Frame Access**: Bypasses IP stack for lower latency
 //! - **Hardware Addressing**: Uses 48-bit MAC addresses
@@ -23,7 +23,7 @@
 //! +-------------------+-------------------+
 //! | Source MAC        | 6 bytes          |
 //! +-------------------+-------------------+
-//! | Ethernet Type     | 2 bytes (0x82DC) |
+//! | Length            | 2 bytes (LLC+NPDU)|
 //! +-------------------+-------------------+
 //! | LLC Header        | 3 bytes          |
 //! | - DSAP: 0x82      |                  |
@@ -38,11 +38,15 @@
 //! +-------------------+-------------------+
 //! ```
 //!
-//! # Implementation Notes
+//! The Length field counts the LLC header and the NPDU, but not the padding, so an NPDU
+//! can be at most 1497 bytes ([`MAX_ETHERNET_NPDU_LENGTH`]).
 //!
-//! This implementation requires raw socket access, which typically needs elevated
-//! privileges on most operating systems. In production, consider using platform-specific
-//! APIs or packet capture libraries like libpcap for better compatibility.
+//! # Frame I/O
+//!
+//! [`EthernetDataLink`] sends and receives whole frames through the [`FrameIo`] trait.
+//! On Linux, [`RawSocket`] implements it with an `AF_PACKET` socket bound to one
+//! interface, which needs the CAP_NET_RAW capability or root access. Other platforms,
+//! or other capture libraries such as libpcap, plug in with their own [`FrameIo`].
 //!
 //! # Examples
 //!
@@ -69,7 +73,11 @@
 //! ```
 
 #[cfg(feature = "std")]
-use std::sync::{Arc, Mutex};
+use std::{
+    io::{self, ErrorKind},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
 
 #[cfg(not(feature = "std"))]
 use alloc::{string::String, vec::Vec};
@@ -90,25 +98,16 @@ use crate::datalink::{DataLink, DataLinkAddress, DataLinkError, DataLinkType, Re
 /// ```
 pub const ETHERNET_BROADCAST_MAC: [u8; 6] = [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF];
 
-/// BACnet Ethernet type field value.
-///
-/// This value (0x82DC) identifies the frame as containing BACnet data.
-/// It's registered with IEEE and must be used for all BACnet/Ethernet frames.
-///
-/// # Examples
-///
-/// ```
-/// use bacnet_rs::datalink::ethernet::BACNET_ETHERNET_TYPE;
+/// Largest value of the 802.3 Length field.
 ///
-/// // Check if a frame is BACnet
-/// let ether_type = 0x82DC_u16;
-/// assert_eq!(ether_type, BACNET_ETHERNET_TYPE);
-/// ```
-pub const BACNET_ETHERNET_TYPE: u16 = 0x82DC;
+/// The two bytes after the source MAC hold the length of the LLC header and
+/// NPDU. Values above 1500 are EtherTypes of Ethernet II frames, which never
+/// carry BACnet.
+pub const MAX_LLC_LENGTH: usize = 1500;
 
 /// BACnet LLC (Logical Link Control) header.
 ///
-/// This 3-byte header follows the Ethernet header and identifies the
+/// This 3-byte header follows the 802.3 header and identifies the
 /// frame as BACnet traffic. Components:
 /// - DSAP (Destination Service Access Point): 0x82
 /// - SSAP (Source Service Access Point): 0x82
@@ -137,7 +136,7 @@ pub const MAX_ETHERNET_FRAME_SIZE: usize = 1514;
 
 /// Ethernet header size in bytes.
 ///
-/// Includes destination MAC (6), source MAC (6), and EtherType (2).
+/// Includes destination MAC (6), source MAC (6), and Length (2).
 pub const ETHERNET_HEADER_SIZE: usize = 14;
 
 /// LLC header size in bytes.
@@ -150,6 +149,11 @@ pub const LLC_HEADER_SIZE: usize = 3;
 /// Combined size of Ethernet header (14) and LLC header (3).
 pub const BACNET_ETHERNET_HEADER_SIZE: usize = ETHERNET_HEADER_SIZE + LLC_HEADER_SIZE;
 
+/// Maximum NPDU length in bytes.
+///
+/// The Length field allows 1500 bytes, of which the LLC header takes 3.
+pub const MAX_ETHERNET_NPDU_LENGTH: usize = MAX_LLC_LENGTH - LLC_HEADER_SIZE;
+
 /// Ethernet frame structure for BACnet communication.
 ///
 /// Represents a complete Ethernet frame containing BACnet data. This structure
@@ -184,12 +188,6 @@ pub struct EthernetFrame {
     /// (LSB of first byte must be 0).
     pub src_mac: [u8; 6],
 
-    /// Ethernet type field (2 bytes).
-    ///
-    /// For BACnet frames, this must be 0x82DC. Other values indicate
-    /// non-BACnet traffic.
-    pub ether_type: u16,
-
     /// LLC header (3 bytes).
     ///
     /// For BACnet frames, this must be [0x82, 0x82, 0x03].
@@ -197,17 +195,15 @@ pub struct EthernetFrame {
 
     /// Payload data (NPDU).
     ///
-    /// Contains the BACnet NPDU (Network Protocol Data Unit). The maximum
-    /// size depends on the Ethernet MTU, typically allowing up to 1497
-    /// bytes of BACnet data.
+    /// Contains the BACnet NPDU (Network Protocol Data Unit), at most
+    /// [`MAX_ETHERNET_NPDU_LENGTH`] bytes.
     pub payload: Vec<u8>,
 }
 
 impl EthernetFrame {
     /// Create a new Ethernet frame for BACnet communication.
     ///
-    /// Automatically sets the Ethernet type to 0x82DC and includes the
-    /// standard BACnet LLC header.
+    /// Includes the standard BACnet LLC header.
     ///
     /// # Arguments
     ///
@@ -230,7 +226,6 @@ impl EthernetFrame {
         Self {
             dest_mac,
             src_mac,
-            ether_type: BACNET_ETHERNET_TYPE,
             llc_header: BACNET_LLC_HEADER,
             payload: npdu,
         }
@@ -294,8 +289,9 @@ impl EthernetFrame {
         // Source MAC
         frame.extend_from_slice(&self.src_mac);
 
-        // Ethernet type
-        frame.extend_from_slice(&self.ether_type.to_be_bytes());
+        // Length of the LLC header and payload, excluding padding
+        let length = (LLC_HEADER_SIZE + self.payload.len()) as u16;
+        frame.extend_from_slice(&length.to_be_bytes());
 
         // LLC header
         frame.extend_from_slice(&self.llc_header);
@@ -327,8 +323,8 @@ impl EthernetFrame {
     /// # Errors
     ///
     /// Returns [`DataLinkError::InvalidFrame`] if:
-    /// - The buffer is too short
-    /// - The Ethernet type is not 0x82DC
+    /// - The buffer is shorter than the Length field says
+    /// - The Length field holds an EtherType
     /// - The LLC header is incorrect
     ///
     /// # Examples
@@ -343,8 +339,8 @@ impl EthernetFrame {
     ///     0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
     ///     // Source MAC
     ///     0x00, 0x11, 0x22, 0x33, 0x44, 0x55,
-    ///     // EtherType
-    ///     0x82, 0xDC,
+    ///     // Length
+    ///     0x00, 0x07,
     ///     // LLC Header
     ///     0x82, 0x82, 0x03,
     ///     // NPDU data
@@ -374,10 +370,11 @@ impl EthernetFrame {
         let mut src_mac = [0u8; 6];
         src_mac.copy_from_slice(&data[6..12]);
 
-        let ether_type = u16::from_be_bytes([data[12], data[13]]);
-
-        // Verify this is a BACnet frame
-        if ether_type != BACNET_ETHERNET_TYPE {
+        // Verify this is an 802.3 frame long enough for its contents
+        let length = u16::from_be_bytes([data[12], data[13]]) as usize;
+        if !(LLC_HEADER_SIZE..=MAX_LLC_LENGTH).contains(&length)
+            || data.len() < ETHERNET_HEADER_SIZE + length
+        {
             return Err(DataLinkError::InvalidFrame);
         }
 
@@ -390,12 +387,11 @@ impl EthernetFrame {
         }
 
         // Extract payload (strip any padding)
-        let payload = data[17..].to_vec();
+        let payload = data[BACNET_ETHERNET_HEADER_SIZE..ETHERNET_HEADER_SIZE + length].to_vec();
 
         Ok(Self {
             dest_mac,
             src_mac,
-            ether_type,
             llc_header,
             payload,
         })
@@ -461,20 +457,265 @@ impl EthernetFrame {
     pub fn is_multicast(&self) -> bool {
         self.dest_mac[0] & 0x01 == 0x01
     }
+
+    /// Whether a received frame is meant for the station at `local_mac`:
+    /// sent by another station to it, to a multicast group or to everyone.
+    pub(crate) fn is_for(&self, local_mac: &[u8; 6]) -> bool {
+        self.src_mac != *local_mac && (self.dest_mac == *local_mac || self.is_multicast())
+    }
+}
+
+/// How long [`EthernetDataLink::receive_frame`](DataLink::receive_frame)
+/// waits for a frame.
+#[cfg(feature = "std")]
+pub const ETHERNET_RECEIVE_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Access to whole Ethernet frames on one interface.
+///
+/// [`EthernetDataLink`] builds and parses the 802.3 and LLC headers; an
+/// implementation only moves frames, without the FCS, to and from the wire.
+/// [`RawSocket`] is the Linux implementation. Elsewhere, wrap the platform's
+/// packet interface (BPF on macOS, Npcap on Windows) in this trait.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "std")] {
+/// use bacnet_rs::datalink::ethernet::{EthernetDataLink, FrameIo};
+/// use std::io;
+/// use std::time::Duration;
+///
+/// /// Drops everything sent and never receives
+/// struct Discard;
+///
+/// impl FrameIo for Discard {
+///     fn send(&mut self, _frame: &[u8]) -> io::Result<()> {
+///         Ok(())
+///     }
+///
+///     fn receive(&mut self, _buffer: &mut [u8], _timeout: Duration) -> io::Result<usize> {
+///         Err(io::ErrorKind::TimedOut.into())
+///     }
+/// }
+///
+/// let link = EthernetDataLink::with_io(Discard, [0x00, 0x11, 0x22, 0x33, 0x44, 0x55]);
+/// # }
+/// ```
+#[cfg(feature = "std")]
+pub trait FrameIo: Send {
+    /// Transmit one complete frame.
+    fn send(&mut self, frame: &[u8]) -> io::Result<()>;
+
+    /// Wait up to `timeout` for a frame and copy it into `buffer`, returning
+    /// its length.
+    ///
+    /// Returns an error of kind [`ErrorKind::TimedOut`] or
+    /// [`ErrorKind::WouldBlock`] when no frame arrives in time.
+    fn receive(&mut self, buffer: &mut [u8], timeout: Duration) -> io::Result<usize>;
+}
+
+/// Linux `AF_PACKET` socket bound to one interface.
+///
+/// The socket receives the 802.3 frames that carry an LLC header, on which
+/// [`EthernetDataLink`] picks out BACnet's SAP. Opening it requires the
+/// CAP_NET_RAW capability or root access.
+///
+/// # Examples
+///
+/// ```no_run
+/// # #[cfg(all(feature = "std", target_os = "linux"))] {
+/// use bacnet_rs::datalink::ethernet::{EthernetDataLink, RawSocket};
+///
+/// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let socket = RawSocket::open("eth0")?;
+/// let mac = RawSocket::mac_address("eth0")?;
+/// let eth_link = EthernetDataLink::with_io(socket, mac);
+/// # Ok(())
+/// # }
+/// # }
+/// ```
+#[cfg(all(feature = "std", target_os = "linux"))]
+#[derive(Debug)]
+pub struct RawSocket {
+    /// The socket.
+    fd: std::os::fd::OwnedFd,
+}
+
+#[cfg(all(feature = "std", target_os = "linux"))]
+impl RawSocket {
+    /// Open a socket on `interface` (e.g. "eth0").
+    ///
+    /// # Errors
+    ///
+    /// Returns the operating system error when the interface does not exist
+    /// or the process lacks permission for raw sockets.
+    pub fn open(interface: &str) -> io::Result<Self> {
+        use std::os::fd::{FromRawFd, OwnedFd};
+
+        let name = std::ffi::CString::new(interface)
+            .map_err(|_| io::Error::new(ErrorKind::InvalidInput, "Invalid interface name"))?;
+        // SAFETY: `name` is a valid NUL-terminated string
+        let index = unsafe { libc::if_nametoindex(name.as_ptr()) };
+        if index == 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let protocol = (libc::ETH_P_802_2 as u16).to_be();
+        // SAFETY: plain system call; the descriptor is checked before use
+        let fd = unsafe {
+            libc::socket(
+                libc::AF_PACKET,
+                libc::SOCK_RAW | libc::SOCK_CLOEXEC,
+                protocol as libc::c_int,
+            )
+        };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: `fd` is a freshly opened descriptor owned by nobody else
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+
+        // SAFETY: all-zero is a valid `sockaddr_ll`
+        let mut address: libc::sockaddr_ll = unsafe { std::mem::zeroed() };
+        address.sll_family = libc::AF_PACKET as u16;
+        address.sll_protocol = protocol;
+        address.sll_ifindex = index as libc::c_int;
+        // SAFETY: `address` is a `sockaddr_ll` of the size passed
+        let result = unsafe {
+            libc::bind(
+                std::os::fd::AsRawFd::as_raw_fd(&fd),
+                &address as *const libc::sockaddr_ll as *const libc::sockaddr,
+                std::mem::size_of::<libc::sockaddr_ll>() as libc::socklen_t,
+            )
+        };
+        if result < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(Self { fd })
+    }
+
+    /// Put the socket in non-blocking mode, or back.
+    pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        use std::os::fd::AsRawFd;
+
+        let fd = self.fd.as_raw_fd();
+        // SAFETY: plain system calls on a descriptor this socket owns
+        let flags = unsafe { libc::fcntl(fd, libc::F_GETFL) };
+        if flags < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let flags = if nonblocking {
+            flags | libc::O_NONBLOCK
+        } else {
+            flags & !libc::O_NONBLOCK
+        };
+        // SAFETY: as above
+        if unsafe { libc::fcntl(fd, libc::F_SETFL, flags) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    /// Read the hardware address of `interface` from sysfs.
+    pub fn mac_address(interface: &str) -> io::Result<[u8; 6]> {
+        let path = format!("/sys/class/net/{}/address", interface);
+        let address = std::fs::read_to_string(path)?;
+        parse_mac_address(address.trim())
+            .map_err(|_| io::Error::new(ErrorKind::InvalidData, "Invalid hardware address"))
+    }
+}
+
+#[cfg(all(feature = "std", target_os = "linux"))]
+impl std::os::fd::AsRawFd for RawSocket {
+    fn as_raw_fd(&self) -> std::os::fd::RawFd {
+        self.fd.as_raw_fd()
+    }
+}
+
+#[cfg(all(feature = "std", target_os = "linux"))]
+impl RawSocket {
+    /// Transmit one frame, without waiting in non-blocking mode.
+    pub(crate) fn send_now(&self, frame: &[u8]) -> io::Result<()> {
+        use std::os::fd::AsRawFd;
+
+        // SAFETY: `frame` is valid for reads of its length
+        let sent = unsafe {
+            libc::send(
+                self.fd.as_raw_fd(),
+                frame.as_ptr() as *const libc::c_void,
+                frame.len(),
+                0,
+            )
+        };
+        if sent < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    /// Copy the next frame into `buffer`, without waiting in non-blocking
+    /// mode.
+    pub(crate) fn receive_now(&self, buffer: &mut [u8]) -> io::Result<usize> {
+        use std::os::fd::AsRawFd;
+
+        // SAFETY: `buffer` is valid for writes of its length
+        let received = unsafe {
+            libc::recv(
+                self.fd.as_raw_fd(),
+                buffer.as_mut_ptr() as *mut libc::c_void,
+                buffer.len(),
+                0,
+            )
+        };
+        if received < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(received as usize)
+    }
+}
+
+#[cfg(all(feature = "std", target_os = "linux"))]
+impl FrameIo for RawSocket {
+    fn send(&mut self, frame: &[u8]) -> io::Result<()> {
+        self.send_now(frame)
+    }
+
+    fn receive(&mut self, buffer: &mut [u8], timeout: Duration) -> io::Result<usize> {
+        use std::os::fd::AsRawFd;
+
+        let mut poll = libc::pollfd {
+            fd: self.fd.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        let timeout = timeout.as_millis().min(libc::c_int::MAX as u128) as libc::c_int;
+        // SAFETY: `poll` is one valid `pollfd`
+        let ready = unsafe { libc::poll(&mut poll, 1, timeout) };
+        if ready < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        if ready == 0 {
+            return Err(ErrorKind::TimedOut.into());
+        }
+        self.receive_now(buffer)
+    }
 }
 
 /// BACnet/Ethernet data link implementation.
 ///
-/// Provides BACnet communication over Ethernet networks using raw frames.
-/// This implementation simulates Ethernet communication for demonstration
-/// purposes. In production, use platform-specific raw socket APIs or
-/// packet capture libraries.
+/// Provides BACnet communication over Ethernet networks using raw frames
+/// sent and received through a [`FrameIo`]. Received frames are kept only
+/// when they carry the BACnet LLC header and are addressed to this station,
+/// to a multicast group or to everyone.
 ///
 /// # Platform Requirements
 ///
-/// - **Linux**: Requires CAP_NET_RAW capability or root access
-/// - **Windows**: Requires WinPcap/Npcap or similar
-/// - **macOS**: Requires root access for BPF (Berkeley Packet Filter)
+/// - **Linux**: [`EthernetDataLink::new`] opens a [`RawSocket`], which
+///   requires CAP_NET_RAW capability or root access
+/// - **Windows**: Requires WinPcap/Npcap or similar behind a [`FrameIo`]
+/// - **macOS**: Requires root access for BPF (Berkeley Packet Filter) behind
+///   a [`FrameIo`]
 ///
 /// # Examples
 ///
@@ -504,32 +745,17 @@ pub struct EthernetDataLink {
     /// Local MAC address of this device.
     local_mac: [u8; 6],
 
-    /// Network interface name.
-    ///
-    /// Examples: "eth0", "en0", "Ethernet"
-    _interface: String,
-
-    /// Receive buffer for incoming frames.
-    ///
-    /// In a real implementation, this would be populated by a packet
-    /// capture thread or event-driven I/O.
-    rx_buffer: Arc<Mutex<Vec<(EthernetFrame, DataLinkAddress)>>>,
-
-    /// Running state flag.
-    ///
-    /// Used to control the receive thread in a real implementation.
-    _running: Arc<Mutex<bool>>,
+    /// Frame access to the interface.
+    io: Mutex<Box<dyn FrameIo>>,
 }
 
 #[cfg(feature = "std")]
 impl EthernetDataLink {
     /// Create a new Ethernet data link.
     ///
-    /// Initializes an Ethernet data link for the specified network interface
-    /// and MAC address. In a production implementation, this would:
-    /// 1. Open a raw socket or packet capture handle
-    /// 2. Set up BPF filters for BACnet traffic (EtherType 0x82DC)
-    /// 3. Start a receive thread for incoming frames
+    /// Opens a [`RawSocket`] on the specified network interface and sends
+    /// from the given MAC address, which is normally the interface's own
+    /// (see [`RawSocket::mac_address`]).
     ///
     /// # Arguments
     ///
@@ -542,10 +768,11 @@ impl EthernetDataLink {
     ///
     /// # Errors
     ///
-    /// In a real implementation, this would return errors for:
-    /// - Invalid interface name
-    /// - Insufficient permissions
-    /// - Interface not found
+    /// Returns [`DataLinkError::IoError`] if:
+    /// - The interface is not found
+    /// - Permissions are insufficient
+    /// - The platform has no raw socket support; use
+    ///   [`with_io`](Self::with_io) there
     ///
     /// # Examples
     ///
@@ -567,27 +794,38 @@ impl EthernetDataLink {
     /// Raw socket access requires elevated privileges. Consider using
     /// capabilities (Linux) or running with minimal required permissions.
     pub fn new(interface: &str, local_mac: [u8; 6]) -> Result<Self> {
-        let rx_buffer = Arc::new(Mutex::new(Vec::new()));
-        let running = Arc::new(Mutex::new(true));
+        #[cfg(target_os = "linux")]
+        {
+            let socket = RawSocket::open(interface).map_err(DataLinkError::IoError)?;
+            Ok(Self::with_io(socket, local_mac))
+        }
 
-        // In a real implementation, we would:
-        // 1. Open a raw socket or pcap handle for the interface
-        // 2. Set up BPF filters for BACnet frames
-        // 3. Start a receive thread
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = (interface, local_mac);
+            Err(DataLinkError::IoError(io::Error::new(
+                ErrorKind::Unsupported,
+                "No raw socket support on this platform",
+            )))
+        }
+    }
 
-        Ok(Self {
+    /// Create a data link on any frame I/O.
+    ///
+    /// # Arguments
+    ///
+    /// * `io` - Access to frames on the interface
+    /// * `local_mac` - Local MAC address to use
+    pub fn with_io<F: FrameIo + 'static>(io: F, local_mac: [u8; 6]) -> Self {
+        Self {
             local_mac,
-            _interface: interface.to_string(),
-            rx_buffer,
-            _running: running,
-        })
+            io: Mutex::new(Box::new(io)),
+        }
     }
 
     /// Send an Ethernet frame on the network interface.
     ///
-    /// In a real implementation, this would write the frame to a raw socket
-    /// or packet injection interface. The FCS is automatically added by the
-    /// network hardware.
+    /// The FCS is automatically added by the network hardware.
     ///
     /// # Arguments
     ///
@@ -596,44 +834,23 @@ impl EthernetDataLink {
     /// # Errors
     ///
     /// Returns an error if:
-    /// - The frame exceeds the maximum Ethernet size
+    /// - The NPDU exceeds [`MAX_ETHERNET_NPDU_LENGTH`]
     /// - The network interface is down
-    /// - Insufficient permissions
     fn send_ethernet_frame(&self, frame: &EthernetFrame) -> Result<()> {
-        // In a real implementation, this would:
-        // 1. Encode the frame
-        // 2. Send it via raw socket or pcap
-
-        let encoded = frame.encode();
-
-        // Validate frame size
-        if encoded.len() > MAX_ETHERNET_FRAME_SIZE {
+        if frame.payload.len() > MAX_ETHERNET_NPDU_LENGTH {
             return Err(DataLinkError::InvalidFrame);
         }
 
-        // Simulate sending (in real implementation, use raw socket)
-        println!(
-            "Sending Ethernet frame: {} bytes to {:02X?}",
-            encoded.len(),
-            frame.dest_mac
-        );
-
-        Ok(())
+        self.io
+            .lock()
+            .unwrap()
+            .send(&frame.encode())
+            .map_err(DataLinkError::IoError)
     }
 
-    /// Simulate receiving a frame (for testing).
-    ///
-    /// This method allows tests to inject frames into the receive buffer
-    /// as if they were received from the network.
-    ///
-    /// # Arguments
-    ///
-    /// * `frame` - The Ethernet frame to inject
-    /// * `source` - The source address to report
-    #[cfg(test)]
-    pub fn simulate_receive(&self, frame: EthernetFrame, source: DataLinkAddress) {
-        let mut buffer = self.rx_buffer.lock().unwrap();
-        buffer.push((frame, source));
+    /// Whether a received frame is meant for this station.
+    fn accepts(&self, frame: &EthernetFrame) -> bool {
+        frame.is_for(&self.local_mac)
     }
 }
 
@@ -655,15 +872,36 @@ impl DataLink for EthernetDataLink {
     }
 
     fn receive_frame(&mut self) -> Result<(Vec<u8>, DataLinkAddress)> {
-        // Check receive buffer
-        let mut buffer = self.rx_buffer.lock().unwrap();
-
-        if let Some((frame, source)) = buffer.pop() {
-            Ok((frame.payload, source))
-        } else {
-            // In real implementation, this would block on socket recv
-            Err(DataLinkError::InvalidFrame)
+        let deadline = Instant::now() + ETHERNET_RECEIVE_TIMEOUT;
+        let mut buffer = [0u8; MAX_ETHERNET_FRAME_SIZE];
+        let mut io = self.io.lock().unwrap();
+
+        // Other traffic on the interface is skipped until the timeout
+        loop {
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            let length = match io.receive(&mut buffer, timeout) {
+                Ok(length) => length,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                    break;
+                }
+                Err(e) => return Err(DataLinkError::IoError(e)),
+            };
+
+            if let Ok(frame) = EthernetFrame::decode(&buffer[..length.min(buffer.len())]) {
+                if self.accepts(&frame) {
+                    return Ok((frame.payload, DataLinkAddress::Ethernet(frame.src_mac)));
+                }
+            }
+            if Instant::now() >= deadline {
+                break;
+            }
         }
+
+        Err(DataLinkError::IoError(io::Error::new(
+            ErrorKind::TimedOut,
+            "No Ethernet frame received",
+        )))
     }
 
     fn link_type(&self) -> DataLinkType {
@@ -673,6 +911,14 @@ impl DataLink for EthernetDataLink {
     fn local_address(&self) -> DataLinkAddress {
         DataLinkAddress::Ethernet(self.local_mac)
     }
+
+    fn broadcast_address(&self) -> DataLinkAddress {
+        DataLinkAddress::Ethernet(ETHERNET_BROADCAST_MAC)
+    }
+
+    fn max_npdu_length(&self) -> usize {
+        MAX_ETHERNET_NPDU_LENGTH
+    }
 }
 
 /// Parse a MAC address from string format.
@@ -779,7 +1025,7 @@ pub fn format_mac_address(mac: &[u8; 6]) -> String {
 ///
 /// Returns [`DataLinkError::InvalidFrame`] if:
 /// - The frame is too short or too long
-/// - The Ethernet type is not 0x82DC
+/// - The Length field holds an EtherType or exceeds the frame
 /// - The LLC header is incorrect
 ///
 /// # Examples
@@ -811,14 +1057,16 @@ pub fn validate_ethernet_frame(data: &[u8]) -> Result<()> {
         return Err(DataLinkError::InvalidFrame);
     }
 
-    // Check Ethernet type
-    let ether_type = u16::from_be_bytes([data[12], data[13]]);
-    if ether_type != BACNET_ETHERNET_TYPE {
+    // Check 802.3 Length field
+    let length = u16::from_be_bytes([data[12], data[13]]) as usize;
+    if !(LLC_HEADER_SIZE..=MAX_LLC_LENGTH).contains(&length)
+        || data.len() < ETHERNET_HEADER_SIZE + length
+    {
         return Err(DataLinkError::InvalidFrame);
     }
 
     // Check LLC header
-    if data.len() >= 17 && data[14..17] != BACNET_LLC_HEADER {
+    if data[14..17] != BACNET_LLC_HEADER {
         return Err(DataLinkError::InvalidFrame);
     }
 
@@ -845,11 +1093,11 @@ mod tests {
         let decoded = EthernetFrame::decode(&encoded).unwrap();
         assert_eq!(decoded.dest_mac, dest_mac);
         assert_eq!(decoded.src_mac, src_mac);
-        assert_eq!(decoded.ether_type, BACNET_ETHERNET_TYPE);
+        assert_eq!(u16::from_be_bytes([encoded[12], encoded[13]]), 7);
         assert_eq!(decoded.llc_header, BACNET_LLC_HEADER);
 
-        // Payload might have padding, so check prefix
-        assert!(decoded.payload.starts_with(&npdu));
+        // The Length field excludes the padding
+        assert_eq!(decoded.payload, npdu);
     }
 
     #[test]
@@ -893,33 +1141,124 @@ mod tests {
         assert!(validate_ethernet_frame(&[]).is_err()); // Too short
         assert!(validate_ethernet_frame(&encoded[..16]).is_err()); // Missing LLC
 
-        // Test wrong Ethernet type
+        // Test an Ethernet II frame (IPv4 EtherType)
         let mut bad_frame = encoded.clone();
         bad_frame[12] = 0x08;
         bad_frame[13] = 0x00;
         assert!(validate_ethernet_frame(&bad_frame).is_err());
+        assert!(EthernetFrame::decode(&bad_frame).is_err());
+
+        // Test a Length field beyond the frame
+        let mut bad_frame = encoded.clone();
+        bad_frame[13] = 0x50;
+        assert!(validate_ethernet_frame(&bad_frame).is_err());
+    }
+
+    /// Frames shared between the ends of a simulated segment
+    #[cfg(feature = "std")]
+    type Segment = std::sync::Arc<Mutex<std::collections::VecDeque<Vec<u8>>>>;
+
+    /// Frame I/O on a simulated segment
+    #[cfg(feature = "std")]
+    struct Wire {
+        sent: Segment,
+        received: Segment,
+    }
+
+    #[cfg(feature = "std")]
+    impl FrameIo for Wire {
+        fn send(&mut self, frame: &[u8]) -> io::Result<()> {
+            self.sent.lock().unwrap().push_back(frame.to_vec());
+            Ok(())
+        }
+
+        fn receive(&mut self, buffer: &mut [u8], _timeout: Duration) -> io::Result<usize> {
+            let frame = self.received.lock().unwrap().pop_front();
+            let frame = frame.ok_or(io::Error::from(ErrorKind::TimedOut))?;
+            buffer[..frame.len()].copy_from_slice(&frame);
+            Ok(frame.len())
+        }
     }
 
     #[cfg(feature = "std")]
     #[test]
     fn test_ethernet_datalink() {
         let local_mac = [0x00, 0x11, 0x22, 0x33, 0x44, 0x55];
-        let mut datalink = EthernetDataLink::new("eth0", local_mac).unwrap();
+        let sent = Segment::default();
+        let received = Segment::default();
+        let wire = Wire {
+            sent: sent.clone(),
+            received: received.clone(),
+        };
+        let mut datalink = EthernetDataLink::with_io(wire, local_mac);
 
         assert_eq!(datalink.link_type(), DataLinkType::Ethernet);
         assert_eq!(
             datalink.local_address(),
             DataLinkAddress::Ethernet(local_mac)
         );
+        assert_eq!(
+            datalink.broadcast_address(),
+            DataLinkAddress::Ethernet(ETHERNET_BROADCAST_MAC)
+        );
+        assert_eq!(datalink.max_npdu_length(), 1497);
 
         // Test sending
         let dest_mac = [0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF];
-        let npdu = vec![0x01, 0x02, 0x03, 0x04];
+        let npdu = vec![0x01, 0x04, 0x00, 0x00];
         let result = datalink.send_frame(&npdu, &DataLinkAddress::Ethernet(dest_mac));
         assert!(result.is_ok());
+        let frame = EthernetFrame::decode(&sent.lock().unwrap().pop_front().unwrap()).unwrap();
+        assert_eq!((frame.dest_mac, frame.src_mac), (dest_mac, local_mac));
+        assert_eq!(frame.payload, npdu);
 
         // Test broadcast
         let result = datalink.send_frame(&npdu, &DataLinkAddress::Broadcast);
         assert!(result.is_ok());
+        let frame = EthernetFrame::decode(&sent.lock().unwrap().pop_front().unwrap()).unwrap();
+        assert!(frame.is_broadcast());
+
+        // Too long for one frame
+        let too_long = [0; MAX_ETHERNET_NPDU_LENGTH + 1];
+        let result = datalink.send_frame(&too_long, &DataLinkAddress::Ethernet(dest_mac));
+        assert!(result.is_err());
+        assert!(datalink
+            .send_frame(&npdu, &DataLinkAddress::MsTP(1))
+            .is_err());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_ethernet_receive_filter() {
+        let local_mac = [0x00, 0x11, 0x22, 0x33, 0x44, 0x55];
+        let peer_mac = [0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF];
+        let received = Segment::default();
+        let wire = Wire {
+            sent: Segment::default(),
+            received: received.clone(),
+        };
+        let mut datalink = EthernetDataLink::with_io(wire, local_mac);
+
+        {
+            let mut segment = received.lock().unwrap();
+            // Another station's unicast, this station's own broadcast, and
+            // an LLC frame for another SAP are all skipped
+            segment.push_back(EthernetFrame::new([0x02; 6], peer_mac, vec![1]).encode());
+            segment.push_back(EthernetFrame::broadcast(local_mac, vec![2]).encode());
+            let mut other_sap = EthernetFrame::broadcast(peer_mac, vec![3]);
+            other_sap.llc_header = [0x42, 0x42, 0x03];
+            segment.push_back(other_sap.encode());
+            segment.push_back(EthernetFrame::new(local_mac, peer_mac, vec![4]).encode());
+            segment.push_back(EthernetFrame::broadcast(peer_mac, vec![5]).encode());
+        }
+
+        let (npdu, source) = datalink.receive_frame().unwrap();
+        assert_eq!(npdu, [4]);
+        assert_eq!(source, DataLinkAddress::Ethernet(peer_mac));
+        assert_eq!(datalink.receive_frame().unwrap().0, [5]);
+        assert!(matches!(
+            datalink.receive_frame(),
+            Err(DataLinkError::IoError(e)) if e.kind() == ErrorKind::TimedOut
+        ));
     }
 }
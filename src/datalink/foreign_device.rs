@@ -0,0 +1,268 @@
+//! Foreign device registration (ASHRAE 135 Annex J.5).
+//!
+//! A BACnet/IP device on a subnet without a BBMD joins the network by
+//! registering with a remote BBMD as a foreign device. The BBMD then forwards
+//! broadcasts to it, and the device sends its own broadcasts to the BBMD in
+//! Distribute-Broadcast-To-Network messages.
+//!
+//! [`ForeignDevice`] tracks one registration: it says when a
+//! Register-Foreign-Device is due, renewing at half the time-to-live so the
+//! registration never lapses, and retrying when the BBMD stays silent or
+//! refuses. [`BacnetIpDataLink`] drives it and reroutes broadcasts while it is
+//! set.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use bacnet_rs::datalink::bip::BacnetIpDataLink;
+//! use bacnet_rs::datalink::{DataLink, DataLinkAddress};
+//!
+//! # fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut data_link = BacnetIpDataLink::new("0.0.0.0:47808")?;
+//! data_link.join_as_foreign_device("192.168.1.10:47808".parse()?, 300)?;
+//!
+//! // Sent to the BBMD as Distribute-Broadcast-To-Network
+//! let who_is_npdu = vec![0x01, 0x20, 0xFF, 0xFF, 0x00, 0xFF, 0x10, 0x08];
+//! data_link.send_frame(&who_is_npdu, &DataLinkAddress::Broadcast)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`BacnetIpDataLink`]: crate::datalink::bip::BacnetIpDataLink
+
+use std::{
+    net::{SocketAddr, SocketAddrV4},
+    time::{Duration, Instant},
+};
+
+use crate::datalink::bip::{BvlcMessage, BvlcResultCode};
+use crate::object::{BacnetIpMode, IpPortSettings};
+
+/// How long to wait for the BBMD to answer a registration before sending it
+/// again.
+pub const REGISTRATION_RETRY_INTERVAL: Duration = Duration::from_secs(10);
+
+/// State of a foreign device registration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegistrationState {
+    /// Not registered yet, or the registration lapsed.
+    Unregistered,
+    /// The BBMD accepted the last registration.
+    Registered,
+    /// The BBMD refused the last registration with a NAK.
+    Rejected,
+}
+
+/// A foreign device registration with one BBMD.
+#[derive(Debug, Clone)]
+pub struct ForeignDevice {
+    /// BBMD registered with.
+    bbmd: SocketAddr,
+
+    /// Time-to-live requested, in seconds.
+    ttl: u16,
+
+    /// Current state.
+    state: RegistrationState,
+
+    /// When the last Register-Foreign-Device was sent, `None` to send one now.
+    last_sent: Option<Instant>,
+
+    /// Whether the last Register-Foreign-Device is still unanswered.
+    awaiting_reply: bool,
+
+    /// When the BBMD last accepted a registration.
+    registered_at: Option<Instant>,
+}
+
+impl ForeignDevice {
+    /// Create a registration with `bbmd`, due to be sent at once.
+    ///
+    /// # Arguments
+    ///
+    /// * `bbmd` - The B/IP address of the BBMD
+    /// * `ttl` - Time-to-live in seconds; zero is raised to one
+    pub fn new(bbmd: SocketAddr, ttl: u16) -> Self {
+        Self {
+            bbmd,
+            ttl: ttl.max(1),
+            state: RegistrationState::Unregistered,
+            last_sent: None,
+            awaiting_reply: false,
+            registered_at: None,
+        }
+    }
+
+    /// Create a registration from the settings of a BACnet/IP Network Port.
+    ///
+    /// Returns `None` unless the port is in foreign mode with an
+    /// FD_BBMD_Address.
+    pub fn from_settings(settings: &IpPortSettings) -> Option<Self> {
+        if settings.mode != BacnetIpMode::Foreign {
+            return None;
+        }
+        let bbmd = settings.fd_bbmd_address?;
+        Some(Self::new(
+            SocketAddrV4::new(bbmd.address.into(), bbmd.port).into(),
+            settings.fd_subscription_lifetime,
+        ))
+    }
+
+    /// The BBMD registered with.
+    pub fn bbmd(&self) -> SocketAddr {
+        self.bbmd
+    }
+
+    /// The time-to-live requested, in seconds.
+    pub fn ttl(&self) -> u16 {
+        self.ttl
+    }
+
+    /// The current registration state.
+    pub fn state(&self) -> RegistrationState {
+        self.state
+    }
+
+    /// Whether the BBMD currently holds the registration.
+    pub fn is_registered(&self) -> bool {
+        self.state == RegistrationState::Registered
+    }
+
+    /// How often an accepted registration is renewed: half the TTL.
+    pub fn renewal_interval(&self) -> Duration {
+        Duration::from_secs((self.ttl as u64).div_ceil(2))
+    }
+
+    /// Send a registration on the next [`poll`](Self::poll), as the
+    /// RENEW_FD_REGISTRATION Network Port command asks.
+    pub fn renew(&mut self) {
+        self.last_sent = None;
+    }
+
+    /// The Register-Foreign-Device to send now, if one is due.
+    ///
+    /// A registration is due at once after creation or [`renew`](Self::renew),
+    /// every [`renewal_interval`](Self::renewal_interval) while registered,
+    /// [`REGISTRATION_RETRY_INTERVAL`] after an unanswered one, and one
+    /// renewal interval after a NAK. A registration the BBMD has not renewed
+    /// within its TTL counts as lapsed.
+    pub fn poll(&mut self, now: Instant) -> Option<BvlcMessage> {
+        if let Some(registered_at) = self.registered_at {
+            if self.is_registered()
+                && now.duration_since(registered_at) >= Duration::from_secs(self.ttl as u64)
+            {
+                self.state = RegistrationState::Unregistered;
+            }
+        }
+
+        let due = match self.last_sent {
+            None => true,
+            Some(sent) => {
+                let elapsed = now.duration_since(sent);
+                if self.awaiting_reply {
+                    elapsed >= REGISTRATION_RETRY_INTERVAL
+                } else {
+                    elapsed >= self.renewal_interval()
+                }
+            }
+        };
+        if !due {
+            return None;
+        }
+
+        self.last_sent = Some(now);
+        self.awaiting_reply = true;
+        Some(BvlcMessage::RegisterForeignDevice { ttl: self.ttl })
+    }
+
+    /// Take a BVLC-Result from the BBMD.
+    ///
+    /// Returns whether the result concerned the registration. A
+    /// Distribute-Broadcast-To-Network NAK means the BBMD no longer knows
+    /// this device, so a new registration becomes due at once.
+    pub fn handle_result(&mut self, code: BvlcResultCode, now: Instant) -> bool {
+        match code {
+            BvlcResultCode::Successful if self.awaiting_reply => {
+                self.state = RegistrationState::Registered;
+                self.registered_at = Some(now);
+                self.awaiting_reply = false;
+                true
+            }
+            BvlcResultCode::RegisterForeignDeviceNak => {
+                self.state = RegistrationState::Rejected;
+                self.awaiting_reply = false;
+                true
+            }
+            BvlcResultCode::DistributeBroadcastToNetworkNak => {
+                self.state = RegistrationState::Unregistered;
+                self.last_sent = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::HostNPort;
+
+    #[test]
+    fn test_registration_schedule() {
+        let start = Instant::now();
+        let mut device = ForeignDevice::new("192.168.1.10:47808".parse().unwrap(), 60);
+        assert_eq!(
+            device.poll(start),
+            Some(BvlcMessage::RegisterForeignDevice { ttl: 60 })
+        );
+        assert_eq!(device.poll(start + Duration::from_secs(5)), None);
+
+        // No answer: try again after the retry interval
+        let retry = start + REGISTRATION_RETRY_INTERVAL;
+        assert!(device.poll(retry).is_some());
+        assert!(device.handle_result(BvlcResultCode::Successful, retry));
+        assert!(device.is_registered());
+
+        // Renewed at half the TTL
+        assert_eq!(device.poll(retry + Duration::from_secs(29)), None);
+        let renewal = retry + Duration::from_secs(30);
+        assert!(device.poll(renewal).is_some());
+
+        // The renewal goes unanswered until the registration lapses
+        for seconds in [40, 50] {
+            assert!(device
+                .poll(renewal + Duration::from_secs(seconds))
+                .is_some());
+        }
+        assert_eq!(device.state(), RegistrationState::Unregistered);
+    }
+
+    #[test]
+    fn test_result_naks() {
+        let start = Instant::now();
+        let mut device = ForeignDevice::new("192.168.1.10:47808".parse().unwrap(), 60);
+        device.poll(start);
+        assert!(device.handle_result(BvlcResultCode::RegisterForeignDeviceNak, start));
+        assert_eq!(device.state(), RegistrationState::Rejected);
+        assert_eq!(device.poll(start + REGISTRATION_RETRY_INTERVAL), None);
+        assert!(device.poll(start + device.renewal_interval()).is_some());
+
+        // Successes for other requests are not taken as registrations
+        device.handle_result(BvlcResultCode::Successful, start);
+        assert!(!device.handle_result(BvlcResultCode::Successful, start));
+        assert!(device.handle_result(BvlcResultCode::DistributeBroadcastToNetworkNak, start));
+        assert_eq!(device.state(), RegistrationState::Unregistered);
+        assert!(device.poll(start).is_some());
+
+        let settings = IpPortSettings {
+            mode: BacnetIpMode::Foreign,
+            fd_bbmd_address: Some(HostNPort::new([10, 0, 0, 1], 47809)),
+            fd_subscription_lifetime: 300,
+            ..Default::default()
+        };
+        let device = ForeignDevice::from_settings(&settings).unwrap();
+        assert_eq!(device.bbmd(), "10.0.0.1:47809".parse().unwrap());
+        assert_eq!(device.ttl(), 300);
+    }
+}
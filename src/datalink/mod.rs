@@ -24,7 +24,7 @@
 //!
 //! ## BACnet/Ethernet (ISO 8802-3)
 //! - Direct Ethernet frame communication
-//! - Uses Ethernet type 0x82DC for BACnet
+//! - Uses 802.2 LLC with DSAP/SSAP 0x82 for BACnet
 //! - LLC header for protocol identification
 //! - Suitable for high-speed local networks
 //!
@@ -35,9 +35,9 @@
 //! - Common in field-level devices
 //!
 //! ## PTP (Point-to-Point)
-//! - Direct serial connection between two devices
+//! - Direct serial or dial-up connection between two half-routers
 //! - Simplified protocol without token passing
-//! - Used for device configuration and testing
+//! - Connections set up on demand, kept alive with heartbeats
 //!
 //! ## ARCnet
 //! - Legacy token-passing network
@@ -193,6 +193,12 @@ pub enum DataLinkError {
     /// supported by the current implementation or when mixing incompatible
     /// address types with data link types.
     UnsupportedType,
+
+    /// The remote link layer refused a request.
+    ///
+    /// Carries the result code of the negative acknowledgement, such as a
+    /// BVLC-Result NAK from a BBMD.
+    Nak(u16),
 }
 
 impl fmt::Display for DataLinkError {
@@ -204,6 +210,9 @@ impl fmt::Display for DataLinkError {
             DataLinkError::CrcError => write!(f, "CRC check failed"),
             DataLinkError::AddressError(msg) => write!(f, "Address error: {}", msg),
             DataLinkError::UnsupportedType => write!(f, "Unsupported data link type"),
+            DataLinkError::Nak(code) => {
+                write!(f, "Request refused with result code 0x{:04X}", code)
+            }
         }
     }
 }
@@ -240,7 +249,7 @@ pub enum DataLinkType {
 
     /// BACnet/Ethernet (ISO 8802-3).
     ///
-    /// Direct Ethernet frame communication using Ethernet type 0x82DC. Provides
+    /// Direct Ethernet frame communication using 802.2 LLC SAP 0x82. Provides
     /// high performance on local networks but requires Ethernet infrastructure
     /// and may need special permissions for raw socket access.
     Ethernet,
@@ -265,6 +274,13 @@ pub enum DataLinkType {
     /// BACnet standard, it is rarely used in new installations. Included for
     /// compatibility with older systems.
     Arcnet,
+
+    /// BACnet/SC (Secure Connect, Annex AB).
+    ///
+    /// BVLC-SC messages carried over TLS-secured WebSocket connections,
+    /// normally through a hub. Nodes are identified by 6-byte virtual MAC
+    /// addresses and authenticate each other with certificates.
+    SecureConnect,
 }
 
 /// Common trait for all data link layer implementations.
@@ -308,7 +324,15 @@ pub enum DataLinkType {
 ///     fn local_address(&self) -> DataLinkAddress {
 ///         DataLinkAddress::Broadcast
 ///     }
+///
+///     fn max_npdu_length(&self) -> usize {
+///         501
+///     }
 /// }
+///
+/// // Upper layers can hold it alongside the built-in data links
+/// let data_link: Box<dyn DataLink> = Box::new(CustomDataLink {});
+/// assert_eq!(data_link.broadcast_address(), DataLinkAddress::Broadcast);
 /// ```
 ///
 /// ## Using the Trait
@@ -317,7 +341,11 @@ pub enum DataLinkType {
 /// use bacnet_rs::datalink::{DataLink, DataLinkAddress};
 ///
 /// fn send_broadcast(data_link: &mut dyn DataLink, data: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
-///     data_link.send_frame(data, &DataLinkAddress::Broadcast)?;
+///     if data.len() > data_link.max_npdu_length() {
+///         return Err("NPDU too long for this data link".into());
+///     }
+///     let broadcast = data_link.broadcast_address();
+///     data_link.send_frame(data, &broadcast)?;
 ///     Ok(())
 /// }
 /// ```
@@ -406,6 +434,53 @@ pub trait DataLink: Send + Sync {
     /// }
     /// ```
     fn local_address(&self) -> DataLinkAddress;
+
+    /// Get the address that reaches every device on the local network.
+    ///
+    /// Frames sent here are delivered to all stations the data link reaches
+    /// directly. The default is the logical [`DataLinkAddress::Broadcast`],
+    /// which every implementation translates; implementations report their
+    /// physical broadcast address where they have one.
+    fn broadcast_address(&self) -> DataLinkAddress {
+        DataLinkAddress::Broadcast
+    }
+
+    /// Get the largest NPDU this data link can carry in one frame.
+    ///
+    /// The network layer uses this to choose the maximum APDU size it
+    /// advertises and to reject messages before they reach the wire:
+    /// - BACnet/IP, Ethernet and BACnet/SC: 1497 octets
+    /// - MS/TP: 1497 octets in extended data frames, 501 in plain ones
+    /// - PTP: 501 octets
+    fn max_npdu_length(&self) -> usize;
+}
+
+/// Boxed data links, so a network layer can hold any transport chosen at
+/// run time as a `Box<dyn DataLink>`.
+impl<T: DataLink + ?Sized> DataLink for Box<T> {
+    fn send_frame(&mut self, frame: &[u8], dest: &DataLinkAddress) -> Result<()> {
+        (**self).send_frame(frame, dest)
+    }
+
+    fn receive_frame(&mut self) -> Result<(Vec<u8>, DataLinkAddress)> {
+        (**self).receive_frame()
+    }
+
+    fn link_type(&self) -> DataLinkType {
+        (**self).link_type()
+    }
+
+    fn local_address(&self) -> DataLinkAddress {
+        (**self).local_address()
+    }
+
+    fn broadcast_address(&self) -> DataLinkAddress {
+        (**self).broadcast_address()
+    }
+
+    fn max_npdu_length(&self) -> usize {
+        (**self).max_npdu_length()
+    }
 }
 
 /// Data link layer address representation.
@@ -462,6 +537,17 @@ pub enum DataLinkAddress {
     /// - 255: Broadcast address
     MsTP(u8),
 
+    /// BACnet/SC virtual MAC address (VMAC).
+    ///
+    /// Used with BACnet/SC data links. The 6-byte VMAC identifies a node on
+    /// the virtual network; `FF:FF:FF:FF:FF:FF` is the broadcast VMAC.
+    SecureConnect([u8; 6]),
+
+    /// The peer at the other end of a PTP connection.
+    ///
+    /// PTP stations have no addresses, as there is only ever one peer.
+    PointToPoint,
+
     /// Broadcast address for sending to all devices.
     ///
     /// This is a logical broadcast that is translated to the appropriate
@@ -469,9 +555,57 @@ pub enum DataLinkAddress {
     /// - BACnet/IP: UDP broadcast or multicast
     /// - Ethernet: FF:FF:FF:FF:FF:FF
     /// - MS/TP: Station address 255
+    /// - BACnet/SC: VMAC FF:FF:FF:FF:FF:FF
+    /// - PTP: The peer
     Broadcast,
 }
 
+impl DataLinkAddress {
+    /// The MAC address in its network layer form, as carried in SADR and
+    /// DADR.
+    ///
+    /// B/IP addresses are the IPv4 address and port in six octets; PTP and
+    /// broadcast addresses are empty.
+    pub fn to_mac(&self) -> Vec<u8> {
+        match self {
+            #[cfg(feature = "std")]
+            DataLinkAddress::Ip(SocketAddr::V4(addr)) => {
+                let mut mac = addr.ip().octets().to_vec();
+                mac.extend_from_slice(&addr.port().to_be_bytes());
+                mac
+            }
+            DataLinkAddress::Ethernet(mac) | DataLinkAddress::SecureConnect(mac) => mac.to_vec(),
+            DataLinkAddress::MsTP(station) => vec![*station],
+            _ => Vec::new(),
+        }
+    }
+
+    /// The address a network layer MAC address stands for on a data link of
+    /// type `link_type`.
+    ///
+    /// An empty MAC is the broadcast address. Returns `None` if the MAC does
+    /// not fit the data link.
+    pub fn from_mac(link_type: DataLinkType, mac: &[u8]) -> Option<Self> {
+        if mac.is_empty() {
+            return Some(DataLinkAddress::Broadcast);
+        }
+        match (link_type, mac.len()) {
+            #[cfg(feature = "std")]
+            (DataLinkType::BacnetIp, 6) => Some(DataLinkAddress::Ip(SocketAddr::from((
+                [mac[0], mac[1], mac[2], mac[3]],
+                u16::from_be_bytes([mac[4], mac[5]]),
+            )))),
+            (DataLinkType::Ethernet, 6) => Some(DataLinkAddress::Ethernet(mac.try_into().ok()?)),
+            (DataLinkType::SecureConnect, 6) => {
+                Some(DataLinkAddress::SecureConnect(mac.try_into().ok()?))
+            }
+            (DataLinkType::MsTP, 1) => Some(DataLinkAddress::MsTP(mac[0])),
+            (DataLinkType::PointToPoint, _) => Some(DataLinkAddress::PointToPoint),
+            _ => None,
+        }
+    }
+}
+
 /// BACnet/IP (Annex J) implementation.
 ///
 /// This module provides BACnet communication over IP networks using UDP port 47808.
@@ -479,11 +613,64 @@ pub enum DataLinkAddress {
 /// device registration, and BBMD (BACnet Broadcast Management Device) support.
 pub mod bip;
 
+/// BBMD (BACnet Broadcast Management Device) role for BACnet/IP.
+///
+/// This module holds the Broadcast Distribution Table with one-hop and
+/// two-hop masks, the Foreign Device Table with TTL expiry, and the rules for
+/// forwarding broadcasts between them.
+#[cfg(feature = "std")]
+pub mod bbmd;
+
+/// Foreign device registration with a remote BBMD.
+///
+/// This module keeps a foreign device registered, renewing it before the
+/// time-to-live expires and retrying after NAKs.
+#[cfg(feature = "std")]
+pub mod foreign_device;
+
+/// BACnet/SC (Annex AB) node implementation.
+///
+/// This module provides the BVLC-SC message codec and a node that connects
+/// to a primary or failover hub over secure WebSockets, keeping the
+/// connection alive with heartbeats and reconnecting when it drops.
+#[cfg(feature = "std")]
+pub mod bsc;
+
+/// BACnet/SC certificate management.
+///
+/// This module checks and installs a node's operational and issuer
+/// certificates, exchanged with configuration tools through File objects,
+/// and warns before they expire.
+#[cfg(feature = "std")]
+pub mod sc_certificates;
+
+/// BACnet/SC direct connections between nodes.
+///
+/// This module holds the state of a direct node-to-node WebSocket
+/// connection, used for unicast traffic that would otherwise cross the hub.
+#[cfg(feature = "std")]
+pub mod sc_direct;
+
+/// BACnet/SC hub function.
+///
+/// This module accepts node connections, keeps the VMAC routing table, and
+/// forwards unicast and broadcast messages between the connected nodes.
+#[cfg(feature = "std")]
+pub mod sc_hub;
+
+/// WebSocket (RFC 6455) framing used by BACnet/SC.
+///
+/// This module implements the opening handshake and binary message framing
+/// over any byte stream, typically a TLS session supplied by the application.
+#[cfg(feature = "std")]
+pub mod websocket;
+
 /// BACnet/Ethernet (ISO 8802-3) implementation.
 ///
 /// This module provides direct Ethernet frame communication for BACnet, using
-/// Ethernet type 0x82DC and LLC headers for protocol identification. It offers
-/// high performance on local networks.
+/// 802.3 frames with an LLC header for protocol identification. It offers
+/// high performance on local networks, over a raw socket on Linux or any
+/// other frame I/O.
 pub mod ethernet;
 
 /// MS/TP (Master-Slave/Token-Passing) implementation.
@@ -493,6 +680,37 @@ pub mod ethernet;
 /// its low cost and ability to support long cable runs.
 pub mod mstp;
 
+/// PTP (Point-to-Point) implementation.
+///
+/// This module provides BACnet communication between two half-routers over
+/// an EIA-232 line or a dial-up modem, with connection establishment,
+/// heartbeats and retransmission of unacknowledged frames.
+#[cfg(feature = "std")]
+pub mod ptp;
+
+/// MS/TP slave proxy for routers.
+///
+/// This module discovers slaves on an MS/TP network and answers Who-Is
+/// requests from other networks on their behalf.
+#[cfg(feature = "std")]
+pub mod slave_proxy;
+
+/// MS/TP automatic station addressing.
+///
+/// This module claims a free station address for a device without address
+/// switches, and backs off when another station turns out to hold it.
+#[cfg(feature = "std")]
+pub mod zero_config;
+
+/// Asynchronous data links on Tokio.
+///
+/// This module provides the `async` data link trait, with BACnet/IP on a
+/// Tokio UDP socket, MS/TP, PTP and BACnet/SC tasks on async byte streams,
+/// BACnet/Ethernet on a raw socket, and an adapter that runs an
+/// application's own blocking data link on the blocking thread pool.
+#[cfg(feature = "async")]
+pub mod async_link;
+
 /// Frame validation and analysis utilities.
 ///
 /// This module provides comprehensive validation functions for all supported
@@ -503,8 +721,17 @@ pub mod validation;
 #[cfg(feature = "std")]
 pub use bip::BacnetIpDataLink;
 
+#[cfg(feature = "std")]
+pub use bsc::ScDataLink;
+
 #[cfg(feature = "std")]
 pub use ethernet::EthernetDataLink;
 
 #[cfg(feature = "std")]
 pub use mstp::MstpDataLink;
+
+#[cfg(feature = "std")]
+pub use ptp::PtpDataLink;
+
+#[cfg(feature = "async")]
+pub use async_link::AsyncDataLink;
@@ -24,18 +24,48 @@
 //! - Header CRC (1 byte)
 //! - Data (0-501 bytes)
 //! - Data CRC (2 bytes) - only if data length > 0
+//!
+//! # Extended Frames
+//!
+//! NPDUs longer than 501 octets, up to 1497, travel in the extended data
+//! frame types (Clause 9.10). Their data field holds the NPDU and a CRC-32K,
+//! each COBS-encoded so that the preamble octet 0x55 never appears, and the
+//! Length field is two less than its size so that stations without extended
+//! frame support skip the frame correctly.
+//!
+//! # State Machines
+//!
+//! [`FrameReceiver`] is the Receive Frame state machine: it finds frames in
+//! the octet stream, checks the CRCs and discards frames interrupted for
+//! Tframe_abort. [`MasterNode`] is the Master Node state machine, which
+//! passes the token, polls for new masters and answers data requests, or
+//! sends Reply-Postponed when the answer takes longer than Treply_delay.
+//! Both do no I/O and take the current time as an argument;
+//! [`MstpDataLink`] runs them on a serial port.
+//!
+//! A station configured with [`MstpConfig::zero_config`] has no address
+//! until it claims one; see [`zero_config`](crate::datalink::zero_config).
 
 #[cfg(feature = "std")]
 use std::{
     collections::VecDeque,
-    sync::{Arc, Mutex},
+    io::{self, ErrorKind, Read, Write},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Condvar, Mutex,
+    },
+    thread::JoinHandle,
+    time::{Duration, Instant},
 };
 
 #[cfg(not(feature = "std"))]
-use alloc::{collections::VecDeque, string::String, vec::Vec};
+use alloc::{string::String, vec::Vec};
 
+#[cfg(feature = "std")]
+use crate::datalink::zero_config::{ZeroConfig, ZeroConfigState};
 use crate::datalink::{DataLink, DataLinkAddress, DataLinkError, DataLinkType, Result};
-use crate::util::crc16_mstp;
+use crate::object::{MstpPortSettings, MstpStatistics};
+use crate::util::{crc16_mstp, crc32k, crc8_mstp};
 
 /// MS/TP frame preamble bytes
 pub const MSTP_PREAMBLE_55: u8 = 0x55;
@@ -50,6 +80,18 @@ pub const MSTP_HEADER_SIZE: usize = 8;
 /// MS/TP maximum frame size
 pub const MSTP_MAX_FRAME_SIZE: usize = MSTP_HEADER_SIZE + MSTP_MAX_DATA_LENGTH + 2;
 
+/// Maximum NPDU length of an extended data frame
+pub const MSTP_MAX_EXTENDED_DATA_LENGTH: usize = 1497;
+
+/// Maximum size of an extended data frame: the NPDU grows by one octet per
+/// 254 when COBS-encoded, plus one, and the encoded CRC-32K takes five
+pub const MSTP_MAX_EXTENDED_FRAME_SIZE: usize =
+    MSTP_HEADER_SIZE + MSTP_MAX_EXTENDED_DATA_LENGTH + MSTP_MAX_EXTENDED_DATA_LENGTH / 254 + 1 + 5;
+
+/// Mask applied to COBS-encoded octets, so that encoded data avoids the
+/// first preamble octet rather than zero
+const COBS_MASK: u8 = MSTP_PREAMBLE_55;
+
 /// MS/TP frame types
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
@@ -70,6 +112,12 @@ pub enum MstpFrameType {
     BacnetDataNotExpectingReply = 6,
     /// Reply Postponed frame
     ReplyPostponed = 7,
+    /// BACnet Extended Data Expecting Reply frame
+    BacnetExtendedDataExpectingReply = 32,
+    /// BACnet Extended Data Not Expecting Reply frame
+    BacnetExtendedDataNotExpectingReply = 33,
+    /// IPv6 over MS/TP Encapsulation frame
+    Ipv6Encapsulation = 34,
 }
 
 impl MstpFrameType {
@@ -84,9 +132,30 @@ impl MstpFrameType {
             5 => Some(Self::BacnetDataExpectingReply),
             6 => Some(Self::BacnetDataNotExpectingReply),
             7 => Some(Self::ReplyPostponed),
+            32 => Some(Self::BacnetExtendedDataExpectingReply),
+            33 => Some(Self::BacnetExtendedDataNotExpectingReply),
+            34 => Some(Self::Ipv6Encapsulation),
             _ => None,
         }
     }
+
+    /// Whether the data field is COBS-encoded with a CRC-32K
+    pub fn is_extended(self) -> bool {
+        matches!(
+            self,
+            Self::BacnetExtendedDataExpectingReply
+                | Self::BacnetExtendedDataNotExpectingReply
+                | Self::Ipv6Encapsulation
+        )
+    }
+
+    /// Whether the frame carries an NPDU that expects a reply
+    pub fn expects_reply(self) -> bool {
+        matches!(
+            self,
+            Self::BacnetDataExpectingReply | Self::BacnetExtendedDataExpectingReply
+        )
+    }
 }
 
 /// MS/TP frame structure
@@ -98,13 +167,14 @@ pub struct MstpFrame {
     pub destination: u8,
     /// Source address
     pub source: u8,
-    /// Data length
+    /// Data length, as in the header; for extended frames, two less than the
+    /// encoded data field
     pub data_length: u16,
     /// Header CRC
     pub header_crc: u8,
     /// Frame data
     pub data: Vec<u8>,
-    /// Data CRC (only present if data_length > 0)
+    /// Data CRC (only present if data_length > 0 in non-extended frames)
     pub data_crc: Option<u16>,
 }
 
@@ -116,11 +186,18 @@ impl MstpFrame {
         source: u8,
         data: Vec<u8>,
     ) -> Result<Self> {
-        if data.len() > MSTP_MAX_DATA_LENGTH {
+        let (data_length, data_crc) = if frame_type.is_extended() {
+            if data.is_empty() || data.len() > MSTP_MAX_EXTENDED_DATA_LENGTH {
+                return Err(DataLinkError::InvalidFrame);
+            }
+            ((cobs_frame_encode(&data).len() - 2) as u16, None)
+        } else if data.len() > MSTP_MAX_DATA_LENGTH {
             return Err(DataLinkError::InvalidFrame);
-        }
-
-        let data_length = data.len() as u16;
+        } else if data.is_empty() {
+            (0, None)
+        } else {
+            (data.len() as u16, Some(crc16_mstp(&data)))
+        };
 
         // Calculate header CRC (without preamble)
         let header_bytes = [
@@ -132,13 +209,6 @@ impl MstpFrame {
         ];
         let header_crc = calculate_header_crc(&header_bytes);
 
-        // Calculate data CRC if there's data
-        let data_crc = if !data.is_empty() {
-            Some(crc16_mstp(&data))
-        } else {
-            None
-        };
-
         Ok(Self {
             frame_type,
             destination,
@@ -155,17 +225,19 @@ impl MstpFrame {
         Self::new(MstpFrameType::Token, destination, source, Vec::new())
     }
 
-    /// Create a BACnet data frame
+    /// Create a BACnet data frame, extended if the NPDU is longer than 501
+    /// octets
     pub fn bacnet_data(
         destination: u8,
         source: u8,
         data: Vec<u8>,
         expecting_reply: bool,
     ) -> Result<Self> {
-        let frame_type = if expecting_reply {
-            MstpFrameType::BacnetDataExpectingReply
-        } else {
-            MstpFrameType::BacnetDataNotExpectingReply
+        let frame_type = match (data.len() > MSTP_MAX_DATA_LENGTH, expecting_reply) {
+            (false, true) => MstpFrameType::BacnetDataExpectingReply,
+            (false, false) => MstpFrameType::BacnetDataNotExpectingReply,
+            (true, true) => MstpFrameType::BacnetExtendedDataExpectingReply,
+            (true, false) => MstpFrameType::BacnetExtendedDataNotExpectingReply,
         };
         Self::new(frame_type, destination, source, data)
     }
@@ -187,7 +259,9 @@ impl MstpFrame {
         frame.push(self.header_crc);
 
         // Data
-        if !self.data.is_empty() {
+        if self.frame_type.is_extended() {
+            frame.extend(cobs_frame_encode(&self.data));
+        } else if !self.data.is_empty() {
             frame.extend_from_slice(&self.data);
 
             // Data CRC
@@ -233,7 +307,11 @@ impl MstpFrame {
         }
 
         // Parse data and CRC if present
-        let (frame_data, data_crc) = if data_length > 0 {
+        let (frame_data, data_crc) = if frame_type.is_extended() {
+            let frame_data =
+                cobs_frame_decode(&data[MSTP_HEADER_SIZE..]).ok_or(DataLinkError::CrcError)?;
+            (frame_data, None)
+        } else if data_length > 0 {
             let data_start = MSTP_HEADER_SIZE;
             let data_end = data_start + data_length as usize;
             let frame_data = data[data_start..data_end].to_vec();
@@ -274,7 +352,10 @@ impl MstpFrame {
     pub fn is_data(&self) -> bool {
         matches!(
             self.frame_type,
-            MstpFrameType::BacnetDataExpectingReply | MstpFrameType::BacnetDataNotExpectingReply
+            MstpFrameType::BacnetDataExpectingReply
+                | MstpFrameType::BacnetDataNotExpectingReply
+                | MstpFrameType::BacnetExtendedDataExpectingReply
+                | MstpFrameType::BacnetExtendedDataNotExpectingReply
         )
     }
 }
@@ -344,12 +425,25 @@ pub struct MstpConfig {
     pub max_master: u8,
     /// Maximum info frames (number of frames to send when holding token)
     pub max_info_frames: u8,
-    /// Token rotation timeout (milliseconds)
+    /// Silence after which the token is taken as lost, Tno_token (milliseconds)
     pub token_timeout: u64,
-    /// Reply timeout (milliseconds)
+    /// Time to wait for a reply to a data request, Treply_timeout (milliseconds)
     pub reply_timeout: u64,
-    /// Usage timeout (milliseconds)
+    /// Time to wait for a station to use a token or answer a Poll-For-Master,
+    /// Tusage_timeout (milliseconds)
     pub usage_timeout: u64,
+    /// Time allowed to answer a data request before sending Reply-Postponed,
+    /// Treply_delay (milliseconds)
+    pub reply_delay: u64,
+    /// Silence within a frame after which it is discarded, Tframe_abort
+    /// (milliseconds). The standard asks for at least 60 bit times and at
+    /// most 100 ms; hosts without a real-time serial driver need the margin.
+    pub frame_abort: u64,
+    /// Baud rate of the serial line
+    pub baud_rate: u32,
+    /// UUID of a station that claims its own address, trying
+    /// `station_address` first when it is free
+    pub zero_config: Option<[u8; 16]>,
 }
 
 impl Default for MstpConfig {
@@ -360,134 +454,981 @@ impl Default for MstpConfig {
             max_info_frames: 1,
             token_timeout: 500,
             reply_timeout: 255,
-            usage_timeout: 50,
+            usage_timeout: 20,
+            reply_delay: 250,
+            frame_abort: 20,
+            baud_rate: 38400,
+            zero_config: None,
+        }
+    }
+}
+
+impl MstpConfig {
+    /// Create a configuration from the settings of an MS/TP Network Port
+    pub fn from_settings(settings: &MstpPortSettings) -> Self {
+        Self {
+            station_address: settings.mac_address,
+            max_master: settings.max_master,
+            max_info_frames: settings.max_info_frames,
+            baud_rate: settings.baud_rate,
+            ..Default::default()
         }
     }
+
+    /// Time to wait after the last octet received before transmitting,
+    /// Tturnaround (40 bit times)
+    #[cfg(feature = "std")]
+    pub fn turnaround(&self) -> Duration {
+        self.bit_times(40)
+    }
+
+    /// Time to transmit `octets` octets, at 10 bits each
+    #[cfg(feature = "std")]
+    pub fn transmission_time(&self, octets: usize) -> Duration {
+        self.bit_times(octets as u64 * 10)
+    }
+
+    #[cfg(feature = "std")]
+    fn bit_times(&self, bits: u64) -> Duration {
+        Duration::from_micros(bits * 1_000_000 / self.baud_rate.max(1) as u64)
+    }
+
+    /// The station after `station` in token order
+    fn next_station(&self, station: u8) -> u8 {
+        ((station as u16 + 1) % (self.max_master as u16 + 1)) as u8
+    }
+}
+
+/// Number of tokens between maintenance Poll-For-Master frames, Npoll
+pub const MSTP_NPOLL: u8 = 50;
+
+/// Number of retries when passing the token, Nretry_token
+pub const MSTP_NRETRY_TOKEN: u8 = 1;
+
+/// Octets that show another station is using the bus, Nmin_octets
+pub const MSTP_NMIN_OCTETS: u32 = 4;
+
+/// Time slot per station when generating a lost token, Tslot (milliseconds)
+pub const MSTP_SLOT_TIME: u64 = 10;
+
+/// Address that reaches every station
+pub const MSTP_BROADCAST_ADDRESS: u8 = 255;
+
+/// What the frame receiver made of the octets on the line
+#[cfg(feature = "std")]
+#[derive(Debug, Clone)]
+pub enum ReceiveEvent {
+    /// A frame with valid CRCs
+    Frame(MstpFrame),
+    /// A frame with valid CRCs of a type this implementation does not know,
+    /// such as a proprietary frame
+    UnknownFrame {
+        frame_type: u8,
+        destination: u8,
+        source: u8,
+    },
+    /// A frame the receiver discarded
+    InvalidFrame(FrameError),
+}
+
+/// Why the frame receiver discarded a frame
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameError {
+    /// The header or data CRC was wrong
+    Crc,
+    /// The frame was cut short by Tframe_abort or was too long to receive
+    Framing,
+}
+
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ReceiveState {
+    /// Waiting for the first preamble octet
+    Idle,
+    /// Waiting for the second preamble octet
+    Preamble,
+    /// Reading the header and its CRC
+    Header,
+    /// Reading the data and its CRC
+    Data,
+}
+
+/// The Receive Frame state machine (Clause 9.5.4), fed one octet at a time
+#[cfg(feature = "std")]
+#[derive(Debug, Clone)]
+pub struct FrameReceiver {
+    state: ReceiveState,
+    /// Octets of the frame so far, preamble included
+    buffer: Vec<u8>,
+    /// Data length from the header
+    data_length: usize,
+    /// When the last octet arrived
+    last_octet: Option<Instant>,
+    /// Tframe_abort
+    frame_abort: Duration,
 }
 
+#[cfg(feature = "std")]
+impl FrameReceiver {
+    /// Create a receiver that discards frames interrupted for `frame_abort`
+    pub fn new(frame_abort: Duration) -> Self {
+        Self {
+            state: ReceiveState::Idle,
+            buffer: Vec::new(),
+            data_length: 0,
+            last_octet: None,
+            frame_abort,
+        }
+    }
+
+    /// Take one octet received at `now`, returning an event once a frame is
+    /// complete or has been abandoned
+    pub fn receive(&mut self, octet: u8, now: Instant) -> Option<ReceiveEvent> {
+        let aborted = self.timeout(now);
+        self.last_octet = Some(now);
+        match self.state {
+            ReceiveState::Idle => {
+                if octet == MSTP_PREAMBLE_55 {
+                    self.buffer = vec![octet];
+                    self.state = ReceiveState::Preamble;
+                }
+            }
+            ReceiveState::Preamble => match octet {
+                MSTP_PREAMBLE_FF => {
+                    self.buffer.push(octet);
+                    self.state = ReceiveState::Header;
+                }
+                // Repeated first preamble octets are allowed
+                MSTP_PREAMBLE_55 => {}
+                _ => self.state = ReceiveState::Idle,
+            },
+            ReceiveState::Header => {
+                self.buffer.push(octet);
+                if self.buffer.len() == MSTP_HEADER_SIZE {
+                    self.state = ReceiveState::Idle;
+                    if crc8_mstp(&self.buffer[2..7]) != self.buffer[7] {
+                        return Some(ReceiveEvent::InvalidFrame(FrameError::Crc));
+                    }
+                    self.data_length =
+                        u16::from_be_bytes([self.buffer[5], self.buffer[6]]) as usize;
+                    if self.data_length == 0 {
+                        return Some(self.complete());
+                    }
+                    self.state = ReceiveState::Data;
+                }
+            }
+            ReceiveState::Data => {
+                self.buffer.push(octet);
+                if self.buffer.len() == MSTP_HEADER_SIZE + self.data_length + 2 {
+                    self.state = ReceiveState::Idle;
+                    return Some(self.complete());
+                }
+            }
+        }
+        aborted
+    }
+
+    /// Abandon a frame interrupted for Tframe_abort
+    pub fn timeout(&mut self, now: Instant) -> Option<ReceiveEvent> {
+        let last_octet = self.last_octet?;
+        if now.saturating_duration_since(last_octet) <= self.frame_abort {
+            return None;
+        }
+        let state = core::mem::replace(&mut self.state, ReceiveState::Idle);
+        match state {
+            ReceiveState::Header | ReceiveState::Data => {
+                Some(ReceiveEvent::InvalidFrame(FrameError::Framing))
+            }
+            _ => None,
+        }
+    }
+
+    fn complete(&mut self) -> ReceiveEvent {
+        let buffer = core::mem::take(&mut self.buffer);
+        let max_size = match MstpFrameType::from_u8(buffer[2]) {
+            Some(frame_type) if frame_type.is_extended() => MSTP_MAX_EXTENDED_FRAME_SIZE,
+            _ => MSTP_MAX_FRAME_SIZE,
+        };
+        if buffer.len() > max_size {
+            // Too long for the input buffer: ReceivedDataNoSpace
+            return ReceiveEvent::InvalidFrame(FrameError::Framing);
+        }
+        if MstpFrameType::from_u8(buffer[2]).is_none() {
+            let data = &buffer[MSTP_HEADER_SIZE..];
+            if data.len() > 2 {
+                let (data, crc) = data.split_at(data.len() - 2);
+                if crc16_mstp(data) != u16::from_le_bytes([crc[0], crc[1]]) {
+                    return ReceiveEvent::InvalidFrame(FrameError::Crc);
+                }
+            }
+            return ReceiveEvent::UnknownFrame {
+                frame_type: buffer[2],
+                destination: buffer[3],
+                source: buffer[4],
+            };
+        }
+        match MstpFrame::decode(&buffer) {
+            Ok(frame) => ReceiveEvent::Frame(frame),
+            Err(DataLinkError::CrcError) => ReceiveEvent::InvalidFrame(FrameError::Crc),
+            Err(_) => ReceiveEvent::InvalidFrame(FrameError::Framing),
+        }
+    }
+}
+
+/// What the master node wants done after an event
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Default)]
+pub struct MstpOutcome {
+    /// Frames to transmit, in order
+    pub transmit: Vec<MstpFrame>,
+    /// NPDUs received for this station, with the source station
+    pub received: Vec<(Vec<u8>, u8)>,
+}
+
+#[cfg(feature = "std")]
+impl MstpOutcome {
+    /// Append the actions of a later outcome
+    pub fn extend(&mut self, other: MstpOutcome) {
+        self.transmit.extend(other.transmit);
+        self.received.extend(other.received);
+    }
+}
+
+/// The Master Node state machine (Clause 9.5.6)
+///
+/// The node keeps the token rotating among the masters on the line: it sends
+/// up to Max_Info_Frames queued frames when it holds the token, passes the
+/// token to the next master it knows of, and every [`MSTP_NPOLL`] tokens
+/// polls the addresses between itself and that master for new ones. When the
+/// line falls silent it regenerates the token after a delay that depends on
+/// its address, so exactly one master does so.
+///
+/// Stations above 127 run the Slave Node state machine instead: they never
+/// take the token and only send queued frames as replies to data requests.
+///
+/// The node does no I/O. It is told about octets and frames received and
+/// polled for timeouts; each call returns the frames to transmit and the
+/// NPDUs received. [`MstpDataLink`] drives it from a serial port.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone)]
+pub struct MasterNode {
+    config: MstpConfig,
+    state: MstpState,
+    /// The next master in token order, NS
+    next_station: u8,
+    /// The station last polled, PS
+    poll_station: u8,
+    /// Tokens passed since the last maintenance poll, TokenCount
+    token_count: u8,
+    /// Frames sent with the current token, FrameCount
+    frame_count: u8,
+    /// Retries of the current token pass, RetryCount
+    retry_count: u8,
+    /// Octets received since the counter was cleared, EventCount
+    event_count: u32,
+    /// Whether no other master answers polls, SoleMaster
+    sole_master: bool,
+    /// When the line last fell silent; SilenceTimer counts from here
+    silence_since: Instant,
+    /// Station and arrival of the data request being answered
+    answering: Option<(u8, Instant)>,
+    /// Frames waiting for the token
+    queue: VecDeque<MstpFrame>,
+    /// Stations seen passing the token, one bit per address
+    masters: u128,
+    /// Automatic addressing, for zero-configuration stations
+    zero_config: Option<ZeroConfig>,
+    /// Counters of frames and events
+    statistics: MstpStatistics,
+}
+
+#[cfg(feature = "std")]
+impl MasterNode {
+    /// Create a node, passing through INITIALIZE to IDLE
+    ///
+    /// A zero-configuration node starts without an address, which reads as
+    /// 255 until it has claimed one.
+    pub fn new(mut config: MstpConfig, now: Instant) -> Self {
+        let zero_config = config
+            .zero_config
+            .map(|uuid| ZeroConfig::new(uuid, &config, now));
+        if zero_config.is_some() {
+            config.station_address = MSTP_BROADCAST_ADDRESS;
+        }
+        let station = config.station_address;
+        Self {
+            config,
+            state: MstpState::Idle,
+            next_station: station,
+            poll_station: station,
+            // Poll for masters at the first token
+            token_count: MSTP_NPOLL,
+            frame_count: 0,
+            retry_count: 0,
+            event_count: 0,
+            sole_master: false,
+            silence_since: now,
+            answering: None,
+            queue: VecDeque::new(),
+            masters: 0,
+            zero_config,
+            statistics: MstpStatistics::default(),
+        }
+    }
+
+    /// The node configuration
+    pub fn config(&self) -> &MstpConfig {
+        &self.config
+    }
+
+    /// The current state
+    pub fn state(&self) -> MstpState {
+        self.state
+    }
+
+    /// The next master in token order, this station when none is known
+    pub fn next_station(&self) -> u8 {
+        self.next_station
+    }
+
+    /// The station last polled for master
+    pub fn poll_station(&self) -> u8 {
+        self.poll_station
+    }
+
+    /// Whether this station found no other master
+    pub fn is_sole_master(&self) -> bool {
+        self.sole_master
+    }
+
+    /// Whether `station` has been seen passing the token
+    pub fn is_known_master(&self, station: u8) -> bool {
+        is_master_node(station) && self.masters & (1 << station) != 0
+    }
+
+    /// Counters of frames and events since the node started or the
+    /// counters were reset
+    pub fn statistics(&self) -> &MstpStatistics {
+        &self.statistics
+    }
+
+    /// Clear the counters
+    pub fn reset_statistics(&mut self) {
+        self.statistics = MstpStatistics::default();
+    }
+
+    /// The state of automatic addressing, `None` for a station with a fixed
+    /// address
+    pub fn zero_config_state(&self) -> Option<ZeroConfigState> {
+        self.zero_config.as_ref().map(ZeroConfig::state)
+    }
+
+    /// Frames waiting for the token
+    pub fn queued(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Queue an NPDU for `destination`, to go out with the token or as the
+    /// reply to a data request from `destination`
+    pub fn queue(&mut self, npdu: Vec<u8>, destination: u8, expecting_reply: bool) -> Result<()> {
+        let expecting_reply = expecting_reply && destination != MSTP_BROADCAST_ADDRESS;
+        let frame = MstpFrame::bacnet_data(
+            destination,
+            self.config.station_address,
+            npdu,
+            expecting_reply,
+        )?;
+        self.queue.push_back(frame);
+        Ok(())
+    }
+
+    /// Note `octets` octets received at `now`, which restarts the silence
+    /// timer and shows other stations are using the line
+    pub fn octets_received(&mut self, octets: usize, now: Instant) {
+        self.silence_since = now;
+        self.event_count = self.event_count.saturating_add(octets as u32);
+        // SawTokenUser and SawFrame
+        if matches!(self.state, MstpState::PassToken | MstpState::NoToken)
+            && self.event_count > MSTP_NMIN_OCTETS
+        {
+            self.state = MstpState::Idle;
+        }
+    }
+
+    /// Note that this station's last transmission ends at `end`
+    pub fn transmission_ended(&mut self, end: Instant) {
+        self.silence_since = end;
+    }
+
+    /// Take a frame event from the receiver
+    pub fn receive(&mut self, event: ReceiveEvent, now: Instant) -> MstpOutcome {
+        let mut out = MstpOutcome::default();
+        let statistics = &mut self.statistics;
+        match &event {
+            ReceiveEvent::Frame(frame) => {
+                count(&mut statistics.frames_received);
+                let for_us = frame.destination == self.config.station_address;
+                match frame.frame_type {
+                    MstpFrameType::Token if for_us => count(&mut statistics.tokens_received),
+                    MstpFrameType::PollForMaster if for_us => {
+                        count(&mut statistics.polls_for_master_received)
+                    }
+                    _ => {}
+                }
+                if frame.frame_type == MstpFrameType::Token && is_master_node(frame.source) {
+                    self.masters |= 1 << frame.source;
+                }
+            }
+            ReceiveEvent::UnknownFrame { .. } => count(&mut statistics.frames_received),
+            ReceiveEvent::InvalidFrame(FrameError::Crc) => count(&mut statistics.crc_errors),
+            ReceiveEvent::InvalidFrame(FrameError::Framing) => {
+                count(&mut statistics.framing_errors)
+            }
+        }
+        if let Some(zero_config) = self.claiming() {
+            let reply = match &event {
+                ReceiveEvent::Frame(frame) => zero_config.receive(frame, now),
+                _ => None,
+            };
+            if let Some(reply) = reply {
+                self.transmit(reply, now, &mut out);
+            }
+            return out;
+        }
+        match self.state {
+            MstpState::WaitForReply => self.receive_reply(event, now, &mut out),
+            MstpState::PollForMaster => match event {
+                ReceiveEvent::Frame(frame)
+                    if frame.destination == self.config.station_address
+                        && frame.frame_type == MstpFrameType::ReplyToPollForMaster =>
+                {
+                    // ReceivedReplyToPFM
+                    self.sole_master = false;
+                    self.next_station = frame.source;
+                    self.poll_station = self.config.station_address;
+                    self.token_count = 0;
+                    self.pass_token(now, &mut out);
+                }
+                ReceiveEvent::InvalidFrame(_) => self.poll_finished(now, &mut out),
+                // ReceivedUnexpectedFrame: perhaps another token
+                _ => self.state = MstpState::Idle,
+            },
+            // A reply is due; other stations should be silent
+            MstpState::AnswerDataRequest => {}
+            _ => {
+                self.state = MstpState::Idle;
+                self.receive_idle(event, now, &mut out);
+            }
+        }
+        out
+    }
+
+    /// Apply the timeouts due at `now`
+    pub fn poll(&mut self, now: Instant) -> MstpOutcome {
+        let mut out = MstpOutcome::default();
+        if let Some(zero_config) = self.claiming() {
+            let test = zero_config.poll(now);
+            let claimed = (zero_config.state() == ZeroConfigState::Use)
+                .then(|| (zero_config.station(), zero_config.holds_token()));
+            if let Some(test) = test {
+                self.transmit(test, now, &mut out);
+            }
+            if let Some((station, holds_token)) = claimed {
+                self.claimed(station, holds_token, now, &mut out);
+            }
+            return out;
+        }
+        let silence = now.saturating_duration_since(self.silence_since);
+        let ms = Duration::from_millis;
+        let station = self.config.station_address;
+
+        if self.state == MstpState::Idle && is_master_node(station) {
+            // LostToken
+            if silence >= ms(self.config.token_timeout) {
+                count(&mut self.statistics.lost_tokens);
+                self.event_count = 0;
+                self.state = MstpState::NoToken;
+            }
+        }
+        match self.state {
+            MstpState::NoToken => {
+                // GenerateToken, once this station's slot comes
+                let slot = self.config.token_timeout + MSTP_SLOT_TIME * station as u64;
+                if silence >= ms(slot) {
+                    self.next_station = station;
+                    self.token_count = 0;
+                    self.poll_for_master(self.config.next_station(station), now, &mut out);
+                }
+            }
+            MstpState::PassToken if silence >= ms(self.config.usage_timeout) => {
+                if self.retry_count < MSTP_NRETRY_TOKEN {
+                    // RetrySendToken
+                    self.retry_count += 1;
+                    count(&mut self.statistics.token_retries);
+                    self.event_count = 0;
+                    let token = self.frame(MstpFrameType::Token, self.next_station);
+                    self.transmit(token, now, &mut out);
+                } else {
+                    // FindNewSuccessor
+                    let poll_station = self.config.next_station(self.next_station);
+                    self.next_station = station;
+                    self.token_count = 0;
+                    self.poll_for_master(poll_station, now, &mut out);
+                }
+            }
+            MstpState::WaitForReply if silence >= ms(self.config.reply_timeout) => {
+                // ReplyTimeout
+                self.frame_count = self.config.max_info_frames;
+                self.done_with_frame(now, &mut out);
+            }
+            MstpState::PollForMaster if silence >= ms(self.config.usage_timeout) => {
+                self.poll_finished(now, &mut out);
+            }
+            MstpState::AnswerDataRequest => self.answer(now, &mut out),
+            _ => {}
+        }
+        out
+    }
+
+    fn receive_idle(&mut self, event: ReceiveEvent, now: Instant, out: &mut MstpOutcome) {
+        // Invalid and unknown frames are ignored
+        let ReceiveEvent::Frame(frame) = event else {
+            return;
+        };
+        let station = self.config.station_address;
+        let for_us = frame.destination == station;
+        let broadcast = frame.destination == MSTP_BROADCAST_ADDRESS;
+        match frame.frame_type {
+            MstpFrameType::Token if for_us && is_master_node(station) => {
+                // ReceivedToken
+                self.frame_count = 0;
+                self.sole_master = false;
+                self.use_token(now, out);
+            }
+            MstpFrameType::PollForMaster if for_us && is_master_node(station) => {
+                let reply = self.frame(MstpFrameType::ReplyToPollForMaster, frame.source);
+                self.transmit(reply, now, out);
+            }
+            frame_type if frame_type.expects_reply() && for_us => {
+                // ReceivedDataNeedingReply
+                out.received.push((frame.data, frame.source));
+                self.answering = Some((frame.source, now));
+                self.state = MstpState::AnswerDataRequest;
+            }
+            _ if frame.is_data() && (for_us || broadcast) => {
+                // ReceivedDataNoReply
+                out.received.push((frame.data, frame.source));
+            }
+            MstpFrameType::TestRequest if for_us => {
+                let response = MstpFrame::new(
+                    MstpFrameType::TestResponse,
+                    frame.source,
+                    station,
+                    frame.data,
+                );
+                if let Ok(response) = response {
+                    self.transmit(response, now, out);
+                }
+            }
+            // ReceivedUnwantedFrame
+            _ => {}
+        }
+    }
+
+    /// WAIT_FOR_REPLY, following the negative list described on
+    /// [`MstpState`]
+    fn receive_reply(&mut self, event: ReceiveEvent, now: Instant, out: &mut MstpOutcome) {
+        let station = self.config.station_address;
+        match event {
+            ReceiveEvent::InvalidFrame(_) => self.done_with_frame(now, out),
+            ReceiveEvent::UnknownFrame { destination, .. } if destination == station => {
+                self.done_with_frame(now, out)
+            }
+            ReceiveEvent::Frame(frame) if frame.destination == station => match frame.frame_type {
+                MstpFrameType::Token
+                | MstpFrameType::PollForMaster
+                | MstpFrameType::ReplyToPollForMaster
+                | MstpFrameType::TestRequest => self.state = MstpState::Idle,
+                _ if frame.is_data() => {
+                    // ReceivedReply
+                    out.received.push((frame.data, frame.source));
+                    self.done_with_frame(now, out);
+                }
+                // ReceivedPostpone, or a test response
+                _ => self.done_with_frame(now, out),
+            },
+            // ReceivedUnexpectedFrame
+            _ => self.state = MstpState::Idle,
+        }
+    }
+
+    /// USE_TOKEN and DONE_WITH_TOKEN, until the token is passed, a reply is
+    /// awaited, or a poll is sent
+    fn use_token(&mut self, now: Instant, out: &mut MstpOutcome) {
+        loop {
+            self.state = MstpState::UseToken;
+            match self.queue.pop_front() {
+                // NothingToSend
+                None => self.frame_count = self.config.max_info_frames,
+                Some(frame) => {
+                    let wait = frame.frame_type.expects_reply();
+                    self.frame_count = self.frame_count.saturating_add(1);
+                    self.transmit(frame, now, out);
+                    if wait {
+                        // SendAndWait
+                        self.state = MstpState::WaitForReply;
+                        return;
+                    }
+                }
+            }
+            if !self.done_with_token(now, out) {
+                return;
+            }
+        }
+    }
+
+    /// DONE_WITH_TOKEN after a frame exchange
+    fn done_with_frame(&mut self, now: Instant, out: &mut MstpOutcome) {
+        if self.done_with_token(now, out) {
+            self.use_token(now, out);
+        }
+    }
+
+    /// DONE_WITH_TOKEN; returns whether to use the token again
+    fn done_with_token(&mut self, now: Instant, out: &mut MstpOutcome) -> bool {
+        self.state = MstpState::DoneWithToken;
+        let station = self.config.station_address;
+        if self.frame_count < self.config.max_info_frames.max(1) {
+            // SendAnotherFrame
+            return true;
+        }
+        if self.token_count < MSTP_NPOLL - 1 {
+            if self.sole_master && self.next_station != self.config.next_station(station) {
+                // SoleMaster: there is nobody to pass the token to
+                self.frame_count = 0;
+                self.token_count += 1;
+                return true;
+            }
+            // SendToken
+            self.token_count += 1;
+            self.pass_token(now, out);
+        } else if self.config.next_station(self.poll_station) == self.next_station {
+            if self.sole_master {
+                // SoleMasterRestartMaintenancePFM
+                let poll_station = self.config.next_station(self.next_station);
+                self.next_station = station;
+                self.token_count = 1;
+                self.poll_for_master(poll_station, now, out);
+            } else {
+                // ResetMaintenancePFM
+                self.poll_station = station;
+                self.token_count = 1;
+                self.pass_token(now, out);
+            }
+        } else {
+            // SendMaintenancePFM
+            let poll_station = self.config.next_station(self.poll_station);
+            self.poll_for_master(poll_station, now, out);
+        }
+        false
+    }
+
+    /// POLL_FOR_MASTER when no reply came
+    fn poll_finished(&mut self, now: Instant, out: &mut MstpOutcome) {
+        let station = self.config.station_address;
+        if self.sole_master {
+            // SoleMaster
+            self.frame_count = 0;
+            self.use_token(now, out);
+        } else if self.next_station != station {
+            // DoneWithPFM
+            self.pass_token(now, out);
+        } else if self.config.next_station(self.poll_station) != station {
+            // SendNextPFM
+            let poll_station = self.config.next_station(self.poll_station);
+            self.poll_for_master(poll_station, now, out);
+        } else {
+            // DeclareSoleMaster
+            self.sole_master = true;
+            self.frame_count = 0;
+            self.use_token(now, out);
+        }
+    }
+
+    /// ANSWER_DATA_REQUEST: send the reply once queued, or Reply-Postponed
+    /// when it takes longer than Treply_delay
+    fn answer(&mut self, now: Instant, out: &mut MstpOutcome) {
+        let Some((requester, since)) = self.answering else {
+            self.state = MstpState::Idle;
+            return;
+        };
+        let reply = self
+            .queue
+            .iter()
+            .position(|frame| frame.destination == requester)
+            .and_then(|i| self.queue.remove(i));
+        let reply = match reply {
+            Some(reply) => reply,
+            None if now.saturating_duration_since(since)
+                >= Duration::from_millis(self.config.reply_delay) =>
+            {
+                self.frame(MstpFrameType::ReplyPostponed, requester)
+            }
+            None => return,
+        };
+        self.answering = None;
+        self.state = MstpState::Idle;
+        self.transmit(reply, now, out);
+    }
+
+    fn pass_token(&mut self, now: Instant, out: &mut MstpOutcome) {
+        self.retry_count = 0;
+        self.event_count = 0;
+        self.state = MstpState::PassToken;
+        let token = self.frame(MstpFrameType::Token, self.next_station);
+        self.transmit(token, now, out);
+    }
+
+    fn poll_for_master(&mut self, station: u8, now: Instant, out: &mut MstpOutcome) {
+        self.poll_station = station;
+        self.retry_count = 0;
+        self.event_count = 0;
+        self.state = MstpState::PollForMaster;
+        let poll = self.frame(MstpFrameType::PollForMaster, station);
+        self.transmit(poll, now, out);
+    }
+
+    fn frame(&self, frame_type: MstpFrameType, destination: u8) -> MstpFrame {
+        MstpFrame::new(
+            frame_type,
+            destination,
+            self.config.station_address,
+            Vec::new(),
+        )
+        .expect("frames without data are always valid")
+    }
+
+    fn transmit(&mut self, frame: MstpFrame, now: Instant, out: &mut MstpOutcome) {
+        count(&mut self.statistics.frames_sent);
+        if frame.frame_type == MstpFrameType::PollForMaster {
+            count(&mut self.statistics.polls_for_master_sent);
+        }
+        self.silence_since = now;
+        out.transmit.push(frame);
+    }
+
+    /// The addressing procedure, while no address has been claimed
+    fn claiming(&mut self) -> Option<&mut ZeroConfig> {
+        self.zero_config
+            .as_mut()
+            .filter(|zero_config| zero_config.state() != ZeroConfigState::Use)
+    }
+
+    /// Take up the address automatic addressing claimed, using the token if
+    /// the claim came with it
+    fn claimed(&mut self, station: u8, holds_token: bool, now: Instant, out: &mut MstpOutcome) {
+        self.config.station_address = station;
+        self.next_station = station;
+        self.poll_station = station;
+        self.token_count = MSTP_NPOLL;
+        // Frames queued before the claim carry no source address yet
+        self.queue = self
+            .queue
+            .drain(..)
+            .filter_map(|frame| {
+                MstpFrame::new(frame.frame_type, frame.destination, station, frame.data).ok()
+            })
+            .collect();
+        if holds_token {
+            self.frame_count = 0;
+            self.use_token(now, out);
+        } else {
+            self.state = MstpState::Idle;
+        }
+    }
+}
+
+/// How long [`MstpDataLink::receive_frame`](DataLink::receive_frame) waits
+/// for a frame
+#[cfg(feature = "std")]
+pub const MSTP_RECEIVE_TIMEOUT: Duration = Duration::from_millis(100);
+
 /// Type alias for receive queue
 #[cfg(feature = "std")]
-type ReceiveQueue = Arc<Mutex<VecDeque<(Vec<u8>, DataLinkAddress)>>>;
+type ReceiveQueue = Arc<(Mutex<VecDeque<(Vec<u8>, DataLinkAddress)>>, Condvar)>;
 
 /// MS/TP data link implementation
+///
+/// A thread owns the serial port and runs the frame receiver and the
+/// [`MasterNode`] state machine, so the token keeps moving between calls.
+/// Frames sent are queued until this station holds the token; frames received
+/// are queued for [`receive_frame`](DataLink::receive_frame).
 #[cfg(feature = "std")]
 pub struct MstpDataLink {
-    /// Configuration
-    config: MstpConfig,
-    /// Current state
-    _state: Arc<Mutex<MstpState>>,
-    /// Token holder
-    _token_holder: Arc<Mutex<Option<u8>>>,
-    /// Next station for token passing
-    _next_station: Arc<Mutex<u8>>,
-    /// Send queue
-    send_queue: Arc<Mutex<VecDeque<(MstpFrame, DataLinkAddress)>>>,
+    /// Master node state machine, shared with the port thread
+    node: Arc<Mutex<MasterNode>>,
     /// Receive queue
     receive_queue: ReceiveQueue,
-    /// Serial port name
-    _port_name: String,
     /// Running flag
-    _running: Arc<Mutex<bool>>,
+    running: Arc<AtomicBool>,
+    /// Port thread
+    thread: Option<JoinHandle<()>>,
 }
 
 #[cfg(feature = "std")]
 impl MstpDataLink {
-    /// Create a new MS/TP data link
+    /// Open the serial device at `path` (e.g. `/dev/ttyUSB0`) and start the
+    /// node on it
     ///
-    /// Note: In a real implementation, this would use a serial port library
-    /// to communicate over RS-485. This is a simplified simulation.
-    pub fn new(port_name: &str, config: MstpConfig) -> Result<Self> {
-        let state = Arc::new(Mutex::new(MstpState::Initialize));
-        let token_holder = Arc::new(Mutex::new(None));
-        let next_station = Arc::new(Mutex::new(
-            (config.station_address + 1) % (config.max_master + 1),
-        ));
-        let send_queue = Arc::new(Mutex::new(VecDeque::new()));
-        let receive_queue = Arc::new(Mutex::new(VecDeque::new()));
-        let running = Arc::new(Mutex::new(true));
+    /// The device must already be set up for the baud rate, 8N1 and raw mode,
+    /// with reads that return within a millisecond or two when no data is
+    /// waiting, as `stty raw min 0 time 0` does, and the RS-485 adapter must
+    /// not echo transmitted octets.
+    pub fn open(path: &str, config: MstpConfig) -> Result<Self> {
+        let port = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(path)
+            .map_err(DataLinkError::IoError)?;
+        Self::new(port, config)
+    }
 
-        // In a real implementation, we would:
-        // 1. Open serial port with appropriate settings (9600-115200 bps, 8N1)
-        // 2. Configure RS-485 transceiver control
-        // 3. Start token passing state machine thread
+    /// Start the node on a byte stream to the line
+    ///
+    /// Reads must time out or return no data within a millisecond or two, so
+    /// the state machine can keep its timing.
+    pub fn new<P: Read + Write + Send + 'static>(port: P, config: MstpConfig) -> Result<Self> {
+        if config.station_address == MSTP_BROADCAST_ADDRESS && config.zero_config.is_none() {
+            return Err(DataLinkError::AddressError(
+                "MS/TP station address 255 is the broadcast address".into(),
+            ));
+        }
+        let node = Arc::new(Mutex::new(MasterNode::new(config.clone(), Instant::now())));
+        let receive_queue: ReceiveQueue = Arc::new((Mutex::new(VecDeque::new()), Condvar::new()));
+        let running = Arc::new(AtomicBool::new(true));
+        let thread = {
+            let node = node.clone();
+            let receive_queue = receive_queue.clone();
+            let running = running.clone();
+            std::thread::spawn(move || run_port(port, config, node, receive_queue, running))
+        };
 
         Ok(Self {
-            config,
-            _state: state,
-            _token_holder: token_holder,
-            _next_station: next_station,
-            send_queue,
+            node,
             receive_queue,
-            _port_name: port_name.to_string(),
-            _running: running,
+            running,
+            thread: Some(thread),
         })
     }
 
-    /// Send an MS/TP frame
-    fn _send_mstp_frame(&self, frame: &MstpFrame) -> Result<()> {
-        // In a real implementation, this would:
-        // 1. Enable RS-485 transmitter
-        // 2. Send frame bytes over serial port
-        // 3. Wait for transmission to complete
-        // 4. Disable RS-485 transmitter
-
-        let encoded = frame.encode();
-
-        println!(
-            "MS/TP: Sending {} frame from {} to {}, {} bytes",
-            match frame.frame_type {
-                MstpFrameType::Token => "Token",
-                MstpFrameType::BacnetDataExpectingReply => "Data (expecting reply)",
-                MstpFrameType::BacnetDataNotExpectingReply => "Data (no reply)",
-                _ => "Other",
-            },
-            frame.source,
-            frame.destination,
-            encoded.len()
-        );
-
-        Ok(())
+    /// The current state of the master node
+    pub fn state(&self) -> MstpState {
+        self.node.lock().unwrap().state()
     }
 
-    /// Handle token possession
-    fn _handle_token(&mut self) -> Result<()> {
-        let mut send_queue = self.send_queue.lock().unwrap();
+    /// The next master in token order, this station when none is known
+    pub fn next_station(&self) -> u8 {
+        self.node.lock().unwrap().next_station()
+    }
 
-        // Send up to max_info_frames
-        let mut frames_sent = 0;
-        while frames_sent < self.config.max_info_frames && !send_queue.is_empty() {
-            if let Some((frame, _)) = send_queue.pop_front() {
-                self._send_mstp_frame(&frame)?;
-                frames_sent += 1;
-            }
-        }
+    /// Whether this station found no other master
+    pub fn is_sole_master(&self) -> bool {
+        self.node.lock().unwrap().is_sole_master()
+    }
 
-        // Pass token to next station
-        let next = *self._next_station.lock().unwrap();
-        let token_frame = MstpFrame::token(next, self.config.station_address)?;
-        self._send_mstp_frame(&token_frame)?;
+    /// Whether `station` has been seen passing the token
+    pub fn is_known_master(&self, station: u8) -> bool {
+        self.node.lock().unwrap().is_known_master(station)
+    }
 
-        // Update next station
-        let mut next_station = self._next_station.lock().unwrap();
-        *next_station = (*next_station + 1) % (self.config.max_master + 1);
+    /// Counters of frames and events on the port
+    pub fn statistics(&self) -> MstpStatistics {
+        *self.node.lock().unwrap().statistics()
+    }
 
-        Ok(())
+    /// Clear the port counters
+    pub fn reset_statistics(&self) {
+        self.node.lock().unwrap().reset_statistics();
     }
+}
 
-    /// Simulate receiving a frame (for testing)
-    #[cfg(test)]
-    pub fn simulate_receive(&self, frame: MstpFrame) {
-        if frame.is_data() && !frame.data.is_empty() {
-            let mut receive_queue = self.receive_queue.lock().unwrap();
-            receive_queue.push_back((frame.data.clone(), DataLinkAddress::MsTP(frame.source)));
+#[cfg(feature = "std")]
+impl Drop for MstpDataLink {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
         }
+    }
+}
 
-        if frame.is_token() && frame.destination == self.config.station_address {
-            let mut token_holder = self._token_holder.lock().unwrap();
-            *token_holder = Some(self.config.station_address);
+/// Body of the port thread
+#[cfg(feature = "std")]
+fn run_port<P: Read + Write>(
+    mut port: P,
+    config: MstpConfig,
+    node: Arc<Mutex<MasterNode>>,
+    receive_queue: ReceiveQueue,
+    running: Arc<AtomicBool>,
+) {
+    let mut receiver = FrameReceiver::new(Duration::from_millis(config.frame_abort));
+    let mut buffer = [0u8; MSTP_MAX_FRAME_SIZE];
+    let mut last_octet = Instant::now();
+    while running.load(Ordering::Relaxed) {
+        let read = port.read(&mut buffer);
+        let now = Instant::now();
+        let mut outcome = MstpOutcome::default();
+        let mut master = node.lock().unwrap();
+        match read {
+            Ok(0) => {
+                drop(master);
+                std::thread::sleep(Duration::from_millis(1));
+                master = node.lock().unwrap();
+            }
+            Ok(count) => {
+                master.octets_received(count, now);
+                last_octet = now;
+                for &octet in &buffer[..count] {
+                    if let Some(event) = receiver.receive(octet, now) {
+                        outcome.extend(master.receive(event, now));
+                    }
+                }
+            }
+            Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+            Err(e) if e.kind() == ErrorKind::Interrupted => {}
+            Err(_) => return,
+        }
+        if let Some(event) = receiver.timeout(now) {
+            outcome.extend(master.receive(event, now));
+        }
+        outcome.extend(master.poll(now));
+        drop(master);
+
+        if !outcome.transmit.is_empty() {
+            // Tturnaround
+            let wait = config.turnaround().saturating_sub(last_octet.elapsed());
+            std::thread::sleep(wait);
+            let mut octets = 0;
+            for frame in &outcome.transmit {
+                let encoded = frame.encode();
+                octets += encoded.len();
+                if port.write_all(&encoded).is_err() {
+                    return;
+                }
+            }
+            let _ = port.flush();
+            let end = Instant::now() + config.transmission_time(octets);
+            node.lock().unwrap().transmission_ended(end);
+        }
 
-            let mut state = self._state.lock().unwrap();
-            *state = MstpState::UseToken;
+        if !outcome.received.is_empty() {
+            let (queue, ready) = &*receive_queue;
+            let mut queue = queue.lock().unwrap();
+            for (npdu, source) in outcome.received {
+                queue.push_back((npdu, DataLinkAddress::MsTP(source)));
+            }
+            ready.notify_all();
         }
     }
 }
@@ -497,7 +1438,7 @@ impl DataLink for MstpDataLink {
     fn send_frame(&mut self, frame: &[u8], dest: &DataLinkAddress) -> Result<()> {
         let dest_addr = match dest {
             DataLinkAddress::MsTP(addr) => *addr,
-            DataLinkAddress::Broadcast => 255,
+            DataLinkAddress::Broadcast => MSTP_BROADCAST_ADDRESS,
             _ => {
                 return Err(DataLinkError::AddressError(
                     "Invalid address type for MS/TP".into(),
@@ -505,30 +1446,28 @@ impl DataLink for MstpDataLink {
             }
         };
 
-        // Create MS/TP frame
-        let mstp_frame = MstpFrame::bacnet_data(
-            dest_addr,
-            self.config.station_address,
-            frame.to_vec(),
-            false, // For now, assume no reply expected
-        )?;
+        // The NPDU control octet says whether a reply is expected
+        let expecting_reply = frame.len() > 1 && frame[1] & 0x04 != 0;
 
         // Queue frame for sending when we have the token
-        let mut send_queue = self.send_queue.lock().unwrap();
-        send_queue.push_back((mstp_frame, DataLinkAddress::MsTP(dest_addr)));
-
-        Ok(())
+        self.node
+            .lock()
+            .unwrap()
+            .queue(frame.to_vec(), dest_addr, expecting_reply)
     }
 
     fn receive_frame(&mut self) -> Result<(Vec<u8>, DataLinkAddress)> {
-        let mut receive_queue = self.receive_queue.lock().unwrap();
-
-        if let Some((data, source)) = receive_queue.pop_front() {
-            Ok((data, source))
-        } else {
-            // In real implementation, this would check serial port
-            Err(DataLinkError::InvalidFrame)
-        }
+        let (queue, ready) = &*self.receive_queue;
+        let queue = queue.lock().unwrap();
+        let (mut queue, _) = ready
+            .wait_timeout_while(queue, MSTP_RECEIVE_TIMEOUT, |queue| queue.is_empty())
+            .unwrap();
+        queue.pop_front().ok_or_else(|| {
+            DataLinkError::IoError(io::Error::new(
+                ErrorKind::TimedOut,
+                "No MS/TP frame received",
+            ))
+        })
     }
 
     fn link_type(&self) -> DataLinkType {
@@ -536,26 +1475,96 @@ impl DataLink for MstpDataLink {
     }
 
     fn local_address(&self) -> DataLinkAddress {
-        DataLinkAddress::MsTP(self.config.station_address)
+        // A zero-configuration station learns its address as it runs
+        DataLinkAddress::MsTP(self.node.lock().unwrap().config().station_address)
+    }
+
+    fn broadcast_address(&self) -> DataLinkAddress {
+        DataLinkAddress::MsTP(MSTP_BROADCAST_ADDRESS)
+    }
+
+    /// Longer NPDUs than 501 octets go in extended data frames, which only
+    /// stations supporting them receive
+    fn max_npdu_length(&self) -> usize {
+        MSTP_MAX_EXTENDED_DATA_LENGTH
     }
 }
 
-/// Calculate MS/TP header CRC
-fn calculate_header_crc(header: &[u8; 5]) -> u8 {
-    let mut crc = 0xFFu8;
+/// Encode the data field of an extended frame: the COBS-encoded data
+/// followed by the COBS-encoded CRC-32K of the encoded data
+fn cobs_frame_encode(data: &[u8]) -> Vec<u8> {
+    let mut encoded = cobs_encode(data);
+    let crc = crc32k(&encoded);
+    encoded.extend(cobs_encode(&crc.to_le_bytes()));
+    encoded
+}
 
-    for &byte in header {
-        crc ^= byte;
-        for _ in 0..8 {
-            if crc & 0x01 != 0 {
-                crc = (crc >> 1) ^ 0x55;
-            } else {
-                crc >>= 1;
+/// Decode the data field of an extended frame, `None` if the CRC-32K or the
+/// encoding is bad
+fn cobs_frame_decode(encoded: &[u8]) -> Option<Vec<u8>> {
+    // Four CRC octets always encode to five
+    let (data, crc) = encoded.split_at(encoded.len().checked_sub(5)?);
+    let crc: [u8; 4] = cobs_decode(crc)?.try_into().ok()?;
+    if crc32k(data) != u32::from_le_bytes(crc) {
+        return None;
+    }
+    cobs_decode(data)
+}
+
+/// Consistent Overhead Byte Stuffing, masked with [`COBS_MASK`]
+fn cobs_encode(data: &[u8]) -> Vec<u8> {
+    let mut encoded = Vec::with_capacity(data.len() + data.len() / 254 + 1);
+    let mut code_index = 0;
+    let mut code = 1u8;
+    encoded.push(0);
+    for (i, &octet) in data.iter().enumerate() {
+        if octet != 0 {
+            encoded.push(octet ^ COBS_MASK);
+            code += 1;
+            if code != 0xFF {
+                continue;
             }
         }
+        encoded[code_index] = code ^ COBS_MASK;
+        code = 1;
+        // A full block at the very end needs no empty block after it
+        if octet != 0 && i == data.len() - 1 {
+            return encoded;
+        }
+        code_index = encoded.len();
+        encoded.push(0);
     }
+    encoded[code_index] = code ^ COBS_MASK;
+    encoded
+}
+
+fn cobs_decode(encoded: &[u8]) -> Option<Vec<u8>> {
+    let mut decoded = Vec::with_capacity(encoded.len());
+    let mut rest = encoded;
+    while let Some((&code, tail)) = rest.split_first() {
+        let code = (code ^ COBS_MASK) as usize;
+        if code == 0 || tail.len() < code - 1 {
+            return None;
+        }
+        let (block, tail) = tail.split_at(code - 1);
+        decoded.extend(block.iter().map(|octet| octet ^ COBS_MASK));
+        if code != 0xFF && !tail.is_empty() {
+            decoded.push(0);
+        }
+        rest = tail;
+    }
+    Some(decoded)
+}
+
+/// Add one to a statistics counter, stopping at the maximum
+#[cfg(feature = "std")]
+fn count(counter: &mut u32) {
+    *counter = counter.saturating_add(1);
+}
 
-    !crc
+/// Calculate MS/TP header CRC
+fn calculate_header_crc(header: &[u8; 5]) -> u8 {
+    crc8_mstp(header)
 }
 
 /// Validate MS/TP address
@@ -658,34 +1667,376 @@ mod tests {
     #[test]
     fn test_max_data_length() {
         let data = vec![0u8; MSTP_MAX_DATA_LENGTH + 1];
-        let result = MstpFrame::bacnet_data(10, 20, data, false);
+        let result = MstpFrame::new(MstpFrameType::BacnetDataNotExpectingReply, 10, 20, data);
         assert!(result.is_err());
 
         let data = vec![0u8; MSTP_MAX_DATA_LENGTH];
         let result = MstpFrame::bacnet_data(10, 20, data, false);
         assert!(result.is_ok());
+
+        // Longer NPDUs go in extended frames, up to their own limit
+        let data = vec![0u8; MSTP_MAX_DATA_LENGTH + 1];
+        let frame = MstpFrame::bacnet_data(10, 20, data, false).unwrap();
+        assert_eq!(
+            frame.frame_type,
+            MstpFrameType::BacnetExtendedDataNotExpectingReply
+        );
+        let data = vec![0u8; MSTP_MAX_EXTENDED_DATA_LENGTH + 1];
+        assert!(MstpFrame::bacnet_data(10, 20, data, true).is_err());
+    }
+
+    #[test]
+    fn test_crc_vectors() {
+        // Annex G.1: Token from 0x05 to 0x10
+        let frame = MstpFrame::token(0x10, 0x05).unwrap();
+        assert_eq!(
+            frame.encode(),
+            [0x55, 0xFF, 0x00, 0x10, 0x05, 0x00, 0x00, 0x8C]
+        );
+
+        // Annex G.2: data 01 22 30 is followed by 10 BD
+        let frame = MstpFrame::bacnet_data(0x10, 0x05, vec![0x01, 0x22, 0x30], false).unwrap();
+        assert_eq!(frame.encode()[8..], [0x01, 0x22, 0x30, 0x10, 0xBD]);
+
+        // CRC-32K check value
+        assert_eq!(crc32k(b"123456789"), 0x2D3DD0AE);
+    }
+
+    #[test]
+    fn test_cobs() {
+        for data in [
+            vec![0x00],
+            vec![0x11, 0x00, 0x00, 0x22],
+            vec![0x55; 254],
+            vec![0x55; 255],
+            [vec![0x01; 254], vec![0x00]].concat(),
+        ] {
+            let encoded = cobs_encode(&data);
+            assert!(!encoded.contains(&COBS_MASK));
+            assert!(encoded.len() <= data.len() + data.len() / 254 + 1);
+            assert_eq!(cobs_decode(&encoded), Some(data));
+        }
+        // A run of 254 non-zero octets fills one block exactly
+        assert_eq!(cobs_encode(&[0x01; 254]).len(), 255);
+        assert_eq!(cobs_decode(&[0x01 ^ COBS_MASK, 0x00]), None);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_extended_frame() {
+        let npdu: Vec<u8> = (0..1000).map(|i| [0x00, 0x55, 0x01][i % 3]).collect();
+        let frame = MstpFrame::bacnet_data(3, 1, npdu.clone(), true).unwrap();
+        assert_eq!(
+            frame.frame_type,
+            MstpFrameType::BacnetExtendedDataExpectingReply
+        );
+        let encoded = frame.encode();
+        assert_eq!(
+            encoded.len(),
+            MSTP_HEADER_SIZE + frame.data_length as usize + 2
+        );
+        assert!(!encoded[2..].contains(&MSTP_PREAMBLE_55));
+        assert_eq!(MstpFrame::decode(&encoded).unwrap().data, npdu);
+
+        let mut receiver = FrameReceiver::new(Duration::from_millis(20));
+        let now = Instant::now();
+        let events: Vec<_> = encoded
+            .iter()
+            .filter_map(|&octet| receiver.receive(octet, now))
+            .collect();
+        assert!(matches!(
+            events.as_slice(),
+            [ReceiveEvent::Frame(received)] if received.data == npdu
+        ));
+
+        let mut corrupted = encoded.clone();
+        corrupted[500] ^= 0x01;
+        assert!(matches!(
+            MstpFrame::decode(&corrupted),
+            Err(DataLinkError::CrcError)
+        ));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_frame_receiver() {
+        let start = Instant::now();
+        let mut receiver = FrameReceiver::new(Duration::from_millis(20));
+        let frame = MstpFrame::bacnet_data(3, 1, vec![0x01, 0x00, 0x10], false).unwrap();
+        let mut line = vec![0x00, 0x55, 0x55];
+        line.extend(&frame.encode()[1..]);
+
+        let events: Vec<_> = line
+            .iter()
+            .filter_map(|&octet| receiver.receive(octet, start))
+            .collect();
+        assert!(matches!(
+            events.as_slice(),
+            [ReceiveEvent::Frame(received)] if received.data == frame.data
+        ));
+
+        // A corrupted data CRC, then a frame cut short
+        let mut corrupted = frame.encode();
+        *corrupted.last_mut().unwrap() ^= 0x01;
+        let events: Vec<_> = corrupted
+            .iter()
+            .filter_map(|&octet| receiver.receive(octet, start))
+            .collect();
+        assert!(matches!(
+            events.as_slice(),
+            [ReceiveEvent::InvalidFrame(FrameError::Crc)]
+        ));
+        for &octet in &frame.encode()[..10] {
+            assert!(receiver.receive(octet, start).is_none());
+        }
+        assert!(receiver
+            .timeout(start + Duration::from_millis(10))
+            .is_none());
+        assert!(matches!(
+            receiver.timeout(start + Duration::from_millis(30)),
+            Some(ReceiveEvent::InvalidFrame(FrameError::Framing))
+        ));
+
+        // Proprietary frame types are reported for the negative list
+        let mut header = [0x80, 4, 1, 0, 0];
+        let mut proprietary = vec![0x55, 0xFF];
+        proprietary.extend(header);
+        proprietary.push(crc8_mstp(&header));
+        let event = proprietary
+            .iter()
+            .filter_map(|&octet| receiver.receive(octet, start))
+            .next();
+        assert!(matches!(
+            event,
+            Some(ReceiveEvent::UnknownFrame {
+                frame_type: 0x80,
+                destination: 4,
+                source: 1
+            })
+        ));
+        header[0] = 0x00;
+        assert_ne!(crc8_mstp(&header), proprietary[7]);
+    }
+
+    /// Run `nodes` on a simulated line from `from` to `to` milliseconds,
+    /// returning the NPDUs delivered as (station, npdu, source). Stations
+    /// queue `reply` to data requests that expect one.
+    #[cfg(feature = "std")]
+    fn run_line(
+        nodes: &mut [MasterNode],
+        start: Instant,
+        from: u64,
+        to: u64,
+        reply: bool,
+    ) -> Vec<(u8, Vec<u8>, u8)> {
+        let mut delivered = Vec::new();
+        for ms in from..to {
+            let now = start + Duration::from_millis(ms);
+            let mut line = VecDeque::new();
+            for (i, node) in nodes.iter_mut().enumerate() {
+                line.push_back((i, node.poll(now)));
+            }
+            while let Some((sender, outcome)) = line.pop_front() {
+                let station = nodes[sender].config().station_address;
+                for (npdu, source) in outcome.received {
+                    if reply && npdu[1] & 0x04 != 0 {
+                        nodes[sender]
+                            .queue(vec![0x01, 0x00, 0xAA], source, false)
+                            .unwrap();
+                    }
+                    delivered.push((station, npdu, source));
+                }
+                for frame in outcome.transmit {
+                    for (i, node) in nodes.iter_mut().enumerate() {
+                        if i != sender {
+                            node.octets_received(frame.encode().len(), now);
+                            let outcome = node.receive(ReceiveEvent::Frame(frame.clone()), now);
+                            line.push_back((i, outcome));
+                        }
+                    }
+                }
+            }
+        }
+        delivered
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_master_token_rotation() {
+        let start = Instant::now();
+        let config = |station_address| MstpConfig {
+            station_address,
+            max_master: 4,
+            ..Default::default()
+        };
+        let mut nodes = [
+            MasterNode::new(config(1), start),
+            MasterNode::new(config(3), start),
+        ];
+
+        // Station 1 regenerates the token first and the masters find each other
+        assert!(run_line(&mut nodes, start, 0, 505, false).is_empty());
+        assert_eq!(nodes[0].state(), MstpState::NoToken);
+        run_line(&mut nodes, start, 505, 1000, false);
+        assert_eq!(nodes[0].next_station(), 3);
+        assert_eq!(nodes[1].next_station(), 1);
+        assert!(!nodes[0].is_sole_master());
+        assert!(nodes[0].is_known_master(3));
+        assert!(nodes[0].statistics().tokens_received > 0);
+        assert_eq!(nodes[0].statistics().crc_errors, 0);
+        assert!(!nodes[0].is_known_master(2));
+
+        // Unconfirmed data goes out with the token
+        nodes[0].queue(vec![0x01, 0x00, 0x10], 3, false).unwrap();
+        let delivered = run_line(&mut nodes, start, 1000, 1100, false);
+        assert_eq!(delivered, vec![(3, vec![0x01, 0x00, 0x10], 1)]);
+
+        // A data request is answered at once...
+        nodes[1].queue(vec![0x01, 0x04, 0x00], 1, true).unwrap();
+        let delivered = run_line(&mut nodes, start, 1100, 1200, true);
+        assert_eq!(
+            delivered,
+            vec![
+                (1, vec![0x01, 0x04, 0x00], 3),
+                (3, vec![0x01, 0x00, 0xAA], 1)
+            ]
+        );
+
+        // ...or postponed after Treply_delay, and the token moves on
+        nodes[1].queue(vec![0x01, 0x04, 0x01], 1, true).unwrap();
+        let delivered = run_line(&mut nodes, start, 1200, 1600, false);
+        assert_eq!(delivered, vec![(1, vec![0x01, 0x04, 0x01], 3)]);
+        nodes[0].queue(vec![0x01, 0x00, 0x11], 3, false).unwrap();
+        assert_eq!(run_line(&mut nodes, start, 1600, 1700, false).len(), 1);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_zero_config_station() {
+        let start = Instant::now();
+        let mut nodes = [
+            MasterNode::new(
+                MstpConfig {
+                    station_address: 1,
+                    max_master: 66,
+                    ..Default::default()
+                },
+                start,
+            ),
+            MasterNode::new(
+                MstpConfig {
+                    zero_config: Some([0; 16]),
+                    ..Default::default()
+                },
+                start,
+            ),
+        ];
+        assert_eq!(nodes[1].config().station_address, MSTP_BROADCAST_ADDRESS);
+        nodes[1].queue(vec![0x01, 0x00, 0x20], 1, false).unwrap();
+
+        // Station 1 polls address 64 until the new station answers and
+        // confirms it; the frame queued meanwhile goes out from 64
+        let delivered = run_line(&mut nodes, start, 0, 14000, false);
+        assert_eq!(nodes[1].zero_config_state(), Some(ZeroConfigState::Use));
+        assert_eq!(nodes[1].config().station_address, 64);
+        assert_eq!(nodes[0].next_station(), 64);
+        assert_eq!(delivered, vec![(1, vec![0x01, 0x00, 0x20], 64)]);
+        assert_eq!(nodes[0].zero_config_state(), None);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_sole_master() {
+        let start = Instant::now();
+        let mut nodes = [MasterNode::new(
+            MstpConfig {
+                station_address: 5,
+                max_master: 7,
+                ..Default::default()
+            },
+            start,
+        )];
+
+        // Token generated at 550 ms, then seven unanswered polls
+        run_line(&mut nodes, start, 0, 700, false);
+        assert!(nodes[0].is_sole_master());
+        assert_eq!(nodes[0].next_station(), 5);
+        let statistics = *nodes[0].statistics();
+        assert_eq!(statistics.lost_tokens, 1);
+        assert!(statistics.polls_for_master_sent >= 7);
+        nodes[0].reset_statistics();
+        assert_eq!(*nodes[0].statistics(), MstpStatistics::default());
+
+        // A sole master keeps the token and sends without waiting
+        nodes[0]
+            .queue(vec![0x01, 0x00], MSTP_BROADCAST_ADDRESS, false)
+            .unwrap();
+        run_line(&mut nodes, start, 700, 800, false);
+        assert_eq!(nodes[0].queued(), 0);
+
+        // Slaves never take the token
+        let mut slave = MasterNode::new(
+            MstpConfig {
+                station_address: 200,
+                ..Default::default()
+            },
+            start,
+        );
+        slave.poll(start + Duration::from_secs(2));
+        assert_eq!(slave.state(), MstpState::Idle);
+        let token = MstpFrame::token(200, 5).unwrap();
+        let outcome = slave.receive(ReceiveEvent::Frame(token), start);
+        assert!(outcome.transmit.is_empty());
     }
 
     #[cfg(feature = "std")]
     #[test]
     fn test_mstp_datalink() {
-        let config = MstpConfig {
-            station_address: 5,
+        use std::net::{TcpListener, TcpStream};
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let a = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (b, _) = listener.accept().unwrap();
+        for stream in [&a, &b] {
+            stream
+                .set_read_timeout(Some(Duration::from_millis(1)))
+                .unwrap();
+            stream.set_nodelay(true).unwrap();
+        }
+        let config = |station_address| MstpConfig {
+            station_address,
+            max_master: 7,
             ..Default::default()
         };
 
-        let mut datalink = MstpDataLink::new("COM1", config).unwrap();
+        let mut datalink = MstpDataLink::new(a, config(5)).unwrap();
+        let mut peer = MstpDataLink::new(b, config(2)).unwrap();
 
         assert_eq!(datalink.link_type(), DataLinkType::MsTP);
         assert_eq!(datalink.local_address(), DataLinkAddress::MsTP(5));
+        assert_eq!(datalink.broadcast_address(), DataLinkAddress::MsTP(255));
+        assert_eq!(datalink.max_npdu_length(), MSTP_MAX_EXTENDED_DATA_LENGTH);
 
         // Test sending
-        let npdu = vec![0x01, 0x02, 0x03, 0x04];
-        let result = datalink.send_frame(&npdu, &DataLinkAddress::MsTP(10));
+        let npdu = vec![0x01, 0x00, 0x03, 0x04];
+        let result = datalink.send_frame(&npdu, &DataLinkAddress::MsTP(2));
         assert!(result.is_ok());
 
         // Test broadcast
         let result = datalink.send_frame(&npdu, &DataLinkAddress::Broadcast);
         assert!(result.is_ok());
+
+        let received: Vec<_> = (0..50)
+            .filter_map(|_| peer.receive_frame().ok())
+            .take(2)
+            .collect();
+        assert_eq!(received, vec![(npdu.clone(), DataLinkAddress::MsTP(5)); 2]);
+        assert_eq!(peer.next_station(), 5);
+        assert!(datalink
+            .send_frame(
+                &npdu,
+                &DataLinkAddress::Ip("10.0.0.1:47808".parse().unwrap())
+            )
+            .is_err());
     }
 }
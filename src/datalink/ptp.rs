@@ -0,0 +1,1260 @@
+//! BACnet PTP (Point-to-Point) Data Link Implementation
+//!
+//! This module implements the BACnet PTP data link layer as defined in ASHRAE 135 Clause 10.
+//! PTP connects two half-routers over an EIA-232 line or a dial-up modem link, so a remote
+//! network or a legacy device can be reached through a serial port.
+//!
+//! # Frame Format
+//!
+//! PTP Frame:
+//! - Preamble (2 bytes): 0x55, 0xFF
+//! - Frame Type (1 byte)
+//! - Length (2 bytes)
+//! - Header CRC (1 byte)
+//! - Data (0-501 bytes)
+//! - Data CRC (2 bytes) - only if length > 0
+//!
+//! After the preamble, every octet equal to DLE (0x10), XON (0x11) or XOFF (0x13) is sent
+//! as DLE followed by the octet with its high bit set, so that modems using software flow
+//! control pass frames through untouched. The CRCs are those of MS/TP, computed over the
+//! octets before escaping.
+//!
+//! # Connections
+//!
+//! The calling device sends the trigger sequence "BACnet" and a carriage return. The
+//! answering device replies with Connect Request, and the calling device completes the
+//! connection with Connect Response, carrying the password when the answering device asks
+//! for one. Either side ends the connection with Disconnect Request.
+//!
+//! While connected, NPDUs travel in Data frames numbered alternately 0 and 1. The receiver
+//! acknowledges each with the Data Ack of the same number, which also says whether it can
+//! take more (XON) or not (XOFF), and asks for a frame with a bad data CRC again with a Data
+//! Nak. Frames not acknowledged within Tresponse are sent again, up to [`PTP_NRETRIES`]
+//! times. A side with nothing to send transmits a Heartbeat every Theartbeat, so a link
+//! silent for Tinactivity is known to be lost.
+//!
+//! # State Machines
+//!
+//! [`PtpReceiver`] finds frames and the trigger sequence in the octet stream.
+//! [`PtpConnection`] runs the connection and data transfer procedures. Both do no I/O and
+//! take the current time as an argument; [`PtpDataLink`] runs them on a serial port.
+
+use std::{
+    collections::VecDeque,
+    io::{self, ErrorKind, Read, Write},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Condvar, Mutex,
+    },
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
+
+use crate::datalink::mstp::FrameError;
+use crate::datalink::{DataLink, DataLinkAddress, DataLinkError, DataLinkType, Result};
+use crate::util::{crc16_mstp, crc8_mstp};
+
+/// PTP frame preamble bytes
+pub const PTP_PREAMBLE_55: u8 = 0x55;
+pub const PTP_PREAMBLE_FF: u8 = 0xFF;
+
+/// Data link escape, which precedes an escaped octet
+pub const PTP_DLE: u8 = 0x10;
+
+/// Software flow control octets, escaped within frames
+pub const PTP_XON: u8 = 0x11;
+pub const PTP_XOFF: u8 = 0x13;
+
+/// Sequence the calling device sends to start a connection
+pub const PTP_TRIGGER: &[u8] = b"BACnet\r";
+
+/// Maximum data length of a frame, and so of an NPDU
+pub const PTP_MAX_DATA_LENGTH: usize = 501;
+
+/// Header size after unescaping: preamble, frame type, length and CRC
+pub const PTP_HEADER_SIZE: usize = 6;
+
+/// Number of times a trigger or Data frame is sent again, Nretries
+pub const PTP_NRETRIES: u8 = 3;
+
+/// PTP frame types
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum PtpFrameType {
+    /// Heartbeat, not ready to receive
+    HeartbeatXoff = 0x00,
+    /// Heartbeat, ready to receive
+    HeartbeatXon = 0x01,
+    /// Data frame with sequence number 0
+    Data0 = 0x02,
+    /// Data frame with sequence number 1
+    Data1 = 0x03,
+    /// Data Ack 0, not ready to receive
+    DataAck0Xoff = 0x04,
+    /// Data Ack 1, not ready to receive
+    DataAck1Xoff = 0x05,
+    /// Data Ack 0, ready to receive
+    DataAck0Xon = 0x06,
+    /// Data Ack 1, ready to receive
+    DataAck1Xon = 0x07,
+    /// Data Nak 0, not ready to receive
+    DataNak0Xoff = 0x08,
+    /// Data Nak 1, not ready to receive
+    DataNak1Xoff = 0x09,
+    /// Data Nak 0, ready to receive
+    DataNak0Xon = 0x0A,
+    /// Data Nak 1, ready to receive
+    DataNak1Xon = 0x0B,
+    /// Connect Request, sent by the answering device
+    ConnectRequest = 0x0C,
+    /// Connect Response, sent by the calling device
+    ConnectResponse = 0x0D,
+    /// Disconnect Request, carrying a [`DisconnectReason`]
+    DisconnectRequest = 0x0E,
+    /// Disconnect Response
+    DisconnectResponse = 0x0F,
+    /// Test Request
+    TestRequest = 0x14,
+    /// Test Response, echoing the request's data
+    TestResponse = 0x15,
+}
+
+impl PtpFrameType {
+    /// Convert from u8
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0x00 => Some(Self::HeartbeatXoff),
+            0x01 => Some(Self::HeartbeatXon),
+            0x02 => Some(Self::Data0),
+            0x03 => Some(Self::Data1),
+            0x04 => Some(Self::DataAck0Xoff),
+            0x05 => Some(Self::DataAck1Xoff),
+            0x06 => Some(Self::DataAck0Xon),
+            0x07 => Some(Self::DataAck1Xon),
+            0x08 => Some(Self::DataNak0Xoff),
+            0x09 => Some(Self::DataNak1Xoff),
+            0x0A => Some(Self::DataNak0Xon),
+            0x0B => Some(Self::DataNak1Xon),
+            0x0C => Some(Self::ConnectRequest),
+            0x0D => Some(Self::ConnectResponse),
+            0x0E => Some(Self::DisconnectRequest),
+            0x0F => Some(Self::DisconnectResponse),
+            0x14 => Some(Self::TestRequest),
+            0x15 => Some(Self::TestResponse),
+            _ => None,
+        }
+    }
+
+    /// The Data frame type with `sequence` (0 or 1)
+    pub fn data(sequence: u8) -> Self {
+        Self::from_u8(0x02 | (sequence & 1)).unwrap()
+    }
+
+    /// The Data Ack frame type for `sequence`, telling whether the receiver
+    /// is `ready` for more
+    pub fn ack(sequence: u8, ready: bool) -> Self {
+        Self::from_u8(0x04 | (ready as u8) << 1 | (sequence & 1)).unwrap()
+    }
+
+    /// The Data Nak frame type for `sequence`
+    pub fn nak(sequence: u8, ready: bool) -> Self {
+        Self::from_u8(0x08 | (ready as u8) << 1 | (sequence & 1)).unwrap()
+    }
+
+    /// The Heartbeat frame type
+    pub fn heartbeat(ready: bool) -> Self {
+        if ready {
+            Self::HeartbeatXon
+        } else {
+            Self::HeartbeatXoff
+        }
+    }
+
+    /// Whether this is a Data frame
+    pub fn is_data(self) -> bool {
+        matches!(self, Self::Data0 | Self::Data1)
+    }
+
+    /// Whether this is a Data Ack frame
+    pub fn is_ack(self) -> bool {
+        matches!(self as u8, 0x04..=0x07)
+    }
+
+    /// The sequence number of a Data, Data Ack or Data Nak frame
+    pub fn sequence(self) -> Option<u8> {
+        match self as u8 {
+            0x02..=0x0B => Some(self as u8 & 1),
+            _ => None,
+        }
+    }
+
+    /// The flow control state a Heartbeat, Data Ack or Data Nak frame
+    /// announces: `true` for XON
+    pub fn ready(self) -> Option<bool> {
+        match self as u8 {
+            0x00 | 0x01 => Some(self as u8 & 1 != 0),
+            0x04..=0x0B => Some(self as u8 & 2 != 0),
+            _ => None,
+        }
+    }
+}
+
+/// Why a connection is closed, the data of a Disconnect Request frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum DisconnectReason {
+    /// Nothing more to send
+    NoMoreData = 0,
+    /// Another connection needs the line
+    Preempted = 1,
+    /// The Connect Response carried the wrong password
+    InvalidPassword = 2,
+    /// Any other reason, such as a peer that stopped acknowledging
+    Other = 3,
+}
+
+/// PTP frame
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PtpFrame {
+    /// Frame type
+    pub frame_type: PtpFrameType,
+    /// Data, before escaping
+    pub data: Vec<u8>,
+}
+
+impl PtpFrame {
+    /// Create a new PTP frame
+    pub fn new(frame_type: PtpFrameType, data: Vec<u8>) -> Result<Self> {
+        if data.len() > PTP_MAX_DATA_LENGTH {
+            return Err(DataLinkError::InvalidFrame);
+        }
+        Ok(Self { frame_type, data })
+    }
+
+    /// Encode frame to bytes, escaping everything after the preamble
+    pub fn encode(&self) -> Vec<u8> {
+        let length = self.data.len() as u16;
+        let header = [self.frame_type as u8, (length >> 8) as u8, length as u8];
+
+        let mut frame = Vec::with_capacity(PTP_HEADER_SIZE + self.data.len() + 2);
+        frame.push(PTP_PREAMBLE_55);
+        frame.push(PTP_PREAMBLE_FF);
+        escape(&mut frame, &header);
+        escape(&mut frame, &[crc8_mstp(&header)]);
+        if !self.data.is_empty() {
+            escape(&mut frame, &self.data);
+            escape(&mut frame, &crc16_mstp(&self.data).to_le_bytes());
+        }
+        frame
+    }
+}
+
+/// Append `octets` to `frame` with DLE, XON and XOFF escaped
+fn escape(frame: &mut Vec<u8>, octets: &[u8]) {
+    for &octet in octets {
+        if matches!(octet, PTP_DLE | PTP_XON | PTP_XOFF) {
+            frame.push(PTP_DLE);
+            frame.push(octet | 0x80);
+        } else {
+            frame.push(octet);
+        }
+    }
+}
+
+/// What the frame receiver made of the octets on the line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PtpEvent {
+    /// A frame with valid CRCs
+    Frame(PtpFrame),
+    /// The trigger sequence of a calling device
+    Trigger,
+    /// A frame with a valid header whose data CRC was wrong
+    BadData(PtpFrameType),
+    /// A frame the receiver discarded
+    InvalidFrame(FrameError),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ReceiveState {
+    /// Waiting for the first preamble octet or a trigger
+    Idle,
+    /// Waiting for the second preamble octet
+    Preamble,
+    /// Reading the header and its CRC
+    Header,
+    /// Reading the data and its CRC
+    Data,
+}
+
+/// The frame reception procedure (Clause 10.4.8), fed one octet at a time
+#[derive(Debug, Clone)]
+pub struct PtpReceiver {
+    state: ReceiveState,
+    /// Unescaped octets after the preamble so far
+    buffer: Vec<u8>,
+    /// Whether the last octet was DLE
+    escaped: bool,
+    /// Data length from the header
+    data_length: usize,
+    /// Octets of the trigger sequence matched in the idle state
+    trigger: usize,
+    /// When the last octet arrived
+    last_octet: Option<Instant>,
+    /// Tframe_abort
+    frame_abort: Duration,
+}
+
+impl PtpReceiver {
+    /// Create a receiver that discards frames interrupted for `frame_abort`
+    pub fn new(frame_abort: Duration) -> Self {
+        Self {
+            state: ReceiveState::Idle,
+            buffer: Vec::new(),
+            escaped: false,
+            data_length: 0,
+            trigger: 0,
+            last_octet: None,
+            frame_abort,
+        }
+    }
+
+    /// Take one octet received at `now`, returning an event once a frame or
+    /// the trigger is complete, or a frame has been abandoned
+    pub fn receive(&mut self, octet: u8, now: Instant) -> Option<PtpEvent> {
+        let aborted = self.timeout(now);
+        self.last_octet = Some(now);
+        match self.state {
+            ReceiveState::Idle => {
+                if octet == PTP_PREAMBLE_55 {
+                    self.trigger = 0;
+                    self.state = ReceiveState::Preamble;
+                    return aborted;
+                }
+                if octet == PTP_TRIGGER[self.trigger] {
+                    self.trigger += 1;
+                } else {
+                    self.trigger = (octet == PTP_TRIGGER[0]) as usize;
+                }
+                if self.trigger == PTP_TRIGGER.len() {
+                    self.trigger = 0;
+                    return Some(PtpEvent::Trigger);
+                }
+            }
+            ReceiveState::Preamble => match octet {
+                PTP_PREAMBLE_FF => {
+                    self.buffer.clear();
+                    self.escaped = false;
+                    self.state = ReceiveState::Header;
+                }
+                PTP_PREAMBLE_55 => {}
+                _ => self.state = ReceiveState::Idle,
+            },
+            ReceiveState::Header | ReceiveState::Data => {
+                let octet = match (self.escaped, octet) {
+                    (false, PTP_DLE) => {
+                        self.escaped = true;
+                        return aborted;
+                    }
+                    (true, octet) => {
+                        self.escaped = false;
+                        octet & 0x7F
+                    }
+                    (false, octet) => octet,
+                };
+                self.buffer.push(octet);
+                return self.octet().or(aborted);
+            }
+        }
+        aborted
+    }
+
+    /// Abandon a frame interrupted for Tframe_abort
+    pub fn timeout(&mut self, now: Instant) -> Option<PtpEvent> {
+        let last_octet = self.last_octet?;
+        if now.saturating_duration_since(last_octet) <= self.frame_abort {
+            return None;
+        }
+        self.trigger = 0;
+        let state = core::mem::replace(&mut self.state, ReceiveState::Idle);
+        match state {
+            ReceiveState::Header | ReceiveState::Data => {
+                Some(PtpEvent::InvalidFrame(FrameError::Framing))
+            }
+            _ => None,
+        }
+    }
+
+    /// Handle an unescaped header or data octet
+    fn octet(&mut self) -> Option<PtpEvent> {
+        if self.state == ReceiveState::Header {
+            if self.buffer.len() < 4 {
+                return None;
+            }
+            self.state = ReceiveState::Idle;
+            if crc8_mstp(&self.buffer[..3]) != self.buffer[3] {
+                return Some(PtpEvent::InvalidFrame(FrameError::Crc));
+            }
+            self.data_length = u16::from_be_bytes([self.buffer[1], self.buffer[2]]) as usize;
+            if self.data_length > PTP_MAX_DATA_LENGTH {
+                return Some(PtpEvent::InvalidFrame(FrameError::Framing));
+            }
+            if self.data_length > 0 {
+                self.state = ReceiveState::Data;
+                return None;
+            }
+        } else if self.buffer.len() < 4 + self.data_length + 2 {
+            return None;
+        }
+
+        self.state = ReceiveState::Idle;
+        // Frames of unknown types are skipped
+        let frame_type = PtpFrameType::from_u8(self.buffer[0])?;
+        let data = self.buffer[4..].to_vec();
+        if data.is_empty() {
+            return Some(PtpEvent::Frame(PtpFrame { frame_type, data }));
+        }
+        let (data, crc) = data.split_at(self.data_length);
+        if crc16_mstp(data) != u16::from_le_bytes([crc[0], crc[1]]) {
+            return Some(PtpEvent::BadData(frame_type));
+        }
+        Some(PtpEvent::Frame(PtpFrame {
+            frame_type,
+            data: data.to_vec(),
+        }))
+    }
+}
+
+/// PTP connection configuration
+#[derive(Debug, Clone)]
+pub struct PtpConfig {
+    /// Baud rate of the serial line
+    pub baud_rate: u32,
+    /// Password sent in the Connect Response when calling, and required of
+    /// the calling device when answering
+    pub password: Option<Vec<u8>>,
+    /// Time to wait for Connect Request after the trigger and for Connect
+    /// Response after Connect Request, Tconn_rqst and Tconn_rsp
+    /// (milliseconds)
+    pub connect_timeout: u64,
+    /// Time to wait for a Data Ack or Disconnect Response, Tresponse
+    /// (milliseconds)
+    pub response_timeout: u64,
+    /// Silence after which a Heartbeat is sent, Theartbeat (milliseconds)
+    pub heartbeat: u64,
+    /// Silence from the peer after which the connection is lost,
+    /// Tinactivity (milliseconds)
+    pub inactivity: u64,
+    /// Silence within a frame after which it is discarded, Tframe_abort
+    /// (milliseconds)
+    pub frame_abort: u64,
+}
+
+impl Default for PtpConfig {
+    fn default() -> Self {
+        Self {
+            baud_rate: 9600,
+            password: None,
+            connect_timeout: 15_000,
+            response_timeout: 5_000,
+            heartbeat: 15_000,
+            inactivity: 60_000,
+            frame_abort: 2_000,
+        }
+    }
+}
+
+/// PTP connection states
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PtpState {
+    /// No connection
+    Disconnected,
+    /// Sent the trigger; waiting for Connect Request
+    Triggered,
+    /// Sent Connect Request; waiting for Connect Response
+    Answering,
+    /// Connected; data frames flow
+    Connected,
+    /// Sent Disconnect Request; waiting for Disconnect Response
+    Disconnecting,
+}
+
+/// What the connection wants done after an event
+#[derive(Debug, Clone, Default)]
+pub struct PtpOutcome {
+    /// Octets to transmit, in order
+    pub transmit: Vec<Vec<u8>>,
+    /// NPDUs received
+    pub received: Vec<Vec<u8>>,
+}
+
+impl PtpOutcome {
+    /// Append the actions of a later outcome
+    pub fn extend(&mut self, other: PtpOutcome) {
+        self.transmit.extend(other.transmit);
+        self.received.extend(other.received);
+    }
+}
+
+/// A Data frame waiting for its Data Ack
+#[derive(Debug, Clone)]
+struct Outstanding {
+    frame: PtpFrame,
+    sent: Instant,
+    retries: u8,
+}
+
+/// The connection and data transfer state machines (Clause 10.4)
+///
+/// The connection calls or answers, then sends queued NPDUs one Data frame
+/// at a time, waiting for each Data Ack and holding back while the peer
+/// says XOFF. It does no I/O: it is told about events from the
+/// [`PtpReceiver`] and polled for timeouts, and each call returns the octets
+/// to transmit and the NPDUs received.
+#[derive(Debug, Clone)]
+pub struct PtpConnection {
+    config: PtpConfig,
+    state: PtpState,
+    /// When the current state was entered or the trigger last sent
+    since: Instant,
+    /// Triggers sent again without an answer
+    retries: u8,
+    /// NPDUs waiting to be sent
+    queue: VecDeque<Vec<u8>>,
+    /// The Data frame sent and not yet acknowledged
+    outstanding: Option<Outstanding>,
+    /// Sequence number of the next Data frame sent
+    tx_sequence: u8,
+    /// Sequence number of the last Data frame received
+    rx_sequence: Option<u8>,
+    /// Whether the peer last said XON
+    peer_ready: bool,
+    /// When a frame was last sent
+    last_transmit: Instant,
+    /// When a frame was last received
+    last_receive: Instant,
+}
+
+impl PtpConnection {
+    /// Create a disconnected connection
+    pub fn new(config: PtpConfig, now: Instant) -> Self {
+        Self {
+            config,
+            state: PtpState::Disconnected,
+            since: now,
+            retries: 0,
+            queue: VecDeque::new(),
+            outstanding: None,
+            tx_sequence: 0,
+            rx_sequence: None,
+            peer_ready: true,
+            last_transmit: now,
+            last_receive: now,
+        }
+    }
+
+    /// The configuration
+    pub fn config(&self) -> &PtpConfig {
+        &self.config
+    }
+
+    /// The current state
+    pub fn state(&self) -> PtpState {
+        self.state
+    }
+
+    /// Whether data frames flow
+    pub fn is_connected(&self) -> bool {
+        self.state == PtpState::Connected
+    }
+
+    /// Number of NPDUs waiting to be sent, the one awaiting its Data Ack
+    /// included
+    pub fn queued(&self) -> usize {
+        self.queue.len() + self.outstanding.is_some() as usize
+    }
+
+    /// Queue an NPDU, sent once connected
+    pub fn queue(&mut self, npdu: Vec<u8>) -> Result<()> {
+        if npdu.len() > PTP_MAX_DATA_LENGTH {
+            return Err(DataLinkError::InvalidFrame);
+        }
+        self.queue.push_back(npdu);
+        Ok(())
+    }
+
+    /// Call the peer by sending the trigger sequence
+    pub fn connect(&mut self, now: Instant) -> PtpOutcome {
+        let mut out = PtpOutcome::default();
+        if self.state == PtpState::Disconnected {
+            self.state = PtpState::Triggered;
+            self.since = now;
+            self.retries = 0;
+            out.transmit.push(PTP_TRIGGER.to_vec());
+        }
+        out
+    }
+
+    /// Close the connection
+    pub fn disconnect(&mut self, now: Instant) -> PtpOutcome {
+        let mut out = PtpOutcome::default();
+        match self.state {
+            PtpState::Connected => self.close(DisconnectReason::NoMoreData, now, &mut out),
+            PtpState::Triggered | PtpState::Answering => self.state = PtpState::Disconnected,
+            _ => {}
+        }
+        out
+    }
+
+    /// Handle an event from the receiver
+    pub fn receive(&mut self, event: PtpEvent, now: Instant) -> PtpOutcome {
+        let mut out = PtpOutcome::default();
+        let frame = match event {
+            PtpEvent::Trigger => {
+                if matches!(self.state, PtpState::Disconnected | PtpState::Answering) {
+                    self.state = PtpState::Answering;
+                    self.since = now;
+                    self.send(PtpFrameType::ConnectRequest, Vec::new(), now, &mut out);
+                }
+                return out;
+            }
+            PtpEvent::BadData(frame_type) => {
+                self.last_receive = now;
+                if self.state == PtpState::Connected && frame_type.is_data() {
+                    let nak = PtpFrameType::nak(frame_type as u8 & 1, true);
+                    self.send(nak, Vec::new(), now, &mut out);
+                }
+                return out;
+            }
+            PtpEvent::InvalidFrame(_) => return out,
+            PtpEvent::Frame(frame) => frame,
+        };
+        self.last_receive = now;
+
+        match (self.state, frame.frame_type) {
+            (_, PtpFrameType::TestRequest) => {
+                self.send(PtpFrameType::TestResponse, frame.data, now, &mut out);
+            }
+            (PtpState::Disconnecting, PtpFrameType::DisconnectResponse) => {
+                self.state = PtpState::Disconnected;
+            }
+            (_, PtpFrameType::DisconnectRequest) => {
+                self.send(PtpFrameType::DisconnectResponse, Vec::new(), now, &mut out);
+                self.state = PtpState::Disconnected;
+            }
+            (PtpState::Triggered | PtpState::Connected, PtpFrameType::ConnectRequest) => {
+                // A repeated Connect Request means the response was lost
+                let password = self.config.password.clone().unwrap_or_default();
+                self.send(PtpFrameType::ConnectResponse, password, now, &mut out);
+                if self.state == PtpState::Triggered {
+                    self.connected(now);
+                }
+            }
+            (PtpState::Answering, PtpFrameType::ConnectResponse) => {
+                let accepted = self
+                    .config
+                    .password
+                    .as_ref()
+                    .is_none_or(|password| *password == frame.data);
+                if accepted {
+                    self.connected(now);
+                } else {
+                    self.close(DisconnectReason::InvalidPassword, now, &mut out);
+                }
+            }
+            (PtpState::Connected, frame_type) => {
+                self.transfer(frame_type, frame.data, now, &mut out)
+            }
+            _ => {}
+        }
+        self.send_next(now, &mut out);
+        out
+    }
+
+    /// Apply the timeouts due at `now`
+    pub fn poll(&mut self, now: Instant) -> PtpOutcome {
+        let mut out = PtpOutcome::default();
+        let waited = now.saturating_duration_since(self.since);
+        match self.state {
+            PtpState::Triggered if waited >= self.connect_timeout() => {
+                if self.retries < PTP_NRETRIES {
+                    self.retries += 1;
+                    self.since = now;
+                    out.transmit.push(PTP_TRIGGER.to_vec());
+                } else {
+                    self.state = PtpState::Disconnected;
+                }
+            }
+            PtpState::Answering if waited >= self.connect_timeout() => {
+                self.state = PtpState::Disconnected;
+            }
+            PtpState::Disconnecting if waited >= self.response_timeout() => {
+                self.state = PtpState::Disconnected;
+            }
+            PtpState::Connected => {
+                let silence = now.saturating_duration_since(self.last_receive);
+                if silence >= Duration::from_millis(self.config.inactivity) {
+                    // The line or the peer is gone
+                    self.state = PtpState::Disconnected;
+                    self.outstanding = None;
+                    return out;
+                }
+                if let Some(outstanding) = &self.outstanding {
+                    if now.saturating_duration_since(outstanding.sent) >= self.response_timeout() {
+                        self.retransmit(now, &mut out);
+                    }
+                }
+                self.send_next(now, &mut out);
+                let idle = now.saturating_duration_since(self.last_transmit);
+                if self.is_connected() && idle >= Duration::from_millis(self.config.heartbeat) {
+                    self.send(PtpFrameType::heartbeat(true), Vec::new(), now, &mut out);
+                }
+            }
+            _ => {}
+        }
+        out
+    }
+
+    /// Handle a frame received while connected
+    fn transfer(
+        &mut self,
+        frame_type: PtpFrameType,
+        data: Vec<u8>,
+        now: Instant,
+        out: &mut PtpOutcome,
+    ) {
+        if let Some(ready) = frame_type.ready() {
+            self.peer_ready = ready;
+        }
+        let Some(sequence) = frame_type.sequence() else {
+            return;
+        };
+        if frame_type.is_data() {
+            // A repeated frame means the Data Ack was lost
+            if self.rx_sequence != Some(sequence) {
+                self.rx_sequence = Some(sequence);
+                out.received.push(data);
+            }
+            self.send(PtpFrameType::ack(sequence, true), Vec::new(), now, out);
+            return;
+        }
+        let awaited = self
+            .outstanding
+            .as_ref()
+            .and_then(|outstanding| outstanding.frame.frame_type.sequence());
+        if awaited != Some(sequence) {
+            return;
+        }
+        if frame_type.is_ack() {
+            self.outstanding = None;
+            self.tx_sequence ^= 1;
+        } else {
+            self.retransmit(now, out);
+        }
+    }
+
+    /// Send the outstanding Data frame again, or give up on the peer
+    fn retransmit(&mut self, now: Instant, out: &mut PtpOutcome) {
+        let Some(outstanding) = &mut self.outstanding else {
+            return;
+        };
+        if outstanding.retries >= PTP_NRETRIES {
+            self.outstanding = None;
+            self.close(DisconnectReason::Other, now, out);
+            return;
+        }
+        outstanding.retries += 1;
+        outstanding.sent = now;
+        let encoded = outstanding.frame.encode();
+        out.transmit.push(encoded);
+        self.last_transmit = now;
+    }
+
+    /// Send the next queued NPDU when the peer can take it
+    fn send_next(&mut self, now: Instant, out: &mut PtpOutcome) {
+        if !self.is_connected() || self.outstanding.is_some() || !self.peer_ready {
+            return;
+        }
+        let Some(npdu) = self.queue.pop_front() else {
+            return;
+        };
+        let frame = PtpFrame {
+            frame_type: PtpFrameType::data(self.tx_sequence),
+            data: npdu,
+        };
+        out.transmit.push(frame.encode());
+        self.last_transmit = now;
+        self.outstanding = Some(Outstanding {
+            frame,
+            sent: now,
+            retries: 0,
+        });
+    }
+
+    fn connected(&mut self, now: Instant) {
+        self.state = PtpState::Connected;
+        self.since = now;
+        self.tx_sequence = 0;
+        self.rx_sequence = None;
+        self.peer_ready = true;
+        self.outstanding = None;
+        self.last_receive = now;
+    }
+
+    fn close(&mut self, reason: DisconnectReason, now: Instant, out: &mut PtpOutcome) {
+        self.send(
+            PtpFrameType::DisconnectRequest,
+            vec![reason as u8],
+            now,
+            out,
+        );
+        self.state = PtpState::Disconnecting;
+        self.since = now;
+    }
+
+    fn send(
+        &mut self,
+        frame_type: PtpFrameType,
+        data: Vec<u8>,
+        now: Instant,
+        out: &mut PtpOutcome,
+    ) {
+        out.transmit.push(PtpFrame { frame_type, data }.encode());
+        self.last_transmit = now;
+    }
+
+    fn connect_timeout(&self) -> Duration {
+        Duration::from_millis(self.config.connect_timeout)
+    }
+
+    fn response_timeout(&self) -> Duration {
+        Duration::from_millis(self.config.response_timeout)
+    }
+}
+
+/// How long [`PtpDataLink::receive_frame`](DataLink::receive_frame) waits
+/// for a frame
+pub const PTP_RECEIVE_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Type alias for receive queue
+type ReceiveQueue = Arc<(Mutex<VecDeque<Vec<u8>>>, Condvar)>;
+
+/// PTP data link implementation
+///
+/// A thread owns the serial port and runs the frame receiver and the
+/// [`PtpConnection`], so heartbeats and retransmissions go out between
+/// calls. Frames sent are queued until the connection is up; call
+/// [`connect`](Self::connect) to dial the peer, or wait for it to call.
+#[derive(Debug)]
+pub struct PtpDataLink {
+    /// Connection state machine, shared with the port thread
+    connection: Arc<Mutex<PtpConnection>>,
+    /// Octets for the port thread to transmit
+    transmit: Arc<Mutex<Vec<Vec<u8>>>>,
+    /// Receive queue
+    receive_queue: ReceiveQueue,
+    /// Running flag
+    running: Arc<AtomicBool>,
+    /// Port thread
+    thread: Option<JoinHandle<()>>,
+}
+
+impl PtpDataLink {
+    /// Open the serial device at `path` (e.g. `/dev/ttyS0`) and wait for
+    /// calls on it
+    ///
+    /// The device must already be set up for the baud rate, 8N1 and raw mode,
+    /// with reads that return within a few milliseconds when no data is
+    /// waiting, as `stty raw min 0 time 0` does.
+    pub fn open(path: &str, config: PtpConfig) -> Result<Self> {
+        let port = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(path)
+            .map_err(DataLinkError::IoError)?;
+        Ok(Self::new(port, config))
+    }
+
+    /// Run the connection on a byte stream to the peer
+    ///
+    /// Reads must time out or return no data within a few milliseconds, so
+    /// the timers keep running.
+    pub fn new<P: Read + Write + Send + 'static>(port: P, config: PtpConfig) -> Self {
+        let frame_abort = Duration::from_millis(config.frame_abort);
+        let connection = Arc::new(Mutex::new(PtpConnection::new(config, Instant::now())));
+        let transmit = Arc::new(Mutex::new(Vec::new()));
+        let receive_queue: ReceiveQueue = Arc::new((Mutex::new(VecDeque::new()), Condvar::new()));
+        let running = Arc::new(AtomicBool::new(true));
+        let thread = {
+            let connection = connection.clone();
+            let transmit = transmit.clone();
+            let receive_queue = receive_queue.clone();
+            let running = running.clone();
+            std::thread::spawn(move || {
+                run_port(
+                    port,
+                    PtpReceiver::new(frame_abort),
+                    connection,
+                    transmit,
+                    receive_queue,
+                    running,
+                )
+            })
+        };
+
+        Self {
+            connection,
+            transmit,
+            receive_queue,
+            running,
+            thread: Some(thread),
+        }
+    }
+
+    /// The current state of the connection
+    pub fn state(&self) -> PtpState {
+        self.connection.lock().unwrap().state()
+    }
+
+    /// Call the peer
+    pub fn connect(&self) {
+        let outcome = self.connection.lock().unwrap().connect(Instant::now());
+        self.transmit.lock().unwrap().extend(outcome.transmit);
+    }
+
+    /// Close the connection
+    pub fn disconnect(&self) {
+        let outcome = self.connection.lock().unwrap().disconnect(Instant::now());
+        self.transmit.lock().unwrap().extend(outcome.transmit);
+    }
+}
+
+impl Drop for PtpDataLink {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Body of the port thread
+fn run_port<P: Read + Write>(
+    mut port: P,
+    mut receiver: PtpReceiver,
+    connection: Arc<Mutex<PtpConnection>>,
+    transmit: Arc<Mutex<Vec<Vec<u8>>>>,
+    receive_queue: ReceiveQueue,
+    running: Arc<AtomicBool>,
+) {
+    let mut buffer = [0u8; 2 * (PTP_HEADER_SIZE + PTP_MAX_DATA_LENGTH + 2)];
+    while running.load(Ordering::Relaxed) {
+        let read = port.read(&mut buffer);
+        let now = Instant::now();
+        let mut outcome = PtpOutcome {
+            transmit: core::mem::take(&mut *transmit.lock().unwrap()),
+            received: Vec::new(),
+        };
+        let mut ptp = connection.lock().unwrap();
+        match read {
+            Ok(0) => {
+                drop(ptp);
+                std::thread::sleep(Duration::from_millis(1));
+                ptp = connection.lock().unwrap();
+            }
+            Ok(count) => {
+                for &octet in &buffer[..count] {
+                    if let Some(event) = receiver.receive(octet, now) {
+                        outcome.extend(ptp.receive(event, now));
+                    }
+                }
+            }
+            Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+            Err(e) if e.kind() == ErrorKind::Interrupted => {}
+            Err(_) => return,
+        }
+        if let Some(event) = receiver.timeout(now) {
+            outcome.extend(ptp.receive(event, now));
+        }
+        outcome.extend(ptp.poll(now));
+        drop(ptp);
+
+        for octets in &outcome.transmit {
+            if port.write_all(octets).is_err() {
+                return;
+            }
+        }
+        let _ = port.flush();
+
+        if !outcome.received.is_empty() {
+            let (queue, ready) = &*receive_queue;
+            queue.lock().unwrap().extend(outcome.received);
+            ready.notify_all();
+        }
+    }
+}
+
+impl DataLink for PtpDataLink {
+    fn send_frame(&mut self, frame: &[u8], dest: &DataLinkAddress) -> Result<()> {
+        match dest {
+            DataLinkAddress::PointToPoint | DataLinkAddress::Broadcast => {}
+            _ => {
+                return Err(DataLinkError::AddressError(
+                    "Invalid address type for PTP".into(),
+                ))
+            }
+        }
+        self.connection.lock().unwrap().queue(frame.to_vec())
+    }
+
+    fn receive_frame(&mut self) -> Result<(Vec<u8>, DataLinkAddress)> {
+        let (queue, ready) = &*self.receive_queue;
+        let queue = queue.lock().unwrap();
+        let (mut queue, _) = ready
+            .wait_timeout_while(queue, PTP_RECEIVE_TIMEOUT, |queue| queue.is_empty())
+            .unwrap();
+        let npdu = queue.pop_front().ok_or_else(|| {
+            DataLinkError::IoError(io::Error::new(ErrorKind::TimedOut, "No PTP frame received"))
+        })?;
+        Ok((npdu, DataLinkAddress::PointToPoint))
+    }
+
+    fn link_type(&self) -> DataLinkType {
+        DataLinkType::PointToPoint
+    }
+
+    fn local_address(&self) -> DataLinkAddress {
+        DataLinkAddress::PointToPoint
+    }
+
+    fn broadcast_address(&self) -> DataLinkAddress {
+        DataLinkAddress::PointToPoint
+    }
+
+    fn max_npdu_length(&self) -> usize {
+        PTP_MAX_DATA_LENGTH
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Feed `octets` through a receiver, collecting the events
+    fn events(octets: &[u8]) -> Vec<PtpEvent> {
+        let now = Instant::now();
+        let mut receiver = PtpReceiver::new(Duration::from_secs(2));
+        octets
+            .iter()
+            .filter_map(|&octet| receiver.receive(octet, now))
+            .collect()
+    }
+
+    /// Carry outcomes between connections `a` (0) and `b` (1) until neither
+    /// has more to send, dropping the first `lose` frames sent by `a`, and
+    /// return the NPDUs `b` received
+    fn exchange(
+        a: &mut PtpConnection,
+        b: &mut PtpConnection,
+        mut pending: Vec<(usize, PtpOutcome)>,
+        now: Instant,
+        lose: &mut usize,
+    ) -> Vec<Vec<u8>> {
+        let mut received = Vec::new();
+        while let Some((sender, outcome)) = pending.pop() {
+            if sender == 1 {
+                received.extend(outcome.received);
+            }
+            for octets in outcome.transmit {
+                if sender == 0 && *lose > 0 {
+                    *lose -= 1;
+                    continue;
+                }
+                let peer = if sender == 0 { &mut *b } else { &mut *a };
+                for event in events(&octets) {
+                    pending.push((1 - sender, peer.receive(event, now)));
+                }
+            }
+        }
+        received
+    }
+
+    /// Poll both connections every millisecond from `from` to `to`
+    fn run_link(
+        a: &mut PtpConnection,
+        b: &mut PtpConnection,
+        start: Instant,
+        from: u64,
+        to: u64,
+        mut lose: usize,
+    ) -> Vec<Vec<u8>> {
+        let mut received = Vec::new();
+        for ms in from..to {
+            let now = start + Duration::from_millis(ms);
+            let pending = vec![(0, a.poll(now)), (1, b.poll(now))];
+            received.extend(exchange(a, b, pending, now, &mut lose));
+        }
+        received
+    }
+
+    #[test]
+    fn test_frame_escaping() {
+        let frame = PtpFrame::new(PtpFrameType::Data1, vec![0x01, 0x10, 0x11, 0x13, 0x55]).unwrap();
+        let encoded = frame.encode();
+        assert_eq!(&encoded[..2], &[PTP_PREAMBLE_55, PTP_PREAMBLE_FF]);
+        assert!(!encoded.contains(&PTP_XON));
+        assert!(!encoded.contains(&PTP_XOFF));
+        assert!(encoded
+            .windows(2)
+            .any(|pair| pair == [PTP_DLE, PTP_XON | 0x80]));
+        assert_eq!(events(&encoded), vec![PtpEvent::Frame(frame.clone())]);
+
+        // Line noise before the frame and the trigger are told apart
+        let mut line = b"xxBACBACnet\r".to_vec();
+        line.extend(&encoded);
+        assert_eq!(
+            events(&line),
+            vec![PtpEvent::Trigger, PtpEvent::Frame(frame)]
+        );
+
+        // A damaged data octet leaves the header intact
+        let mut damaged = encoded.clone();
+        damaged[7] ^= 0x20;
+        assert_eq!(
+            events(&damaged),
+            vec![PtpEvent::BadData(PtpFrameType::Data1)]
+        );
+        damaged[2] ^= 0x01;
+        assert_eq!(
+            events(&damaged),
+            vec![PtpEvent::InvalidFrame(FrameError::Crc)]
+        );
+
+        assert!(PtpFrame::new(PtpFrameType::Data0, vec![0; PTP_MAX_DATA_LENGTH + 1]).is_err());
+        assert_eq!(PtpFrameType::ack(1, false), PtpFrameType::DataAck1Xoff);
+        assert_eq!(PtpFrameType::nak(0, true), PtpFrameType::DataNak0Xon);
+        assert_eq!(PtpFrameType::DataNak1Xoff.ready(), Some(false));
+    }
+
+    #[test]
+    fn test_connect_transfer_and_retransmit() {
+        let start = Instant::now();
+        let config = PtpConfig {
+            password: Some(b"secret".to_vec()),
+            ..Default::default()
+        };
+        let mut caller = PtpConnection::new(config.clone(), start);
+        let mut answerer = PtpConnection::new(config, start);
+
+        let outcome = caller.connect(start);
+        assert_eq!(outcome.transmit, vec![PTP_TRIGGER.to_vec()]);
+        exchange(
+            &mut caller,
+            &mut answerer,
+            vec![(0, outcome)],
+            start,
+            &mut 0,
+        );
+        assert!(caller.is_connected());
+        assert!(answerer.is_connected());
+
+        // The first Data frame is lost and sent again after Tresponse
+        caller.queue(vec![0x01, 0x00, 0xAA]).unwrap();
+        caller.queue(vec![0x01, 0x00, 0xBB]).unwrap();
+        let delivered = run_link(&mut caller, &mut answerer, start, 0, 4_000, 1);
+        assert!(delivered.is_empty());
+        assert_eq!(caller.queued(), 2);
+        let delivered = run_link(&mut caller, &mut answerer, start, 4_000, 6_000, 0);
+        assert_eq!(
+            delivered,
+            vec![vec![0x01, 0x00, 0xAA], vec![0x01, 0x00, 0xBB]]
+        );
+        assert_eq!(caller.queued(), 0);
+
+        // Heartbeats keep an idle connection up past Tinactivity
+        run_link(&mut caller, &mut answerer, start, 6_000, 70_000, 0);
+        assert!(caller.is_connected() && answerer.is_connected());
+
+        let outcome = caller.disconnect(start + Duration::from_secs(70));
+        assert_eq!(caller.state(), PtpState::Disconnecting);
+        for event in events(&outcome.transmit[0]) {
+            answerer.receive(event, start);
+        }
+        assert_eq!(answerer.state(), PtpState::Disconnected);
+    }
+
+    #[test]
+    fn test_wrong_password_and_unanswered_trigger() {
+        let start = Instant::now();
+        let mut answerer = PtpConnection::new(
+            PtpConfig {
+                password: Some(b"secret".to_vec()),
+                ..Default::default()
+            },
+            start,
+        );
+        let outcome = answerer.receive(PtpEvent::Trigger, start);
+        assert_eq!(answerer.state(), PtpState::Answering);
+        assert_eq!(
+            events(&outcome.transmit[0]),
+            vec![PtpEvent::Frame(PtpFrame {
+                frame_type: PtpFrameType::ConnectRequest,
+                data: Vec::new(),
+            })]
+        );
+        let response = PtpFrame::new(PtpFrameType::ConnectResponse, b"guess".to_vec()).unwrap();
+        let outcome = answerer.receive(PtpEvent::Frame(response), start);
+        assert_eq!(answerer.state(), PtpState::Disconnecting);
+        assert_eq!(
+            events(&outcome.transmit[0]),
+            vec![PtpEvent::Frame(PtpFrame {
+                frame_type: PtpFrameType::DisconnectRequest,
+                data: vec![DisconnectReason::InvalidPassword as u8],
+            })]
+        );
+
+        // A caller gives up after the trigger goes unanswered Nretries times
+        let mut caller = PtpConnection::new(PtpConfig::default(), start);
+        caller.connect(start);
+        let mut triggers = 1;
+        for s in 1..=80 {
+            triggers += caller.poll(start + Duration::from_secs(s)).transmit.len();
+        }
+        assert_eq!(triggers, 1 + PTP_NRETRIES as usize);
+        assert_eq!(caller.state(), PtpState::Disconnected);
+    }
+
+    #[test]
+    fn test_ptp_datalink() {
+        use std::net::{TcpListener, TcpStream};
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let a = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (b, _) = listener.accept().unwrap();
+        for stream in [&a, &b] {
+            stream
+                .set_read_timeout(Some(Duration::from_millis(1)))
+                .unwrap();
+            stream.set_nodelay(true).unwrap();
+        }
+
+        let mut datalink = PtpDataLink::new(a, PtpConfig::default());
+        let mut peer = PtpDataLink::new(b, PtpConfig::default());
+        assert_eq!(datalink.link_type(), DataLinkType::PointToPoint);
+        assert_eq!(datalink.local_address(), DataLinkAddress::PointToPoint);
+        assert_eq!(datalink.max_npdu_length(), PTP_MAX_DATA_LENGTH);
+
+        let npdu = vec![0x01, 0x00, 0x10, 0x13];
+        datalink
+            .send_frame(&npdu, &DataLinkAddress::PointToPoint)
+            .unwrap();
+        assert!(datalink
+            .send_frame(&npdu, &DataLinkAddress::MsTP(1))
+            .is_err());
+        datalink.connect();
+
+        let received = (0..50).find_map(|_| peer.receive_frame().ok());
+        assert_eq!(received, Some((npdu, DataLinkAddress::PointToPoint)));
+        assert_eq!(datalink.state(), PtpState::Connected);
+        assert_eq!(peer.state(), PtpState::Connected);
+    }
+}
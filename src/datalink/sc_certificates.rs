@@ -0,0 +1,869 @@
+//! BACnet/SC certificate management (ASHRAE 135 Annex AB.7).
+//!
+//! A BACnet/SC node authenticates with an operational certificate issued by
+//! one of the site's certificate authorities, and trusts peers whose
+//! certificates chain to the same issuers. Configuration tools install these
+//! through the File objects named by the Network Port properties
+//! Operational_Certificate_File and Issuer_Certificate_Files, written with
+//! AtomicWriteFile. To get a new operational certificate, the node places a
+//! PKCS#10 signing request in the Certificate_Signing_Request_File for the
+//! tool to read with AtomicReadFile and take to the authority.
+//!
+//! [`ScCertificates`] holds the certificates of one node. It parses just
+//! enough X.509 to check what it is given: that the certificate is
+//! well-formed, within its validity period, and issued by an installed
+//! issuer. Signatures are checked by the TLS implementation behind the
+//! [`ScConnector`] and [`ScAcceptor`] when connections are made, as is the
+//! generation of key pairs and signing requests, which needs the private
+//! key.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use bacnet_rs::datalink::sc_certificates::ScCertificates;
+//! use bacnet_rs::object::File;
+//! use std::time::SystemTime;
+//!
+//! # fn example(operational: &File, issuer: &File) -> Result<(), Box<dyn std::error::Error>> {
+//! let mut certificates = ScCertificates::new();
+//! // After a configuration tool has written both files
+//! let warnings = certificates.load_from_files(operational, &[issuer], SystemTime::now())?;
+//! for warning in warnings {
+//!     println!("{}", warning);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`ScConnector`]: crate::datalink::bsc::ScConnector
+//! [`ScAcceptor`]: crate::datalink::sc_hub::ScAcceptor
+
+use std::{
+    error::Error,
+    fmt,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::object::{File, ObjectError};
+
+/// How long before a certificate expires that [`ScCertificates::check`]
+/// starts warning about it, unless set otherwise: 30 days.
+pub const DEFAULT_EXPIRY_WARNING: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// DER tag of a SEQUENCE.
+const TAG_SEQUENCE: u8 = 0x30;
+/// DER tag of a SET.
+const TAG_SET: u8 = 0x31;
+/// DER tag of an INTEGER.
+const TAG_INTEGER: u8 = 0x02;
+/// DER tag of an OBJECT IDENTIFIER.
+const TAG_OID: u8 = 0x06;
+/// DER tag of a UTCTime.
+const TAG_UTC_TIME: u8 = 0x17;
+/// DER tag of a GeneralizedTime.
+const TAG_GENERALIZED_TIME: u8 = 0x18;
+/// DER tag of the explicit version field of a TBSCertificate.
+const TAG_VERSION: u8 = 0xA0;
+/// Encoded OID of the commonName attribute (2.5.4.3).
+const OID_COMMON_NAME: &[u8] = &[0x55, 0x04, 0x03];
+
+/// Errors from installing certificates.
+#[derive(Debug)]
+pub enum CertificateError {
+    /// The data is not a DER or PEM encoded certificate or signing request.
+    Malformed(&'static str),
+    /// The data holds no certificate.
+    NoCertificate,
+    /// The certificate's validity period has not started.
+    NotYetValid,
+    /// The certificate's validity period has ended.
+    Expired,
+    /// The certificate was not issued by any installed issuer.
+    UnknownIssuer,
+    /// No signing request has been installed.
+    NoSigningRequest,
+    /// A certificate file could not be read or written.
+    File(ObjectError),
+}
+
+impl fmt::Display for CertificateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CertificateError::Malformed(msg) => write!(f, "Malformed certificate: {}", msg),
+            CertificateError::NoCertificate => write!(f, "No certificate found"),
+            CertificateError::NotYetValid => write!(f, "Certificate is not yet valid"),
+            CertificateError::Expired => write!(f, "Certificate has expired"),
+            CertificateError::UnknownIssuer => {
+                write!(f, "Certificate was not issued by an installed issuer")
+            }
+            CertificateError::NoSigningRequest => write!(f, "No signing request installed"),
+            CertificateError::File(e) => write!(f, "Certificate file error: {}", e),
+        }
+    }
+}
+
+impl Error for CertificateError {}
+
+/// Result type for certificate operations
+pub type Result<T> = std::result::Result<T, CertificateError>;
+
+/// The fields of an X.509 certificate needed to manage it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Certificate {
+    /// The whole certificate, DER encoded.
+    der: Vec<u8>,
+    /// Serial number octets.
+    serial_number: Vec<u8>,
+    /// Issuer name, DER encoded.
+    issuer: Vec<u8>,
+    /// Subject name, DER encoded.
+    subject: Vec<u8>,
+    /// Start of the validity period.
+    not_before: SystemTime,
+    /// End of the validity period.
+    not_after: SystemTime,
+}
+
+impl Certificate {
+    /// Parse one DER encoded certificate.
+    pub fn from_der(der: &[u8]) -> Result<Self> {
+        let (certificate, rest) = expect(der, TAG_SEQUENCE)?;
+        if !rest.is_empty() {
+            return Err(CertificateError::Malformed("trailing data"));
+        }
+        let (mut tbs, _) = expect(certificate, TAG_SEQUENCE)?;
+        if tbs.first() == Some(&TAG_VERSION) {
+            tbs = read_tlv(tbs)?.2;
+        }
+        let (serial_number, tbs) = expect(tbs, TAG_INTEGER)?;
+        let (_, tbs) = expect(tbs, TAG_SEQUENCE)?; // signature algorithm
+        let (issuer, tbs) = expect_raw(tbs, TAG_SEQUENCE)?;
+        let (validity, tbs) = expect(tbs, TAG_SEQUENCE)?;
+        let (subject, _) = expect_raw(tbs, TAG_SEQUENCE)?;
+        let (not_before, validity) = read_time(validity)?;
+        let (not_after, _) = read_time(validity)?;
+        Ok(Self {
+            der: der.to_vec(),
+            serial_number: serial_number.to_vec(),
+            issuer: issuer.to_vec(),
+            subject: subject.to_vec(),
+            not_before,
+            not_after,
+        })
+    }
+
+    /// Parse every certificate in a file, which may hold concatenated DER
+    /// certificates or PEM `CERTIFICATE` blocks.
+    pub fn parse_all(data: &[u8]) -> Result<Vec<Self>> {
+        let ders = if data.first() == Some(&TAG_SEQUENCE) {
+            let mut ders = Vec::new();
+            let mut rest = data;
+            while !rest.is_empty() {
+                let (_, _, next) = read_tlv(rest)?;
+                ders.push(rest[..rest.len() - next.len()].to_vec());
+                rest = next;
+            }
+            ders
+        } else {
+            pem_blocks(data, "CERTIFICATE")?
+        };
+        if ders.is_empty() {
+            return Err(CertificateError::NoCertificate);
+        }
+        ders.iter().map(|der| Self::from_der(der)).collect()
+    }
+
+    /// The certificate, DER encoded.
+    pub fn der(&self) -> &[u8] {
+        &self.der
+    }
+
+    /// The serial number octets.
+    pub fn serial_number(&self) -> &[u8] {
+        &self.serial_number
+    }
+
+    /// The subject's common name, if it has one.
+    pub fn subject_common_name(&self) -> Option<String> {
+        common_name(&self.subject)
+    }
+
+    /// The issuer's common name, if it has one.
+    pub fn issuer_common_name(&self) -> Option<String> {
+        common_name(&self.issuer)
+    }
+
+    /// Start of the validity period.
+    pub fn not_before(&self) -> SystemTime {
+        self.not_before
+    }
+
+    /// End of the validity period.
+    pub fn not_after(&self) -> SystemTime {
+        self.not_after
+    }
+
+    /// Whether the certificate names itself as issuer, as root authorities
+    /// do.
+    pub fn is_self_issued(&self) -> bool {
+        self.issuer == self.subject
+    }
+
+    /// Whether `issuer`'s subject is this certificate's issuer.
+    pub fn is_issued_by(&self, issuer: &Certificate) -> bool {
+        self.issuer == issuer.subject
+    }
+
+    /// Check that `now` falls within the validity period.
+    pub fn check_validity(&self, now: SystemTime) -> Result<()> {
+        if now < self.not_before {
+            Err(CertificateError::NotYetValid)
+        } else if now > self.not_after {
+            Err(CertificateError::Expired)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Time left until the certificate expires, zero once it has.
+    pub fn remaining(&self, now: SystemTime) -> Duration {
+        self.not_after.duration_since(now).unwrap_or_default()
+    }
+
+    /// A short name for messages: the subject common name, or the serial
+    /// number in hex.
+    fn label(&self) -> String {
+        self.subject_common_name()
+            .unwrap_or_else(|| hex::encode(&self.serial_number))
+    }
+}
+
+/// A PKCS#10 certificate signing request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SigningRequest {
+    /// The whole request, DER encoded.
+    der: Vec<u8>,
+    /// Subject name, DER encoded.
+    subject: Vec<u8>,
+}
+
+impl SigningRequest {
+    /// Parse a DER or PEM (`CERTIFICATE REQUEST`) encoded signing request.
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        let der = if data.first() == Some(&TAG_SEQUENCE) {
+            data.to_vec()
+        } else {
+            pem_blocks(data, "CERTIFICATE REQUEST")?
+                .into_iter()
+                .next()
+                .ok_or(CertificateError::Malformed("no signing request"))?
+        };
+        let (request, rest) = expect(&der, TAG_SEQUENCE)?;
+        if !rest.is_empty() {
+            return Err(CertificateError::Malformed("trailing data"));
+        }
+        let (info, _) = expect(request, TAG_SEQUENCE)?;
+        let (version, info) = expect(info, TAG_INTEGER)?;
+        if version != [0] {
+            return Err(CertificateError::Malformed("unsupported request version"));
+        }
+        let (subject, _) = expect_raw(info, TAG_SEQUENCE)?;
+        let subject = subject.to_vec();
+        Ok(Self { der, subject })
+    }
+
+    /// The request, DER encoded.
+    pub fn der(&self) -> &[u8] {
+        &self.der
+    }
+
+    /// The subject's common name, if it has one.
+    pub fn subject_common_name(&self) -> Option<String> {
+        common_name(&self.subject)
+    }
+}
+
+/// Which installed certificate a warning concerns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CertificateRole {
+    /// The operational certificate.
+    Operational,
+    /// The issuer certificate at this index.
+    Issuer(usize),
+}
+
+/// A problem [`ScCertificates::check`] found with an installed certificate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CertificateWarning {
+    /// The certificate expires within the warning period.
+    ExpiresSoon {
+        role: CertificateRole,
+        name: String,
+        remaining: Duration,
+    },
+    /// The certificate has expired.
+    Expired { role: CertificateRole, name: String },
+    /// No operational certificate is installed.
+    NoOperationalCertificate,
+}
+
+impl fmt::Display for CertificateWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CertificateWarning::ExpiresSoon {
+                role,
+                name,
+                remaining,
+            } => write!(
+                f,
+                "{:?} certificate {} expires in {} days",
+                role,
+                name,
+                remaining.as_secs() / (24 * 60 * 60)
+            ),
+            CertificateWarning::Expired { role, name } => {
+                write!(f, "{:?} certificate {} has expired", role, name)
+            }
+            CertificateWarning::NoOperationalCertificate => {
+                write!(f, "No operational certificate installed")
+            }
+        }
+    }
+}
+
+/// The certificates of one BACnet/SC node.
+#[derive(Debug, Clone)]
+pub struct ScCertificates {
+    /// The node's own certificate.
+    operational: Option<Certificate>,
+    /// Certificate authorities trusted to issue node certificates.
+    issuers: Vec<Certificate>,
+    /// The signing request offered for a new operational certificate.
+    signing_request: Option<SigningRequest>,
+    /// How long before expiry to start warning.
+    warning_period: Duration,
+}
+
+impl Default for ScCertificates {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ScCertificates {
+    /// Create an empty store with the default warning period.
+    pub fn new() -> Self {
+        Self {
+            operational: None,
+            issuers: Vec::new(),
+            signing_request: None,
+            warning_period: DEFAULT_EXPIRY_WARNING,
+        }
+    }
+
+    /// Set how long before expiry [`check`](Self::check) warns.
+    pub fn with_warning_period(mut self, period: Duration) -> Self {
+        self.warning_period = period;
+        self
+    }
+
+    /// The operational certificate, once installed.
+    pub fn operational_certificate(&self) -> Option<&Certificate> {
+        self.operational.as_ref()
+    }
+
+    /// The installed issuer certificates.
+    pub fn issuer_certificates(&self) -> &[Certificate] {
+        &self.issuers
+    }
+
+    /// The installed signing request.
+    pub fn signing_request(&self) -> Option<&SigningRequest> {
+        self.signing_request.as_ref()
+    }
+
+    /// Replace the issuer certificates with those in `data`.
+    ///
+    /// Every certificate must be within its validity period. Returns how
+    /// many were installed; on error the previous issuers stay.
+    pub fn install_issuer_certificates(&mut self, data: &[u8], now: SystemTime) -> Result<usize> {
+        self.set_issuers(Certificate::parse_all(data)?, now)
+    }
+
+    fn set_issuers(&mut self, issuers: Vec<Certificate>, now: SystemTime) -> Result<usize> {
+        for issuer in &issuers {
+            issuer.check_validity(now)?;
+        }
+        self.issuers = issuers;
+        Ok(self.issuers.len())
+    }
+
+    /// Install the operational certificate in `data`, the first if it holds
+    /// a chain.
+    ///
+    /// The certificate must be within its validity period and issued by an
+    /// installed issuer, so the issuers go in first. Installing a
+    /// certificate uses up the signing request it answered.
+    pub fn install_operational_certificate(&mut self, data: &[u8], now: SystemTime) -> Result<()> {
+        let certificate = Certificate::parse_all(data)?.remove(0);
+        certificate.check_validity(now)?;
+        if !self
+            .issuers
+            .iter()
+            .any(|issuer| certificate.is_issued_by(issuer))
+        {
+            return Err(CertificateError::UnknownIssuer);
+        }
+        self.operational = Some(certificate);
+        self.signing_request = None;
+        Ok(())
+    }
+
+    /// Install a signing request produced with the node's private key, to be
+    /// offered through the Certificate_Signing_Request_File.
+    pub fn install_signing_request(&mut self, data: &[u8]) -> Result<()> {
+        self.signing_request = Some(SigningRequest::parse(data)?);
+        Ok(())
+    }
+
+    /// Install the certificates configuration tools wrote to the File
+    /// objects named by Operational_Certificate_File and
+    /// Issuer_Certificate_Files.
+    ///
+    /// Empty issuer files are skipped. Nothing changes unless every
+    /// certificate is acceptable. Returns the warnings of
+    /// [`check`](Self::check) for the new certificates.
+    pub fn load_from_files(
+        &mut self,
+        operational: &File,
+        issuers: &[&File],
+        now: SystemTime,
+    ) -> Result<Vec<CertificateWarning>> {
+        let mut issuer_certificates = Vec::new();
+        for file in issuers {
+            let data = file.get_file_data().map_err(CertificateError::File)?;
+            if !data.is_empty() {
+                issuer_certificates.extend(Certificate::parse_all(&data)?);
+            }
+        }
+        let operational_data = operational
+            .get_file_data()
+            .map_err(CertificateError::File)?;
+
+        let mut updated = self.clone();
+        updated.set_issuers(issuer_certificates, now)?;
+        updated.install_operational_certificate(&operational_data, now)?;
+        *self = updated;
+        Ok(self.check(now))
+    }
+
+    /// Write the signing request, DER encoded, to the File object named by
+    /// Certificate_Signing_Request_File.
+    pub fn write_signing_request(&self, file: &mut File) -> Result<()> {
+        let request = self
+            .signing_request
+            .as_ref()
+            .ok_or(CertificateError::NoSigningRequest)?;
+        file.set_file_data(request.der.clone())
+            .map_err(CertificateError::File)
+    }
+
+    /// Warnings about certificates that have expired or expire within the
+    /// warning period, or a missing operational certificate.
+    pub fn check(&self, now: SystemTime) -> Vec<CertificateWarning> {
+        let mut warnings = Vec::new();
+        if self.operational.is_none() {
+            warnings.push(CertificateWarning::NoOperationalCertificate);
+        }
+        let installed = self
+            .operational
+            .iter()
+            .map(|certificate| (CertificateRole::Operational, certificate))
+            .chain(
+                self.issuers
+                    .iter()
+                    .enumerate()
+                    .map(|(i, certificate)| (CertificateRole::Issuer(i), certificate)),
+            );
+        for (role, certificate) in installed {
+            let remaining = certificate.remaining(now);
+            if remaining.is_zero() {
+                warnings.push(CertificateWarning::Expired {
+                    role,
+                    name: certificate.label(),
+                });
+            } else if remaining <= self.warning_period {
+                warnings.push(CertificateWarning::ExpiresSoon {
+                    role,
+                    name: certificate.label(),
+                    remaining,
+                });
+            }
+        }
+        warnings
+    }
+}
+
+/// Split a DER element into its tag, contents and the data after it.
+fn read_tlv(data: &[u8]) -> Result<(u8, &[u8], &[u8])> {
+    let truncated = CertificateError::Malformed("truncated element");
+    let (&tag, data) = data.split_first().ok_or(truncated)?;
+    let (&first, data) = data
+        .split_first()
+        .ok_or(CertificateError::Malformed("truncated length"))?;
+    let (length, data) = if first < 0x80 {
+        (first as usize, data)
+    } else {
+        let octets = (first & 0x7F) as usize;
+        if octets == 0 || octets > 4 || data.len() < octets {
+            return Err(CertificateError::Malformed("invalid length"));
+        }
+        let length = data[..octets]
+            .iter()
+            .fold(0usize, |length, &octet| length << 8 | octet as usize);
+        (length, &data[octets..])
+    };
+    if data.len() < length {
+        return Err(CertificateError::Malformed("truncated element"));
+    }
+    Ok((tag, &data[..length], &data[length..]))
+}
+
+/// Read an element that must have `tag`, returning its contents.
+fn expect(data: &[u8], tag: u8) -> Result<(&[u8], &[u8])> {
+    match read_tlv(data)? {
+        (found, contents, rest) if found == tag => Ok((contents, rest)),
+        _ => Err(CertificateError::Malformed("unexpected element")),
+    }
+}
+
+/// Read an element that must have `tag`, returning it whole.
+fn expect_raw(data: &[u8], tag: u8) -> Result<(&[u8], &[u8])> {
+    let (_, rest) = expect(data, tag)?;
+    Ok((&data[..data.len() - rest.len()], rest))
+}
+
+/// Read a UTCTime or GeneralizedTime in UTC.
+fn read_time(data: &[u8]) -> Result<(SystemTime, &[u8])> {
+    let invalid = || CertificateError::Malformed("invalid time");
+    let (tag, contents, rest) = read_tlv(data)?;
+    let text = std::str::from_utf8(contents).map_err(|_| invalid())?;
+    let text = text.strip_suffix('Z').ok_or_else(invalid)?;
+    let (year, text) = match (tag, text.len()) {
+        (TAG_UTC_TIME, 12) => {
+            // RFC 5280: two-digit years 50-99 are 19xx
+            let year: i64 = text[..2].parse().map_err(|_| invalid())?;
+            (
+                if year >= 50 { 1900 + year } else { 2000 + year },
+                &text[2..],
+            )
+        }
+        (TAG_GENERALIZED_TIME, 14) => (text[..4].parse().map_err(|_| invalid())?, &text[4..]),
+        _ => return Err(invalid()),
+    };
+    if !text.bytes().all(|c| c.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    let field = |i: usize| text[i..i + 2].parse::<i64>().unwrap_or_default();
+    let (month, day) = (field(0), field(2));
+    let (hour, minute, second) = (field(4), field(6), field(8));
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 {
+        return Err(invalid());
+    }
+    let seconds = days_from_civil(year, month, day) * 86400 + hour * 3600 + minute * 60 + second;
+    let time = UNIX_EPOCH + Duration::from_secs(u64::try_from(seconds).unwrap_or_default());
+    Ok((time, rest))
+}
+
+/// Days since 1970-01-01 of a proleptic Gregorian date.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+/// The commonName of a DER encoded Name.
+fn common_name(name: &[u8]) -> Option<String> {
+    let (mut rdns, _) = expect(name, TAG_SEQUENCE).ok()?;
+    while !rdns.is_empty() {
+        let (rdn, rest) = expect(rdns, TAG_SET).ok()?;
+        rdns = rest;
+        let (attribute, _) = expect(rdn, TAG_SEQUENCE).ok()?;
+        let (oid, value) = expect(attribute, TAG_OID).ok()?;
+        if oid == OID_COMMON_NAME {
+            let (_, value, _) = read_tlv(value).ok()?;
+            return String::from_utf8(value.to_vec()).ok();
+        }
+    }
+    None
+}
+
+/// Decode the PEM blocks labelled `label`.
+fn pem_blocks(data: &[u8], label: &str) -> Result<Vec<Vec<u8>>> {
+    let text = std::str::from_utf8(data).map_err(|_| CertificateError::Malformed("not PEM"))?;
+    let begin = format!("-----BEGIN {}-----", label);
+    let end = format!("-----END {}-----", label);
+    let mut blocks = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find(&begin) {
+        let body = &rest[start + begin.len()..];
+        let stop = body
+            .find(&end)
+            .ok_or(CertificateError::Malformed("unterminated PEM block"))?;
+        blocks.push(base64_decode(&body[..stop])?);
+        rest = &body[stop + end.len()..];
+    }
+    Ok(blocks)
+}
+
+/// Decode base64, ignoring whitespace.
+fn base64_decode(text: &str) -> Result<Vec<u8>> {
+    let mut decoded = Vec::new();
+    let mut bits = 0u32;
+    let mut count = 0;
+    for c in text.bytes().filter(|c| !c.is_ascii_whitespace()) {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            b'=' => break,
+            _ => return Err(CertificateError::Malformed("invalid base64")),
+        };
+        bits = bits << 6 | value as u32;
+        count += 6;
+        if count >= 8 {
+            count -= 8;
+            decoded.push((bits >> count) as u8);
+        }
+    }
+    Ok(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tlv(tag: u8, contents: &[u8]) -> Vec<u8> {
+        let mut element = vec![tag];
+        if contents.len() < 0x80 {
+            element.push(contents.len() as u8);
+        } else {
+            element.extend([0x82, (contents.len() >> 8) as u8, contents.len() as u8]);
+        }
+        element.extend(contents);
+        element
+    }
+
+    fn name(common_name: &str) -> Vec<u8> {
+        let attribute = [
+            tlv(TAG_OID, OID_COMMON_NAME),
+            tlv(0x0C, common_name.as_bytes()),
+        ];
+        tlv(
+            TAG_SEQUENCE,
+            &tlv(TAG_SET, &tlv(TAG_SEQUENCE, &attribute.concat())),
+        )
+    }
+
+    /// A certificate with a placeholder key and signature.
+    fn certificate(issuer: &str, subject: &str, not_before: &str, not_after: &str) -> Vec<u8> {
+        let algorithm = tlv(TAG_SEQUENCE, &tlv(TAG_OID, &[0x2A, 0x86, 0x48, 0xCE, 0x3D]));
+        let validity = [
+            tlv(TAG_UTC_TIME, not_before.as_bytes()),
+            tlv(TAG_GENERALIZED_TIME, not_after.as_bytes()),
+        ];
+        let tbs = [
+            tlv(TAG_VERSION, &tlv(TAG_INTEGER, &[2])),
+            tlv(TAG_INTEGER, &[0x01, 0x23]),
+            algorithm.clone(),
+            name(issuer),
+            tlv(TAG_SEQUENCE, &validity.concat()),
+            name(subject),
+            tlv(
+                TAG_SEQUENCE,
+                &[algorithm.clone(), tlv(0x03, &[0; 33])].concat(),
+            ),
+        ];
+        let certificate = [
+            tlv(TAG_SEQUENCE, &tbs.concat()),
+            algorithm,
+            tlv(0x03, &[0; 9]),
+        ];
+        tlv(TAG_SEQUENCE, &certificate.concat())
+    }
+
+    fn pem(der: &[u8]) -> Vec<u8> {
+        const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+        let mut text = String::from("-----BEGIN CERTIFICATE-----\n");
+        for (i, chunk) in der.chunks(3).enumerate() {
+            let bits =
+                chunk.iter().fold(0u32, |bits, &b| bits << 8 | b as u32) << (8 * (3 - chunk.len()));
+            for j in 0..4 {
+                text.push(if j <= chunk.len() {
+                    ALPHABET[(bits >> (18 - 6 * j) & 0x3F) as usize] as char
+                } else {
+                    '='
+                });
+            }
+            if (i + 1).is_multiple_of(16) {
+                text.push('\n');
+            }
+        }
+        text.push_str("\n-----END CERTIFICATE-----\n");
+        text.into_bytes()
+    }
+
+    fn at(seconds: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(seconds)
+    }
+
+    #[test]
+    fn test_certificate_parsing() {
+        let der = certificate("Site CA", "Node 1", "240101000000Z", "20250101000000Z");
+        let parsed = Certificate::from_der(&der).unwrap();
+        assert_eq!(parsed.subject_common_name().as_deref(), Some("Node 1"));
+        assert_eq!(parsed.issuer_common_name().as_deref(), Some("Site CA"));
+        assert_eq!(parsed.serial_number(), &[0x01, 0x23]);
+        assert_eq!(parsed.not_before(), at(1_704_067_200));
+        assert_eq!(parsed.not_after(), at(1_735_689_600));
+        assert!(!parsed.is_self_issued());
+
+        // PEM chains and concatenated DER give the same certificates
+        let ca = certificate("Site CA", "Site CA", "240101000000Z", "20340101000000Z");
+        let chain = [pem(&der), pem(&ca)].concat();
+        let certificates = Certificate::parse_all(&chain).unwrap();
+        assert_eq!(
+            certificates,
+            Certificate::parse_all(&[der.clone(), ca].concat()).unwrap()
+        );
+        assert!(certificates[0].is_issued_by(&certificates[1]));
+        assert!(certificates[1].is_self_issued());
+
+        assert!(matches!(
+            Certificate::from_der(&der[..der.len() - 1]),
+            Err(CertificateError::Malformed(_))
+        ));
+        assert!(matches!(
+            Certificate::parse_all(b"no certificates here"),
+            Err(CertificateError::NoCertificate)
+        ));
+    }
+
+    #[test]
+    fn test_certificate_installation() {
+        let now = at(1_720_000_000); // 2024-07-03
+        let ca = certificate("Site CA", "Site CA", "240101000000Z", "20340101000000Z");
+        let node = certificate("Site CA", "Node 1", "240101000000Z", "20240720000000Z");
+        let stray = certificate("Other CA", "Node 1", "240101000000Z", "20340101000000Z");
+        let expired = certificate("Site CA", "Node 1", "230101000000Z", "20240101000000Z");
+
+        let mut certificates = ScCertificates::new();
+        assert!(matches!(
+            certificates.install_operational_certificate(&node, now),
+            Err(CertificateError::UnknownIssuer)
+        ));
+        assert_eq!(
+            certificates
+                .install_issuer_certificates(&pem(&ca), now)
+                .unwrap(),
+            1
+        );
+        assert!(matches!(
+            certificates.install_operational_certificate(&stray, now),
+            Err(CertificateError::UnknownIssuer)
+        ));
+        assert!(matches!(
+            certificates.install_operational_certificate(&expired, now),
+            Err(CertificateError::Expired)
+        ));
+        assert!(matches!(
+            certificates.install_operational_certificate(&node, at(1_600_000_000)),
+            Err(CertificateError::NotYetValid)
+        ));
+        assert_eq!(
+            certificates.check(now),
+            vec![CertificateWarning::NoOperationalCertificate]
+        );
+
+        certificates
+            .install_operational_certificate(&node, now)
+            .unwrap();
+        let warnings = certificates.check(now);
+        assert!(matches!(
+            warnings.as_slice(),
+            [CertificateWarning::ExpiresSoon { role: CertificateRole::Operational, name, .. }]
+                if name == "Node 1"
+        ));
+        let later = at(1_722_000_000);
+        assert_eq!(
+            certificates.check(later),
+            vec![CertificateWarning::Expired {
+                role: CertificateRole::Operational,
+                name: "Node 1".to_string(),
+            }]
+        );
+        assert!(certificates
+            .clone()
+            .with_warning_period(Duration::from_secs(86400))
+            .check(now)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_certificate_files() {
+        let now = at(1_720_000_000);
+        let ca = certificate("Site CA", "Site CA", "240101000000Z", "20340101000000Z");
+        let node = certificate("Site CA", "Node 1", "240101000000Z", "20340101000000Z");
+        let mut operational = File::new(1, "Operational".to_string(), "DER".to_string());
+        let mut issuer = File::new(2, "Issuer".to_string(), "PEM".to_string());
+        let empty = File::new(3, "Spare issuer".to_string(), "PEM".to_string());
+        let mut request_file = File::new(4, "CSR".to_string(), "DER".to_string());
+
+        // The request is offered until a certificate answers it
+        let mut certificates = ScCertificates::new();
+        let info = [
+            tlv(TAG_INTEGER, &[0]),
+            name("Node 1"),
+            tlv(TAG_SEQUENCE, &[]),
+        ];
+        let request = tlv(TAG_SEQUENCE, &tlv(TAG_SEQUENCE, &info.concat()));
+        certificates.install_signing_request(&request).unwrap();
+        assert_eq!(
+            certificates
+                .signing_request()
+                .unwrap()
+                .subject_common_name()
+                .as_deref(),
+            Some("Node 1")
+        );
+        certificates
+            .write_signing_request(&mut request_file)
+            .unwrap();
+        assert_eq!(request_file.get_file_data().unwrap(), request);
+
+        // A half-written operational file leaves everything as it was
+        issuer.set_file_data(pem(&ca)).unwrap();
+        operational.set_file_data(node[..20].to_vec()).unwrap();
+        assert!(certificates
+            .load_from_files(&operational, &[&issuer, &empty], now)
+            .is_err());
+        assert!(certificates.issuer_certificates().is_empty());
+
+        operational.set_file_data(node.clone()).unwrap();
+        let warnings = certificates
+            .load_from_files(&operational, &[&issuer, &empty], now)
+            .unwrap();
+        assert!(warnings.is_empty());
+        assert_eq!(certificates.operational_certificate().unwrap().der(), node);
+        assert!(certificates.signing_request().is_none());
+        assert!(matches!(
+            certificates.write_signing_request(&mut request_file),
+            Err(CertificateError::NoSigningRequest)
+        ));
+    }
+}
@@ -0,0 +1,328 @@
+//! BACnet/SC direct connections between nodes (ASHRAE 135 Annex AB.4).
+//!
+//! Nodes that exchange a lot of traffic can bypass the hub with a WebSocket
+//! connection of their own. The initiating node asks its peer for its
+//! direct-connect URIs with Address-Resolution, sent through the hub, then
+//! opens a WebSocket with the `dc.bsc.bacnet.org` subprotocol and sends a
+//! Connect-Request just as it does to a hub. Once the peer accepts, unicast
+//! NPDUs between the two travel over the direct connection; everything else,
+//! and all traffic when the direct connection is unavailable, still goes
+//! through the hub.
+//!
+//! [`ScDataLink`] manages direct connections; this module holds the state
+//! of a single one. Accepting connections needs an [`ScAcceptor`], set with
+//! [`ScDataLink::accept_direct_connections`]; nodes without one use
+//! [`NoDirectAcceptor`] and only initiate.
+//!
+//! [`ScDataLink`]: crate::datalink::bsc::ScDataLink
+//! [`ScDataLink::accept_direct_connections`]: crate::datalink::bsc::ScDataLink::accept_direct_connections
+
+use std::{
+    collections::VecDeque,
+    io::{self, ErrorKind, Read, Write},
+    net::TcpStream,
+    time::Instant,
+};
+
+use crate::datalink::bsc::{ConnectInfo, ScMessage, ScNodeConfig, ScPayload, Vmac};
+use crate::datalink::sc_hub::ScAcceptor;
+use crate::datalink::websocket::WebSocket;
+use crate::datalink::DataLinkAddress;
+
+/// WebSocket subprotocol for direct node-to-node connections.
+pub const DIRECT_SUBPROTOCOL: &str = "dc.bsc.bacnet.org";
+
+/// Acceptor for nodes that do not accept direct connections.
+///
+/// The type has no values, so a node using it never listens.
+#[derive(Debug)]
+pub enum NoDirectAcceptor {}
+
+impl ScAcceptor for NoDirectAcceptor {
+    type Stream = TcpStream;
+
+    fn accept(&mut self, _stream: TcpStream) -> io::Result<(TcpStream, Vec<u8>)> {
+        match *self {}
+    }
+}
+
+/// One direct connection, initiated or accepted.
+pub(crate) struct DirectLink<S> {
+    /// WebSocket to the peer.
+    socket: WebSocket<S>,
+
+    /// Peer VMAC expected in the Connect-Accept, for initiated connections.
+    target: Option<Vmac>,
+
+    /// The peer's Connect-Request or Connect-Accept, once exchanged.
+    peer: Option<ConnectInfo>,
+
+    /// Client certificate of the peer, for accepted connections.
+    certificate: Vec<u8>,
+
+    /// When the connection opened.
+    opened: Instant,
+
+    /// When a message last arrived.
+    last_received: Instant,
+
+    /// When a Heartbeat-Request was last sent.
+    last_heartbeat: Option<Instant>,
+
+    /// Message ID of the last message originated.
+    message_id: u16,
+}
+
+impl<S: Read + Write> DirectLink<S> {
+    /// Start a connection to `target` on an opened WebSocket by sending a
+    /// Connect-Request.
+    pub(crate) fn initiate(
+        socket: WebSocket<S>,
+        target: Vmac,
+        config: &ScNodeConfig,
+        now: Instant,
+    ) -> io::Result<Self> {
+        let mut link = Self::new(socket, Some(target), Vec::new(), now);
+        let request = ScMessage::new(
+            link.next_message_id(),
+            ScPayload::ConnectRequest(config.connect_info()),
+        );
+        link.send(&request)?;
+        Ok(link)
+    }
+
+    /// Take a connection accepted from a peer, which must send a
+    /// Connect-Request next.
+    pub(crate) fn accept(socket: WebSocket<S>, certificate: Vec<u8>, now: Instant) -> Self {
+        Self::new(socket, None, certificate, now)
+    }
+
+    fn new(socket: WebSocket<S>, target: Option<Vmac>, certificate: Vec<u8>, now: Instant) -> Self {
+        Self {
+            socket,
+            target,
+            peer: None,
+            certificate,
+            opened: now,
+            last_received: now,
+            last_heartbeat: None,
+            message_id: 0,
+        }
+    }
+
+    /// The peer's VMAC, once the connection is established.
+    pub(crate) fn peer_vmac(&self) -> Option<Vmac> {
+        self.peer.map(|peer| peer.vmac)
+    }
+
+    /// Send an NPDU to the peer.
+    pub(crate) fn send_npdu(&mut self, npdu: &[u8]) -> io::Result<()> {
+        let message = ScMessage::new(
+            self.next_message_id(),
+            ScPayload::EncapsulatedNpdu(npdu.to_vec()),
+        );
+        self.send(&message)
+    }
+
+    /// Handle every message waiting, queueing NPDUs with their source.
+    ///
+    /// `authorize` decides whether an accepted peer may connect with its
+    /// certificate. Returns `false` once the connection has ended.
+    pub(crate) fn service(
+        &mut self,
+        config: &ScNodeConfig,
+        now: Instant,
+        authorize: impl Fn(&[u8], &ConnectInfo) -> bool,
+        frames: &mut VecDeque<(Vec<u8>, DataLinkAddress)>,
+    ) -> bool {
+        loop {
+            let data = match self.socket.receive() {
+                Ok(data) => data,
+                Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                    return true
+                }
+                Err(_) => return false,
+            };
+            let Ok(message) = ScMessage::decode(&data) else {
+                continue;
+            };
+            self.last_received = now;
+            self.last_heartbeat = None;
+
+            match (&message.payload, self.peer) {
+                (ScPayload::ConnectRequest(peer), None) if self.target.is_none() => {
+                    if peer.vmac == config.vmac || !authorize(&self.certificate, peer) {
+                        return false;
+                    }
+                    self.peer = Some(*peer);
+                    let accept = message.reply(ScPayload::ConnectAccept(config.connect_info()));
+                    if self.send(&accept).is_err() {
+                        return false;
+                    }
+                }
+                (ScPayload::ConnectAccept(peer), None) => {
+                    // Only the node that was resolved may answer
+                    if self.target != Some(peer.vmac) {
+                        return false;
+                    }
+                    self.peer = Some(*peer);
+                }
+                (ScPayload::EncapsulatedNpdu(npdu), Some(peer)) => {
+                    frames.push_back((npdu.clone(), peer.vmac.into()));
+                }
+                (ScPayload::HeartbeatRequest, Some(_)) => {
+                    let sent = self.send(&message.reply(ScPayload::HeartbeatAck));
+                    if sent.is_err() {
+                        return false;
+                    }
+                }
+                (ScPayload::DisconnectRequest, _) => {
+                    let _ = self.send(&message.reply(ScPayload::DisconnectAck));
+                    let _ = self.socket.close();
+                    return false;
+                }
+                (ScPayload::DisconnectAck, _) | (ScPayload::Result { nak: Some(_), .. }, None) => {
+                    let _ = self.socket.close();
+                    return false;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Apply the connection timing: the Connect-Accept must arrive within
+    /// the connect wait timeout, the initiator sends heartbeats on an idle
+    /// connection, and either side gives up after two silent heartbeat
+    /// timeouts. Returns `false` once the connection should close.
+    pub(crate) fn maintain(&mut self, config: &ScNodeConfig, now: Instant) -> bool {
+        if self.peer.is_none() {
+            return now.duration_since(self.opened) < config.connect_wait_timeout;
+        }
+        let silent = now.duration_since(self.last_received);
+        if silent >= config.heartbeat_timeout * 2 {
+            return false;
+        }
+        let heartbeat_due = self
+            .last_heartbeat
+            .is_none_or(|sent| now.duration_since(sent) >= config.heartbeat_timeout);
+        if self.target.is_some() && silent >= config.heartbeat_timeout && heartbeat_due {
+            self.last_heartbeat = Some(now);
+            let heartbeat = ScMessage::new(self.next_message_id(), ScPayload::HeartbeatRequest);
+            return self.send(&heartbeat).is_ok();
+        }
+        true
+    }
+
+    /// End the connection, telling the peer first.
+    pub(crate) fn close(&mut self) {
+        let request = ScMessage::new(self.next_message_id(), ScPayload::DisconnectRequest);
+        let _ = self.send(&request);
+        let _ = self.socket.close();
+    }
+
+    fn send(&mut self, message: &ScMessage) -> io::Result<()> {
+        let frame = message
+            .encode()
+            .map_err(|_| io::Error::new(ErrorKind::InvalidInput, "Unencodable message"))?;
+        self.socket.send_binary(&frame)
+    }
+
+    fn next_message_id(&mut self) -> u16 {
+        self.message_id = self.message_id.wrapping_add(1);
+        self.message_id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datalink::bsc::{ScConnectionState, ScConnector, ScDataLink};
+    use crate::datalink::sc_hub::ScHubServer;
+    use crate::datalink::websocket::WebSocketUri;
+    use crate::datalink::DataLink;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    struct PlainAcceptor;
+
+    impl ScAcceptor for PlainAcceptor {
+        type Stream = TcpStream;
+
+        fn accept(&mut self, stream: TcpStream) -> io::Result<(TcpStream, Vec<u8>)> {
+            stream.set_read_timeout(Some(Duration::from_millis(5)))?;
+            Ok((stream, vec![0x30, 0x00]))
+        }
+    }
+
+    struct PlainConnector;
+
+    impl ScConnector for PlainConnector {
+        type Stream = TcpStream;
+
+        fn connect(&mut self, uri: &WebSocketUri) -> io::Result<TcpStream> {
+            let stream = TcpStream::connect((uri.host.as_str(), uri.port))?;
+            stream.set_read_timeout(Some(Duration::from_millis(20)))?;
+            Ok(stream)
+        }
+    }
+
+    fn connected<C: ScConnector, A: ScAcceptor>(mut node: ScDataLink<C, A>) -> ScDataLink<C, A> {
+        for _ in 0..100 {
+            if node.state() == ScConnectionState::Connected {
+                break;
+            }
+            let _ = node.receive_frame();
+        }
+        assert_eq!(node.state(), ScConnectionState::Connected);
+        node
+    }
+
+    #[test]
+    fn test_direct_connection() {
+        let mut server =
+            ScHubServer::bind("127.0.0.1:0", &ScNodeConfig::new(""), PlainAcceptor).unwrap();
+        let uri = format!("ws://{}/", server.local_addr().unwrap());
+        let running = Arc::new(AtomicBool::new(true));
+        let hub = {
+            let running = running.clone();
+            std::thread::spawn(move || {
+                while running.load(Ordering::Relaxed) {
+                    server.poll().unwrap();
+                    std::thread::sleep(Duration::from_millis(1));
+                }
+            })
+        };
+
+        let mut a = ScDataLink::new(ScNodeConfig::new(&uri), PlainConnector)
+            .accept_direct_connections("127.0.0.1:0", PlainAcceptor, Vec::new())
+            .unwrap();
+        let direct_uri = format!("ws://{}/", a.direct_listen_address().unwrap());
+        a.set_direct_uris(vec![direct_uri]);
+        let mut a = connected(a);
+        let a_vmac = a.vmac();
+        let node_a = std::thread::spawn(move || {
+            let frame = (0..500).find_map(|_| a.receive_frame().ok());
+            (frame, a.direct_peers())
+        });
+
+        let mut b = connected(ScDataLink::new(ScNodeConfig::new(&uri), PlainConnector));
+        b.connect_direct(a_vmac).unwrap();
+        for _ in 0..100 {
+            if !b.direct_peers().is_empty() {
+                break;
+            }
+            let _ = b.receive_frame();
+        }
+        assert_eq!(b.direct_peers(), vec![a_vmac]);
+
+        // With the hub gone, only the direct connection can carry the NPDU
+        running.store(false, Ordering::Relaxed);
+        hub.join().unwrap();
+        b.send_frame(&[0x01, 0x00], &a_vmac.into()).unwrap();
+        let (frame, peers) = node_a.join().unwrap();
+        assert_eq!(frame, Some((vec![0x01, 0x00], b.local_address())));
+        assert_eq!(peers, vec![b.vmac()]);
+        assert!(b.connect_direct(a_vmac).is_ok());
+    }
+}
@@ -0,0 +1,643 @@
+//! BACnet/SC hub function (ASHRAE 135 Annex AB.5).
+//!
+//! The hub is the centre of a BACnet/SC network. Nodes open secure
+//! WebSocket connections to it and send a Connect-Request with their VMAC
+//! and device UUID; the hub records the VMAC in its routing table, forwards
+//! unicast messages to the connection holding the destination VMAC, and
+//! replicates broadcasts to every other connected node. Forwarded messages
+//! carry the sender's VMAC as their originating address.
+//!
+//! [`ScHub`] holds the routing table and the forwarding rules without doing
+//! any I/O. [`ScHubServer`] listens for connections, runs the TLS handshake
+//! through an [`ScAcceptor`], and drives the hub.
+//!
+//! # Authentication
+//!
+//! Every node must present a certificate during the TLS handshake. The
+//! acceptor verifies it against the CA certificates of the hub's device and
+//! hands it to the server; connections without one are refused. The
+//! acceptor can also refuse individual nodes at Connect-Request time through
+//! [`ScAcceptor::authorize`].
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use bacnet_rs::datalink::bsc::ScNodeConfig;
+//! use bacnet_rs::datalink::sc_hub::{ScAcceptor, ScHubServer};
+//! use std::net::TcpStream;
+//!
+//! // A real acceptor would run a TLS server handshake requiring a client
+//! // certificate
+//! struct Acceptor;
+//!
+//! impl ScAcceptor for Acceptor {
+//!     type Stream = TcpStream;
+//!
+//!     fn accept(&mut self, stream: TcpStream) -> std::io::Result<(TcpStream, Vec<u8>)> {
+//!         stream.set_read_timeout(Some(std::time::Duration::from_millis(5)))?;
+//!         Ok((stream, vec![0x30]))
+//!     }
+//! }
+//!
+//! # fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let config = ScNodeConfig::new("");
+//! let mut server = ScHubServer::bind("0.0.0.0:443", &config, Acceptor)?;
+//! loop {
+//!     server.poll()?;
+//! }
+//! # }
+//! ```
+
+use std::{
+    collections::HashMap,
+    io::{self, ErrorKind, Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+    time::{Duration, Instant},
+};
+
+use crate::datalink::bsc::{
+    ConnectInfo, ScFunction, ScMessage, ScNak, ScNodeConfig, ScPayload, Vmac,
+    ERROR_CLASS_COMMUNICATION, ERROR_CODE_NODE_DUPLICATE_VMAC, HUB_SUBPROTOCOL,
+};
+use crate::datalink::websocket::WebSocket;
+use crate::datalink::{DataLinkError, Result};
+
+/// Identifies one connection to the hub.
+pub type ConnectionId = u64;
+
+/// What the hub wants done after a message or timer.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HubOutcome {
+    /// Messages to send, with the connection to send each on.
+    pub sends: Vec<(ConnectionId, ScMessage)>,
+    /// Connections to close once the messages are sent.
+    pub close: Vec<ConnectionId>,
+}
+
+/// One connection as the hub sees it.
+#[derive(Debug, Clone)]
+struct HubPeer {
+    /// The node's Connect-Request, once accepted.
+    node: Option<ConnectInfo>,
+
+    /// When the connection opened.
+    opened: Instant,
+
+    /// When a message last arrived on it.
+    last_received: Instant,
+}
+
+/// Routing table and forwarding rules of a BACnet/SC hub.
+#[derive(Debug, Clone)]
+pub struct ScHub {
+    /// Connect-Accept payload: the hub's own VMAC and UUID.
+    info: ConnectInfo,
+
+    /// How long a node may stay silent; the hub closes at twice this.
+    heartbeat_timeout: Duration,
+
+    /// How long a new connection may take to send its Connect-Request.
+    connect_wait_timeout: Duration,
+
+    /// Open connections.
+    peers: HashMap<ConnectionId, HubPeer>,
+
+    /// Connection holding each connected VMAC.
+    routes: HashMap<Vmac, ConnectionId>,
+}
+
+impl ScHub {
+    /// Create a hub with the VMAC, UUID, lengths and timings of `config`.
+    pub fn new(config: &ScNodeConfig) -> Self {
+        Self {
+            info: config.connect_info(),
+            heartbeat_timeout: config.heartbeat_timeout,
+            connect_wait_timeout: config.connect_wait_timeout,
+            peers: HashMap::new(),
+            routes: HashMap::new(),
+        }
+    }
+
+    /// The hub's VMAC.
+    pub fn vmac(&self) -> Vmac {
+        self.info.vmac
+    }
+
+    /// Record a new connection, which must send a Connect-Request next.
+    pub fn connection_opened(&mut self, id: ConnectionId, now: Instant) {
+        self.peers.insert(
+            id,
+            HubPeer {
+                node: None,
+                opened: now,
+                last_received: now,
+            },
+        );
+    }
+
+    /// Forget a closed connection and its route.
+    pub fn connection_closed(&mut self, id: ConnectionId) {
+        if let Some(node) = self.peers.remove(&id).and_then(|peer| peer.node) {
+            if self.routes.get(&node.vmac) == Some(&id) {
+                self.routes.remove(&node.vmac);
+            }
+        }
+    }
+
+    /// The connection holding `vmac`.
+    pub fn route(&self, vmac: Vmac) -> Option<ConnectionId> {
+        self.routes.get(&vmac).copied()
+    }
+
+    /// The connected nodes, as they introduced themselves.
+    pub fn nodes(&self) -> Vec<ConnectInfo> {
+        self.peers.values().filter_map(|peer| peer.node).collect()
+    }
+
+    /// Handle a message that arrived on connection `id`.
+    ///
+    /// A Connect-Request claiming a VMAC that another device holds is
+    /// refused with NODE_DUPLICATE_VMAC; one from the same device UUID
+    /// replaces the older connection, as when a node reconnects before the
+    /// hub noticed the old connection drop. Messages with a destination
+    /// VMAC are forwarded, and everything else from a node that has not
+    /// connected yet is dropped.
+    pub fn process(&mut self, id: ConnectionId, message: ScMessage, now: Instant) -> HubOutcome {
+        let mut outcome = HubOutcome::default();
+        let Some(peer) = self.peers.get_mut(&id) else {
+            return outcome;
+        };
+        peer.last_received = now;
+
+        let sender = match (&message.payload, peer.node) {
+            (ScPayload::ConnectRequest(node), None) => {
+                let node = *node;
+                if let Err(nak) = self.admit(id, node, &mut outcome) {
+                    let reply = message.reply(ScPayload::Result {
+                        function: ScFunction::ConnectRequest as u8,
+                        nak: Some(nak),
+                    });
+                    outcome.sends.push((id, reply));
+                    outcome.close.push(id);
+                    self.connection_closed(id);
+                } else {
+                    let accept = message.reply(ScPayload::ConnectAccept(self.info));
+                    outcome.sends.push((id, accept));
+                }
+                return outcome;
+            }
+            (_, Some(node)) => node.vmac,
+            (_, None) => return outcome,
+        };
+
+        match (&message.payload, message.destination) {
+            (ScPayload::HeartbeatRequest, None) => {
+                outcome
+                    .sends
+                    .push((id, message.reply(ScPayload::HeartbeatAck)));
+            }
+            (ScPayload::DisconnectRequest, None) => {
+                outcome
+                    .sends
+                    .push((id, message.reply(ScPayload::DisconnectAck)));
+                outcome.close.push(id);
+                self.connection_closed(id);
+            }
+            (_, Some(destination)) if destination.is_broadcast() => {
+                for (&target, peer) in &self.peers {
+                    if target != id && peer.node.is_some() {
+                        let mut forward = message.clone();
+                        forward.originating = Some(sender);
+                        outcome.sends.push((target, forward));
+                    }
+                }
+            }
+            (_, Some(destination)) => {
+                if let Some(&target) = self.routes.get(&destination) {
+                    let mut forward = message;
+                    forward.originating = Some(sender);
+                    forward.destination = None;
+                    outcome.sends.push((target, forward));
+                }
+            }
+            _ => {}
+        }
+        outcome
+    }
+
+    /// Close connections that never connected or went silent.
+    ///
+    /// Returns the connections to close; they are already forgotten.
+    pub fn expire(&mut self, now: Instant) -> Vec<ConnectionId> {
+        let expired: Vec<ConnectionId> = self
+            .peers
+            .iter()
+            .filter(|(_, peer)| match peer.node {
+                None => now.duration_since(peer.opened) >= self.connect_wait_timeout,
+                Some(_) => now.duration_since(peer.last_received) >= self.heartbeat_timeout * 2,
+            })
+            .map(|(&id, _)| id)
+            .collect();
+        for &id in &expired {
+            self.connection_closed(id);
+        }
+        expired
+    }
+
+    fn admit(
+        &mut self,
+        id: ConnectionId,
+        node: ConnectInfo,
+        outcome: &mut HubOutcome,
+    ) -> core::result::Result<(), ScNak> {
+        let duplicate = || ScNak {
+            header_marker: 0,
+            error_class: ERROR_CLASS_COMMUNICATION,
+            error_code: ERROR_CODE_NODE_DUPLICATE_VMAC,
+            details: format!("VMAC {} is already in use", node.vmac),
+        };
+        if node.vmac == self.info.vmac || node.vmac.is_broadcast() {
+            return Err(duplicate());
+        }
+        if let Some(&holder) = self.routes.get(&node.vmac) {
+            let same_device = self.peers[&holder]
+                .node
+                .is_some_and(|held| held.device_uuid == node.device_uuid);
+            if !same_device {
+                return Err(duplicate());
+            }
+            self.connection_closed(holder);
+            outcome.close.push(holder);
+        }
+
+        if let Some(peer) = self.peers.get_mut(&id) {
+            peer.node = Some(node);
+        }
+        self.routes.insert(node.vmac, id);
+        Ok(())
+    }
+}
+
+/// Runs the TLS server side of the hub's connections.
+pub trait ScAcceptor: Send + Sync {
+    /// The TLS session type.
+    type Stream: Read + Write + Send + Sync;
+
+    /// Run the TLS handshake on a new connection, requiring a client
+    /// certificate signed by one of the device's CA certificates.
+    ///
+    /// Returns the session and the DER-encoded client certificate. The
+    /// session should have a short read timeout so the hub can serve every
+    /// connection in turn.
+    fn accept(&mut self, stream: TcpStream) -> io::Result<(Self::Stream, Vec<u8>)>;
+
+    /// Whether the node holding `certificate` may join as `node`.
+    ///
+    /// Every node whose certificate passed the handshake is allowed by
+    /// default.
+    fn authorize(&self, certificate: &[u8], node: &ConnectInfo) -> bool {
+        let _ = (certificate, node);
+        true
+    }
+}
+
+/// An open connection of the hub server.
+struct HubLink<S> {
+    /// The node's WebSocket.
+    socket: WebSocket<S>,
+
+    /// The node's client certificate.
+    certificate: Vec<u8>,
+}
+
+/// A BACnet/SC hub listening for node connections.
+pub struct ScHubServer<A: ScAcceptor> {
+    /// Listening socket, non-blocking.
+    listener: TcpListener,
+
+    /// Runs the TLS handshakes.
+    acceptor: A,
+
+    /// Routing table.
+    hub: ScHub,
+
+    /// Open connections.
+    links: HashMap<ConnectionId, HubLink<A::Stream>>,
+
+    /// Identifier for the next connection.
+    next_id: ConnectionId,
+
+    /// How long a node may take over the WebSocket handshake.
+    handshake_timeout: Duration,
+}
+
+impl<A: ScAcceptor> ScHubServer<A> {
+    /// Listen on `addr` as the hub of the device described by `config`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the address cannot be bound.
+    pub fn bind<T: ToSocketAddrs>(addr: T, config: &ScNodeConfig, acceptor: A) -> Result<Self> {
+        let listener = TcpListener::bind(addr).map_err(DataLinkError::IoError)?;
+        listener
+            .set_nonblocking(true)
+            .map_err(DataLinkError::IoError)?;
+        Ok(Self {
+            listener,
+            acceptor,
+            hub: ScHub::new(config),
+            links: HashMap::new(),
+            next_id: 1,
+            handshake_timeout: config.connect_wait_timeout,
+        })
+    }
+
+    /// The address the hub listens on.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the socket address cannot be read.
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        self.listener.local_addr().map_err(DataLinkError::IoError)
+    }
+
+    /// The routing table.
+    pub fn hub(&self) -> &ScHub {
+        &self.hub
+    }
+
+    /// The client certificate of the node holding `vmac`.
+    pub fn certificate(&self, vmac: Vmac) -> Option<&[u8]> {
+        let id = self.hub.route(vmac)?;
+        self.links.get(&id).map(|link| link.certificate.as_slice())
+    }
+
+    /// Accept new connections, forward whatever the nodes have sent, and
+    /// close silent connections.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error only if the listening socket fails; failures of
+    /// single connections close them.
+    pub fn poll(&mut self) -> Result<()> {
+        loop {
+            match self.listener.accept() {
+                Ok((stream, _)) => {
+                    // A refused handshake only loses that connection
+                    let _ = self.accept_connection(stream);
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) => return Err(DataLinkError::IoError(e)),
+            }
+        }
+
+        let ids: Vec<ConnectionId> = self.links.keys().copied().collect();
+        for id in ids {
+            self.service(id);
+        }
+
+        let now = Instant::now();
+        let expired = self.hub.expire(now);
+        for id in expired {
+            self.close(id);
+        }
+        Ok(())
+    }
+
+    fn accept_connection(&mut self, stream: TcpStream) -> io::Result<()> {
+        stream.set_nonblocking(false)?;
+        let (stream, certificate) = self.acceptor.accept(stream)?;
+        if certificate.is_empty() {
+            return Err(io::Error::new(
+                ErrorKind::PermissionDenied,
+                "Node presented no certificate",
+            ));
+        }
+        let socket = WebSocket::accept(stream, &[HUB_SUBPROTOCOL], self.handshake_timeout)?;
+
+        let id = self.next_id;
+        self.next_id += 1;
+        self.hub.connection_opened(id, Instant::now());
+        self.links.insert(
+            id,
+            HubLink {
+                socket,
+                certificate,
+            },
+        );
+        Ok(())
+    }
+
+    /// Handle every complete message waiting on connection `id`.
+    fn service(&mut self, id: ConnectionId) {
+        loop {
+            let Some(link) = self.links.get_mut(&id) else {
+                return;
+            };
+            let data = match link.socket.receive() {
+                Ok(data) => data,
+                Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => return,
+                Err(_) => {
+                    self.hub.connection_closed(id);
+                    self.close(id);
+                    return;
+                }
+            };
+            let Ok(message) = ScMessage::decode(&data) else {
+                continue;
+            };
+            if let ScPayload::ConnectRequest(node) = &message.payload {
+                if !self.acceptor.authorize(&link.certificate, node) {
+                    self.hub.connection_closed(id);
+                    self.close(id);
+                    return;
+                }
+            }
+            let outcome = self.hub.process(id, message, Instant::now());
+            self.dispatch(outcome);
+        }
+    }
+
+    fn dispatch(&mut self, outcome: HubOutcome) {
+        for (target, message) in outcome.sends {
+            let Ok(frame) = message.encode() else {
+                continue;
+            };
+            let failed = self
+                .links
+                .get_mut(&target)
+                .is_some_and(|link| link.socket.send_binary(&frame).is_err());
+            if failed {
+                self.hub.connection_closed(target);
+                self.close(target);
+            }
+        }
+        for id in outcome.close {
+            self.close(id);
+        }
+    }
+
+    fn close(&mut self, id: ConnectionId) {
+        if let Some(mut link) = self.links.remove(&id) {
+            let _ = link.socket.close();
+        }
+    }
+}
+
+impl<A: ScAcceptor> core::fmt::Debug for ScHubServer<A> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ScHubServer")
+            .field("listener", &self.listener)
+            .field("hub", &self.hub)
+            .field("connections", &self.links.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datalink::bsc::{ScConnectionState, ScConnector, ScDataLink};
+    use crate::datalink::websocket::WebSocketUri;
+    use crate::datalink::{DataLink, DataLinkAddress};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    fn node(vmac: u8, uuid: u8) -> ConnectInfo {
+        ConnectInfo {
+            vmac: Vmac([0x02, 0, 0, 0, 0, vmac]),
+            device_uuid: [uuid; 16],
+            max_bvlc_length: 1600,
+            max_npdu_length: 1497,
+        }
+    }
+
+    #[test]
+    fn test_routing_table() {
+        let now = Instant::now();
+        let mut hub = ScHub::new(&ScNodeConfig::new(""));
+        for id in 1..=4 {
+            hub.connection_opened(id, now);
+        }
+        let connect = |info| ScMessage::new(1, ScPayload::ConnectRequest(info));
+
+        let outcome = hub.process(1, connect(node(1, 0xA1)), now);
+        assert!(matches!(
+            outcome.sends[0].1.payload,
+            ScPayload::ConnectAccept(_)
+        ));
+        hub.process(2, connect(node(2, 0xA2)), now);
+
+        // Same VMAC from another device is refused
+        let outcome = hub.process(3, connect(node(1, 0xA3)), now);
+        assert!(matches!(
+            &outcome.sends[0].1.payload,
+            ScPayload::Result { nak: Some(nak), .. }
+                if nak.error_code == ERROR_CODE_NODE_DUPLICATE_VMAC
+        ));
+        assert_eq!(outcome.close, vec![3]);
+
+        // Unicast goes to the route, with the originating VMAC added
+        let npdu = ScMessage::new(9, ScPayload::EncapsulatedNpdu(vec![0x01, 0x00]));
+        let outcome = hub.process(1, npdu.clone().with_destination(node(2, 0).vmac), now);
+        assert_eq!(
+            outcome.sends,
+            vec![(2, npdu.clone().with_originating(node(1, 0).vmac))]
+        );
+
+        // Unconnected connections neither send nor receive broadcasts
+        let outcome = hub.process(4, npdu.clone().with_destination(Vmac::BROADCAST), now);
+        assert!(outcome.sends.is_empty());
+        let outcome = hub.process(2, npdu.clone().with_destination(Vmac::BROADCAST), now);
+        assert_eq!(outcome.sends.len(), 1);
+        assert_eq!(outcome.sends[0].0, 1);
+
+        // The same device reconnecting replaces its old connection
+        hub.connection_opened(5, now);
+        let outcome = hub.process(5, connect(node(1, 0xA1)), now);
+        assert_eq!(outcome.close, vec![1]);
+        assert_eq!(hub.route(node(1, 0).vmac), Some(5));
+
+        // Silent nodes and connections that never connect are closed
+        let config = ScNodeConfig::new("");
+        let later = now + config.connect_wait_timeout;
+        let mut expired = hub.expire(later);
+        expired.sort();
+        assert_eq!(expired, vec![4]);
+        hub.process(2, ScMessage::new(3, ScPayload::HeartbeatRequest), later);
+        let mut expired = hub.expire(now + config.heartbeat_timeout * 2);
+        expired.sort();
+        assert_eq!(expired, vec![5]);
+        assert_eq!(hub.nodes().len(), 1);
+    }
+
+    struct PlainAcceptor;
+
+    impl ScAcceptor for PlainAcceptor {
+        type Stream = TcpStream;
+
+        fn accept(&mut self, stream: TcpStream) -> io::Result<(TcpStream, Vec<u8>)> {
+            stream.set_read_timeout(Some(Duration::from_millis(5)))?;
+            Ok((stream, vec![0x30, 0x00]))
+        }
+    }
+
+    struct PlainConnector;
+
+    impl ScConnector for PlainConnector {
+        type Stream = TcpStream;
+
+        fn connect(&mut self, uri: &WebSocketUri) -> io::Result<TcpStream> {
+            let stream = TcpStream::connect((uri.host.as_str(), uri.port))?;
+            stream.set_read_timeout(Some(Duration::from_millis(20)))?;
+            Ok(stream)
+        }
+    }
+
+    #[test]
+    fn test_hub_server_forwarding() {
+        let mut server =
+            ScHubServer::bind("127.0.0.1:0", &ScNodeConfig::new(""), PlainAcceptor).unwrap();
+        let uri = format!("ws://{}/", server.local_addr().unwrap());
+        let running = Arc::new(AtomicBool::new(true));
+        let hub = {
+            let running = running.clone();
+            std::thread::spawn(move || {
+                while running.load(Ordering::Relaxed) {
+                    server.poll().unwrap();
+                    std::thread::sleep(Duration::from_millis(1));
+                }
+                server
+            })
+        };
+
+        let mut nodes: Vec<_> = (0..2)
+            .map(|_| ScDataLink::new(ScNodeConfig::new(&uri), PlainConnector))
+            .collect();
+        for node in &mut nodes {
+            for _ in 0..100 {
+                if node.state() == ScConnectionState::Connected {
+                    break;
+                }
+                let _ = node.receive_frame();
+            }
+            assert_eq!(node.state(), ScConnectionState::Connected);
+        }
+        let (a, b) = (nodes[0].local_address(), nodes[1].local_address());
+
+        nodes[0]
+            .send_frame(&[0x01, 0x20], &DataLinkAddress::Broadcast)
+            .unwrap();
+        let received = (0..100).find_map(|_| nodes[1].receive_frame().ok());
+        assert_eq!(received, Some((vec![0x01, 0x20], a.clone())));
+
+        nodes[1].send_frame(&[0x01, 0x00], &a).unwrap();
+        let received = (0..100).find_map(|_| nodes[0].receive_frame().ok());
+        assert_eq!(received, Some((vec![0x01, 0x00], b)));
+
+        running.store(false, Ordering::Relaxed);
+        let server = hub.join().unwrap();
+        assert_eq!(server.hub().nodes().len(), 2);
+        assert_eq!(server.certificate(nodes[0].vmac()), Some(&[0x30, 0x00][..]));
+    }
+}
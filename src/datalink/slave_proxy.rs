@@ -0,0 +1,416 @@
+//! MS/TP slave proxy.
+//!
+//! MS/TP slaves never hold the token, so they can only transmit in reply to
+//! a data request addressed to them. They cannot answer a broadcast Who-Is,
+//! which leaves them invisible to device discovery. A router to the MS/TP
+//! network can act as their proxy: it sends each slave a Who-Is of its own
+//! in a Data-Expecting-Reply frame, remembers the I-Am the slave replies
+//! with, and answers Who-Is requests from its other networks with those I-Am
+//! messages, sourced from the slave's network and station address.
+//!
+//! [`SlaveProxy`] keeps the Slave_Address_Binding list. Slaves come from the
+//! port's Manual_Slave_Address_Binding and, with Auto_Slave_Discovery, from a
+//! sweep of every station address that has not been seen passing the token.
+//! Each sweep probes one station per [`SLAVE_PROBE_INTERVAL`]; a slave that
+//! stays silent until the next probe is dropped from the list.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use std::time::Instant;
+//! use bacnet_rs::datalink::mstp::{MstpConfig, MstpDataLink};
+//! use bacnet_rs::datalink::slave_proxy::SlaveProxy;
+//! use bacnet_rs::datalink::{DataLink, DataLinkAddress};
+//! use bacnet_rs::object::MstpPortSettings;
+//!
+//! # fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let settings = MstpPortSettings {
+//!     slave_proxy_enable: true,
+//!     auto_slave_discovery: true,
+//!     ..Default::default()
+//! };
+//! let mut mstp = MstpDataLink::open("/dev/ttyS0", MstpConfig::from_settings(&settings))?;
+//! let mut proxy = SlaveProxy::from_settings(2, &settings);
+//!
+//! if let Some((station, who_is)) = proxy.poll(Instant::now(), |s| mstp.is_known_master(s)) {
+//!     mstp.send_frame(&who_is, &DataLinkAddress::MsTP(station))?;
+//! }
+//! if let Ok((npdu, DataLinkAddress::MsTP(source))) = mstp.receive_frame() {
+//!     proxy.receive(&npdu, source);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::time::{Duration, Instant};
+
+use crate::app::Apdu;
+use crate::network::{NetworkAddress, Npdu};
+use crate::object::{MstpPortSettings, SlaveBinding};
+use crate::service::{IAmRequest, UnconfirmedServiceChoice, WhoIsRequest};
+
+/// Time between probes of successive station addresses.
+pub const SLAVE_PROBE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Time from the start of one sweep of the station addresses to the next.
+pub const SLAVE_DISCOVERY_INTERVAL: Duration = Duration::from_secs(600);
+
+/// A slave that answered a probe.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProxiedSlave {
+    /// Station address of the slave.
+    pub mac_address: u8,
+
+    /// The I-Am the slave replied with.
+    pub i_am: IAmRequest,
+}
+
+/// The slave proxy function of a router port on an MS/TP network.
+#[derive(Debug, Clone)]
+pub struct SlaveProxy {
+    /// Network number of the MS/TP network.
+    network: u16,
+
+    /// This station, never probed.
+    station: u8,
+
+    /// Whether Who-Is requests are answered for slaves.
+    enabled: bool,
+
+    /// Whether every station address is probed.
+    auto_discovery: bool,
+
+    /// Slaves configured by hand.
+    manual: Vec<SlaveBinding>,
+
+    /// Slaves that answered.
+    slaves: Vec<ProxiedSlave>,
+
+    /// Next station address of the sweep in progress.
+    sweep: Option<u16>,
+
+    /// When the last sweep started, `None` to start one now.
+    sweep_started: Option<Instant>,
+
+    /// Station probed last, while unanswered.
+    probing: Option<u8>,
+
+    /// When the last probe was sent.
+    last_probe: Option<Instant>,
+}
+
+impl SlaveProxy {
+    /// Create a proxy for the MS/TP network `network`, where this router is
+    /// `station`, with the proxy and discovery both disabled.
+    pub fn new(network: u16, station: u8) -> Self {
+        Self {
+            network,
+            station,
+            enabled: false,
+            auto_discovery: false,
+            manual: Vec::new(),
+            slaves: Vec::new(),
+            sweep: None,
+            sweep_started: None,
+            probing: None,
+            last_probe: None,
+        }
+    }
+
+    /// Create a proxy from the settings of an MS/TP Network Port on network
+    /// `network`.
+    pub fn from_settings(network: u16, settings: &MstpPortSettings) -> Self {
+        Self {
+            enabled: settings.slave_proxy_enable,
+            auto_discovery: settings.auto_slave_discovery,
+            manual: settings.manual_slave_address_binding.clone(),
+            ..Self::new(network, settings.mac_address)
+        }
+    }
+
+    /// Whether Who-Is requests are answered for slaves.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// The slaves that answered their last probe.
+    pub fn slaves(&self) -> &[ProxiedSlave] {
+        &self.slaves
+    }
+
+    /// The Slave_Address_Binding list for the Network Port.
+    pub fn slave_address_binding(&self) -> Vec<SlaveBinding> {
+        self.slaves
+            .iter()
+            .map(|slave| SlaveBinding {
+                device_identifier: slave.i_am.device_identifier,
+                mac_address: slave.mac_address,
+            })
+            .collect()
+    }
+
+    /// Start a new sweep on the next [`poll`](Self::poll), as the
+    /// RESTART_SLAVE_DISCOVERY Network Port command asks.
+    pub fn restart_discovery(&mut self) {
+        self.sweep = None;
+        self.sweep_started = None;
+    }
+
+    /// The next probe due at `now`, as the station to send it to and a Who-Is
+    /// NPDU expecting a reply.
+    ///
+    /// `is_master` tells whether a station has been seen passing the token;
+    /// masters answer Who-Is themselves and are not probed.
+    pub fn poll(&mut self, now: Instant, is_master: impl Fn(u8) -> bool) -> Option<(u8, Vec<u8>)> {
+        let probe_due = self
+            .last_probe
+            .is_none_or(|sent| now.duration_since(sent) >= SLAVE_PROBE_INTERVAL);
+        if !probe_due {
+            return None;
+        }
+        // The station probed last stayed silent
+        if let Some(station) = self.probing.take() {
+            self.slaves.retain(|slave| slave.mac_address != station);
+        }
+
+        let sweep_due = self
+            .sweep_started
+            .is_none_or(|started| now.duration_since(started) >= SLAVE_DISCOVERY_INTERVAL);
+        if self.sweep.is_none() && sweep_due {
+            self.sweep = Some(0);
+            self.sweep_started = Some(now);
+        }
+        while let Some(next) = self.sweep {
+            if next >= 255 {
+                self.sweep = None;
+                break;
+            }
+            self.sweep = Some(next + 1);
+            let station = next as u8;
+            if station == self.station {
+                continue;
+            }
+            let request = match self.manual_binding(station) {
+                Some(binding) if self.enabled => {
+                    WhoIsRequest::for_device(binding.device_identifier.instance)
+                }
+                _ if self.auto_discovery && !is_master(station) => WhoIsRequest::new(),
+                _ => continue,
+            };
+            self.probing = Some(station);
+            self.last_probe = Some(now);
+            return Some((station, who_is_npdu(&request)));
+        }
+        None
+    }
+
+    /// Take an NPDU received from `source`, recording the I-Am of the
+    /// station being probed.
+    pub fn receive(&mut self, npdu: &[u8], source: u8) {
+        if self.probing != Some(source) {
+            return;
+        }
+        let Some(i_am) = decode_i_am(npdu) else {
+            return;
+        };
+        // A manual binding names the device expected at the address
+        if self
+            .manual_binding(source)
+            .is_some_and(|binding| binding.device_identifier != i_am.device_identifier)
+        {
+            return;
+        }
+        self.probing = None;
+        self.slaves.retain(|slave| slave.mac_address != source);
+        self.slaves.push(ProxiedSlave {
+            mac_address: source,
+            i_am,
+        });
+    }
+
+    /// Answer the service data of a Who-Is received on another network with
+    /// an I-Am NPDU for each slave in range.
+    ///
+    /// The NPDUs are global broadcasts whose source is the slave on the
+    /// MS/TP network. Nothing is answered while the proxy is disabled, the
+    /// network number is unknown, or the request cannot be decoded.
+    pub fn handle_who_is(&self, service_data: &[u8]) -> Vec<Vec<u8>> {
+        let Ok(request) = WhoIsRequest::decode(service_data) else {
+            return Vec::new();
+        };
+        if !self.enabled || self.network == 0 {
+            return Vec::new();
+        }
+        self.slaves
+            .iter()
+            .filter(|slave| request.matches(slave.i_am.device_identifier.instance))
+            .filter_map(|slave| {
+                let mut service_data = Vec::new();
+                slave.i_am.encode(&mut service_data).ok()?;
+                let mut npdu = Npdu::global_broadcast();
+                npdu.control.source_present = true;
+                npdu.source = Some(NetworkAddress::new(self.network, vec![slave.mac_address]));
+                let mut frame = npdu.encode();
+                frame.extend(
+                    Apdu::UnconfirmedRequest {
+                        service_choice: UnconfirmedServiceChoice::IAm,
+                        service_data,
+                    }
+                    .encode(),
+                );
+                Some(frame)
+            })
+            .collect()
+    }
+
+    fn manual_binding(&self, station: u8) -> Option<&SlaveBinding> {
+        self.manual
+            .iter()
+            .find(|binding| binding.mac_address == station)
+    }
+}
+
+/// A local Who-Is NPDU that expects a reply, as slaves need to answer it
+fn who_is_npdu(request: &WhoIsRequest) -> Vec<u8> {
+    let mut npdu = Npdu::new();
+    npdu.control.expecting_reply = true;
+    let mut service_data = Vec::new();
+    // Who-Is limits are always encodable
+    let _ = request.encode(&mut service_data);
+    let mut frame = npdu.encode();
+    frame.extend(
+        Apdu::UnconfirmedRequest {
+            service_choice: UnconfirmedServiceChoice::WhoIs,
+            service_data,
+        }
+        .encode(),
+    );
+    frame
+}
+
+/// The I-Am an application-layer NPDU carries, if any
+fn decode_i_am(npdu: &[u8]) -> Option<IAmRequest> {
+    let (header, length) = Npdu::decode(npdu).ok()?;
+    if header.is_network_message() {
+        return None;
+    }
+    match Apdu::decode(npdu.get(length..)?).ok()? {
+        Apdu::UnconfirmedRequest {
+            service_choice: UnconfirmedServiceChoice::IAm,
+            service_data,
+        } => IAmRequest::decode(&service_data).ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::{ObjectIdentifier, ObjectType};
+
+    fn i_am_npdu(instance: u32) -> Vec<u8> {
+        let i_am = IAmRequest::new(
+            ObjectIdentifier::new(ObjectType::Device, instance),
+            480,
+            3,
+            260,
+        );
+        let mut service_data = Vec::new();
+        i_am.encode(&mut service_data).unwrap();
+        let mut npdu = Npdu::new().encode();
+        npdu.extend(
+            Apdu::UnconfirmedRequest {
+                service_choice: UnconfirmedServiceChoice::IAm,
+                service_data,
+            }
+            .encode(),
+        );
+        npdu
+    }
+
+    #[test]
+    fn test_auto_discovery() {
+        let settings = MstpPortSettings {
+            mac_address: 1,
+            slave_proxy_enable: true,
+            auto_slave_discovery: true,
+            ..Default::default()
+        };
+        let mut proxy = SlaveProxy::from_settings(5, &settings);
+        let start = Instant::now();
+        let at = |seconds| start + Duration::from_secs(seconds);
+
+        // Station 1 is this one and station 2 passes the token
+        let is_master = |station| station == 2;
+        let (station, who_is) = proxy.poll(start, is_master).unwrap();
+        assert_eq!(station, 0);
+        assert_eq!(who_is[1] & 0x04, 0x04);
+        assert!(proxy.poll(start, is_master).is_none());
+        assert_eq!(proxy.poll(at(1), is_master).unwrap().0, 3);
+        proxy.receive(&i_am_npdu(1003), 3);
+        assert_eq!(proxy.slaves()[0].mac_address, 3);
+
+        let mut who_is = Vec::new();
+        WhoIsRequest::for_range(1000, 2000)
+            .encode(&mut who_is)
+            .unwrap();
+        let answers = proxy.handle_who_is(&who_is);
+        assert_eq!(answers.len(), 1);
+        let (npdu, _) = Npdu::decode(&answers[0]).unwrap();
+        assert_eq!(npdu.source, Some(NetworkAddress::new(5, vec![3])));
+        assert_eq!(
+            decode_i_am(&answers[0]),
+            Some(proxy.slaves()[0].i_am.clone())
+        );
+
+        // The sweep ends after station 254; the next one finds 3 silent
+        let mut elapsed = 1;
+        while proxy.sweep.is_some() {
+            elapsed += 1;
+            proxy.poll(at(elapsed), is_master);
+        }
+        assert_eq!(proxy.slave_address_binding().len(), 1);
+        for seconds in [601, 602, 603, 604] {
+            proxy.poll(at(seconds), is_master);
+        }
+        assert!(proxy.slaves().is_empty());
+    }
+
+    #[test]
+    fn test_manual_binding() {
+        let binding = SlaveBinding {
+            device_identifier: ObjectIdentifier::new(ObjectType::Device, 2001),
+            mac_address: 200,
+        };
+        let mut settings = MstpPortSettings {
+            manual_slave_address_binding: vec![binding],
+            ..Default::default()
+        };
+        let start = Instant::now();
+
+        // Manual slaves are only probed by an enabled proxy
+        let mut proxy = SlaveProxy::from_settings(5, &settings);
+        assert!(proxy.poll(start, |_| false).is_none());
+
+        settings.slave_proxy_enable = true;
+        let mut proxy = SlaveProxy::from_settings(5, &settings);
+        let (station, who_is) = proxy.poll(start, |_| false).unwrap();
+        assert_eq!(station, 200);
+        assert!(who_is.ends_with(&{
+            let mut limits = Vec::new();
+            WhoIsRequest::for_device(2001).encode(&mut limits).unwrap();
+            limits
+        }));
+
+        // Another device at the address is not the one configured
+        proxy.receive(&i_am_npdu(2002), 200);
+        assert!(proxy.slaves().is_empty());
+        proxy.receive(&i_am_npdu(2001), 200);
+        assert_eq!(proxy.slave_address_binding(), vec![binding]);
+
+        let mut who_is = Vec::new();
+        WhoIsRequest::for_device(7).encode(&mut who_is).unwrap();
+        assert!(proxy.handle_who_is(&who_is).is_empty());
+        assert!(SlaveProxy::new(0, 1).handle_who_is(&[]).is_empty());
+    }
+}
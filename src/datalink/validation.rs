@@ -13,7 +13,7 @@
 //! - Common error detection patterns
 
 use crate::datalink::DataLinkType;
-use crate::util::crc16_mstp;
+use crate::util::{crc16_mstp, crc8_mstp};
 
 /// Frame validation result with detailed information
 #[derive(Debug, Clone)]
@@ -172,15 +172,18 @@ pub fn validate_ethernet_frame(data: &[u8]) -> ValidationResult {
         });
     }
 
-    // Check Ethernet type for BACnet
-    let ether_type = ((data[12] as u16) << 8) | (data[13] as u16);
-    if ether_type != 0x82DC {
+    // Check the 802.3 Length field, which counts the LLC header and NPDU
+    let length = ((data[12] as usize) << 8) | (data[13] as usize);
+    if length > 1500 {
         result.is_valid = false;
         result.errors.push(ValidationError::InvalidHeader {
-            reason: format!(
-                "Invalid Ethernet type: 0x{:04X}, expected 0x82DC",
-                ether_type
-            ),
+            reason: format!("Ethernet type 0x{:04X} in place of an 802.3 length", length),
+        });
+    } else if length < 3 || data.len() < 14 + length {
+        result.is_valid = false;
+        result.errors.push(ValidationError::PayloadSizeMismatch {
+            declared: length,
+            actual: data.len().saturating_sub(14),
         });
     }
 
@@ -371,12 +374,9 @@ pub fn validate_frame(data: &[u8]) -> ValidationResult {
         return validate_bacnet_ip_frame(data);
     }
 
-    // Check for Ethernet (has BACnet Ethernet type at offset 12-13)
-    if data.len() >= 14 {
-        let ether_type = ((data[12] as u16) << 8) | (data[13] as u16);
-        if ether_type == 0x82DC {
-            return validate_ethernet_frame(data);
-        }
+    // Check for Ethernet (has the BACnet LLC header at offset 14-16)
+    if data.len() >= 17 && data[14..17] == [0x82, 0x82, 0x03] {
+        return validate_ethernet_frame(data);
     }
 
     // Unknown frame type
@@ -393,20 +393,7 @@ pub fn validate_frame(data: &[u8]) -> ValidationResult {
 
 /// Calculate MS/TP header CRC (for validation)
 fn calculate_mstp_header_crc(header: &[u8; 5]) -> u8 {
-    let mut crc = 0xFFu8;
-
-    for &byte in header {
-        crc ^= byte;
-        for _ in 0..8 {
-            if crc & 0x01 != 0 {
-                crc = (crc >> 1) ^ 0x55;
-            } else {
-                crc >>= 1;
-            }
-        }
-    }
-
-    !crc
+    crc8_mstp(header)
 }
 
 /// Perform deep frame analysis
@@ -644,9 +631,9 @@ mod tests {
     fn test_ethernet_validation() {
         // Valid frame
         let mut valid_frame = vec![0u8; 60];
-        // Set Ethernet type
-        valid_frame[12] = 0x82;
-        valid_frame[13] = 0xDC;
+        // Set 802.3 length
+        valid_frame[12] = 0x00;
+        valid_frame[13] = 0x2B;
         // Set LLC header
         valid_frame[14] = 0x82;
         valid_frame[15] = 0x82;
@@ -655,7 +642,7 @@ mod tests {
         let result = validate_ethernet_frame(&valid_frame);
         assert!(result.is_valid);
 
-        // Wrong Ethernet type
+        // Ethernet type in place of the length
         valid_frame[12] = 0x08;
         valid_frame[13] = 0x00;
         let result = validate_ethernet_frame(&valid_frame);
@@ -671,7 +658,7 @@ mod tests {
             0x02, // Destination
             0x01, // Source
             0x00, 0x00, // Data length = 0
-            0xFC, // Header CRC (correct value for this header)
+            0x73, // Header CRC (correct value for this header)
         ];
 
         let result = validate_mstp_frame(&frame);
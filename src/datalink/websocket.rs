@@ -0,0 +1,763 @@
+//! Minimal WebSocket (RFC 6455) framing for BACnet/SC.
+//!
+//! BACnet/SC carries its BVLC messages as binary WebSocket messages over TLS.
+//! This module implements the opening handshake and framing over any byte
+//! stream; the TLS session underneath comes from the application, so the
+//! stack does not tie itself to one TLS library.
+//!
+//! The stream may have a read timeout. Partial frames stay buffered across
+//! timeouts, so [`WebSocket::receive`] can be called again after a
+//! `WouldBlock` or `TimedOut` error.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use bacnet_rs::datalink::websocket::WebSocket;
+//! use std::net::TcpStream;
+//! use std::time::Duration;
+//!
+//! # fn example() -> std::io::Result<()> {
+//! // A TLS stream would normally wrap the TCP connection
+//! let stream = TcpStream::connect("hub.example.com:443")?;
+//! let mut socket = WebSocket::connect(
+//!     stream,
+//!     "hub.example.com",
+//!     "/",
+//!     "hub.bsc.bacnet.org",
+//!     Duration::from_secs(10),
+//! )?;
+//! socket.send_binary(&[0x0A, 0x00, 0x00, 0x01])?;
+//! let reply = socket.receive()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+    collections::hash_map::RandomState,
+    fmt,
+    hash::{BuildHasher, Hasher},
+    io::{self, ErrorKind, Read, Write},
+    str::FromStr,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+/// GUID appended to the client key to form Sec-WebSocket-Accept.
+const ACCEPT_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// Largest message accepted, well above the BVLC-SC maximum.
+const MAX_MESSAGE_LENGTH: usize = 64 * 1024;
+
+const OPCODE_CONTINUATION: u8 = 0x0;
+const OPCODE_TEXT: u8 = 0x1;
+const OPCODE_BINARY: u8 = 0x2;
+const OPCODE_CLOSE: u8 = 0x8;
+const OPCODE_PING: u8 = 0x9;
+const OPCODE_PONG: u8 = 0xA;
+
+/// A `ws://` or `wss://` URI, as found in the hub URIs of a BACnet/SC port.
+///
+/// # Examples
+///
+/// ```
+/// use bacnet_rs::datalink::websocket::WebSocketUri;
+///
+/// let uri: WebSocketUri = "wss://hub.example.com/bacnet".parse().unwrap();
+/// assert_eq!(uri.host, "hub.example.com");
+/// assert_eq!(uri.port, 443);
+/// assert_eq!(uri.path, "/bacnet");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebSocketUri {
+    /// Whether the scheme is `wss`, meaning the connection runs over TLS.
+    pub secure: bool,
+    /// Host name or address, without brackets for IPv6.
+    pub host: String,
+    /// TCP port, defaulting to 443 for `wss` and 80 for `ws`.
+    pub port: u16,
+    /// Request path, `/` when the URI has none.
+    pub path: String,
+}
+
+impl WebSocketUri {
+    /// The value of the HTTP Host header for this URI.
+    pub fn host_header(&self) -> String {
+        if self.host.contains(':') {
+            format!("[{}]:{}", self.host, self.port)
+        } else {
+            format!("{}:{}", self.host, self.port)
+        }
+    }
+}
+
+impl FromStr for WebSocketUri {
+    type Err = io::Error;
+
+    fn from_str(uri: &str) -> io::Result<Self> {
+        let invalid = || {
+            io::Error::new(
+                ErrorKind::InvalidInput,
+                format!("Invalid WebSocket URI {}", uri),
+            )
+        };
+        let (secure, rest) = if let Some(rest) = uri.strip_prefix("wss://") {
+            (true, rest)
+        } else if let Some(rest) = uri.strip_prefix("ws://") {
+            (false, rest)
+        } else {
+            return Err(invalid());
+        };
+        let (authority, path) = match rest.find('/') {
+            Some(index) => rest.split_at(index),
+            None => (rest, "/"),
+        };
+
+        let (host, port) = if let Some(bracketed) = authority.strip_prefix('[') {
+            let (host, after) = bracketed.split_once(']').ok_or_else(invalid)?;
+            (host, after.strip_prefix(':'))
+        } else {
+            match authority.rsplit_once(':') {
+                Some((host, port)) => (host, Some(port)),
+                None => (authority, None),
+            }
+        };
+        let port = match port {
+            Some(port) => port.parse().map_err(|_| invalid())?,
+            None if secure => 443,
+            None => 80,
+        };
+        if host.is_empty() {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            secure,
+            host: host.to_string(),
+            port,
+            path: path.to_string(),
+        })
+    }
+}
+
+impl fmt::Display for WebSocketUri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let scheme = if self.secure { "wss" } else { "ws" };
+        write!(f, "{}://{}{}", scheme, self.host_header(), self.path)
+    }
+}
+
+/// A WebSocket connection over a byte stream.
+#[derive(Debug)]
+pub struct WebSocket<S> {
+    /// Underlying stream, normally a TLS session.
+    stream: S,
+
+    /// Frames received and sent.
+    framing: Framing,
+
+    /// Subprotocol agreed in the handshake.
+    protocol: String,
+}
+
+impl<S: Read + Write> WebSocket<S> {
+    /// Open a WebSocket connection as a client.
+    ///
+    /// Sends the opening handshake asking for `protocol` and checks the
+    /// server's answer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the stream fails, the server refuses the upgrade
+    /// or the subprotocol, or no answer arrives within `timeout`.
+    pub fn connect(
+        stream: S,
+        host: &str,
+        path: &str,
+        protocol: &str,
+        timeout: Duration,
+    ) -> io::Result<Self> {
+        let mut socket = Self::new(stream, true, protocol.to_string());
+        let (request, key) = handshake_request(host, path, protocol);
+        socket.stream.write_all(request.as_bytes())?;
+        socket.stream.flush()?;
+
+        let response = socket.read_http_head(timeout)?;
+        check_handshake_response(&response, &key, protocol)?;
+        Ok(socket)
+    }
+
+    /// Accept a WebSocket connection as a server.
+    ///
+    /// Reads the client's opening handshake and agrees on the first of the
+    /// client's subprotocols that appears in `protocols`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the stream fails, the request is not a WebSocket
+    /// upgrade, none of the subprotocols is supported, or the request does
+    /// not arrive within `timeout`. Refused requests are answered with an
+    /// HTTP error first.
+    pub fn accept(stream: S, protocols: &[&str], timeout: Duration) -> io::Result<Self> {
+        let mut socket = Self::new(stream, false, String::new());
+        let request = socket.read_http_head(timeout)?;
+        let (response, protocol) = match handshake_answer(&request, protocols) {
+            Ok(answer) => answer,
+            Err((refusal, e)) => {
+                socket.stream.write_all(refusal.as_bytes())?;
+                socket.stream.flush()?;
+                return Err(e);
+            }
+        };
+        socket.stream.write_all(response.as_bytes())?;
+        socket.stream.flush()?;
+        socket.protocol = protocol;
+        Ok(socket)
+    }
+
+    fn new(stream: S, client: bool, protocol: String) -> Self {
+        Self {
+            stream,
+            framing: Framing::new(client),
+            protocol,
+        }
+    }
+
+    /// The subprotocol agreed in the handshake.
+    pub fn protocol(&self) -> &str {
+        &self.protocol
+    }
+
+    /// The underlying stream.
+    pub fn get_ref(&self) -> &S {
+        &self.stream
+    }
+
+    /// Send a binary message.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the stream fails or the connection is closing.
+    pub fn send_binary(&mut self, data: &[u8]) -> io::Result<()> {
+        let frame = self.framing.binary(data)?;
+        self.write_frame(&frame)
+    }
+
+    /// Receive the next binary message.
+    ///
+    /// Pings are answered and text messages and pongs skipped on the way.
+    ///
+    /// # Errors
+    ///
+    /// Returns the stream's `WouldBlock` or `TimedOut` error when no complete
+    /// message has arrived, `ConnectionAborted` once the peer closes the
+    /// connection, and `InvalidData` for malformed frames.
+    pub fn receive(&mut self) -> io::Result<Vec<u8>> {
+        loop {
+            let mut replies = Vec::new();
+            let message = self.framing.take_message(&mut replies);
+            for reply in replies {
+                let sent = self.write_frame(&reply);
+                // The echo of a Close may find the stream gone
+                if message.is_ok() {
+                    sent?;
+                }
+            }
+            match message? {
+                Some(message) => return Ok(message),
+                None => self.fill()?,
+            }
+        }
+    }
+
+    /// Start the closing handshake.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the Close frame cannot be sent.
+    pub fn close(&mut self) -> io::Result<()> {
+        match self.framing.close() {
+            Some(frame) => self.write_frame(&frame),
+            None => Ok(()),
+        }
+    }
+
+    fn write_frame(&mut self, frame: &[u8]) -> io::Result<()> {
+        self.stream.write_all(frame)?;
+        self.stream.flush()
+    }
+
+    /// Read whatever the stream has into the buffer.
+    fn fill(&mut self) -> io::Result<()> {
+        let mut chunk = [0u8; 4096];
+        match self.stream.read(&mut chunk)? {
+            0 => Err(stream_ended()),
+            len => {
+                self.framing.extend(&chunk[..len]);
+                Ok(())
+            }
+        }
+    }
+
+    /// Read an HTTP request or response head, leaving any bytes after it
+    /// buffered as frame data.
+    fn read_http_head(&mut self, timeout: Duration) -> io::Result<String> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Some(head) = self.framing.take_http_head()? {
+                return Ok(head);
+            }
+            match self.fill() {
+                Ok(()) => {}
+                Err(e)
+                    if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut)
+                        && Instant::now() < deadline => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+/// The framing of one end of a WebSocket connection, without the I/O.
+///
+/// Received bytes go in with [`extend`](Self::extend) and come out as
+/// messages; frames to send come out as bytes for the caller to write.
+/// [`WebSocket`] drives it on a blocking stream, and the async BACnet/SC
+/// link on a Tokio one.
+#[derive(Debug)]
+pub(crate) struct Framing {
+    /// Bytes received but not yet parsed into frames.
+    buffer: Vec<u8>,
+
+    /// Payload of a fragmented message received so far.
+    fragments: Vec<u8>,
+
+    /// Whether this side opened the connection, and so masks its frames.
+    client: bool,
+
+    /// Whether a Close frame has been sent.
+    closed: bool,
+}
+
+impl Framing {
+    pub(crate) fn new(client: bool) -> Self {
+        Self {
+            buffer: Vec::new(),
+            fragments: Vec::new(),
+            client,
+            closed: false,
+        }
+    }
+
+    /// Add bytes received from the stream.
+    pub(crate) fn extend(&mut self, data: &[u8]) {
+        self.buffer.extend_from_slice(data);
+    }
+
+    /// The frame carrying a binary message.
+    pub(crate) fn binary(&self, data: &[u8]) -> io::Result<Vec<u8>> {
+        if self.closed {
+            return Err(io::Error::new(ErrorKind::NotConnected, "WebSocket closed"));
+        }
+        Ok(self.frame(OPCODE_BINARY, data))
+    }
+
+    /// The Close frame starting the closing handshake, unless one was sent.
+    pub(crate) fn close(&mut self) -> Option<Vec<u8>> {
+        if self.closed {
+            return None;
+        }
+        self.closed = true;
+        // Status 1000: normal closure
+        Some(self.frame(OPCODE_CLOSE, &1000u16.to_be_bytes()))
+    }
+
+    /// Take the next binary message from the bytes received, `None` when it
+    /// has not fully arrived.
+    ///
+    /// Answers to pings and closes go in `replies`, to be sent in order.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConnectionAborted` once the peer closes the connection, and
+    /// `InvalidData` for malformed frames.
+    pub(crate) fn take_message(
+        &mut self,
+        replies: &mut Vec<Vec<u8>>,
+    ) -> io::Result<Option<Vec<u8>>> {
+        while let Some((fin, opcode, payload)) = self.parse_frame()? {
+            match opcode {
+                OPCODE_PING => replies.push(self.frame(OPCODE_PONG, &payload)),
+                OPCODE_PONG => {}
+                OPCODE_CLOSE => {
+                    if !self.closed {
+                        replies.push(self.frame(OPCODE_CLOSE, &payload));
+                        self.closed = true;
+                    }
+                    return Err(io::Error::new(
+                        ErrorKind::ConnectionAborted,
+                        "WebSocket closed by peer",
+                    ));
+                }
+                OPCODE_BINARY | OPCODE_TEXT | OPCODE_CONTINUATION => {
+                    if opcode != OPCODE_CONTINUATION {
+                        self.fragments.clear();
+                    }
+                    self.fragments.extend_from_slice(&payload);
+                    if self.fragments.len() > MAX_MESSAGE_LENGTH {
+                        return Err(invalid_data("WebSocket message too long".to_string()));
+                    }
+                    // BACnet/SC only uses binary messages; text ones are dropped
+                    if fin {
+                        let message = core::mem::take(&mut self.fragments);
+                        if opcode != OPCODE_TEXT {
+                            return Ok(Some(message));
+                        }
+                    }
+                }
+                _ => return Err(invalid_data(format!("Unknown opcode {:#x}", opcode))),
+            }
+        }
+        Ok(None)
+    }
+
+    /// Take an HTTP request or response head from the bytes received, `None`
+    /// when it has not fully arrived; bytes after it stay as frame data.
+    pub(crate) fn take_http_head(&mut self) -> io::Result<Option<String>> {
+        if let Some(end) = self.buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+            let head: Vec<u8> = self.buffer.drain(..end + 4).collect();
+            return String::from_utf8(head)
+                .map(Some)
+                .map_err(|_| invalid_data("HTTP header is not UTF-8".to_string()));
+        }
+        if self.buffer.len() > 8192 {
+            return Err(invalid_data("HTTP header too long".to_string()));
+        }
+        Ok(None)
+    }
+
+    fn frame(&self, opcode: u8, payload: &[u8]) -> Vec<u8> {
+        let mut frame = vec![0x80 | opcode];
+        let mask_bit = if self.client { 0x80 } else { 0x00 };
+        match payload.len() {
+            len @ 0..=125 => frame.push(mask_bit | len as u8),
+            len @ 126..=0xFFFF => {
+                frame.push(mask_bit | 126);
+                frame.extend_from_slice(&(len as u16).to_be_bytes());
+            }
+            len => {
+                frame.push(mask_bit | 127);
+                frame.extend_from_slice(&(len as u64).to_be_bytes());
+            }
+        }
+        if self.client {
+            let mut mask = [0u8; 4];
+            random_bytes(&mut mask);
+            frame.extend_from_slice(&mask);
+            frame.extend(payload.iter().enumerate().map(|(i, b)| b ^ mask[i % 4]));
+        } else {
+            frame.extend_from_slice(payload);
+        }
+        frame
+    }
+
+    /// Take one complete frame from the buffer, if there is one.
+    fn parse_frame(&mut self) -> io::Result<Option<(bool, u8, Vec<u8>)>> {
+        let data = &self.buffer;
+        if data.len() < 2 {
+            return Ok(None);
+        }
+        let fin = data[0] & 0x80 != 0;
+        let opcode = data[0] & 0x0F;
+        let masked = data[1] & 0x80 != 0;
+        let (length, mut offset) = match data[1] & 0x7F {
+            126 if data.len() >= 4 => (u16::from_be_bytes([data[2], data[3]]) as usize, 4),
+            127 if data.len() >= 10 => {
+                let mut length = [0u8; 8];
+                length.copy_from_slice(&data[2..10]);
+                (u64::from_be_bytes(length) as usize, 10)
+            }
+            126 | 127 => return Ok(None),
+            length => (length as usize, 2),
+        };
+        if length > MAX_MESSAGE_LENGTH {
+            return Err(invalid_data("WebSocket frame too long".to_string()));
+        }
+        let mask = if masked {
+            if data.len() < offset + 4 {
+                return Ok(None);
+            }
+            let mask = [
+                data[offset],
+                data[offset + 1],
+                data[offset + 2],
+                data[offset + 3],
+            ];
+            offset += 4;
+            Some(mask)
+        } else {
+            None
+        };
+        if data.len() < offset + length {
+            return Ok(None);
+        }
+
+        let mut payload: Vec<u8> = self.buffer.drain(..offset + length).skip(offset).collect();
+        if let Some(mask) = mask {
+            for (i, byte) in payload.iter_mut().enumerate() {
+                *byte ^= mask[i % 4];
+            }
+        }
+        Ok(Some((fin, opcode, payload)))
+    }
+}
+
+/// The client's opening handshake asking for `protocol`, with the key the
+/// answer must match.
+pub(crate) fn handshake_request(host: &str, path: &str, protocol: &str) -> (String, String) {
+    let mut key = [0u8; 16];
+    random_bytes(&mut key);
+    let key = base64(&key);
+    let request = format!(
+        "GET {} HTTP/1.1\r\nHost: {}\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+         Sec-WebSocket-Key: {}\r\nSec-WebSocket-Version: 13\r\n\
+         Sec-WebSocket-Protocol: {}\r\n\r\n",
+        path, host, key, protocol
+    );
+    (request, key)
+}
+
+/// Check the server's answer to a handshake sent with `key`.
+pub(crate) fn check_handshake_response(
+    response: &str,
+    key: &str,
+    protocol: &str,
+) -> io::Result<()> {
+    let mut lines = response.lines();
+    let status = lines.next().unwrap_or_default();
+    if status.split_whitespace().nth(1) != Some("101") {
+        return Err(invalid_data(format!(
+            "WebSocket upgrade refused: {}",
+            status
+        )));
+    }
+    let headers: Vec<(String, String)> = lines.filter_map(parse_header).collect();
+    if header(&headers, "sec-websocket-accept") != Some(accept_key(key).as_str()) {
+        return Err(invalid_data("Invalid Sec-WebSocket-Accept".to_string()));
+    }
+    if header(&headers, "sec-websocket-protocol") != Some(protocol) {
+        return Err(invalid_data(format!(
+            "Server did not accept subprotocol {}",
+            protocol
+        )));
+    }
+    Ok(())
+}
+
+/// The server's answer to a client's opening handshake, with the
+/// subprotocol agreed, or the HTTP refusal to send with the error.
+fn handshake_answer(
+    request: &str,
+    protocols: &[&str],
+) -> core::result::Result<(String, String), (String, io::Error)> {
+    let refusal = "HTTP/1.1 400 Bad Request\r\nContent-Length: 0\r\n\r\n".to_string();
+    let mut lines = request.lines();
+    let request_line = lines.next().unwrap_or_default();
+    let headers: Vec<(String, String)> = lines.filter_map(parse_header).collect();
+
+    let key = header(&headers, "sec-websocket-key");
+    let upgrade =
+        header(&headers, "upgrade").is_some_and(|value| value.eq_ignore_ascii_case("websocket"));
+    let (Some(key), true, true) = (key, upgrade, request_line.starts_with("GET ")) else {
+        return Err((
+            refusal,
+            invalid_data("Not a WebSocket upgrade request".to_string()),
+        ));
+    };
+    let Some(protocol) = header(&headers, "sec-websocket-protocol")
+        .into_iter()
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .find(|requested| protocols.contains(requested))
+        .map(str::to_string)
+    else {
+        return Err((
+            refusal,
+            invalid_data("No supported WebSocket subprotocol".to_string()),
+        ));
+    };
+
+    let response = format!(
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+         Sec-WebSocket-Accept: {}\r\nSec-WebSocket-Protocol: {}\r\n\r\n",
+        accept_key(key),
+        protocol
+    );
+    Ok((response, protocol))
+}
+
+pub(crate) fn stream_ended() -> io::Error {
+    io::Error::new(ErrorKind::UnexpectedEof, "WebSocket stream ended")
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, message)
+}
+
+fn parse_header(line: &str) -> Option<(String, String)> {
+    let (name, value) = line.split_once(':')?;
+    Some((name.trim().to_ascii_lowercase(), value.trim().to_string()))
+}
+
+fn header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(header, _)| header == name)
+        .map(|(_, value)| value.as_str())
+}
+
+/// Sec-WebSocket-Accept value for a client key.
+fn accept_key(key: &str) -> String {
+    base64(&sha1(format!("{}{}", key, ACCEPT_GUID).as_bytes()))
+}
+
+/// Fill `buffer` with unpredictable bytes.
+///
+/// Uses the randomly seeded std hasher, which is good enough for WebSocket
+/// masks and keys and for picking random VMACs and UUIDs.
+pub(crate) fn random_bytes(buffer: &mut [u8]) {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    for chunk in buffer.chunks_mut(8) {
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u64(COUNTER.fetch_add(1, Ordering::Relaxed));
+        let bytes = hasher.finish().to_be_bytes();
+        chunk.copy_from_slice(&bytes[..chunk.len()]);
+    }
+}
+
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::new();
+    for chunk in data.chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let bits = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(bits >> (18 - 6 * i) & 0x3F) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+fn sha1(data: &[u8]) -> [u8; 20] {
+    let mut state: [u32; 5] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((data.len() as u64) * 8).to_be_bytes());
+
+    for block in message.chunks(64) {
+        let mut w = [0u32; 80];
+        for (i, word) in block.chunks(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..80 {
+            w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e] = state;
+        for (i, word) in w.iter().enumerate() {
+            let (f, k) = match i {
+                0..=19 => ((b & c) | (!b & d), 0x5A827999),
+                20..=39 => (b ^ c ^ d, 0x6ED9EBA1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1BBCDC),
+                _ => (b ^ c ^ d, 0xCA62C1D6),
+            };
+            let temp = a
+                .rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(k)
+                .wrapping_add(*word);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = temp;
+        }
+        for (value, add) in state.iter_mut().zip([a, b, c, d, e]) {
+            *value = value.wrapping_add(add);
+        }
+    }
+
+    let mut digest = [0u8; 20];
+    for (chunk, value) in digest.chunks_mut(4).zip(state) {
+        chunk.copy_from_slice(&value.to_be_bytes());
+    }
+    digest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::{TcpListener, TcpStream};
+
+    #[test]
+    fn test_accept_key() {
+        // Example from RFC 6455 section 1.3
+        assert_eq!(
+            accept_key("dGhlIHNhbXBsZSBub25jZQ=="),
+            "s3pPLMBiTxaQ9kYGzzhZRbK+xOo="
+        );
+        assert_eq!(base64(b"ab"), "YWI=");
+
+        let uri: WebSocketUri = "ws://[fe80::1]:8080".parse().unwrap();
+        assert_eq!(uri.host, "fe80::1");
+        assert_eq!(uri.port, 8080);
+        assert_eq!(uri.to_string(), "ws://[fe80::1]:8080/");
+        assert!("https://hub.example.com".parse::<WebSocketUri>().is_err());
+    }
+
+    #[test]
+    fn test_handshake_and_framing() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut socket =
+                WebSocket::accept(stream, &["hub.bsc.bacnet.org"], Duration::from_secs(5)).unwrap();
+            let message = socket.receive().unwrap();
+            socket.send_binary(&message).unwrap();
+            assert_eq!(
+                socket.receive().unwrap_err().kind(),
+                ErrorKind::ConnectionAborted
+            );
+        });
+
+        let stream = TcpStream::connect(address).unwrap();
+        let mut socket = WebSocket::connect(
+            stream,
+            "localhost",
+            "/",
+            "hub.bsc.bacnet.org",
+            Duration::from_secs(5),
+        )
+        .unwrap();
+        assert_eq!(socket.protocol(), "hub.bsc.bacnet.org");
+
+        // Long enough for the 16-bit length form
+        let message: Vec<u8> = (0..300).map(|i| i as u8).collect();
+        socket.send_binary(&message).unwrap();
+        assert_eq!(socket.receive().unwrap(), message);
+        socket.close().unwrap();
+        server.join().unwrap();
+    }
+}
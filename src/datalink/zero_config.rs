@@ -0,0 +1,300 @@
+//! MS/TP automatic station addressing.
+//!
+//! A zero-configuration station ships without an address switch. It joins
+//! the line with no address, listens, and claims one of the addresses from
+//! [`ZERO_CONFIG_STATION_MIN`] to [`ZERO_CONFIG_STATION_MAX`] that no station
+//! uses:
+//!
+//! 1. While listening, any frame sent from the candidate address, or a token
+//!    passed to it, shows the address is taken and the next one becomes the
+//!    candidate.
+//! 2. The masters on the line poll the candidate for a new master. After
+//!    [`ZERO_CONFIG_NPOLL`] unanswered polls, plus a lottery of up to
+//!    [`ZERO_CONFIG_NSLOTS`] more drawn from the station's UUID so that
+//!    stations joining together do not claim at once, the station answers
+//!    the next poll with Reply-To-Poll-For-Master.
+//! 3. When the poller passes it the token, the station sends a Test-Request
+//!    carrying its UUID to the candidate address. A Test-Response means
+//!    another station holds the address after all; the station goes back to
+//!    listening with the next candidate. Silence confirms the claim, and the
+//!    station uses the token as an ordinary master.
+//!
+//! A line that stays silent has no master to poll the candidate. The station
+//! then confirms the candidate with a Test-Request of its own and, once
+//! confirmed, generates the token as any master would.
+//!
+//! [`MasterNode`] runs the procedure when [`MstpConfig::zero_config`] holds a
+//! UUID; this module holds its state.
+//!
+//! [`MasterNode`]: crate::datalink::mstp::MasterNode
+//! [`MstpConfig::zero_config`]: crate::datalink::mstp::MstpConfig::zero_config
+
+use std::time::{Duration, Instant};
+
+use crate::datalink::mstp::{MstpConfig, MstpFrame, MstpFrameType};
+
+/// Lowest address a zero-configuration station claims.
+pub const ZERO_CONFIG_STATION_MIN: u8 = 64;
+
+/// Highest address a zero-configuration station claims.
+pub const ZERO_CONFIG_STATION_MAX: u8 = 127;
+
+/// Unanswered polls of the candidate address seen before claiming it.
+pub const ZERO_CONFIG_NPOLL: u8 = 8;
+
+/// Number of lottery slots; each adds one poll to wait.
+pub const ZERO_CONFIG_NSLOTS: u8 = 8;
+
+/// Silence after which the line is taken to have no master.
+pub const ZERO_CONFIG_SILENCE: Duration = Duration::from_secs(2);
+
+/// Extra silence waited per lottery slot on a line with no master.
+const SILENCE_SLOT: Duration = Duration::from_millis(100);
+
+/// State of the addressing procedure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZeroConfigState {
+    /// Listening for polls of the candidate address.
+    Idle,
+    /// Answered a poll; waiting for the token.
+    Claim,
+    /// Sent the Test-Request; waiting for a response.
+    Confirm,
+    /// The candidate address is this station's.
+    Use,
+}
+
+/// The automatic addressing procedure of one station.
+#[derive(Debug, Clone)]
+pub struct ZeroConfig {
+    /// UUID that tells this station's Test-Request from another's.
+    uuid: [u8; 16],
+
+    /// Address being claimed.
+    station: u8,
+
+    /// Current state.
+    state: ZeroConfigState,
+
+    /// Unanswered polls of the candidate seen.
+    polls: u8,
+
+    /// Extra polls to wait, drawn from the UUID.
+    lottery: u8,
+
+    /// Whether the claim came with the token.
+    token: bool,
+
+    /// When the last frame arrived.
+    last_frame: Instant,
+
+    /// When the claim or the Test-Request was sent.
+    since: Instant,
+
+    /// Token wait after a claim, Tno_token.
+    token_timeout: Duration,
+
+    /// Test-Response wait, Treply_timeout.
+    reply_timeout: Duration,
+}
+
+impl ZeroConfig {
+    /// Start the procedure for a station with `config`, whose station
+    /// address is the one tried first when it lies in the claimable range.
+    pub fn new(uuid: [u8; 16], config: &MstpConfig, now: Instant) -> Self {
+        let preferred = config.station_address;
+        let station = if (ZERO_CONFIG_STATION_MIN..=ZERO_CONFIG_STATION_MAX).contains(&preferred) {
+            preferred
+        } else {
+            ZERO_CONFIG_STATION_MIN
+        };
+        Self {
+            uuid,
+            station,
+            state: ZeroConfigState::Idle,
+            polls: 0,
+            lottery: uuid.iter().fold(0, |lottery, octet| lottery ^ octet) % ZERO_CONFIG_NSLOTS,
+            token: false,
+            last_frame: now,
+            since: now,
+            token_timeout: Duration::from_millis(config.token_timeout),
+            reply_timeout: Duration::from_millis(config.reply_timeout),
+        }
+    }
+
+    /// The address being claimed, or claimed once the state is
+    /// [`Use`](ZeroConfigState::Use).
+    pub fn station(&self) -> u8 {
+        self.station
+    }
+
+    /// The current state.
+    pub fn state(&self) -> ZeroConfigState {
+        self.state
+    }
+
+    /// The station's UUID.
+    pub fn uuid(&self) -> [u8; 16] {
+        self.uuid
+    }
+
+    /// Whether the station holds the token it was passed while claiming.
+    pub fn holds_token(&self) -> bool {
+        self.token
+    }
+
+    /// Take a frame from the line, returning the frame to transmit, if any.
+    pub fn receive(&mut self, frame: &MstpFrame, now: Instant) -> Option<MstpFrame> {
+        self.last_frame = now;
+        let taken = frame.source == self.station
+            || (frame.destination == self.station && frame.frame_type == MstpFrameType::Token);
+        match self.state {
+            ZeroConfigState::Idle if taken => self.next_candidate(now),
+            ZeroConfigState::Idle
+                if frame.frame_type == MstpFrameType::PollForMaster
+                    && frame.destination == self.station =>
+            {
+                self.polls = self.polls.saturating_add(1);
+                if self.polls > ZERO_CONFIG_NPOLL + self.lottery {
+                    self.state = ZeroConfigState::Claim;
+                    self.since = now;
+                    return MstpFrame::new(
+                        MstpFrameType::ReplyToPollForMaster,
+                        frame.source,
+                        self.station,
+                        Vec::new(),
+                    )
+                    .ok();
+                }
+            }
+            ZeroConfigState::Claim
+                if frame.frame_type == MstpFrameType::Token
+                    && frame.destination == self.station =>
+            {
+                self.token = true;
+                return self.confirm(now);
+            }
+            ZeroConfigState::Claim | ZeroConfigState::Confirm if frame.source == self.station => {
+                // Another station answered for the address
+                self.next_candidate(now);
+            }
+            _ => {}
+        }
+        None
+    }
+
+    /// Apply the timeouts due at `now`, returning the frame to transmit, if
+    /// any.
+    pub fn poll(&mut self, now: Instant) -> Option<MstpFrame> {
+        let silence = now.saturating_duration_since(self.last_frame);
+        let waited = now.saturating_duration_since(self.since);
+        match self.state {
+            ZeroConfigState::Idle
+                if silence >= ZERO_CONFIG_SILENCE + SILENCE_SLOT * self.lottery as u32 =>
+            {
+                return self.confirm(now);
+            }
+            ZeroConfigState::Claim if waited >= self.token_timeout => {
+                // The poller never passed the token
+                self.state = ZeroConfigState::Idle;
+                self.polls = 0;
+            }
+            ZeroConfigState::Confirm if waited >= self.reply_timeout => {
+                self.state = ZeroConfigState::Use;
+            }
+            _ => {}
+        }
+        None
+    }
+
+    /// Send the Test-Request that checks no one else holds the candidate
+    fn confirm(&mut self, now: Instant) -> Option<MstpFrame> {
+        self.state = ZeroConfigState::Confirm;
+        self.since = now;
+        MstpFrame::new(
+            MstpFrameType::TestRequest,
+            self.station,
+            self.station,
+            self.uuid.to_vec(),
+        )
+        .ok()
+    }
+
+    fn next_candidate(&mut self, now: Instant) {
+        self.station = if self.station >= ZERO_CONFIG_STATION_MAX {
+            ZERO_CONFIG_STATION_MIN
+        } else {
+            self.station + 1
+        };
+        self.state = ZeroConfigState::Idle;
+        self.polls = 0;
+        self.token = false;
+        self.last_frame = now;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> MstpConfig {
+        MstpConfig {
+            station_address: 70,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_claim_after_polls() {
+        let start = Instant::now();
+        let mut zero_config = ZeroConfig::new([0; 16], &config(), start);
+        assert_eq!(zero_config.station(), 70);
+
+        // Address 70 answers a poll, so 71 is tried
+        let reply = MstpFrame::new(MstpFrameType::ReplyToPollForMaster, 3, 70, Vec::new());
+        zero_config.receive(&reply.unwrap(), start);
+        assert_eq!(zero_config.station(), 71);
+
+        let poll = MstpFrame::new(MstpFrameType::PollForMaster, 71, 3, Vec::new()).unwrap();
+        for _ in 0..ZERO_CONFIG_NPOLL {
+            assert!(zero_config.receive(&poll, start).is_none());
+        }
+        let reply = zero_config.receive(&poll, start).unwrap();
+        assert_eq!(reply.frame_type, MstpFrameType::ReplyToPollForMaster);
+        assert_eq!((reply.destination, reply.source), (3, 71));
+
+        let test = zero_config
+            .receive(&MstpFrame::token(71, 3).unwrap(), start)
+            .unwrap();
+        assert_eq!(test.frame_type, MstpFrameType::TestRequest);
+        assert_eq!(test.data, [0; 16]);
+        assert!(zero_config
+            .poll(start + Duration::from_millis(100))
+            .is_none());
+        assert_eq!(zero_config.state(), ZeroConfigState::Confirm);
+        zero_config.poll(start + Duration::from_millis(300));
+        assert_eq!(zero_config.state(), ZeroConfigState::Use);
+        assert!(zero_config.holds_token());
+    }
+
+    #[test]
+    fn test_collision_and_silent_line() {
+        let start = Instant::now();
+        let mut uuid = [0; 16];
+        uuid[0] = 3;
+        let mut zero_config = ZeroConfig::new(uuid, &MstpConfig::default(), start);
+        assert_eq!(zero_config.station(), ZERO_CONFIG_STATION_MIN);
+
+        // Nothing on the line: the candidate is tested after the lottery
+        let silence = ZERO_CONFIG_SILENCE + SILENCE_SLOT * 3;
+        assert!(zero_config.poll(start + silence / 2).is_none());
+        let test = zero_config.poll(start + silence).unwrap();
+        assert_eq!(test.destination, 64);
+
+        // The holder of address 64 responds
+        let response = MstpFrame::new(MstpFrameType::TestResponse, 64, 64, uuid.to_vec()).unwrap();
+        zero_config.receive(&response, start + silence);
+        assert_eq!(zero_config.state(), ZeroConfigState::Idle);
+        assert_eq!(zero_config.station(), 65);
+    }
+}
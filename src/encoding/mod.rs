@@ -557,8 +557,15 @@ pub fn decode_enumerated(data: &[u8]) -> Result<(u32, usize)> {
 
 /// Encode a BACnet date
 pub fn encode_date(buffer: &mut Vec<u8>, year: u16, month: u8, day: u8, weekday: u8) -> Result<()> {
+    // The year is an offset from 1900; an offset of 255 is unspecified, so
+    // 2155 and later cannot be represented
+    let year = match year {
+        255 => 255,
+        1900..=2154 => (year - 1900) as u8,
+        _ => return Err(EncodingError::ValueOutOfRange),
+    };
     encode_application_tag(buffer, ApplicationTag::Date, 4)?;
-    buffer.push(((year - 1900) % 256) as u8);
+    buffer.push(year);
     buffer.push(month);
     buffer.push(day);
     buffer.push(weekday);
@@ -855,6 +862,61 @@ pub fn decode_context_enumerated(data: &[u8], expected_tag: u8) -> Result<(u32,
     decode_context_unsigned(data, expected_tag)
 }
 
+/// Encode a context-specific boolean
+pub fn encode_context_boolean(value: bool, tag_number: u8) -> Result<Vec<u8>> {
+    // Unlike the application tag, a context tagged boolean carries its value
+    // in a single content octet
+    let mut buffer = Vec::new();
+    encode_context_tag(&mut buffer, tag_number, 1)?;
+    buffer.push(u8::from(value));
+    Ok(buffer)
+}
+
+/// Decode a context-specific boolean
+pub fn decode_context_boolean(data: &[u8], expected_tag: u8) -> Result<(bool, usize)> {
+    let (tag_number, length, tag_consumed) = decode_context_tag(data)?;
+
+    if tag_number != expected_tag {
+        return Err(EncodingError::InvalidTag);
+    }
+
+    if length != 1 {
+        return Err(EncodingError::InvalidLength);
+    }
+
+    let value = *data
+        .get(tag_consumed)
+        .ok_or(EncodingError::BufferUnderflow)?;
+    Ok((value != 0, tag_consumed + 1))
+}
+
+/// Encode a context-specific real (float) value
+pub fn encode_context_real(value: f32, tag_number: u8) -> Result<Vec<u8>> {
+    let mut buffer = Vec::new();
+    encode_context_tag(&mut buffer, tag_number, 4)?;
+    buffer.extend_from_slice(&value.to_be_bytes());
+    Ok(buffer)
+}
+
+/// Decode a context-specific real (float) value
+pub fn decode_context_real(data: &[u8], expected_tag: u8) -> Result<(f32, usize)> {
+    let (tag_number, length, tag_consumed) = decode_context_tag(data)?;
+
+    if tag_number != expected_tag {
+        return Err(EncodingError::InvalidTag);
+    }
+
+    if length != 4 {
+        return Err(EncodingError::InvalidLength);
+    }
+
+    let bytes = data
+        .get(tag_consumed..tag_consumed + 4)
+        .ok_or(EncodingError::BufferUnderflow)?;
+    let value = f32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    Ok((value, tag_consumed + 4))
+}
+
 /// Encode a context-specific object identifier
 pub fn encode_context_object_id(
     object_type: u16,
@@ -1083,20 +1145,24 @@ pub mod advanced {
         }
 
         /// Encode opening tag for constructed data
+        ///
+        /// Tag numbers above 14 use the extended form, with the number in a
+        /// second octet.
         pub fn encode_opening_tag(buffer: &mut Vec<u8>, tag_number: u8) -> Result<()> {
-            if tag_number > 14 {
-                return Err(EncodingError::ValueOutOfRange);
-            }
-            buffer.push(0x0E | (tag_number << 4));
-            Ok(())
+            encode_constructed_tag(buffer, tag_number, 0x0E)
         }
 
         /// Encode closing tag for constructed data
         pub fn encode_closing_tag(buffer: &mut Vec<u8>, tag_number: u8) -> Result<()> {
-            if tag_number > 14 {
-                return Err(EncodingError::ValueOutOfRange);
+            encode_constructed_tag(buffer, tag_number, 0x0F)
+        }
+
+        fn encode_constructed_tag(buffer: &mut Vec<u8>, tag_number: u8, kind: u8) -> Result<()> {
+            match tag_number {
+                0..=14 => buffer.push(kind | (tag_number << 4)),
+                255 => return Err(EncodingError::ValueOutOfRange),
+                _ => buffer.extend_from_slice(&[0xF0 | kind, tag_number]),
             }
-            buffer.push(0x0F | (tag_number << 4));
             Ok(())
         }
     }
@@ -1292,19 +1358,15 @@ pub mod advanced {
                     pos += consumed;
 
                     match tag {
-                        ApplicationTag::CharacterString => {
-                            if length > self.max_string_length {
-                                return Err(EncodingError::InvalidFormat(
-                                    "String too long".to_string(),
-                                ));
-                            }
+                        ApplicationTag::CharacterString if length > self.max_string_length => {
+                            return Err(EncodingError::InvalidFormat(
+                                "String too long".to_string(),
+                            ));
                         }
-                        ApplicationTag::OctetString => {
-                            if length > self.max_string_length * 2 {
-                                return Err(EncodingError::InvalidFormat(
-                                    "Octet string too long".to_string(),
-                                ));
-                            }
+                        ApplicationTag::OctetString if length > self.max_string_length * 2 => {
+                            return Err(EncodingError::InvalidFormat(
+                                "Octet string too long".to_string(),
+                            ));
                         }
                         _ => {}
                     }
@@ -1850,7 +1912,7 @@ impl EncodingAnalyzer {
             .iter()
             .map(|p| (&p.error_type, p.count))
             .collect();
-        errors.sort_by(|a, b| b.1.cmp(&a.1));
+        errors.sort_by_key(|e| core::cmp::Reverse(e.1));
         errors.truncate(limit);
         errors
     }
@@ -2253,6 +2315,20 @@ mod tests {
         assert_eq!(month, 3);
         assert_eq!(day, 15);
         assert_eq!(weekday, 5);
+
+        // Unspecified years encode as 255; years past 2154 are out of range
+        let mut buffer = Vec::new();
+        encode_date(&mut buffer, 255, 3, 15, 255).unwrap();
+        assert_eq!(decode_date(&buffer).unwrap().0, (255, 3, 15, 255));
+        encode_date(&mut buffer, 2154, 1, 1, 1).unwrap();
+        assert!(matches!(
+            encode_date(&mut buffer, 2155, 1, 1, 1),
+            Err(EncodingError::ValueOutOfRange)
+        ));
+        assert!(matches!(
+            encode_date(&mut buffer, 1899, 1, 1, 1),
+            Err(EncodingError::ValueOutOfRange)
+        ));
     }
 
     #[test]
@@ -2334,6 +2410,16 @@ mod tests {
         assert_eq!(tag_number, 5);
         assert_eq!(length, 10);
         assert_eq!(consumed, 2);
+
+        // Context tagged booleans carry a content octet
+        let encoded = encode_context_boolean(true, 2).unwrap();
+        assert_eq!(encoded, vec![0x29, 0x01]);
+        assert_eq!(decode_context_boolean(&encoded, 2).unwrap(), (true, 2));
+        assert!(decode_context_boolean(&encoded, 3).is_err());
+
+        let encoded = encode_context_real(0.5, 5).unwrap();
+        assert_eq!(encoded, vec![0x5C, 0x3F, 0x00, 0x00, 0x00]);
+        assert_eq!(decode_context_real(&encoded, 5).unwrap(), (0.5, 5));
     }
 
     #[test]
@@ -127,6 +127,10 @@ pub mod vendor;
 #[cfg(feature = "std")]
 pub mod client;
 
+/// Asynchronous client and server over Tokio data links (requires async feature)
+#[cfg(feature = "async")]
+pub mod async_client;
+
 /// Property value decoders for various BACnet data types
 pub mod property;
 
@@ -164,7 +168,7 @@ mod tests {
         assert_eq!(tag as u8, 1);
 
         let obj_type = ObjectType::AnalogInput;
-        assert_eq!(obj_type as u16, 0);
+        assert_eq!(u16::from(obj_type), 0);
 
         let obj_id = ObjectIdentifier::new(ObjectType::Device, 123);
         assert_eq!(obj_id.instance, 123);
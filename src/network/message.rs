@@ -0,0 +1,461 @@
+//! Clause 6 network layer messages
+//!
+//! [`NetworkMessage`] is the decoded form of each network layer message, the
+//! part of an NPDU that follows the NPCI when the control octet marks it as a
+//! network layer message. It converts to and from the raw
+//! [`NetworkLayerMessage`] and, with an NPCI, to and from a complete NPDU.
+
+#[cfg(not(feature = "std"))]
+use alloc::{format, string::ToString, vec::Vec};
+
+use crate::network::{NetworkError, NetworkLayerMessage, NetworkMessageType, Npdu, Result};
+
+/// Reasons given by Reject-Message-To-Network
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum RejectReason {
+    /// Other error
+    Other = 0,
+    /// The network is not directly connected and no route to it is known
+    NoRoute = 1,
+    /// The router to the network is busy
+    RouterBusy = 2,
+    /// Unknown network layer message type
+    UnknownMessageType = 3,
+    /// The message is too long for the network
+    MessageTooLong = 4,
+    /// Security error
+    SecurityError = 5,
+    /// Invalid DADR, SADR or their lengths
+    AddressingError = 6,
+}
+
+impl RejectReason {
+    /// The reason for an octet, `Other` for unknown values
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            1 => RejectReason::NoRoute,
+            2 => RejectReason::RouterBusy,
+            3 => RejectReason::UnknownMessageType,
+            4 => RejectReason::MessageTooLong,
+            5 => RejectReason::SecurityError,
+            6 => RejectReason::AddressingError,
+            _ => RejectReason::Other,
+        }
+    }
+}
+
+/// One entry of Initialize-Routing-Table and its Ack
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoutingTableEntry {
+    /// The network reached
+    pub network: u16,
+    /// Port of the router the network is reached through, 0 to remove the
+    /// entry
+    pub port_id: u8,
+    /// Information for establishing a connection on the port, such as a
+    /// telephone number for PTP
+    pub port_info: Vec<u8>,
+}
+
+/// A decoded network layer message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NetworkMessage {
+    /// Find the router to a network, or all routers for `None`
+    WhoIsRouterToNetwork(Option<u16>),
+    /// The networks the sending router reaches
+    IAmRouterToNetwork(Vec<u16>),
+    /// A half-router that could connect to a network
+    ICouldBeRouterToNetwork {
+        network: u16,
+        /// Lower is better
+        performance_index: u8,
+    },
+    /// An NPDU for `network` could not be routed
+    RejectMessageToNetwork { reason: RejectReason, network: u16 },
+    /// Stop sending to these networks, or all networks if empty, through the
+    /// sending router
+    RouterBusyToNetwork(Vec<u16>),
+    /// These networks, or all networks if empty, are reachable through the
+    /// sending router again
+    RouterAvailableToNetwork(Vec<u16>),
+    /// Update the routing table, or query it if empty
+    InitializeRoutingTable(Vec<RoutingTableEntry>),
+    /// Answer to Initialize-Routing-Table: the routing table for a query,
+    /// empty for an update
+    InitializeRoutingTableAck(Vec<RoutingTableEntry>),
+    /// Connect a half-router to `network`
+    EstablishConnectionToNetwork {
+        network: u16,
+        /// Minutes to stay connected without traffic, 0 for permanently
+        termination_time: u8,
+    },
+    /// Disconnect a half-router from a network
+    DisconnectConnectionToNetwork(u16),
+    /// Ask for the network number of the local network
+    WhatIsNetworkNumber,
+    /// The network number of the local network
+    NetworkNumberIs {
+        network: u16,
+        /// Whether the number is configured rather than learned
+        configured: bool,
+    },
+    /// A vendor proprietary message
+    Proprietary {
+        message_type: u8,
+        vendor_id: u16,
+        data: Vec<u8>,
+    },
+}
+
+impl NetworkMessage {
+    /// The message type
+    pub fn message_type(&self) -> NetworkMessageType {
+        match self {
+            NetworkMessage::WhoIsRouterToNetwork(_) => NetworkMessageType::WhoIsRouterToNetwork,
+            NetworkMessage::IAmRouterToNetwork(_) => NetworkMessageType::IAmRouterToNetwork,
+            NetworkMessage::ICouldBeRouterToNetwork { .. } => {
+                NetworkMessageType::ICouldBeRouterToNetwork
+            }
+            NetworkMessage::RejectMessageToNetwork { .. } => {
+                NetworkMessageType::RejectMessageToNetwork
+            }
+            NetworkMessage::RouterBusyToNetwork(_) => NetworkMessageType::RouterBusyToNetwork,
+            NetworkMessage::RouterAvailableToNetwork(_) => {
+                NetworkMessageType::RouterAvailableToNetwork
+            }
+            NetworkMessage::InitializeRoutingTable(_) => NetworkMessageType::InitializeRoutingTable,
+            NetworkMessage::InitializeRoutingTableAck(_) => {
+                NetworkMessageType::InitializeRoutingTableAck
+            }
+            NetworkMessage::EstablishConnectionToNetwork { .. } => {
+                NetworkMessageType::EstablishConnectionToNetwork
+            }
+            NetworkMessage::DisconnectConnectionToNetwork(_) => {
+                NetworkMessageType::DisconnectConnectionToNetwork
+            }
+            NetworkMessage::WhatIsNetworkNumber => NetworkMessageType::WhatIsNetworkNumber,
+            NetworkMessage::NetworkNumberIs { .. } => NetworkMessageType::NetworkNumberIs,
+            NetworkMessage::Proprietary { message_type, .. } => {
+                NetworkMessageType::Proprietary(*message_type)
+            }
+        }
+    }
+
+    /// The raw message
+    pub fn to_layer_message(&self) -> NetworkLayerMessage {
+        let mut data = Vec::new();
+        match self {
+            NetworkMessage::WhoIsRouterToNetwork(network) => {
+                if let Some(network) = network {
+                    data.extend_from_slice(&network.to_be_bytes());
+                }
+            }
+            NetworkMessage::IAmRouterToNetwork(networks)
+            | NetworkMessage::RouterBusyToNetwork(networks)
+            | NetworkMessage::RouterAvailableToNetwork(networks) => {
+                for network in networks {
+                    data.extend_from_slice(&network.to_be_bytes());
+                }
+            }
+            NetworkMessage::ICouldBeRouterToNetwork {
+                network,
+                performance_index,
+            } => {
+                data.extend_from_slice(&network.to_be_bytes());
+                data.push(*performance_index);
+            }
+            NetworkMessage::RejectMessageToNetwork { reason, network } => {
+                data.push(*reason as u8);
+                data.extend_from_slice(&network.to_be_bytes());
+            }
+            NetworkMessage::InitializeRoutingTable(entries)
+            | NetworkMessage::InitializeRoutingTableAck(entries) => {
+                data.push(entries.len() as u8);
+                for entry in entries {
+                    data.extend_from_slice(&entry.network.to_be_bytes());
+                    data.push(entry.port_id);
+                    data.push(entry.port_info.len() as u8);
+                    data.extend_from_slice(&entry.port_info);
+                }
+            }
+            NetworkMessage::EstablishConnectionToNetwork {
+                network,
+                termination_time,
+            } => {
+                data.extend_from_slice(&network.to_be_bytes());
+                data.push(*termination_time);
+            }
+            NetworkMessage::DisconnectConnectionToNetwork(network) => {
+                data.extend_from_slice(&network.to_be_bytes());
+            }
+            NetworkMessage::WhatIsNetworkNumber => {}
+            NetworkMessage::NetworkNumberIs {
+                network,
+                configured,
+            } => {
+                data.extend_from_slice(&network.to_be_bytes());
+                data.push(*configured as u8);
+            }
+            NetworkMessage::Proprietary {
+                message_type,
+                vendor_id,
+                data: payload,
+            } => {
+                return NetworkLayerMessage::proprietary(*message_type, *vendor_id, payload.clone())
+            }
+        }
+        NetworkLayerMessage::new(self.message_type(), data)
+    }
+
+    /// Decode a raw message
+    pub fn from_layer_message(message: &NetworkLayerMessage) -> Result<Self> {
+        let data = &message.data[..];
+        let message = match message.message_type {
+            NetworkMessageType::WhoIsRouterToNetwork => {
+                NetworkMessage::WhoIsRouterToNetwork(match data.len() {
+                    0 => None,
+                    _ => Some(network_at(data, 0)?),
+                })
+            }
+            NetworkMessageType::IAmRouterToNetwork => {
+                NetworkMessage::IAmRouterToNetwork(network_list(data)?)
+            }
+            NetworkMessageType::ICouldBeRouterToNetwork => {
+                NetworkMessage::ICouldBeRouterToNetwork {
+                    network: network_at(data, 0)?,
+                    performance_index: octet_at(data, 2)?,
+                }
+            }
+            NetworkMessageType::RejectMessageToNetwork => NetworkMessage::RejectMessageToNetwork {
+                reason: RejectReason::from_u8(octet_at(data, 0)?),
+                network: network_at(data, 1)?,
+            },
+            NetworkMessageType::RouterBusyToNetwork => {
+                NetworkMessage::RouterBusyToNetwork(network_list(data)?)
+            }
+            NetworkMessageType::RouterAvailableToNetwork => {
+                NetworkMessage::RouterAvailableToNetwork(network_list(data)?)
+            }
+            NetworkMessageType::InitializeRoutingTable => {
+                NetworkMessage::InitializeRoutingTable(routing_table(data)?)
+            }
+            NetworkMessageType::InitializeRoutingTableAck => {
+                NetworkMessage::InitializeRoutingTableAck(routing_table(data)?)
+            }
+            NetworkMessageType::EstablishConnectionToNetwork => {
+                NetworkMessage::EstablishConnectionToNetwork {
+                    network: network_at(data, 0)?,
+                    termination_time: octet_at(data, 2)?,
+                }
+            }
+            NetworkMessageType::DisconnectConnectionToNetwork => {
+                NetworkMessage::DisconnectConnectionToNetwork(network_at(data, 0)?)
+            }
+            NetworkMessageType::WhatIsNetworkNumber => NetworkMessage::WhatIsNetworkNumber,
+            NetworkMessageType::NetworkNumberIs => NetworkMessage::NetworkNumberIs {
+                network: network_at(data, 0)?,
+                configured: octet_at(data, 2)? == 1,
+            },
+            NetworkMessageType::Proprietary(message_type) => NetworkMessage::Proprietary {
+                message_type,
+                vendor_id: message.vendor_id.unwrap_or(0),
+                data: data.to_vec(),
+            },
+        };
+        Ok(message)
+    }
+
+    /// Encode the message, from the message type on
+    pub fn encode(&self) -> Vec<u8> {
+        self.to_layer_message().encode()
+    }
+
+    /// Decode a message, from the message type on
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        Self::from_layer_message(&NetworkLayerMessage::decode(data)?)
+    }
+
+    /// Encode a complete NPDU carrying this message, with `npdu` as the NPCI
+    pub fn encode_npdu(&self, npdu: &Npdu) -> Vec<u8> {
+        self.to_layer_message().encode_npdu(npdu)
+    }
+
+    /// Decode a complete NPDU carrying a network layer message
+    pub fn decode_npdu(data: &[u8]) -> Result<(Npdu, Self)> {
+        let (npdu, message) = NetworkLayerMessage::decode_npdu(data)?;
+        Ok((npdu, Self::from_layer_message(&message)?))
+    }
+}
+
+/// The network number announced by a Network-Number-Is on the local
+/// network, and whether it is configured, for a device learning the network
+/// number of its port
+///
+/// Routed Network-Number-Is messages, and anything else, give `None`.
+pub fn local_network_number(npdu: &[u8]) -> Option<(u16, bool)> {
+    match NetworkMessage::decode_npdu(npdu).ok()? {
+        (
+            npci,
+            NetworkMessage::NetworkNumberIs {
+                network,
+                configured,
+            },
+        ) if npci.source.is_none() && npci.destination.is_none() => Some((network, configured)),
+        _ => None,
+    }
+}
+
+fn truncated() -> NetworkError {
+    NetworkError::InvalidNpdu("Network message too short".to_string())
+}
+
+fn octet_at(data: &[u8], pos: usize) -> Result<u8> {
+    data.get(pos).copied().ok_or_else(truncated)
+}
+
+fn network_at(data: &[u8], pos: usize) -> Result<u16> {
+    match data.get(pos..pos + 2) {
+        Some(octets) => Ok(u16::from_be_bytes([octets[0], octets[1]])),
+        None => Err(truncated()),
+    }
+}
+
+fn network_list(data: &[u8]) -> Result<Vec<u16>> {
+    if !data.len().is_multiple_of(2) {
+        return Err(NetworkError::InvalidNpdu(format!(
+            "Odd length network list: {} octets",
+            data.len()
+        )));
+    }
+    Ok(data
+        .chunks_exact(2)
+        .map(|n| u16::from_be_bytes([n[0], n[1]]))
+        .collect())
+}
+
+fn routing_table(data: &[u8]) -> Result<Vec<RoutingTableEntry>> {
+    let count = octet_at(data, 0)?;
+    let mut pos = 1;
+    let mut entries = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        let network = network_at(data, pos)?;
+        let port_id = octet_at(data, pos + 2)?;
+        let length = octet_at(data, pos + 3)? as usize;
+        pos += 4;
+        let port_info = data.get(pos..pos + length).ok_or_else(truncated)?.to_vec();
+        pos += length;
+        entries.push(RoutingTableEntry {
+            network,
+            port_id,
+            port_info,
+        });
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_encodings() {
+        let cases = [
+            (NetworkMessage::WhoIsRouterToNetwork(None), vec![0x00]),
+            (
+                NetworkMessage::WhoIsRouterToNetwork(Some(3)),
+                vec![0x00, 0x00, 0x03],
+            ),
+            (
+                NetworkMessage::IAmRouterToNetwork(vec![1, 0x0203]),
+                vec![0x01, 0x00, 0x01, 0x02, 0x03],
+            ),
+            (
+                NetworkMessage::ICouldBeRouterToNetwork {
+                    network: 7,
+                    performance_index: 40,
+                },
+                vec![0x02, 0x00, 0x07, 40],
+            ),
+            (
+                NetworkMessage::RejectMessageToNetwork {
+                    reason: RejectReason::RouterBusy,
+                    network: 9,
+                },
+                vec![0x03, 0x02, 0x00, 0x09],
+            ),
+            (NetworkMessage::RouterBusyToNetwork(vec![]), vec![0x04]),
+            (
+                NetworkMessage::RouterAvailableToNetwork(vec![5]),
+                vec![0x05, 0x00, 0x05],
+            ),
+            (
+                NetworkMessage::InitializeRoutingTable(vec![RoutingTableEntry {
+                    network: 4,
+                    port_id: 2,
+                    port_info: vec![0x35, 0x35],
+                }]),
+                vec![0x06, 0x01, 0x00, 0x04, 0x02, 0x02, 0x35, 0x35],
+            ),
+            (
+                NetworkMessage::InitializeRoutingTableAck(vec![]),
+                vec![0x07, 0x00],
+            ),
+            (
+                NetworkMessage::EstablishConnectionToNetwork {
+                    network: 6,
+                    termination_time: 30,
+                },
+                vec![0x08, 0x00, 0x06, 30],
+            ),
+            (
+                NetworkMessage::DisconnectConnectionToNetwork(6),
+                vec![0x09, 0x00, 0x06],
+            ),
+            (NetworkMessage::WhatIsNetworkNumber, vec![0x12]),
+            (
+                NetworkMessage::NetworkNumberIs {
+                    network: 10,
+                    configured: true,
+                },
+                vec![0x13, 0x00, 0x0A, 0x01],
+            ),
+            (
+                NetworkMessage::Proprietary {
+                    message_type: 0x90,
+                    vendor_id: 260,
+                    data: vec![0xAA],
+                },
+                vec![0x90, 0x01, 0x04, 0xAA],
+            ),
+        ];
+        for (message, encoded) in cases {
+            assert_eq!(message.encode(), encoded, "{:?}", message);
+            assert_eq!(NetworkMessage::decode(&encoded).unwrap(), message);
+        }
+    }
+
+    #[test]
+    fn test_malformed_messages() {
+        // Truncated fields and lists
+        assert!(NetworkMessage::decode(&[0x00, 0x01]).is_err());
+        assert!(NetworkMessage::decode(&[0x01, 0x00, 0x01, 0x02]).is_err());
+        assert!(NetworkMessage::decode(&[0x02, 0x00, 0x07]).is_err());
+        assert!(NetworkMessage::decode(&[0x06, 0x02, 0x00, 0x04, 0x02, 0x00]).is_err());
+        assert!(NetworkMessage::decode(&[0x06, 0x01, 0x00, 0x04, 0x02, 0x03, 0x35]).is_err());
+        assert!(NetworkMessage::decode(&[0x13, 0x00, 0x0A]).is_err());
+
+        // Whole NPDUs
+        let npdu = NetworkMessage::WhatIsNetworkNumber.encode_npdu(&Npdu::network_message(None));
+        assert_eq!(npdu, [0x01, 0x80, 0x12]);
+        let (_, message) = NetworkMessage::decode_npdu(&npdu).unwrap();
+        assert_eq!(message, NetworkMessage::WhatIsNetworkNumber);
+        assert_eq!(local_network_number(&npdu), None);
+        let number_is = NetworkMessage::NetworkNumberIs {
+            network: 12,
+            configured: true,
+        };
+        let npdu = number_is.encode_npdu(&Npdu::network_message(None));
+        assert_eq!(local_network_number(&npdu), Some((12, true)));
+    }
+}
@@ -21,6 +21,14 @@
 //! - Destination network address (DNET, DADR)
 //! - Source network address (SNET, SADR)
 //! - Hop count for routing
+//! - For network layer messages, the message type and, for proprietary
+//!   message types, the vendor ID
+//!
+//! [`Npdu`] covers the NPCI up to the hop count and [`NetworkLayerMessage`]
+//! the message type, vendor ID and the message itself. On encoding, the
+//! destination and source present bits come from the addresses actually
+//! present; on decoding, reserved control bits and malformed addresses are
+//! rejected.
 //!
 //! # Example
 //!
@@ -37,6 +45,24 @@
 //! };
 //! ```
 
+/// Typed codecs for the Clause 6 network layer messages
+pub mod message;
+
+/// Several data links in one stack, with a dispatcher that tags each NPDU
+/// with the port it arrived on
+#[cfg(feature = "std")]
+pub mod port;
+
+/// Routing between the ports of a dispatcher, with a routing table learned
+/// from I-Am-Router-To-Network
+#[cfg(feature = "std")]
+pub mod router;
+
+/// Virtual networks of devices a gateway represents, routed to by the
+/// gateway's router
+#[cfg(feature = "std")]
+pub mod virtual_network;
+
 #[cfg(feature = "std")]
 use std::error::Error;
 
@@ -79,6 +105,10 @@ pub enum NetworkError {
     HopCountExceeded,
     /// Invalid network address
     InvalidAddress,
+    /// No port with this port ID is attached
+    UnknownPort(u8),
+    /// The data link of a port failed
+    DataLink(crate::datalink::DataLinkError),
 }
 
 impl fmt::Display for NetworkError {
@@ -89,6 +119,8 @@ impl fmt::Display for NetworkError {
             NetworkError::NetworkUnreachable(net) => write!(f, "Network {} unreachable", net),
             NetworkError::HopCountExceeded => write!(f, "Hop count exceeded"),
             NetworkError::InvalidAddress => write!(f, "Invalid network address"),
+            NetworkError::UnknownPort(port) => write!(f, "Unknown port {}", port),
+            NetworkError::DataLink(e) => write!(f, "Data link error: {}", e),
         }
     }
 }
@@ -98,24 +130,74 @@ impl Error for NetworkError {}
 
 /// Network layer message types
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-#[repr(u8)]
 pub enum NetworkMessageType {
-    WhoIsRouterToNetwork = 0x00,
-    IAmRouterToNetwork = 0x01,
-    ICouldBeRouterToNetwork = 0x02,
-    RejectMessageToNetwork = 0x03,
-    RouterBusyToNetwork = 0x04,
-    RouterAvailableToNetwork = 0x05,
-    InitializeRoutingTable = 0x06,
-    InitializeRoutingTableAck = 0x07,
-    EstablishConnectionToNetwork = 0x08,
-    DisconnectConnectionToNetwork = 0x09,
-    WhatIsNetworkNumber = 0x12,
-    NetworkNumberIs = 0x13,
+    WhoIsRouterToNetwork,
+    IAmRouterToNetwork,
+    ICouldBeRouterToNetwork,
+    RejectMessageToNetwork,
+    RouterBusyToNetwork,
+    RouterAvailableToNetwork,
+    InitializeRoutingTable,
+    InitializeRoutingTableAck,
+    EstablishConnectionToNetwork,
+    DisconnectConnectionToNetwork,
+    WhatIsNetworkNumber,
+    NetworkNumberIs,
+    /// Vendor proprietary message type (0x80-0xFF), followed by a vendor ID
+    Proprietary(u8),
+}
+
+impl NetworkMessageType {
+    /// The message type for an octet, `None` for reserved values
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0x00 => Some(NetworkMessageType::WhoIsRouterToNetwork),
+            0x01 => Some(NetworkMessageType::IAmRouterToNetwork),
+            0x02 => Some(NetworkMessageType::ICouldBeRouterToNetwork),
+            0x03 => Some(NetworkMessageType::RejectMessageToNetwork),
+            0x04 => Some(NetworkMessageType::RouterBusyToNetwork),
+            0x05 => Some(NetworkMessageType::RouterAvailableToNetwork),
+            0x06 => Some(NetworkMessageType::InitializeRoutingTable),
+            0x07 => Some(NetworkMessageType::InitializeRoutingTableAck),
+            0x08 => Some(NetworkMessageType::EstablishConnectionToNetwork),
+            0x09 => Some(NetworkMessageType::DisconnectConnectionToNetwork),
+            0x12 => Some(NetworkMessageType::WhatIsNetworkNumber),
+            0x13 => Some(NetworkMessageType::NetworkNumberIs),
+            0x80..=0xFF => Some(NetworkMessageType::Proprietary(value)),
+            _ => None,
+        }
+    }
+
+    /// The octet encoding this message type
+    pub fn to_u8(self) -> u8 {
+        match self {
+            NetworkMessageType::WhoIsRouterToNetwork => 0x00,
+            NetworkMessageType::IAmRouterToNetwork => 0x01,
+            NetworkMessageType::ICouldBeRouterToNetwork => 0x02,
+            NetworkMessageType::RejectMessageToNetwork => 0x03,
+            NetworkMessageType::RouterBusyToNetwork => 0x04,
+            NetworkMessageType::RouterAvailableToNetwork => 0x05,
+            NetworkMessageType::InitializeRoutingTable => 0x06,
+            NetworkMessageType::InitializeRoutingTableAck => 0x07,
+            NetworkMessageType::EstablishConnectionToNetwork => 0x08,
+            NetworkMessageType::DisconnectConnectionToNetwork => 0x09,
+            NetworkMessageType::WhatIsNetworkNumber => 0x12,
+            NetworkMessageType::NetworkNumberIs => 0x13,
+            NetworkMessageType::Proprietary(value) => value,
+        }
+    }
+
+    /// Check if this is a vendor proprietary message type
+    pub fn is_proprietary(&self) -> bool {
+        matches!(self, NetworkMessageType::Proprietary(_))
+    }
 }
 
+/// Control octet bits that are reserved and shall be zero
+const RESERVED_CONTROL_BITS: u8 = 0x50;
+
 /// NPDU control flags
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub struct NpduControl {
     /// Network layer message
     pub network_message: bool,
@@ -159,6 +241,16 @@ impl NpduControl {
             priority: byte & 0x03,
         }
     }
+
+    /// The network priority
+    pub fn network_priority(&self) -> NetworkPriority {
+        NetworkPriority::from_bits(self.priority)
+    }
+
+    /// Set the network priority
+    pub fn set_network_priority(&mut self, priority: NetworkPriority) {
+        self.priority = priority.to_bits();
+    }
 }
 
 /// Network address (network number + MAC address)
@@ -185,10 +277,37 @@ impl NetworkAddress {
     pub fn is_local(&self) -> bool {
         self.network == 0
     }
+
+    /// A station, or with an empty MAC the broadcast, on the local network
+    pub fn local(address: Vec<u8>) -> Self {
+        Self::new(0, address)
+    }
+
+    /// The global broadcast address, every station on every network
+    pub fn global_broadcast() -> Self {
+        Self::new(0xFFFF, Vec::new())
+    }
+}
+
+/// A BACnetAddress: a network number (0 for the local network, 0xFFFF for
+/// all networks) and a MAC address on that network
+pub type BacnetAddress = NetworkAddress;
+
+impl From<&crate::datalink::DataLinkAddress> for NetworkAddress {
+    /// The local network address of a data link address
+    fn from(address: &crate::datalink::DataLinkAddress) -> Self {
+        Self::local(address.to_mac())
+    }
+}
+
+impl From<crate::datalink::DataLinkAddress> for NetworkAddress {
+    fn from(address: crate::datalink::DataLinkAddress) -> Self {
+        Self::from(&address)
+    }
 }
 
 /// Network Protocol Data Unit (NPDU)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Npdu {
     /// Protocol version (always 1)
     pub version: u8,
@@ -234,10 +353,56 @@ impl Npdu {
         }
     }
 
+    /// Create the NPCI of a network layer message to `destination`, or to
+    /// the local network for `None`
+    pub fn network_message(destination: Option<NetworkAddress>) -> Self {
+        let mut npdu = Self::new();
+        npdu.control.network_message = true;
+        npdu.set_destination(destination);
+        npdu
+    }
+
     /// Check if this is a network layer message
     pub fn is_network_message(&self) -> bool {
         self.control.network_message
     }
+
+    /// Check if the sender expects a reply
+    pub fn expecting_reply(&self) -> bool {
+        self.control.expecting_reply
+    }
+
+    /// The network priority
+    pub fn priority(&self) -> NetworkPriority {
+        self.control.network_priority()
+    }
+
+    /// Set the destination, with a full hop count for a remote one
+    pub fn set_destination(&mut self, destination: Option<NetworkAddress>) {
+        self.control.destination_present = destination.is_some();
+        self.hop_count = destination.as_ref().map(|_| 255);
+        self.destination = destination;
+    }
+
+    /// Set the source, as a router does when forwarding from a local network
+    pub fn set_source(&mut self, source: Option<NetworkAddress>) {
+        self.control.source_present = source.is_some();
+        self.source = source;
+    }
+
+    /// Check if the destination is a broadcast on a remote network (DLEN 0)
+    pub fn is_remote_broadcast(&self) -> bool {
+        self.destination
+            .as_ref()
+            .is_some_and(|dest| !dest.is_broadcast() && dest.address.is_empty())
+    }
+
+    /// Check if the destination is a global broadcast (DNET 0xFFFF)
+    pub fn is_global_broadcast(&self) -> bool {
+        self.destination
+            .as_ref()
+            .is_some_and(|dest| dest.is_broadcast())
+    }
 }
 
 /// Router information
@@ -259,8 +424,13 @@ impl Npdu {
         // Version
         buffer.push(self.version);
 
-        // Control byte
-        buffer.push(self.control.to_byte());
+        // Control byte, with the present bits matching the addresses
+        let control = NpduControl {
+            destination_present: self.destination.is_some(),
+            source_present: self.source.is_some(),
+            ..self.control
+        };
+        buffer.push(control.to_byte());
 
         // Destination network address
         if let Some(ref dest) = self.destination {
@@ -304,6 +474,12 @@ impl Npdu {
         }
 
         // Control byte
+        if data[pos] & RESERVED_CONTROL_BITS != 0 {
+            return Err(NetworkError::InvalidNpdu(format!(
+                "Reserved control bits set: 0x{:02X}",
+                data[pos]
+            )));
+        }
         let control = NpduControl::from_byte(data[pos]);
         pos += 1;
 
@@ -330,6 +506,11 @@ impl Npdu {
             let address = data[pos..pos + addr_len].to_vec();
             pos += addr_len;
 
+            if network == 0 {
+                return Err(NetworkError::InvalidNpdu(
+                    "Destination network 0".to_string(),
+                ));
+            }
             Some(NetworkAddress::new(network, address))
         } else {
             None
@@ -358,6 +539,12 @@ impl Npdu {
             let address = data[pos..pos + addr_len].to_vec();
             pos += addr_len;
 
+            // A source is always one station on one network
+            if network == 0 || network == 0xFFFF || address.is_empty() {
+                return Err(NetworkError::InvalidNpdu(
+                    "Invalid source address".to_string(),
+                ));
+            }
             Some(NetworkAddress::new(network, address))
         } else {
             None
@@ -394,9 +581,12 @@ impl Default for Npdu {
 }
 
 /// Network layer message handling
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct NetworkLayerMessage {
     /// Message type
     pub message_type: NetworkMessageType,
+    /// Vendor ID, present for proprietary message types only
+    pub vendor_id: Option<u16>,
     /// Message data
     pub data: Vec<u8>,
 }
@@ -404,16 +594,52 @@ pub struct NetworkLayerMessage {
 impl NetworkLayerMessage {
     /// Create a new network layer message
     pub fn new(message_type: NetworkMessageType, data: Vec<u8>) -> Self {
-        Self { message_type, data }
+        Self {
+            message_type,
+            vendor_id: None,
+            data,
+        }
+    }
+
+    /// Create a proprietary network layer message of `vendor_id`
+    pub fn proprietary(message_type: u8, vendor_id: u16, data: Vec<u8>) -> Self {
+        Self {
+            message_type: NetworkMessageType::Proprietary(message_type | 0x80),
+            vendor_id: Some(vendor_id),
+            data,
+        }
     }
 
     /// Encode network layer message
     pub fn encode(&self) -> Vec<u8> {
-        let mut buffer = vec![self.message_type as u8];
+        let mut buffer = vec![self.message_type.to_u8()];
+        if self.message_type.is_proprietary() {
+            buffer.extend_from_slice(&self.vendor_id.unwrap_or(0).to_be_bytes());
+        }
         buffer.extend_from_slice(&self.data);
         buffer
     }
 
+    /// Encode a complete NPDU carrying this message, with `npdu` as the NPCI
+    pub fn encode_npdu(&self, npdu: &Npdu) -> Vec<u8> {
+        let mut npdu = npdu.clone();
+        npdu.control.network_message = true;
+        let mut buffer = npdu.encode();
+        buffer.extend_from_slice(&self.encode());
+        buffer
+    }
+
+    /// Decode a complete NPDU carrying a network layer message
+    pub fn decode_npdu(data: &[u8]) -> Result<(Npdu, Self)> {
+        let (npdu, length) = Npdu::decode(data)?;
+        if !npdu.is_network_message() {
+            return Err(NetworkError::InvalidNpdu(
+                "Not a network layer message".to_string(),
+            ));
+        }
+        Ok((npdu, Self::decode(&data[length..])?))
+    }
+
     /// Decode network layer message
     pub fn decode(data: &[u8]) -> Result<Self> {
         if data.is_empty() {
@@ -422,34 +648,22 @@ impl NetworkLayerMessage {
             ));
         }
 
-        let message_type = match data[0] {
-            0x00 => NetworkMessageType::WhoIsRouterToNetwork,
-            0x01 => NetworkMessageType::IAmRouterToNetwork,
-            0x02 => NetworkMessageType::ICouldBeRouterToNetwork,
-            0x03 => NetworkMessageType::RejectMessageToNetwork,
-            0x04 => NetworkMessageType::RouterBusyToNetwork,
-            0x05 => NetworkMessageType::RouterAvailableToNetwork,
-            0x06 => NetworkMessageType::InitializeRoutingTable,
-            0x07 => NetworkMessageType::InitializeRoutingTableAck,
-            0x08 => NetworkMessageType::EstablishConnectionToNetwork,
-            0x09 => NetworkMessageType::DisconnectConnectionToNetwork,
-            0x12 => NetworkMessageType::WhatIsNetworkNumber,
-            0x13 => NetworkMessageType::NetworkNumberIs,
-            _ => {
-                return Err(NetworkError::InvalidNpdu(format!(
-                    "Unknown network message type: {}",
-                    data[0]
-                )))
-            }
-        };
+        let message_type = NetworkMessageType::from_u8(data[0]).ok_or_else(|| {
+            NetworkError::InvalidNpdu(format!("Unknown network message type: {}", data[0]))
+        })?;
 
-        let message_data = if data.len() > 1 {
-            data[1..].to_vec()
-        } else {
-            Vec::new()
-        };
+        if message_type.is_proprietary() {
+            if data.len() < 3 {
+                return Err(NetworkError::InvalidNpdu("Missing vendor ID".to_string()));
+            }
+            return Ok(NetworkLayerMessage {
+                message_type,
+                vendor_id: Some(u16::from_be_bytes([data[1], data[2]])),
+                data: data[3..].to_vec(),
+            });
+        }
 
-        Ok(NetworkLayerMessage::new(message_type, message_data))
+        Ok(NetworkLayerMessage::new(message_type, data[1..].to_vec()))
     }
 }
 
@@ -1543,6 +1757,78 @@ mod tests {
         assert_eq!(decoded.data, vec![0x00, 0x64]);
     }
 
+    #[test]
+    fn test_npci_control_semantics() {
+        // Routed, expecting reply, life safety priority
+        let mut npdu = Npdu::new();
+        npdu.control.expecting_reply = true;
+        npdu.control
+            .set_network_priority(NetworkPriority::LifeSafety);
+        npdu.set_destination(Some(NetworkAddress::new(5, vec![0x0A])));
+        npdu.set_source(Some(NetworkAddress::new(
+            2,
+            vec![192, 168, 1, 5, 0xBA, 0xC0],
+        )));
+        let encoded = npdu.encode();
+        assert_eq!(
+            encoded,
+            [
+                0x01, 0x2F, 0x00, 0x05, 0x01, 0x0A, 0x00, 0x02, 0x06, 192, 168, 1, 5, 0xBA, 0xC0,
+                0xFF
+            ]
+        );
+        let (decoded, length) = Npdu::decode(&encoded).unwrap();
+        assert_eq!(length, encoded.len());
+        assert_eq!(decoded, npdu);
+        assert!(decoded.expecting_reply());
+        assert_eq!(decoded.priority(), NetworkPriority::LifeSafety);
+
+        // Present bits follow the addresses, whatever the flags say
+        let mut stale = Npdu::new();
+        stale.control.destination_present = true;
+        assert_eq!(stale.encode(), [0x01, 0x00]);
+
+        // Remote and global broadcasts
+        let mut remote = Npdu::new();
+        remote.set_destination(Some(NetworkAddress::new(5, vec![])));
+        assert!(remote.is_remote_broadcast() && !remote.is_global_broadcast());
+        assert!(Npdu::global_broadcast().is_global_broadcast());
+
+        // Reserved bits, DNET 0 and malformed sources are rejected
+        assert!(Npdu::decode(&[0x01, 0x40]).is_err());
+        assert!(Npdu::decode(&[0x01, 0x10]).is_err());
+        assert!(Npdu::decode(&[0x01, 0x20, 0x00, 0x00, 0x00, 0xFF]).is_err());
+        assert!(Npdu::decode(&[0x01, 0x08, 0xFF, 0xFF, 0x01, 0x0A]).is_err());
+        assert!(Npdu::decode(&[0x01, 0x08, 0x00, 0x02, 0x00]).is_err());
+        assert!(Npdu::decode(&[0x01, 0x20, 0x00, 0x05, 0x00]).is_err());
+    }
+
+    #[test]
+    fn test_network_message_npdu() {
+        // Who-Is-Router-To-Network for network 100, broadcast locally
+        let message =
+            NetworkLayerMessage::new(NetworkMessageType::WhoIsRouterToNetwork, vec![0x00, 0x64]);
+        let encoded = message.encode_npdu(&Npdu::network_message(None));
+        assert_eq!(encoded, [0x01, 0x80, 0x00, 0x00, 0x64]);
+        let (npdu, decoded) = NetworkLayerMessage::decode_npdu(&encoded).unwrap();
+        assert!(npdu.is_network_message());
+        assert_eq!(decoded, message);
+
+        // Proprietary types carry a vendor ID
+        let message = NetworkLayerMessage::proprietary(0x81, 260, vec![0xAA]);
+        let encoded = message.encode();
+        assert_eq!(encoded, [0x81, 0x01, 0x04, 0xAA]);
+        let decoded = NetworkLayerMessage::decode(&encoded).unwrap();
+        assert_eq!(decoded.message_type, NetworkMessageType::Proprietary(0x81));
+        assert_eq!(decoded.vendor_id, Some(260));
+        assert_eq!(decoded.data, [0xAA]);
+        assert!(NetworkLayerMessage::decode(&[0x81, 0x01]).is_err());
+
+        // Reserved types and application NPDUs are not network messages
+        assert!(NetworkLayerMessage::decode(&[0x14]).is_err());
+        assert!(NetworkLayerMessage::decode_npdu(&[0x01, 0x04, 0x10, 0x08]).is_err());
+    }
+
     #[test]
     fn test_routing_table() {
         let mut table = RoutingTable::new();
@@ -0,0 +1,355 @@
+//! Several data links in one stack
+//!
+//! A device or router can be attached to more than one network at a time,
+//! say BACnet/IP on two interfaces plus an MS/TP trunk. Each attachment is a
+//! port: a data link with a port ID and the network number of the network it
+//! reaches. [`PortDispatcher`] reads every port in a thread of its own and
+//! hands the NPDUs to one queue, each tagged with the port it arrived on, so
+//! a reply can go back out through the same port to the same station.
+//!
+//! A network number of 0 means the port's network number is not known yet.
+
+use std::{
+    collections::VecDeque,
+    io::ErrorKind,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Condvar, Mutex,
+    },
+    thread::JoinHandle,
+    time::Duration,
+};
+
+use crate::datalink::{DataLink, DataLinkAddress, DataLinkError, DataLinkType};
+use crate::network::{NetworkError, Result};
+
+/// Network number of a port whose network number is not known
+pub const UNKNOWN_NETWORK: u16 = 0;
+
+/// How long a port thread backs off after a receive error
+const ERROR_BACKOFF: Duration = Duration::from_millis(10);
+
+/// A data link shared between its receive thread and senders
+type SharedLink = Arc<Mutex<Box<dyn DataLink>>>;
+
+/// NPDUs received on all ports, tagged with the port ID
+type ReceiveQueue = Arc<(Mutex<VecDeque<(u8, Vec<u8>, DataLinkAddress)>>, Condvar)>;
+
+/// An NPDU received on a port
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReceivedNpdu {
+    /// Port the NPDU arrived on
+    pub port: u8,
+    /// Network number of that port when the NPDU was taken from the queue
+    pub network_number: u16,
+    /// The NPDU
+    pub npdu: Vec<u8>,
+    /// Station on the port's network that sent it
+    pub source: DataLinkAddress,
+}
+
+/// Description of an attached port
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortInfo {
+    /// Port ID
+    pub id: u8,
+    /// Network number, [`UNKNOWN_NETWORK`] if not known
+    pub network_number: u16,
+    /// Type of the port's data link
+    pub link_type: DataLinkType,
+    /// This station's address on the port
+    pub local_address: DataLinkAddress,
+    /// Longest NPDU the port carries
+    pub max_npdu_length: usize,
+}
+
+/// One attached port
+struct Port {
+    /// Port ID
+    id: u8,
+    /// Network number
+    network_number: u16,
+    /// The data link
+    link: SharedLink,
+    /// Receive thread
+    thread: Option<JoinHandle<()>>,
+}
+
+/// The ports of one stack, read together
+///
+/// Each port's data link is locked by its receive thread for one receive
+/// timeout at a time (100 ms for the links in this crate), so a send waits
+/// at most that long.
+pub struct PortDispatcher {
+    /// Ports in the order they were added
+    ports: Vec<Port>,
+    /// NPDUs received on all ports
+    receive_queue: ReceiveQueue,
+    /// Running flag for the receive threads
+    running: Arc<AtomicBool>,
+}
+
+impl PortDispatcher {
+    /// Create a dispatcher with no ports
+    pub fn new() -> Self {
+        Self {
+            ports: Vec::new(),
+            receive_queue: Arc::new((Mutex::new(VecDeque::new()), Condvar::new())),
+            running: Arc::new(AtomicBool::new(true)),
+        }
+    }
+
+    /// Attach `link` as port `id` on network `network_number`, and start
+    /// reading it
+    ///
+    /// Port IDs must be unique, and so must every known network number.
+    pub fn add_port(&mut self, id: u8, network_number: u16, link: Box<dyn DataLink>) -> Result<()> {
+        if self.ports.iter().any(|port| port.id == id) {
+            return Err(NetworkError::RoutingError(format!(
+                "Port {} is already attached",
+                id
+            )));
+        }
+        self.check_network_number(network_number)?;
+
+        let link: SharedLink = Arc::new(Mutex::new(link));
+        let thread = {
+            let link = link.clone();
+            let receive_queue = self.receive_queue.clone();
+            let running = self.running.clone();
+            std::thread::spawn(move || run_port(id, link, receive_queue, running))
+        };
+        self.ports.push(Port {
+            id,
+            network_number,
+            link,
+            thread: Some(thread),
+        });
+        Ok(())
+    }
+
+    /// The attached ports, in the order they were added
+    pub fn ports(&self) -> Vec<PortInfo> {
+        self.ports
+            .iter()
+            .map(|port| {
+                let link = port.link.lock().unwrap();
+                PortInfo {
+                    id: port.id,
+                    network_number: port.network_number,
+                    link_type: link.link_type(),
+                    local_address: link.local_address(),
+                    max_npdu_length: link.max_npdu_length(),
+                }
+            })
+            .collect()
+    }
+
+    /// The network number of a port
+    pub fn network_number(&self, port: u8) -> Option<u16> {
+        self.ports
+            .iter()
+            .find(|p| p.id == port)
+            .map(|p| p.network_number)
+    }
+
+    /// Set the network number of a port, as when it is learned
+    pub fn set_network_number(&mut self, port: u8, network_number: u16) -> Result<()> {
+        if self.network_number(port) == Some(network_number) {
+            return Ok(());
+        }
+        self.check_network_number(network_number)?;
+        let port = self.port_mut(port)?;
+        port.network_number = network_number;
+        Ok(())
+    }
+
+    /// The port directly attached to `network_number`
+    pub fn port_for_network(&self, network_number: u16) -> Option<u8> {
+        if network_number == UNKNOWN_NETWORK {
+            return None;
+        }
+        self.ports
+            .iter()
+            .find(|port| port.network_number == network_number)
+            .map(|port| port.id)
+    }
+
+    /// Wait up to `timeout` for an NPDU on any port
+    pub fn receive(&self, timeout: Duration) -> Option<ReceivedNpdu> {
+        let (queue, ready) = &*self.receive_queue;
+        let queue = queue.lock().unwrap();
+        let (mut queue, _) = ready
+            .wait_timeout_while(queue, timeout, |queue| queue.is_empty())
+            .unwrap();
+        let (port, npdu, source) = queue.pop_front()?;
+        Some(ReceivedNpdu {
+            port,
+            network_number: self.network_number(port).unwrap_or(UNKNOWN_NETWORK),
+            npdu,
+            source,
+        })
+    }
+
+    /// Send an NPDU to `dest` on `port`
+    pub fn send(&self, port: u8, npdu: &[u8], dest: &DataLinkAddress) -> Result<()> {
+        let port = self.port(port)?;
+        port.link
+            .lock()
+            .unwrap()
+            .send_frame(npdu, dest)
+            .map_err(NetworkError::DataLink)
+    }
+
+    /// Send an NPDU back to the station a received NPDU came from, on the
+    /// port it came in on
+    pub fn reply(&self, received: &ReceivedNpdu, npdu: &[u8]) -> Result<()> {
+        self.send(received.port, npdu, &received.source)
+    }
+
+    /// Broadcast an NPDU on the local network of `port`
+    pub fn broadcast(&self, port: u8, npdu: &[u8]) -> Result<()> {
+        self.send(port, npdu, &DataLinkAddress::Broadcast)
+    }
+
+    /// Broadcast an NPDU on the local network of every port but `except`,
+    /// returning the first error after trying them all
+    pub fn broadcast_all(&self, npdu: &[u8], except: Option<u8>) -> Result<()> {
+        let mut result = Ok(());
+        for port in self.ports.iter().filter(|port| Some(port.id) != except) {
+            let sent = self.broadcast(port.id, npdu);
+            if result.is_ok() {
+                result = sent;
+            }
+        }
+        result
+    }
+
+    fn check_network_number(&self, network_number: u16) -> Result<()> {
+        if network_number == 0xFFFF {
+            return Err(NetworkError::InvalidAddress);
+        }
+        if let Some(port) = self.port_for_network(network_number) {
+            return Err(NetworkError::RoutingError(format!(
+                "Network {} is already on port {}",
+                network_number, port
+            )));
+        }
+        Ok(())
+    }
+
+    fn port(&self, id: u8) -> Result<&Port> {
+        self.ports
+            .iter()
+            .find(|port| port.id == id)
+            .ok_or(NetworkError::UnknownPort(id))
+    }
+
+    fn port_mut(&mut self, id: u8) -> Result<&mut Port> {
+        self.ports
+            .iter_mut()
+            .find(|port| port.id == id)
+            .ok_or(NetworkError::UnknownPort(id))
+    }
+}
+
+impl Default for PortDispatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for PortDispatcher {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        for port in &mut self.ports {
+            if let Some(thread) = port.thread.take() {
+                let _ = thread.join();
+            }
+        }
+    }
+}
+
+/// Body of a port's receive thread
+fn run_port(id: u8, link: SharedLink, receive_queue: ReceiveQueue, running: Arc<AtomicBool>) {
+    while running.load(Ordering::Relaxed) {
+        let received = link.lock().unwrap().receive_frame();
+        match received {
+            Ok((npdu, source)) => {
+                let (queue, ready) = &*receive_queue;
+                queue.lock().unwrap().push_back((id, npdu, source));
+                ready.notify_all();
+            }
+            Err(DataLinkError::IoError(e))
+                if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+            // Give senders a turn and avoid spinning on a persistent error
+            Err(_) => std::thread::sleep(ERROR_BACKOFF),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datalink::bip::BacnetIpDataLink;
+
+    fn bip() -> BacnetIpDataLink {
+        BacnetIpDataLink::new("127.0.0.1:0").unwrap()
+    }
+
+    #[test]
+    fn test_receive_tagged_and_reply() {
+        let mut dispatcher = PortDispatcher::new();
+        let (first, second) = (bip(), bip());
+        let first_address = first.local_address();
+        let second_address = second.local_address();
+        dispatcher.add_port(1, 10, Box::new(first)).unwrap();
+        dispatcher.add_port(2, 20, Box::new(second)).unwrap();
+        assert_eq!(dispatcher.port_for_network(20), Some(2));
+        assert_eq!(dispatcher.ports()[1].local_address, second_address);
+
+        let mut peer = bip();
+        peer.send_frame(&[0x01, 0x00, 0xAA], &second_address)
+            .unwrap();
+        let received = dispatcher.receive(Duration::from_secs(2)).unwrap();
+        assert_eq!((received.port, received.network_number), (2, 20));
+        assert_eq!(received.npdu, [0x01, 0x00, 0xAA]);
+        assert_eq!(received.source, peer.local_address());
+
+        // The reply leaves through the port the request came in on
+        dispatcher.reply(&received, &[0x01, 0x00, 0xBB]).unwrap();
+        let (reply, source) = peer.receive_frame().unwrap();
+        assert_eq!(reply, [0x01, 0x00, 0xBB]);
+        assert_eq!(source, second_address);
+
+        peer.send_frame(&[0x01, 0x00, 0xCC], &first_address)
+            .unwrap();
+        let received = dispatcher.receive(Duration::from_secs(2)).unwrap();
+        assert_eq!((received.port, received.network_number), (1, 10));
+        assert!(dispatcher.receive(Duration::from_millis(50)).is_none());
+    }
+
+    #[test]
+    fn test_port_and_network_numbers() {
+        let mut dispatcher = PortDispatcher::new();
+        dispatcher.add_port(1, 10, Box::new(bip())).unwrap();
+        dispatcher
+            .add_port(2, UNKNOWN_NETWORK, Box::new(bip()))
+            .unwrap();
+        // Several ports may wait to learn their network numbers
+        dispatcher
+            .add_port(3, UNKNOWN_NETWORK, Box::new(bip()))
+            .unwrap();
+        assert!(dispatcher.add_port(1, 30, Box::new(bip())).is_err());
+        assert!(dispatcher.add_port(4, 10, Box::new(bip())).is_err());
+        assert_eq!(dispatcher.port_for_network(UNKNOWN_NETWORK), None);
+
+        dispatcher.set_network_number(2, 20).unwrap();
+        assert_eq!(dispatcher.network_number(2), Some(20));
+        assert!(dispatcher.set_network_number(3, 20).is_err());
+        assert!(matches!(
+            dispatcher.send(9, &[0x01, 0x00], &DataLinkAddress::Broadcast),
+            Err(NetworkError::UnknownPort(9))
+        ));
+    }
+}
@@ -0,0 +1,1505 @@
+//! BACnet router
+//!
+//! A router joins the networks of the ports of a [`PortDispatcher`] into an
+//! internetwork (Clause 6.5). It keeps a routing table of the networks it
+//! reaches through other routers, learned from I-Am-Router-To-Network or
+//! configured, and for each NPDU it receives:
+//!
+//! - with no DNET, hands it to the router's own application, or handles it
+//!   if it is a network layer message;
+//! - for a directly connected network, strips DNET/DADR and delivers it to
+//!   DADR (or broadcasts it for DLEN 0) on that network;
+//! - for a network reached through another router, decrements the hop count
+//!   and passes it to that router;
+//! - for the global broadcast network 0xFFFF, delivers it locally and
+//!   broadcasts it on every other port.
+//!
+//! Forwarded NPDUs get SNET/SADR of the originating station when they have
+//! none, so replies can find their way back. An NPDU for an unknown network
+//! is answered with Reject-Message-To-Network while the router looks for a
+//! route with Who-Is-Router-To-Network on its other ports.
+//!
+//! A port added without a network number learns it from the Network-Number-Is
+//! of another router on its network; the router answers What-Is-Network-Number
+//! on its ports, saying whether each number is configured or learned.
+//!
+//! A [`VirtualNetwork`] is reached with no port: the NPDUs for its devices
+//! come out of the router as [`VirtualNpdu`]s for the gateway to answer,
+//! and [`Router::process_virtual`] routes the answers.
+//!
+//! [`Router::process`] works on one received NPDU and returns what to send;
+//! [`Router::poll`] drives it from a [`PortDispatcher`].
+//!
+//! # Example
+//!
+//! ```no_run
+//! use bacnet_rs::datalink::bip::BacnetIpDataLink;
+//! use bacnet_rs::network::port::PortDispatcher;
+//! use bacnet_rs::network::router::Router;
+//! use std::time::Duration;
+//!
+//! let mut dispatcher = PortDispatcher::new();
+//! dispatcher.add_port(1, 10, Box::new(BacnetIpDataLink::new("192.168.1.2:47808")?))?;
+//! dispatcher.add_port(2, 20, Box::new(BacnetIpDataLink::new("10.0.0.2:47808")?))?;
+//!
+//! let mut router = Router::for_dispatcher(&dispatcher);
+//! router.announce(&dispatcher);
+//! loop {
+//!     if let Some(received) = router.poll(&mut dispatcher, Duration::from_millis(100)) {
+//!         // An NPDU for the router's own application
+//!         println!("{:02X?}", received.npdu);
+//!     }
+//! }
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    time::Duration,
+};
+
+use crate::datalink::DataLinkAddress;
+use crate::datalink::DataLinkType;
+use crate::network::message::{NetworkMessage, RejectReason, RoutingTableEntry};
+use crate::network::port::{PortDispatcher, PortInfo, ReceivedNpdu, UNKNOWN_NETWORK};
+use crate::network::virtual_network::{VirtualNetwork, VirtualNpdu};
+use crate::network::{NetworkAddress, NetworkError, NetworkMessageType, Npdu, Result};
+use crate::object::NetworkNumberQuality;
+
+/// Network number of the global broadcast network
+const GLOBAL_BROADCAST: u16 = 0xFFFF;
+
+/// A route to a network that is not directly connected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteEntry {
+    /// The network reached
+    pub network: u16,
+    /// Port the next router is on
+    pub port: u8,
+    /// The next router, on the network of `port`
+    pub next_hop: DataLinkAddress,
+    /// Whether the next router reported itself busy for this network
+    pub busy: bool,
+}
+
+/// A half-router that offered to connect to a network with
+/// I-Could-Be-Router-To-Network
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouterCandidate {
+    /// The network it could connect to
+    pub network: u16,
+    /// Port it is on
+    pub port: u8,
+    /// Its address on the network of `port`
+    pub address: DataLinkAddress,
+    /// Its performance index, lower is better
+    pub performance_index: u8,
+}
+
+/// Network layer requests and reports for the application, which owns the
+/// data links
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RouterEvent {
+    /// Establish-Connection-To-Network: connect the port that reaches
+    /// `network`, for `termination_time` minutes without traffic (0 for
+    /// permanently)
+    EstablishConnection { network: u16, termination_time: u8 },
+    /// Disconnect-Connection-To-Network: drop the connection to `network`
+    DisconnectConnection { network: u16 },
+    /// A port without a configured network number learned it from
+    /// Network-Number-Is
+    NetworkNumberLearned { port: u8, network: u16 },
+    /// Another router announced a different configured network number for a
+    /// port with a configured network number
+    NetworkNumberConflict { port: u8, network: u16 },
+    /// A router rejected an NPDU this router sent to `network`
+    Rejected { network: u16, reason: RejectReason },
+}
+
+/// An NPDU the router sends
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouterSend {
+    /// Port to send on
+    pub port: u8,
+    /// Station on the port's network, or [`DataLinkAddress::Broadcast`]
+    pub destination: DataLinkAddress,
+    /// The NPDU
+    pub npdu: Vec<u8>,
+}
+
+/// What the router does with one received NPDU
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RouterOutcome {
+    /// NPDUs to send
+    pub sends: Vec<RouterSend>,
+    /// The NPDU, if it is for the router's own application
+    pub local: Option<Vec<u8>>,
+    /// Requests and reports for the application
+    pub events: Vec<RouterEvent>,
+    /// NPDUs for devices on virtual networks
+    pub virtual_npdus: Vec<VirtualNpdu>,
+}
+
+/// Routing between the ports of one stack
+#[derive(Debug, Clone, Default)]
+pub struct Router {
+    /// Attached ports
+    ports: Vec<PortInfo>,
+    /// Routes to networks that are not directly connected
+    routes: BTreeMap<u16, RouteEntry>,
+    /// Half-routers that could connect to networks with no route
+    candidates: BTreeMap<u16, RouterCandidate>,
+    /// Ports whose network number was learned rather than configured
+    learned: BTreeSet<u8>,
+    /// Events of polled NPDUs not yet taken
+    events: Vec<RouterEvent>,
+    /// Virtual networks, by network number
+    virtual_networks: BTreeMap<u16, VirtualNetwork>,
+    /// Polled NPDUs for virtual devices not yet taken
+    virtual_npdus: Vec<VirtualNpdu>,
+}
+
+impl Router {
+    /// Create a router with no ports
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a router for the ports of `dispatcher`
+    pub fn for_dispatcher(dispatcher: &PortDispatcher) -> Self {
+        Self {
+            ports: dispatcher.ports(),
+            ..Self::default()
+        }
+    }
+
+    /// Attach a port, replacing one with the same ID
+    ///
+    /// A known network number counts as configured.
+    pub fn add_port(&mut self, port: PortInfo) {
+        self.ports.retain(|p| p.id != port.id);
+        self.learned.remove(&port.id);
+        self.ports.push(port);
+    }
+
+    /// The attached ports
+    pub fn ports(&self) -> &[PortInfo] {
+        &self.ports
+    }
+
+    /// Configure the network number of a port, [`UNKNOWN_NETWORK`] to have
+    /// it learned
+    pub fn set_port_network(&mut self, port: u8, network_number: u16) {
+        if let Some(port) = self.ports.iter_mut().find(|p| p.id == port) {
+            port.network_number = network_number;
+        }
+        self.learned.remove(&port);
+        self.routes.remove(&network_number);
+    }
+
+    /// How the network number of a port was obtained
+    pub fn network_number_quality(&self, port: u8) -> Option<NetworkNumberQuality> {
+        let network = self.port_network(port)?;
+        Some(if network == UNKNOWN_NETWORK {
+            NetworkNumberQuality::Unknown
+        } else if self.learned.contains(&port) {
+            NetworkNumberQuality::Learned
+        } else {
+            NetworkNumberQuality::Configured
+        })
+    }
+
+    /// The routes to networks that are not directly connected
+    pub fn routes(&self) -> impl Iterator<Item = &RouteEntry> {
+        self.routes.values()
+    }
+
+    /// The route to `network`, if it is not directly connected
+    pub fn route(&self, network: u16) -> Option<&RouteEntry> {
+        self.routes.get(&network)
+    }
+
+    /// Configure a route to `network` through the router `next_hop` on
+    /// `port`
+    pub fn add_route(&mut self, network: u16, port: u8, next_hop: DataLinkAddress) -> Result<()> {
+        if network == UNKNOWN_NETWORK || network == GLOBAL_BROADCAST {
+            return Err(NetworkError::InvalidAddress);
+        }
+        if !self.ports.iter().any(|p| p.id == port) {
+            return Err(NetworkError::UnknownPort(port));
+        }
+        if let Some(connected) = self.connected_port(network) {
+            return Err(NetworkError::RoutingError(format!(
+                "Network {} is directly connected to port {}",
+                network, connected.id
+            )));
+        }
+        if self.virtual_networks.contains_key(&network) {
+            return Err(NetworkError::RoutingError(format!(
+                "Network {} is a virtual network",
+                network
+            )));
+        }
+        self.routes.insert(
+            network,
+            RouteEntry {
+                network,
+                port,
+                next_hop,
+                busy: false,
+            },
+        );
+        Ok(())
+    }
+
+    /// Remove the route to `network`
+    pub fn remove_route(&mut self, network: u16) -> Option<RouteEntry> {
+        self.routes.remove(&network)
+    }
+
+    /// Attach a virtual network, replacing one with the same network number
+    ///
+    /// Its network number must not be that of a port, nor its port ID that
+    /// of a port or of another virtual network. Any route to the network is
+    /// dropped.
+    pub fn add_virtual_network(&mut self, network: VirtualNetwork) -> Result<()> {
+        if let Some(connected) = self.connected_port(network.network()) {
+            return Err(NetworkError::RoutingError(format!(
+                "Network {} is directly connected to port {}",
+                network.network(),
+                connected.id
+            )));
+        }
+        let port_taken = self.ports.iter().any(|p| p.id == network.port_id())
+            || self
+                .virtual_networks
+                .values()
+                .any(|v| v.port_id() == network.port_id() && v.network() != network.network());
+        if port_taken {
+            return Err(NetworkError::RoutingError(format!(
+                "Port {} is already attached",
+                network.port_id()
+            )));
+        }
+        self.routes.remove(&network.network());
+        self.candidates.remove(&network.network());
+        self.virtual_networks.insert(network.network(), network);
+        Ok(())
+    }
+
+    /// Remove the virtual network `network`
+    pub fn remove_virtual_network(&mut self, network: u16) -> Option<VirtualNetwork> {
+        self.virtual_networks.remove(&network)
+    }
+
+    /// The virtual network `network`
+    pub fn virtual_network(&self, network: u16) -> Option<&VirtualNetwork> {
+        self.virtual_networks.get(&network)
+    }
+
+    /// The virtual network `network`, to add or remove devices
+    pub fn virtual_network_mut(&mut self, network: u16) -> Option<&mut VirtualNetwork> {
+        self.virtual_networks.get_mut(&network)
+    }
+
+    /// The virtual networks
+    pub fn virtual_networks(&self) -> impl Iterator<Item = &VirtualNetwork> {
+        self.virtual_networks.values()
+    }
+
+    /// Half-routers that offered to connect to networks with no route
+    pub fn candidates(&self) -> impl Iterator<Item = &RouterCandidate> {
+        self.candidates.values()
+    }
+
+    /// The routing table as Initialize-Routing-Table-Ack reports it: every
+    /// directly connected network, virtual networks included, and every
+    /// route, with its port
+    pub fn routing_table(&self) -> Vec<RoutingTableEntry> {
+        let connected = self
+            .ports
+            .iter()
+            .filter(|p| p.network_number != UNKNOWN_NETWORK)
+            .map(|p| (p.network_number, p.id));
+        let virtual_networks = self
+            .virtual_networks
+            .values()
+            .map(|v| (v.network(), v.port_id()));
+        let routed = self
+            .routes
+            .values()
+            .map(|route| (route.network, route.port));
+        connected
+            .chain(virtual_networks)
+            .chain(routed)
+            .map(|(network, port_id)| RoutingTableEntry {
+                network,
+                port_id,
+                port_info: Vec::new(),
+            })
+            .collect()
+    }
+
+    /// Take the events of the NPDUs routed by [`poll`](Self::poll)
+    pub fn take_events(&mut self) -> Vec<RouterEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Take the NPDUs for virtual devices routed by [`poll`](Self::poll) and
+    /// [`send_virtual`](Self::send_virtual)
+    pub fn take_virtual_npdus(&mut self) -> Vec<VirtualNpdu> {
+        std::mem::take(&mut self.virtual_npdus)
+    }
+
+    /// Networks reachable through this router from `port`: the virtual
+    /// networks and those directly connected to, or routed through, other
+    /// ports
+    pub fn reachable_networks(&self, port: u8) -> Vec<u16> {
+        let mut networks: Vec<u16> = self
+            .ports
+            .iter()
+            .filter(|p| p.id != port && p.network_number != UNKNOWN_NETWORK)
+            .map(|p| p.network_number)
+            .chain(
+                self.virtual_networks
+                    .values()
+                    .filter(|v| v.port_id() != port)
+                    .map(|v| v.network()),
+            )
+            .chain(
+                self.routes
+                    .values()
+                    .filter(|route| route.port != port)
+                    .map(|route| route.network),
+            )
+            .collect();
+        networks.sort_unstable();
+        networks.dedup();
+        networks
+    }
+
+    /// The broadcasts announcing this router on each port, as sent at
+    /// startup: Network-Number-Is for a configured network number, or
+    /// What-Is-Network-Number to learn it, then I-Am-Router-To-Network
+    pub fn announcements(&self) -> Vec<RouterSend> {
+        let mut sends = Vec::new();
+        for port in &self.ports {
+            match self.network_number_quality(port.id) {
+                Some(NetworkNumberQuality::Configured) => {
+                    sends.extend(self.network_number_is(port.id))
+                }
+                Some(NetworkNumberQuality::Unknown) => sends.push(RouterSend {
+                    port: port.id,
+                    destination: DataLinkAddress::Broadcast,
+                    npdu: NetworkMessage::WhatIsNetworkNumber
+                        .encode_npdu(&Npdu::network_message(None)),
+                }),
+                _ => {}
+            }
+            let networks = self.reachable_networks(port.id);
+            if !networks.is_empty() {
+                sends.push(i_am_router(port.id, &networks));
+            }
+        }
+        sends
+    }
+
+    /// Send the announcements
+    pub fn announce(&self, dispatcher: &PortDispatcher) {
+        send_all(dispatcher, &self.announcements());
+    }
+
+    /// Wait up to `timeout` for an NPDU on any port of `dispatcher` and route
+    /// it, returning it if it is for the router's own application
+    ///
+    /// Sends are best effort: an NPDU that cannot be sent is dropped, as a
+    /// lost frame would be. The router's network numbers, learned ones
+    /// included, are passed on to the dispatcher. NPDUs for virtual devices
+    /// wait for [`take_virtual_npdus`](Self::take_virtual_npdus).
+    pub fn poll(
+        &mut self,
+        dispatcher: &mut PortDispatcher,
+        timeout: Duration,
+    ) -> Option<ReceivedNpdu> {
+        self.update_dispatcher(dispatcher);
+        let received = dispatcher.receive(timeout)?;
+        let mut outcome = self.process(received.port, &received.npdu, &received.source);
+        send_all(dispatcher, &outcome.sends);
+        self.events.append(&mut outcome.events);
+        self.virtual_npdus.append(&mut outcome.virtual_npdus);
+        self.update_dispatcher(dispatcher);
+        let network_number = self.port_network(received.port)?;
+        outcome.local.map(|npdu| ReceivedNpdu {
+            npdu,
+            network_number,
+            ..received
+        })
+    }
+
+    fn update_dispatcher(&self, dispatcher: &mut PortDispatcher) {
+        for port in &self.ports {
+            if dispatcher
+                .network_number(port.id)
+                .is_some_and(|network| network != port.network_number)
+            {
+                let _ = dispatcher.set_network_number(port.id, port.network_number);
+            }
+        }
+    }
+
+    /// Route an NPDU received on `port` from the station `source`
+    pub fn process(&mut self, port: u8, data: &[u8], source: &DataLinkAddress) -> RouterOutcome {
+        let mut outcome = RouterOutcome::default();
+        if self.port_network(port).is_none() {
+            return outcome;
+        }
+        let Ok((npdu, length)) = Npdu::decode(data) else {
+            return outcome;
+        };
+        let payload = &data[length..];
+
+        match npdu.destination.as_ref().map(|dest| dest.network) {
+            None => {
+                if npdu.is_network_message() {
+                    self.process_network_message(port, &npdu, payload, source, &mut outcome);
+                } else {
+                    outcome.local = Some(data.to_vec());
+                }
+            }
+            Some(GLOBAL_BROADCAST) => {
+                if npdu.is_network_message() {
+                    self.process_network_message(port, &npdu, payload, source, &mut outcome);
+                } else {
+                    outcome.local = Some(data.to_vec());
+                }
+                self.forward(port, npdu, payload, source, &mut outcome);
+            }
+            Some(_) => self.forward(port, npdu, payload, source, &mut outcome),
+        }
+        outcome
+    }
+
+    /// Route an NPDU sent by the device at `mac` on the virtual network
+    /// `network`
+    ///
+    /// The NPDU gets SNET/SADR of the device. One without a DNET has nowhere
+    /// to go, there being no data link under the virtual network; a reject
+    /// comes back as a [`VirtualNpdu`] for the device.
+    pub fn process_virtual(
+        &mut self,
+        network: u16,
+        mac: &[u8],
+        data: &[u8],
+    ) -> Result<RouterOutcome> {
+        let virtual_network = self
+            .virtual_networks
+            .get(&network)
+            .ok_or(NetworkError::NetworkUnreachable(network))?;
+        if virtual_network.device(mac).is_none() {
+            return Err(NetworkError::InvalidAddress);
+        }
+        let from = virtual_network.port_id();
+        let (mut npdu, length) = Npdu::decode(data)?;
+        let payload = &data[length..];
+        let mut outcome = RouterOutcome::default();
+        if npdu.destination.is_none() {
+            return Ok(outcome);
+        }
+        npdu.set_source(Some(NetworkAddress::new(network, mac.to_vec())));
+        self.forward(
+            from,
+            npdu,
+            payload,
+            &DataLinkAddress::Broadcast,
+            &mut outcome,
+        );
+
+        let (sends, rejects) = outcome
+            .sends
+            .into_iter()
+            .partition(|send| send.port != from);
+        outcome.sends = sends;
+        for reject in rejects {
+            let Ok((mut npdu, length)) = Npdu::decode(&reject.npdu) else {
+                continue;
+            };
+            npdu.set_destination(None);
+            let mut data = npdu.encode();
+            data.extend_from_slice(&reject.npdu[length..]);
+            outcome.virtual_npdus.push(VirtualNpdu {
+                network,
+                destination: mac.to_vec(),
+                npdu: data,
+            });
+        }
+        Ok(outcome)
+    }
+
+    /// Route an NPDU sent by a virtual device out through the ports of
+    /// `dispatcher`, best effort as for [`poll`](Self::poll)
+    pub fn send_virtual(
+        &mut self,
+        dispatcher: &PortDispatcher,
+        network: u16,
+        mac: &[u8],
+        data: &[u8],
+    ) -> Result<()> {
+        let mut outcome = self.process_virtual(network, mac, data)?;
+        send_all(dispatcher, &outcome.sends);
+        self.events.append(&mut outcome.events);
+        self.virtual_npdus.append(&mut outcome.virtual_npdus);
+        Ok(())
+    }
+
+    /// Pass an NPDU with a DNET on towards its destination
+    fn forward(
+        &self,
+        from: u8,
+        received: Npdu,
+        payload: &[u8],
+        source: &DataLinkAddress,
+        outcome: &mut RouterOutcome,
+    ) {
+        let Some(dest) = received.destination.clone() else {
+            return;
+        };
+        // Clause 6.2.2: a message whose decremented hop count reaches zero
+        // is discarded
+        let hop_count = received.hop_count.unwrap_or(255).saturating_sub(1);
+        if hop_count == 0 {
+            return;
+        }
+
+        let mut npdu = received.clone();
+        npdu.hop_count = Some(hop_count);
+        let mac = source.to_mac();
+        if npdu.source.is_none() && !mac.is_empty() {
+            if let Some(network) = self.port_network(from).filter(|n| *n != UNKNOWN_NETWORK) {
+                npdu.set_source(Some(NetworkAddress::new(network, mac)));
+            }
+        }
+
+        if dest.network == GLOBAL_BROADCAST {
+            for port in self.ports.iter().filter(|p| p.id != from) {
+                let mut data = npdu.encode();
+                data.extend_from_slice(payload);
+                if data.len() <= port.max_npdu_length {
+                    outcome.sends.push(RouterSend {
+                        port: port.id,
+                        destination: DataLinkAddress::Broadcast,
+                        npdu: data,
+                    });
+                }
+            }
+            for virtual_network in self.virtual_networks.values() {
+                if virtual_network.port_id() != from {
+                    let mut data = npdu.encode();
+                    data.extend_from_slice(payload);
+                    outcome.virtual_npdus.push(VirtualNpdu {
+                        network: virtual_network.network(),
+                        destination: Vec::new(),
+                        npdu: data,
+                    });
+                }
+            }
+            return;
+        }
+
+        if let Some(virtual_network) = self.virtual_networks.get(&dest.network) {
+            // Last hop: one of this stack's virtual devices, or all of them.
+            // Like a station missing from a real network, an unknown device
+            // does not answer.
+            if dest.address.is_empty() || virtual_network.device(&dest.address).is_some() {
+                npdu.set_destination(None);
+                let mut data = npdu.encode();
+                data.extend_from_slice(payload);
+                outcome.virtual_npdus.push(VirtualNpdu {
+                    network: dest.network,
+                    destination: dest.address,
+                    npdu: data,
+                });
+            }
+            return;
+        }
+
+        let (port, destination) = if let Some(port) = self.connected_port(dest.network) {
+            // Last hop: the destination is a station on the port's network
+            if port.id == from {
+                return;
+            }
+            let Some(destination) = DataLinkAddress::from_mac(port.link_type, &dest.address) else {
+                self.reject(
+                    from,
+                    &received,
+                    source,
+                    RejectReason::AddressingError,
+                    outcome,
+                );
+                return;
+            };
+            npdu.set_destination(None);
+            (port, destination)
+        } else if let Some(route) = self.routes.get(&dest.network) {
+            if route.port == from {
+                return;
+            }
+            if route.busy {
+                self.reject(from, &received, source, RejectReason::RouterBusy, outcome);
+                return;
+            }
+            match self.ports.iter().find(|p| p.id == route.port) {
+                Some(port) => (port, route.next_hop.clone()),
+                None => return,
+            }
+        } else {
+            // Look for a route while turning this NPDU away
+            for port in self.ports.iter().filter(|p| p.id != from) {
+                outcome
+                    .sends
+                    .push(who_is_router(port.id, Some(dest.network), None));
+            }
+            self.reject(from, &received, source, RejectReason::NoRoute, outcome);
+            return;
+        };
+
+        let mut data = npdu.encode();
+        data.extend_from_slice(payload);
+        if data.len() > port.max_npdu_length {
+            self.reject(
+                from,
+                &received,
+                source,
+                RejectReason::MessageTooLong,
+                outcome,
+            );
+            return;
+        }
+        outcome.sends.push(RouterSend {
+            port: port.id,
+            destination,
+            npdu: data,
+        });
+    }
+
+    /// Handle a network layer message for this router
+    fn process_network_message(
+        &mut self,
+        port: u8,
+        npdu: &Npdu,
+        payload: &[u8],
+        source: &DataLinkAddress,
+        outcome: &mut RouterOutcome,
+    ) {
+        let message = match NetworkMessage::decode(payload) {
+            Ok(message) => message,
+            Err(_) => {
+                // Reserved message types are rejected, malformed ones dropped
+                let reserved = payload
+                    .first()
+                    .is_some_and(|t| NetworkMessageType::from_u8(*t).is_none());
+                if reserved {
+                    self.reject(
+                        port,
+                        npdu,
+                        source,
+                        RejectReason::UnknownMessageType,
+                        outcome,
+                    );
+                }
+                return;
+            }
+        };
+
+        let local = npdu.source.is_none() && npdu.destination.is_none();
+        match message {
+            NetworkMessage::WhoIsRouterToNetwork(requested) => {
+                let reachable = self.reachable_networks(port);
+                match requested {
+                    None if !reachable.is_empty() => {
+                        outcome.sends.push(i_am_router(port, &reachable))
+                    }
+                    Some(network) if reachable.contains(&network) => {
+                        outcome.sends.push(i_am_router(port, &[network]))
+                    }
+                    Some(network) if self.port_network(port) != Some(network) => {
+                        // Ask the other networks on the requester's behalf
+                        let requester = npdu.source.clone().or_else(|| {
+                            let mac = source.to_mac();
+                            self.port_network(port)
+                                .filter(|n| *n != UNKNOWN_NETWORK && !mac.is_empty())
+                                .map(|n| NetworkAddress::new(n, mac))
+                        });
+                        for other in self.ports.iter().filter(|p| p.id != port) {
+                            outcome.sends.push(who_is_router(
+                                other.id,
+                                Some(network),
+                                requester.clone(),
+                            ));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            NetworkMessage::IAmRouterToNetwork(networks) => {
+                let mut learned = Vec::new();
+                for network in networks {
+                    if network == UNKNOWN_NETWORK
+                        || network == GLOBAL_BROADCAST
+                        || self.is_connected(network)
+                    {
+                        continue;
+                    }
+                    self.routes.insert(
+                        network,
+                        RouteEntry {
+                            network,
+                            port,
+                            next_hop: source.clone(),
+                            busy: false,
+                        },
+                    );
+                    self.candidates.remove(&network);
+                    learned.push(network);
+                }
+                if !learned.is_empty() {
+                    for other in self.ports.iter().filter(|p| p.id != port) {
+                        outcome.sends.push(i_am_router(other.id, &learned));
+                    }
+                }
+            }
+            NetworkMessage::ICouldBeRouterToNetwork {
+                network,
+                performance_index,
+            } => {
+                let known = self.is_connected(network)
+                    || self.routes.contains_key(&network)
+                    || self
+                        .candidates
+                        .get(&network)
+                        .is_some_and(|c| c.performance_index < performance_index);
+                if !known {
+                    self.candidates.insert(
+                        network,
+                        RouterCandidate {
+                            network,
+                            port,
+                            address: source.clone(),
+                            performance_index,
+                        },
+                    );
+                }
+            }
+            NetworkMessage::RejectMessageToNetwork { reason, network } => {
+                // The rejecting router no longer reaches the network
+                let through_source = self
+                    .routes
+                    .get(&network)
+                    .is_some_and(|route| route.port == port && route.next_hop == *source);
+                if reason == RejectReason::NoRoute && through_source {
+                    self.routes.remove(&network);
+                }
+                outcome
+                    .events
+                    .push(RouterEvent::Rejected { network, reason });
+            }
+            NetworkMessage::RouterBusyToNetwork(networks) => {
+                self.set_busy(port, source, &networks, true, outcome)
+            }
+            NetworkMessage::RouterAvailableToNetwork(networks) => {
+                self.set_busy(port, source, &networks, false, outcome)
+            }
+            NetworkMessage::InitializeRoutingTable(entries) => {
+                let table = if entries.is_empty() {
+                    self.routing_table()
+                } else {
+                    for entry in entries {
+                        self.update_route(entry);
+                    }
+                    Vec::new()
+                };
+                let ack = NetworkMessage::InitializeRoutingTableAck(table);
+                outcome.sends.push(RouterSend {
+                    port,
+                    destination: source.clone(),
+                    npdu: ack.encode_npdu(&Npdu::network_message(npdu.source.clone())),
+                });
+            }
+            NetworkMessage::EstablishConnectionToNetwork {
+                network,
+                termination_time,
+            } => outcome.events.push(RouterEvent::EstablishConnection {
+                network,
+                termination_time,
+            }),
+            NetworkMessage::DisconnectConnectionToNetwork(network) => outcome
+                .events
+                .push(RouterEvent::DisconnectConnection { network }),
+            // Network numbers are only asked and announced on the local network
+            NetworkMessage::WhatIsNetworkNumber if local => {
+                outcome.sends.extend(self.network_number_is(port));
+            }
+            NetworkMessage::NetworkNumberIs {
+                network,
+                configured,
+            } if local => self.learn_network_number(port, network, configured, outcome),
+            // Acks and proprietary messages
+            _ => {}
+        }
+    }
+
+    /// Take the network number of `port` from Network-Number-Is
+    ///
+    /// A configured announcement replaces a learned number; a learned one is
+    /// only taken while the number is unknown.
+    fn learn_network_number(
+        &mut self,
+        port: u8,
+        network: u16,
+        configured: bool,
+        outcome: &mut RouterOutcome,
+    ) {
+        if network == UNKNOWN_NETWORK || network == GLOBAL_BROADCAST {
+            return;
+        }
+        let learn = match self.network_number_quality(port) {
+            Some(NetworkNumberQuality::Unknown) => true,
+            Some(NetworkNumberQuality::Learned) => configured,
+            Some(_) => {
+                if configured && self.port_network(port) != Some(network) {
+                    outcome
+                        .events
+                        .push(RouterEvent::NetworkNumberConflict { port, network });
+                }
+                false
+            }
+            None => false,
+        };
+        let in_use = self.virtual_networks.contains_key(&network)
+            || self
+                .connected_port(network)
+                .is_some_and(|other| other.id != port);
+        if !learn || in_use || self.port_network(port) == Some(network) {
+            return;
+        }
+        self.set_port_network(port, network);
+        self.learned.insert(port);
+        outcome
+            .events
+            .push(RouterEvent::NetworkNumberLearned { port, network });
+    }
+
+    /// Network-Number-Is for `port`, if its network number is known
+    fn network_number_is(&self, port: u8) -> Option<RouterSend> {
+        let network = self.port_network(port).filter(|n| *n != UNKNOWN_NETWORK)?;
+        let message = NetworkMessage::NetworkNumberIs {
+            network,
+            configured: !self.learned.contains(&port),
+        };
+        Some(RouterSend {
+            port,
+            destination: DataLinkAddress::Broadcast,
+            npdu: message.encode_npdu(&Npdu::network_message(None)),
+        })
+    }
+
+    /// Mark the routes through the router `source` on `port` busy or
+    /// available, for `networks` or for all of them if empty, and pass the
+    /// news on to the other ports
+    ///
+    /// A busy route stays busy until the router reports it available again.
+    fn set_busy(
+        &mut self,
+        port: u8,
+        source: &DataLinkAddress,
+        networks: &[u16],
+        busy: bool,
+        outcome: &mut RouterOutcome,
+    ) {
+        let mut changed = Vec::new();
+        for route in self.routes.values_mut() {
+            let listed = networks.is_empty() || networks.contains(&route.network);
+            if listed && route.port == port && route.next_hop == *source {
+                route.busy = busy;
+                changed.push(route.network);
+            }
+        }
+        if changed.is_empty() {
+            return;
+        }
+        let message = if busy {
+            NetworkMessage::RouterBusyToNetwork(changed)
+        } else {
+            NetworkMessage::RouterAvailableToNetwork(changed)
+        };
+        let npdu = message.encode_npdu(&Npdu::network_message(None));
+        for other in self.ports.iter().filter(|p| p.id != port) {
+            outcome.sends.push(RouterSend {
+                port: other.id,
+                destination: DataLinkAddress::Broadcast,
+                npdu: npdu.clone(),
+            });
+        }
+    }
+
+    /// Apply one entry of Initialize-Routing-Table
+    ///
+    /// The entry does not name the next router, so NPDUs for the network are
+    /// sent to the PTP peer on a PTP port and broadcast on any other port,
+    /// for the router that reaches the network to pick up.
+    fn update_route(&mut self, entry: RoutingTableEntry) {
+        if entry.port_id == 0 {
+            self.routes.remove(&entry.network);
+            return;
+        }
+        let Some(port) = self.ports.iter().find(|p| p.id == entry.port_id) else {
+            return;
+        };
+        let next_hop = match port.link_type {
+            DataLinkType::PointToPoint => DataLinkAddress::PointToPoint,
+            _ => DataLinkAddress::Broadcast,
+        };
+        let _ = self.add_route(entry.network, entry.port_id, next_hop);
+    }
+
+    /// Send Reject-Message-To-Network for `received` back to its originator
+    fn reject(
+        &self,
+        port: u8,
+        received: &Npdu,
+        source: &DataLinkAddress,
+        reason: RejectReason,
+        outcome: &mut RouterOutcome,
+    ) {
+        let network = received
+            .destination
+            .as_ref()
+            .map(|dest| dest.network)
+            .unwrap_or(UNKNOWN_NETWORK);
+        let message = NetworkMessage::RejectMessageToNetwork { reason, network };
+        outcome.sends.push(RouterSend {
+            port,
+            destination: source.clone(),
+            npdu: message.encode_npdu(&Npdu::network_message(received.source.clone())),
+        });
+    }
+
+    fn port_network(&self, port: u8) -> Option<u16> {
+        self.ports
+            .iter()
+            .find(|p| p.id == port)
+            .map(|p| p.network_number)
+    }
+
+    /// Whether `network` is directly connected to a port or virtual
+    fn is_connected(&self, network: u16) -> bool {
+        self.connected_port(network).is_some() || self.virtual_networks.contains_key(&network)
+    }
+
+    fn connected_port(&self, network: u16) -> Option<&PortInfo> {
+        if network == UNKNOWN_NETWORK {
+            return None;
+        }
+        self.ports.iter().find(|p| p.network_number == network)
+    }
+}
+
+/// An I-Am-Router-To-Network broadcast on `port`
+fn i_am_router(port: u8, networks: &[u16]) -> RouterSend {
+    let message = NetworkMessage::IAmRouterToNetwork(networks.to_vec());
+    RouterSend {
+        port,
+        destination: DataLinkAddress::Broadcast,
+        npdu: message.encode_npdu(&Npdu::network_message(None)),
+    }
+}
+
+/// A Who-Is-Router-To-Network broadcast on `port`, on behalf of `requester`
+fn who_is_router(port: u8, network: Option<u16>, requester: Option<NetworkAddress>) -> RouterSend {
+    let mut npdu = Npdu::network_message(None);
+    npdu.set_source(requester);
+    RouterSend {
+        port,
+        destination: DataLinkAddress::Broadcast,
+        npdu: NetworkMessage::WhoIsRouterToNetwork(network).encode_npdu(&npdu),
+    }
+}
+
+fn send_all(dispatcher: &PortDispatcher, sends: &[RouterSend]) {
+    for send in sends {
+        let _ = dispatcher.send(send.port, &send.npdu, &send.destination);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datalink::bip::BacnetIpDataLink;
+    use crate::datalink::DataLink;
+
+    fn ip(s: &str) -> DataLinkAddress {
+        DataLinkAddress::Ip(s.parse().unwrap())
+    }
+
+    fn port(id: u8, network_number: u16, link_type: DataLinkType) -> PortInfo {
+        PortInfo {
+            id,
+            network_number,
+            link_type,
+            local_address: DataLinkAddress::Broadcast,
+            max_npdu_length: 1497,
+        }
+    }
+
+    /// Port 1 is B/IP network 10, port 2 MS/TP network 20
+    fn router() -> Router {
+        let mut router = Router::new();
+        router.add_port(port(1, 10, DataLinkType::BacnetIp));
+        router.add_port(port(2, 20, DataLinkType::MsTP));
+        router
+    }
+
+    fn decode(send: &RouterSend) -> (Npdu, Vec<u8>) {
+        let (npdu, length) = Npdu::decode(&send.npdu).unwrap();
+        (npdu, send.npdu[length..].to_vec())
+    }
+
+    #[test]
+    fn test_directly_connected_networks() {
+        let mut router = router();
+        let workstation = ip("192.168.1.5:47808");
+
+        // To MS/TP station 5: DNET stripped, SNET/SADR added
+        let mut npdu = Npdu::new();
+        npdu.control.expecting_reply = true;
+        npdu.set_destination(Some(NetworkAddress::new(20, vec![5])));
+        let mut data = npdu.encode();
+        data.extend_from_slice(&[0x00, 0x05, 0x01, 0x0C]);
+        let outcome = router.process(1, &data, &workstation);
+        assert_eq!(outcome.local, None);
+        assert_eq!(outcome.sends.len(), 1);
+        assert_eq!(
+            (outcome.sends[0].port, &outcome.sends[0].destination),
+            (2, &DataLinkAddress::MsTP(5))
+        );
+        let (forwarded, apdu) = decode(&outcome.sends[0]);
+        assert_eq!(forwarded.destination, None);
+        assert_eq!(
+            forwarded.source,
+            Some(NetworkAddress::new(10, vec![192, 168, 1, 5, 0xBA, 0xC0]))
+        );
+        assert!(forwarded.expecting_reply());
+        assert_eq!(apdu, [0x00, 0x05, 0x01, 0x0C]);
+
+        // And the reply back to the workstation
+        let mut reply = Npdu::new();
+        reply.set_destination(Some(NetworkAddress::new(
+            10,
+            vec![192, 168, 1, 5, 0xBA, 0xC0],
+        )));
+        let outcome = router.process(2, &reply.encode(), &DataLinkAddress::MsTP(5));
+        assert_eq!(outcome.sends[0].destination, workstation);
+        assert_eq!(
+            decode(&outcome.sends[0]).0.source,
+            Some(NetworkAddress::new(20, vec![5]))
+        );
+
+        // A global broadcast is both local and passed on
+        let data = Npdu::global_broadcast().encode();
+        let outcome = router.process(1, &data, &workstation);
+        assert_eq!(outcome.local, Some(data));
+        assert_eq!(outcome.sends.len(), 1);
+        assert_eq!(outcome.sends[0].destination, DataLinkAddress::Broadcast);
+        assert_eq!(decode(&outcome.sends[0]).0.hop_count, Some(254));
+
+        // Local traffic stays local, and spent hop counts go nowhere
+        let data = Npdu::new().encode();
+        assert_eq!(router.process(1, &data, &workstation).local, Some(data));
+        let mut spent = Npdu::global_broadcast();
+        spent.hop_count = Some(0);
+        let outcome = router.process(1, &spent.encode(), &workstation);
+        assert!(outcome.sends.is_empty());
+        spent.hop_count = Some(1);
+        let outcome = router.process(1, &spent.encode(), &workstation);
+        assert!(outcome.sends.is_empty());
+        spent.hop_count = Some(2);
+        let outcome = router.process(1, &spent.encode(), &workstation);
+        assert_eq!(decode(&outcome.sends[0]).0.hop_count, Some(1));
+    }
+
+    #[test]
+    fn test_learned_routes_and_rejects() {
+        let mut router = router();
+        let workstation = ip("192.168.1.5:47808");
+        // Network-Number-Is and I-Am-Router-To-Network on each port
+        assert_eq!(router.announcements().len(), 4);
+
+        // A router on MS/TP station 7 reaches network 30
+        let i_am = NetworkMessage::IAmRouterToNetwork(vec![30, 10])
+            .encode_npdu(&Npdu::network_message(None));
+        let outcome = router.process(2, &i_am, &DataLinkAddress::MsTP(7));
+        assert_eq!(router.route(30).unwrap().next_hop, DataLinkAddress::MsTP(7));
+        assert_eq!(router.route(10), None);
+        assert_eq!(outcome.sends, [i_am_router(1, &[30])]);
+
+        // Who-Is-Router-To-Network from the workstation
+        let who_is =
+            NetworkMessage::WhoIsRouterToNetwork(None).encode_npdu(&Npdu::network_message(None));
+        let outcome = router.process(1, &who_is, &workstation);
+        assert_eq!(outcome.sends, [i_am_router(1, &[20, 30])]);
+
+        // To network 30 through the other router, DNET kept
+        let mut npdu = Npdu::new();
+        npdu.set_destination(Some(NetworkAddress::new(30, vec![0x21])));
+        let outcome = router.process(1, &npdu.encode(), &workstation);
+        assert_eq!(outcome.sends[0].destination, DataLinkAddress::MsTP(7));
+        let (forwarded, _) = decode(&outcome.sends[0]);
+        assert_eq!(
+            forwarded.destination,
+            Some(NetworkAddress::new(30, vec![0x21]))
+        );
+        assert_eq!(forwarded.hop_count, Some(254));
+
+        // Network 40 is unknown: look for it, and reject
+        npdu.set_destination(Some(NetworkAddress::new(40, vec![0x21])));
+        let outcome = router.process(1, &npdu.encode(), &workstation);
+        assert_eq!(outcome.sends.len(), 2);
+        assert_eq!(outcome.sends[0], who_is_router(2, Some(40), None));
+        assert_eq!(outcome.sends[1].destination, workstation);
+        let (reject, data) = decode(&outcome.sends[1]);
+        assert!(reject.is_network_message());
+        assert_eq!(data, [0x03, RejectReason::NoRoute as u8, 0x00, 0x28]);
+
+        // Too long for the MS/TP network
+        router.add_port(PortInfo {
+            max_npdu_length: 501,
+            ..port(2, 20, DataLinkType::MsTP)
+        });
+        npdu.set_destination(Some(NetworkAddress::new(20, vec![5])));
+        let mut data = npdu.encode();
+        data.resize(600, 0);
+        let outcome = router.process(1, &data, &workstation);
+        assert_eq!(
+            decode(&outcome.sends[0]).1[1],
+            RejectReason::MessageTooLong as u8
+        );
+    }
+
+    #[test]
+    fn test_router_management_messages() {
+        let mut router = router();
+        let peer = DataLinkAddress::MsTP(7);
+        let i_am = NetworkMessage::IAmRouterToNetwork(vec![30, 31]);
+        router.process(2, &i_am.encode_npdu(&Npdu::network_message(None)), &peer);
+        let message = |message: NetworkMessage| message.encode_npdu(&Npdu::network_message(None));
+
+        // Busy for network 30 only, passed on to the B/IP side
+        let outcome = router.process(
+            2,
+            &message(NetworkMessage::RouterBusyToNetwork(vec![30])),
+            &peer,
+        );
+        assert!(router.route(30).unwrap().busy && !router.route(31).unwrap().busy);
+        let (_, relayed) = NetworkMessage::decode_npdu(&outcome.sends[0].npdu).unwrap();
+        assert_eq!(relayed, NetworkMessage::RouterBusyToNetwork(vec![30]));
+        let mut npdu = Npdu::new();
+        npdu.set_destination(Some(NetworkAddress::new(30, vec![1])));
+        let outcome = router.process(1, &npdu.encode(), &ip("192.168.1.5:47808"));
+        assert_eq!(
+            decode(&outcome.sends[0]).1[1],
+            RejectReason::RouterBusy as u8
+        );
+        router.process(
+            2,
+            &message(NetworkMessage::RouterAvailableToNetwork(vec![])),
+            &peer,
+        );
+        assert!(router.routes().all(|route| !route.busy));
+
+        // Initialize-Routing-Table: query, then remove network 31 and add 40
+        let outcome = router.process(
+            1,
+            &message(NetworkMessage::InitializeRoutingTable(vec![])),
+            &peer,
+        );
+        let (_, ack) = NetworkMessage::decode_npdu(&outcome.sends[0].npdu).unwrap();
+        let NetworkMessage::InitializeRoutingTableAck(table) = ack else {
+            panic!("expected an Ack, got {:?}", ack);
+        };
+        let networks: Vec<_> = table.iter().map(|e| (e.network, e.port_id)).collect();
+        assert_eq!(networks, [(10, 1), (20, 2), (30, 2), (31, 2)]);
+        let update = NetworkMessage::InitializeRoutingTable(vec![
+            RoutingTableEntry {
+                network: 31,
+                port_id: 0,
+                port_info: vec![],
+            },
+            RoutingTableEntry {
+                network: 40,
+                port_id: 2,
+                port_info: vec![],
+            },
+        ]);
+        let outcome = router.process(1, &message(update), &peer);
+        assert_eq!(
+            NetworkMessage::decode_npdu(&outcome.sends[0].npdu)
+                .unwrap()
+                .1,
+            NetworkMessage::InitializeRoutingTableAck(vec![])
+        );
+        assert_eq!(router.route(31), None);
+        assert_eq!(
+            router.route(40).unwrap().next_hop,
+            DataLinkAddress::Broadcast
+        );
+
+        // Half-routers, connection requests and rejects
+        let offer = NetworkMessage::ICouldBeRouterToNetwork {
+            network: 50,
+            performance_index: 9,
+        };
+        router.process(2, &message(offer), &DataLinkAddress::MsTP(9));
+        assert_eq!(
+            router.candidates().next().unwrap().address,
+            DataLinkAddress::MsTP(9)
+        );
+        let establish = NetworkMessage::EstablishConnectionToNetwork {
+            network: 50,
+            termination_time: 5,
+        };
+        let outcome = router.process(1, &message(establish), &peer);
+        assert_eq!(
+            outcome.events,
+            [RouterEvent::EstablishConnection {
+                network: 50,
+                termination_time: 5
+            }]
+        );
+        let reject = NetworkMessage::RejectMessageToNetwork {
+            reason: RejectReason::NoRoute,
+            network: 30,
+        };
+        router.process(2, &message(reject), &peer);
+        assert_eq!(router.route(30), None);
+
+        // Reserved message types are rejected
+        let outcome = router.process(1, &[0x01, 0x80, 0x20], &ip("192.168.1.5:47808"));
+        assert_eq!(decode(&outcome.sends[0]).1, [0x03, 0x03, 0x00, 0x00]);
+    }
+
+    #[test]
+    fn test_network_number_discovery() {
+        let mut router = router();
+        router.add_port(port(3, UNKNOWN_NETWORK, DataLinkType::Ethernet));
+        let message = |message: NetworkMessage| message.encode_npdu(&Npdu::network_message(None));
+        let other_router = DataLinkAddress::Ethernet([2, 0, 0, 0, 0, 9]);
+
+        // The unnumbered port asks, the numbered ones announce
+        let announcements = router.announcements();
+        let asked = announcements.iter().find(|send| send.port == 3).unwrap();
+        assert_eq!(
+            NetworkMessage::decode_npdu(&asked.npdu).unwrap().1,
+            NetworkMessage::WhatIsNetworkNumber
+        );
+        assert_eq!(
+            router.network_number_quality(3),
+            Some(NetworkNumberQuality::Unknown)
+        );
+
+        // A learned number is taken while unknown, and reported as learned
+        let learned = NetworkMessage::NetworkNumberIs {
+            network: 30,
+            configured: false,
+        };
+        let outcome = router.process(3, &message(learned), &other_router);
+        assert_eq!(
+            outcome.events,
+            [RouterEvent::NetworkNumberLearned {
+                port: 3,
+                network: 30
+            }]
+        );
+        assert_eq!(
+            router.network_number_quality(3),
+            Some(NetworkNumberQuality::Learned)
+        );
+        let outcome = router.process(
+            3,
+            &message(NetworkMessage::WhatIsNetworkNumber),
+            &other_router,
+        );
+        assert_eq!(
+            NetworkMessage::decode_npdu(&outcome.sends[0].npdu)
+                .unwrap()
+                .1,
+            NetworkMessage::NetworkNumberIs {
+                network: 30,
+                configured: false
+            }
+        );
+
+        // A configured announcement wins over a learned number
+        let configured = NetworkMessage::NetworkNumberIs {
+            network: 31,
+            configured: true,
+        };
+        router.process(3, &message(configured.clone()), &other_router);
+        assert_eq!(
+            router
+                .ports()
+                .iter()
+                .find(|p| p.id == 3)
+                .unwrap()
+                .network_number,
+            31
+        );
+
+        // But not over a configured one, and numbers stay unique
+        let outcome = router.process(1, &message(configured), &other_router);
+        assert_eq!(
+            outcome.events,
+            [RouterEvent::NetworkNumberConflict {
+                port: 1,
+                network: 31
+            }]
+        );
+        router.set_port_network(3, UNKNOWN_NETWORK);
+        let taken = NetworkMessage::NetworkNumberIs {
+            network: 10,
+            configured: true,
+        };
+        assert!(router
+            .process(3, &message(taken), &other_router)
+            .events
+            .is_empty());
+
+        // Routed What-Is-Network-Number is ignored
+        let mut routed = Npdu::network_message(None);
+        routed.set_source(Some(NetworkAddress::new(20, vec![5])));
+        let outcome = router.process(
+            1,
+            &NetworkMessage::WhatIsNetworkNumber.encode_npdu(&routed),
+            &other_router,
+        );
+        assert!(outcome.sends.is_empty());
+    }
+
+    #[test]
+    fn test_virtual_network() {
+        let mut router = router();
+        let workstation = ip("192.168.1.5:47808");
+        let workstation_address = NetworkAddress::new(10, workstation.to_mac());
+        let mut gateway = VirtualNetwork::new(100, 9).unwrap();
+        gateway.add_device(vec![1], 1001).unwrap();
+        gateway.add_device(vec![2], 1002).unwrap();
+        router.add_virtual_network(gateway).unwrap();
+        assert!(router
+            .add_virtual_network(VirtualNetwork::new(10, 8).unwrap())
+            .is_err());
+        assert!(router
+            .add_virtual_network(VirtualNetwork::new(101, 2).unwrap())
+            .is_err());
+        assert!(router.add_route(100, 1, workstation.clone()).is_err());
+
+        // Announced and reported like a directly connected network
+        assert_eq!(router.reachable_networks(1), [20, 100]);
+        assert!(router
+            .routing_table()
+            .iter()
+            .any(|entry| (entry.network, entry.port_id) == (100, 9)));
+        let i_am =
+            NetworkMessage::IAmRouterToNetwork(vec![100]).encode_npdu(&Npdu::network_message(None));
+        router.process(2, &i_am, &DataLinkAddress::MsTP(7));
+        assert_eq!(router.route(100), None);
+
+        // A request for device 1002, with SNET/SADR of the workstation
+        let mut npdu = Npdu::new();
+        npdu.set_destination(Some(NetworkAddress::new(100, vec![2])));
+        let mut data = npdu.encode();
+        data.extend_from_slice(&[0x00, 0x05, 0x01, 0x0C]);
+        let outcome = router.process(1, &data, &workstation);
+        assert!(outcome.sends.is_empty());
+        assert_eq!(outcome.virtual_npdus.len(), 1);
+        let request = &outcome.virtual_npdus[0];
+        assert_eq!((request.network, &request.destination[..]), (100, &[2][..]));
+        let (forwarded, length) = Npdu::decode(&request.npdu).unwrap();
+        assert_eq!(forwarded.destination, None);
+        assert_eq!(forwarded.source, Some(workstation_address.clone()));
+        assert_eq!(request.npdu[length..], [0x00, 0x05, 0x01, 0x0C]);
+
+        // No device at MAC 7; a global broadcast reaches them all
+        npdu.set_destination(Some(NetworkAddress::new(100, vec![7])));
+        assert!(router
+            .process(1, &npdu.encode(), &workstation)
+            .virtual_npdus
+            .is_empty());
+        let outcome = router.process(1, &Npdu::global_broadcast().encode(), &workstation);
+        assert_eq!(outcome.virtual_npdus[0].destination, Vec::<u8>::new());
+
+        // The answer goes out with SNET/SADR of the device
+        let mut reply = Npdu::new();
+        reply.set_destination(Some(workstation_address));
+        let outcome = router.process_virtual(100, &[2], &reply.encode()).unwrap();
+        assert_eq!(
+            (outcome.sends[0].port, &outcome.sends[0].destination),
+            (1, &workstation)
+        );
+        assert_eq!(
+            decode(&outcome.sends[0]).0.source,
+            Some(NetworkAddress::new(100, vec![2]))
+        );
+        assert!(router.process_virtual(100, &[7], &reply.encode()).is_err());
+
+        // A reject for an unknown network comes back to the device
+        reply.set_destination(Some(NetworkAddress::new(40, vec![1])));
+        let outcome = router.process_virtual(100, &[2], &reply.encode()).unwrap();
+        assert_eq!(outcome.sends.len(), 2);
+        let reject = &outcome.virtual_npdus[0];
+        assert_eq!(reject.destination, [2]);
+        let (npdu, message) = NetworkMessage::decode_npdu(&reject.npdu).unwrap();
+        assert_eq!(npdu.destination, None);
+        assert_eq!(
+            message,
+            NetworkMessage::RejectMessageToNetwork {
+                reason: RejectReason::NoRoute,
+                network: 40
+            }
+        );
+    }
+
+    #[test]
+    fn test_poll_routes_between_ports() {
+        let mut dispatcher = PortDispatcher::new();
+        let first = BacnetIpDataLink::new("127.0.0.1:0").unwrap();
+        let second = BacnetIpDataLink::new("127.0.0.1:0").unwrap();
+        let first_address = first.local_address();
+        dispatcher.add_port(1, 10, Box::new(first)).unwrap();
+        dispatcher.add_port(2, 20, Box::new(second)).unwrap();
+        let mut router = Router::for_dispatcher(&dispatcher);
+
+        let mut workstation = BacnetIpDataLink::new("127.0.0.1:0").unwrap();
+        let mut device = BacnetIpDataLink::new("127.0.0.1:0").unwrap();
+        let mut npdu = Npdu::new();
+        npdu.set_destination(Some(NetworkAddress::new(
+            20,
+            device.local_address().to_mac(),
+        )));
+        let mut data = npdu.encode();
+        data.push(0x10);
+        workstation.send_frame(&data, &first_address).unwrap();
+
+        assert!(router
+            .poll(&mut dispatcher, Duration::from_secs(2))
+            .is_none());
+        let (received, _) = device.receive_frame().unwrap();
+        let (routed, length) = Npdu::decode(&received).unwrap();
+        assert_eq!(routed.destination, None);
+        assert_eq!(
+            routed.source,
+            Some(NetworkAddress::new(
+                10,
+                workstation.local_address().to_mac()
+            ))
+        );
+        assert_eq!(received[length..], [0x10]);
+    }
+}
@@ -0,0 +1,133 @@
+//! Virtual networks for gateways
+//!
+//! A gateway represents the devices behind it, Modbus units say, as BACnet
+//! devices of their own. They sit on a virtual network: a network number
+//! the gateway's [`Router`](super::router::Router) reaches with no data link
+//! underneath, each device a station on it with a MAC address of its own.
+//! The router announces the network in I-Am-Router-To-Network like any it is
+//! directly connected to, and hands the NPDUs for its stations to the
+//! gateway as [`VirtualNpdu`]s instead of sending them on a port. The
+//! gateway answers for the addressed device through
+//! [`Router::process_virtual`](super::router::Router::process_virtual),
+//! which routes the answer out with SNET/SADR of that device.
+
+use std::collections::BTreeMap;
+
+use crate::network::port::UNKNOWN_NETWORK;
+use crate::network::{NetworkError, Result};
+
+/// A network of devices inside this stack
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VirtualNetwork {
+    /// Network number
+    network: u16,
+    /// Port ID the network is reported under in the routing table
+    port_id: u8,
+    /// Device instance of each station, by MAC address
+    devices: BTreeMap<Vec<u8>, u32>,
+}
+
+impl VirtualNetwork {
+    /// Create virtual network `network`, reported as port `port_id`
+    pub fn new(network: u16, port_id: u8) -> Result<Self> {
+        if network == UNKNOWN_NETWORK || network == 0xFFFF {
+            return Err(NetworkError::InvalidAddress);
+        }
+        Ok(Self {
+            network,
+            port_id,
+            devices: BTreeMap::new(),
+        })
+    }
+
+    /// Network number
+    pub fn network(&self) -> u16 {
+        self.network
+    }
+
+    /// Port ID in the routing table
+    pub fn port_id(&self) -> u8 {
+        self.port_id
+    }
+
+    /// Add the device `device_instance` as the station `mac`, replacing the
+    /// device at that MAC address
+    ///
+    /// MAC addresses are 1 to 6 octets; a device instance appears once.
+    pub fn add_device(&mut self, mac: Vec<u8>, device_instance: u32) -> Result<()> {
+        if mac.is_empty() || mac.len() > 6 {
+            return Err(NetworkError::InvalidAddress);
+        }
+        if let Some(other) = self.mac(device_instance).filter(|other| **other != mac) {
+            return Err(NetworkError::RoutingError(format!(
+                "Device {} is already at {:02X?}",
+                device_instance, other
+            )));
+        }
+        self.devices.insert(mac, device_instance);
+        Ok(())
+    }
+
+    /// Remove the station `mac`, returning its device instance
+    pub fn remove_device(&mut self, mac: &[u8]) -> Option<u32> {
+        self.devices.remove(mac)
+    }
+
+    /// The device instance of the station `mac`
+    pub fn device(&self, mac: &[u8]) -> Option<u32> {
+        self.devices.get(mac).copied()
+    }
+
+    /// The MAC address of the device `device_instance`
+    pub fn mac(&self, device_instance: u32) -> Option<&Vec<u8>> {
+        self.devices
+            .iter()
+            .find(|(_, instance)| **instance == device_instance)
+            .map(|(mac, _)| mac)
+    }
+
+    /// The stations, as MAC address and device instance, in MAC order
+    pub fn devices(&self) -> impl Iterator<Item = (&[u8], u32)> {
+        self.devices
+            .iter()
+            .map(|(mac, instance)| (mac.as_slice(), *instance))
+    }
+}
+
+/// An NPDU for devices on a virtual network
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VirtualNpdu {
+    /// The virtual network
+    pub network: u16,
+    /// MAC address of the device, empty for every device on the network
+    pub destination: Vec<u8>,
+    /// The NPDU, without DNET/DADR and with SNET/SADR of the originator
+    /// when it is known
+    pub npdu: Vec<u8>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_devices() {
+        let mut network = VirtualNetwork::new(100, 9).unwrap();
+        network.add_device(vec![1], 1001).unwrap();
+        network.add_device(vec![2], 1002).unwrap();
+        assert_eq!(network.device(&[2]), Some(1002));
+        assert_eq!(network.mac(1001), Some(&vec![1]));
+        assert_eq!(network.devices().count(), 2);
+
+        // A device is at one MAC address, and a MAC address is 1-6 octets
+        assert!(network.add_device(vec![3], 1001).is_err());
+        assert!(network.add_device(Vec::new(), 1003).is_err());
+        assert!(network.add_device(vec![0; 7], 1003).is_err());
+        network.add_device(vec![2], 1004).unwrap();
+        assert_eq!(network.device(&[2]), Some(1004));
+
+        assert_eq!(network.remove_device(&[1]), Some(1001));
+        assert_eq!(network.device(&[1]), None);
+        assert!(VirtualNetwork::new(0xFFFF, 9).is_err());
+    }
+}
@@ -0,0 +1,1796 @@
+//! Access Control Object Types Implementation
+//!
+//! This module implements the physical access control objects defined in ASHRAE 135:
+//! Access Door, Access Point, Access Zone and Credential Data Input.
+//!
+//! - An **Access Door** commands a door through a prioritized Present_Value. Writing
+//!   PULSE_UNLOCK (or EXTENDED_PULSE_UNLOCK) unlocks the door for Door_Pulse_Time (or
+//!   Door_Extended_Pulse_Time), after which the command relinquishes itself. The door
+//!   position and lock state are reported through Door_Status and Lock_Status, and
+//!   Door_Alarm_State flags doors that are forced open or held open too long.
+//! - An **Access Point** reports access events. A granted access pulse-unlocks its
+//!   Access_Doors at Priority_For_Writing through the database's queued writes.
+//! - An **Access Zone** counts the occupants that pass its entry and exit points.
+//! - A **Credential Data Input** presents the authentication factor last read by a
+//!   card reader or keypad.
+
+use crate::object::{
+    current_date_time, current_status_flags, date_time_value, status_flags_bit_string,
+    BacnetObject, Commandable, DeviceObjectPropertyReference, EventState, ObjectError,
+    ObjectIdentifier, ObjectType, PropertyIdentifier, PropertyValue, PropertyWrite, Reliability,
+    Result, DEFAULT_COMMAND_PRIORITY,
+};
+use crate::service::BacnetDateTime;
+use core::time::Duration;
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+/// Commanded door state (BACnetDoorValue)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum DoorValue {
+    /// Locked
+    Lock = 0,
+    /// Unlocked until relinquished
+    Unlock = 1,
+    /// Unlocked for Door_Pulse_Time
+    PulseUnlock = 2,
+    /// Unlocked for Door_Extended_Pulse_Time
+    ExtendedPulseUnlock = 3,
+}
+
+impl TryFrom<u32> for DoorValue {
+    type Error = ObjectError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            0 => Ok(DoorValue::Lock),
+            1 => Ok(DoorValue::Unlock),
+            2 => Ok(DoorValue::PulseUnlock),
+            3 => Ok(DoorValue::ExtendedPulseUnlock),
+            _ => Err(ObjectError::InvalidValue(format!(
+                "Invalid door value: {}",
+                value
+            ))),
+        }
+    }
+}
+
+/// Physical door position (BACnetDoorStatus)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum DoorStatus {
+    /// Closed
+    Closed = 0,
+    /// Opened
+    Opened = 1,
+    /// Position unknown
+    Unknown = 2,
+    /// Door sensor fault
+    DoorFault = 3,
+    /// Door status not used
+    Unused = 4,
+    /// No door status
+    None = 5,
+    /// Closing
+    Closing = 6,
+    /// Opening
+    Opening = 7,
+    /// Locked for safety
+    SafetyLocked = 8,
+    /// Opened within a limited range
+    LimitedOpened = 9,
+}
+
+impl TryFrom<u32> for DoorStatus {
+    type Error = ObjectError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            0 => Ok(DoorStatus::Closed),
+            1 => Ok(DoorStatus::Opened),
+            2 => Ok(DoorStatus::Unknown),
+            3 => Ok(DoorStatus::DoorFault),
+            4 => Ok(DoorStatus::Unused),
+            5 => Ok(DoorStatus::None),
+            6 => Ok(DoorStatus::Closing),
+            7 => Ok(DoorStatus::Opening),
+            8 => Ok(DoorStatus::SafetyLocked),
+            9 => Ok(DoorStatus::LimitedOpened),
+            _ => Err(ObjectError::InvalidValue(format!(
+                "Invalid door status: {}",
+                value
+            ))),
+        }
+    }
+}
+
+/// Physical lock state (BACnetLockStatus)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum LockStatus {
+    /// Locked
+    Locked = 0,
+    /// Unlocked
+    Unlocked = 1,
+    /// Lock fault
+    LockFault = 2,
+    /// Lock status not used
+    Unused = 3,
+    /// Lock state unknown
+    Unknown = 4,
+}
+
+impl TryFrom<u32> for LockStatus {
+    type Error = ObjectError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            0 => Ok(LockStatus::Locked),
+            1 => Ok(LockStatus::Unlocked),
+            2 => Ok(LockStatus::LockFault),
+            3 => Ok(LockStatus::Unused),
+            4 => Ok(LockStatus::Unknown),
+            _ => Err(ObjectError::InvalidValue(format!(
+                "Invalid lock status: {}",
+                value
+            ))),
+        }
+    }
+}
+
+/// Whether the door is closed and locked (BACnetDoorSecuredStatus)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum DoorSecuredStatus {
+    /// Closed and locked
+    Secured = 0,
+    /// Open or unlocked
+    Unsecured = 1,
+    /// Door or lock state unknown
+    Unknown = 2,
+}
+
+impl TryFrom<u32> for DoorSecuredStatus {
+    type Error = ObjectError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            0 => Ok(DoorSecuredStatus::Secured),
+            1 => Ok(DoorSecuredStatus::Unsecured),
+            2 => Ok(DoorSecuredStatus::Unknown),
+            _ => Err(ObjectError::InvalidValue(format!(
+                "Invalid door secured status: {}",
+                value
+            ))),
+        }
+    }
+}
+
+/// Door alarm condition (BACnetDoorAlarmState)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum DoorAlarmState {
+    /// No alarm
+    Normal = 0,
+    /// Generic alarm
+    Alarm = 1,
+    /// Held open longer than Door_Open_Too_Long_Time
+    DoorOpenTooLong = 2,
+    /// Opened while locked
+    ForcedOpen = 3,
+    /// Tamper detected
+    Tamper = 4,
+    /// Door fault
+    DoorFault = 5,
+    /// Locked down
+    LockDown = 6,
+    /// Free access mode
+    FreeAccess = 7,
+    /// Opened for egress
+    EgressOpen = 8,
+}
+
+impl TryFrom<u32> for DoorAlarmState {
+    type Error = ObjectError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            0 => Ok(DoorAlarmState::Normal),
+            1 => Ok(DoorAlarmState::Alarm),
+            2 => Ok(DoorAlarmState::DoorOpenTooLong),
+            3 => Ok(DoorAlarmState::ForcedOpen),
+            4 => Ok(DoorAlarmState::Tamper),
+            5 => Ok(DoorAlarmState::DoorFault),
+            6 => Ok(DoorAlarmState::LockDown),
+            7 => Ok(DoorAlarmState::FreeAccess),
+            8 => Ok(DoorAlarmState::EgressOpen),
+            _ => Err(ObjectError::InvalidValue(format!(
+                "Invalid door alarm state: {}",
+                value
+            ))),
+        }
+    }
+}
+
+/// Access event reported by an Access Point (BACnetAccessEvent)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum AccessEvent {
+    /// No event
+    None = 0,
+    /// Access granted
+    Granted = 1,
+    /// Muster
+    Muster = 2,
+    /// Passback detected
+    PassbackDetected = 3,
+    /// Duress
+    Duress = 4,
+    /// Trace
+    Trace = 5,
+    /// Locked out after too many attempts
+    LockoutMaxAttempts = 6,
+    /// Locked out for another reason
+    LockoutOther = 7,
+    /// Lockout relinquished
+    LockoutRelinquished = 8,
+    /// Locked by a higher priority command
+    LockedByHigherPriority = 9,
+    /// Out of service
+    OutOfService = 10,
+    /// Out of service relinquished
+    OutOfServiceRelinquished = 11,
+    /// Accompanied by another credential
+    AccompanimentBy = 12,
+    /// Authentication factor read
+    AuthenticationFactorRead = 13,
+    /// Authorization delayed
+    AuthorizationDelayed = 14,
+    /// Verification required
+    VerificationRequired = 15,
+    /// No entry after access was granted
+    NoEntryAfterGranted = 16,
+    /// Denied: deny all
+    DeniedDenyAll = 128,
+    /// Denied: unknown credential
+    DeniedUnknownCredential = 129,
+    /// Denied: authentication unavailable
+    DeniedAuthenticationUnavailable = 130,
+    /// Denied: authentication factor timeout
+    DeniedAuthenticationFactorTimeout = 131,
+    /// Denied: incorrect authentication factor
+    DeniedIncorrectAuthenticationFactor = 132,
+    /// Denied: no access rights for the zone
+    DeniedZoneNoAccessRights = 133,
+    /// Denied: no access rights for the point
+    DeniedPointNoAccessRights = 134,
+    /// Denied: no access rights
+    DeniedNoAccessRights = 135,
+    /// Denied: outside the permitted time range
+    DeniedOutOfTimeRange = 136,
+    /// Denied: threat level
+    DeniedThreatLevel = 137,
+    /// Denied: passback
+    DeniedPassback = 138,
+    /// Denied: unexpected location usage
+    DeniedUnexpectedLocationUsage = 139,
+    /// Denied: too many attempts
+    DeniedMaxAttempts = 140,
+    /// Denied: lower occupancy limit
+    DeniedLowerOccupancyLimit = 141,
+    /// Denied: upper occupancy limit
+    DeniedUpperOccupancyLimit = 142,
+    /// Denied: authentication factor lost
+    DeniedAuthenticationFactorLost = 143,
+    /// Denied: authentication factor stolen
+    DeniedAuthenticationFactorStolen = 144,
+    /// Denied: authentication factor damaged
+    DeniedAuthenticationFactorDamaged = 145,
+    /// Denied: authentication factor destroyed
+    DeniedAuthenticationFactorDestroyed = 146,
+    /// Denied: authentication factor disabled
+    DeniedAuthenticationFactorDisabled = 147,
+    /// Denied: authentication factor error
+    DeniedAuthenticationFactorError = 148,
+    /// Denied: credential unassigned
+    DeniedCredentialUnassigned = 149,
+    /// Denied: credential not provisioned
+    DeniedCredentialNotProvisioned = 150,
+    /// Denied: credential not yet active
+    DeniedCredentialNotYetActive = 151,
+    /// Denied: credential expired
+    DeniedCredentialExpired = 152,
+    /// Denied: credential disabled manually
+    DeniedCredentialManualDisable = 153,
+    /// Denied: credential locked out
+    DeniedCredentialLockout = 154,
+    /// Denied: credential exceeded its days
+    DeniedCredentialMaxDays = 155,
+    /// Denied: credential exceeded its uses
+    DeniedCredentialMaxUses = 156,
+    /// Denied: credential inactive
+    DeniedCredentialInactivity = 157,
+    /// Denied: credential disabled
+    DeniedCredentialDisabled = 158,
+    /// Denied: no accompaniment
+    DeniedNoAccompaniment = 159,
+    /// Denied: incorrect accompaniment
+    DeniedIncorrectAccompaniment = 160,
+    /// Denied: lockout
+    DeniedLockout = 161,
+    /// Denied: verification failed
+    DeniedVerificationFailed = 162,
+    /// Denied: verification timeout
+    DeniedVerificationTimeout = 163,
+    /// Denied: other reason
+    DeniedOther = 164,
+}
+
+impl TryFrom<u32> for AccessEvent {
+    type Error = ObjectError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            0 => Ok(AccessEvent::None),
+            1 => Ok(AccessEvent::Granted),
+            2 => Ok(AccessEvent::Muster),
+            3 => Ok(AccessEvent::PassbackDetected),
+            4 => Ok(AccessEvent::Duress),
+            5 => Ok(AccessEvent::Trace),
+            6 => Ok(AccessEvent::LockoutMaxAttempts),
+            7 => Ok(AccessEvent::LockoutOther),
+            8 => Ok(AccessEvent::LockoutRelinquished),
+            9 => Ok(AccessEvent::LockedByHigherPriority),
+            10 => Ok(AccessEvent::OutOfService),
+            11 => Ok(AccessEvent::OutOfServiceRelinquished),
+            12 => Ok(AccessEvent::AccompanimentBy),
+            13 => Ok(AccessEvent::AuthenticationFactorRead),
+            14 => Ok(AccessEvent::AuthorizationDelayed),
+            15 => Ok(AccessEvent::VerificationRequired),
+            16 => Ok(AccessEvent::NoEntryAfterGranted),
+            128 => Ok(AccessEvent::DeniedDenyAll),
+            129 => Ok(AccessEvent::DeniedUnknownCredential),
+            130 => Ok(AccessEvent::DeniedAuthenticationUnavailable),
+            131 => Ok(AccessEvent::DeniedAuthenticationFactorTimeout),
+            132 => Ok(AccessEvent::DeniedIncorrectAuthenticationFactor),
+            133 => Ok(AccessEvent::DeniedZoneNoAccessRights),
+            134 => Ok(AccessEvent::DeniedPointNoAccessRights),
+            135 => Ok(AccessEvent::DeniedNoAccessRights),
+            136 => Ok(AccessEvent::DeniedOutOfTimeRange),
+            137 => Ok(AccessEvent::DeniedThreatLevel),
+            138 => Ok(AccessEvent::DeniedPassback),
+            139 => Ok(AccessEvent::DeniedUnexpectedLocationUsage),
+            140 => Ok(AccessEvent::DeniedMaxAttempts),
+            141 => Ok(AccessEvent::DeniedLowerOccupancyLimit),
+            142 => Ok(AccessEvent::DeniedUpperOccupancyLimit),
+            143 => Ok(AccessEvent::DeniedAuthenticationFactorLost),
+            144 => Ok(AccessEvent::DeniedAuthenticationFactorStolen),
+            145 => Ok(AccessEvent::DeniedAuthenticationFactorDamaged),
+            146 => Ok(AccessEvent::DeniedAuthenticationFactorDestroyed),
+            147 => Ok(AccessEvent::DeniedAuthenticationFactorDisabled),
+            148 => Ok(AccessEvent::DeniedAuthenticationFactorError),
+            149 => Ok(AccessEvent::DeniedCredentialUnassigned),
+            150 => Ok(AccessEvent::DeniedCredentialNotProvisioned),
+            151 => Ok(AccessEvent::DeniedCredentialNotYetActive),
+            152 => Ok(AccessEvent::DeniedCredentialExpired),
+            153 => Ok(AccessEvent::DeniedCredentialManualDisable),
+            154 => Ok(AccessEvent::DeniedCredentialLockout),
+            155 => Ok(AccessEvent::DeniedCredentialMaxDays),
+            156 => Ok(AccessEvent::DeniedCredentialMaxUses),
+            157 => Ok(AccessEvent::DeniedCredentialInactivity),
+            158 => Ok(AccessEvent::DeniedCredentialDisabled),
+            159 => Ok(AccessEvent::DeniedNoAccompaniment),
+            160 => Ok(AccessEvent::DeniedIncorrectAccompaniment),
+            161 => Ok(AccessEvent::DeniedLockout),
+            162 => Ok(AccessEvent::DeniedVerificationFailed),
+            163 => Ok(AccessEvent::DeniedVerificationTimeout),
+            164 => Ok(AccessEvent::DeniedOther),
+            _ => Err(ObjectError::InvalidValue(format!(
+                "Invalid access event: {}",
+                value
+            ))),
+        }
+    }
+}
+
+/// Progress of authentication at an Access Point (BACnetAuthenticationStatus)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum AuthenticationStatus {
+    /// Not ready to authenticate
+    NotReady = 0,
+    /// Ready for a credential
+    Ready = 1,
+    /// Authentication disabled
+    Disabled = 2,
+    /// Waiting for a further authentication factor
+    WaitingForAuthenticationFactor = 3,
+    /// Waiting for an accompanying credential
+    WaitingForAccompaniment = 4,
+    /// Waiting for verification
+    WaitingForVerification = 5,
+    /// Authentication in progress
+    InProgress = 6,
+}
+
+impl TryFrom<u32> for AuthenticationStatus {
+    type Error = ObjectError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            0 => Ok(AuthenticationStatus::NotReady),
+            1 => Ok(AuthenticationStatus::Ready),
+            2 => Ok(AuthenticationStatus::Disabled),
+            3 => Ok(AuthenticationStatus::WaitingForAuthenticationFactor),
+            4 => Ok(AuthenticationStatus::WaitingForAccompaniment),
+            5 => Ok(AuthenticationStatus::WaitingForVerification),
+            6 => Ok(AuthenticationStatus::InProgress),
+            _ => Err(ObjectError::InvalidValue(format!(
+                "Invalid authentication status: {}",
+                value
+            ))),
+        }
+    }
+}
+
+/// Occupancy of an Access Zone relative to its limits (BACnetAccessZoneOccupancyState)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum AccessZoneOccupancyState {
+    /// Between the limits
+    Normal = 0,
+    /// Below Occupancy_Lower_Limit
+    BelowLowerLimit = 1,
+    /// At Occupancy_Lower_Limit
+    AtLowerLimit = 2,
+    /// At Occupancy_Upper_Limit
+    AtUpperLimit = 3,
+    /// Above Occupancy_Upper_Limit
+    AboveUpperLimit = 4,
+    /// Occupancy counting disabled
+    Disabled = 5,
+    /// Occupancy counting not supported
+    NotSupported = 6,
+}
+
+impl TryFrom<u32> for AccessZoneOccupancyState {
+    type Error = ObjectError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            0 => Ok(AccessZoneOccupancyState::Normal),
+            1 => Ok(AccessZoneOccupancyState::BelowLowerLimit),
+            2 => Ok(AccessZoneOccupancyState::AtLowerLimit),
+            3 => Ok(AccessZoneOccupancyState::AtUpperLimit),
+            4 => Ok(AccessZoneOccupancyState::AboveUpperLimit),
+            5 => Ok(AccessZoneOccupancyState::Disabled),
+            6 => Ok(AccessZoneOccupancyState::NotSupported),
+            _ => Err(ObjectError::InvalidValue(format!(
+                "Invalid access zone occupancy state: {}",
+                value
+            ))),
+        }
+    }
+}
+
+fn tenths(value: u32) -> Duration {
+    Duration::from_millis(value as u64 * 100)
+}
+
+fn optional_enumerated(value: Option<u32>) -> Result<PropertyValue> {
+    value
+        .map(PropertyValue::Enumerated)
+        .ok_or(ObjectError::UnknownProperty)
+}
+
+fn object_list_value(objects: &[ObjectIdentifier]) -> PropertyValue {
+    PropertyValue::Array(
+        objects
+            .iter()
+            .copied()
+            .map(PropertyValue::ObjectIdentifier)
+            .collect(),
+    )
+}
+
+/// Access Door object
+#[derive(Debug, Clone)]
+pub struct AccessDoor {
+    /// Object identifier
+    pub identifier: ObjectIdentifier,
+    /// Object name
+    pub object_name: String,
+    /// Description
+    pub description: String,
+    /// Present value (the commanded door state)
+    pub present_value: DoorValue,
+    /// Event state
+    pub event_state: EventState,
+    /// Reliability
+    pub reliability: Reliability,
+    /// Out of service
+    pub out_of_service: bool,
+    /// Priority array and relinquish default
+    pub commandable: Commandable<DoorValue>,
+    /// Door position
+    pub door_status: Option<DoorStatus>,
+    /// Lock state
+    pub lock_status: Option<LockStatus>,
+    /// Whether the door is closed and locked
+    pub secured_status: Option<DoorSecuredStatus>,
+    /// Other doors operated together with this one
+    pub door_members: Option<Vec<DeviceObjectPropertyReference>>,
+    /// Tenths of a second the door stays unlocked after PULSE_UNLOCK
+    pub door_pulse_time: u32,
+    /// Tenths of a second the door stays unlocked after EXTENDED_PULSE_UNLOCK
+    pub door_extended_pulse_time: u32,
+    /// Tenths of a second between an unlock command and unlocking
+    pub door_unlock_delay_time: Option<u32>,
+    /// Tenths of a second the door may stay open before DOOR_OPEN_TOO_LONG
+    pub door_open_too_long_time: u32,
+    /// Door alarm condition
+    pub door_alarm_state: Option<DoorAlarmState>,
+    /// Alarm states that are not reported
+    pub masked_alarm_values: Option<Vec<DoorAlarmState>>,
+    /// Priority and time remaining of the running pulse unlock
+    pulse: Option<(u8, Duration)>,
+    /// How long the door has been open
+    open_time: Duration,
+}
+
+impl AccessDoor {
+    /// Create a new Access Door that is locked by default
+    pub fn new(instance: u32, object_name: String) -> Self {
+        Self {
+            identifier: ObjectIdentifier::new(ObjectType::AccessDoor, instance),
+            object_name,
+            description: String::new(),
+            present_value: DoorValue::Lock,
+            event_state: EventState::Normal,
+            reliability: Reliability::NoFaultDetected,
+            out_of_service: false,
+            commandable: Commandable::new(DoorValue::Lock),
+            door_status: Some(DoorStatus::Closed),
+            lock_status: Some(LockStatus::Locked),
+            secured_status: Some(DoorSecuredStatus::Secured),
+            door_members: None,
+            door_pulse_time: 50,
+            door_extended_pulse_time: 200,
+            door_unlock_delay_time: None,
+            door_open_too_long_time: 300,
+            door_alarm_state: Some(DoorAlarmState::Normal),
+            masked_alarm_values: None,
+            pulse: None,
+            open_time: Duration::ZERO,
+        }
+    }
+
+    /// Write to priority array at specified priority level (1-16)
+    ///
+    /// A pulse unlock starts its timer; when it expires the command at that
+    /// priority is relinquished.
+    pub fn write_priority(&mut self, priority: u8, value: Option<DoorValue>) -> Result<()> {
+        self.commandable.write(priority, value)?;
+        match value {
+            Some(DoorValue::PulseUnlock) => {
+                self.pulse = Some((priority, tenths(self.door_pulse_time)));
+            }
+            Some(DoorValue::ExtendedPulseUnlock) => {
+                self.pulse = Some((priority, tenths(self.door_extended_pulse_time)));
+            }
+            _ => {
+                if self.pulse.is_some_and(|(pulsed, _)| pulsed == priority) {
+                    self.pulse = None;
+                }
+            }
+        }
+        self.update_present_value();
+        Ok(())
+    }
+
+    fn update_present_value(&mut self) {
+        self.present_value = *self.commandable.current();
+    }
+
+    /// Get the effective priority level for current present value
+    pub fn get_effective_priority(&self) -> Option<u8> {
+        self.commandable.active_priority()
+    }
+
+    /// Whether the door is currently commanded unlocked
+    pub fn is_unlock_commanded(&self) -> bool {
+        self.present_value != DoorValue::Lock
+    }
+
+    /// Report the door position from the door sensor
+    ///
+    /// Opening a door that is commanded locked raises FORCED_OPEN; closing the
+    /// door clears FORCED_OPEN and DOOR_OPEN_TOO_LONG.
+    pub fn update_door_status(&mut self, status: DoorStatus) {
+        if self.door_status.is_none() {
+            return;
+        }
+        let was_open = self.door_status == Some(DoorStatus::Opened);
+        self.door_status = Some(status);
+        match status {
+            DoorStatus::Opened if !was_open => {
+                self.open_time = Duration::ZERO;
+                if !self.is_unlock_commanded() && self.lock_status != Some(LockStatus::Unlocked) {
+                    self.raise_alarm(DoorAlarmState::ForcedOpen);
+                }
+            }
+            DoorStatus::Closed => {
+                if matches!(
+                    self.door_alarm_state,
+                    Some(DoorAlarmState::ForcedOpen | DoorAlarmState::DoorOpenTooLong)
+                ) {
+                    self.door_alarm_state = Some(DoorAlarmState::Normal);
+                }
+            }
+            _ => {}
+        }
+        self.update_secured_status();
+    }
+
+    /// Report the lock state from the lock sensor
+    pub fn update_lock_status(&mut self, status: LockStatus) {
+        if self.lock_status.is_none() {
+            return;
+        }
+        self.lock_status = Some(status);
+        self.update_secured_status();
+    }
+
+    fn update_secured_status(&mut self) {
+        if self.secured_status.is_none() {
+            return;
+        }
+        let status = match (self.door_status, self.lock_status) {
+            (Some(DoorStatus::Closed), Some(LockStatus::Locked)) => DoorSecuredStatus::Secured,
+            (Some(DoorStatus::Unknown), _) | (_, Some(LockStatus::Unknown)) => {
+                DoorSecuredStatus::Unknown
+            }
+            _ => DoorSecuredStatus::Unsecured,
+        };
+        self.secured_status = Some(status);
+    }
+
+    fn raise_alarm(&mut self, state: DoorAlarmState) {
+        let masked = self
+            .masked_alarm_values
+            .as_ref()
+            .is_some_and(|masked| masked.contains(&state));
+        if let Some(current) = self.door_alarm_state.as_mut() {
+            if !masked {
+                *current = state;
+            }
+        }
+    }
+}
+
+impl BacnetObject for AccessDoor {
+    fn identifier(&self) -> ObjectIdentifier {
+        self.identifier
+    }
+
+    fn get_property(&self, property: PropertyIdentifier) -> Result<PropertyValue> {
+        match property {
+            PropertyIdentifier::ObjectIdentifier => {
+                Ok(PropertyValue::ObjectIdentifier(self.identifier))
+            }
+            PropertyIdentifier::ObjectName => {
+                Ok(PropertyValue::CharacterString(self.object_name.clone()))
+            }
+            PropertyIdentifier::ObjectType => {
+                Ok(PropertyValue::Enumerated(u32::from(ObjectType::AccessDoor)))
+            }
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::PresentValue => {
+                Ok(PropertyValue::Enumerated(self.present_value as u32))
+            }
+            PropertyIdentifier::StatusFlags => Ok(status_flags_bit_string(current_status_flags(
+                0,
+                self.event_state,
+                self.reliability,
+                self.out_of_service,
+            ))),
+            PropertyIdentifier::EventState => {
+                Ok(PropertyValue::Enumerated(self.event_state as u32))
+            }
+            PropertyIdentifier::Reliability => {
+                Ok(PropertyValue::Enumerated(self.reliability as u32))
+            }
+            PropertyIdentifier::OutOfService => Ok(PropertyValue::Boolean(self.out_of_service)),
+            PropertyIdentifier::PriorityArray => Ok(self
+                .commandable
+                .priority_array_value(|&value| PropertyValue::Enumerated(value as u32))),
+            PropertyIdentifier::RelinquishDefault => Ok(PropertyValue::Enumerated(
+                *self.commandable.relinquish_default() as u32,
+            )),
+            PropertyIdentifier::DoorStatus => {
+                optional_enumerated(self.door_status.map(|s| s as u32))
+            }
+            PropertyIdentifier::LockStatus => {
+                optional_enumerated(self.lock_status.map(|s| s as u32))
+            }
+            PropertyIdentifier::SecuredStatus => {
+                optional_enumerated(self.secured_status.map(|s| s as u32))
+            }
+            PropertyIdentifier::DoorMembers => self
+                .door_members
+                .as_ref()
+                .map(|members| {
+                    PropertyValue::Array(
+                        members
+                            .iter()
+                            .map(DeviceObjectPropertyReference::to_property_value)
+                            .collect(),
+                    )
+                })
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::DoorPulseTime => {
+                Ok(PropertyValue::UnsignedInteger(self.door_pulse_time))
+            }
+            PropertyIdentifier::DoorExtendedPulseTime => Ok(PropertyValue::UnsignedInteger(
+                self.door_extended_pulse_time,
+            )),
+            PropertyIdentifier::DoorUnlockDelayTime => self
+                .door_unlock_delay_time
+                .map(PropertyValue::UnsignedInteger)
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::DoorOpenTooLongTime => {
+                Ok(PropertyValue::UnsignedInteger(self.door_open_too_long_time))
+            }
+            PropertyIdentifier::DoorAlarmState => {
+                optional_enumerated(self.door_alarm_state.map(|s| s as u32))
+            }
+            PropertyIdentifier::MaskedAlarmValues => self
+                .masked_alarm_values
+                .as_ref()
+                .map(|masked| {
+                    PropertyValue::List(
+                        masked
+                            .iter()
+                            .map(|&state| PropertyValue::Enumerated(state as u32))
+                            .collect(),
+                    )
+                })
+                .ok_or(ObjectError::UnknownProperty),
+            _ => Err(ObjectError::UnknownProperty),
+        }
+    }
+
+    fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        self.set_property_with_priority(property, value, DEFAULT_COMMAND_PRIORITY)
+    }
+
+    fn set_property_with_priority(
+        &mut self,
+        property: PropertyIdentifier,
+        value: PropertyValue,
+        priority: u8,
+    ) -> Result<()> {
+        let unsigned = |value: PropertyValue| match value {
+            PropertyValue::UnsignedInteger(v) => Ok(v),
+            _ => Err(ObjectError::InvalidPropertyType),
+        };
+        match property {
+            PropertyIdentifier::ObjectName => {
+                if let PropertyValue::CharacterString(name) = value {
+                    self.object_name = name;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::PresentValue => match value {
+                PropertyValue::Enumerated(val) => {
+                    self.write_priority(priority, Some(DoorValue::try_from(val)?))
+                }
+                // Writing NULL relinquishes the command at this priority
+                PropertyValue::Null => self.write_priority(priority, None),
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            PropertyIdentifier::OutOfService => {
+                if let PropertyValue::Boolean(oos) = value {
+                    self.out_of_service = oos;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::RelinquishDefault => {
+                if let PropertyValue::Enumerated(val) = value {
+                    self.commandable
+                        .set_relinquish_default(DoorValue::try_from(val)?);
+                    self.update_present_value();
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::DoorPulseTime => {
+                self.door_pulse_time = unsigned(value)?;
+                Ok(())
+            }
+            PropertyIdentifier::DoorExtendedPulseTime => {
+                self.door_extended_pulse_time = unsigned(value)?;
+                Ok(())
+            }
+            PropertyIdentifier::DoorUnlockDelayTime if self.door_unlock_delay_time.is_some() => {
+                self.door_unlock_delay_time = Some(unsigned(value)?);
+                Ok(())
+            }
+            PropertyIdentifier::DoorOpenTooLongTime => {
+                self.door_open_too_long_time = unsigned(value)?;
+                Ok(())
+            }
+            PropertyIdentifier::MaskedAlarmValues if self.masked_alarm_values.is_some() => {
+                if let PropertyValue::List(items) = value {
+                    let masked = items
+                        .into_iter()
+                        .map(|item| match item {
+                            PropertyValue::Enumerated(state) => DoorAlarmState::try_from(state),
+                            _ => Err(ObjectError::InvalidPropertyType),
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    self.masked_alarm_values = Some(masked);
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            _ => Err(ObjectError::PropertyNotWritable),
+        }
+    }
+
+    fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
+        match property {
+            PropertyIdentifier::ObjectName
+            | PropertyIdentifier::Description
+            | PropertyIdentifier::PresentValue
+            | PropertyIdentifier::OutOfService
+            | PropertyIdentifier::RelinquishDefault
+            | PropertyIdentifier::DoorPulseTime
+            | PropertyIdentifier::DoorExtendedPulseTime
+            | PropertyIdentifier::DoorOpenTooLongTime => true,
+            PropertyIdentifier::DoorUnlockDelayTime => self.door_unlock_delay_time.is_some(),
+            PropertyIdentifier::MaskedAlarmValues => self.masked_alarm_values.is_some(),
+            _ => false,
+        }
+    }
+
+    fn property_list(&self) -> Vec<PropertyIdentifier> {
+        let mut properties = vec![
+            PropertyIdentifier::ObjectIdentifier,
+            PropertyIdentifier::ObjectName,
+            PropertyIdentifier::ObjectType,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::PresentValue,
+            PropertyIdentifier::StatusFlags,
+            PropertyIdentifier::EventState,
+            PropertyIdentifier::Reliability,
+            PropertyIdentifier::OutOfService,
+            PropertyIdentifier::PriorityArray,
+            PropertyIdentifier::RelinquishDefault,
+        ];
+        let optional = [
+            (self.door_status.is_some(), PropertyIdentifier::DoorStatus),
+            (self.lock_status.is_some(), PropertyIdentifier::LockStatus),
+            (
+                self.secured_status.is_some(),
+                PropertyIdentifier::SecuredStatus,
+            ),
+            (self.door_members.is_some(), PropertyIdentifier::DoorMembers),
+        ];
+        properties.extend(optional.iter().filter(|(p, _)| *p).map(|&(_, id)| id));
+        properties.extend([
+            PropertyIdentifier::DoorPulseTime,
+            PropertyIdentifier::DoorExtendedPulseTime,
+        ]);
+        if self.door_unlock_delay_time.is_some() {
+            properties.push(PropertyIdentifier::DoorUnlockDelayTime);
+        }
+        properties.push(PropertyIdentifier::DoorOpenTooLongTime);
+        if self.door_alarm_state.is_some() {
+            properties.push(PropertyIdentifier::DoorAlarmState);
+        }
+        if self.masked_alarm_values.is_some() {
+            properties.push(PropertyIdentifier::MaskedAlarmValues);
+        }
+        properties
+    }
+
+    fn advance_time(&mut self, elapsed: Duration) {
+        if let Some((priority, remaining)) = self.pulse {
+            if elapsed >= remaining {
+                self.pulse = None;
+                // The priority was checked when the pulse was commanded
+                let _ = self.commandable.relinquish(priority);
+                self.update_present_value();
+            } else {
+                self.pulse = Some((priority, remaining - elapsed));
+            }
+        }
+
+        if self.door_status == Some(DoorStatus::Opened) {
+            self.open_time = self.open_time.saturating_add(elapsed);
+            let limit = tenths(self.door_open_too_long_time);
+            if self.door_open_too_long_time > 0
+                && self.open_time >= limit
+                && self.door_alarm_state == Some(DoorAlarmState::Normal)
+            {
+                self.raise_alarm(DoorAlarmState::DoorOpenTooLong);
+            }
+        }
+    }
+}
+
+/// Access Point object
+#[derive(Debug, Clone)]
+pub struct AccessPoint {
+    /// Object identifier
+    pub identifier: ObjectIdentifier,
+    /// Object name
+    pub object_name: String,
+    /// Description
+    pub description: String,
+    /// Event state
+    pub event_state: EventState,
+    /// Reliability
+    pub reliability: Reliability,
+    /// Out of service
+    pub out_of_service: bool,
+    /// Progress of the current authentication
+    pub authentication_status: AuthenticationStatus,
+    /// Most recent access event
+    pub access_event: AccessEvent,
+    /// Sequence number of the most recent access event
+    pub access_event_tag: u32,
+    /// Time of the most recent access event
+    pub access_event_time: Option<BacnetDateTime>,
+    /// Credential that caused the most recent access event
+    pub access_event_credential: Option<ObjectIdentifier>,
+    /// Access Doors in this device opened when access is granted
+    pub access_doors: Vec<ObjectIdentifier>,
+    /// Priority used to command the Access Doors
+    pub priority_for_writing: u8,
+    /// Zone a credential leaves through this point
+    pub zone_from: Option<ObjectIdentifier>,
+    /// Zone a credential enters through this point
+    pub zone_to: Option<ObjectIdentifier>,
+    pending_writes: Vec<PropertyWrite>,
+}
+
+impl AccessPoint {
+    /// Create a new Access Point with no doors
+    pub fn new(instance: u32, object_name: String) -> Self {
+        Self {
+            identifier: ObjectIdentifier::new(ObjectType::AccessPoint, instance),
+            object_name,
+            description: String::new(),
+            event_state: EventState::Normal,
+            reliability: Reliability::NoFaultDetected,
+            out_of_service: false,
+            authentication_status: AuthenticationStatus::Ready,
+            access_event: AccessEvent::None,
+            access_event_tag: 0,
+            access_event_time: None,
+            access_event_credential: None,
+            access_doors: Vec::new(),
+            priority_for_writing: DEFAULT_COMMAND_PRIORITY,
+            zone_from: None,
+            zone_to: None,
+            pending_writes: Vec::new(),
+        }
+    }
+
+    /// Record an access event from the authentication logic
+    ///
+    /// A granted access queues a PULSE_UNLOCK of every Access Door at
+    /// Priority_For_Writing. Events are ignored while out of service.
+    pub fn record_access_event(
+        &mut self,
+        event: AccessEvent,
+        credential: Option<ObjectIdentifier>,
+        timestamp: Option<BacnetDateTime>,
+    ) {
+        if self.out_of_service {
+            return;
+        }
+        self.access_event = event;
+        self.access_event_tag = self.access_event_tag.wrapping_add(1);
+        self.access_event_time = timestamp.or_else(current_date_time);
+        self.access_event_credential = credential;
+        self.authentication_status = AuthenticationStatus::Ready;
+        if event == AccessEvent::Granted {
+            let priority = self.priority_for_writing;
+            self.pending_writes
+                .extend(self.access_doors.iter().map(|&door| PropertyWrite {
+                    reference: DeviceObjectPropertyReference::new(
+                        door,
+                        PropertyIdentifier::PresentValue,
+                    ),
+                    value: PropertyValue::Enumerated(DoorValue::PulseUnlock as u32),
+                    priority,
+                }));
+        }
+    }
+}
+
+impl BacnetObject for AccessPoint {
+    fn identifier(&self) -> ObjectIdentifier {
+        self.identifier
+    }
+
+    fn get_property(&self, property: PropertyIdentifier) -> Result<PropertyValue> {
+        match property {
+            PropertyIdentifier::ObjectIdentifier => {
+                Ok(PropertyValue::ObjectIdentifier(self.identifier))
+            }
+            PropertyIdentifier::ObjectName => {
+                Ok(PropertyValue::CharacterString(self.object_name.clone()))
+            }
+            PropertyIdentifier::ObjectType => Ok(PropertyValue::Enumerated(u32::from(
+                ObjectType::AccessPoint,
+            ))),
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::StatusFlags => Ok(status_flags_bit_string(current_status_flags(
+                0,
+                self.event_state,
+                self.reliability,
+                self.out_of_service,
+            ))),
+            PropertyIdentifier::EventState => {
+                Ok(PropertyValue::Enumerated(self.event_state as u32))
+            }
+            PropertyIdentifier::Reliability => {
+                Ok(PropertyValue::Enumerated(self.reliability as u32))
+            }
+            PropertyIdentifier::OutOfService => Ok(PropertyValue::Boolean(self.out_of_service)),
+            PropertyIdentifier::AuthenticationStatus => {
+                Ok(PropertyValue::Enumerated(self.authentication_status as u32))
+            }
+            PropertyIdentifier::AccessEvent => {
+                Ok(PropertyValue::Enumerated(self.access_event as u32))
+            }
+            PropertyIdentifier::AccessEventTag => {
+                Ok(PropertyValue::UnsignedInteger(self.access_event_tag))
+            }
+            PropertyIdentifier::AccessEventTime => Ok(date_time_value(self.access_event_time)),
+            PropertyIdentifier::AccessEventCredential => Ok(self
+                .access_event_credential
+                .map(PropertyValue::ObjectIdentifier)
+                .unwrap_or(PropertyValue::Null)),
+            PropertyIdentifier::AccessDoors => Ok(object_list_value(&self.access_doors)),
+            PropertyIdentifier::PriorityForWriting => Ok(PropertyValue::UnsignedInteger(
+                self.priority_for_writing as u32,
+            )),
+            PropertyIdentifier::ZoneFrom => self
+                .zone_from
+                .map(PropertyValue::ObjectIdentifier)
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::ZoneTo => self
+                .zone_to
+                .map(PropertyValue::ObjectIdentifier)
+                .ok_or(ObjectError::UnknownProperty),
+            _ => Err(ObjectError::UnknownProperty),
+        }
+    }
+
+    fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        match property {
+            PropertyIdentifier::ObjectName => {
+                if let PropertyValue::CharacterString(name) = value {
+                    self.object_name = name;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::OutOfService => {
+                if let PropertyValue::Boolean(oos) = value {
+                    self.out_of_service = oos;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::PriorityForWriting => match value {
+                PropertyValue::UnsignedInteger(priority @ 1..=16) => {
+                    self.priority_for_writing = priority as u8;
+                    Ok(())
+                }
+                PropertyValue::UnsignedInteger(_) => Err(ObjectError::InvalidValue(
+                    "Priority must be 1-16".to_string(),
+                )),
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            _ => Err(ObjectError::PropertyNotWritable),
+        }
+    }
+
+    fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
+        matches!(
+            property,
+            PropertyIdentifier::ObjectName
+                | PropertyIdentifier::Description
+                | PropertyIdentifier::OutOfService
+                | PropertyIdentifier::PriorityForWriting
+        )
+    }
+
+    fn property_list(&self) -> Vec<PropertyIdentifier> {
+        let mut properties = vec![
+            PropertyIdentifier::ObjectIdentifier,
+            PropertyIdentifier::ObjectName,
+            PropertyIdentifier::ObjectType,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::StatusFlags,
+            PropertyIdentifier::EventState,
+            PropertyIdentifier::Reliability,
+            PropertyIdentifier::OutOfService,
+            PropertyIdentifier::AuthenticationStatus,
+            PropertyIdentifier::AccessEvent,
+            PropertyIdentifier::AccessEventTag,
+            PropertyIdentifier::AccessEventTime,
+            PropertyIdentifier::AccessEventCredential,
+            PropertyIdentifier::AccessDoors,
+            PropertyIdentifier::PriorityForWriting,
+        ];
+        if self.zone_from.is_some() {
+            properties.push(PropertyIdentifier::ZoneFrom);
+        }
+        if self.zone_to.is_some() {
+            properties.push(PropertyIdentifier::ZoneTo);
+        }
+        properties
+    }
+
+    fn take_pending_writes(&mut self) -> Vec<PropertyWrite> {
+        core::mem::take(&mut self.pending_writes)
+    }
+}
+
+/// Access Zone object
+#[derive(Debug, Clone)]
+pub struct AccessZone {
+    /// Object identifier
+    pub identifier: ObjectIdentifier,
+    /// Object name
+    pub object_name: String,
+    /// Description
+    pub description: String,
+    /// Event state
+    pub event_state: EventState,
+    /// Reliability
+    pub reliability: Reliability,
+    /// Out of service
+    pub out_of_service: bool,
+    /// Identifier of the zone across devices
+    pub global_identifier: u32,
+    /// Occupancy relative to the limits
+    pub occupancy_state: AccessZoneOccupancyState,
+    /// Number of credentials in the zone, if counted
+    pub occupancy_count: Option<u32>,
+    /// Whether occupancy counting is enabled
+    pub occupancy_count_enable: bool,
+    /// Occupancy upper limit
+    pub occupancy_upper_limit: Option<u32>,
+    /// Occupancy lower limit
+    pub occupancy_lower_limit: Option<u32>,
+    /// Credentials currently in the zone
+    pub credentials_in_zone: Vec<ObjectIdentifier>,
+    /// Access Points that lead into the zone
+    pub entry_points: Vec<ObjectIdentifier>,
+    /// Access Points that lead out of the zone
+    pub exit_points: Vec<ObjectIdentifier>,
+}
+
+impl AccessZone {
+    /// Create a new Access Zone with occupancy counting enabled
+    pub fn new(instance: u32, object_name: String) -> Self {
+        Self {
+            identifier: ObjectIdentifier::new(ObjectType::AccessZone, instance),
+            object_name,
+            description: String::new(),
+            event_state: EventState::Normal,
+            reliability: Reliability::NoFaultDetected,
+            out_of_service: false,
+            global_identifier: 0,
+            occupancy_state: AccessZoneOccupancyState::Normal,
+            occupancy_count: Some(0),
+            occupancy_count_enable: true,
+            occupancy_upper_limit: None,
+            occupancy_lower_limit: None,
+            credentials_in_zone: Vec::new(),
+            entry_points: Vec::new(),
+            exit_points: Vec::new(),
+        }
+    }
+
+    /// Record a credential passing `point`, updating the occupancy if the point
+    /// is one of the zone's entry or exit points
+    pub fn record_passage(&mut self, point: ObjectIdentifier, credential: ObjectIdentifier) {
+        if self.entry_points.contains(&point) {
+            if !self.credentials_in_zone.contains(&credential) {
+                self.credentials_in_zone.push(credential);
+            }
+            if self.occupancy_count_enable {
+                if let Some(count) = self.occupancy_count.as_mut() {
+                    *count = count.saturating_add(1);
+                }
+            }
+        } else if self.exit_points.contains(&point) {
+            self.credentials_in_zone.retain(|&c| c != credential);
+            if self.occupancy_count_enable {
+                if let Some(count) = self.occupancy_count.as_mut() {
+                    *count = count.saturating_sub(1);
+                }
+            }
+        }
+        self.update_occupancy_state();
+    }
+
+    fn update_occupancy_state(&mut self) {
+        self.occupancy_state = match self.occupancy_count {
+            None => AccessZoneOccupancyState::NotSupported,
+            Some(_) if !self.occupancy_count_enable => AccessZoneOccupancyState::Disabled,
+            Some(count) => match (self.occupancy_lower_limit, self.occupancy_upper_limit) {
+                (_, Some(upper)) if count > upper => AccessZoneOccupancyState::AboveUpperLimit,
+                (_, Some(upper)) if count == upper => AccessZoneOccupancyState::AtUpperLimit,
+                (Some(lower), _) if count < lower => AccessZoneOccupancyState::BelowLowerLimit,
+                (Some(lower), _) if count == lower => AccessZoneOccupancyState::AtLowerLimit,
+                _ => AccessZoneOccupancyState::Normal,
+            },
+        };
+    }
+}
+
+impl BacnetObject for AccessZone {
+    fn identifier(&self) -> ObjectIdentifier {
+        self.identifier
+    }
+
+    fn get_property(&self, property: PropertyIdentifier) -> Result<PropertyValue> {
+        let optional_unsigned = |value: Option<u32>| {
+            value
+                .map(PropertyValue::UnsignedInteger)
+                .ok_or(ObjectError::UnknownProperty)
+        };
+        match property {
+            PropertyIdentifier::ObjectIdentifier => {
+                Ok(PropertyValue::ObjectIdentifier(self.identifier))
+            }
+            PropertyIdentifier::ObjectName => {
+                Ok(PropertyValue::CharacterString(self.object_name.clone()))
+            }
+            PropertyIdentifier::ObjectType => {
+                Ok(PropertyValue::Enumerated(u32::from(ObjectType::AccessZone)))
+            }
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::GlobalIdentifier => {
+                Ok(PropertyValue::UnsignedInteger(self.global_identifier))
+            }
+            PropertyIdentifier::OccupancyState => {
+                Ok(PropertyValue::Enumerated(self.occupancy_state as u32))
+            }
+            PropertyIdentifier::StatusFlags => Ok(status_flags_bit_string(current_status_flags(
+                0,
+                self.event_state,
+                self.reliability,
+                self.out_of_service,
+            ))),
+            PropertyIdentifier::EventState => {
+                Ok(PropertyValue::Enumerated(self.event_state as u32))
+            }
+            PropertyIdentifier::Reliability => {
+                Ok(PropertyValue::Enumerated(self.reliability as u32))
+            }
+            PropertyIdentifier::OutOfService => Ok(PropertyValue::Boolean(self.out_of_service)),
+            PropertyIdentifier::OccupancyCount => optional_unsigned(self.occupancy_count),
+            PropertyIdentifier::OccupancyCountEnable if self.occupancy_count.is_some() => {
+                Ok(PropertyValue::Boolean(self.occupancy_count_enable))
+            }
+            PropertyIdentifier::OccupancyUpperLimit => {
+                optional_unsigned(self.occupancy_upper_limit)
+            }
+            PropertyIdentifier::OccupancyLowerLimit => {
+                optional_unsigned(self.occupancy_lower_limit)
+            }
+            PropertyIdentifier::CredentialsInZone => Ok(PropertyValue::List(
+                self.credentials_in_zone
+                    .iter()
+                    .copied()
+                    .map(PropertyValue::ObjectIdentifier)
+                    .collect(),
+            )),
+            PropertyIdentifier::EntryPoints => Ok(object_list_value(&self.entry_points)),
+            PropertyIdentifier::ExitPoints => Ok(object_list_value(&self.exit_points)),
+            _ => Err(ObjectError::UnknownProperty),
+        }
+    }
+
+    fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        match property {
+            PropertyIdentifier::ObjectName => {
+                if let PropertyValue::CharacterString(name) = value {
+                    self.object_name = name;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::OutOfService => {
+                if let PropertyValue::Boolean(oos) = value {
+                    self.out_of_service = oos;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::GlobalIdentifier => {
+                if let PropertyValue::UnsignedInteger(id) = value {
+                    self.global_identifier = id;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::OccupancyCountEnable if self.occupancy_count.is_some() => {
+                if let PropertyValue::Boolean(enable) = value {
+                    self.occupancy_count_enable = enable;
+                    self.update_occupancy_state();
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::OccupancyUpperLimit if self.occupancy_upper_limit.is_some() => {
+                if let PropertyValue::UnsignedInteger(limit) = value {
+                    self.occupancy_upper_limit = Some(limit);
+                    self.update_occupancy_state();
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::OccupancyLowerLimit if self.occupancy_lower_limit.is_some() => {
+                if let PropertyValue::UnsignedInteger(limit) = value {
+                    self.occupancy_lower_limit = Some(limit);
+                    self.update_occupancy_state();
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            _ => Err(ObjectError::PropertyNotWritable),
+        }
+    }
+
+    fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
+        match property {
+            PropertyIdentifier::ObjectName
+            | PropertyIdentifier::Description
+            | PropertyIdentifier::OutOfService
+            | PropertyIdentifier::GlobalIdentifier => true,
+            PropertyIdentifier::OccupancyCountEnable => self.occupancy_count.is_some(),
+            PropertyIdentifier::OccupancyUpperLimit => self.occupancy_upper_limit.is_some(),
+            PropertyIdentifier::OccupancyLowerLimit => self.occupancy_lower_limit.is_some(),
+            _ => false,
+        }
+    }
+
+    fn property_list(&self) -> Vec<PropertyIdentifier> {
+        let mut properties = vec![
+            PropertyIdentifier::ObjectIdentifier,
+            PropertyIdentifier::ObjectName,
+            PropertyIdentifier::ObjectType,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::GlobalIdentifier,
+            PropertyIdentifier::OccupancyState,
+            PropertyIdentifier::StatusFlags,
+            PropertyIdentifier::EventState,
+            PropertyIdentifier::Reliability,
+            PropertyIdentifier::OutOfService,
+        ];
+        if self.occupancy_count.is_some() {
+            properties.push(PropertyIdentifier::OccupancyCount);
+            properties.push(PropertyIdentifier::OccupancyCountEnable);
+        }
+        if self.occupancy_upper_limit.is_some() {
+            properties.push(PropertyIdentifier::OccupancyUpperLimit);
+        }
+        if self.occupancy_lower_limit.is_some() {
+            properties.push(PropertyIdentifier::OccupancyLowerLimit);
+        }
+        properties.extend([
+            PropertyIdentifier::CredentialsInZone,
+            PropertyIdentifier::EntryPoints,
+            PropertyIdentifier::ExitPoints,
+        ]);
+        properties
+    }
+}
+
+/// Authentication factor read from a credential (BACnetAuthenticationFactor)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthenticationFactor {
+    /// Format type (BACnetAuthenticationFactorType, 0 = undefined)
+    pub format_type: u32,
+    /// Format class, identifying how `value` is interpreted
+    pub format_class: u32,
+    /// Raw factor data, such as card data or a PIN
+    pub value: Vec<u8>,
+}
+
+impl AuthenticationFactor {
+    /// An undefined factor, reported before any credential has been read
+    pub fn undefined() -> Self {
+        Self {
+            format_type: 0,
+            format_class: 0,
+            value: Vec::new(),
+        }
+    }
+
+    fn to_property_value(&self) -> PropertyValue {
+        PropertyValue::List(vec![
+            PropertyValue::Enumerated(self.format_type),
+            PropertyValue::UnsignedInteger(self.format_class),
+            PropertyValue::OctetString(self.value.clone()),
+        ])
+    }
+
+    fn from_property_value(value: &PropertyValue) -> Result<Self> {
+        match value {
+            PropertyValue::List(items) => match items.as_slice() {
+                [PropertyValue::Enumerated(format_type), PropertyValue::UnsignedInteger(format_class), PropertyValue::OctetString(data)] => {
+                    Ok(Self {
+                        format_type: *format_type,
+                        format_class: *format_class,
+                        value: data.clone(),
+                    })
+                }
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            _ => Err(ObjectError::InvalidPropertyType),
+        }
+    }
+}
+
+/// Credential Data Input object
+#[derive(Debug, Clone)]
+pub struct CredentialDataInput {
+    /// Object identifier
+    pub identifier: ObjectIdentifier,
+    /// Object name
+    pub object_name: String,
+    /// Description
+    pub description: String,
+    /// Present value (the factor last read)
+    pub present_value: AuthenticationFactor,
+    /// Event state
+    pub event_state: EventState,
+    /// Reliability
+    pub reliability: Reliability,
+    /// Out of service
+    pub out_of_service: bool,
+    /// Format types the reader accepts; empty accepts any
+    pub supported_formats: Vec<u32>,
+    /// Format classes the reader accepts; empty accepts any
+    pub supported_format_classes: Vec<u32>,
+    /// Time the present value was last updated
+    pub update_time: Option<BacnetDateTime>,
+}
+
+impl CredentialDataInput {
+    /// Create a new Credential Data Input accepting any format
+    pub fn new(instance: u32, object_name: String) -> Self {
+        Self {
+            identifier: ObjectIdentifier::new(ObjectType::CredentialDataInput, instance),
+            object_name,
+            description: String::new(),
+            present_value: AuthenticationFactor::undefined(),
+            event_state: EventState::Normal,
+            reliability: Reliability::NoFaultDetected,
+            out_of_service: false,
+            supported_formats: Vec::new(),
+            supported_format_classes: Vec::new(),
+            update_time: None,
+        }
+    }
+
+    /// Present a factor read by the reader hardware
+    ///
+    /// Factors in an unsupported format are rejected. While the object is out
+    /// of service the reader is decoupled, so the factor is ignored.
+    pub fn read_factor(
+        &mut self,
+        factor: AuthenticationFactor,
+        timestamp: Option<BacnetDateTime>,
+    ) -> Result<()> {
+        if self.out_of_service {
+            return Ok(());
+        }
+        self.validate(&factor)?;
+        self.present_value = factor;
+        self.update_time = timestamp.or_else(current_date_time);
+        Ok(())
+    }
+
+    fn validate(&self, factor: &AuthenticationFactor) -> Result<()> {
+        let supported = |list: &[u32], value: u32| list.is_empty() || list.contains(&value);
+        if !supported(&self.supported_formats, factor.format_type)
+            || !supported(&self.supported_format_classes, factor.format_class)
+        {
+            return Err(ObjectError::InvalidValue(format!(
+                "Unsupported authentication factor format {}/{}",
+                factor.format_type, factor.format_class
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl BacnetObject for CredentialDataInput {
+    fn identifier(&self) -> ObjectIdentifier {
+        self.identifier
+    }
+
+    fn get_property(&self, property: PropertyIdentifier) -> Result<PropertyValue> {
+        let unsigned_array = |values: &[u32]| {
+            PropertyValue::Array(
+                values
+                    .iter()
+                    .copied()
+                    .map(PropertyValue::UnsignedInteger)
+                    .collect(),
+            )
+        };
+        match property {
+            PropertyIdentifier::ObjectIdentifier => {
+                Ok(PropertyValue::ObjectIdentifier(self.identifier))
+            }
+            PropertyIdentifier::ObjectName => {
+                Ok(PropertyValue::CharacterString(self.object_name.clone()))
+            }
+            PropertyIdentifier::ObjectType => Ok(PropertyValue::Enumerated(u32::from(
+                ObjectType::CredentialDataInput,
+            ))),
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::PresentValue => Ok(self.present_value.to_property_value()),
+            PropertyIdentifier::StatusFlags => Ok(status_flags_bit_string(current_status_flags(
+                0,
+                self.event_state,
+                self.reliability,
+                self.out_of_service,
+            ))),
+            PropertyIdentifier::EventState => {
+                Ok(PropertyValue::Enumerated(self.event_state as u32))
+            }
+            PropertyIdentifier::Reliability => {
+                Ok(PropertyValue::Enumerated(self.reliability as u32))
+            }
+            PropertyIdentifier::OutOfService => Ok(PropertyValue::Boolean(self.out_of_service)),
+            PropertyIdentifier::SupportedFormats => Ok(unsigned_array(&self.supported_formats)),
+            PropertyIdentifier::SupportedFormatClasses => {
+                Ok(unsigned_array(&self.supported_format_classes))
+            }
+            PropertyIdentifier::UpdateTime => Ok(date_time_value(self.update_time)),
+            _ => Err(ObjectError::UnknownProperty),
+        }
+    }
+
+    fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        match property {
+            PropertyIdentifier::ObjectName => {
+                if let PropertyValue::CharacterString(name) = value {
+                    self.object_name = name;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::PresentValue => {
+                if !self.out_of_service {
+                    return Err(ObjectError::WriteAccessDenied);
+                }
+                let factor = AuthenticationFactor::from_property_value(&value)?;
+                self.validate(&factor)?;
+                self.present_value = factor;
+                self.update_time = current_date_time();
+                Ok(())
+            }
+            PropertyIdentifier::OutOfService => {
+                if let PropertyValue::Boolean(oos) = value {
+                    self.out_of_service = oos;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            _ => Err(ObjectError::PropertyNotWritable),
+        }
+    }
+
+    fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
+        match property {
+            PropertyIdentifier::ObjectName
+            | PropertyIdentifier::Description
+            | PropertyIdentifier::OutOfService => true,
+            PropertyIdentifier::PresentValue => self.out_of_service,
+            _ => false,
+        }
+    }
+
+    fn property_list(&self) -> Vec<PropertyIdentifier> {
+        vec![
+            PropertyIdentifier::ObjectIdentifier,
+            PropertyIdentifier::ObjectName,
+            PropertyIdentifier::ObjectType,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::PresentValue,
+            PropertyIdentifier::StatusFlags,
+            PropertyIdentifier::EventState,
+            PropertyIdentifier::Reliability,
+            PropertyIdentifier::OutOfService,
+            PropertyIdentifier::SupportedFormats,
+            PropertyIdentifier::SupportedFormatClasses,
+            PropertyIdentifier::UpdateTime,
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_door_pulse_unlock() {
+        let mut door = AccessDoor::new(1, "Front Door".to_string());
+        door.set_property_with_priority(
+            PropertyIdentifier::PresentValue,
+            PropertyValue::Enumerated(DoorValue::PulseUnlock as u32),
+            8,
+        )
+        .unwrap();
+        assert_eq!(door.present_value, DoorValue::PulseUnlock);
+        assert!(door.is_unlock_commanded());
+
+        door.advance_time(Duration::from_secs(4));
+        assert_eq!(door.present_value, DoorValue::PulseUnlock);
+        door.advance_time(Duration::from_secs(1));
+        assert_eq!(door.present_value, DoorValue::Lock);
+        assert_eq!(door.commandable.get(8), None);
+
+        // A held unlock at another priority is not affected by a pulse ending
+        door.write_priority(10, Some(DoorValue::Unlock)).unwrap();
+        door.write_priority(8, Some(DoorValue::ExtendedPulseUnlock))
+            .unwrap();
+        door.advance_time(Duration::from_secs(20));
+        assert_eq!(door.present_value, DoorValue::Unlock);
+        assert_eq!(door.get_effective_priority(), Some(10));
+    }
+
+    #[test]
+    fn test_door_alarms_and_secured_status() {
+        let mut door = AccessDoor::new(2, "Server Room".to_string());
+        door.update_door_status(DoorStatus::Opened);
+        assert_eq!(door.door_alarm_state, Some(DoorAlarmState::ForcedOpen));
+        assert_eq!(door.secured_status, Some(DoorSecuredStatus::Unsecured));
+        door.update_door_status(DoorStatus::Closed);
+        assert_eq!(door.door_alarm_state, Some(DoorAlarmState::Normal));
+        assert_eq!(door.secured_status, Some(DoorSecuredStatus::Secured));
+
+        door.write_priority(16, Some(DoorValue::Unlock)).unwrap();
+        door.update_lock_status(LockStatus::Unlocked);
+        door.update_door_status(DoorStatus::Opened);
+        assert_eq!(door.door_alarm_state, Some(DoorAlarmState::Normal));
+        door.advance_time(Duration::from_secs(30));
+        assert_eq!(door.door_alarm_state, Some(DoorAlarmState::DoorOpenTooLong));
+    }
+
+    #[test]
+    fn test_access_point_grants_pulse_unlock() {
+        let door = ObjectIdentifier::new(ObjectType::AccessDoor, 1);
+        let mut point = AccessPoint::new(1, "Front Reader".to_string());
+        point.access_doors.push(door);
+        point.priority_for_writing = 6;
+
+        let credential = ObjectIdentifier::new(ObjectType::CredentialDataInput, 1);
+        point.record_access_event(AccessEvent::DeniedUnknownCredential, Some(credential), None);
+        assert!(point.take_pending_writes().is_empty());
+        point.record_access_event(AccessEvent::Granted, Some(credential), None);
+        assert_eq!(point.access_event_tag, 2);
+
+        let writes = point.take_pending_writes();
+        assert_eq!(writes.len(), 1);
+        assert_eq!(writes[0].reference.object_identifier, door);
+        assert_eq!(writes[0].priority, 6);
+        assert_eq!(
+            writes[0].value,
+            PropertyValue::Enumerated(DoorValue::PulseUnlock as u32)
+        );
+    }
+
+    #[test]
+    fn test_zone_occupancy_and_credential_input() {
+        let entry = ObjectIdentifier::new(ObjectType::AccessPoint, 1);
+        let exit = ObjectIdentifier::new(ObjectType::AccessPoint, 2);
+        let mut zone = AccessZone::new(1, "Lab".to_string());
+        zone.entry_points.push(entry);
+        zone.exit_points.push(exit);
+        zone.occupancy_upper_limit = Some(2);
+
+        let badge = |instance| ObjectIdentifier::new(ObjectType::CredentialDataInput, instance);
+        zone.record_passage(entry, badge(1));
+        zone.record_passage(entry, badge(2));
+        assert_eq!(zone.occupancy_state, AccessZoneOccupancyState::AtUpperLimit);
+        zone.record_passage(exit, badge(1));
+        assert_eq!(zone.occupancy_count, Some(1));
+        assert_eq!(zone.credentials_in_zone, vec![badge(2)]);
+        assert_eq!(zone.occupancy_state, AccessZoneOccupancyState::Normal);
+
+        let mut reader = CredentialDataInput::new(1, "Reader".to_string());
+        reader.supported_formats = vec![1];
+        let card = AuthenticationFactor {
+            format_type: 1,
+            format_class: 0,
+            value: vec![0x12, 0x34],
+        };
+        reader.read_factor(card.clone(), None).unwrap();
+        assert_eq!(reader.present_value, card);
+        assert!(reader
+            .read_factor(
+                AuthenticationFactor {
+                    format_type: 7,
+                    ..card
+                },
+                None
+            )
+            .is_err());
+    }
+}
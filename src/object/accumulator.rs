@@ -0,0 +1,537 @@
+//! Accumulator Object Type Implementation
+//!
+//! This module implements the Accumulator object type as defined in ASHRAE 135.
+//! An Accumulator counts pulses from a meter or other totalizing input. Pulses are
+//! optionally divided down by Prescale before being added to Present_Value, which
+//! rolls over to zero once it passes Max_Pres_Value. Scale converts the raw count
+//! into engineering units.
+//!
+//! Writing Value_Set presets the count (for example after a meter exchange); the
+//! previous count is kept in Value_Before_Change and the time of the write in
+//! Value_Change_Time.
+
+use crate::object::{
+    current_date_time, current_status_flags, date_time_value, engineering_units::EngineeringUnits,
+    status_flags_bit_string, BacnetObject, EventState, ObjectError, ObjectIdentifier, ObjectType,
+    PropertyIdentifier, PropertyValue, Reliability, Result,
+};
+use crate::service::BacnetDateTime;
+use core::time::Duration;
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+/// Conversion from Present_Value to engineering units (BACnetScale)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Scale {
+    /// Present_Value is multiplied by this factor
+    Float(f32),
+    /// Present_Value is multiplied by 10 raised to this power
+    Integer(i32),
+}
+
+impl Scale {
+    /// Convert a raw count into engineering units
+    pub fn apply(&self, count: u32) -> f64 {
+        match *self {
+            Scale::Float(factor) => count as f64 * factor as f64,
+            Scale::Integer(exponent) => count as f64 * 10f64.powi(exponent),
+        }
+    }
+
+    fn to_property_value(self) -> PropertyValue {
+        match self {
+            Scale::Float(factor) => PropertyValue::Real(factor),
+            Scale::Integer(exponent) => PropertyValue::SignedInt(exponent),
+        }
+    }
+}
+
+/// Division of input pulses before they are counted (BACnetPrescale)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Prescale {
+    /// Amount added to Present_Value for every `modulo_divide` pulses
+    pub multiplier: u32,
+    /// Number of input pulses per increment
+    pub modulo_divide: u32,
+}
+
+impl Prescale {
+    fn to_property_value(self) -> PropertyValue {
+        PropertyValue::List(vec![
+            PropertyValue::UnsignedInteger(self.multiplier),
+            PropertyValue::UnsignedInteger(self.modulo_divide),
+        ])
+    }
+}
+
+/// Accumulator object
+#[derive(Debug, Clone)]
+pub struct Accumulator {
+    /// Object identifier
+    pub identifier: ObjectIdentifier,
+    /// Object name
+    pub object_name: String,
+    /// Present value (the accumulated count)
+    pub present_value: u32,
+    /// Description
+    pub description: String,
+    /// Device type
+    pub device_type: String,
+    /// Event state
+    pub event_state: EventState,
+    /// Reliability
+    pub reliability: Reliability,
+    /// Out of service
+    pub out_of_service: bool,
+    /// Conversion to engineering units
+    pub scale: Scale,
+    /// Units
+    pub units: EngineeringUnits,
+    /// Input pulse division
+    pub prescale: Option<Prescale>,
+    /// Largest count before Present_Value rolls over to zero
+    pub max_pres_value: u32,
+    /// Time of the most recent write to Value_Set or Value_Before_Change
+    pub value_change_time: Option<BacnetDateTime>,
+    /// Present_Value just before the most recent preset
+    pub value_before_change: Option<u32>,
+    /// Value Present_Value was most recently preset to
+    pub value_set: Option<u32>,
+    /// Pulses received during the last completed Limit_Monitoring_Interval
+    pub pulse_rate: Option<u32>,
+    /// Seconds over which Pulse_Rate is measured
+    pub limit_monitoring_interval: Option<u32>,
+    /// Pulses received but not yet counted because of Prescale
+    pending_pulses: u32,
+    /// Pulses received in the current monitoring interval
+    pulses_this_interval: u32,
+    /// Time elapsed in the current monitoring interval
+    interval_elapsed: Duration,
+}
+
+impl Accumulator {
+    /// Create a new Accumulator counting single pulses with a 32-bit range
+    pub fn new(instance: u32, object_name: String, units: EngineeringUnits) -> Self {
+        Self {
+            identifier: ObjectIdentifier::new(ObjectType::Accumulator, instance),
+            object_name,
+            present_value: 0,
+            description: String::new(),
+            device_type: String::new(),
+            event_state: EventState::Normal,
+            reliability: Reliability::NoFaultDetected,
+            out_of_service: false,
+            scale: Scale::Integer(0),
+            units,
+            prescale: None,
+            max_pres_value: u32::MAX,
+            value_change_time: None,
+            value_before_change: None,
+            value_set: None,
+            pulse_rate: None,
+            limit_monitoring_interval: None,
+            pending_pulses: 0,
+            pulses_this_interval: 0,
+            interval_elapsed: Duration::ZERO,
+        }
+    }
+
+    /// Enable the Value_Set, Value_Before_Change and Value_Change_Time properties
+    pub fn enable_value_set(&mut self) {
+        self.value_set.get_or_insert(self.present_value);
+        self.value_before_change.get_or_insert(self.present_value);
+        if self.value_change_time.is_none() {
+            self.value_change_time = Some(BacnetDateTime::unspecified());
+        }
+    }
+
+    /// Enable Pulse_Rate, measured over `interval_seconds`
+    pub fn enable_pulse_rate(&mut self, interval_seconds: u32) {
+        self.pulse_rate = Some(0);
+        self.limit_monitoring_interval = Some(interval_seconds);
+        self.pulses_this_interval = 0;
+        self.interval_elapsed = Duration::ZERO;
+    }
+
+    /// Present_Value converted to engineering units by Scale
+    pub fn scaled_value(&self) -> f64 {
+        self.scale.apply(self.present_value)
+    }
+
+    /// Count pulses from the physical input
+    ///
+    /// Pulses are divided by Prescale, if present, and Present_Value rolls over
+    /// to zero past Max_Pres_Value. While the object is out of service the count
+    /// is decoupled from the input, so the pulses are ignored.
+    pub fn count_pulses(&mut self, pulses: u32) {
+        if self.out_of_service {
+            return;
+        }
+        self.pulses_this_interval = self.pulses_this_interval.saturating_add(pulses);
+
+        let increment = match self.prescale {
+            Some(Prescale {
+                multiplier,
+                modulo_divide,
+            }) if modulo_divide > 0 => {
+                let total = self.pending_pulses as u64 + pulses as u64;
+                self.pending_pulses = (total % modulo_divide as u64) as u32;
+                (total / modulo_divide as u64) * multiplier as u64
+            }
+            _ => pulses as u64,
+        };
+        let range = self.max_pres_value as u64 + 1;
+        self.present_value = ((self.present_value as u64 + increment) % range) as u32;
+    }
+
+    /// Preset Present_Value, recording the previous count and `timestamp`
+    pub fn set_value_at(&mut self, value: u32, timestamp: Option<BacnetDateTime>) -> Result<()> {
+        if value > self.max_pres_value {
+            return Err(ObjectError::InvalidValue(format!(
+                "Value {} exceeds Max_Pres_Value {}",
+                value, self.max_pres_value
+            )));
+        }
+        self.value_before_change = Some(self.present_value);
+        self.value_set = Some(value);
+        self.present_value = value;
+        self.pending_pulses = 0;
+        self.value_change_time = Some(timestamp.unwrap_or_else(BacnetDateTime::unspecified));
+        Ok(())
+    }
+
+    /// Overwrite Value_Before_Change, recording the current count as Value_Set
+    pub fn set_value_before_change_at(
+        &mut self,
+        value: u32,
+        timestamp: Option<BacnetDateTime>,
+    ) -> Result<()> {
+        if value > self.max_pres_value {
+            return Err(ObjectError::InvalidValue(format!(
+                "Value {} exceeds Max_Pres_Value {}",
+                value, self.max_pres_value
+            )));
+        }
+        self.value_before_change = Some(value);
+        self.value_set = Some(self.present_value);
+        self.value_change_time = Some(timestamp.unwrap_or_else(BacnetDateTime::unspecified));
+        Ok(())
+    }
+
+    fn current_status_flags(&self) -> u8 {
+        current_status_flags(0, self.event_state, self.reliability, self.out_of_service)
+    }
+}
+
+impl BacnetObject for Accumulator {
+    fn identifier(&self) -> ObjectIdentifier {
+        self.identifier
+    }
+
+    fn get_property(&self, property: PropertyIdentifier) -> Result<PropertyValue> {
+        match property {
+            PropertyIdentifier::ObjectIdentifier => {
+                Ok(PropertyValue::ObjectIdentifier(self.identifier))
+            }
+            PropertyIdentifier::ObjectName => {
+                Ok(PropertyValue::CharacterString(self.object_name.clone()))
+            }
+            PropertyIdentifier::ObjectType => Ok(PropertyValue::Enumerated(u32::from(
+                ObjectType::Accumulator,
+            ))),
+            PropertyIdentifier::PresentValue => {
+                Ok(PropertyValue::UnsignedInteger(self.present_value))
+            }
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::DeviceType => {
+                Ok(PropertyValue::CharacterString(self.device_type.clone()))
+            }
+            PropertyIdentifier::StatusFlags => {
+                Ok(status_flags_bit_string(self.current_status_flags()))
+            }
+            PropertyIdentifier::EventState => {
+                Ok(PropertyValue::Enumerated(self.event_state as u32))
+            }
+            PropertyIdentifier::Reliability => {
+                Ok(PropertyValue::Enumerated(self.reliability as u32))
+            }
+            PropertyIdentifier::OutOfService => Ok(PropertyValue::Boolean(self.out_of_service)),
+            PropertyIdentifier::Scale => Ok(self.scale.to_property_value()),
+            PropertyIdentifier::Units => Ok(PropertyValue::Enumerated(self.units.to_u32())),
+            PropertyIdentifier::Prescale => self
+                .prescale
+                .map(Prescale::to_property_value)
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::MaxPresValue => {
+                Ok(PropertyValue::UnsignedInteger(self.max_pres_value))
+            }
+            PropertyIdentifier::ValueChangeTime if self.value_change_time.is_some() => {
+                Ok(date_time_value(self.value_change_time))
+            }
+            PropertyIdentifier::ValueBeforeChange => self
+                .value_before_change
+                .map(PropertyValue::UnsignedInteger)
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::ValueSet => self
+                .value_set
+                .map(PropertyValue::UnsignedInteger)
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::PulseRate => self
+                .pulse_rate
+                .map(PropertyValue::UnsignedInteger)
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::LimitMonitoringInterval => self
+                .limit_monitoring_interval
+                .map(PropertyValue::UnsignedInteger)
+                .ok_or(ObjectError::UnknownProperty),
+            _ => Err(ObjectError::UnknownProperty),
+        }
+    }
+
+    fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        match property {
+            PropertyIdentifier::ObjectName => {
+                if let PropertyValue::CharacterString(name) = value {
+                    self.object_name = name;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::PresentValue => {
+                if !self.out_of_service {
+                    return Err(ObjectError::WriteAccessDenied);
+                }
+                if let PropertyValue::UnsignedInteger(count) = value {
+                    if count > self.max_pres_value {
+                        return Err(ObjectError::InvalidValue(format!(
+                            "Value {} exceeds Max_Pres_Value {}",
+                            count, self.max_pres_value
+                        )));
+                    }
+                    self.present_value = count;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::OutOfService => {
+                if let PropertyValue::Boolean(oos) = value {
+                    self.out_of_service = oos;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::ValueSet if self.value_set.is_some() => {
+                if let PropertyValue::UnsignedInteger(count) = value {
+                    self.set_value_at(count, current_date_time())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::ValueBeforeChange if self.value_before_change.is_some() => {
+                if let PropertyValue::UnsignedInteger(count) = value {
+                    self.set_value_before_change_at(count, current_date_time())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::LimitMonitoringInterval
+                if self.limit_monitoring_interval.is_some() =>
+            {
+                if let PropertyValue::UnsignedInteger(seconds) = value {
+                    self.enable_pulse_rate(seconds);
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            _ => Err(ObjectError::PropertyNotWritable),
+        }
+    }
+
+    fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
+        match property {
+            PropertyIdentifier::ObjectName
+            | PropertyIdentifier::Description
+            | PropertyIdentifier::OutOfService => true,
+            PropertyIdentifier::PresentValue => self.out_of_service,
+            PropertyIdentifier::ValueSet => self.value_set.is_some(),
+            PropertyIdentifier::ValueBeforeChange => self.value_before_change.is_some(),
+            PropertyIdentifier::LimitMonitoringInterval => self.limit_monitoring_interval.is_some(),
+            _ => false,
+        }
+    }
+
+    fn property_list(&self) -> Vec<PropertyIdentifier> {
+        let mut properties = vec![
+            PropertyIdentifier::ObjectIdentifier,
+            PropertyIdentifier::ObjectName,
+            PropertyIdentifier::ObjectType,
+            PropertyIdentifier::PresentValue,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::DeviceType,
+            PropertyIdentifier::StatusFlags,
+            PropertyIdentifier::EventState,
+            PropertyIdentifier::Reliability,
+            PropertyIdentifier::OutOfService,
+            PropertyIdentifier::Scale,
+            PropertyIdentifier::Units,
+        ];
+        if self.prescale.is_some() {
+            properties.push(PropertyIdentifier::Prescale);
+        }
+        properties.push(PropertyIdentifier::MaxPresValue);
+        if self.value_change_time.is_some() {
+            properties.push(PropertyIdentifier::ValueChangeTime);
+        }
+        if self.value_before_change.is_some() {
+            properties.push(PropertyIdentifier::ValueBeforeChange);
+        }
+        if self.value_set.is_some() {
+            properties.push(PropertyIdentifier::ValueSet);
+        }
+        if self.pulse_rate.is_some() {
+            properties.push(PropertyIdentifier::PulseRate);
+        }
+        if self.limit_monitoring_interval.is_some() {
+            properties.push(PropertyIdentifier::LimitMonitoringInterval);
+        }
+        properties
+    }
+
+    fn advance_time(&mut self, elapsed: Duration) {
+        let Some(interval) = self
+            .limit_monitoring_interval
+            .filter(|&seconds| seconds > 0)
+        else {
+            return;
+        };
+        let interval = Duration::from_secs(interval as u64);
+        self.interval_elapsed = self.interval_elapsed.saturating_add(elapsed);
+        if self.interval_elapsed >= interval {
+            // Pulses are not timestamped, so a long step attributes all of them
+            // to the period that just completed
+            self.pulse_rate = Some(self.pulses_this_interval);
+            self.pulses_this_interval = 0;
+            self.interval_elapsed = Duration::ZERO;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::{Date, Time};
+
+    fn meter() -> Accumulator {
+        Accumulator::new(1, "Main Meter".to_string(), EngineeringUnits::KilowattHours)
+    }
+
+    #[test]
+    fn test_prescale_and_rollover() {
+        let mut acc = meter();
+        acc.prescale = Some(Prescale {
+            multiplier: 5,
+            modulo_divide: 4,
+        });
+        acc.max_pres_value = 99;
+
+        acc.count_pulses(3);
+        assert_eq!(acc.present_value, 0);
+        acc.count_pulses(6);
+        assert_eq!(acc.present_value, 10);
+        assert_eq!(acc.pending_pulses, 1);
+
+        acc.count_pulses(75);
+        assert_eq!(acc.present_value, 5);
+
+        acc.scale = Scale::Integer(-1);
+        assert!((acc.scaled_value() - 0.5).abs() < 1e-9);
+        assert!(matches!(
+            acc.get_property(PropertyIdentifier::Prescale),
+            Ok(PropertyValue::List(items)) if items.len() == 2
+        ));
+    }
+
+    #[test]
+    fn test_value_set_bookkeeping() {
+        let mut acc = meter();
+        assert!(acc
+            .set_property(
+                PropertyIdentifier::ValueSet,
+                PropertyValue::UnsignedInteger(0)
+            )
+            .is_err());
+
+        acc.enable_value_set();
+        acc.count_pulses(1234);
+        let when = BacnetDateTime::new(
+            Date {
+                year: 2024,
+                month: 6,
+                day: 3,
+                weekday: 1,
+            },
+            Time {
+                hour: 9,
+                minute: 0,
+                second: 0,
+                hundredths: 0,
+            },
+        );
+        acc.set_value_at(100, Some(when)).unwrap();
+        assert_eq!(acc.present_value, 100);
+        assert_eq!(acc.value_before_change, Some(1234));
+        assert_eq!(acc.value_set, Some(100));
+        assert_eq!(acc.value_change_time, Some(when));
+
+        acc.set_property(
+            PropertyIdentifier::ValueSet,
+            PropertyValue::UnsignedInteger(7),
+        )
+        .unwrap();
+        assert_eq!(acc.value_before_change, Some(100));
+        assert_eq!(acc.present_value, 7);
+        assert!(acc
+            .property_list()
+            .contains(&PropertyIdentifier::ValueChangeTime));
+
+        acc.max_pres_value = 10;
+        assert!(acc.set_value_at(11, None).is_err());
+    }
+
+    #[test]
+    fn test_pulse_rate_and_out_of_service() {
+        let mut acc = meter();
+        acc.enable_pulse_rate(60);
+        acc.count_pulses(30);
+        acc.advance_time(Duration::from_secs(30));
+        assert_eq!(acc.pulse_rate, Some(0));
+        acc.count_pulses(12);
+        acc.advance_time(Duration::from_secs(30));
+        assert_eq!(acc.pulse_rate, Some(42));
+
+        acc.out_of_service = true;
+        acc.count_pulses(10);
+        assert_eq!(acc.present_value, 42);
+        acc.set_property(
+            PropertyIdentifier::PresentValue,
+            PropertyValue::UnsignedInteger(500),
+        )
+        .unwrap();
+        assert_eq!(acc.present_value, 500);
+    }
+}
@@ -0,0 +1,278 @@
+//! Alert Enrollment Object Type Implementation
+//!
+//! This module implements the Alert Enrollment object type as defined in ASHRAE 135.
+//! An Alert Enrollment lets a device report stateless alerts, such as a diagnostic
+//! message from an object that has no event state of its own. Each alert is reported
+//! as a NORMAL to NORMAL notification through the enrollment's Notification_Class;
+//! Present_Value identifies the object that raised the most recent alert.
+
+use crate::object::{
+    date_time_value, status_flags_bit_string, BacnetObject, EventState, NotifyType, ObjectError,
+    ObjectIdentifier, ObjectType, PropertyIdentifier, PropertyValue, Result,
+};
+use crate::service::BacnetDateTime;
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+/// An alert raised through an Alert Enrollment, to be delivered through its
+/// Notification Class
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alert {
+    /// Alert Enrollment the alert is reported for
+    pub enrollment: ObjectIdentifier,
+    /// Object that raised the alert
+    pub source: ObjectIdentifier,
+    /// Notification class to deliver through
+    pub notification_class: u32,
+    /// Notify type for the notification
+    pub notify_type: NotifyType,
+    /// When the alert was raised
+    pub timestamp: Option<BacnetDateTime>,
+    /// Alert-specific values carried in the notification
+    pub alert_values: PropertyValue,
+}
+
+/// Alert Enrollment object
+#[derive(Debug, Clone)]
+pub struct AlertEnrollment {
+    /// Object identifier
+    pub identifier: ObjectIdentifier,
+    /// Object name
+    pub object_name: String,
+    /// Description
+    pub description: String,
+    /// Object that raised the most recent alert
+    pub present_value: ObjectIdentifier,
+    /// Whether alerts are reported at all
+    pub event_detection_enable: bool,
+    /// Notification class that receives the alerts
+    pub notification_class: u32,
+    /// Event enable (to_offnormal, to_fault, to_normal); alerts use to_normal
+    pub event_enable: (bool, bool, bool),
+    /// Acknowledged transitions (to_offnormal, to_fault, to_normal)
+    pub acked_transitions: (bool, bool, bool),
+    /// Notify type
+    pub notify_type: NotifyType,
+    /// Time of the last transition of each kind (to_offnormal, to_fault, to_normal)
+    pub event_time_stamps: [Option<BacnetDateTime>; 3],
+}
+
+impl AlertEnrollment {
+    /// Create a new Alert Enrollment reporting through `notification_class`
+    pub fn new(instance: u32, object_name: String, notification_class: u32) -> Self {
+        let identifier = ObjectIdentifier::new(ObjectType::AlertEnrollment, instance);
+        Self {
+            identifier,
+            object_name,
+            description: String::new(),
+            present_value: identifier,
+            event_detection_enable: true,
+            notification_class,
+            event_enable: (true, true, true),
+            acked_transitions: (true, true, true),
+            notify_type: NotifyType::Event,
+            event_time_stamps: [None; 3],
+        }
+    }
+
+    /// Raise an alert on behalf of `source`
+    ///
+    /// Nothing happens while event detection is disabled. Otherwise Present_Value
+    /// and the to-normal time stamp are updated, and the alert to deliver is
+    /// returned if the to-normal transition is enabled.
+    pub fn raise(
+        &mut self,
+        source: ObjectIdentifier,
+        alert_values: PropertyValue,
+        timestamp: Option<BacnetDateTime>,
+    ) -> Option<Alert> {
+        if !self.event_detection_enable {
+            return None;
+        }
+        self.present_value = source;
+        self.event_time_stamps[2] = timestamp;
+        self.event_enable.2.then_some(Alert {
+            enrollment: self.identifier,
+            source,
+            notification_class: self.notification_class,
+            notify_type: self.notify_type,
+            timestamp,
+            alert_values,
+        })
+    }
+}
+
+impl BacnetObject for AlertEnrollment {
+    fn identifier(&self) -> ObjectIdentifier {
+        self.identifier
+    }
+
+    fn get_property(&self, property: PropertyIdentifier) -> Result<PropertyValue> {
+        match property {
+            PropertyIdentifier::ObjectIdentifier => {
+                Ok(PropertyValue::ObjectIdentifier(self.identifier))
+            }
+            PropertyIdentifier::ObjectName => {
+                Ok(PropertyValue::CharacterString(self.object_name.clone()))
+            }
+            PropertyIdentifier::ObjectType => Ok(PropertyValue::Enumerated(u32::from(
+                ObjectType::AlertEnrollment,
+            ))),
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::PresentValue => {
+                Ok(PropertyValue::ObjectIdentifier(self.present_value))
+            }
+            PropertyIdentifier::StatusFlags => Ok(status_flags_bit_string(0)),
+            PropertyIdentifier::EventState => {
+                Ok(PropertyValue::Enumerated(EventState::Normal as u32))
+            }
+            PropertyIdentifier::EventDetectionEnable => {
+                Ok(PropertyValue::Boolean(self.event_detection_enable))
+            }
+            PropertyIdentifier::NotificationClass => {
+                Ok(PropertyValue::UnsignedInteger(self.notification_class))
+            }
+            PropertyIdentifier::EventEnable => Ok(PropertyValue::BitString(vec![
+                self.event_enable.0,
+                self.event_enable.1,
+                self.event_enable.2,
+            ])),
+            PropertyIdentifier::AckedTransitions => Ok(PropertyValue::BitString(vec![
+                self.acked_transitions.0,
+                self.acked_transitions.1,
+                self.acked_transitions.2,
+            ])),
+            PropertyIdentifier::NotifyType => {
+                Ok(PropertyValue::Enumerated(self.notify_type as u32))
+            }
+            PropertyIdentifier::EventTimeStamps => Ok(PropertyValue::Array(
+                self.event_time_stamps
+                    .iter()
+                    .map(|&timestamp| date_time_value(timestamp))
+                    .collect(),
+            )),
+            _ => Err(ObjectError::UnknownProperty),
+        }
+    }
+
+    fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        match property {
+            PropertyIdentifier::ObjectName => {
+                if let PropertyValue::CharacterString(name) = value {
+                    self.object_name = name;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::EventDetectionEnable => {
+                if let PropertyValue::Boolean(enable) = value {
+                    self.event_detection_enable = enable;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::NotificationClass => {
+                if let PropertyValue::UnsignedInteger(class) = value {
+                    self.notification_class = class;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::EventEnable => {
+                if let PropertyValue::BitString(bits) = value {
+                    let bit = |i: usize| bits.get(i).copied().unwrap_or(false);
+                    self.event_enable = (bit(0), bit(1), bit(2));
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::NotifyType => {
+                if let PropertyValue::Enumerated(notify_type) = value {
+                    self.notify_type = NotifyType::try_from(notify_type)?;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            _ => Err(ObjectError::PropertyNotWritable),
+        }
+    }
+
+    fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
+        matches!(
+            property,
+            PropertyIdentifier::ObjectName
+                | PropertyIdentifier::Description
+                | PropertyIdentifier::EventDetectionEnable
+                | PropertyIdentifier::NotificationClass
+                | PropertyIdentifier::EventEnable
+                | PropertyIdentifier::NotifyType
+        )
+    }
+
+    fn property_list(&self) -> Vec<PropertyIdentifier> {
+        vec![
+            PropertyIdentifier::ObjectIdentifier,
+            PropertyIdentifier::ObjectName,
+            PropertyIdentifier::ObjectType,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::PresentValue,
+            PropertyIdentifier::StatusFlags,
+            PropertyIdentifier::EventState,
+            PropertyIdentifier::EventDetectionEnable,
+            PropertyIdentifier::NotificationClass,
+            PropertyIdentifier::EventEnable,
+            PropertyIdentifier::AckedTransitions,
+            PropertyIdentifier::NotifyType,
+            PropertyIdentifier::EventTimeStamps,
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alert_updates_present_value() {
+        let mut enrollment = AlertEnrollment::new(1, "Diagnostics".to_string(), 5);
+        let source = ObjectIdentifier::new(ObjectType::NetworkPort, 1);
+
+        let alert = enrollment
+            .raise(source, PropertyValue::UnsignedInteger(42), None)
+            .unwrap();
+        assert_eq!(alert.notification_class, 5);
+        assert_eq!(alert.source, source);
+        assert_eq!(
+            enrollment
+                .get_property(PropertyIdentifier::PresentValue)
+                .unwrap(),
+            PropertyValue::ObjectIdentifier(source)
+        );
+
+        enrollment
+            .set_property(
+                PropertyIdentifier::EventEnable,
+                PropertyValue::BitString(vec![true, true, false]),
+            )
+            .unwrap();
+        assert!(enrollment
+            .raise(source, PropertyValue::Null, None)
+            .is_none());
+    }
+}
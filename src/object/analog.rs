@@ -4,8 +4,9 @@
 //! as defined in ASHRAE 135. These objects represent analog (continuous) values in BACnet.
 
 use crate::object::{
-    engineering_units::EngineeringUnits, BacnetObject, ObjectError, ObjectIdentifier, ObjectType,
-    PropertyIdentifier, PropertyValue, Result,
+    current_status_flags, engineering_units::EngineeringUnits, status_flags_bit_string,
+    BacnetObject, Commandable, ObjectError, ObjectIdentifier, ObjectType, PropertyIdentifier,
+    PropertyValue, Result, DEFAULT_COMMAND_PRIORITY,
 };
 
 #[cfg(not(feature = "std"))]
@@ -73,10 +74,8 @@ pub struct AnalogOutput {
     pub max_pres_value: Option<f32>,
     /// Resolution
     pub resolution: Option<f32>,
-    /// Priority array (16 levels)
-    pub priority_array: [Option<f32>; 16],
-    /// Relinquish default
-    pub relinquish_default: f32,
+    /// Priority array and relinquish default
+    pub commandable: Commandable<f32>,
     /// COV increment
     pub cov_increment: Option<f32>,
 }
@@ -102,12 +101,159 @@ pub struct AnalogValue {
     pub out_of_service: bool,
     /// Units
     pub units: EngineeringUnits,
-    /// Priority array (16 levels)
-    pub priority_array: [Option<f32>; 16],
-    /// Relinquish default
-    pub relinquish_default: f32,
+    /// Priority array and relinquish default, present only on commandable
+    /// instances
+    pub commandable: Option<Commandable<f32>>,
     /// COV increment
     pub cov_increment: Option<f32>,
+    /// Intrinsic reporting properties, if the object supports OUT_OF_RANGE reporting
+    pub intrinsic_reporting: Option<AnalogLimitReporting>,
+}
+
+/// Intrinsic reporting (OUT_OF_RANGE algorithm) properties of an analog object
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnalogLimitReporting {
+    /// Time delay in seconds before a transition is reported
+    pub time_delay: u32,
+    /// Notification class that receives the event notifications
+    pub notification_class: u32,
+    /// High limit
+    pub high_limit: f32,
+    /// Low limit
+    pub low_limit: f32,
+    /// Deadband applied when returning to normal
+    pub deadband: f32,
+    /// Limit enable (low_limit_enable, high_limit_enable)
+    pub limit_enable: (bool, bool),
+    /// Event enable (to_offnormal, to_fault, to_normal)
+    pub event_enable: (bool, bool, bool),
+    /// Acknowledged transitions (to_offnormal, to_fault, to_normal)
+    pub acked_transitions: (bool, bool, bool),
+    /// Notify type
+    pub notify_type: NotifyType,
+}
+
+impl AnalogLimitReporting {
+    /// Create reporting properties with the given limits and everything enabled
+    pub fn new(notification_class: u32, low_limit: f32, high_limit: f32) -> Self {
+        Self {
+            time_delay: 0,
+            notification_class,
+            high_limit,
+            low_limit,
+            deadband: 0.0,
+            limit_enable: (true, true),
+            event_enable: (true, true, true),
+            acked_transitions: (true, true, true),
+            notify_type: NotifyType::Alarm,
+        }
+    }
+
+    fn get_property(&self, property: PropertyIdentifier) -> Option<PropertyValue> {
+        let value = match property {
+            PropertyIdentifier::TimeDelay => PropertyValue::UnsignedInteger(self.time_delay),
+            PropertyIdentifier::NotificationClass => {
+                PropertyValue::UnsignedInteger(self.notification_class)
+            }
+            PropertyIdentifier::HighLimit => PropertyValue::Real(self.high_limit),
+            PropertyIdentifier::LowLimit => PropertyValue::Real(self.low_limit),
+            PropertyIdentifier::Deadband => PropertyValue::Real(self.deadband),
+            PropertyIdentifier::LimitEnable => {
+                PropertyValue::BitString(vec![self.limit_enable.0, self.limit_enable.1])
+            }
+            PropertyIdentifier::EventEnable => PropertyValue::BitString(vec![
+                self.event_enable.0,
+                self.event_enable.1,
+                self.event_enable.2,
+            ]),
+            PropertyIdentifier::AckedTransitions => PropertyValue::BitString(vec![
+                self.acked_transitions.0,
+                self.acked_transitions.1,
+                self.acked_transitions.2,
+            ]),
+            PropertyIdentifier::NotifyType => PropertyValue::Enumerated(self.notify_type as u32),
+            _ => return None,
+        };
+        Some(value)
+    }
+
+    fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        match (property, value) {
+            (PropertyIdentifier::TimeDelay, PropertyValue::UnsignedInteger(v)) => {
+                self.time_delay = v;
+            }
+            (PropertyIdentifier::NotificationClass, PropertyValue::UnsignedInteger(v)) => {
+                self.notification_class = v;
+            }
+            (PropertyIdentifier::HighLimit, PropertyValue::Real(v)) => self.high_limit = v,
+            (PropertyIdentifier::LowLimit, PropertyValue::Real(v)) => self.low_limit = v,
+            (PropertyIdentifier::Deadband, PropertyValue::Real(v)) => {
+                if v < 0.0 {
+                    return Err(ObjectError::InvalidValue(
+                        "Deadband must not be negative".to_string(),
+                    ));
+                }
+                self.deadband = v;
+            }
+            (PropertyIdentifier::LimitEnable, PropertyValue::BitString(bits)) => {
+                let bit = |i: usize| bits.get(i).copied().unwrap_or(false);
+                self.limit_enable = (bit(0), bit(1));
+            }
+            (PropertyIdentifier::EventEnable, PropertyValue::BitString(bits)) => {
+                let bit = |i: usize| bits.get(i).copied().unwrap_or(false);
+                self.event_enable = (bit(0), bit(1), bit(2));
+            }
+            (PropertyIdentifier::NotifyType, PropertyValue::Enumerated(v)) => {
+                self.notify_type = NotifyType::try_from(v)?;
+            }
+            _ => return Err(ObjectError::InvalidPropertyType),
+        }
+        Ok(())
+    }
+
+    fn property_list() -> [PropertyIdentifier; 9] {
+        [
+            PropertyIdentifier::TimeDelay,
+            PropertyIdentifier::NotificationClass,
+            PropertyIdentifier::HighLimit,
+            PropertyIdentifier::LowLimit,
+            PropertyIdentifier::Deadband,
+            PropertyIdentifier::LimitEnable,
+            PropertyIdentifier::EventEnable,
+            PropertyIdentifier::AckedTransitions,
+            PropertyIdentifier::NotifyType,
+        ]
+    }
+
+    fn is_property_writable(property: PropertyIdentifier) -> bool {
+        property != PropertyIdentifier::AckedTransitions
+            && Self::property_list().contains(&property)
+    }
+}
+
+/// Notify type enumeration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum NotifyType {
+    Alarm = 0,
+    Event = 1,
+    AckNotification = 2,
+}
+
+impl TryFrom<u32> for NotifyType {
+    type Error = ObjectError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            0 => Ok(NotifyType::Alarm),
+            1 => Ok(NotifyType::Event),
+            2 => Ok(NotifyType::AckNotification),
+            _ => Err(ObjectError::InvalidValue(format!(
+                "Unknown notify type: {}",
+                value
+            ))),
+        }
+    }
 }
 
 /// Event state enumeration
@@ -122,6 +268,25 @@ pub enum EventState {
     LifeSafetyAlarm = 5,
 }
 
+impl TryFrom<u32> for EventState {
+    type Error = ObjectError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            0 => Ok(EventState::Normal),
+            1 => Ok(EventState::Fault),
+            2 => Ok(EventState::Offnormal),
+            3 => Ok(EventState::HighLimit),
+            4 => Ok(EventState::LowLimit),
+            5 => Ok(EventState::LifeSafetyAlarm),
+            _ => Err(ObjectError::InvalidValue(format!(
+                "Unknown event state: {}",
+                value
+            ))),
+        }
+    }
+}
+
 /// Reliability enumeration
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u32)]
@@ -139,6 +304,30 @@ pub enum Reliability {
     ConfigurationError = 10,
 }
 
+impl TryFrom<u32> for Reliability {
+    type Error = ObjectError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            0 => Ok(Reliability::NoFaultDetected),
+            1 => Ok(Reliability::NoSensor),
+            2 => Ok(Reliability::OverRange),
+            3 => Ok(Reliability::UnderRange),
+            4 => Ok(Reliability::OpenLoop),
+            5 => Ok(Reliability::ShortedLoop),
+            6 => Ok(Reliability::NoOutput),
+            7 => Ok(Reliability::UnreliableOther),
+            8 => Ok(Reliability::ProcessError),
+            9 => Ok(Reliability::MultiStateFault),
+            10 => Ok(Reliability::ConfigurationError),
+            _ => Err(ObjectError::InvalidValue(format!(
+                "Unknown reliability: {}",
+                value
+            ))),
+        }
+    }
+}
+
 // EngineeringUnits enum moved to src/object/engineering_units.rs for complete implementation
 
 impl AnalogInput {
@@ -167,6 +356,47 @@ impl AnalogInput {
         self.present_value = value;
     }
 
+    /// Update the present value from the physical input
+    ///
+    /// While the object is out of service the present value is decoupled from
+    /// the sensor, so the sample is ignored. Readings outside the configured
+    /// Min_Pres_Value/Max_Pres_Value range flag the object as unreliable.
+    pub fn update_from_sensor(&mut self, value: f32) {
+        if self.out_of_service {
+            return;
+        }
+
+        self.reliability = match (self.min_pres_value, self.max_pres_value) {
+            (Some(min), _) if value < min => Reliability::UnderRange,
+            (_, Some(max)) if value > max => Reliability::OverRange,
+            _ => Reliability::NoFaultDetected,
+        };
+        self.present_value = value;
+    }
+
+    /// Set the out of service flag
+    ///
+    /// Keeps the out_of_service bit of the status flags in step with the property.
+    pub fn set_out_of_service(&mut self, out_of_service: bool) {
+        self.out_of_service = out_of_service;
+        if out_of_service {
+            self.status_flags |= 0x01;
+        } else {
+            self.status_flags &= !0x01;
+        }
+    }
+
+    /// Current Status_Flags, combining the stored flags with the state derived
+    /// from Event_State, Reliability and Out_Of_Service
+    pub fn current_status_flags(&self) -> u8 {
+        current_status_flags(
+            self.status_flags,
+            self.event_state,
+            self.reliability,
+            self.out_of_service,
+        )
+    }
+
     /// Get status flags as individual booleans
     pub fn get_status_flags(&self) -> (bool, bool, bool, bool) {
         (
@@ -218,8 +448,7 @@ impl AnalogOutput {
             min_pres_value: None,
             max_pres_value: None,
             resolution: None,
-            priority_array: [None; 16],
-            relinquish_default: 0.0,
+            commandable: Commandable::new(0.0),
             cov_increment: None,
         }
     }
@@ -231,35 +460,64 @@ impl AnalogOutput {
                 "Priority must be 1-16".to_string(),
             ));
         }
-        self.priority_array[(priority - 1) as usize] = value;
+        if let Some(val) = value {
+            self.check_range(val)?;
+        }
+        self.commandable.write(priority, value)?;
+        self.update_present_value();
+        Ok(())
+    }
+
+    /// Relinquish the command at the specified priority level (1-16)
+    pub fn relinquish(&mut self, priority: u8) -> Result<()> {
+        self.write_priority(priority, None)
+    }
+
+    /// Set the relinquish default and re-evaluate the present value
+    pub fn set_relinquish_default(&mut self, value: f32) -> Result<()> {
+        self.check_range(value)?;
+        self.commandable.set_relinquish_default(value);
         self.update_present_value();
         Ok(())
     }
 
+    /// Reject commands outside the Min_Pres_Value/Max_Pres_Value range
+    fn check_range(&self, value: f32) -> Result<()> {
+        let below = self.min_pres_value.is_some_and(|min| value < min);
+        let above = self.max_pres_value.is_some_and(|max| value > max);
+        if below || above {
+            return Err(ObjectError::InvalidValue(format!(
+                "Value {} outside present value range",
+                value
+            )));
+        }
+        Ok(())
+    }
+
     /// Update present value based on priority array
     fn update_present_value(&mut self) {
-        // Find highest priority non-null value
-        if let Some(value) = self.priority_array.iter().flatten().next() {
-            self.present_value = *value;
-            return;
-        }
-        // If all priorities are null, use relinquish default
-        self.present_value = self.relinquish_default;
+        self.present_value = *self.commandable.current();
     }
 
     /// Get the effective priority level for current present value
     pub fn get_effective_priority(&self) -> Option<u8> {
-        for (i, priority_value) in self.priority_array.iter().enumerate() {
-            if priority_value.is_some() {
-                return Some((i + 1) as u8);
-            }
-        }
-        None
+        self.commandable.active_priority()
+    }
+
+    /// Current Status_Flags, combining the stored flags with the state derived
+    /// from Event_State, Reliability and Out_Of_Service
+    pub fn current_status_flags(&self) -> u8 {
+        current_status_flags(
+            self.status_flags,
+            self.event_state,
+            self.reliability,
+            self.out_of_service,
+        )
     }
 }
 
 impl AnalogValue {
-    /// Create a new Analog Value object
+    /// Create a new non-commandable Analog Value object
     pub fn new(instance: u32, object_name: String) -> Self {
         Self {
             identifier: ObjectIdentifier::new(ObjectType::AnalogValue, instance),
@@ -271,12 +529,25 @@ impl AnalogValue {
             reliability: Reliability::NoFaultDetected,
             out_of_service: false,
             units: EngineeringUnits::NoUnits,
-            priority_array: [None; 16],
-            relinquish_default: 0.0,
+            commandable: None,
             cov_increment: None,
+            intrinsic_reporting: None,
         }
     }
 
+    /// Create a new commandable Analog Value object with a priority array
+    pub fn new_commandable(instance: u32, object_name: String, relinquish_default: f32) -> Self {
+        let mut av = Self::new(instance, object_name);
+        av.commandable = Some(Commandable::new(relinquish_default));
+        av.present_value = relinquish_default;
+        av
+    }
+
+    /// Whether Present_Value writes are arbitrated through a priority array
+    pub fn is_commandable(&self) -> bool {
+        self.commandable.is_some()
+    }
+
     /// Write to priority array at specified priority level (1-16)
     pub fn write_priority(&mut self, priority: u8, value: Option<f32>) -> Result<()> {
         if !(1..=16).contains(&priority) {
@@ -284,20 +555,32 @@ impl AnalogValue {
                 "Priority must be 1-16".to_string(),
             ));
         }
-        self.priority_array[(priority - 1) as usize] = value;
+        let Some(commandable) = self.commandable.as_mut() else {
+            return Err(ObjectError::InvalidConfiguration(
+                "Analog Value is not commandable".to_string(),
+            ));
+        };
+        commandable.write(priority, value)?;
         self.update_present_value();
         Ok(())
     }
 
     /// Update present value based on priority array
     fn update_present_value(&mut self) {
-        // Find highest priority non-null value
-        if let Some(value) = self.priority_array.iter().flatten().next() {
-            self.present_value = *value;
-            return;
+        if let Some(commandable) = self.commandable.as_ref() {
+            self.present_value = *commandable.current();
         }
-        // If all priorities are null, use relinquish default
-        self.present_value = self.relinquish_default;
+    }
+
+    /// Current Status_Flags, combining the stored flags with the state derived
+    /// from Event_State, Reliability and Out_Of_Service
+    pub fn current_status_flags(&self) -> u8 {
+        current_status_flags(
+            self.status_flags,
+            self.event_state,
+            self.reliability,
+            self.out_of_service,
+        )
     }
 }
 
@@ -314,11 +597,43 @@ impl BacnetObject for AnalogInput {
             PropertyIdentifier::ObjectName => {
                 Ok(PropertyValue::CharacterString(self.object_name.clone()))
             }
-            PropertyIdentifier::ObjectType => {
-                Ok(PropertyValue::Enumerated(ObjectType::AnalogInput as u32))
-            }
+            PropertyIdentifier::ObjectType => Ok(PropertyValue::Enumerated(u32::from(
+                ObjectType::AnalogInput,
+            ))),
             PropertyIdentifier::PresentValue => Ok(PropertyValue::Real(self.present_value)),
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::DeviceType => {
+                Ok(PropertyValue::CharacterString(self.device_type.clone()))
+            }
+            PropertyIdentifier::StatusFlags => {
+                Ok(status_flags_bit_string(self.current_status_flags()))
+            }
+            PropertyIdentifier::EventState => {
+                Ok(PropertyValue::Enumerated(self.event_state as u32))
+            }
+            PropertyIdentifier::Reliability => {
+                Ok(PropertyValue::Enumerated(self.reliability as u32))
+            }
             PropertyIdentifier::OutOfService => Ok(PropertyValue::Boolean(self.out_of_service)),
+            PropertyIdentifier::Units => Ok(PropertyValue::Enumerated(self.units.to_u32())),
+            PropertyIdentifier::MinPresValue => self
+                .min_pres_value
+                .map(PropertyValue::Real)
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::MaxPresValue => self
+                .max_pres_value
+                .map(PropertyValue::Real)
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::Resolution => self
+                .resolution
+                .map(PropertyValue::Real)
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::CovIncrement => self
+                .cov_increment
+                .map(PropertyValue::Real)
+                .ok_or(ObjectError::UnknownProperty),
             _ => Err(ObjectError::UnknownProperty),
         }
     }
@@ -333,9 +648,61 @@ impl BacnetObject for AnalogInput {
                     Err(ObjectError::InvalidPropertyType)
                 }
             }
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::PresentValue => {
+                // Present_Value is only writable while decoupled from the sensor
+                if !self.out_of_service {
+                    return Err(ObjectError::WriteAccessDenied);
+                }
+                if let PropertyValue::Real(val) = value {
+                    self.present_value = val;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Reliability => {
+                if !self.out_of_service {
+                    return Err(ObjectError::WriteAccessDenied);
+                }
+                if let PropertyValue::Enumerated(val) = value {
+                    self.reliability = Reliability::try_from(val)?;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
             PropertyIdentifier::OutOfService => {
                 if let PropertyValue::Boolean(oos) = value {
-                    self.out_of_service = oos;
+                    self.set_out_of_service(oos);
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Units => {
+                if let PropertyValue::Enumerated(units) = value {
+                    self.units = EngineeringUnits::from_u32(units);
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::CovIncrement => {
+                if let PropertyValue::Real(increment) = value {
+                    if increment < 0.0 {
+                        return Err(ObjectError::InvalidValue(
+                            "COV increment must not be negative".to_string(),
+                        ));
+                    }
+                    self.cov_increment = Some(increment);
                     Ok(())
                 } else {
                     Err(ObjectError::InvalidPropertyType)
@@ -346,20 +713,48 @@ impl BacnetObject for AnalogInput {
     }
 
     fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
-        matches!(
-            property,
-            PropertyIdentifier::ObjectName | PropertyIdentifier::OutOfService
-        )
+        match property {
+            PropertyIdentifier::PresentValue | PropertyIdentifier::Reliability => {
+                self.out_of_service
+            }
+            _ => matches!(
+                property,
+                PropertyIdentifier::ObjectName
+                    | PropertyIdentifier::Description
+                    | PropertyIdentifier::OutOfService
+                    | PropertyIdentifier::Units
+                    | PropertyIdentifier::CovIncrement
+            ),
+        }
     }
 
     fn property_list(&self) -> Vec<PropertyIdentifier> {
-        vec![
+        let mut properties = vec![
             PropertyIdentifier::ObjectIdentifier,
             PropertyIdentifier::ObjectName,
             PropertyIdentifier::ObjectType,
             PropertyIdentifier::PresentValue,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::DeviceType,
+            PropertyIdentifier::StatusFlags,
+            PropertyIdentifier::EventState,
+            PropertyIdentifier::Reliability,
             PropertyIdentifier::OutOfService,
-        ]
+            PropertyIdentifier::Units,
+        ];
+        if self.min_pres_value.is_some() {
+            properties.push(PropertyIdentifier::MinPresValue);
+        }
+        if self.max_pres_value.is_some() {
+            properties.push(PropertyIdentifier::MaxPresValue);
+        }
+        if self.resolution.is_some() {
+            properties.push(PropertyIdentifier::Resolution);
+        }
+        if self.cov_increment.is_some() {
+            properties.push(PropertyIdentifier::CovIncrement);
+        }
+        properties
     }
 }
 
@@ -376,27 +771,63 @@ impl BacnetObject for AnalogOutput {
             PropertyIdentifier::ObjectName => {
                 Ok(PropertyValue::CharacterString(self.object_name.clone()))
             }
-            PropertyIdentifier::ObjectType => {
-                Ok(PropertyValue::Enumerated(ObjectType::AnalogOutput as u32))
-            }
+            PropertyIdentifier::ObjectType => Ok(PropertyValue::Enumerated(u32::from(
+                ObjectType::AnalogOutput,
+            ))),
             PropertyIdentifier::PresentValue => Ok(PropertyValue::Real(self.present_value)),
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::DeviceType => {
+                Ok(PropertyValue::CharacterString(self.device_type.clone()))
+            }
+            PropertyIdentifier::StatusFlags => {
+                Ok(status_flags_bit_string(self.current_status_flags()))
+            }
+            PropertyIdentifier::EventState => {
+                Ok(PropertyValue::Enumerated(self.event_state as u32))
+            }
+            PropertyIdentifier::Reliability => {
+                Ok(PropertyValue::Enumerated(self.reliability as u32))
+            }
             PropertyIdentifier::OutOfService => Ok(PropertyValue::Boolean(self.out_of_service)),
-            PropertyIdentifier::PriorityArray => {
-                let array: Vec<PropertyValue> = self
-                    .priority_array
-                    .iter()
-                    .map(|&v| match v {
-                        Some(val) => PropertyValue::Real(val),
-                        None => PropertyValue::Null,
-                    })
-                    .collect();
-                Ok(PropertyValue::Array(array))
+            PropertyIdentifier::Units => Ok(PropertyValue::Enumerated(self.units.to_u32())),
+            PropertyIdentifier::PriorityArray => Ok(self
+                .commandable
+                .priority_array_value(|&value| PropertyValue::Real(value))),
+            PropertyIdentifier::RelinquishDefault => {
+                Ok(PropertyValue::Real(*self.commandable.relinquish_default()))
             }
+            PropertyIdentifier::MinPresValue => self
+                .min_pres_value
+                .map(PropertyValue::Real)
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::MaxPresValue => self
+                .max_pres_value
+                .map(PropertyValue::Real)
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::Resolution => self
+                .resolution
+                .map(PropertyValue::Real)
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::CovIncrement => self
+                .cov_increment
+                .map(PropertyValue::Real)
+                .ok_or(ObjectError::UnknownProperty),
             _ => Err(ObjectError::UnknownProperty),
         }
     }
 
     fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        self.set_property_with_priority(property, value, DEFAULT_COMMAND_PRIORITY)
+    }
+
+    fn set_property_with_priority(
+        &mut self,
+        property: PropertyIdentifier,
+        value: PropertyValue,
+        priority: u8,
+    ) -> Result<()> {
         match property {
             PropertyIdentifier::ObjectName => {
                 if let PropertyValue::CharacterString(name) = value {
@@ -406,10 +837,23 @@ impl BacnetObject for AnalogOutput {
                     Err(ObjectError::InvalidPropertyType)
                 }
             }
-            PropertyIdentifier::PresentValue => {
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::PresentValue => match value {
+                PropertyValue::Real(val) => self.write_priority(priority, Some(val)),
+                // Writing NULL relinquishes the command at this priority
+                PropertyValue::Null => self.write_priority(priority, None),
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            PropertyIdentifier::RelinquishDefault => {
                 if let PropertyValue::Real(val) = value {
-                    // Write to priority 8 (manual operator) by default
-                    self.write_priority(8, Some(val))
+                    self.set_relinquish_default(val)
                 } else {
                     Err(ObjectError::InvalidPropertyType)
                 }
@@ -422,6 +866,27 @@ impl BacnetObject for AnalogOutput {
                     Err(ObjectError::InvalidPropertyType)
                 }
             }
+            PropertyIdentifier::Units => {
+                if let PropertyValue::Enumerated(units) = value {
+                    self.units = EngineeringUnits::from_u32(units);
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::CovIncrement => {
+                if let PropertyValue::Real(increment) = value {
+                    if increment < 0.0 {
+                        return Err(ObjectError::InvalidValue(
+                            "COV increment must not be negative".to_string(),
+                        ));
+                    }
+                    self.cov_increment = Some(increment);
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
             _ => Err(ObjectError::PropertyNotWritable),
         }
     }
@@ -430,20 +895,44 @@ impl BacnetObject for AnalogOutput {
         matches!(
             property,
             PropertyIdentifier::ObjectName
+                | PropertyIdentifier::Description
                 | PropertyIdentifier::PresentValue
+                | PropertyIdentifier::RelinquishDefault
                 | PropertyIdentifier::OutOfService
+                | PropertyIdentifier::Units
+                | PropertyIdentifier::CovIncrement
         )
     }
 
     fn property_list(&self) -> Vec<PropertyIdentifier> {
-        vec![
+        let mut properties = vec![
             PropertyIdentifier::ObjectIdentifier,
             PropertyIdentifier::ObjectName,
             PropertyIdentifier::ObjectType,
             PropertyIdentifier::PresentValue,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::DeviceType,
+            PropertyIdentifier::StatusFlags,
+            PropertyIdentifier::EventState,
+            PropertyIdentifier::Reliability,
             PropertyIdentifier::OutOfService,
+            PropertyIdentifier::Units,
             PropertyIdentifier::PriorityArray,
-        ]
+            PropertyIdentifier::RelinquishDefault,
+        ];
+        if self.min_pres_value.is_some() {
+            properties.push(PropertyIdentifier::MinPresValue);
+        }
+        if self.max_pres_value.is_some() {
+            properties.push(PropertyIdentifier::MaxPresValue);
+        }
+        if self.resolution.is_some() {
+            properties.push(PropertyIdentifier::Resolution);
+        }
+        if self.cov_increment.is_some() {
+            properties.push(PropertyIdentifier::CovIncrement);
+        }
+        properties
     }
 }
 
@@ -460,27 +949,58 @@ impl BacnetObject for AnalogValue {
             PropertyIdentifier::ObjectName => {
                 Ok(PropertyValue::CharacterString(self.object_name.clone()))
             }
-            PropertyIdentifier::ObjectType => {
-                Ok(PropertyValue::Enumerated(ObjectType::AnalogValue as u32))
-            }
+            PropertyIdentifier::ObjectType => Ok(PropertyValue::Enumerated(u32::from(
+                ObjectType::AnalogValue,
+            ))),
             PropertyIdentifier::PresentValue => Ok(PropertyValue::Real(self.present_value)),
-            PropertyIdentifier::OutOfService => Ok(PropertyValue::Boolean(self.out_of_service)),
-            PropertyIdentifier::PriorityArray => {
-                let array: Vec<PropertyValue> = self
-                    .priority_array
-                    .iter()
-                    .map(|&v| match v {
-                        Some(val) => PropertyValue::Real(val),
-                        None => PropertyValue::Null,
-                    })
-                    .collect();
-                Ok(PropertyValue::Array(array))
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
             }
-            _ => Err(ObjectError::UnknownProperty),
+            PropertyIdentifier::StatusFlags => {
+                Ok(status_flags_bit_string(self.current_status_flags()))
+            }
+            PropertyIdentifier::EventState => {
+                Ok(PropertyValue::Enumerated(self.event_state as u32))
+            }
+            PropertyIdentifier::Reliability => {
+                Ok(PropertyValue::Enumerated(self.reliability as u32))
+            }
+            PropertyIdentifier::OutOfService => Ok(PropertyValue::Boolean(self.out_of_service)),
+            PropertyIdentifier::Units => Ok(PropertyValue::Enumerated(self.units.to_u32())),
+            PropertyIdentifier::PriorityArray => self
+                .commandable
+                .as_ref()
+                .map(|commandable| {
+                    commandable.priority_array_value(|&value| PropertyValue::Real(value))
+                })
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::RelinquishDefault => self
+                .commandable
+                .as_ref()
+                .map(|commandable| PropertyValue::Real(*commandable.relinquish_default()))
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::CovIncrement => self
+                .cov_increment
+                .map(PropertyValue::Real)
+                .ok_or(ObjectError::UnknownProperty),
+            _ => self
+                .intrinsic_reporting
+                .as_ref()
+                .and_then(|reporting| reporting.get_property(property))
+                .ok_or(ObjectError::UnknownProperty),
         }
     }
 
     fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        self.set_property_with_priority(property, value, DEFAULT_COMMAND_PRIORITY)
+    }
+
+    fn set_property_with_priority(
+        &mut self,
+        property: PropertyIdentifier,
+        value: PropertyValue,
+        priority: u8,
+    ) -> Result<()> {
         match property {
             PropertyIdentifier::ObjectName => {
                 if let PropertyValue::CharacterString(name) = value {
@@ -490,10 +1010,33 @@ impl BacnetObject for AnalogValue {
                     Err(ObjectError::InvalidPropertyType)
                 }
             }
-            PropertyIdentifier::PresentValue => {
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::PresentValue => match value {
+                PropertyValue::Real(val) if self.is_commandable() => {
+                    self.write_priority(priority, Some(val))
+                }
+                PropertyValue::Real(val) => {
+                    self.present_value = val;
+                    Ok(())
+                }
+                // Writing NULL relinquishes the command at this priority
+                PropertyValue::Null if self.is_commandable() => self.write_priority(priority, None),
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            PropertyIdentifier::RelinquishDefault if self.is_commandable() => {
                 if let PropertyValue::Real(val) = value {
-                    // Write to priority 8 (manual operator) by default
-                    self.write_priority(8, Some(val))
+                    if let Some(commandable) = self.commandable.as_mut() {
+                        commandable.set_relinquish_default(val);
+                    }
+                    self.update_present_value();
+                    Ok(())
                 } else {
                     Err(ObjectError::InvalidPropertyType)
                 }
@@ -506,28 +1049,76 @@ impl BacnetObject for AnalogValue {
                     Err(ObjectError::InvalidPropertyType)
                 }
             }
-            _ => Err(ObjectError::PropertyNotWritable),
+            PropertyIdentifier::Units => {
+                if let PropertyValue::Enumerated(units) = value {
+                    self.units = EngineeringUnits::from_u32(units);
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::CovIncrement => {
+                if let PropertyValue::Real(increment) = value {
+                    if increment < 0.0 {
+                        return Err(ObjectError::InvalidValue(
+                            "COV increment must not be negative".to_string(),
+                        ));
+                    }
+                    self.cov_increment = Some(increment);
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            _ => match self.intrinsic_reporting.as_mut() {
+                Some(reporting) if AnalogLimitReporting::is_property_writable(property) => {
+                    reporting.set_property(property, value)
+                }
+                _ => Err(ObjectError::PropertyNotWritable),
+            },
         }
     }
 
     fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
-        matches!(
-            property,
+        match property {
             PropertyIdentifier::ObjectName
-                | PropertyIdentifier::PresentValue
-                | PropertyIdentifier::OutOfService
-        )
+            | PropertyIdentifier::Description
+            | PropertyIdentifier::PresentValue
+            | PropertyIdentifier::OutOfService
+            | PropertyIdentifier::Units
+            | PropertyIdentifier::CovIncrement => true,
+            PropertyIdentifier::RelinquishDefault => self.is_commandable(),
+            _ => {
+                self.intrinsic_reporting.is_some()
+                    && AnalogLimitReporting::is_property_writable(property)
+            }
+        }
     }
 
     fn property_list(&self) -> Vec<PropertyIdentifier> {
-        vec![
+        let mut properties = vec![
             PropertyIdentifier::ObjectIdentifier,
             PropertyIdentifier::ObjectName,
             PropertyIdentifier::ObjectType,
             PropertyIdentifier::PresentValue,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::StatusFlags,
+            PropertyIdentifier::EventState,
+            PropertyIdentifier::Reliability,
             PropertyIdentifier::OutOfService,
-            PropertyIdentifier::PriorityArray,
-        ]
+            PropertyIdentifier::Units,
+        ];
+        if self.is_commandable() {
+            properties.push(PropertyIdentifier::PriorityArray);
+            properties.push(PropertyIdentifier::RelinquishDefault);
+        }
+        if self.cov_increment.is_some() {
+            properties.push(PropertyIdentifier::CovIncrement);
+        }
+        if self.intrinsic_reporting.is_some() {
+            properties.extend(AnalogLimitReporting::property_list());
+        }
+        properties
     }
 }
 
@@ -544,6 +1135,76 @@ mod tests {
         assert!(!ai.out_of_service);
     }
 
+    #[test]
+    fn test_analog_input_out_of_service() {
+        let mut ai = AnalogInput::new(1, "Supply Air Temp".to_string());
+        ai.min_pres_value = Some(-40.0);
+        ai.max_pres_value = Some(120.0);
+
+        // Present value follows the sensor and is read-only while in service
+        ai.update_from_sensor(21.5);
+        assert_eq!(ai.present_value, 21.5);
+        assert!(!ai.is_property_writable(PropertyIdentifier::PresentValue));
+        assert!(matches!(
+            ai.set_property(PropertyIdentifier::PresentValue, PropertyValue::Real(30.0)),
+            Err(ObjectError::WriteAccessDenied)
+        ));
+
+        // Out of service decouples the sensor and allows overriding the value
+        ai.set_property(
+            PropertyIdentifier::OutOfService,
+            PropertyValue::Boolean(true),
+        )
+        .unwrap();
+        ai.set_property(PropertyIdentifier::PresentValue, PropertyValue::Real(30.0))
+            .unwrap();
+        ai.update_from_sensor(22.0);
+        assert_eq!(ai.present_value, 30.0);
+
+        if let Ok(PropertyValue::BitString(flags)) =
+            ai.get_property(PropertyIdentifier::StatusFlags)
+        {
+            assert_eq!(flags, vec![false, false, false, true]);
+        } else {
+            panic!("Expected BitString");
+        }
+
+        // Back in service, out-of-range readings are flagged as unreliable
+        ai.set_property(
+            PropertyIdentifier::OutOfService,
+            PropertyValue::Boolean(false),
+        )
+        .unwrap();
+        ai.update_from_sensor(150.0);
+        assert_eq!(ai.reliability, Reliability::OverRange);
+        assert_eq!(ai.current_status_flags() & 0x04, 0x04);
+    }
+
+    #[test]
+    fn test_analog_input_optional_properties() {
+        let mut ai = AnalogInput::new(2, "Zone Temp".to_string());
+        assert!(ai.get_property(PropertyIdentifier::CovIncrement).is_err());
+        assert!(!ai
+            .property_list()
+            .contains(&PropertyIdentifier::CovIncrement));
+
+        ai.set_property(PropertyIdentifier::CovIncrement, PropertyValue::Real(0.5))
+            .unwrap();
+        ai.set_property(
+            PropertyIdentifier::Units,
+            PropertyValue::Enumerated(EngineeringUnits::DegreesCelsius.to_u32()),
+        )
+        .unwrap();
+
+        assert!(ai
+            .property_list()
+            .contains(&PropertyIdentifier::CovIncrement));
+        assert_eq!(ai.units, EngineeringUnits::DegreesCelsius);
+        assert!(ai
+            .set_property(PropertyIdentifier::CovIncrement, PropertyValue::Real(-1.0))
+            .is_err());
+    }
+
     #[test]
     fn test_analog_output_priority() {
         let mut ao = AnalogOutput::new(1, "Damper Position".to_string());
@@ -565,10 +1226,70 @@ mod tests {
 
         // Release all priorities
         ao.write_priority(8, None).unwrap();
-        assert_eq!(ao.present_value, ao.relinquish_default);
+        assert_eq!(ao.present_value, *ao.commandable.relinquish_default());
         assert_eq!(ao.get_effective_priority(), None);
     }
 
+    #[test]
+    fn test_analog_output_write_arbitration() {
+        let mut ao = AnalogOutput::new(2, "Valve Position".to_string());
+        ao.set_property(
+            PropertyIdentifier::RelinquishDefault,
+            PropertyValue::Real(10.0),
+        )
+        .unwrap();
+        assert_eq!(ao.present_value, 10.0);
+
+        // Writes without an explicit priority land at priority 16
+        ao.set_property(PropertyIdentifier::PresentValue, PropertyValue::Real(40.0))
+            .unwrap();
+        assert_eq!(ao.get_effective_priority(), Some(16));
+
+        ao.set_property_with_priority(
+            PropertyIdentifier::PresentValue,
+            PropertyValue::Real(90.0),
+            5,
+        )
+        .unwrap();
+        assert_eq!(ao.present_value, 90.0);
+
+        if let Ok(PropertyValue::Array(slots)) = ao.get_property(PropertyIdentifier::PriorityArray)
+        {
+            assert_eq!(slots.len(), 16);
+            assert!(matches!(slots[4], PropertyValue::Real(v) if v == 90.0));
+            assert!(matches!(slots[15], PropertyValue::Real(v) if v == 40.0));
+            assert!(matches!(slots[0], PropertyValue::Null));
+        } else {
+            panic!("Expected Array");
+        }
+
+        // Writing NULL relinquishes the slot
+        ao.set_property_with_priority(PropertyIdentifier::PresentValue, PropertyValue::Null, 5)
+            .unwrap();
+        assert_eq!(ao.present_value, 40.0);
+        ao.set_property(PropertyIdentifier::PresentValue, PropertyValue::Null)
+            .unwrap();
+        assert_eq!(ao.present_value, 10.0);
+        assert_eq!(ao.get_effective_priority(), None);
+
+        // Commands outside the configured range are rejected
+        ao.max_pres_value = Some(100.0);
+        assert!(ao
+            .set_property_with_priority(
+                PropertyIdentifier::PresentValue,
+                PropertyValue::Real(120.0),
+                8
+            )
+            .is_err());
+        assert!(ao
+            .set_property_with_priority(
+                PropertyIdentifier::PresentValue,
+                PropertyValue::Real(50.0),
+                17
+            )
+            .is_err());
+    }
+
     #[test]
     fn test_analog_object_properties() {
         let mut av = AnalogValue::new(1, "Test Value".to_string());
@@ -591,6 +1312,83 @@ mod tests {
         assert!(!av.is_property_writable(PropertyIdentifier::ObjectIdentifier));
     }
 
+    #[test]
+    fn test_analog_value_commandable() {
+        let mut av = AnalogValue::new_commandable(3, "Zone Setpoint".to_string(), 21.0);
+        assert_eq!(av.present_value, 21.0);
+        assert!(av
+            .property_list()
+            .contains(&PropertyIdentifier::PriorityArray));
+
+        av.set_property_with_priority(
+            PropertyIdentifier::PresentValue,
+            PropertyValue::Real(23.5),
+            8,
+        )
+        .unwrap();
+        assert_eq!(av.present_value, 23.5);
+
+        av.set_property_with_priority(PropertyIdentifier::PresentValue, PropertyValue::Null, 8)
+            .unwrap();
+        assert_eq!(av.present_value, 21.0);
+
+        // Non-commandable values are written directly and reject NULL
+        let mut plain = AnalogValue::new(4, "Offset".to_string());
+        plain
+            .set_property(PropertyIdentifier::PresentValue, PropertyValue::Real(1.5))
+            .unwrap();
+        assert_eq!(plain.present_value, 1.5);
+        assert!(plain
+            .set_property(PropertyIdentifier::PresentValue, PropertyValue::Null)
+            .is_err());
+        assert!(plain.write_priority(8, Some(2.0)).is_err());
+        assert!(plain
+            .get_property(PropertyIdentifier::PriorityArray)
+            .is_err());
+        assert!(!plain.is_property_writable(PropertyIdentifier::RelinquishDefault));
+    }
+
+    #[test]
+    fn test_analog_value_intrinsic_reporting() {
+        let mut av = AnalogValue::new(5, "Space Temp Setpoint".to_string());
+        assert!(av.get_property(PropertyIdentifier::HighLimit).is_err());
+        assert!(!av.is_property_writable(PropertyIdentifier::HighLimit));
+
+        av.intrinsic_reporting = Some(AnalogLimitReporting::new(1, 15.0, 30.0));
+        assert!(av.property_list().contains(&PropertyIdentifier::Deadband));
+
+        av.set_property(PropertyIdentifier::HighLimit, PropertyValue::Real(28.0))
+            .unwrap();
+        assert!(matches!(
+            av.get_property(PropertyIdentifier::HighLimit),
+            Ok(PropertyValue::Real(v)) if v == 28.0
+        ));
+
+        av.set_property(
+            PropertyIdentifier::LimitEnable,
+            PropertyValue::BitString(vec![false, true]),
+        )
+        .unwrap();
+        assert_eq!(
+            av.intrinsic_reporting.as_ref().unwrap().limit_enable,
+            (false, true)
+        );
+
+        assert!(av
+            .set_property(PropertyIdentifier::Deadband, PropertyValue::Real(-1.0))
+            .is_err());
+        assert!(av
+            .set_property(PropertyIdentifier::NotifyType, PropertyValue::Enumerated(7))
+            .is_err());
+        assert!(matches!(
+            av.set_property(
+                PropertyIdentifier::AckedTransitions,
+                PropertyValue::BitString(vec![false, false, false])
+            ),
+            Err(ObjectError::PropertyNotWritable)
+        ));
+    }
+
     #[test]
     fn test_status_flags() {
         let mut ai = AnalogInput::new(1, "Test".to_string());
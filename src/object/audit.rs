@@ -0,0 +1,1099 @@
+//! Audit Log and Audit Reporter Object Type Implementations
+//!
+//! This module implements the auditing object types added to ASHRAE 135 by
+//! Addendum 135-2016bi:
+//!
+//! - **Audit Reporter**: decides which operations on the device are audited,
+//!   collects BACnetAuditNotifications for them and releases them in batches
+//!   after Maximum_Send_Delay, or at once when Send_Now is written
+//! - **Audit Log**: a circular buffer of BACnetAuditLogRecords that stores the
+//!   notifications it receives, served to clients through ReadRange
+//!
+//! The application reports each operation to the Audit Reporter and delivers
+//! the batches taken with [`AuditReporter::take_notifications`], either with
+//! AuditNotification requests or directly to a local [`AuditLog`].
+
+use crate::object::trendlog::{logging_active, LogBuffer, LogEntry};
+use crate::object::{
+    current_date_time, current_status_flags, status_flags_bit_string, BacnetObject, EventState,
+    LogBufferRange, ObjectError, ObjectIdentifier, ObjectType, PropertyIdentifier, PropertyValue,
+    Recipient, Reliability, Result,
+};
+use crate::service::BacnetDateTime;
+use core::time::Duration;
+
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, string::String, vec::Vec};
+
+/// Audited operation (BACnetAuditOperation)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum AuditOperation {
+    Read = 0,
+    Write = 1,
+    Create = 2,
+    Delete = 3,
+    LifeSafety = 4,
+    AcknowledgeAlarm = 5,
+    DeviceDisableComm = 6,
+    DeviceEnableComm = 7,
+    DeviceReset = 8,
+    DeviceBackup = 9,
+    DeviceRestore = 10,
+    Subscription = 11,
+    Notification = 12,
+    AuditingFailure = 13,
+    NetworkChanges = 14,
+    General = 15,
+}
+
+impl TryFrom<u32> for AuditOperation {
+    type Error = ObjectError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            0 => Ok(AuditOperation::Read),
+            1 => Ok(AuditOperation::Write),
+            2 => Ok(AuditOperation::Create),
+            3 => Ok(AuditOperation::Delete),
+            4 => Ok(AuditOperation::LifeSafety),
+            5 => Ok(AuditOperation::AcknowledgeAlarm),
+            6 => Ok(AuditOperation::DeviceDisableComm),
+            7 => Ok(AuditOperation::DeviceEnableComm),
+            8 => Ok(AuditOperation::DeviceReset),
+            9 => Ok(AuditOperation::DeviceBackup),
+            10 => Ok(AuditOperation::DeviceRestore),
+            11 => Ok(AuditOperation::Subscription),
+            12 => Ok(AuditOperation::Notification),
+            13 => Ok(AuditOperation::AuditingFailure),
+            14 => Ok(AuditOperation::NetworkChanges),
+            15 => Ok(AuditOperation::General),
+            _ => Err(ObjectError::InvalidValue(format!(
+                "Invalid audit operation: {}",
+                value
+            ))),
+        }
+    }
+}
+
+impl AuditOperation {
+    /// Whether the operation changes the device's configuration rather than
+    /// its runtime state, for Audit_Level AUDIT_CONFIG
+    pub fn is_configuration(&self) -> bool {
+        !matches!(
+            self,
+            AuditOperation::Read
+                | AuditOperation::LifeSafety
+                | AuditOperation::AcknowledgeAlarm
+                | AuditOperation::Subscription
+                | AuditOperation::Notification
+        )
+    }
+}
+
+/// How much an Audit Reporter audits (BACnetAuditLevel)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum AuditLevel {
+    /// Nothing is audited
+    None = 0,
+    /// Every auditable operation is audited
+    AuditAll = 1,
+    /// Only configuration changes are audited
+    AuditConfig = 2,
+    /// The device's default level: everything except reads
+    Default = 3,
+}
+
+impl TryFrom<u32> for AuditLevel {
+    type Error = ObjectError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            0 => Ok(AuditLevel::None),
+            1 => Ok(AuditLevel::AuditAll),
+            2 => Ok(AuditLevel::AuditConfig),
+            3 => Ok(AuditLevel::Default),
+            _ => Err(ObjectError::InvalidValue(format!(
+                "Invalid audit level: {}",
+                value
+            ))),
+        }
+    }
+}
+
+/// Objects an Audit Reporter monitors (BACnetObjectSelector)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectSelector {
+    /// A single object
+    Object(ObjectIdentifier),
+    /// Every object of a type
+    Type(ObjectType),
+}
+
+impl ObjectSelector {
+    /// Whether the selector covers an object
+    pub fn matches(&self, object: &ObjectIdentifier) -> bool {
+        match self {
+            ObjectSelector::Object(selected) => selected == object,
+            ObjectSelector::Type(object_type) => *object_type == object.object_type,
+        }
+    }
+
+    /// Encode as ObjectIdentifier for an object, Enumerated for a type
+    pub fn to_property_value(&self) -> PropertyValue {
+        match self {
+            ObjectSelector::Object(object) => PropertyValue::ObjectIdentifier(*object),
+            ObjectSelector::Type(object_type) => PropertyValue::Enumerated(u32::from(*object_type)),
+        }
+    }
+
+    /// Decode from a property value
+    pub fn from_property_value(value: &PropertyValue) -> Result<Self> {
+        match value {
+            PropertyValue::ObjectIdentifier(object) => Ok(ObjectSelector::Object(*object)),
+            PropertyValue::Enumerated(object_type) => {
+                let object_type = u16::try_from(*object_type).map_err(|_| {
+                    ObjectError::InvalidValue(format!("Invalid object type: {}", object_type))
+                })?;
+                Ok(ObjectSelector::Type(ObjectType::try_from(object_type)?))
+            }
+            _ => Err(ObjectError::InvalidPropertyType),
+        }
+    }
+}
+
+/// A record of an audited operation (BACnetAuditNotification)
+///
+/// Encoded as a positional list of the seventeen fields in ASN.1 order, with Null
+/// standing in for absent optional fields.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditNotification {
+    /// When the operation was requested, by the source's clock
+    pub source_timestamp: Option<BacnetDateTime>,
+    /// When the operation was performed, by the target's clock
+    pub target_timestamp: Option<BacnetDateTime>,
+    /// Device that requested the operation
+    pub source_device: Recipient,
+    /// Object in the source device that requested the operation
+    pub source_object: Option<ObjectIdentifier>,
+    /// What was done
+    pub operation: AuditOperation,
+    /// Comment supplied by the source
+    pub source_comment: Option<String>,
+    /// Comment supplied by the target
+    pub target_comment: Option<String>,
+    /// Invoke ID of the request
+    pub invoke_id: Option<u8>,
+    /// User that requested the operation
+    pub source_user_id: Option<u16>,
+    /// Role of that user
+    pub source_user_role: Option<u8>,
+    /// Device the operation was performed on
+    pub target_device: Recipient,
+    /// Object the operation was performed on
+    pub target_object: Option<ObjectIdentifier>,
+    /// Property the operation was performed on
+    pub target_property: Option<PropertyIdentifier>,
+    /// Priority of a write
+    pub target_priority: Option<u8>,
+    /// Value written
+    pub target_value: Option<PropertyValue>,
+    /// Value of the property after the operation
+    pub current_value: Option<PropertyValue>,
+    /// Error class and code, if the operation failed
+    pub result: Option<(u32, u32)>,
+}
+
+impl AuditNotification {
+    /// Create a notification with no optional fields
+    pub fn new(
+        operation: AuditOperation,
+        source_device: Recipient,
+        target_device: Recipient,
+    ) -> Self {
+        Self {
+            source_timestamp: None,
+            target_timestamp: None,
+            source_device,
+            source_object: None,
+            operation,
+            source_comment: None,
+            target_comment: None,
+            invoke_id: None,
+            source_user_id: None,
+            source_user_role: None,
+            target_device,
+            target_object: None,
+            target_property: None,
+            target_priority: None,
+            target_value: None,
+            current_value: None,
+            result: None,
+        }
+    }
+
+    /// Encode as a property value
+    pub fn to_property_value(&self) -> PropertyValue {
+        fn or_null<T>(value: Option<T>, encode: impl FnOnce(T) -> PropertyValue) -> PropertyValue {
+            value.map(encode).unwrap_or(PropertyValue::Null)
+        }
+        let timestamp = |t: BacnetDateTime| {
+            PropertyValue::List(vec![
+                PropertyValue::Date(t.date),
+                PropertyValue::Time(t.time),
+            ])
+        };
+        PropertyValue::List(vec![
+            or_null(self.source_timestamp, timestamp),
+            or_null(self.target_timestamp, timestamp),
+            self.source_device.to_property_value(),
+            or_null(self.source_object, PropertyValue::ObjectIdentifier),
+            PropertyValue::Enumerated(self.operation as u32),
+            or_null(self.source_comment.clone(), PropertyValue::CharacterString),
+            or_null(self.target_comment.clone(), PropertyValue::CharacterString),
+            or_null(self.invoke_id, |id| {
+                PropertyValue::UnsignedInteger(id as u32)
+            }),
+            or_null(self.source_user_id, |id| {
+                PropertyValue::UnsignedInteger(id as u32)
+            }),
+            or_null(self.source_user_role, |role| {
+                PropertyValue::UnsignedInteger(role as u32)
+            }),
+            self.target_device.to_property_value(),
+            or_null(self.target_object, PropertyValue::ObjectIdentifier),
+            or_null(self.target_property, |p| {
+                PropertyValue::Enumerated(u32::from(p))
+            }),
+            or_null(self.target_priority, |p| {
+                PropertyValue::UnsignedInteger(p as u32)
+            }),
+            or_null(self.target_value.clone(), |v| v),
+            or_null(self.current_value.clone(), |v| v),
+            or_null(self.result, |(class, code)| {
+                PropertyValue::List(vec![
+                    PropertyValue::Enumerated(class),
+                    PropertyValue::Enumerated(code),
+                ])
+            }),
+        ])
+    }
+}
+
+/// Content of an audit log record
+#[derive(Debug, Clone, PartialEq)]
+pub enum AuditLogDatum {
+    /// Change in the status of the log itself
+    LogStatus {
+        log_disabled: bool,
+        buffer_purged: bool,
+        log_interrupted: bool,
+    },
+    /// An audited operation
+    Notification(Box<AuditNotification>),
+    /// Clock change, in seconds
+    TimeChange(f32),
+}
+
+/// A single entry of an Audit Log's Log_Buffer (BACnetAuditLogRecord)
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditLogRecord {
+    /// When the record was stored
+    pub timestamp: BacnetDateTime,
+    /// Recorded datum
+    pub log_datum: AuditLogDatum,
+}
+
+impl AuditLogRecord {
+    /// Encode as `[Date, Time, datum]`
+    pub fn to_property_value(&self) -> PropertyValue {
+        let datum = match &self.log_datum {
+            AuditLogDatum::LogStatus {
+                log_disabled,
+                buffer_purged,
+                log_interrupted,
+            } => PropertyValue::BitString(vec![*log_disabled, *buffer_purged, *log_interrupted]),
+            AuditLogDatum::Notification(notification) => notification.to_property_value(),
+            AuditLogDatum::TimeChange(seconds) => PropertyValue::Real(*seconds),
+        };
+        PropertyValue::List(vec![
+            PropertyValue::Date(self.timestamp.date),
+            PropertyValue::Time(self.timestamp.time),
+            datum,
+        ])
+    }
+}
+
+impl LogEntry for AuditLogRecord {
+    fn timestamp(&self) -> &BacnetDateTime {
+        &self.timestamp
+    }
+
+    fn log_status(timestamp: BacnetDateTime, log_disabled: bool, buffer_purged: bool) -> Self {
+        AuditLogRecord {
+            timestamp,
+            log_datum: AuditLogDatum::LogStatus {
+                log_disabled,
+                buffer_purged,
+                log_interrupted: false,
+            },
+        }
+    }
+}
+
+/// Audit Log object
+#[derive(Debug, Clone)]
+pub struct AuditLog {
+    /// Object identifier
+    pub identifier: ObjectIdentifier,
+    /// Object name
+    pub object_name: String,
+    /// Description
+    pub description: String,
+    /// Whether logging is enabled (Enable)
+    pub log_enable: bool,
+    /// Event state
+    pub event_state: EventState,
+    /// Reliability
+    pub reliability: Reliability,
+    buffer: LogBuffer<AuditLogRecord>,
+}
+
+impl AuditLog {
+    /// Create a new Audit Log with the given buffer capacity
+    pub fn new(instance: u32, object_name: String, buffer_size: u32) -> Self {
+        Self {
+            identifier: ObjectIdentifier::new(ObjectType::AuditLog, instance),
+            object_name,
+            description: String::new(),
+            log_enable: false,
+            event_state: EventState::Normal,
+            reliability: Reliability::NoFaultDetected,
+            buffer: LogBuffer::new(buffer_size),
+        }
+    }
+
+    /// Number of records currently in the buffer
+    pub fn record_count(&self) -> u32 {
+        self.buffer.len()
+    }
+
+    /// Number of records ever collected
+    pub fn total_record_count(&self) -> u32 {
+        self.buffer.total_record_count()
+    }
+
+    /// Maximum number of records the buffer holds
+    pub fn buffer_size(&self) -> u32 {
+        self.buffer.buffer_size()
+    }
+
+    /// Records in the buffer, oldest first
+    pub fn records(&self) -> impl Iterator<Item = &AuditLogRecord> {
+        self.buffer.records()
+    }
+
+    /// Store an audit notification received at `timestamp`
+    ///
+    /// Returns `false` if the notification was discarded because logging is
+    /// disabled.
+    pub fn log_notification(
+        &mut self,
+        timestamp: BacnetDateTime,
+        notification: AuditNotification,
+    ) -> bool {
+        if !logging_active(self.log_enable, None, None, &timestamp) {
+            return false;
+        }
+        self.buffer.push(AuditLogRecord {
+            timestamp,
+            log_datum: AuditLogDatum::Notification(Box::new(notification)),
+        });
+        true
+    }
+
+    /// Enable or disable logging, recording the status change
+    pub fn set_log_enable(&mut self, enable: bool, timestamp: Option<BacnetDateTime>) {
+        if enable == self.log_enable {
+            return;
+        }
+        self.log_enable = enable;
+        self.buffer.push(AuditLogRecord::log_status(
+            timestamp.unwrap_or_else(BacnetDateTime::unspecified),
+            !enable,
+            false,
+        ));
+    }
+
+    /// Remove all records, as when zero is written to Record_Count
+    pub fn purge(&mut self, timestamp: Option<BacnetDateTime>) {
+        self.buffer.clear();
+        if self.log_enable {
+            self.buffer.push(AuditLogRecord::log_status(
+                timestamp.unwrap_or_else(BacnetDateTime::unspecified),
+                false,
+                true,
+            ));
+        }
+    }
+
+    /// Records by 1-based position, for ReadRange by position
+    pub fn read_range_by_position(
+        &self,
+        reference_index: u32,
+        count: i32,
+    ) -> LogBufferRange<AuditLogRecord> {
+        self.buffer.by_position(reference_index, count)
+    }
+
+    /// Records by sequence number, for ReadRange by sequence number
+    pub fn read_range_by_sequence(
+        &self,
+        reference_sequence: u32,
+        count: i32,
+    ) -> LogBufferRange<AuditLogRecord> {
+        self.buffer.by_sequence(reference_sequence, count)
+    }
+
+    /// Records relative to a time, for ReadRange by time
+    pub fn read_range_by_time(
+        &self,
+        reference_time: &BacnetDateTime,
+        count: i32,
+    ) -> LogBufferRange<AuditLogRecord> {
+        self.buffer.by_time(reference_time, count)
+    }
+
+    /// Records that satisfy `matches`, for AuditLogQuery
+    ///
+    /// Starts at `start_at_sequence_number`, or at the oldest record without
+    /// one, and returns at most `count` records with their sequence numbers,
+    /// together with whether no later record matches.
+    pub fn query(
+        &self,
+        start_at_sequence_number: Option<u32>,
+        count: usize,
+        mut matches: impl FnMut(&AuditLogRecord) -> bool,
+    ) -> (Vec<(u32, AuditLogRecord)>, bool) {
+        let start = start_at_sequence_number.unwrap_or(0);
+        let mut found = self
+            .buffer
+            .sequenced()
+            .filter(|(sequence, record)| *sequence >= start && matches(record));
+        let records = found
+            .by_ref()
+            .take(count)
+            .map(|(sequence, record)| (sequence, record.clone()))
+            .collect();
+        (records, found.next().is_none())
+    }
+
+    /// Sequence number of the first record stored after `time`, to start a
+    /// query from a point in time
+    pub fn first_sequence_after(&self, time: &BacnetDateTime) -> Option<u32> {
+        self.buffer.by_time(time, 1).first_sequence_number
+    }
+
+    fn current_status_flags(&self) -> u8 {
+        current_status_flags(0, self.event_state, self.reliability, false)
+    }
+}
+
+impl BacnetObject for AuditLog {
+    fn identifier(&self) -> ObjectIdentifier {
+        self.identifier
+    }
+
+    fn get_property(&self, property: PropertyIdentifier) -> Result<PropertyValue> {
+        match property {
+            PropertyIdentifier::ObjectIdentifier => {
+                Ok(PropertyValue::ObjectIdentifier(self.identifier))
+            }
+            PropertyIdentifier::ObjectName => {
+                Ok(PropertyValue::CharacterString(self.object_name.clone()))
+            }
+            PropertyIdentifier::ObjectType => {
+                Ok(PropertyValue::Enumerated(u32::from(ObjectType::AuditLog)))
+            }
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::LogEnable => Ok(PropertyValue::Boolean(self.log_enable)),
+            PropertyIdentifier::BufferSize => {
+                Ok(PropertyValue::UnsignedInteger(self.buffer_size()))
+            }
+            PropertyIdentifier::LogBuffer => Ok(PropertyValue::List(
+                self.records()
+                    .map(AuditLogRecord::to_property_value)
+                    .collect(),
+            )),
+            PropertyIdentifier::RecordCount => {
+                Ok(PropertyValue::UnsignedInteger(self.record_count()))
+            }
+            PropertyIdentifier::TotalRecordCount => {
+                Ok(PropertyValue::UnsignedInteger(self.total_record_count()))
+            }
+            PropertyIdentifier::StatusFlags => {
+                Ok(status_flags_bit_string(self.current_status_flags()))
+            }
+            PropertyIdentifier::EventState => {
+                Ok(PropertyValue::Enumerated(self.event_state as u32))
+            }
+            PropertyIdentifier::Reliability => {
+                Ok(PropertyValue::Enumerated(self.reliability as u32))
+            }
+            _ => Err(ObjectError::UnknownProperty),
+        }
+    }
+
+    fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        match property {
+            PropertyIdentifier::ObjectName => {
+                if let PropertyValue::CharacterString(name) = value {
+                    self.object_name = name;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::LogEnable => {
+                if let PropertyValue::Boolean(enable) = value {
+                    self.set_log_enable(enable, current_date_time());
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::BufferSize => {
+                if let PropertyValue::UnsignedInteger(size) = value {
+                    self.buffer.resize(size);
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::RecordCount => match value {
+                PropertyValue::UnsignedInteger(0) => {
+                    self.purge(current_date_time());
+                    Ok(())
+                }
+                PropertyValue::UnsignedInteger(_) => Err(ObjectError::InvalidValue(
+                    "Record_Count may only be written with zero".to_string(),
+                )),
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            _ => Err(ObjectError::PropertyNotWritable),
+        }
+    }
+
+    fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
+        matches!(
+            property,
+            PropertyIdentifier::ObjectName
+                | PropertyIdentifier::Description
+                | PropertyIdentifier::LogEnable
+                | PropertyIdentifier::BufferSize
+                | PropertyIdentifier::RecordCount
+        )
+    }
+
+    fn property_list(&self) -> Vec<PropertyIdentifier> {
+        vec![
+            PropertyIdentifier::ObjectIdentifier,
+            PropertyIdentifier::ObjectName,
+            PropertyIdentifier::ObjectType,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::StatusFlags,
+            PropertyIdentifier::EventState,
+            PropertyIdentifier::Reliability,
+            PropertyIdentifier::LogEnable,
+            PropertyIdentifier::BufferSize,
+            PropertyIdentifier::LogBuffer,
+            PropertyIdentifier::RecordCount,
+            PropertyIdentifier::TotalRecordCount,
+        ]
+    }
+
+    fn as_audit_log(&self) -> Option<&AuditLog> {
+        Some(self)
+    }
+
+    fn as_audit_log_mut(&mut self) -> Option<&mut AuditLog> {
+        Some(self)
+    }
+}
+
+/// Audit Reporter object
+#[derive(Debug, Clone)]
+pub struct AuditReporter {
+    /// Object identifier
+    pub identifier: ObjectIdentifier,
+    /// Object name
+    pub object_name: String,
+    /// Description
+    pub description: String,
+    /// Status flags
+    pub status_flags: u8,
+    /// Event state
+    pub event_state: EventState,
+    /// Reliability
+    pub reliability: Reliability,
+    /// Out of service
+    pub out_of_service: bool,
+    /// How much is audited
+    pub audit_level: AuditLevel,
+    /// Where notifications are sent
+    pub audit_notification_recipient: Recipient,
+    /// Write priorities that are audited, indexed by priority - 1
+    pub audit_priority_filter: [bool; 16],
+    /// Operations that are audited, indexed by BACnetAuditOperation
+    pub auditable_operations: [bool; 16],
+    /// Whether this reporter reports operations it requests itself
+    pub audit_source_reporter: bool,
+    /// Whether notifications are sent confirmed
+    pub issue_confirmed_notifications: bool,
+    /// Longest a notification is held before sending, in seconds
+    pub maximum_send_delay: u32,
+    /// Objects audited; empty audits every object
+    pub monitored_objects: Vec<ObjectSelector>,
+    pending: Vec<AuditNotification>,
+    held_for: Duration,
+    send_now: bool,
+}
+
+impl AuditReporter {
+    /// Create a new Audit Reporter sending to the given recipient
+    pub fn new(instance: u32, object_name: String, recipient: Recipient) -> Self {
+        Self {
+            identifier: ObjectIdentifier::new(ObjectType::AuditReporter, instance),
+            object_name,
+            description: String::new(),
+            status_flags: 0,
+            event_state: EventState::Normal,
+            reliability: Reliability::NoFaultDetected,
+            out_of_service: false,
+            audit_level: AuditLevel::Default,
+            audit_notification_recipient: recipient,
+            audit_priority_filter: [true; 16],
+            auditable_operations: [true; 16],
+            audit_source_reporter: false,
+            issue_confirmed_notifications: false,
+            maximum_send_delay: 10,
+            monitored_objects: Vec::new(),
+            pending: Vec::new(),
+            held_for: Duration::ZERO,
+            send_now: false,
+        }
+    }
+
+    /// Whether an operation is audited under the current configuration
+    pub fn is_audited(&self, notification: &AuditNotification) -> bool {
+        let level = match self.audit_level {
+            AuditLevel::None => false,
+            AuditLevel::AuditAll => true,
+            AuditLevel::AuditConfig => notification.operation.is_configuration(),
+            AuditLevel::Default => notification.operation != AuditOperation::Read,
+        };
+        let priority = notification
+            .target_priority
+            .is_none_or(|p| (1..=16).contains(&p) && self.audit_priority_filter[(p - 1) as usize]);
+        let object = self.monitored_objects.is_empty()
+            || notification
+                .target_object
+                .is_some_and(|object| self.monitored_objects.iter().any(|s| s.matches(&object)));
+        level
+            && !self.out_of_service
+            && self.auditable_operations[notification.operation as usize]
+            && priority
+            && object
+    }
+
+    /// Report an operation performed on the device
+    ///
+    /// Returns `false` if the operation is not audited.
+    pub fn report(&mut self, notification: AuditNotification) -> bool {
+        if !self.is_audited(&notification) {
+            return false;
+        }
+        if self.pending.is_empty() {
+            self.held_for = Duration::ZERO;
+        }
+        self.pending.push(notification);
+        true
+    }
+
+    /// Number of notifications held for sending
+    pub fn pending_count(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Take the held notifications once Maximum_Send_Delay has passed since the
+    /// oldest was reported, or Send_Now was written
+    ///
+    /// The application sends them to Audit_Notification_Recipient.
+    pub fn take_notifications(&mut self) -> Vec<AuditNotification> {
+        let due =
+            self.send_now || self.held_for >= Duration::from_secs(self.maximum_send_delay as u64);
+        if !due || self.pending.is_empty() {
+            return Vec::new();
+        }
+        self.send_now = false;
+        self.held_for = Duration::ZERO;
+        core::mem::take(&mut self.pending)
+    }
+
+    /// Current Status_Flags, combining the stored flags with the state derived
+    /// from Event_State, Reliability and Out_Of_Service
+    pub fn current_status_flags(&self) -> u8 {
+        current_status_flags(
+            self.status_flags,
+            self.event_state,
+            self.reliability,
+            self.out_of_service,
+        )
+    }
+}
+
+fn bit_array(value: &PropertyValue) -> Result<[bool; 16]> {
+    match value {
+        PropertyValue::BitString(bits) => {
+            let mut array = [false; 16];
+            for (slot, bit) in array.iter_mut().zip(bits) {
+                *slot = *bit;
+            }
+            Ok(array)
+        }
+        _ => Err(ObjectError::InvalidPropertyType),
+    }
+}
+
+impl BacnetObject for AuditReporter {
+    fn identifier(&self) -> ObjectIdentifier {
+        self.identifier
+    }
+
+    fn get_property(&self, property: PropertyIdentifier) -> Result<PropertyValue> {
+        match property {
+            PropertyIdentifier::ObjectIdentifier => {
+                Ok(PropertyValue::ObjectIdentifier(self.identifier))
+            }
+            PropertyIdentifier::ObjectName => {
+                Ok(PropertyValue::CharacterString(self.object_name.clone()))
+            }
+            PropertyIdentifier::ObjectType => Ok(PropertyValue::Enumerated(u32::from(
+                ObjectType::AuditReporter,
+            ))),
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::StatusFlags => {
+                Ok(status_flags_bit_string(self.current_status_flags()))
+            }
+            PropertyIdentifier::EventState => {
+                Ok(PropertyValue::Enumerated(self.event_state as u32))
+            }
+            PropertyIdentifier::Reliability => {
+                Ok(PropertyValue::Enumerated(self.reliability as u32))
+            }
+            PropertyIdentifier::OutOfService => Ok(PropertyValue::Boolean(self.out_of_service)),
+            PropertyIdentifier::AuditLevel => {
+                Ok(PropertyValue::Enumerated(self.audit_level as u32))
+            }
+            PropertyIdentifier::AuditNotificationRecipient => {
+                Ok(self.audit_notification_recipient.to_property_value())
+            }
+            PropertyIdentifier::AuditPriorityFilter => Ok(PropertyValue::BitString(
+                self.audit_priority_filter.to_vec(),
+            )),
+            PropertyIdentifier::AuditableOperations => {
+                Ok(PropertyValue::BitString(self.auditable_operations.to_vec()))
+            }
+            PropertyIdentifier::AuditSourceReporter => {
+                Ok(PropertyValue::Boolean(self.audit_source_reporter))
+            }
+            PropertyIdentifier::IssueConfirmedNotifications => {
+                Ok(PropertyValue::Boolean(self.issue_confirmed_notifications))
+            }
+            PropertyIdentifier::MaximumSendDelay => {
+                Ok(PropertyValue::UnsignedInteger(self.maximum_send_delay))
+            }
+            PropertyIdentifier::MonitoredObjects => Ok(PropertyValue::List(
+                self.monitored_objects
+                    .iter()
+                    .map(ObjectSelector::to_property_value)
+                    .collect(),
+            )),
+            PropertyIdentifier::SendNow => Ok(PropertyValue::Boolean(self.send_now)),
+            _ => Err(ObjectError::UnknownProperty),
+        }
+    }
+
+    fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        match property {
+            PropertyIdentifier::ObjectName => {
+                if let PropertyValue::CharacterString(name) = value {
+                    self.object_name = name;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::OutOfService => {
+                if let PropertyValue::Boolean(oos) = value {
+                    self.out_of_service = oos;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::AuditLevel => {
+                if let PropertyValue::Enumerated(level) = value {
+                    self.audit_level = AuditLevel::try_from(level)?;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::AuditNotificationRecipient => {
+                self.audit_notification_recipient = Recipient::from_property_value(&value)?;
+                Ok(())
+            }
+            PropertyIdentifier::AuditPriorityFilter => {
+                self.audit_priority_filter = bit_array(&value)?;
+                Ok(())
+            }
+            PropertyIdentifier::AuditableOperations => {
+                self.auditable_operations = bit_array(&value)?;
+                Ok(())
+            }
+            PropertyIdentifier::AuditSourceReporter => {
+                if let PropertyValue::Boolean(enable) = value {
+                    self.audit_source_reporter = enable;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::IssueConfirmedNotifications => {
+                if let PropertyValue::Boolean(confirmed) = value {
+                    self.issue_confirmed_notifications = confirmed;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::MaximumSendDelay => {
+                if let PropertyValue::UnsignedInteger(delay) = value {
+                    self.maximum_send_delay = delay;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::MonitoredObjects => match &value {
+                PropertyValue::List(selectors) => {
+                    self.monitored_objects = selectors
+                        .iter()
+                        .map(ObjectSelector::from_property_value)
+                        .collect::<Result<Vec<_>>>()?;
+                    Ok(())
+                }
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            PropertyIdentifier::SendNow => {
+                if let PropertyValue::Boolean(send_now) = value {
+                    self.send_now = send_now && !self.pending.is_empty();
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            _ => Err(ObjectError::PropertyNotWritable),
+        }
+    }
+
+    fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
+        matches!(
+            property,
+            PropertyIdentifier::ObjectName
+                | PropertyIdentifier::Description
+                | PropertyIdentifier::OutOfService
+                | PropertyIdentifier::AuditLevel
+                | PropertyIdentifier::AuditNotificationRecipient
+                | PropertyIdentifier::AuditPriorityFilter
+                | PropertyIdentifier::AuditableOperations
+                | PropertyIdentifier::AuditSourceReporter
+                | PropertyIdentifier::IssueConfirmedNotifications
+                | PropertyIdentifier::MaximumSendDelay
+                | PropertyIdentifier::MonitoredObjects
+                | PropertyIdentifier::SendNow
+        )
+    }
+
+    fn property_list(&self) -> Vec<PropertyIdentifier> {
+        vec![
+            PropertyIdentifier::ObjectIdentifier,
+            PropertyIdentifier::ObjectName,
+            PropertyIdentifier::ObjectType,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::StatusFlags,
+            PropertyIdentifier::EventState,
+            PropertyIdentifier::Reliability,
+            PropertyIdentifier::OutOfService,
+            PropertyIdentifier::AuditLevel,
+            PropertyIdentifier::AuditNotificationRecipient,
+            PropertyIdentifier::AuditPriorityFilter,
+            PropertyIdentifier::AuditableOperations,
+            PropertyIdentifier::AuditSourceReporter,
+            PropertyIdentifier::IssueConfirmedNotifications,
+            PropertyIdentifier::MaximumSendDelay,
+            PropertyIdentifier::MonitoredObjects,
+            PropertyIdentifier::SendNow,
+        ]
+    }
+
+    fn advance_time(&mut self, elapsed: Duration) {
+        if !self.pending.is_empty() {
+            self.held_for = self.held_for.saturating_add(elapsed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::{Date, Time};
+
+    fn at(second: u8) -> BacnetDateTime {
+        BacnetDateTime::new(
+            Date {
+                year: 2024,
+                month: 9,
+                day: 2,
+                weekday: 1,
+            },
+            Time {
+                hour: 8,
+                minute: 0,
+                second,
+                hundredths: 0,
+            },
+        )
+    }
+
+    fn write_to(object: ObjectIdentifier, priority: u8) -> AuditNotification {
+        let mut notification = AuditNotification::new(
+            AuditOperation::Write,
+            Recipient::Device(ObjectIdentifier::new(ObjectType::Device, 100)),
+            Recipient::Device(ObjectIdentifier::new(ObjectType::Device, 1)),
+        );
+        notification.target_object = Some(object);
+        notification.target_property = Some(PropertyIdentifier::PresentValue);
+        notification.target_priority = Some(priority);
+        notification
+    }
+
+    #[test]
+    fn test_audit_reporter_filters_and_batches() {
+        let mut reporter = AuditReporter::new(
+            1,
+            "Reporter".to_string(),
+            Recipient::Device(ObjectIdentifier::new(ObjectType::Device, 1)),
+        );
+        reporter.maximum_send_delay = 5;
+        reporter.audit_priority_filter[15] = false;
+        reporter.monitored_objects = vec![ObjectSelector::Type(ObjectType::AnalogOutput)];
+
+        let ao = ObjectIdentifier::new(ObjectType::AnalogOutput, 1);
+        let bo = ObjectIdentifier::new(ObjectType::BinaryOutput, 1);
+        assert!(reporter.report(write_to(ao, 8)));
+        assert!(!reporter.report(write_to(ao, 16)));
+        assert!(!reporter.report(write_to(bo, 8)));
+        assert_eq!(reporter.pending_count(), 1);
+
+        reporter.advance_time(Duration::from_secs(3));
+        assert!(reporter.take_notifications().is_empty());
+        reporter.advance_time(Duration::from_secs(2));
+        assert_eq!(reporter.take_notifications().len(), 1);
+
+        reporter.report(write_to(ao, 8));
+        reporter
+            .set_property(PropertyIdentifier::SendNow, PropertyValue::Boolean(true))
+            .unwrap();
+        assert_eq!(reporter.take_notifications().len(), 1);
+        assert_eq!(
+            reporter.get_property(PropertyIdentifier::SendNow).unwrap(),
+            PropertyValue::Boolean(false)
+        );
+    }
+
+    #[test]
+    fn test_audit_log_buffer_and_read_range() {
+        let mut log = AuditLog::new(1, "Audit Trail".to_string(), 3);
+        let ao = ObjectIdentifier::new(ObjectType::AnalogOutput, 1);
+        assert!(!log.log_notification(at(0), write_to(ao, 8)));
+
+        log.set_log_enable(true, Some(at(1)));
+        for second in 2..6 {
+            assert!(log.log_notification(at(second), write_to(ao, second)));
+        }
+        // The enable record and the oldest notification were overwritten
+        assert_eq!(log.record_count(), 3);
+        assert_eq!(log.total_record_count(), 5);
+
+        let range = log.read_range_by_sequence(4, 2);
+        assert_eq!(range.first_sequence_number, Some(4));
+        assert!(range.last_item);
+        let AuditLogDatum::Notification(notification) = &range.records[0].log_datum else {
+            panic!("expected an audit notification");
+        };
+        assert_eq!(notification.target_priority, Some(4));
+
+        let range = log.read_range_by_time(&at(4), -5);
+        assert_eq!(range.records.len(), 1);
+        assert!(range.first_item);
+    }
+
+    #[test]
+    fn test_audit_log_query() {
+        let mut log = AuditLog::new(1, "Audit Trail".to_string(), 10);
+        log.set_log_enable(true, Some(at(0)));
+        let ao = ObjectIdentifier::new(ObjectType::AnalogOutput, 1);
+        for second in 1..6 {
+            log.log_notification(at(second), write_to(ao, second));
+        }
+        let even = |record: &AuditLogRecord| {
+            matches!(&record.log_datum, AuditLogDatum::Notification(n)
+                if n.target_priority.is_some_and(|p| p % 2 == 0))
+        };
+
+        let (records, no_more_items) = log.query(None, 1, even);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].0, 3);
+        assert!(!no_more_items);
+
+        let start = log.first_sequence_after(&at(3));
+        assert_eq!(start, Some(5));
+        let (records, no_more_items) = log.query(start, 5, even);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].0, 5);
+        assert!(no_more_items);
+    }
+}
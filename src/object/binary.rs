@@ -3,10 +3,14 @@
 //! This module implements the Binary Input, Binary Output, and Binary Value object types
 //! as defined in ASHRAE 135. These objects represent binary (two-state) values in BACnet.
 
+use crate::object::DEFAULT_COMMAND_PRIORITY;
 use crate::object::{
-    BacnetObject, EventState, ObjectError, ObjectIdentifier, ObjectType, PropertyIdentifier,
-    PropertyValue, Reliability, Result,
+    current_date_time, current_status_flags, date_time_value, status_flags_bit_string,
+    BacnetObject, Commandable, EventState, ObjectError, ObjectIdentifier, ObjectType,
+    PropertyIdentifier, PropertyValue, Reliability, Result,
 };
+use crate::service::BacnetDateTime;
+use core::time::Duration;
 
 #[cfg(not(feature = "std"))]
 use alloc::{string::String, vec::Vec};
@@ -35,6 +39,20 @@ impl From<BinaryPV> for bool {
     }
 }
 
+impl TryFrom<u32> for BinaryPV {
+    type Error = ObjectError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            0 => Ok(BinaryPV::Inactive),
+            1 => Ok(BinaryPV::Active),
+            _ => Err(ObjectError::InvalidValue(
+                "Binary value must be 0 or 1".to_string(),
+            )),
+        }
+    }
+}
+
 /// Polarity enumeration
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u32)]
@@ -43,6 +61,32 @@ pub enum Polarity {
     Reverse = 1,
 }
 
+impl Polarity {
+    /// Map a physical input/output state to the logical Present_Value
+    pub fn apply(self, physical: BinaryPV) -> BinaryPV {
+        match (self, physical) {
+            (Polarity::Normal, pv) => pv,
+            (Polarity::Reverse, BinaryPV::Active) => BinaryPV::Inactive,
+            (Polarity::Reverse, BinaryPV::Inactive) => BinaryPV::Active,
+        }
+    }
+}
+
+impl TryFrom<u32> for Polarity {
+    type Error = ObjectError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            0 => Ok(Polarity::Normal),
+            1 => Ok(Polarity::Reverse),
+            _ => Err(ObjectError::InvalidValue(format!(
+                "Unknown polarity: {}",
+                value
+            ))),
+        }
+    }
+}
+
 /// Binary Input object
 #[derive(Debug, Clone)]
 pub struct BinaryInput {
@@ -70,12 +114,16 @@ pub struct BinaryInput {
     pub inactive_text: String,
     /// Active text
     pub active_text: String,
-    /// Change of value time
-    pub change_of_state_time: Option<crate::object::Time>,
+    /// Time of the last Present_Value change
+    pub change_of_state_time: Option<BacnetDateTime>,
     /// Change of state count
     pub change_of_state_count: u32,
     /// Time of state count reset
-    pub time_of_state_count_reset: Option<crate::object::Time>,
+    pub time_of_state_count_reset: Option<BacnetDateTime>,
+    /// Accumulated seconds spent in the active state, if tracked
+    pub elapsed_active_time: Option<u32>,
+    /// Time of active time reset
+    pub time_of_active_time_reset: Option<BacnetDateTime>,
 }
 
 /// Binary Output object
@@ -105,16 +153,19 @@ pub struct BinaryOutput {
     pub inactive_text: String,
     /// Active text
     pub active_text: String,
-    /// Priority array (16 levels)
-    pub priority_array: [Option<BinaryPV>; 16],
-    /// Relinquish default
-    pub relinquish_default: BinaryPV,
-    /// Minimum off time
+    /// Priority array and relinquish default
+    pub commandable: Commandable<BinaryPV>,
+    /// Minimum off time in seconds
     pub minimum_off_time: u32,
-    /// Minimum on time
+    /// Minimum on time in seconds
     pub minimum_on_time: u32,
+    /// Time left before the minimum on/off hold at priority 6 is released
+    pub minimum_time_remaining: Option<Duration>,
 }
 
+/// Priority slot used to hold the output during minimum on/off time (Clause 19.2.3)
+const MINIMUM_TIME_PRIORITY: u8 = 6;
+
 /// Binary Value object
 #[derive(Debug, Clone)]
 pub struct BinaryValue {
@@ -138,10 +189,9 @@ pub struct BinaryValue {
     pub inactive_text: String,
     /// Active text
     pub active_text: String,
-    /// Priority array (16 levels)
-    pub priority_array: [Option<BinaryPV>; 16],
-    /// Relinquish default
-    pub relinquish_default: BinaryPV,
+    /// Priority array and relinquish default, present only on commandable
+    /// instances
+    pub commandable: Option<Commandable<BinaryPV>>,
 }
 
 impl BinaryInput {
@@ -163,18 +213,70 @@ impl BinaryInput {
             change_of_state_time: None,
             change_of_state_count: 0,
             time_of_state_count_reset: None,
+            elapsed_active_time: None,
+            time_of_active_time_reset: None,
         }
     }
 
     /// Set the present value and update change of state
     pub fn set_present_value(&mut self, value: BinaryPV) {
+        self.set_present_value_at(value, current_date_time());
+    }
+
+    /// Set the present value, recording `timestamp` as the change of state time
+    pub fn set_present_value_at(&mut self, value: BinaryPV, timestamp: Option<BacnetDateTime>) {
         if value != self.present_value {
             self.present_value = value;
-            self.change_of_state_count += 1;
-            // In a real implementation, would set change_of_state_time to current time
+            self.change_of_state_count = self.change_of_state_count.wrapping_add(1);
+            self.change_of_state_time = timestamp;
         }
     }
 
+    /// Update the present value from the physical input, applying Polarity
+    ///
+    /// While the object is out of service the present value is decoupled from
+    /// the input, so the sample is ignored.
+    pub fn update_from_input(&mut self, physical: BinaryPV) {
+        if self.out_of_service {
+            return;
+        }
+        self.set_present_value(self.polarity.apply(physical));
+    }
+
+    /// Accumulate Elapsed_Active_Time while the present value is active
+    pub fn accumulate_active_time(&mut self, seconds: u32) {
+        if self.present_value == BinaryPV::Active {
+            if let Some(elapsed) = self.elapsed_active_time.as_mut() {
+                *elapsed = elapsed.saturating_add(seconds);
+            }
+        }
+    }
+
+    /// Reset Change_Of_State_Count and record the reset time
+    pub fn reset_change_of_state_count(&mut self, timestamp: Option<BacnetDateTime>) {
+        self.change_of_state_count = 0;
+        self.time_of_state_count_reset = timestamp;
+    }
+
+    /// Reset Elapsed_Active_Time and record the reset time
+    pub fn reset_elapsed_active_time(&mut self, timestamp: Option<BacnetDateTime>) {
+        if self.elapsed_active_time.is_some() {
+            self.elapsed_active_time = Some(0);
+            self.time_of_active_time_reset = timestamp;
+        }
+    }
+
+    /// Current Status_Flags, combining the stored flags with the state derived
+    /// from Event_State, Reliability and Out_Of_Service
+    pub fn current_status_flags(&self) -> u8 {
+        current_status_flags(
+            self.status_flags,
+            self.event_state,
+            self.reliability,
+            self.out_of_service,
+        )
+    }
+
     /// Get status flags as individual booleans
     pub fn get_status_flags(&self) -> (bool, bool, bool, bool) {
         (
@@ -225,10 +327,10 @@ impl BinaryOutput {
             polarity: Polarity::Normal,
             inactive_text: "INACTIVE".to_string(),
             active_text: "ACTIVE".to_string(),
-            priority_array: [None; 16],
-            relinquish_default: BinaryPV::Inactive,
+            commandable: Commandable::new(BinaryPV::Inactive),
             minimum_off_time: 0,
             minimum_on_time: 0,
+            minimum_time_remaining: None,
         }
     }
 
@@ -239,35 +341,62 @@ impl BinaryOutput {
                 "Priority must be 1-16".to_string(),
             ));
         }
-        self.priority_array[(priority - 1) as usize] = value;
+        if priority == MINIMUM_TIME_PRIORITY {
+            // An explicit command at priority 6 takes over the slot from the timer
+            self.minimum_time_remaining = None;
+        }
+        self.commandable.write(priority, value)?;
         self.update_present_value();
         Ok(())
     }
 
     /// Update present value based on priority array
+    ///
+    /// A change of Present_Value holds the new value at priority 6 for the
+    /// configured Minimum_On_Time or Minimum_Off_Time.
     fn update_present_value(&mut self) {
-        // Find highest priority non-null value
-        if let Some(value) = self.priority_array.iter().flatten().next() {
-            self.present_value = *value;
+        let value = *self.commandable.current();
+        if value == self.present_value {
             return;
         }
-        // If all priorities are null, use relinquish default
-        self.present_value = self.relinquish_default;
+        self.present_value = value;
+
+        let minimum = match value {
+            BinaryPV::Active => self.minimum_on_time,
+            BinaryPV::Inactive => self.minimum_off_time,
+        };
+        if minimum > 0 {
+            let _ = self.commandable.write(MINIMUM_TIME_PRIORITY, Some(value));
+            self.minimum_time_remaining = Some(Duration::from_secs(minimum as u64));
+        } else if self.minimum_time_remaining.take().is_some() {
+            let _ = self.commandable.relinquish(MINIMUM_TIME_PRIORITY);
+        }
+    }
+
+    /// Whether a minimum on/off time is currently holding the output
+    pub fn is_minimum_time_active(&self) -> bool {
+        self.minimum_time_remaining.is_some()
     }
 
     /// Get the effective priority level for current present value
     pub fn get_effective_priority(&self) -> Option<u8> {
-        for (i, priority_value) in self.priority_array.iter().enumerate() {
-            if priority_value.is_some() {
-                return Some((i + 1) as u8);
-            }
-        }
-        None
+        self.commandable.active_priority()
+    }
+
+    /// Current Status_Flags, combining the stored flags with the state derived
+    /// from Event_State, Reliability and Out_Of_Service
+    pub fn current_status_flags(&self) -> u8 {
+        current_status_flags(
+            self.status_flags,
+            self.event_state,
+            self.reliability,
+            self.out_of_service,
+        )
     }
 }
 
 impl BinaryValue {
-    /// Create a new Binary Value object
+    /// Create a new non-commandable Binary Value object
     pub fn new(instance: u32, object_name: String) -> Self {
         Self {
             identifier: ObjectIdentifier::new(ObjectType::BinaryValue, instance),
@@ -280,11 +409,27 @@ impl BinaryValue {
             out_of_service: false,
             inactive_text: "INACTIVE".to_string(),
             active_text: "ACTIVE".to_string(),
-            priority_array: [None; 16],
-            relinquish_default: BinaryPV::Inactive,
+            commandable: None,
         }
     }
 
+    /// Create a new commandable Binary Value object with a priority array
+    pub fn new_commandable(
+        instance: u32,
+        object_name: String,
+        relinquish_default: BinaryPV,
+    ) -> Self {
+        let mut bv = Self::new(instance, object_name);
+        bv.commandable = Some(Commandable::new(relinquish_default));
+        bv.present_value = relinquish_default;
+        bv
+    }
+
+    /// Whether Present_Value writes are arbitrated through a priority array
+    pub fn is_commandable(&self) -> bool {
+        self.commandable.is_some()
+    }
+
     /// Write to priority array at specified priority level (1-16)
     pub fn write_priority(&mut self, priority: u8, value: Option<BinaryPV>) -> Result<()> {
         if !(1..=16).contains(&priority) {
@@ -292,20 +437,40 @@ impl BinaryValue {
                 "Priority must be 1-16".to_string(),
             ));
         }
-        self.priority_array[(priority - 1) as usize] = value;
+        let Some(commandable) = self.commandable.as_mut() else {
+            return Err(ObjectError::InvalidConfiguration(
+                "Binary Value is not commandable".to_string(),
+            ));
+        };
+        commandable.write(priority, value)?;
         self.update_present_value();
         Ok(())
     }
 
     /// Update present value based on priority array
     fn update_present_value(&mut self) {
-        // Find highest priority non-null value
-        if let Some(value) = self.priority_array.iter().flatten().next() {
-            self.present_value = *value;
-            return;
+        if let Some(commandable) = self.commandable.as_ref() {
+            self.present_value = *commandable.current();
         }
-        // If all priorities are null, use relinquish default
-        self.present_value = self.relinquish_default;
+    }
+
+    /// Text describing the current Present_Value
+    pub fn state_text(&self) -> &str {
+        match self.present_value {
+            BinaryPV::Active => &self.active_text,
+            BinaryPV::Inactive => &self.inactive_text,
+        }
+    }
+
+    /// Current Status_Flags, combining the stored flags with the state derived
+    /// from Event_State, Reliability and Out_Of_Service
+    pub fn current_status_flags(&self) -> u8 {
+        current_status_flags(
+            self.status_flags,
+            self.event_state,
+            self.reliability,
+            self.out_of_service,
+        )
     }
 }
 
@@ -322,13 +487,49 @@ impl BacnetObject for BinaryInput {
             PropertyIdentifier::ObjectName => {
                 Ok(PropertyValue::CharacterString(self.object_name.clone()))
             }
-            PropertyIdentifier::ObjectType => {
-                Ok(PropertyValue::Enumerated(ObjectType::BinaryInput as u32))
-            }
+            PropertyIdentifier::ObjectType => Ok(PropertyValue::Enumerated(u32::from(
+                ObjectType::BinaryInput,
+            ))),
             PropertyIdentifier::PresentValue => {
                 Ok(PropertyValue::Enumerated(self.present_value as u32))
             }
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::DeviceType => {
+                Ok(PropertyValue::CharacterString(self.device_type.clone()))
+            }
+            PropertyIdentifier::StatusFlags => {
+                Ok(status_flags_bit_string(self.current_status_flags()))
+            }
+            PropertyIdentifier::EventState => {
+                Ok(PropertyValue::Enumerated(self.event_state as u32))
+            }
+            PropertyIdentifier::Reliability => {
+                Ok(PropertyValue::Enumerated(self.reliability as u32))
+            }
             PropertyIdentifier::OutOfService => Ok(PropertyValue::Boolean(self.out_of_service)),
+            PropertyIdentifier::Polarity => Ok(PropertyValue::Enumerated(self.polarity as u32)),
+            PropertyIdentifier::InactiveText => {
+                Ok(PropertyValue::CharacterString(self.inactive_text.clone()))
+            }
+            PropertyIdentifier::ActiveText => {
+                Ok(PropertyValue::CharacterString(self.active_text.clone()))
+            }
+            PropertyIdentifier::ChangeOfStateTime => Ok(date_time_value(self.change_of_state_time)),
+            PropertyIdentifier::ChangeOfStateCount => {
+                Ok(PropertyValue::UnsignedInteger(self.change_of_state_count))
+            }
+            PropertyIdentifier::TimeOfStateCountReset => {
+                Ok(date_time_value(self.time_of_state_count_reset))
+            }
+            PropertyIdentifier::ElapsedActiveTime => self
+                .elapsed_active_time
+                .map(PropertyValue::UnsignedInteger)
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::TimeOfActiveTimeReset if self.elapsed_active_time.is_some() => {
+                Ok(date_time_value(self.time_of_active_time_reset))
+            }
             _ => Err(ObjectError::UnknownProperty),
         }
     }
@@ -343,6 +544,26 @@ impl BacnetObject for BinaryInput {
                     Err(ObjectError::InvalidPropertyType)
                 }
             }
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::PresentValue => {
+                // Present_Value is only writable while decoupled from the input
+                if !self.out_of_service {
+                    return Err(ObjectError::WriteAccessDenied);
+                }
+                if let PropertyValue::Enumerated(val) = value {
+                    self.set_present_value(BinaryPV::try_from(val)?);
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
             PropertyIdentifier::OutOfService => {
                 if let PropertyValue::Boolean(oos) = value {
                     self.out_of_service = oos;
@@ -351,25 +572,96 @@ impl BacnetObject for BinaryInput {
                     Err(ObjectError::InvalidPropertyType)
                 }
             }
+            PropertyIdentifier::Polarity => {
+                if let PropertyValue::Enumerated(polarity) = value {
+                    self.polarity = Polarity::try_from(polarity)?;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::InactiveText => {
+                if let PropertyValue::CharacterString(text) = value {
+                    self.inactive_text = text;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::ActiveText => {
+                if let PropertyValue::CharacterString(text) = value {
+                    self.active_text = text;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            // The counters may only be written with zero, which resets them
+            PropertyIdentifier::ChangeOfStateCount => match value {
+                PropertyValue::UnsignedInteger(0) => {
+                    self.reset_change_of_state_count(current_date_time());
+                    Ok(())
+                }
+                PropertyValue::UnsignedInteger(_) => Err(ObjectError::InvalidValue(
+                    "Change_Of_State_Count can only be reset to 0".to_string(),
+                )),
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            PropertyIdentifier::ElapsedActiveTime if self.elapsed_active_time.is_some() => {
+                match value {
+                    PropertyValue::UnsignedInteger(0) => {
+                        self.reset_elapsed_active_time(current_date_time());
+                        Ok(())
+                    }
+                    PropertyValue::UnsignedInteger(_) => Err(ObjectError::InvalidValue(
+                        "Elapsed_Active_Time can only be reset to 0".to_string(),
+                    )),
+                    _ => Err(ObjectError::InvalidPropertyType),
+                }
+            }
             _ => Err(ObjectError::PropertyNotWritable),
         }
     }
 
     fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
-        matches!(
-            property,
-            PropertyIdentifier::ObjectName | PropertyIdentifier::OutOfService
-        )
+        match property {
+            PropertyIdentifier::ObjectName
+            | PropertyIdentifier::Description
+            | PropertyIdentifier::OutOfService
+            | PropertyIdentifier::Polarity
+            | PropertyIdentifier::InactiveText
+            | PropertyIdentifier::ActiveText
+            | PropertyIdentifier::ChangeOfStateCount => true,
+            PropertyIdentifier::PresentValue => self.out_of_service,
+            PropertyIdentifier::ElapsedActiveTime => self.elapsed_active_time.is_some(),
+            _ => false,
+        }
     }
 
     fn property_list(&self) -> Vec<PropertyIdentifier> {
-        vec![
+        let mut properties = vec![
             PropertyIdentifier::ObjectIdentifier,
             PropertyIdentifier::ObjectName,
             PropertyIdentifier::ObjectType,
             PropertyIdentifier::PresentValue,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::DeviceType,
+            PropertyIdentifier::StatusFlags,
+            PropertyIdentifier::EventState,
+            PropertyIdentifier::Reliability,
             PropertyIdentifier::OutOfService,
-        ]
+            PropertyIdentifier::Polarity,
+            PropertyIdentifier::InactiveText,
+            PropertyIdentifier::ActiveText,
+            PropertyIdentifier::ChangeOfStateTime,
+            PropertyIdentifier::ChangeOfStateCount,
+            PropertyIdentifier::TimeOfStateCountReset,
+        ];
+        if self.elapsed_active_time.is_some() {
+            properties.push(PropertyIdentifier::ElapsedActiveTime);
+            properties.push(PropertyIdentifier::TimeOfActiveTimeReset);
+        }
+        properties
     }
 }
 
@@ -386,29 +678,61 @@ impl BacnetObject for BinaryOutput {
             PropertyIdentifier::ObjectName => {
                 Ok(PropertyValue::CharacterString(self.object_name.clone()))
             }
-            PropertyIdentifier::ObjectType => {
-                Ok(PropertyValue::Enumerated(ObjectType::BinaryOutput as u32))
-            }
+            PropertyIdentifier::ObjectType => Ok(PropertyValue::Enumerated(u32::from(
+                ObjectType::BinaryOutput,
+            ))),
             PropertyIdentifier::PresentValue => {
                 Ok(PropertyValue::Enumerated(self.present_value as u32))
             }
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::DeviceType => {
+                Ok(PropertyValue::CharacterString(self.device_type.clone()))
+            }
+            PropertyIdentifier::StatusFlags => {
+                Ok(status_flags_bit_string(self.current_status_flags()))
+            }
+            PropertyIdentifier::EventState => {
+                Ok(PropertyValue::Enumerated(self.event_state as u32))
+            }
+            PropertyIdentifier::Reliability => {
+                Ok(PropertyValue::Enumerated(self.reliability as u32))
+            }
             PropertyIdentifier::OutOfService => Ok(PropertyValue::Boolean(self.out_of_service)),
-            PropertyIdentifier::PriorityArray => {
-                let array: Vec<PropertyValue> = self
-                    .priority_array
-                    .iter()
-                    .map(|&v| match v {
-                        Some(val) => PropertyValue::Enumerated(val as u32),
-                        None => PropertyValue::Null,
-                    })
-                    .collect();
-                Ok(PropertyValue::Array(array))
+            PropertyIdentifier::Polarity => Ok(PropertyValue::Enumerated(self.polarity as u32)),
+            PropertyIdentifier::InactiveText => {
+                Ok(PropertyValue::CharacterString(self.inactive_text.clone()))
+            }
+            PropertyIdentifier::ActiveText => {
+                Ok(PropertyValue::CharacterString(self.active_text.clone()))
+            }
+            PropertyIdentifier::PriorityArray => Ok(self
+                .commandable
+                .priority_array_value(|&value| PropertyValue::Enumerated(value as u32))),
+            PropertyIdentifier::RelinquishDefault => Ok(PropertyValue::Enumerated(
+                *self.commandable.relinquish_default() as u32,
+            )),
+            PropertyIdentifier::MinimumOffTime => {
+                Ok(PropertyValue::UnsignedInteger(self.minimum_off_time))
+            }
+            PropertyIdentifier::MinimumOnTime => {
+                Ok(PropertyValue::UnsignedInteger(self.minimum_on_time))
             }
             _ => Err(ObjectError::UnknownProperty),
         }
     }
 
     fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        self.set_property_with_priority(property, value, DEFAULT_COMMAND_PRIORITY)
+    }
+
+    fn set_property_with_priority(
+        &mut self,
+        property: PropertyIdentifier,
+        value: PropertyValue,
+        priority: u8,
+    ) -> Result<()> {
         match property {
             PropertyIdentifier::ObjectName => {
                 if let PropertyValue::CharacterString(name) = value {
@@ -418,23 +742,22 @@ impl BacnetObject for BinaryOutput {
                     Err(ObjectError::InvalidPropertyType)
                 }
             }
-            PropertyIdentifier::PresentValue => {
-                if let PropertyValue::Enumerated(val) = value {
-                    let binary_val = match val {
-                        0 => BinaryPV::Inactive,
-                        1 => BinaryPV::Active,
-                        _ => {
-                            return Err(ObjectError::InvalidValue(
-                                "Binary value must be 0 or 1".to_string(),
-                            ))
-                        }
-                    };
-                    // Write to priority 8 (manual operator) by default
-                    self.write_priority(8, Some(binary_val))
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
                 } else {
                     Err(ObjectError::InvalidPropertyType)
                 }
             }
+            PropertyIdentifier::PresentValue => match value {
+                PropertyValue::Enumerated(val) => {
+                    self.write_priority(priority, Some(BinaryPV::try_from(val)?))
+                }
+                // Writing NULL relinquishes the command at this priority
+                PropertyValue::Null => self.write_priority(priority, None),
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
             PropertyIdentifier::OutOfService => {
                 if let PropertyValue::Boolean(oos) = value {
                     self.out_of_service = oos;
@@ -443,6 +766,56 @@ impl BacnetObject for BinaryOutput {
                     Err(ObjectError::InvalidPropertyType)
                 }
             }
+            PropertyIdentifier::Polarity => {
+                if let PropertyValue::Enumerated(polarity) = value {
+                    self.polarity = Polarity::try_from(polarity)?;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::InactiveText => {
+                if let PropertyValue::CharacterString(text) = value {
+                    self.inactive_text = text;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::ActiveText => {
+                if let PropertyValue::CharacterString(text) = value {
+                    self.active_text = text;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::RelinquishDefault => {
+                if let PropertyValue::Enumerated(val) = value {
+                    self.commandable
+                        .set_relinquish_default(BinaryPV::try_from(val)?);
+                    self.update_present_value();
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::MinimumOffTime => {
+                if let PropertyValue::UnsignedInteger(seconds) = value {
+                    self.minimum_off_time = seconds;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::MinimumOnTime => {
+                if let PropertyValue::UnsignedInteger(seconds) = value {
+                    self.minimum_on_time = seconds;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
             _ => Err(ObjectError::PropertyNotWritable),
         }
     }
@@ -451,8 +824,15 @@ impl BacnetObject for BinaryOutput {
         matches!(
             property,
             PropertyIdentifier::ObjectName
+                | PropertyIdentifier::Description
                 | PropertyIdentifier::PresentValue
                 | PropertyIdentifier::OutOfService
+                | PropertyIdentifier::Polarity
+                | PropertyIdentifier::InactiveText
+                | PropertyIdentifier::ActiveText
+                | PropertyIdentifier::RelinquishDefault
+                | PropertyIdentifier::MinimumOffTime
+                | PropertyIdentifier::MinimumOnTime
         )
     }
 
@@ -462,10 +842,36 @@ impl BacnetObject for BinaryOutput {
             PropertyIdentifier::ObjectName,
             PropertyIdentifier::ObjectType,
             PropertyIdentifier::PresentValue,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::DeviceType,
+            PropertyIdentifier::StatusFlags,
+            PropertyIdentifier::EventState,
+            PropertyIdentifier::Reliability,
             PropertyIdentifier::OutOfService,
+            PropertyIdentifier::Polarity,
+            PropertyIdentifier::InactiveText,
+            PropertyIdentifier::ActiveText,
             PropertyIdentifier::PriorityArray,
+            PropertyIdentifier::RelinquishDefault,
+            PropertyIdentifier::MinimumOffTime,
+            PropertyIdentifier::MinimumOnTime,
         ]
     }
+
+    fn advance_time(&mut self, elapsed: Duration) {
+        let Some(remaining) = self.minimum_time_remaining else {
+            return;
+        };
+        match remaining.checked_sub(elapsed) {
+            Some(left) if !left.is_zero() => self.minimum_time_remaining = Some(left),
+            _ => {
+                // Minimum time expired: release the hold and re-arbitrate
+                self.minimum_time_remaining = None;
+                let _ = self.commandable.relinquish(MINIMUM_TIME_PRIORITY);
+                self.update_present_value();
+            }
+        }
+    }
 }
 
 impl BacnetObject for BinaryValue {
@@ -481,29 +887,60 @@ impl BacnetObject for BinaryValue {
             PropertyIdentifier::ObjectName => {
                 Ok(PropertyValue::CharacterString(self.object_name.clone()))
             }
-            PropertyIdentifier::ObjectType => {
-                Ok(PropertyValue::Enumerated(ObjectType::BinaryValue as u32))
-            }
+            PropertyIdentifier::ObjectType => Ok(PropertyValue::Enumerated(u32::from(
+                ObjectType::BinaryValue,
+            ))),
             PropertyIdentifier::PresentValue => {
                 Ok(PropertyValue::Enumerated(self.present_value as u32))
             }
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::StatusFlags => {
+                Ok(status_flags_bit_string(self.current_status_flags()))
+            }
+            PropertyIdentifier::EventState => {
+                Ok(PropertyValue::Enumerated(self.event_state as u32))
+            }
+            PropertyIdentifier::Reliability => {
+                Ok(PropertyValue::Enumerated(self.reliability as u32))
+            }
             PropertyIdentifier::OutOfService => Ok(PropertyValue::Boolean(self.out_of_service)),
-            PropertyIdentifier::PriorityArray => {
-                let array: Vec<PropertyValue> = self
-                    .priority_array
-                    .iter()
-                    .map(|&v| match v {
-                        Some(val) => PropertyValue::Enumerated(val as u32),
-                        None => PropertyValue::Null,
-                    })
-                    .collect();
-                Ok(PropertyValue::Array(array))
+            PropertyIdentifier::InactiveText => {
+                Ok(PropertyValue::CharacterString(self.inactive_text.clone()))
             }
+            PropertyIdentifier::ActiveText => {
+                Ok(PropertyValue::CharacterString(self.active_text.clone()))
+            }
+            PropertyIdentifier::PriorityArray => self
+                .commandable
+                .as_ref()
+                .map(|commandable| {
+                    commandable
+                        .priority_array_value(|&value| PropertyValue::Enumerated(value as u32))
+                })
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::RelinquishDefault => self
+                .commandable
+                .as_ref()
+                .map(|commandable| {
+                    PropertyValue::Enumerated(*commandable.relinquish_default() as u32)
+                })
+                .ok_or(ObjectError::UnknownProperty),
             _ => Err(ObjectError::UnknownProperty),
         }
     }
 
     fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        self.set_property_with_priority(property, value, DEFAULT_COMMAND_PRIORITY)
+    }
+
+    fn set_property_with_priority(
+        &mut self,
+        property: PropertyIdentifier,
+        value: PropertyValue,
+        priority: u8,
+    ) -> Result<()> {
         match property {
             PropertyIdentifier::ObjectName => {
                 if let PropertyValue::CharacterString(name) = value {
@@ -513,19 +950,36 @@ impl BacnetObject for BinaryValue {
                     Err(ObjectError::InvalidPropertyType)
                 }
             }
-            PropertyIdentifier::PresentValue => {
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::PresentValue => match value {
+                PropertyValue::Enumerated(val) => {
+                    let binary_val = BinaryPV::try_from(val)?;
+                    if self.is_commandable() {
+                        self.write_priority(priority, Some(binary_val))
+                    } else {
+                        self.present_value = binary_val;
+                        Ok(())
+                    }
+                }
+                // Writing NULL relinquishes the command at this priority
+                PropertyValue::Null if self.is_commandable() => self.write_priority(priority, None),
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            PropertyIdentifier::RelinquishDefault if self.is_commandable() => {
                 if let PropertyValue::Enumerated(val) = value {
-                    let binary_val = match val {
-                        0 => BinaryPV::Inactive,
-                        1 => BinaryPV::Active,
-                        _ => {
-                            return Err(ObjectError::InvalidValue(
-                                "Binary value must be 0 or 1".to_string(),
-                            ))
-                        }
-                    };
-                    // Write to priority 8 (manual operator) by default
-                    self.write_priority(8, Some(binary_val))
+                    let default = BinaryPV::try_from(val)?;
+                    if let Some(commandable) = self.commandable.as_mut() {
+                        commandable.set_relinquish_default(default);
+                    }
+                    self.update_present_value();
+                    Ok(())
                 } else {
                     Err(ObjectError::InvalidPropertyType)
                 }
@@ -538,28 +992,58 @@ impl BacnetObject for BinaryValue {
                     Err(ObjectError::InvalidPropertyType)
                 }
             }
+            PropertyIdentifier::InactiveText => {
+                if let PropertyValue::CharacterString(text) = value {
+                    self.inactive_text = text;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::ActiveText => {
+                if let PropertyValue::CharacterString(text) = value {
+                    self.active_text = text;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
             _ => Err(ObjectError::PropertyNotWritable),
         }
     }
 
     fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
-        matches!(
-            property,
+        match property {
             PropertyIdentifier::ObjectName
-                | PropertyIdentifier::PresentValue
-                | PropertyIdentifier::OutOfService
-        )
+            | PropertyIdentifier::Description
+            | PropertyIdentifier::PresentValue
+            | PropertyIdentifier::OutOfService
+            | PropertyIdentifier::InactiveText
+            | PropertyIdentifier::ActiveText => true,
+            PropertyIdentifier::RelinquishDefault => self.is_commandable(),
+            _ => false,
+        }
     }
 
     fn property_list(&self) -> Vec<PropertyIdentifier> {
-        vec![
+        let mut properties = vec![
             PropertyIdentifier::ObjectIdentifier,
             PropertyIdentifier::ObjectName,
             PropertyIdentifier::ObjectType,
             PropertyIdentifier::PresentValue,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::StatusFlags,
+            PropertyIdentifier::EventState,
+            PropertyIdentifier::Reliability,
             PropertyIdentifier::OutOfService,
-            PropertyIdentifier::PriorityArray,
-        ]
+            PropertyIdentifier::InactiveText,
+            PropertyIdentifier::ActiveText,
+        ];
+        if self.is_commandable() {
+            properties.push(PropertyIdentifier::PriorityArray);
+            properties.push(PropertyIdentifier::RelinquishDefault);
+        }
+        properties
     }
 }
 
@@ -599,6 +1083,96 @@ mod tests {
         assert_eq!(bi.change_of_state_count, 2);
     }
 
+    #[test]
+    fn test_binary_input_polarity_and_out_of_service() {
+        let mut bi = BinaryInput::new(2, "Filter Alarm".to_string());
+        bi.polarity = Polarity::Reverse;
+
+        // Reverse polarity inverts the physical input
+        bi.update_from_input(BinaryPV::Inactive);
+        assert_eq!(bi.present_value, BinaryPV::Active);
+        assert_eq!(bi.change_of_state_count, 1);
+
+        // Present_Value is read-only until the input is taken out of service
+        assert!(bi
+            .set_property(
+                PropertyIdentifier::PresentValue,
+                PropertyValue::Enumerated(0)
+            )
+            .is_err());
+        bi.set_property(
+            PropertyIdentifier::OutOfService,
+            PropertyValue::Boolean(true),
+        )
+        .unwrap();
+        bi.set_property(
+            PropertyIdentifier::PresentValue,
+            PropertyValue::Enumerated(0),
+        )
+        .unwrap();
+        assert_eq!(bi.present_value, BinaryPV::Inactive);
+
+        // Physical samples are ignored while out of service
+        bi.update_from_input(BinaryPV::Inactive);
+        assert_eq!(bi.present_value, BinaryPV::Inactive);
+        assert_eq!(bi.change_of_state_count, 2);
+
+        if let Ok(PropertyValue::BitString(flags)) =
+            bi.get_property(PropertyIdentifier::StatusFlags)
+        {
+            assert_eq!(flags, vec![false, false, false, true]);
+        } else {
+            panic!("Expected BitString");
+        }
+    }
+
+    #[test]
+    fn test_binary_input_counters() {
+        let mut bi = BinaryInput::new(3, "Pump Status".to_string());
+        bi.elapsed_active_time = Some(0);
+        let when = BacnetDateTime::new(
+            crate::object::Date {
+                year: 2024,
+                month: 3,
+                day: 1,
+                weekday: 5,
+            },
+            crate::object::Time {
+                hour: 8,
+                minute: 30,
+                second: 0,
+                hundredths: 0,
+            },
+        );
+
+        bi.set_present_value_at(BinaryPV::Active, Some(when));
+        assert_eq!(bi.change_of_state_time, Some(when));
+        bi.accumulate_active_time(90);
+        bi.set_present_value_at(BinaryPV::Inactive, Some(when));
+        bi.accumulate_active_time(30);
+        assert_eq!(bi.elapsed_active_time, Some(90));
+
+        assert!(bi
+            .set_property(
+                PropertyIdentifier::ChangeOfStateCount,
+                PropertyValue::UnsignedInteger(5)
+            )
+            .is_err());
+        bi.set_property(
+            PropertyIdentifier::ChangeOfStateCount,
+            PropertyValue::UnsignedInteger(0),
+        )
+        .unwrap();
+        assert_eq!(bi.change_of_state_count, 0);
+
+        bi.reset_elapsed_active_time(Some(when));
+        assert_eq!(bi.elapsed_active_time, Some(0));
+        assert!(matches!(
+            bi.get_property(PropertyIdentifier::TimeOfActiveTimeReset),
+            Ok(PropertyValue::List(ref items)) if items.len() == 2
+        ));
+    }
+
     #[test]
     fn test_binary_output_priority() {
         let mut bo = BinaryOutput::new(1, "Fan Control".to_string());
@@ -619,6 +1193,56 @@ mod tests {
         assert_eq!(bo.get_effective_priority(), Some(8));
     }
 
+    #[test]
+    fn test_binary_output_minimum_on_off_time() {
+        let mut bo = BinaryOutput::new(2, "Compressor".to_string());
+        bo.minimum_on_time = 60;
+        bo.minimum_off_time = 30;
+
+        // Turning on holds the output at priority 6 for the minimum on time
+        bo.write_priority(8, Some(BinaryPV::Active)).unwrap();
+        assert_eq!(bo.present_value, BinaryPV::Active);
+        assert_eq!(bo.get_effective_priority(), Some(6));
+        assert!(bo.is_minimum_time_active());
+
+        // A lower priority command cannot switch it off early
+        bo.write_priority(8, Some(BinaryPV::Inactive)).unwrap();
+        assert_eq!(bo.present_value, BinaryPV::Active);
+
+        bo.advance_time(Duration::from_secs(59));
+        assert_eq!(bo.present_value, BinaryPV::Active);
+
+        // Expiry releases priority 6, applying the pending off command, which
+        // starts the minimum off time
+        bo.advance_time(Duration::from_secs(1));
+        assert_eq!(bo.present_value, BinaryPV::Inactive);
+        assert_eq!(bo.commandable.get(6), Some(&BinaryPV::Inactive));
+        bo.advance_time(Duration::from_secs(30));
+        assert!(!bo.is_minimum_time_active());
+        assert_eq!(bo.get_effective_priority(), Some(8));
+
+        // Priorities above 6 override the minimum time
+        bo.write_priority(8, Some(BinaryPV::Active)).unwrap();
+        bo.write_priority(2, Some(BinaryPV::Inactive)).unwrap();
+        assert_eq!(bo.present_value, BinaryPV::Inactive);
+    }
+
+    #[test]
+    fn test_binary_output_relinquish() {
+        let mut bo = BinaryOutput::new(3, "Exhaust Fan".to_string());
+        bo.set_property_with_priority(
+            PropertyIdentifier::PresentValue,
+            PropertyValue::Enumerated(1),
+            10,
+        )
+        .unwrap();
+        assert_eq!(bo.present_value, BinaryPV::Active);
+        bo.set_property_with_priority(PropertyIdentifier::PresentValue, PropertyValue::Null, 10)
+            .unwrap();
+        assert_eq!(bo.present_value, BinaryPV::Inactive);
+        assert_eq!(bo.get_effective_priority(), None);
+    }
+
     #[test]
     fn test_binary_object_properties() {
         let mut bv = BinaryValue::new(1, "Test Value".to_string());
@@ -646,4 +1270,37 @@ mod tests {
         );
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_binary_value_commandable() {
+        let mut bv = BinaryValue::new_commandable(2, "Night Mode".to_string(), BinaryPV::Inactive);
+        bv.active_text = "NIGHT".to_string();
+        bv.inactive_text = "DAY".to_string();
+        assert_eq!(bv.state_text(), "DAY");
+
+        bv.set_property_with_priority(
+            PropertyIdentifier::PresentValue,
+            PropertyValue::Enumerated(1),
+            4,
+        )
+        .unwrap();
+        assert_eq!(bv.state_text(), "NIGHT");
+        assert!(bv
+            .property_list()
+            .contains(&PropertyIdentifier::RelinquishDefault));
+
+        bv.set_property_with_priority(PropertyIdentifier::PresentValue, PropertyValue::Null, 4)
+            .unwrap();
+        assert_eq!(bv.present_value, BinaryPV::Inactive);
+
+        // Non-commandable values have no priority array
+        let mut plain = BinaryValue::new(3, "Alarm Reset".to_string());
+        assert!(plain.write_priority(8, Some(BinaryPV::Active)).is_err());
+        assert!(plain
+            .get_property(PropertyIdentifier::PriorityArray)
+            .is_err());
+        assert!(plain
+            .set_property(PropertyIdentifier::PresentValue, PropertyValue::Null)
+            .is_err());
+    }
 }
@@ -0,0 +1,406 @@
+//! BitString Value Object Type Implementation
+//!
+//! This module implements the BitString Value object type as defined in ASHRAE 135.
+//! Present_Value is a BACnet bit string and the optional Bit_Text array names each
+//! bit. When Bit_Text is present, Present_Value writes must carry exactly one bit
+//! per Bit_Text entry. Instances created with [`BitStringValue::new_commandable`]
+//! arbitrate Present_Value writes through a priority array.
+
+use crate::object::{
+    current_status_flags, status_flags_bit_string, BacnetObject, Commandable, EventState,
+    ObjectError, ObjectIdentifier, ObjectType, PropertyIdentifier, PropertyValue, Reliability,
+    Result, DEFAULT_COMMAND_PRIORITY,
+};
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+/// BitString Value object
+#[derive(Debug, Clone)]
+pub struct BitStringValue {
+    /// Object identifier
+    pub identifier: ObjectIdentifier,
+    /// Object name
+    pub object_name: String,
+    /// Description
+    pub description: String,
+    /// Present value
+    pub present_value: Vec<bool>,
+    /// Name of each bit of Present_Value
+    pub bit_text: Option<Vec<String>>,
+    /// Status flags
+    pub status_flags: u8,
+    /// Event state
+    pub event_state: EventState,
+    /// Reliability
+    pub reliability: Reliability,
+    /// Out of service
+    pub out_of_service: bool,
+    /// Priority array and relinquish default, present only on commandable
+    /// instances
+    pub commandable: Option<Commandable<Vec<bool>>>,
+}
+
+impl BitStringValue {
+    /// Create a new non-commandable BitString Value object
+    pub fn new(instance: u32, object_name: String) -> Self {
+        Self {
+            identifier: ObjectIdentifier::new(ObjectType::BitStringValue, instance),
+            object_name,
+            description: String::new(),
+            present_value: Vec::new(),
+            bit_text: None,
+            status_flags: 0,
+            event_state: EventState::Normal,
+            reliability: Reliability::NoFaultDetected,
+            out_of_service: false,
+            commandable: None,
+        }
+    }
+
+    /// Create a BitString Value with one named bit per entry of `bit_text`,
+    /// all initially clear
+    pub fn with_bit_text(instance: u32, object_name: String, bit_text: Vec<String>) -> Self {
+        let mut bsv = Self::new(instance, object_name);
+        bsv.present_value = vec![false; bit_text.len()];
+        bsv.bit_text = Some(bit_text);
+        bsv
+    }
+
+    /// Create a new commandable BitString Value object with a priority array
+    pub fn new_commandable(
+        instance: u32,
+        object_name: String,
+        relinquish_default: Vec<bool>,
+    ) -> Self {
+        let mut bsv = Self::new(instance, object_name);
+        bsv.present_value = relinquish_default.clone();
+        bsv.commandable = Some(Commandable::new(relinquish_default));
+        bsv
+    }
+
+    /// Whether Present_Value writes are arbitrated through a priority array
+    pub fn is_commandable(&self) -> bool {
+        self.commandable.is_some()
+    }
+
+    /// Set the present value
+    pub fn set_present_value(&mut self, value: Vec<bool>) -> Result<()> {
+        self.check_length(&value)?;
+        self.present_value = value;
+        Ok(())
+    }
+
+    /// Whether the bit at `bit` (zero-based) is set
+    pub fn bit(&self, bit: usize) -> bool {
+        self.present_value.get(bit).copied().unwrap_or(false)
+    }
+
+    /// Write to priority array at specified priority level (1-16)
+    pub fn write_priority(&mut self, priority: u8, value: Option<Vec<bool>>) -> Result<()> {
+        if !(1..=16).contains(&priority) {
+            return Err(ObjectError::InvalidValue(
+                "Priority must be 1-16".to_string(),
+            ));
+        }
+        if let Some(value) = value.as_ref() {
+            self.check_length(value)?;
+        }
+        let Some(commandable) = self.commandable.as_mut() else {
+            return Err(ObjectError::InvalidConfiguration(
+                "BitString Value is not commandable".to_string(),
+            ));
+        };
+        commandable.write(priority, value)?;
+        self.update_present_value();
+        Ok(())
+    }
+
+    /// Update present value based on priority array
+    fn update_present_value(&mut self) {
+        if let Some(commandable) = self.commandable.as_ref() {
+            self.present_value = commandable.current().clone();
+        }
+    }
+
+    /// Element `index` of Bit_Text; index zero is the array length
+    pub fn bit_text_element(&self, index: u32) -> Result<PropertyValue> {
+        let bit_text = self.bit_text.as_ref().ok_or(ObjectError::UnknownProperty)?;
+        match index {
+            0 => Ok(PropertyValue::UnsignedInteger(bit_text.len() as u32)),
+            _ => bit_text
+                .get(index as usize - 1)
+                .cloned()
+                .map(PropertyValue::CharacterString)
+                .ok_or(ObjectError::InvalidArrayIndex),
+        }
+    }
+
+    /// Replace element `index` (one-based) of Bit_Text
+    pub fn set_bit_text_element(&mut self, index: u32, text: String) -> Result<()> {
+        let bit_text = self.bit_text.as_mut().ok_or(ObjectError::UnknownProperty)?;
+        let slot = index
+            .checked_sub(1)
+            .and_then(|i| bit_text.get_mut(i as usize))
+            .ok_or(ObjectError::InvalidArrayIndex)?;
+        *slot = text;
+        Ok(())
+    }
+
+    fn check_length(&self, value: &[bool]) -> Result<()> {
+        match self.bit_text.as_ref() {
+            Some(bit_text) if bit_text.len() != value.len() => Err(ObjectError::InvalidValue(
+                format!("Expected {} bits, got {}", bit_text.len(), value.len()),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Current Status_Flags, combining the stored flags with the state derived
+    /// from Event_State, Reliability and Out_Of_Service
+    pub fn current_status_flags(&self) -> u8 {
+        current_status_flags(
+            self.status_flags,
+            self.event_state,
+            self.reliability,
+            self.out_of_service,
+        )
+    }
+}
+
+impl BacnetObject for BitStringValue {
+    fn identifier(&self) -> ObjectIdentifier {
+        self.identifier
+    }
+
+    fn get_property(&self, property: PropertyIdentifier) -> Result<PropertyValue> {
+        match property {
+            PropertyIdentifier::ObjectIdentifier => {
+                Ok(PropertyValue::ObjectIdentifier(self.identifier))
+            }
+            PropertyIdentifier::ObjectName => {
+                Ok(PropertyValue::CharacterString(self.object_name.clone()))
+            }
+            PropertyIdentifier::ObjectType => Ok(PropertyValue::Enumerated(u32::from(
+                ObjectType::BitStringValue,
+            ))),
+            PropertyIdentifier::PresentValue => {
+                Ok(PropertyValue::BitString(self.present_value.clone()))
+            }
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::BitText => self
+                .bit_text
+                .as_ref()
+                .map(|bit_text| {
+                    PropertyValue::Array(
+                        bit_text
+                            .iter()
+                            .cloned()
+                            .map(PropertyValue::CharacterString)
+                            .collect(),
+                    )
+                })
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::StatusFlags => {
+                Ok(status_flags_bit_string(self.current_status_flags()))
+            }
+            PropertyIdentifier::EventState => {
+                Ok(PropertyValue::Enumerated(self.event_state as u32))
+            }
+            PropertyIdentifier::Reliability => {
+                Ok(PropertyValue::Enumerated(self.reliability as u32))
+            }
+            PropertyIdentifier::OutOfService => Ok(PropertyValue::Boolean(self.out_of_service)),
+            PropertyIdentifier::PriorityArray => self
+                .commandable
+                .as_ref()
+                .map(|commandable| {
+                    commandable
+                        .priority_array_value(|value| PropertyValue::BitString(value.clone()))
+                })
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::RelinquishDefault => self
+                .commandable
+                .as_ref()
+                .map(|commandable| {
+                    PropertyValue::BitString(commandable.relinquish_default().clone())
+                })
+                .ok_or(ObjectError::UnknownProperty),
+            _ => Err(ObjectError::UnknownProperty),
+        }
+    }
+
+    fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        self.set_property_with_priority(property, value, DEFAULT_COMMAND_PRIORITY)
+    }
+
+    fn set_property_with_priority(
+        &mut self,
+        property: PropertyIdentifier,
+        value: PropertyValue,
+        priority: u8,
+    ) -> Result<()> {
+        match property {
+            PropertyIdentifier::ObjectName => {
+                if let PropertyValue::CharacterString(name) = value {
+                    self.object_name = name;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::PresentValue => match value {
+                PropertyValue::BitString(bits) if self.is_commandable() => {
+                    self.write_priority(priority, Some(bits))
+                }
+                PropertyValue::BitString(bits) => self.set_present_value(bits),
+                // Writing NULL relinquishes the command at this priority
+                PropertyValue::Null if self.is_commandable() => self.write_priority(priority, None),
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            PropertyIdentifier::BitText if self.bit_text.is_some() => {
+                if let PropertyValue::Array(items) = value {
+                    let len = self.bit_text.as_ref().map_or(0, Vec::len);
+                    if items.len() != len {
+                        return Err(ObjectError::InvalidValue(format!(
+                            "Bit_Text must have {} entries",
+                            len
+                        )));
+                    }
+                    let bit_text = items
+                        .into_iter()
+                        .map(|item| match item {
+                            PropertyValue::CharacterString(text) => Ok(text),
+                            _ => Err(ObjectError::InvalidPropertyType),
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    self.bit_text = Some(bit_text);
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::RelinquishDefault if self.is_commandable() => {
+                if let PropertyValue::BitString(bits) = value {
+                    self.check_length(&bits)?;
+                    if let Some(commandable) = self.commandable.as_mut() {
+                        commandable.set_relinquish_default(bits);
+                    }
+                    self.update_present_value();
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::OutOfService => {
+                if let PropertyValue::Boolean(oos) = value {
+                    self.out_of_service = oos;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            _ => Err(ObjectError::PropertyNotWritable),
+        }
+    }
+
+    fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
+        match property {
+            PropertyIdentifier::ObjectName
+            | PropertyIdentifier::Description
+            | PropertyIdentifier::PresentValue
+            | PropertyIdentifier::OutOfService => true,
+            PropertyIdentifier::BitText => self.bit_text.is_some(),
+            PropertyIdentifier::RelinquishDefault => self.is_commandable(),
+            _ => false,
+        }
+    }
+
+    fn property_list(&self) -> Vec<PropertyIdentifier> {
+        let mut properties = vec![
+            PropertyIdentifier::ObjectIdentifier,
+            PropertyIdentifier::ObjectName,
+            PropertyIdentifier::ObjectType,
+            PropertyIdentifier::PresentValue,
+            PropertyIdentifier::Description,
+        ];
+        if self.bit_text.is_some() {
+            properties.push(PropertyIdentifier::BitText);
+        }
+        properties.extend([
+            PropertyIdentifier::StatusFlags,
+            PropertyIdentifier::EventState,
+            PropertyIdentifier::Reliability,
+            PropertyIdentifier::OutOfService,
+        ]);
+        if self.is_commandable() {
+            properties.push(PropertyIdentifier::PriorityArray);
+            properties.push(PropertyIdentifier::RelinquishDefault);
+        }
+        properties
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bit_string_value_bit_text() {
+        let mut bsv = BitStringValue::with_bit_text(
+            1,
+            "Chiller Faults".to_string(),
+            vec!["Low Flow".to_string(), "High Pressure".to_string()],
+        );
+        bsv.set_property(
+            PropertyIdentifier::PresentValue,
+            PropertyValue::BitString(vec![false, true]),
+        )
+        .unwrap();
+        assert!(bsv.bit(1));
+        assert!(matches!(
+            bsv.set_property(
+                PropertyIdentifier::PresentValue,
+                PropertyValue::BitString(vec![true])
+            ),
+            Err(ObjectError::InvalidValue(_))
+        ));
+
+        assert_eq!(
+            bsv.bit_text_element(0).unwrap(),
+            PropertyValue::UnsignedInteger(2)
+        );
+        bsv.set_bit_text_element(2, "Head Pressure".to_string())
+            .unwrap();
+        assert_eq!(
+            bsv.bit_text_element(2).unwrap(),
+            PropertyValue::CharacterString("Head Pressure".to_string())
+        );
+        assert!(matches!(
+            bsv.bit_text_element(3),
+            Err(ObjectError::InvalidArrayIndex)
+        ));
+    }
+
+    #[test]
+    fn test_bit_string_value_commandable() {
+        let mut bsv =
+            BitStringValue::new_commandable(2, "Zone Enables".to_string(), vec![false; 4]);
+        bsv.write_priority(8, Some(vec![true, false, true, false]))
+            .unwrap();
+        assert_eq!(bsv.present_value, vec![true, false, true, false]);
+        bsv.set_property_with_priority(PropertyIdentifier::PresentValue, PropertyValue::Null, 8)
+            .unwrap();
+        assert_eq!(bsv.present_value, vec![false; 4]);
+    }
+}
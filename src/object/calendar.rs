@@ -0,0 +1,443 @@
+//! Calendar Object Type Implementation
+//!
+//! This module implements the Calendar object type as defined in ASHRAE 135.
+//! A calendar holds a list of dates, date ranges and week-n-day patterns, and its
+//! present value is TRUE whenever the current date matches one of them.
+
+use crate::object::{
+    BacnetObject, Date, ObjectError, ObjectIdentifier, ObjectType, PropertyIdentifier,
+    PropertyValue, Result,
+};
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+/// Wildcard value for date fields
+const UNSPECIFIED: u8 = 255;
+
+/// A contiguous range of dates (BACnetDateRange)
+///
+/// A fully unspecified start or end date leaves that side of the range open.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateRange {
+    /// First day of the range
+    pub start_date: Date,
+    /// Last day of the range (inclusive)
+    pub end_date: Date,
+}
+
+impl DateRange {
+    /// Create a new date range
+    pub fn new(start_date: Date, end_date: Date) -> Self {
+        Self {
+            start_date,
+            end_date,
+        }
+    }
+
+    /// Check whether `date` falls within the range
+    pub fn contains(&self, date: &Date) -> bool {
+        let Some(day) = day_number(date) else {
+            return false;
+        };
+        let after_start = day_number(&self.start_date).is_none_or(|start| day >= start);
+        let before_end = day_number(&self.end_date).is_none_or(|end| day <= end);
+        after_start && before_end
+    }
+}
+
+/// A month / week-of-month / day-of-week pattern (BACnetWeekNDay)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WeekNDay {
+    /// Month 1-12, 13 = odd months, 14 = even months, 255 = any month
+    pub month: u8,
+    /// Week of month: 1-5 for days 1-7 .. 29-31, 6 = last 7 days, 7-9 = the
+    /// weeks before the last 7 days, 255 = any week
+    pub week_of_month: u8,
+    /// Day of week 1-7 (Monday-Sunday), 255 = any day
+    pub day_of_week: u8,
+}
+
+impl WeekNDay {
+    /// Create a new week-n-day pattern
+    pub fn new(month: u8, week_of_month: u8, day_of_week: u8) -> Self {
+        Self {
+            month,
+            week_of_month,
+            day_of_week,
+        }
+    }
+
+    /// Check whether `date` matches the pattern
+    pub fn matches(&self, date: &Date) -> bool {
+        if !month_matches(self.month, date.month) {
+            return false;
+        }
+        if self.day_of_week != UNSPECIFIED && weekday_of(date) != Some(self.day_of_week) {
+            return false;
+        }
+        match self.week_of_month {
+            UNSPECIFIED => true,
+            week @ 1..=5 => (date.day as u32).saturating_sub(1) / 7 + 1 == week as u32,
+            week @ 6..=9 => {
+                let Some(last) = days_in_month(date.year, date.month) else {
+                    return false;
+                };
+                // Count 7-day blocks back from the end of the month
+                let days_from_end = last.saturating_sub(date.day) as u32;
+                days_from_end / 7 == (week - 6) as u32
+            }
+            _ => false,
+        }
+    }
+}
+
+/// A single Date_List entry (BACnetCalendarEntry)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalendarEntry {
+    /// A date, possibly containing wildcard fields
+    Date(Date),
+    /// An inclusive range of dates
+    DateRange(DateRange),
+    /// A month / week / weekday pattern
+    WeekNDay(WeekNDay),
+}
+
+impl CalendarEntry {
+    /// Check whether `date` matches this entry
+    pub fn matches(&self, date: &Date) -> bool {
+        match self {
+            CalendarEntry::Date(pattern) => date_matches(pattern, date),
+            CalendarEntry::DateRange(range) => range.contains(date),
+            CalendarEntry::WeekNDay(pattern) => pattern.matches(date),
+        }
+    }
+
+    /// Encode the entry as a property value
+    ///
+    /// Dates map to `Date`, ranges to a two-element `List` of dates and
+    /// week-n-day patterns to their three-octet `OctetString` form.
+    pub fn to_property_value(&self) -> PropertyValue {
+        match self {
+            CalendarEntry::Date(date) => PropertyValue::Date(*date),
+            CalendarEntry::DateRange(range) => PropertyValue::List(vec![
+                PropertyValue::Date(range.start_date),
+                PropertyValue::Date(range.end_date),
+            ]),
+            CalendarEntry::WeekNDay(pattern) => PropertyValue::OctetString(vec![
+                pattern.month,
+                pattern.week_of_month,
+                pattern.day_of_week,
+            ]),
+        }
+    }
+
+    /// Decode an entry from its property value form
+    pub fn from_property_value(value: &PropertyValue) -> Result<Self> {
+        match value {
+            PropertyValue::Date(date) => Ok(CalendarEntry::Date(*date)),
+            PropertyValue::List(items) => match items.as_slice() {
+                [PropertyValue::Date(start), PropertyValue::Date(end)] => {
+                    Ok(CalendarEntry::DateRange(DateRange::new(*start, *end)))
+                }
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            PropertyValue::OctetString(octets) => match octets.as_slice() {
+                [month, week, day] => {
+                    Ok(CalendarEntry::WeekNDay(WeekNDay::new(*month, *week, *day)))
+                }
+                _ => Err(ObjectError::InvalidValue(
+                    "WeekNDay must be 3 octets".to_string(),
+                )),
+            },
+            _ => Err(ObjectError::InvalidPropertyType),
+        }
+    }
+}
+
+/// Check whether a date pattern (with wildcards) matches a concrete date
+pub fn date_matches(pattern: &Date, date: &Date) -> bool {
+    let year_matches = pattern.year == UNSPECIFIED as u16 || pattern.year == date.year;
+    let day_matches = match pattern.day {
+        UNSPECIFIED => true,
+        32 => days_in_month(date.year, date.month) == Some(date.day),
+        33 => !date.day.is_multiple_of(2),
+        34 => date.day.is_multiple_of(2),
+        day => day == date.day,
+    };
+    let weekday_matches =
+        pattern.weekday == UNSPECIFIED || weekday_of(date) == Some(pattern.weekday);
+    year_matches && month_matches(pattern.month, date.month) && day_matches && weekday_matches
+}
+
+fn month_matches(pattern: u8, month: u8) -> bool {
+    match pattern {
+        UNSPECIFIED => true,
+        13 => !month.is_multiple_of(2),
+        14 => month.is_multiple_of(2),
+        m => m == month,
+    }
+}
+
+fn is_leap_year(year: u16) -> bool {
+    (year.is_multiple_of(4) && !year.is_multiple_of(100)) || year.is_multiple_of(400)
+}
+
+/// Number of days in a month, or `None` if the month is not a real month
+fn days_in_month(year: u16, month: u8) -> Option<u8> {
+    match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => Some(31),
+        4 | 6 | 9 | 11 => Some(30),
+        2 if is_leap_year(year) => Some(29),
+        2 => Some(28),
+        _ => None,
+    }
+}
+
+/// Days since 1970-01-01 for a fully specified date
+pub(crate) fn day_number(date: &Date) -> Option<i64> {
+    let last = days_in_month(date.year, date.month)?;
+    if date.year == UNSPECIFIED as u16 || date.day == 0 || date.day > last {
+        return None;
+    }
+    // Howard Hinnant's days_from_civil
+    let (y, m, d) = (date.year as i64, date.month as i64, date.day as i64);
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (m + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    Some(era * 146_097 + doe - 719_468)
+}
+
+/// Day of week (1 = Monday .. 7 = Sunday), derived from the date itself
+pub(crate) fn weekday_of(date: &Date) -> Option<u8> {
+    // 1970-01-01 was a Thursday
+    day_number(date).map(|days| ((days + 3).rem_euclid(7) + 1) as u8)
+}
+
+/// Calendar object
+#[derive(Debug, Clone)]
+pub struct Calendar {
+    /// Object identifier
+    pub identifier: ObjectIdentifier,
+    /// Object name
+    pub object_name: String,
+    /// Description
+    pub description: String,
+    /// Present value (TRUE when the current date is in the date list)
+    pub present_value: bool,
+    /// Date list
+    pub date_list: Vec<CalendarEntry>,
+}
+
+impl Calendar {
+    /// Create a new Calendar object
+    pub fn new(instance: u32, object_name: String) -> Self {
+        Self {
+            identifier: ObjectIdentifier::new(ObjectType::Calendar, instance),
+            object_name,
+            description: String::new(),
+            present_value: false,
+            date_list: Vec::new(),
+        }
+    }
+
+    /// Check whether `date` matches any entry of the date list
+    pub fn is_active(&self, date: &Date) -> bool {
+        self.date_list.iter().any(|entry| entry.matches(date))
+    }
+
+    /// Recompute Present_Value for the given date
+    pub fn update_present_value(&mut self, today: &Date) {
+        self.present_value = self.is_active(today);
+    }
+
+    /// Recompute Present_Value from the local clock
+    #[cfg(feature = "std")]
+    pub fn refresh(&mut self) {
+        let today = crate::service::BacnetDateTime::now().date;
+        self.update_present_value(&today);
+    }
+
+    /// Add an entry to the date list
+    pub fn add_entry(&mut self, entry: CalendarEntry) {
+        self.date_list.push(entry);
+    }
+}
+
+impl BacnetObject for Calendar {
+    fn identifier(&self) -> ObjectIdentifier {
+        self.identifier
+    }
+
+    fn get_property(&self, property: PropertyIdentifier) -> Result<PropertyValue> {
+        match property {
+            PropertyIdentifier::ObjectIdentifier => {
+                Ok(PropertyValue::ObjectIdentifier(self.identifier))
+            }
+            PropertyIdentifier::ObjectName => {
+                Ok(PropertyValue::CharacterString(self.object_name.clone()))
+            }
+            PropertyIdentifier::ObjectType => {
+                Ok(PropertyValue::Enumerated(u32::from(ObjectType::Calendar)))
+            }
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::PresentValue => Ok(PropertyValue::Boolean(self.present_value)),
+            PropertyIdentifier::DateList => Ok(PropertyValue::List(
+                self.date_list
+                    .iter()
+                    .map(CalendarEntry::to_property_value)
+                    .collect(),
+            )),
+            _ => Err(ObjectError::UnknownProperty),
+        }
+    }
+
+    fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        match property {
+            PropertyIdentifier::ObjectName => {
+                if let PropertyValue::CharacterString(name) = value {
+                    self.object_name = name;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::DateList => {
+                if let PropertyValue::List(items) = value {
+                    self.date_list = items
+                        .iter()
+                        .map(CalendarEntry::from_property_value)
+                        .collect::<Result<Vec<_>>>()?;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            _ => Err(ObjectError::PropertyNotWritable),
+        }
+    }
+
+    fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
+        matches!(
+            property,
+            PropertyIdentifier::ObjectName
+                | PropertyIdentifier::Description
+                | PropertyIdentifier::DateList
+        )
+    }
+
+    fn property_list(&self) -> Vec<PropertyIdentifier> {
+        vec![
+            PropertyIdentifier::ObjectIdentifier,
+            PropertyIdentifier::ObjectName,
+            PropertyIdentifier::ObjectType,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::PresentValue,
+            PropertyIdentifier::DateList,
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: u16, month: u8, day: u8) -> Date {
+        Date {
+            year,
+            month,
+            day,
+            weekday: UNSPECIFIED,
+        }
+    }
+
+    #[test]
+    fn test_calendar_dates_and_ranges() {
+        let mut calendar = Calendar::new(1, "Holidays".to_string());
+        // Christmas every year
+        calendar.add_entry(CalendarEntry::Date(Date {
+            year: 255,
+            month: 12,
+            day: 25,
+            weekday: 255,
+        }));
+        calendar.add_entry(CalendarEntry::DateRange(DateRange::new(
+            date(2024, 7, 29),
+            date(2024, 8, 9),
+        )));
+
+        assert!(calendar.is_active(&date(2031, 12, 25)));
+        assert!(calendar.is_active(&date(2024, 8, 1)));
+        assert!(calendar.is_active(&date(2024, 8, 9)));
+        assert!(!calendar.is_active(&date(2024, 8, 10)));
+
+        calendar.update_present_value(&date(2024, 7, 30));
+        assert!(calendar.present_value);
+        calendar.update_present_value(&date(2024, 7, 1));
+        assert!(!calendar.present_value);
+    }
+
+    #[test]
+    fn test_calendar_week_n_day() {
+        // Last Monday in May (US Memorial Day)
+        let memorial_day = CalendarEntry::WeekNDay(WeekNDay::new(5, 6, 1));
+        assert!(memorial_day.matches(&date(2024, 5, 27)));
+        assert!(!memorial_day.matches(&date(2024, 5, 20)));
+
+        // Fourth Thursday in November (US Thanksgiving)
+        let thanksgiving = CalendarEntry::WeekNDay(WeekNDay::new(11, 4, 4));
+        assert!(thanksgiving.matches(&date(2024, 11, 28)));
+        assert!(!thanksgiving.matches(&date(2024, 11, 21)));
+
+        // Every Sunday
+        let sundays = CalendarEntry::WeekNDay(WeekNDay::new(255, 255, 7));
+        assert!(sundays.matches(&date(2024, 3, 3)));
+        assert!(!sundays.matches(&date(2024, 3, 4)));
+
+        // Last day of every month
+        let last_day = CalendarEntry::Date(Date {
+            year: 255,
+            month: 255,
+            day: 32,
+            weekday: 255,
+        });
+        assert!(last_day.matches(&date(2024, 2, 29)));
+        assert!(!last_day.matches(&date(2023, 2, 27)));
+    }
+
+    #[test]
+    fn test_calendar_date_list_property() {
+        let mut calendar = Calendar::new(2, "Shutdowns".to_string());
+        let entries = vec![
+            CalendarEntry::Date(date(2024, 1, 1)),
+            CalendarEntry::DateRange(DateRange::new(date(2024, 4, 1), date(2024, 4, 5))),
+            CalendarEntry::WeekNDay(WeekNDay::new(13, 1, 5)),
+        ];
+        let value = PropertyValue::List(entries.iter().map(|e| e.to_property_value()).collect());
+        calendar
+            .set_property(PropertyIdentifier::DateList, value)
+            .unwrap();
+        assert_eq!(calendar.date_list, entries);
+
+        assert!(calendar
+            .set_property(
+                PropertyIdentifier::DateList,
+                PropertyValue::List(vec![PropertyValue::OctetString(vec![1, 2])])
+            )
+            .is_err());
+        assert!(!calendar.is_property_writable(PropertyIdentifier::PresentValue));
+    }
+}
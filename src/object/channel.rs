@@ -0,0 +1,546 @@
+//! Channel Object Type Implementation
+//!
+//! This module implements the Channel object type as defined in ASHRAE 135. A
+//! Channel relays every write of its Present_Value to all members of
+//! List_Of_Object_Property_References at the priority of the write, so a single
+//! write can drive a whole group of points, as lighting scenes do.
+//!
+//! Before relaying, the value is coerced to the datatype of each member's
+//! Present_Value (Clause 12.53): numbers and Booleans become BINARY_PV for binary
+//! objects, REAL for analog objects and Unsigned for multi-state objects. Members
+//! the value cannot be coerced for are skipped and counted as failed writes. The
+//! outcome is reported through Write_Status once the database has made the writes.
+//!
+//! WriteGroup reaches a channel through one of its Control_Groups. Such writes
+//! wait for the group's entry in Execution_Delay before taking effect, unless
+//! the request inhibits the delay and Allow_Group_Delay_Inhibit permits it. The
+//! host application drives the delays through [`BacnetObject::advance_time`].
+
+use crate::object::{
+    current_status_flags, status_flags_bit_string, BacnetObject, DeviceObjectPropertyReference,
+    EventState, ObjectError, ObjectIdentifier, ObjectType, PropertyIdentifier, PropertyValue,
+    PropertyWrite, Reliability, Result, DEFAULT_COMMAND_PRIORITY,
+};
+use core::time::Duration;
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+/// Progress of the most recent Present_Value write (BACnetWriteStatus)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum WriteStatus {
+    Idle = 0,
+    InProgress = 1,
+    Successful = 2,
+    Failed = 3,
+}
+
+impl TryFrom<u32> for WriteStatus {
+    type Error = ObjectError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            0 => Ok(WriteStatus::Idle),
+            1 => Ok(WriteStatus::InProgress),
+            2 => Ok(WriteStatus::Successful),
+            3 => Ok(WriteStatus::Failed),
+            _ => Err(ObjectError::InvalidValue(format!(
+                "Invalid write status: {}",
+                value
+            ))),
+        }
+    }
+}
+
+/// Coerce a channel value to the datatype of `target`'s property
+///
+/// Returns `None` when the value cannot be converted. NULL (relinquish) passes
+/// through unchanged, as do values for properties other than Present_Value and
+/// targets whose datatype the channel does not know.
+pub fn coerce_value(
+    value: &PropertyValue,
+    target: &DeviceObjectPropertyReference,
+) -> Option<PropertyValue> {
+    if matches!(value, PropertyValue::Null)
+        || target.property_identifier != PropertyIdentifier::PresentValue
+    {
+        return Some(value.clone());
+    }
+    let number = match *value {
+        PropertyValue::Boolean(v) => Some(if v { 1.0 } else { 0.0 }),
+        PropertyValue::UnsignedInteger(v) => Some(v as f64),
+        PropertyValue::SignedInt(v) => Some(v as f64),
+        PropertyValue::Real(v) => Some(v as f64),
+        PropertyValue::Double(v) => Some(v),
+        PropertyValue::Enumerated(v) => Some(v as f64),
+        _ => None,
+    };
+    match target.object_identifier.object_type {
+        ObjectType::BinaryInput | ObjectType::BinaryOutput | ObjectType::BinaryValue => {
+            number.map(|n| PropertyValue::Enumerated((n != 0.0) as u32))
+        }
+        ObjectType::AnalogInput | ObjectType::AnalogOutput | ObjectType::AnalogValue => {
+            number.map(|n| PropertyValue::Real(n as f32))
+        }
+        ObjectType::LargeAnalogValue => number.map(PropertyValue::Double),
+        ObjectType::MultiStateInput
+        | ObjectType::MultiStateOutput
+        | ObjectType::MultiStateValue
+        | ObjectType::PositiveIntegerValue => number
+            .filter(|n| *n >= 0.0 && *n <= u32::MAX as f64)
+            .map(|n| PropertyValue::UnsignedInteger(n as u32)),
+        ObjectType::IntegerValue => number
+            .filter(|n| *n >= i32::MIN as f64 && *n <= i32::MAX as f64)
+            .map(|n| PropertyValue::SignedInt(n as i32)),
+        _ => Some(value.clone()),
+    }
+}
+
+/// Channel object
+#[derive(Debug, Clone)]
+pub struct Channel {
+    /// Object identifier
+    pub identifier: ObjectIdentifier,
+    /// Object name
+    pub object_name: String,
+    /// Description
+    pub description: String,
+    /// Present value (the value last written)
+    pub present_value: PropertyValue,
+    /// Priority of the last Present_Value write
+    pub last_priority: u8,
+    /// Outcome of the last Present_Value write
+    pub write_status: WriteStatus,
+    /// Reliability
+    pub reliability: Reliability,
+    /// Out of service
+    pub out_of_service: bool,
+    /// Properties every Present_Value write is relayed to
+    pub list_of_object_property_references: Vec<DeviceObjectPropertyReference>,
+    /// Channel number lighting controllers address this channel by
+    pub channel_number: u32,
+    /// Control groups this channel belongs to (0 = none)
+    pub control_groups: Vec<u32>,
+    /// Delay in milliseconds before a WriteGroup write through each control
+    /// group takes effect
+    pub execution_delay: Vec<u32>,
+    /// Whether a WriteGroup request may skip Execution_Delay
+    pub allow_group_delay_inhibit: bool,
+    /// WriteGroup writes waiting for their execution delay
+    delayed_writes: Vec<(Duration, PropertyValue, u8)>,
+    pending_writes: Vec<PropertyWrite>,
+    /// Members that received no write because coercion failed
+    coercion_failures: usize,
+}
+
+impl Channel {
+    /// Create a new Channel with no members
+    pub fn new(instance: u32, object_name: String, channel_number: u32) -> Self {
+        Self {
+            identifier: ObjectIdentifier::new(ObjectType::Channel, instance),
+            object_name,
+            description: String::new(),
+            present_value: PropertyValue::Null,
+            last_priority: DEFAULT_COMMAND_PRIORITY,
+            write_status: WriteStatus::Idle,
+            reliability: Reliability::NoFaultDetected,
+            out_of_service: false,
+            list_of_object_property_references: Vec::new(),
+            channel_number,
+            control_groups: Vec::new(),
+            execution_delay: Vec::new(),
+            allow_group_delay_inhibit: false,
+            delayed_writes: Vec::new(),
+            pending_writes: Vec::new(),
+            coercion_failures: 0,
+        }
+    }
+
+    /// Add a member property
+    pub fn add_member(&mut self, reference: DeviceObjectPropertyReference) {
+        self.list_of_object_property_references.push(reference);
+    }
+
+    /// Write Present_Value and queue the coerced writes to every member
+    ///
+    /// While the channel is out of service the value is stored but not relayed.
+    pub fn write(&mut self, value: PropertyValue, priority: u8) -> Result<()> {
+        if !(1..=16).contains(&priority) {
+            return Err(ObjectError::InvalidValue(
+                "Priority must be 1-16".to_string(),
+            ));
+        }
+        self.present_value = value;
+        self.last_priority = priority;
+        if self.out_of_service {
+            return Ok(());
+        }
+
+        self.pending_writes.clear();
+        self.coercion_failures = 0;
+        for reference in &self.list_of_object_property_references {
+            match coerce_value(&self.present_value, reference) {
+                Some(value) => self.pending_writes.push(PropertyWrite {
+                    reference: *reference,
+                    value,
+                    priority,
+                }),
+                None => self.coercion_failures += 1,
+            }
+        }
+        self.write_status = if self.pending_writes.is_empty() {
+            self.completed_status(true)
+        } else {
+            WriteStatus::InProgress
+        };
+        Ok(())
+    }
+
+    /// Write Present_Value on behalf of a WriteGroup request to `group_number`
+    ///
+    /// The write waits for the group's Execution_Delay, if any, unless
+    /// `inhibit_delay` is set and Allow_Group_Delay_Inhibit is TRUE.
+    pub fn write_group(
+        &mut self,
+        value: PropertyValue,
+        priority: u8,
+        group_number: u32,
+        inhibit_delay: bool,
+    ) -> Result<()> {
+        if !(1..=16).contains(&priority) {
+            return Err(ObjectError::InvalidValue(
+                "Priority must be 1-16".to_string(),
+            ));
+        }
+        let delay = self
+            .control_groups
+            .iter()
+            .position(|group| *group == group_number)
+            .and_then(|index| self.execution_delay.get(index))
+            .copied()
+            .unwrap_or(0);
+        if delay == 0 || (inhibit_delay && self.allow_group_delay_inhibit) {
+            self.write(value, priority)
+        } else {
+            self.delayed_writes
+                .push((Duration::from_millis(delay as u64), value, priority));
+            Ok(())
+        }
+    }
+
+    fn completed_status(&self, all_successful: bool) -> WriteStatus {
+        if all_successful && self.coercion_failures == 0 {
+            WriteStatus::Successful
+        } else {
+            WriteStatus::Failed
+        }
+    }
+
+    fn current_status_flags(&self) -> u8 {
+        current_status_flags(0, EventState::Normal, self.reliability, self.out_of_service)
+    }
+}
+
+impl BacnetObject for Channel {
+    fn identifier(&self) -> ObjectIdentifier {
+        self.identifier
+    }
+
+    fn get_property(&self, property: PropertyIdentifier) -> Result<PropertyValue> {
+        match property {
+            PropertyIdentifier::ObjectIdentifier => {
+                Ok(PropertyValue::ObjectIdentifier(self.identifier))
+            }
+            PropertyIdentifier::ObjectName => {
+                Ok(PropertyValue::CharacterString(self.object_name.clone()))
+            }
+            PropertyIdentifier::ObjectType => {
+                Ok(PropertyValue::Enumerated(u32::from(ObjectType::Channel)))
+            }
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::PresentValue => Ok(self.present_value.clone()),
+            PropertyIdentifier::LastPriority => {
+                Ok(PropertyValue::UnsignedInteger(self.last_priority as u32))
+            }
+            PropertyIdentifier::WriteStatus => {
+                Ok(PropertyValue::Enumerated(self.write_status as u32))
+            }
+            PropertyIdentifier::StatusFlags => {
+                Ok(status_flags_bit_string(self.current_status_flags()))
+            }
+            PropertyIdentifier::Reliability => {
+                Ok(PropertyValue::Enumerated(self.reliability as u32))
+            }
+            PropertyIdentifier::OutOfService => Ok(PropertyValue::Boolean(self.out_of_service)),
+            PropertyIdentifier::ListOfObjectPropertyReferences => Ok(PropertyValue::Array(
+                self.list_of_object_property_references
+                    .iter()
+                    .map(DeviceObjectPropertyReference::to_property_value)
+                    .collect(),
+            )),
+            PropertyIdentifier::ChannelNumber => {
+                Ok(PropertyValue::UnsignedInteger(self.channel_number))
+            }
+            PropertyIdentifier::ControlGroups => Ok(PropertyValue::Array(
+                self.control_groups
+                    .iter()
+                    .copied()
+                    .map(PropertyValue::UnsignedInteger)
+                    .collect(),
+            )),
+            PropertyIdentifier::ExecutionDelay => Ok(PropertyValue::Array(
+                self.execution_delay
+                    .iter()
+                    .copied()
+                    .map(PropertyValue::UnsignedInteger)
+                    .collect(),
+            )),
+            PropertyIdentifier::AllowGroupDelayInhibit => {
+                Ok(PropertyValue::Boolean(self.allow_group_delay_inhibit))
+            }
+            _ => Err(ObjectError::UnknownProperty),
+        }
+    }
+
+    fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        self.set_property_with_priority(property, value, DEFAULT_COMMAND_PRIORITY)
+    }
+
+    fn set_property_with_priority(
+        &mut self,
+        property: PropertyIdentifier,
+        value: PropertyValue,
+        priority: u8,
+    ) -> Result<()> {
+        match property {
+            PropertyIdentifier::ObjectName => {
+                if let PropertyValue::CharacterString(name) = value {
+                    self.object_name = name;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::PresentValue => match value {
+                PropertyValue::Array(_) | PropertyValue::List(_) => {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+                value => self.write(value, priority),
+            },
+            PropertyIdentifier::OutOfService => {
+                if let PropertyValue::Boolean(oos) = value {
+                    self.out_of_service = oos;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::ChannelNumber => {
+                if let PropertyValue::UnsignedInteger(number) = value {
+                    self.channel_number = number;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::ControlGroups => {
+                self.control_groups = unsigned_array(value)?;
+                Ok(())
+            }
+            PropertyIdentifier::ExecutionDelay => {
+                self.execution_delay = unsigned_array(value)?;
+                Ok(())
+            }
+            PropertyIdentifier::AllowGroupDelayInhibit => {
+                if let PropertyValue::Boolean(allow) = value {
+                    self.allow_group_delay_inhibit = allow;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            _ => Err(ObjectError::PropertyNotWritable),
+        }
+    }
+
+    fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
+        matches!(
+            property,
+            PropertyIdentifier::ObjectName
+                | PropertyIdentifier::Description
+                | PropertyIdentifier::PresentValue
+                | PropertyIdentifier::OutOfService
+                | PropertyIdentifier::ChannelNumber
+                | PropertyIdentifier::ControlGroups
+                | PropertyIdentifier::ExecutionDelay
+                | PropertyIdentifier::AllowGroupDelayInhibit
+        )
+    }
+
+    fn property_list(&self) -> Vec<PropertyIdentifier> {
+        vec![
+            PropertyIdentifier::ObjectIdentifier,
+            PropertyIdentifier::ObjectName,
+            PropertyIdentifier::ObjectType,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::PresentValue,
+            PropertyIdentifier::LastPriority,
+            PropertyIdentifier::WriteStatus,
+            PropertyIdentifier::StatusFlags,
+            PropertyIdentifier::Reliability,
+            PropertyIdentifier::OutOfService,
+            PropertyIdentifier::ListOfObjectPropertyReferences,
+            PropertyIdentifier::ChannelNumber,
+            PropertyIdentifier::ControlGroups,
+            PropertyIdentifier::ExecutionDelay,
+            PropertyIdentifier::AllowGroupDelayInhibit,
+        ]
+    }
+
+    fn advance_time(&mut self, elapsed: Duration) {
+        let mut due = Vec::new();
+        self.delayed_writes
+            .retain_mut(|(remaining, value, priority)| {
+                *remaining = remaining.saturating_sub(elapsed);
+                if remaining.is_zero() {
+                    due.push((value.clone(), *priority));
+                    false
+                } else {
+                    true
+                }
+            });
+        for (value, priority) in due {
+            // The priority was checked when the write was queued
+            let _ = self.write(value, priority);
+        }
+    }
+
+    fn take_pending_writes(&mut self) -> Vec<PropertyWrite> {
+        core::mem::take(&mut self.pending_writes)
+    }
+
+    fn report_write_results(&mut self, results: &[bool]) {
+        if self.write_status == WriteStatus::InProgress {
+            self.write_status = self.completed_status(results.iter().all(|&ok| ok));
+        }
+    }
+
+    fn as_channel_mut(&mut self) -> Option<&mut Channel> {
+        Some(self)
+    }
+}
+
+fn unsigned_array(value: PropertyValue) -> Result<Vec<u32>> {
+    let PropertyValue::Array(items) = value else {
+        return Err(ObjectError::InvalidPropertyType);
+    };
+    items
+        .into_iter()
+        .map(|item| match item {
+            PropertyValue::UnsignedInteger(number) => Ok(number),
+            _ => Err(ObjectError::InvalidPropertyType),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_channel_coerces_member_writes() {
+        let bo = ObjectIdentifier::new(ObjectType::BinaryOutput, 1);
+        let ao = ObjectIdentifier::new(ObjectType::AnalogOutput, 1);
+        let msv = ObjectIdentifier::new(ObjectType::MultiStateValue, 1);
+        let mut channel = Channel::new(1, "Scene".to_string(), 10);
+        for member in [bo, ao, msv] {
+            channel.add_member(DeviceObjectPropertyReference::new(
+                member,
+                PropertyIdentifier::PresentValue,
+            ));
+        }
+
+        channel
+            .set_property_with_priority(
+                PropertyIdentifier::PresentValue,
+                PropertyValue::Real(75.0),
+                8,
+            )
+            .unwrap();
+        assert_eq!(channel.write_status, WriteStatus::InProgress);
+        let values: Vec<_> = channel
+            .take_pending_writes()
+            .into_iter()
+            .map(|write| (write.value, write.priority))
+            .collect();
+        assert_eq!(
+            values,
+            vec![
+                (PropertyValue::Enumerated(1), 8),
+                (PropertyValue::Real(75.0), 8),
+                (PropertyValue::UnsignedInteger(75), 8),
+            ]
+        );
+
+        channel.report_write_results(&[true, true, false]);
+        assert_eq!(channel.write_status, WriteStatus::Failed);
+    }
+
+    #[test]
+    fn test_channel_uncoercible_value() {
+        let mut channel = Channel::new(2, "Setpoints".to_string(), 11);
+        channel.add_member(DeviceObjectPropertyReference::new(
+            ObjectIdentifier::new(ObjectType::AnalogValue, 1),
+            PropertyIdentifier::PresentValue,
+        ));
+        channel
+            .write(PropertyValue::CharacterString("warm".to_string()), 10)
+            .unwrap();
+        assert!(channel.take_pending_writes().is_empty());
+        assert_eq!(channel.write_status, WriteStatus::Failed);
+        assert_eq!(channel.last_priority, 10);
+    }
+
+    #[test]
+    fn test_write_group_execution_delay() {
+        let mut channel = Channel::new(3, "Lobby".to_string(), 12);
+        channel.add_member(DeviceObjectPropertyReference::new(
+            ObjectIdentifier::new(ObjectType::AnalogOutput, 1),
+            PropertyIdentifier::PresentValue,
+        ));
+        channel.control_groups = vec![4, 5];
+        channel.execution_delay = vec![0, 500];
+
+        channel
+            .write_group(PropertyValue::Real(10.0), 9, 4, false)
+            .unwrap();
+        assert_eq!(channel.take_pending_writes().len(), 1);
+
+        channel
+            .write_group(PropertyValue::Real(20.0), 9, 5, true)
+            .unwrap();
+        assert!(channel.take_pending_writes().is_empty());
+        channel.advance_time(Duration::from_millis(300));
+        assert!(channel.take_pending_writes().is_empty());
+        channel.advance_time(Duration::from_millis(200));
+        assert_eq!(channel.present_value, PropertyValue::Real(20.0));
+        assert_eq!(channel.take_pending_writes().len(), 1);
+
+        channel.allow_group_delay_inhibit = true;
+        channel
+            .write_group(PropertyValue::Real(30.0), 9, 5, true)
+            .unwrap();
+        assert_eq!(channel.present_value, PropertyValue::Real(30.0));
+    }
+}
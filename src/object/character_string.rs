@@ -0,0 +1,314 @@
+//! CharacterString Value Object Type Implementation
+//!
+//! This module implements the CharacterString Value object type as defined in
+//! ASHRAE 135, the text counterpart of the Octet String Value object. Instances
+//! created with [`CharacterStringValue::new_commandable`] arbitrate Present_Value
+//! writes through a priority array.
+
+use crate::object::{
+    current_status_flags, status_flags_bit_string, BacnetObject, Commandable, EventState,
+    ObjectError, ObjectIdentifier, ObjectType, PropertyIdentifier, PropertyValue, Reliability,
+    Result, DEFAULT_COMMAND_PRIORITY,
+};
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+/// Default limit on the encoded length of Present_Value, in bytes, so the
+/// value fits in an unsegmented 1024-byte APDU
+pub const MAX_CHARACTER_STRING_SIZE: usize = 900;
+
+/// CharacterString Value object
+#[derive(Debug, Clone)]
+pub struct CharacterStringValue {
+    /// Object identifier
+    pub identifier: ObjectIdentifier,
+    /// Object name
+    pub object_name: String,
+    /// Description
+    pub description: String,
+    /// Present value
+    pub present_value: String,
+    /// Status flags
+    pub status_flags: u8,
+    /// Event state
+    pub event_state: EventState,
+    /// Reliability
+    pub reliability: Reliability,
+    /// Out of service
+    pub out_of_service: bool,
+    /// Priority array and relinquish default, present only on commandable
+    /// instances
+    pub commandable: Option<Commandable<String>>,
+    /// Maximum length of Present_Value in bytes
+    pub max_length: usize,
+}
+
+impl CharacterStringValue {
+    /// Create a new non-commandable CharacterString Value object
+    pub fn new(instance: u32, object_name: String) -> Self {
+        Self {
+            identifier: ObjectIdentifier::new(ObjectType::CharacterStringValue, instance),
+            object_name,
+            description: String::new(),
+            present_value: String::new(),
+            status_flags: 0,
+            event_state: EventState::Normal,
+            reliability: Reliability::NoFaultDetected,
+            out_of_service: false,
+            commandable: None,
+            max_length: MAX_CHARACTER_STRING_SIZE,
+        }
+    }
+
+    /// Create a new commandable CharacterString Value object with a priority array
+    pub fn new_commandable(instance: u32, object_name: String, relinquish_default: String) -> Self {
+        let mut csv = Self::new(instance, object_name);
+        csv.present_value = relinquish_default.clone();
+        csv.commandable = Some(Commandable::new(relinquish_default));
+        csv
+    }
+
+    /// Whether Present_Value writes are arbitrated through a priority array
+    pub fn is_commandable(&self) -> bool {
+        self.commandable.is_some()
+    }
+
+    /// Set the present value, enforcing the maximum length
+    pub fn set_present_value(&mut self, value: String) -> Result<()> {
+        self.check_length(&value)?;
+        self.present_value = value;
+        Ok(())
+    }
+
+    /// Write to priority array at specified priority level (1-16)
+    pub fn write_priority(&mut self, priority: u8, value: Option<String>) -> Result<()> {
+        if !(1..=16).contains(&priority) {
+            return Err(ObjectError::InvalidValue(
+                "Priority must be 1-16".to_string(),
+            ));
+        }
+        if let Some(value) = value.as_ref() {
+            self.check_length(value)?;
+        }
+        let Some(commandable) = self.commandable.as_mut() else {
+            return Err(ObjectError::InvalidConfiguration(
+                "CharacterString Value is not commandable".to_string(),
+            ));
+        };
+        commandable.write(priority, value)?;
+        self.update_present_value();
+        Ok(())
+    }
+
+    /// Update present value based on priority array
+    fn update_present_value(&mut self) {
+        if let Some(commandable) = self.commandable.as_ref() {
+            self.present_value = commandable.current().clone();
+        }
+    }
+
+    fn check_length(&self, value: &str) -> Result<()> {
+        if value.len() > self.max_length {
+            return Err(ObjectError::InvalidValue(format!(
+                "String of {} bytes exceeds maximum length {}",
+                value.len(),
+                self.max_length
+            )));
+        }
+        Ok(())
+    }
+
+    /// Current Status_Flags, combining the stored flags with the state derived
+    /// from Event_State, Reliability and Out_Of_Service
+    pub fn current_status_flags(&self) -> u8 {
+        current_status_flags(
+            self.status_flags,
+            self.event_state,
+            self.reliability,
+            self.out_of_service,
+        )
+    }
+}
+
+impl BacnetObject for CharacterStringValue {
+    fn identifier(&self) -> ObjectIdentifier {
+        self.identifier
+    }
+
+    fn get_property(&self, property: PropertyIdentifier) -> Result<PropertyValue> {
+        match property {
+            PropertyIdentifier::ObjectIdentifier => {
+                Ok(PropertyValue::ObjectIdentifier(self.identifier))
+            }
+            PropertyIdentifier::ObjectName => {
+                Ok(PropertyValue::CharacterString(self.object_name.clone()))
+            }
+            PropertyIdentifier::ObjectType => Ok(PropertyValue::Enumerated(u32::from(
+                ObjectType::CharacterStringValue,
+            ))),
+            PropertyIdentifier::PresentValue => {
+                Ok(PropertyValue::CharacterString(self.present_value.clone()))
+            }
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::StatusFlags => {
+                Ok(status_flags_bit_string(self.current_status_flags()))
+            }
+            PropertyIdentifier::EventState => {
+                Ok(PropertyValue::Enumerated(self.event_state as u32))
+            }
+            PropertyIdentifier::Reliability => {
+                Ok(PropertyValue::Enumerated(self.reliability as u32))
+            }
+            PropertyIdentifier::OutOfService => Ok(PropertyValue::Boolean(self.out_of_service)),
+            PropertyIdentifier::PriorityArray => self
+                .commandable
+                .as_ref()
+                .map(|commandable| {
+                    commandable
+                        .priority_array_value(|value| PropertyValue::CharacterString(value.clone()))
+                })
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::RelinquishDefault => self
+                .commandable
+                .as_ref()
+                .map(|commandable| {
+                    PropertyValue::CharacterString(commandable.relinquish_default().clone())
+                })
+                .ok_or(ObjectError::UnknownProperty),
+            _ => Err(ObjectError::UnknownProperty),
+        }
+    }
+
+    fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        self.set_property_with_priority(property, value, DEFAULT_COMMAND_PRIORITY)
+    }
+
+    fn set_property_with_priority(
+        &mut self,
+        property: PropertyIdentifier,
+        value: PropertyValue,
+        priority: u8,
+    ) -> Result<()> {
+        match property {
+            PropertyIdentifier::ObjectName => {
+                if let PropertyValue::CharacterString(name) = value {
+                    self.object_name = name;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::PresentValue => match value {
+                PropertyValue::CharacterString(val) if self.is_commandable() => {
+                    self.write_priority(priority, Some(val))
+                }
+                PropertyValue::CharacterString(val) => self.set_present_value(val),
+                // Writing NULL relinquishes the command at this priority
+                PropertyValue::Null if self.is_commandable() => self.write_priority(priority, None),
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            PropertyIdentifier::RelinquishDefault if self.is_commandable() => {
+                if let PropertyValue::CharacterString(val) = value {
+                    self.check_length(&val)?;
+                    if let Some(commandable) = self.commandable.as_mut() {
+                        commandable.set_relinquish_default(val);
+                    }
+                    self.update_present_value();
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::OutOfService => {
+                if let PropertyValue::Boolean(oos) = value {
+                    self.out_of_service = oos;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            _ => Err(ObjectError::PropertyNotWritable),
+        }
+    }
+
+    fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
+        match property {
+            PropertyIdentifier::ObjectName
+            | PropertyIdentifier::Description
+            | PropertyIdentifier::PresentValue
+            | PropertyIdentifier::OutOfService => true,
+            PropertyIdentifier::RelinquishDefault => self.is_commandable(),
+            _ => false,
+        }
+    }
+
+    fn property_list(&self) -> Vec<PropertyIdentifier> {
+        let mut properties = vec![
+            PropertyIdentifier::ObjectIdentifier,
+            PropertyIdentifier::ObjectName,
+            PropertyIdentifier::ObjectType,
+            PropertyIdentifier::PresentValue,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::StatusFlags,
+            PropertyIdentifier::EventState,
+            PropertyIdentifier::Reliability,
+            PropertyIdentifier::OutOfService,
+        ];
+        if self.is_commandable() {
+            properties.push(PropertyIdentifier::PriorityArray);
+            properties.push(PropertyIdentifier::RelinquishDefault);
+        }
+        properties
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_character_string_value_max_length() {
+        let mut csv = CharacterStringValue::new(1, "Message".to_string());
+        csv.max_length = 8;
+        csv.set_property(
+            PropertyIdentifier::PresentValue,
+            PropertyValue::CharacterString("Occupied".to_string()),
+        )
+        .unwrap();
+        assert_eq!(csv.present_value, "Occupied");
+        assert!(csv.set_present_value("Unoccupied".to_string()).is_err());
+        assert_eq!(csv.present_value, "Occupied");
+    }
+
+    #[test]
+    fn test_character_string_value_commandable() {
+        let mut csv =
+            CharacterStringValue::new_commandable(2, "Display Text".to_string(), "Idle".into());
+        csv.set_property_with_priority(
+            PropertyIdentifier::PresentValue,
+            PropertyValue::CharacterString("Fire Alarm".to_string()),
+            2,
+        )
+        .unwrap();
+        csv.write_priority(8, Some("Maintenance".to_string()))
+            .unwrap();
+        assert_eq!(csv.present_value, "Fire Alarm");
+
+        csv.set_property_with_priority(PropertyIdentifier::PresentValue, PropertyValue::Null, 2)
+            .unwrap();
+        assert_eq!(csv.present_value, "Maintenance");
+        csv.write_priority(8, None).unwrap();
+        assert_eq!(csv.present_value, "Idle");
+    }
+}
@@ -0,0 +1,507 @@
+//! Command Object Type Implementation
+//!
+//! This module implements the Command object type as defined in ASHRAE 135. A
+//! Command holds a set of action lists; writing N to Present_Value executes the
+//! Nth list, writing each referenced property in order. In_Process is TRUE while a
+//! list is running and All_Writes_Successful reports the outcome once it finishes.
+//!
+//! Writes are queued through `take_pending_writes` and the database reports their
+//! outcome back through `report_write_results`. A write with a Post_Delay holds the
+//! remaining writes until that many seconds of `advance_time` have passed, and a
+//! failed write with Quit_On_Failure set abandons the rest of the list.
+
+use crate::object::{
+    current_status_flags, status_flags_bit_string, BacnetObject, DeviceObjectPropertyReference,
+    EventState, ObjectError, ObjectIdentifier, ObjectType, PropertyIdentifier, PropertyValue,
+    PropertyWrite, Reliability, Result, DEFAULT_COMMAND_PRIORITY,
+};
+use core::time::Duration;
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+/// One write within an action list (BACnetActionCommand)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActionCommand {
+    /// Device containing the object, or `None` for the local device
+    pub device_identifier: Option<ObjectIdentifier>,
+    /// Object to write
+    pub object_identifier: ObjectIdentifier,
+    /// Property to write
+    pub property_identifier: PropertyIdentifier,
+    /// Array index, if a single element is written
+    pub property_array_index: Option<u32>,
+    /// Value to write
+    pub property_value: PropertyValue,
+    /// Command priority, if the property is commandable
+    pub priority: Option<u8>,
+    /// Seconds to wait after this write before the next one
+    pub post_delay: Option<u32>,
+    /// Abandon the rest of the list if this write fails
+    pub quit_on_failure: bool,
+    /// Whether this write succeeded the last time the list ran
+    pub write_successful: bool,
+}
+
+impl ActionCommand {
+    /// Create a write of `value` to a property of a local object
+    pub fn new(
+        object_identifier: ObjectIdentifier,
+        property_identifier: PropertyIdentifier,
+        property_value: PropertyValue,
+    ) -> Self {
+        Self {
+            device_identifier: None,
+            object_identifier,
+            property_identifier,
+            property_array_index: None,
+            property_value,
+            priority: None,
+            post_delay: None,
+            quit_on_failure: false,
+            write_successful: false,
+        }
+    }
+
+    /// The property write this action performs
+    pub fn to_property_write(&self) -> PropertyWrite {
+        PropertyWrite {
+            reference: DeviceObjectPropertyReference {
+                object_identifier: self.object_identifier,
+                property_identifier: self.property_identifier,
+                property_array_index: self.property_array_index,
+                device_identifier: self.device_identifier,
+            },
+            value: self.property_value.clone(),
+            priority: self.priority.unwrap_or(DEFAULT_COMMAND_PRIORITY),
+        }
+    }
+
+    /// Encode the action as a property value
+    ///
+    /// Optional fields are encoded as Null so each field keeps its position.
+    pub fn to_property_value(&self) -> PropertyValue {
+        let optional_unsigned = |value: Option<u32>| {
+            value
+                .map(PropertyValue::UnsignedInteger)
+                .unwrap_or(PropertyValue::Null)
+        };
+        PropertyValue::List(vec![
+            self.device_identifier
+                .map(PropertyValue::ObjectIdentifier)
+                .unwrap_or(PropertyValue::Null),
+            PropertyValue::ObjectIdentifier(self.object_identifier),
+            PropertyValue::Enumerated(u32::from(self.property_identifier)),
+            optional_unsigned(self.property_array_index),
+            self.property_value.clone(),
+            optional_unsigned(self.priority.map(u32::from)),
+            optional_unsigned(self.post_delay),
+            PropertyValue::Boolean(self.quit_on_failure),
+            PropertyValue::Boolean(self.write_successful),
+        ])
+    }
+}
+
+/// Command object
+#[derive(Debug, Clone)]
+pub struct Command {
+    /// Object identifier
+    pub identifier: ObjectIdentifier,
+    /// Object name
+    pub object_name: String,
+    /// Description
+    pub description: String,
+    /// Present value (the last action list executed, zero for none)
+    pub present_value: u32,
+    /// Event state
+    pub event_state: EventState,
+    /// Reliability
+    pub reliability: Reliability,
+    /// Whether an action list is executing
+    pub in_process: bool,
+    /// Whether every write of the last action list succeeded
+    pub all_writes_successful: bool,
+    /// Action lists, selected by Present_Value starting at one
+    pub action: Vec<Vec<ActionCommand>>,
+    /// Text describing each action list
+    pub action_text: Option<Vec<String>>,
+    next_command: usize,
+    in_flight: Vec<usize>,
+    any_failed: bool,
+    post_delay_remaining: Option<Duration>,
+    pending: Vec<PropertyWrite>,
+}
+
+impl Command {
+    /// Create a new Command with no action lists
+    pub fn new(instance: u32, object_name: String) -> Self {
+        Self {
+            identifier: ObjectIdentifier::new(ObjectType::Command, instance),
+            object_name,
+            description: String::new(),
+            present_value: 0,
+            event_state: EventState::Normal,
+            reliability: Reliability::NoFaultDetected,
+            in_process: false,
+            all_writes_successful: true,
+            action: Vec::new(),
+            action_text: None,
+            next_command: 0,
+            in_flight: Vec::new(),
+            any_failed: false,
+            post_delay_remaining: None,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Append an action list, returning the Present_Value that selects it
+    pub fn add_action(&mut self, commands: Vec<ActionCommand>) -> u32 {
+        self.action.push(commands);
+        self.action.len() as u32
+    }
+
+    /// Start executing action list `action` (one-based)
+    ///
+    /// Zero selects no action. A Command that is already in process rejects the
+    /// request.
+    pub fn execute(&mut self, action: u32) -> Result<()> {
+        if self.in_process {
+            return Err(ObjectError::WriteAccessDenied);
+        }
+        if action as usize > self.action.len() {
+            return Err(ObjectError::InvalidValue(format!(
+                "Action {} not defined; {} action lists exist",
+                action,
+                self.action.len()
+            )));
+        }
+        self.present_value = action;
+        let Some(list) = self.current_list_mut() else {
+            return Ok(());
+        };
+        for command in list.iter_mut() {
+            command.write_successful = false;
+        }
+        self.in_process = true;
+        self.all_writes_successful = false;
+        self.any_failed = false;
+        self.next_command = 0;
+        self.post_delay_remaining = None;
+        self.queue_next_batch();
+        Ok(())
+    }
+
+    fn current_list(&self) -> Option<&Vec<ActionCommand>> {
+        let index = (self.present_value as usize).checked_sub(1)?;
+        self.action.get(index)
+    }
+
+    fn current_list_mut(&mut self) -> Option<&mut Vec<ActionCommand>> {
+        let index = (self.present_value as usize).checked_sub(1)?;
+        self.action.get_mut(index)
+    }
+
+    /// Queue writes up to and including the next one that must be confirmed
+    /// before continuing (a Post_Delay or Quit_On_Failure write)
+    fn queue_next_batch(&mut self) {
+        let start = self.next_command;
+        let Some(list) = self.current_list() else {
+            self.finish();
+            return;
+        };
+        if start >= list.len() {
+            self.finish();
+            return;
+        }
+        let mut end = start;
+        while end < list.len() {
+            let command = &list[end];
+            end += 1;
+            if command.quit_on_failure || command.post_delay.is_some_and(|delay| delay > 0) {
+                break;
+            }
+        }
+        self.pending = list[start..end]
+            .iter()
+            .map(ActionCommand::to_property_write)
+            .collect();
+        self.in_flight = (start..end).collect();
+        self.next_command = end;
+    }
+
+    fn finish(&mut self) {
+        self.in_process = false;
+        self.all_writes_successful = !self.any_failed;
+        self.in_flight.clear();
+        self.pending.clear();
+        self.post_delay_remaining = None;
+    }
+
+    fn current_status_flags(&self) -> u8 {
+        current_status_flags(0, self.event_state, self.reliability, false)
+    }
+}
+
+impl BacnetObject for Command {
+    fn identifier(&self) -> ObjectIdentifier {
+        self.identifier
+    }
+
+    fn get_property(&self, property: PropertyIdentifier) -> Result<PropertyValue> {
+        match property {
+            PropertyIdentifier::ObjectIdentifier => {
+                Ok(PropertyValue::ObjectIdentifier(self.identifier))
+            }
+            PropertyIdentifier::ObjectName => {
+                Ok(PropertyValue::CharacterString(self.object_name.clone()))
+            }
+            PropertyIdentifier::ObjectType => {
+                Ok(PropertyValue::Enumerated(u32::from(ObjectType::Command)))
+            }
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::PresentValue => {
+                Ok(PropertyValue::UnsignedInteger(self.present_value))
+            }
+            PropertyIdentifier::InProcess => Ok(PropertyValue::Boolean(self.in_process)),
+            PropertyIdentifier::AllWritesSuccessful => {
+                Ok(PropertyValue::Boolean(self.all_writes_successful))
+            }
+            PropertyIdentifier::Action => Ok(PropertyValue::Array(
+                self.action
+                    .iter()
+                    .map(|list| {
+                        PropertyValue::List(
+                            list.iter().map(ActionCommand::to_property_value).collect(),
+                        )
+                    })
+                    .collect(),
+            )),
+            PropertyIdentifier::ActionText => self
+                .action_text
+                .as_ref()
+                .map(|texts| {
+                    PropertyValue::Array(
+                        texts
+                            .iter()
+                            .cloned()
+                            .map(PropertyValue::CharacterString)
+                            .collect(),
+                    )
+                })
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::StatusFlags => {
+                Ok(status_flags_bit_string(self.current_status_flags()))
+            }
+            PropertyIdentifier::EventState => {
+                Ok(PropertyValue::Enumerated(self.event_state as u32))
+            }
+            PropertyIdentifier::Reliability => {
+                Ok(PropertyValue::Enumerated(self.reliability as u32))
+            }
+            _ => Err(ObjectError::UnknownProperty),
+        }
+    }
+
+    fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        match property {
+            PropertyIdentifier::ObjectName => {
+                if let PropertyValue::CharacterString(name) = value {
+                    self.object_name = name;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::PresentValue => {
+                if let PropertyValue::UnsignedInteger(action) = value {
+                    self.execute(action)
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::ActionText if self.action_text.is_some() => {
+                if let PropertyValue::Array(items) = value {
+                    let texts = items
+                        .into_iter()
+                        .map(|item| match item {
+                            PropertyValue::CharacterString(text) => Ok(text),
+                            _ => Err(ObjectError::InvalidPropertyType),
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    self.action_text = Some(texts);
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            _ => Err(ObjectError::PropertyNotWritable),
+        }
+    }
+
+    fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
+        match property {
+            PropertyIdentifier::ObjectName | PropertyIdentifier::Description => true,
+            PropertyIdentifier::PresentValue => !self.in_process,
+            PropertyIdentifier::ActionText => self.action_text.is_some(),
+            _ => false,
+        }
+    }
+
+    fn property_list(&self) -> Vec<PropertyIdentifier> {
+        let mut properties = vec![
+            PropertyIdentifier::ObjectIdentifier,
+            PropertyIdentifier::ObjectName,
+            PropertyIdentifier::ObjectType,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::PresentValue,
+            PropertyIdentifier::InProcess,
+            PropertyIdentifier::AllWritesSuccessful,
+            PropertyIdentifier::Action,
+        ];
+        if self.action_text.is_some() {
+            properties.push(PropertyIdentifier::ActionText);
+        }
+        properties.extend([
+            PropertyIdentifier::StatusFlags,
+            PropertyIdentifier::EventState,
+            PropertyIdentifier::Reliability,
+        ]);
+        properties
+    }
+
+    fn advance_time(&mut self, elapsed: Duration) {
+        let Some(remaining) = self.post_delay_remaining else {
+            return;
+        };
+        if elapsed >= remaining {
+            self.post_delay_remaining = None;
+            self.queue_next_batch();
+        } else {
+            self.post_delay_remaining = Some(remaining - elapsed);
+        }
+    }
+
+    fn take_pending_writes(&mut self) -> Vec<PropertyWrite> {
+        core::mem::take(&mut self.pending)
+    }
+
+    fn report_write_results(&mut self, results: &[bool]) {
+        let in_flight = core::mem::take(&mut self.in_flight);
+        let Some(&last) = in_flight.last() else {
+            return;
+        };
+        let mut quit = false;
+        if let Some(list) = self.current_list_mut() {
+            for (&index, &succeeded) in in_flight.iter().zip(results) {
+                let command = &mut list[index];
+                command.write_successful = succeeded;
+                if !succeeded {
+                    quit |= command.quit_on_failure;
+                }
+            }
+        }
+        // Writes without a reported outcome count as failed
+        if results.len() < in_flight.len() || results.iter().any(|&succeeded| !succeeded) {
+            self.any_failed = true;
+        }
+        if quit {
+            self.finish();
+            return;
+        }
+        let post_delay = self
+            .current_list()
+            .and_then(|list| list[last].post_delay)
+            .filter(|&delay| delay > 0);
+        match post_delay {
+            Some(delay) => {
+                self.post_delay_remaining = Some(Duration::from_secs(delay as u64));
+            }
+            None => self.queue_next_batch(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn analog_value(instance: u32) -> ObjectIdentifier {
+        ObjectIdentifier::new(ObjectType::AnalogValue, instance)
+    }
+
+    fn write(instance: u32, value: f32) -> ActionCommand {
+        ActionCommand::new(
+            analog_value(instance),
+            PropertyIdentifier::PresentValue,
+            PropertyValue::Real(value),
+        )
+    }
+
+    #[test]
+    fn test_action_list_sequencing() {
+        let mut command = Command::new(1, "Occupancy Mode".to_string());
+        let mut delayed = write(2, 18.0);
+        delayed.post_delay = Some(30);
+        let occupied = command.add_action(vec![write(1, 21.0), delayed, write(3, 1.0)]);
+
+        command
+            .set_property(
+                PropertyIdentifier::PresentValue,
+                PropertyValue::UnsignedInteger(occupied),
+            )
+            .unwrap();
+        assert!(command.in_process);
+        assert_eq!(command.take_pending_writes().len(), 2);
+        assert!(command
+            .set_property(
+                PropertyIdentifier::PresentValue,
+                PropertyValue::UnsignedInteger(occupied)
+            )
+            .is_err());
+
+        command.report_write_results(&[true, true]);
+        assert!(command.take_pending_writes().is_empty());
+        command.advance_time(Duration::from_secs(29));
+        assert!(command.take_pending_writes().is_empty());
+        command.advance_time(Duration::from_secs(1));
+        let writes = command.take_pending_writes();
+        assert_eq!(writes[0].reference.object_identifier, analog_value(3));
+
+        command.report_write_results(&[false]);
+        assert!(!command.in_process);
+        assert!(!command.all_writes_successful);
+        assert!(command.action[0][0].write_successful);
+        assert!(!command.action[0][2].write_successful);
+    }
+
+    #[test]
+    fn test_quit_on_failure() {
+        let mut command = Command::new(2, "Shutdown".to_string());
+        let mut critical = write(1, 0.0);
+        critical.quit_on_failure = true;
+        command.add_action(vec![critical, write(2, 0.0)]);
+
+        command.execute(1).unwrap();
+        assert_eq!(command.take_pending_writes().len(), 1);
+        command.report_write_results(&[false]);
+        assert!(!command.in_process);
+        assert!(command.take_pending_writes().is_empty());
+        assert!(command.execute(2).is_err());
+
+        if let Ok(PropertyValue::Array(lists)) = command.get_property(PropertyIdentifier::Action) {
+            assert!(matches!(&lists[0], PropertyValue::List(actions) if actions.len() == 2));
+        } else {
+            panic!("Expected Array");
+        }
+    }
+}
@@ -0,0 +1,144 @@
+//! Command Prioritization (Clause 19.2)
+//!
+//! Commandable objects arbitrate Present_Value writes through a 16-slot
+//! Priority_Array. Slot 1 is the highest priority; the highest-priority
+//! non-NULL slot determines the present value, and when every slot is NULL the
+//! Relinquish_Default applies. [`Commandable`] holds the array and default so
+//! each object type only has to validate values and mirror the result into its
+//! own Present_Value.
+
+use crate::object::{ObjectError, PropertyValue, Result};
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::ToString, vec::Vec};
+
+/// Priority array and relinquish default of a commandable object
+#[derive(Debug, Clone, PartialEq)]
+pub struct Commandable<T> {
+    priority_array: [Option<T>; 16],
+    relinquish_default: T,
+}
+
+impl<T: Clone> Commandable<T> {
+    /// Create an empty priority array with the given relinquish default
+    pub fn new(relinquish_default: T) -> Self {
+        Self {
+            priority_array: core::array::from_fn(|_| None),
+            relinquish_default,
+        }
+    }
+
+    /// Command `value` at `priority` (1-16), or relinquish it with `None`
+    pub fn write(&mut self, priority: u8, value: Option<T>) -> Result<()> {
+        let slot = self
+            .priority_array
+            .get_mut(usize::from(priority).wrapping_sub(1))
+            .ok_or_else(|| ObjectError::InvalidValue("Priority must be 1-16".to_string()))?;
+        *slot = value;
+        Ok(())
+    }
+
+    /// Relinquish the command at `priority` (1-16)
+    pub fn relinquish(&mut self, priority: u8) -> Result<()> {
+        self.write(priority, None)
+    }
+
+    /// Relinquish every priority
+    pub fn relinquish_all(&mut self) {
+        self.priority_array = core::array::from_fn(|_| None);
+    }
+
+    /// Value commanded at `priority`, if any
+    pub fn get(&self, priority: u8) -> Option<&T> {
+        self.priority_array
+            .get(usize::from(priority).wrapping_sub(1))?
+            .as_ref()
+    }
+
+    /// All 16 slots, highest priority first
+    pub fn priority_array(&self) -> &[Option<T>; 16] {
+        &self.priority_array
+    }
+
+    /// Value used when every priority is relinquished
+    pub fn relinquish_default(&self) -> &T {
+        &self.relinquish_default
+    }
+
+    /// Change the relinquish default
+    pub fn set_relinquish_default(&mut self, value: T) {
+        self.relinquish_default = value;
+    }
+
+    /// Highest priority holding a command, or `None` if all are relinquished
+    pub fn active_priority(&self) -> Option<u8> {
+        self.priority_array
+            .iter()
+            .position(Option::is_some)
+            .map(|index| index as u8 + 1)
+    }
+
+    /// Value in control: the highest-priority command, or the relinquish
+    /// default if there is none
+    pub fn current(&self) -> &T {
+        self.priority_array
+            .iter()
+            .flatten()
+            .next()
+            .unwrap_or(&self.relinquish_default)
+    }
+
+    /// Encode the Priority_Array property, with NULL for relinquished slots
+    pub fn priority_array_value(&self, encode: impl Fn(&T) -> PropertyValue) -> PropertyValue {
+        PropertyValue::Array(
+            self.priority_array
+                .iter()
+                .map(|slot| slot.as_ref().map_or(PropertyValue::Null, &encode))
+                .collect::<Vec<_>>(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_resolution() {
+        let mut command = Commandable::new(20.0f32);
+        assert_eq!(*command.current(), 20.0);
+        assert_eq!(command.active_priority(), None);
+
+        command.write(10, Some(22.0)).unwrap();
+        command.write(8, Some(18.0)).unwrap();
+        assert_eq!(*command.current(), 18.0);
+        assert_eq!(command.active_priority(), Some(8));
+        assert_eq!(command.get(10), Some(&22.0));
+
+        command.relinquish(8).unwrap();
+        assert_eq!(*command.current(), 22.0);
+        command.relinquish_all();
+        assert_eq!(*command.current(), 20.0);
+
+        assert!(command.write(0, Some(1.0)).is_err());
+        assert!(command.write(17, Some(1.0)).is_err());
+        assert_eq!(command.get(0), None);
+    }
+
+    #[test]
+    fn test_priority_array_value() {
+        let mut command = Commandable::new(String::from("idle"));
+        command.write(16, Some(String::from("run"))).unwrap();
+        let PropertyValue::Array(slots) =
+            command.priority_array_value(|value| PropertyValue::CharacterString(value.clone()))
+        else {
+            panic!("Expected Array");
+        };
+        assert_eq!(slots.len(), 16);
+        assert_eq!(slots[0], PropertyValue::Null);
+        assert_eq!(
+            slots[15],
+            PropertyValue::CharacterString(String::from("run"))
+        );
+    }
+}
@@ -0,0 +1,641 @@
+//! Loop Object Type Implementation
+//!
+//! This module implements the Loop object type as defined in ASHRAE 135. The Loop
+//! describes a feedback control loop through references to its manipulated variable,
+//! controlled variable and setpoint, together with its P/I/D tuning constants. An
+//! optional built-in PID step computes Present_Value every Update_Interval and queues
+//! the write to the manipulated variable at Priority_For_Writing.
+//!
+//! The built-in controller uses the parallel form
+//! `output = bias + Kp·e + Ki·∫e dt + Kd·de/dt`, where the integral time base follows
+//! Integral_Constant_Units (per second, minute or hour) and the derivative time base
+//! follows Derivative_Constant_Units (seconds, minutes or hours).
+
+use crate::object::{
+    current_status_flags, engineering_units::EngineeringUnits, status_flags_bit_string,
+    BacnetObject, DeviceObjectPropertyReference, EventState, ObjectError, ObjectIdentifier,
+    ObjectType, PropertyIdentifier, PropertyValue, PropertyWrite, Reliability, Result,
+};
+use core::time::Duration;
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+/// Loop action enumeration (BACnetAction)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum LoopAction {
+    /// Output increases as the controlled variable rises above the setpoint
+    Direct = 0,
+    /// Output increases as the controlled variable falls below the setpoint
+    Reverse = 1,
+}
+
+impl TryFrom<u32> for LoopAction {
+    type Error = ObjectError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            0 => Ok(LoopAction::Direct),
+            1 => Ok(LoopAction::Reverse),
+            _ => Err(ObjectError::InvalidValue(format!(
+                "Invalid loop action: {}",
+                value
+            ))),
+        }
+    }
+}
+
+/// Loop object
+#[derive(Debug, Clone)]
+pub struct Loop {
+    /// Object identifier
+    pub identifier: ObjectIdentifier,
+    /// Object name
+    pub object_name: String,
+    /// Description
+    pub description: String,
+    /// Present value (the loop output)
+    pub present_value: f32,
+    /// Event state
+    pub event_state: EventState,
+    /// Reliability
+    pub reliability: Reliability,
+    /// Out of service
+    pub out_of_service: bool,
+    /// Milliseconds between executions of the built-in controller
+    pub update_interval: Option<u32>,
+    /// Units of the output
+    pub output_units: EngineeringUnits,
+    /// Property the output is written to
+    pub manipulated_variable_reference: DeviceObjectPropertyReference,
+    /// Property the loop controls
+    pub controlled_variable_reference: DeviceObjectPropertyReference,
+    /// Last known value of the controlled variable
+    pub controlled_variable_value: f32,
+    /// Units of the controlled variable
+    pub controlled_variable_units: EngineeringUnits,
+    /// Property the setpoint is read from, if not local
+    pub setpoint_reference: Option<DeviceObjectPropertyReference>,
+    /// Setpoint
+    pub setpoint: f32,
+    /// Direct or reverse action
+    pub action: LoopAction,
+    /// Proportional gain
+    pub proportional_constant: f32,
+    /// Units of the proportional gain
+    pub proportional_constant_units: EngineeringUnits,
+    /// Integral gain, if integral action is used
+    pub integral_constant: Option<f32>,
+    /// Units of the integral gain
+    pub integral_constant_units: EngineeringUnits,
+    /// Derivative gain, if derivative action is used
+    pub derivative_constant: Option<f32>,
+    /// Units of the derivative gain
+    pub derivative_constant_units: EngineeringUnits,
+    /// Output offset
+    pub bias: Option<f32>,
+    /// Output upper limit
+    pub maximum_output: f32,
+    /// Output lower limit
+    pub minimum_output: f32,
+    /// Priority used for writes to the manipulated variable
+    pub priority_for_writing: u8,
+    /// COV increment
+    pub cov_increment: Option<f32>,
+    /// Whether the built-in PID controller computes Present_Value
+    pub pid_enabled: bool,
+    integral: f32,
+    previous_error: Option<f32>,
+    time_since_update: Duration,
+    pending_write: Option<PropertyWrite>,
+}
+
+impl Loop {
+    /// Create a new Loop with proportional-only control and a 0-100 % output
+    pub fn new(
+        instance: u32,
+        object_name: String,
+        manipulated_variable_reference: DeviceObjectPropertyReference,
+        controlled_variable_reference: DeviceObjectPropertyReference,
+    ) -> Self {
+        Self {
+            identifier: ObjectIdentifier::new(ObjectType::Loop, instance),
+            object_name,
+            description: String::new(),
+            present_value: 0.0,
+            event_state: EventState::Normal,
+            reliability: Reliability::NoFaultDetected,
+            out_of_service: false,
+            update_interval: Some(1000),
+            output_units: EngineeringUnits::Percent,
+            manipulated_variable_reference,
+            controlled_variable_reference,
+            controlled_variable_value: 0.0,
+            controlled_variable_units: EngineeringUnits::NoUnits,
+            setpoint_reference: None,
+            setpoint: 0.0,
+            action: LoopAction::Direct,
+            proportional_constant: 1.0,
+            proportional_constant_units: EngineeringUnits::NoUnits,
+            integral_constant: None,
+            integral_constant_units: EngineeringUnits::PerMinute,
+            derivative_constant: None,
+            derivative_constant_units: EngineeringUnits::Seconds,
+            bias: None,
+            maximum_output: 100.0,
+            minimum_output: 0.0,
+            priority_for_writing: 16,
+            cov_increment: None,
+            pid_enabled: true,
+            integral: 0.0,
+            previous_error: None,
+            time_since_update: Duration::ZERO,
+            pending_write: None,
+        }
+    }
+
+    /// Record a new reading of the controlled variable
+    pub fn update_controlled_variable(&mut self, value: f32) {
+        self.controlled_variable_value = value;
+    }
+
+    /// Clear the integral and derivative history
+    pub fn reset(&mut self) {
+        self.integral = 0.0;
+        self.previous_error = None;
+    }
+
+    /// Run one step of the built-in PID controller over `elapsed`
+    ///
+    /// Updates Present_Value and returns the write for the manipulated variable.
+    /// Nothing is computed while the loop is out of service or the controller is
+    /// disabled.
+    pub fn execute(&mut self, elapsed: Duration) -> Option<PropertyWrite> {
+        if self.out_of_service || !self.pid_enabled {
+            return None;
+        }
+        let error = match self.action {
+            LoopAction::Direct => self.controlled_variable_value - self.setpoint,
+            LoopAction::Reverse => self.setpoint - self.controlled_variable_value,
+        };
+
+        let unclamped_without_integral = self.bias.unwrap_or(0.0)
+            + self.proportional_constant * error
+            + self.derivative_term(error, elapsed);
+        if let Some(ki) = self.integral_constant {
+            let step = ki * error * time_base(elapsed, self.integral_constant_units);
+            let candidate = unclamped_without_integral + self.integral + step;
+            // Anti-windup: stop integrating further into a saturated output
+            let winding_up = (candidate > self.maximum_output && step > 0.0)
+                || (candidate < self.minimum_output && step < 0.0);
+            if !winding_up {
+                self.integral += step;
+            }
+        }
+        self.previous_error = Some(error);
+
+        self.present_value = (unclamped_without_integral + self.integral)
+            .clamp(self.minimum_output, self.maximum_output);
+        Some(self.output_write())
+    }
+
+    /// The write that applies the current Present_Value to the manipulated variable
+    pub fn output_write(&self) -> PropertyWrite {
+        PropertyWrite {
+            reference: self.manipulated_variable_reference,
+            value: PropertyValue::Real(self.present_value),
+            priority: self.priority_for_writing,
+        }
+    }
+
+    fn derivative_term(&self, error: f32, elapsed: Duration) -> f32 {
+        let (Some(kd), Some(previous)) = (self.derivative_constant, self.previous_error) else {
+            return 0.0;
+        };
+        let dt = time_base(elapsed, self.derivative_constant_units);
+        if dt > 0.0 {
+            kd * (error - previous) / dt
+        } else {
+            0.0
+        }
+    }
+
+    fn current_status_flags(&self) -> u8 {
+        current_status_flags(0, self.event_state, self.reliability, self.out_of_service)
+    }
+}
+
+/// `elapsed` expressed in the time base implied by a constant's units
+fn time_base(elapsed: Duration, units: EngineeringUnits) -> f32 {
+    let seconds = elapsed.as_secs_f32();
+    match units {
+        EngineeringUnits::PerMinute | EngineeringUnits::Minutes => seconds / 60.0,
+        EngineeringUnits::PerHour | EngineeringUnits::Hours => seconds / 3600.0,
+        _ => seconds,
+    }
+}
+
+impl BacnetObject for Loop {
+    fn identifier(&self) -> ObjectIdentifier {
+        self.identifier
+    }
+
+    fn get_property(&self, property: PropertyIdentifier) -> Result<PropertyValue> {
+        match property {
+            PropertyIdentifier::ObjectIdentifier => {
+                Ok(PropertyValue::ObjectIdentifier(self.identifier))
+            }
+            PropertyIdentifier::ObjectName => {
+                Ok(PropertyValue::CharacterString(self.object_name.clone()))
+            }
+            PropertyIdentifier::ObjectType => {
+                Ok(PropertyValue::Enumerated(u32::from(ObjectType::Loop)))
+            }
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::PresentValue => Ok(PropertyValue::Real(self.present_value)),
+            PropertyIdentifier::StatusFlags => {
+                Ok(status_flags_bit_string(self.current_status_flags()))
+            }
+            PropertyIdentifier::EventState => {
+                Ok(PropertyValue::Enumerated(self.event_state as u32))
+            }
+            PropertyIdentifier::Reliability => {
+                Ok(PropertyValue::Enumerated(self.reliability as u32))
+            }
+            PropertyIdentifier::OutOfService => Ok(PropertyValue::Boolean(self.out_of_service)),
+            PropertyIdentifier::UpdateInterval => self
+                .update_interval
+                .map(PropertyValue::UnsignedInteger)
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::OutputUnits => {
+                Ok(PropertyValue::Enumerated(self.output_units.to_u32()))
+            }
+            PropertyIdentifier::ManipulatedVariableReference => {
+                Ok(self.manipulated_variable_reference.to_property_value())
+            }
+            PropertyIdentifier::ControlledVariableReference => {
+                Ok(self.controlled_variable_reference.to_property_value())
+            }
+            PropertyIdentifier::ControlledVariableValue => {
+                Ok(PropertyValue::Real(self.controlled_variable_value))
+            }
+            PropertyIdentifier::ControlledVariableUnits => Ok(PropertyValue::Enumerated(
+                self.controlled_variable_units.to_u32(),
+            )),
+            // An empty reference list signals a local setpoint
+            PropertyIdentifier::SetpointReference => Ok(self
+                .setpoint_reference
+                .map(|reference| reference.to_property_value())
+                .unwrap_or(PropertyValue::List(Vec::new()))),
+            PropertyIdentifier::Setpoint => Ok(PropertyValue::Real(self.setpoint)),
+            PropertyIdentifier::Action => Ok(PropertyValue::Enumerated(self.action as u32)),
+            PropertyIdentifier::ProportionalConstant => {
+                Ok(PropertyValue::Real(self.proportional_constant))
+            }
+            PropertyIdentifier::ProportionalConstantUnits => Ok(PropertyValue::Enumerated(
+                self.proportional_constant_units.to_u32(),
+            )),
+            PropertyIdentifier::IntegralConstant => self
+                .integral_constant
+                .map(PropertyValue::Real)
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::IntegralConstantUnits if self.integral_constant.is_some() => Ok(
+                PropertyValue::Enumerated(self.integral_constant_units.to_u32()),
+            ),
+            PropertyIdentifier::DerivativeConstant => self
+                .derivative_constant
+                .map(PropertyValue::Real)
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::DerivativeConstantUnits if self.derivative_constant.is_some() => {
+                Ok(PropertyValue::Enumerated(
+                    self.derivative_constant_units.to_u32(),
+                ))
+            }
+            PropertyIdentifier::Bias => self
+                .bias
+                .map(PropertyValue::Real)
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::MaximumOutput => Ok(PropertyValue::Real(self.maximum_output)),
+            PropertyIdentifier::MinimumOutput => Ok(PropertyValue::Real(self.minimum_output)),
+            PropertyIdentifier::PriorityForWriting => Ok(PropertyValue::UnsignedInteger(
+                self.priority_for_writing as u32,
+            )),
+            PropertyIdentifier::CovIncrement => self
+                .cov_increment
+                .map(PropertyValue::Real)
+                .ok_or(ObjectError::UnknownProperty),
+            _ => Err(ObjectError::UnknownProperty),
+        }
+    }
+
+    fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        let real = |value: PropertyValue| match value {
+            PropertyValue::Real(v) => Ok(v),
+            _ => Err(ObjectError::InvalidPropertyType),
+        };
+        match property {
+            PropertyIdentifier::ObjectName => {
+                if let PropertyValue::CharacterString(name) = value {
+                    self.object_name = name;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::PresentValue => {
+                if !self.out_of_service {
+                    return Err(ObjectError::WriteAccessDenied);
+                }
+                self.present_value = real(value)?;
+                Ok(())
+            }
+            PropertyIdentifier::OutOfService => {
+                if let PropertyValue::Boolean(oos) = value {
+                    self.out_of_service = oos;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Setpoint => {
+                self.setpoint = real(value)?;
+                Ok(())
+            }
+            PropertyIdentifier::Action => {
+                if let PropertyValue::Enumerated(action) = value {
+                    self.action = LoopAction::try_from(action)?;
+                    self.reset();
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::ProportionalConstant => {
+                self.proportional_constant = real(value)?;
+                Ok(())
+            }
+            PropertyIdentifier::IntegralConstant if self.integral_constant.is_some() => {
+                self.integral_constant = Some(real(value)?);
+                Ok(())
+            }
+            PropertyIdentifier::DerivativeConstant if self.derivative_constant.is_some() => {
+                self.derivative_constant = Some(real(value)?);
+                Ok(())
+            }
+            PropertyIdentifier::Bias if self.bias.is_some() => {
+                self.bias = Some(real(value)?);
+                Ok(())
+            }
+            PropertyIdentifier::MaximumOutput => {
+                let maximum = real(value)?;
+                if maximum < self.minimum_output {
+                    return Err(ObjectError::InvalidValue(
+                        "Maximum_Output must not be below Minimum_Output".to_string(),
+                    ));
+                }
+                self.maximum_output = maximum;
+                Ok(())
+            }
+            PropertyIdentifier::MinimumOutput => {
+                let minimum = real(value)?;
+                if minimum > self.maximum_output {
+                    return Err(ObjectError::InvalidValue(
+                        "Minimum_Output must not exceed Maximum_Output".to_string(),
+                    ));
+                }
+                self.minimum_output = minimum;
+                Ok(())
+            }
+            PropertyIdentifier::PriorityForWriting => match value {
+                PropertyValue::UnsignedInteger(priority @ 1..=16) => {
+                    self.priority_for_writing = priority as u8;
+                    Ok(())
+                }
+                PropertyValue::UnsignedInteger(_) => Err(ObjectError::InvalidValue(
+                    "Priority must be 1-16".to_string(),
+                )),
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            PropertyIdentifier::CovIncrement if self.cov_increment.is_some() => {
+                self.cov_increment = Some(real(value)?);
+                Ok(())
+            }
+            _ => Err(ObjectError::PropertyNotWritable),
+        }
+    }
+
+    fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
+        match property {
+            PropertyIdentifier::ObjectName
+            | PropertyIdentifier::Description
+            | PropertyIdentifier::OutOfService
+            | PropertyIdentifier::Setpoint
+            | PropertyIdentifier::Action
+            | PropertyIdentifier::ProportionalConstant
+            | PropertyIdentifier::MaximumOutput
+            | PropertyIdentifier::MinimumOutput
+            | PropertyIdentifier::PriorityForWriting => true,
+            PropertyIdentifier::PresentValue => self.out_of_service,
+            PropertyIdentifier::IntegralConstant => self.integral_constant.is_some(),
+            PropertyIdentifier::DerivativeConstant => self.derivative_constant.is_some(),
+            PropertyIdentifier::Bias => self.bias.is_some(),
+            PropertyIdentifier::CovIncrement => self.cov_increment.is_some(),
+            _ => false,
+        }
+    }
+
+    fn property_list(&self) -> Vec<PropertyIdentifier> {
+        let mut properties = vec![
+            PropertyIdentifier::ObjectIdentifier,
+            PropertyIdentifier::ObjectName,
+            PropertyIdentifier::ObjectType,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::PresentValue,
+            PropertyIdentifier::StatusFlags,
+            PropertyIdentifier::EventState,
+            PropertyIdentifier::Reliability,
+            PropertyIdentifier::OutOfService,
+        ];
+        if self.update_interval.is_some() {
+            properties.push(PropertyIdentifier::UpdateInterval);
+        }
+        properties.extend([
+            PropertyIdentifier::OutputUnits,
+            PropertyIdentifier::ManipulatedVariableReference,
+            PropertyIdentifier::ControlledVariableReference,
+            PropertyIdentifier::ControlledVariableValue,
+            PropertyIdentifier::ControlledVariableUnits,
+            PropertyIdentifier::SetpointReference,
+            PropertyIdentifier::Setpoint,
+            PropertyIdentifier::Action,
+            PropertyIdentifier::ProportionalConstant,
+            PropertyIdentifier::ProportionalConstantUnits,
+        ]);
+        if self.integral_constant.is_some() {
+            properties.push(PropertyIdentifier::IntegralConstant);
+            properties.push(PropertyIdentifier::IntegralConstantUnits);
+        }
+        if self.derivative_constant.is_some() {
+            properties.push(PropertyIdentifier::DerivativeConstant);
+            properties.push(PropertyIdentifier::DerivativeConstantUnits);
+        }
+        if self.bias.is_some() {
+            properties.push(PropertyIdentifier::Bias);
+        }
+        properties.extend([
+            PropertyIdentifier::MaximumOutput,
+            PropertyIdentifier::MinimumOutput,
+            PropertyIdentifier::PriorityForWriting,
+        ]);
+        if self.cov_increment.is_some() {
+            properties.push(PropertyIdentifier::CovIncrement);
+        }
+        properties
+    }
+
+    fn advance_time(&mut self, elapsed: Duration) {
+        let Some(interval) = self.update_interval else {
+            return;
+        };
+        self.time_since_update = self.time_since_update.saturating_add(elapsed);
+        let interval = Duration::from_millis(interval as u64);
+        if self.time_since_update >= interval {
+            let step = core::mem::take(&mut self.time_since_update);
+            if let Some(write) = self.execute(step) {
+                self.pending_write = Some(write);
+            }
+        }
+    }
+
+    fn take_pending_writes(&mut self) -> Vec<PropertyWrite> {
+        self.pending_write.take().into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn heating_loop() -> Loop {
+        let mut control = Loop::new(
+            1,
+            "Zone Heating".to_string(),
+            DeviceObjectPropertyReference::new(
+                ObjectIdentifier::new(ObjectType::AnalogOutput, 1),
+                PropertyIdentifier::PresentValue,
+            ),
+            DeviceObjectPropertyReference::new(
+                ObjectIdentifier::new(ObjectType::AnalogInput, 1),
+                PropertyIdentifier::PresentValue,
+            ),
+        );
+        control.action = LoopAction::Reverse;
+        control.setpoint = 21.0;
+        control.proportional_constant = 10.0;
+        control
+    }
+
+    #[test]
+    fn test_proportional_reverse_action_and_clamping() {
+        let mut control = heating_loop();
+        control.update_controlled_variable(19.0);
+        let write = control.execute(Duration::from_secs(1)).unwrap();
+        assert_eq!(control.present_value, 20.0);
+        assert_eq!(write.value, PropertyValue::Real(20.0));
+        assert_eq!(write.priority, 16);
+
+        control.update_controlled_variable(5.0);
+        control.execute(Duration::from_secs(1));
+        assert_eq!(control.present_value, 100.0);
+
+        control.action = LoopAction::Direct;
+        control.execute(Duration::from_secs(1));
+        assert_eq!(control.present_value, 0.0);
+    }
+
+    #[test]
+    fn test_integral_action_with_anti_windup() {
+        let mut control = heating_loop();
+        control.proportional_constant = 0.0;
+        control.integral_constant = Some(60.0);
+        control.integral_constant_units = EngineeringUnits::PerMinute;
+        control.update_controlled_variable(20.0);
+
+        // 60 repeats per minute on an error of 1 for one second
+        control.execute(Duration::from_secs(1));
+        assert!((control.present_value - 1.0).abs() < 1e-4);
+
+        for _ in 0..200 {
+            control.execute(Duration::from_secs(1));
+        }
+        assert_eq!(control.present_value, 100.0);
+        // Once the error reverses the output leaves saturation immediately
+        control.update_controlled_variable(22.0);
+        control.execute(Duration::from_secs(1));
+        assert!(control.present_value < 100.0);
+    }
+
+    #[test]
+    fn test_update_interval_queues_writes() {
+        let mut control = heating_loop();
+        control.update_interval = Some(500);
+        control.update_controlled_variable(20.0);
+        control.advance_time(Duration::from_millis(200));
+        assert!(control.take_pending_writes().is_empty());
+        control.advance_time(Duration::from_millis(300));
+        assert_eq!(control.take_pending_writes().len(), 1);
+
+        control.out_of_service = true;
+        control.advance_time(Duration::from_secs(1));
+        assert!(control.take_pending_writes().is_empty());
+        control
+            .set_property(PropertyIdentifier::PresentValue, PropertyValue::Real(42.0))
+            .unwrap();
+        assert_eq!(control.present_value, 42.0);
+    }
+
+    #[test]
+    fn test_loop_tuning_properties() {
+        let mut control = heating_loop();
+        control
+            .set_property(
+                PropertyIdentifier::ProportionalConstant,
+                PropertyValue::Real(2.5),
+            )
+            .unwrap();
+        assert_eq!(control.proportional_constant, 2.5);
+        assert!(control
+            .set_property(
+                PropertyIdentifier::IntegralConstant,
+                PropertyValue::Real(1.0)
+            )
+            .is_err());
+        assert!(control
+            .set_property(
+                PropertyIdentifier::MinimumOutput,
+                PropertyValue::Real(150.0)
+            )
+            .is_err());
+        assert!(control
+            .set_property(PropertyIdentifier::PresentValue, PropertyValue::Real(1.0))
+            .is_err());
+        assert!(matches!(
+            control.get_property(PropertyIdentifier::Action),
+            Ok(PropertyValue::Enumerated(1))
+        ));
+        assert!(!control
+            .property_list()
+            .contains(&PropertyIdentifier::DerivativeConstant));
+    }
+}
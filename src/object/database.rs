@@ -7,16 +7,28 @@
 use std::{
     collections::HashMap,
     sync::{Arc, RwLock},
-    time::Instant,
+    time::{Duration, Instant},
 };
 
 #[cfg(not(feature = "std"))]
 use alloc::{boxed::Box, collections::BTreeMap as HashMap, string::String, sync::Arc, vec::Vec};
 
 use super::{
-    BacnetObject, Device, ObjectError, ObjectIdentifier, ObjectType, PropertyIdentifier,
-    PropertyValue, Result,
+    array_element, group::Group, AuditLog, AuditNotification, BacnetObject, Device,
+    DeviceObjectPropertyReference, EventTransition, File, LifeSafetyOperation, NotificationClass,
+    ObjectError, ObjectFactoryRegistry, ObjectIdentifier, ObjectType, PropertyIdentifier,
+    PropertyValue, PropertyWrite, Result,
 };
+use crate::service::{
+    BacnetDateTime, ChangeListError, CovSubscriptionManager, PendingCovNotification,
+    PendingEventNotification, PropertyAccessError, PropertyReference, ReadAccessResult,
+    ReadAccessSpecification, ReadResult, SubscribeCovPropertyRequest, SubscribeCovRequest,
+    WriteGroupRequest,
+};
+
+/// Decides whether DeleteObject may remove an object
+#[cfg(feature = "std")]
+pub type DeletionPolicy = Box<dyn Fn(&dyn BacnetObject) -> bool + Send + Sync>;
 
 /// Object database for managing BACnet objects
 #[cfg(feature = "std")]
@@ -33,6 +45,14 @@ pub struct ObjectDatabase {
     last_modified: Arc<RwLock<Instant>>,
     /// Device object reference (must always exist)
     device_id: ObjectIdentifier,
+    /// COV subscriptions to the objects of the device
+    cov_subscriptions: Arc<RwLock<CovSubscriptionManager>>,
+    /// Event notifications waiting to be sent
+    event_notifications: Arc<RwLock<Vec<PendingEventNotification>>>,
+    /// Factories for the object types CreateObject can make
+    object_factories: Arc<RwLock<ObjectFactoryRegistry>>,
+    /// Host policy for DeleteObject, in addition to the objects' own
+    deletion_policy: Arc<RwLock<Option<DeletionPolicy>>>,
 }
 
 #[cfg(feature = "std")]
@@ -61,6 +81,10 @@ impl ObjectDatabase {
             revision: Arc::new(RwLock::new(1)),
             last_modified: Arc::new(RwLock::new(Instant::now())),
             device_id,
+            cov_subscriptions: Arc::new(RwLock::new(CovSubscriptionManager::new())),
+            event_notifications: Arc::new(RwLock::new(Vec::new())),
+            object_factories: Arc::new(RwLock::new(ObjectFactoryRegistry::new())),
+            deletion_policy: Arc::new(RwLock::new(None)),
         }
     }
 
@@ -160,9 +184,194 @@ impl ObjectDatabase {
         property: PropertyIdentifier,
     ) -> Result<PropertyValue> {
         let objects = self.objects.read().unwrap();
-        match objects.get(&identifier) {
-            Some(obj) => obj.get_property(property),
-            None => Err(ObjectError::NotFound),
+        self.read_property(&objects, identifier, property, None, true)
+    }
+
+    /// Get one element of an array property from an object; index zero is the
+    /// array length
+    pub fn get_property_at(
+        &self,
+        identifier: ObjectIdentifier,
+        property: PropertyIdentifier,
+        index: u32,
+    ) -> Result<PropertyValue> {
+        let objects = self.objects.read().unwrap();
+        self.read_property(&objects, identifier, property, Some(index), true)
+    }
+
+    /// Read the properties named by a ReadAccessSpecification
+    ///
+    /// Properties that cannot be read carry their error in the result, as in a
+    /// ReadPropertyMultiple acknowledgement. The All property expands to every
+    /// property in the object's property list, Required to its required
+    /// properties and Optional to the rest. Property_List itself is never part
+    /// of an expansion (Clause 15.7.3.1.2).
+    pub fn read_access(&self, specification: &ReadAccessSpecification) -> ReadAccessResult {
+        let objects = self.objects.read().unwrap();
+        self.read_access_with(&objects, specification, true)
+    }
+
+    fn read_property(
+        &self,
+        objects: &HashMap<ObjectIdentifier, Box<dyn BacnetObject>>,
+        identifier: ObjectIdentifier,
+        property: PropertyIdentifier,
+        array_index: Option<u32>,
+        resolve_groups: bool,
+    ) -> Result<PropertyValue> {
+        if identifier == self.device_id {
+            // The database is authoritative for the device's object list, revision
+            // and COV subscriptions
+            match property {
+                PropertyIdentifier::ObjectList => {
+                    let mut list: Vec<ObjectIdentifier> = objects.keys().copied().collect();
+                    list.sort_by_key(|id| {
+                        (
+                            id != &self.device_id,
+                            u16::from(id.object_type),
+                            id.instance,
+                        )
+                    });
+                    let list = PropertyValue::Array(
+                        list.into_iter()
+                            .map(PropertyValue::ObjectIdentifier)
+                            .collect(),
+                    );
+                    return match array_index {
+                        Some(index) => array_element(list, index),
+                        None => Ok(list),
+                    };
+                }
+                PropertyIdentifier::DatabaseRevision => {
+                    return Ok(PropertyValue::UnsignedInteger(self.revision()));
+                }
+                PropertyIdentifier::ActiveCovSubscriptions => {
+                    return Ok(self
+                        .cov_subscriptions
+                        .read()
+                        .unwrap()
+                        .active_cov_subscriptions());
+                }
+                _ => {}
+            }
+        }
+        let obj = objects.get(&identifier).ok_or(ObjectError::NotFound)?;
+        if property == PropertyIdentifier::PropertyList {
+            // Property_List omits the naming properties and itself (Clause 12.1.1.4.1)
+            let list = PropertyValue::Array(
+                obj.property_list()
+                    .into_iter()
+                    .filter(|property| {
+                        !matches!(
+                            property,
+                            PropertyIdentifier::ObjectIdentifier
+                                | PropertyIdentifier::ObjectName
+                                | PropertyIdentifier::ObjectType
+                                | PropertyIdentifier::PropertyList
+                        )
+                    })
+                    .map(|property| PropertyValue::Enumerated(u32::from(property)))
+                    .collect(),
+            );
+            return match array_index {
+                Some(index) => array_element(list, index),
+                None => Ok(list),
+            };
+        }
+        if identifier.object_type == ObjectType::Group
+            && property == PropertyIdentifier::PresentValue
+            && resolve_groups
+        {
+            // Group members are read from the other objects; nested Groups are
+            // not resolved again so a Group cannot recurse into itself
+            let PropertyValue::List(members) =
+                obj.get_property(PropertyIdentifier::ListOfGroupMembers)?
+            else {
+                return Err(ObjectError::InvalidPropertyType);
+            };
+            let results: Vec<ReadAccessResult> = members
+                .iter()
+                .filter_map(ReadAccessSpecification::from_property_value)
+                .map(|member| self.read_access_with(objects, &member, false))
+                .collect();
+            let present_value = Group::present_value_from(&results);
+            return match array_index {
+                Some(index) => array_element(present_value, index),
+                None => Ok(present_value),
+            };
+        }
+        match array_index {
+            Some(index) => obj.get_property_at(property, index),
+            None => obj.get_property(property),
+        }
+    }
+
+    fn read_access_with(
+        &self,
+        objects: &HashMap<ObjectIdentifier, Box<dyn BacnetObject>>,
+        specification: &ReadAccessSpecification,
+        resolve_groups: bool,
+    ) -> ReadAccessResult {
+        let identifier = specification.object_identifier;
+        let mut references = Vec::new();
+        for reference in &specification.property_references {
+            let special = PropertyIdentifier::try_from(reference.property_identifier)
+                .ok()
+                .filter(|property| {
+                    matches!(
+                        property,
+                        PropertyIdentifier::All
+                            | PropertyIdentifier::Required
+                            | PropertyIdentifier::Optional
+                    )
+                });
+            if let (Some(special), Some(obj)) = (special, objects.get(&identifier)) {
+                let required = obj.required_properties();
+                let properties = match special {
+                    PropertyIdentifier::All => obj.property_list(),
+                    PropertyIdentifier::Required => required,
+                    _ => obj
+                        .property_list()
+                        .into_iter()
+                        .filter(|property| !required.contains(property))
+                        .collect(),
+                };
+                references.extend(
+                    properties
+                        .into_iter()
+                        .filter(|property| *property != PropertyIdentifier::PropertyList)
+                        .map(|property| PropertyReference::new(u32::from(property))),
+                );
+                continue;
+            }
+            references.push(reference.clone());
+        }
+
+        let list_of_results = references
+            .into_iter()
+            .map(|reference| {
+                let read_result = PropertyIdentifier::try_from(reference.property_identifier)
+                    .map_err(|_| ObjectError::UnknownProperty)
+                    .and_then(|property| {
+                        self.read_property(
+                            objects,
+                            identifier,
+                            property,
+                            reference.property_array_index,
+                            resolve_groups,
+                        )
+                    })
+                    .map_err(|err| PropertyAccessError::from(&err));
+                ReadResult {
+                    property_identifier: reference.property_identifier,
+                    property_array_index: reference.property_array_index,
+                    read_result,
+                }
+            })
+            .collect();
+        ReadAccessResult {
+            object_identifier: identifier,
+            list_of_results,
         }
     }
 
@@ -172,17 +381,524 @@ impl ObjectDatabase {
         identifier: ObjectIdentifier,
         property: PropertyIdentifier,
         value: PropertyValue,
+    ) -> Result<()> {
+        let pending = {
+            let mut objects = self.objects.write().unwrap();
+            let obj = objects.get_mut(&identifier).ok_or(ObjectError::NotFound)?;
+            obj.set_property(property, value)?;
+            self.increment_revision();
+            obj.take_pending_writes()
+        };
+        self.process_object_writes(vec![(identifier, pending)]);
+        Ok(())
+    }
+
+    /// Set one element of an array property on an object
+    pub fn set_property_at(
+        &self,
+        identifier: ObjectIdentifier,
+        property: PropertyIdentifier,
+        index: u32,
+        value: PropertyValue,
+    ) -> Result<()> {
+        let pending = {
+            let mut objects = self.objects.write().unwrap();
+            let obj = objects.get_mut(&identifier).ok_or(ObjectError::NotFound)?;
+            obj.set_property_at(property, index, value)?;
+            self.increment_revision();
+            obj.take_pending_writes()
+        };
+        self.process_object_writes(vec![(identifier, pending)]);
+        Ok(())
+    }
+
+    /// Set a property value on an object at a command priority (1-16)
+    pub fn set_property_with_priority(
+        &self,
+        identifier: ObjectIdentifier,
+        property: PropertyIdentifier,
+        value: PropertyValue,
+        priority: u8,
+    ) -> Result<()> {
+        let pending = {
+            let mut objects = self.objects.write().unwrap();
+            let obj = objects.get_mut(&identifier).ok_or(ObjectError::NotFound)?;
+            obj.set_property_with_priority(property, value, priority)?;
+            self.increment_revision();
+            obj.take_pending_writes()
+        };
+        self.process_object_writes(vec![(identifier, pending)]);
+        Ok(())
+    }
+
+    /// Apply writes requested by objects such as Schedules and Loops
+    ///
+    /// References to other devices are skipped. Failed writes are returned so the
+    /// requesting object can reflect them in its Reliability.
+    pub fn apply_writes(
+        &self,
+        writes: Vec<PropertyWrite>,
+    ) -> Vec<(DeviceObjectPropertyReference, ObjectError)> {
+        let local_device = self.get_device_id();
+        writes
+            .into_iter()
+            .filter(|write| {
+                write
+                    .reference
+                    .device_identifier
+                    .is_none_or(|device| device == local_device)
+            })
+            .filter_map(|write| {
+                let reference = write.reference;
+                self.apply_write(write).err().map(|err| (reference, err))
+            })
+            .collect()
+    }
+
+    /// Advance the timers of every object by `elapsed`
+    ///
+    /// Writes the objects queue while doing so are applied afterwards.
+    pub fn advance_time(&self, elapsed: Duration) {
+        let pending: Vec<(ObjectIdentifier, Vec<PropertyWrite>)> = {
+            let mut objects = self.objects.write().unwrap();
+            objects
+                .iter_mut()
+                .map(|(identifier, obj)| {
+                    obj.advance_time(elapsed);
+                    (*identifier, obj.take_pending_writes())
+                })
+                .collect()
+        };
+        self.process_object_writes(pending);
+        self.cov_subscriptions
+            .write()
+            .unwrap()
+            .advance_time(elapsed);
+    }
+
+    /// Apply a Subscribe COV request from a subscriber device
+    ///
+    /// Only objects with a Present_Value report changes of value; others are
+    /// refused as `TypeNotSupported`.
+    pub fn subscribe_cov(
+        &self,
+        subscriber_device: ObjectIdentifier,
+        request: &SubscribeCovRequest,
+    ) -> Result<()> {
+        {
+            let objects = self.objects.read().unwrap();
+            let obj = objects
+                .get(&request.monitored_object_identifier)
+                .ok_or(ObjectError::NotFound)?;
+            if !obj
+                .property_list()
+                .contains(&PropertyIdentifier::PresentValue)
+            {
+                return Err(ObjectError::TypeNotSupported);
+            }
+        }
+        self.cov_subscriptions
+            .write()
+            .unwrap()
+            .subscribe(subscriber_device, request);
+        Ok(())
+    }
+
+    /// Apply a Subscribe COV Property request from a subscriber device
+    ///
+    /// The monitored property, or array element, must be readable.
+    pub fn subscribe_cov_property(
+        &self,
+        subscriber_device: ObjectIdentifier,
+        request: &SubscribeCovPropertyRequest,
+    ) -> Result<()> {
+        {
+            let objects = self.objects.read().unwrap();
+            let property =
+                PropertyIdentifier::try_from(request.monitored_property.property_identifier)
+                    .map_err(|_| ObjectError::UnknownProperty)?;
+            self.read_property(
+                &objects,
+                request.monitored_object_identifier,
+                property,
+                request.monitored_property.property_array_index,
+                true,
+            )?;
+        }
+        self.cov_subscriptions
+            .write()
+            .unwrap()
+            .subscribe_property(subscriber_device, request);
+        Ok(())
+    }
+
+    /// Build the COV notifications due to subscribers whose monitored objects
+    /// have changed since they were last notified
+    pub fn cov_notifications(&self) -> Vec<PendingCovNotification> {
+        let objects = self.objects.read().unwrap();
+        self.cov_subscriptions.write().unwrap().check_for_changes(
+            self.device_id,
+            |identifier, property, array_index| {
+                self.read_property(&objects, identifier, property, array_index, true)
+                    .ok()
+            },
+        )
+    }
+
+    /// Acknowledge an event transition of an object, setting its bit in
+    /// Acked_Transitions
+    pub fn acknowledge_transition(
+        &self,
+        identifier: ObjectIdentifier,
+        transition: EventTransition,
     ) -> Result<()> {
         let mut objects = self.objects.write().unwrap();
-        match objects.get_mut(&identifier) {
-            Some(obj) => {
-                let result = obj.set_property(property, value);
-                if result.is_ok() {
-                    self.increment_revision();
+        let obj = objects.get_mut(&identifier).ok_or(ObjectError::NotFound)?;
+        obj.acknowledge_transition(transition)?;
+        self.increment_revision();
+        Ok(())
+    }
+
+    /// Apply a LifeSafetyOperation request to one object, or to every Life
+    /// Safety Point and Zone when `identifier` is `None`
+    pub fn life_safety_operation(
+        &self,
+        identifier: Option<ObjectIdentifier>,
+        operation: LifeSafetyOperation,
+    ) -> Result<()> {
+        let mut objects = self.objects.write().unwrap();
+        match identifier {
+            Some(identifier) => {
+                let obj = objects.get_mut(&identifier).ok_or(ObjectError::NotFound)?;
+                obj.life_safety_operation(operation)?;
+            }
+            None => {
+                for obj in objects.values_mut().filter(|obj| {
+                    matches!(
+                        obj.identifier().object_type,
+                        ObjectType::LifeSafetyPoint | ObjectType::LifeSafetyZone
+                    )
+                }) {
+                    obj.life_safety_operation(operation)?;
                 }
-                result
             }
-            None => Err(ObjectError::NotFound),
+        }
+        self.increment_revision();
+        Ok(())
+    }
+
+    /// Distribute a WriteGroup request to the channels in its control group
+    ///
+    /// Returns the number of channel writes made, delayed ones included.
+    /// Member writes of channels that write at once are applied before
+    /// returning.
+    pub fn write_group(&self, request: &WriteGroupRequest) -> usize {
+        if request.group_number == 0 {
+            return 0;
+        }
+        let inhibit_delay = request.inhibit_delay.unwrap_or(false);
+        let mut written = 0;
+        let pending = {
+            let mut objects = self.objects.write().unwrap();
+            let mut pending = Vec::new();
+            for (identifier, obj) in objects.iter_mut() {
+                let Some(channel) = obj.as_channel_mut() else {
+                    continue;
+                };
+                if !channel.control_groups.contains(&request.group_number) {
+                    continue;
+                }
+                for entry in &request.change_list {
+                    if entry.channel as u32 != channel.channel_number {
+                        continue;
+                    }
+                    let priority = entry.overriding_priority.unwrap_or(request.write_priority);
+                    if channel
+                        .write_group(
+                            entry.value.clone(),
+                            priority,
+                            request.group_number,
+                            inhibit_delay,
+                        )
+                        .is_ok()
+                    {
+                        written += 1;
+                    }
+                }
+                pending.push((*identifier, obj.take_pending_writes()));
+            }
+            pending
+        };
+        if written > 0 {
+            self.increment_revision();
+        }
+        self.process_object_writes(pending);
+        written
+    }
+
+    /// Add elements to a list property of an object, as AddListElement does
+    ///
+    /// `values` are regrouped into elements by the object first.
+    pub fn add_list_elements(
+        &self,
+        identifier: ObjectIdentifier,
+        property: PropertyIdentifier,
+        values: Vec<PropertyValue>,
+    ) -> core::result::Result<(), ChangeListError> {
+        self.change_list(identifier, property, values, |obj, elements| {
+            obj.add_list_elements(property, elements)
+        })
+    }
+
+    /// Remove elements from a list property of an object, as
+    /// RemoveListElement does
+    pub fn remove_list_elements(
+        &self,
+        identifier: ObjectIdentifier,
+        property: PropertyIdentifier,
+        values: Vec<PropertyValue>,
+    ) -> core::result::Result<(), ChangeListError> {
+        self.change_list(identifier, property, values, |obj, elements| {
+            obj.remove_list_elements(property, elements)
+        })
+    }
+
+    fn change_list(
+        &self,
+        identifier: ObjectIdentifier,
+        property: PropertyIdentifier,
+        values: Vec<PropertyValue>,
+        change: impl FnOnce(
+            &mut dyn BacnetObject,
+            &[PropertyValue],
+        ) -> core::result::Result<(), ChangeListError>,
+    ) -> core::result::Result<(), ChangeListError> {
+        let failure =
+            |error: ObjectError| ChangeListError::new(PropertyAccessError::from(&error), 0);
+        let mut objects = self.objects.write().unwrap();
+        let obj = objects
+            .get_mut(&identifier)
+            .ok_or_else(|| failure(ObjectError::NotFound))?;
+        let elements = obj.list_elements(property, values).map_err(failure)?;
+        change(obj.as_mut(), &elements)?;
+        self.increment_revision();
+        Ok(())
+    }
+
+    /// Run `f` on a File object, as AtomicReadFile does
+    pub fn read_file<R, E: From<ObjectError>>(
+        &self,
+        identifier: ObjectIdentifier,
+        f: impl FnOnce(&File) -> core::result::Result<R, E>,
+    ) -> core::result::Result<R, E> {
+        let objects = self.objects.read().unwrap();
+        let file = objects
+            .get(&identifier)
+            .and_then(|obj| obj.as_file())
+            .ok_or(ObjectError::NotFound)?;
+        f(file)
+    }
+
+    /// Run `f` on a File object that may change its contents, as
+    /// AtomicWriteFile does
+    pub fn write_file<R, E: From<ObjectError>>(
+        &self,
+        identifier: ObjectIdentifier,
+        f: impl FnOnce(&mut File) -> core::result::Result<R, E>,
+    ) -> core::result::Result<R, E> {
+        let mut objects = self.objects.write().unwrap();
+        let file = objects
+            .get_mut(&identifier)
+            .and_then(|obj| obj.as_file_mut())
+            .ok_or(ObjectError::NotFound)?;
+        let result = f(file)?;
+        self.increment_revision();
+        Ok(result)
+    }
+
+    /// Run `f` on an Audit Log object, as AuditLogQuery does
+    pub fn read_audit_log<R, E: From<ObjectError>>(
+        &self,
+        identifier: ObjectIdentifier,
+        f: impl FnOnce(&AuditLog) -> core::result::Result<R, E>,
+    ) -> core::result::Result<R, E> {
+        let objects = self.objects.read().unwrap();
+        let log = objects
+            .get(&identifier)
+            .and_then(|obj| obj.as_audit_log())
+            .ok_or(ObjectError::NotFound)?;
+        f(log)
+    }
+
+    /// Store audit notifications received at `timestamp` in every Audit Log
+    ///
+    /// Returns the number of logs that stored them; logs with logging
+    /// disabled discard them.
+    pub fn log_audit_notifications(
+        &self,
+        timestamp: BacnetDateTime,
+        notifications: &[AuditNotification],
+    ) -> usize {
+        let mut objects = self.objects.write().unwrap();
+        let mut logs = 0;
+        for log in objects
+            .values_mut()
+            .filter_map(|obj| obj.as_audit_log_mut())
+        {
+            let mut logged = false;
+            for notification in notifications {
+                logged |= log.log_notification(timestamp, notification.clone());
+            }
+            logs += usize::from(logged);
+        }
+        logs
+    }
+
+    /// Register the factory CreateObject uses for `object_type`
+    pub fn register_object_factory<F>(&self, object_type: ObjectType, factory: F)
+    where
+        F: Fn(u32, String) -> Box<dyn BacnetObject> + Send + Sync + 'static,
+    {
+        let mut factories = self.object_factories.write().unwrap();
+        factories.register(object_type, factory);
+    }
+
+    /// Replace every registered object factory
+    pub fn set_object_factories(&self, registry: ObjectFactoryRegistry) {
+        *self.object_factories.write().unwrap() = registry;
+    }
+
+    /// Build an object with the registered factory, without adding it
+    ///
+    /// Returns `None` if no factory is registered for `object_type`.
+    pub fn build_object(
+        &self,
+        object_type: ObjectType,
+        instance: u32,
+        name: String,
+    ) -> Option<Box<dyn BacnetObject>> {
+        let factories = self.object_factories.read().unwrap();
+        factories.create(object_type, instance, name)
+    }
+
+    /// Set the host policy DeleteObject applies after the object's own
+    /// [`BacnetObject::is_deletable`]
+    pub fn set_deletion_policy<F>(&self, policy: F)
+    where
+        F: Fn(&dyn BacnetObject) -> bool + Send + Sync + 'static,
+    {
+        *self.deletion_policy.write().unwrap() = Some(Box::new(policy));
+    }
+
+    /// Check whether DeleteObject may remove an object
+    ///
+    /// The device object is never deletable.
+    pub fn is_deletable(&self, identifier: ObjectIdentifier) -> Result<bool> {
+        let objects = self.objects.read().unwrap();
+        let obj = objects.get(&identifier).ok_or(ObjectError::NotFound)?;
+        if identifier == self.device_id || !obj.is_deletable() {
+            return Ok(false);
+        }
+        let policy = self.deletion_policy.read().unwrap();
+        Ok(policy.as_ref().is_none_or(|allowed| allowed(obj.as_ref())))
+    }
+
+    /// Rebuild the Notification Class object numbered `notification_class`
+    /// from its properties
+    pub fn notification_class(&self, notification_class: u32) -> Option<NotificationClass> {
+        let identifier = ObjectIdentifier::new(ObjectType::NotificationClass, notification_class);
+        let name = match self.get_property(identifier, PropertyIdentifier::ObjectName) {
+            Ok(PropertyValue::CharacterString(name)) => name,
+            _ => return None,
+        };
+        let mut class = NotificationClass::new(notification_class, name);
+        for property in [
+            PropertyIdentifier::Priority,
+            PropertyIdentifier::AckRequired,
+            PropertyIdentifier::RecipientList,
+        ] {
+            let value = self.get_property(identifier, property).ok()?;
+            class.set_property(property, value).ok()?;
+        }
+        Some(class)
+    }
+
+    /// Queue event notifications for the application to send
+    pub fn queue_event_notifications(&self, notifications: Vec<PendingEventNotification>) {
+        self.event_notifications
+            .write()
+            .unwrap()
+            .extend(notifications);
+    }
+
+    /// Take the event notifications queued since the last call
+    pub fn take_event_notifications(&self) -> Vec<PendingEventNotification> {
+        std::mem::take(&mut *self.event_notifications.write().unwrap())
+    }
+
+    /// Activate the pending configuration changes of every object, as done
+    /// when the device is reinitialized with ACTIVATE_CHANGES
+    pub fn activate_changes(&self) {
+        let mut objects = self.objects.write().unwrap();
+        for obj in objects.values_mut() {
+            obj.activate_changes();
+        }
+    }
+
+    /// Apply a single object-requested write
+    ///
+    /// Writes to other devices cannot be made from the local database and are
+    /// reported as `NotFound`.
+    fn apply_write(&self, write: PropertyWrite) -> Result<()> {
+        if write
+            .reference
+            .device_identifier
+            .is_some_and(|device| device != self.device_id)
+        {
+            return Err(ObjectError::NotFound);
+        }
+        if let Some(index) = write.reference.property_array_index {
+            return self.set_property_at(
+                write.reference.object_identifier,
+                write.reference.property_identifier,
+                index,
+                write.value,
+            );
+        }
+        self.set_property_with_priority(
+            write.reference.object_identifier,
+            write.reference.property_identifier,
+            write.value,
+            write.priority,
+        )
+    }
+
+    /// Apply writes queued by objects, report each outcome back to the object
+    /// that queued it, and repeat while the objects queue follow-up writes
+    fn process_object_writes(&self, mut pending: Vec<(ObjectIdentifier, Vec<PropertyWrite>)>) {
+        pending.retain(|(_, writes)| !writes.is_empty());
+        while !pending.is_empty() {
+            let outcomes: Vec<(ObjectIdentifier, Vec<bool>)> = pending
+                .into_iter()
+                .map(|(identifier, writes)| {
+                    let results = writes
+                        .into_iter()
+                        .map(|write| self.apply_write(write).is_ok())
+                        .collect();
+                    (identifier, results)
+                })
+                .collect();
+
+            let mut objects = self.objects.write().unwrap();
+            pending = outcomes
+                .into_iter()
+                .filter_map(|(identifier, results)| {
+                    let obj = objects.get_mut(&identifier)?;
+                    obj.report_write_results(&results);
+                    let follow_up = obj.take_pending_writes();
+                    (!follow_up.is_empty()).then_some((identifier, follow_up))
+                })
+                .collect();
         }
     }
 
@@ -388,6 +1104,69 @@ mod tests {
         binary::BinaryInput,
     };
 
+    #[test]
+    fn test_device_object_list_element() {
+        let device = Device::new(1234, "Test Device".to_string());
+        let db = ObjectDatabase::new(device);
+        let device_id = db.get_device_id();
+        db.add_object(Box::new(AnalogInput::new(1, "AI-1".to_string())))
+            .unwrap();
+
+        assert_eq!(
+            db.get_property_at(device_id, PropertyIdentifier::ObjectList, 0)
+                .unwrap(),
+            PropertyValue::UnsignedInteger(2)
+        );
+        assert_eq!(
+            db.get_property_at(device_id, PropertyIdentifier::ObjectList, 2)
+                .unwrap(),
+            PropertyValue::ObjectIdentifier(ObjectIdentifier::new(ObjectType::AnalogInput, 1))
+        );
+        assert!(matches!(
+            db.get_property_at(device_id, PropertyIdentifier::ObjectList, 3),
+            Err(ObjectError::InvalidArrayIndex)
+        ));
+    }
+
+    #[test]
+    fn test_device_object_list() {
+        let device = Device::new(1234, "Test Device".to_string());
+        let db = ObjectDatabase::new(device);
+        let device_id = db.get_device_id();
+
+        db.add_object(Box::new(AnalogInput::new(2, "AI-2".to_string())))
+            .unwrap();
+        db.add_object(Box::new(AnalogInput::new(1, "AI-1".to_string())))
+            .unwrap();
+
+        if let Ok(PropertyValue::Array(list)) =
+            db.get_property(device_id, PropertyIdentifier::ObjectList)
+        {
+            let ids: Vec<ObjectIdentifier> = list
+                .into_iter()
+                .map(|v| match v {
+                    PropertyValue::ObjectIdentifier(id) => id,
+                    _ => panic!("Expected ObjectIdentifier"),
+                })
+                .collect();
+            assert_eq!(
+                ids,
+                vec![
+                    device_id,
+                    ObjectIdentifier::new(ObjectType::AnalogInput, 1),
+                    ObjectIdentifier::new(ObjectType::AnalogInput, 2),
+                ]
+            );
+        } else {
+            panic!("Expected Array");
+        }
+
+        assert!(matches!(
+            db.get_property(device_id, PropertyIdentifier::DatabaseRevision),
+            Ok(PropertyValue::UnsignedInteger(3))
+        ));
+    }
+
     #[test]
     fn test_database_creation() {
         let device = Device::new(1234, "Test Device".to_string());
@@ -466,6 +1245,165 @@ mod tests {
         assert_eq!(results[0].instance, 2);
     }
 
+    #[test]
+    fn test_command_writes_through_database() {
+        use crate::object::command::{ActionCommand, Command};
+
+        let db = ObjectDatabase::new(Device::new(1234, "Test Device".to_string()));
+        db.add_object(Box::new(AnalogValue::new(1, "AV1".to_string())))
+            .unwrap();
+
+        let mut command = Command::new(1, "Mode".to_string());
+        let target = ObjectIdentifier::new(ObjectType::AnalogValue, 1);
+        let mut delayed = ActionCommand::new(
+            target,
+            PropertyIdentifier::PresentValue,
+            PropertyValue::Real(21.0),
+        );
+        delayed.post_delay = Some(5);
+        let missing = ActionCommand::new(
+            ObjectIdentifier::new(ObjectType::AnalogValue, 99),
+            PropertyIdentifier::PresentValue,
+            PropertyValue::Real(0.0),
+        );
+        command.add_action(vec![delayed, missing]);
+        let command_id = command.identifier();
+        db.add_object(Box::new(command)).unwrap();
+
+        db.set_property(
+            command_id,
+            PropertyIdentifier::PresentValue,
+            PropertyValue::UnsignedInteger(1),
+        )
+        .unwrap();
+        assert_eq!(
+            db.get_property(target, PropertyIdentifier::PresentValue)
+                .unwrap(),
+            PropertyValue::Real(21.0)
+        );
+        assert_eq!(
+            db.get_property(command_id, PropertyIdentifier::InProcess)
+                .unwrap(),
+            PropertyValue::Boolean(true)
+        );
+
+        db.advance_time(Duration::from_secs(5));
+        assert_eq!(
+            db.get_property(command_id, PropertyIdentifier::InProcess)
+                .unwrap(),
+            PropertyValue::Boolean(false)
+        );
+        assert_eq!(
+            db.get_property(command_id, PropertyIdentifier::AllWritesSuccessful)
+                .unwrap(),
+            PropertyValue::Boolean(false)
+        );
+    }
+
+    #[test]
+    fn test_channel_writes_through_database() {
+        use crate::object::{
+            binary::BinaryOutput,
+            channel::{Channel, WriteStatus},
+            DeviceObjectPropertyReference,
+        };
+
+        let db = ObjectDatabase::new(Device::new(1234, "Test Device".to_string()));
+        db.add_object(Box::new(BinaryOutput::new(1, "Lights".to_string())))
+            .unwrap();
+        db.add_object(Box::new(AnalogValue::new(1, "Level".to_string())))
+            .unwrap();
+
+        let bo_id = ObjectIdentifier::new(ObjectType::BinaryOutput, 1);
+        let av_id = ObjectIdentifier::new(ObjectType::AnalogValue, 1);
+        let mut channel = Channel::new(1, "Scene".to_string(), 1);
+        for member in [bo_id, av_id] {
+            channel.add_member(DeviceObjectPropertyReference::new(
+                member,
+                PropertyIdentifier::PresentValue,
+            ));
+        }
+        let channel_id = channel.identifier();
+        db.add_object(Box::new(channel)).unwrap();
+
+        db.set_property_with_priority(
+            channel_id,
+            PropertyIdentifier::PresentValue,
+            PropertyValue::UnsignedInteger(60),
+            9,
+        )
+        .unwrap();
+        assert_eq!(
+            db.get_property(bo_id, PropertyIdentifier::PresentValue)
+                .unwrap(),
+            PropertyValue::Enumerated(1)
+        );
+        assert_eq!(
+            db.get_property(av_id, PropertyIdentifier::PresentValue)
+                .unwrap(),
+            PropertyValue::Real(60.0)
+        );
+        assert_eq!(
+            db.get_property(channel_id, PropertyIdentifier::WriteStatus)
+                .unwrap(),
+            PropertyValue::Enumerated(WriteStatus::Successful as u32)
+        );
+    }
+
+    #[test]
+    fn test_group_present_value() {
+        let db = ObjectDatabase::new(Device::new(1234, "Test Device".to_string()));
+        let mut av = AnalogValue::new(1, "AV1".to_string());
+        av.present_value = 21.5;
+        db.add_object(Box::new(av)).unwrap();
+
+        let av_id = ObjectIdentifier::new(ObjectType::AnalogValue, 1);
+        let mut group = Group::new(1, "Zone".to_string());
+        group.add_member(ReadAccessSpecification::new(
+            av_id,
+            vec![
+                PropertyReference::new(u32::from(PropertyIdentifier::PresentValue)),
+                PropertyReference::new(u32::from(PropertyIdentifier::Setpoint)),
+            ],
+        ));
+        let group_id = group.identifier();
+        db.add_object(Box::new(group)).unwrap();
+
+        let results = db.read_access(&ReadAccessSpecification::new(
+            av_id,
+            vec![PropertyReference::new(u32::from(
+                PropertyIdentifier::PresentValue,
+            ))],
+        ));
+        assert_eq!(
+            results.list_of_results[0].read_result,
+            Ok(PropertyValue::Real(21.5))
+        );
+
+        let present_value = db
+            .get_property(group_id, PropertyIdentifier::PresentValue)
+            .unwrap();
+        let expected = ReadAccessResult {
+            object_identifier: av_id,
+            list_of_results: vec![
+                ReadResult {
+                    property_identifier: u32::from(PropertyIdentifier::PresentValue),
+                    property_array_index: None,
+                    read_result: Ok(PropertyValue::Real(21.5)),
+                },
+                ReadResult {
+                    property_identifier: u32::from(PropertyIdentifier::Setpoint),
+                    property_array_index: None,
+                    read_result: Err(PropertyAccessError {
+                        error_class: 2,
+                        error_code: 32,
+                    }),
+                },
+            ],
+        };
+        assert_eq!(present_value, Group::present_value_from(&[expected]));
+    }
+
     #[test]
     fn test_database_builder() {
         let db = DatabaseBuilder::new()
@@ -0,0 +1,1725 @@
+//! Date, Time and DateTime Value Object Types Implementation
+//!
+//! This module implements the Date Value, Time Value and DateTime Value object
+//! types as defined in ASHRAE 135, together with their pattern variants.
+//!
+//! - The value objects hold a specific date, time or timestamp. Every field of
+//!   Present_Value must be specified, except that a fully unspecified value means
+//!   "no value" and an unspecified weekday is filled in from the date.
+//! - The pattern objects may use BACnet wildcards (255) in any field, as well as
+//!   the special month (13 odd, 14 even) and day (32 last, 33 odd, 34 even)
+//!   values. Each has a `matches` method that tests a concrete value against the
+//!   pattern.
+//!
+//! [`TimeValue`] is not re-exported from [`crate::object`], where the name belongs
+//! to the schedule's time/value pair; use `object::date_time::TimeValue`.
+
+use crate::object::{
+    calendar::{date_matches, weekday_of},
+    current_status_flags, date_time_from_value, date_time_value, status_flags_bit_string,
+    BacnetObject, Commandable, Date, EventState, ObjectError, ObjectIdentifier, ObjectType,
+    PropertyIdentifier, PropertyValue, Reliability, Result, Time, DEFAULT_COMMAND_PRIORITY,
+};
+use crate::service::BacnetDateTime;
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+/// Wildcard value for date and time fields
+const UNSPECIFIED: u8 = 255;
+
+const UNSPECIFIED_DATE: Date = Date {
+    year: UNSPECIFIED as u16,
+    month: UNSPECIFIED,
+    day: UNSPECIFIED,
+    weekday: UNSPECIFIED,
+};
+
+const UNSPECIFIED_TIME: Time = Time {
+    hour: UNSPECIFIED,
+    minute: UNSPECIFIED,
+    second: UNSPECIFIED,
+    hundredths: UNSPECIFIED,
+};
+
+fn field_valid(value: u8, range: core::ops::RangeInclusive<u8>) -> bool {
+    value == UNSPECIFIED || range.contains(&value)
+}
+
+/// Check that every date field is in range, allowing wildcards and the special
+/// month and day values
+fn check_date_pattern(date: Date) -> Result<Date> {
+    let year_valid = date.year == UNSPECIFIED as u16 || (1900..=2154).contains(&date.year);
+    if year_valid
+        && field_valid(date.month, 1..=14)
+        && field_valid(date.day, 1..=34)
+        && field_valid(date.weekday, 1..=7)
+    {
+        Ok(date)
+    } else {
+        Err(ObjectError::InvalidValue(format!(
+            "Invalid date pattern {}-{}-{} (weekday {})",
+            date.year, date.month, date.day, date.weekday
+        )))
+    }
+}
+
+/// Check that a date names a specific day, filling in an unspecified weekday
+fn check_date(date: Date) -> Result<Date> {
+    if date == UNSPECIFIED_DATE {
+        return Ok(date);
+    }
+    let invalid = || {
+        ObjectError::InvalidValue(format!(
+            "{}-{}-{} (weekday {}) is not a specific date",
+            date.year, date.month, date.day, date.weekday
+        ))
+    };
+    check_date_pattern(date)?;
+    let weekday = weekday_of(&date).ok_or_else(invalid)?;
+    if date.weekday != UNSPECIFIED && date.weekday != weekday {
+        return Err(invalid());
+    }
+    Ok(Date { weekday, ..date })
+}
+
+/// Check that every time field is in range, allowing wildcards
+fn check_time_pattern(time: Time) -> Result<Time> {
+    if field_valid(time.hour, 0..=23)
+        && field_valid(time.minute, 0..=59)
+        && field_valid(time.second, 0..=59)
+        && field_valid(time.hundredths, 0..=99)
+    {
+        Ok(time)
+    } else {
+        Err(ObjectError::InvalidValue(format!(
+            "Invalid time pattern {}:{}:{}.{}",
+            time.hour, time.minute, time.second, time.hundredths
+        )))
+    }
+}
+
+/// Check that a time is fully specified
+fn check_time(time: Time) -> Result<Time> {
+    if time == UNSPECIFIED_TIME {
+        return Ok(time);
+    }
+    check_time_pattern(time)?;
+    if [time.hour, time.minute, time.second, time.hundredths].contains(&UNSPECIFIED) {
+        return Err(ObjectError::InvalidValue(format!(
+            "{}:{}:{}.{} is not a specific time",
+            time.hour, time.minute, time.second, time.hundredths
+        )));
+    }
+    Ok(time)
+}
+
+fn check_date_time_pattern(date_time: BacnetDateTime) -> Result<BacnetDateTime> {
+    Ok(BacnetDateTime::new(
+        check_date_pattern(date_time.date)?,
+        check_time_pattern(date_time.time)?,
+    ))
+}
+
+fn check_date_time(date_time: BacnetDateTime) -> Result<BacnetDateTime> {
+    if date_time.is_unspecified() {
+        return Ok(date_time);
+    }
+    if date_time.date == UNSPECIFIED_DATE || date_time.time == UNSPECIFIED_TIME {
+        return Err(ObjectError::InvalidValue(
+            "Date and time must both be specified".to_string(),
+        ));
+    }
+    Ok(BacnetDateTime::new(
+        check_date(date_time.date)?,
+        check_time(date_time.time)?,
+    ))
+}
+
+/// Check whether a time pattern (with wildcards) matches a concrete time
+pub fn time_matches(pattern: &Time, time: &Time) -> bool {
+    let field = |pattern: u8, value: u8| pattern == UNSPECIFIED || pattern == value;
+    field(pattern.hour, time.hour)
+        && field(pattern.minute, time.minute)
+        && field(pattern.second, time.second)
+        && field(pattern.hundredths, time.hundredths)
+}
+
+fn encode_date(date: Date) -> PropertyValue {
+    PropertyValue::Date(date)
+}
+
+fn encode_time(time: Time) -> PropertyValue {
+    PropertyValue::Time(time)
+}
+
+fn encode_date_time(date_time: BacnetDateTime) -> PropertyValue {
+    date_time_value(Some(date_time))
+}
+
+fn decode_date(value: &PropertyValue) -> Result<Date> {
+    match value {
+        PropertyValue::Date(date) => Ok(*date),
+        _ => Err(ObjectError::InvalidPropertyType),
+    }
+}
+
+fn decode_time(value: &PropertyValue) -> Result<Time> {
+    match value {
+        PropertyValue::Time(time) => Ok(*time),
+        _ => Err(ObjectError::InvalidPropertyType),
+    }
+}
+
+/// Date Value object
+#[derive(Debug, Clone)]
+pub struct DateValue {
+    /// Object identifier
+    pub identifier: ObjectIdentifier,
+    /// Object name
+    pub object_name: String,
+    /// Description
+    pub description: String,
+    /// Present value
+    pub present_value: Date,
+    /// Status flags
+    pub status_flags: u8,
+    /// Event state
+    pub event_state: EventState,
+    /// Reliability
+    pub reliability: Reliability,
+    /// Out of service
+    pub out_of_service: bool,
+    /// Priority array and relinquish default, present only on commandable
+    /// instances
+    pub commandable: Option<Commandable<Date>>,
+}
+
+impl DateValue {
+    /// Create a new non-commandable Date Value object
+    pub fn new(instance: u32, object_name: String) -> Self {
+        Self {
+            identifier: ObjectIdentifier::new(ObjectType::DateValue, instance),
+            object_name,
+            description: String::new(),
+            present_value: UNSPECIFIED_DATE,
+            status_flags: 0,
+            event_state: EventState::Normal,
+            reliability: Reliability::NoFaultDetected,
+            out_of_service: false,
+            commandable: None,
+        }
+    }
+
+    /// Create a new commandable Date Value object with a priority array
+    pub fn new_commandable(
+        instance: u32,
+        object_name: String,
+        relinquish_default: Date,
+    ) -> Result<Self> {
+        let relinquish_default = check_date(relinquish_default)?;
+        let mut value = Self::new(instance, object_name);
+        value.commandable = Some(Commandable::new(relinquish_default));
+        value.present_value = relinquish_default;
+        Ok(value)
+    }
+
+    /// Whether Present_Value writes are arbitrated through a priority array
+    pub fn is_commandable(&self) -> bool {
+        self.commandable.is_some()
+    }
+
+    /// Set the present value after validating its fields
+    pub fn set_present_value(&mut self, value: Date) -> Result<()> {
+        self.present_value = check_date(value)?;
+        Ok(())
+    }
+
+    /// Write to priority array at specified priority level (1-16)
+    pub fn write_priority(&mut self, priority: u8, value: Option<Date>) -> Result<()> {
+        if !(1..=16).contains(&priority) {
+            return Err(ObjectError::InvalidValue(
+                "Priority must be 1-16".to_string(),
+            ));
+        }
+        let value = value.map(check_date).transpose()?;
+        let Some(commandable) = self.commandable.as_mut() else {
+            return Err(ObjectError::InvalidConfiguration(
+                "Date Value is not commandable".to_string(),
+            ));
+        };
+        commandable.write(priority, value)?;
+        self.update_present_value();
+        Ok(())
+    }
+
+    /// Update present value based on priority array
+    fn update_present_value(&mut self) {
+        if let Some(commandable) = self.commandable.as_ref() {
+            self.present_value = *commandable.current();
+        }
+    }
+
+    /// Current Status_Flags, combining the stored flags with the state derived
+    /// from Event_State, Reliability and Out_Of_Service
+    pub fn current_status_flags(&self) -> u8 {
+        current_status_flags(
+            self.status_flags,
+            self.event_state,
+            self.reliability,
+            self.out_of_service,
+        )
+    }
+}
+
+impl BacnetObject for DateValue {
+    fn identifier(&self) -> ObjectIdentifier {
+        self.identifier
+    }
+
+    fn get_property(&self, property: PropertyIdentifier) -> Result<PropertyValue> {
+        match property {
+            PropertyIdentifier::ObjectIdentifier => {
+                Ok(PropertyValue::ObjectIdentifier(self.identifier))
+            }
+            PropertyIdentifier::ObjectName => {
+                Ok(PropertyValue::CharacterString(self.object_name.clone()))
+            }
+            PropertyIdentifier::ObjectType => {
+                Ok(PropertyValue::Enumerated(u32::from(ObjectType::DateValue)))
+            }
+            PropertyIdentifier::PresentValue => Ok(encode_date(self.present_value)),
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::StatusFlags => {
+                Ok(status_flags_bit_string(self.current_status_flags()))
+            }
+            PropertyIdentifier::EventState => {
+                Ok(PropertyValue::Enumerated(self.event_state as u32))
+            }
+            PropertyIdentifier::Reliability => {
+                Ok(PropertyValue::Enumerated(self.reliability as u32))
+            }
+            PropertyIdentifier::OutOfService => Ok(PropertyValue::Boolean(self.out_of_service)),
+            PropertyIdentifier::PriorityArray => self
+                .commandable
+                .as_ref()
+                .map(|commandable| commandable.priority_array_value(|&value| encode_date(value)))
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::RelinquishDefault => self
+                .commandable
+                .as_ref()
+                .map(|commandable| encode_date(*commandable.relinquish_default()))
+                .ok_or(ObjectError::UnknownProperty),
+            _ => Err(ObjectError::UnknownProperty),
+        }
+    }
+
+    fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        self.set_property_with_priority(property, value, DEFAULT_COMMAND_PRIORITY)
+    }
+
+    fn set_property_with_priority(
+        &mut self,
+        property: PropertyIdentifier,
+        value: PropertyValue,
+        priority: u8,
+    ) -> Result<()> {
+        match property {
+            PropertyIdentifier::ObjectName => {
+                if let PropertyValue::CharacterString(name) = value {
+                    self.object_name = name;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            // Writing NULL relinquishes the command at this priority
+            PropertyIdentifier::PresentValue
+                if self.is_commandable() && matches!(value, PropertyValue::Null) =>
+            {
+                self.write_priority(priority, None)
+            }
+            PropertyIdentifier::PresentValue => {
+                let val = decode_date(&value)?;
+                if self.is_commandable() {
+                    self.write_priority(priority, Some(val))
+                } else {
+                    self.set_present_value(val)
+                }
+            }
+            PropertyIdentifier::RelinquishDefault if self.is_commandable() => {
+                let default = check_date(decode_date(&value)?)?;
+                if let Some(commandable) = self.commandable.as_mut() {
+                    commandable.set_relinquish_default(default);
+                }
+                self.update_present_value();
+                Ok(())
+            }
+            PropertyIdentifier::OutOfService => {
+                if let PropertyValue::Boolean(oos) = value {
+                    self.out_of_service = oos;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            _ => Err(ObjectError::PropertyNotWritable),
+        }
+    }
+
+    fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
+        match property {
+            PropertyIdentifier::ObjectName
+            | PropertyIdentifier::Description
+            | PropertyIdentifier::PresentValue
+            | PropertyIdentifier::OutOfService => true,
+            PropertyIdentifier::RelinquishDefault => self.is_commandable(),
+            _ => false,
+        }
+    }
+
+    fn property_list(&self) -> Vec<PropertyIdentifier> {
+        let mut properties = vec![
+            PropertyIdentifier::ObjectIdentifier,
+            PropertyIdentifier::ObjectName,
+            PropertyIdentifier::ObjectType,
+            PropertyIdentifier::PresentValue,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::StatusFlags,
+            PropertyIdentifier::EventState,
+            PropertyIdentifier::Reliability,
+            PropertyIdentifier::OutOfService,
+        ];
+        if self.is_commandable() {
+            properties.push(PropertyIdentifier::PriorityArray);
+            properties.push(PropertyIdentifier::RelinquishDefault);
+        }
+        properties
+    }
+}
+
+/// Date Pattern Value object
+#[derive(Debug, Clone)]
+pub struct DatePatternValue {
+    /// Object identifier
+    pub identifier: ObjectIdentifier,
+    /// Object name
+    pub object_name: String,
+    /// Description
+    pub description: String,
+    /// Present value
+    pub present_value: Date,
+    /// Status flags
+    pub status_flags: u8,
+    /// Event state
+    pub event_state: EventState,
+    /// Reliability
+    pub reliability: Reliability,
+    /// Out of service
+    pub out_of_service: bool,
+    /// Priority array and relinquish default, present only on commandable
+    /// instances
+    pub commandable: Option<Commandable<Date>>,
+}
+
+impl DatePatternValue {
+    /// Create a new non-commandable Date Pattern Value object
+    pub fn new(instance: u32, object_name: String) -> Self {
+        Self {
+            identifier: ObjectIdentifier::new(ObjectType::DatePatternValue, instance),
+            object_name,
+            description: String::new(),
+            present_value: UNSPECIFIED_DATE,
+            status_flags: 0,
+            event_state: EventState::Normal,
+            reliability: Reliability::NoFaultDetected,
+            out_of_service: false,
+            commandable: None,
+        }
+    }
+
+    /// Create a new commandable Date Pattern Value object with a priority array
+    pub fn new_commandable(
+        instance: u32,
+        object_name: String,
+        relinquish_default: Date,
+    ) -> Result<Self> {
+        let relinquish_default = check_date_pattern(relinquish_default)?;
+        let mut value = Self::new(instance, object_name);
+        value.commandable = Some(Commandable::new(relinquish_default));
+        value.present_value = relinquish_default;
+        Ok(value)
+    }
+
+    /// Whether Present_Value writes are arbitrated through a priority array
+    pub fn is_commandable(&self) -> bool {
+        self.commandable.is_some()
+    }
+
+    /// Set the present value after validating its fields
+    pub fn set_present_value(&mut self, value: Date) -> Result<()> {
+        self.present_value = check_date_pattern(value)?;
+        Ok(())
+    }
+
+    /// Write to priority array at specified priority level (1-16)
+    pub fn write_priority(&mut self, priority: u8, value: Option<Date>) -> Result<()> {
+        if !(1..=16).contains(&priority) {
+            return Err(ObjectError::InvalidValue(
+                "Priority must be 1-16".to_string(),
+            ));
+        }
+        let value = value.map(check_date_pattern).transpose()?;
+        let Some(commandable) = self.commandable.as_mut() else {
+            return Err(ObjectError::InvalidConfiguration(
+                "Date Pattern Value is not commandable".to_string(),
+            ));
+        };
+        commandable.write(priority, value)?;
+        self.update_present_value();
+        Ok(())
+    }
+
+    /// Update present value based on priority array
+    fn update_present_value(&mut self) {
+        if let Some(commandable) = self.commandable.as_ref() {
+            self.present_value = *commandable.current();
+        }
+    }
+
+    /// Check whether `date` matches the pattern in Present_Value
+    pub fn matches(&self, date: &Date) -> bool {
+        date_matches(&self.present_value, date)
+    }
+
+    /// Current Status_Flags, combining the stored flags with the state derived
+    /// from Event_State, Reliability and Out_Of_Service
+    pub fn current_status_flags(&self) -> u8 {
+        current_status_flags(
+            self.status_flags,
+            self.event_state,
+            self.reliability,
+            self.out_of_service,
+        )
+    }
+}
+
+impl BacnetObject for DatePatternValue {
+    fn identifier(&self) -> ObjectIdentifier {
+        self.identifier
+    }
+
+    fn get_property(&self, property: PropertyIdentifier) -> Result<PropertyValue> {
+        match property {
+            PropertyIdentifier::ObjectIdentifier => {
+                Ok(PropertyValue::ObjectIdentifier(self.identifier))
+            }
+            PropertyIdentifier::ObjectName => {
+                Ok(PropertyValue::CharacterString(self.object_name.clone()))
+            }
+            PropertyIdentifier::ObjectType => Ok(PropertyValue::Enumerated(u32::from(
+                ObjectType::DatePatternValue,
+            ))),
+            PropertyIdentifier::PresentValue => Ok(encode_date(self.present_value)),
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::StatusFlags => {
+                Ok(status_flags_bit_string(self.current_status_flags()))
+            }
+            PropertyIdentifier::EventState => {
+                Ok(PropertyValue::Enumerated(self.event_state as u32))
+            }
+            PropertyIdentifier::Reliability => {
+                Ok(PropertyValue::Enumerated(self.reliability as u32))
+            }
+            PropertyIdentifier::OutOfService => Ok(PropertyValue::Boolean(self.out_of_service)),
+            PropertyIdentifier::PriorityArray => self
+                .commandable
+                .as_ref()
+                .map(|commandable| commandable.priority_array_value(|&value| encode_date(value)))
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::RelinquishDefault => self
+                .commandable
+                .as_ref()
+                .map(|commandable| encode_date(*commandable.relinquish_default()))
+                .ok_or(ObjectError::UnknownProperty),
+            _ => Err(ObjectError::UnknownProperty),
+        }
+    }
+
+    fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        self.set_property_with_priority(property, value, DEFAULT_COMMAND_PRIORITY)
+    }
+
+    fn set_property_with_priority(
+        &mut self,
+        property: PropertyIdentifier,
+        value: PropertyValue,
+        priority: u8,
+    ) -> Result<()> {
+        match property {
+            PropertyIdentifier::ObjectName => {
+                if let PropertyValue::CharacterString(name) = value {
+                    self.object_name = name;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            // Writing NULL relinquishes the command at this priority
+            PropertyIdentifier::PresentValue
+                if self.is_commandable() && matches!(value, PropertyValue::Null) =>
+            {
+                self.write_priority(priority, None)
+            }
+            PropertyIdentifier::PresentValue => {
+                let val = decode_date(&value)?;
+                if self.is_commandable() {
+                    self.write_priority(priority, Some(val))
+                } else {
+                    self.set_present_value(val)
+                }
+            }
+            PropertyIdentifier::RelinquishDefault if self.is_commandable() => {
+                let default = check_date_pattern(decode_date(&value)?)?;
+                if let Some(commandable) = self.commandable.as_mut() {
+                    commandable.set_relinquish_default(default);
+                }
+                self.update_present_value();
+                Ok(())
+            }
+            PropertyIdentifier::OutOfService => {
+                if let PropertyValue::Boolean(oos) = value {
+                    self.out_of_service = oos;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            _ => Err(ObjectError::PropertyNotWritable),
+        }
+    }
+
+    fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
+        match property {
+            PropertyIdentifier::ObjectName
+            | PropertyIdentifier::Description
+            | PropertyIdentifier::PresentValue
+            | PropertyIdentifier::OutOfService => true,
+            PropertyIdentifier::RelinquishDefault => self.is_commandable(),
+            _ => false,
+        }
+    }
+
+    fn property_list(&self) -> Vec<PropertyIdentifier> {
+        let mut properties = vec![
+            PropertyIdentifier::ObjectIdentifier,
+            PropertyIdentifier::ObjectName,
+            PropertyIdentifier::ObjectType,
+            PropertyIdentifier::PresentValue,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::StatusFlags,
+            PropertyIdentifier::EventState,
+            PropertyIdentifier::Reliability,
+            PropertyIdentifier::OutOfService,
+        ];
+        if self.is_commandable() {
+            properties.push(PropertyIdentifier::PriorityArray);
+            properties.push(PropertyIdentifier::RelinquishDefault);
+        }
+        properties
+    }
+}
+
+/// Time Value object
+#[derive(Debug, Clone)]
+pub struct TimeValue {
+    /// Object identifier
+    pub identifier: ObjectIdentifier,
+    /// Object name
+    pub object_name: String,
+    /// Description
+    pub description: String,
+    /// Present value
+    pub present_value: Time,
+    /// Status flags
+    pub status_flags: u8,
+    /// Event state
+    pub event_state: EventState,
+    /// Reliability
+    pub reliability: Reliability,
+    /// Out of service
+    pub out_of_service: bool,
+    /// Priority array and relinquish default, present only on commandable
+    /// instances
+    pub commandable: Option<Commandable<Time>>,
+}
+
+impl TimeValue {
+    /// Create a new non-commandable Time Value object
+    pub fn new(instance: u32, object_name: String) -> Self {
+        Self {
+            identifier: ObjectIdentifier::new(ObjectType::TimeValue, instance),
+            object_name,
+            description: String::new(),
+            present_value: UNSPECIFIED_TIME,
+            status_flags: 0,
+            event_state: EventState::Normal,
+            reliability: Reliability::NoFaultDetected,
+            out_of_service: false,
+            commandable: None,
+        }
+    }
+
+    /// Create a new commandable Time Value object with a priority array
+    pub fn new_commandable(
+        instance: u32,
+        object_name: String,
+        relinquish_default: Time,
+    ) -> Result<Self> {
+        let relinquish_default = check_time(relinquish_default)?;
+        let mut value = Self::new(instance, object_name);
+        value.commandable = Some(Commandable::new(relinquish_default));
+        value.present_value = relinquish_default;
+        Ok(value)
+    }
+
+    /// Whether Present_Value writes are arbitrated through a priority array
+    pub fn is_commandable(&self) -> bool {
+        self.commandable.is_some()
+    }
+
+    /// Set the present value after validating its fields
+    pub fn set_present_value(&mut self, value: Time) -> Result<()> {
+        self.present_value = check_time(value)?;
+        Ok(())
+    }
+
+    /// Write to priority array at specified priority level (1-16)
+    pub fn write_priority(&mut self, priority: u8, value: Option<Time>) -> Result<()> {
+        if !(1..=16).contains(&priority) {
+            return Err(ObjectError::InvalidValue(
+                "Priority must be 1-16".to_string(),
+            ));
+        }
+        let value = value.map(check_time).transpose()?;
+        let Some(commandable) = self.commandable.as_mut() else {
+            return Err(ObjectError::InvalidConfiguration(
+                "Time Value is not commandable".to_string(),
+            ));
+        };
+        commandable.write(priority, value)?;
+        self.update_present_value();
+        Ok(())
+    }
+
+    /// Update present value based on priority array
+    fn update_present_value(&mut self) {
+        if let Some(commandable) = self.commandable.as_ref() {
+            self.present_value = *commandable.current();
+        }
+    }
+
+    /// Current Status_Flags, combining the stored flags with the state derived
+    /// from Event_State, Reliability and Out_Of_Service
+    pub fn current_status_flags(&self) -> u8 {
+        current_status_flags(
+            self.status_flags,
+            self.event_state,
+            self.reliability,
+            self.out_of_service,
+        )
+    }
+}
+
+impl BacnetObject for TimeValue {
+    fn identifier(&self) -> ObjectIdentifier {
+        self.identifier
+    }
+
+    fn get_property(&self, property: PropertyIdentifier) -> Result<PropertyValue> {
+        match property {
+            PropertyIdentifier::ObjectIdentifier => {
+                Ok(PropertyValue::ObjectIdentifier(self.identifier))
+            }
+            PropertyIdentifier::ObjectName => {
+                Ok(PropertyValue::CharacterString(self.object_name.clone()))
+            }
+            PropertyIdentifier::ObjectType => {
+                Ok(PropertyValue::Enumerated(u32::from(ObjectType::TimeValue)))
+            }
+            PropertyIdentifier::PresentValue => Ok(encode_time(self.present_value)),
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::StatusFlags => {
+                Ok(status_flags_bit_string(self.current_status_flags()))
+            }
+            PropertyIdentifier::EventState => {
+                Ok(PropertyValue::Enumerated(self.event_state as u32))
+            }
+            PropertyIdentifier::Reliability => {
+                Ok(PropertyValue::Enumerated(self.reliability as u32))
+            }
+            PropertyIdentifier::OutOfService => Ok(PropertyValue::Boolean(self.out_of_service)),
+            PropertyIdentifier::PriorityArray => self
+                .commandable
+                .as_ref()
+                .map(|commandable| commandable.priority_array_value(|&value| encode_time(value)))
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::RelinquishDefault => self
+                .commandable
+                .as_ref()
+                .map(|commandable| encode_time(*commandable.relinquish_default()))
+                .ok_or(ObjectError::UnknownProperty),
+            _ => Err(ObjectError::UnknownProperty),
+        }
+    }
+
+    fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        self.set_property_with_priority(property, value, DEFAULT_COMMAND_PRIORITY)
+    }
+
+    fn set_property_with_priority(
+        &mut self,
+        property: PropertyIdentifier,
+        value: PropertyValue,
+        priority: u8,
+    ) -> Result<()> {
+        match property {
+            PropertyIdentifier::ObjectName => {
+                if let PropertyValue::CharacterString(name) = value {
+                    self.object_name = name;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            // Writing NULL relinquishes the command at this priority
+            PropertyIdentifier::PresentValue
+                if self.is_commandable() && matches!(value, PropertyValue::Null) =>
+            {
+                self.write_priority(priority, None)
+            }
+            PropertyIdentifier::PresentValue => {
+                let val = decode_time(&value)?;
+                if self.is_commandable() {
+                    self.write_priority(priority, Some(val))
+                } else {
+                    self.set_present_value(val)
+                }
+            }
+            PropertyIdentifier::RelinquishDefault if self.is_commandable() => {
+                let default = check_time(decode_time(&value)?)?;
+                if let Some(commandable) = self.commandable.as_mut() {
+                    commandable.set_relinquish_default(default);
+                }
+                self.update_present_value();
+                Ok(())
+            }
+            PropertyIdentifier::OutOfService => {
+                if let PropertyValue::Boolean(oos) = value {
+                    self.out_of_service = oos;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            _ => Err(ObjectError::PropertyNotWritable),
+        }
+    }
+
+    fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
+        match property {
+            PropertyIdentifier::ObjectName
+            | PropertyIdentifier::Description
+            | PropertyIdentifier::PresentValue
+            | PropertyIdentifier::OutOfService => true,
+            PropertyIdentifier::RelinquishDefault => self.is_commandable(),
+            _ => false,
+        }
+    }
+
+    fn property_list(&self) -> Vec<PropertyIdentifier> {
+        let mut properties = vec![
+            PropertyIdentifier::ObjectIdentifier,
+            PropertyIdentifier::ObjectName,
+            PropertyIdentifier::ObjectType,
+            PropertyIdentifier::PresentValue,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::StatusFlags,
+            PropertyIdentifier::EventState,
+            PropertyIdentifier::Reliability,
+            PropertyIdentifier::OutOfService,
+        ];
+        if self.is_commandable() {
+            properties.push(PropertyIdentifier::PriorityArray);
+            properties.push(PropertyIdentifier::RelinquishDefault);
+        }
+        properties
+    }
+}
+
+/// Time Pattern Value object
+#[derive(Debug, Clone)]
+pub struct TimePatternValue {
+    /// Object identifier
+    pub identifier: ObjectIdentifier,
+    /// Object name
+    pub object_name: String,
+    /// Description
+    pub description: String,
+    /// Present value
+    pub present_value: Time,
+    /// Status flags
+    pub status_flags: u8,
+    /// Event state
+    pub event_state: EventState,
+    /// Reliability
+    pub reliability: Reliability,
+    /// Out of service
+    pub out_of_service: bool,
+    /// Priority array and relinquish default, present only on commandable
+    /// instances
+    pub commandable: Option<Commandable<Time>>,
+}
+
+impl TimePatternValue {
+    /// Create a new non-commandable Time Pattern Value object
+    pub fn new(instance: u32, object_name: String) -> Self {
+        Self {
+            identifier: ObjectIdentifier::new(ObjectType::TimePatternValue, instance),
+            object_name,
+            description: String::new(),
+            present_value: UNSPECIFIED_TIME,
+            status_flags: 0,
+            event_state: EventState::Normal,
+            reliability: Reliability::NoFaultDetected,
+            out_of_service: false,
+            commandable: None,
+        }
+    }
+
+    /// Create a new commandable Time Pattern Value object with a priority array
+    pub fn new_commandable(
+        instance: u32,
+        object_name: String,
+        relinquish_default: Time,
+    ) -> Result<Self> {
+        let relinquish_default = check_time_pattern(relinquish_default)?;
+        let mut value = Self::new(instance, object_name);
+        value.commandable = Some(Commandable::new(relinquish_default));
+        value.present_value = relinquish_default;
+        Ok(value)
+    }
+
+    /// Whether Present_Value writes are arbitrated through a priority array
+    pub fn is_commandable(&self) -> bool {
+        self.commandable.is_some()
+    }
+
+    /// Set the present value after validating its fields
+    pub fn set_present_value(&mut self, value: Time) -> Result<()> {
+        self.present_value = check_time_pattern(value)?;
+        Ok(())
+    }
+
+    /// Write to priority array at specified priority level (1-16)
+    pub fn write_priority(&mut self, priority: u8, value: Option<Time>) -> Result<()> {
+        if !(1..=16).contains(&priority) {
+            return Err(ObjectError::InvalidValue(
+                "Priority must be 1-16".to_string(),
+            ));
+        }
+        let value = value.map(check_time_pattern).transpose()?;
+        let Some(commandable) = self.commandable.as_mut() else {
+            return Err(ObjectError::InvalidConfiguration(
+                "Time Pattern Value is not commandable".to_string(),
+            ));
+        };
+        commandable.write(priority, value)?;
+        self.update_present_value();
+        Ok(())
+    }
+
+    /// Update present value based on priority array
+    fn update_present_value(&mut self) {
+        if let Some(commandable) = self.commandable.as_ref() {
+            self.present_value = *commandable.current();
+        }
+    }
+
+    /// Check whether `time` matches the pattern in Present_Value
+    pub fn matches(&self, time: &Time) -> bool {
+        time_matches(&self.present_value, time)
+    }
+
+    /// Current Status_Flags, combining the stored flags with the state derived
+    /// from Event_State, Reliability and Out_Of_Service
+    pub fn current_status_flags(&self) -> u8 {
+        current_status_flags(
+            self.status_flags,
+            self.event_state,
+            self.reliability,
+            self.out_of_service,
+        )
+    }
+}
+
+impl BacnetObject for TimePatternValue {
+    fn identifier(&self) -> ObjectIdentifier {
+        self.identifier
+    }
+
+    fn get_property(&self, property: PropertyIdentifier) -> Result<PropertyValue> {
+        match property {
+            PropertyIdentifier::ObjectIdentifier => {
+                Ok(PropertyValue::ObjectIdentifier(self.identifier))
+            }
+            PropertyIdentifier::ObjectName => {
+                Ok(PropertyValue::CharacterString(self.object_name.clone()))
+            }
+            PropertyIdentifier::ObjectType => Ok(PropertyValue::Enumerated(u32::from(
+                ObjectType::TimePatternValue,
+            ))),
+            PropertyIdentifier::PresentValue => Ok(encode_time(self.present_value)),
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::StatusFlags => {
+                Ok(status_flags_bit_string(self.current_status_flags()))
+            }
+            PropertyIdentifier::EventState => {
+                Ok(PropertyValue::Enumerated(self.event_state as u32))
+            }
+            PropertyIdentifier::Reliability => {
+                Ok(PropertyValue::Enumerated(self.reliability as u32))
+            }
+            PropertyIdentifier::OutOfService => Ok(PropertyValue::Boolean(self.out_of_service)),
+            PropertyIdentifier::PriorityArray => self
+                .commandable
+                .as_ref()
+                .map(|commandable| commandable.priority_array_value(|&value| encode_time(value)))
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::RelinquishDefault => self
+                .commandable
+                .as_ref()
+                .map(|commandable| encode_time(*commandable.relinquish_default()))
+                .ok_or(ObjectError::UnknownProperty),
+            _ => Err(ObjectError::UnknownProperty),
+        }
+    }
+
+    fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        self.set_property_with_priority(property, value, DEFAULT_COMMAND_PRIORITY)
+    }
+
+    fn set_property_with_priority(
+        &mut self,
+        property: PropertyIdentifier,
+        value: PropertyValue,
+        priority: u8,
+    ) -> Result<()> {
+        match property {
+            PropertyIdentifier::ObjectName => {
+                if let PropertyValue::CharacterString(name) = value {
+                    self.object_name = name;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            // Writing NULL relinquishes the command at this priority
+            PropertyIdentifier::PresentValue
+                if self.is_commandable() && matches!(value, PropertyValue::Null) =>
+            {
+                self.write_priority(priority, None)
+            }
+            PropertyIdentifier::PresentValue => {
+                let val = decode_time(&value)?;
+                if self.is_commandable() {
+                    self.write_priority(priority, Some(val))
+                } else {
+                    self.set_present_value(val)
+                }
+            }
+            PropertyIdentifier::RelinquishDefault if self.is_commandable() => {
+                let default = check_time_pattern(decode_time(&value)?)?;
+                if let Some(commandable) = self.commandable.as_mut() {
+                    commandable.set_relinquish_default(default);
+                }
+                self.update_present_value();
+                Ok(())
+            }
+            PropertyIdentifier::OutOfService => {
+                if let PropertyValue::Boolean(oos) = value {
+                    self.out_of_service = oos;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            _ => Err(ObjectError::PropertyNotWritable),
+        }
+    }
+
+    fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
+        match property {
+            PropertyIdentifier::ObjectName
+            | PropertyIdentifier::Description
+            | PropertyIdentifier::PresentValue
+            | PropertyIdentifier::OutOfService => true,
+            PropertyIdentifier::RelinquishDefault => self.is_commandable(),
+            _ => false,
+        }
+    }
+
+    fn property_list(&self) -> Vec<PropertyIdentifier> {
+        let mut properties = vec![
+            PropertyIdentifier::ObjectIdentifier,
+            PropertyIdentifier::ObjectName,
+            PropertyIdentifier::ObjectType,
+            PropertyIdentifier::PresentValue,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::StatusFlags,
+            PropertyIdentifier::EventState,
+            PropertyIdentifier::Reliability,
+            PropertyIdentifier::OutOfService,
+        ];
+        if self.is_commandable() {
+            properties.push(PropertyIdentifier::PriorityArray);
+            properties.push(PropertyIdentifier::RelinquishDefault);
+        }
+        properties
+    }
+}
+
+/// DateTime Value object
+#[derive(Debug, Clone)]
+pub struct DateTimeValue {
+    /// Object identifier
+    pub identifier: ObjectIdentifier,
+    /// Object name
+    pub object_name: String,
+    /// Description
+    pub description: String,
+    /// Present value
+    pub present_value: BacnetDateTime,
+    /// Status flags
+    pub status_flags: u8,
+    /// Event state
+    pub event_state: EventState,
+    /// Reliability
+    pub reliability: Reliability,
+    /// Out of service
+    pub out_of_service: bool,
+    /// Priority array and relinquish default, present only on commandable
+    /// instances
+    pub commandable: Option<Commandable<BacnetDateTime>>,
+}
+
+impl DateTimeValue {
+    /// Create a new non-commandable DateTime Value object
+    pub fn new(instance: u32, object_name: String) -> Self {
+        Self {
+            identifier: ObjectIdentifier::new(ObjectType::DateTimeValue, instance),
+            object_name,
+            description: String::new(),
+            present_value: BacnetDateTime::unspecified(),
+            status_flags: 0,
+            event_state: EventState::Normal,
+            reliability: Reliability::NoFaultDetected,
+            out_of_service: false,
+            commandable: None,
+        }
+    }
+
+    /// Create a new commandable DateTime Value object with a priority array
+    pub fn new_commandable(
+        instance: u32,
+        object_name: String,
+        relinquish_default: BacnetDateTime,
+    ) -> Result<Self> {
+        let relinquish_default = check_date_time(relinquish_default)?;
+        let mut value = Self::new(instance, object_name);
+        value.commandable = Some(Commandable::new(relinquish_default));
+        value.present_value = relinquish_default;
+        Ok(value)
+    }
+
+    /// Whether Present_Value writes are arbitrated through a priority array
+    pub fn is_commandable(&self) -> bool {
+        self.commandable.is_some()
+    }
+
+    /// Set the present value after validating its fields
+    pub fn set_present_value(&mut self, value: BacnetDateTime) -> Result<()> {
+        self.present_value = check_date_time(value)?;
+        Ok(())
+    }
+
+    /// Write to priority array at specified priority level (1-16)
+    pub fn write_priority(&mut self, priority: u8, value: Option<BacnetDateTime>) -> Result<()> {
+        if !(1..=16).contains(&priority) {
+            return Err(ObjectError::InvalidValue(
+                "Priority must be 1-16".to_string(),
+            ));
+        }
+        let value = value.map(check_date_time).transpose()?;
+        let Some(commandable) = self.commandable.as_mut() else {
+            return Err(ObjectError::InvalidConfiguration(
+                "DateTime Value is not commandable".to_string(),
+            ));
+        };
+        commandable.write(priority, value)?;
+        self.update_present_value();
+        Ok(())
+    }
+
+    /// Update present value based on priority array
+    fn update_present_value(&mut self) {
+        if let Some(commandable) = self.commandable.as_ref() {
+            self.present_value = *commandable.current();
+        }
+    }
+
+    /// Current Status_Flags, combining the stored flags with the state derived
+    /// from Event_State, Reliability and Out_Of_Service
+    pub fn current_status_flags(&self) -> u8 {
+        current_status_flags(
+            self.status_flags,
+            self.event_state,
+            self.reliability,
+            self.out_of_service,
+        )
+    }
+}
+
+impl BacnetObject for DateTimeValue {
+    fn identifier(&self) -> ObjectIdentifier {
+        self.identifier
+    }
+
+    fn get_property(&self, property: PropertyIdentifier) -> Result<PropertyValue> {
+        match property {
+            PropertyIdentifier::ObjectIdentifier => {
+                Ok(PropertyValue::ObjectIdentifier(self.identifier))
+            }
+            PropertyIdentifier::ObjectName => {
+                Ok(PropertyValue::CharacterString(self.object_name.clone()))
+            }
+            PropertyIdentifier::ObjectType => Ok(PropertyValue::Enumerated(u32::from(
+                ObjectType::DateTimeValue,
+            ))),
+            PropertyIdentifier::PresentValue => Ok(encode_date_time(self.present_value)),
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::StatusFlags => {
+                Ok(status_flags_bit_string(self.current_status_flags()))
+            }
+            PropertyIdentifier::EventState => {
+                Ok(PropertyValue::Enumerated(self.event_state as u32))
+            }
+            PropertyIdentifier::Reliability => {
+                Ok(PropertyValue::Enumerated(self.reliability as u32))
+            }
+            PropertyIdentifier::OutOfService => Ok(PropertyValue::Boolean(self.out_of_service)),
+            PropertyIdentifier::PriorityArray => self
+                .commandable
+                .as_ref()
+                .map(|commandable| {
+                    commandable.priority_array_value(|&value| encode_date_time(value))
+                })
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::RelinquishDefault => self
+                .commandable
+                .as_ref()
+                .map(|commandable| encode_date_time(*commandable.relinquish_default()))
+                .ok_or(ObjectError::UnknownProperty),
+            _ => Err(ObjectError::UnknownProperty),
+        }
+    }
+
+    fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        self.set_property_with_priority(property, value, DEFAULT_COMMAND_PRIORITY)
+    }
+
+    fn set_property_with_priority(
+        &mut self,
+        property: PropertyIdentifier,
+        value: PropertyValue,
+        priority: u8,
+    ) -> Result<()> {
+        match property {
+            PropertyIdentifier::ObjectName => {
+                if let PropertyValue::CharacterString(name) = value {
+                    self.object_name = name;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            // Writing NULL relinquishes the command at this priority
+            PropertyIdentifier::PresentValue
+                if self.is_commandable() && matches!(value, PropertyValue::Null) =>
+            {
+                self.write_priority(priority, None)
+            }
+            PropertyIdentifier::PresentValue => {
+                let val = date_time_from_value(&value)?;
+                if self.is_commandable() {
+                    self.write_priority(priority, Some(val))
+                } else {
+                    self.set_present_value(val)
+                }
+            }
+            PropertyIdentifier::RelinquishDefault if self.is_commandable() => {
+                let default = check_date_time(date_time_from_value(&value)?)?;
+                if let Some(commandable) = self.commandable.as_mut() {
+                    commandable.set_relinquish_default(default);
+                }
+                self.update_present_value();
+                Ok(())
+            }
+            PropertyIdentifier::OutOfService => {
+                if let PropertyValue::Boolean(oos) = value {
+                    self.out_of_service = oos;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            _ => Err(ObjectError::PropertyNotWritable),
+        }
+    }
+
+    fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
+        match property {
+            PropertyIdentifier::ObjectName
+            | PropertyIdentifier::Description
+            | PropertyIdentifier::PresentValue
+            | PropertyIdentifier::OutOfService => true,
+            PropertyIdentifier::RelinquishDefault => self.is_commandable(),
+            _ => false,
+        }
+    }
+
+    fn property_list(&self) -> Vec<PropertyIdentifier> {
+        let mut properties = vec![
+            PropertyIdentifier::ObjectIdentifier,
+            PropertyIdentifier::ObjectName,
+            PropertyIdentifier::ObjectType,
+            PropertyIdentifier::PresentValue,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::StatusFlags,
+            PropertyIdentifier::EventState,
+            PropertyIdentifier::Reliability,
+            PropertyIdentifier::OutOfService,
+        ];
+        if self.is_commandable() {
+            properties.push(PropertyIdentifier::PriorityArray);
+            properties.push(PropertyIdentifier::RelinquishDefault);
+        }
+        properties
+    }
+}
+
+/// DateTime Pattern Value object
+#[derive(Debug, Clone)]
+pub struct DateTimePatternValue {
+    /// Object identifier
+    pub identifier: ObjectIdentifier,
+    /// Object name
+    pub object_name: String,
+    /// Description
+    pub description: String,
+    /// Present value
+    pub present_value: BacnetDateTime,
+    /// Status flags
+    pub status_flags: u8,
+    /// Event state
+    pub event_state: EventState,
+    /// Reliability
+    pub reliability: Reliability,
+    /// Out of service
+    pub out_of_service: bool,
+    /// Priority array and relinquish default, present only on commandable
+    /// instances
+    pub commandable: Option<Commandable<BacnetDateTime>>,
+}
+
+impl DateTimePatternValue {
+    /// Create a new non-commandable DateTime Pattern Value object
+    pub fn new(instance: u32, object_name: String) -> Self {
+        Self {
+            identifier: ObjectIdentifier::new(ObjectType::DateTimePatternValue, instance),
+            object_name,
+            description: String::new(),
+            present_value: BacnetDateTime::unspecified(),
+            status_flags: 0,
+            event_state: EventState::Normal,
+            reliability: Reliability::NoFaultDetected,
+            out_of_service: false,
+            commandable: None,
+        }
+    }
+
+    /// Create a new commandable DateTime Pattern Value object with a priority array
+    pub fn new_commandable(
+        instance: u32,
+        object_name: String,
+        relinquish_default: BacnetDateTime,
+    ) -> Result<Self> {
+        let relinquish_default = check_date_time_pattern(relinquish_default)?;
+        let mut value = Self::new(instance, object_name);
+        value.commandable = Some(Commandable::new(relinquish_default));
+        value.present_value = relinquish_default;
+        Ok(value)
+    }
+
+    /// Whether Present_Value writes are arbitrated through a priority array
+    pub fn is_commandable(&self) -> bool {
+        self.commandable.is_some()
+    }
+
+    /// Set the present value after validating its fields
+    pub fn set_present_value(&mut self, value: BacnetDateTime) -> Result<()> {
+        self.present_value = check_date_time_pattern(value)?;
+        Ok(())
+    }
+
+    /// Write to priority array at specified priority level (1-16)
+    pub fn write_priority(&mut self, priority: u8, value: Option<BacnetDateTime>) -> Result<()> {
+        if !(1..=16).contains(&priority) {
+            return Err(ObjectError::InvalidValue(
+                "Priority must be 1-16".to_string(),
+            ));
+        }
+        let value = value.map(check_date_time_pattern).transpose()?;
+        let Some(commandable) = self.commandable.as_mut() else {
+            return Err(ObjectError::InvalidConfiguration(
+                "DateTime Pattern Value is not commandable".to_string(),
+            ));
+        };
+        commandable.write(priority, value)?;
+        self.update_present_value();
+        Ok(())
+    }
+
+    /// Update present value based on priority array
+    fn update_present_value(&mut self) {
+        if let Some(commandable) = self.commandable.as_ref() {
+            self.present_value = *commandable.current();
+        }
+    }
+
+    /// Check whether `date_time` matches the pattern in Present_Value
+    pub fn matches(&self, date_time: &BacnetDateTime) -> bool {
+        date_matches(&self.present_value.date, &date_time.date)
+            && time_matches(&self.present_value.time, &date_time.time)
+    }
+
+    /// Current Status_Flags, combining the stored flags with the state derived
+    /// from Event_State, Reliability and Out_Of_Service
+    pub fn current_status_flags(&self) -> u8 {
+        current_status_flags(
+            self.status_flags,
+            self.event_state,
+            self.reliability,
+            self.out_of_service,
+        )
+    }
+}
+
+impl BacnetObject for DateTimePatternValue {
+    fn identifier(&self) -> ObjectIdentifier {
+        self.identifier
+    }
+
+    fn get_property(&self, property: PropertyIdentifier) -> Result<PropertyValue> {
+        match property {
+            PropertyIdentifier::ObjectIdentifier => {
+                Ok(PropertyValue::ObjectIdentifier(self.identifier))
+            }
+            PropertyIdentifier::ObjectName => {
+                Ok(PropertyValue::CharacterString(self.object_name.clone()))
+            }
+            PropertyIdentifier::ObjectType => Ok(PropertyValue::Enumerated(u32::from(
+                ObjectType::DateTimePatternValue,
+            ))),
+            PropertyIdentifier::PresentValue => Ok(encode_date_time(self.present_value)),
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::StatusFlags => {
+                Ok(status_flags_bit_string(self.current_status_flags()))
+            }
+            PropertyIdentifier::EventState => {
+                Ok(PropertyValue::Enumerated(self.event_state as u32))
+            }
+            PropertyIdentifier::Reliability => {
+                Ok(PropertyValue::Enumerated(self.reliability as u32))
+            }
+            PropertyIdentifier::OutOfService => Ok(PropertyValue::Boolean(self.out_of_service)),
+            PropertyIdentifier::PriorityArray => self
+                .commandable
+                .as_ref()
+                .map(|commandable| {
+                    commandable.priority_array_value(|&value| encode_date_time(value))
+                })
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::RelinquishDefault => self
+                .commandable
+                .as_ref()
+                .map(|commandable| encode_date_time(*commandable.relinquish_default()))
+                .ok_or(ObjectError::UnknownProperty),
+            _ => Err(ObjectError::UnknownProperty),
+        }
+    }
+
+    fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        self.set_property_with_priority(property, value, DEFAULT_COMMAND_PRIORITY)
+    }
+
+    fn set_property_with_priority(
+        &mut self,
+        property: PropertyIdentifier,
+        value: PropertyValue,
+        priority: u8,
+    ) -> Result<()> {
+        match property {
+            PropertyIdentifier::ObjectName => {
+                if let PropertyValue::CharacterString(name) = value {
+                    self.object_name = name;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            // Writing NULL relinquishes the command at this priority
+            PropertyIdentifier::PresentValue
+                if self.is_commandable() && matches!(value, PropertyValue::Null) =>
+            {
+                self.write_priority(priority, None)
+            }
+            PropertyIdentifier::PresentValue => {
+                let val = date_time_from_value(&value)?;
+                if self.is_commandable() {
+                    self.write_priority(priority, Some(val))
+                } else {
+                    self.set_present_value(val)
+                }
+            }
+            PropertyIdentifier::RelinquishDefault if self.is_commandable() => {
+                let default = check_date_time_pattern(date_time_from_value(&value)?)?;
+                if let Some(commandable) = self.commandable.as_mut() {
+                    commandable.set_relinquish_default(default);
+                }
+                self.update_present_value();
+                Ok(())
+            }
+            PropertyIdentifier::OutOfService => {
+                if let PropertyValue::Boolean(oos) = value {
+                    self.out_of_service = oos;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            _ => Err(ObjectError::PropertyNotWritable),
+        }
+    }
+
+    fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
+        match property {
+            PropertyIdentifier::ObjectName
+            | PropertyIdentifier::Description
+            | PropertyIdentifier::PresentValue
+            | PropertyIdentifier::OutOfService => true,
+            PropertyIdentifier::RelinquishDefault => self.is_commandable(),
+            _ => false,
+        }
+    }
+
+    fn property_list(&self) -> Vec<PropertyIdentifier> {
+        let mut properties = vec![
+            PropertyIdentifier::ObjectIdentifier,
+            PropertyIdentifier::ObjectName,
+            PropertyIdentifier::ObjectType,
+            PropertyIdentifier::PresentValue,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::StatusFlags,
+            PropertyIdentifier::EventState,
+            PropertyIdentifier::Reliability,
+            PropertyIdentifier::OutOfService,
+        ];
+        if self.is_commandable() {
+            properties.push(PropertyIdentifier::PriorityArray);
+            properties.push(PropertyIdentifier::RelinquishDefault);
+        }
+        properties
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: u16, month: u8, day: u8, weekday: u8) -> Date {
+        Date {
+            year,
+            month,
+            day,
+            weekday,
+        }
+    }
+
+    fn time(hour: u8, minute: u8) -> Time {
+        Time {
+            hour,
+            minute,
+            second: 0,
+            hundredths: 0,
+        }
+    }
+
+    #[test]
+    fn test_date_value_rejects_wildcards() {
+        let mut dv = DateValue::new(1, "Commissioned".to_string());
+        assert_eq!(dv.present_value, UNSPECIFIED_DATE);
+
+        // The weekday is derived from the date (2024-07-15 was a Monday)
+        dv.set_property(
+            PropertyIdentifier::PresentValue,
+            PropertyValue::Date(date(2024, 7, 15, UNSPECIFIED)),
+        )
+        .unwrap();
+        assert_eq!(dv.present_value.weekday, 1);
+
+        for pattern in [
+            date(2024, UNSPECIFIED, 15, UNSPECIFIED),
+            date(2024, 13, 15, UNSPECIFIED),
+            date(2024, 2, 30, UNSPECIFIED),
+            date(2024, 7, 15, 3),
+        ] {
+            assert!(matches!(
+                dv.set_property(
+                    PropertyIdentifier::PresentValue,
+                    PropertyValue::Date(pattern)
+                ),
+                Err(ObjectError::InvalidValue(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_pattern_values_match() {
+        let mut dpv = DatePatternValue::new(1, "Fridays".to_string());
+        dpv.set_present_value(date(UNSPECIFIED as u16, UNSPECIFIED, UNSPECIFIED, 5))
+            .unwrap();
+        assert!(dpv.matches(&date(2024, 7, 19, 5)));
+        assert!(!dpv.matches(&date(2024, 7, 18, 4)));
+        assert!(dpv
+            .set_present_value(date(2024, 15, 1, UNSPECIFIED))
+            .is_err());
+
+        let mut tpv = TimePatternValue::new(1, "Top of hour".to_string());
+        tpv.set_present_value(Time {
+            hour: UNSPECIFIED,
+            ..time(0, 0)
+        })
+        .unwrap();
+        assert!(tpv.matches(&time(14, 0)));
+        assert!(!tpv.matches(&time(14, 30)));
+
+        let mut dtpv = DateTimePatternValue::new(1, "Monthly".to_string());
+        dtpv.set_present_value(BacnetDateTime::new(
+            date(UNSPECIFIED as u16, UNSPECIFIED, 32, UNSPECIFIED),
+            time(23, 0),
+        ))
+        .unwrap();
+        assert!(dtpv.matches(&BacnetDateTime::new(date(2024, 2, 29, 4), time(23, 0))));
+    }
+
+    #[test]
+    fn test_date_time_value_commandable() {
+        let start = BacnetDateTime::new(date(2024, 1, 1, 1), time(8, 0));
+        let mut dtv = DateTimeValue::new_commandable(1, "Start".to_string(), start).unwrap();
+        let later = BacnetDateTime::new(date(2024, 1, 2, UNSPECIFIED), time(9, 30));
+        dtv.set_property_with_priority(
+            PropertyIdentifier::PresentValue,
+            date_time_value(Some(later)),
+            8,
+        )
+        .unwrap();
+        assert_eq!(dtv.present_value.date.weekday, 2);
+        assert_eq!(dtv.present_value.time, time(9, 30));
+
+        dtv.set_property_with_priority(PropertyIdentifier::PresentValue, PropertyValue::Null, 8)
+            .unwrap();
+        assert_eq!(dtv.present_value, start);
+
+        let partial = BacnetDateTime::new(date(2024, 1, 2, UNSPECIFIED), UNSPECIFIED_TIME);
+        assert!(dtv.write_priority(8, Some(partial)).is_err());
+
+        let mut tv = TimeValue::new(1, "Alarm Time".to_string());
+        assert!(tv
+            .set_present_value(Time {
+                minute: UNSPECIFIED,
+                ..time(6, 0)
+            })
+            .is_err());
+    }
+}
@@ -0,0 +1,1091 @@
+//! Elevator Object Type Implementations
+//!
+//! This module implements the vertical transport object types as defined in
+//! ASHRAE 135:
+//!
+//! - **Elevator Group**: a group of lifts or escalators sharing a machine room,
+//!   holding the group's landing calls
+//! - **Lift**: a single elevator car with its position, direction, door status
+//!   per car door and registered car calls per deck
+//! - **Escalator**: a single escalator or moving walkway
+//!
+//! The objects mirror the state of the elevator controller. The application
+//! updates car position, doors and faults as the controller reports them; calls
+//! written by clients through Landing_Call_Control and Making_Car_Call are
+//! recorded in Landing_Calls and Registered_Car_Call until the application
+//! reports them served.
+
+use crate::object::{
+    access_control::DoorStatus, current_status_flags, engineering_units::EngineeringUnits,
+    status_flags_bit_string, BacnetObject, EventState, ObjectError, ObjectIdentifier, ObjectType,
+    PropertyIdentifier, PropertyValue, Reliability, Result,
+};
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+/// Operating mode of an elevator group (BACnetLiftGroupMode)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum LiftGroupMode {
+    Unknown = 0,
+    Normal = 1,
+    DownPeak = 2,
+    TwoWay = 3,
+    FourWay = 4,
+    EmergencyPower = 5,
+    UpPeak = 6,
+}
+
+/// Direction of a lift car or landing call (BACnetLiftCarDirection)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum LiftCarDirection {
+    Unknown = 0,
+    None = 1,
+    Stopped = 2,
+    Up = 3,
+    Down = 4,
+    UpAndDown = 5,
+}
+
+impl TryFrom<u32> for LiftCarDirection {
+    type Error = ObjectError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            0 => Ok(LiftCarDirection::Unknown),
+            1 => Ok(LiftCarDirection::None),
+            2 => Ok(LiftCarDirection::Stopped),
+            3 => Ok(LiftCarDirection::Up),
+            4 => Ok(LiftCarDirection::Down),
+            5 => Ok(LiftCarDirection::UpAndDown),
+            _ => Err(ObjectError::InvalidValue(format!(
+                "Invalid lift car direction: {}",
+                value
+            ))),
+        }
+    }
+}
+
+/// Command for a lift car door (BACnetLiftCarDoorCommand)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum LiftCarDoorCommand {
+    None = 0,
+    Open = 1,
+    Close = 2,
+}
+
+impl TryFrom<u32> for LiftCarDoorCommand {
+    type Error = ObjectError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            0 => Ok(LiftCarDoorCommand::None),
+            1 => Ok(LiftCarDoorCommand::Open),
+            2 => Ok(LiftCarDoorCommand::Close),
+            _ => Err(ObjectError::InvalidValue(format!(
+                "Invalid lift car door command: {}",
+                value
+            ))),
+        }
+    }
+}
+
+/// Operating mode of a lift car (BACnetLiftCarMode)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum LiftCarMode {
+    Unknown = 0,
+    Normal = 1,
+    Vip = 2,
+    Homing = 3,
+    Parking = 4,
+    AttendantControl = 5,
+    FirefighterControl = 6,
+    EmergencyPower = 7,
+    Inspection = 8,
+    CabinetRecall = 9,
+    EarthquakeOperation = 10,
+    FireOperation = 11,
+    OutOfService = 12,
+    OccupantEvacuation = 13,
+}
+
+/// State of a lift car's drive (BACnetLiftCarDriveStatus)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum LiftCarDriveStatus {
+    Unknown = 0,
+    Stationary = 1,
+    Braking = 2,
+    Accelerate = 3,
+    Decelerate = 4,
+    RatedSpeed = 5,
+    SingleFloorJump = 6,
+    TwoFloorJump = 7,
+    ThreeFloorJump = 8,
+    MultiFloorJump = 9,
+}
+
+/// Operating mode of an escalator (BACnetEscalatorMode)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum EscalatorMode {
+    Unknown = 0,
+    Stop = 1,
+    Up = 2,
+    Down = 3,
+    Inspection = 4,
+    OutOfService = 5,
+}
+
+/// Current movement of an escalator (BACnetEscalatorOperationDirection)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum EscalatorOperationDirection {
+    Unknown = 0,
+    Stopped = 1,
+    UpRatedSpeed = 2,
+    UpReducedSpeed = 3,
+    DownRatedSpeed = 4,
+    DownReducedSpeed = 5,
+}
+
+/// What a landing call asks for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LandingCallCommand {
+    /// Hall call for travel in a direction
+    Direction(LiftCarDirection),
+    /// Destination call for a floor
+    Destination(u8),
+}
+
+/// A landing call (BACnetLandingCallStatus)
+///
+/// Encoded as `List[Unsigned(floor), List[Unsigned(choice), value]]` with
+/// choice 1 for a direction and 2 for a destination floor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LandingCall {
+    /// Floor the call was made at
+    pub floor_number: u8,
+    /// Requested direction or destination
+    pub command: LandingCallCommand,
+}
+
+impl LandingCall {
+    /// Encode as a property value
+    pub fn to_property_value(&self) -> PropertyValue {
+        let (choice, value) = match self.command {
+            LandingCallCommand::Direction(direction) => {
+                (1, PropertyValue::Enumerated(direction as u32))
+            }
+            LandingCallCommand::Destination(floor) => {
+                (2, PropertyValue::UnsignedInteger(floor as u32))
+            }
+        };
+        PropertyValue::List(vec![
+            PropertyValue::UnsignedInteger(self.floor_number as u32),
+            PropertyValue::List(vec![PropertyValue::UnsignedInteger(choice), value]),
+        ])
+    }
+
+    /// Decode from a property value
+    pub fn from_property_value(value: &PropertyValue) -> Result<Self> {
+        let PropertyValue::List(items) = value else {
+            return Err(ObjectError::InvalidPropertyType);
+        };
+        let [PropertyValue::UnsignedInteger(floor), PropertyValue::List(command)] =
+            items.as_slice()
+        else {
+            return Err(ObjectError::InvalidPropertyType);
+        };
+        let command = match command.as_slice() {
+            [PropertyValue::UnsignedInteger(1), PropertyValue::Enumerated(direction)] => {
+                LandingCallCommand::Direction(LiftCarDirection::try_from(*direction)?)
+            }
+            [PropertyValue::UnsignedInteger(2), PropertyValue::UnsignedInteger(destination)] => {
+                LandingCallCommand::Destination(floor_number(*destination)?)
+            }
+            _ => return Err(ObjectError::InvalidPropertyType),
+        };
+        Ok(Self {
+            floor_number: floor_number(*floor)?,
+            command,
+        })
+    }
+}
+
+/// Status of the landing door at one floor (BACnetLandingDoorStatus entry)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LandingDoor {
+    /// Floor number
+    pub floor_number: u8,
+    /// Door status
+    pub door_status: DoorStatus,
+}
+
+fn floor_number(value: u32) -> Result<u8> {
+    u8::try_from(value)
+        .map_err(|_| ObjectError::InvalidValue(format!("Invalid floor number: {}", value)))
+}
+
+fn unsigned_array(values: &[u8]) -> PropertyValue {
+    PropertyValue::Array(
+        values
+            .iter()
+            .map(|v| PropertyValue::UnsignedInteger(*v as u32))
+            .collect(),
+    )
+}
+
+fn text_array(values: &[String]) -> PropertyValue {
+    PropertyValue::Array(
+        values
+            .iter()
+            .map(|text| PropertyValue::CharacterString(text.clone()))
+            .collect(),
+    )
+}
+
+fn enumerated_list(values: &[u32]) -> PropertyValue {
+    PropertyValue::List(
+        values
+            .iter()
+            .map(|v| PropertyValue::Enumerated(*v))
+            .collect(),
+    )
+}
+
+/// Elevator Group object
+#[derive(Debug, Clone)]
+pub struct ElevatorGroup {
+    /// Object identifier
+    pub identifier: ObjectIdentifier,
+    /// Object name
+    pub object_name: String,
+    /// Description
+    pub description: String,
+    /// Machine room the group is controlled from (a Structured View)
+    pub machine_room_id: ObjectIdentifier,
+    /// Group number within the machine room
+    pub group_id: u8,
+    /// Lift or Escalator objects in the group
+    pub group_members: Vec<ObjectIdentifier>,
+    /// Group operating mode, present only on groups of lifts
+    pub group_mode: Option<LiftGroupMode>,
+    /// Landing calls not yet served
+    pub landing_calls: Vec<LandingCall>,
+    /// Last landing call written through Landing_Call_Control
+    pub landing_call_control: Option<LandingCall>,
+}
+
+impl ElevatorGroup {
+    /// Create a new Elevator Group object
+    pub fn new(instance: u32, object_name: String, machine_room_id: ObjectIdentifier) -> Self {
+        Self {
+            identifier: ObjectIdentifier::new(ObjectType::ElevatorGroup, instance),
+            object_name,
+            description: String::new(),
+            machine_room_id,
+            group_id: 0,
+            group_members: Vec::new(),
+            group_mode: None,
+            landing_calls: Vec::new(),
+            landing_call_control: None,
+        }
+    }
+
+    /// Add a Lift or Escalator to the group
+    pub fn add_member(&mut self, member: ObjectIdentifier) -> Result<()> {
+        if !matches!(member.object_type, ObjectType::Lift | ObjectType::Escalator) {
+            return Err(ObjectError::InvalidValue(
+                "Elevator group members must be Lift or Escalator objects".to_string(),
+            ));
+        }
+        if let Some(first) = self.group_members.first() {
+            if first.object_type != member.object_type {
+                return Err(ObjectError::InvalidValue(
+                    "Elevator group members must all be the same type".to_string(),
+                ));
+            }
+        }
+        if !self.group_members.contains(&member) {
+            self.group_members.push(member);
+        }
+        if member.object_type == ObjectType::Lift && self.group_mode.is_none() {
+            self.group_mode = Some(LiftGroupMode::Unknown);
+        }
+        Ok(())
+    }
+
+    /// Record a landing call, as a Landing_Call_Control write does
+    pub fn register_landing_call(&mut self, call: LandingCall) {
+        self.landing_call_control = Some(call);
+        if !self.landing_calls.contains(&call) {
+            self.landing_calls.push(call);
+        }
+    }
+
+    /// Remove a landing call once a car has served it
+    ///
+    /// Returns false if the call was not registered.
+    pub fn serve_landing_call(&mut self, call: &LandingCall) -> bool {
+        let before = self.landing_calls.len();
+        self.landing_calls.retain(|c| c != call);
+        self.landing_calls.len() != before
+    }
+}
+
+impl BacnetObject for ElevatorGroup {
+    fn identifier(&self) -> ObjectIdentifier {
+        self.identifier
+    }
+
+    fn get_property(&self, property: PropertyIdentifier) -> Result<PropertyValue> {
+        match property {
+            PropertyIdentifier::ObjectIdentifier => {
+                Ok(PropertyValue::ObjectIdentifier(self.identifier))
+            }
+            PropertyIdentifier::ObjectName => {
+                Ok(PropertyValue::CharacterString(self.object_name.clone()))
+            }
+            PropertyIdentifier::ObjectType => Ok(PropertyValue::Enumerated(u32::from(
+                ObjectType::ElevatorGroup,
+            ))),
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::MachineRoomId => {
+                Ok(PropertyValue::ObjectIdentifier(self.machine_room_id))
+            }
+            PropertyIdentifier::GroupId => Ok(PropertyValue::UnsignedInteger(self.group_id as u32)),
+            PropertyIdentifier::GroupMembers => Ok(PropertyValue::Array(
+                self.group_members
+                    .iter()
+                    .map(|member| PropertyValue::ObjectIdentifier(*member))
+                    .collect(),
+            )),
+            PropertyIdentifier::GroupMode => self
+                .group_mode
+                .map(|mode| PropertyValue::Enumerated(mode as u32))
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::LandingCalls => Ok(PropertyValue::List(
+                self.landing_calls
+                    .iter()
+                    .map(LandingCall::to_property_value)
+                    .collect(),
+            )),
+            PropertyIdentifier::LandingCallControl => Ok(self
+                .landing_call_control
+                .map(|call| call.to_property_value())
+                .unwrap_or(PropertyValue::Null)),
+            _ => Err(ObjectError::UnknownProperty),
+        }
+    }
+
+    fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        match property {
+            PropertyIdentifier::ObjectName => {
+                if let PropertyValue::CharacterString(name) = value {
+                    self.object_name = name;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::LandingCallControl => {
+                self.register_landing_call(LandingCall::from_property_value(&value)?);
+                Ok(())
+            }
+            _ => Err(ObjectError::PropertyNotWritable),
+        }
+    }
+
+    fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
+        matches!(
+            property,
+            PropertyIdentifier::ObjectName
+                | PropertyIdentifier::Description
+                | PropertyIdentifier::LandingCallControl
+        )
+    }
+
+    fn property_list(&self) -> Vec<PropertyIdentifier> {
+        let mut properties = vec![
+            PropertyIdentifier::ObjectIdentifier,
+            PropertyIdentifier::ObjectName,
+            PropertyIdentifier::ObjectType,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::MachineRoomId,
+            PropertyIdentifier::GroupId,
+            PropertyIdentifier::GroupMembers,
+        ];
+        if self.group_mode.is_some() {
+            properties.push(PropertyIdentifier::GroupMode);
+        }
+        properties.extend([
+            PropertyIdentifier::LandingCalls,
+            PropertyIdentifier::LandingCallControl,
+        ]);
+        properties
+    }
+}
+
+/// Lift object
+#[derive(Debug, Clone)]
+pub struct Lift {
+    /// Object identifier
+    pub identifier: ObjectIdentifier,
+    /// Object name
+    pub object_name: String,
+    /// Description
+    pub description: String,
+    /// Status flags
+    pub status_flags: u8,
+    /// Event state
+    pub event_state: EventState,
+    /// Reliability
+    pub reliability: Reliability,
+    /// Out of service
+    pub out_of_service: bool,
+    /// Elevator Group the lift belongs to
+    pub elevator_group: Option<ObjectIdentifier>,
+    /// Group number within the machine room
+    pub group_id: u8,
+    /// Installation number within the group
+    pub installation_id: u8,
+    /// Text for each floor, indexed by floor number
+    pub floor_text: Vec<String>,
+    /// Floor the car is at
+    pub car_position: u8,
+    /// Direction the car is moving in
+    pub car_moving_direction: LiftCarDirection,
+    /// Direction the car is assigned to travel in
+    pub car_assigned_direction: LiftCarDirection,
+    /// Status of each car door
+    pub car_door_status: Vec<DoorStatus>,
+    /// Last command for each car door
+    pub car_door_command: Vec<LiftCarDoorCommand>,
+    /// Whether the car is within the door zone of a floor
+    pub car_door_zone: bool,
+    /// Car operating mode
+    pub car_mode: LiftCarMode,
+    /// Current load of the car
+    pub car_load: Option<f32>,
+    /// Units of Car_Load
+    pub car_load_units: EngineeringUnits,
+    /// Next floor the car will stop at
+    pub next_stopping_floor: Option<u8>,
+    /// Landing door status at each floor, per car door
+    pub landing_door_status: Vec<Vec<LandingDoor>>,
+    /// Last car call made through Making_Car_Call, per deck
+    pub making_car_call: Vec<u8>,
+    /// Car calls not yet served, per deck
+    pub registered_car_call: Vec<Vec<u8>>,
+    /// Whether the passenger alarm is active
+    pub passenger_alarm: bool,
+    /// Active faults (BACnetLiftFault values)
+    pub fault_signals: Vec<u32>,
+    /// Drive state
+    pub car_drive_status: LiftCarDriveStatus,
+    /// Energy consumed, in kilowatt-hours (optional property)
+    pub energy_meter: Option<f32>,
+}
+
+impl Lift {
+    /// Create a new Lift object with the given number of decks and car doors
+    pub fn new(instance: u32, object_name: String, decks: usize, car_doors: usize) -> Self {
+        Self {
+            identifier: ObjectIdentifier::new(ObjectType::Lift, instance),
+            object_name,
+            description: String::new(),
+            status_flags: 0,
+            event_state: EventState::Normal,
+            reliability: Reliability::NoFaultDetected,
+            out_of_service: false,
+            elevator_group: None,
+            group_id: 0,
+            installation_id: 0,
+            floor_text: Vec::new(),
+            car_position: 0,
+            car_moving_direction: LiftCarDirection::Stopped,
+            car_assigned_direction: LiftCarDirection::None,
+            car_door_status: vec![DoorStatus::Closed; car_doors],
+            car_door_command: vec![LiftCarDoorCommand::None; car_doors],
+            car_door_zone: false,
+            car_mode: LiftCarMode::Normal,
+            car_load: None,
+            car_load_units: EngineeringUnits::Kilograms,
+            next_stopping_floor: None,
+            landing_door_status: vec![Vec::new(); car_doors],
+            making_car_call: vec![0; decks],
+            registered_car_call: vec![Vec::new(); decks],
+            passenger_alarm: false,
+            fault_signals: Vec::new(),
+            car_drive_status: LiftCarDriveStatus::Stationary,
+            energy_meter: None,
+        }
+    }
+
+    /// Register a car call for a floor on a deck (0-based)
+    pub fn register_car_call(&mut self, deck: usize, floor: u8) -> Result<()> {
+        let calls = self
+            .registered_car_call
+            .get_mut(deck)
+            .ok_or_else(|| ObjectError::InvalidValue(format!("Invalid deck: {}", deck)))?;
+        if !calls.contains(&floor) {
+            calls.push(floor);
+        }
+        self.making_car_call[deck] = floor;
+        Ok(())
+    }
+
+    /// Record the car stopping at a floor, clearing the car calls it serves
+    pub fn arrive_at(&mut self, floor: u8) {
+        self.car_position = floor;
+        self.car_moving_direction = LiftCarDirection::Stopped;
+        self.car_drive_status = LiftCarDriveStatus::Stationary;
+        for calls in &mut self.registered_car_call {
+            calls.retain(|f| *f != floor);
+        }
+        if self.next_stopping_floor == Some(floor) {
+            self.next_stopping_floor = None;
+        }
+    }
+
+    /// Current Status_Flags, combining the stored flags with the state derived
+    /// from Event_State, Reliability and Out_Of_Service
+    pub fn current_status_flags(&self) -> u8 {
+        current_status_flags(
+            self.status_flags,
+            self.event_state,
+            self.reliability,
+            self.out_of_service,
+        )
+    }
+}
+
+impl BacnetObject for Lift {
+    fn identifier(&self) -> ObjectIdentifier {
+        self.identifier
+    }
+
+    fn get_property(&self, property: PropertyIdentifier) -> Result<PropertyValue> {
+        match property {
+            PropertyIdentifier::ObjectIdentifier => {
+                Ok(PropertyValue::ObjectIdentifier(self.identifier))
+            }
+            PropertyIdentifier::ObjectName => {
+                Ok(PropertyValue::CharacterString(self.object_name.clone()))
+            }
+            PropertyIdentifier::ObjectType => {
+                Ok(PropertyValue::Enumerated(u32::from(ObjectType::Lift)))
+            }
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::StatusFlags => {
+                Ok(status_flags_bit_string(self.current_status_flags()))
+            }
+            PropertyIdentifier::EventState => {
+                Ok(PropertyValue::Enumerated(self.event_state as u32))
+            }
+            PropertyIdentifier::Reliability => {
+                Ok(PropertyValue::Enumerated(self.reliability as u32))
+            }
+            PropertyIdentifier::OutOfService => Ok(PropertyValue::Boolean(self.out_of_service)),
+            PropertyIdentifier::ElevatorGroup => self
+                .elevator_group
+                .map(PropertyValue::ObjectIdentifier)
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::GroupId => Ok(PropertyValue::UnsignedInteger(self.group_id as u32)),
+            PropertyIdentifier::InstallationId => {
+                Ok(PropertyValue::UnsignedInteger(self.installation_id as u32))
+            }
+            PropertyIdentifier::FloorText => Ok(text_array(&self.floor_text)),
+            PropertyIdentifier::CarPosition => {
+                Ok(PropertyValue::UnsignedInteger(self.car_position as u32))
+            }
+            PropertyIdentifier::CarMovingDirection => {
+                Ok(PropertyValue::Enumerated(self.car_moving_direction as u32))
+            }
+            PropertyIdentifier::CarAssignedDirection => Ok(PropertyValue::Enumerated(
+                self.car_assigned_direction as u32,
+            )),
+            PropertyIdentifier::CarDoorStatus => Ok(PropertyValue::Array(
+                self.car_door_status
+                    .iter()
+                    .map(|status| PropertyValue::Enumerated(*status as u32))
+                    .collect(),
+            )),
+            PropertyIdentifier::CarDoorCommand => Ok(PropertyValue::Array(
+                self.car_door_command
+                    .iter()
+                    .map(|command| PropertyValue::Enumerated(*command as u32))
+                    .collect(),
+            )),
+            PropertyIdentifier::CarDoorZone => Ok(PropertyValue::Boolean(self.car_door_zone)),
+            PropertyIdentifier::CarMode => Ok(PropertyValue::Enumerated(self.car_mode as u32)),
+            PropertyIdentifier::CarLoad => self
+                .car_load
+                .map(PropertyValue::Real)
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::CarLoadUnits if self.car_load.is_some() => {
+                Ok(PropertyValue::Enumerated(self.car_load_units.to_u32()))
+            }
+            PropertyIdentifier::NextStoppingFloor => Ok(self
+                .next_stopping_floor
+                .map(|floor| PropertyValue::UnsignedInteger(floor as u32))
+                .unwrap_or(PropertyValue::Null)),
+            PropertyIdentifier::LandingDoorStatus => Ok(PropertyValue::Array(
+                self.landing_door_status
+                    .iter()
+                    .map(|doors| {
+                        PropertyValue::List(
+                            doors
+                                .iter()
+                                .map(|door| {
+                                    PropertyValue::List(vec![
+                                        PropertyValue::UnsignedInteger(door.floor_number as u32),
+                                        PropertyValue::Enumerated(door.door_status as u32),
+                                    ])
+                                })
+                                .collect(),
+                        )
+                    })
+                    .collect(),
+            )),
+            PropertyIdentifier::MakingCarCall => Ok(unsigned_array(&self.making_car_call)),
+            PropertyIdentifier::RegisteredCarCall => Ok(PropertyValue::Array(
+                self.registered_car_call
+                    .iter()
+                    .map(|calls| {
+                        PropertyValue::List(
+                            calls
+                                .iter()
+                                .map(|floor| PropertyValue::UnsignedInteger(*floor as u32))
+                                .collect(),
+                        )
+                    })
+                    .collect(),
+            )),
+            PropertyIdentifier::PassengerAlarm => Ok(PropertyValue::Boolean(self.passenger_alarm)),
+            PropertyIdentifier::FaultSignals => Ok(enumerated_list(&self.fault_signals)),
+            PropertyIdentifier::CarDriveStatus => {
+                Ok(PropertyValue::Enumerated(self.car_drive_status as u32))
+            }
+            PropertyIdentifier::EnergyMeter => self
+                .energy_meter
+                .map(PropertyValue::Real)
+                .ok_or(ObjectError::UnknownProperty),
+            _ => Err(ObjectError::UnknownProperty),
+        }
+    }
+
+    fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        match property {
+            PropertyIdentifier::ObjectName => {
+                if let PropertyValue::CharacterString(name) = value {
+                    self.object_name = name;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::OutOfService => {
+                if let PropertyValue::Boolean(oos) = value {
+                    self.out_of_service = oos;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::CarDoorCommand => {
+                let PropertyValue::Array(commands) = value else {
+                    return Err(ObjectError::InvalidPropertyType);
+                };
+                if commands.len() != self.car_door_command.len() {
+                    return Err(ObjectError::InvalidValue(format!(
+                        "Expected {} car door commands",
+                        self.car_door_command.len()
+                    )));
+                }
+                self.car_door_command = commands
+                    .iter()
+                    .map(|command| match command {
+                        PropertyValue::Enumerated(command) => {
+                            LiftCarDoorCommand::try_from(*command)
+                        }
+                        _ => Err(ObjectError::InvalidPropertyType),
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(())
+            }
+            PropertyIdentifier::MakingCarCall => {
+                let PropertyValue::Array(floors) = value else {
+                    return Err(ObjectError::InvalidPropertyType);
+                };
+                if floors.len() != self.making_car_call.len() {
+                    return Err(ObjectError::InvalidValue(format!(
+                        "Expected {} car calls",
+                        self.making_car_call.len()
+                    )));
+                }
+                let floors = floors
+                    .iter()
+                    .map(|floor| match floor {
+                        PropertyValue::UnsignedInteger(floor) => floor_number(*floor),
+                        _ => Err(ObjectError::InvalidPropertyType),
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                for (deck, floor) in floors.into_iter().enumerate() {
+                    self.register_car_call(deck, floor)?;
+                }
+                Ok(())
+            }
+            _ => Err(ObjectError::PropertyNotWritable),
+        }
+    }
+
+    fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
+        matches!(
+            property,
+            PropertyIdentifier::ObjectName
+                | PropertyIdentifier::Description
+                | PropertyIdentifier::OutOfService
+                | PropertyIdentifier::CarDoorCommand
+                | PropertyIdentifier::MakingCarCall
+        )
+    }
+
+    fn property_list(&self) -> Vec<PropertyIdentifier> {
+        let mut properties = vec![
+            PropertyIdentifier::ObjectIdentifier,
+            PropertyIdentifier::ObjectName,
+            PropertyIdentifier::ObjectType,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::StatusFlags,
+            PropertyIdentifier::EventState,
+            PropertyIdentifier::Reliability,
+            PropertyIdentifier::OutOfService,
+        ];
+        if self.elevator_group.is_some() {
+            properties.push(PropertyIdentifier::ElevatorGroup);
+        }
+        properties.extend([
+            PropertyIdentifier::GroupId,
+            PropertyIdentifier::InstallationId,
+            PropertyIdentifier::FloorText,
+            PropertyIdentifier::CarPosition,
+            PropertyIdentifier::CarMovingDirection,
+            PropertyIdentifier::CarAssignedDirection,
+            PropertyIdentifier::CarDoorStatus,
+            PropertyIdentifier::CarDoorCommand,
+            PropertyIdentifier::CarDoorZone,
+            PropertyIdentifier::CarMode,
+        ]);
+        if self.car_load.is_some() {
+            properties.push(PropertyIdentifier::CarLoad);
+            properties.push(PropertyIdentifier::CarLoadUnits);
+        }
+        properties.extend([
+            PropertyIdentifier::NextStoppingFloor,
+            PropertyIdentifier::LandingDoorStatus,
+            PropertyIdentifier::MakingCarCall,
+            PropertyIdentifier::RegisteredCarCall,
+            PropertyIdentifier::PassengerAlarm,
+            PropertyIdentifier::FaultSignals,
+            PropertyIdentifier::CarDriveStatus,
+        ]);
+        if self.energy_meter.is_some() {
+            properties.push(PropertyIdentifier::EnergyMeter);
+        }
+        properties
+    }
+}
+
+/// Escalator object
+#[derive(Debug, Clone)]
+pub struct Escalator {
+    /// Object identifier
+    pub identifier: ObjectIdentifier,
+    /// Object name
+    pub object_name: String,
+    /// Description
+    pub description: String,
+    /// Status flags
+    pub status_flags: u8,
+    /// Event state
+    pub event_state: EventState,
+    /// Reliability
+    pub reliability: Reliability,
+    /// Out of service
+    pub out_of_service: bool,
+    /// Elevator Group the escalator belongs to
+    pub elevator_group: Option<ObjectIdentifier>,
+    /// Group number within the machine room
+    pub group_id: u8,
+    /// Installation number within the group
+    pub installation_id: u8,
+    /// Whether the escalator is in energy-saving mode
+    pub power_mode: bool,
+    /// Current movement
+    pub operation_direction: EscalatorOperationDirection,
+    /// Operating mode
+    pub escalator_mode: EscalatorMode,
+    /// Whether the passenger alarm is active
+    pub passenger_alarm: bool,
+    /// Active faults (BACnetEscalatorFault values)
+    pub fault_signals: Vec<u32>,
+    /// Energy consumed, in kilowatt-hours (optional property)
+    pub energy_meter: Option<f32>,
+}
+
+impl Escalator {
+    /// Create a new Escalator object
+    pub fn new(instance: u32, object_name: String) -> Self {
+        Self {
+            identifier: ObjectIdentifier::new(ObjectType::Escalator, instance),
+            object_name,
+            description: String::new(),
+            status_flags: 0,
+            event_state: EventState::Normal,
+            reliability: Reliability::NoFaultDetected,
+            out_of_service: false,
+            elevator_group: None,
+            group_id: 0,
+            installation_id: 0,
+            power_mode: false,
+            operation_direction: EscalatorOperationDirection::Stopped,
+            escalator_mode: EscalatorMode::Stop,
+            passenger_alarm: false,
+            fault_signals: Vec::new(),
+            energy_meter: None,
+        }
+    }
+
+    /// Current Status_Flags, combining the stored flags with the state derived
+    /// from Event_State, Reliability and Out_Of_Service
+    pub fn current_status_flags(&self) -> u8 {
+        current_status_flags(
+            self.status_flags,
+            self.event_state,
+            self.reliability,
+            self.out_of_service,
+        )
+    }
+}
+
+impl BacnetObject for Escalator {
+    fn identifier(&self) -> ObjectIdentifier {
+        self.identifier
+    }
+
+    fn get_property(&self, property: PropertyIdentifier) -> Result<PropertyValue> {
+        match property {
+            PropertyIdentifier::ObjectIdentifier => {
+                Ok(PropertyValue::ObjectIdentifier(self.identifier))
+            }
+            PropertyIdentifier::ObjectName => {
+                Ok(PropertyValue::CharacterString(self.object_name.clone()))
+            }
+            PropertyIdentifier::ObjectType => {
+                Ok(PropertyValue::Enumerated(u32::from(ObjectType::Escalator)))
+            }
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::StatusFlags => {
+                Ok(status_flags_bit_string(self.current_status_flags()))
+            }
+            PropertyIdentifier::EventState => {
+                Ok(PropertyValue::Enumerated(self.event_state as u32))
+            }
+            PropertyIdentifier::Reliability => {
+                Ok(PropertyValue::Enumerated(self.reliability as u32))
+            }
+            PropertyIdentifier::OutOfService => Ok(PropertyValue::Boolean(self.out_of_service)),
+            PropertyIdentifier::ElevatorGroup => self
+                .elevator_group
+                .map(PropertyValue::ObjectIdentifier)
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::GroupId => Ok(PropertyValue::UnsignedInteger(self.group_id as u32)),
+            PropertyIdentifier::InstallationId => {
+                Ok(PropertyValue::UnsignedInteger(self.installation_id as u32))
+            }
+            PropertyIdentifier::PowerMode => Ok(PropertyValue::Boolean(self.power_mode)),
+            PropertyIdentifier::OperationDirection => {
+                Ok(PropertyValue::Enumerated(self.operation_direction as u32))
+            }
+            PropertyIdentifier::EscalatorMode => {
+                Ok(PropertyValue::Enumerated(self.escalator_mode as u32))
+            }
+            PropertyIdentifier::PassengerAlarm => Ok(PropertyValue::Boolean(self.passenger_alarm)),
+            PropertyIdentifier::FaultSignals => Ok(enumerated_list(&self.fault_signals)),
+            PropertyIdentifier::EnergyMeter => self
+                .energy_meter
+                .map(PropertyValue::Real)
+                .ok_or(ObjectError::UnknownProperty),
+            _ => Err(ObjectError::UnknownProperty),
+        }
+    }
+
+    fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        match property {
+            PropertyIdentifier::ObjectName => {
+                if let PropertyValue::CharacterString(name) = value {
+                    self.object_name = name;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::OutOfService => {
+                if let PropertyValue::Boolean(oos) = value {
+                    self.out_of_service = oos;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            _ => Err(ObjectError::PropertyNotWritable),
+        }
+    }
+
+    fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
+        matches!(
+            property,
+            PropertyIdentifier::ObjectName
+                | PropertyIdentifier::Description
+                | PropertyIdentifier::OutOfService
+        )
+    }
+
+    fn property_list(&self) -> Vec<PropertyIdentifier> {
+        let mut properties = vec![
+            PropertyIdentifier::ObjectIdentifier,
+            PropertyIdentifier::ObjectName,
+            PropertyIdentifier::ObjectType,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::StatusFlags,
+            PropertyIdentifier::EventState,
+            PropertyIdentifier::Reliability,
+            PropertyIdentifier::OutOfService,
+        ];
+        if self.elevator_group.is_some() {
+            properties.push(PropertyIdentifier::ElevatorGroup);
+        }
+        properties.extend([
+            PropertyIdentifier::GroupId,
+            PropertyIdentifier::InstallationId,
+            PropertyIdentifier::PowerMode,
+            PropertyIdentifier::OperationDirection,
+            PropertyIdentifier::EscalatorMode,
+            PropertyIdentifier::PassengerAlarm,
+            PropertyIdentifier::FaultSignals,
+        ]);
+        if self.energy_meter.is_some() {
+            properties.push(PropertyIdentifier::EnergyMeter);
+        }
+        properties
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_elevator_group_landing_calls() {
+        let machine_room = ObjectIdentifier::new(ObjectType::StructuredView, 1);
+        let mut group = ElevatorGroup::new(1, "Lobby Lifts".to_string(), machine_room);
+        group
+            .add_member(ObjectIdentifier::new(ObjectType::Lift, 1))
+            .unwrap();
+        assert!(group
+            .add_member(ObjectIdentifier::new(ObjectType::Escalator, 1))
+            .is_err());
+        assert_eq!(group.group_mode, Some(LiftGroupMode::Unknown));
+
+        let call = LandingCall {
+            floor_number: 3,
+            command: LandingCallCommand::Direction(LiftCarDirection::Up),
+        };
+        group
+            .set_property(
+                PropertyIdentifier::LandingCallControl,
+                call.to_property_value(),
+            )
+            .unwrap();
+        assert_eq!(
+            group
+                .get_property(PropertyIdentifier::LandingCalls)
+                .unwrap(),
+            PropertyValue::List(vec![call.to_property_value()])
+        );
+        assert!(group.serve_landing_call(&call));
+        assert!(group.landing_calls.is_empty());
+    }
+
+    #[test]
+    fn test_lift_car_calls_and_doors() {
+        let mut lift = Lift::new(1, "Car A".to_string(), 1, 2);
+        lift.set_property(
+            PropertyIdentifier::MakingCarCall,
+            PropertyValue::Array(vec![PropertyValue::UnsignedInteger(5)]),
+        )
+        .unwrap();
+        lift.register_car_call(0, 8).unwrap();
+        assert!(lift.register_car_call(1, 2).is_err());
+        assert_eq!(lift.registered_car_call[0], vec![5, 8]);
+
+        lift.arrive_at(5);
+        assert_eq!(lift.car_position, 5);
+        assert_eq!(lift.registered_car_call[0], vec![8]);
+
+        lift.set_property(
+            PropertyIdentifier::CarDoorCommand,
+            PropertyValue::Array(vec![
+                PropertyValue::Enumerated(LiftCarDoorCommand::Open as u32),
+                PropertyValue::Enumerated(LiftCarDoorCommand::None as u32),
+            ]),
+        )
+        .unwrap();
+        assert_eq!(lift.car_door_command[0], LiftCarDoorCommand::Open);
+        lift.car_door_status[0] = DoorStatus::Opened;
+        assert_eq!(
+            lift.get_property(PropertyIdentifier::CarDoorStatus)
+                .unwrap(),
+            PropertyValue::Array(vec![
+                PropertyValue::Enumerated(DoorStatus::Opened as u32),
+                PropertyValue::Enumerated(DoorStatus::Closed as u32),
+            ])
+        );
+    }
+}
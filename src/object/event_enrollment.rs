@@ -0,0 +1,841 @@
+//! Event Enrollment Object Type Implementation
+//!
+//! This module implements the Event Enrollment object type as defined in ASHRAE 135.
+//! An Event Enrollment applies an algorithmic event type to a referenced property, so
+//! that local or remote values can generate event notifications without intrinsic
+//! reporting on the monitored object. The caller reads the referenced property and
+//! feeds it to [`EventEnrollment::evaluate`]; time delays are tracked through
+//! [`BacnetObject::advance_time`].
+
+use crate::object::{
+    current_status_flags, date_time_value, status_flags_bit_string, BacnetObject,
+    DeviceObjectPropertyReference, EventState, EventTransition, NotifyType, ObjectError,
+    ObjectIdentifier, ObjectType, PropertyIdentifier, PropertyValue, Reliability, Result,
+};
+use crate::service::BacnetDateTime;
+use core::time::Duration;
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+/// Event algorithm enumeration (BACnetEventType)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum EventType {
+    ChangeOfBitstring = 0,
+    ChangeOfState = 1,
+    ChangeOfValue = 2,
+    CommandFailure = 3,
+    FloatingLimit = 4,
+    OutOfRange = 5,
+    ComplexEventType = 6,
+    ChangeOfLifeSafety = 8,
+    Extended = 9,
+    BufferReady = 10,
+    UnsignedRange = 11,
+    AccessEvent = 13,
+    DoubleOutOfRange = 14,
+    SignedOutOfRange = 15,
+    UnsignedOutOfRange = 16,
+    ChangeOfCharacterstring = 17,
+    ChangeOfStatusFlags = 18,
+    ChangeOfReliability = 19,
+    None = 20,
+    ChangeOfDiscreteValue = 21,
+    ChangeOfTimer = 22,
+}
+
+impl TryFrom<u32> for EventType {
+    type Error = ObjectError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            0 => Ok(EventType::ChangeOfBitstring),
+            1 => Ok(EventType::ChangeOfState),
+            2 => Ok(EventType::ChangeOfValue),
+            3 => Ok(EventType::CommandFailure),
+            4 => Ok(EventType::FloatingLimit),
+            5 => Ok(EventType::OutOfRange),
+            6 => Ok(EventType::ComplexEventType),
+            8 => Ok(EventType::ChangeOfLifeSafety),
+            9 => Ok(EventType::Extended),
+            10 => Ok(EventType::BufferReady),
+            11 => Ok(EventType::UnsignedRange),
+            13 => Ok(EventType::AccessEvent),
+            14 => Ok(EventType::DoubleOutOfRange),
+            15 => Ok(EventType::SignedOutOfRange),
+            16 => Ok(EventType::UnsignedOutOfRange),
+            17 => Ok(EventType::ChangeOfCharacterstring),
+            18 => Ok(EventType::ChangeOfStatusFlags),
+            19 => Ok(EventType::ChangeOfReliability),
+            20 => Ok(EventType::None),
+            21 => Ok(EventType::ChangeOfDiscreteValue),
+            22 => Ok(EventType::ChangeOfTimer),
+            _ => Err(ObjectError::InvalidValue(format!(
+                "Unsupported event type: {}",
+                value
+            ))),
+        }
+    }
+}
+
+/// What counts as a change for the CHANGE_OF_VALUE algorithm
+#[derive(Debug, Clone, PartialEq)]
+pub enum CovCriteria {
+    /// Any change in the masked bits of a bit string
+    Bitmask(Vec<bool>),
+    /// A change of at least this amount in a numeric value
+    ReferencedPropertyIncrement(f32),
+}
+
+/// Algorithm parameters (BACnetEventParameter)
+#[derive(Debug, Clone, PartialEq)]
+pub enum EventParameters {
+    ChangeOfBitstring {
+        time_delay: u32,
+        bitmask: Vec<bool>,
+        list_of_bitstring_values: Vec<Vec<bool>>,
+    },
+    ChangeOfState {
+        time_delay: u32,
+        list_of_values: Vec<PropertyValue>,
+    },
+    ChangeOfValue {
+        time_delay: u32,
+        criteria: CovCriteria,
+    },
+    CommandFailure {
+        time_delay: u32,
+        feedback_property_reference: DeviceObjectPropertyReference,
+    },
+    FloatingLimit {
+        time_delay: u32,
+        setpoint_reference: DeviceObjectPropertyReference,
+        low_diff_limit: f32,
+        high_diff_limit: f32,
+        deadband: f32,
+    },
+    OutOfRange {
+        time_delay: u32,
+        low_limit: f32,
+        high_limit: f32,
+        deadband: f32,
+    },
+}
+
+impl EventParameters {
+    /// The event type these parameters configure
+    pub fn event_type(&self) -> EventType {
+        match self {
+            EventParameters::ChangeOfBitstring { .. } => EventType::ChangeOfBitstring,
+            EventParameters::ChangeOfState { .. } => EventType::ChangeOfState,
+            EventParameters::ChangeOfValue { .. } => EventType::ChangeOfValue,
+            EventParameters::CommandFailure { .. } => EventType::CommandFailure,
+            EventParameters::FloatingLimit { .. } => EventType::FloatingLimit,
+            EventParameters::OutOfRange { .. } => EventType::OutOfRange,
+        }
+    }
+
+    /// Seconds a condition must persist before it is reported
+    pub fn time_delay(&self) -> u32 {
+        match self {
+            EventParameters::ChangeOfBitstring { time_delay, .. }
+            | EventParameters::ChangeOfState { time_delay, .. }
+            | EventParameters::ChangeOfValue { time_delay, .. }
+            | EventParameters::CommandFailure { time_delay, .. }
+            | EventParameters::FloatingLimit { time_delay, .. }
+            | EventParameters::OutOfRange { time_delay, .. } => *time_delay,
+        }
+    }
+
+    /// The second property the algorithm reads, if any
+    ///
+    /// COMMAND_FAILURE compares against its feedback property and FLOATING_LIMIT
+    /// against its setpoint; the value is passed to `evaluate` as `auxiliary`.
+    pub fn auxiliary_reference(&self) -> Option<DeviceObjectPropertyReference> {
+        match self {
+            EventParameters::CommandFailure {
+                feedback_property_reference,
+                ..
+            } => Some(*feedback_property_reference),
+            EventParameters::FloatingLimit {
+                setpoint_reference, ..
+            } => Some(*setpoint_reference),
+            _ => None,
+        }
+    }
+
+    /// Encode as `[Enumerated(event type), Unsigned(time delay), parameters...]`
+    pub fn to_property_value(&self) -> PropertyValue {
+        let mut items = vec![
+            PropertyValue::Enumerated(self.event_type() as u32),
+            PropertyValue::UnsignedInteger(self.time_delay()),
+        ];
+        match self {
+            EventParameters::ChangeOfBitstring {
+                bitmask,
+                list_of_bitstring_values,
+                ..
+            } => {
+                items.push(PropertyValue::BitString(bitmask.clone()));
+                items.push(PropertyValue::List(
+                    list_of_bitstring_values
+                        .iter()
+                        .cloned()
+                        .map(PropertyValue::BitString)
+                        .collect(),
+                ));
+            }
+            EventParameters::ChangeOfState { list_of_values, .. } => {
+                items.push(PropertyValue::List(list_of_values.clone()));
+            }
+            EventParameters::ChangeOfValue { criteria, .. } => items.push(match criteria {
+                CovCriteria::Bitmask(mask) => PropertyValue::BitString(mask.clone()),
+                CovCriteria::ReferencedPropertyIncrement(increment) => {
+                    PropertyValue::Real(*increment)
+                }
+            }),
+            EventParameters::CommandFailure {
+                feedback_property_reference,
+                ..
+            } => items.push(feedback_property_reference.to_property_value()),
+            EventParameters::FloatingLimit {
+                setpoint_reference,
+                low_diff_limit,
+                high_diff_limit,
+                deadband,
+                ..
+            } => items.extend([
+                setpoint_reference.to_property_value(),
+                PropertyValue::Real(*low_diff_limit),
+                PropertyValue::Real(*high_diff_limit),
+                PropertyValue::Real(*deadband),
+            ]),
+            EventParameters::OutOfRange {
+                low_limit,
+                high_limit,
+                deadband,
+                ..
+            } => items.extend([
+                PropertyValue::Real(*low_limit),
+                PropertyValue::Real(*high_limit),
+                PropertyValue::Real(*deadband),
+            ]),
+        }
+        PropertyValue::List(items)
+    }
+}
+
+/// An event state transition detected by an Event Enrollment
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventStateChange {
+    /// State before the transition
+    pub from_state: EventState,
+    /// State after the transition
+    pub to_state: EventState,
+    /// Transition category used for Event_Enable and Notification Class lookups
+    pub transition: EventTransition,
+    /// The monitored value that caused the transition
+    pub monitored_value: PropertyValue,
+    /// Whether Event_Enable allows a notification for this transition
+    pub notify: bool,
+    /// Notification class to deliver through
+    pub notification_class: u32,
+    /// Notify type for the notification
+    pub notify_type: NotifyType,
+    /// Algorithm that detected the transition
+    pub event_type: EventType,
+}
+
+/// Event Enrollment object
+#[derive(Debug, Clone)]
+pub struct EventEnrollment {
+    /// Object identifier
+    pub identifier: ObjectIdentifier,
+    /// Object name
+    pub object_name: String,
+    /// Description
+    pub description: String,
+    /// Notify type
+    pub notify_type: NotifyType,
+    /// Algorithm and its parameters
+    pub event_parameters: EventParameters,
+    /// Monitored property
+    pub object_property_reference: DeviceObjectPropertyReference,
+    /// Event state
+    pub event_state: EventState,
+    /// Event enable (to_offnormal, to_fault, to_normal)
+    pub event_enable: (bool, bool, bool),
+    /// Acknowledged transitions (to_offnormal, to_fault, to_normal)
+    pub acked_transitions: (bool, bool, bool),
+    /// Notification class that receives the event notifications
+    pub notification_class: u32,
+    /// Time of the last transition of each kind (to_offnormal, to_fault, to_normal)
+    pub event_time_stamps: [Option<BacnetDateTime>; 3],
+    /// Reliability
+    pub reliability: Reliability,
+    pending: Option<(EventState, Duration)>,
+    last_reported_value: Option<PropertyValue>,
+}
+
+impl EventEnrollment {
+    /// Create a new Event Enrollment monitoring `reference`
+    pub fn new(
+        instance: u32,
+        object_name: String,
+        object_property_reference: DeviceObjectPropertyReference,
+        event_parameters: EventParameters,
+        notification_class: u32,
+    ) -> Self {
+        Self {
+            identifier: ObjectIdentifier::new(ObjectType::EventEnrollment, instance),
+            object_name,
+            description: String::new(),
+            notify_type: NotifyType::Alarm,
+            event_parameters,
+            object_property_reference,
+            event_state: EventState::Normal,
+            event_enable: (true, true, true),
+            acked_transitions: (true, true, true),
+            notification_class,
+            event_time_stamps: [None; 3],
+            reliability: Reliability::NoFaultDetected,
+            pending: None,
+            last_reported_value: None,
+        }
+    }
+
+    /// Event type of the configured algorithm
+    pub fn event_type(&self) -> EventType {
+        self.event_parameters.event_type()
+    }
+
+    /// Replace the algorithm, restarting detection from the normal state
+    pub fn set_event_parameters(&mut self, event_parameters: EventParameters) {
+        self.event_parameters = event_parameters;
+        self.event_state = EventState::Normal;
+        self.pending = None;
+        self.last_reported_value = None;
+    }
+
+    /// Run the event algorithm against a fresh reading of the monitored property
+    ///
+    /// `auxiliary` carries the feedback or setpoint value for algorithms that need
+    /// one (see [`EventParameters::auxiliary_reference`]). Returns the transition
+    /// once its condition has held for Time_Delay; fault transitions and
+    /// CHANGE_OF_VALUE events are reported immediately.
+    pub fn evaluate(
+        &mut self,
+        monitored: &PropertyValue,
+        auxiliary: Option<&PropertyValue>,
+        timestamp: Option<BacnetDateTime>,
+    ) -> Option<EventStateChange> {
+        let target = match self.target_state(monitored, auxiliary) {
+            Ok(target) => {
+                self.reliability = Reliability::NoFaultDetected;
+                target
+            }
+            Err(reliability) => {
+                self.reliability = reliability;
+                EventState::Fault
+            }
+        };
+
+        if let EventParameters::ChangeOfValue { criteria, .. } = &self.event_parameters {
+            if target == EventState::Normal {
+                let changed = match &self.last_reported_value {
+                    None => {
+                        self.last_reported_value = Some(monitored.clone());
+                        false
+                    }
+                    Some(previous) => value_changed(criteria, previous, monitored),
+                };
+                if changed && self.event_state == EventState::Normal {
+                    self.last_reported_value = Some(monitored.clone());
+                    return Some(self.transition_to(EventState::Normal, monitored, timestamp));
+                }
+            }
+        }
+
+        if target == self.event_state {
+            self.pending = None;
+            return None;
+        }
+        if target == EventState::Fault {
+            return Some(self.transition_to(target, monitored, timestamp));
+        }
+
+        let delay = Duration::from_secs(self.event_parameters.time_delay() as u64);
+        match self.pending {
+            Some((pending, elapsed)) if pending == target && elapsed >= delay => {
+                Some(self.transition_to(target, monitored, timestamp))
+            }
+            Some((pending, _)) if pending == target => None,
+            _ if delay.is_zero() => Some(self.transition_to(target, monitored, timestamp)),
+            _ => {
+                self.pending = Some((target, Duration::ZERO));
+                None
+            }
+        }
+    }
+
+    fn transition_to(
+        &mut self,
+        to_state: EventState,
+        monitored: &PropertyValue,
+        timestamp: Option<BacnetDateTime>,
+    ) -> EventStateChange {
+        let transition = EventTransition::for_state(to_state);
+        let notify = match transition {
+            EventTransition::ToOffnormal => self.event_enable.0,
+            EventTransition::ToFault => self.event_enable.1,
+            EventTransition::ToNormal => self.event_enable.2,
+        };
+        if notify {
+            match transition {
+                EventTransition::ToOffnormal => self.acked_transitions.0 = false,
+                EventTransition::ToFault => self.acked_transitions.1 = false,
+                EventTransition::ToNormal => self.acked_transitions.2 = false,
+            }
+        }
+        self.event_time_stamps[transition as usize] = timestamp;
+        let from_state = self.event_state;
+        self.event_state = to_state;
+        self.pending = None;
+        EventStateChange {
+            from_state,
+            to_state,
+            transition,
+            monitored_value: monitored.clone(),
+            notify,
+            notification_class: self.notification_class,
+            notify_type: self.notify_type,
+            event_type: self.event_type(),
+        }
+    }
+
+    /// The state the algorithm calls for, or the fault reliability if the
+    /// values cannot be evaluated
+    fn target_state(
+        &self,
+        monitored: &PropertyValue,
+        auxiliary: Option<&PropertyValue>,
+    ) -> core::result::Result<EventState, Reliability> {
+        let current = self.event_state;
+        match (&self.event_parameters, monitored) {
+            (
+                EventParameters::ChangeOfBitstring {
+                    bitmask,
+                    list_of_bitstring_values,
+                    ..
+                },
+                PropertyValue::BitString(bits),
+            ) => {
+                let masked = apply_mask(bits, bitmask);
+                let alarm = list_of_bitstring_values
+                    .iter()
+                    .any(|pattern| apply_mask(pattern, bitmask) == masked);
+                Ok(offnormal_if(alarm))
+            }
+            (EventParameters::ChangeOfBitstring { .. }, _) => Err(Reliability::ConfigurationError),
+            (EventParameters::ChangeOfState { list_of_values, .. }, value) => {
+                Ok(offnormal_if(list_of_values.contains(value)))
+            }
+            (EventParameters::ChangeOfValue { criteria, .. }, value) => match (criteria, value) {
+                (CovCriteria::Bitmask(_), PropertyValue::BitString(_))
+                | (CovCriteria::ReferencedPropertyIncrement(_), PropertyValue::Real(_)) => {
+                    Ok(EventState::Normal)
+                }
+                _ => Err(Reliability::ConfigurationError),
+            },
+            (EventParameters::CommandFailure { .. }, value) => match auxiliary {
+                Some(feedback) => Ok(offnormal_if(feedback != value)),
+                None => Err(Reliability::UnreliableOther),
+            },
+            (
+                EventParameters::FloatingLimit {
+                    low_diff_limit,
+                    high_diff_limit,
+                    deadband,
+                    ..
+                },
+                PropertyValue::Real(value),
+            ) => match auxiliary {
+                Some(PropertyValue::Real(setpoint)) => Ok(limit_state(
+                    current,
+                    *value,
+                    setpoint - low_diff_limit,
+                    setpoint + high_diff_limit,
+                    *deadband,
+                )),
+                _ => Err(Reliability::UnreliableOther),
+            },
+            (
+                EventParameters::OutOfRange {
+                    low_limit,
+                    high_limit,
+                    deadband,
+                    ..
+                },
+                PropertyValue::Real(value),
+            ) => Ok(limit_state(
+                current,
+                *value,
+                *low_limit,
+                *high_limit,
+                *deadband,
+            )),
+            (EventParameters::FloatingLimit { .. } | EventParameters::OutOfRange { .. }, _) => {
+                Err(Reliability::ConfigurationError)
+            }
+        }
+    }
+}
+
+fn offnormal_if(alarm: bool) -> EventState {
+    if alarm {
+        EventState::Offnormal
+    } else {
+        EventState::Normal
+    }
+}
+
+fn apply_mask(bits: &[bool], mask: &[bool]) -> Vec<bool> {
+    mask.iter()
+        .enumerate()
+        .map(|(i, &m)| m && bits.get(i).copied().unwrap_or(false))
+        .collect()
+}
+
+/// Limit evaluation shared by OUT_OF_RANGE and FLOATING_LIMIT; the deadband
+/// applies only when returning to normal
+fn limit_state(current: EventState, value: f32, low: f32, high: f32, deadband: f32) -> EventState {
+    match current {
+        EventState::HighLimit if value > high - deadband => EventState::HighLimit,
+        EventState::LowLimit if value < low + deadband => EventState::LowLimit,
+        _ if value > high => EventState::HighLimit,
+        _ if value < low => EventState::LowLimit,
+        _ => EventState::Normal,
+    }
+}
+
+fn value_changed(
+    criteria: &CovCriteria,
+    previous: &PropertyValue,
+    current: &PropertyValue,
+) -> bool {
+    match (criteria, previous, current) {
+        (CovCriteria::Bitmask(mask), PropertyValue::BitString(a), PropertyValue::BitString(b)) => {
+            apply_mask(a, mask) != apply_mask(b, mask)
+        }
+        (
+            CovCriteria::ReferencedPropertyIncrement(increment),
+            PropertyValue::Real(a),
+            PropertyValue::Real(b),
+        ) => (b - a).abs() >= *increment,
+        _ => false,
+    }
+}
+
+impl BacnetObject for EventEnrollment {
+    fn identifier(&self) -> ObjectIdentifier {
+        self.identifier
+    }
+
+    fn get_property(&self, property: PropertyIdentifier) -> Result<PropertyValue> {
+        match property {
+            PropertyIdentifier::ObjectIdentifier => {
+                Ok(PropertyValue::ObjectIdentifier(self.identifier))
+            }
+            PropertyIdentifier::ObjectName => {
+                Ok(PropertyValue::CharacterString(self.object_name.clone()))
+            }
+            PropertyIdentifier::ObjectType => Ok(PropertyValue::Enumerated(u32::from(
+                ObjectType::EventEnrollment,
+            ))),
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::EventType => {
+                Ok(PropertyValue::Enumerated(self.event_type() as u32))
+            }
+            PropertyIdentifier::NotifyType => {
+                Ok(PropertyValue::Enumerated(self.notify_type as u32))
+            }
+            PropertyIdentifier::EventParameters => Ok(self.event_parameters.to_property_value()),
+            PropertyIdentifier::ObjectPropertyReference => {
+                Ok(self.object_property_reference.to_property_value())
+            }
+            PropertyIdentifier::EventState => {
+                Ok(PropertyValue::Enumerated(self.event_state as u32))
+            }
+            PropertyIdentifier::EventEnable => Ok(PropertyValue::BitString(vec![
+                self.event_enable.0,
+                self.event_enable.1,
+                self.event_enable.2,
+            ])),
+            PropertyIdentifier::AckedTransitions => Ok(PropertyValue::BitString(vec![
+                self.acked_transitions.0,
+                self.acked_transitions.1,
+                self.acked_transitions.2,
+            ])),
+            PropertyIdentifier::NotificationClass => {
+                Ok(PropertyValue::UnsignedInteger(self.notification_class))
+            }
+            PropertyIdentifier::EventTimeStamps => Ok(PropertyValue::Array(
+                self.event_time_stamps
+                    .iter()
+                    .map(|&timestamp| date_time_value(timestamp))
+                    .collect(),
+            )),
+            PropertyIdentifier::StatusFlags => Ok(status_flags_bit_string(current_status_flags(
+                0,
+                self.event_state,
+                self.reliability,
+                false,
+            ))),
+            PropertyIdentifier::Reliability => {
+                Ok(PropertyValue::Enumerated(self.reliability as u32))
+            }
+            _ => Err(ObjectError::UnknownProperty),
+        }
+    }
+
+    fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        match property {
+            PropertyIdentifier::ObjectName => {
+                if let PropertyValue::CharacterString(name) = value {
+                    self.object_name = name;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::NotifyType => {
+                if let PropertyValue::Enumerated(notify_type) = value {
+                    self.notify_type = NotifyType::try_from(notify_type)?;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::EventEnable => {
+                if let PropertyValue::BitString(bits) = value {
+                    let bit = |i: usize| bits.get(i).copied().unwrap_or(false);
+                    self.event_enable = (bit(0), bit(1), bit(2));
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::NotificationClass => {
+                if let PropertyValue::UnsignedInteger(class) = value {
+                    self.notification_class = class;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            _ => Err(ObjectError::PropertyNotWritable),
+        }
+    }
+
+    fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
+        matches!(
+            property,
+            PropertyIdentifier::ObjectName
+                | PropertyIdentifier::Description
+                | PropertyIdentifier::NotifyType
+                | PropertyIdentifier::EventEnable
+                | PropertyIdentifier::NotificationClass
+        )
+    }
+
+    fn property_list(&self) -> Vec<PropertyIdentifier> {
+        vec![
+            PropertyIdentifier::ObjectIdentifier,
+            PropertyIdentifier::ObjectName,
+            PropertyIdentifier::ObjectType,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::EventType,
+            PropertyIdentifier::NotifyType,
+            PropertyIdentifier::EventParameters,
+            PropertyIdentifier::ObjectPropertyReference,
+            PropertyIdentifier::EventState,
+            PropertyIdentifier::EventEnable,
+            PropertyIdentifier::AckedTransitions,
+            PropertyIdentifier::NotificationClass,
+            PropertyIdentifier::EventTimeStamps,
+            PropertyIdentifier::StatusFlags,
+            PropertyIdentifier::Reliability,
+        ]
+    }
+
+    fn advance_time(&mut self, elapsed: Duration) {
+        if let Some((_, pending)) = &mut self.pending {
+            *pending = pending.saturating_add(elapsed);
+        }
+    }
+
+    fn acknowledge_transition(&mut self, transition: EventTransition) -> Result<()> {
+        match transition {
+            EventTransition::ToOffnormal => self.acked_transitions.0 = true,
+            EventTransition::ToFault => self.acked_transitions.1 = true,
+            EventTransition::ToNormal => self.acked_transitions.2 = true,
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reference(object_type: ObjectType) -> DeviceObjectPropertyReference {
+        DeviceObjectPropertyReference::new(
+            ObjectIdentifier::new(object_type, 1),
+            PropertyIdentifier::PresentValue,
+        )
+    }
+
+    fn out_of_range(time_delay: u32) -> EventEnrollment {
+        EventEnrollment::new(
+            1,
+            "Supply Temp Alarm".to_string(),
+            reference(ObjectType::AnalogInput),
+            EventParameters::OutOfRange {
+                time_delay,
+                low_limit: 10.0,
+                high_limit: 30.0,
+                deadband: 2.0,
+            },
+            5,
+        )
+    }
+
+    #[test]
+    fn test_out_of_range_with_deadband() {
+        let mut ee = out_of_range(0);
+        assert!(ee
+            .evaluate(&PropertyValue::Real(20.0), None, None)
+            .is_none());
+
+        let change = ee.evaluate(&PropertyValue::Real(31.0), None, None).unwrap();
+        assert_eq!(change.to_state, EventState::HighLimit);
+        assert_eq!(change.transition, EventTransition::ToOffnormal);
+        assert_eq!(change.notification_class, 5);
+        assert!(!ee.acked_transitions.0);
+
+        // Within the deadband the alarm holds
+        assert!(ee
+            .evaluate(&PropertyValue::Real(29.0), None, None)
+            .is_none());
+        let change = ee.evaluate(&PropertyValue::Real(27.5), None, None).unwrap();
+        assert_eq!(change.to_state, EventState::Normal);
+        assert_eq!(change.from_state, EventState::HighLimit);
+    }
+
+    #[test]
+    fn test_time_delay_and_fault() {
+        let mut ee = out_of_range(30);
+        assert!(ee.evaluate(&PropertyValue::Real(5.0), None, None).is_none());
+        ee.advance_time(Duration::from_secs(20));
+        assert!(ee.evaluate(&PropertyValue::Real(5.0), None, None).is_none());
+        ee.advance_time(Duration::from_secs(10));
+        let change = ee.evaluate(&PropertyValue::Real(5.0), None, None).unwrap();
+        assert_eq!(change.to_state, EventState::LowLimit);
+
+        // A value the algorithm cannot interpret is a fault, reported immediately
+        let change = ee
+            .evaluate(&PropertyValue::Boolean(true), None, None)
+            .unwrap();
+        assert_eq!(change.transition, EventTransition::ToFault);
+        assert_eq!(ee.reliability, Reliability::ConfigurationError);
+    }
+
+    #[test]
+    fn test_change_of_state_and_command_failure() {
+        let mut ee = EventEnrollment::new(
+            2,
+            "Fan Status".to_string(),
+            reference(ObjectType::BinaryInput),
+            EventParameters::ChangeOfState {
+                time_delay: 0,
+                list_of_values: vec![PropertyValue::Enumerated(0)],
+            },
+            1,
+        );
+        ee.event_enable = (true, false, false);
+        let change = ee
+            .evaluate(&PropertyValue::Enumerated(0), None, None)
+            .unwrap();
+        assert!(change.notify);
+        let change = ee
+            .evaluate(&PropertyValue::Enumerated(1), None, None)
+            .unwrap();
+        assert!(!change.notify);
+
+        let feedback = reference(ObjectType::BinaryInput);
+        ee.set_event_parameters(EventParameters::CommandFailure {
+            time_delay: 0,
+            feedback_property_reference: feedback,
+        });
+        assert_eq!(ee.event_parameters.auxiliary_reference(), Some(feedback));
+        let commanded = PropertyValue::Enumerated(1);
+        assert!(ee
+            .evaluate(&commanded, Some(&PropertyValue::Enumerated(1)), None)
+            .is_none());
+        let change = ee
+            .evaluate(&commanded, Some(&PropertyValue::Enumerated(0)), None)
+            .unwrap();
+        assert_eq!(change.event_type, EventType::CommandFailure);
+        assert_eq!(change.to_state, EventState::Offnormal);
+    }
+
+    #[test]
+    fn test_change_of_value_and_floating_limit() {
+        let mut ee = EventEnrollment::new(
+            3,
+            "Zone COV".to_string(),
+            reference(ObjectType::AnalogValue),
+            EventParameters::ChangeOfValue {
+                time_delay: 0,
+                criteria: CovCriteria::ReferencedPropertyIncrement(1.0),
+            },
+            1,
+        );
+        assert!(ee
+            .evaluate(&PropertyValue::Real(20.0), None, None)
+            .is_none());
+        assert!(ee
+            .evaluate(&PropertyValue::Real(20.5), None, None)
+            .is_none());
+        let change = ee.evaluate(&PropertyValue::Real(21.0), None, None).unwrap();
+        assert_eq!(change.to_state, EventState::Normal);
+
+        ee.set_event_parameters(EventParameters::FloatingLimit {
+            time_delay: 0,
+            setpoint_reference: reference(ObjectType::AnalogValue),
+            low_diff_limit: 2.0,
+            high_diff_limit: 2.0,
+            deadband: 0.5,
+        });
+        let setpoint = PropertyValue::Real(22.0);
+        let change = ee
+            .evaluate(&PropertyValue::Real(24.5), Some(&setpoint), None)
+            .unwrap();
+        assert_eq!(change.to_state, EventState::HighLimit);
+        assert!(matches!(
+            ee.get_property(PropertyIdentifier::EventType).unwrap(),
+            PropertyValue::Enumerated(4)
+        ));
+    }
+}
@@ -0,0 +1,136 @@
+//! Object Factory Registry
+//!
+//! CreateObject asks a device to make an object of a given type. The device
+//! can only do this for types it knows how to build, so the database keeps a
+//! registry of factories, one per object type, that make a new object from
+//! its instance number and name. Types without a factory cannot be created
+//! dynamically.
+
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, collections::BTreeMap as HashMap, string::String, vec::Vec};
+
+use super::{
+    AnalogValue, BacnetObject, BinaryValue, Calendar, CharacterStringValue, IntegerValue,
+    MultiStateValue, NotificationClass, ObjectType, PositiveIntegerValue,
+};
+
+/// Builds an object of one type from its instance number and name
+pub type ObjectFactory = Box<dyn Fn(u32, String) -> Box<dyn BacnetObject> + Send + Sync>;
+
+/// Factories for the object types that can be created dynamically
+#[derive(Default)]
+pub struct ObjectFactoryRegistry {
+    factories: HashMap<ObjectType, ObjectFactory>,
+}
+
+impl ObjectFactoryRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a registry with factories for the value, Calendar and
+    /// Notification Class objects, which need nothing beyond a name
+    ///
+    /// Multi-state Value objects start with two states.
+    pub fn with_standard_types() -> Self {
+        let mut registry = Self::new();
+        registry.register(ObjectType::AnalogValue, |instance, name| {
+            Box::new(AnalogValue::new(instance, name))
+        });
+        registry.register(ObjectType::BinaryValue, |instance, name| {
+            Box::new(BinaryValue::new(instance, name))
+        });
+        registry.register(ObjectType::MultiStateValue, |instance, name| {
+            Box::new(MultiStateValue::new(instance, name, 2))
+        });
+        registry.register(ObjectType::IntegerValue, |instance, name| {
+            Box::new(IntegerValue::new(instance, name))
+        });
+        registry.register(ObjectType::PositiveIntegerValue, |instance, name| {
+            Box::new(PositiveIntegerValue::new(instance, name))
+        });
+        registry.register(ObjectType::CharacterStringValue, |instance, name| {
+            Box::new(CharacterStringValue::new(instance, name))
+        });
+        registry.register(ObjectType::Calendar, |instance, name| {
+            Box::new(Calendar::new(instance, name))
+        });
+        registry.register(ObjectType::NotificationClass, |instance, name| {
+            Box::new(NotificationClass::new(instance, name))
+        });
+        registry
+    }
+
+    /// Register the factory for `object_type`, replacing any previous one
+    pub fn register<F>(&mut self, object_type: ObjectType, factory: F)
+    where
+        F: Fn(u32, String) -> Box<dyn BacnetObject> + Send + Sync + 'static,
+    {
+        self.factories.insert(object_type, Box::new(factory));
+    }
+
+    /// Remove the factory for `object_type`
+    pub fn unregister(&mut self, object_type: ObjectType) {
+        self.factories.remove(&object_type);
+    }
+
+    /// Check whether objects of `object_type` can be created
+    pub fn supports(&self, object_type: ObjectType) -> bool {
+        self.factories.contains_key(&object_type)
+    }
+
+    /// Build an object of `object_type`, or `None` without a factory for it
+    pub fn create(
+        &self,
+        object_type: ObjectType,
+        instance: u32,
+        name: String,
+    ) -> Option<Box<dyn BacnetObject>> {
+        self.factories
+            .get(&object_type)
+            .map(|factory| factory(instance, name))
+    }
+}
+
+impl core::fmt::Debug for ObjectFactoryRegistry {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ObjectFactoryRegistry")
+            .field("types", &self.factories.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::{ObjectIdentifier, PropertyIdentifier, PropertyValue};
+
+    #[test]
+    fn test_registry_creates_registered_types() {
+        let mut registry = ObjectFactoryRegistry::with_standard_types();
+        assert!(registry.supports(ObjectType::AnalogValue));
+        assert!(!registry.supports(ObjectType::AnalogInput));
+        assert!(registry
+            .create(ObjectType::AnalogInput, 1, String::from("AI"))
+            .is_none());
+
+        let object = registry
+            .create(ObjectType::AnalogValue, 4, String::from("Setpoint"))
+            .unwrap();
+        assert_eq!(
+            object.identifier(),
+            ObjectIdentifier::new(ObjectType::AnalogValue, 4)
+        );
+        assert_eq!(
+            object.get_property(PropertyIdentifier::ObjectName).unwrap(),
+            PropertyValue::CharacterString(String::from("Setpoint"))
+        );
+
+        registry.unregister(ObjectType::AnalogValue);
+        assert!(!registry.supports(ObjectType::AnalogValue));
+    }
+}
@@ -2,15 +2,23 @@
 //!
 //! This module implements the File object type as defined in ASHRAE 135.
 //! File objects represent files that can be accessed using the AtomicReadFile
-//! and AtomicWriteFile services.
+//! and AtomicWriteFile services. File contents live behind the [`FileStorage`]
+//! trait so that a device can serve in-memory buffers, flash partitions or files
+//! on a host file system through the same object.
 
 use crate::object::{
-    BacnetObject, ObjectError, ObjectIdentifier, ObjectType, PropertyIdentifier, PropertyValue,
-    Result,
+    current_date_time, date_time_value, BacnetObject, ObjectError, ObjectIdentifier, ObjectType,
+    PropertyIdentifier, PropertyValue, Result,
 };
+use crate::service::BacnetDateTime;
+use core::fmt;
 
 #[cfg(not(feature = "std"))]
-use alloc::{string::String, vec::Vec};
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+    vec::Vec,
+};
 
 /// File access method enumeration
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -20,8 +28,140 @@ pub enum FileAccessMethod {
     StreamAccess = 1,
 }
 
+/// Backing store for the contents of a File object
+///
+/// Positions and sizes are in octets. Implementations only deal with the byte
+/// stream; record access is layered on top by [`File`].
+pub trait FileStorage: Send + Sync + fmt::Debug {
+    /// Current size in octets
+    fn size(&self) -> u32;
+
+    /// Read up to `count` octets starting at `start`; short reads mean end of file
+    fn read(&self, start: u32, count: u32) -> Result<Vec<u8>>;
+
+    /// Write `data` at `start`, extending the file if needed
+    fn write(&mut self, start: u32, data: &[u8]) -> Result<()>;
+
+    /// Truncate or zero-extend the file to `size` octets
+    fn set_size(&mut self, size: u32) -> Result<()>;
+}
+
+/// File storage held in memory
+#[derive(Debug, Clone, Default)]
+pub struct MemoryFileStorage {
+    data: Vec<u8>,
+}
+
+impl MemoryFileStorage {
+    /// Create storage with initial contents
+    pub fn new(data: Vec<u8>) -> Self {
+        Self { data }
+    }
+}
+
+impl FileStorage for MemoryFileStorage {
+    fn size(&self) -> u32 {
+        self.data.len() as u32
+    }
+
+    fn read(&self, start: u32, count: u32) -> Result<Vec<u8>> {
+        let start = start as usize;
+        if start >= self.data.len() {
+            return Ok(Vec::new()); // EOF
+        }
+        let end = start.saturating_add(count as usize).min(self.data.len());
+        Ok(self.data[start..end].to_vec())
+    }
+
+    fn write(&mut self, start: u32, data: &[u8]) -> Result<()> {
+        let start = start as usize;
+        let required_len = start + data.len();
+        // Extend file if necessary
+        if required_len > self.data.len() {
+            self.data.resize(required_len, 0);
+        }
+        // Write the data (overwrite existing data at this position)
+        self.data[start..required_len].copy_from_slice(data);
+        Ok(())
+    }
+
+    fn set_size(&mut self, size: u32) -> Result<()> {
+        self.data.resize(size as usize, 0);
+        Ok(())
+    }
+}
+
+/// File storage backed by a file on the host file system
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct FsFileStorage {
+    file: std::sync::Mutex<std::fs::File>,
+}
+
+#[cfg(feature = "std")]
+impl FsFileStorage {
+    /// Open (or create) the file at `path` for reading and writing
+    pub fn open<P: AsRef<std::path::Path>>(path: P) -> std::io::Result<Self> {
+        let file = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+        Ok(Self {
+            file: std::sync::Mutex::new(file),
+        })
+    }
+
+    fn io_error(error: std::io::Error) -> ObjectError {
+        ObjectError::InvalidConfiguration(format!("File storage error: {}", error))
+    }
+}
+
+#[cfg(feature = "std")]
+impl FileStorage for FsFileStorage {
+    fn size(&self) -> u32 {
+        self.file
+            .lock()
+            .ok()
+            .and_then(|file| file.metadata().ok())
+            .map_or(0, |metadata| metadata.len().min(u32::MAX as u64) as u32)
+    }
+
+    fn read(&self, start: u32, count: u32) -> Result<Vec<u8>> {
+        use std::io::{Read, Seek, SeekFrom};
+
+        let mut file = self.file.lock().unwrap();
+        file.seek(SeekFrom::Start(start as u64))
+            .map_err(Self::io_error)?;
+        let mut buffer = Vec::new();
+        (&mut *file)
+            .take(count as u64)
+            .read_to_end(&mut buffer)
+            .map_err(Self::io_error)?;
+        Ok(buffer)
+    }
+
+    fn write(&mut self, start: u32, data: &[u8]) -> Result<()> {
+        use std::io::{Seek, SeekFrom, Write};
+
+        let file = self.file.get_mut().unwrap();
+        file.seek(SeekFrom::Start(start as u64))
+            .map_err(Self::io_error)?;
+        file.write_all(data).map_err(Self::io_error)
+    }
+
+    fn set_size(&mut self, size: u32) -> Result<()> {
+        self.file
+            .get_mut()
+            .unwrap()
+            .set_len(size as u64)
+            .map_err(Self::io_error)
+    }
+}
+
 /// File object implementation
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct File {
     /// Object identifier
     pub identifier: ObjectIdentifier,
@@ -29,71 +169,81 @@ pub struct File {
     pub object_name: String,
     /// File type (MIME type or file extension)
     pub file_type: String,
-    /// File size in octets
-    pub file_size: u32,
-    /// Modification date (BACnet Date format)
-    pub modification_date: crate::object::Date,
+    /// Time of the last modification, if known
+    pub modification_date: Option<BacnetDateTime>,
     /// Archive flag
     pub archive: bool,
     /// Read only flag
     pub read_only: bool,
     /// File access method
     pub file_access_method: FileAccessMethod,
-    /// Record count (for record access method)
-    pub record_count: Option<u32>,
     /// Description
     pub description: String,
-    /// File contents (in-memory storage for this implementation)
-    pub file_data: Vec<u8>,
+    /// File contents
+    storage: Box<dyn FileStorage>,
 }
 
 impl File {
-    /// Create a new File object
+    /// Create a new File object with empty in-memory contents
     pub fn new(instance: u32, object_name: String, file_type: String) -> Self {
+        Self::with_storage(
+            instance,
+            object_name,
+            file_type,
+            Box::new(MemoryFileStorage::default()),
+        )
+    }
+
+    /// Create a new File object over the given storage
+    pub fn with_storage(
+        instance: u32,
+        object_name: String,
+        file_type: String,
+        storage: Box<dyn FileStorage>,
+    ) -> Self {
         Self {
             identifier: ObjectIdentifier::new(ObjectType::File, instance),
             object_name,
             file_type,
-            file_size: 0,
-            modification_date: crate::object::Date {
-                year: 2024,
-                month: 1,
-                day: 1,
-                weekday: 1,
-            },
+            modification_date: None,
             archive: false,
             read_only: false,
             file_access_method: FileAccessMethod::StreamAccess,
-            record_count: None,
             description: String::new(),
-            file_data: Vec::new(),
+            storage,
         }
     }
 
-    /// Set file contents
-    pub fn set_file_data(&mut self, data: Vec<u8>) {
-        self.file_data = data;
-        self.file_size = self.file_data.len() as u32;
-        // Update modification date to current (simplified)
-        // In a real implementation, this would use actual system time
+    /// File size in octets
+    pub fn file_size(&self) -> u32 {
+        self.storage.size()
     }
 
-    /// Get file contents
-    pub fn get_file_data(&self) -> &[u8] {
-        &self.file_data
+    /// Number of records (for record access method)
+    pub fn record_count(&self) -> Option<u32> {
+        if self.file_access_method != FileAccessMethod::RecordAccess {
+            return None;
+        }
+        let data = self.storage.read(0, self.file_size()).ok()?;
+        Some(String::from_utf8_lossy(&data).lines().count() as u32)
     }
 
-    /// Read data from file at specified position
-    pub fn read_data(&self, start_position: u32, requested_count: u32) -> Result<Vec<u8>> {
-        let start = start_position as usize;
-        let end = (start_position + requested_count) as usize;
+    /// Replace the file contents
+    pub fn set_file_data(&mut self, data: Vec<u8>) -> Result<()> {
+        self.storage.set_size(0)?;
+        self.storage.write(0, &data)?;
+        self.touch();
+        Ok(())
+    }
 
-        if start >= self.file_data.len() {
-            return Ok(Vec::new()); // EOF
-        }
+    /// Get the complete file contents
+    pub fn get_file_data(&self) -> Result<Vec<u8>> {
+        self.storage.read(0, self.file_size())
+    }
 
-        let actual_end = end.min(self.file_data.len());
-        Ok(self.file_data[start..actual_end].to_vec())
+    /// Read data from file at specified position
+    pub fn read_data(&self, start_position: u32, requested_count: u32) -> Result<Vec<u8>> {
+        self.storage.read(start_position, requested_count)
     }
 
     /// Write data to file at specified position
@@ -101,23 +251,18 @@ impl File {
         if self.read_only {
             return Err(ObjectError::WriteAccessDenied);
         }
-
-        let start = start_position as usize;
-        let data_len = data.len();
-        let required_len = start + data_len;
-
-        // Extend file if necessary
-        if required_len > self.file_data.len() {
-            self.file_data.resize(required_len, 0);
-        }
-
-        // Write the data (overwrite existing data at this position)
-        self.file_data[start..start + data_len].copy_from_slice(data);
-        self.file_size = self.file_data.len() as u32;
-
+        self.storage.write(start_position, data)?;
+        self.touch();
         Ok(())
     }
 
+    /// Append data to the end of the file, returning the position it was written at
+    pub fn append_data(&mut self, data: &[u8]) -> Result<u32> {
+        let position = self.file_size();
+        self.write_data(position, data)?;
+        Ok(position)
+    }
+
     /// Read records from file (for record access method)
     pub fn read_records(&self, start_record: u32, record_count: u32) -> Result<Vec<Vec<u8>>> {
         if self.file_access_method != FileAccessMethod::RecordAccess {
@@ -126,22 +271,15 @@ impl File {
             ));
         }
 
-        // This is a simplified implementation
-        // In practice, records would have defined structure and separators
-        let mut records = Vec::new();
-
-        // For demonstration, treat each line as a record
-        let file_str = String::from_utf8_lossy(&self.file_data);
-        let lines: Vec<&str> = file_str.lines().collect();
-
-        let start_idx = start_record as usize;
-        let end_idx = (start_record + record_count) as usize;
-
-        for line in lines.iter().take(end_idx.min(lines.len())).skip(start_idx) {
-            records.push(line.as_bytes().to_vec());
-        }
-
-        Ok(records)
+        // Records are newline-separated lines of the stored data
+        let data = self.get_file_data()?;
+        let file_str = String::from_utf8_lossy(&data);
+        Ok(file_str
+            .lines()
+            .skip(start_record as usize)
+            .take(record_count as usize)
+            .map(|line| line.as_bytes().to_vec())
+            .collect())
     }
 
     /// Write records to file (for record access method)
@@ -156,9 +294,8 @@ impl File {
             ));
         }
 
-        // This is a simplified implementation
-        // Convert current data to lines
-        let file_str = String::from_utf8_lossy(&self.file_data);
+        let data = self.get_file_data()?;
+        let file_str = String::from_utf8_lossy(&data);
         let mut lines: Vec<String> = file_str.lines().map(|s| s.to_string()).collect();
 
         let start_idx = start_record as usize;
@@ -170,17 +307,26 @@ impl File {
 
         // Replace records
         for (i, record) in records.iter().enumerate() {
-            let record_str = String::from_utf8_lossy(record);
-            lines[start_idx + i] = record_str.to_string();
+            lines[start_idx + i] = String::from_utf8_lossy(record).to_string();
         }
 
-        // Convert back to file data
-        let new_data = lines.join("\n");
-        self.file_data = new_data.into_bytes();
-        self.file_size = self.file_data.len() as u32;
-        self.record_count = Some(lines.len() as u32);
+        self.set_file_data(lines.join("\n").into_bytes())
+    }
 
-        Ok(())
+    /// Keep only the first `count` records (for record access method)
+    pub fn truncate_records(&mut self, count: u32) -> Result<()> {
+        if self.read_only {
+            return Err(ObjectError::WriteAccessDenied);
+        }
+        let records = self.read_records(0, count)?;
+        let data = records.join(&b'\n');
+        self.set_file_data(data)
+    }
+
+    fn touch(&mut self) {
+        if let Some(now) = current_date_time() {
+            self.modification_date = Some(now);
+        }
     }
 }
 
@@ -198,9 +344,25 @@ impl BacnetObject for File {
                 Ok(PropertyValue::CharacterString(self.object_name.clone()))
             }
             PropertyIdentifier::ObjectType => {
-                Ok(PropertyValue::Enumerated(ObjectType::File as u32))
+                Ok(PropertyValue::Enumerated(u32::from(ObjectType::File)))
+            }
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
             }
+            PropertyIdentifier::FileType => {
+                Ok(PropertyValue::CharacterString(self.file_type.clone()))
+            }
+            PropertyIdentifier::FileSize => Ok(PropertyValue::UnsignedInteger(self.file_size())),
+            PropertyIdentifier::ModificationDate => Ok(date_time_value(self.modification_date)),
             PropertyIdentifier::Archive => Ok(PropertyValue::Boolean(self.archive)),
+            PropertyIdentifier::ReadOnly => Ok(PropertyValue::Boolean(self.read_only)),
+            PropertyIdentifier::FileAccessMethod => {
+                Ok(PropertyValue::Enumerated(self.file_access_method as u32))
+            }
+            PropertyIdentifier::RecordCount => self
+                .record_count()
+                .map(PropertyValue::UnsignedInteger)
+                .ok_or(ObjectError::UnknownProperty),
             _ => Err(ObjectError::UnknownProperty),
         }
     }
@@ -215,6 +377,14 @@ impl BacnetObject for File {
                     Err(ObjectError::InvalidPropertyType)
                 }
             }
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
             PropertyIdentifier::Archive => {
                 if let PropertyValue::Boolean(archive) = value {
                     self.archive = archive;
@@ -223,24 +393,75 @@ impl BacnetObject for File {
                     Err(ObjectError::InvalidPropertyType)
                 }
             }
+            // Writing File_Size truncates or extends a stream file
+            PropertyIdentifier::FileSize
+                if self.file_access_method == FileAccessMethod::StreamAccess =>
+            {
+                if let PropertyValue::UnsignedInteger(size) = value {
+                    if self.read_only {
+                        return Err(ObjectError::WriteAccessDenied);
+                    }
+                    self.storage.set_size(size)?;
+                    self.touch();
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            // Writing Record_Count truncates a record file
+            PropertyIdentifier::RecordCount
+                if self.file_access_method == FileAccessMethod::RecordAccess =>
+            {
+                if let PropertyValue::UnsignedInteger(count) = value {
+                    self.truncate_records(count)
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
             _ => Err(ObjectError::PropertyNotWritable),
         }
     }
 
     fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
-        matches!(
-            property,
-            PropertyIdentifier::ObjectName | PropertyIdentifier::Archive
-        )
+        match property {
+            PropertyIdentifier::ObjectName
+            | PropertyIdentifier::Description
+            | PropertyIdentifier::Archive => true,
+            PropertyIdentifier::FileSize => {
+                !self.read_only && self.file_access_method == FileAccessMethod::StreamAccess
+            }
+            PropertyIdentifier::RecordCount => {
+                !self.read_only && self.file_access_method == FileAccessMethod::RecordAccess
+            }
+            _ => false,
+        }
     }
 
     fn property_list(&self) -> Vec<PropertyIdentifier> {
-        vec![
+        let mut properties = vec![
             PropertyIdentifier::ObjectIdentifier,
             PropertyIdentifier::ObjectName,
             PropertyIdentifier::ObjectType,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::FileType,
+            PropertyIdentifier::FileSize,
+            PropertyIdentifier::ModificationDate,
             PropertyIdentifier::Archive,
-        ]
+            PropertyIdentifier::ReadOnly,
+            PropertyIdentifier::FileAccessMethod,
+        ];
+        if self.file_access_method == FileAccessMethod::RecordAccess {
+            properties.push(PropertyIdentifier::RecordCount);
+        }
+        properties
+    }
+
+    fn as_file(&self) -> Option<&File> {
+        Some(self)
+    }
+
+    fn as_file_mut(&mut self) -> Option<&mut File> {
+        Some(self)
     }
 }
 
@@ -254,7 +475,7 @@ mod tests {
         assert_eq!(file.identifier.instance, 1);
         assert_eq!(file.object_name, "config.txt");
         assert_eq!(file.file_type, "text/plain");
-        assert_eq!(file.file_size, 0);
+        assert_eq!(file.file_size(), 0);
     }
 
     #[test]
@@ -267,9 +488,9 @@ mod tests {
 
         // Set initial data
         let data = b"Hello, BACnet File!".to_vec();
-        file.set_file_data(data.clone());
-        assert_eq!(file.file_size, data.len() as u32);
-        assert_eq!(file.get_file_data(), data.as_slice());
+        file.set_file_data(data.clone()).unwrap();
+        assert_eq!(file.file_size(), data.len() as u32);
+        assert_eq!(file.get_file_data().unwrap(), data);
 
         // Test reading data
         let read_data = file.read_data(0, 5).unwrap();
@@ -281,7 +502,7 @@ mod tests {
         // Test writing data (overwrite "BACnet" with "Rust  ")
         file.write_data(7, b"Rust  ").unwrap();
         let expected = b"Hello, Rust   File!";
-        assert_eq!(file.get_file_data(), expected);
+        assert_eq!(file.get_file_data().unwrap(), expected);
     }
 
     #[test]
@@ -291,7 +512,7 @@ mod tests {
 
         // Set initial records as line-separated data
         let initial_data = "Line 1\nLine 2\nLine 3\nLine 4".as_bytes().to_vec();
-        file.set_file_data(initial_data);
+        file.set_file_data(initial_data).unwrap();
 
         // Read records
         let records = file.read_records(1, 2).unwrap();
@@ -341,4 +562,59 @@ mod tests {
         let records = vec![b"test".to_vec()];
         assert!(file.write_records(0, &records).is_err());
     }
+
+    #[test]
+    fn test_file_size_and_record_count_properties() {
+        let mut file = File::new(2, "log.txt".to_string(), "text/plain".to_string());
+        file.set_file_data(b"0123456789".to_vec()).unwrap();
+        assert_eq!(file.append_data(b"AB").unwrap(), 10);
+
+        file.set_property(
+            PropertyIdentifier::FileSize,
+            PropertyValue::UnsignedInteger(4),
+        )
+        .unwrap();
+        assert_eq!(file.get_file_data().unwrap(), b"0123");
+        assert!(matches!(
+            file.get_property(PropertyIdentifier::FileAccessMethod),
+            Ok(PropertyValue::Enumerated(1))
+        ));
+        assert!(file.get_property(PropertyIdentifier::RecordCount).is_err());
+
+        file.file_access_method = FileAccessMethod::RecordAccess;
+        file.set_file_data(b"a\nb\nc".to_vec()).unwrap();
+        assert_eq!(file.record_count(), Some(3));
+        file.set_property(
+            PropertyIdentifier::RecordCount,
+            PropertyValue::UnsignedInteger(1),
+        )
+        .unwrap();
+        assert_eq!(file.get_file_data().unwrap(), b"a");
+        assert!(file
+            .set_property(
+                PropertyIdentifier::FileSize,
+                PropertyValue::UnsignedInteger(0)
+            )
+            .is_err());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_file_system_storage() {
+        let path = std::env::temp_dir().join(format!("bacnet-file-{}.bin", std::process::id()));
+        let storage = FsFileStorage::open(&path).unwrap();
+        let mut file = File::with_storage(
+            3,
+            "backup.bin".to_string(),
+            "application/octet-stream".to_string(),
+            Box::new(storage),
+        );
+        file.set_file_data(b"persisted".to_vec()).unwrap();
+        file.write_data(0, b"P").unwrap();
+        assert_eq!(file.read_data(0, 4).unwrap(), b"Pers");
+        assert_eq!(file.file_size(), 9);
+        assert!(file.modification_date.is_some());
+        assert_eq!(std::fs::read(&path).unwrap(), b"Persisted");
+        std::fs::remove_file(&path).unwrap();
+    }
 }
@@ -0,0 +1,638 @@
+//! Global Group Object Type Implementation
+//!
+//! This module implements the Global Group object type as defined in ASHRAE 135. A
+//! Global Group collects properties of objects that may live in other devices, listed
+//! in Group_Members as BACnetDeviceObjectPropertyReferences. Present_Value holds the
+//! last known value of every member as a BACnetPropertyAccessResult.
+//!
+//! The object does not talk to the network itself. It hands out the SubscribeCOV
+//! requests needed to keep members fresh (renewed every COV_Resubscription_Interval)
+//! and a poll request every Update_Interval; the application performs them and feeds
+//! the results back through [`GlobalGroup::apply_cov_notification`] and
+//! [`GlobalGroup::update_member`]. Member_Status_Flags aggregates the members' status
+//! flags. When COVU_Recipients is configured, a COVU report of the group values
+//! becomes due whenever a member changes and every COVU_Period seconds.
+
+use crate::object::{
+    current_status_flags, notification_class::Recipient, status_flags_bit_string, BacnetObject,
+    DeviceObjectPropertyReference, EventState, ObjectError, ObjectIdentifier, ObjectType,
+    PropertyIdentifier, PropertyValue, Reliability, Result,
+};
+use crate::service::{PropertyAccessError, SubscribeCovRequest};
+use core::time::Duration;
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+/// Error reported for a member that has not been read yet
+/// (error class property, error code value-not-initialized)
+const VALUE_NOT_INITIALIZED: PropertyAccessError = PropertyAccessError {
+    error_class: 2,
+    error_code: 72,
+};
+
+/// Value or error for one member of a Global Group (BACnetPropertyAccessResult)
+#[derive(Debug, Clone, PartialEq)]
+pub struct PropertyAccessResult {
+    /// Object identifier
+    pub object_identifier: ObjectIdentifier,
+    /// Property identifier
+    pub property_identifier: PropertyIdentifier,
+    /// Property array index (optional)
+    pub property_array_index: Option<u32>,
+    /// Device containing the object (optional)
+    pub device_identifier: Option<ObjectIdentifier>,
+    /// The property value, or the error that prevented reading it
+    pub access_result: core::result::Result<PropertyValue, PropertyAccessError>,
+}
+
+impl PropertyAccessResult {
+    /// Encode the result as a property value
+    ///
+    /// The fields are the object, property, array index and device (Null if
+    /// absent), followed by the value or a list of the error class and code.
+    pub fn to_property_value(&self) -> PropertyValue {
+        PropertyValue::List(vec![
+            PropertyValue::ObjectIdentifier(self.object_identifier),
+            PropertyValue::Enumerated(u32::from(self.property_identifier)),
+            self.property_array_index
+                .map(PropertyValue::UnsignedInteger)
+                .unwrap_or(PropertyValue::Null),
+            self.device_identifier
+                .map(PropertyValue::ObjectIdentifier)
+                .unwrap_or(PropertyValue::Null),
+            match &self.access_result {
+                Ok(value) => value.clone(),
+                Err(error) => PropertyValue::List(vec![
+                    PropertyValue::Enumerated(error.error_class),
+                    PropertyValue::Enumerated(error.error_code),
+                ]),
+            },
+        ])
+    }
+}
+
+/// Global Group object
+#[derive(Debug, Clone)]
+pub struct GlobalGroup {
+    /// Object identifier
+    pub identifier: ObjectIdentifier,
+    /// Object name
+    pub object_name: String,
+    /// Description
+    pub description: String,
+    /// Event state
+    pub event_state: EventState,
+    /// Reliability
+    pub reliability: Reliability,
+    /// Out of service
+    pub out_of_service: bool,
+    /// Names of the members, in Group_Members order
+    pub group_member_names: Option<Vec<String>>,
+    /// Seconds between polls of the members
+    pub update_interval: Option<u32>,
+    /// Update interval requested by a client, in seconds
+    pub requested_update_interval: Option<u32>,
+    /// Seconds between renewals of the member COV subscriptions
+    pub cov_resubscription_interval: Option<u32>,
+    /// COV increment requested for analog members
+    pub client_cov_increment: Option<f32>,
+    /// Seconds between periodic COVU reports, zero for change-only reporting
+    pub covu_period: Option<u32>,
+    /// Recipients of COVU reports
+    pub covu_recipients: Option<Vec<Recipient>>,
+    /// Process identifier used for the member COV subscriptions
+    pub subscriber_process_identifier: u32,
+    group_members: Vec<DeviceObjectPropertyReference>,
+    present_value: Vec<PropertyAccessResult>,
+    member_flags: Vec<u8>,
+    time_since_poll: Duration,
+    time_since_subscription: Duration,
+    time_since_covu: Duration,
+    subscriptions_due: bool,
+    covu_due: bool,
+}
+
+impl GlobalGroup {
+    /// Create a new Global Group with no members
+    pub fn new(instance: u32, object_name: String) -> Self {
+        Self {
+            identifier: ObjectIdentifier::new(ObjectType::GlobalGroup, instance),
+            object_name,
+            description: String::new(),
+            event_state: EventState::Normal,
+            reliability: Reliability::NoFaultDetected,
+            out_of_service: false,
+            group_member_names: None,
+            update_interval: None,
+            requested_update_interval: None,
+            cov_resubscription_interval: None,
+            client_cov_increment: None,
+            covu_period: None,
+            covu_recipients: None,
+            subscriber_process_identifier: instance,
+            group_members: Vec::new(),
+            present_value: Vec::new(),
+            member_flags: Vec::new(),
+            time_since_poll: Duration::ZERO,
+            time_since_subscription: Duration::ZERO,
+            time_since_covu: Duration::ZERO,
+            subscriptions_due: false,
+            covu_due: false,
+        }
+    }
+
+    /// Add a member; its value is uninitialized until first read
+    pub fn add_member(&mut self, member: DeviceObjectPropertyReference) {
+        self.group_members.push(member);
+        self.present_value.push(PropertyAccessResult {
+            object_identifier: member.object_identifier,
+            property_identifier: member.property_identifier,
+            property_array_index: member.property_array_index,
+            device_identifier: member.device_identifier,
+            access_result: Err(VALUE_NOT_INITIALIZED),
+        });
+        self.member_flags.push(0);
+        self.subscriptions_due = true;
+    }
+
+    /// The members, in order
+    pub fn group_members(&self) -> &[DeviceObjectPropertyReference] {
+        &self.group_members
+    }
+
+    /// The last known member values, in Group_Members order
+    pub fn present_value(&self) -> &[PropertyAccessResult] {
+        &self.present_value
+    }
+
+    /// Record the value (or read error) of a member
+    ///
+    /// Returns `false` if `member` is not part of the group.
+    pub fn update_member(
+        &mut self,
+        member: &DeviceObjectPropertyReference,
+        result: core::result::Result<PropertyValue, PropertyAccessError>,
+    ) -> bool {
+        let mut found = false;
+        for (index, reference) in self.group_members.iter().enumerate() {
+            if reference == member {
+                found = true;
+                if self.present_value[index].access_result != result {
+                    self.present_value[index].access_result = result.clone();
+                    self.covu_due = true;
+                }
+            }
+        }
+        found
+    }
+
+    /// Apply a COV notification from `device` reporting `values` of `object`
+    ///
+    /// Every member referring to one of the reported properties is updated, and a
+    /// reported Status_Flags becomes the status of all members of that object.
+    pub fn apply_cov_notification(
+        &mut self,
+        device: ObjectIdentifier,
+        object: ObjectIdentifier,
+        values: &[(PropertyIdentifier, PropertyValue)],
+    ) {
+        let flags = values.iter().find_map(|(property, value)| match value {
+            PropertyValue::BitString(bits) if *property == PropertyIdentifier::StatusFlags => Some(
+                bits.iter()
+                    .take(4)
+                    .enumerate()
+                    .fold(0u8, |flags, (bit, &set)| flags | ((set as u8) << (3 - bit))),
+            ),
+            _ => None,
+        });
+        for index in 0..self.group_members.len() {
+            let member = self.group_members[index];
+            if member.object_identifier != object
+                || member.device_identifier.is_some_and(|id| id != device)
+            {
+                continue;
+            }
+            if let Some(flags) = flags {
+                self.member_flags[index] = flags;
+            }
+            let reported = values
+                .iter()
+                .find(|(property, _)| *property == member.property_identifier);
+            if let Some((_, value)) = reported {
+                if member.property_array_index.is_none()
+                    && self.present_value[index].access_result.as_ref() != Ok(value)
+                {
+                    self.present_value[index].access_result = Ok(value.clone());
+                    self.covu_due = true;
+                }
+            }
+        }
+    }
+
+    /// Status flags combined over every member
+    ///
+    /// A member whose value could not be read counts as in fault.
+    pub fn member_status_flags(&self) -> u8 {
+        self.member_flags
+            .iter()
+            .zip(&self.present_value)
+            .fold(0, |flags, (&member, value)| {
+                let fault = if value.access_result.is_err() {
+                    0x04
+                } else {
+                    0
+                };
+                flags | member | fault
+            })
+    }
+
+    /// SubscribeCOV requests for every member object, paired with the device
+    /// they must be sent to, if renewing the subscriptions is due
+    ///
+    /// Subscriptions are due when members are added and every
+    /// COV_Resubscription_Interval; the lifetime is twice that interval so a
+    /// subscription outlives one missed renewal. Taking them clears the request.
+    pub fn take_cov_subscriptions(
+        &mut self,
+    ) -> Vec<(Option<ObjectIdentifier>, SubscribeCovRequest)> {
+        if !core::mem::take(&mut self.subscriptions_due) {
+            return Vec::new();
+        }
+        let mut targets: Vec<(Option<ObjectIdentifier>, ObjectIdentifier)> = Vec::new();
+        for member in &self.group_members {
+            let target = (member.device_identifier, member.object_identifier);
+            if !targets.contains(&target) {
+                targets.push(target);
+            }
+        }
+        targets
+            .into_iter()
+            .map(|(device, object)| {
+                let mut request =
+                    SubscribeCovRequest::new(self.subscriber_process_identifier, object);
+                request.issue_confirmed_notifications = Some(false);
+                request.lifetime = self
+                    .cov_resubscription_interval
+                    .map(|interval| interval.saturating_mul(2));
+                (device, request)
+            })
+            .collect()
+    }
+
+    /// The members to read, if a poll is due every Update_Interval
+    ///
+    /// Taking the request restarts the interval.
+    pub fn take_poll_request(&mut self) -> Option<&[DeviceObjectPropertyReference]> {
+        let interval = Duration::from_secs(self.update_interval.filter(|&s| s > 0)? as u64);
+        if self.time_since_poll < interval {
+            return None;
+        }
+        self.time_since_poll = Duration::ZERO;
+        Some(&self.group_members)
+    }
+
+    /// The group values to report to COVU_Recipients, if a report is due
+    pub fn take_covu_report(&mut self) -> Option<Vec<PropertyAccessResult>> {
+        if self.covu_recipients.as_ref().is_none_or(|r| r.is_empty()) {
+            return None;
+        }
+        if !core::mem::take(&mut self.covu_due) {
+            return None;
+        }
+        self.time_since_covu = Duration::ZERO;
+        Some(self.present_value.clone())
+    }
+
+    fn current_status_flags(&self) -> u8 {
+        current_status_flags(0, self.event_state, self.reliability, self.out_of_service)
+    }
+}
+
+impl BacnetObject for GlobalGroup {
+    fn identifier(&self) -> ObjectIdentifier {
+        self.identifier
+    }
+
+    fn get_property(&self, property: PropertyIdentifier) -> Result<PropertyValue> {
+        let optional_unsigned = |value: Option<u32>| {
+            value
+                .map(PropertyValue::UnsignedInteger)
+                .ok_or(ObjectError::UnknownProperty)
+        };
+        match property {
+            PropertyIdentifier::ObjectIdentifier => {
+                Ok(PropertyValue::ObjectIdentifier(self.identifier))
+            }
+            PropertyIdentifier::ObjectName => {
+                Ok(PropertyValue::CharacterString(self.object_name.clone()))
+            }
+            PropertyIdentifier::ObjectType => Ok(PropertyValue::Enumerated(u32::from(
+                ObjectType::GlobalGroup,
+            ))),
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::GroupMembers => Ok(PropertyValue::Array(
+                self.group_members
+                    .iter()
+                    .map(DeviceObjectPropertyReference::to_property_value)
+                    .collect(),
+            )),
+            PropertyIdentifier::GroupMemberNames => self
+                .group_member_names
+                .as_ref()
+                .map(|names| {
+                    PropertyValue::Array(
+                        names
+                            .iter()
+                            .cloned()
+                            .map(PropertyValue::CharacterString)
+                            .collect(),
+                    )
+                })
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::PresentValue => Ok(PropertyValue::Array(
+                self.present_value
+                    .iter()
+                    .map(PropertyAccessResult::to_property_value)
+                    .collect(),
+            )),
+            PropertyIdentifier::StatusFlags => {
+                Ok(status_flags_bit_string(self.current_status_flags()))
+            }
+            PropertyIdentifier::EventState => {
+                Ok(PropertyValue::Enumerated(self.event_state as u32))
+            }
+            PropertyIdentifier::MemberStatusFlags => {
+                Ok(status_flags_bit_string(self.member_status_flags()))
+            }
+            PropertyIdentifier::Reliability => {
+                Ok(PropertyValue::Enumerated(self.reliability as u32))
+            }
+            PropertyIdentifier::OutOfService => Ok(PropertyValue::Boolean(self.out_of_service)),
+            PropertyIdentifier::UpdateInterval => optional_unsigned(self.update_interval),
+            PropertyIdentifier::RequestedUpdateInterval => {
+                optional_unsigned(self.requested_update_interval)
+            }
+            PropertyIdentifier::CovResubscriptionInterval => {
+                optional_unsigned(self.cov_resubscription_interval)
+            }
+            PropertyIdentifier::ClientCovIncrement => self
+                .client_cov_increment
+                .map(PropertyValue::Real)
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::CovuPeriod => optional_unsigned(self.covu_period),
+            PropertyIdentifier::CovuRecipients => self
+                .covu_recipients
+                .as_ref()
+                .map(|recipients| {
+                    PropertyValue::List(
+                        recipients
+                            .iter()
+                            .map(Recipient::to_property_value)
+                            .collect(),
+                    )
+                })
+                .ok_or(ObjectError::UnknownProperty),
+            _ => Err(ObjectError::UnknownProperty),
+        }
+    }
+
+    fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        let unsigned = |value: PropertyValue| match value {
+            PropertyValue::UnsignedInteger(v) => Ok(v),
+            _ => Err(ObjectError::InvalidPropertyType),
+        };
+        match property {
+            PropertyIdentifier::ObjectName => {
+                if let PropertyValue::CharacterString(name) = value {
+                    self.object_name = name;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::OutOfService => {
+                if let PropertyValue::Boolean(oos) = value {
+                    self.out_of_service = oos;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::RequestedUpdateInterval
+                if self.requested_update_interval.is_some() =>
+            {
+                let seconds = unsigned(value)?;
+                self.requested_update_interval = Some(seconds);
+                if self.update_interval.is_some() {
+                    self.update_interval = Some(seconds);
+                }
+                Ok(())
+            }
+            PropertyIdentifier::CovResubscriptionInterval
+                if self.cov_resubscription_interval.is_some() =>
+            {
+                self.cov_resubscription_interval = Some(unsigned(value)?);
+                self.subscriptions_due = true;
+                Ok(())
+            }
+            PropertyIdentifier::ClientCovIncrement if self.client_cov_increment.is_some() => {
+                if let PropertyValue::Real(increment) = value {
+                    self.client_cov_increment = Some(increment);
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::CovuPeriod if self.covu_period.is_some() => {
+                self.covu_period = Some(unsigned(value)?);
+                self.time_since_covu = Duration::ZERO;
+                Ok(())
+            }
+            PropertyIdentifier::CovuRecipients if self.covu_recipients.is_some() => {
+                if let PropertyValue::List(items) = value {
+                    let recipients = items
+                        .iter()
+                        .map(Recipient::from_property_value)
+                        .collect::<Result<Vec<_>>>()?;
+                    self.covu_recipients = Some(recipients);
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            _ => Err(ObjectError::PropertyNotWritable),
+        }
+    }
+
+    fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
+        match property {
+            PropertyIdentifier::ObjectName
+            | PropertyIdentifier::Description
+            | PropertyIdentifier::OutOfService => true,
+            PropertyIdentifier::RequestedUpdateInterval => self.requested_update_interval.is_some(),
+            PropertyIdentifier::CovResubscriptionInterval => {
+                self.cov_resubscription_interval.is_some()
+            }
+            PropertyIdentifier::ClientCovIncrement => self.client_cov_increment.is_some(),
+            PropertyIdentifier::CovuPeriod => self.covu_period.is_some(),
+            PropertyIdentifier::CovuRecipients => self.covu_recipients.is_some(),
+            _ => false,
+        }
+    }
+
+    fn property_list(&self) -> Vec<PropertyIdentifier> {
+        let mut properties = vec![
+            PropertyIdentifier::ObjectIdentifier,
+            PropertyIdentifier::ObjectName,
+            PropertyIdentifier::ObjectType,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::GroupMembers,
+        ];
+        if self.group_member_names.is_some() {
+            properties.push(PropertyIdentifier::GroupMemberNames);
+        }
+        properties.extend([
+            PropertyIdentifier::PresentValue,
+            PropertyIdentifier::StatusFlags,
+            PropertyIdentifier::EventState,
+            PropertyIdentifier::MemberStatusFlags,
+            PropertyIdentifier::Reliability,
+            PropertyIdentifier::OutOfService,
+        ]);
+        let optional = [
+            (
+                self.update_interval.is_some(),
+                PropertyIdentifier::UpdateInterval,
+            ),
+            (
+                self.requested_update_interval.is_some(),
+                PropertyIdentifier::RequestedUpdateInterval,
+            ),
+            (
+                self.cov_resubscription_interval.is_some(),
+                PropertyIdentifier::CovResubscriptionInterval,
+            ),
+            (
+                self.client_cov_increment.is_some(),
+                PropertyIdentifier::ClientCovIncrement,
+            ),
+            (self.covu_period.is_some(), PropertyIdentifier::CovuPeriod),
+            (
+                self.covu_recipients.is_some(),
+                PropertyIdentifier::CovuRecipients,
+            ),
+        ];
+        properties.extend(
+            optional
+                .into_iter()
+                .filter(|(present, _)| *present)
+                .map(|(_, property)| property),
+        );
+        properties
+    }
+
+    fn advance_time(&mut self, elapsed: Duration) {
+        self.time_since_poll = self.time_since_poll.saturating_add(elapsed);
+
+        if let Some(interval) = self.cov_resubscription_interval.filter(|&s| s > 0) {
+            self.time_since_subscription = self.time_since_subscription.saturating_add(elapsed);
+            if self.time_since_subscription >= Duration::from_secs(interval as u64) {
+                self.time_since_subscription = Duration::ZERO;
+                self.subscriptions_due = true;
+            }
+        }
+
+        if let Some(period) = self.covu_period.filter(|&s| s > 0) {
+            self.time_since_covu = self.time_since_covu.saturating_add(elapsed);
+            if self.time_since_covu >= Duration::from_secs(period as u64) {
+                self.covu_due = true;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn remote_member(instance: u32) -> DeviceObjectPropertyReference {
+        DeviceObjectPropertyReference {
+            device_identifier: Some(ObjectIdentifier::new(ObjectType::Device, 200)),
+            ..DeviceObjectPropertyReference::new(
+                ObjectIdentifier::new(ObjectType::AnalogInput, instance),
+                PropertyIdentifier::PresentValue,
+            )
+        }
+    }
+
+    #[test]
+    fn test_cov_refresh_and_member_status() {
+        let mut group = GlobalGroup::new(1, "Remote Temps".to_string());
+        group.cov_resubscription_interval = Some(300);
+        group.add_member(remote_member(1));
+        group.add_member(remote_member(2));
+
+        let subscriptions = group.take_cov_subscriptions();
+        assert_eq!(subscriptions.len(), 2);
+        assert_eq!(subscriptions[0].1.lifetime, Some(600));
+        assert!(group.take_cov_subscriptions().is_empty());
+        group.advance_time(Duration::from_secs(300));
+        assert_eq!(group.take_cov_subscriptions().len(), 2);
+
+        // Uninitialized members count as faults
+        assert_eq!(group.member_status_flags(), 0x04);
+
+        let device = ObjectIdentifier::new(ObjectType::Device, 200);
+        group.apply_cov_notification(
+            device,
+            ObjectIdentifier::new(ObjectType::AnalogInput, 1),
+            &[
+                (PropertyIdentifier::PresentValue, PropertyValue::Real(20.0)),
+                (
+                    PropertyIdentifier::StatusFlags,
+                    PropertyValue::BitString(vec![true, false, false, false]),
+                ),
+            ],
+        );
+        assert!(group.update_member(&remote_member(2), Ok(PropertyValue::Real(22.0))));
+        assert_eq!(
+            group.present_value()[0].access_result,
+            Ok(PropertyValue::Real(20.0))
+        );
+        assert_eq!(group.member_status_flags(), 0x08);
+    }
+
+    #[test]
+    fn test_covu_reporting() {
+        let mut group = GlobalGroup::new(2, "Reported".to_string());
+        group.add_member(remote_member(1));
+        group.update_member(&remote_member(1), Ok(PropertyValue::Real(1.0)));
+        assert!(group.take_covu_report().is_none());
+
+        group.covu_recipients = Some(vec![Recipient::Device(ObjectIdentifier::new(
+            ObjectType::Device,
+            9,
+        ))]);
+        group.covu_period = Some(60);
+        group.update_member(&remote_member(1), Ok(PropertyValue::Real(2.0)));
+        assert_eq!(group.take_covu_report().unwrap().len(), 1);
+        assert!(group.take_covu_report().is_none());
+
+        group.advance_time(Duration::from_secs(60));
+        assert!(group.take_covu_report().is_some());
+        assert!(group
+            .property_list()
+            .contains(&PropertyIdentifier::CovuRecipients));
+    }
+}
@@ -0,0 +1,190 @@
+//! Group Object Type Implementation
+//!
+//! This module implements the Group object type as defined in ASHRAE 135. A Group
+//! names a set of properties of local objects through List_Of_Group_Members, a list
+//! of ReadAccessSpecifications. Reading Present_Value returns the current values of
+//! every member as ReadAccessResults, exactly as ReadPropertyMultiple would.
+//!
+//! The member values live in other objects, so Present_Value is resolved by the
+//! [`ObjectDatabase`](crate::object::database::ObjectDatabase) holding the Group.
+//! Outside a database, [`Group::read_members`] evaluates the members with a
+//! caller-supplied reader.
+
+use crate::object::{
+    BacnetObject, ObjectError, ObjectIdentifier, ObjectType, PropertyIdentifier, PropertyValue,
+    Result,
+};
+use crate::service::{ReadAccessResult, ReadAccessSpecification};
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+/// Group object
+#[derive(Debug, Clone)]
+pub struct Group {
+    /// Object identifier
+    pub identifier: ObjectIdentifier,
+    /// Object name
+    pub object_name: String,
+    /// Description
+    pub description: String,
+    /// Properties whose values make up Present_Value
+    pub list_of_group_members: Vec<ReadAccessSpecification>,
+}
+
+impl Group {
+    /// Create a new Group with no members
+    pub fn new(instance: u32, object_name: String) -> Self {
+        Self {
+            identifier: ObjectIdentifier::new(ObjectType::Group, instance),
+            object_name,
+            description: String::new(),
+            list_of_group_members: Vec::new(),
+        }
+    }
+
+    /// Add the properties of one object to the group
+    pub fn add_member(&mut self, member: ReadAccessSpecification) {
+        self.list_of_group_members.push(member);
+    }
+
+    /// Read every member with `read`, in List_Of_Group_Members order
+    pub fn read_members<F>(&self, read: F) -> Vec<ReadAccessResult>
+    where
+        F: FnMut(&ReadAccessSpecification) -> ReadAccessResult,
+    {
+        self.list_of_group_members.iter().map(read).collect()
+    }
+
+    /// Encode member results as the Group's Present_Value
+    pub fn present_value_from(results: &[ReadAccessResult]) -> PropertyValue {
+        PropertyValue::List(
+            results
+                .iter()
+                .map(ReadAccessResult::to_property_value)
+                .collect(),
+        )
+    }
+}
+
+impl BacnetObject for Group {
+    fn identifier(&self) -> ObjectIdentifier {
+        self.identifier
+    }
+
+    fn get_property(&self, property: PropertyIdentifier) -> Result<PropertyValue> {
+        match property {
+            PropertyIdentifier::ObjectIdentifier => {
+                Ok(PropertyValue::ObjectIdentifier(self.identifier))
+            }
+            PropertyIdentifier::ObjectName => {
+                Ok(PropertyValue::CharacterString(self.object_name.clone()))
+            }
+            PropertyIdentifier::ObjectType => {
+                Ok(PropertyValue::Enumerated(u32::from(ObjectType::Group)))
+            }
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::ListOfGroupMembers => Ok(PropertyValue::List(
+                self.list_of_group_members
+                    .iter()
+                    .map(ReadAccessSpecification::to_property_value)
+                    .collect(),
+            )),
+            PropertyIdentifier::PresentValue => Err(ObjectError::InvalidConfiguration(
+                "Group Present_Value is resolved by the object database".to_string(),
+            )),
+            _ => Err(ObjectError::UnknownProperty),
+        }
+    }
+
+    fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        match property {
+            PropertyIdentifier::ObjectName => {
+                if let PropertyValue::CharacterString(name) = value {
+                    self.object_name = name;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            _ => Err(ObjectError::PropertyNotWritable),
+        }
+    }
+
+    fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
+        matches!(
+            property,
+            PropertyIdentifier::ObjectName | PropertyIdentifier::Description
+        )
+    }
+
+    fn property_list(&self) -> Vec<PropertyIdentifier> {
+        vec![
+            PropertyIdentifier::ObjectIdentifier,
+            PropertyIdentifier::ObjectName,
+            PropertyIdentifier::ObjectType,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::ListOfGroupMembers,
+            PropertyIdentifier::PresentValue,
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::service::{PropertyReference, ReadResult};
+
+    #[test]
+    fn test_group_members_round_trip() {
+        let mut group = Group::new(1, "Zone Temps".to_string());
+        group.add_member(ReadAccessSpecification::new(
+            ObjectIdentifier::new(ObjectType::AnalogInput, 1),
+            vec![
+                PropertyReference::new(u32::from(PropertyIdentifier::PresentValue)),
+                PropertyReference::with_array_index(
+                    u32::from(PropertyIdentifier::PriorityArray),
+                    8,
+                ),
+            ],
+        ));
+
+        let encoded = group
+            .get_property(PropertyIdentifier::ListOfGroupMembers)
+            .unwrap();
+        let PropertyValue::List(members) = encoded else {
+            panic!("Expected List");
+        };
+        let decoded = ReadAccessSpecification::from_property_value(&members[0]).unwrap();
+        assert_eq!(decoded.object_identifier.instance, 1);
+        assert_eq!(decoded.property_references[1].property_array_index, Some(8));
+
+        let results = group.read_members(|member| ReadAccessResult {
+            object_identifier: member.object_identifier,
+            list_of_results: member
+                .property_references
+                .iter()
+                .map(|reference| ReadResult {
+                    property_identifier: reference.property_identifier,
+                    property_array_index: reference.property_array_index,
+                    read_result: Ok(PropertyValue::Real(20.5)),
+                })
+                .collect(),
+        });
+        assert_eq!(results[0].list_of_results.len(), 2);
+        assert!(matches!(
+            Group::present_value_from(&results),
+            PropertyValue::List(items) if items.len() == 1
+        ));
+    }
+}
@@ -0,0 +1,381 @@
+//! Large Analog Value Object Type Implementation
+//!
+//! This module implements the Large Analog Value object type as defined in ASHRAE
+//! 135. It mirrors the Analog Value object but holds a double-precision
+//! Present_Value, for energy totals and other quantities that overflow REAL
+//! precision. COV_Increment, Min/Max_Pres_Value and Resolution are Double as well.
+
+use crate::object::{
+    current_status_flags, engineering_units::EngineeringUnits, status_flags_bit_string,
+    BacnetObject, Commandable, EventState, ObjectError, ObjectIdentifier, ObjectType,
+    PropertyIdentifier, PropertyValue, Reliability, Result, DEFAULT_COMMAND_PRIORITY,
+};
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+/// Large Analog Value object
+#[derive(Debug, Clone)]
+pub struct LargeAnalogValue {
+    /// Object identifier
+    pub identifier: ObjectIdentifier,
+    /// Object name
+    pub object_name: String,
+    /// Description
+    pub description: String,
+    /// Present value
+    pub present_value: f64,
+    /// Status flags
+    pub status_flags: u8,
+    /// Event state
+    pub event_state: EventState,
+    /// Reliability
+    pub reliability: Reliability,
+    /// Out of service
+    pub out_of_service: bool,
+    /// Units
+    pub units: EngineeringUnits,
+    /// Priority array and relinquish default, present only on commandable
+    /// instances
+    pub commandable: Option<Commandable<f64>>,
+    /// COV increment
+    pub cov_increment: Option<f64>,
+    /// Lowest value Present_Value may take
+    pub min_pres_value: Option<f64>,
+    /// Highest value Present_Value may take
+    pub max_pres_value: Option<f64>,
+    /// Smallest change in Present_Value the device distinguishes
+    pub resolution: Option<f64>,
+}
+
+impl LargeAnalogValue {
+    /// Create a new non-commandable Large Analog Value object
+    pub fn new(instance: u32, object_name: String) -> Self {
+        Self {
+            identifier: ObjectIdentifier::new(ObjectType::LargeAnalogValue, instance),
+            object_name,
+            description: String::new(),
+            present_value: 0.0,
+            status_flags: 0,
+            event_state: EventState::Normal,
+            reliability: Reliability::NoFaultDetected,
+            out_of_service: false,
+            units: EngineeringUnits::NoUnits,
+            commandable: None,
+            cov_increment: None,
+            min_pres_value: None,
+            max_pres_value: None,
+            resolution: None,
+        }
+    }
+
+    /// Create a new commandable Large Analog Value object with a priority array
+    pub fn new_commandable(instance: u32, object_name: String, relinquish_default: f64) -> Self {
+        let mut value = Self::new(instance, object_name);
+        value.commandable = Some(Commandable::new(relinquish_default));
+        value.present_value = relinquish_default;
+        value
+    }
+
+    /// Whether Present_Value writes are arbitrated through a priority array
+    pub fn is_commandable(&self) -> bool {
+        self.commandable.is_some()
+    }
+
+    /// Set the present value, enforcing Min_Pres_Value and Max_Pres_Value
+    pub fn set_present_value(&mut self, value: f64) -> Result<()> {
+        self.check_range(value)?;
+        self.present_value = value;
+        Ok(())
+    }
+
+    /// Write to priority array at specified priority level (1-16)
+    pub fn write_priority(&mut self, priority: u8, value: Option<f64>) -> Result<()> {
+        if !(1..=16).contains(&priority) {
+            return Err(ObjectError::InvalidValue(
+                "Priority must be 1-16".to_string(),
+            ));
+        }
+        if let Some(value) = value {
+            self.check_range(value)?;
+        }
+        let Some(commandable) = self.commandable.as_mut() else {
+            return Err(ObjectError::InvalidConfiguration(
+                "Large Analog Value is not commandable".to_string(),
+            ));
+        };
+        commandable.write(priority, value)?;
+        self.update_present_value();
+        Ok(())
+    }
+
+    /// Update present value based on priority array
+    fn update_present_value(&mut self) {
+        if let Some(commandable) = self.commandable.as_ref() {
+            self.present_value = *commandable.current();
+        }
+    }
+
+    fn check_range(&self, value: f64) -> Result<()> {
+        if self.min_pres_value.is_some_and(|min| value < min)
+            || self.max_pres_value.is_some_and(|max| value > max)
+        {
+            return Err(ObjectError::InvalidValue(format!(
+                "{} is outside Min_Pres_Value..Max_Pres_Value",
+                value
+            )));
+        }
+        Ok(())
+    }
+
+    /// Current Status_Flags, combining the stored flags with the state derived
+    /// from Event_State, Reliability and Out_Of_Service
+    pub fn current_status_flags(&self) -> u8 {
+        current_status_flags(
+            self.status_flags,
+            self.event_state,
+            self.reliability,
+            self.out_of_service,
+        )
+    }
+}
+
+impl BacnetObject for LargeAnalogValue {
+    fn identifier(&self) -> ObjectIdentifier {
+        self.identifier
+    }
+
+    fn get_property(&self, property: PropertyIdentifier) -> Result<PropertyValue> {
+        match property {
+            PropertyIdentifier::ObjectIdentifier => {
+                Ok(PropertyValue::ObjectIdentifier(self.identifier))
+            }
+            PropertyIdentifier::ObjectName => {
+                Ok(PropertyValue::CharacterString(self.object_name.clone()))
+            }
+            PropertyIdentifier::ObjectType => Ok(PropertyValue::Enumerated(u32::from(
+                ObjectType::LargeAnalogValue,
+            ))),
+            PropertyIdentifier::PresentValue => Ok(PropertyValue::Double(self.present_value)),
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::StatusFlags => {
+                Ok(status_flags_bit_string(self.current_status_flags()))
+            }
+            PropertyIdentifier::EventState => {
+                Ok(PropertyValue::Enumerated(self.event_state as u32))
+            }
+            PropertyIdentifier::Reliability => {
+                Ok(PropertyValue::Enumerated(self.reliability as u32))
+            }
+            PropertyIdentifier::OutOfService => Ok(PropertyValue::Boolean(self.out_of_service)),
+            PropertyIdentifier::Units => Ok(PropertyValue::Enumerated(self.units.to_u32())),
+            PropertyIdentifier::PriorityArray => self
+                .commandable
+                .as_ref()
+                .map(|commandable| {
+                    commandable.priority_array_value(|&value| PropertyValue::Double(value))
+                })
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::RelinquishDefault => self
+                .commandable
+                .as_ref()
+                .map(|commandable| PropertyValue::Double(*commandable.relinquish_default()))
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::CovIncrement => self
+                .cov_increment
+                .map(PropertyValue::Double)
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::MinPresValue => self
+                .min_pres_value
+                .map(PropertyValue::Double)
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::MaxPresValue => self
+                .max_pres_value
+                .map(PropertyValue::Double)
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::Resolution => self
+                .resolution
+                .map(PropertyValue::Double)
+                .ok_or(ObjectError::UnknownProperty),
+            _ => Err(ObjectError::UnknownProperty),
+        }
+    }
+
+    fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        self.set_property_with_priority(property, value, DEFAULT_COMMAND_PRIORITY)
+    }
+
+    fn set_property_with_priority(
+        &mut self,
+        property: PropertyIdentifier,
+        value: PropertyValue,
+        priority: u8,
+    ) -> Result<()> {
+        match property {
+            PropertyIdentifier::ObjectName => {
+                if let PropertyValue::CharacterString(name) = value {
+                    self.object_name = name;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::PresentValue => match value {
+                PropertyValue::Double(val) if self.is_commandable() => {
+                    self.write_priority(priority, Some(val))
+                }
+                PropertyValue::Double(val) => self.set_present_value(val),
+                // Writing NULL relinquishes the command at this priority
+                PropertyValue::Null if self.is_commandable() => self.write_priority(priority, None),
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            PropertyIdentifier::RelinquishDefault if self.is_commandable() => {
+                if let PropertyValue::Double(val) = value {
+                    self.check_range(val)?;
+                    if let Some(commandable) = self.commandable.as_mut() {
+                        commandable.set_relinquish_default(val);
+                    }
+                    self.update_present_value();
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::OutOfService => {
+                if let PropertyValue::Boolean(oos) = value {
+                    self.out_of_service = oos;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Units => {
+                if let PropertyValue::Enumerated(units) = value {
+                    self.units = EngineeringUnits::from_u32(units);
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::CovIncrement => {
+                if let PropertyValue::Double(increment) = value {
+                    if increment < 0.0 {
+                        return Err(ObjectError::InvalidValue(
+                            "COV increment must not be negative".to_string(),
+                        ));
+                    }
+                    self.cov_increment = Some(increment);
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            _ => Err(ObjectError::PropertyNotWritable),
+        }
+    }
+
+    fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
+        match property {
+            PropertyIdentifier::ObjectName
+            | PropertyIdentifier::Description
+            | PropertyIdentifier::PresentValue
+            | PropertyIdentifier::OutOfService
+            | PropertyIdentifier::Units
+            | PropertyIdentifier::CovIncrement => true,
+            PropertyIdentifier::RelinquishDefault => self.is_commandable(),
+            _ => false,
+        }
+    }
+
+    fn property_list(&self) -> Vec<PropertyIdentifier> {
+        let mut properties = vec![
+            PropertyIdentifier::ObjectIdentifier,
+            PropertyIdentifier::ObjectName,
+            PropertyIdentifier::ObjectType,
+            PropertyIdentifier::PresentValue,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::StatusFlags,
+            PropertyIdentifier::EventState,
+            PropertyIdentifier::Reliability,
+            PropertyIdentifier::OutOfService,
+            PropertyIdentifier::Units,
+        ];
+        if self.is_commandable() {
+            properties.push(PropertyIdentifier::PriorityArray);
+            properties.push(PropertyIdentifier::RelinquishDefault);
+        }
+        let optional = [
+            (
+                self.cov_increment.is_some(),
+                PropertyIdentifier::CovIncrement,
+            ),
+            (
+                self.min_pres_value.is_some(),
+                PropertyIdentifier::MinPresValue,
+            ),
+            (
+                self.max_pres_value.is_some(),
+                PropertyIdentifier::MaxPresValue,
+            ),
+            (self.resolution.is_some(), PropertyIdentifier::Resolution),
+        ];
+        properties.extend(optional.iter().filter(|(p, _)| *p).map(|&(_, id)| id));
+        properties
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_large_analog_value_precision() {
+        let mut lav = LargeAnalogValue::new(1, "Site Energy".to_string());
+        lav.units = EngineeringUnits::KilowattHours;
+        let total = 123_456_789.125;
+        lav.set_property(
+            PropertyIdentifier::PresentValue,
+            PropertyValue::Double(total),
+        )
+        .unwrap();
+        assert_eq!(
+            lav.get_property(PropertyIdentifier::PresentValue).unwrap(),
+            PropertyValue::Double(total)
+        );
+        assert!(matches!(
+            lav.set_property(PropertyIdentifier::PresentValue, PropertyValue::Real(1.0)),
+            Err(ObjectError::InvalidPropertyType)
+        ));
+    }
+
+    #[test]
+    fn test_large_analog_value_cov_increment() {
+        let mut lav = LargeAnalogValue::new_commandable(2, "Demand Limit".to_string(), 500.0);
+        lav.set_property(PropertyIdentifier::CovIncrement, PropertyValue::Double(0.5))
+            .unwrap();
+        assert!(lav
+            .property_list()
+            .contains(&PropertyIdentifier::CovIncrement));
+        assert!(lav
+            .set_property(
+                PropertyIdentifier::CovIncrement,
+                PropertyValue::Double(-1.0)
+            )
+            .is_err());
+
+        lav.write_priority(10, Some(750.0)).unwrap();
+        assert_eq!(lav.present_value, 750.0);
+        lav.write_priority(10, None).unwrap();
+        assert_eq!(lav.present_value, 500.0);
+    }
+}
@@ -0,0 +1,816 @@
+//! Life Safety Point and Life Safety Zone Object Type Implementations
+//!
+//! This module implements the Life Safety Point and Life Safety Zone object types
+//! as defined in ASHRAE 135. A point reports the state of one detector or
+//! sensor, a zone the combined state of a group of points. Both latch alarms and
+//! faults: Tracking_Value follows the input, while Present_Value keeps the most
+//! recent alarm or fault until an operator resets it.
+//!
+//! Operators act on the objects through the LifeSafetyOperation service, which
+//! silences or unsilences the annunciation and resets latched states (Clauses
+//! 12.15 and 12.16). Silenced shows what is silenced and Operation_Expected the
+//! operation the object waits for next.
+
+use crate::object::{
+    current_status_flags, status_flags_bit_string, BacnetObject, EventState, ObjectError,
+    ObjectIdentifier, ObjectType, PropertyIdentifier, PropertyValue, Reliability, Result,
+};
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+/// Life safety state (BACnetLifeSafetyState)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum LifeSafetyState {
+    Quiet = 0,
+    PreAlarm = 1,
+    Alarm = 2,
+    Fault = 3,
+    FaultPreAlarm = 4,
+    FaultAlarm = 5,
+    NotReady = 6,
+    Active = 7,
+    Tamper = 8,
+    TestAlarm = 9,
+    TestActive = 10,
+    TestFault = 11,
+    TestFaultAlarm = 12,
+    Holdup = 13,
+    Duress = 14,
+    TamperAlarm = 15,
+    Abnormal = 16,
+    EmergencyPower = 17,
+    Delayed = 18,
+    Blocked = 19,
+    LocalAlarm = 20,
+    GeneralAlarm = 21,
+    Supervisory = 22,
+    TestSupervisory = 23,
+}
+
+impl LifeSafetyState {
+    /// Whether the state is an alarm that latches until reset
+    pub fn is_alarm(self) -> bool {
+        matches!(
+            self,
+            LifeSafetyState::PreAlarm
+                | LifeSafetyState::Alarm
+                | LifeSafetyState::FaultPreAlarm
+                | LifeSafetyState::FaultAlarm
+                | LifeSafetyState::TestAlarm
+                | LifeSafetyState::TestFaultAlarm
+                | LifeSafetyState::Holdup
+                | LifeSafetyState::Duress
+                | LifeSafetyState::TamperAlarm
+                | LifeSafetyState::LocalAlarm
+                | LifeSafetyState::GeneralAlarm
+        )
+    }
+
+    /// Whether the state is a fault that latches until reset
+    pub fn is_fault(self) -> bool {
+        matches!(
+            self,
+            LifeSafetyState::Fault
+                | LifeSafetyState::FaultPreAlarm
+                | LifeSafetyState::FaultAlarm
+                | LifeSafetyState::TestFault
+                | LifeSafetyState::TestFaultAlarm
+        )
+    }
+}
+
+impl TryFrom<u32> for LifeSafetyState {
+    type Error = ObjectError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            0 => Ok(LifeSafetyState::Quiet),
+            1 => Ok(LifeSafetyState::PreAlarm),
+            2 => Ok(LifeSafetyState::Alarm),
+            3 => Ok(LifeSafetyState::Fault),
+            4 => Ok(LifeSafetyState::FaultPreAlarm),
+            5 => Ok(LifeSafetyState::FaultAlarm),
+            6 => Ok(LifeSafetyState::NotReady),
+            7 => Ok(LifeSafetyState::Active),
+            8 => Ok(LifeSafetyState::Tamper),
+            9 => Ok(LifeSafetyState::TestAlarm),
+            10 => Ok(LifeSafetyState::TestActive),
+            11 => Ok(LifeSafetyState::TestFault),
+            12 => Ok(LifeSafetyState::TestFaultAlarm),
+            13 => Ok(LifeSafetyState::Holdup),
+            14 => Ok(LifeSafetyState::Duress),
+            15 => Ok(LifeSafetyState::TamperAlarm),
+            16 => Ok(LifeSafetyState::Abnormal),
+            17 => Ok(LifeSafetyState::EmergencyPower),
+            18 => Ok(LifeSafetyState::Delayed),
+            19 => Ok(LifeSafetyState::Blocked),
+            20 => Ok(LifeSafetyState::LocalAlarm),
+            21 => Ok(LifeSafetyState::GeneralAlarm),
+            22 => Ok(LifeSafetyState::Supervisory),
+            23 => Ok(LifeSafetyState::TestSupervisory),
+            _ => Err(ObjectError::InvalidValue(format!(
+                "Invalid life safety state: {}",
+                value
+            ))),
+        }
+    }
+}
+
+/// Operating mode (BACnetLifeSafetyMode)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum LifeSafetyMode {
+    Off = 0,
+    On = 1,
+    Test = 2,
+    Manned = 3,
+    Unmanned = 4,
+    Armed = 5,
+    Disarmed = 6,
+    Prearmed = 7,
+    Slow = 8,
+    Fast = 9,
+    Disconnected = 10,
+    Enabled = 11,
+    Disabled = 12,
+    AutomaticReleaseDisabled = 13,
+    Default = 14,
+}
+
+impl TryFrom<u32> for LifeSafetyMode {
+    type Error = ObjectError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            0 => Ok(LifeSafetyMode::Off),
+            1 => Ok(LifeSafetyMode::On),
+            2 => Ok(LifeSafetyMode::Test),
+            3 => Ok(LifeSafetyMode::Manned),
+            4 => Ok(LifeSafetyMode::Unmanned),
+            5 => Ok(LifeSafetyMode::Armed),
+            6 => Ok(LifeSafetyMode::Disarmed),
+            7 => Ok(LifeSafetyMode::Prearmed),
+            8 => Ok(LifeSafetyMode::Slow),
+            9 => Ok(LifeSafetyMode::Fast),
+            10 => Ok(LifeSafetyMode::Disconnected),
+            11 => Ok(LifeSafetyMode::Enabled),
+            12 => Ok(LifeSafetyMode::Disabled),
+            13 => Ok(LifeSafetyMode::AutomaticReleaseDisabled),
+            14 => Ok(LifeSafetyMode::Default),
+            _ => Err(ObjectError::InvalidValue(format!(
+                "Invalid life safety mode: {}",
+                value
+            ))),
+        }
+    }
+}
+
+/// What is silenced (BACnetSilencedState)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum SilencedState {
+    Unsilenced = 0,
+    AudibleSilenced = 1,
+    VisibleSilenced = 2,
+    AllSilenced = 3,
+}
+
+impl SilencedState {
+    fn from_flags(audible: bool, visible: bool) -> Self {
+        match (audible, visible) {
+            (false, false) => SilencedState::Unsilenced,
+            (true, false) => SilencedState::AudibleSilenced,
+            (false, true) => SilencedState::VisibleSilenced,
+            (true, true) => SilencedState::AllSilenced,
+        }
+    }
+
+    fn audible(self) -> bool {
+        matches!(
+            self,
+            SilencedState::AudibleSilenced | SilencedState::AllSilenced
+        )
+    }
+
+    fn visible(self) -> bool {
+        matches!(
+            self,
+            SilencedState::VisibleSilenced | SilencedState::AllSilenced
+        )
+    }
+}
+
+impl TryFrom<u32> for SilencedState {
+    type Error = ObjectError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            0 => Ok(SilencedState::Unsilenced),
+            1 => Ok(SilencedState::AudibleSilenced),
+            2 => Ok(SilencedState::VisibleSilenced),
+            3 => Ok(SilencedState::AllSilenced),
+            _ => Err(ObjectError::InvalidValue(format!(
+                "Invalid silenced state: {}",
+                value
+            ))),
+        }
+    }
+}
+
+/// Operator request (BACnetLifeSafetyOperation)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum LifeSafetyOperation {
+    None = 0,
+    Silence = 1,
+    SilenceAudible = 2,
+    SilenceVisual = 3,
+    Reset = 4,
+    ResetAlarm = 5,
+    ResetFault = 6,
+    Unsilence = 7,
+    UnsilenceAudible = 8,
+    UnsilenceVisual = 9,
+}
+
+impl TryFrom<u32> for LifeSafetyOperation {
+    type Error = ObjectError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            0 => Ok(LifeSafetyOperation::None),
+            1 => Ok(LifeSafetyOperation::Silence),
+            2 => Ok(LifeSafetyOperation::SilenceAudible),
+            3 => Ok(LifeSafetyOperation::SilenceVisual),
+            4 => Ok(LifeSafetyOperation::Reset),
+            5 => Ok(LifeSafetyOperation::ResetAlarm),
+            6 => Ok(LifeSafetyOperation::ResetFault),
+            7 => Ok(LifeSafetyOperation::Unsilence),
+            8 => Ok(LifeSafetyOperation::UnsilenceAudible),
+            9 => Ok(LifeSafetyOperation::UnsilenceVisual),
+            _ => Err(ObjectError::InvalidValue(format!(
+                "Invalid life safety operation: {}",
+                value
+            ))),
+        }
+    }
+}
+
+/// Latched state and annunciation shared by points and zones
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LifeSafetyStatus {
+    /// Latched state
+    pub present_value: LifeSafetyState,
+    /// Unlatched state of the input
+    pub tracking_value: LifeSafetyState,
+    /// What is silenced
+    pub silenced: SilencedState,
+    /// Operation the object waits for next
+    pub operation_expected: LifeSafetyOperation,
+}
+
+impl Default for LifeSafetyStatus {
+    fn default() -> Self {
+        Self {
+            present_value: LifeSafetyState::Quiet,
+            tracking_value: LifeSafetyState::Quiet,
+            silenced: SilencedState::Unsilenced,
+            operation_expected: LifeSafetyOperation::None,
+        }
+    }
+}
+
+impl LifeSafetyStatus {
+    /// Follow a new input state
+    ///
+    /// Present_Value follows unless it holds a latched alarm or fault and the
+    /// input is not a new one. A new alarm or fault unsilences the object.
+    pub fn track(&mut self, state: LifeSafetyState) {
+        let latching = state.is_alarm() || state.is_fault();
+        let latched = self.present_value.is_alarm() || self.present_value.is_fault();
+        if latching && state != self.present_value {
+            self.silenced = SilencedState::Unsilenced;
+        }
+        if latching || !latched {
+            self.present_value = state;
+        }
+        self.tracking_value = state;
+        self.update_operation_expected();
+    }
+
+    /// Apply an operator request
+    pub fn apply(&mut self, operation: LifeSafetyOperation) {
+        let (audible, visible) = (self.silenced.audible(), self.silenced.visible());
+        match operation {
+            LifeSafetyOperation::None => {}
+            LifeSafetyOperation::Silence => self.silenced = SilencedState::AllSilenced,
+            LifeSafetyOperation::SilenceAudible => {
+                self.silenced = SilencedState::from_flags(true, visible)
+            }
+            LifeSafetyOperation::SilenceVisual => {
+                self.silenced = SilencedState::from_flags(audible, true)
+            }
+            LifeSafetyOperation::Unsilence => self.silenced = SilencedState::Unsilenced,
+            LifeSafetyOperation::UnsilenceAudible => {
+                self.silenced = SilencedState::from_flags(false, visible)
+            }
+            LifeSafetyOperation::UnsilenceVisual => {
+                self.silenced = SilencedState::from_flags(audible, false)
+            }
+            LifeSafetyOperation::Reset => self.unlatch(),
+            LifeSafetyOperation::ResetAlarm => {
+                if self.present_value.is_alarm() {
+                    self.unlatch();
+                }
+            }
+            LifeSafetyOperation::ResetFault => {
+                if self.present_value.is_fault() {
+                    self.unlatch();
+                }
+            }
+        }
+        self.update_operation_expected();
+    }
+
+    /// Event_State implied by Present_Value
+    pub fn event_state(&self) -> EventState {
+        if self.present_value.is_alarm() {
+            EventState::LifeSafetyAlarm
+        } else if self.present_value.is_fault() {
+            EventState::Fault
+        } else if self.present_value == LifeSafetyState::Quiet {
+            EventState::Normal
+        } else {
+            EventState::Offnormal
+        }
+    }
+
+    fn unlatch(&mut self) {
+        self.present_value = self.tracking_value;
+        self.silenced = SilencedState::Unsilenced;
+    }
+
+    fn update_operation_expected(&mut self) {
+        let latched = self.present_value.is_alarm() || self.present_value.is_fault();
+        self.operation_expected = if !latched {
+            LifeSafetyOperation::None
+        } else if self.silenced != SilencedState::AllSilenced {
+            LifeSafetyOperation::Silence
+        } else {
+            LifeSafetyOperation::Reset
+        };
+    }
+
+    fn get_property(&self, property: PropertyIdentifier) -> Option<PropertyValue> {
+        let value = match property {
+            PropertyIdentifier::PresentValue => self.present_value as u32,
+            PropertyIdentifier::TrackingValue => self.tracking_value as u32,
+            PropertyIdentifier::Silenced => self.silenced as u32,
+            PropertyIdentifier::OperationExpected => self.operation_expected as u32,
+            PropertyIdentifier::EventState => self.event_state() as u32,
+            _ => return None,
+        };
+        Some(PropertyValue::Enumerated(value))
+    }
+}
+
+/// Properties every life safety object has, in Property_List order
+const LIFE_SAFETY_PROPERTIES: &[PropertyIdentifier] = &[
+    PropertyIdentifier::ObjectIdentifier,
+    PropertyIdentifier::ObjectName,
+    PropertyIdentifier::ObjectType,
+    PropertyIdentifier::Description,
+    PropertyIdentifier::PresentValue,
+    PropertyIdentifier::TrackingValue,
+    PropertyIdentifier::StatusFlags,
+    PropertyIdentifier::EventState,
+    PropertyIdentifier::Reliability,
+    PropertyIdentifier::OutOfService,
+    PropertyIdentifier::Mode,
+    PropertyIdentifier::AcceptedModes,
+    PropertyIdentifier::Silenced,
+    PropertyIdentifier::OperationExpected,
+];
+
+/// Life Safety Point object
+#[derive(Debug, Clone)]
+pub struct LifeSafetyPoint {
+    /// Object identifier
+    pub identifier: ObjectIdentifier,
+    /// Object name
+    pub object_name: String,
+    /// Description
+    pub description: String,
+    /// Latched state and annunciation
+    pub status: LifeSafetyStatus,
+    /// Reliability
+    pub reliability: Reliability,
+    /// Out of service
+    pub out_of_service: bool,
+    /// Operating mode
+    pub mode: LifeSafetyMode,
+    /// Modes Mode may be written with
+    pub accepted_modes: Vec<LifeSafetyMode>,
+}
+
+impl LifeSafetyPoint {
+    /// Create a new quiet Life Safety Point accepting the ON and OFF modes
+    pub fn new(instance: u32, object_name: String) -> Self {
+        Self {
+            identifier: ObjectIdentifier::new(ObjectType::LifeSafetyPoint, instance),
+            object_name,
+            description: String::new(),
+            status: LifeSafetyStatus::default(),
+            reliability: Reliability::NoFaultDetected,
+            out_of_service: false,
+            mode: LifeSafetyMode::On,
+            accepted_modes: vec![LifeSafetyMode::On, LifeSafetyMode::Off],
+        }
+    }
+
+    /// Report the state of the detector
+    pub fn update_state(&mut self, state: LifeSafetyState) {
+        self.status.track(state);
+    }
+}
+
+impl BacnetObject for LifeSafetyPoint {
+    fn identifier(&self) -> ObjectIdentifier {
+        self.identifier
+    }
+
+    fn get_property(&self, property: PropertyIdentifier) -> Result<PropertyValue> {
+        if let Some(value) = self.status.get_property(property) {
+            return Ok(value);
+        }
+        match property {
+            PropertyIdentifier::ObjectIdentifier => {
+                Ok(PropertyValue::ObjectIdentifier(self.identifier))
+            }
+            PropertyIdentifier::ObjectName => {
+                Ok(PropertyValue::CharacterString(self.object_name.clone()))
+            }
+            PropertyIdentifier::ObjectType => Ok(PropertyValue::Enumerated(u32::from(
+                ObjectType::LifeSafetyPoint,
+            ))),
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::StatusFlags => Ok(status_flags(
+                &self.status,
+                self.reliability,
+                self.out_of_service,
+            )),
+            PropertyIdentifier::Reliability => {
+                Ok(PropertyValue::Enumerated(self.reliability as u32))
+            }
+            PropertyIdentifier::OutOfService => Ok(PropertyValue::Boolean(self.out_of_service)),
+            PropertyIdentifier::Mode => Ok(PropertyValue::Enumerated(self.mode as u32)),
+            PropertyIdentifier::AcceptedModes => Ok(modes_value(&self.accepted_modes)),
+            _ => Err(ObjectError::UnknownProperty),
+        }
+    }
+
+    fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        match property {
+            PropertyIdentifier::ObjectName => {
+                if let PropertyValue::CharacterString(name) = value {
+                    self.object_name = name;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::OutOfService => {
+                if let PropertyValue::Boolean(out_of_service) = value {
+                    self.out_of_service = out_of_service;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::PresentValue if self.out_of_service => {
+                self.status.track(state_value(value)?);
+                Ok(())
+            }
+            PropertyIdentifier::Mode => {
+                self.mode = mode_value(value, &self.accepted_modes)?;
+                Ok(())
+            }
+            _ => Err(ObjectError::PropertyNotWritable),
+        }
+    }
+
+    fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
+        matches!(
+            property,
+            PropertyIdentifier::ObjectName
+                | PropertyIdentifier::Description
+                | PropertyIdentifier::OutOfService
+                | PropertyIdentifier::Mode
+        ) || (property == PropertyIdentifier::PresentValue && self.out_of_service)
+    }
+
+    fn property_list(&self) -> Vec<PropertyIdentifier> {
+        LIFE_SAFETY_PROPERTIES.to_vec()
+    }
+
+    fn life_safety_operation(&mut self, operation: LifeSafetyOperation) -> Result<()> {
+        self.status.apply(operation);
+        Ok(())
+    }
+}
+
+/// Life Safety Zone object
+#[derive(Debug, Clone)]
+pub struct LifeSafetyZone {
+    /// Object identifier
+    pub identifier: ObjectIdentifier,
+    /// Object name
+    pub object_name: String,
+    /// Description
+    pub description: String,
+    /// Latched state and annunciation
+    pub status: LifeSafetyStatus,
+    /// Reliability
+    pub reliability: Reliability,
+    /// Out of service
+    pub out_of_service: bool,
+    /// Operating mode
+    pub mode: LifeSafetyMode,
+    /// Modes Mode may be written with
+    pub accepted_modes: Vec<LifeSafetyMode>,
+    /// Points and zones that make up the zone
+    pub zone_members: Vec<ObjectIdentifier>,
+}
+
+impl LifeSafetyZone {
+    /// Create a new quiet Life Safety Zone with no members, accepting the ON
+    /// and OFF modes
+    pub fn new(instance: u32, object_name: String) -> Self {
+        Self {
+            identifier: ObjectIdentifier::new(ObjectType::LifeSafetyZone, instance),
+            object_name,
+            description: String::new(),
+            status: LifeSafetyStatus::default(),
+            reliability: Reliability::NoFaultDetected,
+            out_of_service: false,
+            mode: LifeSafetyMode::On,
+            accepted_modes: vec![LifeSafetyMode::On, LifeSafetyMode::Off],
+            zone_members: Vec::new(),
+        }
+    }
+
+    /// Add a point or zone to the zone
+    pub fn add_member(&mut self, member: ObjectIdentifier) {
+        self.zone_members.push(member);
+    }
+
+    /// Report the combined state of the members
+    pub fn update_state(&mut self, state: LifeSafetyState) {
+        self.status.track(state);
+    }
+}
+
+impl BacnetObject for LifeSafetyZone {
+    fn identifier(&self) -> ObjectIdentifier {
+        self.identifier
+    }
+
+    fn get_property(&self, property: PropertyIdentifier) -> Result<PropertyValue> {
+        if let Some(value) = self.status.get_property(property) {
+            return Ok(value);
+        }
+        match property {
+            PropertyIdentifier::ObjectIdentifier => {
+                Ok(PropertyValue::ObjectIdentifier(self.identifier))
+            }
+            PropertyIdentifier::ObjectName => {
+                Ok(PropertyValue::CharacterString(self.object_name.clone()))
+            }
+            PropertyIdentifier::ObjectType => Ok(PropertyValue::Enumerated(u32::from(
+                ObjectType::LifeSafetyZone,
+            ))),
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::StatusFlags => Ok(status_flags(
+                &self.status,
+                self.reliability,
+                self.out_of_service,
+            )),
+            PropertyIdentifier::Reliability => {
+                Ok(PropertyValue::Enumerated(self.reliability as u32))
+            }
+            PropertyIdentifier::OutOfService => Ok(PropertyValue::Boolean(self.out_of_service)),
+            PropertyIdentifier::Mode => Ok(PropertyValue::Enumerated(self.mode as u32)),
+            PropertyIdentifier::AcceptedModes => Ok(modes_value(&self.accepted_modes)),
+            PropertyIdentifier::ZoneMembers => Ok(PropertyValue::List(
+                self.zone_members
+                    .iter()
+                    .map(|member| PropertyValue::ObjectIdentifier(*member))
+                    .collect(),
+            )),
+            _ => Err(ObjectError::UnknownProperty),
+        }
+    }
+
+    fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        match property {
+            PropertyIdentifier::ObjectName => {
+                if let PropertyValue::CharacterString(name) = value {
+                    self.object_name = name;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::OutOfService => {
+                if let PropertyValue::Boolean(out_of_service) = value {
+                    self.out_of_service = out_of_service;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::PresentValue if self.out_of_service => {
+                self.status.track(state_value(value)?);
+                Ok(())
+            }
+            PropertyIdentifier::Mode => {
+                self.mode = mode_value(value, &self.accepted_modes)?;
+                Ok(())
+            }
+            PropertyIdentifier::ZoneMembers => {
+                let PropertyValue::List(members) = value else {
+                    return Err(ObjectError::InvalidPropertyType);
+                };
+                self.zone_members = members
+                    .into_iter()
+                    .map(|member| match member {
+                        PropertyValue::ObjectIdentifier(member) => Ok(member),
+                        _ => Err(ObjectError::InvalidPropertyType),
+                    })
+                    .collect::<Result<_>>()?;
+                Ok(())
+            }
+            _ => Err(ObjectError::PropertyNotWritable),
+        }
+    }
+
+    fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
+        matches!(
+            property,
+            PropertyIdentifier::ObjectName
+                | PropertyIdentifier::Description
+                | PropertyIdentifier::OutOfService
+                | PropertyIdentifier::Mode
+                | PropertyIdentifier::ZoneMembers
+        ) || (property == PropertyIdentifier::PresentValue && self.out_of_service)
+    }
+
+    fn property_list(&self) -> Vec<PropertyIdentifier> {
+        let mut properties = LIFE_SAFETY_PROPERTIES.to_vec();
+        properties.push(PropertyIdentifier::ZoneMembers);
+        properties
+    }
+
+    fn life_safety_operation(&mut self, operation: LifeSafetyOperation) -> Result<()> {
+        self.status.apply(operation);
+        Ok(())
+    }
+}
+
+fn status_flags(
+    status: &LifeSafetyStatus,
+    reliability: Reliability,
+    out_of_service: bool,
+) -> PropertyValue {
+    status_flags_bit_string(current_status_flags(
+        0,
+        status.event_state(),
+        reliability,
+        out_of_service,
+    ))
+}
+
+fn modes_value(modes: &[LifeSafetyMode]) -> PropertyValue {
+    PropertyValue::List(
+        modes
+            .iter()
+            .map(|mode| PropertyValue::Enumerated(*mode as u32))
+            .collect(),
+    )
+}
+
+fn state_value(value: PropertyValue) -> Result<LifeSafetyState> {
+    let PropertyValue::Enumerated(state) = value else {
+        return Err(ObjectError::InvalidPropertyType);
+    };
+    LifeSafetyState::try_from(state)
+}
+
+fn mode_value(value: PropertyValue, accepted_modes: &[LifeSafetyMode]) -> Result<LifeSafetyMode> {
+    let PropertyValue::Enumerated(mode) = value else {
+        return Err(ObjectError::InvalidPropertyType);
+    };
+    let mode = LifeSafetyMode::try_from(mode)?;
+    if accepted_modes.contains(&mode) {
+        Ok(mode)
+    } else {
+        Err(ObjectError::InvalidValue(format!(
+            "Mode {:?} is not accepted",
+            mode
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alarm_latches_until_reset() {
+        let mut point = LifeSafetyPoint::new(1, "Smoke 1".to_string());
+        point.update_state(LifeSafetyState::Alarm);
+        point.update_state(LifeSafetyState::Quiet);
+        assert_eq!(point.status.present_value, LifeSafetyState::Alarm);
+        assert_eq!(point.status.tracking_value, LifeSafetyState::Quiet);
+        assert_eq!(
+            point
+                .get_property(PropertyIdentifier::OperationExpected)
+                .unwrap(),
+            PropertyValue::Enumerated(LifeSafetyOperation::Silence as u32)
+        );
+
+        point
+            .life_safety_operation(LifeSafetyOperation::SilenceAudible)
+            .unwrap();
+        point
+            .life_safety_operation(LifeSafetyOperation::SilenceVisual)
+            .unwrap();
+        assert_eq!(point.status.silenced, SilencedState::AllSilenced);
+        assert_eq!(point.status.operation_expected, LifeSafetyOperation::Reset);
+
+        // Resetting faults leaves the alarm latched
+        point
+            .life_safety_operation(LifeSafetyOperation::ResetFault)
+            .unwrap();
+        assert_eq!(point.status.present_value, LifeSafetyState::Alarm);
+
+        point
+            .life_safety_operation(LifeSafetyOperation::ResetAlarm)
+            .unwrap();
+        assert_eq!(point.status.present_value, LifeSafetyState::Quiet);
+        assert_eq!(point.status.silenced, SilencedState::Unsilenced);
+        assert_eq!(point.status.operation_expected, LifeSafetyOperation::None);
+    }
+
+    #[test]
+    fn test_zone_properties() {
+        let mut zone = LifeSafetyZone::new(2, "Floor 1".to_string());
+        zone.add_member(ObjectIdentifier::new(ObjectType::LifeSafetyPoint, 1));
+        zone.update_state(LifeSafetyState::Fault);
+        assert_eq!(
+            zone.get_property(PropertyIdentifier::EventState).unwrap(),
+            PropertyValue::Enumerated(EventState::Fault as u32)
+        );
+        zone.life_safety_operation(LifeSafetyOperation::Silence)
+            .unwrap();
+        zone.life_safety_operation(LifeSafetyOperation::UnsilenceAudible)
+            .unwrap();
+        assert_eq!(
+            zone.get_property(PropertyIdentifier::Silenced).unwrap(),
+            PropertyValue::Enumerated(SilencedState::VisibleSilenced as u32)
+        );
+
+        assert!(zone
+            .set_property(
+                PropertyIdentifier::Mode,
+                PropertyValue::Enumerated(LifeSafetyMode::Test as u32)
+            )
+            .is_err());
+        assert!(matches!(
+            zone.get_property(PropertyIdentifier::ZoneMembers).unwrap(),
+            PropertyValue::List(members) if members.len() == 1
+        ));
+    }
+}
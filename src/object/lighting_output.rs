@@ -0,0 +1,1344 @@
+//! Lighting Output Object Type Implementation
+//!
+//! This module implements the Lighting Output and Binary Lighting Output object
+//! types as defined in ASHRAE 135. A Lighting Output is a dimmable load
+//! controlled in percent of full output. Present_Value is commanded through a
+//! priority array like an Analog Output, and the Lighting_Command property
+//! accepts BACnetLightingCommand operations:
+//!
+//! - **FADE_TO** / **RAMP_TO**: command a level and move towards it over a fade
+//!   time or at a ramp rate
+//! - **STEP_UP** / **STEP_DOWN** / **STEP_ON** / **STEP_OFF**: change the level by
+//!   a step increment
+//! - **WARN** / **WARN_OFF** / **WARN_RELINQUISH**: blink-warn occupants and, for
+//!   the latter two, turn off or relinquish once Egress_Time has elapsed
+//! - **STOP**: halt a fade or ramp at the current level
+//!
+//! Tracking_Value is the level actually being output. It is moved towards
+//! Present_Value by the ramp engine in [`LightingOutput::advance_time`], which
+//! the application drives from its periodic tick.
+//!
+//! A Binary Lighting Output is an on/off load whose Present_Value accepts the
+//! BACnetBinaryLightingPV values, including the same WARN and egress behaviour.
+
+use crate::object::{
+    current_status_flags, status_flags_bit_string, BacnetObject, Commandable, EventState,
+    ObjectError, ObjectIdentifier, ObjectType, PropertyIdentifier, PropertyValue, Reliability,
+    Result, DEFAULT_COMMAND_PRIORITY,
+};
+use core::time::Duration;
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+/// Lighting command operation (BACnetLightingOperation)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum LightingOperation {
+    None = 0,
+    FadeTo = 1,
+    RampTo = 2,
+    StepUp = 3,
+    StepDown = 4,
+    StepOn = 5,
+    StepOff = 6,
+    Warn = 7,
+    WarnOff = 8,
+    WarnRelinquish = 9,
+    Stop = 10,
+}
+
+impl TryFrom<u32> for LightingOperation {
+    type Error = ObjectError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            0 => Ok(LightingOperation::None),
+            1 => Ok(LightingOperation::FadeTo),
+            2 => Ok(LightingOperation::RampTo),
+            3 => Ok(LightingOperation::StepUp),
+            4 => Ok(LightingOperation::StepDown),
+            5 => Ok(LightingOperation::StepOn),
+            6 => Ok(LightingOperation::StepOff),
+            7 => Ok(LightingOperation::Warn),
+            8 => Ok(LightingOperation::WarnOff),
+            9 => Ok(LightingOperation::WarnRelinquish),
+            10 => Ok(LightingOperation::Stop),
+            _ => Err(ObjectError::InvalidValue(format!(
+                "Invalid lighting operation: {}",
+                value
+            ))),
+        }
+    }
+}
+
+/// Lighting transition in progress (BACnetLightingInProgress)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum LightingInProgress {
+    Idle = 0,
+    FadeActive = 1,
+    RampActive = 2,
+    NotControlled = 3,
+    Other = 4,
+}
+
+/// Transition used for plain Present_Value writes (BACnetLightingTransition)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum LightingTransition {
+    None = 0,
+    Fade = 1,
+    Ramp = 2,
+}
+
+impl TryFrom<u32> for LightingTransition {
+    type Error = ObjectError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            0 => Ok(LightingTransition::None),
+            1 => Ok(LightingTransition::Fade),
+            2 => Ok(LightingTransition::Ramp),
+            _ => Err(ObjectError::InvalidValue(format!(
+                "Invalid lighting transition: {}",
+                value
+            ))),
+        }
+    }
+}
+
+/// A BACnetLightingCommand
+///
+/// Encoded as `List[Enumerated(operation), target_level, ramp_rate,
+/// step_increment, fade_time, priority]`, with Null standing in for absent
+/// optional fields. Absent fields fall back to the object's defaults.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LightingCommand {
+    /// Operation to perform
+    pub operation: LightingOperation,
+    /// Target level in percent, for FADE_TO and RAMP_TO
+    pub target_level: Option<f32>,
+    /// Ramp rate in percent per second, for RAMP_TO
+    pub ramp_rate: Option<f32>,
+    /// Step increment in percent, for the STEP operations
+    pub step_increment: Option<f32>,
+    /// Fade time in milliseconds, for FADE_TO
+    pub fade_time: Option<u32>,
+    /// Command priority (1-16)
+    pub priority: Option<u8>,
+}
+
+impl LightingCommand {
+    /// Create a command with no optional fields
+    pub fn new(operation: LightingOperation) -> Self {
+        Self {
+            operation,
+            target_level: None,
+            ramp_rate: None,
+            step_increment: None,
+            fade_time: None,
+            priority: None,
+        }
+    }
+
+    /// FADE_TO the target level over the given time, or Default_Fade_Time
+    pub fn fade_to(target_level: f32, fade_time: Option<u32>) -> Self {
+        Self {
+            target_level: Some(target_level),
+            fade_time,
+            ..Self::new(LightingOperation::FadeTo)
+        }
+    }
+
+    /// RAMP_TO the target level at the given rate, or Default_Ramp_Rate
+    pub fn ramp_to(target_level: f32, ramp_rate: Option<f32>) -> Self {
+        Self {
+            target_level: Some(target_level),
+            ramp_rate,
+            ..Self::new(LightingOperation::RampTo)
+        }
+    }
+
+    /// Set the priority the command is executed at
+    pub fn with_priority(mut self, priority: u8) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    /// Encode as a property value
+    pub fn to_property_value(&self) -> PropertyValue {
+        let real = |v: Option<f32>| v.map(PropertyValue::Real).unwrap_or(PropertyValue::Null);
+        PropertyValue::List(vec![
+            PropertyValue::Enumerated(self.operation as u32),
+            real(self.target_level),
+            real(self.ramp_rate),
+            real(self.step_increment),
+            self.fade_time
+                .map(PropertyValue::UnsignedInteger)
+                .unwrap_or(PropertyValue::Null),
+            self.priority
+                .map(|p| PropertyValue::UnsignedInteger(p as u32))
+                .unwrap_or(PropertyValue::Null),
+        ])
+    }
+
+    /// Decode from a property value
+    pub fn from_property_value(value: &PropertyValue) -> Result<Self> {
+        let PropertyValue::List(items) = value else {
+            return Err(ObjectError::InvalidPropertyType);
+        };
+        let real = |index: usize| match items.get(index) {
+            None | Some(PropertyValue::Null) => Ok(None),
+            Some(PropertyValue::Real(v)) => Ok(Some(*v)),
+            Some(_) => Err(ObjectError::InvalidPropertyType),
+        };
+        let unsigned = |index: usize| match items.get(index) {
+            None | Some(PropertyValue::Null) => Ok(None),
+            Some(PropertyValue::UnsignedInteger(v)) => Ok(Some(*v)),
+            Some(_) => Err(ObjectError::InvalidPropertyType),
+        };
+        let operation = match items.first() {
+            Some(PropertyValue::Enumerated(op)) => LightingOperation::try_from(*op)?,
+            _ => return Err(ObjectError::InvalidPropertyType),
+        };
+        Ok(Self {
+            operation,
+            target_level: real(1)?,
+            ramp_rate: real(2)?,
+            step_increment: real(3)?,
+            fade_time: unsigned(4)?,
+            priority: unsigned(5)?.map(|p| p.min(u8::MAX as u32) as u8),
+        })
+    }
+}
+
+/// How Tracking_Value moves towards Present_Value
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Motion {
+    Immediate,
+    Fade { remaining: Duration },
+    Ramp { rate: f32 },
+}
+
+/// What happens at a command priority when Egress_Time runs out
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Egress {
+    priority: u8,
+    remaining: Duration,
+    relinquish: bool,
+}
+
+/// Lighting Output object
+#[derive(Debug, Clone)]
+pub struct LightingOutput {
+    /// Object identifier
+    pub identifier: ObjectIdentifier,
+    /// Object name
+    pub object_name: String,
+    /// Description
+    pub description: String,
+    /// Present value, the commanded level in percent
+    pub present_value: f32,
+    /// Tracking value, the level currently being output
+    pub tracking_value: f32,
+    /// Last lighting command written
+    pub lighting_command: LightingCommand,
+    /// Transition in progress
+    pub in_progress: LightingInProgress,
+    /// Status flags
+    pub status_flags: u8,
+    /// Event state
+    pub event_state: EventState,
+    /// Reliability
+    pub reliability: Reliability,
+    /// Out of service
+    pub out_of_service: bool,
+    /// Whether WARN operations blink the output
+    pub blink_warn_enable: bool,
+    /// Delay in seconds between a WARN_OFF / WARN_RELINQUISH and its effect
+    pub egress_time: u32,
+    /// Whether an egress delay is running
+    pub egress_active: bool,
+    /// Fade time in milliseconds used when a command gives none
+    pub default_fade_time: u32,
+    /// Ramp rate in percent per second used when a command gives none
+    pub default_ramp_rate: f32,
+    /// Step increment in percent used when a command gives none
+    pub default_step_increment: f32,
+    /// Transition for plain Present_Value writes (optional property)
+    pub transition: Option<LightingTransition>,
+    /// Priority array and relinquish default
+    pub commandable: Commandable<f32>,
+    /// Priority for lighting commands that give none
+    pub lighting_command_default_priority: u8,
+    /// Lowest non-zero level the load can be driven at
+    pub min_actual_value: f32,
+    /// Highest level the load can be driven at
+    pub max_actual_value: f32,
+    /// Power drawn at full output, in kilowatts
+    pub power: f32,
+    motion: Motion,
+    egress: Option<Egress>,
+    blink_warn_requested: bool,
+}
+
+impl LightingOutput {
+    /// Create a new Lighting Output object
+    pub fn new(instance: u32, object_name: String) -> Self {
+        Self {
+            identifier: ObjectIdentifier::new(ObjectType::LightingOutput, instance),
+            object_name,
+            description: String::new(),
+            present_value: 0.0,
+            tracking_value: 0.0,
+            lighting_command: LightingCommand::new(LightingOperation::None),
+            in_progress: LightingInProgress::Idle,
+            status_flags: 0,
+            event_state: EventState::Normal,
+            reliability: Reliability::NoFaultDetected,
+            out_of_service: false,
+            blink_warn_enable: true,
+            egress_time: 0,
+            egress_active: false,
+            default_fade_time: 0,
+            default_ramp_rate: 100.0,
+            default_step_increment: 1.0,
+            transition: None,
+            commandable: Commandable::new(0.0),
+            lighting_command_default_priority: DEFAULT_COMMAND_PRIORITY,
+            min_actual_value: 0.0,
+            max_actual_value: 100.0,
+            power: 0.0,
+            motion: Motion::Immediate,
+            egress: None,
+            blink_warn_requested: false,
+        }
+    }
+
+    /// Instantaneous power drawn at the current Tracking_Value, in kilowatts
+    pub fn instantaneous_power(&self) -> f32 {
+        self.power * self.tracking_value / 100.0
+    }
+
+    /// Take a pending blink-warn request raised by a WARN operation
+    ///
+    /// The application blinks the physical output when this returns true.
+    pub fn take_blink_warn(&mut self) -> bool {
+        core::mem::take(&mut self.blink_warn_requested)
+    }
+
+    /// Write to priority array at specified priority level (1-16), moving
+    /// Tracking_Value according to Transition
+    pub fn write_priority(&mut self, priority: u8, value: Option<f32>) -> Result<()> {
+        check_priority(priority)?;
+        if let Some(value) = value {
+            check_level(value)?;
+        }
+        self.cancel_egress(priority);
+        let motion = self.default_motion();
+        self.command(priority, value, motion);
+        Ok(())
+    }
+
+    /// Execute a lighting command
+    pub fn execute(&mut self, command: LightingCommand) -> Result<()> {
+        let priority = command
+            .priority
+            .unwrap_or(self.lighting_command_default_priority);
+        check_priority(priority)?;
+        let step = command
+            .step_increment
+            .unwrap_or(self.default_step_increment);
+        let relinquish = command.operation == LightingOperation::WarnRelinquish;
+
+        match command.operation {
+            LightingOperation::None => {}
+            LightingOperation::FadeTo | LightingOperation::RampTo => {
+                let target = command.target_level.ok_or_else(|| {
+                    ObjectError::InvalidValue("Lighting command requires a target level".into())
+                })?;
+                check_level(target)?;
+                let motion = if command.operation == LightingOperation::FadeTo {
+                    let fade_time = command.fade_time.unwrap_or(self.default_fade_time);
+                    Motion::Fade {
+                        remaining: Duration::from_millis(fade_time as u64),
+                    }
+                } else {
+                    Motion::Ramp {
+                        rate: command.ramp_rate.unwrap_or(self.default_ramp_rate),
+                    }
+                };
+                self.cancel_egress(priority);
+                self.command(priority, Some(target), motion);
+            }
+            LightingOperation::StepUp | LightingOperation::StepOn => {
+                let level = self.present_value;
+                // STEP_UP leaves an output that is off alone; STEP_ON turns it on
+                if level == 0.0 && command.operation == LightingOperation::StepUp {
+                    return self.record(command);
+                }
+                let target = if level == 0.0 {
+                    self.min_actual_value.max(step)
+                } else {
+                    level + step
+                };
+                self.cancel_egress(priority);
+                self.command(priority, Some(target.min(100.0)), Motion::Immediate);
+            }
+            LightingOperation::StepDown | LightingOperation::StepOff => {
+                let level = self.present_value;
+                if level == 0.0 {
+                    return self.record(command);
+                }
+                let mut target = level - step;
+                if target < self.min_actual_value.max(f32::EPSILON) {
+                    // STEP_DOWN stops at the minimum level; STEP_OFF turns the output off
+                    target = if command.operation == LightingOperation::StepOff {
+                        0.0
+                    } else {
+                        self.min_actual_value
+                    };
+                }
+                self.cancel_egress(priority);
+                self.command(priority, Some(target), Motion::Immediate);
+            }
+            LightingOperation::Warn => self.blink_warn(),
+            LightingOperation::WarnOff | LightingOperation::WarnRelinquish => {
+                let slot = self.commandable.get(priority);
+                let already_off = if relinquish {
+                    slot.is_none()
+                } else {
+                    slot == Some(&0.0)
+                };
+                if !already_off {
+                    self.blink_warn();
+                    self.egress = Some(Egress {
+                        priority,
+                        remaining: Duration::from_secs(self.egress_time as u64),
+                        relinquish,
+                    });
+                    self.egress_active = true;
+                    // A zero Egress_Time takes effect immediately
+                    self.advance_egress(Duration::ZERO);
+                }
+            }
+            LightingOperation::Stop => {
+                if self.in_progress != LightingInProgress::Idle {
+                    let level = self.tracking_value;
+                    self.command(priority, Some(level), Motion::Immediate);
+                    self.motion = Motion::Immediate;
+                    self.in_progress = LightingInProgress::Idle;
+                }
+            }
+        }
+        self.record(command)
+    }
+
+    fn record(&mut self, command: LightingCommand) -> Result<()> {
+        self.lighting_command = command;
+        Ok(())
+    }
+
+    fn blink_warn(&mut self) {
+        if self.blink_warn_enable && self.present_value > 0.0 {
+            self.blink_warn_requested = true;
+        }
+    }
+
+    fn default_motion(&self) -> Motion {
+        match self.transition {
+            Some(LightingTransition::Fade) => Motion::Fade {
+                remaining: Duration::from_millis(self.default_fade_time as u64),
+            },
+            Some(LightingTransition::Ramp) => Motion::Ramp {
+                rate: self.default_ramp_rate,
+            },
+            Some(LightingTransition::None) | None => Motion::Immediate,
+        }
+    }
+
+    fn cancel_egress(&mut self, priority: u8) {
+        if self.egress.is_some_and(|e| e.priority == priority) {
+            self.egress = None;
+            self.egress_active = false;
+        }
+    }
+
+    /// Store a level at a priority and start moving Tracking_Value towards the
+    /// resulting Present_Value
+    fn command(&mut self, priority: u8, value: Option<f32>, motion: Motion) {
+        let value = value.map(|v| self.clamp_level(v));
+        // Callers check the priority before commanding
+        let _ = self.commandable.write(priority, value);
+        self.present_value = *self.commandable.current();
+        self.start_motion(motion);
+    }
+
+    fn start_motion(&mut self, motion: Motion) {
+        self.motion = motion;
+        self.in_progress = match motion {
+            Motion::Fade { .. } => LightingInProgress::FadeActive,
+            Motion::Ramp { .. } => LightingInProgress::RampActive,
+            Motion::Immediate => LightingInProgress::Idle,
+        };
+        self.step_motion(Duration::ZERO);
+    }
+
+    /// Limit a non-zero level to Min_Actual_Value..=Max_Actual_Value; zero is off
+    fn clamp_level(&self, level: f32) -> f32 {
+        if level <= 0.0 {
+            0.0
+        } else {
+            level.clamp(self.min_actual_value, self.max_actual_value)
+        }
+    }
+
+    fn step_motion(&mut self, elapsed: Duration) {
+        let target = self.present_value;
+        match &mut self.motion {
+            Motion::Immediate => self.tracking_value = target,
+            Motion::Fade { remaining } => {
+                if elapsed >= *remaining {
+                    self.tracking_value = target;
+                } else {
+                    let fraction = elapsed.as_secs_f32() / remaining.as_secs_f32();
+                    self.tracking_value += (target - self.tracking_value) * fraction;
+                    *remaining -= elapsed;
+                }
+            }
+            Motion::Ramp { rate } => {
+                let delta = *rate * elapsed.as_secs_f32();
+                if (target - self.tracking_value).abs() <= delta || *rate <= 0.0 {
+                    self.tracking_value = target;
+                } else if target > self.tracking_value {
+                    self.tracking_value += delta;
+                } else {
+                    self.tracking_value -= delta;
+                }
+            }
+        }
+        if self.tracking_value == target {
+            self.motion = Motion::Immediate;
+            self.in_progress = LightingInProgress::Idle;
+        }
+    }
+
+    fn advance_egress(&mut self, elapsed: Duration) {
+        let Some(egress) = self.egress.as_mut() else {
+            return;
+        };
+        egress.remaining = egress.remaining.saturating_sub(elapsed);
+        if !egress.remaining.is_zero() {
+            return;
+        }
+        let egress = *egress;
+        self.egress = None;
+        self.egress_active = false;
+        let value = if egress.relinquish { None } else { Some(0.0) };
+        let motion = self.default_motion();
+        self.command(egress.priority, value, motion);
+    }
+
+    /// Current Status_Flags, combining the stored flags with the state derived
+    /// from Event_State, Reliability and Out_Of_Service
+    pub fn current_status_flags(&self) -> u8 {
+        current_status_flags(
+            self.status_flags,
+            self.event_state,
+            self.reliability,
+            self.out_of_service,
+        )
+    }
+}
+
+fn check_priority(priority: u8) -> Result<()> {
+    if !(1..=16).contains(&priority) {
+        return Err(ObjectError::InvalidValue(
+            "Priority must be 1-16".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn check_level(level: f32) -> Result<()> {
+    if !(0.0..=100.0).contains(&level) {
+        return Err(ObjectError::InvalidValue(format!(
+            "Lighting level {} outside 0.0-100.0",
+            level
+        )));
+    }
+    Ok(())
+}
+
+impl BacnetObject for LightingOutput {
+    fn identifier(&self) -> ObjectIdentifier {
+        self.identifier
+    }
+
+    fn get_property(&self, property: PropertyIdentifier) -> Result<PropertyValue> {
+        match property {
+            PropertyIdentifier::ObjectIdentifier => {
+                Ok(PropertyValue::ObjectIdentifier(self.identifier))
+            }
+            PropertyIdentifier::ObjectName => {
+                Ok(PropertyValue::CharacterString(self.object_name.clone()))
+            }
+            PropertyIdentifier::ObjectType => Ok(PropertyValue::Enumerated(u32::from(
+                ObjectType::LightingOutput,
+            ))),
+            PropertyIdentifier::PresentValue => Ok(PropertyValue::Real(self.present_value)),
+            PropertyIdentifier::TrackingValue => Ok(PropertyValue::Real(self.tracking_value)),
+            PropertyIdentifier::LightingCommand => Ok(self.lighting_command.to_property_value()),
+            PropertyIdentifier::InProgress => {
+                Ok(PropertyValue::Enumerated(self.in_progress as u32))
+            }
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::StatusFlags => {
+                Ok(status_flags_bit_string(self.current_status_flags()))
+            }
+            PropertyIdentifier::EventState => {
+                Ok(PropertyValue::Enumerated(self.event_state as u32))
+            }
+            PropertyIdentifier::Reliability => {
+                Ok(PropertyValue::Enumerated(self.reliability as u32))
+            }
+            PropertyIdentifier::OutOfService => Ok(PropertyValue::Boolean(self.out_of_service)),
+            PropertyIdentifier::BlinkWarnEnable => {
+                Ok(PropertyValue::Boolean(self.blink_warn_enable))
+            }
+            PropertyIdentifier::EgressTime => Ok(PropertyValue::UnsignedInteger(self.egress_time)),
+            PropertyIdentifier::EgressActive => Ok(PropertyValue::Boolean(self.egress_active)),
+            PropertyIdentifier::DefaultFadeTime => {
+                Ok(PropertyValue::UnsignedInteger(self.default_fade_time))
+            }
+            PropertyIdentifier::DefaultRampRate => Ok(PropertyValue::Real(self.default_ramp_rate)),
+            PropertyIdentifier::DefaultStepIncrement => {
+                Ok(PropertyValue::Real(self.default_step_increment))
+            }
+            PropertyIdentifier::Transition => self
+                .transition
+                .map(|t| PropertyValue::Enumerated(t as u32))
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::PriorityArray => Ok(self
+                .commandable
+                .priority_array_value(|&value| PropertyValue::Real(value))),
+            PropertyIdentifier::RelinquishDefault => {
+                Ok(PropertyValue::Real(*self.commandable.relinquish_default()))
+            }
+            PropertyIdentifier::LightingCommandDefaultPriority => Ok(
+                PropertyValue::UnsignedInteger(self.lighting_command_default_priority as u32),
+            ),
+            PropertyIdentifier::MinActualValue => Ok(PropertyValue::Real(self.min_actual_value)),
+            PropertyIdentifier::MaxActualValue => Ok(PropertyValue::Real(self.max_actual_value)),
+            PropertyIdentifier::Power => Ok(PropertyValue::Real(self.power)),
+            PropertyIdentifier::InstantaneousPower => {
+                Ok(PropertyValue::Real(self.instantaneous_power()))
+            }
+            _ => Err(ObjectError::UnknownProperty),
+        }
+    }
+
+    fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        self.set_property_with_priority(property, value, DEFAULT_COMMAND_PRIORITY)
+    }
+
+    fn set_property_with_priority(
+        &mut self,
+        property: PropertyIdentifier,
+        value: PropertyValue,
+        priority: u8,
+    ) -> Result<()> {
+        match property {
+            PropertyIdentifier::ObjectName => {
+                if let PropertyValue::CharacterString(name) = value {
+                    self.object_name = name;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::PresentValue => match value {
+                PropertyValue::Real(val) => self.write_priority(priority, Some(val)),
+                // Writing NULL relinquishes the command at this priority
+                PropertyValue::Null => self.write_priority(priority, None),
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            PropertyIdentifier::LightingCommand => {
+                self.execute(LightingCommand::from_property_value(&value)?)
+            }
+            PropertyIdentifier::OutOfService => {
+                if let PropertyValue::Boolean(oos) = value {
+                    self.out_of_service = oos;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::BlinkWarnEnable => {
+                if let PropertyValue::Boolean(enable) = value {
+                    self.blink_warn_enable = enable;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::EgressTime => {
+                if let PropertyValue::UnsignedInteger(time) = value {
+                    self.egress_time = time;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::DefaultFadeTime => {
+                if let PropertyValue::UnsignedInteger(time) = value {
+                    self.default_fade_time = time;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::DefaultRampRate => match value {
+                PropertyValue::Real(rate) if rate > 0.0 => {
+                    self.default_ramp_rate = rate;
+                    Ok(())
+                }
+                PropertyValue::Real(_) => Err(ObjectError::InvalidValue(
+                    "Ramp rate must be positive".to_string(),
+                )),
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            PropertyIdentifier::DefaultStepIncrement => match value {
+                PropertyValue::Real(step) if step > 0.0 && step <= 100.0 => {
+                    self.default_step_increment = step;
+                    Ok(())
+                }
+                PropertyValue::Real(_) => Err(ObjectError::InvalidValue(
+                    "Step increment must be in 0.0-100.0".to_string(),
+                )),
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            PropertyIdentifier::Transition if self.transition.is_some() => {
+                if let PropertyValue::Enumerated(transition) = value {
+                    self.transition = Some(LightingTransition::try_from(transition)?);
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::RelinquishDefault => {
+                if let PropertyValue::Real(val) = value {
+                    check_level(val)?;
+                    self.commandable.set_relinquish_default(val);
+                    if self.commandable.active_priority().is_none() {
+                        self.present_value = val;
+                        let motion = self.default_motion();
+                        self.start_motion(motion);
+                    }
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::LightingCommandDefaultPriority => {
+                if let PropertyValue::UnsignedInteger(p) = value {
+                    let p = p.min(u8::MAX as u32) as u8;
+                    check_priority(p)?;
+                    self.lighting_command_default_priority = p;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            _ => Err(ObjectError::PropertyNotWritable),
+        }
+    }
+
+    fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
+        match property {
+            PropertyIdentifier::ObjectName
+            | PropertyIdentifier::Description
+            | PropertyIdentifier::PresentValue
+            | PropertyIdentifier::LightingCommand
+            | PropertyIdentifier::OutOfService
+            | PropertyIdentifier::BlinkWarnEnable
+            | PropertyIdentifier::EgressTime
+            | PropertyIdentifier::DefaultFadeTime
+            | PropertyIdentifier::DefaultRampRate
+            | PropertyIdentifier::DefaultStepIncrement
+            | PropertyIdentifier::RelinquishDefault
+            | PropertyIdentifier::LightingCommandDefaultPriority => true,
+            PropertyIdentifier::Transition => self.transition.is_some(),
+            _ => false,
+        }
+    }
+
+    fn property_list(&self) -> Vec<PropertyIdentifier> {
+        let mut properties = vec![
+            PropertyIdentifier::ObjectIdentifier,
+            PropertyIdentifier::ObjectName,
+            PropertyIdentifier::ObjectType,
+            PropertyIdentifier::PresentValue,
+            PropertyIdentifier::TrackingValue,
+            PropertyIdentifier::LightingCommand,
+            PropertyIdentifier::InProgress,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::StatusFlags,
+            PropertyIdentifier::EventState,
+            PropertyIdentifier::Reliability,
+            PropertyIdentifier::OutOfService,
+            PropertyIdentifier::BlinkWarnEnable,
+            PropertyIdentifier::EgressTime,
+            PropertyIdentifier::EgressActive,
+            PropertyIdentifier::DefaultFadeTime,
+            PropertyIdentifier::DefaultRampRate,
+            PropertyIdentifier::DefaultStepIncrement,
+        ];
+        if self.transition.is_some() {
+            properties.push(PropertyIdentifier::Transition);
+        }
+        properties.extend([
+            PropertyIdentifier::PriorityArray,
+            PropertyIdentifier::RelinquishDefault,
+            PropertyIdentifier::LightingCommandDefaultPriority,
+            PropertyIdentifier::MinActualValue,
+            PropertyIdentifier::MaxActualValue,
+            PropertyIdentifier::Power,
+            PropertyIdentifier::InstantaneousPower,
+        ]);
+        properties
+    }
+
+    fn advance_time(&mut self, elapsed: Duration) {
+        self.advance_egress(elapsed);
+        self.step_motion(elapsed);
+    }
+}
+
+/// Binary Lighting Output Present_Value (BACnetBinaryLightingPV)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum BinaryLightingPV {
+    Off = 0,
+    On = 1,
+    Warn = 2,
+    WarnOff = 3,
+    WarnRelinquish = 4,
+    Stop = 5,
+}
+
+impl TryFrom<u32> for BinaryLightingPV {
+    type Error = ObjectError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            0 => Ok(BinaryLightingPV::Off),
+            1 => Ok(BinaryLightingPV::On),
+            2 => Ok(BinaryLightingPV::Warn),
+            3 => Ok(BinaryLightingPV::WarnOff),
+            4 => Ok(BinaryLightingPV::WarnRelinquish),
+            5 => Ok(BinaryLightingPV::Stop),
+            _ => Err(ObjectError::InvalidValue(format!(
+                "Invalid binary lighting value: {}",
+                value
+            ))),
+        }
+    }
+}
+
+/// How long a blink-warn turns the output off before restoring it
+pub const BLINK_WARN_DURATION: Duration = Duration::from_secs(1);
+
+/// Binary Lighting Output object
+///
+/// Only OFF and ON are stored in the priority array. WARN blinks the output,
+/// WARN_OFF and WARN_RELINQUISH blink it and then turn off or relinquish the
+/// priority once Egress_Time has elapsed, and STOP cancels a pending egress.
+/// The physical output to drive is [`BinaryLightingOutput::output_on`].
+#[derive(Debug, Clone)]
+pub struct BinaryLightingOutput {
+    /// Object identifier
+    pub identifier: ObjectIdentifier,
+    /// Object name
+    pub object_name: String,
+    /// Description
+    pub description: String,
+    /// Present value, ON or OFF
+    pub present_value: BinaryLightingPV,
+    /// Status flags
+    pub status_flags: u8,
+    /// Event state
+    pub event_state: EventState,
+    /// Reliability
+    pub reliability: Reliability,
+    /// Out of service
+    pub out_of_service: bool,
+    /// Whether WARN values blink the output
+    pub blink_warn_enable: bool,
+    /// Delay in seconds between a WARN_OFF / WARN_RELINQUISH and its effect
+    pub egress_time: u32,
+    /// Whether an egress delay is running
+    pub egress_active: bool,
+    /// Priority array of ON / OFF and relinquish default
+    pub commandable: Commandable<BinaryLightingPV>,
+    /// Power drawn while on, in kilowatts
+    pub power: f32,
+    egress: Option<Egress>,
+    blink_remaining: Option<Duration>,
+}
+
+impl BinaryLightingOutput {
+    /// Create a new Binary Lighting Output object
+    pub fn new(instance: u32, object_name: String) -> Self {
+        Self {
+            identifier: ObjectIdentifier::new(ObjectType::BinaryLightingOutput, instance),
+            object_name,
+            description: String::new(),
+            present_value: BinaryLightingPV::Off,
+            status_flags: 0,
+            event_state: EventState::Normal,
+            reliability: Reliability::NoFaultDetected,
+            out_of_service: false,
+            blink_warn_enable: true,
+            egress_time: 0,
+            egress_active: false,
+            commandable: Commandable::new(BinaryLightingPV::Off),
+            power: 0.0,
+            egress: None,
+            blink_remaining: None,
+        }
+    }
+
+    /// Whether the physical output is currently on, taking a blink-warn in
+    /// progress into account
+    pub fn output_on(&self) -> bool {
+        self.present_value == BinaryLightingPV::On && self.blink_remaining.is_none()
+    }
+
+    /// Instantaneous power drawn by the output, in kilowatts
+    pub fn instantaneous_power(&self) -> f32 {
+        if self.output_on() {
+            self.power
+        } else {
+            0.0
+        }
+    }
+
+    /// Write to priority array at specified priority level (1-16)
+    ///
+    /// `None` relinquishes the priority. The WARN values and STOP act on the
+    /// priority without being stored.
+    pub fn write_priority(&mut self, priority: u8, value: Option<BinaryLightingPV>) -> Result<()> {
+        check_priority(priority)?;
+        match value {
+            None | Some(BinaryLightingPV::Off) | Some(BinaryLightingPV::On) => {
+                self.cancel_egress(priority);
+                self.commandable.write(priority, value)?;
+                self.update_present_value();
+            }
+            Some(BinaryLightingPV::Warn) => self.blink_warn(),
+            Some(pv @ (BinaryLightingPV::WarnOff | BinaryLightingPV::WarnRelinquish)) => {
+                let relinquish = pv == BinaryLightingPV::WarnRelinquish;
+                let already_off = match self.commandable.get(priority) {
+                    None => relinquish,
+                    Some(&value) => value == BinaryLightingPV::Off,
+                };
+                if !already_off {
+                    self.blink_warn();
+                    self.egress = Some(Egress {
+                        priority,
+                        remaining: Duration::from_secs(self.egress_time as u64),
+                        relinquish,
+                    });
+                    self.egress_active = true;
+                    // A zero Egress_Time takes effect immediately
+                    self.advance_egress(Duration::ZERO);
+                }
+            }
+            Some(BinaryLightingPV::Stop) => self.cancel_egress(priority),
+        }
+        Ok(())
+    }
+
+    fn blink_warn(&mut self) {
+        if self.blink_warn_enable && self.present_value == BinaryLightingPV::On {
+            self.blink_remaining = Some(BLINK_WARN_DURATION);
+        }
+    }
+
+    fn cancel_egress(&mut self, priority: u8) {
+        if self.egress.is_some_and(|e| e.priority == priority) {
+            self.egress = None;
+            self.egress_active = false;
+        }
+    }
+
+    fn advance_egress(&mut self, elapsed: Duration) {
+        let Some(egress) = self.egress.as_mut() else {
+            return;
+        };
+        egress.remaining = egress.remaining.saturating_sub(elapsed);
+        if !egress.remaining.is_zero() {
+            return;
+        }
+        let egress = *egress;
+        self.egress = None;
+        self.egress_active = false;
+        let value = (!egress.relinquish).then_some(BinaryLightingPV::Off);
+        // The priority was checked when the WARN value was written
+        let _ = self.commandable.write(egress.priority, value);
+        self.update_present_value();
+    }
+
+    /// Update present value based on priority array
+    fn update_present_value(&mut self) {
+        self.present_value = *self.commandable.current();
+    }
+
+    /// Current Status_Flags, combining the stored flags with the state derived
+    /// from Event_State, Reliability and Out_Of_Service
+    pub fn current_status_flags(&self) -> u8 {
+        current_status_flags(
+            self.status_flags,
+            self.event_state,
+            self.reliability,
+            self.out_of_service,
+        )
+    }
+}
+
+impl BacnetObject for BinaryLightingOutput {
+    fn identifier(&self) -> ObjectIdentifier {
+        self.identifier
+    }
+
+    fn get_property(&self, property: PropertyIdentifier) -> Result<PropertyValue> {
+        match property {
+            PropertyIdentifier::ObjectIdentifier => {
+                Ok(PropertyValue::ObjectIdentifier(self.identifier))
+            }
+            PropertyIdentifier::ObjectName => {
+                Ok(PropertyValue::CharacterString(self.object_name.clone()))
+            }
+            PropertyIdentifier::ObjectType => Ok(PropertyValue::Enumerated(u32::from(
+                ObjectType::BinaryLightingOutput,
+            ))),
+            PropertyIdentifier::PresentValue => {
+                Ok(PropertyValue::Enumerated(self.present_value as u32))
+            }
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::StatusFlags => {
+                Ok(status_flags_bit_string(self.current_status_flags()))
+            }
+            PropertyIdentifier::EventState => {
+                Ok(PropertyValue::Enumerated(self.event_state as u32))
+            }
+            PropertyIdentifier::Reliability => {
+                Ok(PropertyValue::Enumerated(self.reliability as u32))
+            }
+            PropertyIdentifier::OutOfService => Ok(PropertyValue::Boolean(self.out_of_service)),
+            PropertyIdentifier::BlinkWarnEnable => {
+                Ok(PropertyValue::Boolean(self.blink_warn_enable))
+            }
+            PropertyIdentifier::EgressTime => Ok(PropertyValue::UnsignedInteger(self.egress_time)),
+            PropertyIdentifier::EgressActive => Ok(PropertyValue::Boolean(self.egress_active)),
+            PropertyIdentifier::PriorityArray => Ok(self
+                .commandable
+                .priority_array_value(|&value| PropertyValue::Enumerated(value as u32))),
+            PropertyIdentifier::RelinquishDefault => Ok(PropertyValue::Enumerated(
+                *self.commandable.relinquish_default() as u32,
+            )),
+            PropertyIdentifier::Power => Ok(PropertyValue::Real(self.power)),
+            PropertyIdentifier::InstantaneousPower => {
+                Ok(PropertyValue::Real(self.instantaneous_power()))
+            }
+            _ => Err(ObjectError::UnknownProperty),
+        }
+    }
+
+    fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        self.set_property_with_priority(property, value, DEFAULT_COMMAND_PRIORITY)
+    }
+
+    fn set_property_with_priority(
+        &mut self,
+        property: PropertyIdentifier,
+        value: PropertyValue,
+        priority: u8,
+    ) -> Result<()> {
+        match property {
+            PropertyIdentifier::ObjectName => {
+                if let PropertyValue::CharacterString(name) = value {
+                    self.object_name = name;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::PresentValue => match value {
+                PropertyValue::Enumerated(val) => {
+                    self.write_priority(priority, Some(BinaryLightingPV::try_from(val)?))
+                }
+                // Writing NULL relinquishes the command at this priority
+                PropertyValue::Null => self.write_priority(priority, None),
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            PropertyIdentifier::OutOfService => {
+                if let PropertyValue::Boolean(oos) = value {
+                    self.out_of_service = oos;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::BlinkWarnEnable => {
+                if let PropertyValue::Boolean(enable) = value {
+                    self.blink_warn_enable = enable;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::EgressTime => {
+                if let PropertyValue::UnsignedInteger(time) = value {
+                    self.egress_time = time;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::RelinquishDefault => match value {
+                PropertyValue::Enumerated(val) => match BinaryLightingPV::try_from(val)? {
+                    pv @ (BinaryLightingPV::Off | BinaryLightingPV::On) => {
+                        self.commandable.set_relinquish_default(pv);
+                        self.update_present_value();
+                        Ok(())
+                    }
+                    _ => Err(ObjectError::InvalidValue(
+                        "Relinquish_Default must be OFF or ON".to_string(),
+                    )),
+                },
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            _ => Err(ObjectError::PropertyNotWritable),
+        }
+    }
+
+    fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
+        matches!(
+            property,
+            PropertyIdentifier::ObjectName
+                | PropertyIdentifier::Description
+                | PropertyIdentifier::PresentValue
+                | PropertyIdentifier::OutOfService
+                | PropertyIdentifier::BlinkWarnEnable
+                | PropertyIdentifier::EgressTime
+                | PropertyIdentifier::RelinquishDefault
+        )
+    }
+
+    fn property_list(&self) -> Vec<PropertyIdentifier> {
+        vec![
+            PropertyIdentifier::ObjectIdentifier,
+            PropertyIdentifier::ObjectName,
+            PropertyIdentifier::ObjectType,
+            PropertyIdentifier::PresentValue,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::StatusFlags,
+            PropertyIdentifier::EventState,
+            PropertyIdentifier::Reliability,
+            PropertyIdentifier::OutOfService,
+            PropertyIdentifier::BlinkWarnEnable,
+            PropertyIdentifier::EgressTime,
+            PropertyIdentifier::EgressActive,
+            PropertyIdentifier::PriorityArray,
+            PropertyIdentifier::RelinquishDefault,
+            PropertyIdentifier::Power,
+            PropertyIdentifier::InstantaneousPower,
+        ]
+    }
+
+    fn advance_time(&mut self, elapsed: Duration) {
+        if let Some(remaining) = self.blink_remaining {
+            self.blink_remaining = remaining
+                .checked_sub(elapsed)
+                .filter(|remaining| !remaining.is_zero());
+        }
+        self.advance_egress(elapsed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lighting_output_fade_and_ramp() {
+        let mut lo = LightingOutput::new(1, "Office Lights".to_string());
+        lo.execute(LightingCommand::fade_to(80.0, Some(4000)))
+            .unwrap();
+        assert_eq!(lo.present_value, 80.0);
+        assert_eq!(lo.tracking_value, 0.0);
+        assert_eq!(lo.in_progress, LightingInProgress::FadeActive);
+
+        lo.advance_time(Duration::from_secs(1));
+        assert!((lo.tracking_value - 20.0).abs() < 0.01);
+        lo.advance_time(Duration::from_secs(3));
+        assert_eq!(lo.tracking_value, 80.0);
+        assert_eq!(lo.in_progress, LightingInProgress::Idle);
+
+        lo.execute(LightingCommand::ramp_to(20.0, Some(10.0)))
+            .unwrap();
+        assert_eq!(lo.in_progress, LightingInProgress::RampActive);
+        lo.advance_time(Duration::from_secs(2));
+        assert!((lo.tracking_value - 60.0).abs() < 0.01);
+
+        // STOP holds the level reached so far
+        lo.execute(LightingCommand::new(LightingOperation::Stop))
+            .unwrap();
+        assert_eq!(lo.in_progress, LightingInProgress::Idle);
+        assert!((lo.present_value - 60.0).abs() < 0.01);
+        lo.advance_time(Duration::from_secs(2));
+        assert!((lo.tracking_value - 60.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_lighting_output_steps() {
+        let mut lo = LightingOutput::new(2, "Corridor".to_string());
+        lo.min_actual_value = 10.0;
+        let mut step = LightingCommand::new(LightingOperation::StepUp);
+        step.step_increment = Some(15.0);
+
+        // STEP_UP does nothing while off, STEP_ON turns the output on
+        lo.execute(step).unwrap();
+        assert_eq!(lo.present_value, 0.0);
+        step.operation = LightingOperation::StepOn;
+        lo.execute(step).unwrap();
+        assert_eq!(lo.present_value, 15.0);
+        step.operation = LightingOperation::StepUp;
+        lo.execute(step).unwrap();
+        assert_eq!(lo.present_value, 30.0);
+        assert_eq!(lo.tracking_value, 30.0);
+
+        step.operation = LightingOperation::StepDown;
+        step.step_increment = Some(25.0);
+        lo.execute(step).unwrap();
+        assert_eq!(lo.present_value, 10.0);
+        step.operation = LightingOperation::StepOff;
+        lo.execute(step).unwrap();
+        assert_eq!(lo.present_value, 0.0);
+    }
+
+    #[test]
+    fn test_lighting_output_warn_relinquish_after_egress() {
+        let mut lo = LightingOutput::new(3, "Conference".to_string());
+        lo.egress_time = 30;
+        lo.commandable.set_relinquish_default(0.0);
+        lo.set_property(
+            PropertyIdentifier::LightingCommand,
+            LightingCommand::fade_to(100.0, Some(0))
+                .with_priority(10)
+                .to_property_value(),
+        )
+        .unwrap();
+        assert_eq!(lo.tracking_value, 100.0);
+
+        let mut warn = LightingCommand::new(LightingOperation::WarnRelinquish);
+        warn.priority = Some(10);
+        lo.execute(warn).unwrap();
+        assert!(lo.take_blink_warn());
+        assert!(lo.egress_active);
+        assert_eq!(lo.present_value, 100.0);
+
+        lo.advance_time(Duration::from_secs(20));
+        assert_eq!(lo.present_value, 100.0);
+        lo.advance_time(Duration::from_secs(10));
+        assert!(!lo.egress_active);
+        assert_eq!(lo.commandable.get(10), None);
+        assert_eq!(lo.present_value, 0.0);
+        assert_eq!(
+            lo.get_property(PropertyIdentifier::EgressActive).unwrap(),
+            PropertyValue::Boolean(false)
+        );
+    }
+
+    #[test]
+    fn test_binary_lighting_output_warn_off_egress() {
+        let mut blo = BinaryLightingOutput::new(4, "Warehouse Bay".to_string());
+        blo.egress_time = 60;
+        blo.power = 0.4;
+        blo.set_property(
+            PropertyIdentifier::PresentValue,
+            PropertyValue::Enumerated(BinaryLightingPV::On as u32),
+        )
+        .unwrap();
+        assert!(blo.output_on());
+        assert_eq!(blo.instantaneous_power(), 0.4);
+
+        blo.write_priority(DEFAULT_COMMAND_PRIORITY, Some(BinaryLightingPV::WarnOff))
+            .unwrap();
+        assert!(blo.egress_active);
+        assert_eq!(blo.present_value, BinaryLightingPV::On);
+        // The blink turns the output off briefly and then restores it
+        assert!(!blo.output_on());
+        blo.advance_time(BLINK_WARN_DURATION);
+        assert!(blo.output_on());
+
+        blo.advance_time(Duration::from_secs(59));
+        assert_eq!(blo.present_value, BinaryLightingPV::Off);
+        assert!(!blo.egress_active);
+        assert_eq!(
+            blo.commandable.get(DEFAULT_COMMAND_PRIORITY),
+            Some(&BinaryLightingPV::Off)
+        );
+    }
+
+    #[test]
+    fn test_binary_lighting_output_stop_cancels_egress() {
+        let mut blo = BinaryLightingOutput::new(5, "Stairwell".to_string());
+        blo.egress_time = 30;
+        blo.commandable
+            .set_relinquish_default(BinaryLightingPV::Off);
+        blo.write_priority(12, Some(BinaryLightingPV::On)).unwrap();
+        blo.write_priority(12, Some(BinaryLightingPV::WarnRelinquish))
+            .unwrap();
+        assert!(blo.egress_active);
+
+        blo.set_property_with_priority(
+            PropertyIdentifier::PresentValue,
+            PropertyValue::Enumerated(BinaryLightingPV::Stop as u32),
+            12,
+        )
+        .unwrap();
+        assert!(!blo.egress_active);
+        blo.advance_time(Duration::from_secs(60));
+        assert_eq!(blo.present_value, BinaryLightingPV::On);
+
+        blo.write_priority(12, Some(BinaryLightingPV::WarnRelinquish))
+            .unwrap();
+        blo.advance_time(Duration::from_secs(30));
+        assert_eq!(blo.commandable.get(12), None);
+        assert_eq!(blo.present_value, BinaryLightingPV::Off);
+    }
+}
@@ -0,0 +1,613 @@
+//! Load Control Object Type Implementation
+//!
+//! This module implements the Load Control object type as defined in ASHRAE 135,
+//! the standard interface for demand-response load shedding. A client requests a
+//! shed by writing Requested_Shed_Level, Start_Time and Shed_Duration; Present_Value
+//! then follows the shed state machine:
+//!
+//! - **SHED_INACTIVE**: no shed is requested, or the shed period has ended
+//! - **SHED_REQUEST_PENDING**: a shed is requested but Start_Time has not arrived;
+//!   Expected_Shed_Level reports what the device plans to achieve
+//! - **SHED_COMPLIANT** / **SHED_NON_COMPLIANT**: the shed period is running and
+//!   Actual_Shed_Level does or does not meet Requested_Shed_Level
+//!
+//! The object does not shed load itself. When a shed starts, changes or ends it
+//! raises a request, taken with [`LoadControl::take_shed_request`], and the
+//! application reports what it achieved through
+//! [`LoadControl::report_actual_shed_level`].
+
+use crate::object::{
+    calendar::day_number, current_date_time, current_status_flags, date_time_from_value,
+    date_time_value, status_flags_bit_string, BacnetObject, EventState, ObjectError,
+    ObjectIdentifier, ObjectType, PropertyIdentifier, PropertyValue, Reliability, Result,
+};
+use crate::service::BacnetDateTime;
+use core::time::Duration;
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+/// Load Control Present_Value (BACnetShedState)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum ShedState {
+    ShedInactive = 0,
+    ShedRequestPending = 1,
+    ShedCompliant = 2,
+    ShedNonCompliant = 3,
+}
+
+impl TryFrom<u32> for ShedState {
+    type Error = ObjectError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            0 => Ok(ShedState::ShedInactive),
+            1 => Ok(ShedState::ShedRequestPending),
+            2 => Ok(ShedState::ShedCompliant),
+            3 => Ok(ShedState::ShedNonCompliant),
+            _ => Err(ObjectError::InvalidValue(format!(
+                "Invalid shed state: {}",
+                value
+            ))),
+        }
+    }
+}
+
+impl ShedState {
+    /// Whether the shed period is running
+    pub fn is_shedding(self) -> bool {
+        matches!(self, ShedState::ShedCompliant | ShedState::ShedNonCompliant)
+    }
+}
+
+/// A shed level (BACnetShedLevel)
+///
+/// Encoded as `List[Unsigned(choice), value]`, since the percent and level
+/// choices are both Unsigned.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShedLevel {
+    /// Percent of Full_Duty_Baseline the load may consume (100 = no shed)
+    Percent(u32),
+    /// Index into the device's Shed_Levels (0 = no shed)
+    Level(u32),
+    /// Amount of load to shed, in kilowatts (0.0 = no shed)
+    Amount(f32),
+}
+
+impl ShedLevel {
+    /// The "no shed" value of the same choice
+    pub fn default_for(self) -> Self {
+        match self {
+            ShedLevel::Percent(_) => ShedLevel::Percent(100),
+            ShedLevel::Level(_) => ShedLevel::Level(0),
+            ShedLevel::Amount(_) => ShedLevel::Amount(0.0),
+        }
+    }
+
+    /// Whether this level requests no shed
+    pub fn is_default(self) -> bool {
+        self == self.default_for()
+    }
+
+    /// Whether `self` sheds at least as much as `requested`
+    ///
+    /// Percent and amount are compared through `baseline` (Full_Duty_Baseline)
+    /// when the choices differ; other mixed choices never comply.
+    pub fn meets(self, requested: ShedLevel, baseline: Option<f32>) -> bool {
+        match (self, requested) {
+            (ShedLevel::Percent(actual), ShedLevel::Percent(requested)) => actual <= requested,
+            (ShedLevel::Level(actual), ShedLevel::Level(requested)) => actual >= requested,
+            (ShedLevel::Amount(actual), ShedLevel::Amount(requested)) => actual >= requested,
+            (ShedLevel::Percent(actual), ShedLevel::Amount(requested)) => {
+                baseline.is_some_and(|baseline| {
+                    baseline * (100 - actual.min(100)) as f32 / 100.0 >= requested
+                })
+            }
+            (ShedLevel::Amount(actual), ShedLevel::Percent(requested)) => {
+                baseline.is_some_and(|baseline| {
+                    baseline > 0.0 && 100.0 - actual / baseline * 100.0 <= requested as f32
+                })
+            }
+            _ => false,
+        }
+    }
+
+    fn to_property_value(self) -> PropertyValue {
+        let (choice, value) = match self {
+            ShedLevel::Percent(percent) => (0, PropertyValue::UnsignedInteger(percent)),
+            ShedLevel::Level(level) => (1, PropertyValue::UnsignedInteger(level)),
+            ShedLevel::Amount(amount) => (2, PropertyValue::Real(amount)),
+        };
+        PropertyValue::List(vec![PropertyValue::UnsignedInteger(choice), value])
+    }
+
+    fn from_property_value(value: &PropertyValue) -> Result<Self> {
+        match value {
+            PropertyValue::List(items) => match items.as_slice() {
+                [PropertyValue::UnsignedInteger(0), PropertyValue::UnsignedInteger(percent)] => {
+                    Ok(ShedLevel::Percent(*percent))
+                }
+                [PropertyValue::UnsignedInteger(1), PropertyValue::UnsignedInteger(level)] => {
+                    Ok(ShedLevel::Level(*level))
+                }
+                [PropertyValue::UnsignedInteger(2), PropertyValue::Real(amount)] => {
+                    Ok(ShedLevel::Amount(*amount))
+                }
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            _ => Err(ObjectError::InvalidPropertyType),
+        }
+    }
+}
+
+/// Seconds since 1970-01-01 for a fully specified date and time
+fn timestamp_seconds(date_time: &BacnetDateTime) -> Option<i64> {
+    let days = day_number(&date_time.date)?;
+    let time = &date_time.time;
+    if time.hour > 23 || time.minute > 59 || time.second > 59 {
+        return None;
+    }
+    Some(days * 86_400 + time.hour as i64 * 3_600 + time.minute as i64 * 60 + time.second as i64)
+}
+
+/// Load Control object
+#[derive(Debug, Clone)]
+pub struct LoadControl {
+    /// Object identifier
+    pub identifier: ObjectIdentifier,
+    /// Object name
+    pub object_name: String,
+    /// Description
+    pub description: String,
+    /// Present value (the shed state)
+    pub present_value: ShedState,
+    /// Text describing the current shed state
+    pub state_description: Option<String>,
+    /// Event state
+    pub event_state: EventState,
+    /// Reliability
+    pub reliability: Reliability,
+    /// Shed level the client asked for
+    pub requested_shed_level: ShedLevel,
+    /// When the shed period begins
+    pub start_time: Option<BacnetDateTime>,
+    /// Length of the shed period, in minutes
+    pub shed_duration: u32,
+    /// Averaging window for compliance, in minutes
+    pub duty_window: u32,
+    /// Whether the object responds to shed requests
+    pub enable: bool,
+    /// Full load of the controlled equipment, in kilowatts
+    pub full_duty_baseline: Option<f32>,
+    /// Shed level the device plans to achieve
+    pub expected_shed_level: ShedLevel,
+    /// Shed level the device is achieving
+    pub actual_shed_level: ShedLevel,
+    /// Levels the device supports for the level choice
+    pub shed_levels: Vec<u32>,
+    /// A description of each entry of Shed_Levels
+    pub shed_level_descriptions: Vec<String>,
+    /// Set when a write changed the request
+    request_changed: bool,
+    /// Shed level the application must move to
+    shed_request: Option<ShedLevel>,
+}
+
+impl LoadControl {
+    /// Create a new Load Control with no shed requested
+    pub fn new(instance: u32, object_name: String) -> Self {
+        Self {
+            identifier: ObjectIdentifier::new(ObjectType::LoadControl, instance),
+            object_name,
+            description: String::new(),
+            present_value: ShedState::ShedInactive,
+            state_description: None,
+            event_state: EventState::Normal,
+            reliability: Reliability::NoFaultDetected,
+            requested_shed_level: ShedLevel::Percent(100),
+            start_time: None,
+            shed_duration: 0,
+            duty_window: 0,
+            enable: true,
+            full_duty_baseline: None,
+            expected_shed_level: ShedLevel::Percent(100),
+            actual_shed_level: ShedLevel::Percent(100),
+            shed_levels: Vec::new(),
+            shed_level_descriptions: Vec::new(),
+            request_changed: false,
+            shed_request: None,
+        }
+    }
+
+    /// Request a shed, as a client writing the three request properties would
+    pub fn request_shed(&mut self, level: ShedLevel, start_time: BacnetDateTime, duration: u32) {
+        self.requested_shed_level = level;
+        self.start_time = Some(start_time);
+        self.shed_duration = duration;
+        self.request_changed = true;
+    }
+
+    /// The shed level the device can achieve for `requested`
+    ///
+    /// A level request is lowered to the highest supported Shed_Levels entry not
+    /// above it; percent and amount requests are taken as given.
+    pub fn achievable_level(&self, requested: ShedLevel) -> ShedLevel {
+        match requested {
+            ShedLevel::Level(level) if !self.shed_levels.is_empty() => ShedLevel::Level(
+                self.shed_levels
+                    .iter()
+                    .copied()
+                    .filter(|&supported| supported <= level)
+                    .max()
+                    .unwrap_or(0),
+            ),
+            other => other,
+        }
+    }
+
+    /// Run the shed state machine at `now`
+    pub fn evaluate(&mut self, now: &BacnetDateTime) {
+        let next = self.next_state(now);
+        let previous = self.present_value;
+        let changed = core::mem::take(&mut self.request_changed);
+
+        match next {
+            ShedState::ShedInactive => {
+                self.expected_shed_level = self.requested_shed_level.default_for();
+                if previous.is_shedding() {
+                    self.shed_request = Some(self.expected_shed_level);
+                }
+            }
+            ShedState::ShedRequestPending => {
+                self.expected_shed_level = self.achievable_level(self.requested_shed_level);
+                if previous.is_shedding() {
+                    // The request moved into the future: stop shedding until it starts
+                    self.shed_request = Some(self.requested_shed_level.default_for());
+                }
+            }
+            _ => {
+                self.expected_shed_level = self.achievable_level(self.requested_shed_level);
+                if !previous.is_shedding() || changed {
+                    self.shed_request = Some(self.expected_shed_level);
+                }
+            }
+        }
+        self.present_value = next;
+    }
+
+    fn next_state(&self, now: &BacnetDateTime) -> ShedState {
+        if !self.enable || self.requested_shed_level.is_default() || self.shed_duration == 0 {
+            return ShedState::ShedInactive;
+        }
+        let (Some(start), Some(now)) = (
+            self.start_time.as_ref().and_then(timestamp_seconds),
+            timestamp_seconds(now),
+        ) else {
+            return ShedState::ShedInactive;
+        };
+        let end = start + self.shed_duration as i64 * 60;
+        if now < start {
+            ShedState::ShedRequestPending
+        } else if now >= end {
+            ShedState::ShedInactive
+        } else {
+            self.compliance()
+        }
+    }
+
+    fn compliance(&self) -> ShedState {
+        if self
+            .actual_shed_level
+            .meets(self.requested_shed_level, self.full_duty_baseline)
+        {
+            ShedState::ShedCompliant
+        } else {
+            ShedState::ShedNonCompliant
+        }
+    }
+
+    /// Take the shed level the application must move its loads to, if any
+    pub fn take_shed_request(&mut self) -> Option<ShedLevel> {
+        self.shed_request.take()
+    }
+
+    /// Report the shed level the application achieved
+    ///
+    /// While the shed period is running this re-evaluates compliance.
+    pub fn report_actual_shed_level(&mut self, level: ShedLevel) {
+        self.actual_shed_level = level;
+        if self.present_value.is_shedding() {
+            self.present_value = self.compliance();
+        }
+    }
+
+    fn current_status_flags(&self) -> u8 {
+        current_status_flags(0, self.event_state, self.reliability, false)
+    }
+}
+
+impl BacnetObject for LoadControl {
+    fn identifier(&self) -> ObjectIdentifier {
+        self.identifier
+    }
+
+    fn get_property(&self, property: PropertyIdentifier) -> Result<PropertyValue> {
+        match property {
+            PropertyIdentifier::ObjectIdentifier => {
+                Ok(PropertyValue::ObjectIdentifier(self.identifier))
+            }
+            PropertyIdentifier::ObjectName => {
+                Ok(PropertyValue::CharacterString(self.object_name.clone()))
+            }
+            PropertyIdentifier::ObjectType => Ok(PropertyValue::Enumerated(u32::from(
+                ObjectType::LoadControl,
+            ))),
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::PresentValue => {
+                Ok(PropertyValue::Enumerated(self.present_value as u32))
+            }
+            PropertyIdentifier::StateDescription => self
+                .state_description
+                .clone()
+                .map(PropertyValue::CharacterString)
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::StatusFlags => {
+                Ok(status_flags_bit_string(self.current_status_flags()))
+            }
+            PropertyIdentifier::EventState => {
+                Ok(PropertyValue::Enumerated(self.event_state as u32))
+            }
+            PropertyIdentifier::Reliability => {
+                Ok(PropertyValue::Enumerated(self.reliability as u32))
+            }
+            PropertyIdentifier::RequestedShedLevel => {
+                Ok(self.requested_shed_level.to_property_value())
+            }
+            PropertyIdentifier::StartTime => Ok(date_time_value(self.start_time)),
+            PropertyIdentifier::ShedDuration => {
+                Ok(PropertyValue::UnsignedInteger(self.shed_duration))
+            }
+            PropertyIdentifier::DutyWindow => Ok(PropertyValue::UnsignedInteger(self.duty_window)),
+            // Property 133 is Enable; the identifier is shared with Trend Log
+            PropertyIdentifier::LogEnable => Ok(PropertyValue::Boolean(self.enable)),
+            PropertyIdentifier::FullDutyBaseline => self
+                .full_duty_baseline
+                .map(PropertyValue::Real)
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::ExpectedShedLevel => {
+                Ok(self.expected_shed_level.to_property_value())
+            }
+            PropertyIdentifier::ActualShedLevel => Ok(self.actual_shed_level.to_property_value()),
+            PropertyIdentifier::ShedLevels => Ok(PropertyValue::Array(
+                self.shed_levels
+                    .iter()
+                    .copied()
+                    .map(PropertyValue::UnsignedInteger)
+                    .collect(),
+            )),
+            PropertyIdentifier::ShedLevelDescriptions => Ok(PropertyValue::Array(
+                self.shed_level_descriptions
+                    .iter()
+                    .cloned()
+                    .map(PropertyValue::CharacterString)
+                    .collect(),
+            )),
+            _ => Err(ObjectError::UnknownProperty),
+        }
+    }
+
+    fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        match property {
+            PropertyIdentifier::ObjectName => {
+                if let PropertyValue::CharacterString(name) = value {
+                    self.object_name = name;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::StateDescription if self.state_description.is_some() => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.state_description = Some(description);
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::RequestedShedLevel => {
+                self.requested_shed_level = ShedLevel::from_property_value(&value)?;
+                self.request_changed = true;
+                Ok(())
+            }
+            PropertyIdentifier::StartTime => {
+                let start_time = date_time_from_value(&value)?;
+                self.start_time = (!start_time.is_unspecified()).then_some(start_time);
+                self.request_changed = true;
+                Ok(())
+            }
+            PropertyIdentifier::ShedDuration => {
+                if let PropertyValue::UnsignedInteger(minutes) = value {
+                    self.shed_duration = minutes;
+                    self.request_changed = true;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::DutyWindow => {
+                if let PropertyValue::UnsignedInteger(minutes) = value {
+                    self.duty_window = minutes;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::LogEnable => {
+                if let PropertyValue::Boolean(enable) = value {
+                    self.enable = enable;
+                    self.request_changed = true;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::FullDutyBaseline if self.full_duty_baseline.is_some() => {
+                if let PropertyValue::Real(baseline) = value {
+                    self.full_duty_baseline = Some(baseline);
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            _ => Err(ObjectError::PropertyNotWritable),
+        }
+    }
+
+    fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
+        match property {
+            PropertyIdentifier::ObjectName
+            | PropertyIdentifier::Description
+            | PropertyIdentifier::RequestedShedLevel
+            | PropertyIdentifier::StartTime
+            | PropertyIdentifier::ShedDuration
+            | PropertyIdentifier::DutyWindow
+            | PropertyIdentifier::LogEnable => true,
+            PropertyIdentifier::StateDescription => self.state_description.is_some(),
+            PropertyIdentifier::FullDutyBaseline => self.full_duty_baseline.is_some(),
+            _ => false,
+        }
+    }
+
+    fn property_list(&self) -> Vec<PropertyIdentifier> {
+        let mut properties = vec![
+            PropertyIdentifier::ObjectIdentifier,
+            PropertyIdentifier::ObjectName,
+            PropertyIdentifier::ObjectType,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::PresentValue,
+        ];
+        if self.state_description.is_some() {
+            properties.push(PropertyIdentifier::StateDescription);
+        }
+        properties.extend([
+            PropertyIdentifier::StatusFlags,
+            PropertyIdentifier::EventState,
+            PropertyIdentifier::Reliability,
+            PropertyIdentifier::RequestedShedLevel,
+            PropertyIdentifier::StartTime,
+            PropertyIdentifier::ShedDuration,
+            PropertyIdentifier::DutyWindow,
+            PropertyIdentifier::LogEnable,
+        ]);
+        if self.full_duty_baseline.is_some() {
+            properties.push(PropertyIdentifier::FullDutyBaseline);
+        }
+        properties.extend([
+            PropertyIdentifier::ExpectedShedLevel,
+            PropertyIdentifier::ActualShedLevel,
+            PropertyIdentifier::ShedLevels,
+            PropertyIdentifier::ShedLevelDescriptions,
+        ]);
+        properties
+    }
+
+    fn advance_time(&mut self, _elapsed: Duration) {
+        if let Some(now) = current_date_time() {
+            self.evaluate(&now);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::{Date, Time};
+
+    fn at(hour: u8, minute: u8) -> BacnetDateTime {
+        BacnetDateTime::new(
+            Date {
+                year: 2024,
+                month: 7,
+                day: 15,
+                weekday: 1,
+            },
+            Time {
+                hour,
+                minute,
+                second: 0,
+                hundredths: 0,
+            },
+        )
+    }
+
+    #[test]
+    fn test_shed_state_machine() {
+        let mut lc = LoadControl::new(1, "Chiller Shed".to_string());
+        lc.request_shed(ShedLevel::Percent(70), at(14, 0), 60);
+
+        lc.evaluate(&at(13, 30));
+        assert_eq!(lc.present_value, ShedState::ShedRequestPending);
+        assert_eq!(lc.expected_shed_level, ShedLevel::Percent(70));
+        assert_eq!(lc.take_shed_request(), None);
+
+        lc.evaluate(&at(14, 0));
+        assert_eq!(lc.present_value, ShedState::ShedNonCompliant);
+        assert_eq!(lc.take_shed_request(), Some(ShedLevel::Percent(70)));
+        lc.report_actual_shed_level(ShedLevel::Percent(65));
+        assert_eq!(lc.present_value, ShedState::ShedCompliant);
+
+        lc.evaluate(&at(15, 0));
+        assert_eq!(lc.present_value, ShedState::ShedInactive);
+        assert_eq!(lc.take_shed_request(), Some(ShedLevel::Percent(100)));
+    }
+
+    #[test]
+    fn test_shed_request_through_properties() {
+        let mut lc = LoadControl::new(2, "Lighting Shed".to_string());
+        lc.shed_levels = vec![0, 1, 2, 4];
+        lc.set_property(
+            PropertyIdentifier::RequestedShedLevel,
+            ShedLevel::Level(3).to_property_value(),
+        )
+        .unwrap();
+        lc.set_property(
+            PropertyIdentifier::StartTime,
+            date_time_value(Some(at(9, 0))),
+        )
+        .unwrap();
+        lc.set_property(
+            PropertyIdentifier::ShedDuration,
+            PropertyValue::UnsignedInteger(30),
+        )
+        .unwrap();
+
+        lc.evaluate(&at(9, 10));
+        assert_eq!(lc.expected_shed_level, ShedLevel::Level(2));
+        assert_eq!(lc.take_shed_request(), Some(ShedLevel::Level(2)));
+
+        // Disabling the object cancels the shed
+        lc.set_property(PropertyIdentifier::LogEnable, PropertyValue::Boolean(false))
+            .unwrap();
+        lc.evaluate(&at(9, 11));
+        assert_eq!(lc.present_value, ShedState::ShedInactive);
+        assert_eq!(lc.take_shed_request(), Some(ShedLevel::Level(0)));
+    }
+
+    #[test]
+    fn test_shed_level_compliance_with_baseline() {
+        assert!(ShedLevel::Amount(30.0).meets(ShedLevel::Percent(75), Some(100.0)));
+        assert!(!ShedLevel::Percent(80).meets(ShedLevel::Amount(25.0), Some(100.0)));
+        assert!(!ShedLevel::Level(1).meets(ShedLevel::Percent(50), Some(100.0)));
+    }
+}
@@ -0,0 +1,464 @@
+//! BacnetObject Boilerplate Macro
+//!
+//! Most objects map their properties one-to-one onto struct fields, and the
+//! `get_property`, `set_property`, `is_property_writable` and `property_list`
+//! implementations are long runs of near-identical match arms. The
+//! [`bacnet_object!`](crate::bacnet_object) macro generates all four from
+//! annotations on the struct fields:
+//!
+//! ```
+//! use bacnet_rs::bacnet_object;
+//! use bacnet_rs::object::{BacnetObject, ObjectIdentifier, ObjectType, PropertyIdentifier};
+//!
+//! bacnet_object! {
+//!     /// Gateway point mirrored from another protocol
+//!     #[derive(Debug, Clone)]
+//!     pub struct GatewayPoint {
+//!         #[bacnet(identifier)]
+//!         pub identifier: ObjectIdentifier,
+//!         #[bacnet(ObjectName, writable)]
+//!         pub object_name: String,
+//!         #[bacnet(PresentValue)]
+//!         pub present_value: f32,
+//!         /// Address in the source protocol
+//!         #[bacnet(skip)]
+//!         pub source_address: u16,
+//!     }
+//! }
+//!
+//! let point = GatewayPoint {
+//!     identifier: ObjectIdentifier::new(ObjectType::AnalogValue, 1),
+//!     object_name: "Supply Temp".to_string(),
+//!     present_value: 18.5,
+//!     source_address: 40001,
+//! };
+//! assert!(point.get_property(PropertyIdentifier::PresentValue).is_ok());
+//! assert!(!point.is_property_writable(PropertyIdentifier::PresentValue));
+//! ```
+//!
+//! The first field must be the `#[bacnet(identifier)]` field; it supplies
+//! Object_Identifier and Object_Type. Every other field carries one of
+//! `#[bacnet(Property)]` (read-only), `#[bacnet(Property, writable)]` or
+//! `#[bacnet(skip)]`. Property fields must implement [`PropertyField`].
+//!
+//! Properties that are not a plain copy of their field name methods of the
+//! struct instead:
+//!
+//! - `read = Self::method` reads the property as `method(&self) ->
+//!   PropertyValue`, for values derived from the field, such as
+//!   Status_Flags.
+//! - `write = Self::method` makes the property writable through
+//!   `method(&mut self, &PropertyValue) -> Result<()>`, for writes that
+//!   check more than the datatype.
+//! - `writable_if = Self::method`, after `write`, allows the write only while
+//!   `method(&self) -> bool` holds, and fails with `WriteAccessDenied`
+//!   otherwise.
+//!
+//! `read` comes first, and may be followed by `writable` or `write`:
+//!
+//! ```text
+//! #[bacnet(StatusFlags, read = Self::status_flags_value)]
+//! #[bacnet(PresentValue, write = Self::write_present_value, writable_if = Self::is_out_of_service)]
+//! ```
+//!
+//! [`OctetString`](crate::object::OctetString) is defined this way.
+
+use crate::object::{
+    BinaryPV, EngineeringUnits, EventState, ObjectError, ObjectIdentifier, PropertyValue,
+    Reliability, Result,
+};
+
+use super::{Date, Time};
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+/// Conversion between a struct field and its property value
+pub trait PropertyField: Sized {
+    /// Encode the field as a property value
+    fn to_property_value(&self) -> PropertyValue;
+
+    /// Decode a written property value, failing with `InvalidPropertyType` if
+    /// the datatype does not match
+    fn from_property_value(value: &PropertyValue) -> Result<Self>;
+}
+
+macro_rules! impl_property_field {
+    ($($ty:ty => $variant:ident),* $(,)?) => {
+        $(
+            impl PropertyField for $ty {
+                fn to_property_value(&self) -> PropertyValue {
+                    PropertyValue::$variant(self.clone())
+                }
+
+                fn from_property_value(value: &PropertyValue) -> Result<Self> {
+                    match value {
+                        PropertyValue::$variant(value) => Ok(value.clone()),
+                        _ => Err(ObjectError::InvalidPropertyType),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_property_field! {
+    bool => Boolean,
+    u32 => UnsignedInteger,
+    i32 => SignedInt,
+    f32 => Real,
+    f64 => Double,
+    String => CharacterString,
+    Vec<u8> => OctetString,
+    ObjectIdentifier => ObjectIdentifier,
+    Date => Date,
+    Time => Time,
+}
+
+/// Optional fields read as NULL when unset; writing NULL clears them
+impl<T: PropertyField> PropertyField for Option<T> {
+    fn to_property_value(&self) -> PropertyValue {
+        match self {
+            Some(value) => value.to_property_value(),
+            None => PropertyValue::Null,
+        }
+    }
+
+    fn from_property_value(value: &PropertyValue) -> Result<Self> {
+        match value {
+            PropertyValue::Null => Ok(None),
+            _ => T::from_property_value(value).map(Some),
+        }
+    }
+}
+
+fn enumerated(value: &PropertyValue) -> Result<u32> {
+    match value {
+        PropertyValue::Enumerated(value) => Ok(*value),
+        _ => Err(ObjectError::InvalidPropertyType),
+    }
+}
+
+impl PropertyField for Reliability {
+    fn to_property_value(&self) -> PropertyValue {
+        PropertyValue::Enumerated(*self as u32)
+    }
+
+    fn from_property_value(value: &PropertyValue) -> Result<Self> {
+        Reliability::try_from(enumerated(value)?)
+    }
+}
+
+impl PropertyField for BinaryPV {
+    fn to_property_value(&self) -> PropertyValue {
+        PropertyValue::Enumerated(*self as u32)
+    }
+
+    fn from_property_value(value: &PropertyValue) -> Result<Self> {
+        BinaryPV::try_from(enumerated(value)?)
+    }
+}
+
+impl PropertyField for EngineeringUnits {
+    fn to_property_value(&self) -> PropertyValue {
+        PropertyValue::Enumerated(self.to_u32())
+    }
+
+    fn from_property_value(value: &PropertyValue) -> Result<Self> {
+        Ok(EngineeringUnits::from_u32(enumerated(value)?))
+    }
+}
+
+impl PropertyField for EventState {
+    fn to_property_value(&self) -> PropertyValue {
+        PropertyValue::Enumerated(*self as u32)
+    }
+
+    fn from_property_value(value: &PropertyValue) -> Result<Self> {
+        match enumerated(value)? {
+            0 => Ok(EventState::Normal),
+            1 => Ok(EventState::Fault),
+            2 => Ok(EventState::Offnormal),
+            3 => Ok(EventState::HighLimit),
+            4 => Ok(EventState::LowLimit),
+            5 => Ok(EventState::LifeSafetyAlarm),
+            value => Err(ObjectError::InvalidValue(format!(
+                "Invalid event state: {}",
+                value
+            ))),
+        }
+    }
+}
+
+/// Define a struct and generate its `BacnetObject` implementation from field
+/// annotations
+///
+/// See the [module documentation](crate::object::macros) for the annotation
+/// syntax.
+#[macro_export]
+macro_rules! bacnet_object {
+    (
+        $(#[$struct_meta:meta])*
+        $struct_vis:vis struct $name:ident {
+            $(#[doc = $id_doc:expr])*
+            #[bacnet(identifier)]
+            $id_vis:vis $id_field:ident : $id_ty:ty,
+            $(
+                $(#[doc = $doc:expr])*
+                #[bacnet($($spec:tt)*)]
+                $field_vis:vis $field:ident : $field_ty:ty
+            ),* $(,)?
+        }
+    ) => {
+        $(#[$struct_meta])*
+        $struct_vis struct $name {
+            $(#[doc = $id_doc])*
+            $id_vis $id_field: $id_ty,
+            $(
+                $(#[doc = $doc])*
+                $field_vis $field: $field_ty,
+            )*
+        }
+
+        impl $crate::object::BacnetObject for $name {
+            fn identifier(&self) -> $crate::object::ObjectIdentifier {
+                self.$id_field
+            }
+
+            fn get_property(
+                &self,
+                property: $crate::object::PropertyIdentifier,
+            ) -> $crate::object::Result<$crate::object::PropertyValue> {
+                match property {
+                    $crate::object::PropertyIdentifier::ObjectIdentifier => {
+                        return Ok($crate::object::PropertyValue::ObjectIdentifier(
+                            self.$id_field,
+                        ));
+                    }
+                    $crate::object::PropertyIdentifier::ObjectType => {
+                        return Ok($crate::object::PropertyValue::Enumerated(
+                            u32::from(self.$id_field.object_type),
+                        ));
+                    }
+                    _ => {}
+                }
+                $(
+                    if let Some(value) =
+                        $crate::__bacnet_object_get!(self, property, $field, $($spec)*)
+                    {
+                        return Ok(value);
+                    }
+                )*
+                Err($crate::object::ObjectError::UnknownProperty)
+            }
+
+            fn set_property(
+                &mut self,
+                property: $crate::object::PropertyIdentifier,
+                value: $crate::object::PropertyValue,
+            ) -> $crate::object::Result<()> {
+                if matches!(
+                    property,
+                    $crate::object::PropertyIdentifier::ObjectIdentifier
+                        | $crate::object::PropertyIdentifier::ObjectType
+                ) {
+                    return Err($crate::object::ObjectError::PropertyNotWritable);
+                }
+                $(
+                    if let Some(result) =
+                        $crate::__bacnet_object_set!(self, property, &value, $field, $($spec)*)
+                    {
+                        return result;
+                    }
+                )*
+                Err($crate::object::ObjectError::UnknownProperty)
+            }
+
+            fn is_property_writable(&self, property: $crate::object::PropertyIdentifier) -> bool {
+                false $(|| $crate::__bacnet_object_writable!(self, property, $($spec)*))*
+            }
+
+            fn property_list(&self) -> Vec<$crate::object::PropertyIdentifier> {
+                #[allow(unused_mut)]
+                let mut properties = vec![
+                    $crate::object::PropertyIdentifier::ObjectIdentifier,
+                    $crate::object::PropertyIdentifier::ObjectType,
+                ];
+                $($crate::__bacnet_object_list!(properties, $($spec)*);)*
+                properties
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __bacnet_object_get {
+    ($self:ident, $property:ident, $field:ident, skip) => {
+        None::<$crate::object::PropertyValue>
+    };
+    ($self:ident, $property:ident, $field:ident, $prop:ident, read = $read:path $(, $($rest:tt)*)?) => {
+        ($property == $crate::object::PropertyIdentifier::$prop).then(|| $read($self))
+    };
+    ($self:ident, $property:ident, $field:ident, $prop:ident $(, $($rest:tt)*)?) => {
+        ($property == $crate::object::PropertyIdentifier::$prop)
+            .then(|| $crate::object::PropertyField::to_property_value(&$self.$field))
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __bacnet_object_set {
+    ($self:ident, $property:ident, $value:expr, $field:ident, skip) => {
+        None::<$crate::object::Result<()>>
+    };
+    (
+        $self:ident, $property:ident, $value:expr, $field:ident,
+        $prop:ident, $(read = $read:path,)? write = $write:path $(, writable_if = $allowed:path)?
+    ) => {
+        ($property == $crate::object::PropertyIdentifier::$prop).then(|| {
+            $(
+                if !$allowed($self) {
+                    return Err($crate::object::ObjectError::WriteAccessDenied);
+                }
+            )?
+            $write($self, $value)
+        })
+    };
+    ($self:ident, $property:ident, $value:expr, $field:ident, $prop:ident, $(read = $read:path,)? writable) => {
+        ($property == $crate::object::PropertyIdentifier::$prop).then(|| {
+            $self.$field = $crate::object::PropertyField::from_property_value($value)?;
+            Ok(())
+        })
+    };
+    ($self:ident, $property:ident, $value:expr, $field:ident, $prop:ident $(, read = $read:path)?) => {
+        ($property == $crate::object::PropertyIdentifier::$prop)
+            .then_some(Err($crate::object::ObjectError::PropertyNotWritable))
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __bacnet_object_writable {
+    (
+        $self:ident, $property:ident,
+        $prop:ident, $(read = $read:path,)? write = $write:path, writable_if = $allowed:path
+    ) => {
+        $property == $crate::object::PropertyIdentifier::$prop && $allowed($self)
+    };
+    ($self:ident, $property:ident, $prop:ident, $(read = $read:path,)? write = $write:path) => {
+        $property == $crate::object::PropertyIdentifier::$prop
+    };
+    ($self:ident, $property:ident, $prop:ident, $(read = $read:path,)? writable) => {
+        $property == $crate::object::PropertyIdentifier::$prop
+    };
+    ($self:ident, $property:ident, $($spec:tt)*) => {
+        false
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __bacnet_object_list {
+    ($properties:ident, skip) => {};
+    ($properties:ident, $prop:ident $(, $($rest:tt)*)?) => {
+        $properties.push($crate::object::PropertyIdentifier::$prop)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::object::{
+        BacnetObject, ObjectError, ObjectIdentifier, ObjectType, PropertyIdentifier, PropertyValue,
+        Reliability,
+    };
+
+    #[cfg(not(feature = "std"))]
+    use alloc::{string::String, vec::Vec};
+
+    crate::bacnet_object! {
+        #[derive(Debug, Clone)]
+        struct TestPoint {
+            #[bacnet(identifier)]
+            identifier: ObjectIdentifier,
+            /// Object name
+            #[bacnet(ObjectName, writable)]
+            object_name: String,
+            #[bacnet(PresentValue)]
+            present_value: f32,
+            #[bacnet(Reliability, writable)]
+            reliability: Reliability,
+            #[bacnet(Description, writable)]
+            description: Option<String>,
+            #[bacnet(skip)]
+            scan_count: u32,
+        }
+    }
+
+    fn point() -> TestPoint {
+        TestPoint {
+            identifier: ObjectIdentifier::new(ObjectType::AnalogValue, 3),
+            object_name: "Point".to_string(),
+            present_value: 12.5,
+            reliability: Reliability::NoFaultDetected,
+            description: None,
+            scan_count: 0,
+        }
+    }
+
+    #[test]
+    fn test_generated_reads_and_list() {
+        let point = point();
+        assert_eq!(point.identifier().instance, 3);
+        assert_eq!(
+            point.get_property(PropertyIdentifier::ObjectType).unwrap(),
+            PropertyValue::Enumerated(u32::from(ObjectType::AnalogValue))
+        );
+        assert_eq!(
+            point
+                .get_property(PropertyIdentifier::PresentValue)
+                .unwrap(),
+            PropertyValue::Real(12.5)
+        );
+        assert_eq!(
+            point.get_property(PropertyIdentifier::Description).unwrap(),
+            PropertyValue::Null
+        );
+        assert!(matches!(
+            point.get_property(PropertyIdentifier::Units),
+            Err(ObjectError::UnknownProperty)
+        ));
+        assert_eq!(point.property_list().len(), 6);
+        assert_eq!(point.scan_count, 0);
+    }
+
+    #[test]
+    fn test_generated_writes() {
+        let mut point = point();
+        point
+            .set_property(
+                PropertyIdentifier::Reliability,
+                PropertyValue::Enumerated(Reliability::OverRange as u32),
+            )
+            .unwrap();
+        assert_eq!(point.reliability, Reliability::OverRange);
+        point
+            .set_property(
+                PropertyIdentifier::Description,
+                PropertyValue::CharacterString("Zone".to_string()),
+            )
+            .unwrap();
+        assert_eq!(point.description.as_deref(), Some("Zone"));
+
+        assert!(matches!(
+            point.set_property(PropertyIdentifier::PresentValue, PropertyValue::Real(1.0)),
+            Err(ObjectError::PropertyNotWritable)
+        ));
+        assert!(matches!(
+            point.set_property(PropertyIdentifier::ObjectName, PropertyValue::Real(1.0)),
+            Err(ObjectError::InvalidPropertyType)
+        ));
+        assert!(point.is_property_writable(PropertyIdentifier::ObjectName));
+        assert!(!point.is_property_writable(PropertyIdentifier::PresentValue));
+    }
+}
@@ -159,6 +159,8 @@ pub enum ObjectError {
     WriteAccessDenied,
     /// Invalid object configuration
     InvalidConfiguration(String),
+    /// Array index out of range for the property
+    InvalidArrayIndex,
 }
 
 impl fmt::Display for ObjectError {
@@ -174,6 +176,7 @@ impl fmt::Display for ObjectError {
             ObjectError::InvalidValue(msg) => write!(f, "Invalid value: {}", msg),
             ObjectError::WriteAccessDenied => write!(f, "Write access denied"),
             ObjectError::InvalidConfiguration(msg) => write!(f, "Invalid configuration: {}", msg),
+            ObjectError::InvalidArrayIndex => write!(f, "Invalid array index"),
         }
     }
 }
@@ -216,9 +219,110 @@ pub enum ObjectType {
     LoadControl = 28,
     StructuredView = 29,
     AccessDoor = 30,
+    Timer = 31,
+    AccessPoint = 33,
+    AccessZone = 34,
+    CredentialDataInput = 37,
+    BitStringValue = 39,
+    CharacterStringValue = 40,
+    DatePatternValue = 41,
+    DateValue = 42,
+    DateTimePatternValue = 43,
+    DateTimeValue = 44,
+    IntegerValue = 45,
+    LargeAnalogValue = 46,
     OctetString = 47,
+    PositiveIntegerValue = 48,
+    TimePatternValue = 49,
+    TimeValue = 50,
+    NotificationForwarder = 51,
+    AlertEnrollment = 52,
+    Channel = 53,
+    LightingOutput = 54,
+    BinaryLightingOutput = 55,
+    NetworkPort = 56,
+    ElevatorGroup = 57,
+    Escalator = 58,
+    Lift = 59,
+    Staging = 60,
+    AuditLog = 61,
+    AuditReporter = 62,
     // ... many more standard types
-    // Vendor specific range starts at 128
+    /// Vendor-specific object type (128-1023)
+    Proprietary(u16),
+}
+
+impl From<ObjectType> for u16 {
+    fn from(object_type: ObjectType) -> u16 {
+        match object_type {
+            ObjectType::AnalogInput => 0,
+            ObjectType::AnalogOutput => 1,
+            ObjectType::AnalogValue => 2,
+            ObjectType::BinaryInput => 3,
+            ObjectType::BinaryOutput => 4,
+            ObjectType::BinaryValue => 5,
+            ObjectType::Calendar => 6,
+            ObjectType::Command => 7,
+            ObjectType::Device => 8,
+            ObjectType::EventEnrollment => 9,
+            ObjectType::File => 10,
+            ObjectType::Group => 11,
+            ObjectType::Loop => 12,
+            ObjectType::MultiStateInput => 13,
+            ObjectType::MultiStateOutput => 14,
+            ObjectType::MultiStateValue => 19,
+            ObjectType::NotificationClass => 15,
+            ObjectType::Program => 16,
+            ObjectType::Schedule => 17,
+            ObjectType::Averaging => 18,
+            ObjectType::TrendLog => 20,
+            ObjectType::LifeSafetyPoint => 21,
+            ObjectType::LifeSafetyZone => 22,
+            ObjectType::Accumulator => 23,
+            ObjectType::PulseConverter => 24,
+            ObjectType::EventLog => 25,
+            ObjectType::GlobalGroup => 26,
+            ObjectType::TrendLogMultiple => 27,
+            ObjectType::LoadControl => 28,
+            ObjectType::StructuredView => 29,
+            ObjectType::AccessDoor => 30,
+            ObjectType::Timer => 31,
+            ObjectType::AccessPoint => 33,
+            ObjectType::AccessZone => 34,
+            ObjectType::CredentialDataInput => 37,
+            ObjectType::BitStringValue => 39,
+            ObjectType::CharacterStringValue => 40,
+            ObjectType::DatePatternValue => 41,
+            ObjectType::DateValue => 42,
+            ObjectType::DateTimePatternValue => 43,
+            ObjectType::DateTimeValue => 44,
+            ObjectType::IntegerValue => 45,
+            ObjectType::LargeAnalogValue => 46,
+            ObjectType::OctetString => 47,
+            ObjectType::PositiveIntegerValue => 48,
+            ObjectType::TimePatternValue => 49,
+            ObjectType::TimeValue => 50,
+            ObjectType::NotificationForwarder => 51,
+            ObjectType::AlertEnrollment => 52,
+            ObjectType::Channel => 53,
+            ObjectType::LightingOutput => 54,
+            ObjectType::BinaryLightingOutput => 55,
+            ObjectType::NetworkPort => 56,
+            ObjectType::ElevatorGroup => 57,
+            ObjectType::Escalator => 58,
+            ObjectType::Lift => 59,
+            ObjectType::Staging => 60,
+            ObjectType::AuditLog => 61,
+            ObjectType::AuditReporter => 62,
+            ObjectType::Proprietary(value) => value,
+        }
+    }
+}
+
+impl From<ObjectType> for u32 {
+    fn from(object_type: ObjectType) -> u32 {
+        u16::from(object_type) as u32
+    }
 }
 
 impl TryFrom<u16> for ObjectType {
@@ -257,7 +361,35 @@ impl TryFrom<u16> for ObjectType {
             28 => Ok(ObjectType::LoadControl),
             29 => Ok(ObjectType::StructuredView),
             30 => Ok(ObjectType::AccessDoor),
+            31 => Ok(ObjectType::Timer),
+            33 => Ok(ObjectType::AccessPoint),
+            34 => Ok(ObjectType::AccessZone),
+            37 => Ok(ObjectType::CredentialDataInput),
+            39 => Ok(ObjectType::BitStringValue),
+            40 => Ok(ObjectType::CharacterStringValue),
+            41 => Ok(ObjectType::DatePatternValue),
+            42 => Ok(ObjectType::DateValue),
+            43 => Ok(ObjectType::DateTimePatternValue),
+            44 => Ok(ObjectType::DateTimeValue),
+            45 => Ok(ObjectType::IntegerValue),
+            46 => Ok(ObjectType::LargeAnalogValue),
             47 => Ok(ObjectType::OctetString),
+            48 => Ok(ObjectType::PositiveIntegerValue),
+            49 => Ok(ObjectType::TimePatternValue),
+            50 => Ok(ObjectType::TimeValue),
+            51 => Ok(ObjectType::NotificationForwarder),
+            52 => Ok(ObjectType::AlertEnrollment),
+            53 => Ok(ObjectType::Channel),
+            54 => Ok(ObjectType::LightingOutput),
+            55 => Ok(ObjectType::BinaryLightingOutput),
+            56 => Ok(ObjectType::NetworkPort),
+            57 => Ok(ObjectType::ElevatorGroup),
+            58 => Ok(ObjectType::Escalator),
+            59 => Ok(ObjectType::Lift),
+            60 => Ok(ObjectType::Staging),
+            61 => Ok(ObjectType::AuditLog),
+            62 => Ok(ObjectType::AuditReporter),
+            128..=1023 => Ok(ObjectType::Proprietary(value)),
             _ => Err(ObjectError::InvalidValue(format!(
                 "Unknown object type: {}",
                 value
@@ -288,39 +420,311 @@ pub enum PropertyIdentifier {
     Bias = 14,
     ChangeOfStateCount = 15,
     ChangeOfStateTime = 16,
+    ControlledVariableReference = 19,
+    ControlledVariableUnits = 20,
+    ControlledVariableValue = 21,
+    NotificationClass = 17,
+    CovIncrement = 22,
+    DateList = 23,
+    Deadband = 25,
+    DerivativeConstant = 26,
+    DerivativeConstantUnits = 27,
+    Description = 28,
+    DescriptionOfHalt = 29,
+    DeviceType = 31,
+    EffectivePeriod = 32,
+    ElapsedActiveTime = 33,
+    EventEnable = 35,
+    EventState = 36,
+    EventType = 37,
+    ExceptionSchedule = 38,
+    FaultValues = 39,
+    FileAccessMethod = 41,
+    FileSize = 42,
+    FileType = 43,
+    HighLimit = 45,
+    InactiveText = 46,
+    InProcess = 47,
+    InstanceOf = 48,
+    IntegralConstant = 49,
+    IntegralConstantUnits = 50,
+    IssueConfirmedNotifications = 51,
+    LimitEnable = 52,
+    ListOfGroupMembers = 53,
+    ListOfObjectPropertyReferences = 54,
+    ManipulatedVariableReference = 60,
+    MaximumOutput = 61,
+    LowLimit = 59,
     // ... many more properties
     DatabaseRevision = 155,
+    MaintenanceRequired = 158,
+    MemberOf = 159,
+    Mode = 160,
+    OperationExpected = 161,
+    Silenced = 163,
+    TrackingValue = 164,
+    ZoneMembers = 165,
+    LifeSafetyAlarmValues = 166,
+    AutoSlaveDiscovery = 169,
+    ManualSlaveAddressBinding = 170,
+    SlaveAddressBinding = 171,
+    SlaveProxyEnable = 172,
     FirmwareRevision = 44,
     MaxApduLengthAccepted = 62,
+    MaxInfoFrames = 63,
+    MaxMaster = 64,
+    MaxPresValue = 65,
+    MinimumOffTime = 66,
+    MinimumOnTime = 67,
+    MinimumOutput = 68,
+    MinPresValue = 69,
     ModelName = 70,
+    ModificationDate = 71,
+    NumberOfApduRetries = 73,
+    NumberOfStates = 74,
+    NotifyType = 72,
     ObjectIdentifier = 75,
     ObjectList = 76,
     ObjectName = 77,
+    ObjectPropertyReference = 78,
     ObjectType = 79,
+    Optional = 80,
     OutOfService = 81,
     OutputUnits = 82,
+    EventParameters = 83,
+    Polarity = 84,
     PresentValue = 85,
+    ScheduleDefault = 174,
+    ProtocolObjectTypesSupported = 96,
+    ProtocolServicesSupported = 97,
     ProtocolRevision = 139,
     ProtocolVersion = 98,
+    ReadOnly = 99,
+    ReasonForHalt = 100,
+    Reliability = 103,
+    Resolution = 106,
     SegmentationSupported = 107,
+    Setpoint = 108,
+    SetpointReference = 109,
+    StateText = 110,
+    StatusFlags = 111,
     SystemStatus = 112,
+    TimeDelay = 113,
+    TimeOfActiveTimeReset = 114,
+    TimeOfStateCountReset = 115,
+    Units = 117,
+    UpdateInterval = 118,
+    WeeklySchedule = 123,
     VendorIdentifier = 120,
     VendorName = 121,
     Priority = 86,
     PriorityArray = 87,
+    PriorityForWriting = 88,
     ProcessIdentifier = 89,
     ProgramChange = 90,
     ProgramLocation = 91,
     ProgramState = 92,
     ProportionalConstant = 93,
     ProportionalConstantUnits = 94,
+    RecipientList = 102,
+    RelinquishDefault = 104,
+    Required = 105,
+    BufferSize = 126,
+    ClientCovIncrement = 127,
+    CovResubscriptionInterval = 128,
+    EventTimeStamps = 130,
+    LogBuffer = 131,
+    LogDeviceObjectProperty = 132,
+    LogEnable = 133,
+    LogInterval = 134,
+    RecordCount = 141,
+    StartTime = 142,
+    StopTime = 143,
+    StopWhenFull = 144,
+    TotalRecordCount = 145,
+    ActiveCovSubscriptions = 152,
+    LoggingType = 197,
+    AdjustValue = 176,
+    Count = 177,
+    CountBeforeChange = 178,
+    CountChangeTime = 179,
+    CovPeriod = 180,
+    InputReference = 181,
+    LimitMonitoringInterval = 182,
+    Prescale = 185,
+    PulseRate = 186,
+    Scale = 187,
+    ScaleFactor = 188,
+    UpdateTime = 189,
+    ValueBeforeChange = 190,
+    ValueSet = 191,
+    ValueChangeTime = 192,
+    Trigger = 205,
+    NodeSubtype = 207,
+    NodeType = 208,
+    SubordinateAnnotations = 210,
+    SubordinateList = 211,
+    ActualShedLevel = 212,
+    DutyWindow = 213,
+    ExpectedShedLevel = 214,
+    FullDutyBaseline = 215,
+    RequestedShedLevel = 218,
+    ShedDuration = 219,
+    ShedLevelDescriptions = 220,
+    ShedLevels = 221,
+    StateDescription = 222,
+    DoorAlarmState = 226,
+    DoorExtendedPulseTime = 227,
+    DoorMembers = 228,
+    DoorOpenTooLongTime = 229,
+    DoorPulseTime = 230,
+    DoorStatus = 231,
+    DoorUnlockDelayTime = 232,
+    LockStatus = 233,
+    MaskedAlarmValues = 234,
+    SecuredStatus = 235,
+    AccessDoors = 246,
+    AccessEvent = 247,
+    AccessEventAuthenticationFactor = 248,
+    AccessEventCredential = 249,
+    AccessEventTime = 250,
     // Protocol Revision 30 - Authentication/Authorization Properties
     AuthenticationFactors = 257,
     AuthenticationPolicyList = 258,
     AuthenticationPolicyNames = 259,
     AuthenticationStatus = 260,
     AuthorizationMode = 261,
+    CredentialsInZone = 266,
+    EntryPoints = 268,
+    ExitPoints = 269,
+    OccupancyCount = 290,
+    OccupancyCountEnable = 292,
+    OccupancyLowerLimit = 294,
+    OccupancyState = 296,
+    OccupancyUpperLimit = 297,
+    SupportedFormats = 304,
+    SupportedFormatClasses = 305,
+    ZoneFrom = 320,
+    ZoneTo = 321,
+    AccessEventTag = 322,
+    GlobalIdentifier = 323,
+    BitText = 343,
+    GroupMembers = 345,
+    GroupMemberNames = 346,
+    MemberStatusFlags = 347,
+    RequestedUpdateInterval = 348,
+    CovuPeriod = 349,
+    CovuRecipients = 350,
+    EventDetectionEnable = 353,
+    LocalForwardingOnly = 360,
+    ProcessIdentifierFilter = 361,
+    SubscribedRecipients = 362,
+    PortFilter = 363,
     AuthorizationExemptions = 364,
+    AllowGroupDelayInhibit = 365,
+    ChannelNumber = 366,
+    ControlGroups = 367,
+    ExecutionDelay = 368,
+    LastPriority = 369,
+    WriteStatus = 370,
+    PropertyList = 371,
+    BlinkWarnEnable = 373,
+    DefaultFadeTime = 374,
+    DefaultRampRate = 375,
+    DefaultStepIncrement = 376,
+    EgressTime = 377,
+    InProgress = 378,
+    InstantaneousPower = 379,
+    LightingCommand = 380,
+    LightingCommandDefaultPriority = 381,
+    MaxActualValue = 382,
+    MinActualValue = 383,
+    Power = 384,
+    Transition = 385,
+    EgressActive = 386,
+    DefaultTimeout = 393,
+    InitialTimeout = 394,
+    LastStateChange = 395,
+    StateChangeValues = 396,
+    TimerRunning = 397,
+    TimerState = 398,
+    ApduLength = 399,
+    IpAddress = 400,
+    IpDefaultGateway = 401,
+    IpDhcpEnable = 402,
+    IpDnsServer = 406,
+    BacnetIpGlobalAddress = 407,
+    BacnetIpMode = 408,
+    BacnetIpNatTraversal = 410,
+    IpSubnetMask = 411,
+    BacnetIpUdpPort = 412,
+    BbmdAcceptFdRegistrations = 413,
+    BbmdBroadcastDistributionTable = 414,
+    BbmdForeignDeviceTable = 415,
+    ChangesPending = 416,
+    Command = 417,
+    FdBbmdAddress = 418,
+    FdSubscriptionLifetime = 419,
+    LinkSpeed = 420,
+    MacAddress = 423,
+    NetworkNumber = 425,
+    NetworkNumberQuality = 426,
+    NetworkType = 427,
+    AssignedLandingCalls = 447,
+    CarAssignedDirection = 448,
+    CarDoorCommand = 449,
+    CarDoorStatus = 450,
+    CarDoorText = 451,
+    CarDoorZone = 452,
+    CarDriveStatus = 453,
+    CarLoad = 454,
+    CarLoadUnits = 455,
+    CarMode = 456,
+    CarMovingDirection = 457,
+    CarPosition = 458,
+    ElevatorGroup = 459,
+    EnergyMeter = 460,
+    EscalatorMode = 462,
+    FaultSignals = 463,
+    FloorText = 464,
+    GroupId = 465,
+    GroupMode = 467,
+    HigherDeck = 468,
+    InstallationId = 469,
+    LandingCalls = 470,
+    LandingCallControl = 471,
+    LandingDoorStatus = 472,
+    LowerDeck = 473,
+    MachineRoomId = 474,
+    MakingCarCall = 475,
+    NextStoppingFloor = 476,
+    OperationDirection = 477,
+    PassengerAlarm = 478,
+    PowerMode = 479,
+    RegisteredCarCall = 480,
+    ProtocolLevel = 482,
+    PresentStage = 493,
+    Stages = 494,
+    StageNames = 495,
+    TargetReferences = 496,
+    AuditSourceReporter = 497,
+    AuditLevel = 498,
+    AuditNotificationRecipient = 499,
+    AuditPriorityFilter = 500,
+    AuditableOperations = 501,
+    MaximumSendDelay = 503,
+    MonitoredObjects = 504,
+    SendNow = 505,
+    ScPrimaryHubUri = 4194306,
+    ScFailoverHubUri = 4194307,
+    ScMinimumReconnectTime = 4194308,
+    ScMaximumReconnectTime = 4194309,
+    ScConnectWaitTimeout = 4194310,
+    ScDisconnectWaitTimeout = 4194311,
+    ScHeartbeatTimeout = 4194312,
+    OperationalCertificateFile = 4194326,
+    IssuerCertificateFiles = 4194327,
+    CertificateSigningRequestFile = 4194328,
     // Reserved range properties (Protocol Revision 30)
     AuthorizationCache = 4194343,
     AuthorizationGroups = 4194344,
@@ -329,6 +733,684 @@ pub enum PropertyIdentifier {
     AuthorizationServer = 4194347,
     AuthorizationStatus = 4194348,
     // ... continues with many more properties
+    /// Vendor-specific property (512-4194303)
+    Proprietary(u32),
+}
+
+impl From<PropertyIdentifier> for u32 {
+    fn from(property: PropertyIdentifier) -> u32 {
+        match property {
+            PropertyIdentifier::AcceptedModes => 175,
+            PropertyIdentifier::AckedTransitions => 0,
+            PropertyIdentifier::AckRequired => 1,
+            PropertyIdentifier::Action => 2,
+            PropertyIdentifier::ActionText => 3,
+            PropertyIdentifier::ActiveText => 4,
+            PropertyIdentifier::ActiveVtSessions => 5,
+            PropertyIdentifier::AlarmValue => 6,
+            PropertyIdentifier::AlarmValues => 7,
+            PropertyIdentifier::All => 8,
+            PropertyIdentifier::AllWritesSuccessful => 9,
+            PropertyIdentifier::ApduSegmentTimeout => 10,
+            PropertyIdentifier::ApduTimeout => 11,
+            PropertyIdentifier::ApplicationSoftwareVersion => 12,
+            PropertyIdentifier::Archive => 13,
+            PropertyIdentifier::Bias => 14,
+            PropertyIdentifier::ChangeOfStateCount => 15,
+            PropertyIdentifier::ChangeOfStateTime => 16,
+            PropertyIdentifier::ControlledVariableReference => 19,
+            PropertyIdentifier::ControlledVariableUnits => 20,
+            PropertyIdentifier::ControlledVariableValue => 21,
+            PropertyIdentifier::NotificationClass => 17,
+            PropertyIdentifier::CovIncrement => 22,
+            PropertyIdentifier::DateList => 23,
+            PropertyIdentifier::Deadband => 25,
+            PropertyIdentifier::DerivativeConstant => 26,
+            PropertyIdentifier::DerivativeConstantUnits => 27,
+            PropertyIdentifier::Description => 28,
+            PropertyIdentifier::DescriptionOfHalt => 29,
+            PropertyIdentifier::DeviceType => 31,
+            PropertyIdentifier::EffectivePeriod => 32,
+            PropertyIdentifier::ElapsedActiveTime => 33,
+            PropertyIdentifier::EventEnable => 35,
+            PropertyIdentifier::EventState => 36,
+            PropertyIdentifier::EventType => 37,
+            PropertyIdentifier::ExceptionSchedule => 38,
+            PropertyIdentifier::FaultValues => 39,
+            PropertyIdentifier::FileAccessMethod => 41,
+            PropertyIdentifier::FileSize => 42,
+            PropertyIdentifier::FileType => 43,
+            PropertyIdentifier::HighLimit => 45,
+            PropertyIdentifier::InactiveText => 46,
+            PropertyIdentifier::InProcess => 47,
+            PropertyIdentifier::InstanceOf => 48,
+            PropertyIdentifier::IntegralConstant => 49,
+            PropertyIdentifier::IntegralConstantUnits => 50,
+            PropertyIdentifier::IssueConfirmedNotifications => 51,
+            PropertyIdentifier::LimitEnable => 52,
+            PropertyIdentifier::ListOfGroupMembers => 53,
+            PropertyIdentifier::ListOfObjectPropertyReferences => 54,
+            PropertyIdentifier::ManipulatedVariableReference => 60,
+            PropertyIdentifier::MaximumOutput => 61,
+            PropertyIdentifier::LowLimit => 59,
+            PropertyIdentifier::DatabaseRevision => 155,
+            PropertyIdentifier::MaintenanceRequired => 158,
+            PropertyIdentifier::MemberOf => 159,
+            PropertyIdentifier::Mode => 160,
+            PropertyIdentifier::OperationExpected => 161,
+            PropertyIdentifier::Silenced => 163,
+            PropertyIdentifier::TrackingValue => 164,
+            PropertyIdentifier::ZoneMembers => 165,
+            PropertyIdentifier::LifeSafetyAlarmValues => 166,
+            PropertyIdentifier::AutoSlaveDiscovery => 169,
+            PropertyIdentifier::ManualSlaveAddressBinding => 170,
+            PropertyIdentifier::SlaveAddressBinding => 171,
+            PropertyIdentifier::SlaveProxyEnable => 172,
+            PropertyIdentifier::FirmwareRevision => 44,
+            PropertyIdentifier::MaxApduLengthAccepted => 62,
+            PropertyIdentifier::MaxInfoFrames => 63,
+            PropertyIdentifier::MaxMaster => 64,
+            PropertyIdentifier::MaxPresValue => 65,
+            PropertyIdentifier::MinimumOffTime => 66,
+            PropertyIdentifier::MinimumOnTime => 67,
+            PropertyIdentifier::MinimumOutput => 68,
+            PropertyIdentifier::MinPresValue => 69,
+            PropertyIdentifier::ModelName => 70,
+            PropertyIdentifier::ModificationDate => 71,
+            PropertyIdentifier::NumberOfApduRetries => 73,
+            PropertyIdentifier::NumberOfStates => 74,
+            PropertyIdentifier::NotifyType => 72,
+            PropertyIdentifier::ObjectIdentifier => 75,
+            PropertyIdentifier::ObjectList => 76,
+            PropertyIdentifier::ObjectName => 77,
+            PropertyIdentifier::ObjectPropertyReference => 78,
+            PropertyIdentifier::ObjectType => 79,
+            PropertyIdentifier::OutOfService => 81,
+            PropertyIdentifier::OutputUnits => 82,
+            PropertyIdentifier::EventParameters => 83,
+            PropertyIdentifier::Polarity => 84,
+            PropertyIdentifier::PresentValue => 85,
+            PropertyIdentifier::ScheduleDefault => 174,
+            PropertyIdentifier::ProtocolObjectTypesSupported => 96,
+            PropertyIdentifier::ProtocolServicesSupported => 97,
+            PropertyIdentifier::ProtocolRevision => 139,
+            PropertyIdentifier::ProtocolVersion => 98,
+            PropertyIdentifier::ReadOnly => 99,
+            PropertyIdentifier::ReasonForHalt => 100,
+            PropertyIdentifier::Reliability => 103,
+            PropertyIdentifier::Resolution => 106,
+            PropertyIdentifier::SegmentationSupported => 107,
+            PropertyIdentifier::Setpoint => 108,
+            PropertyIdentifier::SetpointReference => 109,
+            PropertyIdentifier::StateText => 110,
+            PropertyIdentifier::StatusFlags => 111,
+            PropertyIdentifier::SystemStatus => 112,
+            PropertyIdentifier::TimeDelay => 113,
+            PropertyIdentifier::TimeOfActiveTimeReset => 114,
+            PropertyIdentifier::TimeOfStateCountReset => 115,
+            PropertyIdentifier::Units => 117,
+            PropertyIdentifier::UpdateInterval => 118,
+            PropertyIdentifier::WeeklySchedule => 123,
+            PropertyIdentifier::VendorIdentifier => 120,
+            PropertyIdentifier::VendorName => 121,
+            PropertyIdentifier::Priority => 86,
+            PropertyIdentifier::PriorityArray => 87,
+            PropertyIdentifier::PriorityForWriting => 88,
+            PropertyIdentifier::ProcessIdentifier => 89,
+            PropertyIdentifier::ProgramChange => 90,
+            PropertyIdentifier::ProgramLocation => 91,
+            PropertyIdentifier::ProgramState => 92,
+            PropertyIdentifier::ProportionalConstant => 93,
+            PropertyIdentifier::ProportionalConstantUnits => 94,
+            PropertyIdentifier::RecipientList => 102,
+            PropertyIdentifier::RelinquishDefault => 104,
+            PropertyIdentifier::BufferSize => 126,
+            PropertyIdentifier::ClientCovIncrement => 127,
+            PropertyIdentifier::CovResubscriptionInterval => 128,
+            PropertyIdentifier::EventTimeStamps => 130,
+            PropertyIdentifier::LogBuffer => 131,
+            PropertyIdentifier::LogDeviceObjectProperty => 132,
+            PropertyIdentifier::LogEnable => 133,
+            PropertyIdentifier::LogInterval => 134,
+            PropertyIdentifier::RecordCount => 141,
+            PropertyIdentifier::StartTime => 142,
+            PropertyIdentifier::StopTime => 143,
+            PropertyIdentifier::StopWhenFull => 144,
+            PropertyIdentifier::TotalRecordCount => 145,
+            PropertyIdentifier::LoggingType => 197,
+            PropertyIdentifier::AdjustValue => 176,
+            PropertyIdentifier::Count => 177,
+            PropertyIdentifier::CountBeforeChange => 178,
+            PropertyIdentifier::CountChangeTime => 179,
+            PropertyIdentifier::CovPeriod => 180,
+            PropertyIdentifier::InputReference => 181,
+            PropertyIdentifier::LimitMonitoringInterval => 182,
+            PropertyIdentifier::Prescale => 185,
+            PropertyIdentifier::PulseRate => 186,
+            PropertyIdentifier::Scale => 187,
+            PropertyIdentifier::ScaleFactor => 188,
+            PropertyIdentifier::UpdateTime => 189,
+            PropertyIdentifier::ValueBeforeChange => 190,
+            PropertyIdentifier::ValueSet => 191,
+            PropertyIdentifier::ValueChangeTime => 192,
+            PropertyIdentifier::Trigger => 205,
+            PropertyIdentifier::NodeSubtype => 207,
+            PropertyIdentifier::NodeType => 208,
+            PropertyIdentifier::SubordinateAnnotations => 210,
+            PropertyIdentifier::SubordinateList => 211,
+            PropertyIdentifier::ActualShedLevel => 212,
+            PropertyIdentifier::DutyWindow => 213,
+            PropertyIdentifier::ExpectedShedLevel => 214,
+            PropertyIdentifier::FullDutyBaseline => 215,
+            PropertyIdentifier::RequestedShedLevel => 218,
+            PropertyIdentifier::ShedDuration => 219,
+            PropertyIdentifier::ShedLevelDescriptions => 220,
+            PropertyIdentifier::ShedLevels => 221,
+            PropertyIdentifier::StateDescription => 222,
+            PropertyIdentifier::DoorAlarmState => 226,
+            PropertyIdentifier::DoorExtendedPulseTime => 227,
+            PropertyIdentifier::DoorMembers => 228,
+            PropertyIdentifier::DoorOpenTooLongTime => 229,
+            PropertyIdentifier::DoorPulseTime => 230,
+            PropertyIdentifier::DoorStatus => 231,
+            PropertyIdentifier::DoorUnlockDelayTime => 232,
+            PropertyIdentifier::LockStatus => 233,
+            PropertyIdentifier::MaskedAlarmValues => 234,
+            PropertyIdentifier::SecuredStatus => 235,
+            PropertyIdentifier::AccessDoors => 246,
+            PropertyIdentifier::AccessEvent => 247,
+            PropertyIdentifier::AccessEventAuthenticationFactor => 248,
+            PropertyIdentifier::AccessEventCredential => 249,
+            PropertyIdentifier::AccessEventTime => 250,
+            PropertyIdentifier::AuthenticationFactors => 257,
+            PropertyIdentifier::AuthenticationPolicyList => 258,
+            PropertyIdentifier::AuthenticationPolicyNames => 259,
+            PropertyIdentifier::AuthenticationStatus => 260,
+            PropertyIdentifier::AuthorizationMode => 261,
+            PropertyIdentifier::CredentialsInZone => 266,
+            PropertyIdentifier::EntryPoints => 268,
+            PropertyIdentifier::ExitPoints => 269,
+            PropertyIdentifier::OccupancyCount => 290,
+            PropertyIdentifier::OccupancyCountEnable => 292,
+            PropertyIdentifier::OccupancyLowerLimit => 294,
+            PropertyIdentifier::OccupancyState => 296,
+            PropertyIdentifier::OccupancyUpperLimit => 297,
+            PropertyIdentifier::SupportedFormats => 304,
+            PropertyIdentifier::SupportedFormatClasses => 305,
+            PropertyIdentifier::ZoneFrom => 320,
+            PropertyIdentifier::ZoneTo => 321,
+            PropertyIdentifier::AccessEventTag => 322,
+            PropertyIdentifier::GlobalIdentifier => 323,
+            PropertyIdentifier::BitText => 343,
+            PropertyIdentifier::GroupMembers => 345,
+            PropertyIdentifier::GroupMemberNames => 346,
+            PropertyIdentifier::MemberStatusFlags => 347,
+            PropertyIdentifier::RequestedUpdateInterval => 348,
+            PropertyIdentifier::CovuPeriod => 349,
+            PropertyIdentifier::CovuRecipients => 350,
+            PropertyIdentifier::EventDetectionEnable => 353,
+            PropertyIdentifier::LocalForwardingOnly => 360,
+            PropertyIdentifier::ProcessIdentifierFilter => 361,
+            PropertyIdentifier::SubscribedRecipients => 362,
+            PropertyIdentifier::PortFilter => 363,
+            PropertyIdentifier::AuthorizationExemptions => 364,
+            PropertyIdentifier::AllowGroupDelayInhibit => 365,
+            PropertyIdentifier::ChannelNumber => 366,
+            PropertyIdentifier::ControlGroups => 367,
+            PropertyIdentifier::ExecutionDelay => 368,
+            PropertyIdentifier::LastPriority => 369,
+            PropertyIdentifier::WriteStatus => 370,
+            PropertyIdentifier::PropertyList => 371,
+            PropertyIdentifier::BlinkWarnEnable => 373,
+            PropertyIdentifier::DefaultFadeTime => 374,
+            PropertyIdentifier::DefaultRampRate => 375,
+            PropertyIdentifier::DefaultStepIncrement => 376,
+            PropertyIdentifier::EgressTime => 377,
+            PropertyIdentifier::InProgress => 378,
+            PropertyIdentifier::InstantaneousPower => 379,
+            PropertyIdentifier::LightingCommand => 380,
+            PropertyIdentifier::LightingCommandDefaultPriority => 381,
+            PropertyIdentifier::MaxActualValue => 382,
+            PropertyIdentifier::MinActualValue => 383,
+            PropertyIdentifier::Power => 384,
+            PropertyIdentifier::Transition => 385,
+            PropertyIdentifier::EgressActive => 386,
+            PropertyIdentifier::DefaultTimeout => 393,
+            PropertyIdentifier::InitialTimeout => 394,
+            PropertyIdentifier::LastStateChange => 395,
+            PropertyIdentifier::StateChangeValues => 396,
+            PropertyIdentifier::TimerRunning => 397,
+            PropertyIdentifier::TimerState => 398,
+            PropertyIdentifier::ApduLength => 399,
+            PropertyIdentifier::IpAddress => 400,
+            PropertyIdentifier::IpDefaultGateway => 401,
+            PropertyIdentifier::IpDhcpEnable => 402,
+            PropertyIdentifier::IpDnsServer => 406,
+            PropertyIdentifier::BacnetIpGlobalAddress => 407,
+            PropertyIdentifier::BacnetIpMode => 408,
+            PropertyIdentifier::BacnetIpNatTraversal => 410,
+            PropertyIdentifier::IpSubnetMask => 411,
+            PropertyIdentifier::BacnetIpUdpPort => 412,
+            PropertyIdentifier::BbmdAcceptFdRegistrations => 413,
+            PropertyIdentifier::BbmdBroadcastDistributionTable => 414,
+            PropertyIdentifier::BbmdForeignDeviceTable => 415,
+            PropertyIdentifier::ChangesPending => 416,
+            PropertyIdentifier::Command => 417,
+            PropertyIdentifier::FdBbmdAddress => 418,
+            PropertyIdentifier::FdSubscriptionLifetime => 419,
+            PropertyIdentifier::LinkSpeed => 420,
+            PropertyIdentifier::MacAddress => 423,
+            PropertyIdentifier::NetworkNumber => 425,
+            PropertyIdentifier::NetworkNumberQuality => 426,
+            PropertyIdentifier::NetworkType => 427,
+            PropertyIdentifier::AssignedLandingCalls => 447,
+            PropertyIdentifier::CarAssignedDirection => 448,
+            PropertyIdentifier::CarDoorCommand => 449,
+            PropertyIdentifier::CarDoorStatus => 450,
+            PropertyIdentifier::CarDoorText => 451,
+            PropertyIdentifier::CarDoorZone => 452,
+            PropertyIdentifier::CarDriveStatus => 453,
+            PropertyIdentifier::CarLoad => 454,
+            PropertyIdentifier::CarLoadUnits => 455,
+            PropertyIdentifier::CarMode => 456,
+            PropertyIdentifier::CarMovingDirection => 457,
+            PropertyIdentifier::CarPosition => 458,
+            PropertyIdentifier::ElevatorGroup => 459,
+            PropertyIdentifier::EnergyMeter => 460,
+            PropertyIdentifier::EscalatorMode => 462,
+            PropertyIdentifier::FaultSignals => 463,
+            PropertyIdentifier::FloorText => 464,
+            PropertyIdentifier::GroupId => 465,
+            PropertyIdentifier::GroupMode => 467,
+            PropertyIdentifier::HigherDeck => 468,
+            PropertyIdentifier::InstallationId => 469,
+            PropertyIdentifier::LandingCalls => 470,
+            PropertyIdentifier::LandingCallControl => 471,
+            PropertyIdentifier::LandingDoorStatus => 472,
+            PropertyIdentifier::LowerDeck => 473,
+            PropertyIdentifier::MachineRoomId => 474,
+            PropertyIdentifier::MakingCarCall => 475,
+            PropertyIdentifier::NextStoppingFloor => 476,
+            PropertyIdentifier::OperationDirection => 477,
+            PropertyIdentifier::PassengerAlarm => 478,
+            PropertyIdentifier::PowerMode => 479,
+            PropertyIdentifier::RegisteredCarCall => 480,
+            PropertyIdentifier::ProtocolLevel => 482,
+            PropertyIdentifier::PresentStage => 493,
+            PropertyIdentifier::Stages => 494,
+            PropertyIdentifier::StageNames => 495,
+            PropertyIdentifier::TargetReferences => 496,
+            PropertyIdentifier::AuditSourceReporter => 497,
+            PropertyIdentifier::AuditLevel => 498,
+            PropertyIdentifier::AuditNotificationRecipient => 499,
+            PropertyIdentifier::AuditPriorityFilter => 500,
+            PropertyIdentifier::AuditableOperations => 501,
+            PropertyIdentifier::MaximumSendDelay => 503,
+            PropertyIdentifier::MonitoredObjects => 504,
+            PropertyIdentifier::SendNow => 505,
+            PropertyIdentifier::ScPrimaryHubUri => 4194306,
+            PropertyIdentifier::ScFailoverHubUri => 4194307,
+            PropertyIdentifier::ScMinimumReconnectTime => 4194308,
+            PropertyIdentifier::ScMaximumReconnectTime => 4194309,
+            PropertyIdentifier::ScConnectWaitTimeout => 4194310,
+            PropertyIdentifier::ScDisconnectWaitTimeout => 4194311,
+            PropertyIdentifier::ScHeartbeatTimeout => 4194312,
+            PropertyIdentifier::OperationalCertificateFile => 4194326,
+            PropertyIdentifier::IssuerCertificateFiles => 4194327,
+            PropertyIdentifier::CertificateSigningRequestFile => 4194328,
+            PropertyIdentifier::AuthorizationCache => 4194343,
+            PropertyIdentifier::AuthorizationGroups => 4194344,
+            PropertyIdentifier::AuthorizationPolicy => 4194345,
+            PropertyIdentifier::AuthorizationScope => 4194346,
+            PropertyIdentifier::AuthorizationServer => 4194347,
+            PropertyIdentifier::AuthorizationStatus => 4194348,
+            PropertyIdentifier::Optional => 80,
+            PropertyIdentifier::Required => 105,
+            PropertyIdentifier::ActiveCovSubscriptions => 152,
+            PropertyIdentifier::Proprietary(value) => value,
+        }
+    }
+}
+
+impl TryFrom<u32> for PropertyIdentifier {
+    type Error = ObjectError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            0 => Ok(PropertyIdentifier::AckedTransitions),
+            1 => Ok(PropertyIdentifier::AckRequired),
+            2 => Ok(PropertyIdentifier::Action),
+            3 => Ok(PropertyIdentifier::ActionText),
+            4 => Ok(PropertyIdentifier::ActiveText),
+            5 => Ok(PropertyIdentifier::ActiveVtSessions),
+            6 => Ok(PropertyIdentifier::AlarmValue),
+            7 => Ok(PropertyIdentifier::AlarmValues),
+            8 => Ok(PropertyIdentifier::All),
+            9 => Ok(PropertyIdentifier::AllWritesSuccessful),
+            10 => Ok(PropertyIdentifier::ApduSegmentTimeout),
+            11 => Ok(PropertyIdentifier::ApduTimeout),
+            12 => Ok(PropertyIdentifier::ApplicationSoftwareVersion),
+            13 => Ok(PropertyIdentifier::Archive),
+            14 => Ok(PropertyIdentifier::Bias),
+            15 => Ok(PropertyIdentifier::ChangeOfStateCount),
+            16 => Ok(PropertyIdentifier::ChangeOfStateTime),
+            17 => Ok(PropertyIdentifier::NotificationClass),
+            19 => Ok(PropertyIdentifier::ControlledVariableReference),
+            20 => Ok(PropertyIdentifier::ControlledVariableUnits),
+            21 => Ok(PropertyIdentifier::ControlledVariableValue),
+            22 => Ok(PropertyIdentifier::CovIncrement),
+            23 => Ok(PropertyIdentifier::DateList),
+            25 => Ok(PropertyIdentifier::Deadband),
+            26 => Ok(PropertyIdentifier::DerivativeConstant),
+            27 => Ok(PropertyIdentifier::DerivativeConstantUnits),
+            28 => Ok(PropertyIdentifier::Description),
+            29 => Ok(PropertyIdentifier::DescriptionOfHalt),
+            31 => Ok(PropertyIdentifier::DeviceType),
+            32 => Ok(PropertyIdentifier::EffectivePeriod),
+            33 => Ok(PropertyIdentifier::ElapsedActiveTime),
+            35 => Ok(PropertyIdentifier::EventEnable),
+            36 => Ok(PropertyIdentifier::EventState),
+            37 => Ok(PropertyIdentifier::EventType),
+            38 => Ok(PropertyIdentifier::ExceptionSchedule),
+            39 => Ok(PropertyIdentifier::FaultValues),
+            41 => Ok(PropertyIdentifier::FileAccessMethod),
+            42 => Ok(PropertyIdentifier::FileSize),
+            43 => Ok(PropertyIdentifier::FileType),
+            44 => Ok(PropertyIdentifier::FirmwareRevision),
+            45 => Ok(PropertyIdentifier::HighLimit),
+            46 => Ok(PropertyIdentifier::InactiveText),
+            47 => Ok(PropertyIdentifier::InProcess),
+            48 => Ok(PropertyIdentifier::InstanceOf),
+            49 => Ok(PropertyIdentifier::IntegralConstant),
+            50 => Ok(PropertyIdentifier::IntegralConstantUnits),
+            51 => Ok(PropertyIdentifier::IssueConfirmedNotifications),
+            52 => Ok(PropertyIdentifier::LimitEnable),
+            53 => Ok(PropertyIdentifier::ListOfGroupMembers),
+            54 => Ok(PropertyIdentifier::ListOfObjectPropertyReferences),
+            59 => Ok(PropertyIdentifier::LowLimit),
+            60 => Ok(PropertyIdentifier::ManipulatedVariableReference),
+            61 => Ok(PropertyIdentifier::MaximumOutput),
+            62 => Ok(PropertyIdentifier::MaxApduLengthAccepted),
+            63 => Ok(PropertyIdentifier::MaxInfoFrames),
+            64 => Ok(PropertyIdentifier::MaxMaster),
+            65 => Ok(PropertyIdentifier::MaxPresValue),
+            66 => Ok(PropertyIdentifier::MinimumOffTime),
+            67 => Ok(PropertyIdentifier::MinimumOnTime),
+            68 => Ok(PropertyIdentifier::MinimumOutput),
+            69 => Ok(PropertyIdentifier::MinPresValue),
+            70 => Ok(PropertyIdentifier::ModelName),
+            71 => Ok(PropertyIdentifier::ModificationDate),
+            72 => Ok(PropertyIdentifier::NotifyType),
+            73 => Ok(PropertyIdentifier::NumberOfApduRetries),
+            74 => Ok(PropertyIdentifier::NumberOfStates),
+            75 => Ok(PropertyIdentifier::ObjectIdentifier),
+            76 => Ok(PropertyIdentifier::ObjectList),
+            77 => Ok(PropertyIdentifier::ObjectName),
+            78 => Ok(PropertyIdentifier::ObjectPropertyReference),
+            79 => Ok(PropertyIdentifier::ObjectType),
+            80 => Ok(PropertyIdentifier::Optional),
+            81 => Ok(PropertyIdentifier::OutOfService),
+            82 => Ok(PropertyIdentifier::OutputUnits),
+            83 => Ok(PropertyIdentifier::EventParameters),
+            84 => Ok(PropertyIdentifier::Polarity),
+            85 => Ok(PropertyIdentifier::PresentValue),
+            86 => Ok(PropertyIdentifier::Priority),
+            87 => Ok(PropertyIdentifier::PriorityArray),
+            88 => Ok(PropertyIdentifier::PriorityForWriting),
+            89 => Ok(PropertyIdentifier::ProcessIdentifier),
+            90 => Ok(PropertyIdentifier::ProgramChange),
+            91 => Ok(PropertyIdentifier::ProgramLocation),
+            92 => Ok(PropertyIdentifier::ProgramState),
+            93 => Ok(PropertyIdentifier::ProportionalConstant),
+            94 => Ok(PropertyIdentifier::ProportionalConstantUnits),
+            96 => Ok(PropertyIdentifier::ProtocolObjectTypesSupported),
+            97 => Ok(PropertyIdentifier::ProtocolServicesSupported),
+            98 => Ok(PropertyIdentifier::ProtocolVersion),
+            99 => Ok(PropertyIdentifier::ReadOnly),
+            100 => Ok(PropertyIdentifier::ReasonForHalt),
+            102 => Ok(PropertyIdentifier::RecipientList),
+            103 => Ok(PropertyIdentifier::Reliability),
+            104 => Ok(PropertyIdentifier::RelinquishDefault),
+            105 => Ok(PropertyIdentifier::Required),
+            106 => Ok(PropertyIdentifier::Resolution),
+            107 => Ok(PropertyIdentifier::SegmentationSupported),
+            108 => Ok(PropertyIdentifier::Setpoint),
+            109 => Ok(PropertyIdentifier::SetpointReference),
+            110 => Ok(PropertyIdentifier::StateText),
+            111 => Ok(PropertyIdentifier::StatusFlags),
+            112 => Ok(PropertyIdentifier::SystemStatus),
+            113 => Ok(PropertyIdentifier::TimeDelay),
+            114 => Ok(PropertyIdentifier::TimeOfActiveTimeReset),
+            115 => Ok(PropertyIdentifier::TimeOfStateCountReset),
+            117 => Ok(PropertyIdentifier::Units),
+            118 => Ok(PropertyIdentifier::UpdateInterval),
+            120 => Ok(PropertyIdentifier::VendorIdentifier),
+            121 => Ok(PropertyIdentifier::VendorName),
+            123 => Ok(PropertyIdentifier::WeeklySchedule),
+            126 => Ok(PropertyIdentifier::BufferSize),
+            127 => Ok(PropertyIdentifier::ClientCovIncrement),
+            128 => Ok(PropertyIdentifier::CovResubscriptionInterval),
+            130 => Ok(PropertyIdentifier::EventTimeStamps),
+            131 => Ok(PropertyIdentifier::LogBuffer),
+            132 => Ok(PropertyIdentifier::LogDeviceObjectProperty),
+            133 => Ok(PropertyIdentifier::LogEnable),
+            134 => Ok(PropertyIdentifier::LogInterval),
+            139 => Ok(PropertyIdentifier::ProtocolRevision),
+            141 => Ok(PropertyIdentifier::RecordCount),
+            142 => Ok(PropertyIdentifier::StartTime),
+            143 => Ok(PropertyIdentifier::StopTime),
+            144 => Ok(PropertyIdentifier::StopWhenFull),
+            145 => Ok(PropertyIdentifier::TotalRecordCount),
+            152 => Ok(PropertyIdentifier::ActiveCovSubscriptions),
+            155 => Ok(PropertyIdentifier::DatabaseRevision),
+            158 => Ok(PropertyIdentifier::MaintenanceRequired),
+            159 => Ok(PropertyIdentifier::MemberOf),
+            160 => Ok(PropertyIdentifier::Mode),
+            161 => Ok(PropertyIdentifier::OperationExpected),
+            163 => Ok(PropertyIdentifier::Silenced),
+            164 => Ok(PropertyIdentifier::TrackingValue),
+            165 => Ok(PropertyIdentifier::ZoneMembers),
+            166 => Ok(PropertyIdentifier::LifeSafetyAlarmValues),
+            169 => Ok(PropertyIdentifier::AutoSlaveDiscovery),
+            170 => Ok(PropertyIdentifier::ManualSlaveAddressBinding),
+            171 => Ok(PropertyIdentifier::SlaveAddressBinding),
+            172 => Ok(PropertyIdentifier::SlaveProxyEnable),
+            174 => Ok(PropertyIdentifier::ScheduleDefault),
+            175 => Ok(PropertyIdentifier::AcceptedModes),
+            176 => Ok(PropertyIdentifier::AdjustValue),
+            177 => Ok(PropertyIdentifier::Count),
+            178 => Ok(PropertyIdentifier::CountBeforeChange),
+            179 => Ok(PropertyIdentifier::CountChangeTime),
+            180 => Ok(PropertyIdentifier::CovPeriod),
+            181 => Ok(PropertyIdentifier::InputReference),
+            182 => Ok(PropertyIdentifier::LimitMonitoringInterval),
+            185 => Ok(PropertyIdentifier::Prescale),
+            186 => Ok(PropertyIdentifier::PulseRate),
+            187 => Ok(PropertyIdentifier::Scale),
+            188 => Ok(PropertyIdentifier::ScaleFactor),
+            189 => Ok(PropertyIdentifier::UpdateTime),
+            190 => Ok(PropertyIdentifier::ValueBeforeChange),
+            191 => Ok(PropertyIdentifier::ValueSet),
+            192 => Ok(PropertyIdentifier::ValueChangeTime),
+            197 => Ok(PropertyIdentifier::LoggingType),
+            205 => Ok(PropertyIdentifier::Trigger),
+            207 => Ok(PropertyIdentifier::NodeSubtype),
+            208 => Ok(PropertyIdentifier::NodeType),
+            210 => Ok(PropertyIdentifier::SubordinateAnnotations),
+            211 => Ok(PropertyIdentifier::SubordinateList),
+            212 => Ok(PropertyIdentifier::ActualShedLevel),
+            213 => Ok(PropertyIdentifier::DutyWindow),
+            214 => Ok(PropertyIdentifier::ExpectedShedLevel),
+            215 => Ok(PropertyIdentifier::FullDutyBaseline),
+            218 => Ok(PropertyIdentifier::RequestedShedLevel),
+            219 => Ok(PropertyIdentifier::ShedDuration),
+            220 => Ok(PropertyIdentifier::ShedLevelDescriptions),
+            221 => Ok(PropertyIdentifier::ShedLevels),
+            222 => Ok(PropertyIdentifier::StateDescription),
+            226 => Ok(PropertyIdentifier::DoorAlarmState),
+            227 => Ok(PropertyIdentifier::DoorExtendedPulseTime),
+            228 => Ok(PropertyIdentifier::DoorMembers),
+            229 => Ok(PropertyIdentifier::DoorOpenTooLongTime),
+            230 => Ok(PropertyIdentifier::DoorPulseTime),
+            231 => Ok(PropertyIdentifier::DoorStatus),
+            232 => Ok(PropertyIdentifier::DoorUnlockDelayTime),
+            233 => Ok(PropertyIdentifier::LockStatus),
+            234 => Ok(PropertyIdentifier::MaskedAlarmValues),
+            235 => Ok(PropertyIdentifier::SecuredStatus),
+            246 => Ok(PropertyIdentifier::AccessDoors),
+            247 => Ok(PropertyIdentifier::AccessEvent),
+            248 => Ok(PropertyIdentifier::AccessEventAuthenticationFactor),
+            249 => Ok(PropertyIdentifier::AccessEventCredential),
+            250 => Ok(PropertyIdentifier::AccessEventTime),
+            257 => Ok(PropertyIdentifier::AuthenticationFactors),
+            258 => Ok(PropertyIdentifier::AuthenticationPolicyList),
+            259 => Ok(PropertyIdentifier::AuthenticationPolicyNames),
+            260 => Ok(PropertyIdentifier::AuthenticationStatus),
+            261 => Ok(PropertyIdentifier::AuthorizationMode),
+            266 => Ok(PropertyIdentifier::CredentialsInZone),
+            268 => Ok(PropertyIdentifier::EntryPoints),
+            269 => Ok(PropertyIdentifier::ExitPoints),
+            290 => Ok(PropertyIdentifier::OccupancyCount),
+            292 => Ok(PropertyIdentifier::OccupancyCountEnable),
+            294 => Ok(PropertyIdentifier::OccupancyLowerLimit),
+            296 => Ok(PropertyIdentifier::OccupancyState),
+            297 => Ok(PropertyIdentifier::OccupancyUpperLimit),
+            304 => Ok(PropertyIdentifier::SupportedFormats),
+            305 => Ok(PropertyIdentifier::SupportedFormatClasses),
+            320 => Ok(PropertyIdentifier::ZoneFrom),
+            321 => Ok(PropertyIdentifier::ZoneTo),
+            322 => Ok(PropertyIdentifier::AccessEventTag),
+            323 => Ok(PropertyIdentifier::GlobalIdentifier),
+            343 => Ok(PropertyIdentifier::BitText),
+            345 => Ok(PropertyIdentifier::GroupMembers),
+            346 => Ok(PropertyIdentifier::GroupMemberNames),
+            347 => Ok(PropertyIdentifier::MemberStatusFlags),
+            348 => Ok(PropertyIdentifier::RequestedUpdateInterval),
+            349 => Ok(PropertyIdentifier::CovuPeriod),
+            350 => Ok(PropertyIdentifier::CovuRecipients),
+            353 => Ok(PropertyIdentifier::EventDetectionEnable),
+            360 => Ok(PropertyIdentifier::LocalForwardingOnly),
+            361 => Ok(PropertyIdentifier::ProcessIdentifierFilter),
+            362 => Ok(PropertyIdentifier::SubscribedRecipients),
+            363 => Ok(PropertyIdentifier::PortFilter),
+            364 => Ok(PropertyIdentifier::AuthorizationExemptions),
+            365 => Ok(PropertyIdentifier::AllowGroupDelayInhibit),
+            366 => Ok(PropertyIdentifier::ChannelNumber),
+            367 => Ok(PropertyIdentifier::ControlGroups),
+            368 => Ok(PropertyIdentifier::ExecutionDelay),
+            369 => Ok(PropertyIdentifier::LastPriority),
+            370 => Ok(PropertyIdentifier::WriteStatus),
+            371 => Ok(PropertyIdentifier::PropertyList),
+            373 => Ok(PropertyIdentifier::BlinkWarnEnable),
+            374 => Ok(PropertyIdentifier::DefaultFadeTime),
+            375 => Ok(PropertyIdentifier::DefaultRampRate),
+            376 => Ok(PropertyIdentifier::DefaultStepIncrement),
+            377 => Ok(PropertyIdentifier::EgressTime),
+            378 => Ok(PropertyIdentifier::InProgress),
+            379 => Ok(PropertyIdentifier::InstantaneousPower),
+            380 => Ok(PropertyIdentifier::LightingCommand),
+            381 => Ok(PropertyIdentifier::LightingCommandDefaultPriority),
+            382 => Ok(PropertyIdentifier::MaxActualValue),
+            383 => Ok(PropertyIdentifier::MinActualValue),
+            384 => Ok(PropertyIdentifier::Power),
+            385 => Ok(PropertyIdentifier::Transition),
+            386 => Ok(PropertyIdentifier::EgressActive),
+            393 => Ok(PropertyIdentifier::DefaultTimeout),
+            394 => Ok(PropertyIdentifier::InitialTimeout),
+            395 => Ok(PropertyIdentifier::LastStateChange),
+            396 => Ok(PropertyIdentifier::StateChangeValues),
+            397 => Ok(PropertyIdentifier::TimerRunning),
+            398 => Ok(PropertyIdentifier::TimerState),
+            399 => Ok(PropertyIdentifier::ApduLength),
+            400 => Ok(PropertyIdentifier::IpAddress),
+            401 => Ok(PropertyIdentifier::IpDefaultGateway),
+            402 => Ok(PropertyIdentifier::IpDhcpEnable),
+            406 => Ok(PropertyIdentifier::IpDnsServer),
+            407 => Ok(PropertyIdentifier::BacnetIpGlobalAddress),
+            408 => Ok(PropertyIdentifier::BacnetIpMode),
+            410 => Ok(PropertyIdentifier::BacnetIpNatTraversal),
+            411 => Ok(PropertyIdentifier::IpSubnetMask),
+            412 => Ok(PropertyIdentifier::BacnetIpUdpPort),
+            413 => Ok(PropertyIdentifier::BbmdAcceptFdRegistrations),
+            414 => Ok(PropertyIdentifier::BbmdBroadcastDistributionTable),
+            415 => Ok(PropertyIdentifier::BbmdForeignDeviceTable),
+            416 => Ok(PropertyIdentifier::ChangesPending),
+            417 => Ok(PropertyIdentifier::Command),
+            418 => Ok(PropertyIdentifier::FdBbmdAddress),
+            419 => Ok(PropertyIdentifier::FdSubscriptionLifetime),
+            420 => Ok(PropertyIdentifier::LinkSpeed),
+            423 => Ok(PropertyIdentifier::MacAddress),
+            425 => Ok(PropertyIdentifier::NetworkNumber),
+            426 => Ok(PropertyIdentifier::NetworkNumberQuality),
+            427 => Ok(PropertyIdentifier::NetworkType),
+            447 => Ok(PropertyIdentifier::AssignedLandingCalls),
+            448 => Ok(PropertyIdentifier::CarAssignedDirection),
+            449 => Ok(PropertyIdentifier::CarDoorCommand),
+            450 => Ok(PropertyIdentifier::CarDoorStatus),
+            451 => Ok(PropertyIdentifier::CarDoorText),
+            452 => Ok(PropertyIdentifier::CarDoorZone),
+            453 => Ok(PropertyIdentifier::CarDriveStatus),
+            454 => Ok(PropertyIdentifier::CarLoad),
+            455 => Ok(PropertyIdentifier::CarLoadUnits),
+            456 => Ok(PropertyIdentifier::CarMode),
+            457 => Ok(PropertyIdentifier::CarMovingDirection),
+            458 => Ok(PropertyIdentifier::CarPosition),
+            459 => Ok(PropertyIdentifier::ElevatorGroup),
+            460 => Ok(PropertyIdentifier::EnergyMeter),
+            462 => Ok(PropertyIdentifier::EscalatorMode),
+            463 => Ok(PropertyIdentifier::FaultSignals),
+            464 => Ok(PropertyIdentifier::FloorText),
+            465 => Ok(PropertyIdentifier::GroupId),
+            467 => Ok(PropertyIdentifier::GroupMode),
+            468 => Ok(PropertyIdentifier::HigherDeck),
+            469 => Ok(PropertyIdentifier::InstallationId),
+            470 => Ok(PropertyIdentifier::LandingCalls),
+            471 => Ok(PropertyIdentifier::LandingCallControl),
+            472 => Ok(PropertyIdentifier::LandingDoorStatus),
+            473 => Ok(PropertyIdentifier::LowerDeck),
+            474 => Ok(PropertyIdentifier::MachineRoomId),
+            475 => Ok(PropertyIdentifier::MakingCarCall),
+            476 => Ok(PropertyIdentifier::NextStoppingFloor),
+            477 => Ok(PropertyIdentifier::OperationDirection),
+            478 => Ok(PropertyIdentifier::PassengerAlarm),
+            479 => Ok(PropertyIdentifier::PowerMode),
+            480 => Ok(PropertyIdentifier::RegisteredCarCall),
+            482 => Ok(PropertyIdentifier::ProtocolLevel),
+            493 => Ok(PropertyIdentifier::PresentStage),
+            494 => Ok(PropertyIdentifier::Stages),
+            495 => Ok(PropertyIdentifier::StageNames),
+            496 => Ok(PropertyIdentifier::TargetReferences),
+            497 => Ok(PropertyIdentifier::AuditSourceReporter),
+            498 => Ok(PropertyIdentifier::AuditLevel),
+            499 => Ok(PropertyIdentifier::AuditNotificationRecipient),
+            500 => Ok(PropertyIdentifier::AuditPriorityFilter),
+            501 => Ok(PropertyIdentifier::AuditableOperations),
+            503 => Ok(PropertyIdentifier::MaximumSendDelay),
+            504 => Ok(PropertyIdentifier::MonitoredObjects),
+            505 => Ok(PropertyIdentifier::SendNow),
+            4194306 => Ok(PropertyIdentifier::ScPrimaryHubUri),
+            4194307 => Ok(PropertyIdentifier::ScFailoverHubUri),
+            4194308 => Ok(PropertyIdentifier::ScMinimumReconnectTime),
+            4194309 => Ok(PropertyIdentifier::ScMaximumReconnectTime),
+            4194310 => Ok(PropertyIdentifier::ScConnectWaitTimeout),
+            4194311 => Ok(PropertyIdentifier::ScDisconnectWaitTimeout),
+            4194312 => Ok(PropertyIdentifier::ScHeartbeatTimeout),
+            4194326 => Ok(PropertyIdentifier::OperationalCertificateFile),
+            4194327 => Ok(PropertyIdentifier::IssuerCertificateFiles),
+            4194328 => Ok(PropertyIdentifier::CertificateSigningRequestFile),
+            4194343 => Ok(PropertyIdentifier::AuthorizationCache),
+            4194344 => Ok(PropertyIdentifier::AuthorizationGroups),
+            4194345 => Ok(PropertyIdentifier::AuthorizationPolicy),
+            4194346 => Ok(PropertyIdentifier::AuthorizationScope),
+            4194347 => Ok(PropertyIdentifier::AuthorizationServer),
+            4194348 => Ok(PropertyIdentifier::AuthorizationStatus),
+            512..=4194303 => Ok(PropertyIdentifier::Proprietary(value)),
+            _ => Err(ObjectError::InvalidValue(format!(
+                "Unknown property identifier: {}",
+                value
+            ))),
+        }
+    }
 }
 
 /// Object identifier (type + instance number)
@@ -353,6 +1435,116 @@ impl ObjectIdentifier {
     }
 }
 
+/// Reference to a property of an object, optionally in another device
+/// (BACnetDeviceObjectPropertyReference)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeviceObjectPropertyReference {
+    /// Referenced object
+    pub object_identifier: ObjectIdentifier,
+    /// Referenced property
+    pub property_identifier: PropertyIdentifier,
+    /// Array index, if a single element is referenced
+    pub property_array_index: Option<u32>,
+    /// Device containing the object, or `None` for the local device
+    pub device_identifier: Option<ObjectIdentifier>,
+}
+
+impl DeviceObjectPropertyReference {
+    /// Create a reference to a property of a local object
+    pub fn new(
+        object_identifier: ObjectIdentifier,
+        property_identifier: PropertyIdentifier,
+    ) -> Self {
+        Self {
+            object_identifier,
+            property_identifier,
+            property_array_index: None,
+            device_identifier: None,
+        }
+    }
+
+    /// Encode the reference as a property value
+    pub fn to_property_value(&self) -> PropertyValue {
+        let mut items = vec![
+            PropertyValue::ObjectIdentifier(self.object_identifier),
+            PropertyValue::Enumerated(u32::from(self.property_identifier)),
+        ];
+        if let Some(index) = self.property_array_index {
+            items.push(PropertyValue::UnsignedInteger(index));
+        }
+        if let Some(device) = self.device_identifier {
+            items.push(PropertyValue::ObjectIdentifier(device));
+        }
+        PropertyValue::List(items)
+    }
+}
+
+/// Reference to an object, optionally in another device (BACnetDeviceObjectReference)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeviceObjectReference {
+    /// Device containing the object, or `None` for the local device
+    pub device_identifier: Option<ObjectIdentifier>,
+    /// Referenced object
+    pub object_identifier: ObjectIdentifier,
+}
+
+impl DeviceObjectReference {
+    /// Create a reference to a local object
+    pub fn new(object_identifier: ObjectIdentifier) -> Self {
+        Self {
+            device_identifier: None,
+            object_identifier,
+        }
+    }
+
+    /// Create a reference to an object in another device
+    pub fn remote(
+        device_identifier: ObjectIdentifier,
+        object_identifier: ObjectIdentifier,
+    ) -> Self {
+        Self {
+            device_identifier: Some(device_identifier),
+            object_identifier,
+        }
+    }
+
+    /// Encode the reference as a property value
+    pub fn to_property_value(&self) -> PropertyValue {
+        let mut items = Vec::with_capacity(2);
+        if let Some(device) = self.device_identifier {
+            items.push(PropertyValue::ObjectIdentifier(device));
+        }
+        items.push(PropertyValue::ObjectIdentifier(self.object_identifier));
+        PropertyValue::List(items)
+    }
+
+    /// Decode a reference encoded by [`DeviceObjectReference::to_property_value`]
+    pub fn from_property_value(value: &PropertyValue) -> Result<Self> {
+        match value {
+            PropertyValue::List(items) => match items.as_slice() {
+                [PropertyValue::ObjectIdentifier(object)] => Ok(Self::new(*object)),
+                [PropertyValue::ObjectIdentifier(device), PropertyValue::ObjectIdentifier(object)] => {
+                    Ok(Self::remote(*device, *object))
+                }
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            _ => Err(ObjectError::InvalidPropertyType),
+        }
+    }
+}
+
+/// A write an object needs made to a referenced property, such as a
+/// Schedule's Present_Value, a Loop's output or a Command action
+#[derive(Debug, Clone, PartialEq)]
+pub struct PropertyWrite {
+    /// Property to write
+    pub reference: DeviceObjectPropertyReference,
+    /// Value to write
+    pub value: PropertyValue,
+    /// Command priority to write at
+    pub priority: u8,
+}
+
 /// Trait for all BACnet objects
 pub trait BacnetObject: Send + Sync {
     /// Get the object identifier
@@ -364,15 +1556,227 @@ pub trait BacnetObject: Send + Sync {
     /// Set a property value
     fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()>;
 
+    /// Set a property value at a command priority (1-16)
+    ///
+    /// Commandable objects route Present_Value writes through their priority
+    /// array. Objects without one ignore the priority.
+    fn set_property_with_priority(
+        &mut self,
+        property: PropertyIdentifier,
+        value: PropertyValue,
+        priority: u8,
+    ) -> Result<()> {
+        let _ = priority;
+        self.set_property(property, value)
+    }
+
+    /// Get one element of a BACnetARRAY property
+    ///
+    /// Index 0 returns the array length as an unsigned integer and indices
+    /// 1..=N return a single element, as ReadProperty with an array index
+    /// requires.
+    fn get_property_at(&self, property: PropertyIdentifier, index: u32) -> Result<PropertyValue> {
+        array_element(self.get_property(property)?, index)
+    }
+
+    /// Set one element of a BACnetARRAY property
+    ///
+    /// The default reads the whole array, replaces the element and writes the
+    /// array back. Index 0 may shrink the array to the given length. Writing
+    /// element N of Priority_Array commands Present_Value at priority N, with
+    /// NULL relinquishing it.
+    fn set_property_at(
+        &mut self,
+        property: PropertyIdentifier,
+        index: u32,
+        value: PropertyValue,
+    ) -> Result<()> {
+        if property == PropertyIdentifier::PriorityArray {
+            let priority = u8::try_from(index)
+                .ok()
+                .filter(|priority| (1..=16).contains(priority))
+                .ok_or(ObjectError::InvalidArrayIndex)?;
+            return self.set_property_with_priority(
+                PropertyIdentifier::PresentValue,
+                value,
+                priority,
+            );
+        }
+        let array = replace_array_element(self.get_property(property)?, index, value)?;
+        self.set_property(property, array)
+    }
+
     /// Check if property is writable
     fn is_property_writable(&self, property: PropertyIdentifier) -> bool;
 
     /// Get list of all properties
     fn property_list(&self) -> Vec<PropertyIdentifier>;
+
+    /// Get the properties the standard requires of this object type
+    ///
+    /// ReadPropertyMultiple expands REQUIRED to this list and OPTIONAL to the
+    /// rest of `property_list`. The default keeps the properties that are
+    /// required wherever they appear: identification, status and command
+    /// prioritization. Object types with other required properties override it.
+    fn required_properties(&self) -> Vec<PropertyIdentifier> {
+        self.property_list()
+            .into_iter()
+            .filter(|property| COMMONLY_REQUIRED_PROPERTIES.contains(property))
+            .collect()
+    }
+
+    /// Advance the object's internal timers by `elapsed`
+    ///
+    /// Objects with time-dependent behaviour (minimum on/off times, ramps,
+    /// schedules) override this. The default does nothing.
+    fn advance_time(&mut self, elapsed: core::time::Duration) {
+        let _ = elapsed;
+    }
+
+    /// Take the writes this object has queued for referenced properties
+    ///
+    /// Objects that drive other properties (such as a Loop's output) queue
+    /// writes while their timers run; the database applies them after
+    /// advancing time. The default has none.
+    fn take_pending_writes(&mut self) -> Vec<PropertyWrite> {
+        Vec::new()
+    }
+
+    /// Receive the outcome of the writes last returned by `take_pending_writes`
+    ///
+    /// `results` holds one entry per write, in order. Objects that sequence
+    /// writes (such as a Command's action lists) use this to continue or stop.
+    /// The default ignores the results.
+    fn report_write_results(&mut self, results: &[bool]) {
+        let _ = results;
+    }
+
+    /// Make pending configuration changes take effect
+    ///
+    /// Called when the device is reinitialized with ACTIVATE_CHANGES. Objects
+    /// that stage configuration writes (such as a Network Port) override this.
+    /// The default does nothing.
+    fn activate_changes(&mut self) {}
+
+    /// Acknowledge the latest `transition`, as AcknowledgeAlarm does
+    ///
+    /// Event-initiating objects set the transition's bit in Acked_Transitions.
+    /// The default reports that the object has no Acked_Transitions.
+    fn acknowledge_transition(&mut self, transition: EventTransition) -> Result<()> {
+        let _ = transition;
+        Err(ObjectError::UnknownProperty)
+    }
+
+    /// Group the values of an AddListElement or RemoveListElement request
+    /// into elements of the list property `property`
+    ///
+    /// The encoding has no boundaries between elements, so objects whose list
+    /// elements span several values (such as Recipient_List destinations)
+    /// override this. The default takes each value as an element.
+    fn list_elements(
+        &self,
+        property: PropertyIdentifier,
+        values: Vec<PropertyValue>,
+    ) -> Result<Vec<PropertyValue>> {
+        let _ = property;
+        Ok(values)
+    }
+
+    /// Add `elements` to the list property `property`, as AddListElement does
+    ///
+    /// Elements already in the list are left as they are. Either every element
+    /// is added or the list is unchanged and the error names the first element
+    /// that failed. The default works on any property read and written as a
+    /// [`PropertyValue::List`].
+    fn add_list_elements(
+        &mut self,
+        property: PropertyIdentifier,
+        elements: &[PropertyValue],
+    ) -> core::result::Result<(), crate::service::ChangeListError> {
+        crate::service::list_element::add_list_elements(self, property, elements)
+    }
+
+    /// Remove `elements` from the list property `property`, as
+    /// RemoveListElement does
+    ///
+    /// Either every element is removed or the list is unchanged and the error
+    /// names the first element that is not in the list.
+    fn remove_list_elements(
+        &mut self,
+        property: PropertyIdentifier,
+        elements: &[PropertyValue],
+    ) -> core::result::Result<(), crate::service::ChangeListError> {
+        crate::service::list_element::remove_list_elements(self, property, elements)
+    }
+
+    /// View this object as a File, for AtomicReadFile and AtomicWriteFile
+    ///
+    /// File objects return themselves. The default returns `None`.
+    fn as_file(&self) -> Option<&File> {
+        None
+    }
+
+    /// View this object as a mutable File, for AtomicWriteFile
+    fn as_file_mut(&mut self) -> Option<&mut File> {
+        None
+    }
+
+    /// View this object as an Audit Log, for AuditLogQuery
+    ///
+    /// Audit Log objects return themselves. The default returns `None`.
+    fn as_audit_log(&self) -> Option<&AuditLog> {
+        None
+    }
+
+    /// View this object as a mutable Audit Log, for storing received audit
+    /// notifications
+    fn as_audit_log_mut(&mut self) -> Option<&mut AuditLog> {
+        None
+    }
+
+    /// View this object as a mutable Channel, for WriteGroup
+    ///
+    /// Channel objects return themselves. The default returns `None`.
+    fn as_channel_mut(&mut self) -> Option<&mut Channel> {
+        None
+    }
+
+    /// Apply a LifeSafetyOperation request to this object
+    ///
+    /// Life Safety Point and Zone objects silence, unsilence or reset
+    /// themselves. The default reports that the object takes no operations.
+    fn life_safety_operation(&mut self, operation: LifeSafetyOperation) -> Result<()> {
+        let _ = operation;
+        Err(ObjectError::InvalidConfiguration(
+            "Not a life safety object".to_string(),
+        ))
+    }
+
+    /// Whether DeleteObject may remove this object
+    ///
+    /// Objects that other parts of the device depend on can refuse deletion.
+    /// The default allows it.
+    fn is_deletable(&self) -> bool {
+        true
+    }
 }
 
+/// Properties required by every object type that has them
+const COMMONLY_REQUIRED_PROPERTIES: &[PropertyIdentifier] = &[
+    PropertyIdentifier::ObjectIdentifier,
+    PropertyIdentifier::ObjectName,
+    PropertyIdentifier::ObjectType,
+    PropertyIdentifier::PresentValue,
+    PropertyIdentifier::StatusFlags,
+    PropertyIdentifier::EventState,
+    PropertyIdentifier::OutOfService,
+    PropertyIdentifier::Units,
+    PropertyIdentifier::PriorityArray,
+    PropertyIdentifier::RelinquishDefault,
+];
+
 /// Property values can be of various types
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum PropertyValue {
     Null,
     Boolean(bool),
@@ -391,10 +1795,127 @@ pub enum PropertyValue {
     List(Vec<PropertyValue>),
 }
 
+/// Priority assumed for commands that do not specify one (Clause 19.2.1)
+pub const DEFAULT_COMMAND_PRIORITY: u8 = 16;
+
+/// Encode a 4-bit status flags value (in_alarm, fault, overridden, out_of_service)
+/// as a Status_Flags bit string
+pub fn status_flags_bit_string(flags: u8) -> PropertyValue {
+    PropertyValue::BitString(vec![
+        (flags & 0x08) != 0, // in_alarm
+        (flags & 0x04) != 0, // fault
+        (flags & 0x02) != 0, // overridden
+        (flags & 0x01) != 0, // out_of_service
+    ])
+}
+
+/// Current Status_Flags: the stored flags combined with in_alarm from
+/// Event_State, fault from Reliability and out_of_service (Clause 12)
+///
+/// Objects without a stored value, an Event_State or an Out_Of_Service pass
+/// `0`, [`EventState::Normal`] or `false`.
+pub fn current_status_flags(
+    stored: u8,
+    event_state: EventState,
+    reliability: Reliability,
+    out_of_service: bool,
+) -> u8 {
+    let mut flags = stored;
+    if event_state != EventState::Normal {
+        flags |= 0x08;
+    }
+    if reliability != Reliability::NoFaultDetected {
+        flags |= 0x04;
+    }
+    if out_of_service {
+        flags |= 0x01;
+    }
+    flags
+}
+
+/// Select element `index` of an array value; index zero is the array length
+pub fn array_element(value: PropertyValue, index: u32) -> Result<PropertyValue> {
+    let PropertyValue::Array(mut items) = value else {
+        return Err(ObjectError::InvalidArrayIndex);
+    };
+    match index {
+        0 => Ok(PropertyValue::UnsignedInteger(items.len() as u32)),
+        _ if index as usize <= items.len() => Ok(items.swap_remove(index as usize - 1)),
+        _ => Err(ObjectError::InvalidArrayIndex),
+    }
+}
+
+/// Replace element `index` of an array value; writing index zero truncates
+/// the array to the given length
+pub fn replace_array_element(
+    array: PropertyValue,
+    index: u32,
+    value: PropertyValue,
+) -> Result<PropertyValue> {
+    let PropertyValue::Array(mut items) = array else {
+        return Err(ObjectError::InvalidArrayIndex);
+    };
+    match index {
+        0 => {
+            let PropertyValue::UnsignedInteger(length) = value else {
+                return Err(ObjectError::InvalidPropertyType);
+            };
+            if length as usize > items.len() {
+                return Err(ObjectError::InvalidValue(
+                    "Array can only be grown by writing the whole array".into(),
+                ));
+            }
+            items.truncate(length as usize);
+        }
+        _ => {
+            let element = items
+                .get_mut(index as usize - 1)
+                .ok_or(ObjectError::InvalidArrayIndex)?;
+            *element = value;
+        }
+    }
+    Ok(PropertyValue::Array(items))
+}
+
+/// Encode a BACnetDateTime as a date/time sequence, using the unspecified
+/// value when no timestamp has been recorded
+pub fn date_time_value(date_time: Option<crate::service::BacnetDateTime>) -> PropertyValue {
+    let date_time = date_time.unwrap_or_else(crate::service::BacnetDateTime::unspecified);
+    PropertyValue::List(vec![
+        PropertyValue::Date(date_time.date),
+        PropertyValue::Time(date_time.time),
+    ])
+}
+
+/// Decode a date/time sequence written as `[Date, Time]`
+pub fn date_time_from_value(value: &PropertyValue) -> Result<crate::service::BacnetDateTime> {
+    match value {
+        PropertyValue::List(items) => match items.as_slice() {
+            [PropertyValue::Date(date), PropertyValue::Time(time)] => {
+                Ok(crate::service::BacnetDateTime::new(*date, *time))
+            }
+            _ => Err(ObjectError::InvalidPropertyType),
+        },
+        _ => Err(ObjectError::InvalidPropertyType),
+    }
+}
+
+/// Current local time for object timestamps, when a clock is available
+pub(crate) fn current_date_time() -> Option<crate::service::BacnetDateTime> {
+    #[cfg(feature = "std")]
+    {
+        Some(crate::service::BacnetDateTime::now())
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        None
+    }
+}
+
 /// BACnet date representation
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Date {
-    pub year: u16,   // 1900-2155, 255 = unspecified
+    pub year: u16,   // 1900-2154, 255 = unspecified
     pub month: u8,   // 1-12, 13 = odd months, 14 = even months, 255 = unspecified
     pub day: u8,     // 1-31, 32 = last day of month, 255 = unspecified
     pub weekday: u8, // 1-7 (Mon-Sun), 255 = unspecified
@@ -446,6 +1967,14 @@ pub struct Device {
     pub device_address_binding: Vec<AddressBinding>,
     /// Database revision
     pub database_revision: u32,
+    /// Description
+    pub description: String,
+    /// APDU timeout in milliseconds
+    pub apdu_timeout: u32,
+    /// Number of APDU retries
+    pub number_of_apdu_retries: u32,
+    /// Objects contained in this device, including the device itself
+    pub object_list: Vec<ObjectIdentifier>,
 }
 
 impl Device {
@@ -469,7 +1998,47 @@ impl Device {
             segmentation_supported: Segmentation::Both,
             device_address_binding: Vec::new(),
             database_revision: 1,
+            description: String::new(),
+            apdu_timeout: 3000,
+            number_of_apdu_retries: 3,
+            object_list: vec![ObjectIdentifier::new(ObjectType::Device, instance)],
+        }
+    }
+
+    /// Add an object to the Object_List, bumping the database revision
+    pub fn add_object_to_list(&mut self, identifier: ObjectIdentifier) {
+        if !self.object_list.contains(&identifier) {
+            self.object_list.push(identifier);
+            self.add_supported_object_type(identifier.object_type);
+            self.database_revision = self.database_revision.wrapping_add(1);
+        }
+    }
+
+    /// Remove an object from the Object_List, bumping the database revision
+    ///
+    /// The device's own identifier is never removed.
+    pub fn remove_object_from_list(&mut self, identifier: ObjectIdentifier) -> bool {
+        if identifier == self.identifier {
+            return false;
+        }
+        let before = self.object_list.len();
+        self.object_list.retain(|&id| id != identifier);
+        let removed = self.object_list.len() != before;
+        if removed {
+            self.database_revision = self.database_revision.wrapping_add(1);
+        }
+        removed
+    }
+
+    /// Protocol_Object_Types_Supported as a bit string indexed by object type
+    pub fn object_types_supported_bit_string(&self) -> PropertyValue {
+        let mut bits = vec![false; PROTOCOL_OBJECT_TYPES_BITS];
+        for &object_type in &self.object_types_supported {
+            if let Some(bit) = bits.get_mut(u32::from(object_type) as usize) {
+                *bit = true;
+            }
         }
+        PropertyValue::BitString(bits)
     }
 
     /// Add an object type to the supported list
@@ -536,7 +2105,7 @@ impl BacnetObject for Device {
                 Ok(PropertyValue::CharacterString(self.object_name.clone()))
             }
             PropertyIdentifier::ObjectType => {
-                Ok(PropertyValue::Enumerated(self.object_type as u32))
+                Ok(PropertyValue::Enumerated(u32::from(self.object_type)))
             }
             PropertyIdentifier::SystemStatus => {
                 Ok(PropertyValue::Enumerated(self.system_status as u32))
@@ -571,6 +2140,27 @@ impl BacnetObject for Device {
             PropertyIdentifier::DatabaseRevision => {
                 Ok(PropertyValue::UnsignedInteger(self.database_revision))
             }
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::ProtocolServicesSupported => {
+                Ok(self.protocol_services_supported.to_bit_string())
+            }
+            PropertyIdentifier::ProtocolObjectTypesSupported => {
+                Ok(self.object_types_supported_bit_string())
+            }
+            PropertyIdentifier::ObjectList => Ok(PropertyValue::Array(
+                self.object_list
+                    .iter()
+                    .map(|&id| PropertyValue::ObjectIdentifier(id))
+                    .collect(),
+            )),
+            PropertyIdentifier::ApduTimeout => {
+                Ok(PropertyValue::UnsignedInteger(self.apdu_timeout))
+            }
+            PropertyIdentifier::NumberOfApduRetries => {
+                Ok(PropertyValue::UnsignedInteger(self.number_of_apdu_retries))
+            }
             _ => Err(ObjectError::UnknownProperty),
         }
     }
@@ -625,6 +2215,30 @@ impl BacnetObject for Device {
                     Err(ObjectError::InvalidPropertyType)
                 }
             }
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::ApduTimeout => {
+                if let PropertyValue::UnsignedInteger(timeout) = value {
+                    self.apdu_timeout = timeout;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::NumberOfApduRetries => {
+                if let PropertyValue::UnsignedInteger(retries) = value {
+                    self.number_of_apdu_retries = retries;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
             _ => Err(ObjectError::PropertyNotWritable),
         }
     }
@@ -638,6 +2252,9 @@ impl BacnetObject for Device {
                 | PropertyIdentifier::FirmwareRevision
                 | PropertyIdentifier::ApplicationSoftwareVersion
                 | PropertyIdentifier::DatabaseRevision
+                | PropertyIdentifier::Description
+                | PropertyIdentifier::ApduTimeout
+                | PropertyIdentifier::NumberOfApduRetries
         )
     }
 
@@ -657,8 +2274,20 @@ impl BacnetObject for Device {
             PropertyIdentifier::MaxApduLengthAccepted,
             PropertyIdentifier::SegmentationSupported,
             PropertyIdentifier::DatabaseRevision,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::ProtocolServicesSupported,
+            PropertyIdentifier::ProtocolObjectTypesSupported,
+            PropertyIdentifier::ObjectList,
+            PropertyIdentifier::ApduTimeout,
+            PropertyIdentifier::NumberOfApduRetries,
         ]
     }
+
+    fn required_properties(&self) -> Vec<PropertyIdentifier> {
+        let mut properties = self.property_list();
+        properties.retain(|property| *property != PropertyIdentifier::Description);
+        properties
+    }
 }
 
 /// Device status enumeration
@@ -683,16 +2312,25 @@ pub enum Segmentation {
     NoSegmentation = 3,
 }
 
+/// Length of the Protocol_Object_Types_Supported bit string (through Color Temperature)
+pub const PROTOCOL_OBJECT_TYPES_BITS: usize = 65;
+
+/// Length of the Protocol_Services_Supported bit string (through You-Are)
+pub const PROTOCOL_SERVICES_BITS: u8 = 49;
+
 /// Protocol services supported bitfield
+///
+/// Bit numbers follow BACnetServicesSupported (e.g. 12 = readProperty,
+/// 34 = who-Is), not the service choice codes.
 #[derive(Debug, Clone, Default)]
 pub struct ProtocolServicesSupported {
-    pub bits: [u8; 5], // 40 bits for all BACnet services
+    pub bits: [u8; 7], // 49 bits for all BACnet services
 }
 
 impl ProtocolServicesSupported {
     /// Set a service as supported
     pub fn set_service(&mut self, service: u8, supported: bool) {
-        if service < 40 {
+        if service < PROTOCOL_SERVICES_BITS {
             let byte_index = service / 8;
             let bit_index = service % 8;
             if supported {
@@ -705,7 +2343,7 @@ impl ProtocolServicesSupported {
 
     /// Check if a service is supported
     pub fn is_service_supported(&self, service: u8) -> bool {
-        if service < 40 {
+        if service < PROTOCOL_SERVICES_BITS {
             let byte_index = service / 8;
             let bit_index = service % 8;
             (self.bits[byte_index as usize] & (1 << bit_index)) != 0
@@ -713,6 +2351,15 @@ impl ProtocolServicesSupported {
             false
         }
     }
+
+    /// Encode as the Protocol_Services_Supported bit string
+    pub fn to_bit_string(&self) -> PropertyValue {
+        PropertyValue::BitString(
+            (0..PROTOCOL_SERVICES_BITS)
+                .map(|service| self.is_service_supported(service))
+                .collect(),
+        )
+    }
 }
 
 /// Address binding for device routing
@@ -722,34 +2369,174 @@ pub struct AddressBinding {
     pub network_address: Vec<u8>,
 }
 
+/// Access control object types (Access Door, Access Point, Access Zone, Credential Data Input)
+pub mod access_control;
+/// Accumulator object type for pulse-counting meters
+pub mod accumulator;
+/// Alert Enrollment object type for stateless alerts
+pub mod alert_enrollment;
 /// Analog object types (AI, AO, AV)
 pub mod analog;
+/// Audit Log and Audit Reporter object types
+pub mod audit;
 /// Binary object types (BI, BO, BV)
 pub mod binary;
+/// BitString Value object type
+pub mod bit_string;
+/// Calendar object type
+pub mod calendar;
+/// Channel object type for relaying one write to many points
+pub mod channel;
+/// CharacterString Value object type
+pub mod character_string;
+/// Command object type
+pub mod command;
+/// Priority array shared by commandable objects
+pub mod commandable;
+/// Loop object type with an optional built-in PID controller
+pub mod control_loop;
 /// Object database for managing BACnet objects
 #[cfg(feature = "std")]
 pub mod database;
+/// Date, Time and DateTime Value object types and their pattern variants
+pub mod date_time;
 /// Device object and object functions API
 pub mod device;
+/// Elevator Group, Lift and Escalator object types
+pub mod elevator;
 /// Engineering units enumeration
 pub mod engineering_units;
+/// Event Enrollment object type
+pub mod event_enrollment;
+/// Per-type factories for objects created by CreateObject
+pub mod factory;
 /// File object type
 pub mod file;
+/// Global Group object type for members in remote devices
+pub mod global_group;
+/// Group object type
+pub mod group;
+/// Integer Value and Positive Integer Value object types
+pub mod integer;
+/// Large Analog Value object type (double-precision Present_Value)
+pub mod large_analog;
+/// Life Safety Point and Life Safety Zone object types
+pub mod life_safety;
+/// Lighting Output and Binary Lighting Output object types
+pub mod lighting_output;
+/// Load Control object type for demand-response load shedding
+pub mod load_control;
+/// Macro generating BacnetObject implementations from annotated fields
+pub mod macros;
 /// Multi-state object types (MSI, MSO, MSV)
 pub mod multistate;
+/// Network Port object type for datalink configuration
+pub mod network_port;
+/// Notification Class object type
+pub mod notification_class;
+/// Notification Forwarder object type
+pub mod notification_forwarder;
 /// Octet String object type
 pub mod octet_string;
+/// Program object type
+pub mod program;
+/// Vendor-specific object types and proprietary property extensions
+pub mod proprietary;
+/// Pulse Converter object type for scaled pulse totals
+pub mod pulse_converter;
+/// Schedule object type
+pub mod schedule;
+/// Staging object type for multi-stage equipment
+pub mod staging;
+/// Structured View object type for navigable point hierarchies
+pub mod structured_view;
+/// Timer object type
+pub mod timer;
+/// Trend Log object type
+pub mod trendlog;
+/// Trend Log Multiple object type
+pub mod trendlog_multiple;
 
-pub use analog::{AnalogInput, AnalogOutput, AnalogValue, EventState, Reliability};
+pub use access_control::{
+    AccessDoor, AccessEvent, AccessPoint, AccessZone, AccessZoneOccupancyState,
+    AuthenticationFactor, AuthenticationStatus, CredentialDataInput, DoorAlarmState,
+    DoorSecuredStatus, DoorStatus, DoorValue, LockStatus,
+};
+pub use accumulator::{Accumulator, Prescale, Scale};
+pub use alert_enrollment::{Alert, AlertEnrollment};
+pub use analog::{
+    AnalogInput, AnalogLimitReporting, AnalogOutput, AnalogValue, EventState, NotifyType,
+    Reliability,
+};
+pub use audit::{
+    AuditLevel, AuditLog, AuditLogDatum, AuditLogRecord, AuditNotification, AuditOperation,
+    AuditReporter, ObjectSelector,
+};
 pub use binary::{BinaryInput, BinaryOutput, BinaryPV, BinaryValue, Polarity};
+pub use bit_string::BitStringValue;
+pub use calendar::{Calendar, CalendarEntry, DateRange, WeekNDay};
+pub use channel::{Channel, WriteStatus};
+pub use character_string::CharacterStringValue;
+pub use command::{ActionCommand, Command};
+pub use commandable::Commandable;
+pub use control_loop::{Loop, LoopAction};
+pub use date_time::{
+    DatePatternValue, DateTimePatternValue, DateTimeValue, DateValue, TimePatternValue,
+};
 pub use device::{DeviceObject, ObjectFunctions};
+pub use elevator::{
+    ElevatorGroup, Escalator, EscalatorMode, EscalatorOperationDirection, LandingCall,
+    LandingCallCommand, LandingDoor, Lift, LiftCarDirection, LiftCarDoorCommand,
+    LiftCarDriveStatus, LiftCarMode, LiftGroupMode,
+};
 pub use engineering_units::EngineeringUnits;
-pub use file::{File, FileAccessMethod};
+pub use event_enrollment::{
+    CovCriteria, EventEnrollment, EventParameters, EventStateChange, EventType,
+};
+pub use factory::{ObjectFactory, ObjectFactoryRegistry};
+#[cfg(feature = "std")]
+pub use file::FsFileStorage;
+pub use file::{File, FileAccessMethod, FileStorage, MemoryFileStorage};
+pub use global_group::{GlobalGroup, PropertyAccessResult};
+pub use group::Group;
+pub use integer::{IntegerValue, PositiveIntegerValue};
+pub use large_analog::LargeAnalogValue;
+pub use life_safety::{
+    LifeSafetyMode, LifeSafetyOperation, LifeSafetyPoint, LifeSafetyState, LifeSafetyStatus,
+    LifeSafetyZone, SilencedState,
+};
+pub use lighting_output::{
+    BinaryLightingOutput, BinaryLightingPV, LightingCommand, LightingInProgress, LightingOperation,
+    LightingOutput, LightingTransition,
+};
+pub use load_control::{LoadControl, ShedLevel, ShedState};
+pub use macros::PropertyField;
 pub use multistate::{MultiStateInput, MultiStateOutput, MultiStateValue};
+pub use network_port::{
+    BacnetIpMode, BdtTableEntry, DatalinkSettings, FdtTableEntry, HostNPort, IpPortSettings,
+    MstpPortSettings, MstpStatistics, NetworkNumberQuality, NetworkPort, NetworkPortCommand,
+    NetworkPortConfig, NetworkType, ProtocolLevel, ScPortSettings, SlaveBinding,
+};
+pub use notification_class::{Destination, EventTransition, NotificationClass, Recipient};
+pub use notification_forwarder::{
+    EventNotificationSubscription, ForwardTarget, NotificationForwarder, PortPermission,
+    ReceivedNotification,
+};
 pub use octet_string::OctetString;
+pub use program::{
+    Program, ProgramError, ProgramHalt, ProgramHandler, ProgramRequest, ProgramState,
+};
+pub use proprietary::{PropertyMap, ProprietaryExtension, ProprietaryObject};
+pub use pulse_converter::PulseConverter;
+pub use schedule::{Schedule, SpecialEvent, SpecialEventPeriod, TimeValue};
+pub use staging::{StageLimitValue, Staging};
+pub use structured_view::{NodeType, StructuredView};
+pub use timer::{Timer, TimerState, TimerTransition};
+pub use trendlog::{LogBufferRange, LogDatum, LogRecord, LoggingType, TrendLog};
+pub use trendlog_multiple::{LogMultipleData, LogMultipleRecord, TrendLogMultiple};
 
 #[cfg(feature = "std")]
-pub use database::{DatabaseBuilder, DatabaseStatistics, ObjectDatabase};
+pub use database::{DatabaseBuilder, DatabaseStatistics, DeletionPolicy, ObjectDatabase};
 
 #[cfg(test)]
 mod tests {
@@ -806,4 +2593,125 @@ mod tests {
         assert!(!services.is_service_supported(1));
         assert!(!services.is_service_supported(13));
     }
+
+    #[test]
+    fn test_device_object_list_and_capabilities() {
+        let mut device = Device::new(789, "Controller".to_string());
+        let ai = ObjectIdentifier::new(ObjectType::AnalogInput, 1);
+
+        device.add_object_to_list(ai);
+        device.add_object_to_list(ai);
+        assert_eq!(device.object_list.len(), 2);
+        assert_eq!(device.database_revision, 2);
+        assert!(!device.remove_object_from_list(device.identifier));
+
+        if let Ok(PropertyValue::BitString(bits)) =
+            device.get_property(PropertyIdentifier::ProtocolObjectTypesSupported)
+        {
+            assert_eq!(bits.len(), PROTOCOL_OBJECT_TYPES_BITS);
+            assert!(bits[u32::from(ObjectType::Device) as usize]);
+            assert!(bits[u32::from(ObjectType::AnalogInput) as usize]);
+            assert!(!bits[u32::from(ObjectType::BinaryInput) as usize]);
+        } else {
+            panic!("Expected BitString");
+        }
+
+        device.protocol_services_supported.set_service(48, true); // You-Are
+        if let Ok(PropertyValue::BitString(bits)) =
+            device.get_property(PropertyIdentifier::ProtocolServicesSupported)
+        {
+            assert_eq!(bits.len(), PROTOCOL_SERVICES_BITS as usize);
+            assert!(bits[48]);
+        } else {
+            panic!("Expected BitString");
+        }
+
+        device
+            .set_property(
+                PropertyIdentifier::ApduTimeout,
+                PropertyValue::UnsignedInteger(6000),
+            )
+            .unwrap();
+        assert_eq!(device.apdu_timeout, 6000);
+        assert!(device.remove_object_from_list(ai));
+    }
+
+    #[test]
+    fn test_current_status_flags() {
+        // Overridden only comes from the stored flags
+        assert_eq!(
+            current_status_flags(
+                0x02,
+                EventState::Normal,
+                Reliability::NoFaultDetected,
+                false
+            ),
+            0x02
+        );
+        assert_eq!(
+            current_status_flags(0x02, EventState::Offnormal, Reliability::OverRange, true),
+            0x0F
+        );
+    }
+
+    #[test]
+    fn test_array_element_access() {
+        let mut msv = MultiStateValue::new(1, "Mode".to_string(), 3);
+        msv.set_property_at(
+            PropertyIdentifier::StateText,
+            2,
+            PropertyValue::CharacterString("HEAT".to_string()),
+        )
+        .unwrap();
+        assert_eq!(
+            msv.get_property_at(PropertyIdentifier::StateText, 2)
+                .unwrap(),
+            PropertyValue::CharacterString("HEAT".to_string())
+        );
+        assert_eq!(
+            msv.get_property_at(PropertyIdentifier::StateText, 0)
+                .unwrap(),
+            PropertyValue::UnsignedInteger(3)
+        );
+        assert!(matches!(
+            msv.get_property_at(PropertyIdentifier::StateText, 4),
+            Err(ObjectError::InvalidArrayIndex)
+        ));
+        assert!(matches!(
+            msv.get_property_at(PropertyIdentifier::ObjectName, 1),
+            Err(ObjectError::InvalidArrayIndex)
+        ));
+    }
+
+    #[test]
+    fn test_priority_array_element_write() {
+        let mut ao = AnalogOutput::new(1, "Damper".to_string());
+        ao.set_property_at(
+            PropertyIdentifier::PriorityArray,
+            8,
+            PropertyValue::Real(40.0),
+        )
+        .unwrap();
+        assert_eq!(
+            ao.get_property_at(PropertyIdentifier::PriorityArray, 8)
+                .unwrap(),
+            PropertyValue::Real(40.0)
+        );
+        assert_eq!(
+            ao.get_property(PropertyIdentifier::PresentValue).unwrap(),
+            PropertyValue::Real(40.0)
+        );
+
+        ao.set_property_at(PropertyIdentifier::PriorityArray, 8, PropertyValue::Null)
+            .unwrap();
+        assert_eq!(
+            ao.get_property_at(PropertyIdentifier::PriorityArray, 8)
+                .unwrap(),
+            PropertyValue::Null
+        );
+        assert!(matches!(
+            ao.set_property_at(PropertyIdentifier::PriorityArray, 17, PropertyValue::Null),
+            Err(ObjectError::InvalidArrayIndex)
+        ));
+    }
 }
@@ -4,13 +4,67 @@
 //! object types as defined in ASHRAE 135. These objects represent multi-position values.
 
 use crate::object::{
-    BacnetObject, EventState, ObjectError, ObjectIdentifier, ObjectType, PropertyIdentifier,
-    PropertyValue, Reliability, Result,
+    current_status_flags, status_flags_bit_string, BacnetObject, Commandable, EventState,
+    ObjectError, ObjectIdentifier, ObjectType, PropertyIdentifier, PropertyValue, Reliability,
+    Result, DEFAULT_COMMAND_PRIORITY,
 };
 
 #[cfg(not(feature = "std"))]
 use alloc::{string::String, vec::Vec};
 
+/// Read the State_Text array, or one element of it
+///
+/// Index 0 returns the array length, indices 1..=N return a single entry and
+/// `None` returns the whole array.
+fn state_text_element(state_text: &[String], array_index: Option<u32>) -> Result<PropertyValue> {
+    match array_index {
+        None => Ok(PropertyValue::Array(
+            state_text
+                .iter()
+                .cloned()
+                .map(PropertyValue::CharacterString)
+                .collect(),
+        )),
+        Some(0) => Ok(PropertyValue::UnsignedInteger(state_text.len() as u32)),
+        Some(index) => state_text
+            .get((index - 1) as usize)
+            .cloned()
+            .map(PropertyValue::CharacterString)
+            .ok_or(ObjectError::InvalidArrayIndex),
+    }
+}
+
+/// Validate that a state lies within 1..=Number_Of_States
+fn check_state(value: u32, number_of_states: u32) -> Result<()> {
+    if value < 1 || value > number_of_states {
+        return Err(ObjectError::InvalidValue(format!(
+            "Value must be between 1 and {}",
+            number_of_states
+        )));
+    }
+    Ok(())
+}
+
+/// Parse a complete State_Text array written by a client
+fn parse_state_text(value: PropertyValue, number_of_states: u32) -> Result<Vec<String>> {
+    let PropertyValue::Array(items) = value else {
+        return Err(ObjectError::InvalidPropertyType);
+    };
+    if items.len() != number_of_states as usize {
+        return Err(ObjectError::InvalidValue(format!(
+            "State_Text must have {} entries",
+            number_of_states
+        )));
+    }
+    items
+        .into_iter()
+        .map(|item| match item {
+            PropertyValue::CharacterString(text) => Ok(text),
+            _ => Err(ObjectError::InvalidPropertyType),
+        })
+        .collect()
+}
+
 /// Multi-state Input object
 #[derive(Debug, Clone)]
 pub struct MultiStateInput {
@@ -63,10 +117,8 @@ pub struct MultiStateOutput {
     pub number_of_states: u32,
     /// State text array
     pub state_text: Vec<String>,
-    /// Priority array (16 levels)
-    pub priority_array: [Option<u32>; 16],
-    /// Relinquish default
-    pub relinquish_default: u32,
+    /// Priority array and relinquish default
+    pub commandable: Commandable<u32>,
 }
 
 /// Multi-state Value object
@@ -92,10 +144,15 @@ pub struct MultiStateValue {
     pub number_of_states: u32,
     /// State text array
     pub state_text: Vec<String>,
-    /// Priority array (16 levels)
-    pub priority_array: [Option<u32>; 16],
-    /// Relinquish default
-    pub relinquish_default: u32,
+    /// Whether Present_Value rejects writes unless out of service
+    pub read_only: bool,
+    /// Priority array and relinquish default, present only on commandable
+    /// instances
+    pub commandable: Option<Commandable<u32>>,
+    /// States that are considered alarm conditions
+    pub alarm_values: Option<Vec<u32>>,
+    /// States that are considered fault conditions
+    pub fault_values: Option<Vec<u32>>,
 }
 
 impl MultiStateInput {
@@ -153,6 +210,41 @@ impl MultiStateInput {
         self.state_text[(state - 1) as usize] = text;
         Ok(())
     }
+
+    /// Read State_Text with an optional array index
+    pub fn read_state_text(&self, array_index: Option<u32>) -> Result<PropertyValue> {
+        state_text_element(&self.state_text, array_index)
+    }
+
+    /// Update the present value from the physical input
+    ///
+    /// While the object is out of service the present value is decoupled from
+    /// the input, so the sample is ignored. A state outside 1..=Number_Of_States
+    /// flags the object with a multi-state fault.
+    pub fn update_from_input(&mut self, state: u32) {
+        if self.out_of_service {
+            return;
+        }
+        if state < 1 || state > self.number_of_states {
+            self.reliability = Reliability::MultiStateFault;
+            return;
+        }
+        if self.reliability == Reliability::MultiStateFault {
+            self.reliability = Reliability::NoFaultDetected;
+        }
+        self.present_value = state;
+    }
+
+    /// Current Status_Flags, combining the stored flags with the state derived
+    /// from Event_State, Reliability and Out_Of_Service
+    pub fn current_status_flags(&self) -> u8 {
+        current_status_flags(
+            self.status_flags,
+            self.event_state,
+            self.reliability,
+            self.out_of_service,
+        )
+    }
 }
 
 impl MultiStateOutput {
@@ -175,8 +267,7 @@ impl MultiStateOutput {
             out_of_service: false,
             number_of_states,
             state_text,
-            priority_array: [None; 16],
-            relinquish_default: 1,
+            commandable: Commandable::new(1),
         }
     }
 
@@ -189,43 +280,58 @@ impl MultiStateOutput {
         }
 
         if let Some(val) = value {
-            if val < 1 || val > self.number_of_states {
-                return Err(ObjectError::InvalidValue(format!(
-                    "Value must be between 1 and {}",
-                    self.number_of_states
-                )));
-            }
+            check_state(val, self.number_of_states)?;
         }
 
-        self.priority_array[(priority - 1) as usize] = value;
+        self.commandable.write(priority, value)?;
+        self.update_present_value();
+        Ok(())
+    }
+
+    /// Set the relinquish default (validates range)
+    pub fn set_relinquish_default(&mut self, value: u32) -> Result<()> {
+        check_state(value, self.number_of_states)?;
+        self.commandable.set_relinquish_default(value);
         self.update_present_value();
         Ok(())
     }
 
     /// Update present value based on priority array
     fn update_present_value(&mut self) {
-        // Find highest priority non-null value
-        if let Some(value) = self.priority_array.iter().flatten().next() {
-            self.present_value = *value;
-            return;
-        }
-        // If all priorities are null, use relinquish default
-        self.present_value = self.relinquish_default;
+        self.present_value = *self.commandable.current();
     }
 
     /// Get the effective priority level for current present value
     pub fn get_effective_priority(&self) -> Option<u8> {
-        for (i, priority_value) in self.priority_array.iter().enumerate() {
-            if priority_value.is_some() {
-                return Some((i + 1) as u8);
-            }
-        }
-        None
+        self.commandable.active_priority()
+    }
+
+    /// Get the current state text
+    pub fn get_state_text(&self) -> Option<&str> {
+        self.state_text
+            .get(self.present_value.checked_sub(1)? as usize)
+            .map(String::as_str)
+    }
+
+    /// Read State_Text with an optional array index
+    pub fn read_state_text(&self, array_index: Option<u32>) -> Result<PropertyValue> {
+        state_text_element(&self.state_text, array_index)
+    }
+
+    /// Current Status_Flags, combining the stored flags with the state derived
+    /// from Event_State, Reliability and Out_Of_Service
+    pub fn current_status_flags(&self) -> u8 {
+        current_status_flags(
+            self.status_flags,
+            self.event_state,
+            self.reliability,
+            self.out_of_service,
+        )
     }
 }
 
 impl MultiStateValue {
-    /// Create a new Multi-state Value object
+    /// Create a new writable, non-commandable Multi-state Value object
     pub fn new(instance: u32, object_name: String, number_of_states: u32) -> Self {
         let mut state_text = Vec::with_capacity(number_of_states as usize);
         for i in 1..=number_of_states {
@@ -243,11 +349,39 @@ impl MultiStateValue {
             out_of_service: false,
             number_of_states,
             state_text,
-            priority_array: [None; 16],
-            relinquish_default: 1,
+            read_only: false,
+            commandable: None,
+            alarm_values: None,
+            fault_values: None,
         }
     }
 
+    /// Create a new commandable Multi-state Value object with a priority array
+    pub fn new_commandable(
+        instance: u32,
+        object_name: String,
+        number_of_states: u32,
+        relinquish_default: u32,
+    ) -> Result<Self> {
+        check_state(relinquish_default, number_of_states)?;
+        let mut msv = Self::new(instance, object_name, number_of_states);
+        msv.commandable = Some(Commandable::new(relinquish_default));
+        msv.present_value = relinquish_default;
+        Ok(msv)
+    }
+
+    /// Whether Present_Value writes are arbitrated through a priority array
+    pub fn is_commandable(&self) -> bool {
+        self.commandable.is_some()
+    }
+
+    /// Set the present value directly (validates range)
+    pub fn set_present_value(&mut self, value: u32) -> Result<()> {
+        check_state(value, self.number_of_states)?;
+        self.present_value = value;
+        Ok(())
+    }
+
     /// Write to priority array at specified priority level (1-16)
     pub fn write_priority(&mut self, priority: u8, value: Option<u32>) -> Result<()> {
         if !(1..=16).contains(&priority) {
@@ -257,31 +391,106 @@ impl MultiStateValue {
         }
 
         if let Some(val) = value {
-            if val < 1 || val > self.number_of_states {
-                return Err(ObjectError::InvalidValue(format!(
-                    "Value must be between 1 and {}",
-                    self.number_of_states
-                )));
-            }
+            check_state(val, self.number_of_states)?;
         }
 
-        self.priority_array[(priority - 1) as usize] = value;
+        let Some(commandable) = self.commandable.as_mut() else {
+            return Err(ObjectError::InvalidConfiguration(
+                "Multi-state Value is not commandable".to_string(),
+            ));
+        };
+        commandable.write(priority, value)?;
         self.update_present_value();
         Ok(())
     }
 
     /// Update present value based on priority array
     fn update_present_value(&mut self) {
-        // Find highest priority non-null value
-        if let Some(value) = self.priority_array.iter().flatten().next() {
-            self.present_value = *value;
-            return;
+        if let Some(commandable) = self.commandable.as_ref() {
+            self.present_value = *commandable.current();
         }
-        // If all priorities are null, use relinquish default
-        self.present_value = self.relinquish_default;
+    }
+
+    /// Get the current state text
+    pub fn get_state_text(&self) -> Option<&str> {
+        self.state_text
+            .get(self.present_value.checked_sub(1)? as usize)
+            .map(String::as_str)
+    }
+
+    /// Read State_Text with an optional array index
+    pub fn read_state_text(&self, array_index: Option<u32>) -> Result<PropertyValue> {
+        state_text_element(&self.state_text, array_index)
+    }
+
+    /// Set the Alarm_Values list (each state must be within 1..=Number_Of_States)
+    pub fn set_alarm_values(&mut self, values: Vec<u32>) -> Result<()> {
+        for &value in &values {
+            check_state(value, self.number_of_states)?;
+        }
+        self.alarm_values = Some(values);
+        Ok(())
+    }
+
+    /// Set the Fault_Values list (each state must be within 1..=Number_Of_States)
+    pub fn set_fault_values(&mut self, values: Vec<u32>) -> Result<()> {
+        for &value in &values {
+            check_state(value, self.number_of_states)?;
+        }
+        self.fault_values = Some(values);
+        Ok(())
+    }
+
+    /// Whether the present value is listed in Alarm_Values
+    pub fn is_alarm_state(&self) -> bool {
+        self.alarm_values
+            .as_ref()
+            .is_some_and(|values| values.contains(&self.present_value))
+    }
+
+    /// Whether the present value is listed in Fault_Values
+    pub fn is_fault_state(&self) -> bool {
+        self.fault_values
+            .as_ref()
+            .is_some_and(|values| values.contains(&self.present_value))
+    }
+
+    /// Current Status_Flags, combining the stored flags with the state derived
+    /// from Event_State, Reliability and Out_Of_Service
+    pub fn current_status_flags(&self) -> u8 {
+        current_status_flags(
+            self.status_flags,
+            self.event_state,
+            self.reliability,
+            self.out_of_service,
+        )
     }
 }
 
+/// Encode a list of states as a BACnet list of unsigned values
+fn state_list(values: &[u32]) -> PropertyValue {
+    PropertyValue::List(
+        values
+            .iter()
+            .map(|&v| PropertyValue::UnsignedInteger(v))
+            .collect(),
+    )
+}
+
+/// Parse a BACnet list of unsigned values written by a client
+fn parse_state_list(value: PropertyValue) -> Result<Vec<u32>> {
+    let (PropertyValue::List(items) | PropertyValue::Array(items)) = value else {
+        return Err(ObjectError::InvalidPropertyType);
+    };
+    items
+        .into_iter()
+        .map(|item| match item {
+            PropertyValue::UnsignedInteger(v) => Ok(v),
+            _ => Err(ObjectError::InvalidPropertyType),
+        })
+        .collect()
+}
+
 impl BacnetObject for MultiStateInput {
     fn identifier(&self) -> ObjectIdentifier {
         self.identifier
@@ -295,13 +504,32 @@ impl BacnetObject for MultiStateInput {
             PropertyIdentifier::ObjectName => {
                 Ok(PropertyValue::CharacterString(self.object_name.clone()))
             }
-            PropertyIdentifier::ObjectType => Ok(PropertyValue::Enumerated(
-                ObjectType::MultiStateInput as u32,
-            )),
+            PropertyIdentifier::ObjectType => Ok(PropertyValue::Enumerated(u32::from(
+                ObjectType::MultiStateInput,
+            ))),
             PropertyIdentifier::PresentValue => {
                 Ok(PropertyValue::UnsignedInteger(self.present_value))
             }
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::DeviceType => {
+                Ok(PropertyValue::CharacterString(self.device_type.clone()))
+            }
+            PropertyIdentifier::StatusFlags => {
+                Ok(status_flags_bit_string(self.current_status_flags()))
+            }
+            PropertyIdentifier::EventState => {
+                Ok(PropertyValue::Enumerated(self.event_state as u32))
+            }
+            PropertyIdentifier::Reliability => {
+                Ok(PropertyValue::Enumerated(self.reliability as u32))
+            }
             PropertyIdentifier::OutOfService => Ok(PropertyValue::Boolean(self.out_of_service)),
+            PropertyIdentifier::NumberOfStates => {
+                Ok(PropertyValue::UnsignedInteger(self.number_of_states))
+            }
+            PropertyIdentifier::StateText => self.read_state_text(None),
             _ => Err(ObjectError::UnknownProperty),
         }
     }
@@ -316,6 +544,36 @@ impl BacnetObject for MultiStateInput {
                     Err(ObjectError::InvalidPropertyType)
                 }
             }
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::PresentValue => {
+                // Present_Value is only writable while decoupled from the input
+                if !self.out_of_service {
+                    return Err(ObjectError::WriteAccessDenied);
+                }
+                if let PropertyValue::UnsignedInteger(val) = value {
+                    self.set_present_value(val)
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Reliability => {
+                if !self.out_of_service {
+                    return Err(ObjectError::WriteAccessDenied);
+                }
+                if let PropertyValue::Enumerated(reliability) = value {
+                    self.reliability = Reliability::try_from(reliability)?;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
             PropertyIdentifier::OutOfService => {
                 if let PropertyValue::Boolean(oos) = value {
                     self.out_of_service = oos;
@@ -324,15 +582,25 @@ impl BacnetObject for MultiStateInput {
                     Err(ObjectError::InvalidPropertyType)
                 }
             }
+            PropertyIdentifier::StateText => {
+                self.state_text = parse_state_text(value, self.number_of_states)?;
+                Ok(())
+            }
             _ => Err(ObjectError::PropertyNotWritable),
         }
     }
 
     fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
-        matches!(
-            property,
-            PropertyIdentifier::ObjectName | PropertyIdentifier::OutOfService
-        )
+        match property {
+            PropertyIdentifier::ObjectName
+            | PropertyIdentifier::Description
+            | PropertyIdentifier::OutOfService
+            | PropertyIdentifier::StateText => true,
+            PropertyIdentifier::PresentValue | PropertyIdentifier::Reliability => {
+                self.out_of_service
+            }
+            _ => false,
+        }
     }
 
     fn property_list(&self) -> Vec<PropertyIdentifier> {
@@ -341,7 +609,14 @@ impl BacnetObject for MultiStateInput {
             PropertyIdentifier::ObjectName,
             PropertyIdentifier::ObjectType,
             PropertyIdentifier::PresentValue,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::DeviceType,
+            PropertyIdentifier::StatusFlags,
+            PropertyIdentifier::EventState,
+            PropertyIdentifier::Reliability,
             PropertyIdentifier::OutOfService,
+            PropertyIdentifier::NumberOfStates,
+            PropertyIdentifier::StateText,
         ]
     }
 }
@@ -359,29 +634,52 @@ impl BacnetObject for MultiStateOutput {
             PropertyIdentifier::ObjectName => {
                 Ok(PropertyValue::CharacterString(self.object_name.clone()))
             }
-            PropertyIdentifier::ObjectType => Ok(PropertyValue::Enumerated(
-                ObjectType::MultiStateOutput as u32,
-            )),
+            PropertyIdentifier::ObjectType => Ok(PropertyValue::Enumerated(u32::from(
+                ObjectType::MultiStateOutput,
+            ))),
             PropertyIdentifier::PresentValue => {
                 Ok(PropertyValue::UnsignedInteger(self.present_value))
             }
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::DeviceType => {
+                Ok(PropertyValue::CharacterString(self.device_type.clone()))
+            }
+            PropertyIdentifier::StatusFlags => {
+                Ok(status_flags_bit_string(self.current_status_flags()))
+            }
+            PropertyIdentifier::EventState => {
+                Ok(PropertyValue::Enumerated(self.event_state as u32))
+            }
+            PropertyIdentifier::Reliability => {
+                Ok(PropertyValue::Enumerated(self.reliability as u32))
+            }
             PropertyIdentifier::OutOfService => Ok(PropertyValue::Boolean(self.out_of_service)),
-            PropertyIdentifier::PriorityArray => {
-                let array: Vec<PropertyValue> = self
-                    .priority_array
-                    .iter()
-                    .map(|&v| match v {
-                        Some(val) => PropertyValue::UnsignedInteger(val),
-                        None => PropertyValue::Null,
-                    })
-                    .collect();
-                Ok(PropertyValue::Array(array))
+            PropertyIdentifier::NumberOfStates => {
+                Ok(PropertyValue::UnsignedInteger(self.number_of_states))
             }
+            PropertyIdentifier::StateText => self.read_state_text(None),
+            PropertyIdentifier::PriorityArray => Ok(self
+                .commandable
+                .priority_array_value(|&value| PropertyValue::UnsignedInteger(value))),
+            PropertyIdentifier::RelinquishDefault => Ok(PropertyValue::UnsignedInteger(
+                *self.commandable.relinquish_default(),
+            )),
             _ => Err(ObjectError::UnknownProperty),
         }
     }
 
     fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        self.set_property_with_priority(property, value, DEFAULT_COMMAND_PRIORITY)
+    }
+
+    fn set_property_with_priority(
+        &mut self,
+        property: PropertyIdentifier,
+        value: PropertyValue,
+        priority: u8,
+    ) -> Result<()> {
         match property {
             PropertyIdentifier::ObjectName => {
                 if let PropertyValue::CharacterString(name) = value {
@@ -391,10 +689,23 @@ impl BacnetObject for MultiStateOutput {
                     Err(ObjectError::InvalidPropertyType)
                 }
             }
-            PropertyIdentifier::PresentValue => {
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::PresentValue => match value {
+                PropertyValue::UnsignedInteger(val) => self.write_priority(priority, Some(val)),
+                // Writing NULL relinquishes the command at this priority
+                PropertyValue::Null => self.write_priority(priority, None),
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            PropertyIdentifier::RelinquishDefault => {
                 if let PropertyValue::UnsignedInteger(val) = value {
-                    // Write to priority 8 (manual operator) by default
-                    self.write_priority(8, Some(val))
+                    self.set_relinquish_default(val)
                 } else {
                     Err(ObjectError::InvalidPropertyType)
                 }
@@ -407,6 +718,10 @@ impl BacnetObject for MultiStateOutput {
                     Err(ObjectError::InvalidPropertyType)
                 }
             }
+            PropertyIdentifier::StateText => {
+                self.state_text = parse_state_text(value, self.number_of_states)?;
+                Ok(())
+            }
             _ => Err(ObjectError::PropertyNotWritable),
         }
     }
@@ -415,8 +730,11 @@ impl BacnetObject for MultiStateOutput {
         matches!(
             property,
             PropertyIdentifier::ObjectName
+                | PropertyIdentifier::Description
                 | PropertyIdentifier::PresentValue
+                | PropertyIdentifier::RelinquishDefault
                 | PropertyIdentifier::OutOfService
+                | PropertyIdentifier::StateText
         )
     }
 
@@ -426,8 +744,16 @@ impl BacnetObject for MultiStateOutput {
             PropertyIdentifier::ObjectName,
             PropertyIdentifier::ObjectType,
             PropertyIdentifier::PresentValue,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::DeviceType,
+            PropertyIdentifier::StatusFlags,
+            PropertyIdentifier::EventState,
+            PropertyIdentifier::Reliability,
             PropertyIdentifier::OutOfService,
+            PropertyIdentifier::NumberOfStates,
+            PropertyIdentifier::StateText,
             PropertyIdentifier::PriorityArray,
+            PropertyIdentifier::RelinquishDefault,
         ]
     }
 }
@@ -445,29 +771,67 @@ impl BacnetObject for MultiStateValue {
             PropertyIdentifier::ObjectName => {
                 Ok(PropertyValue::CharacterString(self.object_name.clone()))
             }
-            PropertyIdentifier::ObjectType => Ok(PropertyValue::Enumerated(
-                ObjectType::MultiStateValue as u32,
-            )),
+            PropertyIdentifier::ObjectType => Ok(PropertyValue::Enumerated(u32::from(
+                ObjectType::MultiStateValue,
+            ))),
             PropertyIdentifier::PresentValue => {
                 Ok(PropertyValue::UnsignedInteger(self.present_value))
             }
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::StatusFlags => {
+                Ok(status_flags_bit_string(self.current_status_flags()))
+            }
+            PropertyIdentifier::EventState => {
+                Ok(PropertyValue::Enumerated(self.event_state as u32))
+            }
+            PropertyIdentifier::Reliability => {
+                Ok(PropertyValue::Enumerated(self.reliability as u32))
+            }
             PropertyIdentifier::OutOfService => Ok(PropertyValue::Boolean(self.out_of_service)),
-            PropertyIdentifier::PriorityArray => {
-                let array: Vec<PropertyValue> = self
-                    .priority_array
-                    .iter()
-                    .map(|&v| match v {
-                        Some(val) => PropertyValue::UnsignedInteger(val),
-                        None => PropertyValue::Null,
-                    })
-                    .collect();
-                Ok(PropertyValue::Array(array))
+            PropertyIdentifier::NumberOfStates => {
+                Ok(PropertyValue::UnsignedInteger(self.number_of_states))
             }
+            PropertyIdentifier::StateText => self.read_state_text(None),
+            PropertyIdentifier::PriorityArray => self
+                .commandable
+                .as_ref()
+                .map(|commandable| {
+                    commandable.priority_array_value(|&value| PropertyValue::UnsignedInteger(value))
+                })
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::RelinquishDefault => self
+                .commandable
+                .as_ref()
+                .map(|commandable| {
+                    PropertyValue::UnsignedInteger(*commandable.relinquish_default())
+                })
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::AlarmValues => self
+                .alarm_values
+                .as_deref()
+                .map(state_list)
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::FaultValues => self
+                .fault_values
+                .as_deref()
+                .map(state_list)
+                .ok_or(ObjectError::UnknownProperty),
             _ => Err(ObjectError::UnknownProperty),
         }
     }
 
     fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        self.set_property_with_priority(property, value, DEFAULT_COMMAND_PRIORITY)
+    }
+
+    fn set_property_with_priority(
+        &mut self,
+        property: PropertyIdentifier,
+        value: PropertyValue,
+        priority: u8,
+    ) -> Result<()> {
         match property {
             PropertyIdentifier::ObjectName => {
                 if let PropertyValue::CharacterString(name) = value {
@@ -477,10 +841,38 @@ impl BacnetObject for MultiStateValue {
                     Err(ObjectError::InvalidPropertyType)
                 }
             }
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
             PropertyIdentifier::PresentValue => {
+                if self.read_only && !self.out_of_service {
+                    return Err(ObjectError::WriteAccessDenied);
+                }
+                match value {
+                    PropertyValue::UnsignedInteger(val) if self.is_commandable() => {
+                        self.write_priority(priority, Some(val))
+                    }
+                    PropertyValue::UnsignedInteger(val) => self.set_present_value(val),
+                    // Writing NULL relinquishes the command at this priority
+                    PropertyValue::Null if self.is_commandable() => {
+                        self.write_priority(priority, None)
+                    }
+                    _ => Err(ObjectError::InvalidPropertyType),
+                }
+            }
+            PropertyIdentifier::RelinquishDefault if self.is_commandable() => {
                 if let PropertyValue::UnsignedInteger(val) = value {
-                    // Write to priority 8 (manual operator) by default
-                    self.write_priority(8, Some(val))
+                    check_state(val, self.number_of_states)?;
+                    if let Some(commandable) = self.commandable.as_mut() {
+                        commandable.set_relinquish_default(val);
+                    }
+                    self.update_present_value();
+                    Ok(())
                 } else {
                     Err(ObjectError::InvalidPropertyType)
                 }
@@ -493,28 +885,59 @@ impl BacnetObject for MultiStateValue {
                     Err(ObjectError::InvalidPropertyType)
                 }
             }
+            PropertyIdentifier::StateText => {
+                self.state_text = parse_state_text(value, self.number_of_states)?;
+                Ok(())
+            }
+            PropertyIdentifier::AlarmValues if self.alarm_values.is_some() => {
+                self.set_alarm_values(parse_state_list(value)?)
+            }
+            PropertyIdentifier::FaultValues if self.fault_values.is_some() => {
+                self.set_fault_values(parse_state_list(value)?)
+            }
             _ => Err(ObjectError::PropertyNotWritable),
         }
     }
 
     fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
-        matches!(
-            property,
+        match property {
             PropertyIdentifier::ObjectName
-                | PropertyIdentifier::PresentValue
-                | PropertyIdentifier::OutOfService
-        )
+            | PropertyIdentifier::Description
+            | PropertyIdentifier::OutOfService
+            | PropertyIdentifier::StateText => true,
+            PropertyIdentifier::PresentValue => !self.read_only || self.out_of_service,
+            PropertyIdentifier::RelinquishDefault => self.is_commandable(),
+            PropertyIdentifier::AlarmValues => self.alarm_values.is_some(),
+            PropertyIdentifier::FaultValues => self.fault_values.is_some(),
+            _ => false,
+        }
     }
 
     fn property_list(&self) -> Vec<PropertyIdentifier> {
-        vec![
+        let mut properties = vec![
             PropertyIdentifier::ObjectIdentifier,
             PropertyIdentifier::ObjectName,
             PropertyIdentifier::ObjectType,
             PropertyIdentifier::PresentValue,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::StatusFlags,
+            PropertyIdentifier::EventState,
+            PropertyIdentifier::Reliability,
             PropertyIdentifier::OutOfService,
-            PropertyIdentifier::PriorityArray,
-        ]
+            PropertyIdentifier::NumberOfStates,
+            PropertyIdentifier::StateText,
+        ];
+        if self.is_commandable() {
+            properties.push(PropertyIdentifier::PriorityArray);
+            properties.push(PropertyIdentifier::RelinquishDefault);
+        }
+        if self.alarm_values.is_some() {
+            properties.push(PropertyIdentifier::AlarmValues);
+        }
+        if self.fault_values.is_some() {
+            properties.push(PropertyIdentifier::FaultValues);
+        }
+        properties
     }
 }
 
@@ -550,6 +973,65 @@ mod tests {
         assert!(msi.set_present_value(4).is_err());
     }
 
+    #[test]
+    fn test_multistate_input_state_text_index() {
+        let mut msi = MultiStateInput::new(2, "Fan Speed".to_string(), 3);
+        msi.set_state_text(2, "LOW".to_string()).unwrap();
+
+        assert!(matches!(
+            msi.read_state_text(Some(0)),
+            Ok(PropertyValue::UnsignedInteger(3))
+        ));
+        assert!(matches!(
+            msi.read_state_text(Some(2)),
+            Ok(PropertyValue::CharacterString(ref t)) if t == "LOW"
+        ));
+        assert!(matches!(
+            msi.read_state_text(Some(4)),
+            Err(ObjectError::InvalidArrayIndex)
+        ));
+        if let Ok(PropertyValue::Array(items)) = msi.get_property(PropertyIdentifier::StateText) {
+            assert_eq!(items.len(), 3);
+        } else {
+            panic!("Expected Array");
+        }
+    }
+
+    #[test]
+    fn test_multistate_input_reliability() {
+        let mut msi = MultiStateInput::new(3, "Damper Mode".to_string(), 3);
+
+        msi.update_from_input(2);
+        assert_eq!(msi.present_value, 2);
+
+        // Out-of-range inputs leave the value alone and flag a fault
+        msi.update_from_input(7);
+        assert_eq!(msi.present_value, 2);
+        assert_eq!(msi.reliability, Reliability::MultiStateFault);
+        msi.update_from_input(3);
+        assert_eq!(msi.reliability, Reliability::NoFaultDetected);
+
+        assert!(msi
+            .set_property(
+                PropertyIdentifier::PresentValue,
+                PropertyValue::UnsignedInteger(1)
+            )
+            .is_err());
+        msi.set_property(
+            PropertyIdentifier::OutOfService,
+            PropertyValue::Boolean(true),
+        )
+        .unwrap();
+        msi.set_property(
+            PropertyIdentifier::PresentValue,
+            PropertyValue::UnsignedInteger(1),
+        )
+        .unwrap();
+        assert_eq!(msi.present_value, 1);
+        msi.update_from_input(3);
+        assert_eq!(msi.present_value, 1);
+    }
+
     #[test]
     fn test_multistate_output_priority() {
         let mut mso = MultiStateOutput::new(1, "Sequence Control".to_string(), 4);
@@ -572,6 +1054,47 @@ mod tests {
         assert_eq!(mso.present_value, 3); // Back to priority 8 value
     }
 
+    #[test]
+    fn test_multistate_output_write_validation() {
+        let mut mso = MultiStateOutput::new(2, "Fan Speed Command".to_string(), 3);
+
+        for invalid in [0, 4] {
+            assert!(matches!(
+                mso.set_property_with_priority(
+                    PropertyIdentifier::PresentValue,
+                    PropertyValue::UnsignedInteger(invalid),
+                    8
+                ),
+                Err(ObjectError::InvalidValue(_))
+            ));
+        }
+        assert!(mso
+            .set_property(
+                PropertyIdentifier::RelinquishDefault,
+                PropertyValue::UnsignedInteger(9)
+            )
+            .is_err());
+
+        mso.set_property(
+            PropertyIdentifier::RelinquishDefault,
+            PropertyValue::UnsignedInteger(2),
+        )
+        .unwrap();
+        assert_eq!(mso.present_value, 2);
+
+        mso.set_property_with_priority(
+            PropertyIdentifier::PresentValue,
+            PropertyValue::UnsignedInteger(3),
+            8,
+        )
+        .unwrap();
+        assert_eq!(mso.present_value, 3);
+        mso.set_property_with_priority(PropertyIdentifier::PresentValue, PropertyValue::Null, 8)
+            .unwrap();
+        assert_eq!(mso.present_value, 2);
+        assert_eq!(mso.get_effective_priority(), None);
+    }
+
     #[test]
     fn test_multistate_properties() {
         let mut msv = MultiStateValue::new(1, "Operating Mode".to_string(), 4);
@@ -592,4 +1115,68 @@ mod tests {
         .unwrap();
         assert_eq!(msv.present_value, 3);
     }
+
+    #[test]
+    fn test_multistate_value_configurations() {
+        // Read-only values only accept writes while out of service
+        let mut msv = MultiStateValue::new(2, "Occupancy Mode".to_string(), 3);
+        msv.read_only = true;
+        assert!(matches!(
+            msv.set_property(
+                PropertyIdentifier::PresentValue,
+                PropertyValue::UnsignedInteger(2)
+            ),
+            Err(ObjectError::WriteAccessDenied)
+        ));
+        msv.out_of_service = true;
+        msv.set_property(
+            PropertyIdentifier::PresentValue,
+            PropertyValue::UnsignedInteger(2),
+        )
+        .unwrap();
+        assert_eq!(msv.present_value, 2);
+
+        // Commandable values arbitrate through the priority array
+        let mut cmd = MultiStateValue::new_commandable(3, "Season".to_string(), 4, 1).unwrap();
+        cmd.set_property_with_priority(
+            PropertyIdentifier::PresentValue,
+            PropertyValue::UnsignedInteger(4),
+            9,
+        )
+        .unwrap();
+        assert_eq!(cmd.present_value, 4);
+        cmd.set_property_with_priority(PropertyIdentifier::PresentValue, PropertyValue::Null, 9)
+            .unwrap();
+        assert_eq!(cmd.present_value, 1);
+        assert!(MultiStateValue::new_commandable(4, "Bad".to_string(), 2, 3).is_err());
+    }
+
+    #[test]
+    fn test_multistate_value_alarm_fault_values() {
+        let mut msv = MultiStateValue::new(5, "Chiller State".to_string(), 4);
+        assert!(msv.get_property(PropertyIdentifier::AlarmValues).is_err());
+
+        msv.set_alarm_values(vec![3]).unwrap();
+        msv.set_fault_values(vec![4]).unwrap();
+        assert!(msv.set_alarm_values(vec![5]).is_err());
+        assert!(msv
+            .property_list()
+            .contains(&PropertyIdentifier::FaultValues));
+
+        msv.set_present_value(3).unwrap();
+        assert!(msv.is_alarm_state());
+        assert!(!msv.is_fault_state());
+
+        msv.set_property(
+            PropertyIdentifier::FaultValues,
+            PropertyValue::List(vec![PropertyValue::UnsignedInteger(3)]),
+        )
+        .unwrap();
+        assert!(msv.is_fault_state());
+        if let Ok(PropertyValue::List(items)) = msv.get_property(PropertyIdentifier::AlarmValues) {
+            assert!(matches!(items[..], [PropertyValue::UnsignedInteger(3)]));
+        } else {
+            panic!("Expected List");
+        }
+    }
 }
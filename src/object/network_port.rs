@@ -0,0 +1,1594 @@
+//! Network Port Object Type Implementation
+//!
+//! This module implements the Network Port object type as defined in ASHRAE 135,
+//! which exposes the configuration of one of the device's datalinks: BACnet/IP
+//! addressing and BBMD tables, MS/TP station settings, or BACnet/SC hub
+//! connection parameters.
+//!
+//! Writes to configuration properties do not take effect immediately. They are
+//! held as pending values, which are what the properties read back, and
+//! Changes_Pending becomes TRUE. The pending values become active when the
+//! device is reinitialized with ACTIVATE_CHANGES, which calls
+//! [`BacnetObject::activate_changes`], or are dropped by writing
+//! DISCARD_CHANGES to Command. The application picks up newly activated
+//! settings with [`NetworkPort::take_activated_config`] and reconfigures the
+//! datalink to match.
+
+use crate::object::{
+    current_status_flags, status_flags_bit_string, BacnetObject, EventState, ObjectError,
+    ObjectIdentifier, ObjectType, PropertyIdentifier, PropertyValue, Reliability, Result,
+};
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+/// Datalink type of a network port (BACnetNetworkType)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum NetworkType {
+    Ethernet = 0,
+    Arcnet = 1,
+    Mstp = 2,
+    Ptp = 3,
+    Lontalk = 4,
+    Ipv4 = 5,
+    Zigbee = 6,
+    Virtual = 7,
+    Ipv6 = 9,
+    Serial = 10,
+    SecureConnect = 11,
+}
+
+/// Layer a network port operates at (BACnetProtocolLevel)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum ProtocolLevel {
+    Physical = 0,
+    Protocol = 1,
+    BacnetApplication = 2,
+    NonBacnetApplication = 3,
+}
+
+/// How the network number was obtained (BACnetNetworkNumberQuality)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum NetworkNumberQuality {
+    Unknown = 0,
+    Learned = 1,
+    LearnedConfigured = 2,
+    Configured = 3,
+}
+
+/// Network port command (BACnetNetworkPortCommand)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum NetworkPortCommand {
+    Idle = 0,
+    DiscardChanges = 1,
+    RenewFdRegistration = 2,
+    RestartSlaveDiscovery = 3,
+    RenewDhcp = 4,
+    RestartAutonegotiation = 5,
+    Disconnect = 6,
+    RestartPort = 7,
+}
+
+impl TryFrom<u32> for NetworkPortCommand {
+    type Error = ObjectError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            0 => Ok(NetworkPortCommand::Idle),
+            1 => Ok(NetworkPortCommand::DiscardChanges),
+            2 => Ok(NetworkPortCommand::RenewFdRegistration),
+            3 => Ok(NetworkPortCommand::RestartSlaveDiscovery),
+            4 => Ok(NetworkPortCommand::RenewDhcp),
+            5 => Ok(NetworkPortCommand::RestartAutonegotiation),
+            6 => Ok(NetworkPortCommand::Disconnect),
+            7 => Ok(NetworkPortCommand::RestartPort),
+            _ => Err(ObjectError::InvalidValue(format!(
+                "Invalid network port command: {}",
+                value
+            ))),
+        }
+    }
+}
+
+/// BACnet/IP operating mode (BACnetIPMode)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum BacnetIpMode {
+    Normal = 0,
+    Foreign = 1,
+    Bbmd = 2,
+}
+
+impl TryFrom<u32> for BacnetIpMode {
+    type Error = ObjectError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            0 => Ok(BacnetIpMode::Normal),
+            1 => Ok(BacnetIpMode::Foreign),
+            2 => Ok(BacnetIpMode::Bbmd),
+            _ => Err(ObjectError::InvalidValue(format!(
+                "Invalid BACnet/IP mode: {}",
+                value
+            ))),
+        }
+    }
+}
+
+/// An IPv4 address and UDP port (BACnetHostNPort)
+///
+/// Encoded as `List[OctetString(address), Unsigned(port)]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HostNPort {
+    /// IPv4 address
+    pub address: [u8; 4],
+    /// UDP port
+    pub port: u16,
+}
+
+impl HostNPort {
+    /// Create a new host and port
+    pub fn new(address: [u8; 4], port: u16) -> Self {
+        Self { address, port }
+    }
+
+    /// Encode as a property value
+    pub fn to_property_value(&self) -> PropertyValue {
+        PropertyValue::List(vec![
+            PropertyValue::OctetString(self.address.to_vec()),
+            PropertyValue::UnsignedInteger(self.port as u32),
+        ])
+    }
+
+    /// Decode from a property value
+    pub fn from_property_value(value: &PropertyValue) -> Result<Self> {
+        match value {
+            PropertyValue::List(items) => match items.as_slice() {
+                [address, PropertyValue::UnsignedInteger(port)] => Ok(Self {
+                    address: ipv4_from_value(address)?,
+                    port: udp_port(*port)?,
+                }),
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            _ => Err(ObjectError::InvalidPropertyType),
+        }
+    }
+}
+
+/// A Broadcast Distribution Table entry (BACnetBDTEntry)
+///
+/// Encoded as `List[OctetString(address), Unsigned(port), OctetString(mask)]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BdtTableEntry {
+    /// Peer BBMD address
+    pub bbmd_address: HostNPort,
+    /// Broadcast distribution mask
+    pub broadcast_mask: [u8; 4],
+}
+
+impl BdtTableEntry {
+    /// Encode as a property value
+    pub fn to_property_value(&self) -> PropertyValue {
+        PropertyValue::List(vec![
+            PropertyValue::OctetString(self.bbmd_address.address.to_vec()),
+            PropertyValue::UnsignedInteger(self.bbmd_address.port as u32),
+            PropertyValue::OctetString(self.broadcast_mask.to_vec()),
+        ])
+    }
+
+    /// Decode from a property value
+    pub fn from_property_value(value: &PropertyValue) -> Result<Self> {
+        match value {
+            PropertyValue::List(items) => match items.as_slice() {
+                [address, PropertyValue::UnsignedInteger(port), mask] => Ok(Self {
+                    bbmd_address: HostNPort::new(ipv4_from_value(address)?, udp_port(*port)?),
+                    broadcast_mask: ipv4_from_value(mask)?,
+                }),
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            _ => Err(ObjectError::InvalidPropertyType),
+        }
+    }
+}
+
+/// A Foreign Device Table entry (BACnetFDTEntry), maintained by the datalink
+///
+/// Encoded as `List[OctetString(address), Unsigned(port), Unsigned(ttl),
+/// Unsigned(remaining)]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FdtTableEntry {
+    /// Registered foreign device
+    pub address: HostNPort,
+    /// Time-to-live the device registered with, in seconds
+    pub time_to_live: u16,
+    /// Seconds remaining before the registration expires
+    pub remaining_time_to_live: u16,
+}
+
+impl FdtTableEntry {
+    /// Encode as a property value
+    pub fn to_property_value(&self) -> PropertyValue {
+        PropertyValue::List(vec![
+            PropertyValue::OctetString(self.address.address.to_vec()),
+            PropertyValue::UnsignedInteger(self.address.port as u32),
+            PropertyValue::UnsignedInteger(self.time_to_live as u32),
+            PropertyValue::UnsignedInteger(self.remaining_time_to_live as u32),
+        ])
+    }
+}
+
+/// An MS/TP slave address binding (BACnetAddressBinding)
+///
+/// Encoded as `List[ObjectIdentifier(device), Unsigned(network),
+/// OctetString(mac)]`, with network 0 for the port's own network.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlaveBinding {
+    /// Device object of the slave
+    pub device_identifier: ObjectIdentifier,
+    /// Station address of the slave
+    pub mac_address: u8,
+}
+
+impl SlaveBinding {
+    /// Encode as a property value
+    pub fn to_property_value(&self) -> PropertyValue {
+        PropertyValue::List(vec![
+            PropertyValue::ObjectIdentifier(self.device_identifier),
+            PropertyValue::UnsignedInteger(0),
+            PropertyValue::OctetString(vec![self.mac_address]),
+        ])
+    }
+
+    /// Decode from a property value
+    pub fn from_property_value(value: &PropertyValue) -> Result<Self> {
+        match value {
+            PropertyValue::List(items) => match items.as_slice() {
+                [PropertyValue::ObjectIdentifier(device), PropertyValue::UnsignedInteger(0), PropertyValue::OctetString(mac)] => {
+                    match mac.as_slice() {
+                        [station] if *station < 255 => Ok(Self {
+                            device_identifier: *device,
+                            mac_address: *station,
+                        }),
+                        _ => Err(ObjectError::InvalidValue(
+                            "MS/TP slave address must be one octet 0-254".to_string(),
+                        )),
+                    }
+                }
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            _ => Err(ObjectError::InvalidPropertyType),
+        }
+    }
+}
+
+/// BACnet/IP port settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct IpPortSettings {
+    /// IP address
+    pub ip_address: [u8; 4],
+    /// Subnet mask
+    pub subnet_mask: [u8; 4],
+    /// Default gateway
+    pub default_gateway: [u8; 4],
+    /// DNS servers
+    pub dns_servers: Vec<[u8; 4]>,
+    /// UDP port
+    pub udp_port: u16,
+    /// Whether the address is obtained by DHCP
+    pub dhcp_enable: bool,
+    /// Operating mode
+    pub mode: BacnetIpMode,
+    /// Whether foreign device registrations are accepted (BBMD mode)
+    pub accept_fd_registrations: bool,
+    /// Broadcast Distribution Table (BBMD mode)
+    pub broadcast_distribution_table: Vec<BdtTableEntry>,
+    /// BBMD to register with (foreign mode)
+    pub fd_bbmd_address: Option<HostNPort>,
+    /// Registration time-to-live in seconds (foreign mode)
+    pub fd_subscription_lifetime: u16,
+    /// Whether the device is behind a NAT router
+    pub nat_traversal: bool,
+    /// Public address of the NAT router, used when NAT traversal is on
+    pub global_address: Option<HostNPort>,
+}
+
+impl Default for IpPortSettings {
+    fn default() -> Self {
+        Self {
+            ip_address: [0, 0, 0, 0],
+            subnet_mask: [255, 255, 255, 0],
+            default_gateway: [0, 0, 0, 0],
+            dns_servers: Vec::new(),
+            udp_port: 0xBAC0,
+            dhcp_enable: false,
+            mode: BacnetIpMode::Normal,
+            accept_fd_registrations: false,
+            broadcast_distribution_table: Vec::new(),
+            fd_bbmd_address: None,
+            fd_subscription_lifetime: 0,
+            nat_traversal: false,
+            global_address: None,
+        }
+    }
+}
+
+/// Baud rates an MS/TP port may be configured with
+pub const MSTP_BAUD_RATES: [u32; 6] = [9600, 19200, 38400, 57600, 76800, 115200];
+
+/// MS/TP port settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct MstpPortSettings {
+    /// Station address (MAC_Address)
+    pub mac_address: u8,
+    /// Highest master station address polled for
+    pub max_master: u8,
+    /// Frames sent per token
+    pub max_info_frames: u8,
+    /// Baud rate (Link_Speed)
+    pub baud_rate: u32,
+    /// Whether Who-Is requests are answered for slaves
+    pub slave_proxy_enable: bool,
+    /// Whether slaves are searched for on the line
+    pub auto_slave_discovery: bool,
+    /// Slaves configured by hand
+    pub manual_slave_address_binding: Vec<SlaveBinding>,
+}
+
+impl Default for MstpPortSettings {
+    fn default() -> Self {
+        Self {
+            mac_address: 1,
+            max_master: 127,
+            max_info_frames: 1,
+            baud_rate: 38400,
+            slave_proxy_enable: false,
+            auto_slave_discovery: false,
+            manual_slave_address_binding: Vec::new(),
+        }
+    }
+}
+
+/// First proprietary property number of the MS/TP statistics; one
+/// read-only Unsigned property follows per counter, in the order of
+/// [`MstpStatistics::counters`]
+pub const MSTP_STATISTICS_PROPERTY_BASE: u32 = 512;
+
+/// MS/TP port statistics, maintained by the datalink
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MstpStatistics {
+    /// Valid frames received, for any station
+    pub frames_received: u32,
+    /// Frames transmitted
+    pub frames_sent: u32,
+    /// Tokens passed to this station
+    pub tokens_received: u32,
+    /// Poll-For-Master frames sent
+    pub polls_for_master_sent: u32,
+    /// Poll-For-Master frames addressed to this station
+    pub polls_for_master_received: u32,
+    /// Frames cut short by Tframe_abort or too long to receive
+    pub framing_errors: u32,
+    /// Frames with a bad header or data CRC
+    pub crc_errors: u32,
+    /// Token passes repeated because the next station did not use the token
+    pub token_retries: u32,
+    /// Times the line fell silent for Tno_token
+    pub lost_tokens: u32,
+}
+
+impl MstpStatistics {
+    /// The counters in field order
+    pub fn counters(&self) -> [u32; 9] {
+        [
+            self.frames_received,
+            self.frames_sent,
+            self.tokens_received,
+            self.polls_for_master_sent,
+            self.polls_for_master_received,
+            self.framing_errors,
+            self.crc_errors,
+            self.token_retries,
+            self.lost_tokens,
+        ]
+    }
+
+    /// The proprietary properties the counters are read through
+    pub fn properties() -> impl Iterator<Item = PropertyIdentifier> {
+        (0..9).map(|offset| PropertyIdentifier::Proprietary(MSTP_STATISTICS_PROPERTY_BASE + offset))
+    }
+}
+
+/// BACnet/SC port settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScPortSettings {
+    /// URI of the primary hub
+    pub primary_hub_uri: String,
+    /// URI of the failover hub
+    pub failover_hub_uri: String,
+    /// Minimum delay before reconnecting to a hub, in seconds
+    pub minimum_reconnect_time: u16,
+    /// Maximum delay before reconnecting to a hub, in seconds
+    pub maximum_reconnect_time: u16,
+    /// Time to wait for a connection to be accepted, in seconds
+    pub connect_wait_timeout: u16,
+    /// Time to wait for a disconnect to be acknowledged, in seconds
+    pub disconnect_wait_timeout: u16,
+    /// Idle time before a heartbeat is sent, in seconds
+    pub heartbeat_timeout: u16,
+    /// File object holding the operational certificate
+    pub operational_certificate_file: Option<ObjectIdentifier>,
+    /// File objects holding the issuer (CA) certificates
+    pub issuer_certificate_files: Vec<ObjectIdentifier>,
+    /// File object offering the certificate signing request
+    pub certificate_signing_request_file: Option<ObjectIdentifier>,
+}
+
+impl Default for ScPortSettings {
+    fn default() -> Self {
+        Self {
+            primary_hub_uri: String::new(),
+            failover_hub_uri: String::new(),
+            minimum_reconnect_time: 5,
+            maximum_reconnect_time: 600,
+            connect_wait_timeout: 10,
+            disconnect_wait_timeout: 10,
+            heartbeat_timeout: 300,
+            operational_certificate_file: None,
+            issuer_certificate_files: Vec::new(),
+            certificate_signing_request_file: None,
+        }
+    }
+}
+
+/// Datalink-specific settings of a network port
+#[derive(Debug, Clone, PartialEq)]
+pub enum DatalinkSettings {
+    Ipv4(IpPortSettings),
+    Mstp(MstpPortSettings),
+    SecureConnect(ScPortSettings),
+}
+
+impl DatalinkSettings {
+    /// Network type the settings belong to
+    pub fn network_type(&self) -> NetworkType {
+        match self {
+            DatalinkSettings::Ipv4(_) => NetworkType::Ipv4,
+            DatalinkSettings::Mstp(_) => NetworkType::Mstp,
+            DatalinkSettings::SecureConnect(_) => NetworkType::SecureConnect,
+        }
+    }
+}
+
+/// The configurable state of a network port, held both as the active
+/// configuration and as the pending one awaiting activation
+#[derive(Debug, Clone, PartialEq)]
+pub struct NetworkPortConfig {
+    /// BACnet network number (0 when unknown)
+    pub network_number: u16,
+    /// Datalink settings
+    pub settings: DatalinkSettings,
+}
+
+/// Network Port object
+#[derive(Debug, Clone)]
+pub struct NetworkPort {
+    /// Object identifier
+    pub identifier: ObjectIdentifier,
+    /// Object name
+    pub object_name: String,
+    /// Description
+    pub description: String,
+    /// Status flags
+    pub status_flags: u8,
+    /// Reliability
+    pub reliability: Reliability,
+    /// Out of service
+    pub out_of_service: bool,
+    /// Protocol level
+    pub protocol_level: ProtocolLevel,
+    /// Network number quality
+    pub network_number_quality: NetworkNumberQuality,
+    /// Maximum APDU length the port accepts
+    pub apdu_length: u32,
+    /// Foreign Device Table, maintained by the datalink (BBMD mode)
+    pub foreign_device_table: Vec<FdtTableEntry>,
+    /// Slaves proxied for, maintained by the datalink (MS/TP)
+    pub slave_address_binding: Vec<SlaveBinding>,
+    /// Port statistics, maintained by the datalink (MS/TP)
+    pub mstp_statistics: MstpStatistics,
+    active: NetworkPortConfig,
+    pending: NetworkPortConfig,
+    changes_pending: bool,
+    command: NetworkPortCommand,
+    activated: bool,
+}
+
+impl NetworkPort {
+    /// Create a new Network Port object with the given active configuration
+    pub fn new(instance: u32, object_name: String, config: NetworkPortConfig) -> Self {
+        let apdu_length = match config.settings {
+            DatalinkSettings::Mstp(_) => 480,
+            _ => 1476,
+        };
+        let network_number_quality = if config.network_number == 0 {
+            NetworkNumberQuality::Unknown
+        } else {
+            NetworkNumberQuality::Configured
+        };
+        Self {
+            identifier: ObjectIdentifier::new(ObjectType::NetworkPort, instance),
+            object_name,
+            description: String::new(),
+            status_flags: 0,
+            reliability: Reliability::NoFaultDetected,
+            out_of_service: false,
+            protocol_level: ProtocolLevel::BacnetApplication,
+            network_number_quality,
+            apdu_length,
+            foreign_device_table: Vec::new(),
+            slave_address_binding: Vec::new(),
+            mstp_statistics: MstpStatistics::default(),
+            pending: config.clone(),
+            active: config,
+            changes_pending: false,
+            command: NetworkPortCommand::Idle,
+            activated: false,
+        }
+    }
+
+    /// Network type of the port
+    pub fn network_type(&self) -> NetworkType {
+        self.active.settings.network_type()
+    }
+
+    /// Configuration the datalink is currently running with
+    pub fn active_config(&self) -> &NetworkPortConfig {
+        &self.active
+    }
+
+    /// Configuration written but not yet activated
+    pub fn pending_config(&self) -> &NetworkPortConfig {
+        &self.pending
+    }
+
+    /// Whether written configuration is awaiting activation
+    pub fn changes_pending(&self) -> bool {
+        self.changes_pending
+    }
+
+    /// Drop pending configuration changes
+    pub fn discard_changes(&mut self) {
+        self.pending = self.active.clone();
+        self.changes_pending = false;
+    }
+
+    /// Take the configuration made active by the last activation, if any
+    ///
+    /// The application applies it to the datalink.
+    pub fn take_activated_config(&mut self) -> Option<NetworkPortConfig> {
+        if core::mem::take(&mut self.activated) {
+            Some(self.active.clone())
+        } else {
+            None
+        }
+    }
+
+    /// Take a network number learned from a Network-Number-Is on the port
+    ///
+    /// A configured network number is kept. Returns whether the number was
+    /// taken.
+    pub fn learn_network_number(&mut self, network_number: u16) -> bool {
+        let configured = matches!(
+            self.network_number_quality,
+            NetworkNumberQuality::Configured | NetworkNumberQuality::LearnedConfigured
+        );
+        if configured || network_number == 0 || network_number == 0xFFFF {
+            return false;
+        }
+        self.active.network_number = network_number;
+        self.pending.network_number = network_number;
+        self.changes_pending = self.pending != self.active;
+        self.network_number_quality = NetworkNumberQuality::Learned;
+        true
+    }
+
+    /// Take a Command written by a client for the application to carry out
+    ///
+    /// Command reads back the operation until it is taken, then IDLE.
+    pub fn take_command(&mut self) -> Option<NetworkPortCommand> {
+        match core::mem::replace(&mut self.command, NetworkPortCommand::Idle) {
+            NetworkPortCommand::Idle => None,
+            command => Some(command),
+        }
+    }
+
+    /// Apply a change to the pending configuration
+    fn change(&mut self, apply: impl FnOnce(&mut NetworkPortConfig) -> Result<()>) -> Result<()> {
+        let mut pending = self.pending.clone();
+        apply(&mut pending)?;
+        self.pending = pending;
+        self.changes_pending = self.pending != self.active;
+        Ok(())
+    }
+
+    fn ip(&self) -> Option<&IpPortSettings> {
+        match &self.pending.settings {
+            DatalinkSettings::Ipv4(ip) => Some(ip),
+            _ => None,
+        }
+    }
+
+    fn mstp(&self) -> Option<&MstpPortSettings> {
+        match &self.pending.settings {
+            DatalinkSettings::Mstp(mstp) => Some(mstp),
+            _ => None,
+        }
+    }
+
+    fn sc(&self) -> Option<&ScPortSettings> {
+        match &self.pending.settings {
+            DatalinkSettings::SecureConnect(sc) => Some(sc),
+            _ => None,
+        }
+    }
+
+    /// Current Status_Flags, combining the stored flags with the state derived
+    /// from Reliability and Out_Of_Service
+    pub fn current_status_flags(&self) -> u8 {
+        current_status_flags(
+            self.status_flags,
+            EventState::Normal,
+            self.reliability,
+            self.out_of_service,
+        )
+    }
+
+    /// Write a datalink-specific property into the pending configuration
+    fn set_datalink_property(
+        &mut self,
+        property: PropertyIdentifier,
+        value: PropertyValue,
+    ) -> Result<()> {
+        self.change(|config| match (&mut config.settings, property) {
+            (DatalinkSettings::Ipv4(ip), PropertyIdentifier::IpAddress) => {
+                ip.ip_address = ipv4_from_value(&value)?;
+                Ok(())
+            }
+            (DatalinkSettings::Ipv4(ip), PropertyIdentifier::IpSubnetMask) => {
+                ip.subnet_mask = ipv4_from_value(&value)?;
+                Ok(())
+            }
+            (DatalinkSettings::Ipv4(ip), PropertyIdentifier::IpDefaultGateway) => {
+                ip.default_gateway = ipv4_from_value(&value)?;
+                Ok(())
+            }
+            (DatalinkSettings::Ipv4(ip), PropertyIdentifier::IpDnsServer) => match &value {
+                PropertyValue::Array(servers) => {
+                    ip.dns_servers = servers
+                        .iter()
+                        .map(ipv4_from_value)
+                        .collect::<Result<Vec<_>>>()?;
+                    Ok(())
+                }
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            (DatalinkSettings::Ipv4(ip), PropertyIdentifier::BacnetIpUdpPort) => match value {
+                PropertyValue::UnsignedInteger(port) => {
+                    ip.udp_port = udp_port(port)?;
+                    Ok(())
+                }
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            (DatalinkSettings::Ipv4(ip), PropertyIdentifier::IpDhcpEnable) => match value {
+                PropertyValue::Boolean(enable) => {
+                    ip.dhcp_enable = enable;
+                    Ok(())
+                }
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            (DatalinkSettings::Ipv4(ip), PropertyIdentifier::BacnetIpMode) => match value {
+                PropertyValue::Enumerated(mode) => {
+                    ip.mode = BacnetIpMode::try_from(mode)?;
+                    Ok(())
+                }
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            (DatalinkSettings::Ipv4(ip), PropertyIdentifier::BbmdAcceptFdRegistrations) => {
+                match value {
+                    PropertyValue::Boolean(accept) => {
+                        ip.accept_fd_registrations = accept;
+                        Ok(())
+                    }
+                    _ => Err(ObjectError::InvalidPropertyType),
+                }
+            }
+            (DatalinkSettings::Ipv4(ip), PropertyIdentifier::BbmdBroadcastDistributionTable) => {
+                match &value {
+                    PropertyValue::List(entries) => {
+                        ip.broadcast_distribution_table = entries
+                            .iter()
+                            .map(BdtTableEntry::from_property_value)
+                            .collect::<Result<Vec<_>>>()?;
+                        Ok(())
+                    }
+                    _ => Err(ObjectError::InvalidPropertyType),
+                }
+            }
+            (DatalinkSettings::Ipv4(ip), PropertyIdentifier::FdBbmdAddress) => {
+                ip.fd_bbmd_address = match &value {
+                    PropertyValue::Null => None,
+                    value => Some(HostNPort::from_property_value(value)?),
+                };
+                Ok(())
+            }
+            (DatalinkSettings::Ipv4(ip), PropertyIdentifier::BacnetIpNatTraversal) => match value {
+                PropertyValue::Boolean(enable) => {
+                    ip.nat_traversal = enable;
+                    Ok(())
+                }
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            (DatalinkSettings::Ipv4(ip), PropertyIdentifier::BacnetIpGlobalAddress) => {
+                ip.global_address = match &value {
+                    PropertyValue::Null => None,
+                    value => Some(HostNPort::from_property_value(value)?),
+                };
+                Ok(())
+            }
+            (DatalinkSettings::Ipv4(ip), PropertyIdentifier::FdSubscriptionLifetime) => match value
+            {
+                PropertyValue::UnsignedInteger(ttl) if ttl <= u16::MAX as u32 => {
+                    ip.fd_subscription_lifetime = ttl as u16;
+                    Ok(())
+                }
+                PropertyValue::UnsignedInteger(_) => Err(ObjectError::InvalidValue(
+                    "Subscription lifetime must be 0-65535".to_string(),
+                )),
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            (DatalinkSettings::Mstp(mstp), PropertyIdentifier::MacAddress) => match &value {
+                PropertyValue::OctetString(mac) => match mac.as_slice() {
+                    [station] if *station <= 127 => {
+                        mstp.mac_address = *station;
+                        Ok(())
+                    }
+                    _ => Err(ObjectError::InvalidValue(
+                        "MS/TP MAC address must be one octet 0-127".to_string(),
+                    )),
+                },
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            (DatalinkSettings::Mstp(mstp), PropertyIdentifier::MaxMaster) => match value {
+                PropertyValue::UnsignedInteger(max) if (1..=127).contains(&max) => {
+                    mstp.max_master = max as u8;
+                    Ok(())
+                }
+                PropertyValue::UnsignedInteger(_) => Err(ObjectError::InvalidValue(
+                    "Max_Master must be 1-127".to_string(),
+                )),
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            (DatalinkSettings::Mstp(mstp), PropertyIdentifier::MaxInfoFrames) => match value {
+                PropertyValue::UnsignedInteger(frames) if (1..=255).contains(&frames) => {
+                    mstp.max_info_frames = frames as u8;
+                    Ok(())
+                }
+                PropertyValue::UnsignedInteger(_) => Err(ObjectError::InvalidValue(
+                    "Max_Info_Frames must be 1-255".to_string(),
+                )),
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            (DatalinkSettings::Mstp(mstp), PropertyIdentifier::LinkSpeed) => match value {
+                PropertyValue::Real(speed) if MSTP_BAUD_RATES.contains(&(speed as u32)) => {
+                    mstp.baud_rate = speed as u32;
+                    Ok(())
+                }
+                PropertyValue::Real(speed) => Err(ObjectError::InvalidValue(format!(
+                    "Unsupported MS/TP baud rate: {}",
+                    speed
+                ))),
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            (DatalinkSettings::Mstp(mstp), PropertyIdentifier::SlaveProxyEnable) => match value {
+                PropertyValue::Boolean(enable) => {
+                    mstp.slave_proxy_enable = enable;
+                    Ok(())
+                }
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            (DatalinkSettings::Mstp(mstp), PropertyIdentifier::AutoSlaveDiscovery) => match value {
+                PropertyValue::Boolean(enable) => {
+                    mstp.auto_slave_discovery = enable;
+                    Ok(())
+                }
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            (DatalinkSettings::Mstp(mstp), PropertyIdentifier::ManualSlaveAddressBinding) => {
+                match &value {
+                    PropertyValue::List(entries) => {
+                        mstp.manual_slave_address_binding = entries
+                            .iter()
+                            .map(SlaveBinding::from_property_value)
+                            .collect::<Result<Vec<_>>>()?;
+                        Ok(())
+                    }
+                    _ => Err(ObjectError::InvalidPropertyType),
+                }
+            }
+            (DatalinkSettings::SecureConnect(sc), PropertyIdentifier::ScPrimaryHubUri) => {
+                match value {
+                    PropertyValue::CharacterString(uri) => {
+                        sc.primary_hub_uri = uri;
+                        Ok(())
+                    }
+                    _ => Err(ObjectError::InvalidPropertyType),
+                }
+            }
+            (DatalinkSettings::SecureConnect(sc), PropertyIdentifier::ScFailoverHubUri) => {
+                match value {
+                    PropertyValue::CharacterString(uri) => {
+                        sc.failover_hub_uri = uri;
+                        Ok(())
+                    }
+                    _ => Err(ObjectError::InvalidPropertyType),
+                }
+            }
+            (DatalinkSettings::SecureConnect(sc), PropertyIdentifier::ScMinimumReconnectTime) => {
+                let time = sc_time(&value, 2..=300)?;
+                if time > sc.maximum_reconnect_time {
+                    return Err(ObjectError::InvalidValue(
+                        "Minimum reconnect time exceeds maximum".to_string(),
+                    ));
+                }
+                sc.minimum_reconnect_time = time;
+                Ok(())
+            }
+            (DatalinkSettings::SecureConnect(sc), PropertyIdentifier::ScMaximumReconnectTime) => {
+                let time = sc_time(&value, 2..=600)?;
+                if time < sc.minimum_reconnect_time {
+                    return Err(ObjectError::InvalidValue(
+                        "Maximum reconnect time is below minimum".to_string(),
+                    ));
+                }
+                sc.maximum_reconnect_time = time;
+                Ok(())
+            }
+            (DatalinkSettings::SecureConnect(sc), PropertyIdentifier::ScConnectWaitTimeout) => {
+                sc.connect_wait_timeout = sc_time(&value, 5..=300)?;
+                Ok(())
+            }
+            (DatalinkSettings::SecureConnect(sc), PropertyIdentifier::ScDisconnectWaitTimeout) => {
+                sc.disconnect_wait_timeout = sc_time(&value, 5..=300)?;
+                Ok(())
+            }
+            (DatalinkSettings::SecureConnect(sc), PropertyIdentifier::ScHeartbeatTimeout) => {
+                sc.heartbeat_timeout = sc_time(&value, 1..=u16::MAX)?;
+                Ok(())
+            }
+            _ => Err(ObjectError::PropertyNotWritable),
+        })
+    }
+}
+
+/// Decode a four-octet IPv4 address or mask
+fn ipv4_from_value(value: &PropertyValue) -> Result<[u8; 4]> {
+    match value {
+        PropertyValue::OctetString(octets) => octets
+            .as_slice()
+            .try_into()
+            .map_err(|_| ObjectError::InvalidValue("IPv4 address must be 4 octets".to_string())),
+        _ => Err(ObjectError::InvalidPropertyType),
+    }
+}
+
+fn udp_port(port: u32) -> Result<u16> {
+    match u16::try_from(port) {
+        Ok(port) if port != 0 => Ok(port),
+        _ => Err(ObjectError::InvalidValue(format!(
+            "Invalid UDP port: {}",
+            port
+        ))),
+    }
+}
+
+fn sc_time(value: &PropertyValue, range: core::ops::RangeInclusive<u16>) -> Result<u16> {
+    match value {
+        PropertyValue::UnsignedInteger(time) => u16::try_from(*time)
+            .ok()
+            .filter(|time| range.contains(time))
+            .ok_or_else(|| {
+                ObjectError::InvalidValue(format!(
+                    "Time must be {}-{} seconds",
+                    range.start(),
+                    range.end()
+                ))
+            }),
+        _ => Err(ObjectError::InvalidPropertyType),
+    }
+}
+
+fn ipv4_value(address: [u8; 4]) -> PropertyValue {
+    PropertyValue::OctetString(address.to_vec())
+}
+
+impl BacnetObject for NetworkPort {
+    fn identifier(&self) -> ObjectIdentifier {
+        self.identifier
+    }
+
+    fn get_property(&self, property: PropertyIdentifier) -> Result<PropertyValue> {
+        match property {
+            PropertyIdentifier::ObjectIdentifier => {
+                Ok(PropertyValue::ObjectIdentifier(self.identifier))
+            }
+            PropertyIdentifier::ObjectName => {
+                Ok(PropertyValue::CharacterString(self.object_name.clone()))
+            }
+            PropertyIdentifier::ObjectType => Ok(PropertyValue::Enumerated(u32::from(
+                ObjectType::NetworkPort,
+            ))),
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::StatusFlags => {
+                Ok(status_flags_bit_string(self.current_status_flags()))
+            }
+            PropertyIdentifier::Reliability => {
+                Ok(PropertyValue::Enumerated(self.reliability as u32))
+            }
+            PropertyIdentifier::OutOfService => Ok(PropertyValue::Boolean(self.out_of_service)),
+            PropertyIdentifier::NetworkType => {
+                Ok(PropertyValue::Enumerated(self.network_type() as u32))
+            }
+            PropertyIdentifier::ProtocolLevel => {
+                Ok(PropertyValue::Enumerated(self.protocol_level as u32))
+            }
+            PropertyIdentifier::NetworkNumber => Ok(PropertyValue::UnsignedInteger(
+                self.pending.network_number as u32,
+            )),
+            PropertyIdentifier::NetworkNumberQuality => Ok(PropertyValue::Enumerated(
+                self.network_number_quality as u32,
+            )),
+            PropertyIdentifier::ChangesPending => Ok(PropertyValue::Boolean(self.changes_pending)),
+            PropertyIdentifier::Command => Ok(PropertyValue::Enumerated(self.command as u32)),
+            PropertyIdentifier::ApduLength => Ok(PropertyValue::UnsignedInteger(self.apdu_length)),
+            PropertyIdentifier::MacAddress => match &self.pending.settings {
+                DatalinkSettings::Ipv4(ip) => {
+                    let mut mac = ip.ip_address.to_vec();
+                    mac.extend_from_slice(&ip.udp_port.to_be_bytes());
+                    Ok(PropertyValue::OctetString(mac))
+                }
+                DatalinkSettings::Mstp(mstp) => {
+                    Ok(PropertyValue::OctetString(vec![mstp.mac_address]))
+                }
+                DatalinkSettings::SecureConnect(_) => Err(ObjectError::UnknownProperty),
+            },
+            PropertyIdentifier::LinkSpeed => self
+                .mstp()
+                .map(|mstp| PropertyValue::Real(mstp.baud_rate as f32))
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::MaxMaster => self
+                .mstp()
+                .map(|mstp| PropertyValue::UnsignedInteger(mstp.max_master as u32))
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::MaxInfoFrames => self
+                .mstp()
+                .map(|mstp| PropertyValue::UnsignedInteger(mstp.max_info_frames as u32))
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::SlaveProxyEnable => self
+                .mstp()
+                .map(|mstp| PropertyValue::Boolean(mstp.slave_proxy_enable))
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::AutoSlaveDiscovery => self
+                .mstp()
+                .map(|mstp| PropertyValue::Boolean(mstp.auto_slave_discovery))
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::ManualSlaveAddressBinding => self
+                .mstp()
+                .map(|mstp| {
+                    PropertyValue::List(
+                        mstp.manual_slave_address_binding
+                            .iter()
+                            .map(SlaveBinding::to_property_value)
+                            .collect(),
+                    )
+                })
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::SlaveAddressBinding => self
+                .mstp()
+                .map(|_| {
+                    PropertyValue::List(
+                        self.slave_address_binding
+                            .iter()
+                            .map(SlaveBinding::to_property_value)
+                            .collect(),
+                    )
+                })
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::Proprietary(number)
+                if self.mstp().is_some() && number >= MSTP_STATISTICS_PROPERTY_BASE =>
+            {
+                let counters = self.mstp_statistics.counters();
+                let offset = (number - MSTP_STATISTICS_PROPERTY_BASE) as usize;
+                counters
+                    .get(offset)
+                    .map(|&counter| PropertyValue::UnsignedInteger(counter))
+                    .ok_or(ObjectError::UnknownProperty)
+            }
+            PropertyIdentifier::IpAddress
+            | PropertyIdentifier::IpSubnetMask
+            | PropertyIdentifier::IpDefaultGateway
+            | PropertyIdentifier::IpDnsServer
+            | PropertyIdentifier::BacnetIpUdpPort
+            | PropertyIdentifier::IpDhcpEnable
+            | PropertyIdentifier::BacnetIpMode
+            | PropertyIdentifier::BbmdAcceptFdRegistrations
+            | PropertyIdentifier::BbmdBroadcastDistributionTable
+            | PropertyIdentifier::BbmdForeignDeviceTable
+            | PropertyIdentifier::FdBbmdAddress
+            | PropertyIdentifier::FdSubscriptionLifetime
+            | PropertyIdentifier::BacnetIpNatTraversal
+            | PropertyIdentifier::BacnetIpGlobalAddress => {
+                let ip = self.ip().ok_or(ObjectError::UnknownProperty)?;
+                Ok(match property {
+                    PropertyIdentifier::IpAddress => ipv4_value(ip.ip_address),
+                    PropertyIdentifier::IpSubnetMask => ipv4_value(ip.subnet_mask),
+                    PropertyIdentifier::IpDefaultGateway => ipv4_value(ip.default_gateway),
+                    PropertyIdentifier::IpDnsServer => PropertyValue::Array(
+                        ip.dns_servers.iter().copied().map(ipv4_value).collect(),
+                    ),
+                    PropertyIdentifier::BacnetIpUdpPort => {
+                        PropertyValue::UnsignedInteger(ip.udp_port as u32)
+                    }
+                    PropertyIdentifier::IpDhcpEnable => PropertyValue::Boolean(ip.dhcp_enable),
+                    PropertyIdentifier::BacnetIpMode => PropertyValue::Enumerated(ip.mode as u32),
+                    PropertyIdentifier::BbmdAcceptFdRegistrations => {
+                        PropertyValue::Boolean(ip.accept_fd_registrations)
+                    }
+                    PropertyIdentifier::BbmdBroadcastDistributionTable => PropertyValue::List(
+                        ip.broadcast_distribution_table
+                            .iter()
+                            .map(BdtTableEntry::to_property_value)
+                            .collect(),
+                    ),
+                    PropertyIdentifier::BbmdForeignDeviceTable => PropertyValue::List(
+                        self.foreign_device_table
+                            .iter()
+                            .map(FdtTableEntry::to_property_value)
+                            .collect(),
+                    ),
+                    PropertyIdentifier::FdBbmdAddress => ip
+                        .fd_bbmd_address
+                        .map(|address| address.to_property_value())
+                        .unwrap_or(PropertyValue::Null),
+                    PropertyIdentifier::BacnetIpNatTraversal => {
+                        PropertyValue::Boolean(ip.nat_traversal)
+                    }
+                    PropertyIdentifier::BacnetIpGlobalAddress => ip
+                        .global_address
+                        .map(|address| address.to_property_value())
+                        .unwrap_or(PropertyValue::Null),
+                    _ => PropertyValue::UnsignedInteger(ip.fd_subscription_lifetime as u32),
+                })
+            }
+            PropertyIdentifier::ScPrimaryHubUri
+            | PropertyIdentifier::ScFailoverHubUri
+            | PropertyIdentifier::ScMinimumReconnectTime
+            | PropertyIdentifier::ScMaximumReconnectTime
+            | PropertyIdentifier::ScConnectWaitTimeout
+            | PropertyIdentifier::ScDisconnectWaitTimeout
+            | PropertyIdentifier::ScHeartbeatTimeout
+            | PropertyIdentifier::OperationalCertificateFile
+            | PropertyIdentifier::IssuerCertificateFiles
+            | PropertyIdentifier::CertificateSigningRequestFile => {
+                let sc = self.sc().ok_or(ObjectError::UnknownProperty)?;
+                let file = |file: Option<ObjectIdentifier>| {
+                    file.map(PropertyValue::ObjectIdentifier)
+                        .unwrap_or(PropertyValue::Null)
+                };
+                Ok(match property {
+                    PropertyIdentifier::ScPrimaryHubUri => {
+                        PropertyValue::CharacterString(sc.primary_hub_uri.clone())
+                    }
+                    PropertyIdentifier::ScFailoverHubUri => {
+                        PropertyValue::CharacterString(sc.failover_hub_uri.clone())
+                    }
+                    PropertyIdentifier::ScMinimumReconnectTime => {
+                        PropertyValue::UnsignedInteger(sc.minimum_reconnect_time as u32)
+                    }
+                    PropertyIdentifier::ScMaximumReconnectTime => {
+                        PropertyValue::UnsignedInteger(sc.maximum_reconnect_time as u32)
+                    }
+                    PropertyIdentifier::ScConnectWaitTimeout => {
+                        PropertyValue::UnsignedInteger(sc.connect_wait_timeout as u32)
+                    }
+                    PropertyIdentifier::ScDisconnectWaitTimeout => {
+                        PropertyValue::UnsignedInteger(sc.disconnect_wait_timeout as u32)
+                    }
+                    PropertyIdentifier::ScHeartbeatTimeout => {
+                        PropertyValue::UnsignedInteger(sc.heartbeat_timeout as u32)
+                    }
+                    PropertyIdentifier::OperationalCertificateFile => {
+                        file(sc.operational_certificate_file)
+                    }
+                    PropertyIdentifier::IssuerCertificateFiles => PropertyValue::Array(
+                        sc.issuer_certificate_files
+                            .iter()
+                            .copied()
+                            .map(PropertyValue::ObjectIdentifier)
+                            .collect(),
+                    ),
+                    _ => file(sc.certificate_signing_request_file),
+                })
+            }
+            _ => Err(ObjectError::UnknownProperty),
+        }
+    }
+
+    fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        match property {
+            PropertyIdentifier::ObjectName => {
+                if let PropertyValue::CharacterString(name) = value {
+                    self.object_name = name;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::OutOfService => {
+                if let PropertyValue::Boolean(oos) = value {
+                    self.out_of_service = oos;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::NetworkNumber => match value {
+                PropertyValue::UnsignedInteger(number) if number <= 65534 => {
+                    self.change(|config| {
+                        config.network_number = number as u16;
+                        Ok(())
+                    })
+                }
+                PropertyValue::UnsignedInteger(_) => Err(ObjectError::InvalidValue(
+                    "Network number must be 0-65534".to_string(),
+                )),
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            PropertyIdentifier::Command => match value {
+                PropertyValue::Enumerated(command) => {
+                    match NetworkPortCommand::try_from(command)? {
+                        NetworkPortCommand::Idle => {}
+                        NetworkPortCommand::DiscardChanges => self.discard_changes(),
+                        command => self.command = command,
+                    }
+                    Ok(())
+                }
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            _ => self.set_datalink_property(property, value),
+        }
+    }
+
+    fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
+        match property {
+            PropertyIdentifier::ObjectName
+            | PropertyIdentifier::Description
+            | PropertyIdentifier::OutOfService
+            | PropertyIdentifier::NetworkNumber
+            | PropertyIdentifier::Command => true,
+            PropertyIdentifier::IpAddress
+            | PropertyIdentifier::IpSubnetMask
+            | PropertyIdentifier::IpDefaultGateway
+            | PropertyIdentifier::IpDnsServer
+            | PropertyIdentifier::BacnetIpUdpPort
+            | PropertyIdentifier::IpDhcpEnable
+            | PropertyIdentifier::BacnetIpMode
+            | PropertyIdentifier::BbmdAcceptFdRegistrations
+            | PropertyIdentifier::BbmdBroadcastDistributionTable
+            | PropertyIdentifier::FdBbmdAddress
+            | PropertyIdentifier::FdSubscriptionLifetime
+            | PropertyIdentifier::BacnetIpNatTraversal
+            | PropertyIdentifier::BacnetIpGlobalAddress => self.ip().is_some(),
+            PropertyIdentifier::MacAddress
+            | PropertyIdentifier::MaxMaster
+            | PropertyIdentifier::MaxInfoFrames
+            | PropertyIdentifier::LinkSpeed
+            | PropertyIdentifier::SlaveProxyEnable
+            | PropertyIdentifier::AutoSlaveDiscovery
+            | PropertyIdentifier::ManualSlaveAddressBinding => self.mstp().is_some(),
+            PropertyIdentifier::ScPrimaryHubUri
+            | PropertyIdentifier::ScFailoverHubUri
+            | PropertyIdentifier::ScMinimumReconnectTime
+            | PropertyIdentifier::ScMaximumReconnectTime
+            | PropertyIdentifier::ScConnectWaitTimeout
+            | PropertyIdentifier::ScDisconnectWaitTimeout
+            | PropertyIdentifier::ScHeartbeatTimeout => self.sc().is_some(),
+            _ => false,
+        }
+    }
+
+    fn property_list(&self) -> Vec<PropertyIdentifier> {
+        let mut properties = vec![
+            PropertyIdentifier::ObjectIdentifier,
+            PropertyIdentifier::ObjectName,
+            PropertyIdentifier::ObjectType,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::StatusFlags,
+            PropertyIdentifier::Reliability,
+            PropertyIdentifier::OutOfService,
+            PropertyIdentifier::NetworkType,
+            PropertyIdentifier::ProtocolLevel,
+            PropertyIdentifier::NetworkNumber,
+            PropertyIdentifier::NetworkNumberQuality,
+            PropertyIdentifier::ChangesPending,
+            PropertyIdentifier::Command,
+            PropertyIdentifier::ApduLength,
+        ];
+        match &self.pending.settings {
+            DatalinkSettings::Ipv4(_) => properties.extend([
+                PropertyIdentifier::MacAddress,
+                PropertyIdentifier::IpAddress,
+                PropertyIdentifier::IpSubnetMask,
+                PropertyIdentifier::IpDefaultGateway,
+                PropertyIdentifier::IpDnsServer,
+                PropertyIdentifier::BacnetIpUdpPort,
+                PropertyIdentifier::IpDhcpEnable,
+                PropertyIdentifier::BacnetIpMode,
+                PropertyIdentifier::BbmdAcceptFdRegistrations,
+                PropertyIdentifier::BbmdBroadcastDistributionTable,
+                PropertyIdentifier::BbmdForeignDeviceTable,
+                PropertyIdentifier::FdBbmdAddress,
+                PropertyIdentifier::FdSubscriptionLifetime,
+                PropertyIdentifier::BacnetIpNatTraversal,
+                PropertyIdentifier::BacnetIpGlobalAddress,
+            ]),
+            DatalinkSettings::Mstp(_) => {
+                properties.extend([
+                    PropertyIdentifier::MacAddress,
+                    PropertyIdentifier::LinkSpeed,
+                    PropertyIdentifier::MaxMaster,
+                    PropertyIdentifier::MaxInfoFrames,
+                    PropertyIdentifier::SlaveProxyEnable,
+                    PropertyIdentifier::AutoSlaveDiscovery,
+                    PropertyIdentifier::ManualSlaveAddressBinding,
+                    PropertyIdentifier::SlaveAddressBinding,
+                ]);
+                properties.extend(MstpStatistics::properties());
+            }
+            DatalinkSettings::SecureConnect(_) => properties.extend([
+                PropertyIdentifier::ScPrimaryHubUri,
+                PropertyIdentifier::ScFailoverHubUri,
+                PropertyIdentifier::ScMinimumReconnectTime,
+                PropertyIdentifier::ScMaximumReconnectTime,
+                PropertyIdentifier::ScConnectWaitTimeout,
+                PropertyIdentifier::ScDisconnectWaitTimeout,
+                PropertyIdentifier::ScHeartbeatTimeout,
+                PropertyIdentifier::OperationalCertificateFile,
+                PropertyIdentifier::IssuerCertificateFiles,
+                PropertyIdentifier::CertificateSigningRequestFile,
+            ]),
+        }
+        properties
+    }
+
+    fn activate_changes(&mut self) {
+        if !self.changes_pending {
+            return;
+        }
+        if self.pending.network_number != self.active.network_number {
+            self.network_number_quality = if self.pending.network_number == 0 {
+                NetworkNumberQuality::Unknown
+            } else {
+                NetworkNumberQuality::Configured
+            };
+        }
+        self.active = self.pending.clone();
+        self.changes_pending = false;
+        self.activated = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip_port() -> NetworkPort {
+        let settings = IpPortSettings {
+            ip_address: [192, 168, 1, 10],
+            ..Default::default()
+        };
+        NetworkPort::new(
+            1,
+            "BACnet/IP Port".to_string(),
+            NetworkPortConfig {
+                network_number: 1,
+                settings: DatalinkSettings::Ipv4(settings),
+            },
+        )
+    }
+
+    #[test]
+    fn test_network_port_changes_pending_until_activated() {
+        let mut port = ip_port();
+        port.set_property(
+            PropertyIdentifier::IpAddress,
+            PropertyValue::OctetString(vec![10, 0, 0, 5]),
+        )
+        .unwrap();
+        port.set_property(
+            PropertyIdentifier::BacnetIpUdpPort,
+            PropertyValue::UnsignedInteger(47809),
+        )
+        .unwrap();
+
+        // Reads return the pending values while the datalink keeps the old ones
+        assert!(port.changes_pending());
+        assert_eq!(
+            port.get_property(PropertyIdentifier::MacAddress).unwrap(),
+            PropertyValue::OctetString(vec![10, 0, 0, 5, 0xBA, 0xC1])
+        );
+        let DatalinkSettings::Ipv4(active) = &port.active_config().settings else {
+            panic!("expected BACnet/IP settings");
+        };
+        assert_eq!(active.ip_address, [192, 168, 1, 10]);
+        assert_eq!(port.take_activated_config(), None);
+
+        port.activate_changes();
+        assert!(!port.changes_pending());
+        let config = port.take_activated_config().unwrap();
+        let DatalinkSettings::Ipv4(ip) = config.settings else {
+            panic!("expected BACnet/IP settings");
+        };
+        assert_eq!(ip.ip_address, [10, 0, 0, 5]);
+        assert_eq!(ip.udp_port, 47809);
+    }
+
+    #[test]
+    fn test_network_port_discard_changes_and_validation() {
+        let mut port = ip_port();
+        let bdt = PropertyValue::List(vec![BdtTableEntry {
+            bbmd_address: HostNPort::new([192, 168, 2, 1], 0xBAC0),
+            broadcast_mask: [255, 255, 255, 255],
+        }
+        .to_property_value()]);
+        port.set_property(PropertyIdentifier::BbmdBroadcastDistributionTable, bdt)
+            .unwrap();
+        assert!(port.changes_pending());
+        assert!(port
+            .set_property(
+                PropertyIdentifier::IpSubnetMask,
+                PropertyValue::OctetString(vec![255, 255, 0]),
+            )
+            .is_err());
+        assert!(matches!(
+            port.set_property(
+                PropertyIdentifier::MaxMaster,
+                PropertyValue::UnsignedInteger(10)
+            ),
+            Err(ObjectError::PropertyNotWritable)
+        ));
+
+        port.set_property(
+            PropertyIdentifier::Command,
+            PropertyValue::Enumerated(NetworkPortCommand::DiscardChanges as u32),
+        )
+        .unwrap();
+        assert!(!port.changes_pending());
+        assert_eq!(
+            port.get_property(PropertyIdentifier::BbmdBroadcastDistributionTable)
+                .unwrap(),
+            PropertyValue::List(vec![])
+        );
+
+        port.set_property(
+            PropertyIdentifier::Command,
+            PropertyValue::Enumerated(NetworkPortCommand::RenewFdRegistration as u32),
+        )
+        .unwrap();
+        assert_eq!(
+            port.take_command(),
+            Some(NetworkPortCommand::RenewFdRegistration)
+        );
+        assert_eq!(port.take_command(), None);
+    }
+
+    #[test]
+    fn test_network_port_learned_network_number() {
+        let mut port = NetworkPort::new(
+            2,
+            "Unnumbered Port".to_string(),
+            NetworkPortConfig {
+                network_number: 0,
+                settings: DatalinkSettings::Ipv4(IpPortSettings::default()),
+            },
+        );
+        assert_eq!(port.network_number_quality, NetworkNumberQuality::Unknown);
+        assert!(port.learn_network_number(7));
+        assert_eq!(port.active_config().network_number, 7);
+        assert_eq!(
+            port.get_property(PropertyIdentifier::NetworkNumberQuality)
+                .unwrap(),
+            PropertyValue::Enumerated(NetworkNumberQuality::Learned as u32)
+        );
+        assert!(!port.changes_pending());
+
+        // Configuring a number ends learning
+        port.set_property(
+            PropertyIdentifier::NetworkNumber,
+            PropertyValue::UnsignedInteger(9),
+        )
+        .unwrap();
+        port.activate_changes();
+        assert_eq!(
+            port.network_number_quality,
+            NetworkNumberQuality::Configured
+        );
+        assert!(!port.learn_network_number(7));
+        assert!(!ip_port().learn_network_number(7));
+    }
+
+    #[test]
+    fn test_network_port_mstp_settings() {
+        let mut port = NetworkPort::new(
+            2,
+            "MS/TP Port".to_string(),
+            NetworkPortConfig {
+                network_number: 2,
+                settings: DatalinkSettings::Mstp(MstpPortSettings::default()),
+            },
+        );
+        assert_eq!(port.apdu_length, 480);
+        port.set_property(PropertyIdentifier::LinkSpeed, PropertyValue::Real(76800.0))
+            .unwrap();
+        port.set_property(
+            PropertyIdentifier::MacAddress,
+            PropertyValue::OctetString(vec![12]),
+        )
+        .unwrap();
+        assert!(port
+            .set_property(PropertyIdentifier::LinkSpeed, PropertyValue::Real(4800.0))
+            .is_err());
+        assert!(port
+            .set_property(
+                PropertyIdentifier::MacAddress,
+                PropertyValue::OctetString(vec![200]),
+            )
+            .is_err());
+
+        port.activate_changes();
+        let DatalinkSettings::Mstp(mstp) = &port.active_config().settings else {
+            panic!("expected MS/TP settings");
+        };
+        assert_eq!(mstp.baud_rate, 76800);
+        assert_eq!(mstp.mac_address, 12);
+    }
+
+    #[test]
+    fn test_network_port_slave_proxy() {
+        let mut port = NetworkPort::new(
+            2,
+            "MS/TP Port".to_string(),
+            NetworkPortConfig {
+                network_number: 2,
+                settings: DatalinkSettings::Mstp(MstpPortSettings::default()),
+            },
+        );
+        let binding = SlaveBinding {
+            device_identifier: ObjectIdentifier::new(ObjectType::Device, 1001),
+            mac_address: 200,
+        };
+        port.set_property(
+            PropertyIdentifier::SlaveProxyEnable,
+            PropertyValue::Boolean(true),
+        )
+        .unwrap();
+        port.set_property(
+            PropertyIdentifier::ManualSlaveAddressBinding,
+            PropertyValue::List(vec![binding.to_property_value()]),
+        )
+        .unwrap();
+        assert!(port
+            .set_property(
+                PropertyIdentifier::ManualSlaveAddressBinding,
+                PropertyValue::List(vec![PropertyValue::List(vec![
+                    PropertyValue::ObjectIdentifier(binding.device_identifier),
+                    PropertyValue::UnsignedInteger(0),
+                    PropertyValue::OctetString(vec![255]),
+                ])]),
+            )
+            .is_err());
+        assert!(!port.is_property_writable(PropertyIdentifier::SlaveAddressBinding));
+
+        port.activate_changes();
+        let DatalinkSettings::Mstp(mstp) = &port.active_config().settings else {
+            panic!("expected MS/TP settings");
+        };
+        assert!(mstp.slave_proxy_enable);
+        assert_eq!(mstp.manual_slave_address_binding, vec![binding]);
+
+        port.slave_address_binding.push(binding);
+        assert_eq!(
+            port.get_property(PropertyIdentifier::SlaveAddressBinding)
+                .unwrap(),
+            PropertyValue::List(vec![binding.to_property_value()])
+        );
+    }
+
+    #[test]
+    fn test_network_port_mstp_statistics() {
+        let mut port = NetworkPort::new(
+            2,
+            "MS/TP Port".to_string(),
+            NetworkPortConfig {
+                network_number: 2,
+                settings: DatalinkSettings::Mstp(MstpPortSettings::default()),
+            },
+        );
+        port.mstp_statistics.crc_errors = 3;
+        let crc_errors = PropertyIdentifier::Proprietary(MSTP_STATISTICS_PROPERTY_BASE + 6);
+        assert!(port.property_list().contains(&crc_errors));
+        assert_eq!(
+            port.get_property(crc_errors).unwrap(),
+            PropertyValue::UnsignedInteger(3)
+        );
+        assert!(!port.is_property_writable(crc_errors));
+        assert!(port
+            .get_property(PropertyIdentifier::Proprietary(
+                MSTP_STATISTICS_PROPERTY_BASE + 9
+            ))
+            .is_err());
+        assert!(ip_port().get_property(crc_errors).is_err());
+    }
+
+    #[test]
+    fn test_network_port_sc_certificate_files() {
+        let file = |instance| ObjectIdentifier::new(ObjectType::File, instance);
+        let port = NetworkPort::new(
+            3,
+            "SC Port".to_string(),
+            NetworkPortConfig {
+                network_number: 3,
+                settings: DatalinkSettings::SecureConnect(ScPortSettings {
+                    operational_certificate_file: Some(file(1)),
+                    issuer_certificate_files: vec![file(2), file(3)],
+                    ..Default::default()
+                }),
+            },
+        );
+        assert_eq!(
+            port.get_property(PropertyIdentifier::OperationalCertificateFile)
+                .unwrap(),
+            PropertyValue::ObjectIdentifier(file(1))
+        );
+        assert_eq!(
+            port.get_property(PropertyIdentifier::IssuerCertificateFiles)
+                .unwrap(),
+            PropertyValue::Array(vec![
+                PropertyValue::ObjectIdentifier(file(2)),
+                PropertyValue::ObjectIdentifier(file(3)),
+            ])
+        );
+        assert_eq!(
+            port.get_property(PropertyIdentifier::CertificateSigningRequestFile)
+                .unwrap(),
+            PropertyValue::Null
+        );
+        assert!(!port.is_property_writable(PropertyIdentifier::OperationalCertificateFile));
+        assert!(port
+            .property_list()
+            .contains(&PropertyIdentifier::IssuerCertificateFiles));
+    }
+}
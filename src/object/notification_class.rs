@@ -0,0 +1,555 @@
+//! Notification Class Object Type Implementation
+//!
+//! This module implements the Notification Class object type as defined in ASHRAE 135.
+//! A Notification Class holds the priorities and acknowledgment requirements for each
+//! event transition, and the Recipient_List the event subsystem fans notifications out to.
+
+use crate::object::schedule::time_key;
+use crate::object::{
+    BacnetObject, Date, EventState, ObjectError, ObjectIdentifier, ObjectType, PropertyIdentifier,
+    PropertyValue, Result, Time,
+};
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+/// Event state transition (BACnetEventTransitionBits order)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum EventTransition {
+    ToOffnormal = 0,
+    ToFault = 1,
+    ToNormal = 2,
+}
+
+impl EventTransition {
+    /// The transition that enters `state`
+    pub fn for_state(state: EventState) -> Self {
+        match state {
+            EventState::Normal => EventTransition::ToNormal,
+            EventState::Fault => EventTransition::ToFault,
+            _ => EventTransition::ToOffnormal,
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Where a notification is sent (BACnetRecipient)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Recipient {
+    /// A device, resolved to an address through Who-Is
+    Device(ObjectIdentifier),
+    /// A network address
+    Address {
+        /// Network number (0 = local network, 0xFFFF = broadcast)
+        network: u16,
+        /// MAC address on that network
+        mac_address: Vec<u8>,
+    },
+}
+
+impl Recipient {
+    pub(crate) fn to_property_value(&self) -> PropertyValue {
+        match self {
+            Recipient::Device(device) => PropertyValue::ObjectIdentifier(*device),
+            Recipient::Address {
+                network,
+                mac_address,
+            } => PropertyValue::List(vec![
+                PropertyValue::UnsignedInteger(*network as u32),
+                PropertyValue::OctetString(mac_address.clone()),
+            ]),
+        }
+    }
+
+    pub(crate) fn from_property_value(value: &PropertyValue) -> Result<Self> {
+        match value {
+            PropertyValue::ObjectIdentifier(device) if device.object_type == ObjectType::Device => {
+                Ok(Recipient::Device(*device))
+            }
+            PropertyValue::List(items) => match items.as_slice() {
+                [PropertyValue::UnsignedInteger(network), PropertyValue::OctetString(mac)]
+                    if *network <= u16::MAX as u32 =>
+                {
+                    Ok(Recipient::Address {
+                        network: *network as u16,
+                        mac_address: mac.clone(),
+                    })
+                }
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            _ => Err(ObjectError::InvalidPropertyType),
+        }
+    }
+}
+
+/// A Recipient_List entry (BACnetDestination)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Destination {
+    /// Days the destination is active, Monday first
+    pub valid_days: [bool; 7],
+    /// Start of the daily window
+    pub from_time: Time,
+    /// End of the daily window (inclusive)
+    pub to_time: Time,
+    /// Who receives the notification
+    pub recipient: Recipient,
+    /// Process identifier passed in the notification
+    pub process_identifier: u32,
+    /// Send ConfirmedEventNotification rather than the unconfirmed service
+    pub issue_confirmed_notifications: bool,
+    /// Transitions sent to this destination
+    pub transitions: [bool; 3],
+}
+
+impl Destination {
+    /// A destination that receives every transition at all times
+    pub fn new(recipient: Recipient, process_identifier: u32) -> Self {
+        Self {
+            valid_days: [true; 7],
+            from_time: Time {
+                hour: 0,
+                minute: 0,
+                second: 0,
+                hundredths: 0,
+            },
+            to_time: Time {
+                hour: 23,
+                minute: 59,
+                second: 59,
+                hundredths: 99,
+            },
+            recipient,
+            process_identifier,
+            issue_confirmed_notifications: false,
+            transitions: [true; 3],
+        }
+    }
+
+    /// Whether a notification for `transition` at this date and time goes here
+    pub fn accepts(&self, transition: EventTransition, date: &Date, time: &Time) -> bool {
+        let day_valid = match date.weekday {
+            day @ 1..=7 => self.valid_days[(day - 1) as usize],
+            _ => true,
+        };
+        let now = time_key(time);
+        day_valid
+            && self.transitions[transition.index()]
+            && time_key(&self.from_time) <= now
+            && now <= time_key(&self.to_time)
+    }
+
+    pub(crate) fn to_property_value(&self) -> PropertyValue {
+        PropertyValue::List(vec![
+            PropertyValue::BitString(self.valid_days.to_vec()),
+            PropertyValue::Time(self.from_time),
+            PropertyValue::Time(self.to_time),
+            self.recipient.to_property_value(),
+            PropertyValue::UnsignedInteger(self.process_identifier),
+            PropertyValue::Boolean(self.issue_confirmed_notifications),
+            PropertyValue::BitString(self.transitions.to_vec()),
+        ])
+    }
+
+    pub(crate) fn from_property_value(value: &PropertyValue) -> Result<Self> {
+        let PropertyValue::List(items) = value else {
+            return Err(ObjectError::InvalidPropertyType);
+        };
+        let [days, from, to, recipient, process, confirmed, transitions] = items.as_slice() else {
+            return Err(ObjectError::InvalidPropertyType);
+        };
+        match (days, from, to, process, confirmed, transitions) {
+            (
+                PropertyValue::BitString(days),
+                PropertyValue::Time(from),
+                PropertyValue::Time(to),
+                PropertyValue::UnsignedInteger(process),
+                PropertyValue::Boolean(confirmed),
+                PropertyValue::BitString(transitions),
+            ) => Ok(Self {
+                valid_days: bits(days)?,
+                from_time: *from,
+                to_time: *to,
+                recipient: Recipient::from_property_value(recipient)?,
+                process_identifier: *process,
+                issue_confirmed_notifications: *confirmed,
+                transitions: bits(transitions)?,
+            }),
+            _ => Err(ObjectError::InvalidPropertyType),
+        }
+    }
+
+    /// Regroup destinations that were encoded back to back into one value each
+    ///
+    /// A destination is seven values, or eight when its recipient is an
+    /// address (network number and MAC address). Values that are already
+    /// grouped pass through.
+    pub(crate) fn group_values(values: Vec<PropertyValue>) -> Result<Vec<PropertyValue>> {
+        if values
+            .iter()
+            .all(|value| matches!(value, PropertyValue::List(_)))
+        {
+            return Ok(values);
+        }
+        let mut values = values.into_iter();
+        let mut destinations = Vec::new();
+        while let Some(valid_days) = values.next() {
+            let mut fields = vec![valid_days];
+            fields.extend(values.by_ref().take(2));
+            let recipient = match values.next() {
+                Some(PropertyValue::UnsignedInteger(network)) => PropertyValue::List(vec![
+                    PropertyValue::UnsignedInteger(network),
+                    values.next().ok_or(ObjectError::InvalidPropertyType)?,
+                ]),
+                Some(device) => device,
+                None => return Err(ObjectError::InvalidPropertyType),
+            };
+            fields.push(recipient);
+            fields.extend(values.by_ref().take(3));
+            if fields.len() != 7 {
+                return Err(ObjectError::InvalidPropertyType);
+            }
+            destinations.push(PropertyValue::List(fields));
+        }
+        Ok(destinations)
+    }
+}
+
+/// Convert a bit string into a fixed number of flags
+fn bits<const N: usize>(value: &[bool]) -> Result<[bool; N]> {
+    value
+        .try_into()
+        .map_err(|_| ObjectError::InvalidValue(format!("Expected {} bits", N)))
+}
+
+/// Notification Class object
+#[derive(Debug, Clone)]
+pub struct NotificationClass {
+    /// Object identifier
+    pub identifier: ObjectIdentifier,
+    /// Object name
+    pub object_name: String,
+    /// Description
+    pub description: String,
+    /// Priorities for to-offnormal, to-fault and to-normal notifications
+    pub priority: [u8; 3],
+    /// Whether each transition requires acknowledgment
+    pub ack_required: [bool; 3],
+    /// Notification destinations
+    pub recipient_list: Vec<Destination>,
+}
+
+impl NotificationClass {
+    /// Create a new Notification Class; the instance is the class number
+    pub fn new(instance: u32, object_name: String) -> Self {
+        Self {
+            identifier: ObjectIdentifier::new(ObjectType::NotificationClass, instance),
+            object_name,
+            description: String::new(),
+            priority: [255; 3],
+            ack_required: [false; 3],
+            recipient_list: Vec::new(),
+        }
+    }
+
+    /// Notification class number used by event-initiating objects
+    pub fn notification_class(&self) -> u32 {
+        self.identifier.instance
+    }
+
+    /// Event priority for a transition
+    pub fn priority_for(&self, transition: EventTransition) -> u8 {
+        self.priority[transition.index()]
+    }
+
+    /// Whether a transition must be acknowledged
+    pub fn is_ack_required(&self, transition: EventTransition) -> bool {
+        self.ack_required[transition.index()]
+    }
+
+    /// Add a destination to the Recipient_List
+    pub fn add_recipient(&mut self, destination: Destination) {
+        if !self.recipient_list.contains(&destination) {
+            self.recipient_list.push(destination);
+        }
+    }
+
+    /// Destinations a notification for `transition` must be sent to
+    pub fn recipients_for<'a>(
+        &'a self,
+        transition: EventTransition,
+        date: &'a Date,
+        time: &'a Time,
+    ) -> impl Iterator<Item = &'a Destination> + 'a {
+        self.recipient_list
+            .iter()
+            .filter(move |destination| destination.accepts(transition, date, time))
+    }
+}
+
+impl BacnetObject for NotificationClass {
+    fn identifier(&self) -> ObjectIdentifier {
+        self.identifier
+    }
+
+    fn get_property(&self, property: PropertyIdentifier) -> Result<PropertyValue> {
+        match property {
+            PropertyIdentifier::ObjectIdentifier => {
+                Ok(PropertyValue::ObjectIdentifier(self.identifier))
+            }
+            PropertyIdentifier::ObjectName => {
+                Ok(PropertyValue::CharacterString(self.object_name.clone()))
+            }
+            PropertyIdentifier::ObjectType => Ok(PropertyValue::Enumerated(u32::from(
+                ObjectType::NotificationClass,
+            ))),
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::NotificationClass => {
+                Ok(PropertyValue::UnsignedInteger(self.notification_class()))
+            }
+            PropertyIdentifier::Priority => Ok(PropertyValue::Array(
+                self.priority
+                    .iter()
+                    .map(|&p| PropertyValue::UnsignedInteger(p as u32))
+                    .collect(),
+            )),
+            PropertyIdentifier::AckRequired => {
+                Ok(PropertyValue::BitString(self.ack_required.to_vec()))
+            }
+            PropertyIdentifier::RecipientList => Ok(PropertyValue::List(
+                self.recipient_list
+                    .iter()
+                    .map(Destination::to_property_value)
+                    .collect(),
+            )),
+            _ => Err(ObjectError::UnknownProperty),
+        }
+    }
+
+    fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        match property {
+            PropertyIdentifier::ObjectName => {
+                if let PropertyValue::CharacterString(name) = value {
+                    self.object_name = name;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Priority => {
+                let PropertyValue::Array(values) = value else {
+                    return Err(ObjectError::InvalidPropertyType);
+                };
+                if values.len() != 3 {
+                    return Err(ObjectError::InvalidValue(
+                        "Priority must have 3 entries".to_string(),
+                    ));
+                }
+                let mut priority = [0u8; 3];
+                for (slot, value) in priority.iter_mut().zip(values.iter()) {
+                    match value {
+                        PropertyValue::UnsignedInteger(p) if *p <= 255 => *slot = *p as u8,
+                        PropertyValue::UnsignedInteger(_) => {
+                            return Err(ObjectError::InvalidValue(
+                                "Priority must be 0-255".to_string(),
+                            ))
+                        }
+                        _ => return Err(ObjectError::InvalidPropertyType),
+                    }
+                }
+                self.priority = priority;
+                Ok(())
+            }
+            PropertyIdentifier::AckRequired => {
+                if let PropertyValue::BitString(flags) = value {
+                    self.ack_required = bits(&flags)?;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::RecipientList => {
+                if let PropertyValue::List(entries) = value {
+                    self.recipient_list = entries
+                        .iter()
+                        .map(Destination::from_property_value)
+                        .collect::<Result<Vec<_>>>()?;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            _ => Err(ObjectError::PropertyNotWritable),
+        }
+    }
+
+    fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
+        matches!(
+            property,
+            PropertyIdentifier::ObjectName
+                | PropertyIdentifier::Description
+                | PropertyIdentifier::Priority
+                | PropertyIdentifier::AckRequired
+                | PropertyIdentifier::RecipientList
+        )
+    }
+
+    fn property_list(&self) -> Vec<PropertyIdentifier> {
+        vec![
+            PropertyIdentifier::ObjectIdentifier,
+            PropertyIdentifier::ObjectName,
+            PropertyIdentifier::ObjectType,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::NotificationClass,
+            PropertyIdentifier::Priority,
+            PropertyIdentifier::AckRequired,
+            PropertyIdentifier::RecipientList,
+        ]
+    }
+
+    fn list_elements(
+        &self,
+        property: PropertyIdentifier,
+        values: Vec<PropertyValue>,
+    ) -> Result<Vec<PropertyValue>> {
+        match property {
+            PropertyIdentifier::RecipientList => Destination::group_values(values),
+            _ => Ok(values),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(hour: u8) -> Time {
+        Time {
+            hour,
+            minute: 0,
+            second: 0,
+            hundredths: 0,
+        }
+    }
+
+    fn saturday() -> Date {
+        Date {
+            year: 2024,
+            month: 3,
+            day: 9,
+            weekday: 6,
+        }
+    }
+
+    #[test]
+    fn test_recipient_filtering() {
+        let mut nc = NotificationClass::new(5, "Critical Alarms".to_string());
+        nc.priority = [10, 20, 200];
+        nc.ack_required = [true, true, false];
+
+        let workstation = Destination::new(
+            Recipient::Device(ObjectIdentifier::new(ObjectType::Device, 100)),
+            1,
+        );
+        let mut pager = Destination::new(
+            Recipient::Address {
+                network: 0,
+                mac_address: vec![192, 168, 1, 20, 0xBA, 0xC0],
+            },
+            2,
+        );
+        pager.valid_days = [true, true, true, true, true, false, false];
+        pager.from_time = time(8);
+        pager.to_time = time(17);
+        pager.transitions = [true, true, false];
+        nc.add_recipient(workstation.clone());
+        nc.add_recipient(pager.clone());
+        nc.add_recipient(workstation);
+        assert_eq!(nc.recipient_list.len(), 2);
+
+        assert_eq!(nc.notification_class(), 5);
+        assert_eq!(nc.priority_for(EventTransition::ToFault), 20);
+        assert!(!nc.is_ack_required(EventTransition::ToNormal));
+
+        let monday = Date {
+            weekday: 1,
+            ..saturday()
+        };
+        assert_eq!(
+            nc.recipients_for(EventTransition::ToOffnormal, &monday, &time(9))
+                .count(),
+            2
+        );
+        assert_eq!(
+            nc.recipients_for(EventTransition::ToNormal, &monday, &time(9))
+                .count(),
+            1
+        );
+        assert_eq!(
+            nc.recipients_for(EventTransition::ToOffnormal, &saturday(), &time(9))
+                .count(),
+            1
+        );
+        assert_eq!(
+            nc.recipients_for(EventTransition::ToOffnormal, &monday, &time(18))
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_notification_class_properties() {
+        let mut nc = NotificationClass::new(1, "NC".to_string());
+        let mut destination = Destination::new(
+            Recipient::Address {
+                network: 5,
+                mac_address: vec![0x0A],
+            },
+            7,
+        );
+        destination.issue_confirmed_notifications = true;
+        nc.recipient_list.push(destination.clone());
+
+        let list = nc.get_property(PropertyIdentifier::RecipientList).unwrap();
+        nc.recipient_list.clear();
+        nc.set_property(PropertyIdentifier::RecipientList, list)
+            .unwrap();
+        assert_eq!(nc.recipient_list, vec![destination]);
+
+        nc.set_property(
+            PropertyIdentifier::Priority,
+            PropertyValue::Array(vec![
+                PropertyValue::UnsignedInteger(1),
+                PropertyValue::UnsignedInteger(2),
+                PropertyValue::UnsignedInteger(3),
+            ]),
+        )
+        .unwrap();
+        assert_eq!(nc.priority, [1, 2, 3]);
+        assert!(nc
+            .set_property(
+                PropertyIdentifier::AckRequired,
+                PropertyValue::BitString(vec![true])
+            )
+            .is_err());
+        assert!(nc
+            .set_property(
+                PropertyIdentifier::NotificationClass,
+                PropertyValue::UnsignedInteger(2)
+            )
+            .is_err());
+    }
+}
@@ -0,0 +1,543 @@
+//! Notification Forwarder Object Type Implementation
+//!
+//! This module implements the Notification Forwarder object type as defined in
+//! ASHRAE 135. A Notification Forwarder re-distributes the event notifications a
+//! device receives to its own Recipient_List and Subscribed_Recipients, so that
+//! devices with small recipient lists can delegate distribution to a larger one.
+//!
+//! A received notification is forwarded only if it passes the filters:
+//!
+//! - **Process_Identifier_Filter**: when set, the notification's process identifier
+//!   must match
+//! - **Port_Filter**: when present, the port the notification arrived on must be
+//!   listed and enabled
+//! - **Local_Forwarding_Only**: when set, only notifications that originated in
+//!   this device are forwarded
+//!
+//! [`NotificationForwarder::forward`] returns the recipients; the application
+//! re-sends the notification to each with the given process identifier.
+//! Subscription lifetimes count down through [`BacnetObject::advance_time`].
+
+use crate::object::{
+    current_status_flags, status_flags_bit_string, BacnetObject, Date, Destination, EventState,
+    EventTransition, ObjectError, ObjectIdentifier, ObjectType, PropertyIdentifier, PropertyValue,
+    Recipient, Reliability, Result, Time,
+};
+use core::time::Duration;
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+/// Temporary recipient added to Subscribed_Recipients
+/// (BACnetEventNotificationSubscription)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventNotificationSubscription {
+    /// Who receives the notifications
+    pub recipient: Recipient,
+    /// Process identifier passed in the notifications
+    pub process_identifier: u32,
+    /// Send ConfirmedEventNotification rather than the unconfirmed service
+    pub issue_confirmed_notifications: bool,
+    /// Seconds until the subscription lapses
+    pub time_remaining: u32,
+}
+
+impl EventNotificationSubscription {
+    fn to_property_value(&self) -> PropertyValue {
+        PropertyValue::List(vec![
+            self.recipient.to_property_value(),
+            PropertyValue::UnsignedInteger(self.process_identifier),
+            PropertyValue::Boolean(self.issue_confirmed_notifications),
+            PropertyValue::UnsignedInteger(self.time_remaining),
+        ])
+    }
+
+    fn from_property_value(value: &PropertyValue) -> Result<Self> {
+        let PropertyValue::List(items) = value else {
+            return Err(ObjectError::InvalidPropertyType);
+        };
+        let [recipient, process, confirmed, time_remaining] = items.as_slice() else {
+            return Err(ObjectError::InvalidPropertyType);
+        };
+        match (process, confirmed, time_remaining) {
+            (
+                PropertyValue::UnsignedInteger(process),
+                PropertyValue::Boolean(confirmed),
+                PropertyValue::UnsignedInteger(time_remaining),
+            ) => Ok(Self {
+                recipient: Recipient::from_property_value(recipient)?,
+                process_identifier: *process,
+                issue_confirmed_notifications: *confirmed,
+                time_remaining: *time_remaining,
+            }),
+            _ => Err(ObjectError::InvalidPropertyType),
+        }
+    }
+}
+
+/// Whether notifications received on a port are forwarded (BACnetPortPermission)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PortPermission {
+    /// Network Port object instance of the port
+    pub port_id: u8,
+    /// Whether notifications from the port are forwarded
+    pub enabled: bool,
+}
+
+impl PortPermission {
+    fn to_property_value(self) -> PropertyValue {
+        PropertyValue::List(vec![
+            PropertyValue::UnsignedInteger(self.port_id as u32),
+            PropertyValue::Boolean(self.enabled),
+        ])
+    }
+
+    fn from_property_value(value: &PropertyValue) -> Result<Self> {
+        match value {
+            PropertyValue::List(items) => match items.as_slice() {
+                [PropertyValue::UnsignedInteger(port), PropertyValue::Boolean(enabled)]
+                    if *port <= u8::MAX as u32 =>
+                {
+                    Ok(Self {
+                        port_id: *port as u8,
+                        enabled: *enabled,
+                    })
+                }
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            _ => Err(ObjectError::InvalidPropertyType),
+        }
+    }
+}
+
+/// What the forwarder needs to know about a received event notification
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReceivedNotification {
+    /// Process identifier the notification was addressed to
+    pub process_identifier: u32,
+    /// Transition the notification reports
+    pub transition: EventTransition,
+    /// Port the notification arrived on
+    pub port_id: u8,
+    /// Whether the notification originated in this device
+    pub from_local_device: bool,
+}
+
+/// A recipient a notification is forwarded to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForwardTarget {
+    /// Who receives the notification
+    pub recipient: Recipient,
+    /// Process identifier to send the notification with
+    pub process_identifier: u32,
+    /// Send ConfirmedEventNotification rather than the unconfirmed service
+    pub issue_confirmed_notifications: bool,
+}
+
+/// Notification Forwarder object
+#[derive(Debug, Clone)]
+pub struct NotificationForwarder {
+    /// Object identifier
+    pub identifier: ObjectIdentifier,
+    /// Object name
+    pub object_name: String,
+    /// Description
+    pub description: String,
+    /// Reliability
+    pub reliability: Reliability,
+    /// Out of service; nothing is forwarded while set
+    pub out_of_service: bool,
+    /// Permanent destinations
+    pub recipient_list: Vec<Destination>,
+    /// Temporary destinations
+    pub subscribed_recipients: Vec<EventNotificationSubscription>,
+    /// Process identifier a notification must carry; `None` forwards any
+    pub process_identifier_filter: Option<u32>,
+    /// Ports whose notifications are forwarded; `None` forwards from every port
+    pub port_filter: Option<Vec<PortPermission>>,
+    /// Forward only notifications that originated in this device
+    pub local_forwarding_only: bool,
+    subscription_elapsed: Duration,
+}
+
+impl NotificationForwarder {
+    /// Create a new Notification Forwarder with empty recipient lists
+    pub fn new(instance: u32, object_name: String) -> Self {
+        Self {
+            identifier: ObjectIdentifier::new(ObjectType::NotificationForwarder, instance),
+            object_name,
+            description: String::new(),
+            reliability: Reliability::NoFaultDetected,
+            out_of_service: false,
+            recipient_list: Vec::new(),
+            subscribed_recipients: Vec::new(),
+            process_identifier_filter: None,
+            port_filter: None,
+            local_forwarding_only: false,
+            subscription_elapsed: Duration::ZERO,
+        }
+    }
+
+    /// Add a destination to the Recipient_List
+    pub fn add_recipient(&mut self, destination: Destination) {
+        if !self.recipient_list.contains(&destination) {
+            self.recipient_list.push(destination);
+        }
+    }
+
+    /// Add or renew a subscription
+    ///
+    /// A subscription with the same recipient and process identifier is
+    /// replaced.
+    pub fn subscribe(&mut self, subscription: EventNotificationSubscription) {
+        self.subscribed_recipients.retain(|existing| {
+            existing.recipient != subscription.recipient
+                || existing.process_identifier != subscription.process_identifier
+        });
+        self.subscribed_recipients.push(subscription);
+    }
+
+    /// Whether a received notification passes the forwarding filters
+    pub fn accepts(&self, notification: &ReceivedNotification) -> bool {
+        let process = self
+            .process_identifier_filter
+            .is_none_or(|filter| filter == notification.process_identifier);
+        let port = self.port_filter.as_ref().is_none_or(|ports| {
+            ports
+                .iter()
+                .any(|p| p.port_id == notification.port_id && p.enabled)
+        });
+        let origin = !self.local_forwarding_only || notification.from_local_device;
+        !self.out_of_service && process && port && origin
+    }
+
+    /// Recipients a received notification is forwarded to at this date and time
+    pub fn forward(
+        &self,
+        notification: &ReceivedNotification,
+        date: &Date,
+        time: &Time,
+    ) -> Vec<ForwardTarget> {
+        if !self.accepts(notification) {
+            return Vec::new();
+        }
+        let permanent = self
+            .recipient_list
+            .iter()
+            .filter(|destination| destination.accepts(notification.transition, date, time))
+            .map(|destination| ForwardTarget {
+                recipient: destination.recipient.clone(),
+                process_identifier: destination.process_identifier,
+                issue_confirmed_notifications: destination.issue_confirmed_notifications,
+            });
+        let subscribed = self
+            .subscribed_recipients
+            .iter()
+            .map(|subscription| ForwardTarget {
+                recipient: subscription.recipient.clone(),
+                process_identifier: subscription.process_identifier,
+                issue_confirmed_notifications: subscription.issue_confirmed_notifications,
+            });
+        let mut targets: Vec<ForwardTarget> = Vec::new();
+        for target in permanent.chain(subscribed) {
+            if !targets.contains(&target) {
+                targets.push(target);
+            }
+        }
+        targets
+    }
+
+    fn current_status_flags(&self) -> u8 {
+        current_status_flags(0, EventState::Normal, self.reliability, self.out_of_service)
+    }
+}
+
+impl BacnetObject for NotificationForwarder {
+    fn identifier(&self) -> ObjectIdentifier {
+        self.identifier
+    }
+
+    fn get_property(&self, property: PropertyIdentifier) -> Result<PropertyValue> {
+        match property {
+            PropertyIdentifier::ObjectIdentifier => {
+                Ok(PropertyValue::ObjectIdentifier(self.identifier))
+            }
+            PropertyIdentifier::ObjectName => {
+                Ok(PropertyValue::CharacterString(self.object_name.clone()))
+            }
+            PropertyIdentifier::ObjectType => Ok(PropertyValue::Enumerated(u32::from(
+                ObjectType::NotificationForwarder,
+            ))),
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::StatusFlags => {
+                Ok(status_flags_bit_string(self.current_status_flags()))
+            }
+            PropertyIdentifier::Reliability => {
+                Ok(PropertyValue::Enumerated(self.reliability as u32))
+            }
+            PropertyIdentifier::OutOfService => Ok(PropertyValue::Boolean(self.out_of_service)),
+            PropertyIdentifier::RecipientList => Ok(PropertyValue::List(
+                self.recipient_list
+                    .iter()
+                    .map(Destination::to_property_value)
+                    .collect(),
+            )),
+            PropertyIdentifier::SubscribedRecipients => Ok(PropertyValue::List(
+                self.subscribed_recipients
+                    .iter()
+                    .map(EventNotificationSubscription::to_property_value)
+                    .collect(),
+            )),
+            PropertyIdentifier::ProcessIdentifierFilter => Ok(self
+                .process_identifier_filter
+                .map(PropertyValue::UnsignedInteger)
+                .unwrap_or(PropertyValue::Null)),
+            PropertyIdentifier::PortFilter => self
+                .port_filter
+                .as_ref()
+                .map(|ports| {
+                    PropertyValue::Array(
+                        ports.iter().map(|port| port.to_property_value()).collect(),
+                    )
+                })
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::LocalForwardingOnly => {
+                Ok(PropertyValue::Boolean(self.local_forwarding_only))
+            }
+            _ => Err(ObjectError::UnknownProperty),
+        }
+    }
+
+    fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        match property {
+            PropertyIdentifier::ObjectName => {
+                if let PropertyValue::CharacterString(name) = value {
+                    self.object_name = name;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::OutOfService => {
+                if let PropertyValue::Boolean(oos) = value {
+                    self.out_of_service = oos;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::RecipientList => match value {
+                PropertyValue::List(items) => {
+                    self.recipient_list = items
+                        .iter()
+                        .map(Destination::from_property_value)
+                        .collect::<Result<Vec<_>>>()?;
+                    Ok(())
+                }
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            PropertyIdentifier::SubscribedRecipients => match value {
+                PropertyValue::List(items) => {
+                    self.subscribed_recipients = items
+                        .iter()
+                        .map(EventNotificationSubscription::from_property_value)
+                        .collect::<Result<Vec<_>>>()?;
+                    Ok(())
+                }
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            PropertyIdentifier::ProcessIdentifierFilter => match value {
+                PropertyValue::Null => {
+                    self.process_identifier_filter = None;
+                    Ok(())
+                }
+                PropertyValue::UnsignedInteger(process) => {
+                    self.process_identifier_filter = Some(process);
+                    Ok(())
+                }
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            PropertyIdentifier::PortFilter => match value {
+                PropertyValue::Array(items) if self.port_filter.is_some() => {
+                    self.port_filter = Some(
+                        items
+                            .iter()
+                            .map(PortPermission::from_property_value)
+                            .collect::<Result<Vec<_>>>()?,
+                    );
+                    Ok(())
+                }
+                PropertyValue::Array(_) => Err(ObjectError::UnknownProperty),
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            PropertyIdentifier::LocalForwardingOnly => {
+                if let PropertyValue::Boolean(local) = value {
+                    self.local_forwarding_only = local;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            _ => Err(ObjectError::PropertyNotWritable),
+        }
+    }
+
+    fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
+        matches!(
+            property,
+            PropertyIdentifier::ObjectName
+                | PropertyIdentifier::Description
+                | PropertyIdentifier::OutOfService
+                | PropertyIdentifier::RecipientList
+                | PropertyIdentifier::SubscribedRecipients
+                | PropertyIdentifier::ProcessIdentifierFilter
+                | PropertyIdentifier::LocalForwardingOnly
+        ) || (property == PropertyIdentifier::PortFilter && self.port_filter.is_some())
+    }
+
+    fn property_list(&self) -> Vec<PropertyIdentifier> {
+        let mut properties = vec![
+            PropertyIdentifier::ObjectIdentifier,
+            PropertyIdentifier::ObjectName,
+            PropertyIdentifier::ObjectType,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::StatusFlags,
+            PropertyIdentifier::Reliability,
+            PropertyIdentifier::OutOfService,
+            PropertyIdentifier::RecipientList,
+            PropertyIdentifier::SubscribedRecipients,
+            PropertyIdentifier::ProcessIdentifierFilter,
+        ];
+        if self.port_filter.is_some() {
+            properties.push(PropertyIdentifier::PortFilter);
+        }
+        properties.push(PropertyIdentifier::LocalForwardingOnly);
+        properties
+    }
+
+    fn list_elements(
+        &self,
+        property: PropertyIdentifier,
+        values: Vec<PropertyValue>,
+    ) -> Result<Vec<PropertyValue>> {
+        match property {
+            PropertyIdentifier::RecipientList => Destination::group_values(values),
+            _ => Ok(values),
+        }
+    }
+
+    fn advance_time(&mut self, elapsed: Duration) {
+        self.subscription_elapsed += elapsed;
+        let seconds = self.subscription_elapsed.as_secs();
+        if seconds == 0 {
+            return;
+        }
+        self.subscription_elapsed -= Duration::from_secs(seconds);
+        let seconds = u32::try_from(seconds).unwrap_or(u32::MAX);
+        for subscription in &mut self.subscribed_recipients {
+            subscription.time_remaining = subscription.time_remaining.saturating_sub(seconds);
+        }
+        self.subscribed_recipients
+            .retain(|subscription| subscription.time_remaining > 0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monday_noon() -> (Date, Time) {
+        (
+            Date {
+                year: 2024,
+                month: 9,
+                day: 2,
+                weekday: 1,
+            },
+            Time {
+                hour: 12,
+                minute: 0,
+                second: 0,
+                hundredths: 0,
+            },
+        )
+    }
+
+    fn received(process_identifier: u32, port_id: u8) -> ReceivedNotification {
+        ReceivedNotification {
+            process_identifier,
+            transition: EventTransition::ToOffnormal,
+            port_id,
+            from_local_device: false,
+        }
+    }
+
+    #[test]
+    fn test_forwarder_filters() {
+        let (date, time) = monday_noon();
+        let mut forwarder = NotificationForwarder::new(1, "Forwarder".to_string());
+        forwarder.add_recipient(Destination::new(
+            Recipient::Device(ObjectIdentifier::new(ObjectType::Device, 10)),
+            7,
+        ));
+        assert_eq!(forwarder.forward(&received(1, 1), &date, &time).len(), 1);
+
+        forwarder
+            .set_property(
+                PropertyIdentifier::ProcessIdentifierFilter,
+                PropertyValue::UnsignedInteger(3),
+            )
+            .unwrap();
+        assert!(forwarder.forward(&received(1, 1), &date, &time).is_empty());
+        let targets = forwarder.forward(&received(3, 1), &date, &time);
+        assert_eq!(targets[0].process_identifier, 7);
+
+        forwarder.port_filter = Some(vec![
+            PortPermission {
+                port_id: 1,
+                enabled: false,
+            },
+            PortPermission {
+                port_id: 2,
+                enabled: true,
+            },
+        ]);
+        assert!(forwarder.forward(&received(3, 1), &date, &time).is_empty());
+        assert!(forwarder.forward(&received(3, 3), &date, &time).is_empty());
+        assert_eq!(forwarder.forward(&received(3, 2), &date, &time).len(), 1);
+
+        forwarder.local_forwarding_only = true;
+        assert!(forwarder.forward(&received(3, 2), &date, &time).is_empty());
+    }
+
+    #[test]
+    fn test_subscriptions_lapse() {
+        let (date, time) = monday_noon();
+        let mut forwarder = NotificationForwarder::new(1, "Forwarder".to_string());
+        let subscription = EventNotificationSubscription {
+            recipient: Recipient::Device(ObjectIdentifier::new(ObjectType::Device, 20)),
+            process_identifier: 1,
+            issue_confirmed_notifications: true,
+            time_remaining: 60,
+        };
+        forwarder.subscribe(subscription.clone());
+        forwarder.subscribe(subscription);
+        assert_eq!(forwarder.subscribed_recipients.len(), 1);
+        assert!(forwarder.forward(&received(1, 1), &date, &time)[0].issue_confirmed_notifications);
+
+        forwarder.advance_time(Duration::from_secs(30));
+        assert_eq!(forwarder.subscribed_recipients[0].time_remaining, 30);
+        forwarder.advance_time(Duration::from_secs(30));
+        assert!(forwarder.subscribed_recipients.is_empty());
+    }
+}
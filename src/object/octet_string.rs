@@ -1,10 +1,13 @@
 //! Octet String Value Object Type Implementation
 //!
-//! This module implements the Octet String Value object type as defined in ASHRAE 135
+//! This module implements the Octet String Value object type as defined in ASHRAE 135.
+//! Present_Value is maintained by the application and can only be written over the
+//! network while the object is out of service. The `BacnetObject` implementation is
+//! generated by [`bacnet_object!`](crate::bacnet_object).
 
 use crate::object::{
-    BacnetObject, ObjectError, ObjectIdentifier, ObjectType, PropertyIdentifier, PropertyValue,
-    Result,
+    current_status_flags, status_flags_bit_string, EventState, ObjectError, ObjectIdentifier,
+    ObjectType, PropertyValue, Reliability, Result,
 };
 
 #[cfg(not(feature = "std"))]
@@ -37,25 +40,35 @@ impl BoundedVec {
     // pub fn as_slice(&self) -> &[u8] {
     //     &self.inner
     // }
-
-    pub fn len(&self) -> usize {
-        self.inner.len()
-    }
 }
 
-/// Octet String Value object
-#[derive(Debug, Clone)]
-pub struct OctetString {
-    /// Object identifier R
-    pub identifier: ObjectIdentifier,
-    /// Object name R
-    pub object_name: String,
-    /// Description O
-    pub description: String,
-    /// Present value R (required to be writeable when out_of_service is true)
-    pub present_value: Vec<u8>,
-    /// Status flags R
-    pub status_flags: u8,
+crate::bacnet_object! {
+    /// Octet String Value object
+    #[derive(Debug, Clone)]
+    pub struct OctetString {
+        /// Object identifier R
+        #[bacnet(identifier)]
+        pub identifier: ObjectIdentifier,
+        /// Object name R
+        #[bacnet(ObjectName, writable)]
+        pub object_name: String,
+        /// Present value R (required to be writeable when out_of_service is true)
+        #[bacnet(
+            PresentValue,
+            write = Self::write_present_value,
+            writable_if = Self::is_out_of_service
+        )]
+        pub present_value: Vec<u8>,
+        /// Description O
+        #[bacnet(Description, writable)]
+        pub description: String,
+        /// Status flags R
+        #[bacnet(StatusFlags, read = Self::status_flags_value)]
+        pub status_flags: u8,
+        /// Out of service O
+        #[bacnet(OutOfService, writable)]
+        pub out_of_service: bool,
+    }
 }
 
 impl OctetString {
@@ -67,6 +80,7 @@ impl OctetString {
             description: String::new(),
             present_value: Vec::new(),
             status_flags: 0,
+            out_of_service: false,
         }
     }
 
@@ -80,6 +94,55 @@ impl OctetString {
         Ok(())
     }
 
+    /// Update the present value from the application
+    ///
+    /// While the object is out of service the present value is decoupled from
+    /// the application, so the update is ignored.
+    pub fn update_from_application(
+        &mut self,
+        value: Vec<u8>,
+    ) -> std::result::Result<(), BoundedVecError> {
+        if self.out_of_service {
+            return Ok(());
+        }
+        self.set_present_value(value)
+    }
+
+    /// Current Status_Flags, combining the stored flags with Out_Of_Service
+    pub fn current_status_flags(&self) -> u8 {
+        current_status_flags(
+            self.status_flags,
+            EventState::Normal,
+            Reliability::NoFaultDetected,
+            self.out_of_service,
+        )
+    }
+
+    /// Status_Flags as read over the network
+    fn status_flags_value(&self) -> PropertyValue {
+        status_flags_bit_string(self.current_status_flags())
+    }
+
+    /// Present_Value is only writable while decoupled from the application
+    fn is_out_of_service(&self) -> bool {
+        self.out_of_service
+    }
+
+    /// Write Present_Value over the network, within the size limit
+    fn write_present_value(&mut self, value: &PropertyValue) -> Result<()> {
+        let PropertyValue::OctetString(data) = value else {
+            return Err(ObjectError::InvalidPropertyType);
+        };
+        self.set_present_value(data.clone()).map_err(
+            |BoundedVecError::OversizeData { len, max_len }| {
+                ObjectError::InvalidValue(format!(
+                    "Octet string of {} bytes exceeds {} bytes",
+                    len, max_len
+                ))
+            },
+        )
+    }
+
     pub fn get_status_flags(&self) -> (bool, bool, bool, bool) {
         (
             (self.status_flags & 0x08) != 0, // in_alarm
@@ -112,60 +175,10 @@ impl OctetString {
     }
 }
 
-impl BacnetObject for OctetString {
-    fn identifier(&self) -> ObjectIdentifier {
-        self.identifier
-    }
-
-    fn get_property(&self, property: PropertyIdentifier) -> Result<PropertyValue> {
-        match property {
-            PropertyIdentifier::ObjectIdentifier => {
-                Ok(PropertyValue::ObjectIdentifier(self.identifier))
-            }
-            PropertyIdentifier::ObjectName => {
-                Ok(PropertyValue::CharacterString(self.object_name.clone()))
-            }
-            PropertyIdentifier::ObjectType => {
-                Ok(PropertyValue::Enumerated(ObjectType::OctetString as u32))
-            }
-            PropertyIdentifier::PresentValue => {
-                Ok(PropertyValue::OctetString(self.present_value.clone()))
-            }
-            _ => Err(ObjectError::UnknownProperty),
-        }
-    }
-
-    fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
-        match property {
-            PropertyIdentifier::ObjectName => {
-                if let PropertyValue::CharacterString(name) = value {
-                    self.object_name = name;
-                    Ok(())
-                } else {
-                    Err(ObjectError::InvalidPropertyType)
-                }
-            }
-            _ => Err(ObjectError::PropertyNotWritable),
-        }
-    }
-
-    fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
-        matches!(property, PropertyIdentifier::ObjectName)
-    }
-
-    fn property_list(&self) -> Vec<PropertyIdentifier> {
-        vec![
-            PropertyIdentifier::ObjectIdentifier,
-            PropertyIdentifier::ObjectName,
-            PropertyIdentifier::ObjectType,
-            PropertyIdentifier::PresentValue,
-        ]
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::object::{BacnetObject, PropertyIdentifier};
 
     #[test]
     fn test_octet_string_creation() {
@@ -198,4 +211,101 @@ mod tests {
         let data = vec![1; MAX_OCTET_STRING_SIZE + 1];
         assert!(octet_string.set_present_value(data.clone()).is_err());
     }
+
+    #[test]
+    fn test_octet_string_out_of_service() {
+        let mut octet_string = OctetString::new(1, "test".to_string());
+        assert!(matches!(
+            octet_string.set_property(
+                PropertyIdentifier::PresentValue,
+                PropertyValue::OctetString(vec![1])
+            ),
+            Err(ObjectError::WriteAccessDenied)
+        ));
+
+        octet_string
+            .set_property(
+                PropertyIdentifier::OutOfService,
+                PropertyValue::Boolean(true),
+            )
+            .unwrap();
+        assert!(octet_string.is_property_writable(PropertyIdentifier::PresentValue));
+        assert_eq!(
+            octet_string
+                .get_property(PropertyIdentifier::StatusFlags)
+                .unwrap(),
+            PropertyValue::BitString(vec![false, false, false, true])
+        );
+
+        octet_string
+            .set_property(
+                PropertyIdentifier::PresentValue,
+                PropertyValue::OctetString(vec![9, 9]),
+            )
+            .unwrap();
+        // The application no longer drives Present_Value
+        octet_string.update_from_application(vec![1, 2]).unwrap();
+        assert_eq!(octet_string.present_value, vec![9, 9]);
+        assert!(matches!(
+            octet_string.set_property(
+                PropertyIdentifier::PresentValue,
+                PropertyValue::OctetString(vec![0; MAX_OCTET_STRING_SIZE + 1])
+            ),
+            Err(ObjectError::InvalidValue(_))
+        ));
+    }
+
+    #[test]
+    fn test_octet_string_generated_properties() {
+        let mut octet_string = OctetString::new(4, "Raw".to_string());
+        assert_eq!(
+            octet_string.property_list(),
+            vec![
+                PropertyIdentifier::ObjectIdentifier,
+                PropertyIdentifier::ObjectType,
+                PropertyIdentifier::ObjectName,
+                PropertyIdentifier::PresentValue,
+                PropertyIdentifier::Description,
+                PropertyIdentifier::StatusFlags,
+                PropertyIdentifier::OutOfService,
+            ]
+        );
+        assert_eq!(
+            octet_string
+                .get_property(PropertyIdentifier::ObjectType)
+                .unwrap(),
+            PropertyValue::Enumerated(u32::from(ObjectType::OctetString))
+        );
+
+        octet_string
+            .set_property(
+                PropertyIdentifier::Description,
+                PropertyValue::CharacterString("Modbus block".to_string()),
+            )
+            .unwrap();
+        assert_eq!(
+            octet_string
+                .get_property(PropertyIdentifier::Description)
+                .unwrap(),
+            PropertyValue::CharacterString("Modbus block".to_string())
+        );
+        assert!(matches!(
+            octet_string.set_property(PropertyIdentifier::Description, PropertyValue::Real(1.0)),
+            Err(ObjectError::InvalidPropertyType)
+        ));
+        assert!(matches!(
+            octet_string.set_property(
+                PropertyIdentifier::StatusFlags,
+                PropertyValue::BitString(vec![false; 4])
+            ),
+            Err(ObjectError::PropertyNotWritable)
+        ));
+        assert!(!octet_string.is_property_writable(PropertyIdentifier::PresentValue));
+        assert!(!octet_string.is_property_writable(PropertyIdentifier::StatusFlags));
+        assert!(octet_string.is_property_writable(PropertyIdentifier::OutOfService));
+        assert!(matches!(
+            octet_string.get_property(PropertyIdentifier::Units),
+            Err(ObjectError::UnknownProperty)
+        ));
+    }
 }
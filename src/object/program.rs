@@ -0,0 +1,512 @@
+//! Program Object Type Implementation
+//!
+//! This module implements the Program object type as defined in ASHRAE 135. The object
+//! exposes the lifecycle of an application program to BACnet: writes to Program_Change
+//! are validated against Program_State and forwarded to a [`ProgramHandler`] supplied by
+//! the application, whose outcome drives Program_State, Reason_For_Halt and
+//! Description_Of_Halt.
+
+use crate::object::{
+    current_status_flags, status_flags_bit_string, BacnetObject, EventState, ObjectError,
+    ObjectIdentifier, ObjectType, PropertyIdentifier, PropertyValue, Reliability, Result,
+};
+use core::fmt;
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+/// Program state enumeration (BACnetProgramState)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum ProgramState {
+    Idle = 0,
+    Loading = 1,
+    Running = 2,
+    Waiting = 3,
+    Halted = 4,
+    Unloading = 5,
+}
+
+/// Program change request enumeration (BACnetProgramRequest)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum ProgramRequest {
+    Ready = 0,
+    Load = 1,
+    Run = 2,
+    Halt = 3,
+    Restart = 4,
+    Unload = 5,
+}
+
+impl TryFrom<u32> for ProgramRequest {
+    type Error = ObjectError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            0 => Ok(ProgramRequest::Ready),
+            1 => Ok(ProgramRequest::Load),
+            2 => Ok(ProgramRequest::Run),
+            3 => Ok(ProgramRequest::Halt),
+            4 => Ok(ProgramRequest::Restart),
+            5 => Ok(ProgramRequest::Unload),
+            _ => Err(ObjectError::InvalidValue(format!(
+                "Invalid program request: {}",
+                value
+            ))),
+        }
+    }
+}
+
+/// Reason a program halted (BACnetProgramError)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum ProgramError {
+    Normal = 0,
+    LoadFailed = 1,
+    Internal = 2,
+    Program = 3,
+    Other = 4,
+}
+
+/// Failure reported by a [`ProgramHandler`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgramHalt {
+    /// Value for Reason_For_Halt
+    pub reason: ProgramError,
+    /// Value for Description_Of_Halt
+    pub description: String,
+}
+
+impl ProgramHalt {
+    /// Create a new halt report
+    pub fn new(reason: ProgramError, description: impl Into<String>) -> Self {
+        Self {
+            reason,
+            description: description.into(),
+        }
+    }
+}
+
+/// Application hooks for the program lifecycle
+///
+/// Each method is called when the matching request is written to Program_Change
+/// and allowed in the current Program_State. Returning an error halts the program
+/// with the given reason.
+pub trait ProgramHandler: Send + Sync {
+    /// Load the program; a loaded program waits in the halted state
+    fn load(&mut self) -> core::result::Result<(), ProgramHalt> {
+        Ok(())
+    }
+
+    /// Begin or resume execution
+    fn run(&mut self) -> core::result::Result<(), ProgramHalt>;
+
+    /// Stop execution, keeping the program loaded
+    fn halt(&mut self) {}
+
+    /// Restart execution from the initialization point
+    fn restart(&mut self) -> core::result::Result<(), ProgramHalt> {
+        self.halt();
+        self.run()
+    }
+
+    /// Stop execution and unload the program
+    fn unload(&mut self) {}
+}
+
+/// Program object
+pub struct Program {
+    /// Object identifier
+    pub identifier: ObjectIdentifier,
+    /// Object name
+    pub object_name: String,
+    /// Description
+    pub description: String,
+    /// Current program state
+    pub program_state: ProgramState,
+    /// Reason for the last halt
+    pub reason_for_halt: ProgramError,
+    /// Description of the last halt
+    pub description_of_halt: String,
+    /// Location within the program, if the application reports one
+    pub program_location: Option<String>,
+    /// Name of the program this object is an instance of
+    pub instance_of: Option<String>,
+    /// Reliability
+    pub reliability: Reliability,
+    /// Out of service
+    pub out_of_service: bool,
+    handler: Option<Box<dyn ProgramHandler>>,
+}
+
+impl fmt::Debug for Program {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Program")
+            .field("identifier", &self.identifier)
+            .field("object_name", &self.object_name)
+            .field("program_state", &self.program_state)
+            .field("reason_for_halt", &self.reason_for_halt)
+            .field("has_handler", &self.handler.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Program {
+    /// Create a new Program object with no application attached
+    pub fn new(instance: u32, object_name: String) -> Self {
+        Self {
+            identifier: ObjectIdentifier::new(ObjectType::Program, instance),
+            object_name,
+            description: String::new(),
+            program_state: ProgramState::Idle,
+            reason_for_halt: ProgramError::Normal,
+            description_of_halt: String::new(),
+            program_location: None,
+            instance_of: None,
+            reliability: Reliability::NoFaultDetected,
+            out_of_service: false,
+            handler: None,
+        }
+    }
+
+    /// Attach the application logic driven by Program_Change
+    pub fn with_handler(mut self, handler: Box<dyn ProgramHandler>) -> Self {
+        self.handler = Some(handler);
+        self
+    }
+
+    /// Process a Program_Change request
+    pub fn request(&mut self, request: ProgramRequest) -> Result<()> {
+        use ProgramState::*;
+
+        let state = self.program_state;
+        let allowed = match request {
+            ProgramRequest::Ready => true,
+            ProgramRequest::Load => matches!(state, Idle | Halted),
+            ProgramRequest::Run => matches!(state, Idle | Halted | Waiting),
+            ProgramRequest::Halt => matches!(state, Running | Waiting),
+            ProgramRequest::Restart => matches!(state, Running | Waiting | Halted),
+            ProgramRequest::Unload => state != Idle,
+        };
+        if !allowed {
+            return Err(ObjectError::InvalidValue(format!(
+                "Program request {:?} not allowed in state {:?}",
+                request, state
+            )));
+        }
+        if request != ProgramRequest::Ready && self.handler.is_none() {
+            return Err(ObjectError::InvalidConfiguration(
+                "No program attached".to_string(),
+            ));
+        }
+
+        match request {
+            ProgramRequest::Ready => {}
+            ProgramRequest::Load => {
+                self.program_state = Loading;
+                let outcome = self.handler_mut().load();
+                self.settle(outcome, Halted);
+            }
+            ProgramRequest::Run => {
+                if state == Idle {
+                    self.program_state = Loading;
+                    if let Err(halt) = self.handler_mut().load() {
+                        self.halted(halt);
+                        return Ok(());
+                    }
+                }
+                let outcome = self.handler_mut().run();
+                self.settle(outcome, Running);
+            }
+            ProgramRequest::Halt => {
+                self.handler_mut().halt();
+                self.halted(ProgramHalt::new(ProgramError::Normal, ""));
+            }
+            ProgramRequest::Restart => {
+                let outcome = self.handler_mut().restart();
+                self.settle(outcome, Running);
+            }
+            ProgramRequest::Unload => {
+                self.program_state = Unloading;
+                self.handler_mut().unload();
+                self.program_state = Idle;
+                self.reason_for_halt = ProgramError::Normal;
+                self.description_of_halt.clear();
+            }
+        }
+        Ok(())
+    }
+
+    /// Record that the running program halted on its own
+    pub fn report_halt(&mut self, halt: ProgramHalt) {
+        if self.program_state != ProgramState::Idle {
+            self.halted(halt);
+        }
+    }
+
+    /// Record that the running program is waiting for an external event
+    pub fn set_waiting(&mut self, waiting: bool) {
+        match (self.program_state, waiting) {
+            (ProgramState::Running, true) => self.program_state = ProgramState::Waiting,
+            (ProgramState::Waiting, false) => self.program_state = ProgramState::Running,
+            _ => {}
+        }
+    }
+
+    fn handler_mut(&mut self) -> &mut dyn ProgramHandler {
+        self.handler
+            .as_deref_mut()
+            .expect("program handler checked before dispatch")
+    }
+
+    fn settle(&mut self, outcome: core::result::Result<(), ProgramHalt>, success: ProgramState) {
+        match outcome {
+            Ok(()) => {
+                self.program_state = success;
+                if success == ProgramState::Running {
+                    self.reason_for_halt = ProgramError::Normal;
+                    self.description_of_halt.clear();
+                }
+            }
+            Err(halt) => self.halted(halt),
+        }
+    }
+
+    fn halted(&mut self, halt: ProgramHalt) {
+        self.program_state = ProgramState::Halted;
+        self.reason_for_halt = halt.reason;
+        self.description_of_halt = halt.description;
+    }
+
+    fn current_status_flags(&self) -> u8 {
+        current_status_flags(0, EventState::Normal, self.reliability, self.out_of_service)
+    }
+}
+
+impl BacnetObject for Program {
+    fn identifier(&self) -> ObjectIdentifier {
+        self.identifier
+    }
+
+    fn get_property(&self, property: PropertyIdentifier) -> Result<PropertyValue> {
+        match property {
+            PropertyIdentifier::ObjectIdentifier => {
+                Ok(PropertyValue::ObjectIdentifier(self.identifier))
+            }
+            PropertyIdentifier::ObjectName => {
+                Ok(PropertyValue::CharacterString(self.object_name.clone()))
+            }
+            PropertyIdentifier::ObjectType => {
+                Ok(PropertyValue::Enumerated(u32::from(ObjectType::Program)))
+            }
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::ProgramState => {
+                Ok(PropertyValue::Enumerated(self.program_state as u32))
+            }
+            // Requests are processed synchronously, so the change is always ready
+            PropertyIdentifier::ProgramChange => {
+                Ok(PropertyValue::Enumerated(ProgramRequest::Ready as u32))
+            }
+            PropertyIdentifier::ReasonForHalt => {
+                Ok(PropertyValue::Enumerated(self.reason_for_halt as u32))
+            }
+            PropertyIdentifier::DescriptionOfHalt => Ok(PropertyValue::CharacterString(
+                self.description_of_halt.clone(),
+            )),
+            PropertyIdentifier::ProgramLocation => self
+                .program_location
+                .clone()
+                .map(PropertyValue::CharacterString)
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::InstanceOf => self
+                .instance_of
+                .clone()
+                .map(PropertyValue::CharacterString)
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::StatusFlags => {
+                Ok(status_flags_bit_string(self.current_status_flags()))
+            }
+            PropertyIdentifier::Reliability => {
+                Ok(PropertyValue::Enumerated(self.reliability as u32))
+            }
+            PropertyIdentifier::OutOfService => Ok(PropertyValue::Boolean(self.out_of_service)),
+            _ => Err(ObjectError::UnknownProperty),
+        }
+    }
+
+    fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        match property {
+            PropertyIdentifier::ObjectName => {
+                if let PropertyValue::CharacterString(name) = value {
+                    self.object_name = name;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::ProgramChange => {
+                if let PropertyValue::Enumerated(request) = value {
+                    self.request(ProgramRequest::try_from(request)?)
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::OutOfService => {
+                if let PropertyValue::Boolean(oos) = value {
+                    self.out_of_service = oos;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            _ => Err(ObjectError::PropertyNotWritable),
+        }
+    }
+
+    fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
+        matches!(
+            property,
+            PropertyIdentifier::ObjectName
+                | PropertyIdentifier::Description
+                | PropertyIdentifier::ProgramChange
+                | PropertyIdentifier::OutOfService
+        )
+    }
+
+    fn property_list(&self) -> Vec<PropertyIdentifier> {
+        let mut properties = vec![
+            PropertyIdentifier::ObjectIdentifier,
+            PropertyIdentifier::ObjectName,
+            PropertyIdentifier::ObjectType,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::ProgramState,
+            PropertyIdentifier::ProgramChange,
+            PropertyIdentifier::ReasonForHalt,
+            PropertyIdentifier::DescriptionOfHalt,
+        ];
+        if self.program_location.is_some() {
+            properties.push(PropertyIdentifier::ProgramLocation);
+        }
+        if self.instance_of.is_some() {
+            properties.push(PropertyIdentifier::InstanceOf);
+        }
+        properties.extend([
+            PropertyIdentifier::StatusFlags,
+            PropertyIdentifier::Reliability,
+            PropertyIdentifier::OutOfService,
+        ]);
+        properties
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Default)]
+    struct Recorder {
+        calls: Arc<Mutex<Vec<&'static str>>>,
+        fail_run: bool,
+    }
+
+    impl ProgramHandler for Recorder {
+        fn load(&mut self) -> core::result::Result<(), ProgramHalt> {
+            self.calls.lock().unwrap().push("load");
+            Ok(())
+        }
+
+        fn run(&mut self) -> core::result::Result<(), ProgramHalt> {
+            self.calls.lock().unwrap().push("run");
+            if self.fail_run {
+                Err(ProgramHalt::new(ProgramError::Program, "divide by zero"))
+            } else {
+                Ok(())
+            }
+        }
+
+        fn halt(&mut self) {
+            self.calls.lock().unwrap().push("halt");
+        }
+
+        fn unload(&mut self) {
+            self.calls.lock().unwrap().push("unload");
+        }
+    }
+
+    fn change(program: &mut Program, request: ProgramRequest) -> Result<()> {
+        program.set_property(
+            PropertyIdentifier::ProgramChange,
+            PropertyValue::Enumerated(request as u32),
+        )
+    }
+
+    #[test]
+    fn test_program_lifecycle() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let mut program =
+            Program::new(1, "Optimal Start".to_string()).with_handler(Box::new(Recorder {
+                calls: calls.clone(),
+                fail_run: false,
+            }));
+
+        change(&mut program, ProgramRequest::Load).unwrap();
+        assert_eq!(program.program_state, ProgramState::Halted);
+        change(&mut program, ProgramRequest::Run).unwrap();
+        assert_eq!(program.program_state, ProgramState::Running);
+        assert!(change(&mut program, ProgramRequest::Load).is_err());
+
+        program.set_waiting(true);
+        assert_eq!(program.program_state, ProgramState::Waiting);
+        change(&mut program, ProgramRequest::Restart).unwrap();
+        assert_eq!(program.program_state, ProgramState::Running);
+
+        change(&mut program, ProgramRequest::Halt).unwrap();
+        assert_eq!(program.program_state, ProgramState::Halted);
+        change(&mut program, ProgramRequest::Unload).unwrap();
+        assert_eq!(program.program_state, ProgramState::Idle);
+
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec!["load", "run", "halt", "run", "halt", "unload"]
+        );
+        assert!(matches!(
+            program.get_property(PropertyIdentifier::ProgramChange),
+            Ok(PropertyValue::Enumerated(0))
+        ));
+    }
+
+    #[test]
+    fn test_program_halt_reporting() {
+        let mut program = Program::new(2, "Broken".to_string()).with_handler(Box::new(Recorder {
+            fail_run: true,
+            ..Default::default()
+        }));
+        change(&mut program, ProgramRequest::Run).unwrap();
+        assert_eq!(program.program_state, ProgramState::Halted);
+        assert_eq!(program.reason_for_halt, ProgramError::Program);
+        assert!(matches!(
+            program.get_property(PropertyIdentifier::DescriptionOfHalt),
+            Ok(PropertyValue::CharacterString(d)) if d == "divide by zero"
+        ));
+
+        let mut detached = Program::new(3, "Detached".to_string());
+        assert!(change(&mut detached, ProgramRequest::Run).is_err());
+        assert!(change(&mut detached, ProgramRequest::Halt).is_err());
+    }
+}
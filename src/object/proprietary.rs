@@ -0,0 +1,500 @@
+//! Proprietary Object and Property Support
+//!
+//! This module provides two ways of representing vendor-specific data:
+//!
+//! - [`ProprietaryObject`]: a generic object for vendor-specific object types
+//!   (128-1023). Instead of fixed fields it keeps an ordered map of property
+//!   values, so gateways can surface points from other protocols that do not fit
+//!   any standard object type.
+//! - [`ProprietaryExtension`]: a wrapper that adds vendor-specific properties
+//!   (512-4194303) to any other object. Reads, writes and Property_List consult
+//!   the extension map first and fall through to the wrapped object.
+//!
+//! Each map entry is either read-only or writable; writes must keep the datatype
+//! of the current value.
+
+use crate::object::{
+    array_element, replace_array_element, BacnetObject, ObjectError, ObjectIdentifier, ObjectType,
+    PropertyIdentifier, PropertyValue, PropertyWrite, Result,
+};
+use core::time::Duration;
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+#[derive(Debug, Clone)]
+struct PropertyEntry {
+    property: PropertyIdentifier,
+    value: PropertyValue,
+    writable: bool,
+}
+
+/// Ordered map of property values with per-property writability
+#[derive(Debug, Clone, Default)]
+pub struct PropertyMap {
+    entries: Vec<PropertyEntry>,
+}
+
+impl PropertyMap {
+    /// Create an empty map
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a property, or replace the value and writability of an existing one
+    pub fn insert(&mut self, property: PropertyIdentifier, value: PropertyValue, writable: bool) {
+        match self.entry_mut(property) {
+            Some(entry) => {
+                entry.value = value;
+                entry.writable = writable;
+            }
+            None => self.entries.push(PropertyEntry {
+                property,
+                value,
+                writable,
+            }),
+        }
+    }
+
+    /// Remove a property, returning its value
+    pub fn remove(&mut self, property: PropertyIdentifier) -> Option<PropertyValue> {
+        let index = self
+            .entries
+            .iter()
+            .position(|entry| entry.property == property)?;
+        Some(self.entries.remove(index).value)
+    }
+
+    /// Current value of a property
+    pub fn get(&self, property: PropertyIdentifier) -> Option<&PropertyValue> {
+        self.entry(property).map(|entry| &entry.value)
+    }
+
+    /// Whether the map holds a property
+    pub fn contains(&self, property: PropertyIdentifier) -> bool {
+        self.entry(property).is_some()
+    }
+
+    /// Whether a property is held and writable
+    pub fn is_writable(&self, property: PropertyIdentifier) -> bool {
+        self.entry(property).is_some_and(|entry| entry.writable)
+    }
+
+    /// Write a property as a client would
+    ///
+    /// Fails with `UnknownProperty` if the property is not held,
+    /// `PropertyNotWritable` if it is read-only, and `InvalidPropertyType` if the
+    /// value's datatype differs from the current one.
+    pub fn write(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        let entry = self
+            .entry_mut(property)
+            .ok_or(ObjectError::UnknownProperty)?;
+        if !entry.writable {
+            return Err(ObjectError::PropertyNotWritable);
+        }
+        if core::mem::discriminant(&entry.value) != core::mem::discriminant(&value) {
+            return Err(ObjectError::InvalidPropertyType);
+        }
+        entry.value = value;
+        Ok(())
+    }
+
+    /// Update a property from the application side, ignoring writability
+    pub fn update(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        let entry = self
+            .entry_mut(property)
+            .ok_or(ObjectError::UnknownProperty)?;
+        entry.value = value;
+        Ok(())
+    }
+
+    /// Properties held, in insertion order
+    pub fn properties(&self) -> impl Iterator<Item = PropertyIdentifier> + '_ {
+        self.entries.iter().map(|entry| entry.property)
+    }
+
+    fn entry(&self, property: PropertyIdentifier) -> Option<&PropertyEntry> {
+        self.entries.iter().find(|entry| entry.property == property)
+    }
+
+    fn entry_mut(&mut self, property: PropertyIdentifier) -> Option<&mut PropertyEntry> {
+        self.entries
+            .iter_mut()
+            .find(|entry| entry.property == property)
+    }
+}
+
+/// Object of a vendor-specific type with an arbitrary set of properties
+#[derive(Debug, Clone)]
+pub struct ProprietaryObject {
+    /// Object identifier
+    pub identifier: ObjectIdentifier,
+    /// Object name
+    pub object_name: String,
+    properties: PropertyMap,
+}
+
+impl ProprietaryObject {
+    /// Create an object of vendor-specific type `object_type` (128-1023)
+    pub fn new(object_type: u16, instance: u32, object_name: String) -> Result<Self> {
+        if !(128..=1023).contains(&object_type) {
+            return Err(ObjectError::InvalidValue(format!(
+                "Proprietary object types are 128-1023, not {}",
+                object_type
+            )));
+        }
+        Ok(Self {
+            identifier: ObjectIdentifier::new(ObjectType::Proprietary(object_type), instance),
+            object_name,
+            properties: PropertyMap::new(),
+        })
+    }
+
+    /// Add a property, or replace the value and writability of an existing one
+    ///
+    /// Object_Identifier, Object_Name and Object_Type are maintained by the
+    /// object itself and cannot be added.
+    pub fn add_property(
+        &mut self,
+        property: PropertyIdentifier,
+        value: PropertyValue,
+        writable: bool,
+    ) -> Result<()> {
+        if matches!(
+            property,
+            PropertyIdentifier::ObjectIdentifier
+                | PropertyIdentifier::ObjectName
+                | PropertyIdentifier::ObjectType
+        ) {
+            return Err(ObjectError::InvalidConfiguration(format!(
+                "{:?} is maintained by the object",
+                property
+            )));
+        }
+        self.properties.insert(property, value, writable);
+        Ok(())
+    }
+
+    /// Remove a property, returning its value
+    pub fn remove_property(&mut self, property: PropertyIdentifier) -> Option<PropertyValue> {
+        self.properties.remove(property)
+    }
+
+    /// Update a property from the application side, ignoring writability
+    pub fn update_property(
+        &mut self,
+        property: PropertyIdentifier,
+        value: PropertyValue,
+    ) -> Result<()> {
+        self.properties.update(property, value)
+    }
+}
+
+impl BacnetObject for ProprietaryObject {
+    fn identifier(&self) -> ObjectIdentifier {
+        self.identifier
+    }
+
+    fn get_property(&self, property: PropertyIdentifier) -> Result<PropertyValue> {
+        match property {
+            PropertyIdentifier::ObjectIdentifier => {
+                Ok(PropertyValue::ObjectIdentifier(self.identifier))
+            }
+            PropertyIdentifier::ObjectName => {
+                Ok(PropertyValue::CharacterString(self.object_name.clone()))
+            }
+            PropertyIdentifier::ObjectType => Ok(PropertyValue::Enumerated(u32::from(
+                self.identifier.object_type,
+            ))),
+            _ => self
+                .properties
+                .get(property)
+                .cloned()
+                .ok_or(ObjectError::UnknownProperty),
+        }
+    }
+
+    fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        match property {
+            PropertyIdentifier::ObjectName => {
+                if let PropertyValue::CharacterString(name) = value {
+                    self.object_name = name;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::ObjectIdentifier | PropertyIdentifier::ObjectType => {
+                Err(ObjectError::PropertyNotWritable)
+            }
+            _ => self.properties.write(property, value),
+        }
+    }
+
+    fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
+        property == PropertyIdentifier::ObjectName || self.properties.is_writable(property)
+    }
+
+    fn property_list(&self) -> Vec<PropertyIdentifier> {
+        let mut properties = vec![
+            PropertyIdentifier::ObjectIdentifier,
+            PropertyIdentifier::ObjectName,
+            PropertyIdentifier::ObjectType,
+        ];
+        properties.extend(self.properties.properties());
+        properties
+    }
+}
+
+/// Any object extended with vendor-specific properties
+///
+/// Every standard behaviour, including commanding at a priority and the timer
+/// hooks, is passed through to the wrapped object.
+#[derive(Debug, Clone)]
+pub struct ProprietaryExtension<O> {
+    object: O,
+    properties: PropertyMap,
+}
+
+impl<O: BacnetObject> ProprietaryExtension<O> {
+    /// Wrap an object with an empty extension map
+    pub fn new(object: O) -> Self {
+        Self {
+            object,
+            properties: PropertyMap::new(),
+        }
+    }
+
+    /// Add a vendor-specific property (512-4194303), or replace an existing one
+    pub fn add_property(
+        &mut self,
+        property: PropertyIdentifier,
+        value: PropertyValue,
+        writable: bool,
+    ) -> Result<()> {
+        if !matches!(property, PropertyIdentifier::Proprietary(_)) {
+            return Err(ObjectError::InvalidConfiguration(format!(
+                "{:?} is not a proprietary property",
+                property
+            )));
+        }
+        self.properties.insert(property, value, writable);
+        Ok(())
+    }
+
+    /// Remove a vendor-specific property, returning its value
+    pub fn remove_property(&mut self, property: PropertyIdentifier) -> Option<PropertyValue> {
+        self.properties.remove(property)
+    }
+
+    /// Update a vendor-specific property from the application side, ignoring
+    /// writability
+    pub fn update_property(
+        &mut self,
+        property: PropertyIdentifier,
+        value: PropertyValue,
+    ) -> Result<()> {
+        self.properties.update(property, value)
+    }
+
+    /// The wrapped object
+    pub fn inner(&self) -> &O {
+        &self.object
+    }
+
+    /// The wrapped object, mutably
+    pub fn inner_mut(&mut self) -> &mut O {
+        &mut self.object
+    }
+
+    /// Unwrap the object, dropping the extension properties
+    pub fn into_inner(self) -> O {
+        self.object
+    }
+}
+
+impl<O: BacnetObject> BacnetObject for ProprietaryExtension<O> {
+    fn identifier(&self) -> ObjectIdentifier {
+        self.object.identifier()
+    }
+
+    fn get_property(&self, property: PropertyIdentifier) -> Result<PropertyValue> {
+        match self.properties.get(property) {
+            Some(value) => Ok(value.clone()),
+            None => self.object.get_property(property),
+        }
+    }
+
+    fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        if self.properties.contains(property) {
+            self.properties.write(property, value)
+        } else {
+            self.object.set_property(property, value)
+        }
+    }
+
+    fn get_property_at(&self, property: PropertyIdentifier, index: u32) -> Result<PropertyValue> {
+        match self.properties.get(property) {
+            Some(value) => array_element(value.clone(), index),
+            None => self.object.get_property_at(property, index),
+        }
+    }
+
+    fn set_property_at(
+        &mut self,
+        property: PropertyIdentifier,
+        index: u32,
+        value: PropertyValue,
+    ) -> Result<()> {
+        match self.properties.get(property) {
+            Some(array) => {
+                let array = replace_array_element(array.clone(), index, value)?;
+                self.properties.write(property, array)
+            }
+            None => self.object.set_property_at(property, index, value),
+        }
+    }
+
+    fn set_property_with_priority(
+        &mut self,
+        property: PropertyIdentifier,
+        value: PropertyValue,
+        priority: u8,
+    ) -> Result<()> {
+        if self.properties.contains(property) {
+            self.properties.write(property, value)
+        } else {
+            self.object
+                .set_property_with_priority(property, value, priority)
+        }
+    }
+
+    fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
+        if self.properties.contains(property) {
+            self.properties.is_writable(property)
+        } else {
+            self.object.is_property_writable(property)
+        }
+    }
+
+    fn property_list(&self) -> Vec<PropertyIdentifier> {
+        let mut properties = self.object.property_list();
+        properties.extend(self.properties.properties());
+        properties
+    }
+
+    fn required_properties(&self) -> Vec<PropertyIdentifier> {
+        self.object.required_properties()
+    }
+
+    fn advance_time(&mut self, elapsed: Duration) {
+        self.object.advance_time(elapsed);
+    }
+
+    fn take_pending_writes(&mut self) -> Vec<PropertyWrite> {
+        self.object.take_pending_writes()
+    }
+
+    fn report_write_results(&mut self, results: &[bool]) {
+        self.object.report_write_results(results);
+    }
+
+    fn activate_changes(&mut self) {
+        self.object.activate_changes();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::OctetString;
+
+    #[test]
+    fn test_proprietary_object_properties() {
+        let mut object = ProprietaryObject::new(200, 1, "Gateway Point".to_string()).unwrap();
+        object
+            .add_property(
+                PropertyIdentifier::PresentValue,
+                PropertyValue::Real(21.5),
+                true,
+            )
+            .unwrap();
+        object
+            .add_property(
+                PropertyIdentifier::Description,
+                PropertyValue::CharacterString("Zone temperature".to_string()),
+                false,
+            )
+            .unwrap();
+
+        assert_eq!(
+            object.get_property(PropertyIdentifier::ObjectType).unwrap(),
+            PropertyValue::Enumerated(200)
+        );
+        assert_eq!(object.property_list().len(), 5);
+
+        object
+            .set_property(PropertyIdentifier::PresentValue, PropertyValue::Real(22.0))
+            .unwrap();
+        assert!(matches!(
+            object.set_property(
+                PropertyIdentifier::PresentValue,
+                PropertyValue::Boolean(true)
+            ),
+            Err(ObjectError::InvalidPropertyType)
+        ));
+        assert!(matches!(
+            object.set_property(
+                PropertyIdentifier::Description,
+                PropertyValue::CharacterString("x".to_string())
+            ),
+            Err(ObjectError::PropertyNotWritable)
+        ));
+        assert!(matches!(
+            object.get_property(PropertyIdentifier::Units),
+            Err(ObjectError::UnknownProperty)
+        ));
+    }
+
+    #[test]
+    fn test_proprietary_object_type_range() {
+        assert!(ProprietaryObject::new(8, 1, "Device".to_string()).is_err());
+        let object = ProprietaryObject::new(1023, 7, "Meter".to_string()).unwrap();
+        assert_eq!(
+            ObjectType::try_from(1023u16).unwrap(),
+            object.identifier.object_type
+        );
+    }
+
+    #[test]
+    fn test_extension_adds_properties_to_standard_object() {
+        let vendor_property = PropertyIdentifier::try_from(1000).unwrap();
+        assert_eq!(vendor_property, PropertyIdentifier::Proprietary(1000));
+
+        let mut object = ProprietaryExtension::new(OctetString::new(1, "Blob".to_string()));
+        assert!(matches!(
+            object.get_property(vendor_property),
+            Err(ObjectError::UnknownProperty)
+        ));
+        object
+            .add_property(vendor_property, PropertyValue::UnsignedInteger(5), true)
+            .unwrap();
+        assert!(object
+            .add_property(PropertyIdentifier::Description, PropertyValue::Null, false)
+            .is_err());
+
+        object
+            .set_property(vendor_property, PropertyValue::UnsignedInteger(6))
+            .unwrap();
+        assert_eq!(
+            object.get_property(vendor_property).unwrap(),
+            PropertyValue::UnsignedInteger(6)
+        );
+        assert_eq!(
+            object.get_property(PropertyIdentifier::ObjectName).unwrap(),
+            PropertyValue::CharacterString("Blob".to_string())
+        );
+        assert_eq!(object.property_list().last(), Some(&vendor_property));
+        assert!(object.is_property_writable(vendor_property));
+    }
+}
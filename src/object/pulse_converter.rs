@@ -0,0 +1,354 @@
+//! Pulse Converter Object Type Implementation
+//!
+//! This module implements the Pulse Converter object type as defined in ASHRAE 135.
+//! A Pulse Converter turns pulses, typically from the Accumulator named by
+//! Input_Reference, into an engineering-unit total: every counted pulse adds
+//! Scale_Factor to Present_Value. Writing Adjust_Value corrects the total and
+//! restarts Count, keeping the previous count in Count_Before_Change.
+//!
+//! When COV_Period is non-zero the object also becomes due for a periodic COV
+//! notification every COV_Period seconds, independent of COV_Increment.
+
+use crate::object::{
+    current_date_time, current_status_flags, date_time_value, engineering_units::EngineeringUnits,
+    status_flags_bit_string, BacnetObject, DeviceObjectPropertyReference, EventState, ObjectError,
+    ObjectIdentifier, ObjectType, PropertyIdentifier, PropertyValue, Reliability, Result,
+};
+use crate::service::BacnetDateTime;
+use core::time::Duration;
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+/// Pulse Converter object
+#[derive(Debug, Clone)]
+pub struct PulseConverter {
+    /// Object identifier
+    pub identifier: ObjectIdentifier,
+    /// Object name
+    pub object_name: String,
+    /// Description
+    pub description: String,
+    /// Present value (the scaled total)
+    pub present_value: f32,
+    /// Source of the pulses, usually an Accumulator's Present_Value
+    pub input_reference: Option<DeviceObjectPropertyReference>,
+    /// Event state
+    pub event_state: EventState,
+    /// Reliability
+    pub reliability: Reliability,
+    /// Out of service
+    pub out_of_service: bool,
+    /// Units
+    pub units: EngineeringUnits,
+    /// Amount added to Present_Value per pulse
+    pub scale_factor: f32,
+    /// Last value written to Adjust_Value
+    pub adjust_value: f32,
+    /// Pulses counted since the last adjustment
+    pub count: u32,
+    /// Time Present_Value was last updated
+    pub update_time: Option<BacnetDateTime>,
+    /// Time of the last adjustment
+    pub count_change_time: Option<BacnetDateTime>,
+    /// Count just before the last adjustment
+    pub count_before_change: u32,
+    /// COV increment
+    pub cov_increment: Option<f32>,
+    /// Seconds between periodic COV notifications, zero to disable
+    pub cov_period: Option<u32>,
+    cov_period_elapsed: Duration,
+    periodic_cov_due: bool,
+}
+
+impl PulseConverter {
+    /// Create a new Pulse Converter with a scale factor of one
+    pub fn new(instance: u32, object_name: String, units: EngineeringUnits) -> Self {
+        Self {
+            identifier: ObjectIdentifier::new(ObjectType::PulseConverter, instance),
+            object_name,
+            description: String::new(),
+            present_value: 0.0,
+            input_reference: None,
+            event_state: EventState::Normal,
+            reliability: Reliability::NoFaultDetected,
+            out_of_service: false,
+            units,
+            scale_factor: 1.0,
+            adjust_value: 0.0,
+            count: 0,
+            update_time: None,
+            count_change_time: None,
+            count_before_change: 0,
+            cov_increment: None,
+            cov_period: None,
+            cov_period_elapsed: Duration::ZERO,
+            periodic_cov_due: false,
+        }
+    }
+
+    /// Count pulses from the input
+    pub fn count_pulses(&mut self, pulses: u32) {
+        self.count_pulses_at(pulses, current_date_time());
+    }
+
+    /// Count pulses from the input, recording `timestamp` as the update time
+    ///
+    /// While the object is out of service Present_Value is decoupled from the
+    /// input, so the pulses are ignored.
+    pub fn count_pulses_at(&mut self, pulses: u32, timestamp: Option<BacnetDateTime>) {
+        if self.out_of_service || pulses == 0 {
+            return;
+        }
+        self.count = self.count.wrapping_add(pulses);
+        self.present_value += pulses as f32 * self.scale_factor;
+        self.update_time = timestamp;
+    }
+
+    /// Apply a correction to Present_Value and restart Count
+    pub fn adjust(&mut self, adjustment: f32, timestamp: Option<BacnetDateTime>) {
+        self.adjust_value = adjustment;
+        self.present_value += adjustment;
+        self.count_before_change = self.count;
+        self.count = 0;
+        self.count_change_time = timestamp;
+        self.update_time = timestamp;
+    }
+
+    /// Whether a periodic COV notification has become due, clearing the flag
+    pub fn take_periodic_cov(&mut self) -> bool {
+        core::mem::take(&mut self.periodic_cov_due)
+    }
+
+    fn current_status_flags(&self) -> u8 {
+        current_status_flags(0, self.event_state, self.reliability, self.out_of_service)
+    }
+}
+
+impl BacnetObject for PulseConverter {
+    fn identifier(&self) -> ObjectIdentifier {
+        self.identifier
+    }
+
+    fn get_property(&self, property: PropertyIdentifier) -> Result<PropertyValue> {
+        match property {
+            PropertyIdentifier::ObjectIdentifier => {
+                Ok(PropertyValue::ObjectIdentifier(self.identifier))
+            }
+            PropertyIdentifier::ObjectName => {
+                Ok(PropertyValue::CharacterString(self.object_name.clone()))
+            }
+            PropertyIdentifier::ObjectType => Ok(PropertyValue::Enumerated(u32::from(
+                ObjectType::PulseConverter,
+            ))),
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::PresentValue => Ok(PropertyValue::Real(self.present_value)),
+            PropertyIdentifier::InputReference => self
+                .input_reference
+                .map(|reference| reference.to_property_value())
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::StatusFlags => {
+                Ok(status_flags_bit_string(self.current_status_flags()))
+            }
+            PropertyIdentifier::EventState => {
+                Ok(PropertyValue::Enumerated(self.event_state as u32))
+            }
+            PropertyIdentifier::Reliability => {
+                Ok(PropertyValue::Enumerated(self.reliability as u32))
+            }
+            PropertyIdentifier::OutOfService => Ok(PropertyValue::Boolean(self.out_of_service)),
+            PropertyIdentifier::Units => Ok(PropertyValue::Enumerated(self.units.to_u32())),
+            PropertyIdentifier::ScaleFactor => Ok(PropertyValue::Real(self.scale_factor)),
+            PropertyIdentifier::AdjustValue => Ok(PropertyValue::Real(self.adjust_value)),
+            PropertyIdentifier::Count => Ok(PropertyValue::UnsignedInteger(self.count)),
+            PropertyIdentifier::UpdateTime => Ok(date_time_value(self.update_time)),
+            PropertyIdentifier::CountChangeTime => Ok(date_time_value(self.count_change_time)),
+            PropertyIdentifier::CountBeforeChange => {
+                Ok(PropertyValue::UnsignedInteger(self.count_before_change))
+            }
+            PropertyIdentifier::CovIncrement => self
+                .cov_increment
+                .map(PropertyValue::Real)
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::CovPeriod => self
+                .cov_period
+                .map(PropertyValue::UnsignedInteger)
+                .ok_or(ObjectError::UnknownProperty),
+            _ => Err(ObjectError::UnknownProperty),
+        }
+    }
+
+    fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        match property {
+            PropertyIdentifier::ObjectName => {
+                if let PropertyValue::CharacterString(name) = value {
+                    self.object_name = name;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::PresentValue => {
+                if !self.out_of_service {
+                    return Err(ObjectError::WriteAccessDenied);
+                }
+                if let PropertyValue::Real(total) = value {
+                    self.present_value = total;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::OutOfService => {
+                if let PropertyValue::Boolean(oos) = value {
+                    self.out_of_service = oos;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::AdjustValue => {
+                if let PropertyValue::Real(adjustment) = value {
+                    self.adjust(adjustment, current_date_time());
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::CovIncrement if self.cov_increment.is_some() => {
+                if let PropertyValue::Real(increment) = value {
+                    self.cov_increment = Some(increment);
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::CovPeriod if self.cov_period.is_some() => {
+                if let PropertyValue::UnsignedInteger(seconds) = value {
+                    self.cov_period = Some(seconds);
+                    self.cov_period_elapsed = Duration::ZERO;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            _ => Err(ObjectError::PropertyNotWritable),
+        }
+    }
+
+    fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
+        match property {
+            PropertyIdentifier::ObjectName
+            | PropertyIdentifier::Description
+            | PropertyIdentifier::OutOfService
+            | PropertyIdentifier::AdjustValue => true,
+            PropertyIdentifier::PresentValue => self.out_of_service,
+            PropertyIdentifier::CovIncrement => self.cov_increment.is_some(),
+            PropertyIdentifier::CovPeriod => self.cov_period.is_some(),
+            _ => false,
+        }
+    }
+
+    fn property_list(&self) -> Vec<PropertyIdentifier> {
+        let mut properties = vec![
+            PropertyIdentifier::ObjectIdentifier,
+            PropertyIdentifier::ObjectName,
+            PropertyIdentifier::ObjectType,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::PresentValue,
+        ];
+        if self.input_reference.is_some() {
+            properties.push(PropertyIdentifier::InputReference);
+        }
+        properties.extend([
+            PropertyIdentifier::StatusFlags,
+            PropertyIdentifier::EventState,
+            PropertyIdentifier::Reliability,
+            PropertyIdentifier::OutOfService,
+            PropertyIdentifier::Units,
+            PropertyIdentifier::ScaleFactor,
+            PropertyIdentifier::AdjustValue,
+            PropertyIdentifier::Count,
+            PropertyIdentifier::UpdateTime,
+            PropertyIdentifier::CountChangeTime,
+            PropertyIdentifier::CountBeforeChange,
+        ]);
+        if self.cov_increment.is_some() {
+            properties.push(PropertyIdentifier::CovIncrement);
+        }
+        if self.cov_period.is_some() {
+            properties.push(PropertyIdentifier::CovPeriod);
+        }
+        properties
+    }
+
+    fn advance_time(&mut self, elapsed: Duration) {
+        let Some(period) = self.cov_period.filter(|&seconds| seconds > 0) else {
+            return;
+        };
+        let period = Duration::from_secs(period as u64);
+        self.cov_period_elapsed = self.cov_period_elapsed.saturating_add(elapsed);
+        if self.cov_period_elapsed >= period {
+            self.cov_period_elapsed = Duration::ZERO;
+            self.periodic_cov_due = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scaling_and_adjustment() {
+        let mut converter =
+            PulseConverter::new(1, "Gas Total".to_string(), EngineeringUnits::CubicMeters);
+        converter.scale_factor = 0.01;
+        converter.count_pulses_at(250, None);
+        assert_eq!(converter.count, 250);
+        assert!((converter.present_value - 2.5).abs() < 1e-5);
+
+        converter
+            .set_property(PropertyIdentifier::AdjustValue, PropertyValue::Real(-0.5))
+            .unwrap();
+        assert!((converter.present_value - 2.0).abs() < 1e-5);
+        assert_eq!(converter.count, 0);
+        assert_eq!(converter.count_before_change, 250);
+        assert!(matches!(
+            converter.get_property(PropertyIdentifier::AdjustValue),
+            Ok(PropertyValue::Real(v)) if v == -0.5
+        ));
+
+        converter.out_of_service = true;
+        converter.count_pulses_at(100, None);
+        assert_eq!(converter.count, 0);
+    }
+
+    #[test]
+    fn test_periodic_cov() {
+        let mut converter =
+            PulseConverter::new(2, "Water Total".to_string(), EngineeringUnits::CubicMeters);
+        converter.advance_time(Duration::from_secs(120));
+        assert!(!converter.take_periodic_cov());
+
+        converter.cov_period = Some(60);
+        converter.advance_time(Duration::from_secs(30));
+        assert!(!converter.take_periodic_cov());
+        converter.advance_time(Duration::from_secs(30));
+        assert!(converter.take_periodic_cov());
+        assert!(!converter.take_periodic_cov());
+        assert!(converter
+            .property_list()
+            .contains(&PropertyIdentifier::CovPeriod));
+    }
+}
@@ -0,0 +1,751 @@
+//! Schedule Object Type Implementation
+//!
+//! This module implements the Schedule object type as defined in ASHRAE 135 together
+//! with its evaluation engine. The engine combines the Weekly_Schedule, the
+//! Exception_Schedule (special events, optionally referencing Calendar objects) and
+//! Schedule_Default into a Present_Value, and reports the writes that must be made to
+//! the List_Of_Object_Property_References whenever that value changes.
+
+use crate::object::{
+    calendar::{CalendarEntry, DateRange},
+    BacnetObject, Date, DeviceObjectPropertyReference, ObjectError, ObjectIdentifier, ObjectType,
+    PropertyIdentifier, PropertyValue, PropertyWrite, Reliability, Result, Time,
+};
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+/// A time and the value that takes effect at it (BACnetTimeValue)
+#[derive(Debug, Clone)]
+pub struct TimeValue {
+    /// Time of day at which the value takes effect
+    pub time: Time,
+    /// Scheduled value; `Null` relinquishes control
+    pub value: PropertyValue,
+}
+
+impl TimeValue {
+    /// Create a new time/value pair
+    pub fn new(time: Time, value: PropertyValue) -> Self {
+        Self { time, value }
+    }
+
+    fn to_property_value(&self) -> PropertyValue {
+        PropertyValue::List(vec![PropertyValue::Time(self.time), self.value.clone()])
+    }
+
+    fn from_property_value(value: &PropertyValue) -> Result<Self> {
+        match value {
+            PropertyValue::List(items) => match items.as_slice() {
+                [PropertyValue::Time(time), value] => Ok(Self::new(*time, value.clone())),
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            _ => Err(ObjectError::InvalidPropertyType),
+        }
+    }
+}
+
+/// When a special event applies
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpecialEventPeriod {
+    /// An inline calendar entry
+    CalendarEntry(CalendarEntry),
+    /// A Calendar object whose Present_Value selects the event
+    CalendarReference(ObjectIdentifier),
+}
+
+/// An Exception_Schedule entry (BACnetSpecialEvent)
+#[derive(Debug, Clone)]
+pub struct SpecialEvent {
+    /// Days on which the event applies
+    pub period: SpecialEventPeriod,
+    /// Values scheduled for those days
+    pub list_of_time_values: Vec<TimeValue>,
+    /// Event priority (1 = highest, 16 = lowest)
+    pub event_priority: u8,
+}
+
+impl SpecialEvent {
+    /// Create a new special event
+    pub fn new(
+        period: SpecialEventPeriod,
+        list_of_time_values: Vec<TimeValue>,
+        priority: u8,
+    ) -> Self {
+        Self {
+            period,
+            list_of_time_values,
+            event_priority: priority,
+        }
+    }
+
+    fn to_property_value(&self) -> PropertyValue {
+        let period = match self.period {
+            SpecialEventPeriod::CalendarEntry(entry) => entry.to_property_value(),
+            SpecialEventPeriod::CalendarReference(id) => PropertyValue::ObjectIdentifier(id),
+        };
+        PropertyValue::List(vec![
+            period,
+            PropertyValue::List(
+                self.list_of_time_values
+                    .iter()
+                    .map(TimeValue::to_property_value)
+                    .collect(),
+            ),
+            PropertyValue::UnsignedInteger(self.event_priority as u32),
+        ])
+    }
+
+    fn from_property_value(value: &PropertyValue) -> Result<Self> {
+        let PropertyValue::List(items) = value else {
+            return Err(ObjectError::InvalidPropertyType);
+        };
+        let [period, PropertyValue::List(time_values), PropertyValue::UnsignedInteger(priority)] =
+            items.as_slice()
+        else {
+            return Err(ObjectError::InvalidPropertyType);
+        };
+        let period = match period {
+            PropertyValue::ObjectIdentifier(id) if id.object_type == ObjectType::Calendar => {
+                SpecialEventPeriod::CalendarReference(*id)
+            }
+            other => SpecialEventPeriod::CalendarEntry(CalendarEntry::from_property_value(other)?),
+        };
+        if !(1..=16).contains(priority) {
+            return Err(ObjectError::InvalidValue(
+                "Event priority must be 1-16".to_string(),
+            ));
+        }
+        Ok(Self::new(
+            period,
+            time_values
+                .iter()
+                .map(TimeValue::from_property_value)
+                .collect::<Result<Vec<_>>>()?,
+            *priority as u8,
+        ))
+    }
+}
+
+/// Sortable key for a time of day; unspecified fields count as zero
+pub(crate) fn time_key(time: &Time) -> u32 {
+    let field = |v: u8| if v == 255 { 0 } else { v as u32 };
+    field(time.hour) * 360_000
+        + field(time.minute) * 6_000
+        + field(time.second) * 100
+        + field(time.hundredths)
+}
+
+/// The value in effect at `now` from a list of time values, if any entry has started
+fn value_at<'a>(time_values: &'a [TimeValue], now: &Time) -> Option<&'a PropertyValue> {
+    time_values
+        .iter()
+        .filter(|tv| time_key(&tv.time) <= time_key(now))
+        .max_by_key(|tv| time_key(&tv.time))
+        .map(|tv| &tv.value)
+}
+
+/// Schedule object
+#[derive(Debug, Clone)]
+pub struct Schedule {
+    /// Object identifier
+    pub identifier: ObjectIdentifier,
+    /// Object name
+    pub object_name: String,
+    /// Description
+    pub description: String,
+    /// Present value
+    pub present_value: PropertyValue,
+    /// Dates between which the schedule is active
+    pub effective_period: DateRange,
+    /// Weekly schedule, Monday first
+    pub weekly_schedule: Option<[Vec<TimeValue>; 7]>,
+    /// Exception schedule
+    pub exception_schedule: Option<Vec<SpecialEvent>>,
+    /// Value used when no schedule entry applies
+    pub schedule_default: PropertyValue,
+    /// Properties written with the present value
+    pub list_of_object_property_references: Vec<DeviceObjectPropertyReference>,
+    /// Priority used for those writes
+    pub priority_for_writing: u8,
+    /// Status flags
+    pub status_flags: u8,
+    /// Reliability
+    pub reliability: Reliability,
+    /// Out of service
+    pub out_of_service: bool,
+}
+
+impl Schedule {
+    /// Create a new Schedule object with an unbounded effective period
+    pub fn new(instance: u32, object_name: String, schedule_default: PropertyValue) -> Self {
+        let any_date = Date {
+            year: 255,
+            month: 255,
+            day: 255,
+            weekday: 255,
+        };
+        Self {
+            identifier: ObjectIdentifier::new(ObjectType::Schedule, instance),
+            object_name,
+            description: String::new(),
+            present_value: schedule_default.clone(),
+            effective_period: DateRange::new(any_date, any_date),
+            weekly_schedule: Some(Default::default()),
+            exception_schedule: None,
+            schedule_default,
+            list_of_object_property_references: Vec::new(),
+            priority_for_writing: 16,
+            status_flags: 0,
+            reliability: Reliability::NoFaultDetected,
+            out_of_service: false,
+        }
+    }
+
+    /// Compute the scheduled value for a date and time
+    ///
+    /// `calendar_active` resolves Calendar references in the exception schedule;
+    /// references it cannot resolve are treated as inactive.
+    pub fn scheduled_value<F>(&self, date: &Date, time: &Time, calendar_active: F) -> PropertyValue
+    where
+        F: Fn(ObjectIdentifier) -> Option<bool>,
+    {
+        if !self.effective_period.contains(date) {
+            return self.schedule_default.clone();
+        }
+
+        // The highest-priority exception with a non-NULL value in effect wins;
+        // among equal priorities the earlier entry wins
+        let exception = self
+            .exception_schedule
+            .iter()
+            .flatten()
+            .filter(|event| match event.period {
+                SpecialEventPeriod::CalendarEntry(entry) => entry.matches(date),
+                SpecialEventPeriod::CalendarReference(id) => calendar_active(id).unwrap_or(false),
+            })
+            .filter_map(|event| {
+                value_at(&event.list_of_time_values, time)
+                    .filter(|value| !matches!(value, PropertyValue::Null))
+                    .map(|value| (event.event_priority, value))
+            })
+            .min_by_key(|(priority, _)| *priority);
+        if let Some((_, value)) = exception {
+            return value.clone();
+        }
+
+        let weekly = self.weekly_schedule.as_ref().and_then(|week| {
+            let day = weekday_index(date)?;
+            value_at(&week[day], time)
+        });
+        match weekly {
+            Some(PropertyValue::Null) | None => self.schedule_default.clone(),
+            Some(value) => value.clone(),
+        }
+    }
+
+    /// Re-evaluate the schedule at a time boundary
+    ///
+    /// Updates Present_Value and, when it changed, returns the writes for every
+    /// entry of List_Of_Object_Property_References at Priority_For_Writing.
+    /// Nothing is evaluated while the object is out of service.
+    pub fn evaluate<F>(
+        &mut self,
+        date: &Date,
+        time: &Time,
+        calendar_active: F,
+    ) -> Vec<PropertyWrite>
+    where
+        F: Fn(ObjectIdentifier) -> Option<bool>,
+    {
+        if self.out_of_service {
+            return Vec::new();
+        }
+        let value = self.scheduled_value(date, time, calendar_active);
+        if value == self.present_value {
+            return Vec::new();
+        }
+        self.present_value = value;
+        self.pending_writes()
+    }
+
+    /// Writes that propagate the current Present_Value to all references
+    pub fn pending_writes(&self) -> Vec<PropertyWrite> {
+        self.list_of_object_property_references
+            .iter()
+            .map(|reference| PropertyWrite {
+                reference: *reference,
+                value: self.present_value.clone(),
+                priority: self.priority_for_writing,
+            })
+            .collect()
+    }
+
+    /// The next time today at which the scheduled value may change
+    pub fn next_transition(&self, date: &Date, time: &Time) -> Option<Time> {
+        let weekly = self
+            .weekly_schedule
+            .as_ref()
+            .zip(weekday_index(date))
+            .map(|(week, day)| week[day].as_slice())
+            .unwrap_or(&[]);
+        let exceptions = self
+            .exception_schedule
+            .iter()
+            .flatten()
+            .flat_map(|event| event.list_of_time_values.iter());
+        weekly
+            .iter()
+            .chain(exceptions)
+            .map(|tv| tv.time)
+            .filter(|t| time_key(t) > time_key(time))
+            .min_by_key(time_key)
+    }
+
+    /// Check the configuration for consistency (Clause 12.24)
+    ///
+    /// Every scheduled value must be NULL or share the datatype of Schedule_Default.
+    pub fn validate(&self) -> Result<()> {
+        let weekly = self.weekly_schedule.iter().flatten().flatten();
+        let exceptions = self
+            .exception_schedule
+            .iter()
+            .flatten()
+            .flat_map(|event| event.list_of_time_values.iter());
+        for tv in weekly.chain(exceptions) {
+            if !matches!(tv.value, PropertyValue::Null)
+                && !same_datatype(&tv.value, &self.schedule_default)
+            {
+                return Err(ObjectError::InvalidConfiguration(
+                    "Scheduled value datatype differs from Schedule_Default".to_string(),
+                ));
+            }
+        }
+        if !(1..=16).contains(&self.priority_for_writing) {
+            return Err(ObjectError::InvalidConfiguration(
+                "Priority_For_Writing must be 1-16".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn weekly_schedule_value(&self) -> Result<PropertyValue> {
+        let week = self
+            .weekly_schedule
+            .as_ref()
+            .ok_or(ObjectError::UnknownProperty)?;
+        Ok(PropertyValue::Array(
+            week.iter()
+                .map(|day| {
+                    PropertyValue::List(day.iter().map(TimeValue::to_property_value).collect())
+                })
+                .collect(),
+        ))
+    }
+}
+
+/// Index into the weekly schedule (0 = Monday) for a date
+fn weekday_index(date: &Date) -> Option<usize> {
+    let weekday = if (1..=7).contains(&date.weekday) {
+        date.weekday
+    } else {
+        crate::object::calendar::weekday_of(date)?
+    };
+    Some((weekday - 1) as usize)
+}
+
+fn same_datatype(a: &PropertyValue, b: &PropertyValue) -> bool {
+    core::mem::discriminant(a) == core::mem::discriminant(b)
+}
+
+impl BacnetObject for Schedule {
+    fn identifier(&self) -> ObjectIdentifier {
+        self.identifier
+    }
+
+    fn get_property(&self, property: PropertyIdentifier) -> Result<PropertyValue> {
+        match property {
+            PropertyIdentifier::ObjectIdentifier => {
+                Ok(PropertyValue::ObjectIdentifier(self.identifier))
+            }
+            PropertyIdentifier::ObjectName => {
+                Ok(PropertyValue::CharacterString(self.object_name.clone()))
+            }
+            PropertyIdentifier::ObjectType => {
+                Ok(PropertyValue::Enumerated(u32::from(ObjectType::Schedule)))
+            }
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::PresentValue => Ok(self.present_value.clone()),
+            PropertyIdentifier::EffectivePeriod => {
+                Ok(CalendarEntry::DateRange(self.effective_period).to_property_value())
+            }
+            PropertyIdentifier::WeeklySchedule => self.weekly_schedule_value(),
+            PropertyIdentifier::ExceptionSchedule => self
+                .exception_schedule
+                .as_ref()
+                .map(|events| {
+                    PropertyValue::Array(
+                        events.iter().map(SpecialEvent::to_property_value).collect(),
+                    )
+                })
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::ScheduleDefault => Ok(self.schedule_default.clone()),
+            PropertyIdentifier::ListOfObjectPropertyReferences => Ok(PropertyValue::List(
+                self.list_of_object_property_references
+                    .iter()
+                    .map(DeviceObjectPropertyReference::to_property_value)
+                    .collect(),
+            )),
+            PropertyIdentifier::PriorityForWriting => Ok(PropertyValue::UnsignedInteger(
+                self.priority_for_writing as u32,
+            )),
+            PropertyIdentifier::StatusFlags => Ok(crate::object::status_flags_bit_string(
+                crate::object::current_status_flags(
+                    self.status_flags,
+                    crate::object::EventState::Normal,
+                    self.reliability,
+                    self.out_of_service,
+                ),
+            )),
+            PropertyIdentifier::Reliability => {
+                Ok(PropertyValue::Enumerated(self.reliability as u32))
+            }
+            PropertyIdentifier::OutOfService => Ok(PropertyValue::Boolean(self.out_of_service)),
+            _ => Err(ObjectError::UnknownProperty),
+        }
+    }
+
+    fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        match property {
+            PropertyIdentifier::ObjectName => {
+                if let PropertyValue::CharacterString(name) = value {
+                    self.object_name = name;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::PresentValue => {
+                // Present_Value is only writable while the engine is suspended
+                if !self.out_of_service {
+                    return Err(ObjectError::WriteAccessDenied);
+                }
+                self.present_value = value;
+                Ok(())
+            }
+            PropertyIdentifier::EffectivePeriod => {
+                match CalendarEntry::from_property_value(&value)? {
+                    CalendarEntry::DateRange(range) => {
+                        self.effective_period = range;
+                        Ok(())
+                    }
+                    _ => Err(ObjectError::InvalidPropertyType),
+                }
+            }
+            PropertyIdentifier::WeeklySchedule => {
+                let PropertyValue::Array(days) = value else {
+                    return Err(ObjectError::InvalidPropertyType);
+                };
+                if days.len() != 7 {
+                    return Err(ObjectError::InvalidValue(
+                        "Weekly_Schedule must have 7 entries".to_string(),
+                    ));
+                }
+                let mut week: [Vec<TimeValue>; 7] = Default::default();
+                for (slot, day) in week.iter_mut().zip(days.iter()) {
+                    let PropertyValue::List(items) = day else {
+                        return Err(ObjectError::InvalidPropertyType);
+                    };
+                    *slot = items
+                        .iter()
+                        .map(TimeValue::from_property_value)
+                        .collect::<Result<Vec<_>>>()?;
+                }
+                self.weekly_schedule = Some(week);
+                Ok(())
+            }
+            PropertyIdentifier::ExceptionSchedule => {
+                if let PropertyValue::Array(events) = value {
+                    self.exception_schedule = Some(
+                        events
+                            .iter()
+                            .map(SpecialEvent::from_property_value)
+                            .collect::<Result<Vec<_>>>()?,
+                    );
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::ScheduleDefault => {
+                self.schedule_default = value;
+                Ok(())
+            }
+            PropertyIdentifier::PriorityForWriting => match value {
+                PropertyValue::UnsignedInteger(priority @ 1..=16) => {
+                    self.priority_for_writing = priority as u8;
+                    Ok(())
+                }
+                PropertyValue::UnsignedInteger(_) => Err(ObjectError::InvalidValue(
+                    "Priority must be 1-16".to_string(),
+                )),
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            PropertyIdentifier::OutOfService => {
+                if let PropertyValue::Boolean(oos) = value {
+                    self.out_of_service = oos;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            _ => Err(ObjectError::PropertyNotWritable),
+        }
+    }
+
+    fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
+        match property {
+            PropertyIdentifier::ObjectName
+            | PropertyIdentifier::Description
+            | PropertyIdentifier::EffectivePeriod
+            | PropertyIdentifier::WeeklySchedule
+            | PropertyIdentifier::ExceptionSchedule
+            | PropertyIdentifier::ScheduleDefault
+            | PropertyIdentifier::PriorityForWriting
+            | PropertyIdentifier::OutOfService => true,
+            PropertyIdentifier::PresentValue => self.out_of_service,
+            _ => false,
+        }
+    }
+
+    fn property_list(&self) -> Vec<PropertyIdentifier> {
+        let mut properties = vec![
+            PropertyIdentifier::ObjectIdentifier,
+            PropertyIdentifier::ObjectName,
+            PropertyIdentifier::ObjectType,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::PresentValue,
+            PropertyIdentifier::EffectivePeriod,
+        ];
+        if self.weekly_schedule.is_some() {
+            properties.push(PropertyIdentifier::WeeklySchedule);
+        }
+        if self.exception_schedule.is_some() {
+            properties.push(PropertyIdentifier::ExceptionSchedule);
+        }
+        properties.extend([
+            PropertyIdentifier::ScheduleDefault,
+            PropertyIdentifier::ListOfObjectPropertyReferences,
+            PropertyIdentifier::PriorityForWriting,
+            PropertyIdentifier::StatusFlags,
+            PropertyIdentifier::Reliability,
+            PropertyIdentifier::OutOfService,
+        ]);
+        properties
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::calendar::WeekNDay;
+
+    fn time(hour: u8, minute: u8) -> Time {
+        Time {
+            hour,
+            minute,
+            second: 0,
+            hundredths: 0,
+        }
+    }
+
+    // 2024-03-04 was a Monday
+    fn monday() -> Date {
+        Date {
+            year: 2024,
+            month: 3,
+            day: 4,
+            weekday: 1,
+        }
+    }
+
+    fn occupancy_schedule() -> Schedule {
+        let mut schedule = Schedule::new(1, "Occupancy".to_string(), PropertyValue::Enumerated(0));
+        let mut week: [Vec<TimeValue>; 7] = Default::default();
+        for day in week.iter_mut().take(5) {
+            day.push(TimeValue::new(time(7, 0), PropertyValue::Enumerated(1)));
+            day.push(TimeValue::new(time(18, 0), PropertyValue::Null));
+        }
+        schedule.weekly_schedule = Some(week);
+        schedule
+    }
+
+    #[test]
+    fn test_weekly_schedule_evaluation() {
+        let schedule = occupancy_schedule();
+        let no_calendars = |_| None;
+
+        assert!(matches!(
+            schedule.scheduled_value(&monday(), &time(6, 59), no_calendars),
+            PropertyValue::Enumerated(0)
+        ));
+        assert!(matches!(
+            schedule.scheduled_value(&monday(), &time(12, 0), no_calendars),
+            PropertyValue::Enumerated(1)
+        ));
+        // NULL falls back to the schedule default
+        assert!(matches!(
+            schedule.scheduled_value(&monday(), &time(18, 30), no_calendars),
+            PropertyValue::Enumerated(0)
+        ));
+        // Saturday, weekday derived from the date
+        let saturday = Date {
+            year: 2024,
+            month: 3,
+            day: 9,
+            weekday: 255,
+        };
+        assert!(matches!(
+            schedule.scheduled_value(&saturday, &time(12, 0), no_calendars),
+            PropertyValue::Enumerated(0)
+        ));
+        assert_eq!(
+            schedule.next_transition(&monday(), &time(12, 0)),
+            Some(time(18, 0))
+        );
+    }
+
+    #[test]
+    fn test_exception_schedule_priority_and_calendars() {
+        let mut schedule = occupancy_schedule();
+        let holidays = ObjectIdentifier::new(ObjectType::Calendar, 1);
+        schedule.exception_schedule = Some(vec![
+            // Holiday calendar: unoccupied all day
+            SpecialEvent::new(
+                SpecialEventPeriod::CalendarReference(holidays),
+                vec![TimeValue::new(time(0, 0), PropertyValue::Enumerated(0))],
+                10,
+            ),
+            // First Monday of the month: late start, higher priority
+            SpecialEvent::new(
+                SpecialEventPeriod::CalendarEntry(CalendarEntry::WeekNDay(WeekNDay::new(
+                    255, 1, 1,
+                ))),
+                vec![
+                    TimeValue::new(time(7, 0), PropertyValue::Enumerated(2)),
+                    TimeValue::new(time(9, 0), PropertyValue::Null),
+                ],
+                5,
+            ),
+        ]);
+
+        let holiday = |id: ObjectIdentifier| Some(id == holidays);
+        assert!(matches!(
+            schedule.scheduled_value(&monday(), &time(8, 0), holiday),
+            PropertyValue::Enumerated(2)
+        ));
+        // Once the priority 5 event relinquishes, the holiday applies
+        assert!(matches!(
+            schedule.scheduled_value(&monday(), &time(10, 0), holiday),
+            PropertyValue::Enumerated(0)
+        ));
+        assert!(matches!(
+            schedule.scheduled_value(&monday(), &time(10, 0), |_| Some(false)),
+            PropertyValue::Enumerated(1)
+        ));
+    }
+
+    #[test]
+    fn test_schedule_writes_and_effective_period() {
+        let mut schedule = occupancy_schedule();
+        let target = DeviceObjectPropertyReference::new(
+            ObjectIdentifier::new(ObjectType::BinaryValue, 3),
+            PropertyIdentifier::PresentValue,
+        );
+        schedule.list_of_object_property_references.push(target);
+        schedule.priority_for_writing = 12;
+
+        let writes = schedule.evaluate(&monday(), &time(7, 0), |_| None);
+        assert_eq!(writes.len(), 1);
+        assert_eq!(writes[0].priority, 12);
+        assert_eq!(writes[0].reference, target);
+        assert!(matches!(writes[0].value, PropertyValue::Enumerated(1)));
+
+        // No change, no writes
+        assert!(schedule
+            .evaluate(&monday(), &time(8, 0), |_| None)
+            .is_empty());
+
+        // Outside the effective period the default applies
+        schedule.effective_period = DateRange::new(
+            Date {
+                year: 2024,
+                month: 4,
+                day: 1,
+                weekday: 255,
+            },
+            Date {
+                year: 255,
+                month: 255,
+                day: 255,
+                weekday: 255,
+            },
+        );
+        let writes = schedule.evaluate(&monday(), &time(8, 0), |_| None);
+        assert!(matches!(writes[0].value, PropertyValue::Enumerated(0)));
+
+        schedule.weekly_schedule.as_mut().unwrap()[0]
+            .push(TimeValue::new(time(12, 0), PropertyValue::Real(1.0)));
+        assert!(schedule.validate().is_err());
+    }
+
+    #[test]
+    fn test_schedule_properties_round_trip() {
+        let mut schedule = occupancy_schedule();
+        let weekly = schedule
+            .get_property(PropertyIdentifier::WeeklySchedule)
+            .unwrap();
+        schedule.weekly_schedule = None;
+        schedule
+            .set_property(PropertyIdentifier::WeeklySchedule, weekly)
+            .unwrap();
+        assert_eq!(schedule.weekly_schedule.as_ref().unwrap()[0].len(), 2);
+
+        let events = PropertyValue::Array(vec![SpecialEvent::new(
+            SpecialEventPeriod::CalendarReference(ObjectIdentifier::new(ObjectType::Calendar, 2)),
+            vec![TimeValue::new(time(0, 0), PropertyValue::Enumerated(0))],
+            3,
+        )
+        .to_property_value()]);
+        schedule
+            .set_property(PropertyIdentifier::ExceptionSchedule, events)
+            .unwrap();
+        assert!(matches!(
+            schedule.exception_schedule.as_ref().unwrap()[0].period,
+            SpecialEventPeriod::CalendarReference(_)
+        ));
+
+        assert!(schedule
+            .set_property(
+                PropertyIdentifier::PriorityForWriting,
+                PropertyValue::UnsignedInteger(0)
+            )
+            .is_err());
+        assert!(schedule
+            .set_property(
+                PropertyIdentifier::PresentValue,
+                PropertyValue::Enumerated(1)
+            )
+            .is_err());
+    }
+}
@@ -0,0 +1,577 @@
+//! Staging Object Type Implementation
+//!
+//! This module implements the Staging object type added to ASHRAE 135 by Addendum
+//! 135-2016bd. A Staging object maps a commandable analog Present_Value onto a set
+//! of stages, such as the compressors of a multi-stage chiller. Each entry of the
+//! Stages array gives the lower limit of a stage, the bits that say which
+//! Target_References are active in it, and a deadband.
+//!
+//! Present_Stage is the highest stage whose limit Present_Value has reached, with
+//! stage 1 also covering values below its own limit. Moving up happens as soon as
+//! a limit is reached; moving down only once Present_Value falls below the current
+//! stage's limit by more than its deadband, so a value hovering at a limit does not
+//! cycle the outputs. On every stage change the stage's bits are written to the
+//! targets' Present_Value at Priority_For_Writing, coerced to each target's
+//! datatype as a Channel does, so binary and analog outputs can be staged alike.
+
+use crate::object::channel::coerce_value;
+use crate::object::{
+    current_status_flags, engineering_units::EngineeringUnits, status_flags_bit_string,
+    BacnetObject, Commandable, DeviceObjectPropertyReference, DeviceObjectReference, EventState,
+    ObjectError, ObjectIdentifier, ObjectType, PropertyIdentifier, PropertyValue, PropertyWrite,
+    Reliability, Result, DEFAULT_COMMAND_PRIORITY,
+};
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+/// One entry of the Stages array (BACnetStageLimitValue)
+#[derive(Debug, Clone, PartialEq)]
+pub struct StageLimitValue {
+    /// Present_Value at which the stage is entered
+    pub limit: f32,
+    /// Which Target_References are active in this stage, by position
+    pub values: Vec<bool>,
+    /// How far below `limit` Present_Value must fall to leave the stage
+    pub deadband: f32,
+}
+
+impl StageLimitValue {
+    /// Create a stage entry
+    pub fn new(limit: f32, values: Vec<bool>, deadband: f32) -> Self {
+        Self {
+            limit,
+            values,
+            deadband,
+        }
+    }
+
+    /// Encode as `[limit, values, deadband]`
+    pub fn to_property_value(&self) -> PropertyValue {
+        PropertyValue::List(vec![
+            PropertyValue::Real(self.limit),
+            PropertyValue::BitString(self.values.clone()),
+            PropertyValue::Real(self.deadband),
+        ])
+    }
+
+    /// Decode from `[limit, values, deadband]`
+    pub fn from_property_value(value: &PropertyValue) -> Result<Self> {
+        let PropertyValue::List(items) = value else {
+            return Err(ObjectError::InvalidPropertyType);
+        };
+        let [limit, values, deadband] = items.as_slice() else {
+            return Err(ObjectError::InvalidPropertyType);
+        };
+        match (limit, values, deadband) {
+            (
+                PropertyValue::Real(limit),
+                PropertyValue::BitString(values),
+                PropertyValue::Real(deadband),
+            ) => Ok(Self::new(*limit, values.clone(), *deadband)),
+            _ => Err(ObjectError::InvalidPropertyType),
+        }
+    }
+}
+
+/// Staging object
+#[derive(Debug, Clone)]
+pub struct Staging {
+    /// Object identifier
+    pub identifier: ObjectIdentifier,
+    /// Object name
+    pub object_name: String,
+    /// Description
+    pub description: String,
+    /// Present value, as commanded through the priority array
+    pub present_value: f32,
+    /// Status flags
+    pub status_flags: u8,
+    /// Event state
+    pub event_state: EventState,
+    /// Reliability
+    pub reliability: Reliability,
+    /// Out of service; the targets are not written while set
+    pub out_of_service: bool,
+    /// Engineering units of Present_Value
+    pub units: EngineeringUnits,
+    /// Objects whose Present_Value the stages drive
+    pub target_references: Vec<DeviceObjectReference>,
+    /// Priority for writing to the targets
+    pub priority_for_writing: u8,
+    /// Smallest value that may be commanded
+    pub min_pres_value: Option<f32>,
+    /// Largest value that may be commanded
+    pub max_pres_value: Option<f32>,
+    /// Priority array and relinquish default
+    pub commandable: Commandable<f32>,
+    /// Names of the stages, if configured
+    pub stage_names: Option<Vec<String>>,
+    stages: Vec<StageLimitValue>,
+    present_stage: u32,
+    pending_writes: Vec<PropertyWrite>,
+}
+
+impl Staging {
+    /// Create a new Staging object; `stages` must be in ascending order of limit
+    pub fn new(
+        instance: u32,
+        object_name: String,
+        stages: Vec<StageLimitValue>,
+        target_references: Vec<DeviceObjectReference>,
+    ) -> Result<Self> {
+        check_stages(&stages)?;
+        let mut staging = Self {
+            identifier: ObjectIdentifier::new(ObjectType::Staging, instance),
+            object_name,
+            description: String::new(),
+            present_value: 0.0,
+            status_flags: 0,
+            event_state: EventState::Normal,
+            reliability: Reliability::NoFaultDetected,
+            out_of_service: false,
+            units: EngineeringUnits::NoUnits,
+            target_references,
+            priority_for_writing: 16,
+            min_pres_value: None,
+            max_pres_value: None,
+            commandable: Commandable::new(0.0),
+            stage_names: None,
+            stages,
+            present_stage: 0,
+            pending_writes: Vec::new(),
+        };
+        staging.update_present_value();
+        Ok(staging)
+    }
+
+    /// Configured stages, in ascending order of limit
+    pub fn stages(&self) -> &[StageLimitValue] {
+        &self.stages
+    }
+
+    /// Replace the stages and re-evaluate the present stage
+    pub fn set_stages(&mut self, stages: Vec<StageLimitValue>) -> Result<()> {
+        check_stages(&stages)?;
+        self.stages = stages;
+        self.present_stage = 0;
+        self.update_stage();
+        Ok(())
+    }
+
+    /// Current stage, 1-based; 0 when no stages are configured
+    pub fn present_stage(&self) -> u32 {
+        self.present_stage
+    }
+
+    /// Write to priority array at specified priority level (1-16)
+    pub fn write_priority(&mut self, priority: u8, value: Option<f32>) -> Result<()> {
+        if !(1..=16).contains(&priority) {
+            return Err(ObjectError::InvalidValue(
+                "Priority must be 1-16".to_string(),
+            ));
+        }
+        if let Some(val) = value {
+            self.check_range(val)?;
+        }
+        self.commandable.write(priority, value)?;
+        self.update_present_value();
+        Ok(())
+    }
+
+    /// Reject commands outside the Min_Pres_Value/Max_Pres_Value range
+    fn check_range(&self, value: f32) -> Result<()> {
+        let below = self.min_pres_value.is_some_and(|min| value < min);
+        let above = self.max_pres_value.is_some_and(|max| value > max);
+        if below || above {
+            return Err(ObjectError::InvalidValue(format!(
+                "Value {} outside present value range",
+                value
+            )));
+        }
+        Ok(())
+    }
+
+    fn update_present_value(&mut self) {
+        self.present_value = *self.commandable.current();
+        self.update_stage();
+    }
+
+    /// The stage Present_Value calls for, given the current stage
+    fn target_stage(&self) -> u32 {
+        if self.stages.is_empty() {
+            return 0;
+        }
+        let reached = self
+            .stages
+            .iter()
+            .rposition(|stage| self.present_value >= stage.limit)
+            .unwrap_or(0) as u32
+            + 1;
+        let mut stage = self.present_stage.max(1);
+        if reached >= stage {
+            return reached;
+        }
+        while stage > reached {
+            let current = &self.stages[(stage - 1) as usize];
+            if self.present_value >= current.limit - current.deadband {
+                break;
+            }
+            stage -= 1;
+        }
+        stage
+    }
+
+    fn update_stage(&mut self) {
+        let stage = self.target_stage();
+        if stage == self.present_stage {
+            return;
+        }
+        self.present_stage = stage;
+        if self.out_of_service || stage == 0 {
+            return;
+        }
+        let values = &self.stages[(stage - 1) as usize].values;
+        self.pending_writes.clear();
+        for (index, target) in self.target_references.iter().enumerate() {
+            let mut reference = DeviceObjectPropertyReference::new(
+                target.object_identifier,
+                PropertyIdentifier::PresentValue,
+            );
+            reference.device_identifier = target.device_identifier;
+            let active = PropertyValue::Boolean(values.get(index).copied().unwrap_or(false));
+            if let Some(value) = coerce_value(&active, &reference) {
+                self.pending_writes.push(PropertyWrite {
+                    reference,
+                    value,
+                    priority: self.priority_for_writing,
+                });
+            }
+        }
+    }
+
+    /// Current Status_Flags, combining the stored flags with the state derived
+    /// from Event_State, Reliability and Out_Of_Service
+    pub fn current_status_flags(&self) -> u8 {
+        current_status_flags(
+            self.status_flags,
+            self.event_state,
+            self.reliability,
+            self.out_of_service,
+        )
+    }
+}
+
+fn check_stages(stages: &[StageLimitValue]) -> Result<()> {
+    if stages.windows(2).any(|pair| pair[1].limit <= pair[0].limit) {
+        return Err(ObjectError::InvalidValue(
+            "Stage limits must be in ascending order".to_string(),
+        ));
+    }
+    if stages.iter().any(|stage| stage.deadband < 0.0) {
+        return Err(ObjectError::InvalidValue(
+            "Stage deadband must not be negative".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+impl BacnetObject for Staging {
+    fn identifier(&self) -> ObjectIdentifier {
+        self.identifier
+    }
+
+    fn get_property(&self, property: PropertyIdentifier) -> Result<PropertyValue> {
+        match property {
+            PropertyIdentifier::ObjectIdentifier => {
+                Ok(PropertyValue::ObjectIdentifier(self.identifier))
+            }
+            PropertyIdentifier::ObjectName => {
+                Ok(PropertyValue::CharacterString(self.object_name.clone()))
+            }
+            PropertyIdentifier::ObjectType => {
+                Ok(PropertyValue::Enumerated(u32::from(ObjectType::Staging)))
+            }
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::PresentValue => Ok(PropertyValue::Real(self.present_value)),
+            PropertyIdentifier::PresentStage => {
+                Ok(PropertyValue::UnsignedInteger(self.present_stage))
+            }
+            PropertyIdentifier::Stages => Ok(PropertyValue::Array(
+                self.stages
+                    .iter()
+                    .map(StageLimitValue::to_property_value)
+                    .collect(),
+            )),
+            PropertyIdentifier::StageNames => self
+                .stage_names
+                .as_ref()
+                .map(|names| {
+                    PropertyValue::Array(
+                        names
+                            .iter()
+                            .cloned()
+                            .map(PropertyValue::CharacterString)
+                            .collect(),
+                    )
+                })
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::StatusFlags => {
+                Ok(status_flags_bit_string(self.current_status_flags()))
+            }
+            PropertyIdentifier::EventState => {
+                Ok(PropertyValue::Enumerated(self.event_state as u32))
+            }
+            PropertyIdentifier::Reliability => {
+                Ok(PropertyValue::Enumerated(self.reliability as u32))
+            }
+            PropertyIdentifier::OutOfService => Ok(PropertyValue::Boolean(self.out_of_service)),
+            PropertyIdentifier::Units => Ok(PropertyValue::Enumerated(self.units.to_u32())),
+            PropertyIdentifier::TargetReferences => Ok(PropertyValue::Array(
+                self.target_references
+                    .iter()
+                    .map(DeviceObjectReference::to_property_value)
+                    .collect(),
+            )),
+            PropertyIdentifier::PriorityForWriting => Ok(PropertyValue::UnsignedInteger(
+                self.priority_for_writing as u32,
+            )),
+            PropertyIdentifier::MinPresValue => self
+                .min_pres_value
+                .map(PropertyValue::Real)
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::MaxPresValue => self
+                .max_pres_value
+                .map(PropertyValue::Real)
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::PriorityArray => Ok(self
+                .commandable
+                .priority_array_value(|&value| PropertyValue::Real(value))),
+            PropertyIdentifier::RelinquishDefault => {
+                Ok(PropertyValue::Real(*self.commandable.relinquish_default()))
+            }
+            _ => Err(ObjectError::UnknownProperty),
+        }
+    }
+
+    fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        self.set_property_with_priority(property, value, DEFAULT_COMMAND_PRIORITY)
+    }
+
+    fn set_property_with_priority(
+        &mut self,
+        property: PropertyIdentifier,
+        value: PropertyValue,
+        priority: u8,
+    ) -> Result<()> {
+        match property {
+            PropertyIdentifier::ObjectName => {
+                if let PropertyValue::CharacterString(name) = value {
+                    self.object_name = name;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::PresentValue => match value {
+                PropertyValue::Real(val) => self.write_priority(priority, Some(val)),
+                // Writing NULL relinquishes the command at this priority
+                PropertyValue::Null => self.write_priority(priority, None),
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            PropertyIdentifier::Stages => match value {
+                PropertyValue::Array(items) => self.set_stages(
+                    items
+                        .iter()
+                        .map(StageLimitValue::from_property_value)
+                        .collect::<Result<Vec<_>>>()?,
+                ),
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            PropertyIdentifier::StageNames => match value {
+                PropertyValue::Array(items) => {
+                    let names = items
+                        .into_iter()
+                        .map(|item| match item {
+                            PropertyValue::CharacterString(name) => Ok(name),
+                            _ => Err(ObjectError::InvalidPropertyType),
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    self.stage_names = Some(names);
+                    Ok(())
+                }
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            PropertyIdentifier::OutOfService => {
+                if let PropertyValue::Boolean(oos) = value {
+                    self.out_of_service = oos;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Units => {
+                if let PropertyValue::Enumerated(units) = value {
+                    self.units = EngineeringUnits::from_u32(units);
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::PriorityForWriting => match value {
+                PropertyValue::UnsignedInteger(priority @ 1..=16) => {
+                    self.priority_for_writing = priority as u8;
+                    Ok(())
+                }
+                PropertyValue::UnsignedInteger(_) => Err(ObjectError::InvalidValue(
+                    "Priority must be 1-16".to_string(),
+                )),
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            PropertyIdentifier::RelinquishDefault => {
+                if let PropertyValue::Real(val) = value {
+                    self.check_range(val)?;
+                    self.commandable.set_relinquish_default(val);
+                    self.update_present_value();
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            _ => Err(ObjectError::PropertyNotWritable),
+        }
+    }
+
+    fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
+        matches!(
+            property,
+            PropertyIdentifier::ObjectName
+                | PropertyIdentifier::Description
+                | PropertyIdentifier::PresentValue
+                | PropertyIdentifier::Stages
+                | PropertyIdentifier::StageNames
+                | PropertyIdentifier::OutOfService
+                | PropertyIdentifier::Units
+                | PropertyIdentifier::PriorityForWriting
+                | PropertyIdentifier::RelinquishDefault
+        )
+    }
+
+    fn property_list(&self) -> Vec<PropertyIdentifier> {
+        let mut properties = vec![
+            PropertyIdentifier::ObjectIdentifier,
+            PropertyIdentifier::ObjectName,
+            PropertyIdentifier::ObjectType,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::PresentValue,
+            PropertyIdentifier::PresentStage,
+            PropertyIdentifier::Stages,
+        ];
+        if self.stage_names.is_some() {
+            properties.push(PropertyIdentifier::StageNames);
+        }
+        properties.extend([
+            PropertyIdentifier::StatusFlags,
+            PropertyIdentifier::EventState,
+            PropertyIdentifier::Reliability,
+            PropertyIdentifier::OutOfService,
+            PropertyIdentifier::Units,
+            PropertyIdentifier::TargetReferences,
+            PropertyIdentifier::PriorityForWriting,
+        ]);
+        if self.min_pres_value.is_some() {
+            properties.push(PropertyIdentifier::MinPresValue);
+        }
+        if self.max_pres_value.is_some() {
+            properties.push(PropertyIdentifier::MaxPresValue);
+        }
+        properties.extend([
+            PropertyIdentifier::PriorityArray,
+            PropertyIdentifier::RelinquishDefault,
+        ]);
+        properties
+    }
+
+    fn take_pending_writes(&mut self) -> Vec<PropertyWrite> {
+        core::mem::take(&mut self.pending_writes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chiller_stages() -> Staging {
+        Staging::new(
+            1,
+            "Chiller Staging".to_string(),
+            vec![
+                StageLimitValue::new(0.0, vec![false, false], 0.0),
+                StageLimitValue::new(40.0, vec![true, false], 5.0),
+                StageLimitValue::new(70.0, vec![true, true], 5.0),
+            ],
+            vec![
+                DeviceObjectReference::new(ObjectIdentifier::new(ObjectType::BinaryOutput, 1)),
+                DeviceObjectReference::new(ObjectIdentifier::new(ObjectType::AnalogOutput, 2)),
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_staging_writes_stage_bits_to_targets() {
+        let mut staging = chiller_stages();
+        assert_eq!(staging.present_stage(), 1);
+        staging.take_pending_writes();
+
+        staging.write_priority(8, Some(75.0)).unwrap();
+        assert_eq!(staging.present_stage(), 3);
+        let writes = staging.take_pending_writes();
+        assert_eq!(writes.len(), 2);
+        assert_eq!(writes[0].value, PropertyValue::Enumerated(1));
+        assert_eq!(writes[1].value, PropertyValue::Real(1.0));
+        assert_eq!(writes[1].priority, 16);
+
+        staging.write_priority(8, Some(30.0)).unwrap();
+        assert_eq!(staging.present_stage(), 1);
+        let writes = staging.take_pending_writes();
+        assert_eq!(writes[0].value, PropertyValue::Enumerated(0));
+        assert_eq!(writes[1].value, PropertyValue::Real(0.0));
+    }
+
+    #[test]
+    fn test_staging_deadband_holds_stage() {
+        let mut staging = chiller_stages();
+        staging.write_priority(8, Some(72.0)).unwrap();
+        assert_eq!(staging.present_stage(), 3);
+
+        // Within the deadband of stage 3
+        staging.write_priority(8, Some(66.0)).unwrap();
+        assert_eq!(staging.present_stage(), 3);
+
+        // Below stage 3's deadband but within stage 2's
+        staging.write_priority(8, Some(36.0)).unwrap();
+        assert_eq!(staging.present_stage(), 2);
+
+        staging.write_priority(8, None).unwrap();
+        assert_eq!(staging.present_stage(), 1);
+
+        assert!(matches!(
+            staging.set_stages(vec![
+                StageLimitValue::new(10.0, vec![], 0.0),
+                StageLimitValue::new(5.0, vec![], 0.0),
+            ]),
+            Err(ObjectError::InvalidValue(_))
+        ));
+    }
+}
@@ -0,0 +1,369 @@
+//! Structured View Object Type Implementation
+//!
+//! This module implements the Structured View object type as defined in ASHRAE 135.
+//! A Structured View is a node in a hierarchy of objects that operator workstations
+//! can browse. Node_Type classifies the node (building, floor, equipment, ...),
+//! Subordinate_List names the child objects, which may be other Structured Views or
+//! objects in other devices, and Subordinate_Annotations holds an optional text
+//! label for each child.
+
+use crate::object::{
+    BacnetObject, DeviceObjectReference, ObjectError, ObjectIdentifier, ObjectType,
+    PropertyIdentifier, PropertyValue, Result,
+};
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+/// Kind of node a Structured View represents (BACnetNodeType)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum NodeType {
+    Unknown = 0,
+    System = 1,
+    Network = 2,
+    Device = 3,
+    Organizational = 4,
+    Area = 5,
+    Equipment = 6,
+    Point = 7,
+    Collection = 8,
+    Property = 9,
+    Functional = 10,
+    Other = 11,
+    Subsystem = 12,
+    Building = 13,
+    Floor = 14,
+    Section = 15,
+    Module = 16,
+    Tree = 17,
+    Member = 18,
+    Protocol = 19,
+    Room = 20,
+    Zone = 21,
+}
+
+impl TryFrom<u32> for NodeType {
+    type Error = ObjectError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            0 => Ok(NodeType::Unknown),
+            1 => Ok(NodeType::System),
+            2 => Ok(NodeType::Network),
+            3 => Ok(NodeType::Device),
+            4 => Ok(NodeType::Organizational),
+            5 => Ok(NodeType::Area),
+            6 => Ok(NodeType::Equipment),
+            7 => Ok(NodeType::Point),
+            8 => Ok(NodeType::Collection),
+            9 => Ok(NodeType::Property),
+            10 => Ok(NodeType::Functional),
+            11 => Ok(NodeType::Other),
+            12 => Ok(NodeType::Subsystem),
+            13 => Ok(NodeType::Building),
+            14 => Ok(NodeType::Floor),
+            15 => Ok(NodeType::Section),
+            16 => Ok(NodeType::Module),
+            17 => Ok(NodeType::Tree),
+            18 => Ok(NodeType::Member),
+            19 => Ok(NodeType::Protocol),
+            20 => Ok(NodeType::Room),
+            21 => Ok(NodeType::Zone),
+            _ => Err(ObjectError::InvalidValue(format!(
+                "Invalid node type: {}",
+                value
+            ))),
+        }
+    }
+}
+
+/// Structured View object
+#[derive(Debug, Clone)]
+pub struct StructuredView {
+    /// Object identifier
+    pub identifier: ObjectIdentifier,
+    /// Object name
+    pub object_name: String,
+    /// Description
+    pub description: String,
+    /// Kind of node
+    pub node_type: NodeType,
+    /// Free-form refinement of the node type, such as "AHU"
+    pub node_subtype: Option<String>,
+    /// Child objects of this node
+    pub subordinate_list: Vec<DeviceObjectReference>,
+    /// A label for each child, parallel to Subordinate_List
+    pub subordinate_annotations: Option<Vec<String>>,
+}
+
+impl StructuredView {
+    /// Create a new Structured View with no subordinates
+    pub fn new(instance: u32, object_name: String, node_type: NodeType) -> Self {
+        Self {
+            identifier: ObjectIdentifier::new(ObjectType::StructuredView, instance),
+            object_name,
+            description: String::new(),
+            node_type,
+            node_subtype: None,
+            subordinate_list: Vec::new(),
+            subordinate_annotations: None,
+        }
+    }
+
+    /// Append a child, with an optional annotation
+    ///
+    /// The first annotation enables Subordinate_Annotations; children without
+    /// one are annotated with an empty string.
+    pub fn add_subordinate(
+        &mut self,
+        subordinate: DeviceObjectReference,
+        annotation: Option<&str>,
+    ) {
+        self.subordinate_list.push(subordinate);
+        if annotation.is_some() && self.subordinate_annotations.is_none() {
+            self.subordinate_annotations = Some(Vec::new());
+        }
+        self.resize_annotations();
+        if let (Some(annotation), Some(annotations)) =
+            (annotation, self.subordinate_annotations.as_mut())
+        {
+            if let Some(last) = annotations.last_mut() {
+                *last = annotation.to_string();
+            }
+        }
+    }
+
+    /// Remove the child at `index` (zero-based), with its annotation
+    pub fn remove_subordinate(&mut self, index: usize) -> Option<DeviceObjectReference> {
+        if index >= self.subordinate_list.len() {
+            return None;
+        }
+        if let Some(annotations) = self.subordinate_annotations.as_mut() {
+            annotations.remove(index);
+        }
+        Some(self.subordinate_list.remove(index))
+    }
+
+    /// Iterate over the children with their annotations
+    pub fn subordinates(&self) -> impl Iterator<Item = (&DeviceObjectReference, Option<&str>)> {
+        self.subordinate_list.iter().enumerate().map(|(i, child)| {
+            let annotation = self
+                .subordinate_annotations
+                .as_ref()
+                .and_then(|annotations| annotations.get(i))
+                .map(String::as_str);
+            (child, annotation)
+        })
+    }
+
+    /// Keep Subordinate_Annotations the same size as Subordinate_List
+    fn resize_annotations(&mut self) {
+        let len = self.subordinate_list.len();
+        if let Some(annotations) = self.subordinate_annotations.as_mut() {
+            annotations.resize(len, String::new());
+        }
+    }
+}
+
+impl BacnetObject for StructuredView {
+    fn identifier(&self) -> ObjectIdentifier {
+        self.identifier
+    }
+
+    fn get_property(&self, property: PropertyIdentifier) -> Result<PropertyValue> {
+        match property {
+            PropertyIdentifier::ObjectIdentifier => {
+                Ok(PropertyValue::ObjectIdentifier(self.identifier))
+            }
+            PropertyIdentifier::ObjectName => {
+                Ok(PropertyValue::CharacterString(self.object_name.clone()))
+            }
+            PropertyIdentifier::ObjectType => Ok(PropertyValue::Enumerated(u32::from(
+                ObjectType::StructuredView,
+            ))),
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::NodeType => Ok(PropertyValue::Enumerated(self.node_type as u32)),
+            PropertyIdentifier::NodeSubtype => self
+                .node_subtype
+                .clone()
+                .map(PropertyValue::CharacterString)
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::SubordinateList => Ok(PropertyValue::Array(
+                self.subordinate_list
+                    .iter()
+                    .map(DeviceObjectReference::to_property_value)
+                    .collect(),
+            )),
+            PropertyIdentifier::SubordinateAnnotations => self
+                .subordinate_annotations
+                .as_ref()
+                .map(|annotations| {
+                    PropertyValue::Array(
+                        annotations
+                            .iter()
+                            .cloned()
+                            .map(PropertyValue::CharacterString)
+                            .collect(),
+                    )
+                })
+                .ok_or(ObjectError::UnknownProperty),
+            _ => Err(ObjectError::UnknownProperty),
+        }
+    }
+
+    fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        match property {
+            PropertyIdentifier::ObjectName => {
+                if let PropertyValue::CharacterString(name) = value {
+                    self.object_name = name;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::NodeType => {
+                if let PropertyValue::Enumerated(node_type) = value {
+                    self.node_type = NodeType::try_from(node_type)?;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::NodeSubtype if self.node_subtype.is_some() => {
+                if let PropertyValue::CharacterString(subtype) = value {
+                    self.node_subtype = Some(subtype);
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::SubordinateList => {
+                if let PropertyValue::Array(items) = value {
+                    self.subordinate_list = items
+                        .iter()
+                        .map(DeviceObjectReference::from_property_value)
+                        .collect::<Result<Vec<_>>>()?;
+                    self.resize_annotations();
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::SubordinateAnnotations
+                if self.subordinate_annotations.is_some() =>
+            {
+                if let PropertyValue::Array(items) = value {
+                    if items.len() != self.subordinate_list.len() {
+                        return Err(ObjectError::InvalidValue(
+                            "Subordinate_Annotations must match Subordinate_List in size"
+                                .to_string(),
+                        ));
+                    }
+                    let annotations = items
+                        .into_iter()
+                        .map(|item| match item {
+                            PropertyValue::CharacterString(annotation) => Ok(annotation),
+                            _ => Err(ObjectError::InvalidPropertyType),
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    self.subordinate_annotations = Some(annotations);
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            _ => Err(ObjectError::PropertyNotWritable),
+        }
+    }
+
+    fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
+        match property {
+            PropertyIdentifier::ObjectName
+            | PropertyIdentifier::Description
+            | PropertyIdentifier::NodeType
+            | PropertyIdentifier::SubordinateList => true,
+            PropertyIdentifier::NodeSubtype => self.node_subtype.is_some(),
+            PropertyIdentifier::SubordinateAnnotations => self.subordinate_annotations.is_some(),
+            _ => false,
+        }
+    }
+
+    fn property_list(&self) -> Vec<PropertyIdentifier> {
+        let mut properties = vec![
+            PropertyIdentifier::ObjectIdentifier,
+            PropertyIdentifier::ObjectName,
+            PropertyIdentifier::ObjectType,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::NodeType,
+        ];
+        if self.node_subtype.is_some() {
+            properties.push(PropertyIdentifier::NodeSubtype);
+        }
+        properties.push(PropertyIdentifier::SubordinateList);
+        if self.subordinate_annotations.is_some() {
+            properties.push(PropertyIdentifier::SubordinateAnnotations);
+        }
+        properties
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subordinates_and_annotations() {
+        let mut floor = StructuredView::new(1, "Floor 2".to_string(), NodeType::Floor);
+        let ahu = ObjectIdentifier::new(ObjectType::StructuredView, 2);
+        let sensor = ObjectIdentifier::new(ObjectType::AnalogInput, 7);
+        floor.add_subordinate(DeviceObjectReference::new(ahu), None);
+        assert!(floor.subordinate_annotations.is_none());
+        floor.add_subordinate(DeviceObjectReference::new(sensor), Some("Corridor temp"));
+        assert_eq!(
+            floor.subordinate_annotations,
+            Some(vec![String::new(), "Corridor temp".to_string()])
+        );
+
+        let labels: Vec<_> = floor.subordinates().map(|(_, label)| label).collect();
+        assert_eq!(labels, vec![Some(""), Some("Corridor temp")]);
+
+        assert_eq!(
+            floor.remove_subordinate(0),
+            Some(DeviceObjectReference::new(ahu))
+        );
+        assert_eq!(floor.subordinate_annotations.as_ref().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_subordinate_list_write() {
+        let mut view = StructuredView::new(1, "Plant".to_string(), NodeType::Equipment);
+        view.subordinate_annotations = Some(Vec::new());
+        let device = ObjectIdentifier::new(ObjectType::Device, 1001);
+        let pump = ObjectIdentifier::new(ObjectType::BinaryOutput, 3);
+        let list = PropertyValue::Array(vec![
+            DeviceObjectReference::new(pump).to_property_value(),
+            DeviceObjectReference::remote(device, pump).to_property_value(),
+        ]);
+        view.set_property(PropertyIdentifier::SubordinateList, list)
+            .unwrap();
+        assert_eq!(view.subordinate_list[1].device_identifier, Some(device));
+        assert_eq!(view.subordinate_annotations.as_ref().unwrap().len(), 2);
+
+        let result = view.set_property(
+            PropertyIdentifier::SubordinateAnnotations,
+            PropertyValue::Array(vec![PropertyValue::CharacterString("Pump".to_string())]),
+        );
+        assert!(matches!(result, Err(ObjectError::InvalidValue(_))));
+    }
+}
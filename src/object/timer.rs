@@ -0,0 +1,584 @@
+//! Timer Object Type Implementation
+//!
+//! This module implements the Timer object type as defined in ASHRAE 135. A Timer
+//! counts Present_Value down to zero in milliseconds and moves between the IDLE,
+//! RUNNING and EXPIRED states (Clause 12.57):
+//!
+//! - Writing a non-zero Present_Value, or TRUE to Timer_Running, starts the timer;
+//!   the latter uses Default_Timeout
+//! - Writing zero to Present_Value, or FALSE to Timer_Running, forces it to expire
+//! - Writing IDLE to Timer_State stops it
+//!
+//! On every transition the matching entry of State_Change_Values, if any, is
+//! written to all members of List_Of_Object_Property_References at
+//! Priority_For_Writing. The host application drives the countdown through
+//! [`BacnetObject::advance_time`]; the writes are collected with
+//! [`BacnetObject::take_pending_writes`].
+
+use crate::object::{
+    current_date_time, current_status_flags, date_time_value, status_flags_bit_string,
+    BacnetObject, DeviceObjectPropertyReference, EventState, ObjectError, ObjectIdentifier,
+    ObjectType, PropertyIdentifier, PropertyValue, PropertyWrite, Reliability, Result,
+};
+use crate::service::BacnetDateTime;
+use core::time::Duration;
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+/// Timer state (BACnetTimerState)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum TimerState {
+    Idle = 0,
+    Running = 1,
+    Expired = 2,
+}
+
+impl TryFrom<u32> for TimerState {
+    type Error = ObjectError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            0 => Ok(TimerState::Idle),
+            1 => Ok(TimerState::Running),
+            2 => Ok(TimerState::Expired),
+            _ => Err(ObjectError::InvalidValue(format!(
+                "Invalid timer state: {}",
+                value
+            ))),
+        }
+    }
+}
+
+/// Timer state transition (BACnetTimerTransition)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum TimerTransition {
+    None = 0,
+    IdleToRunning = 1,
+    RunningToIdle = 2,
+    RunningToRunning = 3,
+    RunningToExpired = 4,
+    ForcedToExpired = 5,
+    ExpiredToIdle = 6,
+    ExpiredToRunning = 7,
+}
+
+impl TryFrom<u32> for TimerTransition {
+    type Error = ObjectError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            0 => Ok(TimerTransition::None),
+            1 => Ok(TimerTransition::IdleToRunning),
+            2 => Ok(TimerTransition::RunningToIdle),
+            3 => Ok(TimerTransition::RunningToRunning),
+            4 => Ok(TimerTransition::RunningToExpired),
+            5 => Ok(TimerTransition::ForcedToExpired),
+            6 => Ok(TimerTransition::ExpiredToIdle),
+            7 => Ok(TimerTransition::ExpiredToRunning),
+            _ => Err(ObjectError::InvalidValue(format!(
+                "Invalid timer transition: {}",
+                value
+            ))),
+        }
+    }
+}
+
+/// Timer object
+#[derive(Debug, Clone)]
+pub struct Timer {
+    /// Object identifier
+    pub identifier: ObjectIdentifier,
+    /// Object name
+    pub object_name: String,
+    /// Description
+    pub description: String,
+    /// Time remaining, in milliseconds
+    pub present_value: u32,
+    /// Status flags
+    pub status_flags: u8,
+    /// Event state
+    pub event_state: EventState,
+    /// Reliability
+    pub reliability: Reliability,
+    /// Out of service
+    pub out_of_service: bool,
+    /// Current state
+    pub timer_state: TimerState,
+    /// Most recent transition
+    pub last_state_change: TimerTransition,
+    /// When the most recent transition happened
+    pub update_time: Option<BacnetDateTime>,
+    /// Timeout of the current or most recent run, in milliseconds
+    pub initial_timeout: u32,
+    /// Timeout used when started through Timer_Running, in milliseconds
+    pub default_timeout: u32,
+    /// Smallest timeout that may be written, in milliseconds
+    pub min_pres_value: Option<u32>,
+    /// Largest timeout that may be written, in milliseconds
+    pub max_pres_value: Option<u32>,
+    /// Countdown resolution, in milliseconds
+    pub resolution: Option<u32>,
+    /// Value written on each transition, indexed by BACnetTimerTransition - 1;
+    /// `None` writes nothing
+    pub state_change_values: [Option<PropertyValue>; 7],
+    /// Properties written on state changes
+    pub list_of_object_property_references: Vec<DeviceObjectPropertyReference>,
+    /// Priority for writing to the referenced properties
+    pub priority_for_writing: u8,
+    pending_writes: Vec<PropertyWrite>,
+}
+
+impl Timer {
+    /// Create a new idle Timer
+    pub fn new(instance: u32, object_name: String, default_timeout: u32) -> Self {
+        Self {
+            identifier: ObjectIdentifier::new(ObjectType::Timer, instance),
+            object_name,
+            description: String::new(),
+            present_value: 0,
+            status_flags: 0,
+            event_state: EventState::Normal,
+            reliability: Reliability::NoFaultDetected,
+            out_of_service: false,
+            timer_state: TimerState::Idle,
+            last_state_change: TimerTransition::None,
+            update_time: None,
+            initial_timeout: 0,
+            default_timeout,
+            min_pres_value: None,
+            max_pres_value: None,
+            resolution: None,
+            state_change_values: Default::default(),
+            list_of_object_property_references: Vec::new(),
+            priority_for_writing: 16,
+            pending_writes: Vec::new(),
+        }
+    }
+
+    /// Add a property written on state changes
+    pub fn add_reference(&mut self, reference: DeviceObjectPropertyReference) {
+        self.list_of_object_property_references.push(reference);
+    }
+
+    /// Set the value written on a transition
+    pub fn set_state_change_value(&mut self, transition: TimerTransition, value: PropertyValue) {
+        if transition != TimerTransition::None {
+            self.state_change_values[transition as usize - 1] = Some(value);
+        }
+    }
+
+    /// Whether the timer is counting down
+    pub fn is_running(&self) -> bool {
+        self.timer_state == TimerState::Running
+    }
+
+    /// Start, or restart, the timer with a timeout in milliseconds
+    pub fn start(&mut self, timeout: u32) -> Result<()> {
+        if timeout == 0 {
+            return Err(ObjectError::InvalidValue(
+                "Timeout must be non-zero".to_string(),
+            ));
+        }
+        if self.min_pres_value.is_some_and(|min| timeout < min)
+            || self.max_pres_value.is_some_and(|max| timeout > max)
+        {
+            return Err(ObjectError::InvalidValue(
+                "Timeout outside Min_Pres_Value..Max_Pres_Value".to_string(),
+            ));
+        }
+        let transition = match self.timer_state {
+            TimerState::Idle => TimerTransition::IdleToRunning,
+            TimerState::Running => TimerTransition::RunningToRunning,
+            TimerState::Expired => TimerTransition::ExpiredToRunning,
+        };
+        self.initial_timeout = timeout;
+        self.present_value = timeout;
+        self.transition(TimerState::Running, transition);
+        Ok(())
+    }
+
+    /// Expire a running timer before its timeout
+    pub fn force_expire(&mut self) {
+        if self.is_running() {
+            self.present_value = 0;
+            self.transition(TimerState::Expired, TimerTransition::ForcedToExpired);
+        }
+    }
+
+    /// Return the timer to IDLE
+    pub fn stop(&mut self) {
+        let transition = match self.timer_state {
+            TimerState::Idle => return,
+            TimerState::Running => TimerTransition::RunningToIdle,
+            TimerState::Expired => TimerTransition::ExpiredToIdle,
+        };
+        self.present_value = 0;
+        self.transition(TimerState::Idle, transition);
+    }
+
+    fn transition(&mut self, state: TimerState, transition: TimerTransition) {
+        self.timer_state = state;
+        self.last_state_change = transition;
+        self.update_time = current_date_time();
+        let Some(value) = &self.state_change_values[transition as usize - 1] else {
+            return;
+        };
+        for reference in &self.list_of_object_property_references {
+            self.pending_writes.push(PropertyWrite {
+                reference: *reference,
+                value: value.clone(),
+                priority: self.priority_for_writing,
+            });
+        }
+    }
+
+    /// Current Status_Flags, combining the stored flags with the state derived
+    /// from Event_State, Reliability and Out_Of_Service
+    pub fn current_status_flags(&self) -> u8 {
+        current_status_flags(
+            self.status_flags,
+            self.event_state,
+            self.reliability,
+            self.out_of_service,
+        )
+    }
+
+    fn state_change_value(value: &Option<PropertyValue>) -> PropertyValue {
+        value.clone().unwrap_or(PropertyValue::Null)
+    }
+}
+
+impl BacnetObject for Timer {
+    fn identifier(&self) -> ObjectIdentifier {
+        self.identifier
+    }
+
+    fn get_property(&self, property: PropertyIdentifier) -> Result<PropertyValue> {
+        match property {
+            PropertyIdentifier::ObjectIdentifier => {
+                Ok(PropertyValue::ObjectIdentifier(self.identifier))
+            }
+            PropertyIdentifier::ObjectName => {
+                Ok(PropertyValue::CharacterString(self.object_name.clone()))
+            }
+            PropertyIdentifier::ObjectType => {
+                Ok(PropertyValue::Enumerated(u32::from(ObjectType::Timer)))
+            }
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::PresentValue => {
+                Ok(PropertyValue::UnsignedInteger(self.present_value))
+            }
+            PropertyIdentifier::StatusFlags => {
+                Ok(status_flags_bit_string(self.current_status_flags()))
+            }
+            PropertyIdentifier::EventState => {
+                Ok(PropertyValue::Enumerated(self.event_state as u32))
+            }
+            PropertyIdentifier::Reliability => {
+                Ok(PropertyValue::Enumerated(self.reliability as u32))
+            }
+            PropertyIdentifier::OutOfService => Ok(PropertyValue::Boolean(self.out_of_service)),
+            PropertyIdentifier::TimerState => {
+                Ok(PropertyValue::Enumerated(self.timer_state as u32))
+            }
+            PropertyIdentifier::TimerRunning => Ok(PropertyValue::Boolean(self.is_running())),
+            PropertyIdentifier::LastStateChange => {
+                Ok(PropertyValue::Enumerated(self.last_state_change as u32))
+            }
+            PropertyIdentifier::UpdateTime => Ok(date_time_value(self.update_time)),
+            PropertyIdentifier::InitialTimeout => {
+                Ok(PropertyValue::UnsignedInteger(self.initial_timeout))
+            }
+            PropertyIdentifier::DefaultTimeout => {
+                Ok(PropertyValue::UnsignedInteger(self.default_timeout))
+            }
+            PropertyIdentifier::MinPresValue => self
+                .min_pres_value
+                .map(PropertyValue::UnsignedInteger)
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::MaxPresValue => self
+                .max_pres_value
+                .map(PropertyValue::UnsignedInteger)
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::Resolution => self
+                .resolution
+                .map(PropertyValue::UnsignedInteger)
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::StateChangeValues => Ok(PropertyValue::Array(
+                self.state_change_values
+                    .iter()
+                    .map(Self::state_change_value)
+                    .collect(),
+            )),
+            PropertyIdentifier::ListOfObjectPropertyReferences => Ok(PropertyValue::List(
+                self.list_of_object_property_references
+                    .iter()
+                    .map(DeviceObjectPropertyReference::to_property_value)
+                    .collect(),
+            )),
+            PropertyIdentifier::PriorityForWriting => Ok(PropertyValue::UnsignedInteger(
+                self.priority_for_writing as u32,
+            )),
+            _ => Err(ObjectError::UnknownProperty),
+        }
+    }
+
+    fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        match property {
+            PropertyIdentifier::ObjectName => {
+                if let PropertyValue::CharacterString(name) = value {
+                    self.object_name = name;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::PresentValue => match value {
+                PropertyValue::UnsignedInteger(0) => {
+                    self.force_expire();
+                    Ok(())
+                }
+                PropertyValue::UnsignedInteger(timeout) => self.start(timeout),
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            PropertyIdentifier::OutOfService => {
+                if let PropertyValue::Boolean(oos) = value {
+                    self.out_of_service = oos;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::TimerState => match value {
+                PropertyValue::Enumerated(state) => match TimerState::try_from(state)? {
+                    TimerState::Idle => {
+                        self.stop();
+                        Ok(())
+                    }
+                    _ => Err(ObjectError::InvalidValue(
+                        "Timer_State may only be written with IDLE".to_string(),
+                    )),
+                },
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            PropertyIdentifier::TimerRunning => match value {
+                PropertyValue::Boolean(true) => self.start(self.default_timeout),
+                PropertyValue::Boolean(false) => {
+                    self.force_expire();
+                    Ok(())
+                }
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            PropertyIdentifier::DefaultTimeout => {
+                if let PropertyValue::UnsignedInteger(timeout) = value {
+                    self.default_timeout = timeout;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::StateChangeValues => match value {
+                PropertyValue::Array(values) if values.len() == 7 => {
+                    for (slot, value) in self.state_change_values.iter_mut().zip(values) {
+                        *slot = match value {
+                            PropertyValue::Null => None,
+                            value => Some(value),
+                        };
+                    }
+                    Ok(())
+                }
+                PropertyValue::Array(_) => Err(ObjectError::InvalidValue(
+                    "State_Change_Values must have 7 entries".to_string(),
+                )),
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            PropertyIdentifier::PriorityForWriting => match value {
+                PropertyValue::UnsignedInteger(priority @ 1..=16) => {
+                    self.priority_for_writing = priority as u8;
+                    Ok(())
+                }
+                PropertyValue::UnsignedInteger(_) => Err(ObjectError::InvalidValue(
+                    "Priority must be 1-16".to_string(),
+                )),
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            _ => Err(ObjectError::PropertyNotWritable),
+        }
+    }
+
+    fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
+        matches!(
+            property,
+            PropertyIdentifier::ObjectName
+                | PropertyIdentifier::Description
+                | PropertyIdentifier::PresentValue
+                | PropertyIdentifier::OutOfService
+                | PropertyIdentifier::TimerState
+                | PropertyIdentifier::TimerRunning
+                | PropertyIdentifier::DefaultTimeout
+                | PropertyIdentifier::StateChangeValues
+                | PropertyIdentifier::PriorityForWriting
+        )
+    }
+
+    fn property_list(&self) -> Vec<PropertyIdentifier> {
+        let mut properties = vec![
+            PropertyIdentifier::ObjectIdentifier,
+            PropertyIdentifier::ObjectName,
+            PropertyIdentifier::ObjectType,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::PresentValue,
+            PropertyIdentifier::StatusFlags,
+            PropertyIdentifier::EventState,
+            PropertyIdentifier::Reliability,
+            PropertyIdentifier::OutOfService,
+            PropertyIdentifier::TimerState,
+            PropertyIdentifier::TimerRunning,
+            PropertyIdentifier::LastStateChange,
+            PropertyIdentifier::UpdateTime,
+            PropertyIdentifier::InitialTimeout,
+            PropertyIdentifier::DefaultTimeout,
+        ];
+        if self.min_pres_value.is_some() {
+            properties.push(PropertyIdentifier::MinPresValue);
+        }
+        if self.max_pres_value.is_some() {
+            properties.push(PropertyIdentifier::MaxPresValue);
+        }
+        if self.resolution.is_some() {
+            properties.push(PropertyIdentifier::Resolution);
+        }
+        properties.extend([
+            PropertyIdentifier::StateChangeValues,
+            PropertyIdentifier::ListOfObjectPropertyReferences,
+            PropertyIdentifier::PriorityForWriting,
+        ]);
+        properties
+    }
+
+    fn advance_time(&mut self, elapsed: Duration) {
+        if !self.is_running() {
+            return;
+        }
+        let elapsed = u32::try_from(elapsed.as_millis()).unwrap_or(u32::MAX);
+        self.present_value = self.present_value.saturating_sub(elapsed);
+        if self.present_value == 0 {
+            self.transition(TimerState::Expired, TimerTransition::RunningToExpired);
+        }
+    }
+
+    fn take_pending_writes(&mut self) -> Vec<PropertyWrite> {
+        core::mem::take(&mut self.pending_writes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timer_counts_down_and_writes_on_expiry() {
+        let mut timer = Timer::new(1, "Override Timer".to_string(), 5000);
+        let target = DeviceObjectPropertyReference::new(
+            ObjectIdentifier::new(ObjectType::BinaryOutput, 3),
+            PropertyIdentifier::PresentValue,
+        );
+        timer.add_reference(target);
+        timer.priority_for_writing = 9;
+        timer.set_state_change_value(TimerTransition::IdleToRunning, PropertyValue::Enumerated(1));
+        timer.set_state_change_value(
+            TimerTransition::RunningToExpired,
+            PropertyValue::Enumerated(0),
+        );
+
+        timer
+            .set_property(
+                PropertyIdentifier::TimerRunning,
+                PropertyValue::Boolean(true),
+            )
+            .unwrap();
+        assert_eq!(timer.present_value, 5000);
+        let writes = timer.take_pending_writes();
+        assert_eq!(writes.len(), 1);
+        assert_eq!(writes[0].value, PropertyValue::Enumerated(1));
+        assert_eq!(writes[0].priority, 9);
+
+        timer.advance_time(Duration::from_millis(3000));
+        assert_eq!(
+            timer
+                .get_property(PropertyIdentifier::PresentValue)
+                .unwrap(),
+            PropertyValue::UnsignedInteger(2000)
+        );
+        assert!(timer.take_pending_writes().is_empty());
+
+        timer.advance_time(Duration::from_millis(2500));
+        assert_eq!(timer.timer_state, TimerState::Expired);
+        assert_eq!(timer.last_state_change, TimerTransition::RunningToExpired);
+        assert_eq!(
+            timer.take_pending_writes(),
+            vec![PropertyWrite {
+                reference: target,
+                value: PropertyValue::Enumerated(0),
+                priority: 9,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_timer_state_writes() {
+        let mut timer = Timer::new(1, "Timer".to_string(), 1000);
+        timer.min_pres_value = Some(100);
+        assert!(matches!(
+            timer.set_property(
+                PropertyIdentifier::PresentValue,
+                PropertyValue::UnsignedInteger(50)
+            ),
+            Err(ObjectError::InvalidValue(_))
+        ));
+
+        timer
+            .set_property(
+                PropertyIdentifier::PresentValue,
+                PropertyValue::UnsignedInteger(400),
+            )
+            .unwrap();
+        assert_eq!(timer.initial_timeout, 400);
+        timer
+            .set_property(
+                PropertyIdentifier::PresentValue,
+                PropertyValue::UnsignedInteger(0),
+            )
+            .unwrap();
+        assert_eq!(timer.last_state_change, TimerTransition::ForcedToExpired);
+
+        assert!(matches!(
+            timer.set_property(
+                PropertyIdentifier::TimerState,
+                PropertyValue::Enumerated(TimerState::Running as u32)
+            ),
+            Err(ObjectError::InvalidValue(_))
+        ));
+        timer
+            .set_property(
+                PropertyIdentifier::TimerState,
+                PropertyValue::Enumerated(TimerState::Idle as u32),
+            )
+            .unwrap();
+        assert_eq!(timer.timer_state, TimerState::Idle);
+        assert_eq!(timer.last_state_change, TimerTransition::ExpiredToIdle);
+    }
+}
@@ -0,0 +1,902 @@
+//! Trend Log Object Type Implementation
+//!
+//! This module implements the Trend Log object type as defined in ASHRAE 135. A Trend
+//! Log keeps a bounded, circular buffer of BACnetLogRecords for a monitored property.
+//! Records carry a sequence number derived from Total_Record_Count so the buffer can be
+//! served by position, sequence number or time, as ReadRange requires.
+
+use crate::object::{
+    current_date_time, current_status_flags, date_time_from_value, date_time_value,
+    status_flags_bit_string, BacnetObject, DeviceObjectPropertyReference, EventState, ObjectError,
+    ObjectIdentifier, ObjectType, PropertyIdentifier, PropertyValue, Reliability, Result,
+};
+use crate::service::BacnetDateTime;
+use core::time::Duration;
+
+#[cfg(not(feature = "std"))]
+use alloc::{collections::VecDeque, string::String, vec::Vec};
+#[cfg(feature = "std")]
+use std::collections::VecDeque;
+
+/// How a log acquires its records (BACnetLoggingType)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum LoggingType {
+    /// Sampled every Log_Interval
+    Polled = 0,
+    /// Recorded on change of value notifications
+    Cov = 1,
+    /// Recorded when Trigger is written
+    Triggered = 2,
+}
+
+impl TryFrom<u32> for LoggingType {
+    type Error = ObjectError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            0 => Ok(LoggingType::Polled),
+            1 => Ok(LoggingType::Cov),
+            2 => Ok(LoggingType::Triggered),
+            _ => Err(ObjectError::InvalidValue(format!(
+                "Invalid logging type: {}",
+                value
+            ))),
+        }
+    }
+}
+
+/// Content of a log record
+#[derive(Debug, Clone)]
+pub enum LogDatum {
+    /// Change in the status of the log itself
+    LogStatus {
+        log_disabled: bool,
+        buffer_purged: bool,
+        log_interrupted: bool,
+    },
+    Boolean(bool),
+    Real(f32),
+    Enumerated(u32),
+    Unsigned(u32),
+    Signed(i32),
+    BitString(Vec<bool>),
+    Null,
+    /// The monitored property could not be read
+    Failure {
+        error_class: u32,
+        error_code: u32,
+    },
+    /// Clock change, in seconds
+    TimeChange(f32),
+    /// Any other datatype
+    Any(PropertyValue),
+}
+
+impl LogDatum {
+    /// Wrap a sampled property value in the matching datum choice
+    pub fn from_property_value(value: PropertyValue) -> Self {
+        match value {
+            PropertyValue::Boolean(v) => LogDatum::Boolean(v),
+            PropertyValue::Real(v) => LogDatum::Real(v),
+            PropertyValue::Enumerated(v) => LogDatum::Enumerated(v),
+            PropertyValue::UnsignedInteger(v) => LogDatum::Unsigned(v),
+            PropertyValue::SignedInt(v) => LogDatum::Signed(v),
+            PropertyValue::BitString(v) => LogDatum::BitString(v),
+            PropertyValue::Null => LogDatum::Null,
+            other => LogDatum::Any(other),
+        }
+    }
+
+    /// Encode the datum as a property value
+    pub fn to_property_value(&self) -> PropertyValue {
+        match self {
+            LogDatum::LogStatus {
+                log_disabled,
+                buffer_purged,
+                log_interrupted,
+            } => PropertyValue::BitString(vec![*log_disabled, *buffer_purged, *log_interrupted]),
+            LogDatum::Boolean(v) => PropertyValue::Boolean(*v),
+            LogDatum::Real(v) | LogDatum::TimeChange(v) => PropertyValue::Real(*v),
+            LogDatum::Enumerated(v) => PropertyValue::Enumerated(*v),
+            LogDatum::Unsigned(v) => PropertyValue::UnsignedInteger(*v),
+            LogDatum::Signed(v) => PropertyValue::SignedInt(*v),
+            LogDatum::BitString(v) => PropertyValue::BitString(v.clone()),
+            LogDatum::Null => PropertyValue::Null,
+            LogDatum::Failure {
+                error_class,
+                error_code,
+            } => PropertyValue::List(vec![
+                PropertyValue::Enumerated(*error_class),
+                PropertyValue::Enumerated(*error_code),
+            ]),
+            LogDatum::Any(v) => v.clone(),
+        }
+    }
+}
+
+/// A single entry of Log_Buffer (BACnetLogRecord)
+#[derive(Debug, Clone)]
+pub struct LogRecord {
+    /// When the record was taken
+    pub timestamp: BacnetDateTime,
+    /// Recorded datum
+    pub log_datum: LogDatum,
+    /// Status flags of the monitored object, if known
+    pub status_flags: Option<u8>,
+}
+
+impl LogRecord {
+    /// Create a new log record
+    pub fn new(timestamp: BacnetDateTime, log_datum: LogDatum, status_flags: Option<u8>) -> Self {
+        Self {
+            timestamp,
+            log_datum,
+            status_flags,
+        }
+    }
+
+    /// Encode as `[Date, Time, datum, status flags?]`
+    pub fn to_property_value(&self) -> PropertyValue {
+        let mut items = vec![
+            PropertyValue::Date(self.timestamp.date),
+            PropertyValue::Time(self.timestamp.time),
+            self.log_datum.to_property_value(),
+        ];
+        if let Some(flags) = self.status_flags {
+            items.push(status_flags_bit_string(flags));
+        }
+        PropertyValue::List(items)
+    }
+}
+
+/// Records selected from a log buffer for ReadRange
+#[derive(Debug, Clone)]
+pub struct LogBufferRange<R = LogRecord> {
+    /// Sequence number of the first returned record
+    pub first_sequence_number: Option<u32>,
+    /// Returned records, oldest first
+    pub records: Vec<R>,
+    /// The first returned record is the oldest in the buffer
+    pub first_item: bool,
+    /// The last returned record is the newest in the buffer
+    pub last_item: bool,
+}
+
+impl<R> Default for LogBufferRange<R> {
+    fn default() -> Self {
+        Self {
+            first_sequence_number: None,
+            records: Vec::new(),
+            first_item: false,
+            last_item: false,
+        }
+    }
+}
+
+/// A record type that can be kept in a log buffer
+pub(crate) trait LogEntry: Clone {
+    /// When the record was taken
+    fn timestamp(&self) -> &BacnetDateTime;
+
+    /// A log-status record reporting a change of the log itself
+    fn log_status(timestamp: BacnetDateTime, log_disabled: bool, buffer_purged: bool) -> Self;
+}
+
+impl LogEntry for LogRecord {
+    fn timestamp(&self) -> &BacnetDateTime {
+        &self.timestamp
+    }
+
+    fn log_status(timestamp: BacnetDateTime, log_disabled: bool, buffer_purged: bool) -> Self {
+        LogRecord::new(
+            timestamp,
+            LogDatum::LogStatus {
+                log_disabled,
+                buffer_purged,
+                log_interrupted: false,
+            },
+            None,
+        )
+    }
+}
+
+/// Whether a record taken at `timestamp` falls inside the logging window
+pub(crate) fn logging_active(
+    log_enable: bool,
+    start_time: Option<BacnetDateTime>,
+    stop_time: Option<BacnetDateTime>,
+    timestamp: &BacnetDateTime,
+) -> bool {
+    let key = date_time_key(timestamp);
+    log_enable
+        && start_time.is_none_or(|start| key >= date_time_key(&start))
+        && stop_time.is_none_or(|stop| key < date_time_key(&stop))
+}
+
+/// Sortable key for a timestamp; unspecified fields count as zero
+pub(crate) fn date_time_key(date_time: &BacnetDateTime) -> u64 {
+    let field = |v: u8| if v == 255 { 0 } else { v as u64 };
+    let year = if date_time.date.year == 255 {
+        0
+    } else {
+        date_time.date.year as u64
+    };
+    let date = (year * 100 + field(date_time.date.month)) * 100 + field(date_time.date.day);
+    let time = ((field(date_time.time.hour) * 100 + field(date_time.time.minute)) * 100
+        + field(date_time.time.second))
+        * 100
+        + field(date_time.time.hundredths);
+    date * 100_000_000 + time
+}
+
+/// Bounded circular buffer of sequence-numbered log records
+#[derive(Debug, Clone)]
+pub(crate) struct LogBuffer<R> {
+    records: VecDeque<(u32, R)>,
+    buffer_size: u32,
+    total_record_count: u32,
+}
+
+impl<R: LogEntry> LogBuffer<R> {
+    pub(crate) fn new(buffer_size: u32) -> Self {
+        Self {
+            records: VecDeque::new(),
+            buffer_size,
+            total_record_count: 0,
+        }
+    }
+
+    pub(crate) fn len(&self) -> u32 {
+        self.records.len() as u32
+    }
+
+    pub(crate) fn is_full(&self) -> bool {
+        self.len() >= self.buffer_size
+    }
+
+    pub(crate) fn buffer_size(&self) -> u32 {
+        self.buffer_size
+    }
+
+    pub(crate) fn total_record_count(&self) -> u32 {
+        self.total_record_count
+    }
+
+    /// Append a record, overwriting the oldest when full
+    pub(crate) fn push(&mut self, record: R) {
+        if self.buffer_size == 0 {
+            return;
+        }
+        while self.is_full() {
+            self.records.pop_front();
+        }
+        // Total_Record_Count wraps from 2^32 - 1 back to 1
+        self.total_record_count = match self.total_record_count {
+            u32::MAX => 1,
+            n => n + 1,
+        };
+        self.records.push_back((self.total_record_count, record));
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.records.clear();
+    }
+
+    /// Change the capacity, keeping the newest records
+    pub(crate) fn resize(&mut self, buffer_size: u32) {
+        self.buffer_size = buffer_size;
+        while self.len() > buffer_size {
+            self.records.pop_front();
+        }
+    }
+
+    pub(crate) fn records(&self) -> impl Iterator<Item = &R> {
+        self.records.iter().map(|(_, record)| record)
+    }
+
+    /// Records with their sequence numbers, oldest first
+    pub(crate) fn sequenced(&self) -> impl Iterator<Item = (u32, &R)> {
+        self.records
+            .iter()
+            .map(|(sequence, record)| (*sequence, record))
+    }
+
+    /// Select records by 1-based position; a negative count reads backwards
+    pub(crate) fn by_position(&self, reference_index: u32, count: i32) -> LogBufferRange<R> {
+        let len = self.records.len();
+        let index = reference_index as usize;
+        if index == 0 || index > len || count == 0 {
+            return LogBufferRange::default();
+        }
+        let (start, end) = if count > 0 {
+            (index - 1, (index - 1 + count as usize).min(len))
+        } else {
+            (index.saturating_sub(count.unsigned_abs() as usize), index)
+        };
+        self.slice(start, end)
+    }
+
+    /// Select records starting from a sequence number; a negative count reads backwards
+    pub(crate) fn by_sequence(&self, reference_sequence: u32, count: i32) -> LogBufferRange<R> {
+        match self
+            .records
+            .iter()
+            .position(|(sequence, _)| *sequence == reference_sequence)
+        {
+            Some(position) => self.by_position(position as u32 + 1, count),
+            None => LogBufferRange::default(),
+        }
+    }
+
+    /// Select records newer (positive count) or older (negative count) than a time
+    pub(crate) fn by_time(&self, reference_time: &BacnetDateTime, count: i32) -> LogBufferRange<R> {
+        let key = date_time_key(reference_time);
+        let len = self.records.len();
+        if count > 0 {
+            match self
+                .records
+                .iter()
+                .position(|(_, record)| date_time_key(record.timestamp()) > key)
+            {
+                Some(start) => self.slice(start, (start + count as usize).min(len)),
+                None => LogBufferRange::default(),
+            }
+        } else if count < 0 {
+            match self
+                .records
+                .iter()
+                .rposition(|(_, record)| date_time_key(record.timestamp()) < key)
+            {
+                Some(last) => self.slice(
+                    (last + 1).saturating_sub(count.unsigned_abs() as usize),
+                    last + 1,
+                ),
+                None => LogBufferRange::default(),
+            }
+        } else {
+            LogBufferRange::default()
+        }
+    }
+
+    fn slice(&self, start: usize, end: usize) -> LogBufferRange<R> {
+        let len = self.records.len();
+        LogBufferRange {
+            first_sequence_number: self.records.get(start).map(|(sequence, _)| *sequence),
+            records: self
+                .records
+                .range(start..end)
+                .map(|(_, record)| record.clone())
+                .collect(),
+            first_item: start == 0 && end > start,
+            last_item: end == len && end > start,
+        }
+    }
+}
+
+/// Trend Log object
+#[derive(Debug, Clone)]
+pub struct TrendLog {
+    /// Object identifier
+    pub identifier: ObjectIdentifier,
+    /// Object name
+    pub object_name: String,
+    /// Description
+    pub description: String,
+    /// Whether logging is enabled
+    pub log_enable: bool,
+    /// Logging starts at this time, if set
+    pub start_time: Option<BacnetDateTime>,
+    /// Logging stops at this time, if set
+    pub stop_time: Option<BacnetDateTime>,
+    /// Property being logged
+    pub log_device_object_property: Option<DeviceObjectPropertyReference>,
+    /// Polling interval in hundredths of a second
+    pub log_interval: u32,
+    /// Stop logging instead of overwriting when the buffer is full
+    pub stop_when_full: bool,
+    /// How records are acquired
+    pub logging_type: LoggingType,
+    /// Event state
+    pub event_state: EventState,
+    /// Reliability
+    pub reliability: Reliability,
+    buffer: LogBuffer<LogRecord>,
+    trigger: bool,
+    time_since_poll: Duration,
+}
+
+impl TrendLog {
+    /// Create a new polled Trend Log with the given buffer capacity
+    pub fn new(instance: u32, object_name: String, buffer_size: u32) -> Self {
+        Self {
+            identifier: ObjectIdentifier::new(ObjectType::TrendLog, instance),
+            object_name,
+            description: String::new(),
+            log_enable: false,
+            start_time: None,
+            stop_time: None,
+            log_device_object_property: None,
+            log_interval: 6000,
+            stop_when_full: false,
+            logging_type: LoggingType::Polled,
+            event_state: EventState::Normal,
+            reliability: Reliability::NoFaultDetected,
+            buffer: LogBuffer::new(buffer_size),
+            trigger: false,
+            time_since_poll: Duration::ZERO,
+        }
+    }
+
+    /// Number of records currently in the buffer
+    pub fn record_count(&self) -> u32 {
+        self.buffer.len()
+    }
+
+    /// Number of records ever collected
+    pub fn total_record_count(&self) -> u32 {
+        self.buffer.total_record_count()
+    }
+
+    /// Maximum number of records the buffer holds
+    pub fn buffer_size(&self) -> u32 {
+        self.buffer.buffer_size()
+    }
+
+    /// Records in the buffer, oldest first
+    pub fn records(&self) -> impl Iterator<Item = &LogRecord> {
+        self.buffer.records()
+    }
+
+    /// Whether a record taken at `timestamp` would be logged
+    pub fn is_logging(&self, timestamp: &BacnetDateTime) -> bool {
+        logging_active(self.log_enable, self.start_time, self.stop_time, timestamp)
+    }
+
+    /// Log a sampled value of the monitored property
+    ///
+    /// Returns `false` if the record was discarded because logging is not active.
+    pub fn log_value(
+        &mut self,
+        timestamp: BacnetDateTime,
+        value: PropertyValue,
+        status_flags: Option<u8>,
+    ) -> bool {
+        self.log_record(LogRecord::new(
+            timestamp,
+            LogDatum::from_property_value(value),
+            status_flags,
+        ))
+    }
+
+    /// Log a record, honouring Log_Enable, Start_Time, Stop_Time and Stop_When_Full
+    pub fn log_record(&mut self, record: LogRecord) -> bool {
+        if !self.is_logging(&record.timestamp) || (self.stop_when_full && self.buffer.is_full()) {
+            return false;
+        }
+        self.buffer.push(record);
+        if self.stop_when_full && self.buffer.is_full() {
+            self.log_enable = false;
+        }
+        true
+    }
+
+    /// Enable or disable logging, recording the status change
+    pub fn set_log_enable(&mut self, enable: bool, timestamp: Option<BacnetDateTime>) {
+        if enable == self.log_enable {
+            return;
+        }
+        if enable && self.stop_when_full && self.buffer.is_full() {
+            // A full stop-when-full log stays disabled until it is purged
+            return;
+        }
+        self.log_enable = enable;
+        self.log_status(timestamp, !enable, false);
+    }
+
+    /// Remove all records, as when zero is written to Record_Count
+    pub fn purge(&mut self, timestamp: Option<BacnetDateTime>) {
+        self.buffer.clear();
+        if self.log_enable {
+            self.log_status(timestamp, false, true);
+        }
+    }
+
+    fn log_status(&mut self, timestamp: Option<BacnetDateTime>, disabled: bool, purged: bool) {
+        self.buffer.push(LogRecord::log_status(
+            timestamp.unwrap_or_else(BacnetDateTime::unspecified),
+            disabled,
+            purged,
+        ));
+    }
+
+    /// The property to sample, if a polled or triggered acquisition is due
+    ///
+    /// Polled logs become due every Log_Interval (tracked by `advance_time`);
+    /// triggered logs become due when TRUE is written to Trigger. Taking the
+    /// request clears it.
+    pub fn take_acquisition_request(&mut self) -> Option<DeviceObjectPropertyReference> {
+        let interval = Duration::from_millis(self.log_interval as u64 * 10);
+        let due = match self.logging_type {
+            LoggingType::Polled => {
+                self.log_interval > 0 && self.time_since_poll >= interval && {
+                    self.time_since_poll = Duration::ZERO;
+                    true
+                }
+            }
+            LoggingType::Triggered => core::mem::take(&mut self.trigger),
+            LoggingType::Cov => false,
+        };
+        self.log_device_object_property.filter(|_| due)
+    }
+
+    /// Records by 1-based position, for ReadRange by position
+    pub fn read_range_by_position(&self, reference_index: u32, count: i32) -> LogBufferRange {
+        self.buffer.by_position(reference_index, count)
+    }
+
+    /// Records by sequence number, for ReadRange by sequence number
+    pub fn read_range_by_sequence(&self, reference_sequence: u32, count: i32) -> LogBufferRange {
+        self.buffer.by_sequence(reference_sequence, count)
+    }
+
+    /// Records relative to a time, for ReadRange by time
+    pub fn read_range_by_time(
+        &self,
+        reference_time: &BacnetDateTime,
+        count: i32,
+    ) -> LogBufferRange {
+        self.buffer.by_time(reference_time, count)
+    }
+
+    fn current_status_flags(&self) -> u8 {
+        current_status_flags(0, self.event_state, self.reliability, false)
+    }
+}
+
+impl BacnetObject for TrendLog {
+    fn identifier(&self) -> ObjectIdentifier {
+        self.identifier
+    }
+
+    fn get_property(&self, property: PropertyIdentifier) -> Result<PropertyValue> {
+        match property {
+            PropertyIdentifier::ObjectIdentifier => {
+                Ok(PropertyValue::ObjectIdentifier(self.identifier))
+            }
+            PropertyIdentifier::ObjectName => {
+                Ok(PropertyValue::CharacterString(self.object_name.clone()))
+            }
+            PropertyIdentifier::ObjectType => {
+                Ok(PropertyValue::Enumerated(u32::from(ObjectType::TrendLog)))
+            }
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::LogEnable => Ok(PropertyValue::Boolean(self.log_enable)),
+            PropertyIdentifier::StartTime => Ok(date_time_value(self.start_time)),
+            PropertyIdentifier::StopTime => Ok(date_time_value(self.stop_time)),
+            PropertyIdentifier::LogDeviceObjectProperty => self
+                .log_device_object_property
+                .map(|reference| reference.to_property_value())
+                .ok_or(ObjectError::UnknownProperty),
+            PropertyIdentifier::LogInterval => {
+                Ok(PropertyValue::UnsignedInteger(self.log_interval))
+            }
+            PropertyIdentifier::StopWhenFull => Ok(PropertyValue::Boolean(self.stop_when_full)),
+            PropertyIdentifier::BufferSize => {
+                Ok(PropertyValue::UnsignedInteger(self.buffer_size()))
+            }
+            PropertyIdentifier::LogBuffer => Ok(PropertyValue::List(
+                self.records().map(LogRecord::to_property_value).collect(),
+            )),
+            PropertyIdentifier::RecordCount => {
+                Ok(PropertyValue::UnsignedInteger(self.record_count()))
+            }
+            PropertyIdentifier::TotalRecordCount => {
+                Ok(PropertyValue::UnsignedInteger(self.total_record_count()))
+            }
+            PropertyIdentifier::LoggingType => {
+                Ok(PropertyValue::Enumerated(self.logging_type as u32))
+            }
+            PropertyIdentifier::Trigger if self.logging_type == LoggingType::Triggered => {
+                Ok(PropertyValue::Boolean(self.trigger))
+            }
+            PropertyIdentifier::StatusFlags => {
+                Ok(status_flags_bit_string(self.current_status_flags()))
+            }
+            PropertyIdentifier::EventState => {
+                Ok(PropertyValue::Enumerated(self.event_state as u32))
+            }
+            PropertyIdentifier::Reliability => {
+                Ok(PropertyValue::Enumerated(self.reliability as u32))
+            }
+            _ => Err(ObjectError::UnknownProperty),
+        }
+    }
+
+    fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        match property {
+            PropertyIdentifier::ObjectName => {
+                if let PropertyValue::CharacterString(name) = value {
+                    self.object_name = name;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::LogEnable => {
+                if let PropertyValue::Boolean(enable) = value {
+                    if enable && self.stop_when_full && self.buffer.is_full() {
+                        return Err(ObjectError::InvalidConfiguration(
+                            "Log buffer is full".to_string(),
+                        ));
+                    }
+                    self.set_log_enable(enable, current_date_time());
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::StartTime => {
+                let start = date_time_from_value(&value)?;
+                self.start_time = (!start.is_unspecified()).then_some(start);
+                Ok(())
+            }
+            PropertyIdentifier::StopTime => {
+                let stop = date_time_from_value(&value)?;
+                self.stop_time = (!stop.is_unspecified()).then_some(stop);
+                Ok(())
+            }
+            PropertyIdentifier::LogInterval => {
+                if let PropertyValue::UnsignedInteger(interval) = value {
+                    self.log_interval = interval;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::StopWhenFull => {
+                if let PropertyValue::Boolean(stop) = value {
+                    self.stop_when_full = stop;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::BufferSize => {
+                if let PropertyValue::UnsignedInteger(size) = value {
+                    self.buffer.resize(size);
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::RecordCount => match value {
+                PropertyValue::UnsignedInteger(0) => {
+                    self.purge(current_date_time());
+                    Ok(())
+                }
+                PropertyValue::UnsignedInteger(_) => Err(ObjectError::InvalidValue(
+                    "Record_Count may only be written with zero".to_string(),
+                )),
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            PropertyIdentifier::LoggingType => {
+                if let PropertyValue::Enumerated(logging_type) = value {
+                    self.logging_type = LoggingType::try_from(logging_type)?;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Trigger if self.logging_type == LoggingType::Triggered => {
+                if let PropertyValue::Boolean(trigger) = value {
+                    self.trigger = trigger;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            _ => Err(ObjectError::PropertyNotWritable),
+        }
+    }
+
+    fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
+        match property {
+            PropertyIdentifier::ObjectName
+            | PropertyIdentifier::Description
+            | PropertyIdentifier::LogEnable
+            | PropertyIdentifier::StartTime
+            | PropertyIdentifier::StopTime
+            | PropertyIdentifier::LogInterval
+            | PropertyIdentifier::StopWhenFull
+            | PropertyIdentifier::BufferSize
+            | PropertyIdentifier::RecordCount
+            | PropertyIdentifier::LoggingType => true,
+            PropertyIdentifier::Trigger => self.logging_type == LoggingType::Triggered,
+            _ => false,
+        }
+    }
+
+    fn property_list(&self) -> Vec<PropertyIdentifier> {
+        let mut properties = vec![
+            PropertyIdentifier::ObjectIdentifier,
+            PropertyIdentifier::ObjectName,
+            PropertyIdentifier::ObjectType,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::LogEnable,
+            PropertyIdentifier::StartTime,
+            PropertyIdentifier::StopTime,
+        ];
+        if self.log_device_object_property.is_some() {
+            properties.push(PropertyIdentifier::LogDeviceObjectProperty);
+        }
+        properties.extend([
+            PropertyIdentifier::LogInterval,
+            PropertyIdentifier::StopWhenFull,
+            PropertyIdentifier::BufferSize,
+            PropertyIdentifier::LogBuffer,
+            PropertyIdentifier::RecordCount,
+            PropertyIdentifier::TotalRecordCount,
+            PropertyIdentifier::LoggingType,
+        ]);
+        if self.logging_type == LoggingType::Triggered {
+            properties.push(PropertyIdentifier::Trigger);
+        }
+        properties.extend([
+            PropertyIdentifier::StatusFlags,
+            PropertyIdentifier::EventState,
+            PropertyIdentifier::Reliability,
+        ]);
+        properties
+    }
+
+    fn advance_time(&mut self, elapsed: Duration) {
+        if self.logging_type == LoggingType::Polled {
+            self.time_since_poll = self.time_since_poll.saturating_add(elapsed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::{Date, Time};
+
+    fn at(minute: u8) -> BacnetDateTime {
+        BacnetDateTime::new(
+            Date {
+                year: 2024,
+                month: 5,
+                day: 1,
+                weekday: 3,
+            },
+            Time {
+                hour: 12,
+                minute,
+                second: 0,
+                hundredths: 0,
+            },
+        )
+    }
+
+    fn enabled_log(buffer_size: u32) -> TrendLog {
+        let mut log = TrendLog::new(1, "Zone Temp Log".to_string(), buffer_size);
+        log.log_enable = true;
+        log
+    }
+
+    #[test]
+    fn test_circular_buffer_wraps() {
+        let mut log = enabled_log(3);
+        for minute in 0..5 {
+            assert!(log.log_value(at(minute), PropertyValue::Real(minute as f32), None));
+        }
+        assert_eq!(log.record_count(), 3);
+        assert_eq!(log.total_record_count(), 5);
+        let oldest = log.records().next().unwrap();
+        assert!(matches!(oldest.log_datum, LogDatum::Real(v) if v == 2.0));
+
+        let range = log.read_range_by_position(1, 10);
+        assert_eq!(range.first_sequence_number, Some(3));
+        assert_eq!(range.records.len(), 3);
+        assert!(range.first_item && range.last_item);
+    }
+
+    #[test]
+    fn test_stop_when_full_and_purge() {
+        let mut log = enabled_log(2);
+        log.stop_when_full = true;
+        assert!(log.log_value(at(0), PropertyValue::Boolean(true), Some(0)));
+        assert!(log.log_value(at(1), PropertyValue::Boolean(false), Some(0)));
+        assert!(!log.log_enable);
+        assert!(!log.log_value(at(2), PropertyValue::Boolean(true), None));
+        assert!(log
+            .set_property(PropertyIdentifier::LogEnable, PropertyValue::Boolean(true))
+            .is_err());
+
+        assert!(log
+            .set_property(
+                PropertyIdentifier::RecordCount,
+                PropertyValue::UnsignedInteger(1)
+            )
+            .is_err());
+        log.set_property(
+            PropertyIdentifier::RecordCount,
+            PropertyValue::UnsignedInteger(0),
+        )
+        .unwrap();
+        assert_eq!(log.record_count(), 0);
+        log.set_log_enable(true, Some(at(3)));
+        // Re-enabling records a log status entry
+        assert!(matches!(
+            log.records().next().unwrap().log_datum,
+            LogDatum::LogStatus {
+                log_disabled: false,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_start_stop_time_window() {
+        let mut log = enabled_log(10);
+        log.start_time = Some(at(10));
+        log.stop_time = Some(at(20));
+        assert!(!log.log_value(at(5), PropertyValue::Real(1.0), None));
+        assert!(log.log_value(at(15), PropertyValue::Real(2.0), None));
+        assert!(!log.log_value(at(20), PropertyValue::Real(3.0), None));
+        assert_eq!(log.record_count(), 1);
+    }
+
+    #[test]
+    fn test_read_range_by_sequence_and_time() {
+        let mut log = enabled_log(10);
+        for minute in 0..6 {
+            log.log_value(
+                at(minute * 10),
+                PropertyValue::UnsignedInteger(minute as u32),
+                None,
+            );
+        }
+
+        let range = log.read_range_by_sequence(3, -2);
+        assert_eq!(range.first_sequence_number, Some(2));
+        assert_eq!(range.records.len(), 2);
+        assert!(!range.first_item && !range.last_item);
+
+        let range = log.read_range_by_time(&at(25), 2);
+        assert_eq!(range.first_sequence_number, Some(4));
+        assert!(matches!(range.records[0].log_datum, LogDatum::Unsigned(3)));
+
+        let range = log.read_range_by_time(&at(25), -10);
+        assert_eq!(range.records.len(), 3);
+        assert!(range.first_item);
+
+        assert!(log.read_range_by_sequence(99, 1).records.is_empty());
+    }
+
+    #[test]
+    fn test_polled_acquisition() {
+        let mut log = enabled_log(10);
+        log.log_interval = 100;
+        log.log_device_object_property = Some(DeviceObjectPropertyReference::new(
+            ObjectIdentifier::new(ObjectType::AnalogInput, 1),
+            PropertyIdentifier::PresentValue,
+        ));
+        log.advance_time(Duration::from_millis(500));
+        assert!(log.take_acquisition_request().is_none());
+        log.advance_time(Duration::from_millis(500));
+        assert!(log.take_acquisition_request().is_some());
+        assert!(log.take_acquisition_request().is_none());
+    }
+}
@@ -0,0 +1,590 @@
+//! Trend Log Multiple Object Type Implementation
+//!
+//! This module implements the Trend Log Multiple object type as defined in ASHRAE 135.
+//! It samples a list of properties on a shared interval and stores each sample set as
+//! one BACnetLogMultipleRecord, reusing the circular buffer of the Trend Log object.
+
+use crate::object::trendlog::{logging_active, LogBuffer, LogEntry};
+use crate::object::{
+    current_date_time, current_status_flags, date_time_from_value, date_time_value,
+    status_flags_bit_string, BacnetObject, DeviceObjectPropertyReference, EventState,
+    LogBufferRange, LogDatum, LoggingType, ObjectError, ObjectIdentifier, ObjectType,
+    PropertyIdentifier, PropertyValue, Reliability, Result,
+};
+use crate::service::BacnetDateTime;
+use core::time::Duration;
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+/// Content of a multiple-datum log record
+#[derive(Debug, Clone)]
+pub enum LogMultipleData {
+    /// Change in the status of the log itself
+    LogStatus {
+        log_disabled: bool,
+        buffer_purged: bool,
+        log_interrupted: bool,
+    },
+    /// One datum per entry of Log_DeviceObjectProperty, in order
+    LogData(Vec<LogDatum>),
+    /// Clock change, in seconds
+    TimeChange(f32),
+}
+
+/// A single entry of a Trend Log Multiple's Log_Buffer (BACnetLogMultipleRecord)
+#[derive(Debug, Clone)]
+pub struct LogMultipleRecord {
+    /// When the sample set was taken
+    pub timestamp: BacnetDateTime,
+    /// Recorded data
+    pub log_data: LogMultipleData,
+}
+
+impl LogMultipleRecord {
+    /// Create a new log record
+    pub fn new(timestamp: BacnetDateTime, log_data: LogMultipleData) -> Self {
+        Self {
+            timestamp,
+            log_data,
+        }
+    }
+
+    /// Encode as `[Date, Time, data]`
+    pub fn to_property_value(&self) -> PropertyValue {
+        let data = match &self.log_data {
+            LogMultipleData::LogStatus {
+                log_disabled,
+                buffer_purged,
+                log_interrupted,
+            } => PropertyValue::BitString(vec![*log_disabled, *buffer_purged, *log_interrupted]),
+            LogMultipleData::LogData(data) => {
+                PropertyValue::List(data.iter().map(LogDatum::to_property_value).collect())
+            }
+            LogMultipleData::TimeChange(seconds) => PropertyValue::Real(*seconds),
+        };
+        PropertyValue::List(vec![
+            PropertyValue::Date(self.timestamp.date),
+            PropertyValue::Time(self.timestamp.time),
+            data,
+        ])
+    }
+}
+
+impl LogEntry for LogMultipleRecord {
+    fn timestamp(&self) -> &BacnetDateTime {
+        &self.timestamp
+    }
+
+    fn log_status(timestamp: BacnetDateTime, log_disabled: bool, buffer_purged: bool) -> Self {
+        LogMultipleRecord::new(
+            timestamp,
+            LogMultipleData::LogStatus {
+                log_disabled,
+                buffer_purged,
+                log_interrupted: false,
+            },
+        )
+    }
+}
+
+/// Trend Log Multiple object
+#[derive(Debug, Clone)]
+pub struct TrendLogMultiple {
+    /// Object identifier
+    pub identifier: ObjectIdentifier,
+    /// Object name
+    pub object_name: String,
+    /// Description
+    pub description: String,
+    /// Whether logging is enabled
+    pub log_enable: bool,
+    /// Logging starts at this time, if set
+    pub start_time: Option<BacnetDateTime>,
+    /// Logging stops at this time, if set
+    pub stop_time: Option<BacnetDateTime>,
+    /// Properties sampled into each record
+    pub log_device_object_property: Vec<DeviceObjectPropertyReference>,
+    /// Polling interval in hundredths of a second
+    pub log_interval: u32,
+    /// Stop logging instead of overwriting when the buffer is full
+    pub stop_when_full: bool,
+    /// How records are acquired (polled or triggered)
+    pub logging_type: LoggingType,
+    /// Event state
+    pub event_state: EventState,
+    /// Reliability
+    pub reliability: Reliability,
+    buffer: LogBuffer<LogMultipleRecord>,
+    trigger: bool,
+    time_since_poll: Duration,
+}
+
+impl TrendLogMultiple {
+    /// Create a new polled Trend Log Multiple with the given buffer capacity
+    pub fn new(instance: u32, object_name: String, buffer_size: u32) -> Self {
+        Self {
+            identifier: ObjectIdentifier::new(ObjectType::TrendLogMultiple, instance),
+            object_name,
+            description: String::new(),
+            log_enable: false,
+            start_time: None,
+            stop_time: None,
+            log_device_object_property: Vec::new(),
+            log_interval: 6000,
+            stop_when_full: false,
+            logging_type: LoggingType::Polled,
+            event_state: EventState::Normal,
+            reliability: Reliability::NoFaultDetected,
+            buffer: LogBuffer::new(buffer_size),
+            trigger: false,
+            time_since_poll: Duration::ZERO,
+        }
+    }
+
+    /// Number of records currently in the buffer
+    pub fn record_count(&self) -> u32 {
+        self.buffer.len()
+    }
+
+    /// Number of records ever collected
+    pub fn total_record_count(&self) -> u32 {
+        self.buffer.total_record_count()
+    }
+
+    /// Maximum number of records the buffer holds
+    pub fn buffer_size(&self) -> u32 {
+        self.buffer.buffer_size()
+    }
+
+    /// Records in the buffer, oldest first
+    pub fn records(&self) -> impl Iterator<Item = &LogMultipleRecord> {
+        self.buffer.records()
+    }
+
+    /// Whether a record taken at `timestamp` would be logged
+    pub fn is_logging(&self, timestamp: &BacnetDateTime) -> bool {
+        logging_active(self.log_enable, self.start_time, self.stop_time, timestamp)
+    }
+
+    /// Log one sample set, given in Log_DeviceObjectProperty order
+    ///
+    /// A sample set that does not cover every reference is rejected; use
+    /// [`LogDatum::Failure`] for properties that could not be read.
+    pub fn log_values(&mut self, timestamp: BacnetDateTime, data: Vec<LogDatum>) -> Result<bool> {
+        if data.len() != self.log_device_object_property.len() {
+            return Err(ObjectError::InvalidValue(format!(
+                "Expected {} log data, got {}",
+                self.log_device_object_property.len(),
+                data.len()
+            )));
+        }
+        Ok(self.log_record(LogMultipleRecord::new(
+            timestamp,
+            LogMultipleData::LogData(data),
+        )))
+    }
+
+    /// Log a record, honouring Log_Enable, Start_Time, Stop_Time and Stop_When_Full
+    pub fn log_record(&mut self, record: LogMultipleRecord) -> bool {
+        if !self.is_logging(&record.timestamp) || (self.stop_when_full && self.buffer.is_full()) {
+            return false;
+        }
+        self.buffer.push(record);
+        if self.stop_when_full && self.buffer.is_full() {
+            self.log_enable = false;
+        }
+        true
+    }
+
+    /// Enable or disable logging, recording the status change
+    pub fn set_log_enable(&mut self, enable: bool, timestamp: Option<BacnetDateTime>) {
+        if enable == self.log_enable || (enable && self.stop_when_full && self.buffer.is_full()) {
+            return;
+        }
+        self.log_enable = enable;
+        self.buffer.push(LogMultipleRecord::log_status(
+            timestamp.unwrap_or_else(BacnetDateTime::unspecified),
+            !enable,
+            false,
+        ));
+    }
+
+    /// Remove all records, as when zero is written to Record_Count
+    pub fn purge(&mut self, timestamp: Option<BacnetDateTime>) {
+        self.buffer.clear();
+        if self.log_enable {
+            self.buffer.push(LogMultipleRecord::log_status(
+                timestamp.unwrap_or_else(BacnetDateTime::unspecified),
+                false,
+                true,
+            ));
+        }
+    }
+
+    /// The properties to sample, if a polled or triggered acquisition is due
+    ///
+    /// All references share one Log_Interval. Taking the request clears it.
+    pub fn take_acquisition_request(&mut self) -> Option<&[DeviceObjectPropertyReference]> {
+        let interval = Duration::from_millis(self.log_interval as u64 * 10);
+        let due = match self.logging_type {
+            LoggingType::Polled => {
+                self.log_interval > 0 && self.time_since_poll >= interval && {
+                    self.time_since_poll = Duration::ZERO;
+                    true
+                }
+            }
+            LoggingType::Triggered => core::mem::take(&mut self.trigger),
+            LoggingType::Cov => false,
+        };
+        (due && !self.log_device_object_property.is_empty())
+            .then_some(self.log_device_object_property.as_slice())
+    }
+
+    /// Records by 1-based position, for ReadRange by position
+    pub fn read_range_by_position(
+        &self,
+        reference_index: u32,
+        count: i32,
+    ) -> LogBufferRange<LogMultipleRecord> {
+        self.buffer.by_position(reference_index, count)
+    }
+
+    /// Records by sequence number, for ReadRange by sequence number
+    pub fn read_range_by_sequence(
+        &self,
+        reference_sequence: u32,
+        count: i32,
+    ) -> LogBufferRange<LogMultipleRecord> {
+        self.buffer.by_sequence(reference_sequence, count)
+    }
+
+    /// Records relative to a time, for ReadRange by time
+    pub fn read_range_by_time(
+        &self,
+        reference_time: &BacnetDateTime,
+        count: i32,
+    ) -> LogBufferRange<LogMultipleRecord> {
+        self.buffer.by_time(reference_time, count)
+    }
+
+    fn current_status_flags(&self) -> u8 {
+        current_status_flags(0, self.event_state, self.reliability, false)
+    }
+}
+
+impl BacnetObject for TrendLogMultiple {
+    fn identifier(&self) -> ObjectIdentifier {
+        self.identifier
+    }
+
+    fn get_property(&self, property: PropertyIdentifier) -> Result<PropertyValue> {
+        match property {
+            PropertyIdentifier::ObjectIdentifier => {
+                Ok(PropertyValue::ObjectIdentifier(self.identifier))
+            }
+            PropertyIdentifier::ObjectName => {
+                Ok(PropertyValue::CharacterString(self.object_name.clone()))
+            }
+            PropertyIdentifier::ObjectType => Ok(PropertyValue::Enumerated(u32::from(
+                ObjectType::TrendLogMultiple,
+            ))),
+            PropertyIdentifier::Description => {
+                Ok(PropertyValue::CharacterString(self.description.clone()))
+            }
+            PropertyIdentifier::LogEnable => Ok(PropertyValue::Boolean(self.log_enable)),
+            PropertyIdentifier::StartTime => Ok(date_time_value(self.start_time)),
+            PropertyIdentifier::StopTime => Ok(date_time_value(self.stop_time)),
+            PropertyIdentifier::LogDeviceObjectProperty => Ok(PropertyValue::Array(
+                self.log_device_object_property
+                    .iter()
+                    .map(DeviceObjectPropertyReference::to_property_value)
+                    .collect(),
+            )),
+            PropertyIdentifier::LogInterval => {
+                Ok(PropertyValue::UnsignedInteger(self.log_interval))
+            }
+            PropertyIdentifier::StopWhenFull => Ok(PropertyValue::Boolean(self.stop_when_full)),
+            PropertyIdentifier::BufferSize => {
+                Ok(PropertyValue::UnsignedInteger(self.buffer_size()))
+            }
+            PropertyIdentifier::LogBuffer => Ok(PropertyValue::List(
+                self.records()
+                    .map(LogMultipleRecord::to_property_value)
+                    .collect(),
+            )),
+            PropertyIdentifier::RecordCount => {
+                Ok(PropertyValue::UnsignedInteger(self.record_count()))
+            }
+            PropertyIdentifier::TotalRecordCount => {
+                Ok(PropertyValue::UnsignedInteger(self.total_record_count()))
+            }
+            PropertyIdentifier::LoggingType => {
+                Ok(PropertyValue::Enumerated(self.logging_type as u32))
+            }
+            PropertyIdentifier::Trigger if self.logging_type == LoggingType::Triggered => {
+                Ok(PropertyValue::Boolean(self.trigger))
+            }
+            PropertyIdentifier::StatusFlags => {
+                Ok(status_flags_bit_string(self.current_status_flags()))
+            }
+            PropertyIdentifier::EventState => {
+                Ok(PropertyValue::Enumerated(self.event_state as u32))
+            }
+            PropertyIdentifier::Reliability => {
+                Ok(PropertyValue::Enumerated(self.reliability as u32))
+            }
+            _ => Err(ObjectError::UnknownProperty),
+        }
+    }
+
+    fn set_property(&mut self, property: PropertyIdentifier, value: PropertyValue) -> Result<()> {
+        match property {
+            PropertyIdentifier::ObjectName => {
+                if let PropertyValue::CharacterString(name) = value {
+                    self.object_name = name;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Description => {
+                if let PropertyValue::CharacterString(description) = value {
+                    self.description = description;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::LogEnable => {
+                if let PropertyValue::Boolean(enable) = value {
+                    if enable && self.stop_when_full && self.buffer.is_full() {
+                        return Err(ObjectError::InvalidConfiguration(
+                            "Log buffer is full".to_string(),
+                        ));
+                    }
+                    self.set_log_enable(enable, current_date_time());
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::StartTime => {
+                let start = date_time_from_value(&value)?;
+                self.start_time = (!start.is_unspecified()).then_some(start);
+                Ok(())
+            }
+            PropertyIdentifier::StopTime => {
+                let stop = date_time_from_value(&value)?;
+                self.stop_time = (!stop.is_unspecified()).then_some(stop);
+                Ok(())
+            }
+            PropertyIdentifier::LogInterval => {
+                if let PropertyValue::UnsignedInteger(interval) = value {
+                    self.log_interval = interval;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::StopWhenFull => {
+                if let PropertyValue::Boolean(stop) = value {
+                    self.stop_when_full = stop;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::BufferSize => {
+                if let PropertyValue::UnsignedInteger(size) = value {
+                    self.buffer.resize(size);
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::RecordCount => match value {
+                PropertyValue::UnsignedInteger(0) => {
+                    self.purge(current_date_time());
+                    Ok(())
+                }
+                PropertyValue::UnsignedInteger(_) => Err(ObjectError::InvalidValue(
+                    "Record_Count may only be written with zero".to_string(),
+                )),
+                _ => Err(ObjectError::InvalidPropertyType),
+            },
+            PropertyIdentifier::LoggingType => {
+                if let PropertyValue::Enumerated(logging_type) = value {
+                    match LoggingType::try_from(logging_type)? {
+                        // Trend Log Multiple has no COV subscriptions of its own
+                        LoggingType::Cov => Err(ObjectError::InvalidValue(
+                            "COV logging is not supported by Trend Log Multiple".to_string(),
+                        )),
+                        logging_type => {
+                            self.logging_type = logging_type;
+                            Ok(())
+                        }
+                    }
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            PropertyIdentifier::Trigger if self.logging_type == LoggingType::Triggered => {
+                if let PropertyValue::Boolean(trigger) = value {
+                    self.trigger = trigger;
+                    Ok(())
+                } else {
+                    Err(ObjectError::InvalidPropertyType)
+                }
+            }
+            _ => Err(ObjectError::PropertyNotWritable),
+        }
+    }
+
+    fn is_property_writable(&self, property: PropertyIdentifier) -> bool {
+        match property {
+            PropertyIdentifier::ObjectName
+            | PropertyIdentifier::Description
+            | PropertyIdentifier::LogEnable
+            | PropertyIdentifier::StartTime
+            | PropertyIdentifier::StopTime
+            | PropertyIdentifier::LogInterval
+            | PropertyIdentifier::StopWhenFull
+            | PropertyIdentifier::BufferSize
+            | PropertyIdentifier::RecordCount
+            | PropertyIdentifier::LoggingType => true,
+            PropertyIdentifier::Trigger => self.logging_type == LoggingType::Triggered,
+            _ => false,
+        }
+    }
+
+    fn property_list(&self) -> Vec<PropertyIdentifier> {
+        let mut properties = vec![
+            PropertyIdentifier::ObjectIdentifier,
+            PropertyIdentifier::ObjectName,
+            PropertyIdentifier::ObjectType,
+            PropertyIdentifier::Description,
+            PropertyIdentifier::LogEnable,
+            PropertyIdentifier::StartTime,
+            PropertyIdentifier::StopTime,
+            PropertyIdentifier::LogDeviceObjectProperty,
+            PropertyIdentifier::LogInterval,
+            PropertyIdentifier::StopWhenFull,
+            PropertyIdentifier::BufferSize,
+            PropertyIdentifier::LogBuffer,
+            PropertyIdentifier::RecordCount,
+            PropertyIdentifier::TotalRecordCount,
+            PropertyIdentifier::LoggingType,
+        ];
+        if self.logging_type == LoggingType::Triggered {
+            properties.push(PropertyIdentifier::Trigger);
+        }
+        properties.extend([
+            PropertyIdentifier::StatusFlags,
+            PropertyIdentifier::EventState,
+            PropertyIdentifier::Reliability,
+        ]);
+        properties
+    }
+
+    fn advance_time(&mut self, elapsed: Duration) {
+        if self.logging_type == LoggingType::Polled {
+            self.time_since_poll = self.time_since_poll.saturating_add(elapsed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::{Date, Time};
+
+    fn at(minute: u8) -> BacnetDateTime {
+        BacnetDateTime::new(
+            Date {
+                year: 2024,
+                month: 5,
+                day: 1,
+                weekday: 3,
+            },
+            Time {
+                hour: 8,
+                minute,
+                second: 0,
+                hundredths: 0,
+            },
+        )
+    }
+
+    fn zone_log() -> TrendLogMultiple {
+        let mut log = TrendLogMultiple::new(1, "Zone Log".to_string(), 2);
+        log.log_device_object_property = vec![
+            DeviceObjectPropertyReference::new(
+                ObjectIdentifier::new(ObjectType::AnalogInput, 1),
+                PropertyIdentifier::PresentValue,
+            ),
+            DeviceObjectPropertyReference::new(
+                ObjectIdentifier::new(ObjectType::BinaryInput, 1),
+                PropertyIdentifier::PresentValue,
+            ),
+        ];
+        log.log_enable = true;
+        log
+    }
+
+    #[test]
+    fn test_multiple_records_share_buffer_semantics() {
+        let mut log = zone_log();
+        for minute in 0..3 {
+            let logged = log
+                .log_values(
+                    at(minute),
+                    vec![LogDatum::Real(minute as f32), LogDatum::Enumerated(1)],
+                )
+                .unwrap();
+            assert!(logged);
+        }
+        assert_eq!(log.record_count(), 2);
+        assert_eq!(log.total_record_count(), 3);
+        assert!(log.log_values(at(4), vec![LogDatum::Null]).is_err());
+
+        let range = log.read_range_by_sequence(3, -1);
+        assert_eq!(range.first_sequence_number, Some(3));
+        assert!(range.last_item);
+        match &range.records[0].log_data {
+            LogMultipleData::LogData(data) => {
+                assert!(matches!(data[0], LogDatum::Real(v) if v == 2.0))
+            }
+            other => panic!("unexpected record {:?}", other),
+        }
+
+        let PropertyValue::List(items) = range.records[0].to_property_value() else {
+            panic!("record should encode as a list");
+        };
+        assert!(matches!(&items[2], PropertyValue::List(data) if data.len() == 2));
+    }
+
+    #[test]
+    fn test_shared_interval_and_trigger() {
+        let mut log = zone_log();
+        log.log_interval = 1000;
+        log.advance_time(Duration::from_secs(10));
+        assert_eq!(log.take_acquisition_request().map(<[_]>::len), Some(2));
+        assert!(log.take_acquisition_request().is_none());
+
+        assert!(log
+            .set_property(
+                PropertyIdentifier::LoggingType,
+                PropertyValue::Enumerated(LoggingType::Cov as u32)
+            )
+            .is_err());
+        log.set_property(
+            PropertyIdentifier::LoggingType,
+            PropertyValue::Enumerated(LoggingType::Triggered as u32),
+        )
+        .unwrap();
+        log.set_property(PropertyIdentifier::Trigger, PropertyValue::Boolean(true))
+            .unwrap();
+        assert!(log.take_acquisition_request().is_some());
+    }
+}
@@ -0,0 +1,437 @@
+//! AcknowledgeAlarm Service (Clause 13.5)
+//!
+//! An operator acknowledges an event transition by naming the object, the
+//! event state entered and the time stamp of the transition. The time stamp
+//! must match the transition's entry in the object's Event_Time_Stamps, so an
+//! acknowledgment cannot apply to a later occurrence the operator has not
+//! seen. Once the bit in Acked_Transitions is set, an ACK_NOTIFICATION is sent
+//! to the recipients of the object's Notification Class.
+//! [`handle_acknowledge_alarm`] applies a request to an
+//! [`ObjectDatabase`](crate::object::database::ObjectDatabase) and queues those
+//! notifications on it.
+
+use super::cov::decode_context_identifier;
+use super::event_notification::{decode_context_value, encode_context_value};
+use super::read_property_multiple::expect_tag;
+use super::{BacnetDateTime, TimeStamp};
+use crate::encoding::{
+    advanced::context::{encode_closing_tag, encode_opening_tag},
+    decode_context_enumerated, decode_context_unsigned, encode_context_enumerated,
+    encode_context_object_id, encode_context_unsigned, ApplicationTag, EncodingError,
+    Result as EncodingResult,
+};
+use crate::object::{EventState, ObjectIdentifier, PropertyValue};
+
+#[cfg(feature = "std")]
+use super::event_summary::event_type_of;
+#[cfg(feature = "std")]
+use super::{
+    ConfirmedServiceChoice, EventNotificationRequest, PendingEventNotification,
+    PropertyAccessError, RejectReason,
+};
+#[cfg(feature = "std")]
+use crate::{
+    app::Apdu,
+    object::{
+        database::ObjectDatabase, date_time_from_value, EventTransition, NotifyType,
+        PropertyIdentifier,
+    },
+};
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+
+/// Error class services (5), code invalid-time-stamp (14)
+#[cfg(feature = "std")]
+const INVALID_TIME_STAMP: PropertyAccessError = PropertyAccessError {
+    error_class: 5,
+    error_code: 14,
+};
+
+/// Acknowledge Alarm request (confirmed service)
+#[derive(Debug, Clone, PartialEq)]
+pub struct AcknowledgeAlarmRequest {
+    /// Process identifier of the acknowledging application
+    pub acknowledging_process_identifier: u32,
+    /// Object whose transition is acknowledged
+    pub event_object_identifier: ObjectIdentifier,
+    /// Event state entered by the acknowledged transition
+    pub event_state_acknowledged: EventState,
+    /// Time stamp of the acknowledged transition
+    pub time_stamp: TimeStamp,
+    /// Who acknowledged the transition
+    pub acknowledgment_source: String,
+    /// When the transition was acknowledged
+    pub time_of_acknowledgment: TimeStamp,
+}
+
+impl AcknowledgeAlarmRequest {
+    /// Create a new Acknowledge Alarm request
+    pub fn new(
+        acknowledging_process_identifier: u32,
+        event_object_identifier: ObjectIdentifier,
+        event_state_acknowledged: EventState,
+        time_stamp: TimeStamp,
+        acknowledgment_source: String,
+        time_of_acknowledgment: TimeStamp,
+    ) -> Self {
+        Self {
+            acknowledging_process_identifier,
+            event_object_identifier,
+            event_state_acknowledged,
+            time_stamp,
+            acknowledgment_source,
+            time_of_acknowledgment,
+        }
+    }
+
+    /// Encode the Acknowledge Alarm request
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        // Acknowledging process identifier - context tag 0
+        buffer.extend_from_slice(&encode_context_unsigned(
+            self.acknowledging_process_identifier,
+            0,
+        )?);
+
+        // Event object identifier - context tag 1
+        buffer.extend_from_slice(&encode_context_object_id(
+            u16::from(self.event_object_identifier.object_type),
+            self.event_object_identifier.instance,
+            1,
+        )?);
+
+        // Event state acknowledged - context tag 2
+        buffer.extend_from_slice(&encode_context_enumerated(
+            self.event_state_acknowledged as u32,
+            2,
+        )?);
+
+        // Time stamp - context tag 3
+        encode_opening_tag(buffer, 3)?;
+        self.time_stamp.encode(buffer)?;
+        encode_closing_tag(buffer, 3)?;
+
+        // Acknowledgment source - context tag 4
+        encode_context_value(
+            buffer,
+            &PropertyValue::CharacterString(self.acknowledgment_source.clone()),
+            4,
+        )?;
+
+        // Time of acknowledgment - context tag 5
+        encode_opening_tag(buffer, 5)?;
+        self.time_of_acknowledgment.encode(buffer)?;
+        encode_closing_tag(buffer, 5)
+    }
+
+    /// Decode an Acknowledge Alarm request
+    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
+        let (acknowledging_process_identifier, mut pos) = decode_context_unsigned(data, 0)?;
+        let (event_object_identifier, consumed) = decode_context_identifier(&data[pos..], 1)?;
+        pos += consumed;
+        let (event_state, consumed) = decode_context_enumerated(&data[pos..], 2)?;
+        pos += consumed;
+        let event_state_acknowledged =
+            EventState::try_from(event_state).map_err(|_| EncodingError::ValueOutOfRange)?;
+
+        expect_tag(data, pos, 0x3E)?;
+        let (time_stamp, consumed) = TimeStamp::decode(&data[pos + 1..])?;
+        pos += consumed + 1;
+        expect_tag(data, pos, 0x3F)?;
+        pos += 1;
+
+        let acknowledgment_source =
+            match decode_context_value(&data[pos..], 4, ApplicationTag::CharacterString)? {
+                (PropertyValue::CharacterString(source), consumed) => {
+                    pos += consumed;
+                    source
+                }
+                _ => return Err(EncodingError::InvalidTag),
+            };
+
+        expect_tag(data, pos, 0x5E)?;
+        let (time_of_acknowledgment, consumed) = TimeStamp::decode(&data[pos + 1..])?;
+        pos += consumed + 1;
+        expect_tag(data, pos, 0x5F)?;
+        pos += 1;
+
+        if pos != data.len() {
+            return Err(EncodingError::InvalidFormat(
+                "Unexpected data after Acknowledge Alarm request".to_string(),
+            ));
+        }
+
+        Ok(Self::new(
+            acknowledging_process_identifier,
+            event_object_identifier,
+            event_state_acknowledged,
+            time_stamp,
+            acknowledgment_source,
+            time_of_acknowledgment,
+        ))
+    }
+
+    /// Whether the request's time stamp identifies a recorded transition time
+    ///
+    /// Objects record date-times, so a time-of-day stamp compares against the
+    /// time component and a sequence number never matches.
+    pub fn matches_time_stamp(&self, recorded: &BacnetDateTime) -> bool {
+        if recorded.is_unspecified() {
+            return false;
+        }
+        match &self.time_stamp {
+            TimeStamp::DateTime(date_time) => date_time == recorded,
+            TimeStamp::Time(time) => *time == recorded.time,
+            TimeStamp::SequenceNumber(_) => false,
+        }
+    }
+}
+
+/// Acknowledge an event transition of an object in a database
+///
+/// The transition is the one entering the acknowledged event state. Its
+/// Event_Time_Stamps entry must match the request, or the acknowledgment fails
+/// with INVALID_TIME_STAMP. On success the ACK_NOTIFICATION for each recipient
+/// of the object's Notification Class is queued with
+/// [`ObjectDatabase::queue_event_notifications`].
+#[cfg(feature = "std")]
+pub fn acknowledge_alarm(
+    database: &ObjectDatabase,
+    request: &AcknowledgeAlarmRequest,
+) -> Result<(), PropertyAccessError> {
+    let object = request.event_object_identifier;
+    let transition = EventTransition::for_state(request.event_state_acknowledged);
+
+    let recorded = database
+        .get_property_at(
+            object,
+            PropertyIdentifier::EventTimeStamps,
+            transition as u32 + 1,
+        )
+        .map_err(|err| PropertyAccessError::from(&err))?;
+    let recorded =
+        date_time_from_value(&recorded).map_err(|err| PropertyAccessError::from(&err))?;
+    if !request.matches_time_stamp(&recorded) {
+        return Err(INVALID_TIME_STAMP);
+    }
+
+    database
+        .acknowledge_transition(object, transition)
+        .map_err(|err| PropertyAccessError::from(&err))?;
+
+    let Ok(PropertyValue::UnsignedInteger(class)) =
+        database.get_property(object, PropertyIdentifier::NotificationClass)
+    else {
+        return Ok(());
+    };
+    let Some(notification_class) = database.notification_class(class) else {
+        return Ok(());
+    };
+    let event_type = event_type_of(database, object);
+
+    let notification = EventNotificationRequest {
+        process_identifier: 0,
+        initiating_device_identifier: database.get_device_id(),
+        event_object_identifier: object,
+        time_stamp: request.time_of_acknowledgment,
+        notification_class: class,
+        priority: notification_class.priority_for(transition),
+        event_type,
+        message_text: None,
+        notify_type: NotifyType::AckNotification,
+        ack_required: None,
+        from_state: None,
+        to_state: request.event_state_acknowledged,
+        event_values: None,
+    };
+    let now = match request.time_of_acknowledgment {
+        TimeStamp::DateTime(date_time) => date_time,
+        _ => BacnetDateTime::now(),
+    };
+    database.queue_event_notifications(PendingEventNotification::for_recipients(
+        &notification_class,
+        &notification,
+        &now.date,
+        &now.time,
+    ));
+    Ok(())
+}
+
+/// Answer an Acknowledge Alarm request
+///
+/// Returns a SimpleAck once the transition is acknowledged, an Error PDU if
+/// the object is unknown, has no event reporting or the time stamp does not
+/// match, or a Reject PDU if the request cannot be decoded.
+#[cfg(feature = "std")]
+pub fn handle_acknowledge_alarm(
+    database: &ObjectDatabase,
+    invoke_id: u8,
+    service_data: &[u8],
+) -> Apdu {
+    let service_choice = ConfirmedServiceChoice::AcknowledgeAlarm as u8;
+    let request = match AcknowledgeAlarmRequest::decode(service_data) {
+        Ok(request) => request,
+        Err(error) => return Apdu::reject(invoke_id, RejectReason::for_decode_error(&error)),
+    };
+
+    match acknowledge_alarm(database, &request) {
+        Ok(()) => Apdu::SimpleAck {
+            invoke_id,
+            service_choice,
+        },
+        Err(error) => Apdu::error(invoke_id, service_choice, error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "std")]
+    use crate::object::EventType;
+    use crate::object::{Date, ObjectType, Time};
+
+    fn transition_time() -> BacnetDateTime {
+        BacnetDateTime::new(
+            Date {
+                year: 2024,
+                month: 3,
+                day: 14,
+                weekday: 4,
+            },
+            Time {
+                hour: 9,
+                minute: 30,
+                second: 0,
+                hundredths: 0,
+            },
+        )
+    }
+
+    #[test]
+    fn test_acknowledge_alarm_request() {
+        let request = AcknowledgeAlarmRequest::new(
+            1,
+            ObjectIdentifier::new(ObjectType::EventEnrollment, 1),
+            EventState::HighLimit,
+            TimeStamp::DateTime(transition_time()),
+            String::from("Operator"),
+            TimeStamp::SequenceNumber(12),
+        );
+        let mut buffer = Vec::new();
+        request.encode(&mut buffer).unwrap();
+        assert_eq!(AcknowledgeAlarmRequest::decode(&buffer).unwrap(), request);
+
+        assert!(request.matches_time_stamp(&transition_time()));
+        assert!(!request.matches_time_stamp(&BacnetDateTime::unspecified()));
+        let by_time = AcknowledgeAlarmRequest {
+            time_stamp: TimeStamp::Time(transition_time().time),
+            ..request
+        };
+        assert!(by_time.matches_time_stamp(&transition_time()));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_handle_acknowledge_alarm() {
+        use crate::object::{
+            BacnetObject, Destination, Device, DeviceObjectPropertyReference, EventEnrollment,
+            EventParameters, NotificationClass, Recipient,
+        };
+
+        let mut ee = EventEnrollment::new(
+            1,
+            String::from("Supply Temp Alarm"),
+            DeviceObjectPropertyReference::new(
+                ObjectIdentifier::new(ObjectType::AnalogInput, 1),
+                PropertyIdentifier::PresentValue,
+            ),
+            EventParameters::OutOfRange {
+                time_delay: 0,
+                low_limit: 10.0,
+                high_limit: 30.0,
+                deadband: 2.0,
+            },
+            5,
+        );
+        ee.evaluate(&PropertyValue::Real(31.0), None, Some(transition_time()))
+            .unwrap();
+        let ee_id = ee.identifier();
+
+        let operator = ObjectIdentifier::new(ObjectType::Device, 99);
+        let mut nc = NotificationClass::new(5, String::from("Alarms"));
+        nc.priority = [100, 50, 200];
+        nc.add_recipient(Destination::new(Recipient::Device(operator), 8));
+
+        let database = ObjectDatabase::new(Device::new(15, String::from("Device")));
+        database.add_object(Box::new(ee)).unwrap();
+        database.add_object(Box::new(nc)).unwrap();
+
+        let acked = |database: &ObjectDatabase| {
+            database
+                .get_property(ee_id, PropertyIdentifier::AckedTransitions)
+                .unwrap()
+        };
+        assert_eq!(
+            acked(&database),
+            PropertyValue::BitString(vec![false, true, true])
+        );
+
+        let encode = |time_stamp: TimeStamp| {
+            let mut service_data = Vec::new();
+            AcknowledgeAlarmRequest::new(
+                1,
+                ee_id,
+                EventState::HighLimit,
+                time_stamp,
+                String::from("Operator"),
+                TimeStamp::DateTime(transition_time()),
+            )
+            .encode(&mut service_data)
+            .unwrap();
+            service_data
+        };
+
+        // A time stamp of another occurrence is refused
+        let stale = encode(TimeStamp::SequenceNumber(3));
+        assert!(matches!(
+            handle_acknowledge_alarm(&database, 1, &stale),
+            Apdu::Error {
+                error_class: 5,
+                error_code: 14,
+                ..
+            }
+        ));
+        assert!(database.take_event_notifications().is_empty());
+
+        let service_data = encode(TimeStamp::DateTime(transition_time()));
+        assert!(matches!(
+            handle_acknowledge_alarm(&database, 2, &service_data),
+            Apdu::SimpleAck {
+                invoke_id: 2,
+                service_choice: 0,
+            }
+        ));
+        assert_eq!(
+            acked(&database),
+            PropertyValue::BitString(vec![true, true, true])
+        );
+
+        let notifications = database.take_event_notifications();
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0].recipient, Recipient::Device(operator));
+        let notification = &notifications[0].notification;
+        assert_eq!(notification.process_identifier, 8);
+        assert_eq!(notification.notify_type, NotifyType::AckNotification);
+        assert_eq!(notification.event_type, EventType::OutOfRange);
+        assert_eq!(notification.priority, 100);
+        assert_eq!(notification.to_state, EventState::HighLimit);
+
+        assert!(matches!(
+            handle_acknowledge_alarm(&database, 3, &[0xFF]),
+            Apdu::Reject { invoke_id: 3, .. }
+        ));
+    }
+}
@@ -0,0 +1,1087 @@
+//! AtomicReadFile and AtomicWriteFile Services (Clauses 14.1 and 14.2)
+//!
+//! Both services move one block of a File object's contents in a single
+//! transaction: a run of octets with stream access, or a run of records with
+//! record access. A write starting at -1 appends to the end of the file. The
+//! server dispatches requests into the File object's
+//! [`FileStorage`](crate::object::FileStorage); clients split transfers larger
+//! than one APDU with [`AtomicWriteFileRequest::stream_chunks`],
+//! [`AtomicWriteFileRequest::record_chunks`] and [`max_stream_chunk`].
+
+use super::event_notification::{decode_context_value, encode_context_value, Reader};
+use crate::encoding::{
+    advanced::context::{encode_closing_tag, encode_opening_tag},
+    decode_boolean, decode_object_identifier, decode_octet_string, decode_signed, decode_unsigned,
+    encode_boolean, encode_object_identifier, encode_octet_string, encode_signed, encode_unsigned,
+    ApplicationTag, EncodingError, Result as EncodingResult,
+};
+use crate::object::{ObjectIdentifier, ObjectType, PropertyValue};
+
+#[cfg(feature = "std")]
+use super::{AbortReason, ConfirmedServiceChoice, PropertyAccessError, RejectReason};
+#[cfg(feature = "std")]
+use crate::{
+    app::Apdu,
+    object::{database::ObjectDatabase, File},
+};
+
+#[cfg(not(feature = "std"))]
+use alloc::{format, string::ToString, vec::Vec};
+
+/// Confirmed request APDU header: PDU type, segmentation, invoke ID and
+/// service choice
+const REQUEST_HEADER: usize = 4;
+
+/// Largest encoding of the parameters around stream file data: file
+/// identifier, access method tags, start position and octet string header
+const STREAM_OVERHEAD: usize = 16;
+
+/// Largest encoding of the parameters around file records: file identifier,
+/// access method tags, start record and record count
+const RECORD_OVERHEAD: usize = 17;
+
+/// Largest octet string header for a record or chunk below 64 KiB
+const OCTET_STRING_HEADER: usize = 4;
+
+/// Error class services (5), code file-access-denied (5)
+#[cfg(feature = "std")]
+const FILE_ACCESS_DENIED: PropertyAccessError = PropertyAccessError {
+    error_class: 5,
+    error_code: 5,
+};
+
+/// Error class services (5), code invalid-file-access-method (10)
+#[cfg(feature = "std")]
+const INVALID_FILE_ACCESS_METHOD: PropertyAccessError = PropertyAccessError {
+    error_class: 5,
+    error_code: 10,
+};
+
+/// Error class services (5), code invalid-file-start-position (11)
+#[cfg(feature = "std")]
+const INVALID_FILE_START_POSITION: PropertyAccessError = PropertyAccessError {
+    error_class: 5,
+    error_code: 11,
+};
+
+/// Largest number of file octets one AtomicReadFile or AtomicWriteFile can
+/// carry in an APDU of `max_apdu` octets
+///
+/// The bound covers both directions: the file data of a write request and of
+/// a read acknowledgement, whose header is one octet shorter.
+pub fn max_stream_chunk(max_apdu: usize) -> usize {
+    max_apdu.saturating_sub(REQUEST_HEADER + STREAM_OVERHEAD)
+}
+
+/// Atomic Read File request (confirmed service)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AtomicReadFileRequest {
+    /// File object identifier
+    pub file_identifier: ObjectIdentifier,
+    /// Access method specification
+    pub access_method: FileAccessMethod,
+}
+
+/// File access method for atomic read/write
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileAccessMethod {
+    /// Stream access - read/write bytes at position
+    StreamAccess {
+        /// File position to start reading/writing
+        file_start_position: i32,
+        /// Number of octets to read (for read operations)
+        requested_octet_count: u32,
+    },
+    /// Record access - read/write records
+    RecordAccess {
+        /// Starting record number
+        file_start_record: i32,
+        /// Number of records to read (for read operations)
+        requested_record_count: u32,
+    },
+}
+
+impl AtomicReadFileRequest {
+    /// Create a new Atomic Read File request with stream access
+    pub fn new_stream_access(
+        file_identifier: ObjectIdentifier,
+        start_position: i32,
+        octet_count: u32,
+    ) -> Self {
+        Self {
+            file_identifier,
+            access_method: FileAccessMethod::StreamAccess {
+                file_start_position: start_position,
+                requested_octet_count: octet_count,
+            },
+        }
+    }
+
+    /// Create a new Atomic Read File request with record access
+    pub fn new_record_access(
+        file_identifier: ObjectIdentifier,
+        start_record: i32,
+        record_count: u32,
+    ) -> Self {
+        Self {
+            file_identifier,
+            access_method: FileAccessMethod::RecordAccess {
+                file_start_record: start_record,
+                requested_record_count: record_count,
+            },
+        }
+    }
+
+    /// Encode the Atomic Read File request
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        // File identifier - application tagged
+        encode_file_identifier(buffer, &self.file_identifier)?;
+
+        match &self.access_method {
+            // Stream access - context tag 0
+            FileAccessMethod::StreamAccess {
+                file_start_position,
+                requested_octet_count,
+            } => {
+                encode_opening_tag(buffer, 0)?;
+                encode_signed(buffer, *file_start_position)?;
+                encode_unsigned(buffer, *requested_octet_count)?;
+                encode_closing_tag(buffer, 0)?;
+            }
+            // Record access - context tag 1
+            FileAccessMethod::RecordAccess {
+                file_start_record,
+                requested_record_count,
+            } => {
+                encode_opening_tag(buffer, 1)?;
+                encode_signed(buffer, *file_start_record)?;
+                encode_unsigned(buffer, *requested_record_count)?;
+                encode_closing_tag(buffer, 1)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Decode an Atomic Read File request
+    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
+        let (file_identifier, consumed) = decode_file_identifier(data)?;
+        let mut reader = Reader::new(&data[consumed..]);
+
+        let access_method = if reader.is_context(0) {
+            reader.open(0)?;
+            let file_start_position = reader.advance(decode_signed(reader.rest())?);
+            let requested_octet_count = reader.advance(decode_unsigned(reader.rest())?);
+            reader.close(0)?;
+            FileAccessMethod::StreamAccess {
+                file_start_position,
+                requested_octet_count,
+            }
+        } else {
+            reader.open(1)?;
+            let file_start_record = reader.advance(decode_signed(reader.rest())?);
+            let requested_record_count = reader.advance(decode_unsigned(reader.rest())?);
+            reader.close(1)?;
+            FileAccessMethod::RecordAccess {
+                file_start_record,
+                requested_record_count,
+            }
+        };
+
+        expect_end(&reader, "Atomic Read File request")?;
+        Ok(Self {
+            file_identifier,
+            access_method,
+        })
+    }
+}
+
+/// Atomic Read File response (confirmed service)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AtomicReadFileResponse {
+    /// End of file flag
+    pub end_of_file: bool,
+    /// Access method and data
+    pub access_method_result: FileAccessMethodResult,
+}
+
+/// File access method result for atomic read response
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileAccessMethodResult {
+    /// Stream access result
+    StreamAccess {
+        /// File position after read
+        file_start_position: i32,
+        /// File data read
+        file_data: Vec<u8>,
+    },
+    /// Record access result
+    RecordAccess {
+        /// Starting record number
+        file_start_record: i32,
+        /// Number of records returned
+        record_count: u32,
+        /// Record data
+        file_record_data: Vec<Vec<u8>>,
+    },
+}
+
+impl AtomicReadFileResponse {
+    /// Create a new stream access response
+    pub fn new_stream_access(end_of_file: bool, start_position: i32, data: Vec<u8>) -> Self {
+        Self {
+            end_of_file,
+            access_method_result: FileAccessMethodResult::StreamAccess {
+                file_start_position: start_position,
+                file_data: data,
+            },
+        }
+    }
+
+    /// Create a new record access response
+    pub fn new_record_access(end_of_file: bool, start_record: i32, records: Vec<Vec<u8>>) -> Self {
+        let record_count = records.len() as u32;
+        Self {
+            end_of_file,
+            access_method_result: FileAccessMethodResult::RecordAccess {
+                file_start_record: start_record,
+                record_count,
+                file_record_data: records,
+            },
+        }
+    }
+
+    /// Encode the Atomic Read File acknowledgement
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        // End of file - application tagged
+        encode_boolean(buffer, self.end_of_file)?;
+
+        match &self.access_method_result {
+            // Stream access - context tag 0
+            FileAccessMethodResult::StreamAccess {
+                file_start_position,
+                file_data,
+            } => {
+                encode_opening_tag(buffer, 0)?;
+                encode_signed(buffer, *file_start_position)?;
+                encode_octet_string(buffer, file_data)?;
+                encode_closing_tag(buffer, 0)?;
+            }
+            // Record access - context tag 1
+            FileAccessMethodResult::RecordAccess {
+                file_start_record,
+                file_record_data,
+                ..
+            } => {
+                encode_opening_tag(buffer, 1)?;
+                encode_signed(buffer, *file_start_record)?;
+                encode_records(buffer, file_record_data)?;
+                encode_closing_tag(buffer, 1)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Decode an Atomic Read File acknowledgement
+    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
+        let mut reader = Reader::new(data);
+        let end_of_file = reader.advance(decode_boolean(reader.rest())?);
+
+        let access_method_result = if reader.is_context(0) {
+            reader.open(0)?;
+            let file_start_position = reader.advance(decode_signed(reader.rest())?);
+            let file_data = reader.advance(decode_octet_string(reader.rest())?);
+            reader.close(0)?;
+            FileAccessMethodResult::StreamAccess {
+                file_start_position,
+                file_data,
+            }
+        } else {
+            reader.open(1)?;
+            let file_start_record = reader.advance(decode_signed(reader.rest())?);
+            let file_record_data = decode_records(&mut reader)?;
+            reader.close(1)?;
+            FileAccessMethodResult::RecordAccess {
+                file_start_record,
+                record_count: file_record_data.len() as u32,
+                file_record_data,
+            }
+        };
+
+        expect_end(&reader, "Atomic Read File acknowledgement")?;
+        Ok(Self {
+            end_of_file,
+            access_method_result,
+        })
+    }
+}
+
+/// Atomic Write File request (confirmed service)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AtomicWriteFileRequest {
+    /// File object identifier
+    pub file_identifier: ObjectIdentifier,
+    /// Access method and data
+    pub access_method: FileWriteAccessMethod,
+}
+
+/// File write access method for atomic write
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileWriteAccessMethod {
+    /// Stream access - write bytes at position
+    StreamAccess {
+        /// File position to start writing
+        file_start_position: i32,
+        /// Data to write
+        file_data: Vec<u8>,
+    },
+    /// Record access - write records
+    RecordAccess {
+        /// Starting record number
+        file_start_record: i32,
+        /// Number of records to write
+        record_count: u32,
+        /// Record data to write
+        file_record_data: Vec<Vec<u8>>,
+    },
+}
+
+impl AtomicWriteFileRequest {
+    /// Create a new Atomic Write File request with stream access
+    pub fn new_stream_access(
+        file_identifier: ObjectIdentifier,
+        start_position: i32,
+        data: Vec<u8>,
+    ) -> Self {
+        Self {
+            file_identifier,
+            access_method: FileWriteAccessMethod::StreamAccess {
+                file_start_position: start_position,
+                file_data: data,
+            },
+        }
+    }
+
+    /// Create a new Atomic Write File request with record access
+    pub fn new_record_access(
+        file_identifier: ObjectIdentifier,
+        start_record: i32,
+        records: Vec<Vec<u8>>,
+    ) -> Self {
+        let record_count = records.len() as u32;
+        Self {
+            file_identifier,
+            access_method: FileWriteAccessMethod::RecordAccess {
+                file_start_record: start_record,
+                record_count,
+                file_record_data: records,
+            },
+        }
+    }
+
+    /// Split a stream write of `data` at `start_position` into requests that
+    /// each fit an APDU of `max_apdu` octets
+    ///
+    /// A start position of -1 appends every chunk in turn, so the requests
+    /// must be sent in order.
+    pub fn stream_chunks(
+        file_identifier: ObjectIdentifier,
+        start_position: i32,
+        data: &[u8],
+        max_apdu: usize,
+    ) -> Vec<Self> {
+        let chunk_size = max_stream_chunk(max_apdu).max(1);
+        let mut offset = 0;
+        data.chunks(chunk_size)
+            .map(|chunk| {
+                let position = if start_position == -1 {
+                    -1
+                } else {
+                    start_position.saturating_add(offset)
+                };
+                offset = offset.saturating_add(chunk.len() as i32);
+                Self::new_stream_access(file_identifier, position, chunk.to_vec())
+            })
+            .collect()
+    }
+
+    /// Split a record write starting at `start_record` into requests that
+    /// each fit an APDU of `max_apdu` octets
+    ///
+    /// Records are never split; one larger than an APDU goes in a request of
+    /// its own. A start record of -1 appends every request in turn.
+    pub fn record_chunks(
+        file_identifier: ObjectIdentifier,
+        start_record: i32,
+        records: &[Vec<u8>],
+        max_apdu: usize,
+    ) -> Vec<Self> {
+        let budget = max_apdu.saturating_sub(REQUEST_HEADER + RECORD_OVERHEAD);
+        let mut requests = Vec::new();
+        let mut chunk: Vec<Vec<u8>> = Vec::new();
+        let mut chunk_size = 0;
+        let mut next_record = start_record;
+
+        let mut flush = |chunk: &mut Vec<Vec<u8>>, next_record: &mut i32| {
+            let records = core::mem::take(chunk);
+            let count = records.len() as i32;
+            requests.push(Self::new_record_access(
+                file_identifier,
+                *next_record,
+                records,
+            ));
+            if *next_record != -1 {
+                *next_record = next_record.saturating_add(count);
+            }
+        };
+
+        for record in records {
+            let size = OCTET_STRING_HEADER + record.len();
+            if !chunk.is_empty() && chunk_size + size > budget {
+                flush(&mut chunk, &mut next_record);
+                chunk_size = 0;
+            }
+            chunk.push(record.clone());
+            chunk_size += size;
+        }
+        if !chunk.is_empty() {
+            flush(&mut chunk, &mut next_record);
+        }
+        requests
+    }
+
+    /// Encode the Atomic Write File request
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        // File identifier - application tagged
+        encode_file_identifier(buffer, &self.file_identifier)?;
+
+        match &self.access_method {
+            // Stream access - context tag 0
+            FileWriteAccessMethod::StreamAccess {
+                file_start_position,
+                file_data,
+            } => {
+                encode_opening_tag(buffer, 0)?;
+                encode_signed(buffer, *file_start_position)?;
+                encode_octet_string(buffer, file_data)?;
+                encode_closing_tag(buffer, 0)?;
+            }
+            // Record access - context tag 1
+            FileWriteAccessMethod::RecordAccess {
+                file_start_record,
+                file_record_data,
+                ..
+            } => {
+                encode_opening_tag(buffer, 1)?;
+                encode_signed(buffer, *file_start_record)?;
+                encode_records(buffer, file_record_data)?;
+                encode_closing_tag(buffer, 1)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Decode an Atomic Write File request
+    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
+        let (file_identifier, consumed) = decode_file_identifier(data)?;
+        let mut reader = Reader::new(&data[consumed..]);
+
+        let access_method = if reader.is_context(0) {
+            reader.open(0)?;
+            let file_start_position = reader.advance(decode_signed(reader.rest())?);
+            let file_data = reader.advance(decode_octet_string(reader.rest())?);
+            reader.close(0)?;
+            FileWriteAccessMethod::StreamAccess {
+                file_start_position,
+                file_data,
+            }
+        } else {
+            reader.open(1)?;
+            let file_start_record = reader.advance(decode_signed(reader.rest())?);
+            let file_record_data = decode_records(&mut reader)?;
+            reader.close(1)?;
+            FileWriteAccessMethod::RecordAccess {
+                file_start_record,
+                record_count: file_record_data.len() as u32,
+                file_record_data,
+            }
+        };
+
+        expect_end(&reader, "Atomic Write File request")?;
+        Ok(Self {
+            file_identifier,
+            access_method,
+        })
+    }
+}
+
+/// Atomic Write File response (confirmed service)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AtomicWriteFileResponse {
+    /// File start position (for stream access) or start record (for record access)
+    pub file_start_position: i32,
+    /// Whether the write used record access
+    pub record_access: bool,
+}
+
+impl AtomicWriteFileResponse {
+    /// Create a new stream access response
+    pub fn new_stream_access(start_position: i32) -> Self {
+        Self {
+            file_start_position: start_position,
+            record_access: false,
+        }
+    }
+
+    /// Create a new record access response
+    pub fn new_record_access(start_record: i32) -> Self {
+        Self {
+            file_start_position: start_record,
+            record_access: true,
+        }
+    }
+
+    /// Encode the Atomic Write File acknowledgement
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        // File start position - context tag 0, or file start record - context tag 1
+        encode_context_value(
+            buffer,
+            &PropertyValue::SignedInt(self.file_start_position),
+            u8::from(self.record_access),
+        )
+    }
+
+    /// Decode an Atomic Write File acknowledgement
+    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
+        let record_access = !Reader::new(data).is_context(0);
+        let (value, consumed) =
+            decode_context_value(data, u8::from(record_access), ApplicationTag::SignedInt)?;
+        let PropertyValue::SignedInt(file_start_position) = value else {
+            return Err(EncodingError::InvalidTag);
+        };
+        if consumed != data.len() {
+            return Err(EncodingError::InvalidFormat(
+                "Unexpected data after Atomic Write File acknowledgement".to_string(),
+            ));
+        }
+        Ok(Self {
+            file_start_position,
+            record_access,
+        })
+    }
+}
+
+fn encode_file_identifier(
+    buffer: &mut Vec<u8>,
+    file_identifier: &ObjectIdentifier,
+) -> EncodingResult<()> {
+    encode_object_identifier(
+        buffer,
+        u16::from(file_identifier.object_type),
+        file_identifier.instance,
+    )
+}
+
+fn decode_file_identifier(data: &[u8]) -> EncodingResult<(ObjectIdentifier, usize)> {
+    let ((object_type, instance), consumed) = decode_object_identifier(data)?;
+    let object_type =
+        ObjectType::try_from(object_type).map_err(|_| EncodingError::ValueOutOfRange)?;
+    Ok((ObjectIdentifier::new(object_type, instance), consumed))
+}
+
+/// Record count followed by the records as octet strings
+fn encode_records(buffer: &mut Vec<u8>, records: &[Vec<u8>]) -> EncodingResult<()> {
+    let count = u32::try_from(records.len()).map_err(|_| EncodingError::ValueOutOfRange)?;
+    encode_unsigned(buffer, count)?;
+    for record in records {
+        encode_octet_string(buffer, record)?;
+    }
+    Ok(())
+}
+
+fn decode_records(reader: &mut Reader) -> EncodingResult<Vec<Vec<u8>>> {
+    let count = reader.advance(decode_unsigned(reader.rest())?);
+    let mut records = Vec::new();
+    for _ in 0..count {
+        records.push(reader.advance(decode_octet_string(reader.rest())?));
+    }
+    Ok(records)
+}
+
+fn expect_end(reader: &Reader, what: &str) -> EncodingResult<()> {
+    if reader.rest().is_empty() {
+        Ok(())
+    } else {
+        Err(EncodingError::InvalidFormat(format!(
+            "Unexpected data after {}",
+            what
+        )))
+    }
+}
+
+/// Validate a start position or record against the end of the file
+///
+/// Reads may start anywhere up to the end; writes may also start at -1 to
+/// append.
+#[cfg(feature = "std")]
+fn start_of(start: i32, end: u32, append: bool) -> Result<u32, PropertyAccessError> {
+    if append && start == -1 {
+        return Ok(end);
+    }
+    u32::try_from(start)
+        .ok()
+        .filter(|&start| start <= end)
+        .ok_or(INVALID_FILE_START_POSITION)
+}
+
+/// Read a block of a File object, as AtomicReadFile does
+///
+/// Reads past the end of the file return what is there with End_Of_File
+/// set.
+#[cfg(feature = "std")]
+pub fn atomic_read_file(
+    database: &ObjectDatabase,
+    request: &AtomicReadFileRequest,
+) -> Result<AtomicReadFileResponse, PropertyAccessError> {
+    database.read_file(request.file_identifier, |file: &File| {
+        match request.access_method {
+            FileAccessMethod::StreamAccess {
+                file_start_position,
+                requested_octet_count,
+            } => {
+                if file.record_count().is_some() {
+                    return Err(INVALID_FILE_ACCESS_METHOD);
+                }
+                let size = file.file_size();
+                let start = start_of(file_start_position, size, false)?;
+                let data = file.read_data(start, requested_octet_count)?;
+                let end_of_file = start as usize + data.len() >= size as usize;
+                Ok(AtomicReadFileResponse::new_stream_access(
+                    end_of_file,
+                    file_start_position,
+                    data,
+                ))
+            }
+            FileAccessMethod::RecordAccess {
+                file_start_record,
+                requested_record_count,
+            } => {
+                let count = file.record_count().ok_or(INVALID_FILE_ACCESS_METHOD)?;
+                let start = start_of(file_start_record, count, false)?;
+                let records = file.read_records(start, requested_record_count)?;
+                let end_of_file = start as usize + records.len() >= count as usize;
+                Ok(AtomicReadFileResponse::new_record_access(
+                    end_of_file,
+                    file_start_record,
+                    records,
+                ))
+            }
+        }
+    })
+}
+
+/// Write a block of a File object, as AtomicWriteFile does
+///
+/// Returns the position or record the block was written at, which differs
+/// from the request when it appended. Writes may start anywhere up to the end
+/// of the file.
+#[cfg(feature = "std")]
+pub fn atomic_write_file(
+    database: &ObjectDatabase,
+    request: &AtomicWriteFileRequest,
+) -> Result<AtomicWriteFileResponse, PropertyAccessError> {
+    database.write_file(request.file_identifier, |file: &mut File| {
+        if file.read_only {
+            return Err(FILE_ACCESS_DENIED);
+        }
+        match &request.access_method {
+            FileWriteAccessMethod::StreamAccess {
+                file_start_position,
+                file_data,
+            } => {
+                if file.record_count().is_some() {
+                    return Err(INVALID_FILE_ACCESS_METHOD);
+                }
+                let start = start_of(*file_start_position, file.file_size(), true)?;
+                file.write_data(start, file_data)?;
+                Ok(AtomicWriteFileResponse::new_stream_access(start as i32))
+            }
+            FileWriteAccessMethod::RecordAccess {
+                file_start_record,
+                file_record_data,
+                ..
+            } => {
+                let count = file.record_count().ok_or(INVALID_FILE_ACCESS_METHOD)?;
+                let start = start_of(*file_start_record, count, true)?;
+                file.write_records(start, file_record_data)?;
+                Ok(AtomicWriteFileResponse::new_record_access(start as i32))
+            }
+        }
+    })
+}
+
+/// Handle an Atomic Read File request
+///
+/// Returns a ComplexAck with the block read, an Error PDU if the file cannot
+/// be read as requested, or a Reject PDU if the request cannot be decoded.
+#[cfg(feature = "std")]
+pub fn handle_atomic_read_file(
+    database: &ObjectDatabase,
+    invoke_id: u8,
+    service_data: &[u8],
+) -> Apdu {
+    let service_choice = ConfirmedServiceChoice::AtomicReadFile as u8;
+    let request = match AtomicReadFileRequest::decode(service_data) {
+        Ok(request) => request,
+        Err(error) => return Apdu::reject(invoke_id, RejectReason::for_decode_error(&error)),
+    };
+
+    match atomic_read_file(database, &request) {
+        Ok(response) => complex_ack(invoke_id, service_choice, |buffer| response.encode(buffer)),
+        Err(error) => Apdu::error(invoke_id, service_choice, error),
+    }
+}
+
+/// Handle an Atomic Write File request
+///
+/// Returns a ComplexAck with the position written at, an Error PDU if the
+/// file cannot be written as requested, or a Reject PDU if the request cannot
+/// be decoded.
+#[cfg(feature = "std")]
+pub fn handle_atomic_write_file(
+    database: &ObjectDatabase,
+    invoke_id: u8,
+    service_data: &[u8],
+) -> Apdu {
+    let service_choice = ConfirmedServiceChoice::AtomicWriteFile as u8;
+    let request = match AtomicWriteFileRequest::decode(service_data) {
+        Ok(request) => request,
+        Err(error) => return Apdu::reject(invoke_id, RejectReason::for_decode_error(&error)),
+    };
+
+    match atomic_write_file(database, &request) {
+        Ok(response) => complex_ack(invoke_id, service_choice, |buffer| response.encode(buffer)),
+        Err(error) => Apdu::error(invoke_id, service_choice, error),
+    }
+}
+
+#[cfg(feature = "std")]
+fn complex_ack(
+    invoke_id: u8,
+    service_choice: u8,
+    encode: impl FnOnce(&mut Vec<u8>) -> EncodingResult<()>,
+) -> Apdu {
+    let mut service_data = Vec::new();
+    match encode(&mut service_data) {
+        Ok(()) => Apdu::ComplexAck {
+            segmented: false,
+            more_follows: false,
+            invoke_id,
+            sequence_number: None,
+            proposed_window_size: None,
+            service_choice,
+            service_data,
+        },
+        Err(error) => Apdu::abort(true, invoke_id, AbortReason::for_encode_error(&error)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_atomic_read_file_request() {
+        let file_id = ObjectIdentifier::new(ObjectType::File, 1);
+
+        // Test stream access
+        let read_stream = AtomicReadFileRequest::new_stream_access(file_id, 0, 1024);
+        match &read_stream.access_method {
+            FileAccessMethod::StreamAccess {
+                file_start_position,
+                requested_octet_count,
+            } => {
+                assert_eq!(*file_start_position, 0);
+                assert_eq!(*requested_octet_count, 1024);
+            }
+            _ => panic!("Expected StreamAccess"),
+        }
+
+        // Test record access
+        let read_record = AtomicReadFileRequest::new_record_access(file_id, 5, 10);
+        match &read_record.access_method {
+            FileAccessMethod::RecordAccess {
+                file_start_record,
+                requested_record_count,
+            } => {
+                assert_eq!(*file_start_record, 5);
+                assert_eq!(*requested_record_count, 10);
+            }
+            _ => panic!("Expected RecordAccess"),
+        }
+
+        // Test encoding
+        let mut buffer = Vec::new();
+        read_stream.encode(&mut buffer).unwrap();
+        assert!(!buffer.is_empty());
+    }
+
+    #[test]
+    fn test_atomic_read_file_response() {
+        // Test stream access response
+        let data = vec![1, 2, 3, 4, 5];
+        let response_stream = AtomicReadFileResponse::new_stream_access(false, 0, data.clone());
+        assert!(!response_stream.end_of_file);
+
+        match &response_stream.access_method_result {
+            FileAccessMethodResult::StreamAccess {
+                file_start_position,
+                file_data,
+            } => {
+                assert_eq!(*file_start_position, 0);
+                assert_eq!(*file_data, data);
+            }
+            _ => panic!("Expected StreamAccess result"),
+        }
+
+        // Test record access response
+        let records = vec![vec![1, 2], vec![3, 4], vec![5, 6]];
+        let response_record = AtomicReadFileResponse::new_record_access(true, 10, records.clone());
+        assert!(response_record.end_of_file);
+
+        match &response_record.access_method_result {
+            FileAccessMethodResult::RecordAccess {
+                file_start_record,
+                record_count,
+                file_record_data,
+            } => {
+                assert_eq!(*file_start_record, 10);
+                assert_eq!(*record_count, 3);
+                assert_eq!(*file_record_data, records);
+            }
+            _ => panic!("Expected RecordAccess result"),
+        }
+    }
+
+    #[test]
+    fn test_atomic_write_file_request() {
+        let file_id = ObjectIdentifier::new(ObjectType::File, 1);
+
+        // Test stream access
+        let data = vec![65, 66, 67, 68]; // "ABCD"
+        let write_stream = AtomicWriteFileRequest::new_stream_access(file_id, 100, data.clone());
+        match &write_stream.access_method {
+            FileWriteAccessMethod::StreamAccess {
+                file_start_position,
+                file_data,
+            } => {
+                assert_eq!(*file_start_position, 100);
+                assert_eq!(*file_data, data);
+            }
+            _ => panic!("Expected StreamAccess"),
+        }
+
+        // Test record access
+        let records = vec![
+            b"Record 1".to_vec(),
+            b"Record 2".to_vec(),
+            b"Record 3".to_vec(),
+        ];
+        let write_record = AtomicWriteFileRequest::new_record_access(file_id, 5, records.clone());
+        match &write_record.access_method {
+            FileWriteAccessMethod::RecordAccess {
+                file_start_record,
+                record_count,
+                file_record_data,
+            } => {
+                assert_eq!(*file_start_record, 5);
+                assert_eq!(*record_count, 3);
+                assert_eq!(*file_record_data, records);
+            }
+            _ => panic!("Expected RecordAccess"),
+        }
+
+        // Test encoding
+        let mut buffer = Vec::new();
+        write_stream.encode(&mut buffer).unwrap();
+        assert!(!buffer.is_empty());
+    }
+
+    #[test]
+    fn test_atomic_write_file_response() {
+        let response = AtomicWriteFileResponse {
+            file_start_position: 150,
+            record_access: false,
+        };
+        assert_eq!(response.file_start_position, 150);
+    }
+
+    #[test]
+    fn test_atomic_file_codecs() {
+        let file_id = ObjectIdentifier::new(ObjectType::File, 1);
+
+        let read = AtomicReadFileRequest::new_record_access(file_id, 5, 10);
+        let mut buffer = Vec::new();
+        read.encode(&mut buffer).unwrap();
+        assert_eq!(
+            buffer,
+            [0xC4, 0x02, 0x80, 0x00, 0x01, 0x1E, 0x31, 0x05, 0x21, 0x0A, 0x1F]
+        );
+        assert_eq!(AtomicReadFileRequest::decode(&buffer).unwrap(), read);
+
+        let ack = AtomicReadFileResponse::new_stream_access(true, 0, b"Chiller".to_vec());
+        let mut buffer = Vec::new();
+        ack.encode(&mut buffer).unwrap();
+        assert_eq!(&buffer[..5], [0x11, 0x0E, 0x31, 0x00, 0x65]);
+        assert_eq!(AtomicReadFileResponse::decode(&buffer).unwrap(), ack);
+
+        let ack = AtomicReadFileResponse::new_record_access(false, 2, vec![vec![1], vec![2, 3]]);
+        let mut buffer = Vec::new();
+        ack.encode(&mut buffer).unwrap();
+        assert_eq!(AtomicReadFileResponse::decode(&buffer).unwrap(), ack);
+
+        let write = AtomicWriteFileRequest::new_record_access(file_id, -1, vec![b"one".to_vec()]);
+        let mut buffer = Vec::new();
+        write.encode(&mut buffer).unwrap();
+        assert_eq!(AtomicWriteFileRequest::decode(&buffer).unwrap(), write);
+
+        let ack = AtomicWriteFileResponse::new_record_access(12);
+        let mut buffer = Vec::new();
+        ack.encode(&mut buffer).unwrap();
+        assert_eq!(buffer, [0x19, 0x0C]);
+        assert_eq!(AtomicWriteFileResponse::decode(&buffer).unwrap(), ack);
+    }
+
+    #[test]
+    fn test_chunking() {
+        let file_id = ObjectIdentifier::new(ObjectType::File, 1);
+        let data: Vec<u8> = (0..=255).cycle().take(3000).collect();
+
+        let chunks = AtomicWriteFileRequest::stream_chunks(file_id, 100, &data, 1476);
+        assert_eq!(chunks.len(), 3);
+        let mut reassembled = Vec::new();
+        for (chunk, expected_start) in chunks.iter().zip([100, 1556, 3012]) {
+            let mut buffer = Vec::new();
+            chunk.encode(&mut buffer).unwrap();
+            assert!(REQUEST_HEADER + buffer.len() <= 1476);
+            let FileWriteAccessMethod::StreamAccess {
+                file_start_position,
+                file_data,
+            } = &chunk.access_method
+            else {
+                panic!("Expected StreamAccess");
+            };
+            assert_eq!(*file_start_position, expected_start);
+            reassembled.extend_from_slice(file_data);
+        }
+        assert_eq!(reassembled, data);
+
+        let records = vec![vec![b'x'; 100]; 10];
+        let chunks = AtomicWriteFileRequest::record_chunks(file_id, 0, &records, 480);
+        let starts: Vec<_> = chunks
+            .iter()
+            .map(|chunk| match &chunk.access_method {
+                FileWriteAccessMethod::RecordAccess {
+                    file_start_record,
+                    record_count,
+                    ..
+                } => (*file_start_record, *record_count),
+                _ => panic!("Expected RecordAccess"),
+            })
+            .collect();
+        assert_eq!(starts, vec![(0, 4), (4, 4), (8, 2)]);
+        for chunk in &chunks {
+            let mut buffer = Vec::new();
+            chunk.encode(&mut buffer).unwrap();
+            assert!(REQUEST_HEADER + buffer.len() <= 480);
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_handle_atomic_file_services() {
+        use crate::object::{Device, FileAccessMethod as AccessMethod};
+
+        let database = ObjectDatabase::new(Device::new(15, String::from("Device")));
+        let mut log = File::new(1, String::from("Trend Log"), String::from("text/plain"));
+        log.set_file_data(b"alpha\nbeta".to_vec()).unwrap();
+        log.file_access_method = AccessMethod::RecordAccess;
+        database.add_object(Box::new(log)).unwrap();
+        let mut firmware = File::new(2, String::from("Firmware"), String::from("bin"));
+        firmware.set_file_data(b"0123456789".to_vec()).unwrap();
+        database.add_object(Box::new(firmware)).unwrap();
+        let log_id = ObjectIdentifier::new(ObjectType::File, 1);
+        let firmware_id = ObjectIdentifier::new(ObjectType::File, 2);
+
+        // Stream reads stop at the end of the file
+        let read = AtomicReadFileRequest::new_stream_access(firmware_id, 6, 100);
+        assert_eq!(
+            atomic_read_file(&database, &read).unwrap(),
+            AtomicReadFileResponse::new_stream_access(true, 6, b"6789".to_vec())
+        );
+        let past_end = AtomicReadFileRequest::new_stream_access(firmware_id, 11, 1);
+        assert_eq!(
+            atomic_read_file(&database, &past_end),
+            Err(INVALID_FILE_START_POSITION)
+        );
+        let wrong_method = AtomicReadFileRequest::new_record_access(firmware_id, 0, 1);
+        assert_eq!(
+            atomic_read_file(&database, &wrong_method),
+            Err(INVALID_FILE_ACCESS_METHOD)
+        );
+
+        // Appending a record reports where it went
+        let append = AtomicWriteFileRequest::new_record_access(log_id, -1, vec![b"gamma".to_vec()]);
+        assert_eq!(
+            atomic_write_file(&database, &append).unwrap(),
+            AtomicWriteFileResponse::new_record_access(2)
+        );
+        let read = AtomicReadFileRequest::new_record_access(log_id, 1, 5);
+        assert_eq!(
+            atomic_read_file(&database, &read).unwrap(),
+            AtomicReadFileResponse::new_record_access(
+                true,
+                1,
+                vec![b"beta".to_vec(), b"gamma".to_vec()]
+            )
+        );
+
+        // Through the handlers
+        let mut service_data = Vec::new();
+        AtomicWriteFileRequest::new_stream_access(firmware_id, 10, b"AB".to_vec())
+            .encode(&mut service_data)
+            .unwrap();
+        let Apdu::ComplexAck {
+            service_choice,
+            service_data,
+            ..
+        } = handle_atomic_write_file(&database, 1, &service_data)
+        else {
+            panic!("Expected ComplexAck");
+        };
+        assert_eq!(service_choice, 7);
+        assert_eq!(
+            AtomicWriteFileResponse::decode(&service_data).unwrap(),
+            AtomicWriteFileResponse::new_stream_access(10)
+        );
+
+        let mut service_data = Vec::new();
+        AtomicReadFileRequest::new_stream_access(ObjectIdentifier::new(ObjectType::File, 9), 0, 1)
+            .encode(&mut service_data)
+            .unwrap();
+        assert!(matches!(
+            handle_atomic_read_file(&database, 2, &service_data),
+            Apdu::Error {
+                error_class: 1,
+                error_code: 31,
+                ..
+            }
+        ));
+        assert!(matches!(
+            handle_atomic_read_file(&database, 3, &[0x0C]),
+            Apdu::Reject { invoke_id: 3, .. }
+        ));
+    }
+}
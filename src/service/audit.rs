@@ -0,0 +1,1025 @@
+//! ConfirmedAuditNotification, UnconfirmedAuditNotification and AuditLogQuery
+//! Services
+//!
+//! An Audit Reporter forwards the BACnetAuditNotifications it collects in
+//! batches with an audit notification request; the receiving device stores
+//! them in its Audit Log objects. AuditLogQuery reads an Audit Log selectively:
+//! the records for one target device, object, property or priority, or those
+//! requested by one source device or object, optionally narrowed to some
+//! operations and to successful or failed ones. Queries start at a sequence
+//! number, which [`AuditLog::first_sequence_after`] finds for a point in time.
+//!
+//! [`AuditLog::first_sequence_after`]: crate::object::AuditLog::first_sequence_after
+
+use super::event_notification::{
+    encode_abstract_value, encode_bits, encode_context_value, encode_date_time, encode_identifier,
+    Reader, TimeStamp,
+};
+use super::event_summary::{decode_recipient, encode_recipient};
+use super::{BacnetDateTime, ConfirmedServiceChoice, UnconfirmedServiceChoice};
+use crate::app::{Apdu, MaxApduSize, MaxSegments};
+use crate::encoding::{
+    advanced::context::{encode_closing_tag, encode_opening_tag},
+    decode_enumerated, decode_octet_string, decode_unsigned, encode_context_boolean,
+    encode_context_enumerated, encode_context_real, encode_context_unsigned, encode_enumerated,
+    encode_octet_string, encode_unsigned, EncodingError, Result as EncodingResult,
+};
+use crate::object::{
+    AuditLogDatum, AuditLogRecord, AuditNotification, AuditOperation, ObjectIdentifier,
+    PropertyIdentifier, PropertyValue, Recipient,
+};
+
+#[cfg(feature = "std")]
+use super::{AbortReason, PropertyAccessError, RejectReason};
+#[cfg(feature = "std")]
+use crate::object::{current_date_time, database::ObjectDatabase};
+
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, string::String, string::ToString, vec::Vec};
+
+/// Audit notification request (confirmed or unconfirmed service)
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditNotificationRequest {
+    /// Notifications forwarded
+    pub notifications: Vec<AuditNotification>,
+}
+
+impl AuditNotificationRequest {
+    /// Create a new request
+    pub fn new(notifications: Vec<AuditNotification>) -> Self {
+        Self { notifications }
+    }
+
+    /// Encode the request
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        encode_opening_tag(buffer, 0)?;
+        for notification in &self.notifications {
+            encode_notification(buffer, notification)?;
+        }
+        encode_closing_tag(buffer, 0)
+    }
+
+    /// Decode a request
+    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
+        let mut reader = Reader::new(data);
+        reader.open(0)?;
+        let mut notifications = Vec::new();
+        while !reader.at_close(0) {
+            notifications.push(decode_notification(&mut reader)?);
+        }
+        reader.close(0)?;
+
+        if !reader.rest().is_empty() {
+            return Err(EncodingError::InvalidFormat(
+                "Unexpected data after Audit Notification request".to_string(),
+            ));
+        }
+        Ok(Self { notifications })
+    }
+
+    /// Wrap the request in a confirmed or unconfirmed request APDU
+    pub fn to_apdu(&self, confirmed: bool, invoke_id: u8) -> EncodingResult<Apdu> {
+        let mut service_data = Vec::new();
+        self.encode(&mut service_data)?;
+        Ok(if confirmed {
+            Apdu::ConfirmedRequest {
+                segmented: false,
+                more_follows: false,
+                segmented_response_accepted: false,
+                max_segments: MaxSegments::Unspecified,
+                max_response_size: MaxApduSize::Up1476,
+                invoke_id,
+                sequence_number: None,
+                proposed_window_size: None,
+                service_choice: ConfirmedServiceChoice::ConfirmedAuditNotification,
+                service_data,
+            }
+        } else {
+            Apdu::UnconfirmedRequest {
+                service_choice: UnconfirmedServiceChoice::UnconfirmedAuditNotification,
+                service_data,
+            }
+        })
+    }
+}
+
+fn encode_timestamp(
+    buffer: &mut Vec<u8>,
+    timestamp: &BacnetDateTime,
+    tag_number: u8,
+) -> EncodingResult<()> {
+    encode_opening_tag(buffer, tag_number)?;
+    TimeStamp::DateTime(*timestamp).encode(buffer)?;
+    encode_closing_tag(buffer, tag_number)
+}
+
+fn encode_context_recipient(
+    buffer: &mut Vec<u8>,
+    recipient: &Recipient,
+    tag_number: u8,
+) -> EncodingResult<()> {
+    encode_opening_tag(buffer, tag_number)?;
+    encode_recipient(buffer, recipient)?;
+    encode_closing_tag(buffer, tag_number)
+}
+
+fn encode_optional_unsigned(
+    buffer: &mut Vec<u8>,
+    value: Option<u32>,
+    tag_number: u8,
+) -> EncodingResult<()> {
+    if let Some(value) = value {
+        buffer.extend_from_slice(&encode_context_unsigned(value, tag_number)?);
+    }
+    Ok(())
+}
+
+fn encode_optional_string(
+    buffer: &mut Vec<u8>,
+    value: &Option<String>,
+    tag_number: u8,
+) -> EncodingResult<()> {
+    match value {
+        Some(value) => encode_context_value(
+            buffer,
+            &PropertyValue::CharacterString(value.clone()),
+            tag_number,
+        ),
+        None => Ok(()),
+    }
+}
+
+fn encode_notification(
+    buffer: &mut Vec<u8>,
+    notification: &AuditNotification,
+) -> EncodingResult<()> {
+    if let Some(timestamp) = &notification.source_timestamp {
+        encode_timestamp(buffer, timestamp, 0)?;
+    }
+    if let Some(timestamp) = &notification.target_timestamp {
+        encode_timestamp(buffer, timestamp, 1)?;
+    }
+    encode_context_recipient(buffer, &notification.source_device, 2)?;
+    if let Some(object) = &notification.source_object {
+        encode_identifier(buffer, object, 3)?;
+    }
+    buffer.extend_from_slice(&encode_context_enumerated(
+        notification.operation as u32,
+        4,
+    )?);
+    encode_optional_string(buffer, &notification.source_comment, 5)?;
+    encode_optional_string(buffer, &notification.target_comment, 6)?;
+    encode_optional_unsigned(buffer, notification.invoke_id.map(u32::from), 7)?;
+    encode_optional_unsigned(buffer, notification.source_user_id.map(u32::from), 8)?;
+    encode_optional_unsigned(buffer, notification.source_user_role.map(u32::from), 9)?;
+    encode_context_recipient(buffer, &notification.target_device, 10)?;
+    if let Some(object) = &notification.target_object {
+        encode_identifier(buffer, object, 11)?;
+    }
+    if let Some(property) = notification.target_property {
+        encode_opening_tag(buffer, 12)?;
+        buffer.extend_from_slice(&encode_context_enumerated(u32::from(property), 0)?);
+        encode_closing_tag(buffer, 12)?;
+    }
+    encode_optional_unsigned(buffer, notification.target_priority.map(u32::from), 13)?;
+    if let Some(value) = &notification.target_value {
+        encode_abstract_value(buffer, value, 14)?;
+    }
+    if let Some(value) = &notification.current_value {
+        encode_abstract_value(buffer, value, 15)?;
+    }
+    if let Some((error_class, error_code)) = notification.result {
+        encode_opening_tag(buffer, 16)?;
+        encode_enumerated(buffer, error_class)?;
+        encode_enumerated(buffer, error_code)?;
+        encode_closing_tag(buffer, 16)?;
+    }
+    Ok(())
+}
+
+fn decode_timestamp(reader: &mut Reader, tag_number: u8) -> EncodingResult<BacnetDateTime> {
+    reader.open(tag_number)?;
+    let TimeStamp::DateTime(timestamp) = reader.time_stamp()? else {
+        return Err(EncodingError::InvalidFormat(
+            "Audit timestamps must be date-times".to_string(),
+        ));
+    };
+    reader.close(tag_number)?;
+    Ok(timestamp)
+}
+
+fn decode_context_recipient(reader: &mut Reader, tag_number: u8) -> EncodingResult<Recipient> {
+    reader.open(tag_number)?;
+    let recipient = decode_recipient(reader)?;
+    reader.close(tag_number)?;
+    Ok(recipient)
+}
+
+fn narrow<T: TryFrom<u32>>(value: Option<u32>) -> EncodingResult<Option<T>> {
+    value
+        .map(|value| T::try_from(value).map_err(|_| EncodingError::ValueOutOfRange))
+        .transpose()
+}
+
+fn decode_notification(reader: &mut Reader) -> EncodingResult<AuditNotification> {
+    let source_timestamp = reader.optional(0, decode_timestamp)?;
+    let target_timestamp = reader.optional(1, decode_timestamp)?;
+    let source_device = decode_context_recipient(reader, 2)?;
+    let source_object = reader.optional(3, Reader::identifier)?;
+    let operation = AuditOperation::try_from(reader.enumerated(4)?)
+        .map_err(|_| EncodingError::ValueOutOfRange)?;
+    let source_comment = reader.optional(5, Reader::string)?;
+    let target_comment = reader.optional(6, Reader::string)?;
+    let invoke_id = narrow(reader.optional(7, Reader::unsigned)?)?;
+    let source_user_id = narrow(reader.optional(8, Reader::unsigned)?)?;
+    let source_user_role = narrow(reader.optional(9, Reader::unsigned)?)?;
+    let target_device = decode_context_recipient(reader, 10)?;
+    let target_object = reader.optional(11, Reader::identifier)?;
+    let target_property = reader.optional(12, |reader, tag_number| {
+        reader.open(tag_number)?;
+        let property = PropertyIdentifier::try_from(reader.enumerated(0)?)
+            .map_err(|_| EncodingError::ValueOutOfRange)?;
+        // Audit notifications keep no array index
+        reader.optional(1, Reader::unsigned)?;
+        reader.close(tag_number)?;
+        Ok(property)
+    })?;
+    let target_priority = narrow(reader.optional(13, Reader::unsigned)?)?;
+    let target_value = reader.optional(14, Reader::abstract_value)?;
+    let current_value = reader.optional(15, Reader::abstract_value)?;
+    let result = if reader.at_open(16) {
+        reader.open(16)?;
+        let error_class = reader.advance(decode_enumerated(reader.rest())?);
+        let error_code = reader.advance(decode_enumerated(reader.rest())?);
+        reader.close(16)?;
+        Some((error_class, error_code))
+    } else {
+        None
+    };
+
+    Ok(AuditNotification {
+        source_timestamp,
+        target_timestamp,
+        source_device,
+        source_object,
+        operation,
+        source_comment,
+        target_comment,
+        invoke_id,
+        source_user_id,
+        source_user_role,
+        target_device,
+        target_object,
+        target_property,
+        target_priority,
+        target_value,
+        current_value,
+        result,
+    })
+}
+
+/// Which records an audit log query returns by their result
+/// (BACnetSuccessFilter)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[repr(u32)]
+pub enum SuccessFilter {
+    /// Every record
+    #[default]
+    All = 0,
+    /// Records of operations that succeeded
+    SuccessesOnly = 1,
+    /// Records of operations that failed
+    FailuresOnly = 2,
+}
+
+impl TryFrom<u32> for SuccessFilter {
+    type Error = EncodingError;
+
+    fn try_from(value: u32) -> EncodingResult<Self> {
+        match value {
+            0 => Ok(SuccessFilter::All),
+            1 => Ok(SuccessFilter::SuccessesOnly),
+            2 => Ok(SuccessFilter::FailuresOnly),
+            _ => Err(EncodingError::ValueOutOfRange),
+        }
+    }
+}
+
+impl SuccessFilter {
+    /// Whether a record with `result` passes the filter
+    pub fn matches(&self, result: Option<(u32, u32)>) -> bool {
+        match self {
+            SuccessFilter::All => true,
+            SuccessFilter::SuccessesOnly => result.is_none(),
+            SuccessFilter::FailuresOnly => result.is_some(),
+        }
+    }
+}
+
+/// Query for the records of operations performed on a device
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditQueryByTarget {
+    /// Device the operations were performed on
+    pub device_identifier: ObjectIdentifier,
+    /// Network number and MAC address of that device (optional)
+    pub device_address: Option<(u16, Vec<u8>)>,
+    /// Object the operations were performed on (optional)
+    pub object_identifier: Option<ObjectIdentifier>,
+    /// Property the operations were performed on (optional)
+    pub property_identifier: Option<PropertyIdentifier>,
+    /// Array index of that property (optional); audit records keep no array
+    /// index, so it does not narrow the query
+    pub array_index: Option<u32>,
+    /// Priority of the writes (optional)
+    pub priority: Option<u8>,
+    /// Operations returned, indexed by BACnetAuditOperation (optional)
+    pub operations: Option<[bool; 16]>,
+    /// Records returned by their result
+    pub result_filter: SuccessFilter,
+}
+
+impl AuditQueryByTarget {
+    /// Create a query for every operation performed on a device
+    pub fn new(device_identifier: ObjectIdentifier) -> Self {
+        Self {
+            device_identifier,
+            device_address: None,
+            object_identifier: None,
+            property_identifier: None,
+            array_index: None,
+            priority: None,
+            operations: None,
+            result_filter: SuccessFilter::All,
+        }
+    }
+}
+
+/// Query for the records of operations a device requested
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditQueryBySource {
+    /// Device that requested the operations
+    pub device_identifier: ObjectIdentifier,
+    /// Network number and MAC address of that device (optional)
+    pub device_address: Option<(u16, Vec<u8>)>,
+    /// Object in that device that requested the operations (optional)
+    pub object_identifier: Option<ObjectIdentifier>,
+    /// Operations returned, indexed by BACnetAuditOperation (optional)
+    pub operations: Option<[bool; 16]>,
+    /// Records returned by their result
+    pub result_filter: SuccessFilter,
+}
+
+impl AuditQueryBySource {
+    /// Create a query for every operation a device requested
+    pub fn new(device_identifier: ObjectIdentifier) -> Self {
+        Self {
+            device_identifier,
+            device_address: None,
+            object_identifier: None,
+            operations: None,
+            result_filter: SuccessFilter::All,
+        }
+    }
+}
+
+/// Selection criteria of an audit log query (BACnetAuditLogQueryParameters)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuditLogQueryParameters {
+    /// Operations performed on a device
+    ByTarget(AuditQueryByTarget),
+    /// Operations requested by a device
+    BySource(AuditQueryBySource),
+}
+
+impl AuditLogQueryParameters {
+    /// Whether a notification meets the criteria
+    pub fn matches(&self, notification: &AuditNotification) -> bool {
+        let operation = |operations: &Option<[bool; 16]>| {
+            operations.is_none_or(|operations| operations[notification.operation as usize])
+        };
+        match self {
+            AuditLogQueryParameters::ByTarget(query) => {
+                is_device(
+                    &notification.target_device,
+                    &query.device_identifier,
+                    &query.device_address,
+                ) && query
+                    .object_identifier
+                    .is_none_or(|object| notification.target_object == Some(object))
+                    && query
+                        .property_identifier
+                        .is_none_or(|property| notification.target_property == Some(property))
+                    && query
+                        .priority
+                        .is_none_or(|priority| notification.target_priority == Some(priority))
+                    && operation(&query.operations)
+                    && query.result_filter.matches(notification.result)
+            }
+            AuditLogQueryParameters::BySource(query) => {
+                is_device(
+                    &notification.source_device,
+                    &query.device_identifier,
+                    &query.device_address,
+                ) && query
+                    .object_identifier
+                    .is_none_or(|object| notification.source_object == Some(object))
+                    && operation(&query.operations)
+                    && query.result_filter.matches(notification.result)
+            }
+        }
+    }
+
+    /// Whether a log record holds a notification that meets the criteria
+    ///
+    /// Log status and time change records never match.
+    pub fn matches_record(&self, record: &AuditLogRecord) -> bool {
+        match &record.log_datum {
+            AuditLogDatum::Notification(notification) => self.matches(notification),
+            _ => false,
+        }
+    }
+
+    /// Encode the parameters
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        match self {
+            AuditLogQueryParameters::ByTarget(query) => {
+                encode_opening_tag(buffer, 0)?;
+                encode_identifier(buffer, &query.device_identifier, 0)?;
+                encode_address(buffer, &query.device_address, 1)?;
+                if let Some(object) = &query.object_identifier {
+                    encode_identifier(buffer, object, 2)?;
+                }
+                if let Some(property) = query.property_identifier {
+                    buffer.extend_from_slice(&encode_context_enumerated(u32::from(property), 3)?);
+                }
+                if let Some(index) = query.array_index {
+                    buffer.extend_from_slice(&encode_context_unsigned(index, 4)?);
+                }
+                if let Some(priority) = query.priority {
+                    buffer.extend_from_slice(&encode_context_unsigned(priority as u32, 5)?);
+                }
+                if let Some(operations) = &query.operations {
+                    encode_bits(buffer, operations, 6)?;
+                }
+                buffer
+                    .extend_from_slice(&encode_context_enumerated(query.result_filter as u32, 7)?);
+                encode_closing_tag(buffer, 0)
+            }
+            AuditLogQueryParameters::BySource(query) => {
+                encode_opening_tag(buffer, 1)?;
+                encode_identifier(buffer, &query.device_identifier, 0)?;
+                encode_address(buffer, &query.device_address, 1)?;
+                if let Some(object) = &query.object_identifier {
+                    encode_identifier(buffer, object, 2)?;
+                }
+                if let Some(operations) = &query.operations {
+                    encode_bits(buffer, operations, 3)?;
+                }
+                buffer
+                    .extend_from_slice(&encode_context_enumerated(query.result_filter as u32, 4)?);
+                encode_closing_tag(buffer, 1)
+            }
+        }
+    }
+
+    fn decode(reader: &mut Reader) -> EncodingResult<Self> {
+        if reader.at_open(0) {
+            reader.open(0)?;
+            let query = AuditQueryByTarget {
+                device_identifier: reader.identifier(0)?,
+                device_address: decode_address(reader, 1)?,
+                object_identifier: reader.optional(2, Reader::identifier)?,
+                property_identifier: reader
+                    .optional(3, Reader::enumerated)?
+                    .map(|property| {
+                        PropertyIdentifier::try_from(property)
+                            .map_err(|_| EncodingError::ValueOutOfRange)
+                    })
+                    .transpose()?,
+                array_index: reader.optional(4, Reader::unsigned)?,
+                priority: reader
+                    .optional(5, Reader::unsigned)?
+                    .map(priority)
+                    .transpose()?,
+                operations: reader.optional(6, Reader::bits)?.map(|bits| flags(&bits)),
+                result_filter: SuccessFilter::try_from(reader.enumerated(7)?)?,
+            };
+            reader.close(0)?;
+            Ok(AuditLogQueryParameters::ByTarget(query))
+        } else {
+            reader.open(1)?;
+            let query = AuditQueryBySource {
+                device_identifier: reader.identifier(0)?,
+                device_address: decode_address(reader, 1)?,
+                object_identifier: reader.optional(2, Reader::identifier)?,
+                operations: reader.optional(3, Reader::bits)?.map(|bits| flags(&bits)),
+                result_filter: SuccessFilter::try_from(reader.enumerated(4)?)?,
+            };
+            reader.close(1)?;
+            Ok(AuditLogQueryParameters::BySource(query))
+        }
+    }
+}
+
+fn is_device(
+    recipient: &Recipient,
+    device_identifier: &ObjectIdentifier,
+    device_address: &Option<(u16, Vec<u8>)>,
+) -> bool {
+    match recipient {
+        Recipient::Device(device) => device == device_identifier,
+        Recipient::Address {
+            network,
+            mac_address,
+        } => device_address
+            .as_ref()
+            .is_some_and(|(n, mac)| n == network && mac == mac_address),
+    }
+}
+
+fn priority(value: u32) -> EncodingResult<u8> {
+    match value {
+        1..=16 => Ok(value as u8),
+        _ => Err(EncodingError::ValueOutOfRange),
+    }
+}
+
+fn flags(bits: &[bool]) -> [bool; 16] {
+    let mut flags = [false; 16];
+    for (flag, bit) in flags.iter_mut().zip(bits) {
+        *flag = *bit;
+    }
+    flags
+}
+
+fn encode_address(
+    buffer: &mut Vec<u8>,
+    address: &Option<(u16, Vec<u8>)>,
+    tag_number: u8,
+) -> EncodingResult<()> {
+    if let Some((network, mac_address)) = address {
+        encode_opening_tag(buffer, tag_number)?;
+        encode_unsigned(buffer, u32::from(*network))?;
+        encode_octet_string(buffer, mac_address)?;
+        encode_closing_tag(buffer, tag_number)?;
+    }
+    Ok(())
+}
+
+fn decode_address(reader: &mut Reader, tag_number: u8) -> EncodingResult<Option<(u16, Vec<u8>)>> {
+    if !reader.at_open(tag_number) {
+        return Ok(None);
+    }
+    reader.open(tag_number)?;
+    let network = u16::try_from(reader.advance(decode_unsigned(reader.rest())?))
+        .map_err(|_| EncodingError::ValueOutOfRange)?;
+    let mac_address = reader.advance(decode_octet_string(reader.rest())?);
+    reader.close(tag_number)?;
+    Ok(Some((network, mac_address)))
+}
+
+/// Audit Log Query request (confirmed service)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditLogQueryRequest {
+    /// Audit Log object to query
+    pub audit_log: ObjectIdentifier,
+    /// Records to return
+    pub query_parameters: AuditLogQueryParameters,
+    /// Sequence number to start at (optional); the oldest record without one
+    pub start_at_sequence_number: Option<u32>,
+    /// Largest number of records to return
+    pub requested_count: u16,
+}
+
+impl AuditLogQueryRequest {
+    /// Create a new request starting at the oldest record
+    pub fn new(
+        audit_log: ObjectIdentifier,
+        query_parameters: AuditLogQueryParameters,
+        requested_count: u16,
+    ) -> Self {
+        Self {
+            audit_log,
+            query_parameters,
+            start_at_sequence_number: None,
+            requested_count,
+        }
+    }
+
+    /// Start the query at a sequence number
+    pub fn starting_at(mut self, sequence_number: u32) -> Self {
+        self.start_at_sequence_number = Some(sequence_number);
+        self
+    }
+
+    /// Encode the request
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        encode_identifier(buffer, &self.audit_log, 0)?;
+        encode_opening_tag(buffer, 1)?;
+        self.query_parameters.encode(buffer)?;
+        encode_closing_tag(buffer, 1)?;
+        if let Some(sequence_number) = self.start_at_sequence_number {
+            buffer.extend_from_slice(&encode_context_unsigned(sequence_number, 2)?);
+        }
+        buffer.extend_from_slice(&encode_context_unsigned(self.requested_count as u32, 3)?);
+        Ok(())
+    }
+
+    /// Decode a request
+    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
+        let mut reader = Reader::new(data);
+        let audit_log = reader.identifier(0)?;
+        reader.open(1)?;
+        let query_parameters = AuditLogQueryParameters::decode(&mut reader)?;
+        reader.close(1)?;
+        let start_at_sequence_number = reader.optional(2, Reader::unsigned)?;
+        let requested_count =
+            u16::try_from(reader.unsigned(3)?).map_err(|_| EncodingError::ValueOutOfRange)?;
+
+        if !reader.rest().is_empty() {
+            return Err(EncodingError::InvalidFormat(
+                "Unexpected data after Audit Log Query request".to_string(),
+            ));
+        }
+        Ok(Self {
+            audit_log,
+            query_parameters,
+            start_at_sequence_number,
+            requested_count,
+        })
+    }
+}
+
+/// A record returned by an audit log query
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditLogQueryResult {
+    /// Sequence number of the record in the log
+    pub sequence_number: u32,
+    /// The record
+    pub log_record: AuditLogRecord,
+}
+
+/// Audit Log Query acknowledgement
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditLogQueryAck {
+    /// Audit Log object queried
+    pub audit_log: ObjectIdentifier,
+    /// Matching records, oldest first
+    pub records: Vec<AuditLogQueryResult>,
+    /// Whether no later record matches
+    pub no_more_items: bool,
+}
+
+impl AuditLogQueryAck {
+    /// Encode the acknowledgement
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        encode_identifier(buffer, &self.audit_log, 0)?;
+        encode_opening_tag(buffer, 1)?;
+        for result in &self.records {
+            buffer.extend_from_slice(&encode_context_unsigned(result.sequence_number, 0)?);
+            encode_opening_tag(buffer, 1)?;
+            encode_record(buffer, &result.log_record)?;
+            encode_closing_tag(buffer, 1)?;
+        }
+        encode_closing_tag(buffer, 1)?;
+        buffer.extend_from_slice(&encode_context_boolean(self.no_more_items, 2)?);
+        Ok(())
+    }
+
+    /// Decode an acknowledgement
+    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
+        let mut reader = Reader::new(data);
+        let audit_log = reader.identifier(0)?;
+        reader.open(1)?;
+        let mut records = Vec::new();
+        while !reader.at_close(1) {
+            let sequence_number = reader.unsigned(0)?;
+            reader.open(1)?;
+            let log_record = decode_record(&mut reader)?;
+            reader.close(1)?;
+            records.push(AuditLogQueryResult {
+                sequence_number,
+                log_record,
+            });
+        }
+        reader.close(1)?;
+        let no_more_items = reader.boolean(2)?;
+
+        if !reader.rest().is_empty() {
+            return Err(EncodingError::InvalidFormat(
+                "Unexpected data after Audit Log Query acknowledgement".to_string(),
+            ));
+        }
+        Ok(Self {
+            audit_log,
+            records,
+            no_more_items,
+        })
+    }
+}
+
+fn encode_record(buffer: &mut Vec<u8>, record: &AuditLogRecord) -> EncodingResult<()> {
+    encode_date_time(buffer, &record.timestamp, 0)?;
+    encode_opening_tag(buffer, 1)?;
+    match &record.log_datum {
+        AuditLogDatum::LogStatus {
+            log_disabled,
+            buffer_purged,
+            log_interrupted,
+        } => encode_bits(
+            buffer,
+            &[*log_disabled, *buffer_purged, *log_interrupted],
+            0,
+        )?,
+        AuditLogDatum::Notification(notification) => {
+            encode_opening_tag(buffer, 1)?;
+            encode_notification(buffer, notification)?;
+            encode_closing_tag(buffer, 1)?;
+        }
+        AuditLogDatum::TimeChange(seconds) => {
+            buffer.extend_from_slice(&encode_context_real(*seconds, 2)?)
+        }
+    }
+    encode_closing_tag(buffer, 1)
+}
+
+fn decode_record(reader: &mut Reader) -> EncodingResult<AuditLogRecord> {
+    let timestamp = reader.date_time(0)?;
+    reader.open(1)?;
+    let log_datum = if reader.is_context(0) {
+        let bits = reader.bits(0)?;
+        let bit = |index: usize| bits.get(index).copied().unwrap_or(false);
+        AuditLogDatum::LogStatus {
+            log_disabled: bit(0),
+            buffer_purged: bit(1),
+            log_interrupted: bit(2),
+        }
+    } else if reader.at_open(1) {
+        reader.open(1)?;
+        let notification = decode_notification(reader)?;
+        reader.close(1)?;
+        AuditLogDatum::Notification(Box::new(notification))
+    } else {
+        AuditLogDatum::TimeChange(reader.real(2)?)
+    };
+    reader.close(1)?;
+    Ok(AuditLogRecord {
+        timestamp,
+        log_datum,
+    })
+}
+
+/// Store the notifications of a Confirmed Audit Notification request in the
+/// database's Audit Logs
+///
+/// Returns a SimpleAck, or a Reject PDU if the request cannot be decoded.
+/// Logs with logging disabled discard the notifications.
+#[cfg(feature = "std")]
+pub fn handle_audit_notification(
+    database: &ObjectDatabase,
+    invoke_id: u8,
+    service_data: &[u8],
+) -> Apdu {
+    let request = match AuditNotificationRequest::decode(service_data) {
+        Ok(request) => request,
+        Err(error) => return Apdu::reject(invoke_id, RejectReason::for_decode_error(&error)),
+    };
+    log_notifications(database, &request);
+    Apdu::SimpleAck {
+        invoke_id,
+        service_choice: ConfirmedServiceChoice::ConfirmedAuditNotification as u8,
+    }
+}
+
+/// Store the notifications of an Unconfirmed Audit Notification request in
+/// the database's Audit Logs
+///
+/// Returns the number of logs that stored them.
+#[cfg(feature = "std")]
+pub fn handle_unconfirmed_audit_notification(
+    database: &ObjectDatabase,
+    service_data: &[u8],
+) -> EncodingResult<usize> {
+    let request = AuditNotificationRequest::decode(service_data)?;
+    Ok(log_notifications(database, &request))
+}
+
+#[cfg(feature = "std")]
+fn log_notifications(database: &ObjectDatabase, request: &AuditNotificationRequest) -> usize {
+    let timestamp = current_date_time().unwrap_or_else(BacnetDateTime::unspecified);
+    database.log_audit_notifications(timestamp, &request.notifications)
+}
+
+/// Run an Audit Log Query against an Audit Log in an object database
+#[cfg(feature = "std")]
+pub fn audit_log_query(
+    database: &ObjectDatabase,
+    request: &AuditLogQueryRequest,
+) -> Result<AuditLogQueryAck, PropertyAccessError> {
+    let (records, no_more_items) = database.read_audit_log(request.audit_log, |log| {
+        Ok::<_, PropertyAccessError>(log.query(
+            request.start_at_sequence_number,
+            request.requested_count as usize,
+            |record| request.query_parameters.matches_record(record),
+        ))
+    })?;
+    Ok(AuditLogQueryAck {
+        audit_log: request.audit_log,
+        records: records
+            .into_iter()
+            .map(|(sequence_number, log_record)| AuditLogQueryResult {
+                sequence_number,
+                log_record,
+            })
+            .collect(),
+        no_more_items,
+    })
+}
+
+/// Answer an Audit Log Query request
+///
+/// Returns a ComplexAck with the matching records, an Error PDU if the
+/// object is not an Audit Log, or a Reject PDU if the request cannot be
+/// decoded.
+#[cfg(feature = "std")]
+pub fn handle_audit_log_query(
+    database: &ObjectDatabase,
+    invoke_id: u8,
+    service_data: &[u8],
+) -> Apdu {
+    let service_choice = ConfirmedServiceChoice::AuditLogQuery as u8;
+    let request = match AuditLogQueryRequest::decode(service_data) {
+        Ok(request) => request,
+        Err(error) => return Apdu::reject(invoke_id, RejectReason::for_decode_error(&error)),
+    };
+
+    match audit_log_query(database, &request) {
+        Ok(ack) => {
+            let mut service_data = Vec::new();
+            if let Err(error) = ack.encode(&mut service_data) {
+                return Apdu::abort(true, invoke_id, AbortReason::for_encode_error(&error));
+            }
+            Apdu::ComplexAck {
+                segmented: false,
+                more_follows: false,
+                invoke_id,
+                sequence_number: None,
+                proposed_window_size: None,
+                service_choice,
+                service_data,
+            }
+        }
+        Err(error) => Apdu::error(invoke_id, service_choice, error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::{AuditLog, Date, Device, ObjectType, Time};
+
+    fn at(second: u8) -> BacnetDateTime {
+        BacnetDateTime::new(
+            Date {
+                year: 2024,
+                month: 9,
+                day: 2,
+                weekday: 1,
+            },
+            Time {
+                hour: 8,
+                minute: 0,
+                second,
+                hundredths: 0,
+            },
+        )
+    }
+
+    fn write_by(source: u32, priority: u8) -> AuditNotification {
+        let mut notification = AuditNotification::new(
+            AuditOperation::Write,
+            Recipient::Device(ObjectIdentifier::new(ObjectType::Device, source)),
+            Recipient::Device(ObjectIdentifier::new(ObjectType::Device, 1)),
+        );
+        notification.target_object = Some(ObjectIdentifier::new(ObjectType::AnalogOutput, 1));
+        notification.target_property = Some(PropertyIdentifier::PresentValue);
+        notification.target_priority = Some(priority);
+        notification
+    }
+
+    #[test]
+    fn test_notification_codec() {
+        let mut failed = write_by(100, 8);
+        failed.source_timestamp = Some(at(1));
+        failed.source_comment = Some("Override".to_string());
+        failed.invoke_id = Some(3);
+        failed.target_value = Some(PropertyValue::Real(21.5));
+        failed.result = Some((2, 40));
+        let mut from_address = AuditNotification::new(
+            AuditOperation::DeviceReset,
+            Recipient::Address {
+                network: 5,
+                mac_address: vec![0x0A],
+            },
+            Recipient::Device(ObjectIdentifier::new(ObjectType::Device, 1)),
+        );
+        from_address.target_comment = Some("Warm start".to_string());
+
+        let request = AuditNotificationRequest::new(vec![failed, from_address]);
+        let mut buffer = Vec::new();
+        request.encode(&mut buffer).unwrap();
+        assert_eq!(AuditNotificationRequest::decode(&buffer).unwrap(), request);
+        // The result is an extended-tag-number constructed field
+        assert!(buffer
+            .windows(6)
+            .any(|window| window == [0xFE, 0x10, 0x91, 0x02, 0x91, 0x28]));
+    }
+
+    #[test]
+    fn test_query_codec() {
+        let mut by_target = AuditQueryByTarget::new(ObjectIdentifier::new(ObjectType::Device, 1));
+        by_target.property_identifier = Some(PropertyIdentifier::PresentValue);
+        by_target.priority = Some(8);
+        by_target.result_filter = SuccessFilter::FailuresOnly;
+        let request = AuditLogQueryRequest::new(
+            ObjectIdentifier::new(ObjectType::AuditLog, 1),
+            AuditLogQueryParameters::ByTarget(by_target),
+            10,
+        )
+        .starting_at(4);
+        let mut buffer = Vec::new();
+        request.encode(&mut buffer).unwrap();
+        assert_eq!(AuditLogQueryRequest::decode(&buffer).unwrap(), request);
+
+        let mut by_source = AuditQueryBySource::new(ObjectIdentifier::new(ObjectType::Device, 9));
+        by_source.device_address = Some((0, vec![192, 168, 1, 9, 0xBA, 0xC0]));
+        let mut operations = [false; 16];
+        operations[AuditOperation::Write as usize] = true;
+        by_source.operations = Some(operations);
+        let request = AuditLogQueryRequest::new(
+            ObjectIdentifier::new(ObjectType::AuditLog, 1),
+            AuditLogQueryParameters::BySource(by_source),
+            5,
+        );
+        let mut buffer = Vec::new();
+        request.encode(&mut buffer).unwrap();
+        assert_eq!(AuditLogQueryRequest::decode(&buffer).unwrap(), request);
+    }
+
+    #[test]
+    fn test_notify_and_query_database() {
+        let database = ObjectDatabase::new(Device::new(1, "Supervisor".to_string()));
+        let mut log = AuditLog::new(1, "Audit Trail".to_string(), 50);
+        log.set_log_enable(true, Some(at(0)));
+        database.add_object(Box::new(log)).unwrap();
+
+        let mut failed = write_by(100, 8);
+        failed.result = Some((2, 40));
+        let mut service_data = Vec::new();
+        AuditNotificationRequest::new(vec![write_by(100, 8), write_by(200, 9), failed])
+            .encode(&mut service_data)
+            .unwrap();
+        assert!(matches!(
+            handle_audit_notification(&database, 4, &service_data),
+            Apdu::SimpleAck {
+                invoke_id: 4,
+                service_choice: 32,
+            }
+        ));
+
+        let mut by_source = AuditQueryBySource::new(ObjectIdentifier::new(ObjectType::Device, 100));
+        by_source.result_filter = SuccessFilter::SuccessesOnly;
+        let audit_log = ObjectIdentifier::new(ObjectType::AuditLog, 1);
+        let mut service_data = Vec::new();
+        AuditLogQueryRequest::new(audit_log, AuditLogQueryParameters::BySource(by_source), 10)
+            .encode(&mut service_data)
+            .unwrap();
+        let Apdu::ComplexAck {
+            service_choice: 33,
+            service_data: ack,
+            ..
+        } = handle_audit_log_query(&database, 5, &service_data)
+        else {
+            panic!("expected a ComplexAck");
+        };
+        let ack = AuditLogQueryAck::decode(&ack).unwrap();
+        assert!(ack.no_more_items);
+        assert_eq!(ack.records.len(), 1);
+        // The enable record is number 1
+        assert_eq!(ack.records[0].sequence_number, 2);
+
+        let mut by_target = AuditQueryByTarget::new(ObjectIdentifier::new(ObjectType::Device, 1));
+        by_target.priority = Some(8);
+        let request =
+            AuditLogQueryRequest::new(audit_log, AuditLogQueryParameters::ByTarget(by_target), 1);
+        let ack = audit_log_query(&database, &request).unwrap();
+        assert_eq!(ack.records[0].sequence_number, 2);
+        assert!(!ack.no_more_items);
+
+        let request = AuditLogQueryRequest {
+            audit_log: ObjectIdentifier::new(ObjectType::AuditLog, 2),
+            ..request
+        };
+        assert!(audit_log_query(&database, &request).is_err());
+    }
+}
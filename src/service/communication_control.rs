@@ -0,0 +1,397 @@
+//! DeviceCommunicationControl Service (Clause 16.1)
+//!
+//! A client can silence a device for a while, for instance to keep it off a
+//! busy network during maintenance. Disable stops the device answering
+//! anything except DeviceCommunicationControl and ReinitializeDevice;
+//! disable-initiation lets it answer requests but stops it starting any
+//! exchange of its own other than an I-Am answering a Who-Is. Either lasts until an enable request
+//! or until the optional duration runs out.
+//!
+//! [`CommunicationControl`] holds the state and decides which APDUs may pass;
+//! the [`ApplicationLayerHandler`](crate::app::ApplicationLayerHandler)
+//! consults it for every APDU it receives and for every APDU the stack's
+//! clients and server loop send.
+
+use super::event_notification::{encode_context_value, Reader};
+use super::{ConfirmedServiceChoice, PropertyAccessError, RejectReason, UnconfirmedServiceChoice};
+use crate::app::Apdu;
+use crate::encoding::{
+    encode_context_enumerated, encode_context_unsigned, ApplicationTag, EncodingError,
+    Result as EncodingResult,
+};
+use crate::object::PropertyValue;
+use core::time::Duration;
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, string::ToString, vec::Vec};
+
+/// Longest password a request may carry, in characters
+pub const MAX_PASSWORD_LENGTH: usize = 20;
+
+/// Error class security (4), code password-failure (26)
+pub(crate) const PASSWORD_FAILURE: PropertyAccessError = PropertyAccessError {
+    error_class: 4,
+    error_code: 26,
+};
+
+/// Communication state requested by DeviceCommunicationControl
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[repr(u32)]
+pub enum EnableDisable {
+    /// Normal communication
+    #[default]
+    Enable = 0,
+    /// Answer only DeviceCommunicationControl and ReinitializeDevice
+    Disable = 1,
+    /// Answer requests but initiate nothing except I-Am
+    DisableInitiation = 2,
+}
+
+impl TryFrom<u32> for EnableDisable {
+    type Error = EncodingError;
+
+    fn try_from(value: u32) -> EncodingResult<Self> {
+        match value {
+            0 => Ok(Self::Enable),
+            1 => Ok(Self::Disable),
+            2 => Ok(Self::DisableInitiation),
+            _ => Err(EncodingError::ValueOutOfRange),
+        }
+    }
+}
+
+/// Device Communication Control request (confirmed service)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceCommunicationControlRequest {
+    /// Minutes until communication is enabled again (optional, indefinite if
+    /// absent)
+    pub time_duration: Option<u16>,
+    /// Requested state
+    pub enable_disable: EnableDisable,
+    /// Password (optional, at most 20 characters)
+    pub password: Option<String>,
+}
+
+impl DeviceCommunicationControlRequest {
+    /// Create a new request for `enable_disable`, indefinitely and without a
+    /// password
+    pub fn new(enable_disable: EnableDisable) -> Self {
+        Self {
+            time_duration: None,
+            enable_disable,
+            password: None,
+        }
+    }
+
+    /// Limit the request to `minutes`
+    pub fn with_duration(mut self, minutes: u16) -> Self {
+        self.time_duration = Some(minutes);
+        self
+    }
+
+    /// Add the device's password
+    pub fn with_password(mut self, password: impl Into<String>) -> Self {
+        self.password = Some(password.into());
+        self
+    }
+
+    /// Encode the request
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        if let Some(minutes) = self.time_duration {
+            buffer.extend_from_slice(&encode_context_unsigned(u32::from(minutes), 0)?);
+        }
+        buffer.extend_from_slice(&encode_context_enumerated(self.enable_disable as u32, 1)?);
+        if let Some(password) = &self.password {
+            encode_password(buffer, password, 2)?;
+        }
+        Ok(())
+    }
+
+    /// Decode a request
+    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
+        let mut reader = Reader::new(data);
+        let time_duration = reader
+            .optional(0, Reader::unsigned)?
+            .map(|minutes| u16::try_from(minutes).map_err(|_| EncodingError::ValueOutOfRange))
+            .transpose()?;
+        let enable_disable = EnableDisable::try_from(reader.enumerated(1)?)?;
+        let password = decode_password(&mut reader, 2)?;
+
+        if !reader.rest().is_empty() {
+            return Err(EncodingError::InvalidFormat(
+                "Unexpected data after Device Communication Control request".to_string(),
+            ));
+        }
+        Ok(Self {
+            time_duration,
+            enable_disable,
+            password,
+        })
+    }
+}
+
+/// Encode a password as a context-tagged character string
+pub(super) fn encode_password(
+    buffer: &mut Vec<u8>,
+    password: &str,
+    tag_number: u8,
+) -> EncodingResult<()> {
+    if password.is_empty() || password.chars().count() > MAX_PASSWORD_LENGTH {
+        return Err(EncodingError::ValueOutOfRange);
+    }
+    encode_context_value(
+        buffer,
+        &PropertyValue::CharacterString(password.into()),
+        tag_number,
+    )
+}
+
+/// Decode an optional context-tagged password of 1 to 20 characters
+pub(super) fn decode_password(
+    reader: &mut Reader<'_>,
+    tag_number: u8,
+) -> EncodingResult<Option<String>> {
+    if !reader.is_context(tag_number) {
+        return Ok(None);
+    }
+    match reader.value(tag_number, ApplicationTag::CharacterString)? {
+        PropertyValue::CharacterString(password)
+            if !password.is_empty() && password.chars().count() <= MAX_PASSWORD_LENGTH =>
+        {
+            Ok(Some(password))
+        }
+        _ => Err(EncodingError::ValueOutOfRange),
+    }
+}
+
+/// Check a request's password against the device's
+///
+/// A device without a password accepts any request.
+pub(crate) fn check_password(
+    device_password: Option<&str>,
+    password: Option<&str>,
+) -> Result<(), PropertyAccessError> {
+    match device_password {
+        Some(expected) if password != Some(expected) => Err(PASSWORD_FAILURE),
+        _ => Ok(()),
+    }
+}
+
+/// Communication state of a device, as set by DeviceCommunicationControl
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CommunicationControl {
+    state: EnableDisable,
+    remaining: Option<Duration>,
+}
+
+impl CommunicationControl {
+    /// Create a new control with communication enabled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Current state
+    pub fn state(&self) -> EnableDisable {
+        self.state
+    }
+
+    /// Time left before communication is enabled again, if the current
+    /// state is timed
+    pub fn remaining(&self) -> Option<Duration> {
+        self.remaining
+    }
+
+    /// Apply a request after checking its password against
+    /// `device_password`
+    pub fn apply(
+        &mut self,
+        request: &DeviceCommunicationControlRequest,
+        device_password: Option<&str>,
+    ) -> Result<(), PropertyAccessError> {
+        check_password(device_password, request.password.as_deref())?;
+        self.state = request.enable_disable;
+        self.remaining = match request.enable_disable {
+            EnableDisable::Enable => None,
+            _ => request
+                .time_duration
+                .map(|minutes| Duration::from_secs(u64::from(minutes) * 60)),
+        };
+        Ok(())
+    }
+
+    /// Count down a timed state by `elapsed`, enabling communication once
+    /// the duration has run out
+    pub fn advance_time(&mut self, elapsed: Duration) {
+        if let Some(remaining) = self.remaining {
+            match remaining.checked_sub(elapsed) {
+                Some(remaining) if !remaining.is_zero() => self.remaining = Some(remaining),
+                _ => *self = Self::new(),
+            }
+        }
+    }
+
+    /// Whether a received APDU should be processed
+    ///
+    /// While disabled only DeviceCommunicationControl and ReinitializeDevice
+    /// requests are.
+    pub fn accepts(&self, apdu: &Apdu) -> bool {
+        match self.state {
+            EnableDisable::Disable => matches!(
+                apdu,
+                Apdu::ConfirmedRequest {
+                    service_choice: ConfirmedServiceChoice::DeviceCommunicationControl
+                        | ConfirmedServiceChoice::ReinitializeDevice,
+                    ..
+                }
+            ),
+            _ => true,
+        }
+    }
+
+    /// Whether the device may send an APDU
+    ///
+    /// Replies are always allowed, as requests that must not be answered are
+    /// never accepted. Requests are held back while disabled, and while
+    /// initiation alone is disabled all but an I-Am answering a Who-Is, which
+    /// `answering_who_is` marks.
+    pub fn may_send(&self, apdu: &Apdu, answering_who_is: bool) -> bool {
+        let is_request = matches!(
+            apdu,
+            Apdu::ConfirmedRequest { .. } | Apdu::UnconfirmedRequest { .. }
+        );
+        match self.state {
+            EnableDisable::Enable => true,
+            EnableDisable::Disable => !is_request,
+            EnableDisable::DisableInitiation => {
+                !is_request
+                    || (answering_who_is
+                        && matches!(
+                            apdu,
+                            Apdu::UnconfirmedRequest {
+                                service_choice: UnconfirmedServiceChoice::IAm,
+                                ..
+                            }
+                        ))
+            }
+        }
+    }
+}
+
+/// Answer a Device Communication Control request
+///
+/// Returns a SimpleAck once the state is changed, an Error PDU if the
+/// password is wrong, or a Reject PDU if the request cannot be decoded.
+pub fn handle_device_communication_control(
+    control: &mut CommunicationControl,
+    device_password: Option<&str>,
+    invoke_id: u8,
+    service_data: &[u8],
+) -> Apdu {
+    let service_choice = ConfirmedServiceChoice::DeviceCommunicationControl as u8;
+    let request = match DeviceCommunicationControlRequest::decode(service_data) {
+        Ok(request) => request,
+        Err(error) => return Apdu::reject(invoke_id, RejectReason::for_decode_error(&error)),
+    };
+
+    match control.apply(&request, device_password) {
+        Ok(()) => Apdu::SimpleAck {
+            invoke_id,
+            service_choice,
+        },
+        Err(error) => Apdu::error(invoke_id, service_choice, error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_codec() {
+        let request = DeviceCommunicationControlRequest::new(EnableDisable::DisableInitiation)
+            .with_duration(5)
+            .with_password("secret");
+        let mut buffer = Vec::new();
+        request.encode(&mut buffer).unwrap();
+        assert_eq!(
+            buffer,
+            [0x09, 0x05, 0x19, 0x02, 0x2D, 0x07, 0x00, b's', b'e', b'c', b'r', b'e', b't']
+        );
+        assert_eq!(
+            DeviceCommunicationControlRequest::decode(&buffer).unwrap(),
+            request
+        );
+
+        let request = DeviceCommunicationControlRequest::new(EnableDisable::Enable);
+        let mut buffer = Vec::new();
+        request.encode(&mut buffer).unwrap();
+        assert_eq!(buffer, [0x19, 0x00]);
+        assert_eq!(
+            DeviceCommunicationControlRequest::decode(&buffer).unwrap(),
+            request
+        );
+
+        let too_long = DeviceCommunicationControlRequest::new(EnableDisable::Disable)
+            .with_password("a".repeat(21));
+        assert!(too_long.encode(&mut Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_timed_disable_and_password() {
+        let mut control = CommunicationControl::new();
+        let request = DeviceCommunicationControlRequest::new(EnableDisable::Disable)
+            .with_duration(2)
+            .with_password("wrong");
+        assert_eq!(
+            control.apply(&request, Some("secret")),
+            Err(PASSWORD_FAILURE)
+        );
+        assert_eq!(control.state(), EnableDisable::Enable);
+
+        let request = request.with_password("secret");
+        control.apply(&request, Some("secret")).unwrap();
+        let read = Apdu::ConfirmedRequest {
+            segmented: false,
+            more_follows: false,
+            segmented_response_accepted: false,
+            max_segments: crate::app::MaxSegments::Unspecified,
+            max_response_size: crate::app::MaxApduSize::Up1476,
+            invoke_id: 1,
+            sequence_number: None,
+            proposed_window_size: None,
+            service_choice: ConfirmedServiceChoice::ReadProperty,
+            service_data: Vec::new(),
+        };
+        let i_am = Apdu::UnconfirmedRequest {
+            service_choice: UnconfirmedServiceChoice::IAm,
+            service_data: Vec::new(),
+        };
+        assert!(!control.accepts(&read));
+        assert!(!control.may_send(&i_am, true));
+
+        control.advance_time(Duration::from_secs(60));
+        assert_eq!(control.remaining(), Some(Duration::from_secs(60)));
+        control.advance_time(Duration::from_secs(60));
+        assert_eq!(control.state(), EnableDisable::Enable);
+        assert!(control.accepts(&read));
+
+        control
+            .apply(
+                &DeviceCommunicationControlRequest::new(EnableDisable::DisableInitiation),
+                None,
+            )
+            .unwrap();
+        assert!(control.accepts(&read));
+        // Only an I-Am answering a Who-Is goes out
+        assert!(control.may_send(&i_am, true));
+        assert!(!control.may_send(&i_am, false));
+        assert!(!control.may_send(&read, false));
+        assert!(control.may_send(
+            &Apdu::SimpleAck {
+                invoke_id: 1,
+                service_choice: 15,
+            },
+            false
+        ));
+    }
+}
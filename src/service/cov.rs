@@ -0,0 +1,860 @@
+//! SubscribeCOV Service and COV Reporting (Clauses 13.1 and 13.14)
+//!
+//! A client subscribes to changes of value of an object, asking for confirmed
+//! or unconfirmed notifications for a lifetime in seconds; a subscription
+//! with neither is a cancellation. The server keeps the subscriptions in a
+//! [`CovSubscriptionManager`], which ages and expires them, reports them as
+//! the device's Active_COV_Subscriptions and compares the monitored
+//! Present_Value and Status_Flags against their last reported values to
+//! decide when a notification is due. [`handle_subscribe_cov`] applies a
+//! request to the table kept by an
+//! [`ObjectDatabase`](crate::object::database::ObjectDatabase).
+
+use core::time::Duration;
+
+use super::{
+    BacnetPropertyValue, CovNotificationRequest, PropertyReference, SubscribeCovPropertyRequest,
+};
+use crate::encoding::{
+    decode_context_boolean, decode_context_object_id, decode_context_unsigned,
+    encode_context_boolean, encode_context_object_id, encode_context_unsigned, EncodingError,
+    Result as EncodingResult,
+};
+use crate::object::{ObjectIdentifier, ObjectType, PropertyIdentifier, PropertyValue};
+
+use super::{ConfirmedServiceChoice, UnconfirmedServiceChoice};
+use crate::app::{Apdu, MaxApduSize, MaxSegments};
+
+#[cfg(feature = "std")]
+use super::{PropertyAccessError, RejectReason};
+#[cfg(feature = "std")]
+use crate::object::database::ObjectDatabase;
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::ToString, vec, vec::Vec};
+
+/// Subscribe COV request (confirmed service)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubscribeCovRequest {
+    /// Subscriber process identifier
+    pub subscriber_process_identifier: u32,
+    /// Monitored object identifier
+    pub monitored_object_identifier: ObjectIdentifier,
+    /// Issue confirmed notifications
+    pub issue_confirmed_notifications: Option<bool>,
+    /// Lifetime (seconds, 0 = permanent)
+    pub lifetime: Option<u32>,
+}
+
+impl SubscribeCovRequest {
+    /// Create a new Subscribe COV request
+    ///
+    /// Without a confirmation preference or lifetime the request cancels the
+    /// subscription.
+    pub fn new(
+        subscriber_process_identifier: u32,
+        monitored_object_identifier: ObjectIdentifier,
+    ) -> Self {
+        Self {
+            subscriber_process_identifier,
+            monitored_object_identifier,
+            issue_confirmed_notifications: None,
+            lifetime: None,
+        }
+    }
+
+    /// Create a new Subscribe COV request with confirmation preference
+    pub fn with_confirmation(
+        subscriber_process_identifier: u32,
+        monitored_object_identifier: ObjectIdentifier,
+        issue_confirmed_notifications: bool,
+    ) -> Self {
+        Self {
+            subscriber_process_identifier,
+            monitored_object_identifier,
+            issue_confirmed_notifications: Some(issue_confirmed_notifications),
+            lifetime: None,
+        }
+    }
+
+    /// Create a new Subscribe COV request with lifetime
+    pub fn with_lifetime(
+        subscriber_process_identifier: u32,
+        monitored_object_identifier: ObjectIdentifier,
+        lifetime: u32,
+    ) -> Self {
+        Self {
+            subscriber_process_identifier,
+            monitored_object_identifier,
+            issue_confirmed_notifications: None,
+            lifetime: Some(lifetime),
+        }
+    }
+
+    /// Check if this request cancels a subscription
+    pub fn is_cancellation(&self) -> bool {
+        self.issue_confirmed_notifications.is_none() && self.lifetime.is_none()
+    }
+
+    /// Encode the Subscribe COV request
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        // Subscriber process identifier - context tag 0
+        buffer.extend_from_slice(&encode_context_unsigned(
+            self.subscriber_process_identifier,
+            0,
+        )?);
+
+        // Monitored object identifier - context tag 1
+        buffer.extend_from_slice(&encode_context_object_id(
+            u16::from(self.monitored_object_identifier.object_type),
+            self.monitored_object_identifier.instance,
+            1,
+        )?);
+
+        // Issue confirmed notifications - context tag 2 (optional)
+        if let Some(confirmed) = self.issue_confirmed_notifications {
+            buffer.extend_from_slice(&encode_context_boolean(confirmed, 2)?);
+        }
+
+        // Lifetime - context tag 3 (optional)
+        if let Some(lifetime) = self.lifetime {
+            buffer.extend_from_slice(&encode_context_unsigned(lifetime, 3)?);
+        }
+
+        Ok(())
+    }
+
+    /// Decode a Subscribe COV request
+    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
+        let (subscriber_process_identifier, mut pos) = decode_context_unsigned(data, 0)?;
+        let (monitored_object_identifier, consumed) = decode_context_identifier(&data[pos..], 1)?;
+        pos += consumed;
+
+        let mut request = Self::new(subscriber_process_identifier, monitored_object_identifier);
+        if let Ok((confirmed, consumed)) = decode_context_boolean(&data[pos..], 2) {
+            request.issue_confirmed_notifications = Some(confirmed);
+            pos += consumed;
+        }
+        if let Ok((lifetime, consumed)) = decode_context_unsigned(&data[pos..], 3) {
+            request.lifetime = Some(lifetime);
+            pos += consumed;
+        }
+
+        if pos != data.len() {
+            return Err(EncodingError::InvalidFormat(
+                "Unexpected data after Subscribe COV request".to_string(),
+            ));
+        }
+        Ok(request)
+    }
+}
+
+/// Decode a context tagged object identifier
+pub(super) fn decode_context_identifier(
+    data: &[u8],
+    tag: u8,
+) -> EncodingResult<(ObjectIdentifier, usize)> {
+    let ((object_type, instance), consumed) = decode_context_object_id(data, tag)?;
+    let object_type =
+        ObjectType::try_from(object_type).map_err(|_| EncodingError::ValueOutOfRange)?;
+    Ok((ObjectIdentifier::new(object_type, instance), consumed))
+}
+
+/// COV Subscription information
+#[derive(Debug, Clone)]
+pub struct CovSubscription {
+    /// Subscriber process identifier
+    pub subscriber_process_identifier: u32,
+    /// Subscriber device identifier
+    pub subscriber_device_identifier: ObjectIdentifier,
+    /// Monitored object identifier
+    pub monitored_object_identifier: ObjectIdentifier,
+    /// Monitored property (for COV Property subscriptions)
+    pub monitored_property: Option<PropertyReference>,
+    /// Issue confirmed notifications
+    pub issue_confirmed_notifications: bool,
+    /// Lifetime (seconds, 0 = permanent)
+    pub lifetime: u32,
+    /// Remaining time (seconds)
+    pub time_remaining: u32,
+    /// COV increment (for analog properties)
+    pub cov_increment: Option<f32>,
+    /// Values sent in the last notification; `None` until the first one
+    last_reported: Option<Vec<PropertyValue>>,
+}
+
+impl CovSubscription {
+    /// Create a new COV subscription
+    pub fn new(
+        subscriber_process_identifier: u32,
+        subscriber_device_identifier: ObjectIdentifier,
+        monitored_object_identifier: ObjectIdentifier,
+        lifetime: u32,
+    ) -> Self {
+        Self {
+            subscriber_process_identifier,
+            subscriber_device_identifier,
+            monitored_object_identifier,
+            monitored_property: None,
+            issue_confirmed_notifications: false,
+            lifetime,
+            time_remaining: lifetime,
+            cov_increment: None,
+            last_reported: None,
+        }
+    }
+
+    /// Create the subscription a Subscribe COV request asks for
+    ///
+    /// A missing lifetime subscribes indefinitely and a missing confirmation
+    /// preference asks for unconfirmed notifications.
+    pub fn from_request(
+        subscriber_device_identifier: ObjectIdentifier,
+        request: &SubscribeCovRequest,
+    ) -> Self {
+        let mut subscription = Self::new(
+            request.subscriber_process_identifier,
+            subscriber_device_identifier,
+            request.monitored_object_identifier,
+            request.lifetime.unwrap_or(0),
+        );
+        subscription.issue_confirmed_notifications =
+            request.issue_confirmed_notifications.unwrap_or(false);
+        subscription
+    }
+
+    /// Create the subscription a Subscribe COV Property request asks for
+    pub fn from_property_request(
+        subscriber_device_identifier: ObjectIdentifier,
+        request: &SubscribeCovPropertyRequest,
+    ) -> Self {
+        let mut subscription = Self::new(
+            request.subscriber_process_identifier,
+            subscriber_device_identifier,
+            request.monitored_object_identifier,
+            request.lifetime.unwrap_or(0),
+        );
+        subscription.issue_confirmed_notifications =
+            request.issue_confirmed_notifications.unwrap_or(false);
+        subscription.monitored_property = Some(request.monitored_property.clone());
+        subscription.cov_increment = request.cov_increment;
+        subscription
+    }
+
+    /// Check if this subscription is the one of a subscriber process for a
+    /// monitored object and property
+    fn is_for(
+        &self,
+        subscriber_device: ObjectIdentifier,
+        subscriber_process: u32,
+        monitored_object: ObjectIdentifier,
+        monitored_property: Option<&PropertyReference>,
+    ) -> bool {
+        self.subscriber_device_identifier == subscriber_device
+            && self.subscriber_process_identifier == subscriber_process
+            && self.monitored_object_identifier == monitored_object
+            && self.monitored_property.as_ref() == monitored_property
+    }
+
+    /// Check if subscription has expired
+    pub fn is_expired(&self) -> bool {
+        self.lifetime > 0 && self.time_remaining == 0
+    }
+
+    /// Update time remaining (should be called periodically)
+    pub fn update_time(&mut self, elapsed_seconds: u32) {
+        if self.lifetime > 0 {
+            self.time_remaining = self.time_remaining.saturating_sub(elapsed_seconds);
+        }
+    }
+
+    /// Represent the subscription as a BACnetCOVSubscription entry of the
+    /// Active_COV_Subscriptions property
+    ///
+    /// The entry is a list of the recipient process (subscriber device and
+    /// process identifier), the monitored property reference, the
+    /// confirmation flag, the time remaining and, if set, the COV increment.
+    pub fn to_property_value(&self) -> PropertyValue {
+        let mut reference = vec![PropertyValue::ObjectIdentifier(
+            self.monitored_object_identifier,
+        )];
+        match &self.monitored_property {
+            Some(property) => {
+                reference.push(PropertyValue::Enumerated(property.property_identifier));
+                if let Some(index) = property.property_array_index {
+                    reference.push(PropertyValue::UnsignedInteger(index));
+                }
+            }
+            None => reference.push(PropertyValue::Enumerated(u32::from(
+                PropertyIdentifier::PresentValue,
+            ))),
+        }
+
+        let mut items = vec![
+            PropertyValue::List(vec![
+                PropertyValue::ObjectIdentifier(self.subscriber_device_identifier),
+                PropertyValue::UnsignedInteger(self.subscriber_process_identifier),
+            ]),
+            PropertyValue::List(reference),
+            PropertyValue::Boolean(self.issue_confirmed_notifications),
+            PropertyValue::UnsignedInteger(self.time_remaining),
+        ];
+        if let Some(increment) = self.cov_increment {
+            items.push(PropertyValue::Real(increment));
+        }
+        PropertyValue::List(items)
+    }
+
+    /// Check the current values of the monitored object against the last
+    /// reported ones
+    ///
+    /// The monitored value must move by at least the COV increment, when
+    /// there is one, while any change of the other values is reported.
+    fn is_change(&self, values: &[PropertyValue], cov_increment: Option<f32>) -> bool {
+        let Some(last) = &self.last_reported else {
+            return true;
+        };
+        if last.len() != values.len() {
+            return true;
+        }
+        let (Some(last_present_value), Some(present_value)) = (last.first(), values.first()) else {
+            return false;
+        };
+        let present_value_changed = match (
+            numeric_value(last_present_value),
+            numeric_value(present_value),
+            cov_increment,
+        ) {
+            (Some(last), Some(current), Some(increment)) => {
+                (current - last).abs() >= f64::from(increment)
+            }
+            _ => last_present_value != present_value,
+        };
+        present_value_changed || last[1..] != values[1..]
+    }
+}
+
+fn numeric_value(value: &PropertyValue) -> Option<f64> {
+    match value {
+        PropertyValue::Real(value) => Some(f64::from(*value)),
+        PropertyValue::Double(value) => Some(*value),
+        PropertyValue::UnsignedInteger(value) => Some(f64::from(*value)),
+        PropertyValue::SignedInt(value) => Some(f64::from(*value)),
+        _ => None,
+    }
+}
+
+/// A COV notification due to a subscriber
+#[derive(Debug, Clone)]
+pub struct PendingCovNotification {
+    /// Device the notification is sent to
+    pub subscriber_device_identifier: ObjectIdentifier,
+    /// Send as a ConfirmedCOVNotification rather than an unconfirmed one
+    pub issue_confirmed_notifications: bool,
+    /// The notification to send
+    pub notification: CovNotificationRequest,
+}
+
+impl PendingCovNotification {
+    /// Build the COV notification APDU to send to the subscriber
+    ///
+    /// `invoke_id` is used only for a ConfirmedCOVNotification.
+    pub fn to_apdu(&self, invoke_id: u8) -> EncodingResult<Apdu> {
+        let mut service_data = Vec::new();
+        self.notification.encode(&mut service_data)?;
+        Ok(if self.issue_confirmed_notifications {
+            Apdu::ConfirmedRequest {
+                segmented: false,
+                more_follows: false,
+                segmented_response_accepted: false,
+                max_segments: MaxSegments::Unspecified,
+                max_response_size: MaxApduSize::Up1476,
+                invoke_id,
+                sequence_number: None,
+                proposed_window_size: None,
+                service_choice: ConfirmedServiceChoice::ConfirmedCOVNotification,
+                service_data,
+            }
+        } else {
+            Apdu::UnconfirmedRequest {
+                service_choice: UnconfirmedServiceChoice::UnconfirmedCOVNotification,
+                service_data,
+            }
+        })
+    }
+}
+
+/// COV Subscription manager
+#[derive(Debug, Default)]
+pub struct CovSubscriptionManager {
+    /// List of active subscriptions
+    subscriptions: Vec<CovSubscription>,
+    /// Time elapsed since the timers last dropped by a whole second
+    elapsed_remainder: Duration,
+}
+
+impl CovSubscriptionManager {
+    /// Create a new COV subscription manager
+    pub fn new() -> Self {
+        Self {
+            subscriptions: Vec::new(),
+            elapsed_remainder: Duration::ZERO,
+        }
+    }
+
+    /// Add a new subscription
+    pub fn add_subscription(&mut self, subscription: CovSubscription) {
+        // Remove any existing subscription for the same object, property and
+        // subscriber
+        self.subscriptions.retain(|s| {
+            !s.is_for(
+                subscription.subscriber_device_identifier,
+                subscription.subscriber_process_identifier,
+                subscription.monitored_object_identifier,
+                subscription.monitored_property.as_ref(),
+            )
+        });
+
+        self.subscriptions.push(subscription);
+    }
+
+    /// Remove a subscription to an object
+    ///
+    /// Subscriptions of the same process to properties of the object are
+    /// kept; see [`remove_property_subscription`](Self::remove_property_subscription).
+    pub fn remove_subscription(
+        &mut self,
+        subscriber_device: ObjectIdentifier,
+        subscriber_process: u32,
+        monitored_object: ObjectIdentifier,
+    ) {
+        self.subscriptions.retain(|s| {
+            !s.is_for(
+                subscriber_device,
+                subscriber_process,
+                monitored_object,
+                None,
+            )
+        });
+    }
+
+    /// Remove a subscription to a property of an object
+    pub fn remove_property_subscription(
+        &mut self,
+        subscriber_device: ObjectIdentifier,
+        subscriber_process: u32,
+        monitored_object: ObjectIdentifier,
+        monitored_property: &PropertyReference,
+    ) {
+        self.subscriptions.retain(|s| {
+            !s.is_for(
+                subscriber_device,
+                subscriber_process,
+                monitored_object,
+                Some(monitored_property),
+            )
+        });
+    }
+
+    /// Apply a Subscribe COV request from a subscriber device
+    ///
+    /// A new subscription replaces any earlier one of the same subscriber
+    /// process for the object, and is reported in full by the next
+    /// [`check_for_changes`](Self::check_for_changes). Cancelling a
+    /// subscription that does not exist is not an error.
+    pub fn subscribe(
+        &mut self,
+        subscriber_device_identifier: ObjectIdentifier,
+        request: &SubscribeCovRequest,
+    ) {
+        if request.is_cancellation() {
+            self.remove_subscription(
+                subscriber_device_identifier,
+                request.subscriber_process_identifier,
+                request.monitored_object_identifier,
+            );
+        } else {
+            self.add_subscription(CovSubscription::from_request(
+                subscriber_device_identifier,
+                request,
+            ));
+        }
+    }
+
+    /// Apply a Subscribe COV Property request from a subscriber device
+    ///
+    /// Property subscriptions are kept apart from object subscriptions, so a
+    /// process may hold both for the same object.
+    pub fn subscribe_property(
+        &mut self,
+        subscriber_device_identifier: ObjectIdentifier,
+        request: &SubscribeCovPropertyRequest,
+    ) {
+        if request.is_cancellation() {
+            self.remove_property_subscription(
+                subscriber_device_identifier,
+                request.subscriber_process_identifier,
+                request.monitored_object_identifier,
+                &request.monitored_property,
+            );
+        } else {
+            self.add_subscription(CovSubscription::from_property_request(
+                subscriber_device_identifier,
+                request,
+            ));
+        }
+    }
+
+    /// Get all subscriptions for a monitored object
+    pub fn get_subscriptions_for_object(
+        &self,
+        object_id: ObjectIdentifier,
+    ) -> Vec<&CovSubscription> {
+        self.subscriptions
+            .iter()
+            .filter(|s| s.monitored_object_identifier == object_id && !s.is_expired())
+            .collect()
+    }
+
+    /// Remove expired subscriptions
+    pub fn cleanup_expired(&mut self) {
+        self.subscriptions.retain(|s| !s.is_expired());
+    }
+
+    /// Update all subscription timers
+    pub fn update_timers(&mut self, elapsed_seconds: u32) {
+        for subscription in &mut self.subscriptions {
+            subscription.update_time(elapsed_seconds);
+        }
+    }
+
+    /// Age the subscriptions by `elapsed` and drop the expired ones
+    ///
+    /// Fractions of a second are carried over to the next call, so frequent
+    /// short steps age the subscriptions as much as one long step.
+    pub fn advance_time(&mut self, elapsed: Duration) {
+        let elapsed = self.elapsed_remainder + elapsed;
+        let seconds = u32::try_from(elapsed.as_secs()).unwrap_or(u32::MAX);
+        self.elapsed_remainder = Duration::from_nanos(u64::from(elapsed.subsec_nanos()));
+        self.update_timers(seconds);
+        self.cleanup_expired();
+    }
+
+    /// Get total number of active subscriptions
+    pub fn active_count(&self) -> usize {
+        self.subscriptions
+            .iter()
+            .filter(|s| !s.is_expired())
+            .count()
+    }
+
+    /// The Active_COV_Subscriptions property value of the device
+    pub fn active_cov_subscriptions(&self) -> PropertyValue {
+        PropertyValue::List(
+            self.subscriptions
+                .iter()
+                .filter(|s| !s.is_expired())
+                .map(CovSubscription::to_property_value)
+                .collect(),
+        )
+    }
+
+    /// Find the subscriptions whose monitored values have changed and build
+    /// the notifications due to them
+    ///
+    /// `read` returns the current value of a property, or of one element of
+    /// an array property. Each notification carries the monitored property,
+    /// Present_Value for object subscriptions, followed by Status_Flags when
+    /// the object has them. The COV increment of the subscription sets how
+    /// far a numeric value must move; Present_Value falls back to the
+    /// object's COV_Increment. The values reported become the reference for
+    /// the next check.
+    pub fn check_for_changes<F>(
+        &mut self,
+        device_identifier: ObjectIdentifier,
+        mut read: F,
+    ) -> Vec<PendingCovNotification>
+    where
+        F: FnMut(ObjectIdentifier, PropertyIdentifier, Option<u32>) -> Option<PropertyValue>,
+    {
+        let mut pending = Vec::new();
+        for subscription in self.subscriptions.iter_mut().filter(|s| !s.is_expired()) {
+            let object = subscription.monitored_object_identifier;
+            let (property, array_index) = match &subscription.monitored_property {
+                Some(reference) => {
+                    match PropertyIdentifier::try_from(reference.property_identifier) {
+                        Ok(property) => (property, reference.property_array_index),
+                        Err(_) => continue,
+                    }
+                }
+                None => (PropertyIdentifier::PresentValue, None),
+            };
+            let Some(value) = read(object, property, array_index) else {
+                continue;
+            };
+            let mut values = vec![value];
+            if property != PropertyIdentifier::StatusFlags {
+                values.extend(read(object, PropertyIdentifier::StatusFlags, None));
+            }
+
+            let cov_increment = subscription.cov_increment.or_else(|| {
+                if property != PropertyIdentifier::PresentValue {
+                    return None;
+                }
+                match read(object, PropertyIdentifier::CovIncrement, None) {
+                    Some(PropertyValue::Real(increment)) => Some(increment),
+                    _ => None,
+                }
+            });
+            if !subscription.is_change(&values, cov_increment) {
+                continue;
+            }
+
+            let mut list_of_values = Vec::with_capacity(values.len());
+            let mut reported = BacnetPropertyValue::new(u32::from(property), values[0].clone());
+            reported.property_array_index = array_index;
+            list_of_values.push(reported);
+            if let Some(status_flags) = values.get(1) {
+                list_of_values.push(BacnetPropertyValue::new(
+                    u32::from(PropertyIdentifier::StatusFlags),
+                    status_flags.clone(),
+                ));
+            }
+
+            subscription.last_reported = Some(values);
+            pending.push(PendingCovNotification {
+                subscriber_device_identifier: subscription.subscriber_device_identifier,
+                issue_confirmed_notifications: subscription.issue_confirmed_notifications,
+                notification: CovNotificationRequest::new(
+                    subscription.subscriber_process_identifier,
+                    device_identifier,
+                    object,
+                    subscription.time_remaining,
+                    list_of_values,
+                ),
+            });
+        }
+        pending
+    }
+}
+
+/// Answer a Subscribe COV request from a subscriber device
+///
+/// Returns a SimpleAck once the subscription table is updated, an Error PDU
+/// if the object is unknown or does not report changes of value, or a Reject
+/// PDU if the request cannot be decoded.
+#[cfg(feature = "std")]
+pub fn handle_subscribe_cov(
+    database: &ObjectDatabase,
+    subscriber_device_identifier: ObjectIdentifier,
+    invoke_id: u8,
+    service_data: &[u8],
+) -> Apdu {
+    let service_choice = ConfirmedServiceChoice::SubscribeCOV as u8;
+    let request = match SubscribeCovRequest::decode(service_data) {
+        Ok(request) => request,
+        Err(error) => return Apdu::reject(invoke_id, RejectReason::for_decode_error(&error)),
+    };
+
+    match database.subscribe_cov(subscriber_device_identifier, &request) {
+        Ok(()) => Apdu::SimpleAck {
+            invoke_id,
+            service_choice,
+        },
+        Err(error) => Apdu::error(invoke_id, service_choice, PropertyAccessError::from(&error)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subscribe_cov_request() {
+        let object_id = ObjectIdentifier::new(ObjectType::AnalogInput, 1);
+        let cov_req = SubscribeCovRequest::new(123, object_id);
+
+        assert_eq!(cov_req.subscriber_process_identifier, 123);
+        assert_eq!(cov_req.monitored_object_identifier.instance, 1);
+        assert_eq!(cov_req.issue_confirmed_notifications, None);
+        assert_eq!(cov_req.lifetime, None);
+        assert!(cov_req.is_cancellation());
+
+        // Test with confirmation
+        let cov_confirmed = SubscribeCovRequest::with_confirmation(123, object_id, true);
+        assert_eq!(cov_confirmed.issue_confirmed_notifications, Some(true));
+
+        // Test with lifetime
+        let cov_lifetime = SubscribeCovRequest::with_lifetime(123, object_id, 3600);
+        assert_eq!(cov_lifetime.lifetime, Some(3600));
+
+        // Test encoding
+        let mut buffer = Vec::new();
+        cov_req.encode(&mut buffer).unwrap();
+        assert!(!buffer.is_empty());
+
+        let mut request = SubscribeCovRequest::with_lifetime(70000, object_id, 3600);
+        request.issue_confirmed_notifications = Some(true);
+        for request in [cov_req, cov_confirmed, cov_lifetime, request] {
+            let mut buffer = Vec::new();
+            request.encode(&mut buffer).unwrap();
+            assert_eq!(SubscribeCovRequest::decode(&buffer).unwrap(), request);
+        }
+    }
+
+    #[test]
+    fn test_cov_subscription_manager() {
+        let mut manager = CovSubscriptionManager::new();
+
+        let device_id = ObjectIdentifier::new(ObjectType::Device, 1);
+        let object_id = ObjectIdentifier::new(ObjectType::AnalogInput, 1);
+
+        let subscription = CovSubscription::new(123, device_id, object_id, 3600);
+        manager.add_subscription(subscription);
+
+        assert_eq!(manager.active_count(), 1);
+
+        let subscriptions = manager.get_subscriptions_for_object(object_id);
+        assert_eq!(subscriptions.len(), 1);
+        assert_eq!(subscriptions[0].subscriber_process_identifier, 123);
+
+        // Test time updates
+        manager.update_timers(1800); // 30 minutes
+        let subscriptions = manager.get_subscriptions_for_object(object_id);
+        assert_eq!(subscriptions[0].time_remaining, 1800);
+
+        // Test expiration
+        manager.update_timers(1800); // Another 30 minutes
+        assert_eq!(manager.active_count(), 0);
+
+        manager.cleanup_expired();
+        assert_eq!(manager.subscriptions.len(), 0);
+
+        // Subscribe, age in fractions of a second, then cancel
+        manager.subscribe(
+            device_id,
+            &SubscribeCovRequest::with_lifetime(5, object_id, 2),
+        );
+        for _ in 0..3 {
+            manager.advance_time(Duration::from_millis(500));
+        }
+        assert_eq!(manager.subscriptions[0].time_remaining, 1);
+        assert!(matches!(
+            manager.active_cov_subscriptions(),
+            PropertyValue::List(entries) if entries.len() == 1
+        ));
+        manager.subscribe(device_id, &SubscribeCovRequest::new(5, object_id));
+        assert_eq!(manager.active_count(), 0);
+    }
+
+    #[test]
+    fn test_check_for_changes() {
+        let mut manager = CovSubscriptionManager::new();
+        let device_id = ObjectIdentifier::new(ObjectType::Device, 1);
+        let object_id = ObjectIdentifier::new(ObjectType::AnalogInput, 1);
+        manager.subscribe(
+            device_id,
+            &SubscribeCovRequest::with_confirmation(9, object_id, true),
+        );
+
+        let check = |manager: &mut CovSubscriptionManager, value: f32| {
+            manager.check_for_changes(device_id, |_, property, _| match property {
+                PropertyIdentifier::PresentValue => Some(PropertyValue::Real(value)),
+                PropertyIdentifier::CovIncrement => Some(PropertyValue::Real(1.0)),
+                _ => None,
+            })
+        };
+
+        // The first check reports the subscribed values
+        let pending = check(&mut manager, 20.0);
+        assert_eq!(pending.len(), 1);
+        assert!(pending[0].issue_confirmed_notifications);
+        assert_eq!(pending[0].notification.subscriber_process_identifier, 9);
+        assert_eq!(
+            pending[0].notification.list_of_values,
+            vec![BacnetPropertyValue::new(
+                u32::from(PropertyIdentifier::PresentValue),
+                PropertyValue::Real(20.0)
+            )]
+        );
+        assert!(matches!(
+            pending[0].to_apdu(3).unwrap(),
+            Apdu::ConfirmedRequest {
+                invoke_id: 3,
+                service_choice: ConfirmedServiceChoice::ConfirmedCOVNotification,
+                ..
+            }
+        ));
+
+        // Changes below the COV increment are not reported
+        assert!(check(&mut manager, 20.5).is_empty());
+        assert_eq!(check(&mut manager, 21.0).len(), 1);
+        assert!(check(&mut manager, 21.0).is_empty());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_handle_subscribe_cov() {
+        use crate::object::{analog::AnalogInput, Device};
+
+        let database = ObjectDatabase::new(Device::new(15, String::from("Device")));
+        database
+            .add_object(Box::new(AnalogInput::new(1, String::from("AI-1"))))
+            .unwrap();
+        let ai = ObjectIdentifier::new(ObjectType::AnalogInput, 1);
+        let subscriber = ObjectIdentifier::new(ObjectType::Device, 99);
+
+        let mut service_data = Vec::new();
+        SubscribeCovRequest::with_lifetime(4, ai, 60)
+            .encode(&mut service_data)
+            .unwrap();
+        assert!(matches!(
+            handle_subscribe_cov(&database, subscriber, 1, &service_data),
+            Apdu::SimpleAck {
+                invoke_id: 1,
+                service_choice: 5,
+            }
+        ));
+        let active = database
+            .get_property(
+                database.get_device_id(),
+                PropertyIdentifier::ActiveCovSubscriptions,
+            )
+            .unwrap();
+        assert!(matches!(active, PropertyValue::List(entries) if entries.len() == 1));
+
+        let pending = database.cov_notifications();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].subscriber_device_identifier, subscriber);
+        assert_eq!(
+            pending[0]
+                .notification
+                .initiating_device_identifier
+                .instance,
+            15
+        );
+        assert!(database.cov_notifications().is_empty());
+
+        // The subscription expires with its lifetime
+        database.advance_time(std::time::Duration::from_secs(60));
+        assert!(database.cov_notifications().is_empty());
+
+        // Unknown objects cannot be subscribed to
+        let mut service_data = Vec::new();
+        SubscribeCovRequest::with_lifetime(
+            4,
+            ObjectIdentifier::new(ObjectType::AnalogInput, 2),
+            60,
+        )
+        .encode(&mut service_data)
+        .unwrap();
+        assert!(matches!(
+            handle_subscribe_cov(&database, subscriber, 2, &service_data),
+            Apdu::Error {
+                error_class: 1,
+                error_code: 31,
+                ..
+            }
+        ));
+    }
+}
@@ -0,0 +1,181 @@
+//! ConfirmedCOVNotification and UnconfirmedCOVNotification Services (Clauses
+//! 13.6 and 13.7)
+//!
+//! Both services carry the same parameters: the subscriber process the
+//! notification is meant for, the initiating device and monitored object,
+//! the time left on the subscription and the list of property values that
+//! changed. Only the PDU differs; a confirmed notification is acknowledged
+//! with a SimpleAck. The server builds them from its subscription table with
+//! [`CovSubscriptionManager`](super::CovSubscriptionManager); clients route
+//! them to callbacks with
+//! [`CovNotificationDispatcher`](crate::client::CovNotificationDispatcher).
+
+use super::cov::decode_context_identifier;
+use super::read_property_multiple::expect_tag;
+use super::BacnetPropertyValue;
+use crate::encoding::{
+    advanced::context::{encode_closing_tag, encode_opening_tag},
+    decode_context_unsigned, encode_context_object_id, encode_context_unsigned, EncodingError,
+    Result as EncodingResult,
+};
+use crate::object::{ObjectIdentifier, PropertyIdentifier, PropertyValue};
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::ToString, vec::Vec};
+
+/// COV Notification request (confirmed or unconfirmed service)
+#[derive(Debug, Clone, PartialEq)]
+pub struct CovNotificationRequest {
+    /// Subscriber process identifier
+    pub subscriber_process_identifier: u32,
+    /// Initiating device identifier
+    pub initiating_device_identifier: ObjectIdentifier,
+    /// Monitored object identifier
+    pub monitored_object_identifier: ObjectIdentifier,
+    /// Time remaining (seconds)
+    pub time_remaining: u32,
+    /// List of values (property-value pairs)
+    pub list_of_values: Vec<BacnetPropertyValue>,
+}
+
+impl CovNotificationRequest {
+    /// Create a new COV Notification request
+    pub fn new(
+        subscriber_process_identifier: u32,
+        initiating_device_identifier: ObjectIdentifier,
+        monitored_object_identifier: ObjectIdentifier,
+        time_remaining: u32,
+        list_of_values: Vec<BacnetPropertyValue>,
+    ) -> Self {
+        Self {
+            subscriber_process_identifier,
+            initiating_device_identifier,
+            monitored_object_identifier,
+            time_remaining,
+            list_of_values,
+        }
+    }
+
+    /// Get the reported value of a property, if the notification has one
+    pub fn value(&self, property: PropertyIdentifier) -> Option<&PropertyValue> {
+        let property = u32::from(property);
+        self.list_of_values
+            .iter()
+            .find(|value| value.property_identifier == property)
+            .map(|value| &value.value)
+    }
+
+    /// Encode the COV Notification request
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        // Subscriber process identifier - context tag 0
+        buffer.extend_from_slice(&encode_context_unsigned(
+            self.subscriber_process_identifier,
+            0,
+        )?);
+
+        // Initiating device identifier - context tag 1
+        buffer.extend_from_slice(&encode_context_object_id(
+            u16::from(self.initiating_device_identifier.object_type),
+            self.initiating_device_identifier.instance,
+            1,
+        )?);
+
+        // Monitored object identifier - context tag 2
+        buffer.extend_from_slice(&encode_context_object_id(
+            u16::from(self.monitored_object_identifier.object_type),
+            self.monitored_object_identifier.instance,
+            2,
+        )?);
+
+        // Time remaining - context tag 3
+        buffer.extend_from_slice(&encode_context_unsigned(self.time_remaining, 3)?);
+
+        // List of values - context tag 4
+        encode_opening_tag(buffer, 4)?;
+        for value in &self.list_of_values {
+            value.encode(buffer)?;
+        }
+        encode_closing_tag(buffer, 4)?;
+
+        Ok(())
+    }
+
+    /// Decode a COV Notification request
+    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
+        let (subscriber_process_identifier, mut pos) = decode_context_unsigned(data, 0)?;
+        let (initiating_device_identifier, consumed) = decode_context_identifier(&data[pos..], 1)?;
+        pos += consumed;
+        let (monitored_object_identifier, consumed) = decode_context_identifier(&data[pos..], 2)?;
+        pos += consumed;
+        let (time_remaining, consumed) = decode_context_unsigned(&data[pos..], 3)?;
+        pos += consumed;
+
+        expect_tag(data, pos, 0x4E)?;
+        pos += 1;
+        let mut list_of_values = Vec::new();
+        while data.get(pos).is_some_and(|&tag| tag != 0x4F) {
+            let (value, consumed) = BacnetPropertyValue::decode(&data[pos..])?;
+            list_of_values.push(value);
+            pos += consumed;
+        }
+        expect_tag(data, pos, 0x4F)?;
+        pos += 1;
+
+        if pos != data.len() {
+            return Err(EncodingError::InvalidFormat(
+                "Unexpected data after COV Notification request".to_string(),
+            ));
+        }
+
+        Ok(Self::new(
+            subscriber_process_identifier,
+            initiating_device_identifier,
+            monitored_object_identifier,
+            time_remaining,
+            list_of_values,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::{status_flags_bit_string, ObjectType};
+
+    #[test]
+    fn test_cov_notification_request() {
+        let device_id = ObjectIdentifier::new(ObjectType::Device, 1);
+        let object_id = ObjectIdentifier::new(ObjectType::AnalogInput, 1);
+        let values = vec![
+            BacnetPropertyValue::new(
+                u32::from(PropertyIdentifier::PresentValue),
+                PropertyValue::Real(25.5),
+            ),
+            BacnetPropertyValue::new(
+                u32::from(PropertyIdentifier::StatusFlags),
+                status_flags_bit_string(0),
+            ),
+        ];
+
+        let notification = CovNotificationRequest::new(123, device_id, object_id, 3600, values);
+
+        assert_eq!(notification.subscriber_process_identifier, 123);
+        assert_eq!(notification.initiating_device_identifier, device_id);
+        assert_eq!(notification.monitored_object_identifier, object_id);
+        assert_eq!(notification.time_remaining, 3600);
+        assert_eq!(notification.list_of_values.len(), 2);
+        assert_eq!(
+            notification.value(PropertyIdentifier::PresentValue),
+            Some(&PropertyValue::Real(25.5))
+        );
+
+        // Test encoding
+        let mut buffer = Vec::new();
+        notification.encode(&mut buffer).unwrap();
+        assert!(!buffer.is_empty());
+        assert_eq!(
+            CovNotificationRequest::decode(&buffer).unwrap(),
+            notification
+        );
+    }
+}
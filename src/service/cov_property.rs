@@ -0,0 +1,780 @@
+//! SubscribeCOVProperty and SubscribeCOVPropertyMultiple Services (Clauses
+//! 13.15 and 13.16)
+//!
+//! Property subscriptions monitor any property of an object, or one element
+//! of an array property, instead of the Present_Value and Status_Flags
+//! watched by SubscribeCOV. A subscriber may supply its own COV increment
+//! for numeric properties. The multiple variant subscribes one process to
+//! several properties of several objects at once; it is applied as a whole,
+//! so when one subscription cannot be made none of them is.
+//!
+//! Subscriptions of the multiple variant are kept in the same table as the
+//! single-property ones and are reported with ordinary COV notifications, one
+//! per monitored property. As with
+//! [`WritePropertyMultiple`](super::write_property_multiple), the
+//! [`Apdu::Error`](crate::app::Apdu) PDU carries only the error class and
+//! code; the full [`SubscribeCovPropertyMultipleError`] has its own codec.
+
+use super::cov::decode_context_identifier;
+use super::read_property_multiple::expect_tag;
+use super::{PropertyAccessError, PropertyReference};
+use crate::encoding::{
+    advanced::context::{encode_closing_tag, encode_opening_tag},
+    decode_context_boolean, decode_context_real, decode_context_unsigned, decode_enumerated,
+    encode_context_boolean, encode_context_object_id, encode_context_real, encode_context_unsigned,
+    encode_enumerated, EncodingError, Result as EncodingResult,
+};
+use crate::object::ObjectIdentifier;
+
+#[cfg(feature = "std")]
+use super::{ConfirmedServiceChoice, RejectReason};
+#[cfg(feature = "std")]
+use crate::{app::Apdu, object::database::ObjectDatabase};
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::ToString, vec::Vec};
+
+/// Subscribe COV Property request (confirmed service)
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubscribeCovPropertyRequest {
+    /// Subscriber process identifier
+    pub subscriber_process_identifier: u32,
+    /// Monitored object identifier
+    pub monitored_object_identifier: ObjectIdentifier,
+    /// Issue confirmed notifications
+    pub issue_confirmed_notifications: Option<bool>,
+    /// Lifetime (seconds, 0 = permanent)
+    pub lifetime: Option<u32>,
+    /// Monitored property reference
+    pub monitored_property: PropertyReference,
+    /// COV increment (optional)
+    pub cov_increment: Option<f32>,
+}
+
+impl SubscribeCovPropertyRequest {
+    /// Create a new Subscribe COV Property request
+    ///
+    /// Without a confirmation preference or lifetime the request cancels the
+    /// subscription.
+    pub fn new(
+        subscriber_process_identifier: u32,
+        monitored_object_identifier: ObjectIdentifier,
+        monitored_property: PropertyReference,
+    ) -> Self {
+        Self {
+            subscriber_process_identifier,
+            monitored_object_identifier,
+            issue_confirmed_notifications: None,
+            lifetime: None,
+            monitored_property,
+            cov_increment: None,
+        }
+    }
+
+    /// Set COV increment
+    pub fn with_cov_increment(mut self, increment: f32) -> Self {
+        self.cov_increment = Some(increment);
+        self
+    }
+
+    /// Check if this request cancels a subscription
+    pub fn is_cancellation(&self) -> bool {
+        self.issue_confirmed_notifications.is_none() && self.lifetime.is_none()
+    }
+
+    /// Encode the Subscribe COV Property request
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        // Subscriber process identifier - context tag 0
+        buffer.extend_from_slice(&encode_context_unsigned(
+            self.subscriber_process_identifier,
+            0,
+        )?);
+
+        // Monitored object identifier - context tag 1
+        buffer.extend_from_slice(&encode_context_object_id(
+            u16::from(self.monitored_object_identifier.object_type),
+            self.monitored_object_identifier.instance,
+            1,
+        )?);
+
+        // Issue confirmed notifications - context tag 2 (optional)
+        if let Some(confirmed) = self.issue_confirmed_notifications {
+            buffer.extend_from_slice(&encode_context_boolean(confirmed, 2)?);
+        }
+
+        // Lifetime - context tag 3 (optional)
+        if let Some(lifetime) = self.lifetime {
+            buffer.extend_from_slice(&encode_context_unsigned(lifetime, 3)?);
+        }
+
+        // Monitored property - context tag 4
+        encode_opening_tag(buffer, 4)?;
+        self.monitored_property.encode(buffer, 0)?;
+        encode_closing_tag(buffer, 4)?;
+
+        // COV increment - context tag 5 (optional)
+        if let Some(increment) = self.cov_increment {
+            buffer.extend_from_slice(&encode_context_real(increment, 5)?);
+        }
+
+        Ok(())
+    }
+
+    /// Decode a Subscribe COV Property request
+    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
+        let (subscriber_process_identifier, mut pos) = decode_context_unsigned(data, 0)?;
+        let (monitored_object_identifier, consumed) = decode_context_identifier(&data[pos..], 1)?;
+        pos += consumed;
+
+        let (issue_confirmed_notifications, consumed) = decode_optional_boolean(&data[pos..], 2);
+        pos += consumed;
+        let (lifetime, consumed) = decode_optional_unsigned(&data[pos..], 3);
+        pos += consumed;
+
+        expect_tag(data, pos, 0x4E)?;
+        pos += 1;
+        let (monitored_property, consumed) = PropertyReference::decode(&data[pos..], 0)?;
+        pos += consumed;
+        expect_tag(data, pos, 0x4F)?;
+        pos += 1;
+
+        let cov_increment = match decode_context_real(&data[pos..], 5) {
+            Ok((increment, consumed)) => {
+                pos += consumed;
+                Some(increment)
+            }
+            Err(_) => None,
+        };
+
+        if pos != data.len() {
+            return Err(EncodingError::InvalidFormat(
+                "Unexpected data after Subscribe COV Property request".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            subscriber_process_identifier,
+            monitored_object_identifier,
+            issue_confirmed_notifications,
+            lifetime,
+            monitored_property,
+            cov_increment,
+        })
+    }
+}
+
+fn decode_optional_boolean(data: &[u8], tag: u8) -> (Option<bool>, usize) {
+    match decode_context_boolean(data, tag) {
+        Ok((value, consumed)) => (Some(value), consumed),
+        Err(_) => (None, 0),
+    }
+}
+
+fn decode_optional_unsigned(data: &[u8], tag: u8) -> (Option<u32>, usize) {
+    match decode_context_unsigned(data, tag) {
+        Ok((value, consumed)) => (Some(value), consumed),
+        Err(_) => (None, 0),
+    }
+}
+
+/// Subscribe COV Property Multiple request (confirmed service)
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubscribeCovPropertyMultipleRequest {
+    /// Subscriber process identifier
+    pub subscriber_process_identifier: u32,
+    /// Issue confirmed notifications
+    pub issue_confirmed_notifications: Option<bool>,
+    /// Lifetime (seconds, 0 = permanent)
+    pub lifetime: Option<u32>,
+    /// Longest time (seconds) notifications may be held back to be combined
+    pub max_notification_delay: Option<u32>,
+    /// Objects and the properties of each to subscribe to
+    pub list_of_cov_subscription_specifications: Vec<CovSubscriptionSpecification>,
+}
+
+/// An object and the properties of it to subscribe to
+#[derive(Debug, Clone, PartialEq)]
+pub struct CovSubscriptionSpecification {
+    /// Monitored object identifier
+    pub monitored_object_identifier: ObjectIdentifier,
+    /// Properties to monitor
+    pub list_of_cov_references: Vec<CovReference>,
+}
+
+/// A property to monitor, with its COV increment
+#[derive(Debug, Clone, PartialEq)]
+pub struct CovReference {
+    /// Monitored property reference
+    pub monitored_property: PropertyReference,
+    /// COV increment (optional)
+    pub cov_increment: Option<f32>,
+    /// Ask for the time of change of each value
+    pub timestamped: bool,
+}
+
+/// Error returned when a Subscribe COV Property Multiple request fails
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubscribeCovPropertyMultipleError {
+    /// Why the subscription failed
+    pub error: PropertyAccessError,
+    /// Object of the first subscription that could not be made
+    pub monitored_object_identifier: ObjectIdentifier,
+    /// Property of the first subscription that could not be made
+    pub monitored_property: PropertyReference,
+}
+
+impl SubscribeCovPropertyMultipleRequest {
+    /// Create a new Subscribe COV Property Multiple request
+    ///
+    /// Without a confirmation preference or lifetime the request cancels the
+    /// subscriptions.
+    pub fn new(
+        subscriber_process_identifier: u32,
+        list_of_cov_subscription_specifications: Vec<CovSubscriptionSpecification>,
+    ) -> Self {
+        Self {
+            subscriber_process_identifier,
+            issue_confirmed_notifications: None,
+            lifetime: None,
+            max_notification_delay: None,
+            list_of_cov_subscription_specifications,
+        }
+    }
+
+    /// Check if this request cancels the subscriptions
+    pub fn is_cancellation(&self) -> bool {
+        self.issue_confirmed_notifications.is_none() && self.lifetime.is_none()
+    }
+
+    /// Split the request into one Subscribe COV Property request per
+    /// monitored property
+    pub fn property_requests(&self) -> Vec<SubscribeCovPropertyRequest> {
+        self.list_of_cov_subscription_specifications
+            .iter()
+            .flat_map(|spec| {
+                spec.list_of_cov_references.iter().map(|reference| {
+                    let mut request = SubscribeCovPropertyRequest::new(
+                        self.subscriber_process_identifier,
+                        spec.monitored_object_identifier,
+                        reference.monitored_property.clone(),
+                    );
+                    request.issue_confirmed_notifications = self.issue_confirmed_notifications;
+                    request.lifetime = self.lifetime;
+                    request.cov_increment = reference.cov_increment;
+                    request
+                })
+            })
+            .collect()
+    }
+
+    /// Encode the Subscribe COV Property Multiple request
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        // Subscriber process identifier - context tag 0
+        buffer.extend_from_slice(&encode_context_unsigned(
+            self.subscriber_process_identifier,
+            0,
+        )?);
+
+        // Issue confirmed notifications - context tag 1 (optional)
+        if let Some(confirmed) = self.issue_confirmed_notifications {
+            buffer.extend_from_slice(&encode_context_boolean(confirmed, 1)?);
+        }
+
+        // Lifetime - context tag 2 (optional)
+        if let Some(lifetime) = self.lifetime {
+            buffer.extend_from_slice(&encode_context_unsigned(lifetime, 2)?);
+        }
+
+        // Max notification delay - context tag 3 (optional)
+        if let Some(delay) = self.max_notification_delay {
+            buffer.extend_from_slice(&encode_context_unsigned(delay, 3)?);
+        }
+
+        // List of COV subscription specifications - context tag 4
+        encode_opening_tag(buffer, 4)?;
+        for spec in &self.list_of_cov_subscription_specifications {
+            spec.encode(buffer)?;
+        }
+        encode_closing_tag(buffer, 4)?;
+
+        Ok(())
+    }
+
+    /// Decode a Subscribe COV Property Multiple request
+    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
+        let (subscriber_process_identifier, mut pos) = decode_context_unsigned(data, 0)?;
+        let (issue_confirmed_notifications, consumed) = decode_optional_boolean(&data[pos..], 1);
+        pos += consumed;
+        let (lifetime, consumed) = decode_optional_unsigned(&data[pos..], 2);
+        pos += consumed;
+        let (max_notification_delay, consumed) = decode_optional_unsigned(&data[pos..], 3);
+        pos += consumed;
+
+        expect_tag(data, pos, 0x4E)?;
+        pos += 1;
+        let mut list_of_cov_subscription_specifications = Vec::new();
+        while data.get(pos).is_some_and(|&tag| tag != 0x4F) {
+            let (spec, consumed) = CovSubscriptionSpecification::decode(&data[pos..])?;
+            list_of_cov_subscription_specifications.push(spec);
+            pos += consumed;
+        }
+        expect_tag(data, pos, 0x4F)?;
+        pos += 1;
+
+        if pos != data.len() {
+            return Err(EncodingError::InvalidFormat(
+                "Unexpected data after Subscribe COV Property Multiple request".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            subscriber_process_identifier,
+            issue_confirmed_notifications,
+            lifetime,
+            max_notification_delay,
+            list_of_cov_subscription_specifications,
+        })
+    }
+}
+
+impl CovSubscriptionSpecification {
+    /// Create a new COV subscription specification
+    pub fn new(
+        monitored_object_identifier: ObjectIdentifier,
+        list_of_cov_references: Vec<CovReference>,
+    ) -> Self {
+        Self {
+            monitored_object_identifier,
+            list_of_cov_references,
+        }
+    }
+
+    fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        // Monitored object identifier - context tag 0
+        buffer.extend_from_slice(&encode_context_object_id(
+            u16::from(self.monitored_object_identifier.object_type),
+            self.monitored_object_identifier.instance,
+            0,
+        )?);
+
+        // List of COV references - context tag 1
+        encode_opening_tag(buffer, 1)?;
+        for reference in &self.list_of_cov_references {
+            reference.encode(buffer)?;
+        }
+        encode_closing_tag(buffer, 1)
+    }
+
+    fn decode(data: &[u8]) -> EncodingResult<(Self, usize)> {
+        let (monitored_object_identifier, mut pos) = decode_context_identifier(data, 0)?;
+        expect_tag(data, pos, 0x1E)?;
+        pos += 1;
+        let mut list_of_cov_references = Vec::new();
+        while data.get(pos).is_some_and(|&tag| tag != 0x1F) {
+            let (reference, consumed) = CovReference::decode(&data[pos..])?;
+            list_of_cov_references.push(reference);
+            pos += consumed;
+        }
+        expect_tag(data, pos, 0x1F)?;
+        pos += 1;
+
+        Ok((
+            Self::new(monitored_object_identifier, list_of_cov_references),
+            pos,
+        ))
+    }
+}
+
+impl CovReference {
+    /// Create a new COV reference without an increment or timestamps
+    pub fn new(monitored_property: PropertyReference) -> Self {
+        Self {
+            monitored_property,
+            cov_increment: None,
+            timestamped: false,
+        }
+    }
+
+    /// Set COV increment
+    pub fn with_cov_increment(mut self, increment: f32) -> Self {
+        self.cov_increment = Some(increment);
+        self
+    }
+
+    fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        // Monitored property - context tag 0
+        encode_opening_tag(buffer, 0)?;
+        self.monitored_property.encode(buffer, 0)?;
+        encode_closing_tag(buffer, 0)?;
+
+        // COV increment - context tag 1 (optional)
+        if let Some(increment) = self.cov_increment {
+            buffer.extend_from_slice(&encode_context_real(increment, 1)?);
+        }
+
+        // Timestamped - context tag 2
+        buffer.extend_from_slice(&encode_context_boolean(self.timestamped, 2)?);
+        Ok(())
+    }
+
+    fn decode(data: &[u8]) -> EncodingResult<(Self, usize)> {
+        expect_tag(data, 0, 0x0E)?;
+        let mut pos = 1;
+        let (monitored_property, consumed) = PropertyReference::decode(&data[pos..], 0)?;
+        pos += consumed;
+        expect_tag(data, pos, 0x0F)?;
+        pos += 1;
+
+        let cov_increment = match decode_context_real(&data[pos..], 1) {
+            Ok((increment, consumed)) => {
+                pos += consumed;
+                Some(increment)
+            }
+            Err(_) => None,
+        };
+        let (timestamped, consumed) = decode_context_boolean(&data[pos..], 2)?;
+        pos += consumed;
+
+        Ok((
+            Self {
+                monitored_property,
+                cov_increment,
+                timestamped,
+            },
+            pos,
+        ))
+    }
+}
+
+impl SubscribeCovPropertyMultipleError {
+    /// Encode the error (SubscribeCOVPropertyMultiple-Error)
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        // Error type - context tag 0
+        encode_opening_tag(buffer, 0)?;
+        self.encode_error(buffer)?;
+        encode_closing_tag(buffer, 0)?;
+
+        // First failed subscription - context tag 1, repeating the error
+        encode_opening_tag(buffer, 1)?;
+        buffer.extend_from_slice(&encode_context_object_id(
+            u16::from(self.monitored_object_identifier.object_type),
+            self.monitored_object_identifier.instance,
+            0,
+        )?);
+        encode_opening_tag(buffer, 1)?;
+        self.monitored_property.encode(buffer, 0)?;
+        encode_closing_tag(buffer, 1)?;
+        encode_opening_tag(buffer, 2)?;
+        self.encode_error(buffer)?;
+        encode_closing_tag(buffer, 2)?;
+        encode_closing_tag(buffer, 1)?;
+
+        Ok(())
+    }
+
+    fn encode_error(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        encode_enumerated(buffer, self.error.error_class)?;
+        encode_enumerated(buffer, self.error.error_code)
+    }
+
+    /// Decode the error
+    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
+        expect_tag(data, 0, 0x0E)?;
+        let (_, mut pos) = decode_error(&data[1..])?;
+        pos += 1;
+        expect_tag(data, pos, 0x0F)?;
+        pos += 1;
+
+        expect_tag(data, pos, 0x1E)?;
+        pos += 1;
+        let (monitored_object_identifier, consumed) = decode_context_identifier(&data[pos..], 0)?;
+        pos += consumed;
+        expect_tag(data, pos, 0x1E)?;
+        pos += 1;
+        let (monitored_property, consumed) = PropertyReference::decode(&data[pos..], 0)?;
+        pos += consumed;
+        expect_tag(data, pos, 0x1F)?;
+        pos += 1;
+
+        // The error of the failed subscription is the one reported
+        expect_tag(data, pos, 0x2E)?;
+        pos += 1;
+        let (error, consumed) = decode_error(&data[pos..])?;
+        pos += consumed;
+        expect_tag(data, pos, 0x2F)?;
+        pos += 1;
+        expect_tag(data, pos, 0x1F)?;
+
+        Ok(Self {
+            error,
+            monitored_object_identifier,
+            monitored_property,
+        })
+    }
+}
+
+fn decode_error(data: &[u8]) -> EncodingResult<(PropertyAccessError, usize)> {
+    let (error_class, mut pos) = decode_enumerated(data)?;
+    let (error_code, consumed) = decode_enumerated(&data[pos..])?;
+    pos += consumed;
+    Ok((
+        PropertyAccessError {
+            error_class,
+            error_code,
+        },
+        pos,
+    ))
+}
+
+/// Apply a Subscribe COV Property Multiple request to an object database
+///
+/// Every monitored property is checked before any subscription is made; the
+/// first one that cannot be subscribed to fails the whole request.
+#[cfg(feature = "std")]
+pub fn subscribe_cov_property_multiple(
+    database: &ObjectDatabase,
+    subscriber_device_identifier: ObjectIdentifier,
+    request: &SubscribeCovPropertyMultipleRequest,
+) -> core::result::Result<(), SubscribeCovPropertyMultipleError> {
+    let requests = request.property_requests();
+    for property_request in &requests {
+        let property = crate::object::PropertyIdentifier::try_from(
+            property_request.monitored_property.property_identifier,
+        )
+        .map_err(|_| crate::object::ObjectError::UnknownProperty);
+        let readable = property.and_then(|property| {
+            let identifier = property_request.monitored_object_identifier;
+            match property_request.monitored_property.property_array_index {
+                Some(index) => database.get_property_at(identifier, property, index),
+                None => database.get_property(identifier, property),
+            }
+        });
+        if let Err(error) = readable {
+            return Err(SubscribeCovPropertyMultipleError {
+                error: PropertyAccessError::from(&error),
+                monitored_object_identifier: property_request.monitored_object_identifier,
+                monitored_property: property_request.monitored_property.clone(),
+            });
+        }
+    }
+
+    for property_request in &requests {
+        database
+            .subscribe_cov_property(subscriber_device_identifier, property_request)
+            .map_err(|error| SubscribeCovPropertyMultipleError {
+                error: PropertyAccessError::from(&error),
+                monitored_object_identifier: property_request.monitored_object_identifier,
+                monitored_property: property_request.monitored_property.clone(),
+            })?;
+    }
+    Ok(())
+}
+
+/// Answer a Subscribe COV Property request from a subscriber device
+///
+/// Returns a SimpleAck once the subscription table is updated, an Error PDU
+/// if the property cannot be read, or a Reject PDU if the request cannot be
+/// decoded.
+#[cfg(feature = "std")]
+pub fn handle_subscribe_cov_property(
+    database: &ObjectDatabase,
+    subscriber_device_identifier: ObjectIdentifier,
+    invoke_id: u8,
+    service_data: &[u8],
+) -> Apdu {
+    let service_choice = ConfirmedServiceChoice::SubscribeCOVProperty as u8;
+    let request = match SubscribeCovPropertyRequest::decode(service_data) {
+        Ok(request) => request,
+        Err(error) => return Apdu::reject(invoke_id, RejectReason::for_decode_error(&error)),
+    };
+
+    let result = database
+        .subscribe_cov_property(subscriber_device_identifier, &request)
+        .map_err(|error| PropertyAccessError::from(&error));
+    subscription_response(invoke_id, service_choice, result)
+}
+
+/// Answer a Subscribe COV Property Multiple request from a subscriber device
+///
+/// Returns a SimpleAck once every subscription is made, an Error PDU with the
+/// class and code of the first one that failed, or a Reject PDU if the
+/// request cannot be decoded.
+#[cfg(feature = "std")]
+pub fn handle_subscribe_cov_property_multiple(
+    database: &ObjectDatabase,
+    subscriber_device_identifier: ObjectIdentifier,
+    invoke_id: u8,
+    service_data: &[u8],
+) -> Apdu {
+    let service_choice = ConfirmedServiceChoice::SubscribeCOVPropertyMultiple as u8;
+    let request = match SubscribeCovPropertyMultipleRequest::decode(service_data) {
+        Ok(request) => request,
+        Err(error) => return Apdu::reject(invoke_id, RejectReason::for_decode_error(&error)),
+    };
+
+    let result = subscribe_cov_property_multiple(database, subscriber_device_identifier, &request)
+        .map_err(|failure| failure.error);
+    subscription_response(invoke_id, service_choice, result)
+}
+
+#[cfg(feature = "std")]
+fn subscription_response(
+    invoke_id: u8,
+    service_choice: u8,
+    result: core::result::Result<(), PropertyAccessError>,
+) -> Apdu {
+    match result {
+        Ok(()) => Apdu::SimpleAck {
+            invoke_id,
+            service_choice,
+        },
+        Err(error) => Apdu::error(invoke_id, service_choice, error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::{ObjectType, PropertyIdentifier};
+
+    #[test]
+    fn test_request_round_trip() {
+        let object_id = ObjectIdentifier::new(ObjectType::AnalogInput, 1);
+        let present_value = PropertyReference::new(u32::from(PropertyIdentifier::PresentValue));
+
+        let mut request = SubscribeCovPropertyRequest::new(7, object_id, present_value.clone())
+            .with_cov_increment(0.5);
+        request.lifetime = Some(600);
+        let mut buffer = Vec::new();
+        request.encode(&mut buffer).unwrap();
+        assert_eq!(
+            SubscribeCovPropertyRequest::decode(&buffer).unwrap(),
+            request
+        );
+
+        let mut multiple = SubscribeCovPropertyMultipleRequest::new(
+            7,
+            vec![CovSubscriptionSpecification::new(
+                object_id,
+                vec![
+                    CovReference::new(present_value).with_cov_increment(0.5),
+                    CovReference::new(PropertyReference::with_array_index(
+                        u32::from(PropertyIdentifier::PriorityArray),
+                        8,
+                    )),
+                ],
+            )],
+        );
+        multiple.issue_confirmed_notifications = Some(true);
+        multiple.max_notification_delay = Some(5);
+        let mut buffer = Vec::new();
+        multiple.encode(&mut buffer).unwrap();
+        assert_eq!(
+            SubscribeCovPropertyMultipleRequest::decode(&buffer).unwrap(),
+            multiple
+        );
+        assert_eq!(multiple.property_requests().len(), 2);
+
+        let error = SubscribeCovPropertyMultipleError {
+            error: PropertyAccessError {
+                error_class: 2,
+                error_code: 32,
+            },
+            monitored_object_identifier: object_id,
+            monitored_property: PropertyReference::new(u32::from(PropertyIdentifier::Units)),
+        };
+        let mut buffer = Vec::new();
+        error.encode(&mut buffer).unwrap();
+        assert_eq!(
+            SubscribeCovPropertyMultipleError::decode(&buffer).unwrap(),
+            error
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_property_subscriptions() {
+        use crate::object::{analog::AnalogValue, Device, PropertyValue};
+
+        let database = ObjectDatabase::new(Device::new(15, String::from("Device")));
+        database
+            .add_object(Box::new(AnalogValue::new(1, String::from("AV-1"))))
+            .unwrap();
+        let av = ObjectIdentifier::new(ObjectType::AnalogValue, 1);
+        let subscriber = ObjectIdentifier::new(ObjectType::Device, 99);
+
+        // Subscribe to Present_Value with a client increment of 2.0
+        let mut request = SubscribeCovPropertyRequest::new(
+            3,
+            av,
+            PropertyReference::new(u32::from(PropertyIdentifier::PresentValue)),
+        )
+        .with_cov_increment(2.0);
+        request.lifetime = Some(0);
+        let mut service_data = Vec::new();
+        request.encode(&mut service_data).unwrap();
+        assert!(matches!(
+            handle_subscribe_cov_property(&database, subscriber, 1, &service_data),
+            Apdu::SimpleAck {
+                service_choice: 28,
+                ..
+            }
+        ));
+        assert_eq!(database.cov_notifications().len(), 1);
+
+        database
+            .set_property(
+                av,
+                PropertyIdentifier::PresentValue,
+                PropertyValue::Real(1.0),
+            )
+            .unwrap();
+        assert!(database.cov_notifications().is_empty());
+        database
+            .set_property(
+                av,
+                PropertyIdentifier::PresentValue,
+                PropertyValue::Real(2.5),
+            )
+            .unwrap();
+        assert_eq!(database.cov_notifications().len(), 1);
+
+        // One unreadable property fails the whole multiple request
+        let mut multiple = SubscribeCovPropertyMultipleRequest::new(
+            4,
+            vec![CovSubscriptionSpecification::new(
+                av,
+                vec![
+                    CovReference::new(PropertyReference::new(u32::from(
+                        PropertyIdentifier::Description,
+                    ))),
+                    CovReference::new(PropertyReference::new(u32::from(
+                        PropertyIdentifier::FileSize,
+                    ))),
+                ],
+            )],
+        );
+        multiple.lifetime = Some(60);
+        let failure =
+            subscribe_cov_property_multiple(&database, subscriber, &multiple).unwrap_err();
+        assert_eq!(
+            failure.monitored_property.property_identifier,
+            u32::from(PropertyIdentifier::FileSize)
+        );
+        assert!(database.cov_notifications().is_empty());
+
+        multiple.list_of_cov_subscription_specifications[0]
+            .list_of_cov_references
+            .pop();
+        let mut service_data = Vec::new();
+        multiple.encode(&mut service_data).unwrap();
+        assert!(matches!(
+            handle_subscribe_cov_property_multiple(&database, subscriber, 2, &service_data),
+            Apdu::SimpleAck {
+                service_choice: 30,
+                ..
+            }
+        ));
+        assert_eq!(database.cov_notifications().len(), 1);
+    }
+}
@@ -0,0 +1,1768 @@
+//! ConfirmedEventNotification and UnconfirmedEventNotification Services
+//! (Clauses 13.8 and 13.9)
+//!
+//! Both services report an event state transition to a recipient process: the
+//! object and time of the transition, its notification class, priority and
+//! notify type, the states left and entered, and the event values
+//! (BACnetNotificationParameters) of the algorithm that detected it. Only the
+//! PDU differs; a confirmed notification is acknowledged with a SimpleAck.
+//! An event-initiating device builds notifications from an
+//! [`EventStateChange`] with [`EventNotificationRequest::from_state_change`]
+//! and [`NotificationParameters::for_state_change`]; a recipient decodes them
+//! with [`EventNotificationRequest::decode`].
+
+use super::read_property::{decode_property_value, encode_property_value};
+use super::{
+    BacnetDateTime, BacnetPropertyValue, ConfirmedServiceChoice, UnconfirmedServiceChoice,
+};
+use crate::app::{Apdu, MaxApduSize, MaxSegments};
+use crate::encoding::{
+    advanced::context::{encode_closing_tag, encode_opening_tag},
+    decode_application_tag, decode_context_boolean, decode_context_enumerated,
+    decode_context_object_id, decode_context_real, decode_context_tag, decode_context_unsigned,
+    encode_application_tag, encode_context_boolean, encode_context_enumerated,
+    encode_context_object_id, encode_context_real, encode_context_tag, encode_context_unsigned,
+    ApplicationTag, EncodingError, Result as EncodingResult,
+};
+use crate::object::{
+    CovCriteria, Date, DeviceObjectPropertyReference, DeviceObjectReference, EventParameters,
+    EventState, EventStateChange, EventTransition, EventType, NotificationClass, NotifyType,
+    ObjectIdentifier, ObjectType, PropertyIdentifier, PropertyValue, ReceivedNotification,
+    Recipient, Reliability, Time,
+};
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+/// When an event occurred (BACnetTimeStamp)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeStamp {
+    /// Time of day
+    Time(Time),
+    /// Sequence number
+    SequenceNumber(u32),
+    /// Date and time
+    DateTime(BacnetDateTime),
+}
+
+impl From<BacnetDateTime> for TimeStamp {
+    fn from(date_time: BacnetDateTime) -> Self {
+        TimeStamp::DateTime(date_time)
+    }
+}
+
+impl TimeStamp {
+    /// Encode the time stamp choice
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        match self {
+            // Time - context tag 0
+            TimeStamp::Time(time) => encode_context_value(buffer, &PropertyValue::Time(*time), 0),
+            // Sequence number - context tag 1
+            TimeStamp::SequenceNumber(number) => {
+                buffer.extend_from_slice(&encode_context_unsigned(*number, 1)?);
+                Ok(())
+            }
+            // Date and time - context tag 2
+            TimeStamp::DateTime(date_time) => encode_date_time(buffer, date_time, 2),
+        }
+    }
+
+    /// Decode a time stamp choice, returning it and the number of bytes consumed
+    pub fn decode(data: &[u8]) -> EncodingResult<(Self, usize)> {
+        let mut reader = Reader::new(data);
+        let time_stamp = reader.time_stamp()?;
+        Ok((time_stamp, reader.pos))
+    }
+}
+
+/// A value of one of the state enumerations (BACnetPropertyStates)
+///
+/// `choice` is the context tag naming the enumeration, such as 0 for
+/// boolean-value, 1 for binary-value or 11 for unsigned-value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PropertyStates {
+    /// Which enumeration the value belongs to
+    pub choice: u8,
+    /// The state
+    pub value: u32,
+}
+
+impl PropertyStates {
+    /// Create a property state
+    pub fn new(choice: u8, value: u32) -> Self {
+        Self { choice, value }
+    }
+
+    /// The property state for a monitored Present_Value
+    ///
+    /// Booleans map to boolean-value, enumerations to binary-value and
+    /// unsigned values to unsigned-value (the multi-state objects).
+    pub fn from_property_value(value: &PropertyValue) -> Option<Self> {
+        match value {
+            PropertyValue::Boolean(value) => Some(Self::new(0, u32::from(*value))),
+            PropertyValue::Enumerated(value) => Some(Self::new(1, *value)),
+            PropertyValue::UnsignedInteger(value) => Some(Self::new(11, *value)),
+            _ => None,
+        }
+    }
+
+    /// Encode the property state; booleans and enumerations share the
+    /// unsigned encoding
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        let encoded = encode_context_unsigned(self.value, 0)?;
+        if self.choice <= 14 {
+            buffer.push(encoded[0] | (self.choice << 4));
+        } else {
+            // Extended tag number in the octet after the tag
+            buffer.extend_from_slice(&[0xF0 | encoded[0], self.choice]);
+        }
+        buffer.extend_from_slice(&encoded[1..]);
+        Ok(())
+    }
+
+    /// Decode a property state, returning it and the number of bytes consumed
+    pub fn decode(data: &[u8]) -> EncodingResult<(Self, usize)> {
+        let tag = *data.first().ok_or(EncodingError::UnexpectedEndOfData)?;
+        if tag & 0x0F >= 0x0E || tag & 0x08 == 0 {
+            return Err(EncodingError::InvalidTag);
+        }
+        let (choice, header) = if tag >> 4 == 15 {
+            let choice = *data.get(1).ok_or(EncodingError::UnexpectedEndOfData)?;
+            (choice, 2)
+        } else {
+            (tag >> 4, 1)
+        };
+        let mut rebased = vec![tag & 0x0F];
+        rebased.extend_from_slice(&data[header..]);
+        let (value, consumed) = decode_context_unsigned(&rebased, 0)?;
+        Ok((Self::new(choice, value), consumed - 1 + header))
+    }
+}
+
+/// New value reported by the CHANGE_OF_VALUE algorithm
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChangedValue {
+    /// A bit string that changed in its masked bits
+    Bits(Vec<bool>),
+    /// A numeric value that changed by at least the increment
+    Real(f32),
+}
+
+/// Event values of a notification (BACnetNotificationParameters)
+///
+/// One variant per event algorithm; the choice tag of each is its
+/// [`EventType`]. Status flags are in BACnetStatusFlags order.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NotificationParameters {
+    ChangeOfBitstring {
+        referenced_bitstring: Vec<bool>,
+        status_flags: Vec<bool>,
+    },
+    ChangeOfState {
+        new_state: PropertyStates,
+        status_flags: Vec<bool>,
+    },
+    ChangeOfValue {
+        new_value: ChangedValue,
+        status_flags: Vec<bool>,
+    },
+    CommandFailure {
+        command_value: PropertyValue,
+        status_flags: Vec<bool>,
+        feedback_value: PropertyValue,
+    },
+    FloatingLimit {
+        reference_value: f32,
+        status_flags: Vec<bool>,
+        setpoint_value: f32,
+        error_limit: f32,
+    },
+    OutOfRange {
+        exceeding_value: f32,
+        status_flags: Vec<bool>,
+        deadband: f32,
+        exceeded_limit: f32,
+    },
+    ComplexEventType {
+        values: Vec<BacnetPropertyValue>,
+    },
+    ChangeOfLifeSafety {
+        new_state: u32,
+        new_mode: u32,
+        status_flags: Vec<bool>,
+        operation_expected: u32,
+    },
+    /// Vendor-defined algorithm; the parameters are application-tagged values
+    Extended {
+        vendor_id: u16,
+        extended_event_type: u32,
+        parameters: Vec<PropertyValue>,
+    },
+    BufferReady {
+        buffer_property: DeviceObjectPropertyReference,
+        previous_notification: u32,
+        current_notification: u32,
+    },
+    UnsignedRange {
+        exceeding_value: u32,
+        status_flags: Vec<bool>,
+        exceeded_limit: u32,
+    },
+    /// Access event; the optional authentication factor is not carried
+    AccessEvent {
+        access_event: u32,
+        status_flags: Vec<bool>,
+        access_event_tag: u32,
+        access_event_time: TimeStamp,
+        access_credential: DeviceObjectReference,
+    },
+    DoubleOutOfRange {
+        exceeding_value: f64,
+        status_flags: Vec<bool>,
+        deadband: f64,
+        exceeded_limit: f64,
+    },
+    SignedOutOfRange {
+        exceeding_value: i32,
+        status_flags: Vec<bool>,
+        deadband: u32,
+        exceeded_limit: i32,
+    },
+    UnsignedOutOfRange {
+        exceeding_value: u32,
+        status_flags: Vec<bool>,
+        deadband: u32,
+        exceeded_limit: u32,
+    },
+    ChangeOfCharacterstring {
+        changed_value: String,
+        status_flags: Vec<bool>,
+        alarm_value: String,
+    },
+    ChangeOfStatusFlags {
+        present_value: Option<PropertyValue>,
+        referenced_flags: Vec<bool>,
+    },
+    ChangeOfReliability {
+        reliability: Reliability,
+        status_flags: Vec<bool>,
+        property_values: Vec<BacnetPropertyValue>,
+    },
+    ChangeOfDiscreteValue {
+        new_value: PropertyValue,
+        status_flags: Vec<bool>,
+    },
+    ChangeOfTimer {
+        new_state: u32,
+        status_flags: Vec<bool>,
+        update_time: BacnetDateTime,
+        last_state_change: Option<u32>,
+        initial_timeout: Option<u32>,
+        expiration_time: Option<BacnetDateTime>,
+    },
+}
+
+impl NotificationParameters {
+    /// The event algorithm these values belong to
+    pub fn event_type(&self) -> EventType {
+        match self {
+            NotificationParameters::ChangeOfBitstring { .. } => EventType::ChangeOfBitstring,
+            NotificationParameters::ChangeOfState { .. } => EventType::ChangeOfState,
+            NotificationParameters::ChangeOfValue { .. } => EventType::ChangeOfValue,
+            NotificationParameters::CommandFailure { .. } => EventType::CommandFailure,
+            NotificationParameters::FloatingLimit { .. } => EventType::FloatingLimit,
+            NotificationParameters::OutOfRange { .. } => EventType::OutOfRange,
+            NotificationParameters::ComplexEventType { .. } => EventType::ComplexEventType,
+            NotificationParameters::ChangeOfLifeSafety { .. } => EventType::ChangeOfLifeSafety,
+            NotificationParameters::Extended { .. } => EventType::Extended,
+            NotificationParameters::BufferReady { .. } => EventType::BufferReady,
+            NotificationParameters::UnsignedRange { .. } => EventType::UnsignedRange,
+            NotificationParameters::AccessEvent { .. } => EventType::AccessEvent,
+            NotificationParameters::DoubleOutOfRange { .. } => EventType::DoubleOutOfRange,
+            NotificationParameters::SignedOutOfRange { .. } => EventType::SignedOutOfRange,
+            NotificationParameters::UnsignedOutOfRange { .. } => EventType::UnsignedOutOfRange,
+            NotificationParameters::ChangeOfCharacterstring { .. } => {
+                EventType::ChangeOfCharacterstring
+            }
+            NotificationParameters::ChangeOfStatusFlags { .. } => EventType::ChangeOfStatusFlags,
+            NotificationParameters::ChangeOfReliability { .. } => EventType::ChangeOfReliability,
+            NotificationParameters::ChangeOfDiscreteValue { .. } => {
+                EventType::ChangeOfDiscreteValue
+            }
+            NotificationParameters::ChangeOfTimer { .. } => EventType::ChangeOfTimer,
+        }
+    }
+
+    /// Build the event values for a transition detected by an Event Enrollment
+    ///
+    /// `auxiliary` is the feedback or setpoint value the algorithm read and
+    /// `status_flags` those of the event-initiating object. Returns `None` if
+    /// the monitored value does not fit the algorithm.
+    pub fn for_state_change(
+        parameters: &EventParameters,
+        change: &EventStateChange,
+        auxiliary: Option<&PropertyValue>,
+        status_flags: Vec<bool>,
+    ) -> Option<Self> {
+        let monitored = &change.monitored_value;
+        // A return to normal reports the limit that was exceeded
+        let limit_state = if change.to_state == EventState::Normal {
+            change.from_state
+        } else {
+            change.to_state
+        };
+
+        Some(match parameters {
+            EventParameters::ChangeOfBitstring { .. } => match monitored {
+                PropertyValue::BitString(bits) => NotificationParameters::ChangeOfBitstring {
+                    referenced_bitstring: bits.clone(),
+                    status_flags,
+                },
+                _ => return None,
+            },
+            EventParameters::ChangeOfState { .. } => NotificationParameters::ChangeOfState {
+                new_state: PropertyStates::from_property_value(monitored)?,
+                status_flags,
+            },
+            EventParameters::ChangeOfValue { criteria, .. } => {
+                let new_value = match (criteria, monitored) {
+                    (CovCriteria::Bitmask(_), PropertyValue::BitString(bits)) => {
+                        ChangedValue::Bits(bits.clone())
+                    }
+                    (CovCriteria::ReferencedPropertyIncrement(_), value) => {
+                        ChangedValue::Real(real_value(value)?)
+                    }
+                    _ => return None,
+                };
+                NotificationParameters::ChangeOfValue {
+                    new_value,
+                    status_flags,
+                }
+            }
+            EventParameters::CommandFailure { .. } => NotificationParameters::CommandFailure {
+                command_value: monitored.clone(),
+                status_flags,
+                feedback_value: auxiliary.cloned().unwrap_or(PropertyValue::Null),
+            },
+            EventParameters::FloatingLimit {
+                low_diff_limit,
+                high_diff_limit,
+                ..
+            } => {
+                let reference_value = real_value(monitored)?;
+                let setpoint_value = real_value(auxiliary?)?;
+                let error_limit = match limit_state {
+                    EventState::LowLimit => *low_diff_limit,
+                    EventState::HighLimit => *high_diff_limit,
+                    _ if reference_value < setpoint_value => *low_diff_limit,
+                    _ => *high_diff_limit,
+                };
+                NotificationParameters::FloatingLimit {
+                    reference_value,
+                    status_flags,
+                    setpoint_value,
+                    error_limit,
+                }
+            }
+            EventParameters::OutOfRange {
+                low_limit,
+                high_limit,
+                deadband,
+                ..
+            } => {
+                let exceeding_value = real_value(monitored)?;
+                let exceeded_limit = match limit_state {
+                    EventState::LowLimit => *low_limit,
+                    EventState::HighLimit => *high_limit,
+                    _ if exceeding_value < (low_limit + high_limit) / 2.0 => *low_limit,
+                    _ => *high_limit,
+                };
+                NotificationParameters::OutOfRange {
+                    exceeding_value,
+                    status_flags,
+                    deadband: *deadband,
+                    exceeded_limit,
+                }
+            }
+        })
+    }
+
+    /// Encode the event values choice
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        let choice = self.event_type() as u8;
+        encode_opening_tag(buffer, choice)?;
+        match self {
+            NotificationParameters::ChangeOfBitstring {
+                referenced_bitstring,
+                status_flags,
+            } => {
+                encode_bits(buffer, referenced_bitstring, 0)?;
+                encode_bits(buffer, status_flags, 1)?;
+            }
+            NotificationParameters::ChangeOfState {
+                new_state,
+                status_flags,
+            } => {
+                encode_opening_tag(buffer, 0)?;
+                new_state.encode(buffer)?;
+                encode_closing_tag(buffer, 0)?;
+                encode_bits(buffer, status_flags, 1)?;
+            }
+            NotificationParameters::ChangeOfValue {
+                new_value,
+                status_flags,
+            } => {
+                encode_opening_tag(buffer, 0)?;
+                match new_value {
+                    ChangedValue::Bits(bits) => encode_bits(buffer, bits, 0)?,
+                    ChangedValue::Real(value) => {
+                        buffer.extend_from_slice(&encode_context_real(*value, 1)?)
+                    }
+                }
+                encode_closing_tag(buffer, 0)?;
+                encode_bits(buffer, status_flags, 1)?;
+            }
+            NotificationParameters::CommandFailure {
+                command_value,
+                status_flags,
+                feedback_value,
+            } => {
+                encode_abstract_value(buffer, command_value, 0)?;
+                encode_bits(buffer, status_flags, 1)?;
+                encode_abstract_value(buffer, feedback_value, 2)?;
+            }
+            NotificationParameters::FloatingLimit {
+                reference_value,
+                status_flags,
+                setpoint_value,
+                error_limit,
+            } => {
+                buffer.extend_from_slice(&encode_context_real(*reference_value, 0)?);
+                encode_bits(buffer, status_flags, 1)?;
+                buffer.extend_from_slice(&encode_context_real(*setpoint_value, 2)?);
+                buffer.extend_from_slice(&encode_context_real(*error_limit, 3)?);
+            }
+            NotificationParameters::OutOfRange {
+                exceeding_value,
+                status_flags,
+                deadband,
+                exceeded_limit,
+            } => {
+                buffer.extend_from_slice(&encode_context_real(*exceeding_value, 0)?);
+                encode_bits(buffer, status_flags, 1)?;
+                buffer.extend_from_slice(&encode_context_real(*deadband, 2)?);
+                buffer.extend_from_slice(&encode_context_real(*exceeded_limit, 3)?);
+            }
+            NotificationParameters::ComplexEventType { values } => {
+                for value in values {
+                    value.encode(buffer)?;
+                }
+            }
+            NotificationParameters::ChangeOfLifeSafety {
+                new_state,
+                new_mode,
+                status_flags,
+                operation_expected,
+            } => {
+                buffer.extend_from_slice(&encode_context_enumerated(*new_state, 0)?);
+                buffer.extend_from_slice(&encode_context_enumerated(*new_mode, 1)?);
+                encode_bits(buffer, status_flags, 2)?;
+                buffer.extend_from_slice(&encode_context_enumerated(*operation_expected, 3)?);
+            }
+            NotificationParameters::Extended {
+                vendor_id,
+                extended_event_type,
+                parameters,
+            } => {
+                buffer.extend_from_slice(&encode_context_unsigned(u32::from(*vendor_id), 0)?);
+                buffer.extend_from_slice(&encode_context_unsigned(*extended_event_type, 1)?);
+                encode_opening_tag(buffer, 2)?;
+                for parameter in parameters {
+                    encode_property_value(buffer, parameter)?;
+                }
+                encode_closing_tag(buffer, 2)?;
+            }
+            NotificationParameters::BufferReady {
+                buffer_property,
+                previous_notification,
+                current_notification,
+            } => {
+                encode_opening_tag(buffer, 0)?;
+                encode_device_object_property_reference(buffer, buffer_property)?;
+                encode_closing_tag(buffer, 0)?;
+                buffer.extend_from_slice(&encode_context_unsigned(*previous_notification, 1)?);
+                buffer.extend_from_slice(&encode_context_unsigned(*current_notification, 2)?);
+            }
+            NotificationParameters::UnsignedRange {
+                exceeding_value,
+                status_flags,
+                exceeded_limit,
+            } => {
+                buffer.extend_from_slice(&encode_context_unsigned(*exceeding_value, 0)?);
+                encode_bits(buffer, status_flags, 1)?;
+                buffer.extend_from_slice(&encode_context_unsigned(*exceeded_limit, 2)?);
+            }
+            NotificationParameters::AccessEvent {
+                access_event,
+                status_flags,
+                access_event_tag,
+                access_event_time,
+                access_credential,
+            } => {
+                buffer.extend_from_slice(&encode_context_enumerated(*access_event, 0)?);
+                encode_bits(buffer, status_flags, 1)?;
+                buffer.extend_from_slice(&encode_context_unsigned(*access_event_tag, 2)?);
+                encode_opening_tag(buffer, 3)?;
+                access_event_time.encode(buffer)?;
+                encode_closing_tag(buffer, 3)?;
+                encode_opening_tag(buffer, 4)?;
+                encode_device_object_reference(buffer, access_credential)?;
+                encode_closing_tag(buffer, 4)?;
+            }
+            NotificationParameters::DoubleOutOfRange {
+                exceeding_value,
+                status_flags,
+                deadband,
+                exceeded_limit,
+            } => {
+                encode_context_value(buffer, &PropertyValue::Double(*exceeding_value), 0)?;
+                encode_bits(buffer, status_flags, 1)?;
+                encode_context_value(buffer, &PropertyValue::Double(*deadband), 2)?;
+                encode_context_value(buffer, &PropertyValue::Double(*exceeded_limit), 3)?;
+            }
+            NotificationParameters::SignedOutOfRange {
+                exceeding_value,
+                status_flags,
+                deadband,
+                exceeded_limit,
+            } => {
+                encode_context_value(buffer, &PropertyValue::SignedInt(*exceeding_value), 0)?;
+                encode_bits(buffer, status_flags, 1)?;
+                buffer.extend_from_slice(&encode_context_unsigned(*deadband, 2)?);
+                encode_context_value(buffer, &PropertyValue::SignedInt(*exceeded_limit), 3)?;
+            }
+            NotificationParameters::UnsignedOutOfRange {
+                exceeding_value,
+                status_flags,
+                deadband,
+                exceeded_limit,
+            } => {
+                buffer.extend_from_slice(&encode_context_unsigned(*exceeding_value, 0)?);
+                encode_bits(buffer, status_flags, 1)?;
+                buffer.extend_from_slice(&encode_context_unsigned(*deadband, 2)?);
+                buffer.extend_from_slice(&encode_context_unsigned(*exceeded_limit, 3)?);
+            }
+            NotificationParameters::ChangeOfCharacterstring {
+                changed_value,
+                status_flags,
+                alarm_value,
+            } => {
+                encode_context_string(buffer, changed_value, 0)?;
+                encode_bits(buffer, status_flags, 1)?;
+                encode_context_string(buffer, alarm_value, 2)?;
+            }
+            NotificationParameters::ChangeOfStatusFlags {
+                present_value,
+                referenced_flags,
+            } => {
+                if let Some(present_value) = present_value {
+                    encode_abstract_value(buffer, present_value, 0)?;
+                }
+                encode_bits(buffer, referenced_flags, 1)?;
+            }
+            NotificationParameters::ChangeOfReliability {
+                reliability,
+                status_flags,
+                property_values,
+            } => {
+                buffer.extend_from_slice(&encode_context_enumerated(*reliability as u32, 0)?);
+                encode_bits(buffer, status_flags, 1)?;
+                encode_opening_tag(buffer, 2)?;
+                for value in property_values {
+                    value.encode(buffer)?;
+                }
+                encode_closing_tag(buffer, 2)?;
+            }
+            NotificationParameters::ChangeOfDiscreteValue {
+                new_value,
+                status_flags,
+            } => {
+                encode_abstract_value(buffer, new_value, 0)?;
+                encode_bits(buffer, status_flags, 1)?;
+            }
+            NotificationParameters::ChangeOfTimer {
+                new_state,
+                status_flags,
+                update_time,
+                last_state_change,
+                initial_timeout,
+                expiration_time,
+            } => {
+                buffer.extend_from_slice(&encode_context_enumerated(*new_state, 0)?);
+                encode_bits(buffer, status_flags, 1)?;
+                encode_date_time(buffer, update_time, 2)?;
+                if let Some(last_state_change) = last_state_change {
+                    buffer.extend_from_slice(&encode_context_enumerated(*last_state_change, 3)?);
+                }
+                if let Some(initial_timeout) = initial_timeout {
+                    buffer.extend_from_slice(&encode_context_unsigned(*initial_timeout, 4)?);
+                }
+                if let Some(expiration_time) = expiration_time {
+                    encode_date_time(buffer, expiration_time, 5)?;
+                }
+            }
+        }
+        encode_closing_tag(buffer, choice)
+    }
+
+    /// Decode an event values choice, returning it and the number of bytes
+    /// consumed
+    pub fn decode(data: &[u8]) -> EncodingResult<(Self, usize)> {
+        let tag = *data.first().ok_or(EncodingError::UnexpectedEndOfData)?;
+        if tag & 0x0F != 0x0E {
+            return Err(EncodingError::InvalidTag);
+        }
+        let choice = match tag >> 4 {
+            15 => *data.get(1).ok_or(EncodingError::UnexpectedEndOfData)?,
+            choice => choice,
+        };
+        let event_type =
+            EventType::try_from(u32::from(choice)).map_err(|_| EncodingError::InvalidTag)?;
+
+        let mut reader = Reader::new(data);
+        reader.open(choice)?;
+        let parameters = match event_type {
+            EventType::ChangeOfBitstring => NotificationParameters::ChangeOfBitstring {
+                referenced_bitstring: reader.bits(0)?,
+                status_flags: reader.bits(1)?,
+            },
+            EventType::ChangeOfState => {
+                reader.open(0)?;
+                let new_state = reader.advance(PropertyStates::decode(reader.rest())?);
+                reader.close(0)?;
+                NotificationParameters::ChangeOfState {
+                    new_state,
+                    status_flags: reader.bits(1)?,
+                }
+            }
+            EventType::ChangeOfValue => {
+                reader.open(0)?;
+                let new_value = if reader.is_context(0) {
+                    ChangedValue::Bits(reader.bits(0)?)
+                } else {
+                    ChangedValue::Real(reader.real(1)?)
+                };
+                reader.close(0)?;
+                NotificationParameters::ChangeOfValue {
+                    new_value,
+                    status_flags: reader.bits(1)?,
+                }
+            }
+            EventType::CommandFailure => NotificationParameters::CommandFailure {
+                command_value: reader.abstract_value(0)?,
+                status_flags: reader.bits(1)?,
+                feedback_value: reader.abstract_value(2)?,
+            },
+            EventType::FloatingLimit => NotificationParameters::FloatingLimit {
+                reference_value: reader.real(0)?,
+                status_flags: reader.bits(1)?,
+                setpoint_value: reader.real(2)?,
+                error_limit: reader.real(3)?,
+            },
+            EventType::OutOfRange => NotificationParameters::OutOfRange {
+                exceeding_value: reader.real(0)?,
+                status_flags: reader.bits(1)?,
+                deadband: reader.real(2)?,
+                exceeded_limit: reader.real(3)?,
+            },
+            EventType::ComplexEventType => {
+                let mut values = Vec::new();
+                while !reader.at_close(choice) {
+                    values.push(reader.advance(BacnetPropertyValue::decode(reader.rest())?));
+                }
+                NotificationParameters::ComplexEventType { values }
+            }
+            EventType::ChangeOfLifeSafety => NotificationParameters::ChangeOfLifeSafety {
+                new_state: reader.enumerated(0)?,
+                new_mode: reader.enumerated(1)?,
+                status_flags: reader.bits(2)?,
+                operation_expected: reader.enumerated(3)?,
+            },
+            EventType::Extended => {
+                let vendor_id = u16::try_from(reader.unsigned(0)?)
+                    .map_err(|_| EncodingError::ValueOutOfRange)?;
+                let extended_event_type = reader.unsigned(1)?;
+                reader.open(2)?;
+                let parameters = match reader.advance(decode_property_value(reader.rest())?) {
+                    PropertyValue::Array(items) => items,
+                    item => vec![item],
+                };
+                reader.close(2)?;
+                NotificationParameters::Extended {
+                    vendor_id,
+                    extended_event_type,
+                    parameters,
+                }
+            }
+            EventType::BufferReady => {
+                reader.open(0)?;
+                let buffer_property = reader.device_object_property_reference()?;
+                reader.close(0)?;
+                NotificationParameters::BufferReady {
+                    buffer_property,
+                    previous_notification: reader.unsigned(1)?,
+                    current_notification: reader.unsigned(2)?,
+                }
+            }
+            EventType::UnsignedRange => NotificationParameters::UnsignedRange {
+                exceeding_value: reader.unsigned(0)?,
+                status_flags: reader.bits(1)?,
+                exceeded_limit: reader.unsigned(2)?,
+            },
+            EventType::AccessEvent => {
+                let access_event = reader.enumerated(0)?;
+                let status_flags = reader.bits(1)?;
+                let access_event_tag = reader.unsigned(2)?;
+                reader.open(3)?;
+                let access_event_time = reader.time_stamp()?;
+                reader.close(3)?;
+                reader.open(4)?;
+                let access_credential = reader.device_object_reference()?;
+                reader.close(4)?;
+                NotificationParameters::AccessEvent {
+                    access_event,
+                    status_flags,
+                    access_event_tag,
+                    access_event_time,
+                    access_credential,
+                }
+            }
+            EventType::DoubleOutOfRange => NotificationParameters::DoubleOutOfRange {
+                exceeding_value: reader.double(0)?,
+                status_flags: reader.bits(1)?,
+                deadband: reader.double(2)?,
+                exceeded_limit: reader.double(3)?,
+            },
+            EventType::SignedOutOfRange => NotificationParameters::SignedOutOfRange {
+                exceeding_value: reader.signed(0)?,
+                status_flags: reader.bits(1)?,
+                deadband: reader.unsigned(2)?,
+                exceeded_limit: reader.signed(3)?,
+            },
+            EventType::UnsignedOutOfRange => NotificationParameters::UnsignedOutOfRange {
+                exceeding_value: reader.unsigned(0)?,
+                status_flags: reader.bits(1)?,
+                deadband: reader.unsigned(2)?,
+                exceeded_limit: reader.unsigned(3)?,
+            },
+            EventType::ChangeOfCharacterstring => NotificationParameters::ChangeOfCharacterstring {
+                changed_value: reader.string(0)?,
+                status_flags: reader.bits(1)?,
+                alarm_value: reader.string(2)?,
+            },
+            EventType::ChangeOfStatusFlags => NotificationParameters::ChangeOfStatusFlags {
+                present_value: if reader.is_context(0) {
+                    Some(reader.abstract_value(0)?)
+                } else {
+                    None
+                },
+                referenced_flags: reader.bits(1)?,
+            },
+            EventType::ChangeOfReliability => {
+                let reliability = Reliability::try_from(reader.enumerated(0)?)
+                    .map_err(|_| EncodingError::ValueOutOfRange)?;
+                let status_flags = reader.bits(1)?;
+                reader.open(2)?;
+                let mut property_values = Vec::new();
+                while !reader.at_close(2) {
+                    property_values
+                        .push(reader.advance(BacnetPropertyValue::decode(reader.rest())?));
+                }
+                reader.close(2)?;
+                NotificationParameters::ChangeOfReliability {
+                    reliability,
+                    status_flags,
+                    property_values,
+                }
+            }
+            EventType::ChangeOfDiscreteValue => NotificationParameters::ChangeOfDiscreteValue {
+                new_value: reader.abstract_value(0)?,
+                status_flags: reader.bits(1)?,
+            },
+            EventType::ChangeOfTimer => NotificationParameters::ChangeOfTimer {
+                new_state: reader.enumerated(0)?,
+                status_flags: reader.bits(1)?,
+                update_time: reader.date_time(2)?,
+                last_state_change: reader.optional(3, Reader::enumerated)?,
+                initial_timeout: reader.optional(4, Reader::unsigned)?,
+                expiration_time: reader.optional(5, Reader::date_time)?,
+            },
+            EventType::None => return Err(EncodingError::InvalidTag),
+        };
+        reader.close(choice)?;
+        Ok((parameters, reader.pos))
+    }
+}
+
+/// Event Notification request (confirmed or unconfirmed service)
+///
+/// `ack_required`, `from_state` and `event_values` are present in every
+/// notification except an ACK_NOTIFICATION.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventNotificationRequest {
+    /// Recipient process identifier
+    pub process_identifier: u32,
+    /// Initiating device identifier
+    pub initiating_device_identifier: ObjectIdentifier,
+    /// Object whose event state changed
+    pub event_object_identifier: ObjectIdentifier,
+    /// When the transition occurred
+    pub time_stamp: TimeStamp,
+    /// Notification class of the event-initiating object
+    pub notification_class: u32,
+    /// Priority of the notification
+    pub priority: u8,
+    /// Algorithm that detected the transition
+    pub event_type: EventType,
+    /// Message text (optional)
+    pub message_text: Option<String>,
+    /// Alarm, event or acknowledgment notification
+    pub notify_type: NotifyType,
+    /// Whether the transition must be acknowledged
+    pub ack_required: Option<bool>,
+    /// State before the transition
+    pub from_state: Option<EventState>,
+    /// State after the transition
+    pub to_state: EventState,
+    /// Algorithm-specific event values
+    pub event_values: Option<NotificationParameters>,
+}
+
+impl EventNotificationRequest {
+    /// Build the notification for a transition detected by an event-initiating
+    /// object
+    ///
+    /// Priority and Ack_Required come from the transition's entries in the
+    /// Notification Class; `process_identifier` is that of the recipient.
+    pub fn from_state_change(
+        process_identifier: u32,
+        initiating_device_identifier: ObjectIdentifier,
+        event_object_identifier: ObjectIdentifier,
+        time_stamp: TimeStamp,
+        change: &EventStateChange,
+        notification_class: &NotificationClass,
+        event_values: Option<NotificationParameters>,
+    ) -> Self {
+        Self {
+            process_identifier,
+            initiating_device_identifier,
+            event_object_identifier,
+            time_stamp,
+            notification_class: notification_class.notification_class(),
+            priority: notification_class.priority_for(change.transition),
+            event_type: change.event_type,
+            message_text: None,
+            notify_type: change.notify_type,
+            ack_required: Some(notification_class.is_ack_required(change.transition)),
+            from_state: Some(change.from_state),
+            to_state: change.to_state,
+            event_values,
+        }
+    }
+
+    /// Set the message text
+    pub fn with_message_text(mut self, message_text: impl Into<String>) -> Self {
+        self.message_text = Some(message_text.into());
+        self
+    }
+
+    /// The transition this notification reports
+    pub fn transition(&self) -> EventTransition {
+        EventTransition::for_state(self.to_state)
+    }
+
+    /// What a Notification Forwarder needs to route this notification
+    pub fn to_received_notification(
+        &self,
+        port_id: u8,
+        from_local_device: bool,
+    ) -> ReceivedNotification {
+        ReceivedNotification {
+            process_identifier: self.process_identifier,
+            transition: self.transition(),
+            port_id,
+            from_local_device,
+        }
+    }
+
+    /// Build the event notification APDU
+    ///
+    /// `invoke_id` is used only for a ConfirmedEventNotification.
+    pub fn to_apdu(&self, confirmed: bool, invoke_id: u8) -> EncodingResult<Apdu> {
+        let mut service_data = Vec::new();
+        self.encode(&mut service_data)?;
+        Ok(if confirmed {
+            Apdu::ConfirmedRequest {
+                segmented: false,
+                more_follows: false,
+                segmented_response_accepted: false,
+                max_segments: MaxSegments::Unspecified,
+                max_response_size: MaxApduSize::Up1476,
+                invoke_id,
+                sequence_number: None,
+                proposed_window_size: None,
+                service_choice: ConfirmedServiceChoice::ConfirmedEventNotification,
+                service_data,
+            }
+        } else {
+            Apdu::UnconfirmedRequest {
+                service_choice: UnconfirmedServiceChoice::UnconfirmedEventNotification,
+                service_data,
+            }
+        })
+    }
+
+    /// Encode the Event Notification request
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        // Process identifier - context tag 0
+        buffer.extend_from_slice(&encode_context_unsigned(self.process_identifier, 0)?);
+
+        // Initiating device identifier - context tag 1
+        encode_identifier(buffer, &self.initiating_device_identifier, 1)?;
+
+        // Event object identifier - context tag 2
+        encode_identifier(buffer, &self.event_object_identifier, 2)?;
+
+        // Time stamp - context tag 3
+        encode_opening_tag(buffer, 3)?;
+        self.time_stamp.encode(buffer)?;
+        encode_closing_tag(buffer, 3)?;
+
+        // Notification class and priority - context tags 4 and 5
+        buffer.extend_from_slice(&encode_context_unsigned(self.notification_class, 4)?);
+        buffer.extend_from_slice(&encode_context_unsigned(u32::from(self.priority), 5)?);
+
+        // Event type - context tag 6
+        buffer.extend_from_slice(&encode_context_enumerated(self.event_type as u32, 6)?);
+
+        // Message text - context tag 7 (optional)
+        if let Some(message_text) = &self.message_text {
+            encode_context_string(buffer, message_text, 7)?;
+        }
+
+        // Notify type - context tag 8
+        buffer.extend_from_slice(&encode_context_enumerated(self.notify_type as u32, 8)?);
+
+        // Ack required - context tag 9
+        if let Some(ack_required) = self.ack_required {
+            buffer.extend_from_slice(&encode_context_boolean(ack_required, 9)?);
+        }
+
+        // From state - context tag 10
+        if let Some(from_state) = self.from_state {
+            buffer.extend_from_slice(&encode_context_enumerated(from_state as u32, 10)?);
+        }
+
+        // To state - context tag 11
+        buffer.extend_from_slice(&encode_context_enumerated(self.to_state as u32, 11)?);
+
+        // Event values - context tag 12
+        if let Some(event_values) = &self.event_values {
+            encode_opening_tag(buffer, 12)?;
+            event_values.encode(buffer)?;
+            encode_closing_tag(buffer, 12)?;
+        }
+
+        Ok(())
+    }
+
+    /// Decode an Event Notification request
+    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
+        let mut reader = Reader::new(data);
+        let process_identifier = reader.unsigned(0)?;
+        let initiating_device_identifier = reader.identifier(1)?;
+        let event_object_identifier = reader.identifier(2)?;
+        reader.open(3)?;
+        let time_stamp = reader.time_stamp()?;
+        reader.close(3)?;
+        let notification_class = reader.unsigned(4)?;
+        let priority =
+            u8::try_from(reader.unsigned(5)?).map_err(|_| EncodingError::ValueOutOfRange)?;
+        let event_type = EventType::try_from(reader.enumerated(6)?)
+            .map_err(|_| EncodingError::ValueOutOfRange)?;
+        let message_text = reader.optional(7, Reader::string)?;
+        let notify_type = NotifyType::try_from(reader.enumerated(8)?)
+            .map_err(|_| EncodingError::ValueOutOfRange)?;
+        let ack_required = reader.optional(9, Reader::boolean)?;
+        let from_state = reader
+            .optional(10, Reader::enumerated)?
+            .map(EventState::try_from)
+            .transpose()
+            .map_err(|_| EncodingError::ValueOutOfRange)?;
+        let to_state = EventState::try_from(reader.enumerated(11)?)
+            .map_err(|_| EncodingError::ValueOutOfRange)?;
+        let event_values = if reader.is_context(12) {
+            reader.open(12)?;
+            let event_values = reader.advance(NotificationParameters::decode(reader.rest())?);
+            reader.close(12)?;
+            Some(event_values)
+        } else {
+            None
+        };
+
+        if reader.pos != data.len() {
+            return Err(EncodingError::InvalidFormat(
+                "Unexpected data after Event Notification request".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            process_identifier,
+            initiating_device_identifier,
+            event_object_identifier,
+            time_stamp,
+            notification_class,
+            priority,
+            event_type,
+            message_text,
+            notify_type,
+            ack_required,
+            from_state,
+            to_state,
+            event_values,
+        })
+    }
+}
+
+/// An event notification due to one recipient of a Notification Class
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingEventNotification {
+    /// Who receives the notification
+    pub recipient: Recipient,
+    /// Send a ConfirmedEventNotification rather than the unconfirmed service
+    pub issue_confirmed_notifications: bool,
+    /// The notification, addressed to the recipient's process
+    pub notification: EventNotificationRequest,
+}
+
+impl PendingEventNotification {
+    /// Address a notification to each destination in the Recipient_List that
+    /// accepts its transition at `date` and `time`
+    pub fn for_recipients(
+        notification_class: &NotificationClass,
+        notification: &EventNotificationRequest,
+        date: &Date,
+        time: &Time,
+    ) -> Vec<Self> {
+        notification_class
+            .recipients_for(notification.transition(), date, time)
+            .map(|destination| Self {
+                recipient: destination.recipient.clone(),
+                issue_confirmed_notifications: destination.issue_confirmed_notifications,
+                notification: EventNotificationRequest {
+                    process_identifier: destination.process_identifier,
+                    ..notification.clone()
+                },
+            })
+            .collect()
+    }
+
+    /// Build the event notification APDU to send to the recipient
+    ///
+    /// `invoke_id` is used only for a ConfirmedEventNotification.
+    pub fn to_apdu(&self, invoke_id: u8) -> EncodingResult<Apdu> {
+        self.notification
+            .to_apdu(self.issue_confirmed_notifications, invoke_id)
+    }
+}
+
+fn real_value(value: &PropertyValue) -> Option<f32> {
+    match value {
+        PropertyValue::Real(value) => Some(*value),
+        PropertyValue::Double(value) => Some(*value as f32),
+        PropertyValue::UnsignedInteger(value) => Some(*value as f32),
+        PropertyValue::SignedInt(value) => Some(*value as f32),
+        _ => None,
+    }
+}
+
+/// Encode a primitive value with a context tag in place of its application tag
+pub(super) fn encode_context_value(
+    buffer: &mut Vec<u8>,
+    value: &PropertyValue,
+    tag_number: u8,
+) -> EncodingResult<()> {
+    match value {
+        // An application boolean carries its value in the tag; a context one
+        // in a content octet
+        PropertyValue::Boolean(value) => {
+            buffer.extend_from_slice(&encode_context_boolean(*value, tag_number)?);
+        }
+        PropertyValue::Array(_) | PropertyValue::List(_) => {
+            return Err(EncodingError::InvalidFormat(
+                "Constructed values cannot be context tagged".to_string(),
+            ))
+        }
+        _ => {
+            let mut encoded = Vec::new();
+            encode_property_value(&mut encoded, value)?;
+            let (_, length, consumed) = decode_application_tag(&encoded)?;
+            encode_context_tag(buffer, tag_number, length)?;
+            buffer.extend_from_slice(&encoded[consumed..]);
+        }
+    }
+    Ok(())
+}
+
+/// Decode a context-tagged primitive value of a known application type
+pub(super) fn decode_context_value(
+    data: &[u8],
+    tag_number: u8,
+    tag: ApplicationTag,
+) -> EncodingResult<(PropertyValue, usize)> {
+    if tag == ApplicationTag::Boolean {
+        let (value, consumed) = decode_context_boolean(data, tag_number)?;
+        return Ok((PropertyValue::Boolean(value), consumed));
+    }
+    let (found, length, consumed) = decode_context_tag(data)?;
+    if found != tag_number || data[0] & 0x07 >= 6 {
+        return Err(EncodingError::InvalidTag);
+    }
+    let content = data
+        .get(consumed..consumed + length)
+        .ok_or(EncodingError::BufferUnderflow)?;
+    let mut encoded = Vec::new();
+    encode_application_tag(&mut encoded, tag, length)?;
+    encoded.extend_from_slice(content);
+    let (value, _) = decode_property_value(&encoded)?;
+    Ok((value, consumed + length))
+}
+
+pub(super) fn encode_bits(
+    buffer: &mut Vec<u8>,
+    bits: &[bool],
+    tag_number: u8,
+) -> EncodingResult<()> {
+    encode_context_value(buffer, &PropertyValue::BitString(bits.to_vec()), tag_number)
+}
+
+fn encode_context_string(buffer: &mut Vec<u8>, value: &str, tag_number: u8) -> EncodingResult<()> {
+    encode_context_value(
+        buffer,
+        &PropertyValue::CharacterString(value.to_string()),
+        tag_number,
+    )
+}
+
+pub(super) fn encode_identifier(
+    buffer: &mut Vec<u8>,
+    identifier: &ObjectIdentifier,
+    tag_number: u8,
+) -> EncodingResult<()> {
+    buffer.extend_from_slice(&encode_context_object_id(
+        u16::from(identifier.object_type),
+        identifier.instance,
+        tag_number,
+    )?);
+    Ok(())
+}
+
+/// Encode an ABSTRACT-SYNTAX value as application-tagged data in a
+/// constructed context tag
+pub(super) fn encode_abstract_value(
+    buffer: &mut Vec<u8>,
+    value: &PropertyValue,
+    tag_number: u8,
+) -> EncodingResult<()> {
+    encode_opening_tag(buffer, tag_number)?;
+    encode_property_value(buffer, value)?;
+    encode_closing_tag(buffer, tag_number)
+}
+
+pub(super) fn encode_date_time(
+    buffer: &mut Vec<u8>,
+    date_time: &BacnetDateTime,
+    tag_number: u8,
+) -> EncodingResult<()> {
+    encode_opening_tag(buffer, tag_number)?;
+    date_time.encode(buffer)?;
+    encode_closing_tag(buffer, tag_number)
+}
+
+fn encode_device_object_property_reference(
+    buffer: &mut Vec<u8>,
+    reference: &DeviceObjectPropertyReference,
+) -> EncodingResult<()> {
+    encode_identifier(buffer, &reference.object_identifier, 0)?;
+    buffer.extend_from_slice(&encode_context_enumerated(
+        u32::from(reference.property_identifier),
+        1,
+    )?);
+    if let Some(index) = reference.property_array_index {
+        buffer.extend_from_slice(&encode_context_unsigned(index, 2)?);
+    }
+    if let Some(device) = &reference.device_identifier {
+        encode_identifier(buffer, device, 3)?;
+    }
+    Ok(())
+}
+
+fn encode_device_object_reference(
+    buffer: &mut Vec<u8>,
+    reference: &DeviceObjectReference,
+) -> EncodingResult<()> {
+    if let Some(device) = &reference.device_identifier {
+        encode_identifier(buffer, device, 0)?;
+    }
+    encode_identifier(buffer, &reference.object_identifier, 1)
+}
+
+fn constructed_tag(tag_number: u8, opening: bool) -> EncodingResult<Vec<u8>> {
+    let mut tag = Vec::new();
+    if opening {
+        encode_opening_tag(&mut tag, tag_number)?;
+    } else {
+        encode_closing_tag(&mut tag, tag_number)?;
+    }
+    Ok(tag)
+}
+
+/// Cursor over the context-tagged fields of event service data
+pub(super) struct Reader<'a> {
+    data: &'a [u8],
+    pub(super) pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub(super) fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    pub(super) fn rest(&self) -> &'a [u8] {
+        &self.data[self.pos..]
+    }
+
+    pub(super) fn advance<T>(&mut self, (value, consumed): (T, usize)) -> T {
+        self.pos += consumed;
+        value
+    }
+
+    /// Whether the next field is a primitive or opening tag numbered `tag_number`
+    ///
+    /// Tag numbers from 15 up use the extended form and never match.
+    pub(super) fn is_context(&self, tag_number: u8) -> bool {
+        tag_number < 15
+            && self
+                .data
+                .get(self.pos)
+                .is_some_and(|&tag| tag & 0x08 != 0 && tag & 0x07 != 0x07 && tag >> 4 == tag_number)
+    }
+
+    pub(super) fn at_open(&self, tag_number: u8) -> bool {
+        constructed_tag(tag_number, true).is_ok_and(|tag| self.rest().starts_with(&tag))
+    }
+
+    pub(super) fn at_close(&self, tag_number: u8) -> bool {
+        constructed_tag(tag_number, false).is_ok_and(|tag| self.rest().starts_with(&tag))
+    }
+
+    pub(super) fn expect(&mut self, tag_number: u8, opening: bool) -> EncodingResult<()> {
+        let tag = constructed_tag(tag_number, opening)?;
+        if self.rest().starts_with(&tag) {
+            self.pos += tag.len();
+            Ok(())
+        } else if self.rest().is_empty() {
+            Err(EncodingError::UnexpectedEndOfData)
+        } else {
+            Err(EncodingError::InvalidTag)
+        }
+    }
+
+    pub(super) fn open(&mut self, tag_number: u8) -> EncodingResult<()> {
+        self.expect(tag_number, true)
+    }
+
+    pub(super) fn close(&mut self, tag_number: u8) -> EncodingResult<()> {
+        self.expect(tag_number, false)
+    }
+
+    pub(super) fn optional<T>(
+        &mut self,
+        tag_number: u8,
+        read: fn(&mut Self, u8) -> EncodingResult<T>,
+    ) -> EncodingResult<Option<T>> {
+        if self.is_context(tag_number) {
+            read(self, tag_number).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    pub(super) fn unsigned(&mut self, tag_number: u8) -> EncodingResult<u32> {
+        Ok(self.advance(decode_context_unsigned(self.rest(), tag_number)?))
+    }
+
+    pub(super) fn enumerated(&mut self, tag_number: u8) -> EncodingResult<u32> {
+        Ok(self.advance(decode_context_enumerated(self.rest(), tag_number)?))
+    }
+
+    pub(super) fn boolean(&mut self, tag_number: u8) -> EncodingResult<bool> {
+        Ok(self.advance(decode_context_boolean(self.rest(), tag_number)?))
+    }
+
+    pub(super) fn real(&mut self, tag_number: u8) -> EncodingResult<f32> {
+        Ok(self.advance(decode_context_real(self.rest(), tag_number)?))
+    }
+
+    pub(super) fn identifier(&mut self, tag_number: u8) -> EncodingResult<ObjectIdentifier> {
+        let (object_type, instance) =
+            self.advance(decode_context_object_id(self.rest(), tag_number)?);
+        let object_type =
+            ObjectType::try_from(object_type).map_err(|_| EncodingError::ValueOutOfRange)?;
+        Ok(ObjectIdentifier::new(object_type, instance))
+    }
+
+    pub(super) fn value(
+        &mut self,
+        tag_number: u8,
+        tag: ApplicationTag,
+    ) -> EncodingResult<PropertyValue> {
+        Ok(self.advance(decode_context_value(self.rest(), tag_number, tag)?))
+    }
+
+    pub(super) fn bits(&mut self, tag_number: u8) -> EncodingResult<Vec<bool>> {
+        match self.value(tag_number, ApplicationTag::BitString)? {
+            PropertyValue::BitString(bits) => Ok(bits),
+            _ => Err(EncodingError::InvalidTag),
+        }
+    }
+
+    pub(super) fn double(&mut self, tag_number: u8) -> EncodingResult<f64> {
+        match self.value(tag_number, ApplicationTag::Double)? {
+            PropertyValue::Double(value) => Ok(value),
+            _ => Err(EncodingError::InvalidTag),
+        }
+    }
+
+    pub(super) fn signed(&mut self, tag_number: u8) -> EncodingResult<i32> {
+        match self.value(tag_number, ApplicationTag::SignedInt)? {
+            PropertyValue::SignedInt(value) => Ok(value),
+            _ => Err(EncodingError::InvalidTag),
+        }
+    }
+
+    pub(super) fn string(&mut self, tag_number: u8) -> EncodingResult<String> {
+        match self.value(tag_number, ApplicationTag::CharacterString)? {
+            PropertyValue::CharacterString(value) => Ok(value),
+            _ => Err(EncodingError::InvalidTag),
+        }
+    }
+
+    pub(super) fn abstract_value(&mut self, tag_number: u8) -> EncodingResult<PropertyValue> {
+        self.open(tag_number)?;
+        let value = self.advance(decode_property_value(self.rest())?);
+        self.close(tag_number)?;
+        Ok(value)
+    }
+
+    pub(super) fn date_time(&mut self, tag_number: u8) -> EncodingResult<BacnetDateTime> {
+        self.open(tag_number)?;
+        let date_time = self.advance(BacnetDateTime::decode(self.rest())?);
+        self.close(tag_number)?;
+        Ok(date_time)
+    }
+
+    pub(super) fn time_stamp(&mut self) -> EncodingResult<TimeStamp> {
+        if self.is_context(0) {
+            match self.value(0, ApplicationTag::Time)? {
+                PropertyValue::Time(time) => Ok(TimeStamp::Time(time)),
+                _ => Err(EncodingError::InvalidTag),
+            }
+        } else if self.is_context(1) {
+            Ok(TimeStamp::SequenceNumber(self.unsigned(1)?))
+        } else {
+            Ok(TimeStamp::DateTime(self.date_time(2)?))
+        }
+    }
+
+    pub(super) fn device_object_property_reference(
+        &mut self,
+    ) -> EncodingResult<DeviceObjectPropertyReference> {
+        let object_identifier = self.identifier(0)?;
+        let property_identifier = PropertyIdentifier::try_from(self.enumerated(1)?)
+            .map_err(|_| EncodingError::ValueOutOfRange)?;
+        let property_array_index = self.optional(2, Self::unsigned)?;
+        let device_identifier = self.optional(3, Self::identifier)?;
+        Ok(DeviceObjectPropertyReference {
+            object_identifier,
+            property_identifier,
+            property_array_index,
+            device_identifier,
+        })
+    }
+
+    pub(super) fn device_object_reference(&mut self) -> EncodingResult<DeviceObjectReference> {
+        let device_identifier = self.optional(0, Self::identifier)?;
+        let object_identifier = self.identifier(1)?;
+        Ok(DeviceObjectReference {
+            device_identifier,
+            object_identifier,
+        })
+    }
+
+    /// Raw contents of the constructed field `tag_number`, for fields whose
+    /// encoding is not known to the library
+    pub(super) fn constructed(&mut self, tag_number: u8) -> EncodingResult<Vec<u8>> {
+        self.open(tag_number)?;
+        let start = self.pos;
+        let mut depth = 0usize;
+        while depth > 0 || !self.at_close(tag_number) {
+            let data = self.rest();
+            let Some(&tag) = data.first() else {
+                return Err(EncodingError::UnexpectedEndOfData);
+            };
+            let context = tag & 0x08 != 0;
+            let mut header = if tag >> 4 == 0x0F { 2 } else { 1 };
+            let length = match tag & 0x07 {
+                6 if context => {
+                    depth += 1;
+                    0
+                }
+                7 if context => {
+                    depth = depth.checked_sub(1).ok_or(EncodingError::InvalidTag)?;
+                    0
+                }
+                // An application-tagged boolean carries its value in the tag
+                _ if !context && tag >> 4 == ApplicationTag::Boolean as u8 => 0,
+                5 => {
+                    let extended = *data.get(header).ok_or(EncodingError::UnexpectedEndOfData)?;
+                    header += 1;
+                    match extended {
+                        254 | 255 => {
+                            let width = if extended == 254 { 2 } else { 4 };
+                            let bytes = data
+                                .get(header..header + width)
+                                .ok_or(EncodingError::UnexpectedEndOfData)?;
+                            header += width;
+                            bytes
+                                .iter()
+                                .fold(0usize, |length, &byte| length << 8 | byte as usize)
+                        }
+                        length => length as usize,
+                    }
+                }
+                length => length as usize,
+            };
+            if data.len() < header + length {
+                return Err(EncodingError::UnexpectedEndOfData);
+            }
+            self.pos += header + length;
+        }
+        let contents = self.data[start..self.pos].to_vec();
+        self.close(tag_number)?;
+        Ok(contents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::{status_flags_bit_string, EventEnrollment};
+
+    fn date_time() -> BacnetDateTime {
+        BacnetDateTime::new(
+            Date {
+                year: 2024,
+                month: 3,
+                day: 14,
+                weekday: 4,
+            },
+            Time {
+                hour: 9,
+                minute: 30,
+                second: 0,
+                hundredths: 0,
+            },
+        )
+    }
+
+    fn flags() -> Vec<bool> {
+        vec![true, false, false, false]
+    }
+
+    #[test]
+    fn test_notification_parameters_round_trip() {
+        let ai = ObjectIdentifier::new(ObjectType::AnalogInput, 1);
+        let all = vec![
+            NotificationParameters::ChangeOfBitstring {
+                referenced_bitstring: vec![true, false, true],
+                status_flags: flags(),
+            },
+            NotificationParameters::ChangeOfState {
+                new_state: PropertyStates::new(1, 1),
+                status_flags: flags(),
+            },
+            NotificationParameters::ChangeOfState {
+                new_state: PropertyStates::new(40, 3),
+                status_flags: flags(),
+            },
+            NotificationParameters::ChangeOfValue {
+                new_value: ChangedValue::Real(21.5),
+                status_flags: flags(),
+            },
+            NotificationParameters::ChangeOfValue {
+                new_value: ChangedValue::Bits(vec![false, true]),
+                status_flags: flags(),
+            },
+            NotificationParameters::CommandFailure {
+                command_value: PropertyValue::Enumerated(1),
+                status_flags: flags(),
+                feedback_value: PropertyValue::Enumerated(0),
+            },
+            NotificationParameters::FloatingLimit {
+                reference_value: 30.0,
+                status_flags: flags(),
+                setpoint_value: 22.0,
+                error_limit: 5.0,
+            },
+            NotificationParameters::OutOfRange {
+                exceeding_value: 31.0,
+                status_flags: flags(),
+                deadband: 2.0,
+                exceeded_limit: 30.0,
+            },
+            NotificationParameters::ComplexEventType {
+                values: vec![BacnetPropertyValue::new(
+                    u32::from(PropertyIdentifier::PresentValue),
+                    PropertyValue::Real(1.0),
+                )],
+            },
+            NotificationParameters::ChangeOfLifeSafety {
+                new_state: 2,
+                new_mode: 1,
+                status_flags: flags(),
+                operation_expected: 0,
+            },
+            NotificationParameters::Extended {
+                vendor_id: 260,
+                extended_event_type: 7,
+                parameters: vec![PropertyValue::UnsignedInteger(5), PropertyValue::Real(1.5)],
+            },
+            NotificationParameters::BufferReady {
+                buffer_property: DeviceObjectPropertyReference {
+                    object_identifier: ObjectIdentifier::new(ObjectType::TrendLog, 1),
+                    property_identifier: PropertyIdentifier::LogBuffer,
+                    property_array_index: None,
+                    device_identifier: Some(ObjectIdentifier::new(ObjectType::Device, 9)),
+                },
+                previous_notification: 100,
+                current_notification: 200,
+            },
+            NotificationParameters::UnsignedRange {
+                exceeding_value: 120,
+                status_flags: flags(),
+                exceeded_limit: 100,
+            },
+            NotificationParameters::AccessEvent {
+                access_event: 1,
+                status_flags: flags(),
+                access_event_tag: 4,
+                access_event_time: TimeStamp::SequenceNumber(12),
+                access_credential: DeviceObjectReference {
+                    device_identifier: None,
+                    object_identifier: ai,
+                },
+            },
+            NotificationParameters::DoubleOutOfRange {
+                exceeding_value: 101.25,
+                status_flags: flags(),
+                deadband: 0.5,
+                exceeded_limit: 100.0,
+            },
+            NotificationParameters::SignedOutOfRange {
+                exceeding_value: -12,
+                status_flags: flags(),
+                deadband: 1,
+                exceeded_limit: -10,
+            },
+            NotificationParameters::UnsignedOutOfRange {
+                exceeding_value: 12,
+                status_flags: flags(),
+                deadband: 1,
+                exceeded_limit: 10,
+            },
+            NotificationParameters::ChangeOfCharacterstring {
+                changed_value: "Fault".to_string(),
+                status_flags: flags(),
+                alarm_value: "Fault".to_string(),
+            },
+            NotificationParameters::ChangeOfStatusFlags {
+                present_value: Some(PropertyValue::Real(3.0)),
+                referenced_flags: flags(),
+            },
+            NotificationParameters::ChangeOfStatusFlags {
+                present_value: None,
+                referenced_flags: flags(),
+            },
+            NotificationParameters::ChangeOfReliability {
+                reliability: Reliability::OverRange,
+                status_flags: flags(),
+                property_values: vec![BacnetPropertyValue::new(
+                    u32::from(PropertyIdentifier::PresentValue),
+                    PropertyValue::Real(250.0),
+                )],
+            },
+            NotificationParameters::ChangeOfDiscreteValue {
+                new_value: PropertyValue::UnsignedInteger(3),
+                status_flags: flags(),
+            },
+            NotificationParameters::ChangeOfTimer {
+                new_state: 1,
+                status_flags: flags(),
+                update_time: date_time(),
+                last_state_change: Some(2),
+                initial_timeout: Some(60),
+                expiration_time: None,
+            },
+        ];
+
+        for parameters in all {
+            let mut buffer = Vec::new();
+            parameters.encode(&mut buffer).unwrap();
+            let (decoded, consumed) = NotificationParameters::decode(&buffer).unwrap();
+            assert_eq!(decoded, parameters);
+            assert_eq!(consumed, buffer.len());
+        }
+    }
+
+    #[test]
+    fn test_event_notification_round_trip() {
+        let device = ObjectIdentifier::new(ObjectType::Device, 9);
+        let ai = ObjectIdentifier::new(ObjectType::AnalogInput, 1);
+        let alarm = EventNotificationRequest {
+            process_identifier: 7,
+            initiating_device_identifier: device,
+            event_object_identifier: ai,
+            time_stamp: TimeStamp::DateTime(date_time()),
+            notification_class: 5,
+            priority: 100,
+            event_type: EventType::OutOfRange,
+            message_text: Some("High temperature".to_string()),
+            notify_type: NotifyType::Alarm,
+            ack_required: Some(true),
+            from_state: Some(EventState::Normal),
+            to_state: EventState::HighLimit,
+            event_values: Some(NotificationParameters::OutOfRange {
+                exceeding_value: 31.0,
+                status_flags: flags(),
+                deadband: 2.0,
+                exceeded_limit: 30.0,
+            }),
+        };
+        let mut buffer = Vec::new();
+        alarm.encode(&mut buffer).unwrap();
+        assert_eq!(EventNotificationRequest::decode(&buffer).unwrap(), alarm);
+
+        // An acknowledgment notification omits the optional fields
+        let ack = EventNotificationRequest {
+            time_stamp: TimeStamp::Time(date_time().time),
+            message_text: None,
+            notify_type: NotifyType::AckNotification,
+            ack_required: None,
+            from_state: None,
+            event_values: None,
+            ..alarm
+        };
+        let mut buffer = Vec::new();
+        ack.encode(&mut buffer).unwrap();
+        assert_eq!(EventNotificationRequest::decode(&buffer).unwrap(), ack);
+
+        buffer.push(0x00);
+        assert!(EventNotificationRequest::decode(&buffer).is_err());
+    }
+
+    #[test]
+    fn test_notification_from_state_change() {
+        let parameters = EventParameters::OutOfRange {
+            time_delay: 0,
+            low_limit: 10.0,
+            high_limit: 30.0,
+            deadband: 2.0,
+        };
+        let mut ee = EventEnrollment::new(
+            1,
+            "Supply Temp Alarm".to_string(),
+            DeviceObjectPropertyReference::new(
+                ObjectIdentifier::new(ObjectType::AnalogInput, 1),
+                PropertyIdentifier::PresentValue,
+            ),
+            parameters.clone(),
+            5,
+        );
+        let change = ee.evaluate(&PropertyValue::Real(31.0), None, None).unwrap();
+
+        let mut nc = NotificationClass::new(5, "Alarms".to_string());
+        nc.priority = [100, 50, 200];
+        nc.ack_required = [true, false, false];
+
+        let status_flags = match status_flags_bit_string(0b0001) {
+            PropertyValue::BitString(bits) => bits,
+            _ => unreachable!(),
+        };
+        let event_values =
+            NotificationParameters::for_state_change(&parameters, &change, None, status_flags);
+        assert!(matches!(
+            event_values,
+            Some(NotificationParameters::OutOfRange { exceeded_limit, .. }) if exceeded_limit == 30.0
+        ));
+
+        let notification = EventNotificationRequest::from_state_change(
+            3,
+            ObjectIdentifier::new(ObjectType::Device, 9),
+            ee.identifier,
+            TimeStamp::SequenceNumber(1),
+            &change,
+            &nc,
+            event_values,
+        );
+        assert_eq!(notification.priority, 100);
+        assert_eq!(notification.ack_required, Some(true));
+        assert_eq!(notification.to_state, EventState::HighLimit);
+        assert_eq!(notification.transition(), EventTransition::ToOffnormal);
+
+        let received = notification.to_received_notification(1, true);
+        assert_eq!(received.process_identifier, 3);
+        assert_eq!(received.transition, EventTransition::ToOffnormal);
+
+        match notification.to_apdu(true, 4).unwrap() {
+            Apdu::ConfirmedRequest {
+                service_choice,
+                invoke_id,
+                service_data,
+                ..
+            } => {
+                assert_eq!(
+                    service_choice,
+                    ConfirmedServiceChoice::ConfirmedEventNotification
+                );
+                assert_eq!(invoke_id, 4);
+                assert_eq!(
+                    EventNotificationRequest::decode(&service_data).unwrap(),
+                    notification
+                );
+            }
+            other => panic!("Expected ConfirmedRequest, got {:?}", other),
+        }
+        assert!(matches!(
+            notification.to_apdu(false, 0).unwrap(),
+            Apdu::UnconfirmedRequest {
+                service_choice: UnconfirmedServiceChoice::UnconfirmedEventNotification,
+                ..
+            }
+        ));
+    }
+}
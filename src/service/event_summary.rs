@@ -0,0 +1,1106 @@
+//! GetAlarmSummary, GetEnrollmentSummary and GetEventInformation Services
+//! (Clauses 13.10, 13.11 and 13.12)
+//!
+//! The three services summarize the event-initiating objects of a device:
+//! those with Acked_Transitions, either Event Enrollments or objects with
+//! intrinsic reporting.
+//!
+//! - **GetAlarmSummary** lists the objects in an alarm state whose notify
+//!   type is ALARM.
+//! - **GetEnrollmentSummary** lists the objects passing a set of filters on
+//!   acknowledgment, recipient, event state, event type, priority and
+//!   notification class.
+//! - **GetEventInformation** lists the objects that are not normal or have
+//!   unacknowledged transitions, a bounded number at a time. More_Events tells
+//!   the client to ask again, starting after the last object it received.
+
+use super::event_notification::{encode_bits, encode_identifier, Reader};
+use super::TimeStamp;
+use crate::encoding::{
+    advanced::bitstring::{decode_bit_string, encode_bit_string},
+    advanced::context::{encode_closing_tag, encode_opening_tag},
+    decode_enumerated, decode_object_identifier, decode_octet_string, decode_unsigned,
+    encode_context_boolean, encode_context_enumerated, encode_context_unsigned, encode_enumerated,
+    encode_object_identifier, encode_octet_string, encode_unsigned, EncodingError,
+    Result as EncodingResult,
+};
+use crate::object::{EventState, EventType, NotifyType, ObjectIdentifier, ObjectType, Recipient};
+
+#[cfg(feature = "std")]
+use super::{AbortReason, BacnetDateTime, ConfirmedServiceChoice, RejectReason};
+#[cfg(feature = "std")]
+use crate::{
+    app::Apdu,
+    object::{
+        database::ObjectDatabase, date_time_from_value, EventTransition, NotificationClass,
+        PropertyIdentifier, PropertyValue,
+    },
+};
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::ToString, vec::Vec};
+
+/// Event summaries sent in one GetEventInformation acknowledgement, which
+/// keeps a response of full summaries within a 1476-octet APDU
+#[cfg(feature = "std")]
+pub const MAX_EVENT_SUMMARIES: usize = 16;
+
+/// An object in alarm (GetAlarmSummary acknowledgement entry)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AlarmSummary {
+    /// Object in alarm
+    pub object_identifier: ObjectIdentifier,
+    /// Its event state
+    pub alarm_state: EventState,
+    /// Acknowledged transitions (to_offnormal, to_fault, to_normal)
+    pub acknowledged_transitions: [bool; 3],
+}
+
+/// Get Alarm Summary acknowledgement (ComplexAck service data)
+///
+/// The request has no parameters.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct GetAlarmSummaryAck {
+    /// Objects in alarm
+    pub alarm_summaries: Vec<AlarmSummary>,
+}
+
+impl GetAlarmSummaryAck {
+    /// Encode the Get Alarm Summary acknowledgement
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        for summary in &self.alarm_summaries {
+            encode_app_identifier(buffer, &summary.object_identifier)?;
+            encode_enumerated(buffer, summary.alarm_state as u32)?;
+            encode_bit_string(buffer, &summary.acknowledged_transitions)?;
+        }
+        Ok(())
+    }
+
+    /// Decode a Get Alarm Summary acknowledgement
+    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
+        let mut alarm_summaries = Vec::new();
+        let mut pos = 0;
+        while pos < data.len() {
+            let (object_identifier, consumed) = decode_app_identifier(&data[pos..])?;
+            pos += consumed;
+            let (alarm_state, consumed) = decode_event_state(&data[pos..])?;
+            pos += consumed;
+            let (bits, consumed) = decode_bit_string(&data[pos..])?;
+            pos += consumed;
+            alarm_summaries.push(AlarmSummary {
+                object_identifier,
+                alarm_state,
+                acknowledged_transitions: transition_bits(&bits)?,
+            });
+        }
+        Ok(Self { alarm_summaries })
+    }
+}
+
+/// Which acknowledgment states GetEnrollmentSummary reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum AcknowledgmentFilter {
+    All = 0,
+    Acked = 1,
+    NotAcked = 2,
+}
+
+impl TryFrom<u32> for AcknowledgmentFilter {
+    type Error = EncodingError;
+
+    fn try_from(value: u32) -> EncodingResult<Self> {
+        match value {
+            0 => Ok(AcknowledgmentFilter::All),
+            1 => Ok(AcknowledgmentFilter::Acked),
+            2 => Ok(AcknowledgmentFilter::NotAcked),
+            _ => Err(EncodingError::ValueOutOfRange),
+        }
+    }
+}
+
+/// Which event states GetEnrollmentSummary reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum EventStateFilter {
+    Offnormal = 0,
+    Fault = 1,
+    Normal = 2,
+    All = 3,
+    /// Any state other than normal
+    Active = 4,
+}
+
+impl TryFrom<u32> for EventStateFilter {
+    type Error = EncodingError;
+
+    fn try_from(value: u32) -> EncodingResult<Self> {
+        match value {
+            0 => Ok(EventStateFilter::Offnormal),
+            1 => Ok(EventStateFilter::Fault),
+            2 => Ok(EventStateFilter::Normal),
+            3 => Ok(EventStateFilter::All),
+            4 => Ok(EventStateFilter::Active),
+            _ => Err(EncodingError::ValueOutOfRange),
+        }
+    }
+}
+
+impl EventStateFilter {
+    /// Whether an object in `state` passes the filter
+    pub fn matches(self, state: EventState) -> bool {
+        match self {
+            EventStateFilter::Offnormal => !matches!(state, EventState::Normal | EventState::Fault),
+            EventStateFilter::Fault => state == EventState::Fault,
+            EventStateFilter::Normal => state == EventState::Normal,
+            EventStateFilter::All => true,
+            EventStateFilter::Active => state != EventState::Normal,
+        }
+    }
+}
+
+/// A recipient and the process it delivers notifications to
+/// (BACnetRecipientProcess)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecipientProcess {
+    /// The recipient
+    pub recipient: Recipient,
+    /// Its process identifier
+    pub process_identifier: u32,
+}
+
+/// Get Enrollment Summary request (confirmed service)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GetEnrollmentSummaryRequest {
+    /// Acknowledgment states to report
+    pub acknowledgment_filter: AcknowledgmentFilter,
+    /// Only objects whose notification class sends to this recipient process
+    pub enrollment_filter: Option<RecipientProcess>,
+    /// Only objects in these event states
+    pub event_state_filter: Option<EventStateFilter>,
+    /// Only objects using this algorithm
+    pub event_type_filter: Option<EventType>,
+    /// Only objects whose current priority is within `min..=max`
+    pub priority_filter: Option<(u8, u8)>,
+    /// Only objects in this notification class
+    pub notification_class_filter: Option<u32>,
+}
+
+impl GetEnrollmentSummaryRequest {
+    /// Create a request filtered only by acknowledgment state
+    pub fn new(acknowledgment_filter: AcknowledgmentFilter) -> Self {
+        Self {
+            acknowledgment_filter,
+            enrollment_filter: None,
+            event_state_filter: None,
+            event_type_filter: None,
+            priority_filter: None,
+            notification_class_filter: None,
+        }
+    }
+
+    /// Encode the Get Enrollment Summary request
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        // Acknowledgment filter - context tag 0
+        buffer.extend_from_slice(&encode_context_enumerated(
+            self.acknowledgment_filter as u32,
+            0,
+        )?);
+
+        // Enrollment filter - context tag 1
+        if let Some(filter) = &self.enrollment_filter {
+            encode_opening_tag(buffer, 1)?;
+            encode_opening_tag(buffer, 0)?;
+            encode_recipient(buffer, &filter.recipient)?;
+            encode_closing_tag(buffer, 0)?;
+            buffer.extend_from_slice(&encode_context_unsigned(filter.process_identifier, 1)?);
+            encode_closing_tag(buffer, 1)?;
+        }
+
+        // Event state filter - context tag 2
+        if let Some(filter) = self.event_state_filter {
+            buffer.extend_from_slice(&encode_context_enumerated(filter as u32, 2)?);
+        }
+
+        // Event type filter - context tag 3
+        if let Some(event_type) = self.event_type_filter {
+            buffer.extend_from_slice(&encode_context_enumerated(event_type as u32, 3)?);
+        }
+
+        // Priority filter - context tag 4
+        if let Some((min, max)) = self.priority_filter {
+            encode_opening_tag(buffer, 4)?;
+            buffer.extend_from_slice(&encode_context_unsigned(u32::from(min), 0)?);
+            buffer.extend_from_slice(&encode_context_unsigned(u32::from(max), 1)?);
+            encode_closing_tag(buffer, 4)?;
+        }
+
+        // Notification class filter - context tag 5
+        if let Some(class) = self.notification_class_filter {
+            buffer.extend_from_slice(&encode_context_unsigned(class, 5)?);
+        }
+
+        Ok(())
+    }
+
+    /// Decode a Get Enrollment Summary request
+    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
+        let mut reader = Reader::new(data);
+        let mut request = Self::new(AcknowledgmentFilter::try_from(reader.enumerated(0)?)?);
+
+        if reader.is_context(1) {
+            reader.open(1)?;
+            reader.open(0)?;
+            let recipient = decode_recipient(&mut reader)?;
+            reader.close(0)?;
+            let process_identifier = reader.unsigned(1)?;
+            reader.close(1)?;
+            request.enrollment_filter = Some(RecipientProcess {
+                recipient,
+                process_identifier,
+            });
+        }
+        request.event_state_filter = reader
+            .optional(2, Reader::enumerated)?
+            .map(EventStateFilter::try_from)
+            .transpose()?;
+        request.event_type_filter = reader
+            .optional(3, Reader::enumerated)?
+            .map(|event_type| {
+                EventType::try_from(event_type).map_err(|_| EncodingError::ValueOutOfRange)
+            })
+            .transpose()?;
+        if reader.is_context(4) {
+            reader.open(4)?;
+            let min = reader.unsigned(0)?;
+            let max = reader.unsigned(1)?;
+            reader.close(4)?;
+            request.priority_filter = Some((priority(min)?, priority(max)?));
+        }
+        request.notification_class_filter = reader.optional(5, Reader::unsigned)?;
+
+        if reader.pos != data.len() {
+            return Err(EncodingError::InvalidFormat(
+                "Unexpected data after Get Enrollment Summary request".to_string(),
+            ));
+        }
+        Ok(request)
+    }
+}
+
+/// An event-initiating object (GetEnrollmentSummary acknowledgement entry)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EnrollmentSummary {
+    /// Event-initiating object
+    pub object_identifier: ObjectIdentifier,
+    /// Algorithm it applies
+    pub event_type: EventType,
+    /// Its event state
+    pub event_state: EventState,
+    /// Priority of the transition into that state
+    pub priority: u8,
+    /// Notification class it reports through (optional)
+    pub notification_class: Option<u32>,
+}
+
+/// Get Enrollment Summary acknowledgement (ComplexAck service data)
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct GetEnrollmentSummaryAck {
+    /// Objects that passed the filters
+    pub enrollment_summaries: Vec<EnrollmentSummary>,
+}
+
+impl GetEnrollmentSummaryAck {
+    /// Encode the Get Enrollment Summary acknowledgement
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        for summary in &self.enrollment_summaries {
+            encode_app_identifier(buffer, &summary.object_identifier)?;
+            encode_enumerated(buffer, summary.event_type as u32)?;
+            encode_enumerated(buffer, summary.event_state as u32)?;
+            encode_unsigned(buffer, u32::from(summary.priority))?;
+            if let Some(class) = summary.notification_class {
+                encode_unsigned(buffer, class)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Decode a Get Enrollment Summary acknowledgement
+    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
+        let mut enrollment_summaries = Vec::new();
+        let mut pos = 0;
+        while pos < data.len() {
+            let (object_identifier, consumed) = decode_app_identifier(&data[pos..])?;
+            pos += consumed;
+            let (event_type, consumed) = decode_enumerated(&data[pos..])?;
+            pos += consumed;
+            let event_type =
+                EventType::try_from(event_type).map_err(|_| EncodingError::ValueOutOfRange)?;
+            let (event_state, consumed) = decode_event_state(&data[pos..])?;
+            pos += consumed;
+            let (value, consumed) = decode_unsigned(&data[pos..])?;
+            pos += consumed;
+            // The optional class is the only other unsigned (application tag 2)
+            let notification_class = match data.get(pos) {
+                Some(&tag) if tag >> 4 == 2 && tag & 0x08 == 0 => {
+                    let (class, consumed) = decode_unsigned(&data[pos..])?;
+                    pos += consumed;
+                    Some(class)
+                }
+                _ => None,
+            };
+            enrollment_summaries.push(EnrollmentSummary {
+                object_identifier,
+                event_type,
+                event_state,
+                priority: priority(value)?,
+                notification_class,
+            });
+        }
+        Ok(Self {
+            enrollment_summaries,
+        })
+    }
+}
+
+/// Get Event Information request (confirmed service)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct GetEventInformationRequest {
+    /// Last object of the previous response, to continue after it
+    pub last_received_object_identifier: Option<ObjectIdentifier>,
+}
+
+impl GetEventInformationRequest {
+    /// Encode the Get Event Information request
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        // Last received object identifier - context tag 0 (optional)
+        if let Some(identifier) = &self.last_received_object_identifier {
+            encode_identifier(buffer, identifier, 0)?;
+        }
+        Ok(())
+    }
+
+    /// Decode a Get Event Information request
+    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
+        let mut reader = Reader::new(data);
+        let last_received_object_identifier = reader.optional(0, Reader::identifier)?;
+        if reader.pos != data.len() {
+            return Err(EncodingError::InvalidFormat(
+                "Unexpected data after Get Event Information request".to_string(),
+            ));
+        }
+        Ok(Self {
+            last_received_object_identifier,
+        })
+    }
+}
+
+/// An object with an active or unacknowledged event
+/// (GetEventInformation acknowledgement entry)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventSummary {
+    /// Event-initiating object
+    pub object_identifier: ObjectIdentifier,
+    /// Its event state
+    pub event_state: EventState,
+    /// Acknowledged transitions (to_offnormal, to_fault, to_normal)
+    pub acknowledged_transitions: [bool; 3],
+    /// Time of the last transition of each kind
+    pub event_time_stamps: [TimeStamp; 3],
+    /// Notify type
+    pub notify_type: NotifyType,
+    /// Event enable (to_offnormal, to_fault, to_normal)
+    pub event_enable: [bool; 3],
+    /// Priority of each transition
+    pub event_priorities: [u8; 3],
+}
+
+/// Get Event Information acknowledgement (ComplexAck service data)
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct GetEventInformationAck {
+    /// Objects with active or unacknowledged events
+    pub list_of_event_summaries: Vec<EventSummary>,
+    /// Whether more summaries follow the last one in the list
+    pub more_events: bool,
+}
+
+impl GetEventInformationAck {
+    /// Encode the Get Event Information acknowledgement
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        // List of event summaries - context tag 0
+        encode_opening_tag(buffer, 0)?;
+        for summary in &self.list_of_event_summaries {
+            encode_identifier(buffer, &summary.object_identifier, 0)?;
+            buffer.extend_from_slice(&encode_context_enumerated(summary.event_state as u32, 1)?);
+            encode_bits(buffer, &summary.acknowledged_transitions, 2)?;
+            encode_opening_tag(buffer, 3)?;
+            for time_stamp in &summary.event_time_stamps {
+                time_stamp.encode(buffer)?;
+            }
+            encode_closing_tag(buffer, 3)?;
+            buffer.extend_from_slice(&encode_context_enumerated(summary.notify_type as u32, 4)?);
+            encode_bits(buffer, &summary.event_enable, 5)?;
+            encode_opening_tag(buffer, 6)?;
+            for &priority in &summary.event_priorities {
+                encode_unsigned(buffer, u32::from(priority))?;
+            }
+            encode_closing_tag(buffer, 6)?;
+        }
+        encode_closing_tag(buffer, 0)?;
+
+        // More events - context tag 1
+        buffer.extend_from_slice(&encode_context_boolean(self.more_events, 1)?);
+        Ok(())
+    }
+
+    /// Decode a Get Event Information acknowledgement
+    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
+        let mut reader = Reader::new(data);
+        let mut list_of_event_summaries = Vec::new();
+        reader.open(0)?;
+        while !reader.at_close(0) {
+            let object_identifier = reader.identifier(0)?;
+            let event_state = EventState::try_from(reader.enumerated(1)?)
+                .map_err(|_| EncodingError::ValueOutOfRange)?;
+            let acknowledged_transitions = transition_bits(&reader.bits(2)?)?;
+            reader.open(3)?;
+            let event_time_stamps = [
+                reader.time_stamp()?,
+                reader.time_stamp()?,
+                reader.time_stamp()?,
+            ];
+            reader.close(3)?;
+            let notify_type = NotifyType::try_from(reader.enumerated(4)?)
+                .map_err(|_| EncodingError::ValueOutOfRange)?;
+            let event_enable = transition_bits(&reader.bits(5)?)?;
+            reader.open(6)?;
+            let mut event_priorities = [0; 3];
+            for slot in &mut event_priorities {
+                *slot = priority(reader.advance(decode_unsigned(reader.rest())?))?;
+            }
+            reader.close(6)?;
+            list_of_event_summaries.push(EventSummary {
+                object_identifier,
+                event_state,
+                acknowledged_transitions,
+                event_time_stamps,
+                notify_type,
+                event_enable,
+                event_priorities,
+            });
+        }
+        reader.close(0)?;
+        let more_events = reader.boolean(1)?;
+
+        if reader.pos != data.len() {
+            return Err(EncodingError::InvalidFormat(
+                "Unexpected data after Get Event Information acknowledgement".to_string(),
+            ));
+        }
+        Ok(Self {
+            list_of_event_summaries,
+            more_events,
+        })
+    }
+}
+
+fn encode_app_identifier(
+    buffer: &mut Vec<u8>,
+    identifier: &ObjectIdentifier,
+) -> EncodingResult<()> {
+    encode_object_identifier(
+        buffer,
+        u16::from(identifier.object_type),
+        identifier.instance,
+    )
+}
+
+fn decode_app_identifier(data: &[u8]) -> EncodingResult<(ObjectIdentifier, usize)> {
+    let ((object_type, instance), consumed) = decode_object_identifier(data)?;
+    let object_type =
+        ObjectType::try_from(object_type).map_err(|_| EncodingError::ValueOutOfRange)?;
+    Ok((ObjectIdentifier::new(object_type, instance), consumed))
+}
+
+fn decode_event_state(data: &[u8]) -> EncodingResult<(EventState, usize)> {
+    let (state, consumed) = decode_enumerated(data)?;
+    let state = EventState::try_from(state).map_err(|_| EncodingError::ValueOutOfRange)?;
+    Ok((state, consumed))
+}
+
+fn transition_bits(bits: &[bool]) -> EncodingResult<[bool; 3]> {
+    match bits {
+        [to_offnormal, to_fault, to_normal, ..] => Ok([*to_offnormal, *to_fault, *to_normal]),
+        _ => Err(EncodingError::InvalidFormat(
+            "Expected 3 event transition bits".to_string(),
+        )),
+    }
+}
+
+fn priority(value: u32) -> EncodingResult<u8> {
+    u8::try_from(value).map_err(|_| EncodingError::ValueOutOfRange)
+}
+
+pub(super) fn encode_recipient(buffer: &mut Vec<u8>, recipient: &Recipient) -> EncodingResult<()> {
+    match recipient {
+        // Device - context tag 0
+        Recipient::Device(device) => encode_identifier(buffer, device, 0),
+        // Address - context tag 1
+        Recipient::Address {
+            network,
+            mac_address,
+        } => {
+            encode_opening_tag(buffer, 1)?;
+            encode_unsigned(buffer, u32::from(*network))?;
+            encode_octet_string(buffer, mac_address)?;
+            encode_closing_tag(buffer, 1)
+        }
+    }
+}
+
+pub(super) fn decode_recipient(reader: &mut Reader) -> EncodingResult<Recipient> {
+    if reader.is_context(0) {
+        return Ok(Recipient::Device(reader.identifier(0)?));
+    }
+    reader.open(1)?;
+    let network = u16::try_from(reader.advance(decode_unsigned(reader.rest())?))
+        .map_err(|_| EncodingError::ValueOutOfRange)?;
+    let mac_address = reader.advance(decode_octet_string(reader.rest())?);
+    reader.close(1)?;
+    Ok(Recipient::Address {
+        network,
+        mac_address,
+    })
+}
+
+/// The algorithm an event-initiating object applies
+///
+/// Event Enrollments report it in Event_Type; objects with intrinsic
+/// reporting apply the algorithm their object type defines.
+#[cfg(feature = "std")]
+pub(super) fn event_type_of(database: &ObjectDatabase, object: ObjectIdentifier) -> EventType {
+    if let Ok(PropertyValue::Enumerated(event_type)) =
+        database.get_property(object, PropertyIdentifier::EventType)
+    {
+        if let Ok(event_type) = EventType::try_from(event_type) {
+            return event_type;
+        }
+    }
+    match object.object_type {
+        ObjectType::AnalogInput
+        | ObjectType::AnalogOutput
+        | ObjectType::AnalogValue
+        | ObjectType::LargeAnalogValue => EventType::OutOfRange,
+        ObjectType::IntegerValue => EventType::SignedOutOfRange,
+        ObjectType::PositiveIntegerValue => EventType::UnsignedOutOfRange,
+        ObjectType::BinaryInput
+        | ObjectType::BinaryOutput
+        | ObjectType::BinaryValue
+        | ObjectType::MultiStateInput
+        | ObjectType::MultiStateOutput
+        | ObjectType::MultiStateValue => EventType::ChangeOfState,
+        _ => EventType::None,
+    }
+}
+
+/// The event properties of an event-initiating object in a database
+#[cfg(feature = "std")]
+struct EventInitiatingObject {
+    identifier: ObjectIdentifier,
+    event_state: EventState,
+    acked_transitions: [bool; 3],
+    event_enable: [bool; 3],
+    notify_type: NotifyType,
+    event_time_stamps: [BacnetDateTime; 3],
+    notification_class: Option<NotificationClass>,
+}
+
+#[cfg(feature = "std")]
+impl EventInitiatingObject {
+    fn read(database: &ObjectDatabase, identifier: ObjectIdentifier) -> Option<Self> {
+        let property = |property| database.get_property(identifier, property).ok();
+        let bits = |property| match property {
+            Some(PropertyValue::BitString(bits)) => transition_bits(&bits).ok(),
+            _ => None,
+        };
+
+        let acked_transitions = bits(property(PropertyIdentifier::AckedTransitions))?;
+        let event_state = match property(PropertyIdentifier::EventState) {
+            Some(PropertyValue::Enumerated(state)) => EventState::try_from(state).ok()?,
+            _ => EventState::Normal,
+        };
+        let event_enable = bits(property(PropertyIdentifier::EventEnable)).unwrap_or([true; 3]);
+        let notify_type = match property(PropertyIdentifier::NotifyType) {
+            Some(PropertyValue::Enumerated(notify_type)) => {
+                NotifyType::try_from(notify_type).unwrap_or(NotifyType::Alarm)
+            }
+            _ => NotifyType::Alarm,
+        };
+        let mut event_time_stamps = [BacnetDateTime::unspecified(); 3];
+        if let Some(PropertyValue::Array(items)) = property(PropertyIdentifier::EventTimeStamps) {
+            for (slot, item) in event_time_stamps.iter_mut().zip(&items) {
+                if let Ok(date_time) = date_time_from_value(item) {
+                    *slot = date_time;
+                }
+            }
+        }
+        let notification_class = match property(PropertyIdentifier::NotificationClass) {
+            Some(PropertyValue::UnsignedInteger(class)) => database.notification_class(class),
+            _ => None,
+        };
+
+        Some(Self {
+            identifier,
+            event_state,
+            acked_transitions,
+            event_enable,
+            notify_type,
+            event_time_stamps,
+            notification_class,
+        })
+    }
+
+    fn priority(&self, transition: EventTransition) -> u8 {
+        self.notification_class
+            .as_ref()
+            .map_or(255, |class| class.priority_for(transition))
+    }
+
+    fn is_acked(&self) -> bool {
+        self.acked_transitions.iter().all(|&acked| acked)
+    }
+}
+
+/// The event-initiating objects of a database, in object identifier order
+#[cfg(feature = "std")]
+fn event_initiating_objects(database: &ObjectDatabase) -> Vec<EventInitiatingObject> {
+    let mut identifiers = database.get_all_objects();
+    identifiers.sort_by_key(|id| (u16::from(id.object_type), id.instance));
+    identifiers
+        .into_iter()
+        .filter_map(|identifier| EventInitiatingObject::read(database, identifier))
+        .collect()
+}
+
+/// Summarize the objects of a database in an alarm state with notify type
+/// ALARM
+#[cfg(feature = "std")]
+pub fn get_alarm_summary(database: &ObjectDatabase) -> GetAlarmSummaryAck {
+    let alarm_summaries = event_initiating_objects(database)
+        .into_iter()
+        .filter(|object| {
+            object.event_state != EventState::Normal && object.notify_type == NotifyType::Alarm
+        })
+        .map(|object| AlarmSummary {
+            object_identifier: object.identifier,
+            alarm_state: object.event_state,
+            acknowledged_transitions: object.acked_transitions,
+        })
+        .collect();
+    GetAlarmSummaryAck { alarm_summaries }
+}
+
+/// Summarize the event-initiating objects of a database that pass the
+/// request's filters
+#[cfg(feature = "std")]
+pub fn get_enrollment_summary(
+    database: &ObjectDatabase,
+    request: &GetEnrollmentSummaryRequest,
+) -> GetEnrollmentSummaryAck {
+    let enrollment_summaries = event_initiating_objects(database)
+        .into_iter()
+        .filter_map(|object| {
+            let acknowledged = match request.acknowledgment_filter {
+                AcknowledgmentFilter::All => true,
+                AcknowledgmentFilter::Acked => object.is_acked(),
+                AcknowledgmentFilter::NotAcked => !object.is_acked(),
+            };
+            let enrolled = request.enrollment_filter.as_ref().is_none_or(|filter| {
+                object.notification_class.as_ref().is_some_and(|class| {
+                    class.recipient_list.iter().any(|destination| {
+                        destination.recipient == filter.recipient
+                            && destination.process_identifier == filter.process_identifier
+                    })
+                })
+            });
+            let in_state = request
+                .event_state_filter
+                .is_none_or(|filter| filter.matches(object.event_state));
+            let event_type = event_type_of(database, object.identifier);
+            let of_type = request
+                .event_type_filter
+                .is_none_or(|filter| filter == event_type);
+            let priority = object.priority(EventTransition::for_state(object.event_state));
+            let in_priority = request
+                .priority_filter
+                .is_none_or(|(min, max)| (min..=max).contains(&priority));
+            let class = object
+                .notification_class
+                .as_ref()
+                .map(NotificationClass::notification_class);
+            let in_class = request
+                .notification_class_filter
+                .is_none_or(|filter| class == Some(filter));
+
+            (acknowledged && enrolled && in_state && of_type && in_priority && in_class).then_some(
+                EnrollmentSummary {
+                    object_identifier: object.identifier,
+                    event_type,
+                    event_state: object.event_state,
+                    priority,
+                    notification_class: class,
+                },
+            )
+        })
+        .collect();
+    GetEnrollmentSummaryAck {
+        enrollment_summaries,
+    }
+}
+
+/// Summarize the objects of a database that are not normal or have
+/// unacknowledged transitions
+///
+/// Summaries start after the request's last received object and stop at
+/// `max_summaries`, with More_Events set if any remain.
+#[cfg(feature = "std")]
+pub fn get_event_information(
+    database: &ObjectDatabase,
+    request: &GetEventInformationRequest,
+    max_summaries: usize,
+) -> GetEventInformationAck {
+    let key = |id: &ObjectIdentifier| (u16::from(id.object_type), id.instance);
+    let mut summaries = event_initiating_objects(database)
+        .into_iter()
+        .filter(|object| {
+            request
+                .last_received_object_identifier
+                .is_none_or(|last| key(&object.identifier) > key(&last))
+        })
+        .filter(|object| object.event_state != EventState::Normal || !object.is_acked())
+        .map(|object| EventSummary {
+            object_identifier: object.identifier,
+            event_state: object.event_state,
+            acknowledged_transitions: object.acked_transitions,
+            event_time_stamps: object.event_time_stamps.map(TimeStamp::DateTime),
+            notify_type: object.notify_type,
+            event_enable: object.event_enable,
+            event_priorities: [
+                EventTransition::ToOffnormal,
+                EventTransition::ToFault,
+                EventTransition::ToNormal,
+            ]
+            .map(|transition| object.priority(transition)),
+        });
+
+    let list_of_event_summaries: Vec<EventSummary> =
+        summaries.by_ref().take(max_summaries).collect();
+    let more_events = summaries.next().is_some();
+    GetEventInformationAck {
+        list_of_event_summaries,
+        more_events,
+    }
+}
+
+/// Answer a Get Alarm Summary request with a ComplexAck
+#[cfg(feature = "std")]
+pub fn handle_get_alarm_summary(database: &ObjectDatabase, invoke_id: u8) -> Apdu {
+    let mut service_data = Vec::new();
+    let result = get_alarm_summary(database).encode(&mut service_data);
+    complex_ack(
+        invoke_id,
+        ConfirmedServiceChoice::GetAlarmSummary,
+        result.map(|()| service_data),
+    )
+}
+
+/// Answer a Get Enrollment Summary request
+///
+/// Returns a ComplexAck with the objects passing the filters, or a Reject PDU
+/// if the request cannot be decoded.
+#[cfg(feature = "std")]
+pub fn handle_get_enrollment_summary(
+    database: &ObjectDatabase,
+    invoke_id: u8,
+    service_data: &[u8],
+) -> Apdu {
+    let request = match GetEnrollmentSummaryRequest::decode(service_data) {
+        Ok(request) => request,
+        Err(error) => return Apdu::reject(invoke_id, RejectReason::for_decode_error(&error)),
+    };
+    let mut service_data = Vec::new();
+    let result = get_enrollment_summary(database, &request).encode(&mut service_data);
+    complex_ack(
+        invoke_id,
+        ConfirmedServiceChoice::GetEnrollmentSummary,
+        result.map(|()| service_data),
+    )
+}
+
+/// Answer a Get Event Information request
+///
+/// Returns a ComplexAck with up to [`MAX_EVENT_SUMMARIES`] summaries, or a
+/// Reject PDU if the request cannot be decoded.
+#[cfg(feature = "std")]
+pub fn handle_get_event_information(
+    database: &ObjectDatabase,
+    invoke_id: u8,
+    service_data: &[u8],
+) -> Apdu {
+    let request = match GetEventInformationRequest::decode(service_data) {
+        Ok(request) => request,
+        Err(error) => return Apdu::reject(invoke_id, RejectReason::for_decode_error(&error)),
+    };
+    let mut service_data = Vec::new();
+    let result =
+        get_event_information(database, &request, MAX_EVENT_SUMMARIES).encode(&mut service_data);
+    complex_ack(
+        invoke_id,
+        ConfirmedServiceChoice::GetEventInformation,
+        result.map(|()| service_data),
+    )
+}
+
+#[cfg(feature = "std")]
+fn complex_ack(
+    invoke_id: u8,
+    service_choice: ConfirmedServiceChoice,
+    service_data: EncodingResult<Vec<u8>>,
+) -> Apdu {
+    match service_data {
+        Ok(service_data) => Apdu::ComplexAck {
+            segmented: false,
+            more_follows: false,
+            invoke_id,
+            sequence_number: None,
+            proposed_window_size: None,
+            service_choice: service_choice as u8,
+            service_data,
+        },
+        Err(error) => Apdu::abort(true, invoke_id, AbortReason::for_encode_error(&error)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary_codecs() {
+        let ai = ObjectIdentifier::new(ObjectType::AnalogInput, 1);
+        let ee = ObjectIdentifier::new(ObjectType::EventEnrollment, 2);
+
+        let alarms = GetAlarmSummaryAck {
+            alarm_summaries: vec![AlarmSummary {
+                object_identifier: ai,
+                alarm_state: EventState::HighLimit,
+                acknowledged_transitions: [false, true, true],
+            }],
+        };
+        let mut buffer = Vec::new();
+        alarms.encode(&mut buffer).unwrap();
+        assert_eq!(GetAlarmSummaryAck::decode(&buffer).unwrap(), alarms);
+
+        let request = GetEnrollmentSummaryRequest {
+            enrollment_filter: Some(RecipientProcess {
+                recipient: Recipient::Address {
+                    network: 5,
+                    mac_address: vec![192, 168, 1, 10, 0xBA, 0xC0],
+                },
+                process_identifier: 3,
+            }),
+            event_state_filter: Some(EventStateFilter::Active),
+            event_type_filter: Some(EventType::OutOfRange),
+            priority_filter: Some((1, 100)),
+            notification_class_filter: Some(5),
+            ..GetEnrollmentSummaryRequest::new(AcknowledgmentFilter::NotAcked)
+        };
+        let mut buffer = Vec::new();
+        request.encode(&mut buffer).unwrap();
+        assert_eq!(
+            GetEnrollmentSummaryRequest::decode(&buffer).unwrap(),
+            request
+        );
+
+        let enrollments = GetEnrollmentSummaryAck {
+            enrollment_summaries: vec![
+                EnrollmentSummary {
+                    object_identifier: ai,
+                    event_type: EventType::OutOfRange,
+                    event_state: EventState::HighLimit,
+                    priority: 100,
+                    notification_class: Some(5),
+                },
+                EnrollmentSummary {
+                    object_identifier: ee,
+                    event_type: EventType::ChangeOfState,
+                    event_state: EventState::Normal,
+                    priority: 200,
+                    notification_class: None,
+                },
+            ],
+        };
+        let mut buffer = Vec::new();
+        enrollments.encode(&mut buffer).unwrap();
+        assert_eq!(
+            GetEnrollmentSummaryAck::decode(&buffer).unwrap(),
+            enrollments
+        );
+
+        let request = GetEventInformationRequest {
+            last_received_object_identifier: Some(ai),
+        };
+        let mut buffer = Vec::new();
+        request.encode(&mut buffer).unwrap();
+        assert_eq!(
+            GetEventInformationRequest::decode(&buffer).unwrap(),
+            request
+        );
+
+        let information = GetEventInformationAck {
+            list_of_event_summaries: vec![EventSummary {
+                object_identifier: ee,
+                event_state: EventState::Offnormal,
+                acknowledged_transitions: [false, true, true],
+                event_time_stamps: [
+                    TimeStamp::SequenceNumber(4),
+                    TimeStamp::SequenceNumber(0),
+                    TimeStamp::SequenceNumber(0),
+                ],
+                notify_type: NotifyType::Alarm,
+                event_enable: [true, true, false],
+                event_priorities: [100, 50, 200],
+            }],
+            more_events: true,
+        };
+        let mut buffer = Vec::new();
+        information.encode(&mut buffer).unwrap();
+        assert_eq!(
+            GetEventInformationAck::decode(&buffer).unwrap(),
+            information
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_event_summary_handlers() {
+        use crate::object::{
+            Destination, Device, DeviceObjectPropertyReference, EventEnrollment, EventParameters,
+        };
+
+        let database = ObjectDatabase::new(Device::new(15, String::from("Device")));
+        let operator = Recipient::Device(ObjectIdentifier::new(ObjectType::Device, 99));
+        let mut nc = NotificationClass::new(5, String::from("Alarms"));
+        nc.priority = [100, 50, 200];
+        nc.add_recipient(Destination::new(operator.clone(), 8));
+        database.add_object(Box::new(nc)).unwrap();
+
+        // Three enrollments: two in high-limit alarm, one normal
+        for instance in 1..=3 {
+            let mut ee = EventEnrollment::new(
+                instance,
+                format!("Alarm {}", instance),
+                DeviceObjectPropertyReference::new(
+                    ObjectIdentifier::new(ObjectType::AnalogInput, instance),
+                    PropertyIdentifier::PresentValue,
+                ),
+                EventParameters::OutOfRange {
+                    time_delay: 0,
+                    low_limit: 10.0,
+                    high_limit: 30.0,
+                    deadband: 2.0,
+                },
+                5,
+            );
+            if instance != 2 {
+                ee.evaluate(&PropertyValue::Real(31.0), None, None).unwrap();
+            }
+            database.add_object(Box::new(ee)).unwrap();
+        }
+        let ee = |instance| ObjectIdentifier::new(ObjectType::EventEnrollment, instance);
+
+        let alarms = get_alarm_summary(&database);
+        let in_alarm: Vec<_> = alarms
+            .alarm_summaries
+            .iter()
+            .map(|summary| summary.object_identifier)
+            .collect();
+        assert_eq!(in_alarm, vec![ee(1), ee(3)]);
+        assert_eq!(
+            alarms.alarm_summaries[0].acknowledged_transitions,
+            [false, true, true]
+        );
+
+        let request = GetEnrollmentSummaryRequest {
+            enrollment_filter: Some(RecipientProcess {
+                recipient: operator,
+                process_identifier: 8,
+            }),
+            event_state_filter: Some(EventStateFilter::Normal),
+            ..GetEnrollmentSummaryRequest::new(AcknowledgmentFilter::All)
+        };
+        let enrollments = get_enrollment_summary(&database, &request);
+        assert_eq!(
+            enrollments.enrollment_summaries,
+            vec![EnrollmentSummary {
+                object_identifier: ee(2),
+                event_type: EventType::OutOfRange,
+                event_state: EventState::Normal,
+                priority: 200,
+                notification_class: Some(5),
+            }]
+        );
+        let unacked = GetEnrollmentSummaryRequest {
+            priority_filter: Some((90, 110)),
+            ..GetEnrollmentSummaryRequest::new(AcknowledgmentFilter::NotAcked)
+        };
+        assert_eq!(
+            get_enrollment_summary(&database, &unacked)
+                .enrollment_summaries
+                .len(),
+            2
+        );
+
+        // One summary per response walks the list with More_Events
+        let first = get_event_information(&database, &GetEventInformationRequest::default(), 1);
+        assert_eq!(first.list_of_event_summaries[0].object_identifier, ee(1));
+        assert_eq!(
+            first.list_of_event_summaries[0].event_priorities,
+            [100, 50, 200]
+        );
+        assert!(first.more_events);
+        let next = GetEventInformationRequest {
+            last_received_object_identifier: Some(ee(1)),
+        };
+        let second = get_event_information(&database, &next, 1);
+        assert_eq!(second.list_of_event_summaries[0].object_identifier, ee(3));
+        assert!(!second.more_events);
+
+        let mut service_data = Vec::new();
+        next.encode(&mut service_data).unwrap();
+        let Apdu::ComplexAck {
+            service_choice,
+            service_data,
+            ..
+        } = handle_get_event_information(&database, 1, &service_data)
+        else {
+            panic!("Expected ComplexAck");
+        };
+        assert_eq!(service_choice, 29);
+        assert_eq!(
+            GetEventInformationAck::decode(&service_data).unwrap(),
+            second
+        );
+        assert!(matches!(
+            handle_get_alarm_summary(&database, 2),
+            Apdu::ComplexAck {
+                service_choice: 3,
+                ..
+            }
+        ));
+        assert!(matches!(
+            handle_get_enrollment_summary(&database, 3, &[0xFF]),
+            Apdu::Reject { invoke_id: 3, .. }
+        ));
+    }
+}
@@ -0,0 +1,230 @@
+//! LifeSafetyOperation Service (Clause 13.13)
+//!
+//! An operator silences, unsilences or resets a Life Safety Point or Zone, or
+//! every such object in the device when the request names no object. The
+//! objects update Silenced and Operation_Expected themselves (see
+//! [`LifeSafetyStatus`](crate::object::LifeSafetyStatus)); the requesting
+//! process and source identify the operator for the audit trail.
+
+use super::event_notification::{encode_context_value, encode_identifier, Reader};
+use crate::encoding::{
+    encode_context_enumerated, encode_context_unsigned, ApplicationTag, EncodingError,
+    Result as EncodingResult,
+};
+use crate::object::{LifeSafetyOperation, ObjectIdentifier, PropertyValue};
+
+#[cfg(feature = "std")]
+use super::{ConfirmedServiceChoice, PropertyAccessError, RejectReason};
+#[cfg(feature = "std")]
+use crate::{
+    app::Apdu,
+    object::{database::ObjectDatabase, ObjectError, ObjectType},
+};
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, string::ToString, vec::Vec};
+
+/// Error class object (1), code unsupported-object-type (36)
+#[cfg(feature = "std")]
+const UNSUPPORTED_OBJECT_TYPE: PropertyAccessError = PropertyAccessError {
+    error_class: 1,
+    error_code: 36,
+};
+
+/// Life Safety Operation request (confirmed service)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LifeSafetyOperationRequest {
+    /// Process of the operator's workstation
+    pub requesting_process_identifier: u32,
+    /// Operator or device making the request
+    pub requesting_source: String,
+    /// Requested operation
+    pub request: LifeSafetyOperation,
+    /// Object to operate on; `None` means every life safety object
+    pub object_identifier: Option<ObjectIdentifier>,
+}
+
+impl LifeSafetyOperationRequest {
+    /// Create a new request for every life safety object in the device
+    pub fn new(
+        requesting_process_identifier: u32,
+        requesting_source: impl Into<String>,
+        request: LifeSafetyOperation,
+    ) -> Self {
+        Self {
+            requesting_process_identifier,
+            requesting_source: requesting_source.into(),
+            request,
+            object_identifier: None,
+        }
+    }
+
+    /// Restrict the request to one object
+    pub fn for_object(mut self, object_identifier: ObjectIdentifier) -> Self {
+        self.object_identifier = Some(object_identifier);
+        self
+    }
+
+    /// Encode the request
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        buffer.extend_from_slice(&encode_context_unsigned(
+            self.requesting_process_identifier,
+            0,
+        )?);
+        encode_context_value(
+            buffer,
+            &PropertyValue::CharacterString(self.requesting_source.clone()),
+            1,
+        )?;
+        buffer.extend_from_slice(&encode_context_enumerated(self.request as u32, 2)?);
+        if let Some(object_identifier) = &self.object_identifier {
+            encode_identifier(buffer, object_identifier, 3)?;
+        }
+        Ok(())
+    }
+
+    /// Decode a request
+    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
+        let mut reader = Reader::new(data);
+        let requesting_process_identifier = reader.unsigned(0)?;
+        let PropertyValue::CharacterString(requesting_source) =
+            reader.value(1, ApplicationTag::CharacterString)?
+        else {
+            return Err(EncodingError::InvalidTag);
+        };
+        let request = LifeSafetyOperation::try_from(reader.enumerated(2)?)
+            .map_err(|_| EncodingError::ValueOutOfRange)?;
+        let object_identifier = reader.optional(3, Reader::identifier)?;
+
+        if !reader.rest().is_empty() {
+            return Err(EncodingError::InvalidFormat(
+                "Unexpected data after Life Safety Operation request".to_string(),
+            ));
+        }
+        Ok(Self {
+            requesting_process_identifier,
+            requesting_source,
+            request,
+            object_identifier,
+        })
+    }
+}
+
+/// Apply a Life Safety Operation request to an object database
+///
+/// A named object must be a Life Safety Point or Zone.
+#[cfg(feature = "std")]
+pub fn life_safety_operation(
+    database: &ObjectDatabase,
+    request: &LifeSafetyOperationRequest,
+) -> Result<(), PropertyAccessError> {
+    if let Some(object_identifier) = request.object_identifier {
+        if !database.contains(object_identifier) {
+            return Err(ObjectError::NotFound.into());
+        }
+        if !matches!(
+            object_identifier.object_type,
+            ObjectType::LifeSafetyPoint | ObjectType::LifeSafetyZone
+        ) {
+            return Err(UNSUPPORTED_OBJECT_TYPE);
+        }
+    }
+    database.life_safety_operation(request.object_identifier, request.request)?;
+    Ok(())
+}
+
+/// Answer a Life Safety Operation request
+///
+/// Returns a SimpleAck once the operation is applied, an Error PDU if the
+/// object is missing or is not a life safety object, or a Reject PDU if the
+/// request cannot be decoded.
+#[cfg(feature = "std")]
+pub fn handle_life_safety_operation(
+    database: &ObjectDatabase,
+    invoke_id: u8,
+    service_data: &[u8],
+) -> Apdu {
+    let service_choice = ConfirmedServiceChoice::LifeSafetyOperation as u8;
+    let request = match LifeSafetyOperationRequest::decode(service_data) {
+        Ok(request) => request,
+        Err(error) => return Apdu::reject(invoke_id, RejectReason::for_decode_error(&error)),
+    };
+
+    match life_safety_operation(database, &request) {
+        Ok(()) => Apdu::SimpleAck {
+            invoke_id,
+            service_choice,
+        },
+        Err(error) => Apdu::error(invoke_id, service_choice, error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::{
+        Device, LifeSafetyPoint, LifeSafetyState, LifeSafetyZone, ObjectType, PropertyIdentifier,
+        SilencedState,
+    };
+
+    #[test]
+    fn test_request_codec() {
+        let request = LifeSafetyOperationRequest::new(18, "Op", LifeSafetyOperation::Reset)
+            .for_object(ObjectIdentifier::new(ObjectType::LifeSafetyPoint, 1));
+        let mut buffer = Vec::new();
+        request.encode(&mut buffer).unwrap();
+        assert_eq!(
+            buffer,
+            [0x09, 0x12, 0x1B, 0x00, b'O', b'p', 0x29, 0x04, 0x3C, 0x05, 0x40, 0x00, 0x01]
+        );
+        assert_eq!(
+            LifeSafetyOperationRequest::decode(&buffer).unwrap(),
+            request
+        );
+    }
+
+    #[test]
+    fn test_handle_life_safety_operation() {
+        let database = ObjectDatabase::new(Device::new(1, "Panel".to_string()));
+        let mut point = LifeSafetyPoint::new(1, "Smoke 1".to_string());
+        point.update_state(LifeSafetyState::Alarm);
+        database.add_object(Box::new(point)).unwrap();
+        let mut zone = LifeSafetyZone::new(1, "Floor 1".to_string());
+        zone.update_state(LifeSafetyState::Alarm);
+        database.add_object(Box::new(zone)).unwrap();
+
+        let mut service_data = Vec::new();
+        LifeSafetyOperationRequest::new(1, "Op", LifeSafetyOperation::Silence)
+            .encode(&mut service_data)
+            .unwrap();
+        assert!(matches!(
+            handle_life_safety_operation(&database, 5, &service_data),
+            Apdu::SimpleAck {
+                invoke_id: 5,
+                service_choice: 27,
+            }
+        ));
+        for object_type in [ObjectType::LifeSafetyPoint, ObjectType::LifeSafetyZone] {
+            let identifier = ObjectIdentifier::new(object_type, 1);
+            assert_eq!(
+                database
+                    .get_property(identifier, PropertyIdentifier::Silenced)
+                    .unwrap(),
+                PropertyValue::Enumerated(SilencedState::AllSilenced as u32)
+            );
+            assert_eq!(
+                database
+                    .get_property(identifier, PropertyIdentifier::OperationExpected)
+                    .unwrap(),
+                PropertyValue::Enumerated(LifeSafetyOperation::Reset as u32)
+            );
+        }
+
+        let request = LifeSafetyOperationRequest::new(1, "Op", LifeSafetyOperation::Reset)
+            .for_object(ObjectIdentifier::new(ObjectType::Device, 1));
+        assert_eq!(
+            life_safety_operation(&database, &request),
+            Err(UNSUPPORTED_OBJECT_TYPE)
+        );
+    }
+}
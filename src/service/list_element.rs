@@ -0,0 +1,587 @@
+//! AddListElement and RemoveListElement Services (Clauses 15.1 and 15.2)
+//!
+//! Both services change a list property by element rather than rewriting it
+//! whole, which lets several clients edit a shared list such as a
+//! Recipient_List without overwriting each other. Each request is all or
+//! nothing: when an element cannot be added or removed the list is left as it
+//! was and the error names the first element that failed.
+//!
+//! Objects take part through [`BacnetObject::add_list_elements`] and
+//! [`BacnetObject::remove_list_elements`], whose defaults work on any property
+//! read and written as a [`PropertyValue::List`].
+
+use super::read_property::{
+    decode_property_reference, decode_property_value, encode_property_reference,
+    encode_property_value,
+};
+use super::read_property_multiple::expect_tag;
+use super::{AbortReason, ConfirmedServiceChoice, PropertyAccessError};
+use crate::app::Apdu;
+use crate::encoding::{
+    advanced::context::{encode_closing_tag, encode_opening_tag},
+    decode_context_unsigned, decode_enumerated, encode_context_unsigned, encode_enumerated,
+    EncodingError, Result as EncodingResult,
+};
+use crate::object::{BacnetObject, ObjectIdentifier, PropertyIdentifier, PropertyValue};
+
+#[cfg(feature = "std")]
+use super::RejectReason;
+#[cfg(feature = "std")]
+use crate::object::{database::ObjectDatabase, ObjectError};
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::ToString, vec::Vec};
+
+/// Error class services (5), code property-is-not-a-list (22)
+const PROPERTY_IS_NOT_A_LIST: PropertyAccessError = PropertyAccessError {
+    error_class: 5,
+    error_code: 22,
+};
+
+/// Error class services (5), code list-element-not-found (81)
+const LIST_ELEMENT_NOT_FOUND: PropertyAccessError = PropertyAccessError {
+    error_class: 5,
+    error_code: 81,
+};
+
+/// Error class property (2), code write-access-denied (40)
+const WRITE_ACCESS_DENIED: PropertyAccessError = PropertyAccessError {
+    error_class: 2,
+    error_code: 40,
+};
+
+/// Error class property (2), code property-is-not-an-array (50)
+#[cfg(feature = "std")]
+const PROPERTY_IS_NOT_AN_ARRAY: PropertyAccessError = PropertyAccessError {
+    error_class: 2,
+    error_code: 50,
+};
+
+/// Add List Element or Remove List Element request (confirmed service)
+///
+/// Both services carry the same parameters.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListElementRequest {
+    /// Object holding the list
+    pub object_identifier: ObjectIdentifier,
+    /// List property to change
+    pub property_identifier: u32,
+    /// Array index, for an array whose elements are lists (optional)
+    pub property_array_index: Option<u32>,
+    /// Elements to add or remove
+    pub list_of_elements: Vec<PropertyValue>,
+}
+
+impl ListElementRequest {
+    /// Create a new request changing `property_identifier` by `list_of_elements`
+    pub fn new(
+        object_identifier: ObjectIdentifier,
+        property_identifier: u32,
+        list_of_elements: Vec<PropertyValue>,
+    ) -> Self {
+        Self {
+            object_identifier,
+            property_identifier,
+            property_array_index: None,
+            list_of_elements,
+        }
+    }
+
+    /// Encode the request
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        encode_property_reference(
+            buffer,
+            self.object_identifier,
+            self.property_identifier,
+            self.property_array_index,
+        )?;
+
+        // List of elements - context tag 3
+        encode_opening_tag(buffer, 3)?;
+        for element in &self.list_of_elements {
+            encode_property_value(buffer, element)?;
+        }
+        encode_closing_tag(buffer, 3)?;
+
+        Ok(())
+    }
+
+    /// Decode a request
+    ///
+    /// The encoding has no boundaries between elements, so the list holds one
+    /// value per application-tagged item; the server regroups them with
+    /// [`BacnetObject::list_elements`].
+    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
+        let (object_identifier, property_identifier, property_array_index, mut pos) =
+            decode_property_reference(data)?;
+
+        expect_tag(data, pos, 0x3E)?;
+        pos += 1;
+        let (value, consumed) = decode_property_value(&data[pos..])?;
+        pos += consumed;
+        expect_tag(data, pos, 0x3F)?;
+        pos += 1;
+
+        if pos != data.len() {
+            return Err(EncodingError::InvalidFormat(
+                "Unexpected data after list element request".to_string(),
+            ));
+        }
+
+        let list_of_elements = match value {
+            PropertyValue::Array(items) => items,
+            value => vec![value],
+        };
+        Ok(Self {
+            object_identifier,
+            property_identifier,
+            property_array_index,
+            list_of_elements,
+        })
+    }
+}
+
+/// Error returned when an Add List Element or Remove List Element request
+/// fails (Change-List-Error)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChangeListError {
+    /// Why the change failed
+    pub error: PropertyAccessError,
+    /// 1-based number of the first element that failed, or 0 if the failure
+    /// was not caused by an element
+    pub first_failed_element_number: u32,
+}
+
+impl ChangeListError {
+    /// Create a new Change List error
+    pub fn new(error: PropertyAccessError, first_failed_element_number: u32) -> Self {
+        Self {
+            error,
+            first_failed_element_number,
+        }
+    }
+
+    /// Encode the Change List error parameters
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        // Error type - context tag 0
+        encode_opening_tag(buffer, 0)?;
+        encode_enumerated(buffer, self.error.error_class)?;
+        encode_enumerated(buffer, self.error.error_code)?;
+        encode_closing_tag(buffer, 0)?;
+
+        self.encode_parameters(buffer)
+    }
+
+    /// Decode the Change List error parameters
+    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
+        expect_tag(data, 0, 0x0E)?;
+        let mut pos = 1;
+        let (error_class, consumed) = decode_enumerated(&data[pos..])?;
+        pos += consumed;
+        let (error_code, consumed) = decode_enumerated(&data[pos..])?;
+        pos += consumed;
+        expect_tag(data, pos, 0x0F)?;
+        pos += 1;
+
+        Self::decode_parameters(
+            PropertyAccessError {
+                error_class,
+                error_code,
+            },
+            &data[pos..],
+        )
+    }
+
+    /// An Error PDU reporting this failure for the request `invoke_id`
+    pub fn to_apdu(
+        &self,
+        invoke_id: u8,
+        service_choice: ConfirmedServiceChoice,
+    ) -> EncodingResult<Apdu> {
+        let mut parameters = Vec::new();
+        self.encode_parameters(&mut parameters)?;
+        Ok(Apdu::error_with_parameters(
+            invoke_id,
+            service_choice as u8,
+            self.error,
+            parameters,
+        ))
+    }
+
+    /// The failure reported by an AddListElement, RemoveListElement or
+    /// CreateObject Error PDU, if `apdu` is one
+    pub fn from_apdu(apdu: &Apdu) -> Option<Self> {
+        const SERVICES: [ConfirmedServiceChoice; 3] = [
+            ConfirmedServiceChoice::AddListElement,
+            ConfirmedServiceChoice::RemoveListElement,
+            ConfirmedServiceChoice::CreateObject,
+        ];
+        match apdu {
+            Apdu::Error {
+                service_choice,
+                error_class,
+                error_code,
+                error_parameters,
+                ..
+            } if SERVICES
+                .iter()
+                .any(|&service| service as u8 == *service_choice) =>
+            {
+                let error = PropertyAccessError {
+                    error_class: *error_class,
+                    error_code: *error_code,
+                };
+                Self::decode_parameters(error, error_parameters).ok()
+            }
+            _ => None,
+        }
+    }
+
+    fn encode_parameters(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        // First failed element number - context tag 1
+        buffer.extend_from_slice(&encode_context_unsigned(
+            self.first_failed_element_number,
+            1,
+        )?);
+        Ok(())
+    }
+
+    fn decode_parameters(error: PropertyAccessError, data: &[u8]) -> EncodingResult<Self> {
+        let (first_failed_element_number, consumed) = decode_context_unsigned(data, 1)?;
+        if consumed != data.len() {
+            return Err(EncodingError::InvalidFormat(
+                "Unexpected data after Change List error".to_string(),
+            ));
+        }
+        Ok(Self::new(error, first_failed_element_number))
+    }
+}
+
+/// Read a list property and check that it can be written
+fn writable_list<T: BacnetObject + ?Sized>(
+    object: &T,
+    property: PropertyIdentifier,
+) -> Result<Vec<PropertyValue>, ChangeListError> {
+    let list = match object.get_property(property) {
+        Ok(PropertyValue::List(list)) => list,
+        Ok(_) => return Err(ChangeListError::new(PROPERTY_IS_NOT_A_LIST, 0)),
+        Err(error) => return Err(ChangeListError::new(PropertyAccessError::from(&error), 0)),
+    };
+    if !object.is_property_writable(property) {
+        return Err(ChangeListError::new(WRITE_ACCESS_DENIED, 0));
+    }
+    Ok(list)
+}
+
+/// Default [`BacnetObject::add_list_elements`]
+///
+/// Writes the list after each new element so that a rejected element can be
+/// named, then restores the original list if any is rejected.
+pub(crate) fn add_list_elements<T: BacnetObject + ?Sized>(
+    object: &mut T,
+    property: PropertyIdentifier,
+    elements: &[PropertyValue],
+) -> Result<(), ChangeListError> {
+    let original = writable_list(object, property)?;
+    let mut list = original.clone();
+    for (number, element) in (1..).zip(elements) {
+        if list.contains(element) {
+            continue;
+        }
+        list.push(element.clone());
+        if let Err(error) = object.set_property(property, PropertyValue::List(list.clone())) {
+            // Restoring a list the object held a moment ago cannot fail
+            let _ = object.set_property(property, PropertyValue::List(original));
+            return Err(ChangeListError::new(
+                PropertyAccessError::from(&error),
+                number,
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Default [`BacnetObject::remove_list_elements`]
+pub(crate) fn remove_list_elements<T: BacnetObject + ?Sized>(
+    object: &mut T,
+    property: PropertyIdentifier,
+    elements: &[PropertyValue],
+) -> Result<(), ChangeListError> {
+    let mut list = writable_list(object, property)?;
+    for (number, element) in (1..).zip(elements) {
+        let index = list
+            .iter()
+            .position(|item| item == element)
+            .ok_or(ChangeListError::new(LIST_ELEMENT_NOT_FOUND, number))?;
+        list.remove(index);
+    }
+    object
+        .set_property(property, PropertyValue::List(list))
+        .map_err(|error| ChangeListError::new(PropertyAccessError::from(&error), 0))
+}
+
+/// Apply an Add List Element request to an object database
+#[cfg(feature = "std")]
+pub fn add_list_element(
+    database: &ObjectDatabase,
+    request: &ListElementRequest,
+) -> Result<(), ChangeListError> {
+    let property = list_property(request)?;
+    database.add_list_elements(
+        request.object_identifier,
+        property,
+        request.list_of_elements.clone(),
+    )
+}
+
+/// Apply a Remove List Element request to an object database
+#[cfg(feature = "std")]
+pub fn remove_list_element(
+    database: &ObjectDatabase,
+    request: &ListElementRequest,
+) -> Result<(), ChangeListError> {
+    let property = list_property(request)?;
+    database.remove_list_elements(
+        request.object_identifier,
+        property,
+        request.list_of_elements.clone(),
+    )
+}
+
+/// The list property a request changes
+///
+/// Arrays of lists are not supported, so an array index is refused.
+#[cfg(feature = "std")]
+fn list_property(request: &ListElementRequest) -> Result<PropertyIdentifier, ChangeListError> {
+    if request.property_array_index.is_some() {
+        return Err(ChangeListError::new(PROPERTY_IS_NOT_AN_ARRAY, 0));
+    }
+    PropertyIdentifier::try_from(request.property_identifier).map_err(|_| {
+        ChangeListError::new(PropertyAccessError::from(&ObjectError::UnknownProperty), 0)
+    })
+}
+
+/// Answer an Add List Element request
+///
+/// Returns a SimpleAck once every element is in the list, an Error PDU if any
+/// element could not be added, or a Reject PDU if the request cannot be
+/// decoded.
+#[cfg(feature = "std")]
+pub fn handle_add_list_element(
+    database: &ObjectDatabase,
+    invoke_id: u8,
+    service_data: &[u8],
+) -> Apdu {
+    handle(
+        invoke_id,
+        ConfirmedServiceChoice::AddListElement,
+        service_data,
+        |request| add_list_element(database, request),
+    )
+}
+
+/// Answer a Remove List Element request
+///
+/// Returns a SimpleAck once every element is gone from the list, an Error PDU
+/// if any element could not be removed, or a Reject PDU if the request cannot
+/// be decoded.
+#[cfg(feature = "std")]
+pub fn handle_remove_list_element(
+    database: &ObjectDatabase,
+    invoke_id: u8,
+    service_data: &[u8],
+) -> Apdu {
+    handle(
+        invoke_id,
+        ConfirmedServiceChoice::RemoveListElement,
+        service_data,
+        |request| remove_list_element(database, request),
+    )
+}
+
+#[cfg(feature = "std")]
+fn handle(
+    invoke_id: u8,
+    service_choice: ConfirmedServiceChoice,
+    service_data: &[u8],
+    apply: impl FnOnce(&ListElementRequest) -> Result<(), ChangeListError>,
+) -> Apdu {
+    let request = match ListElementRequest::decode(service_data) {
+        Ok(request) => request,
+        Err(error) => return Apdu::reject(invoke_id, RejectReason::for_decode_error(&error)),
+    };
+
+    match apply(&request) {
+        Ok(()) => Apdu::SimpleAck {
+            invoke_id,
+            service_choice: service_choice as u8,
+        },
+        Err(failure) => failure
+            .to_apdu(invoke_id, service_choice)
+            .unwrap_or_else(|error| {
+                Apdu::abort(true, invoke_id, AbortReason::for_encode_error(&error))
+            }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::ObjectType;
+
+    #[test]
+    fn test_codecs() {
+        let request = ListElementRequest::new(
+            ObjectIdentifier::new(ObjectType::Calendar, 1),
+            u32::from(PropertyIdentifier::DateList),
+            vec![
+                PropertyValue::OctetString(vec![12, 0xFF, 7]),
+                PropertyValue::OctetString(vec![1, 0xFF, 0xFF]),
+            ],
+        );
+        let mut buffer = Vec::new();
+        request.encode(&mut buffer).unwrap();
+        assert_eq!(ListElementRequest::decode(&buffer).unwrap(), request);
+
+        let error = ChangeListError::new(LIST_ELEMENT_NOT_FOUND, 2);
+        let mut buffer = Vec::new();
+        error.encode(&mut buffer).unwrap();
+        assert_eq!(buffer, [0x0E, 0x91, 0x05, 0x91, 0x51, 0x0F, 0x19, 0x02]);
+        assert_eq!(ChangeListError::decode(&buffer).unwrap(), error);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_handle_list_element_services() {
+        use crate::object::{Calendar, Date, Destination, Device, NotificationClass, Recipient};
+
+        let database = ObjectDatabase::new(Device::new(15, String::from("Device")));
+        database
+            .add_object(Box::new(Calendar::new(1, String::from("Holidays"))))
+            .unwrap();
+        let mut nc = NotificationClass::new(5, String::from("Alarms"));
+        nc.add_recipient(Destination::new(
+            Recipient::Device(ObjectIdentifier::new(ObjectType::Device, 99)),
+            8,
+        ));
+        database.add_object(Box::new(nc)).unwrap();
+        let calendar = ObjectIdentifier::new(ObjectType::Calendar, 1);
+        let nc = ObjectIdentifier::new(ObjectType::NotificationClass, 5);
+
+        let date = Date {
+            year: 2024,
+            month: 12,
+            day: 25,
+            weekday: 3,
+        };
+        let christmas = PropertyValue::Date(date);
+        let add = ListElementRequest::new(
+            calendar,
+            u32::from(PropertyIdentifier::DateList),
+            vec![christmas.clone(), christmas.clone()],
+        );
+        assert_eq!(add_list_element(&database, &add), Ok(()));
+        assert_eq!(
+            database
+                .get_property(calendar, PropertyIdentifier::DateList)
+                .unwrap(),
+            PropertyValue::List(vec![christmas.clone()])
+        );
+
+        // An invalid element leaves the list untouched
+        let invalid = ListElementRequest::new(
+            calendar,
+            u32::from(PropertyIdentifier::DateList),
+            vec![
+                PropertyValue::Date(Date { day: 26, ..date }),
+                PropertyValue::Real(1.0),
+            ],
+        );
+        assert_eq!(
+            add_list_element(&database, &invalid)
+                .unwrap_err()
+                .first_failed_element_number,
+            2
+        );
+        assert_eq!(
+            database
+                .get_property(calendar, PropertyIdentifier::DateList)
+                .unwrap(),
+            PropertyValue::List(vec![christmas.clone()])
+        );
+
+        // Recipient_List destinations arrive as flat values and are regrouped
+        let operator = Destination::new(
+            Recipient::Address {
+                network: 5,
+                mac_address: vec![10, 0, 0, 7, 0xBA, 0xC0],
+            },
+            3,
+        );
+        let mut flat = Vec::new();
+        ListElementRequest::new(
+            nc,
+            u32::from(PropertyIdentifier::RecipientList),
+            vec![operator.to_property_value()],
+        )
+        .encode(&mut flat)
+        .unwrap();
+        assert!(matches!(
+            handle_add_list_element(&database, 1, &flat),
+            Apdu::SimpleAck {
+                service_choice: 8,
+                ..
+            }
+        ));
+        assert_eq!(
+            database.notification_class(5).unwrap().recipient_list[1],
+            operator
+        );
+
+        let remove = ListElementRequest::new(
+            nc,
+            u32::from(PropertyIdentifier::RecipientList),
+            vec![operator.to_property_value(), operator.to_property_value()],
+        );
+        assert_eq!(
+            remove_list_element(&database, &remove),
+            Err(ChangeListError::new(LIST_ELEMENT_NOT_FOUND, 2))
+        );
+        // The failing element number reaches the client
+        let mut service_data = Vec::new();
+        remove.encode(&mut service_data).unwrap();
+        let reply = handle_remove_list_element(&database, 3, &service_data);
+        let received = Apdu::decode(&reply.encode()).unwrap();
+        assert_eq!(received, reply);
+        assert_eq!(
+            ChangeListError::from_apdu(&received),
+            Some(ChangeListError::new(LIST_ELEMENT_NOT_FOUND, 2))
+        );
+        let mut service_data = Vec::new();
+        ListElementRequest::new(
+            nc,
+            u32::from(PropertyIdentifier::RecipientList),
+            vec![operator.to_property_value()],
+        )
+        .encode(&mut service_data)
+        .unwrap();
+        assert!(matches!(
+            handle_remove_list_element(&database, 2, &service_data),
+            Apdu::SimpleAck { .. }
+        ));
+        assert_eq!(
+            database.notification_class(5).unwrap().recipient_list.len(),
+            1
+        );
+
+        let not_a_list = ListElementRequest::new(
+            nc,
+            u32::from(PropertyIdentifier::Priority),
+            vec![PropertyValue::UnsignedInteger(1)],
+        );
+        assert_eq!(
+            add_list_element(&database, &not_a_list),
+            Err(ChangeListError::new(PROPERTY_IS_NOT_A_LIST, 0))
+        );
+    }
+}
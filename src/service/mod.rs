@@ -90,7 +90,7 @@
 //!
 //! // Create a read property request
 //! let object_id = ObjectIdentifier::new(ObjectType::AnalogInput, 1);
-//! let request = ReadPropertyRequest::new(object_id, PropertyIdentifier::PresentValue as u32);
+//! let request = ReadPropertyRequest::new(object_id, u32::from(PropertyIdentifier::PresentValue));
 //!
 //! // This would be sent as a confirmed service
 //! let service_choice = ConfirmedServiceChoice::ReadProperty;
@@ -117,9 +117,9 @@
 //! // Create a read property multiple request
 //! let object_id = ObjectIdentifier::new(ObjectType::Device, 12345);
 //! let property_refs = vec![
-//!     PropertyReference::new(PropertyIdentifier::ObjectName as u32),
+//!     PropertyReference::new(u32::from(PropertyIdentifier::ObjectName)),
 //!     PropertyReference::new(70), // ModelName
-//!     PropertyReference::new(PropertyIdentifier::VendorName as u32),
+//!     PropertyReference::new(u32::from(PropertyIdentifier::VendorName)),
 //! ];
 //! let spec = ReadAccessSpecification::new(object_id, property_refs);
 //!
@@ -192,6 +192,8 @@ pub enum ServiceError {
     Rejected(RejectReason),
     /// Service aborted by remote device
     Aborted(AbortReason),
+    /// Service failed, with the class and code of the Error PDU
+    Failed(PropertyAccessError),
     /// Encoding/decoding error
     EncodingError(String),
     /// Unsupported service choice
@@ -204,8 +206,13 @@ impl fmt::Display for ServiceError {
             ServiceError::UnsupportedService => write!(f, "Service not supported"),
             ServiceError::InvalidParameters(msg) => write!(f, "Invalid parameters: {}", msg),
             ServiceError::Timeout => write!(f, "Service timeout"),
-            ServiceError::Rejected(reason) => write!(f, "Service rejected: {:?}", reason),
-            ServiceError::Aborted(reason) => write!(f, "Service aborted: {:?}", reason),
+            ServiceError::Rejected(reason) => write!(f, "Service rejected: {}", reason),
+            ServiceError::Aborted(reason) => write!(f, "Service aborted: {}", reason),
+            ServiceError::Failed(error) => write!(
+                f,
+                "Service failed: class {} code {}",
+                error.error_class, error.error_code
+            ),
             ServiceError::EncodingError(msg) => write!(f, "Encoding error: {}", msg),
             ServiceError::UnsupportedServiceChoice(choice) => {
                 write!(f, "Unsupported service choice: {}", choice)
@@ -217,16 +224,66 @@ impl fmt::Display for ServiceError {
 #[cfg(feature = "std")]
 impl Error for ServiceError {}
 
+impl ServiceError {
+    /// The PDU a server answers a confirmed request with when its service
+    /// fails with this error
+    ///
+    /// Requests that cannot be decoded or are not understood are rejected,
+    /// failures of the service itself answered with an Error PDU, and
+    /// failures of the transaction aborted.
+    pub fn to_apdu(&self, invoke_id: u8, service_choice: u8) -> Apdu {
+        match self {
+            ServiceError::UnsupportedService | ServiceError::UnsupportedServiceChoice(_) => {
+                Apdu::reject(invoke_id, RejectReason::UnrecognizedService)
+            }
+            ServiceError::InvalidParameters(_) => {
+                Apdu::reject(invoke_id, RejectReason::InconsistentParameters)
+            }
+            ServiceError::EncodingError(_) => Apdu::reject(invoke_id, RejectReason::InvalidTag),
+            ServiceError::Rejected(reason) => Apdu::reject(invoke_id, *reason),
+            ServiceError::Timeout => {
+                Apdu::abort(true, invoke_id, AbortReason::ApplicationExceededReplyTime)
+            }
+            ServiceError::Aborted(reason) => Apdu::abort(true, invoke_id, *reason),
+            ServiceError::Failed(error) => Apdu::error(invoke_id, service_choice, *error),
+        }
+    }
+
+    /// The error an Error, Reject or Abort PDU answering a confirmed request
+    /// reports
+    pub fn from_apdu(apdu: &Apdu) -> Option<Self> {
+        match *apdu {
+            Apdu::Error {
+                error_class,
+                error_code,
+                ..
+            } => Some(ServiceError::Failed(PropertyAccessError {
+                error_class,
+                error_code,
+            })),
+            Apdu::Reject { reject_reason, .. } => {
+                Some(ServiceError::Rejected(RejectReason::from_u8(reject_reason)))
+            }
+            Apdu::Abort { abort_reason, .. } => {
+                Some(ServiceError::Aborted(AbortReason::from_u8(abort_reason)))
+            }
+            _ => None,
+        }
+    }
+}
+
 /// Confirmed service choices
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
 pub enum ConfirmedServiceChoice {
     // Alarm and Event Services
     AcknowledgeAlarm = 0,
+    ConfirmedCOVNotification = 1,
     ConfirmedEventNotification = 2,
     GetAlarmSummary = 3,
     GetEnrollmentSummary = 4,
     GetEventInformation = 29,
+    LifeSafetyOperation = 27,
 
     // File Access Services
     AtomicReadFile = 6,
@@ -244,6 +301,8 @@ pub enum ConfirmedServiceChoice {
 
     // Remote Device Management Services
     DeviceCommunicationControl = 17,
+    ConfirmedPrivateTransfer = 18,
+    ConfirmedTextMessage = 19,
     ReinitializeDevice = 20,
 
     // Virtual Terminal Services
@@ -255,10 +314,15 @@ pub enum ConfirmedServiceChoice {
     Authenticate = 24,
     RequestKey = 25,
 
+    // Audit Reporting Services
+    ConfirmedAuditNotification = 32,
+    AuditLogQuery = 33,
+
     // Other Services
     ReadRange = 26,
     SubscribeCOV = 5,
     SubscribeCOVProperty = 28,
+    SubscribeCOVPropertyMultiple = 30,
 
     // Protocol Revision 30 - Security Services
     AuthRequest = 34,
@@ -270,10 +334,12 @@ impl TryFrom<u8> for ConfirmedServiceChoice {
     fn try_from(value: u8) -> Result<Self> {
         match value {
             0 => Ok(Self::AcknowledgeAlarm),
+            1 => Ok(Self::ConfirmedCOVNotification),
             2 => Ok(Self::ConfirmedEventNotification),
             3 => Ok(Self::GetAlarmSummary),
             4 => Ok(Self::GetEnrollmentSummary),
             29 => Ok(Self::GetEventInformation),
+            27 => Ok(Self::LifeSafetyOperation),
             6 => Ok(Self::AtomicReadFile),
             7 => Ok(Self::AtomicWriteFile),
             8 => Ok(Self::AddListElement),
@@ -285,15 +351,20 @@ impl TryFrom<u8> for ConfirmedServiceChoice {
             15 => Ok(Self::WriteProperty),
             16 => Ok(Self::WritePropertyMultiple),
             17 => Ok(Self::DeviceCommunicationControl),
+            18 => Ok(Self::ConfirmedPrivateTransfer),
+            19 => Ok(Self::ConfirmedTextMessage),
             20 => Ok(Self::ReinitializeDevice),
             21 => Ok(Self::VtOpen),
             22 => Ok(Self::VtClose),
             23 => Ok(Self::VtData),
             24 => Ok(Self::Authenticate),
             25 => Ok(Self::RequestKey),
+            32 => Ok(Self::ConfirmedAuditNotification),
+            33 => Ok(Self::AuditLogQuery),
             26 => Ok(Self::ReadRange),
             5 => Ok(Self::SubscribeCOV),
             28 => Ok(Self::SubscribeCOVProperty),
+            30 => Ok(Self::SubscribeCOVPropertyMultiple),
             34 => Ok(Self::AuthRequest),
             _ => Err(ServiceError::UnsupportedServiceChoice(value)),
         }
@@ -345,1289 +416,294 @@ impl TryFrom<u8> for UnconfirmedServiceChoice {
     }
 }
 
-/// Reject reason codes
+/// Reject reason codes (Clause 21, BACnetRejectReason)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
 pub enum RejectReason {
+    /// Other, including proprietary reasons
     Other = 0,
+    /// The request is too long to be processed
     BufferOverflow = 1,
+    /// Parameters are valid alone but not together
     InconsistentParameters = 2,
+    /// A parameter has the wrong datatype
     InvalidParameterDataType = 3,
+    /// A tag is not valid at its position
     InvalidTag = 4,
+    /// A required parameter is missing
     MissingRequiredParameter = 5,
+    /// A parameter is out of its range
     ParameterOutOfRange = 6,
+    /// The request has more parameters than the service takes
     TooManyArguments = 7,
+    /// An enumerated parameter has an undefined value
     UndefinedEnumeration = 8,
+    /// The service is unknown or not supported
     UnrecognizedService = 9,
 }
 
-/// Abort reason codes
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum AbortReason {
-    Other = 0,
-    BufferOverflow = 1,
-    InvalidApduInThisState = 2,
-    PreemptedByHigherPriorityTask = 3,
-    SegmentationNotSupported = 4,
-}
-
-use crate::encoding::{
-    decode_context_enumerated, decode_context_object_id, decode_context_unsigned,
-    decode_enumerated, decode_object_identifier, decode_unsigned, encode_context_enumerated,
-    encode_context_object_id, encode_context_unsigned, encode_enumerated, encode_object_identifier,
-    encode_unsigned, Result as EncodingResult,
-};
-use crate::object::{ObjectIdentifier, PropertyValue};
-
-/// Special array index value indicating all elements
-pub const BACNET_ARRAY_ALL: u32 = 0xFFFFFFFF;
-
-/// Who-Is request (unconfirmed service)
-#[derive(Debug, Clone, PartialEq, Eq, Default)]
-pub struct WhoIsRequest {
-    /// Low limit of device instance range (optional)
-    pub device_instance_range_low_limit: Option<u32>,
-    /// High limit of device instance range (optional)
-    pub device_instance_range_high_limit: Option<u32>,
-}
-
-impl WhoIsRequest {
-    /// Create a new Who-Is request for all devices
-    pub fn new() -> Self {
-        Self {
-            device_instance_range_low_limit: None,
-            device_instance_range_high_limit: None,
-        }
-    }
-
-    /// Create a new Who-Is request for a specific device
-    pub fn for_device(device_instance: u32) -> Self {
-        Self {
-            device_instance_range_low_limit: Some(device_instance),
-            device_instance_range_high_limit: Some(device_instance),
-        }
-    }
-
-    /// Create a new Who-Is request for a range of devices
-    pub fn for_range(low: u32, high: u32) -> Self {
-        Self {
-            device_instance_range_low_limit: Some(low),
-            device_instance_range_high_limit: Some(high),
-        }
-    }
-
-    /// Encode the Who-Is request
-    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
-        // Both low and high limits must be present together, or both absent
-        // This matches bacnet-stack behavior
-        if let (Some(low), Some(high)) = (
-            self.device_instance_range_low_limit,
-            self.device_instance_range_high_limit,
-        ) {
-            // Context tag 0 - low limit
-            let low_bytes = encode_context_unsigned(low, 0)?;
-            buffer.extend_from_slice(&low_bytes);
-
-            // Context tag 1 - high limit
-            let high_bytes = encode_context_unsigned(high, 1)?;
-            buffer.extend_from_slice(&high_bytes);
-        }
-        // If only one limit is present, encode nothing (broadcast to all)
-
-        Ok(())
-    }
-
-    /// Decode a Who-Is request
-    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
-        let mut request = WhoIsRequest::new();
-        let mut pos = 0;
-
-        // Try to decode context tag 0 (low limit)
-        if pos < data.len() {
-            match decode_context_unsigned(&data[pos..], 0) {
-                Ok((low, consumed)) => {
-                    request.device_instance_range_low_limit = Some(low);
-                    pos += consumed;
-
-                    // If we have low limit, we must have high limit
-                    if pos < data.len() {
-                        match decode_context_unsigned(&data[pos..], 1) {
-                            Ok((high, _consumed)) => {
-                                request.device_instance_range_high_limit = Some(high);
-                            }
-                            Err(_) => {
-                                // Invalid format - low without high
-                                return Err(crate::encoding::EncodingError::InvalidFormat(
-                                    "Who-Is request has low limit without high limit".to_string(),
-                                ));
-                            }
-                        }
-                    }
-                }
-                Err(_) => {
-                    // No device range specified - broadcast to all
-                }
-            }
-        }
-
-        Ok(request)
-    }
-
-    /// Check if this request matches a device instance
-    pub fn matches(&self, device_instance: u32) -> bool {
-        match (
-            self.device_instance_range_low_limit,
-            self.device_instance_range_high_limit,
-        ) {
-            (None, None) => true, // Matches all devices
-            (Some(low), Some(high)) => device_instance >= low && device_instance <= high,
-            (Some(low), None) => device_instance >= low,
-            (None, Some(high)) => device_instance <= high,
-        }
-    }
-}
-
-/// I-Am response (unconfirmed service)
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct IAmRequest {
-    /// Device object identifier
-    pub device_identifier: ObjectIdentifier,
-    /// Maximum APDU length accepted
-    pub max_apdu_length_accepted: u32,
-    /// Segmentation supported
-    pub segmentation_supported: u32,
-    /// Vendor identifier
-    pub vendor_identifier: u32,
-}
-
-impl IAmRequest {
-    /// Create a new I-Am request
-    pub fn new(
-        device_identifier: ObjectIdentifier,
-        max_apdu_length_accepted: u32,
-        segmentation_supported: u32,
-        vendor_identifier: u32,
-    ) -> Self {
-        Self {
-            device_identifier,
-            max_apdu_length_accepted,
-            segmentation_supported,
-            vendor_identifier,
-        }
-    }
-
-    /// Encode the I-Am request
-    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
-        // Device identifier (object identifier) - application tag
-        encode_object_identifier(
-            buffer,
-            self.device_identifier.object_type as u16,
-            self.device_identifier.instance,
-        )?;
-
-        // Maximum APDU length accepted - application tag
-        encode_unsigned(buffer, self.max_apdu_length_accepted)?;
-
-        // Segmentation supported - application tag (enumerated)
-        encode_enumerated(buffer, self.segmentation_supported)?;
-
-        // Vendor identifier - application tag
-        encode_unsigned(buffer, self.vendor_identifier)?;
-
-        Ok(())
-    }
-
-    /// Decode an I-Am request
-    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
-        let mut pos = 0;
-
-        // Decode device identifier - application tag
-        let ((object_type, instance), consumed) = decode_object_identifier(&data[pos..])?;
-        let device_identifier = ObjectIdentifier {
-            object_type: crate::object::ObjectType::try_from(object_type)
-                .unwrap_or(crate::object::ObjectType::Device),
-            instance,
-        };
-        pos += consumed;
-
-        // Decode max APDU length accepted - application tag
-        let (max_apdu_length_accepted, consumed) = decode_unsigned(&data[pos..])?;
-        pos += consumed;
-
-        // Decode segmentation supported - application tag (enumerated)
-        let (segmentation_supported, consumed) = decode_enumerated(&data[pos..])?;
-        pos += consumed;
-
-        // Decode vendor identifier - application tag
-        let (vendor_identifier, _consumed) = decode_unsigned(&data[pos..])?;
-
-        Ok(IAmRequest::new(
-            device_identifier,
-            max_apdu_length_accepted,
-            segmentation_supported,
-            vendor_identifier,
-        ))
-    }
-}
-
-/// Read Property request (confirmed service)
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct ReadPropertyRequest {
-    /// Object identifier to read from
-    pub object_identifier: ObjectIdentifier,
-    /// Property identifier to read
-    pub property_identifier: u32,
-    /// Property array index (optional)
-    pub property_array_index: Option<u32>,
-}
-
-impl ReadPropertyRequest {
-    /// Create a new Read Property request
-    pub fn new(object_identifier: ObjectIdentifier, property_identifier: u32) -> Self {
-        Self {
-            object_identifier,
-            property_identifier,
-            property_array_index: None,
-        }
-    }
-
-    /// Create a new Read Property request with array index
-    pub fn with_array_index(
-        object_identifier: ObjectIdentifier,
-        property_identifier: u32,
-        array_index: u32,
-    ) -> Self {
-        Self {
-            object_identifier,
-            property_identifier,
-            property_array_index: Some(array_index),
-        }
-    }
-
-    /// Encode the Read Property request
-    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
-        // Object identifier - context tag 0
-        let obj_id_bytes = encode_context_object_id(
-            self.object_identifier.object_type as u16,
-            self.object_identifier.instance,
-            0,
-        )?;
-        buffer.extend_from_slice(&obj_id_bytes);
-
-        // Property identifier - context tag 1 (as enumerated)
-        let prop_id_bytes = encode_context_enumerated(self.property_identifier, 1)?;
-        buffer.extend_from_slice(&prop_id_bytes);
-
-        // Property array index - context tag 2 (optional)
-        if let Some(array_index) = self.property_array_index {
-            let array_bytes = encode_context_unsigned(array_index, 2)?;
-            buffer.extend_from_slice(&array_bytes);
-        }
-
-        Ok(())
-    }
-}
-
-/// Read Property response (confirmed service)
-#[derive(Debug, Clone)]
-pub struct ReadPropertyResponse {
-    /// Object identifier that was read
-    pub object_identifier: ObjectIdentifier,
-    /// Property identifier that was read
-    pub property_identifier: u32,
-    /// Property array index (optional)
-    pub property_array_index: Option<u32>,
-    /// Property value
-    pub property_value: Vec<u8>, // Raw encoded property value
-}
-
-impl ReadPropertyResponse {
-    /// Create a new Read Property response
-    pub fn new(
-        object_identifier: ObjectIdentifier,
-        property_identifier: u32,
-        property_value: Vec<u8>,
-    ) -> Self {
-        Self {
-            object_identifier,
-            property_identifier,
-            property_array_index: None,
-            property_value,
+impl RejectReason {
+    /// The reason for an octet, `Other` for unknown and proprietary values
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            1 => RejectReason::BufferOverflow,
+            2 => RejectReason::InconsistentParameters,
+            3 => RejectReason::InvalidParameterDataType,
+            4 => RejectReason::InvalidTag,
+            5 => RejectReason::MissingRequiredParameter,
+            6 => RejectReason::ParameterOutOfRange,
+            7 => RejectReason::TooManyArguments,
+            8 => RejectReason::UndefinedEnumeration,
+            9 => RejectReason::UnrecognizedService,
+            _ => RejectReason::Other,
         }
     }
 
-    /// Decode a Read Property response
-    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
-        let mut pos = 0;
-
-        // Decode object identifier - context tag 0
-        let ((object_type, instance), consumed) = decode_context_object_id(&data[pos..], 0)?;
-        let object_identifier = ObjectIdentifier {
-            object_type: crate::object::ObjectType::try_from(object_type)
-                .unwrap_or(crate::object::ObjectType::Device),
-            instance,
-        };
-        pos += consumed;
-
-        // Decode property identifier - context tag 1
-        let (property_identifier, consumed) = decode_context_enumerated(&data[pos..], 1)?;
-        pos += consumed;
-
-        // Property array index - context tag 2 (optional)
-        let property_array_index = match decode_context_unsigned(&data[pos..], 2) {
-            Ok((array_index, consumed)) => {
-                pos += consumed;
-                if array_index == BACNET_ARRAY_ALL {
-                    None
-                } else {
-                    Some(array_index)
-                }
-            }
-            Err(_) => None,
-        };
-
-        // Property value - context tag 3 (opening tag)
-        if pos >= data.len() || data[pos] != 0x3E {
-            return Err(crate::encoding::EncodingError::InvalidTag);
-        }
-        pos += 1;
-
-        // Find closing tag
-        let value_start = pos;
-        let mut value_end = pos;
-        while value_end < data.len() {
-            if data[value_end] == 0x3F {
-                break;
+    /// The reason to reject a request whose service data failed to decode
+    /// with `error`
+    pub fn for_decode_error(error: &EncodingError) -> Self {
+        match error {
+            EncodingError::BufferUnderflow | EncodingError::UnexpectedEndOfData => {
+                RejectReason::MissingRequiredParameter
             }
-            value_end += 1;
+            EncodingError::ValueOutOfRange => RejectReason::ParameterOutOfRange,
+            EncodingError::BufferOverflow => RejectReason::BufferOverflow,
+            EncodingError::InvalidTag
+            | EncodingError::InvalidLength
+            | EncodingError::InvalidFormat(_) => RejectReason::InvalidTag,
         }
-
-        if value_end >= data.len() {
-            return Err(crate::encoding::EncodingError::InvalidTag);
-        }
-
-        let property_value = data[value_start..value_end].to_vec();
-
-        Ok(ReadPropertyResponse {
-            object_identifier,
-            property_identifier,
-            property_array_index,
-            property_value,
-        })
     }
 }
 
-/// Write Property request (confirmed service)
-#[derive(Debug, Clone)]
-pub struct WritePropertyRequest {
-    /// Object identifier to write to
-    pub object_identifier: ObjectIdentifier,
-    /// Property identifier to write
-    pub property_identifier: u32,
-    /// Property array index (optional)
-    pub property_array_index: Option<u32>,
-    /// Property value to write
-    pub property_value: Vec<u8>, // Raw encoded property value
-    /// Priority (optional, 1-16)
-    pub priority: Option<u8>,
-}
-
-impl WritePropertyRequest {
-    /// Create a new Write Property request
-    pub fn new(
-        object_identifier: ObjectIdentifier,
-        property_identifier: u32,
-        property_value: Vec<u8>,
-    ) -> Self {
-        Self {
-            object_identifier,
-            property_identifier,
-            property_array_index: None,
-            property_value,
-            priority: None,
-        }
-    }
-
-    /// Create a new Write Property request with priority
-    pub fn with_priority(
-        object_identifier: ObjectIdentifier,
-        property_identifier: u32,
-        property_value: Vec<u8>,
-        priority: u8,
-    ) -> Self {
-        Self {
-            object_identifier,
-            property_identifier,
-            property_array_index: None,
-            property_value,
-            priority: Some(priority),
-        }
-    }
-
-    /// Create a new Write Property request with array index
-    pub fn with_array_index(
-        object_identifier: ObjectIdentifier,
-        property_identifier: u32,
-        array_index: u32,
-        property_value: Vec<u8>,
-    ) -> Self {
-        Self {
-            object_identifier,
-            property_identifier,
-            property_array_index: Some(array_index),
-            property_value,
-            priority: None,
-        }
-    }
-
-    /// Encode the Write Property request
-    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
-        // Object identifier - context tag 0
-        let object_id = crate::util::encode_object_id(
-            self.object_identifier.object_type as u16,
-            self.object_identifier.instance,
-        )
-        .ok_or(crate::encoding::EncodingError::InvalidFormat(
-            "Invalid object identifier".to_string(),
-        ))?;
-        buffer.push(0x0C); // Context tag 0, length 4
-        buffer.extend_from_slice(&object_id.to_be_bytes());
-
-        // Property identifier - context tag 1
-        buffer.push(0x19); // Context tag 1, length 1
-        buffer.push(self.property_identifier as u8);
-
-        // Property array index - context tag 2 (optional)
-        if let Some(array_index) = self.property_array_index {
-            buffer.push(0x29); // Context tag 2, length 1
-            buffer.push(array_index as u8);
-        }
-
-        // Property value - context tag 3 (opening tag)
-        buffer.push(0x3E); // Context tag 3, opening tag
-        buffer.extend_from_slice(&self.property_value);
-        buffer.push(0x3F); // Context tag 3, closing tag
-
-        // Priority - context tag 4 (optional)
-        if let Some(priority) = self.priority {
-            buffer.push(0x49); // Context tag 4, length 1
-            buffer.push(priority);
-        }
-
-        Ok(())
-    }
-
-    /// Decode a Write Property request
-    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
-        let mut pos = 0;
-
-        // Decode object identifier - context tag 0
-        if pos + 5 > data.len() || data[pos] != 0x0C {
-            return Err(crate::encoding::EncodingError::InvalidTag);
-        }
-        pos += 1;
-
-        let object_id_bytes = [data[pos], data[pos + 1], data[pos + 2], data[pos + 3]];
-        let object_id = u32::from_be_bytes(object_id_bytes);
-        let (object_type, instance) = crate::util::decode_object_id(object_id);
-        let object_identifier = ObjectIdentifier {
-            object_type: crate::object::ObjectType::try_from(object_type)
-                .unwrap_or(crate::object::ObjectType::Device),
-            instance,
-        };
-        pos += 4;
-
-        // Decode property identifier - context tag 1
-        if pos + 2 > data.len() || data[pos] != 0x19 {
-            return Err(crate::encoding::EncodingError::InvalidTag);
-        }
-        pos += 1;
-        let property_identifier = data[pos] as u32;
-        pos += 1;
-
-        // Property array index - context tag 2 (optional)
-        let property_array_index = if pos < data.len() && data[pos] == 0x29 {
-            pos += 1;
-            let array_index = data[pos] as u32;
-            pos += 1;
-            Some(array_index)
-        } else {
-            None
-        };
-
-        // Property value - context tag 3 (opening tag)
-        if pos >= data.len() || data[pos] != 0x3E {
-            return Err(crate::encoding::EncodingError::InvalidTag);
-        }
-        pos += 1;
-
-        // Find closing tag
-        let value_start = pos;
-        let mut value_end = pos;
-        while value_end < data.len() {
-            if data[value_end] == 0x3F {
-                break;
-            }
-            value_end += 1;
-        }
-
-        if value_end >= data.len() {
-            return Err(crate::encoding::EncodingError::InvalidTag);
-        }
-
-        let property_value = data[value_start..value_end].to_vec();
-        pos = value_end + 1;
-
-        // Priority - context tag 4 (optional)
-        let priority = if pos < data.len() && data[pos] == 0x49 {
-            pos += 1;
-            if pos < data.len() {
-                Some(data[pos])
-            } else {
-                None
-            }
-        } else {
-            None
+impl fmt::Display for RejectReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            RejectReason::Other => "other",
+            RejectReason::BufferOverflow => "buffer overflow",
+            RejectReason::InconsistentParameters => "inconsistent parameters",
+            RejectReason::InvalidParameterDataType => "invalid parameter datatype",
+            RejectReason::InvalidTag => "invalid tag",
+            RejectReason::MissingRequiredParameter => "missing required parameter",
+            RejectReason::ParameterOutOfRange => "parameter out of range",
+            RejectReason::TooManyArguments => "too many arguments",
+            RejectReason::UndefinedEnumeration => "undefined enumeration",
+            RejectReason::UnrecognizedService => "unrecognized service",
         };
-
-        Ok(WritePropertyRequest {
-            object_identifier,
-            property_identifier,
-            property_array_index,
-            property_value,
-            priority,
-        })
-    }
-}
-
-/// Read Property Multiple request (confirmed service)
-#[derive(Debug, Clone)]
-pub struct ReadPropertyMultipleRequest {
-    /// List of objects and properties to read
-    pub read_access_specifications: Vec<ReadAccessSpecification>,
-}
-
-#[derive(Debug, Clone)]
-pub struct ReadAccessSpecification {
-    /// Object identifier
-    pub object_identifier: ObjectIdentifier,
-    /// List of properties to read
-    pub property_references: Vec<PropertyReference>,
-}
-
-#[derive(Debug, Clone)]
-pub struct PropertyReference {
-    /// Property identifier
-    pub property_identifier: u32,
-    /// Property array index (optional)
-    pub property_array_index: Option<u32>,
-}
-
-impl ReadPropertyMultipleRequest {
-    /// Create a new Read Property Multiple request
-    pub fn new(read_access_specifications: Vec<ReadAccessSpecification>) -> Self {
-        Self {
-            read_access_specifications,
-        }
-    }
-
-    /// Add a read access specification
-    pub fn add_specification(&mut self, spec: ReadAccessSpecification) {
-        self.read_access_specifications.push(spec);
-    }
-}
-
-impl ReadAccessSpecification {
-    /// Create a new read access specification
-    pub fn new(
-        object_identifier: ObjectIdentifier,
-        property_references: Vec<PropertyReference>,
-    ) -> Self {
-        Self {
-            object_identifier,
-            property_references,
-        }
-    }
-
-    /// Add a property reference
-    pub fn add_property(&mut self, property_reference: PropertyReference) {
-        self.property_references.push(property_reference);
-    }
-}
-
-impl PropertyReference {
-    /// Create a new property reference
-    pub fn new(property_identifier: u32) -> Self {
-        Self {
-            property_identifier,
-            property_array_index: None,
-        }
-    }
-
-    /// Create a new property reference with array index
-    pub fn with_array_index(property_identifier: u32, array_index: u32) -> Self {
-        Self {
-            property_identifier,
-            property_array_index: Some(array_index),
-        }
-    }
-}
-
-/// Subscribe COV request (confirmed service)
-#[derive(Debug, Clone)]
-pub struct SubscribeCovRequest {
-    /// Subscriber process identifier
-    pub subscriber_process_identifier: u32,
-    /// Monitored object identifier
-    pub monitored_object_identifier: ObjectIdentifier,
-    /// Issue confirmed notifications
-    pub issue_confirmed_notifications: Option<bool>,
-    /// Lifetime (seconds, 0 = permanent)
-    pub lifetime: Option<u32>,
-}
-
-impl SubscribeCovRequest {
-    /// Create a new Subscribe COV request
-    pub fn new(
-        subscriber_process_identifier: u32,
-        monitored_object_identifier: ObjectIdentifier,
-    ) -> Self {
-        Self {
-            subscriber_process_identifier,
-            monitored_object_identifier,
-            issue_confirmed_notifications: None,
-            lifetime: None,
-        }
-    }
-
-    /// Create a new Subscribe COV request with confirmation preference
-    pub fn with_confirmation(
-        subscriber_process_identifier: u32,
-        monitored_object_identifier: ObjectIdentifier,
-        issue_confirmed_notifications: bool,
-    ) -> Self {
-        Self {
-            subscriber_process_identifier,
-            monitored_object_identifier,
-            issue_confirmed_notifications: Some(issue_confirmed_notifications),
-            lifetime: None,
-        }
-    }
-
-    /// Create a new Subscribe COV request with lifetime
-    pub fn with_lifetime(
-        subscriber_process_identifier: u32,
-        monitored_object_identifier: ObjectIdentifier,
-        lifetime: u32,
-    ) -> Self {
-        Self {
-            subscriber_process_identifier,
-            monitored_object_identifier,
-            issue_confirmed_notifications: None,
-            lifetime: Some(lifetime),
-        }
-    }
-
-    /// Encode the Subscribe COV request
-    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
-        // Subscriber process identifier - context tag 0
-        buffer.push(0x09); // Context tag 0, length 1
-        buffer.push(self.subscriber_process_identifier as u8);
-
-        // Monitored object identifier - context tag 1
-        let object_id = crate::util::encode_object_id(
-            self.monitored_object_identifier.object_type as u16,
-            self.monitored_object_identifier.instance,
-        )
-        .ok_or(crate::encoding::EncodingError::InvalidFormat(
-            "Invalid object identifier".to_string(),
-        ))?;
-        buffer.push(0x1C); // Context tag 1, length 4
-        buffer.extend_from_slice(&object_id.to_be_bytes());
-
-        // Issue confirmed notifications - context tag 2 (optional)
-        if let Some(confirmed) = self.issue_confirmed_notifications {
-            buffer.push(0x22); // Context tag 2, length 2
-            buffer.push(if confirmed { 1 } else { 0 });
-        }
-
-        // Lifetime - context tag 3 (optional)
-        if let Some(lifetime) = self.lifetime {
-            buffer.push(0x39); // Context tag 3, length 1
-            buffer.push(lifetime as u8);
-        }
-
-        Ok(())
-    }
-}
-
-/// Subscribe COV Property request (confirmed service)
-#[derive(Debug, Clone)]
-pub struct SubscribeCovPropertyRequest {
-    /// Subscriber process identifier
-    pub subscriber_process_identifier: u32,
-    /// Monitored object identifier
-    pub monitored_object_identifier: ObjectIdentifier,
-    /// Issue confirmed notifications
-    pub issue_confirmed_notifications: Option<bool>,
-    /// Lifetime (seconds, 0 = permanent)
-    pub lifetime: Option<u32>,
-    /// Monitored property reference
-    pub monitored_property: PropertyReference,
-    /// COV increment (optional)
-    pub cov_increment: Option<f32>,
-}
-
-impl SubscribeCovPropertyRequest {
-    /// Create a new Subscribe COV Property request
-    pub fn new(
-        subscriber_process_identifier: u32,
-        monitored_object_identifier: ObjectIdentifier,
-        monitored_property: PropertyReference,
-    ) -> Self {
-        Self {
-            subscriber_process_identifier,
-            monitored_object_identifier,
-            issue_confirmed_notifications: None,
-            lifetime: None,
-            monitored_property,
-            cov_increment: None,
-        }
-    }
-
-    /// Set COV increment
-    pub fn with_cov_increment(mut self, increment: f32) -> Self {
-        self.cov_increment = Some(increment);
-        self
-    }
-}
-
-/// COV Notification request (unconfirmed service)
-#[derive(Debug, Clone)]
-pub struct CovNotificationRequest {
-    /// Subscriber process identifier
-    pub subscriber_process_identifier: u32,
-    /// Initiating device identifier
-    pub initiating_device_identifier: ObjectIdentifier,
-    /// Monitored object identifier
-    pub monitored_object_identifier: ObjectIdentifier,
-    /// Time remaining (seconds)
-    pub time_remaining: u32,
-    /// List of values (property-value pairs)
-    pub list_of_values: Vec<PropertyValue>,
-}
-
-impl CovNotificationRequest {
-    /// Create a new COV Notification request
-    pub fn new(
-        subscriber_process_identifier: u32,
-        initiating_device_identifier: ObjectIdentifier,
-        monitored_object_identifier: ObjectIdentifier,
-        time_remaining: u32,
-        list_of_values: Vec<PropertyValue>,
-    ) -> Self {
-        Self {
-            subscriber_process_identifier,
-            initiating_device_identifier,
-            monitored_object_identifier,
-            time_remaining,
-            list_of_values,
-        }
-    }
-
-    /// Encode the COV Notification request
-    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
-        // Subscriber process identifier - context tag 0
-        buffer.push(0x09); // Context tag 0, length 1
-        buffer.push(self.subscriber_process_identifier as u8);
-
-        // Initiating device identifier - context tag 1
-        let device_id = crate::util::encode_object_id(
-            self.initiating_device_identifier.object_type as u16,
-            self.initiating_device_identifier.instance,
-        )
-        .ok_or(crate::encoding::EncodingError::InvalidFormat(
-            "Invalid device identifier".to_string(),
-        ))?;
-        buffer.push(0x1C); // Context tag 1, length 4
-        buffer.extend_from_slice(&device_id.to_be_bytes());
-
-        // Monitored object identifier - context tag 2
-        let object_id = crate::util::encode_object_id(
-            self.monitored_object_identifier.object_type as u16,
-            self.monitored_object_identifier.instance,
-        )
-        .ok_or(crate::encoding::EncodingError::InvalidFormat(
-            "Invalid object identifier".to_string(),
-        ))?;
-        buffer.push(0x2C); // Context tag 2, length 4
-        buffer.extend_from_slice(&object_id.to_be_bytes());
-
-        // Time remaining - context tag 3
-        buffer.push(0x39); // Context tag 3, length 1
-        buffer.push(self.time_remaining as u8);
-
-        // List of values would be encoded here in a real implementation
-        // This is complex as it involves encoding property-value pairs
-
-        Ok(())
-    }
-}
-
-/// COV Subscription information
-#[derive(Debug, Clone)]
-pub struct CovSubscription {
-    /// Subscriber process identifier
-    pub subscriber_process_identifier: u32,
-    /// Subscriber device identifier
-    pub subscriber_device_identifier: ObjectIdentifier,
-    /// Monitored object identifier
-    pub monitored_object_identifier: ObjectIdentifier,
-    /// Monitored property (for COV Property subscriptions)
-    pub monitored_property: Option<PropertyReference>,
-    /// Issue confirmed notifications
-    pub issue_confirmed_notifications: bool,
-    /// Lifetime (seconds, 0 = permanent)
-    pub lifetime: u32,
-    /// Remaining time (seconds)
-    pub time_remaining: u32,
-    /// COV increment (for analog properties)
-    pub cov_increment: Option<f32>,
-}
-
-impl CovSubscription {
-    /// Create a new COV subscription
-    pub fn new(
-        subscriber_process_identifier: u32,
-        subscriber_device_identifier: ObjectIdentifier,
-        monitored_object_identifier: ObjectIdentifier,
-        lifetime: u32,
-    ) -> Self {
-        Self {
-            subscriber_process_identifier,
-            subscriber_device_identifier,
-            monitored_object_identifier,
-            monitored_property: None,
-            issue_confirmed_notifications: false,
-            lifetime,
-            time_remaining: lifetime,
-            cov_increment: None,
-        }
-    }
-
-    /// Check if subscription has expired
-    pub fn is_expired(&self) -> bool {
-        self.lifetime > 0 && self.time_remaining == 0
-    }
-
-    /// Update time remaining (should be called periodically)
-    pub fn update_time(&mut self, elapsed_seconds: u32) {
-        if self.lifetime > 0 {
-            self.time_remaining = self.time_remaining.saturating_sub(elapsed_seconds);
-        }
-    }
-}
-
-/// COV Subscription manager
-#[derive(Debug, Default)]
-pub struct CovSubscriptionManager {
-    /// List of active subscriptions
-    subscriptions: Vec<CovSubscription>,
-}
-
-impl CovSubscriptionManager {
-    /// Create a new COV subscription manager
-    pub fn new() -> Self {
-        Self {
-            subscriptions: Vec::new(),
-        }
-    }
-
-    /// Add a new subscription
-    pub fn add_subscription(&mut self, subscription: CovSubscription) {
-        // Remove any existing subscription for the same object and subscriber
-        self.subscriptions.retain(|s| {
-            !(s.subscriber_device_identifier == subscription.subscriber_device_identifier
-                && s.subscriber_process_identifier == subscription.subscriber_process_identifier
-                && s.monitored_object_identifier == subscription.monitored_object_identifier)
-        });
-
-        self.subscriptions.push(subscription);
-    }
-
-    /// Remove a subscription
-    pub fn remove_subscription(
-        &mut self,
-        subscriber_device: ObjectIdentifier,
-        subscriber_process: u32,
-        monitored_object: ObjectIdentifier,
-    ) {
-        self.subscriptions.retain(|s| {
-            !(s.subscriber_device_identifier == subscriber_device
-                && s.subscriber_process_identifier == subscriber_process
-                && s.monitored_object_identifier == monitored_object)
-        });
-    }
-
-    /// Get all subscriptions for a monitored object
-    pub fn get_subscriptions_for_object(
-        &self,
-        object_id: ObjectIdentifier,
-    ) -> Vec<&CovSubscription> {
-        self.subscriptions
-            .iter()
-            .filter(|s| s.monitored_object_identifier == object_id && !s.is_expired())
-            .collect()
-    }
-
-    /// Remove expired subscriptions
-    pub fn cleanup_expired(&mut self) {
-        self.subscriptions.retain(|s| !s.is_expired());
-    }
-
-    /// Update all subscription timers
-    pub fn update_timers(&mut self, elapsed_seconds: u32) {
-        for subscription in &mut self.subscriptions {
-            subscription.update_time(elapsed_seconds);
-        }
-    }
-
-    /// Get total number of active subscriptions
-    pub fn active_count(&self) -> usize {
-        self.subscriptions
-            .iter()
-            .filter(|s| !s.is_expired())
-            .count()
+        f.write_str(reason)
     }
 }
 
-/// Atomic Read File request (confirmed service)
-#[derive(Debug, Clone)]
-pub struct AtomicReadFileRequest {
-    /// File object identifier
-    pub file_identifier: ObjectIdentifier,
-    /// Access method specification
-    pub access_method: FileAccessMethod,
-}
-
-/// File access method for atomic read/write
-#[derive(Debug, Clone)]
-pub enum FileAccessMethod {
-    /// Stream access - read/write bytes at position
-    StreamAccess {
-        /// File position to start reading/writing
-        file_start_position: i32,
-        /// Number of octets to read (for read operations)
-        requested_octet_count: u32,
-    },
-    /// Record access - read/write records
-    RecordAccess {
-        /// Starting record number
-        file_start_record: i32,
-        /// Number of records to read (for read operations)
-        requested_record_count: u32,
-    },
+/// Abort reason codes (Clause 21, BACnetAbortReason)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum AbortReason {
+    /// Other, including proprietary reasons
+    Other = 0,
+    /// A segmented message is longer than can be reassembled
+    BufferOverflow = 1,
+    /// The APDU is not expected in the transaction's state
+    InvalidApduInThisState = 2,
+    /// A task of higher priority took the resources of the transaction
+    PreemptedByHigherPriorityTask = 3,
+    /// The message would need segmentation the peer does not support
+    SegmentationNotSupported = 4,
+    /// A security error
+    SecurityError = 5,
+    /// The security of the message is insufficient
+    InsufficientSecurity = 6,
+    /// The proposed window size is not between 1 and 127
+    WindowSizeOutOfRange = 7,
+    /// The server took longer to reply than its APDU_Timeout allows
+    ApplicationExceededReplyTime = 8,
+    /// The device is out of resources for the transaction
+    OutOfResources = 9,
+    /// A transaction state machine timed out
+    TsmTimeout = 10,
+    /// The APDU is longer than the peer accepts, even in segments
+    ApduTooLong = 11,
 }
 
-impl AtomicReadFileRequest {
-    /// Create a new Atomic Read File request with stream access
-    pub fn new_stream_access(
-        file_identifier: ObjectIdentifier,
-        start_position: i32,
-        octet_count: u32,
-    ) -> Self {
-        Self {
-            file_identifier,
-            access_method: FileAccessMethod::StreamAccess {
-                file_start_position: start_position,
-                requested_octet_count: octet_count,
-            },
+impl AbortReason {
+    /// The reason for an octet, `Other` for unknown and proprietary values
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            1 => AbortReason::BufferOverflow,
+            2 => AbortReason::InvalidApduInThisState,
+            3 => AbortReason::PreemptedByHigherPriorityTask,
+            4 => AbortReason::SegmentationNotSupported,
+            5 => AbortReason::SecurityError,
+            6 => AbortReason::InsufficientSecurity,
+            7 => AbortReason::WindowSizeOutOfRange,
+            8 => AbortReason::ApplicationExceededReplyTime,
+            9 => AbortReason::OutOfResources,
+            10 => AbortReason::TsmTimeout,
+            11 => AbortReason::ApduTooLong,
+            _ => AbortReason::Other,
         }
     }
 
-    /// Create a new Atomic Read File request with record access
-    pub fn new_record_access(
-        file_identifier: ObjectIdentifier,
-        start_record: i32,
-        record_count: u32,
-    ) -> Self {
-        Self {
-            file_identifier,
-            access_method: FileAccessMethod::RecordAccess {
-                file_start_record: start_record,
-                requested_record_count: record_count,
-            },
+    /// The reason to abort a transaction whose response failed to encode
+    /// with `error`
+    pub fn for_encode_error(error: &EncodingError) -> Self {
+        match error {
+            EncodingError::BufferOverflow => AbortReason::BufferOverflow,
+            _ => AbortReason::Other,
         }
     }
-
-    /// Encode the Atomic Read File request
-    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
-        // File identifier - context tag 0
-        let file_id = crate::util::encode_object_id(
-            self.file_identifier.object_type as u16,
-            self.file_identifier.instance,
-        )
-        .ok_or(crate::encoding::EncodingError::InvalidFormat(
-            "Invalid file identifier".to_string(),
-        ))?;
-        buffer.push(0x0C); // Context tag 0, length 4
-        buffer.extend_from_slice(&file_id.to_be_bytes());
-
-        // Access method - context tag 1 (opening tag)
-        buffer.push(0x1E); // Context tag 1, opening tag
-
-        match &self.access_method {
-            FileAccessMethod::StreamAccess {
-                file_start_position,
-                requested_octet_count,
-            } => {
-                // Stream access - context tag 0 (opening tag)
-                buffer.push(0x0E); // Context tag 0, opening tag
-
-                // File start position - context tag 0
-                buffer.push(0x09); // Context tag 0, length 1
-                buffer.extend_from_slice(&file_start_position.to_be_bytes());
-
-                // Requested octet count - context tag 1
-                buffer.push(0x19); // Context tag 1, length 1
-                buffer.extend_from_slice(&requested_octet_count.to_be_bytes());
-
-                buffer.push(0x0F); // Context tag 0, closing tag
-            }
-            FileAccessMethod::RecordAccess {
-                file_start_record,
-                requested_record_count,
-            } => {
-                // Record access - context tag 1 (opening tag)
-                buffer.push(0x1E); // Context tag 1, opening tag
-
-                // File start record - context tag 0
-                buffer.push(0x09); // Context tag 0, length 1
-                buffer.extend_from_slice(&file_start_record.to_be_bytes());
-
-                // Requested record count - context tag 1
-                buffer.push(0x19); // Context tag 1, length 1
-                buffer.extend_from_slice(&requested_record_count.to_be_bytes());
-
-                buffer.push(0x1F); // Context tag 1, closing tag
-            }
-        }
-
-        buffer.push(0x1F); // Context tag 1, closing tag
-
-        Ok(())
-    }
-}
-
-/// Atomic Read File response (confirmed service)
-#[derive(Debug, Clone)]
-pub struct AtomicReadFileResponse {
-    /// End of file flag
-    pub end_of_file: bool,
-    /// Access method and data
-    pub access_method_result: FileAccessMethodResult,
 }
 
-/// File access method result for atomic read response
-#[derive(Debug, Clone)]
-pub enum FileAccessMethodResult {
-    /// Stream access result
-    StreamAccess {
-        /// File position after read
-        file_start_position: i32,
-        /// File data read
-        file_data: Vec<u8>,
-    },
-    /// Record access result
-    RecordAccess {
-        /// Starting record number
-        file_start_record: i32,
-        /// Number of records returned
-        record_count: u32,
-        /// Record data
-        file_record_data: Vec<Vec<u8>>,
-    },
-}
-
-impl AtomicReadFileResponse {
-    /// Create a new stream access response
-    pub fn new_stream_access(end_of_file: bool, start_position: i32, data: Vec<u8>) -> Self {
-        Self {
-            end_of_file,
-            access_method_result: FileAccessMethodResult::StreamAccess {
-                file_start_position: start_position,
-                file_data: data,
-            },
-        }
-    }
-
-    /// Create a new record access response
-    pub fn new_record_access(end_of_file: bool, start_record: i32, records: Vec<Vec<u8>>) -> Self {
-        let record_count = records.len() as u32;
-        Self {
-            end_of_file,
-            access_method_result: FileAccessMethodResult::RecordAccess {
-                file_start_record: start_record,
-                record_count,
-                file_record_data: records,
-            },
-        }
+impl fmt::Display for AbortReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            AbortReason::Other => "other",
+            AbortReason::BufferOverflow => "buffer overflow",
+            AbortReason::InvalidApduInThisState => "invalid APDU in this state",
+            AbortReason::PreemptedByHigherPriorityTask => "preempted by higher priority task",
+            AbortReason::SegmentationNotSupported => "segmentation not supported",
+            AbortReason::SecurityError => "security error",
+            AbortReason::InsufficientSecurity => "insufficient security",
+            AbortReason::WindowSizeOutOfRange => "window size out of range",
+            AbortReason::ApplicationExceededReplyTime => "application exceeded reply time",
+            AbortReason::OutOfResources => "out of resources",
+            AbortReason::TsmTimeout => "TSM timeout",
+            AbortReason::ApduTooLong => "APDU too long",
+        };
+        f.write_str(reason)
     }
 }
 
-/// Atomic Write File request (confirmed service)
-#[derive(Debug, Clone)]
-pub struct AtomicWriteFileRequest {
-    /// File object identifier
-    pub file_identifier: ObjectIdentifier,
-    /// Access method and data
-    pub access_method: FileWriteAccessMethod,
-}
+use crate::app::Apdu;
+use crate::encoding::{
+    decode_context_enumerated, decode_context_object_id, decode_context_unsigned, EncodingError,
+    Result as EncodingResult,
+};
+use crate::object::{ObjectError, ObjectIdentifier};
+
+/// Special array index value indicating all elements
+pub const BACNET_ARRAY_ALL: u32 = 0xFFFFFFFF;
 
-/// File write access method for atomic write
+/// Read Property response (confirmed service)
 #[derive(Debug, Clone)]
-pub enum FileWriteAccessMethod {
-    /// Stream access - write bytes at position
-    StreamAccess {
-        /// File position to start writing
-        file_start_position: i32,
-        /// Data to write
-        file_data: Vec<u8>,
-    },
-    /// Record access - write records
-    RecordAccess {
-        /// Starting record number
-        file_start_record: i32,
-        /// Number of records to write
-        record_count: u32,
-        /// Record data to write
-        file_record_data: Vec<Vec<u8>>,
-    },
+pub struct ReadPropertyResponse {
+    /// Object identifier that was read
+    pub object_identifier: ObjectIdentifier,
+    /// Property identifier that was read
+    pub property_identifier: u32,
+    /// Property array index (optional)
+    pub property_array_index: Option<u32>,
+    /// Property value
+    pub property_value: Vec<u8>, // Raw encoded property value
 }
 
-impl AtomicWriteFileRequest {
-    /// Create a new Atomic Write File request with stream access
-    pub fn new_stream_access(
-        file_identifier: ObjectIdentifier,
-        start_position: i32,
-        data: Vec<u8>,
+impl ReadPropertyResponse {
+    /// Create a new Read Property response
+    pub fn new(
+        object_identifier: ObjectIdentifier,
+        property_identifier: u32,
+        property_value: Vec<u8>,
     ) -> Self {
         Self {
-            file_identifier,
-            access_method: FileWriteAccessMethod::StreamAccess {
-                file_start_position: start_position,
-                file_data: data,
-            },
+            object_identifier,
+            property_identifier,
+            property_array_index: None,
+            property_value,
         }
     }
 
-    /// Create a new Atomic Write File request with record access
-    pub fn new_record_access(
-        file_identifier: ObjectIdentifier,
-        start_record: i32,
-        records: Vec<Vec<u8>>,
-    ) -> Self {
-        let record_count = records.len() as u32;
-        Self {
-            file_identifier,
-            access_method: FileWriteAccessMethod::RecordAccess {
-                file_start_record: start_record,
-                record_count,
-                file_record_data: records,
-            },
-        }
-    }
+    /// Decode a Read Property response
+    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
+        let mut pos = 0;
 
-    /// Encode the Atomic Write File request
-    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
-        // File identifier - context tag 0
-        let file_id = crate::util::encode_object_id(
-            self.file_identifier.object_type as u16,
-            self.file_identifier.instance,
-        )
-        .ok_or(crate::encoding::EncodingError::InvalidFormat(
-            "Invalid file identifier".to_string(),
-        ))?;
-        buffer.push(0x0C); // Context tag 0, length 4
-        buffer.extend_from_slice(&file_id.to_be_bytes());
-
-        // Access method - context tag 1 (opening tag)
-        buffer.push(0x1E); // Context tag 1, opening tag
-
-        match &self.access_method {
-            FileWriteAccessMethod::StreamAccess {
-                file_start_position,
-                file_data,
-            } => {
-                // Stream access - context tag 0 (opening tag)
-                buffer.push(0x0E); // Context tag 0, opening tag
-
-                // File start position - context tag 0
-                buffer.push(0x09); // Context tag 0, length 1
-                buffer.extend_from_slice(&file_start_position.to_be_bytes());
-
-                // File data - context tag 1 (opening tag)
-                buffer.push(0x1E); // Context tag 1, opening tag
-                buffer.extend_from_slice(file_data);
-                buffer.push(0x1F); // Context tag 1, closing tag
-
-                buffer.push(0x0F); // Context tag 0, closing tag
-            }
-            FileWriteAccessMethod::RecordAccess {
-                file_start_record,
-                record_count: _,
-                file_record_data,
-            } => {
-                // Record access - context tag 1 (opening tag)
-                buffer.push(0x1E); // Context tag 1, opening tag
-
-                // File start record - context tag 0
-                buffer.push(0x09); // Context tag 0, length 1
-                buffer.extend_from_slice(&file_start_record.to_be_bytes());
-
-                // Record count - context tag 1
-                let record_count = file_record_data.len() as u32;
-                buffer.push(0x19); // Context tag 1, length 1
-                buffer.extend_from_slice(&record_count.to_be_bytes());
-
-                // File record data - context tag 2 (opening tag)
-                buffer.push(0x2E); // Context tag 2, opening tag
-                for record in file_record_data {
-                    // Each record as octet string
-                    buffer.push(0x65); // Application tag 6 (OctetString), length depends on record size
-                    buffer.push(record.len() as u8);
-                    buffer.extend_from_slice(record);
+        // Decode object identifier - context tag 0
+        let ((object_type, instance), consumed) = decode_context_object_id(&data[pos..], 0)?;
+        let object_identifier = ObjectIdentifier {
+            object_type: crate::object::ObjectType::try_from(object_type)
+                .unwrap_or(crate::object::ObjectType::Device),
+            instance,
+        };
+        pos += consumed;
+
+        // Decode property identifier - context tag 1
+        let (property_identifier, consumed) = decode_context_enumerated(&data[pos..], 1)?;
+        pos += consumed;
+
+        // Property array index - context tag 2 (optional)
+        let property_array_index = match decode_context_unsigned(&data[pos..], 2) {
+            Ok((array_index, consumed)) => {
+                pos += consumed;
+                if array_index == BACNET_ARRAY_ALL {
+                    None
+                } else {
+                    Some(array_index)
                 }
-                buffer.push(0x2F); // Context tag 2, closing tag
+            }
+            Err(_) => None,
+        };
 
-                buffer.push(0x1F); // Context tag 1, closing tag
+        // Property value - context tag 3 (opening tag)
+        if pos >= data.len() || data[pos] != 0x3E {
+            return Err(crate::encoding::EncodingError::InvalidTag);
+        }
+        pos += 1;
+
+        // Find closing tag
+        let value_start = pos;
+        let mut value_end = pos;
+        while value_end < data.len() {
+            if data[value_end] == 0x3F {
+                break;
             }
+            value_end += 1;
+        }
+
+        if value_end >= data.len() {
+            return Err(crate::encoding::EncodingError::InvalidTag);
         }
 
-        buffer.push(0x1F); // Context tag 1, closing tag
+        let property_value = data[value_start..value_end].to_vec();
 
-        Ok(())
+        Ok(ReadPropertyResponse {
+            object_identifier,
+            property_identifier,
+            property_array_index,
+            property_value,
+        })
     }
 }
 
-/// Atomic Write File response (confirmed service)
-#[derive(Debug, Clone)]
-pub struct AtomicWriteFileResponse {
-    /// File start position (for stream access) or start record (for record access)
-    pub file_start_position: i32,
+/// Error returned in place of a property value (BACnet Error: class and code)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PropertyAccessError {
+    /// Error class
+    pub error_class: u32,
+    /// Error code
+    pub error_code: u32,
 }
 
-/// Time Synchronization request (unconfirmed service)
-#[derive(Debug, Clone)]
-pub struct TimeSynchronizationRequest {
-    /// Date and time to synchronize to
-    pub date_time: BacnetDateTime,
+impl From<&ObjectError> for PropertyAccessError {
+    fn from(error: &ObjectError) -> Self {
+        // Error class: object (1), property (2); error codes per Clause 21
+        let (error_class, error_code) = match error {
+            ObjectError::NotFound | ObjectError::InstanceNotFound => (1, 31),
+            ObjectError::TypeNotSupported => (1, 36),
+            ObjectError::PropertyNotFound | ObjectError::UnknownProperty => (2, 32),
+            ObjectError::PropertyNotWritable | ObjectError::WriteAccessDenied => (2, 40),
+            ObjectError::InvalidPropertyType => (2, 9),
+            ObjectError::InvalidValue(_) => (2, 37),
+            ObjectError::InvalidArrayIndex => (2, 42),
+            ObjectError::InvalidConfiguration(_) => (1, 0),
+        };
+        Self {
+            error_class,
+            error_code,
+        }
+    }
 }
 
-/// UTC Time Synchronization request (unconfirmed service)
-#[derive(Debug, Clone)]
-pub struct UtcTimeSynchronizationRequest {
-    /// UTC date and time to synchronize to
-    pub utc_date_time: BacnetDateTime,
+impl From<ObjectError> for PropertyAccessError {
+    fn from(error: ObjectError) -> Self {
+        Self::from(&error)
+    }
 }
 
 /// BACnet Date and Time structure
@@ -1760,78 +836,115 @@ impl BacnetDateTime {
     }
 }
 
-impl TimeSynchronizationRequest {
-    /// Create a new Time Synchronization request
-    pub fn new(date_time: BacnetDateTime) -> Self {
-        Self { date_time }
-    }
-
-    /// Create Time Synchronization request with current time
-    #[cfg(feature = "std")]
-    pub fn now() -> Self {
-        Self::new(BacnetDateTime::now())
-    }
-
-    /// Encode the Time Synchronization request
-    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
-        self.date_time.encode(buffer)
-    }
-
-    /// Decode a Time Synchronization request
-    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
-        let (date_time, _consumed) = BacnetDateTime::decode(data)?;
-        Ok(Self::new(date_time))
-    }
-}
-
-impl UtcTimeSynchronizationRequest {
-    /// Create a new UTC Time Synchronization request
-    pub fn new(utc_date_time: BacnetDateTime) -> Self {
-        Self { utc_date_time }
-    }
-
-    /// Create UTC Time Synchronization request with current UTC time
-    #[cfg(feature = "std")]
-    pub fn now() -> Self {
-        use chrono::{Datelike, Timelike, Utc};
-
-        let now = Utc::now();
-        let year = now.year() as u16;
-        let month = now.month() as u8;
-        let day = now.day() as u8;
-        let weekday = now.weekday().number_from_monday() as u8;
-        let hour = now.hour() as u8;
-        let minute = now.minute() as u8;
-        let second = now.second() as u8;
-        let hundredths = (now.nanosecond() / 10_000_000) as u8;
-
-        let date = crate::object::Date {
-            year,
-            month,
-            day,
-            weekday,
-        };
-        let time = crate::object::Time {
-            hour,
-            minute,
-            second,
-            hundredths,
-        };
-        let utc_date_time = BacnetDateTime::new(date, time);
-        Self::new(utc_date_time)
-    }
-
-    /// Encode the UTC Time Synchronization request
-    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
-        self.utc_date_time.encode(buffer)
-    }
-
-    /// Decode a UTC Time Synchronization request
-    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
-        let (utc_date_time, _consumed) = BacnetDateTime::decode(data)?;
-        Ok(Self::new(utc_date_time))
-    }
-}
+/// Who-Is and I-Am codecs and the Who-Is responder
+pub mod who_is;
+pub use who_is::{IAmRequest, WhoIsRequest, WhoIsResponder};
+/// Who-Has and I-Have codecs and the Who-Has responder
+pub mod who_has;
+pub use who_has::{IHaveRequest, WhoHasObject, WhoHasRequest};
+/// ReadProperty request and acknowledgement codecs and server-side handling
+pub mod read_property;
+pub use read_property::{ReadPropertyAck, ReadPropertyRequest};
+/// ReadPropertyMultiple request and acknowledgement codecs and server-side handling
+pub mod read_property_multiple;
+pub use read_property_multiple::{
+    PropertyReference, ReadAccessResult, ReadAccessSpecification, ReadPropertyMultipleAck,
+    ReadPropertyMultipleRequest, ReadResult,
+};
+/// WriteProperty request codec and server-side handling
+pub mod write_property;
+pub use write_property::WritePropertyRequest;
+/// SubscribeCOV codecs, the COV subscription table and server-side handling
+pub mod cov;
+pub use cov::{
+    CovSubscription, CovSubscriptionManager, PendingCovNotification, SubscribeCovRequest,
+};
+/// COVNotification codecs, confirmed and unconfirmed
+pub mod cov_notification;
+pub use cov_notification::CovNotificationRequest;
+/// AcknowledgeAlarm request codec and server-side handling
+pub mod acknowledge_alarm;
+pub use acknowledge_alarm::AcknowledgeAlarmRequest;
+/// EventNotification codecs, confirmed and unconfirmed, with the BACnetNotificationParameters set
+pub mod event_notification;
+pub use event_notification::{
+    ChangedValue, EventNotificationRequest, NotificationParameters, PendingEventNotification,
+    PropertyStates, TimeStamp,
+};
+/// GetAlarmSummary, GetEnrollmentSummary and GetEventInformation codecs and server-side handling
+pub mod event_summary;
+pub use event_summary::{
+    AcknowledgmentFilter, AlarmSummary, EnrollmentSummary, EventStateFilter, EventSummary,
+    GetAlarmSummaryAck, GetEnrollmentSummaryAck, GetEnrollmentSummaryRequest,
+    GetEventInformationAck, GetEventInformationRequest, RecipientProcess,
+};
+/// SubscribeCOVProperty and SubscribeCOVPropertyMultiple codecs and server-side handling
+pub mod cov_property;
+pub use cov_property::{
+    CovReference, CovSubscriptionSpecification, SubscribeCovPropertyMultipleError,
+    SubscribeCovPropertyMultipleRequest, SubscribeCovPropertyRequest,
+};
+/// AtomicReadFile and AtomicWriteFile codecs, server-side handling and chunking
+pub mod atomic_file;
+pub use atomic_file::{
+    max_stream_chunk, AtomicReadFileRequest, AtomicReadFileResponse, AtomicWriteFileRequest,
+    AtomicWriteFileResponse, FileAccessMethod, FileAccessMethodResult, FileWriteAccessMethod,
+};
+/// AddListElement and RemoveListElement codecs and server-side handling
+pub mod list_element;
+pub use list_element::{ChangeListError, ListElementRequest};
+/// CreateObject and DeleteObject codecs and server-side handling
+pub mod object_lifecycle;
+pub use object_lifecycle::{
+    CreateObjectAck, CreateObjectError, CreateObjectRequest, DeleteObjectRequest, ObjectSpecifier,
+};
+/// DeviceCommunicationControl codec and the communication state it sets
+pub mod communication_control;
+pub use communication_control::{
+    CommunicationControl, DeviceCommunicationControlRequest, EnableDisable,
+};
+/// ReinitializeDevice codec and password-checked dispatch to the host
+pub mod reinitialize_device;
+pub use reinitialize_device::{ReinitializeDeviceRequest, ReinitializedState};
+/// TimeSynchronization and UTCTimeSynchronization codecs, the clock trait and the time master
+pub mod time_sync;
+#[cfg(feature = "std")]
+pub use time_sync::SystemClock;
+pub use time_sync::{
+    Clock, PendingTimeSynchronization, TimeMaster, TimeSynchronizationRequest,
+    UtcTimeSynchronizationRequest,
+};
+/// ConfirmedPrivateTransfer and UnconfirmedPrivateTransfer codec and the vendor service registry
+pub mod private_transfer;
+pub use private_transfer::{
+    PrivateTransferAck, PrivateTransferHandler, PrivateTransferRegistry, PrivateTransferRequest,
+};
+/// ConfirmedTextMessage and UnconfirmedTextMessage codec and receive callback
+pub mod text_message;
+pub use text_message::{
+    MessageClass, MessagePriority, TextMessageCallback, TextMessageReceiver, TextMessageRequest,
+};
+/// LifeSafetyOperation codec and server-side handling
+pub mod life_safety_operation;
+pub use life_safety_operation::LifeSafetyOperationRequest;
+/// WriteGroup codec and channel dispatch
+pub mod write_group;
+pub use write_group::{GroupChannelValue, WriteGroupRequest};
+/// Audit notification and AuditLogQuery codecs and Audit Log handling
+pub mod audit;
+pub use audit::{
+    AuditLogQueryAck, AuditLogQueryParameters, AuditLogQueryRequest, AuditLogQueryResult,
+    AuditNotificationRequest, AuditQueryBySource, AuditQueryByTarget, SuccessFilter,
+};
+/// Who-Am-I and You-Are codecs and device commissioning
+pub mod who_am_i;
+pub use who_am_i::{DeviceAssignment, DeviceCommissioner, WhoAmIRequest, YouAreRequest};
+/// WritePropertyMultiple request codec and server-side handling
+pub mod write_property_multiple;
+pub use write_property_multiple::{
+    BacnetPropertyValue, ObjectPropertyReference, WriteAccessSpecification,
+    WritePropertyMultipleError, WritePropertyMultipleRequest,
+};
 
 #[cfg(test)]
 mod tests {
@@ -1960,207 +1073,6 @@ mod tests {
         );
     }
 
-    #[test]
-    fn test_subscribe_cov_request() {
-        let object_id = ObjectIdentifier::new(ObjectType::AnalogInput, 1);
-        let cov_req = SubscribeCovRequest::new(123, object_id);
-
-        assert_eq!(cov_req.subscriber_process_identifier, 123);
-        assert_eq!(cov_req.monitored_object_identifier.instance, 1);
-        assert_eq!(cov_req.issue_confirmed_notifications, None);
-        assert_eq!(cov_req.lifetime, None);
-
-        // Test with confirmation
-        let cov_confirmed = SubscribeCovRequest::with_confirmation(123, object_id, true);
-        assert_eq!(cov_confirmed.issue_confirmed_notifications, Some(true));
-
-        // Test with lifetime
-        let cov_lifetime = SubscribeCovRequest::with_lifetime(123, object_id, 3600);
-        assert_eq!(cov_lifetime.lifetime, Some(3600));
-
-        // Test encoding
-        let mut buffer = Vec::new();
-        cov_req.encode(&mut buffer).unwrap();
-        assert!(!buffer.is_empty());
-    }
-
-    #[test]
-    fn test_cov_subscription_manager() {
-        let mut manager = CovSubscriptionManager::new();
-
-        let device_id = ObjectIdentifier::new(ObjectType::Device, 1);
-        let object_id = ObjectIdentifier::new(ObjectType::AnalogInput, 1);
-
-        let subscription = CovSubscription::new(123, device_id, object_id, 3600);
-        manager.add_subscription(subscription);
-
-        assert_eq!(manager.active_count(), 1);
-
-        let subscriptions = manager.get_subscriptions_for_object(object_id);
-        assert_eq!(subscriptions.len(), 1);
-        assert_eq!(subscriptions[0].subscriber_process_identifier, 123);
-
-        // Test time updates
-        manager.update_timers(1800); // 30 minutes
-        let subscriptions = manager.get_subscriptions_for_object(object_id);
-        assert_eq!(subscriptions[0].time_remaining, 1800);
-
-        // Test expiration
-        manager.update_timers(1800); // Another 30 minutes
-        assert_eq!(manager.active_count(), 0);
-
-        manager.cleanup_expired();
-        assert_eq!(manager.subscriptions.len(), 0);
-    }
-
-    #[test]
-    fn test_cov_notification_request() {
-        let device_id = ObjectIdentifier::new(ObjectType::Device, 1);
-        let object_id = ObjectIdentifier::new(ObjectType::AnalogInput, 1);
-        let values = vec![
-            crate::object::PropertyValue::Real(25.5), // Present Value
-            crate::object::PropertyValue::Boolean(false), // Status Flags
-        ];
-
-        let notification = CovNotificationRequest::new(123, device_id, object_id, 3600, values);
-
-        assert_eq!(notification.subscriber_process_identifier, 123);
-        assert_eq!(notification.initiating_device_identifier, device_id);
-        assert_eq!(notification.monitored_object_identifier, object_id);
-        assert_eq!(notification.time_remaining, 3600);
-        assert_eq!(notification.list_of_values.len(), 2);
-
-        // Test encoding
-        let mut buffer = Vec::new();
-        notification.encode(&mut buffer).unwrap();
-        assert!(!buffer.is_empty());
-    }
-
-    #[test]
-    fn test_atomic_read_file_request() {
-        let file_id = ObjectIdentifier::new(ObjectType::File, 1);
-
-        // Test stream access
-        let read_stream = AtomicReadFileRequest::new_stream_access(file_id, 0, 1024);
-        match &read_stream.access_method {
-            FileAccessMethod::StreamAccess {
-                file_start_position,
-                requested_octet_count,
-            } => {
-                assert_eq!(*file_start_position, 0);
-                assert_eq!(*requested_octet_count, 1024);
-            }
-            _ => panic!("Expected StreamAccess"),
-        }
-
-        // Test record access
-        let read_record = AtomicReadFileRequest::new_record_access(file_id, 5, 10);
-        match &read_record.access_method {
-            FileAccessMethod::RecordAccess {
-                file_start_record,
-                requested_record_count,
-            } => {
-                assert_eq!(*file_start_record, 5);
-                assert_eq!(*requested_record_count, 10);
-            }
-            _ => panic!("Expected RecordAccess"),
-        }
-
-        // Test encoding
-        let mut buffer = Vec::new();
-        read_stream.encode(&mut buffer).unwrap();
-        assert!(!buffer.is_empty());
-    }
-
-    #[test]
-    fn test_atomic_read_file_response() {
-        // Test stream access response
-        let data = vec![1, 2, 3, 4, 5];
-        let response_stream = AtomicReadFileResponse::new_stream_access(false, 0, data.clone());
-        assert!(!response_stream.end_of_file);
-
-        match &response_stream.access_method_result {
-            FileAccessMethodResult::StreamAccess {
-                file_start_position,
-                file_data,
-            } => {
-                assert_eq!(*file_start_position, 0);
-                assert_eq!(*file_data, data);
-            }
-            _ => panic!("Expected StreamAccess result"),
-        }
-
-        // Test record access response
-        let records = vec![vec![1, 2], vec![3, 4], vec![5, 6]];
-        let response_record = AtomicReadFileResponse::new_record_access(true, 10, records.clone());
-        assert!(response_record.end_of_file);
-
-        match &response_record.access_method_result {
-            FileAccessMethodResult::RecordAccess {
-                file_start_record,
-                record_count,
-                file_record_data,
-            } => {
-                assert_eq!(*file_start_record, 10);
-                assert_eq!(*record_count, 3);
-                assert_eq!(*file_record_data, records);
-            }
-            _ => panic!("Expected RecordAccess result"),
-        }
-    }
-
-    #[test]
-    fn test_atomic_write_file_request() {
-        let file_id = ObjectIdentifier::new(ObjectType::File, 1);
-
-        // Test stream access
-        let data = vec![65, 66, 67, 68]; // "ABCD"
-        let write_stream = AtomicWriteFileRequest::new_stream_access(file_id, 100, data.clone());
-        match &write_stream.access_method {
-            FileWriteAccessMethod::StreamAccess {
-                file_start_position,
-                file_data,
-            } => {
-                assert_eq!(*file_start_position, 100);
-                assert_eq!(*file_data, data);
-            }
-            _ => panic!("Expected StreamAccess"),
-        }
-
-        // Test record access
-        let records = vec![
-            b"Record 1".to_vec(),
-            b"Record 2".to_vec(),
-            b"Record 3".to_vec(),
-        ];
-        let write_record = AtomicWriteFileRequest::new_record_access(file_id, 5, records.clone());
-        match &write_record.access_method {
-            FileWriteAccessMethod::RecordAccess {
-                file_start_record,
-                record_count,
-                file_record_data,
-            } => {
-                assert_eq!(*file_start_record, 5);
-                assert_eq!(*record_count, 3);
-                assert_eq!(*file_record_data, records);
-            }
-            _ => panic!("Expected RecordAccess"),
-        }
-
-        // Test encoding
-        let mut buffer = Vec::new();
-        write_stream.encode(&mut buffer).unwrap();
-        assert!(!buffer.is_empty());
-    }
-
-    #[test]
-    fn test_atomic_write_file_response() {
-        let response = AtomicWriteFileResponse {
-            file_start_position: 150,
-        };
-        assert_eq!(response.file_start_position, 150);
-    }
-
     #[test]
     fn test_bacnet_datetime() {
         // Test creating specific datetime
@@ -2203,78 +1115,57 @@ mod tests {
     }
 
     #[test]
-    fn test_time_synchronization_request() {
-        let date = crate::object::Date {
-            year: 2024,
-            month: 6,
-            day: 20,
-            weekday: 4,
-        };
-        let time = crate::object::Time {
-            hour: 10,
-            minute: 15,
-            second: 30,
-            hundredths: 25,
-        };
-        let datetime = BacnetDateTime::new(date, time);
-        let time_sync = TimeSynchronizationRequest::new(datetime);
-
-        assert_eq!(time_sync.date_time, datetime);
-
-        // Test encoding/decoding
-        let mut buffer = Vec::new();
-        time_sync.encode(&mut buffer).unwrap();
-        assert_eq!(buffer.len(), 10);
-
-        let decoded = TimeSynchronizationRequest::decode(&buffer).unwrap();
-        assert_eq!(decoded.date_time, datetime);
-    }
+    fn test_failure_pdus() {
+        // Decode failures are rejected with the reason that fits
+        let truncated = WritePropertyRequest::decode(&[0x0C]).unwrap_err();
+        assert_eq!(
+            RejectReason::for_decode_error(&truncated),
+            RejectReason::MissingRequiredParameter
+        );
+        assert_eq!(
+            RejectReason::for_decode_error(&EncodingError::ValueOutOfRange),
+            RejectReason::ParameterOutOfRange
+        );
 
-    #[test]
-    fn test_utc_time_synchronization_request() {
-        let date = crate::object::Date {
-            year: 2024,
-            month: 6,
-            day: 20,
-            weekday: 4,
-        };
-        let time = crate::object::Time {
-            hour: 18,
-            minute: 45,
-            second: 15,
-            hundredths: 75,
+        // Each handler error maps to the PDU type the client expects, and
+        // back to the same error
+        let not_found = PropertyAccessError {
+            error_class: 1,
+            error_code: 31,
         };
-        let utc_datetime = BacnetDateTime::new(date, time);
-        let utc_sync = UtcTimeSynchronizationRequest::new(utc_datetime);
-
-        assert_eq!(utc_sync.utc_date_time, utc_datetime);
-
-        // Test encoding/decoding
-        let mut buffer = Vec::new();
-        utc_sync.encode(&mut buffer).unwrap();
-        assert_eq!(buffer.len(), 10);
-
-        let decoded = UtcTimeSynchronizationRequest::decode(&buffer).unwrap();
-        assert_eq!(decoded.utc_date_time, utc_datetime);
-    }
+        let apdu = ServiceError::Failed(not_found).to_apdu(3, 12);
+        assert_eq!(apdu, Apdu::error(3, 12, not_found));
+        assert!(matches!(
+            ServiceError::from_apdu(&apdu),
+            Some(ServiceError::Failed(error)) if error == not_found
+        ));
+
+        let apdu = ServiceError::UnsupportedService.to_apdu(3, 12);
+        assert_eq!(apdu, Apdu::reject(3, RejectReason::UnrecognizedService));
+        assert!(matches!(
+            ServiceError::from_apdu(&apdu),
+            Some(ServiceError::Rejected(RejectReason::UnrecognizedService))
+        ));
+
+        let apdu = ServiceError::Timeout.to_apdu(3, 12);
+        assert_eq!(
+            apdu,
+            Apdu::abort(true, 3, AbortReason::ApplicationExceededReplyTime)
+        );
+        let error = ServiceError::from_apdu(&Apdu::decode(&apdu.encode()).unwrap()).unwrap();
+        assert_eq!(
+            error.to_string(),
+            "Service aborted: application exceeded reply time"
+        );
+        assert!(ServiceError::from_apdu(&Apdu::SimpleAck {
+            invoke_id: 3,
+            service_choice: 12
+        })
+        .is_none());
 
-    #[cfg(feature = "std")]
-    #[test]
-    fn test_time_synchronization_now() {
-        // Test creating time sync with current time
-        let now_sync = TimeSynchronizationRequest::now();
-        assert!(!now_sync.date_time.is_unspecified());
-
-        let utc_now_sync = UtcTimeSynchronizationRequest::now();
-        assert!(!utc_now_sync.utc_date_time.is_unspecified());
-
-        // The current time should be reasonable
-        assert!(now_sync.date_time.date.year >= 2024);
-        assert!(now_sync.date_time.date.month >= 1 && now_sync.date_time.date.month <= 12);
-        assert!(now_sync.date_time.date.day >= 1 && now_sync.date_time.date.day <= 31);
-        assert!(now_sync.date_time.time.hour <= 23);
-        assert!(now_sync.date_time.time.minute <= 59);
-        assert!(now_sync.date_time.time.second <= 59);
-        assert!(now_sync.date_time.time.hundredths <= 99);
+        // Proprietary and unknown reasons read as Other
+        assert_eq!(RejectReason::from_u8(200), RejectReason::Other);
+        assert_eq!(AbortReason::from_u8(11), AbortReason::ApduTooLong);
+        assert_eq!(AbortReason::from_u8(64), AbortReason::Other);
     }
 }
@@ -0,0 +1,564 @@
+//! CreateObject and DeleteObject Services (Clauses 15.3 and 15.4)
+//!
+//! CreateObject names either an object type, leaving the server to pick the
+//! instance, or a full object identifier, and may carry initial property
+//! values. The server builds the object with the factory registered for its
+//! type (see [`ObjectFactoryRegistry`](crate::object::ObjectFactoryRegistry))
+//! and writes the initial values before adding it, so a rejected value leaves
+//! the device unchanged and the error names the value that failed.
+//!
+//! DeleteObject removes an object if both the object
+//! ([`BacnetObject::is_deletable`](crate::object::BacnetObject::is_deletable))
+//! and the host's deletion policy allow it. The Device object's Object_List
+//! follows both services without further work, since it is built from the
+//! database's contents.
+
+use super::event_notification::{encode_identifier, Reader};
+use super::list_element::ChangeListError;
+use super::write_property_multiple::BacnetPropertyValue;
+use crate::encoding::{
+    advanced::context::{encode_closing_tag, encode_opening_tag},
+    decode_object_identifier, encode_context_enumerated, encode_object_identifier, EncodingError,
+    Result as EncodingResult,
+};
+use crate::object::{ObjectIdentifier, ObjectType};
+
+#[cfg(feature = "std")]
+use super::{AbortReason, ConfirmedServiceChoice, PropertyAccessError, RejectReason};
+#[cfg(feature = "std")]
+use crate::{
+    app::Apdu,
+    object::{
+        database::ObjectDatabase, BacnetObject, ObjectError, PropertyIdentifier, PropertyValue,
+    },
+};
+
+#[cfg(not(feature = "std"))]
+use alloc::{format, string::ToString, vec::Vec};
+
+/// Error class object (1), code dynamic-creation-not-supported (4)
+#[cfg(feature = "std")]
+const DYNAMIC_CREATION_NOT_SUPPORTED: PropertyAccessError = PropertyAccessError {
+    error_class: 1,
+    error_code: 4,
+};
+
+/// Error class object (1), code object-deletion-not-permitted (23)
+#[cfg(feature = "std")]
+const OBJECT_DELETION_NOT_PERMITTED: PropertyAccessError = PropertyAccessError {
+    error_class: 1,
+    error_code: 23,
+};
+
+/// Error class object (1), code object-identifier-already-exists (24)
+#[cfg(feature = "std")]
+const OBJECT_IDENTIFIER_ALREADY_EXISTS: PropertyAccessError = PropertyAccessError {
+    error_class: 1,
+    error_code: 24,
+};
+
+/// Error class property (2), code duplicate-name (48)
+#[cfg(feature = "std")]
+const DUPLICATE_NAME: PropertyAccessError = PropertyAccessError {
+    error_class: 2,
+    error_code: 48,
+};
+
+/// Error returned when a Create Object request fails (CreateObject-Error)
+///
+/// It has the same parameters as the Change List error; the element number
+/// counts the initial values.
+pub type CreateObjectError = ChangeListError;
+
+/// The object a Create Object request asks for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectSpecifier {
+    /// An object of this type, at an instance the server chooses
+    ObjectType(ObjectType),
+    /// An object with this identifier
+    ObjectIdentifier(ObjectIdentifier),
+}
+
+/// Create Object request (confirmed service)
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreateObjectRequest {
+    /// Type or identifier of the new object
+    pub object_specifier: ObjectSpecifier,
+    /// Property values to give the new object (may be empty)
+    pub list_of_initial_values: Vec<BacnetPropertyValue>,
+}
+
+impl CreateObjectRequest {
+    /// Create a request for an object of `object_type`
+    pub fn by_type(object_type: ObjectType) -> Self {
+        Self {
+            object_specifier: ObjectSpecifier::ObjectType(object_type),
+            list_of_initial_values: Vec::new(),
+        }
+    }
+
+    /// Create a request for the object `object_identifier`
+    pub fn by_identifier(object_identifier: ObjectIdentifier) -> Self {
+        Self {
+            object_specifier: ObjectSpecifier::ObjectIdentifier(object_identifier),
+            list_of_initial_values: Vec::new(),
+        }
+    }
+
+    /// Add initial property values
+    pub fn with_initial_values(mut self, values: Vec<BacnetPropertyValue>) -> Self {
+        self.list_of_initial_values = values;
+        self
+    }
+
+    /// Encode the request
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        // Object specifier - context tag 0
+        encode_opening_tag(buffer, 0)?;
+        match self.object_specifier {
+            ObjectSpecifier::ObjectType(object_type) => {
+                buffer.extend_from_slice(&encode_context_enumerated(
+                    u32::from(u16::from(object_type)),
+                    0,
+                )?);
+            }
+            ObjectSpecifier::ObjectIdentifier(identifier) => {
+                encode_identifier(buffer, &identifier, 1)?;
+            }
+        }
+        encode_closing_tag(buffer, 0)?;
+
+        // List of initial values - context tag 1 (optional)
+        if !self.list_of_initial_values.is_empty() {
+            encode_opening_tag(buffer, 1)?;
+            for value in &self.list_of_initial_values {
+                value.encode(buffer)?;
+            }
+            encode_closing_tag(buffer, 1)?;
+        }
+
+        Ok(())
+    }
+
+    /// Decode a request
+    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
+        let mut reader = Reader::new(data);
+
+        reader.open(0)?;
+        let object_specifier = if reader.is_context(0) {
+            let object_type = u16::try_from(reader.enumerated(0)?)
+                .ok()
+                .and_then(|object_type| ObjectType::try_from(object_type).ok())
+                .ok_or(EncodingError::ValueOutOfRange)?;
+            ObjectSpecifier::ObjectType(object_type)
+        } else {
+            ObjectSpecifier::ObjectIdentifier(reader.identifier(1)?)
+        };
+        reader.close(0)?;
+
+        let mut list_of_initial_values = Vec::new();
+        if reader.is_context(1) {
+            reader.open(1)?;
+            while !reader.at_close(1) {
+                list_of_initial_values
+                    .push(reader.advance(BacnetPropertyValue::decode(reader.rest())?));
+            }
+            reader.close(1)?;
+        }
+
+        if !reader.rest().is_empty() {
+            return Err(EncodingError::InvalidFormat(
+                "Unexpected data after Create Object request".to_string(),
+            ));
+        }
+        Ok(Self {
+            object_specifier,
+            list_of_initial_values,
+        })
+    }
+}
+
+/// Create Object acknowledgement (ComplexAck service data)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CreateObjectAck {
+    /// Identifier of the object created
+    pub object_identifier: ObjectIdentifier,
+}
+
+impl CreateObjectAck {
+    /// Encode the Create Object acknowledgement
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        encode_app_identifier(buffer, &self.object_identifier)
+    }
+
+    /// Decode a Create Object acknowledgement
+    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
+        let object_identifier = decode_only_identifier(data, "Create Object acknowledgement")?;
+        Ok(Self { object_identifier })
+    }
+}
+
+/// Delete Object request (confirmed service)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeleteObjectRequest {
+    /// Object to delete
+    pub object_identifier: ObjectIdentifier,
+}
+
+impl DeleteObjectRequest {
+    /// Create a new Delete Object request
+    pub fn new(object_identifier: ObjectIdentifier) -> Self {
+        Self { object_identifier }
+    }
+
+    /// Encode the request
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        encode_app_identifier(buffer, &self.object_identifier)
+    }
+
+    /// Decode a request
+    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
+        let object_identifier = decode_only_identifier(data, "Delete Object request")?;
+        Ok(Self { object_identifier })
+    }
+}
+
+fn encode_app_identifier(
+    buffer: &mut Vec<u8>,
+    identifier: &ObjectIdentifier,
+) -> EncodingResult<()> {
+    encode_object_identifier(
+        buffer,
+        u16::from(identifier.object_type),
+        identifier.instance,
+    )
+}
+
+/// Decode service data made of one application-tagged object identifier
+fn decode_only_identifier(data: &[u8], what: &str) -> EncodingResult<ObjectIdentifier> {
+    let ((object_type, instance), consumed) = decode_object_identifier(data)?;
+    if consumed != data.len() {
+        return Err(EncodingError::InvalidFormat(format!(
+            "Unexpected data after {}",
+            what
+        )));
+    }
+    let object_type =
+        ObjectType::try_from(object_type).map_err(|_| EncodingError::ValueOutOfRange)?;
+    Ok(ObjectIdentifier::new(object_type, instance))
+}
+
+/// Apply a Create Object request to an object database
+///
+/// A request by type gets the next free instance of that type. The new object
+/// is named by an Object_Name initial value if there is one, otherwise after
+/// its type and instance. Either the object is added with every initial value
+/// written, or nothing changes.
+#[cfg(feature = "std")]
+pub fn create_object(
+    database: &ObjectDatabase,
+    request: &CreateObjectRequest,
+) -> Result<ObjectIdentifier, CreateObjectError> {
+    let identifier = match request.object_specifier {
+        ObjectSpecifier::ObjectType(object_type) => {
+            ObjectIdentifier::new(object_type, database.next_instance(object_type))
+        }
+        ObjectSpecifier::ObjectIdentifier(identifier) => {
+            if database.contains(identifier) {
+                return Err(CreateObjectError::new(OBJECT_IDENTIFIER_ALREADY_EXISTS, 0));
+            }
+            identifier
+        }
+    };
+
+    let mut name = None;
+    for (number, value) in (1..).zip(&request.list_of_initial_values) {
+        if value.property_identifier != u32::from(PropertyIdentifier::ObjectName) {
+            continue;
+        }
+        match &value.value {
+            PropertyValue::CharacterString(object_name) => name = Some((number, object_name)),
+            _ => {
+                return Err(CreateObjectError::new(
+                    PropertyAccessError::from(&ObjectError::InvalidPropertyType),
+                    number,
+                ))
+            }
+        }
+    }
+    let (name_number, name) = match name {
+        Some((number, name)) => (number, name.clone()),
+        None => (
+            0,
+            format!("{:?} {}", identifier.object_type, identifier.instance),
+        ),
+    };
+    if database.contains_name(&name) {
+        return Err(CreateObjectError::new(DUPLICATE_NAME, name_number));
+    }
+
+    let mut object = database
+        .build_object(identifier.object_type, identifier.instance, name)
+        .ok_or(CreateObjectError::new(DYNAMIC_CREATION_NOT_SUPPORTED, 0))?;
+    for (number, value) in (1..).zip(&request.list_of_initial_values) {
+        if value.property_identifier == u32::from(PropertyIdentifier::ObjectName) {
+            continue;
+        }
+        initialize(object.as_mut(), value)
+            .map_err(|error| CreateObjectError::new(PropertyAccessError::from(&error), number))?;
+    }
+
+    database
+        .add_object(object)
+        .map_err(|error| CreateObjectError::new(PropertyAccessError::from(&error), 0))?;
+    Ok(identifier)
+}
+
+/// Write one initial value to an object that is not yet in the database
+#[cfg(feature = "std")]
+fn initialize(
+    object: &mut dyn BacnetObject,
+    value: &BacnetPropertyValue,
+) -> Result<(), ObjectError> {
+    let property = PropertyIdentifier::try_from(value.property_identifier)
+        .map_err(|_| ObjectError::UnknownProperty)?;
+    match (value.property_array_index, value.priority) {
+        (Some(index), _) => object.set_property_at(property, index, value.value.clone()),
+        (None, Some(priority)) => {
+            object.set_property_with_priority(property, value.value.clone(), priority)
+        }
+        (None, None) => object.set_property(property, value.value.clone()),
+    }
+}
+
+/// Apply a Delete Object request to an object database
+#[cfg(feature = "std")]
+pub fn delete_object(
+    database: &ObjectDatabase,
+    request: &DeleteObjectRequest,
+) -> Result<(), PropertyAccessError> {
+    if !database.is_deletable(request.object_identifier)? {
+        return Err(OBJECT_DELETION_NOT_PERMITTED);
+    }
+    database.remove_object(request.object_identifier)?;
+    Ok(())
+}
+
+/// Answer a Create Object request
+///
+/// Returns a ComplexAck with the new object's identifier, an Error PDU naming
+/// the first failed initial value if the object cannot be created, or a Reject
+/// PDU if the request cannot be decoded.
+#[cfg(feature = "std")]
+pub fn handle_create_object(database: &ObjectDatabase, invoke_id: u8, service_data: &[u8]) -> Apdu {
+    let service_choice = ConfirmedServiceChoice::CreateObject as u8;
+    let request = match CreateObjectRequest::decode(service_data) {
+        Ok(request) => request,
+        Err(error) => return Apdu::reject(invoke_id, RejectReason::for_decode_error(&error)),
+    };
+
+    match create_object(database, &request) {
+        Ok(object_identifier) => {
+            let mut service_data = Vec::new();
+            match (CreateObjectAck { object_identifier }).encode(&mut service_data) {
+                Ok(()) => Apdu::ComplexAck {
+                    segmented: false,
+                    more_follows: false,
+                    invoke_id,
+                    sequence_number: None,
+                    proposed_window_size: None,
+                    service_choice,
+                    service_data,
+                },
+                Err(error) => Apdu::abort(true, invoke_id, AbortReason::for_encode_error(&error)),
+            }
+        }
+        Err(failure) => failure
+            .to_apdu(invoke_id, ConfirmedServiceChoice::CreateObject)
+            .unwrap_or_else(|error| {
+                Apdu::abort(true, invoke_id, AbortReason::for_encode_error(&error))
+            }),
+    }
+}
+
+/// Answer a Delete Object request
+///
+/// Returns a SimpleAck once the object is gone, an Error PDU if it cannot be
+/// deleted, or a Reject PDU if the request cannot be decoded.
+#[cfg(feature = "std")]
+pub fn handle_delete_object(database: &ObjectDatabase, invoke_id: u8, service_data: &[u8]) -> Apdu {
+    let service_choice = ConfirmedServiceChoice::DeleteObject as u8;
+    let request = match DeleteObjectRequest::decode(service_data) {
+        Ok(request) => request,
+        Err(error) => return Apdu::reject(invoke_id, RejectReason::for_decode_error(&error)),
+    };
+
+    match delete_object(database, &request) {
+        Ok(()) => Apdu::SimpleAck {
+            invoke_id,
+            service_choice,
+        },
+        Err(error) => Apdu::error(invoke_id, service_choice, error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::{PropertyIdentifier, PropertyValue};
+
+    #[test]
+    fn test_codecs() {
+        let request =
+            CreateObjectRequest::by_type(ObjectType::AnalogValue).with_initial_values(vec![
+                BacnetPropertyValue::new(
+                    u32::from(PropertyIdentifier::PresentValue),
+                    PropertyValue::Real(20.5),
+                ),
+            ]);
+        let mut buffer = Vec::new();
+        request.encode(&mut buffer).unwrap();
+        assert_eq!(&buffer[..3], [0x0E, 0x09, 0x02]);
+        assert_eq!(CreateObjectRequest::decode(&buffer).unwrap(), request);
+
+        let identifier = ObjectIdentifier::new(ObjectType::BinaryValue, 3);
+        let request = CreateObjectRequest::by_identifier(identifier);
+        let mut buffer = Vec::new();
+        request.encode(&mut buffer).unwrap();
+        assert_eq!(buffer, [0x0E, 0x1C, 0x01, 0x40, 0x00, 0x03, 0x0F]);
+        assert_eq!(CreateObjectRequest::decode(&buffer).unwrap(), request);
+
+        let mut buffer = Vec::new();
+        DeleteObjectRequest::new(identifier)
+            .encode(&mut buffer)
+            .unwrap();
+        assert_eq!(buffer, [0xC4, 0x01, 0x40, 0x00, 0x03]);
+        assert_eq!(
+            DeleteObjectRequest::decode(&buffer)
+                .unwrap()
+                .object_identifier,
+            identifier
+        );
+        assert_eq!(
+            CreateObjectAck::decode(&buffer).unwrap().object_identifier,
+            identifier
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_create_and_delete_objects() {
+        use crate::object::{AnalogValue, Device, ObjectFactoryRegistry};
+
+        let database = ObjectDatabase::new(Device::new(20, String::from("Device")));
+        let device = database.get_device_id();
+        database
+            .add_object(Box::new(AnalogValue::new(4, String::from("Existing"))))
+            .unwrap();
+        let request = CreateObjectRequest::by_type(ObjectType::AnalogValue);
+        assert_eq!(
+            create_object(&database, &request),
+            Err(CreateObjectError::new(DYNAMIC_CREATION_NOT_SUPPORTED, 0))
+        );
+
+        database.set_object_factories(ObjectFactoryRegistry::with_standard_types());
+        let request = request.with_initial_values(vec![
+            BacnetPropertyValue::new(
+                u32::from(PropertyIdentifier::ObjectName),
+                PropertyValue::CharacterString(String::from("Setpoint")),
+            ),
+            BacnetPropertyValue::new(
+                u32::from(PropertyIdentifier::PresentValue),
+                PropertyValue::Real(21.0),
+            ),
+        ]);
+        let created = create_object(&database, &request).unwrap();
+        assert_eq!(created, ObjectIdentifier::new(ObjectType::AnalogValue, 5));
+        assert_eq!(
+            database
+                .get_property(created, PropertyIdentifier::PresentValue)
+                .unwrap(),
+            PropertyValue::Real(21.0)
+        );
+        let PropertyValue::Array(object_list) = database
+            .get_property(device, PropertyIdentifier::ObjectList)
+            .unwrap()
+        else {
+            panic!("Expected Object_List array");
+        };
+        assert!(object_list.contains(&PropertyValue::ObjectIdentifier(created)));
+
+        assert_eq!(
+            create_object(&database, &request),
+            Err(CreateObjectError::new(DUPLICATE_NAME, 1))
+        );
+        let existing = ObjectIdentifier::new(ObjectType::AnalogValue, 4);
+        assert_eq!(
+            create_object(&database, &CreateObjectRequest::by_identifier(existing)),
+            Err(CreateObjectError::new(OBJECT_IDENTIFIER_ALREADY_EXISTS, 0))
+        );
+
+        // A rejected initial value leaves nothing behind
+        let invalid =
+            CreateObjectRequest::by_identifier(ObjectIdentifier::new(ObjectType::AnalogValue, 9))
+                .with_initial_values(vec![BacnetPropertyValue::new(
+                    u32::from(PropertyIdentifier::PresentValue),
+                    PropertyValue::CharacterString(String::from("warm")),
+                )]);
+        assert_eq!(
+            create_object(&database, &invalid)
+                .unwrap_err()
+                .first_failed_element_number,
+            1
+        );
+        assert!(!database.contains(ObjectIdentifier::new(ObjectType::AnalogValue, 9)));
+        let mut service_data = Vec::new();
+        invalid.encode(&mut service_data).unwrap();
+        let reply =
+            Apdu::decode(&handle_create_object(&database, 3, &service_data).encode()).unwrap();
+        assert_eq!(
+            CreateObjectError::from_apdu(&reply).map(|error| error.first_failed_element_number),
+            Some(1)
+        );
+
+        database.set_deletion_policy(|object| object.identifier().instance != 4);
+        let mut service_data = Vec::new();
+        DeleteObjectRequest::new(existing)
+            .encode(&mut service_data)
+            .unwrap();
+        assert!(matches!(
+            handle_delete_object(&database, 1, &service_data),
+            Apdu::Error {
+                service_choice: 11,
+                error_class: 1,
+                error_code: 23,
+                ..
+            }
+        ));
+        assert_eq!(
+            delete_object(&database, &DeleteObjectRequest::new(device)),
+            Err(OBJECT_DELETION_NOT_PERMITTED)
+        );
+
+        let mut service_data = Vec::new();
+        DeleteObjectRequest::new(created)
+            .encode(&mut service_data)
+            .unwrap();
+        assert!(matches!(
+            handle_delete_object(&database, 2, &service_data),
+            Apdu::SimpleAck {
+                service_choice: 11,
+                ..
+            }
+        ));
+        assert!(!database.contains(created));
+        assert!(matches!(
+            handle_delete_object(&database, 3, &service_data),
+            Apdu::Error {
+                error_class: 1,
+                error_code: 31,
+                ..
+            }
+        ));
+    }
+}
@@ -0,0 +1,326 @@
+//! ConfirmedPrivateTransfer and UnconfirmedPrivateTransfer Services
+//! (Clauses 16.2 and 16.3)
+//!
+//! Private transfers carry vendor-defined services identified by a vendor ID
+//! and a service number. Their parameters are opaque to the standard, so the
+//! library moves them as raw encoded bytes and leaves their meaning to a
+//! handler the application registers for each (vendor ID, service number)
+//! pair in a [`PrivateTransferRegistry`].
+
+use super::event_notification::Reader;
+use super::{AbortReason, ConfirmedServiceChoice, PropertyAccessError, RejectReason};
+use crate::app::Apdu;
+use crate::encoding::{
+    advanced::context::{encode_closing_tag, encode_opening_tag},
+    encode_context_unsigned, EncodingError, Result as EncodingResult,
+};
+
+#[cfg(feature = "std")]
+use std::collections::BTreeMap;
+
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, collections::BTreeMap, string::ToString, vec::Vec};
+
+/// Error class services (5), code optional-functionality-not-supported (45)
+const OPTIONAL_FUNCTIONALITY_NOT_SUPPORTED: PropertyAccessError = PropertyAccessError {
+    error_class: 5,
+    error_code: 45,
+};
+
+/// Private Transfer request (confirmed or unconfirmed service)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrivateTransferRequest {
+    /// Vendor defining the service
+    pub vendor_id: u16,
+    /// Vendor's number for the service
+    pub service_number: u32,
+    /// Encoded service parameters (optional)
+    pub service_parameters: Option<Vec<u8>>,
+}
+
+impl PrivateTransferRequest {
+    /// Create a new request without parameters
+    pub fn new(vendor_id: u16, service_number: u32) -> Self {
+        Self {
+            vendor_id,
+            service_number,
+            service_parameters: None,
+        }
+    }
+
+    /// Add encoded service parameters
+    pub fn with_parameters(mut self, service_parameters: Vec<u8>) -> Self {
+        self.service_parameters = Some(service_parameters);
+        self
+    }
+
+    /// Encode the request
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        encode_transfer(
+            buffer,
+            self.vendor_id,
+            self.service_number,
+            self.service_parameters.as_deref(),
+        )
+    }
+
+    /// Decode a request
+    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
+        let (vendor_id, service_number, service_parameters) = decode_transfer(data)?;
+        Ok(Self {
+            vendor_id,
+            service_number,
+            service_parameters,
+        })
+    }
+}
+
+/// Confirmed Private Transfer acknowledgement
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrivateTransferAck {
+    /// Vendor defining the service
+    pub vendor_id: u16,
+    /// Vendor's number for the service
+    pub service_number: u32,
+    /// Encoded result (optional)
+    pub result_block: Option<Vec<u8>>,
+}
+
+impl PrivateTransferAck {
+    /// Encode the acknowledgement
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        encode_transfer(
+            buffer,
+            self.vendor_id,
+            self.service_number,
+            self.result_block.as_deref(),
+        )
+    }
+
+    /// Decode an acknowledgement
+    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
+        let (vendor_id, service_number, result_block) = decode_transfer(data)?;
+        Ok(Self {
+            vendor_id,
+            service_number,
+            result_block,
+        })
+    }
+}
+
+fn encode_transfer(
+    buffer: &mut Vec<u8>,
+    vendor_id: u16,
+    service_number: u32,
+    block: Option<&[u8]>,
+) -> EncodingResult<()> {
+    buffer.extend_from_slice(&encode_context_unsigned(vendor_id as u32, 0)?);
+    buffer.extend_from_slice(&encode_context_unsigned(service_number, 1)?);
+    if let Some(block) = block {
+        encode_opening_tag(buffer, 2)?;
+        buffer.extend_from_slice(block);
+        encode_closing_tag(buffer, 2)?;
+    }
+    Ok(())
+}
+
+fn decode_transfer(data: &[u8]) -> EncodingResult<(u16, u32, Option<Vec<u8>>)> {
+    let mut reader = Reader::new(data);
+    let vendor_id =
+        u16::try_from(reader.unsigned(0)?).map_err(|_| EncodingError::ValueOutOfRange)?;
+    let service_number = reader.unsigned(1)?;
+    let block = reader.optional(2, Reader::constructed)?;
+
+    if !reader.rest().is_empty() {
+        return Err(EncodingError::InvalidFormat(
+            "Unexpected data after Private Transfer".to_string(),
+        ));
+    }
+    Ok((vendor_id, service_number, block))
+}
+
+/// Handles one private service: takes the encoded service parameters and
+/// returns the encoded result block
+pub type PrivateTransferHandler =
+    Box<dyn Fn(Option<&[u8]>) -> Result<Option<Vec<u8>>, PropertyAccessError> + Send + Sync>;
+
+/// Handlers for the private services a device supports, keyed by vendor ID
+/// and service number
+#[derive(Default)]
+pub struct PrivateTransferRegistry {
+    handlers: BTreeMap<(u16, u32), PrivateTransferHandler>,
+}
+
+impl PrivateTransferRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the handler for a private service, replacing any previous one
+    pub fn register<F>(&mut self, vendor_id: u16, service_number: u32, handler: F)
+    where
+        F: Fn(Option<&[u8]>) -> Result<Option<Vec<u8>>, PropertyAccessError>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.handlers
+            .insert((vendor_id, service_number), Box::new(handler));
+    }
+
+    /// Remove the handler for a private service
+    pub fn unregister(&mut self, vendor_id: u16, service_number: u32) -> bool {
+        self.handlers.remove(&(vendor_id, service_number)).is_some()
+    }
+
+    /// Check whether a private service has a handler
+    pub fn supports(&self, vendor_id: u16, service_number: u32) -> bool {
+        self.handlers.contains_key(&(vendor_id, service_number))
+    }
+
+    /// Run the handler for a request
+    ///
+    /// Services without a handler fail with optional-functionality-not-supported.
+    pub fn handle(
+        &self,
+        request: &PrivateTransferRequest,
+    ) -> Result<PrivateTransferAck, PropertyAccessError> {
+        let handler = self
+            .handlers
+            .get(&(request.vendor_id, request.service_number))
+            .ok_or(OPTIONAL_FUNCTIONALITY_NOT_SUPPORTED)?;
+        Ok(PrivateTransferAck {
+            vendor_id: request.vendor_id,
+            service_number: request.service_number,
+            result_block: handler(request.service_parameters.as_deref())?,
+        })
+    }
+}
+
+impl core::fmt::Debug for PrivateTransferRegistry {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("PrivateTransferRegistry")
+            .field("services", &self.handlers.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// Answer a Confirmed Private Transfer request
+///
+/// Returns a ComplexAck with the handler's result block, an Error PDU if there
+/// is no handler or it fails, or a Reject PDU if the request cannot be
+/// decoded.
+pub fn handle_confirmed_private_transfer(
+    registry: &PrivateTransferRegistry,
+    invoke_id: u8,
+    service_data: &[u8],
+) -> Apdu {
+    let service_choice = ConfirmedServiceChoice::ConfirmedPrivateTransfer as u8;
+    let request = match PrivateTransferRequest::decode(service_data) {
+        Ok(request) => request,
+        Err(error) => return Apdu::reject(invoke_id, RejectReason::for_decode_error(&error)),
+    };
+
+    match registry.handle(&request) {
+        Ok(ack) => {
+            let mut service_data = Vec::new();
+            if let Err(error) = ack.encode(&mut service_data) {
+                return Apdu::abort(true, invoke_id, AbortReason::for_encode_error(&error));
+            }
+            Apdu::ComplexAck {
+                segmented: false,
+                more_follows: false,
+                invoke_id,
+                sequence_number: None,
+                proposed_window_size: None,
+                service_choice,
+                service_data,
+            }
+        }
+        Err(error) => Apdu::error(invoke_id, service_choice, error),
+    }
+}
+
+/// Pass an Unconfirmed Private Transfer request to its handler
+///
+/// Returns whether a handler accepted it. Unconfirmed services have no reply,
+/// so the handler's result block is discarded.
+pub fn handle_unconfirmed_private_transfer(
+    registry: &PrivateTransferRegistry,
+    service_data: &[u8],
+) -> EncodingResult<bool> {
+    let request = PrivateTransferRequest::decode(service_data)?;
+    Ok(registry.handle(&request).is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_codec() {
+        // Parameters holding a nested constructed field and a boolean
+        let parameters = vec![0x0E, 0x21, 0x05, 0x0F, 0x11, 0x19, 0x02];
+        let request = PrivateTransferRequest::new(260, 7).with_parameters(parameters);
+        let mut buffer = Vec::new();
+        request.encode(&mut buffer).unwrap();
+        assert_eq!(
+            buffer,
+            [0x0A, 0x01, 0x04, 0x19, 0x07, 0x2E, 0x0E, 0x21, 0x05, 0x0F, 0x11, 0x19, 0x02, 0x2F]
+        );
+        assert_eq!(PrivateTransferRequest::decode(&buffer).unwrap(), request);
+
+        let request = PrivateTransferRequest::new(8, 1);
+        let mut buffer = Vec::new();
+        request.encode(&mut buffer).unwrap();
+        assert_eq!(PrivateTransferRequest::decode(&buffer).unwrap(), request);
+
+        // Unterminated parameters
+        assert!(PrivateTransferRequest::decode(&[0x09, 0x08, 0x19, 0x01, 0x2E, 0x21]).is_err());
+    }
+
+    #[test]
+    fn test_registry_dispatch() {
+        let mut registry = PrivateTransferRegistry::new();
+        registry.register(260, 7, |parameters| {
+            Ok(Some(vec![
+                0x21,
+                parameters.map_or(0, |parameters| parameters.len() as u8),
+            ]))
+        });
+
+        let mut service_data = Vec::new();
+        PrivateTransferRequest::new(260, 7)
+            .with_parameters(vec![0x21, 0x05])
+            .encode(&mut service_data)
+            .unwrap();
+        let Apdu::ComplexAck {
+            invoke_id: 2,
+            service_choice: 18,
+            service_data: ack,
+            ..
+        } = handle_confirmed_private_transfer(&registry, 2, &service_data)
+        else {
+            panic!("expected a ComplexAck");
+        };
+        assert_eq!(
+            PrivateTransferAck::decode(&ack).unwrap().result_block,
+            Some(vec![0x21, 0x02])
+        );
+
+        let mut service_data = Vec::new();
+        PrivateTransferRequest::new(260, 8)
+            .encode(&mut service_data)
+            .unwrap();
+        assert!(matches!(
+            handle_confirmed_private_transfer(&registry, 3, &service_data),
+            Apdu::Error {
+                error_class: 5,
+                error_code: 45,
+                ..
+            }
+        ));
+        assert!(!handle_unconfirmed_private_transfer(&registry, &service_data).unwrap());
+    }
+}
@@ -0,0 +1,490 @@
+//! ReadProperty Service (Clause 15.5)
+//!
+//! A client names an object, a property and optionally an array index; the
+//! server answers with a ComplexAck carrying the value, or with an Error PDU
+//! whose class and code explain why the property could not be read. This module
+//! holds the request and acknowledgement codecs, the application-tagged value
+//! codec they share, and [`handle_read_property`] which answers a request from
+//! an [`ObjectDatabase`](crate::object::database::ObjectDatabase).
+
+use crate::encoding::{
+    advanced::bitstring::{decode_bit_string, encode_bit_string},
+    advanced::context::{encode_closing_tag, encode_opening_tag},
+    decode_application_tag, decode_boolean, decode_character_string, decode_context_enumerated,
+    decode_context_object_id, decode_context_unsigned, decode_date, decode_double,
+    decode_enumerated, decode_object_identifier, decode_octet_string, decode_real, decode_signed,
+    decode_time, decode_unsigned, encode_application_tag, encode_boolean, encode_character_string,
+    encode_context_enumerated, encode_context_object_id, encode_context_unsigned, encode_date,
+    encode_double, encode_enumerated, encode_object_identifier, encode_octet_string, encode_real,
+    encode_signed, encode_time, encode_unsigned, ApplicationTag, EncodingError,
+    Result as EncodingResult,
+};
+use crate::object::{Date, ObjectIdentifier, ObjectType, PropertyValue, Time};
+
+#[cfg(feature = "std")]
+use super::{AbortReason, ConfirmedServiceChoice, PropertyAccessError, RejectReason};
+#[cfg(feature = "std")]
+use crate::{
+    app::Apdu,
+    object::{database::ObjectDatabase, ObjectError, PropertyIdentifier},
+};
+
+use super::BACNET_ARRAY_ALL;
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::ToString, vec::Vec};
+
+/// Read Property request (confirmed service)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReadPropertyRequest {
+    /// Object identifier to read from
+    pub object_identifier: ObjectIdentifier,
+    /// Property identifier to read
+    pub property_identifier: u32,
+    /// Property array index (optional)
+    pub property_array_index: Option<u32>,
+}
+
+impl ReadPropertyRequest {
+    /// Create a new Read Property request
+    pub fn new(object_identifier: ObjectIdentifier, property_identifier: u32) -> Self {
+        Self {
+            object_identifier,
+            property_identifier,
+            property_array_index: None,
+        }
+    }
+
+    /// Create a new Read Property request with array index
+    pub fn with_array_index(
+        object_identifier: ObjectIdentifier,
+        property_identifier: u32,
+        array_index: u32,
+    ) -> Self {
+        Self {
+            object_identifier,
+            property_identifier,
+            property_array_index: Some(array_index),
+        }
+    }
+
+    /// Encode the Read Property request
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        encode_property_reference(
+            buffer,
+            self.object_identifier,
+            self.property_identifier,
+            self.property_array_index,
+        )
+    }
+
+    /// Decode a Read Property request
+    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
+        let (object_identifier, property_identifier, property_array_index, consumed) =
+            decode_property_reference(data)?;
+        if consumed != data.len() {
+            return Err(EncodingError::InvalidFormat(
+                "Unexpected data after Read Property request".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            object_identifier,
+            property_identifier,
+            property_array_index,
+        })
+    }
+}
+
+/// Read Property acknowledgement (ComplexAck service data)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReadPropertyAck {
+    /// Object identifier that was read
+    pub object_identifier: ObjectIdentifier,
+    /// Property identifier that was read
+    pub property_identifier: u32,
+    /// Property array index (optional)
+    pub property_array_index: Option<u32>,
+    /// Property value
+    pub property_value: PropertyValue,
+}
+
+impl ReadPropertyAck {
+    /// Create a new Read Property acknowledgement
+    pub fn new(
+        object_identifier: ObjectIdentifier,
+        property_identifier: u32,
+        property_value: PropertyValue,
+    ) -> Self {
+        Self {
+            object_identifier,
+            property_identifier,
+            property_array_index: None,
+            property_value,
+        }
+    }
+
+    /// Encode the Read Property acknowledgement
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        encode_property_reference(
+            buffer,
+            self.object_identifier,
+            self.property_identifier,
+            self.property_array_index,
+        )?;
+
+        // Property value - context tag 3
+        encode_opening_tag(buffer, 3)?;
+        encode_property_value(buffer, &self.property_value)?;
+        encode_closing_tag(buffer, 3)?;
+
+        Ok(())
+    }
+
+    /// Decode a Read Property acknowledgement
+    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
+        let (object_identifier, property_identifier, property_array_index, mut pos) =
+            decode_property_reference(data)?;
+
+        // Property value - context tag 3 (opening tag)
+        if data.get(pos) != Some(&0x3E) {
+            return Err(EncodingError::InvalidTag);
+        }
+        pos += 1;
+
+        let (property_value, consumed) = decode_property_value(&data[pos..])?;
+        pos += consumed;
+
+        // Closing tag 3
+        if data.get(pos) != Some(&0x3F) {
+            return Err(EncodingError::InvalidTag);
+        }
+
+        Ok(Self {
+            object_identifier,
+            property_identifier,
+            property_array_index,
+            property_value,
+        })
+    }
+}
+
+/// Encode the object identifier (tag 0), property identifier (tag 1) and
+/// optional array index (tag 2) shared by the request and acknowledgement
+pub(super) fn encode_property_reference(
+    buffer: &mut Vec<u8>,
+    object_identifier: ObjectIdentifier,
+    property_identifier: u32,
+    property_array_index: Option<u32>,
+) -> EncodingResult<()> {
+    buffer.extend_from_slice(&encode_context_object_id(
+        u16::from(object_identifier.object_type),
+        object_identifier.instance,
+        0,
+    )?);
+    buffer.extend_from_slice(&encode_context_enumerated(property_identifier, 1)?);
+    if let Some(array_index) = property_array_index {
+        buffer.extend_from_slice(&encode_context_unsigned(array_index, 2)?);
+    }
+    Ok(())
+}
+
+/// Decode the fields written by `encode_property_reference`, returning them
+/// with the number of bytes consumed
+pub(super) fn decode_property_reference(
+    data: &[u8],
+) -> EncodingResult<(ObjectIdentifier, u32, Option<u32>, usize)> {
+    let ((object_type, instance), mut pos) = decode_context_object_id(data, 0)?;
+    let object_type =
+        ObjectType::try_from(object_type).map_err(|_| EncodingError::ValueOutOfRange)?;
+    let object_identifier = ObjectIdentifier::new(object_type, instance);
+
+    let (property_identifier, consumed) = decode_context_enumerated(&data[pos..], 1)?;
+    pos += consumed;
+
+    let property_array_index = match decode_context_unsigned(&data[pos..], 2) {
+        Ok((array_index, consumed)) => {
+            pos += consumed;
+            (array_index != BACNET_ARRAY_ALL).then_some(array_index)
+        }
+        Err(_) => None,
+    };
+
+    Ok((
+        object_identifier,
+        property_identifier,
+        property_array_index,
+        pos,
+    ))
+}
+
+/// Encode a property value as application-tagged data
+///
+/// Arrays and lists encode as the sequence of their elements, so nested
+/// structure is not preserved on the wire.
+pub fn encode_property_value(buffer: &mut Vec<u8>, value: &PropertyValue) -> EncodingResult<()> {
+    match value {
+        PropertyValue::Null => encode_application_tag(buffer, ApplicationTag::Null, 0),
+        PropertyValue::Boolean(value) => encode_boolean(buffer, *value),
+        PropertyValue::UnsignedInteger(value) => encode_unsigned(buffer, *value),
+        PropertyValue::SignedInt(value) => encode_signed(buffer, *value),
+        PropertyValue::Real(value) => encode_real(buffer, *value),
+        PropertyValue::Double(value) => encode_double(buffer, *value),
+        PropertyValue::OctetString(value) => encode_octet_string(buffer, value),
+        PropertyValue::CharacterString(value) => encode_character_string(buffer, value),
+        PropertyValue::BitString(bits) => encode_bit_string(buffer, bits),
+        PropertyValue::Enumerated(value) => encode_enumerated(buffer, *value),
+        PropertyValue::Date(date) => {
+            encode_date(buffer, date.year, date.month, date.day, date.weekday)
+        }
+        PropertyValue::Time(time) => {
+            encode_time(buffer, time.hour, time.minute, time.second, time.hundredths)
+        }
+        PropertyValue::ObjectIdentifier(id) => {
+            encode_object_identifier(buffer, u16::from(id.object_type), id.instance)
+        }
+        PropertyValue::Array(items) | PropertyValue::List(items) => items
+            .iter()
+            .try_for_each(|item| encode_property_value(buffer, item)),
+    }
+}
+
+/// Decode application-tagged data up to the end of `data` or the first
+/// context tag, returning the value and the number of bytes consumed
+///
+/// A single element decodes as that value; any other count decodes as an
+/// array of the elements.
+pub fn decode_property_value(data: &[u8]) -> EncodingResult<(PropertyValue, usize)> {
+    let mut items = Vec::new();
+    let mut pos = 0;
+    // Bit 3 of the tag octet marks a context tag, which ends the value
+    while pos < data.len() && data[pos] & 0x08 == 0 {
+        let (item, consumed) = decode_application_value(&data[pos..])?;
+        items.push(item);
+        pos += consumed;
+    }
+
+    let value = if items.len() == 1 {
+        items.remove(0)
+    } else {
+        PropertyValue::Array(items)
+    };
+    Ok((value, pos))
+}
+
+fn decode_application_value(data: &[u8]) -> EncodingResult<(PropertyValue, usize)> {
+    let (tag, _, tag_length) = decode_application_tag(data)?;
+    Ok(match tag {
+        ApplicationTag::Null => (PropertyValue::Null, tag_length),
+        ApplicationTag::Boolean => {
+            let (value, consumed) = decode_boolean(data)?;
+            (PropertyValue::Boolean(value), consumed)
+        }
+        ApplicationTag::UnsignedInt => {
+            let (value, consumed) = decode_unsigned(data)?;
+            (PropertyValue::UnsignedInteger(value), consumed)
+        }
+        ApplicationTag::SignedInt => {
+            let (value, consumed) = decode_signed(data)?;
+            (PropertyValue::SignedInt(value), consumed)
+        }
+        ApplicationTag::Real => {
+            let (value, consumed) = decode_real(data)?;
+            (PropertyValue::Real(value), consumed)
+        }
+        ApplicationTag::Double => {
+            let (value, consumed) = decode_double(data)?;
+            (PropertyValue::Double(value), consumed)
+        }
+        ApplicationTag::OctetString => {
+            let (value, consumed) = decode_octet_string(data)?;
+            (PropertyValue::OctetString(value), consumed)
+        }
+        ApplicationTag::CharacterString => {
+            let (value, consumed) = decode_character_string(data)?;
+            (PropertyValue::CharacterString(value), consumed)
+        }
+        ApplicationTag::BitString => {
+            let (bits, consumed) = decode_bit_string(data)?;
+            (PropertyValue::BitString(bits), consumed)
+        }
+        ApplicationTag::Enumerated => {
+            let (value, consumed) = decode_enumerated(data)?;
+            (PropertyValue::Enumerated(value), consumed)
+        }
+        ApplicationTag::Date => {
+            let ((year, month, day, weekday), consumed) = decode_date(data)?;
+            let date = Date {
+                year,
+                month,
+                day,
+                weekday,
+            };
+            (PropertyValue::Date(date), consumed)
+        }
+        ApplicationTag::Time => {
+            let ((hour, minute, second, hundredths), consumed) = decode_time(data)?;
+            let time = Time {
+                hour,
+                minute,
+                second,
+                hundredths,
+            };
+            (PropertyValue::Time(time), consumed)
+        }
+        ApplicationTag::ObjectIdentifier => {
+            let ((object_type, instance), consumed) = decode_object_identifier(data)?;
+            let object_type =
+                ObjectType::try_from(object_type).map_err(|_| EncodingError::ValueOutOfRange)?;
+            let id = ObjectIdentifier::new(object_type, instance);
+            (PropertyValue::ObjectIdentifier(id), consumed)
+        }
+        ApplicationTag::Reserved13 | ApplicationTag::Reserved14 | ApplicationTag::Reserved15 => {
+            return Err(EncodingError::InvalidTag)
+        }
+    })
+}
+
+/// Resolve a Read Property request against an object database
+#[cfg(feature = "std")]
+pub fn read_property(
+    database: &ObjectDatabase,
+    request: &ReadPropertyRequest,
+) -> core::result::Result<ReadPropertyAck, PropertyAccessError> {
+    let property = PropertyIdentifier::try_from(request.property_identifier)
+        .map_err(|_| PropertyAccessError::from(&ObjectError::UnknownProperty))?;
+    let value = match request.property_array_index {
+        Some(index) => database.get_property_at(request.object_identifier, property, index),
+        None => database.get_property(request.object_identifier, property),
+    }
+    .map_err(|error| PropertyAccessError::from(&error))?;
+
+    Ok(ReadPropertyAck {
+        object_identifier: request.object_identifier,
+        property_identifier: request.property_identifier,
+        property_array_index: request.property_array_index,
+        property_value: value,
+    })
+}
+
+/// Answer a Read Property request from an object database
+///
+/// Returns the ComplexAck carrying the value, an Error PDU if the property
+/// cannot be read, or a Reject PDU if the request cannot be decoded.
+#[cfg(feature = "std")]
+pub fn handle_read_property(database: &ObjectDatabase, invoke_id: u8, service_data: &[u8]) -> Apdu {
+    let service_choice = ConfirmedServiceChoice::ReadProperty as u8;
+    let request = match ReadPropertyRequest::decode(service_data) {
+        Ok(request) => request,
+        Err(error) => return Apdu::reject(invoke_id, RejectReason::for_decode_error(&error)),
+    };
+
+    let ack = match read_property(database, &request) {
+        Ok(ack) => ack,
+        Err(error) => return Apdu::error(invoke_id, service_choice, error),
+    };
+
+    let mut service_data = Vec::new();
+    match ack.encode(&mut service_data) {
+        Ok(()) => Apdu::ComplexAck {
+            segmented: false,
+            more_follows: false,
+            invoke_id,
+            sequence_number: None,
+            proposed_window_size: None,
+            service_choice,
+            service_data,
+        },
+        Err(error) => Apdu::abort(true, invoke_id, AbortReason::for_encode_error(&error)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_round_trip() {
+        let object_id = ObjectIdentifier::new(ObjectType::AnalogValue, 7);
+        let request = ReadPropertyRequest::with_array_index(object_id, 87, 3);
+        let mut buffer = Vec::new();
+        request.encode(&mut buffer).unwrap();
+        assert_eq!(ReadPropertyRequest::decode(&buffer).unwrap(), request);
+
+        // Trailing data is not a valid request
+        buffer.push(0x00);
+        assert!(ReadPropertyRequest::decode(&buffer).is_err());
+    }
+
+    #[test]
+    fn test_ack_round_trip() {
+        let object_id = ObjectIdentifier::new(ObjectType::Device, 1234);
+        let values = [
+            PropertyValue::Real(21.5),
+            PropertyValue::CharacterString(String::from("Boiler")),
+            PropertyValue::BitString(vec![false, true, false, false]),
+            PropertyValue::Array(vec![
+                PropertyValue::ObjectIdentifier(object_id),
+                PropertyValue::ObjectIdentifier(ObjectIdentifier::new(ObjectType::AnalogInput, 1)),
+            ]),
+            PropertyValue::Array(Vec::new()),
+        ];
+        for value in values {
+            let ack = ReadPropertyAck::new(object_id, 76, value);
+            let mut buffer = Vec::new();
+            ack.encode(&mut buffer).unwrap();
+            assert_eq!(ReadPropertyAck::decode(&buffer).unwrap(), ack);
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_handle_read_property() {
+        use crate::object::{analog::AnalogInput, Device};
+
+        let database = ObjectDatabase::new(Device::new(1234, String::from("Device")));
+        database
+            .add_object(Box::new(AnalogInput::new(1, String::from("AI-1"))))
+            .unwrap();
+        let ai = ObjectIdentifier::new(ObjectType::AnalogInput, 1);
+
+        let mut request = Vec::new();
+        ReadPropertyRequest::new(ai, u32::from(PropertyIdentifier::ObjectName))
+            .encode(&mut request)
+            .unwrap();
+        let Apdu::ComplexAck { service_data, .. } = handle_read_property(&database, 1, &request)
+        else {
+            panic!("Expected ComplexAck");
+        };
+        let ack = ReadPropertyAck::decode(&service_data).unwrap();
+        assert_eq!(
+            ack.property_value,
+            PropertyValue::CharacterString(String::from("AI-1"))
+        );
+
+        // Unknown object: error class object (1), code unknown-object (31)
+        let mut request = Vec::new();
+        ReadPropertyRequest::new(
+            ObjectIdentifier::new(ObjectType::AnalogInput, 99),
+            u32::from(PropertyIdentifier::PresentValue),
+        )
+        .encode(&mut request)
+        .unwrap();
+        assert!(matches!(
+            handle_read_property(&database, 2, &request),
+            Apdu::Error {
+                invoke_id: 2,
+                service_choice: 12,
+                error_class: 1,
+                error_code: 31,
+                ..
+            }
+        ));
+
+        // Undecodable request
+        assert!(matches!(
+            handle_read_property(&database, 3, &[0xFF]),
+            Apdu::Reject { invoke_id: 3, .. }
+        ));
+    }
+}
@@ -0,0 +1,638 @@
+//! ReadPropertyMultiple Service (Clause 15.7)
+//!
+//! A request lists objects and, for each, the properties to read; the special
+//! identifiers ALL, REQUIRED and OPTIONAL stand for groups of an object's
+//! properties. The acknowledgement answers every property in turn, carrying
+//! either its value or the error that prevented reading it, so one bad property
+//! does not fail the whole response. This module holds the codecs for both and
+//! [`handle_read_property_multiple`] which answers a request from an
+//! [`ObjectDatabase`](crate::object::database::ObjectDatabase).
+
+use super::read_property::{decode_property_value, encode_property_value};
+use super::{PropertyAccessError, BACNET_ARRAY_ALL};
+use crate::encoding::{
+    advanced::context::{encode_closing_tag, encode_opening_tag},
+    decode_context_enumerated, decode_context_object_id, decode_context_unsigned,
+    decode_enumerated, encode_context_enumerated, encode_context_object_id,
+    encode_context_unsigned, encode_enumerated, EncodingError, Result as EncodingResult,
+};
+use crate::object::{ObjectIdentifier, ObjectType, PropertyValue};
+
+#[cfg(feature = "std")]
+use super::{AbortReason, ConfirmedServiceChoice, RejectReason};
+#[cfg(feature = "std")]
+use crate::{app::Apdu, object::database::ObjectDatabase};
+
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+
+/// Read Property Multiple request (confirmed service)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReadPropertyMultipleRequest {
+    /// List of objects and properties to read
+    pub read_access_specifications: Vec<ReadAccessSpecification>,
+}
+
+/// An object and the properties to read from it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReadAccessSpecification {
+    /// Object identifier
+    pub object_identifier: ObjectIdentifier,
+    /// List of properties to read
+    pub property_references: Vec<PropertyReference>,
+}
+
+/// A property, and optionally one array element, to read
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PropertyReference {
+    /// Property identifier
+    pub property_identifier: u32,
+    /// Property array index (optional)
+    pub property_array_index: Option<u32>,
+}
+
+impl ReadPropertyMultipleRequest {
+    /// Create a new Read Property Multiple request
+    pub fn new(read_access_specifications: Vec<ReadAccessSpecification>) -> Self {
+        Self {
+            read_access_specifications,
+        }
+    }
+
+    /// Add a read access specification
+    pub fn add_specification(&mut self, spec: ReadAccessSpecification) {
+        self.read_access_specifications.push(spec);
+    }
+}
+
+impl ReadAccessSpecification {
+    /// Create a new read access specification
+    pub fn new(
+        object_identifier: ObjectIdentifier,
+        property_references: Vec<PropertyReference>,
+    ) -> Self {
+        Self {
+            object_identifier,
+            property_references,
+        }
+    }
+
+    /// Add a property reference
+    pub fn add_property(&mut self, property_reference: PropertyReference) {
+        self.property_references.push(property_reference);
+    }
+}
+
+impl PropertyReference {
+    /// Create a new property reference
+    pub fn new(property_identifier: u32) -> Self {
+        Self {
+            property_identifier,
+            property_array_index: None,
+        }
+    }
+
+    /// Create a new property reference with array index
+    pub fn with_array_index(property_identifier: u32, array_index: u32) -> Self {
+        Self {
+            property_identifier,
+            property_array_index: Some(array_index),
+        }
+    }
+}
+
+impl ReadAccessSpecification {
+    /// Encode the specification as a property value, as used by a Group's
+    /// List_Of_Group_Members
+    pub fn to_property_value(&self) -> PropertyValue {
+        PropertyValue::List(vec![
+            PropertyValue::ObjectIdentifier(self.object_identifier),
+            PropertyValue::List(
+                self.property_references
+                    .iter()
+                    .map(PropertyReference::to_property_value)
+                    .collect(),
+            ),
+        ])
+    }
+
+    /// Decode a specification encoded by `to_property_value`
+    pub fn from_property_value(value: &PropertyValue) -> Option<Self> {
+        let PropertyValue::List(items) = value else {
+            return None;
+        };
+        let [PropertyValue::ObjectIdentifier(object_identifier), PropertyValue::List(references)] =
+            items.as_slice()
+        else {
+            return None;
+        };
+        let property_references = references
+            .iter()
+            .map(PropertyReference::from_property_value)
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self::new(*object_identifier, property_references))
+    }
+}
+
+impl PropertyReference {
+    fn to_property_value(&self) -> PropertyValue {
+        let mut items = vec![PropertyValue::Enumerated(self.property_identifier)];
+        if let Some(index) = self.property_array_index {
+            items.push(PropertyValue::UnsignedInteger(index));
+        }
+        PropertyValue::List(items)
+    }
+
+    fn from_property_value(value: &PropertyValue) -> Option<Self> {
+        match value {
+            PropertyValue::List(items) => match items.as_slice() {
+                [PropertyValue::Enumerated(property)] => Some(Self::new(*property)),
+                [PropertyValue::Enumerated(property), PropertyValue::UnsignedInteger(index)] => {
+                    Some(Self::with_array_index(*property, *index))
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+/// Value or error for one property of a ReadAccessResult
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReadResult {
+    /// Property identifier
+    pub property_identifier: u32,
+    /// Property array index (optional)
+    pub property_array_index: Option<u32>,
+    /// The property value, or the error that prevented reading it
+    pub read_result: core::result::Result<PropertyValue, PropertyAccessError>,
+}
+
+/// Results of reading the properties named by a ReadAccessSpecification, as
+/// returned in a ReadPropertyMultiple acknowledgement
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReadAccessResult {
+    /// Object identifier
+    pub object_identifier: ObjectIdentifier,
+    /// One result per property read
+    pub list_of_results: Vec<ReadResult>,
+}
+
+impl ReadAccessResult {
+    /// Encode the result as a property value, as used by a Group's Present_Value
+    ///
+    /// Each property result is a list of the property identifier, its array
+    /// index (Null if absent) and either the value or a list of the error class
+    /// and code.
+    pub fn to_property_value(&self) -> PropertyValue {
+        let results = self
+            .list_of_results
+            .iter()
+            .map(|result| {
+                let outcome = match &result.read_result {
+                    Ok(value) => value.clone(),
+                    Err(error) => PropertyValue::List(vec![
+                        PropertyValue::Enumerated(error.error_class),
+                        PropertyValue::Enumerated(error.error_code),
+                    ]),
+                };
+                PropertyValue::List(vec![
+                    PropertyValue::Enumerated(result.property_identifier),
+                    result
+                        .property_array_index
+                        .map(PropertyValue::UnsignedInteger)
+                        .unwrap_or(PropertyValue::Null),
+                    outcome,
+                ])
+            })
+            .collect();
+        PropertyValue::List(vec![
+            PropertyValue::ObjectIdentifier(self.object_identifier),
+            PropertyValue::List(results),
+        ])
+    }
+}
+
+/// Read Property Multiple acknowledgement (ComplexAck service data)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReadPropertyMultipleAck {
+    /// One result per read access specification in the request
+    pub list_of_read_access_results: Vec<ReadAccessResult>,
+}
+
+impl ReadPropertyMultipleAck {
+    /// Create a new Read Property Multiple acknowledgement
+    pub fn new(list_of_read_access_results: Vec<ReadAccessResult>) -> Self {
+        Self {
+            list_of_read_access_results,
+        }
+    }
+
+    /// Encode the Read Property Multiple acknowledgement
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        self.list_of_read_access_results
+            .iter()
+            .try_for_each(|result| result.encode(buffer))
+    }
+
+    /// Decode a Read Property Multiple acknowledgement
+    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
+        let mut list_of_read_access_results = Vec::new();
+        let mut pos = 0;
+        while pos < data.len() {
+            let (result, consumed) = ReadAccessResult::decode(&data[pos..])?;
+            list_of_read_access_results.push(result);
+            pos += consumed;
+        }
+        Ok(Self::new(list_of_read_access_results))
+    }
+}
+
+impl ReadPropertyMultipleRequest {
+    /// Encode the Read Property Multiple request
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        self.read_access_specifications
+            .iter()
+            .try_for_each(|spec| spec.encode(buffer))
+    }
+
+    /// Decode a Read Property Multiple request
+    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
+        let mut read_access_specifications = Vec::new();
+        let mut pos = 0;
+        while pos < data.len() {
+            let (spec, consumed) = ReadAccessSpecification::decode(&data[pos..])?;
+            read_access_specifications.push(spec);
+            pos += consumed;
+        }
+        if read_access_specifications.is_empty() {
+            return Err(EncodingError::UnexpectedEndOfData);
+        }
+        Ok(Self::new(read_access_specifications))
+    }
+}
+
+impl ReadAccessSpecification {
+    /// Encode the specification
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        encode_object_identifier_tag(buffer, self.object_identifier)?;
+
+        // List of property references - context tag 1
+        encode_opening_tag(buffer, 1)?;
+        for reference in &self.property_references {
+            reference.encode(buffer, 0)?;
+        }
+        encode_closing_tag(buffer, 1)?;
+
+        Ok(())
+    }
+
+    /// Decode a specification, returning it with the number of bytes consumed
+    pub fn decode(data: &[u8]) -> EncodingResult<(Self, usize)> {
+        let (object_identifier, mut pos) = decode_object_identifier_tag(data)?;
+
+        // List of property references - context tag 1
+        expect_tag(data, pos, 0x1E)?;
+        pos += 1;
+        let mut property_references = Vec::new();
+        while data.get(pos).is_some_and(|&tag| tag != 0x1F) {
+            let (reference, consumed) = PropertyReference::decode(&data[pos..], 0)?;
+            property_references.push(reference);
+            pos += consumed;
+        }
+        expect_tag(data, pos, 0x1F)?;
+        pos += 1;
+
+        Ok((Self::new(object_identifier, property_references), pos))
+    }
+}
+
+impl PropertyReference {
+    /// Encode the property identifier and optional array index with context
+    /// tags `tag` and `tag + 1`
+    pub(super) fn encode(&self, buffer: &mut Vec<u8>, tag: u8) -> EncodingResult<()> {
+        buffer.extend_from_slice(&encode_context_enumerated(self.property_identifier, tag)?);
+        if let Some(array_index) = self.property_array_index {
+            buffer.extend_from_slice(&encode_context_unsigned(array_index, tag + 1)?);
+        }
+        Ok(())
+    }
+
+    pub(super) fn decode(data: &[u8], tag: u8) -> EncodingResult<(Self, usize)> {
+        let (property_identifier, mut pos) = decode_context_enumerated(data, tag)?;
+        let property_array_index = match decode_context_unsigned(&data[pos..], tag + 1) {
+            Ok((array_index, consumed)) => {
+                pos += consumed;
+                (array_index != BACNET_ARRAY_ALL).then_some(array_index)
+            }
+            Err(_) => None,
+        };
+        Ok((
+            Self {
+                property_identifier,
+                property_array_index,
+            },
+            pos,
+        ))
+    }
+}
+
+impl ReadAccessResult {
+    /// Encode the result, embedding each property's value or error
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        encode_object_identifier_tag(buffer, self.object_identifier)?;
+
+        // List of results - context tag 1
+        encode_opening_tag(buffer, 1)?;
+        for result in &self.list_of_results {
+            PropertyReference {
+                property_identifier: result.property_identifier,
+                property_array_index: result.property_array_index,
+            }
+            .encode(buffer, 2)?;
+            match &result.read_result {
+                Ok(value) => {
+                    encode_opening_tag(buffer, 4)?;
+                    encode_property_value(buffer, value)?;
+                    encode_closing_tag(buffer, 4)?;
+                }
+                Err(error) => {
+                    encode_opening_tag(buffer, 5)?;
+                    encode_enumerated(buffer, error.error_class)?;
+                    encode_enumerated(buffer, error.error_code)?;
+                    encode_closing_tag(buffer, 5)?;
+                }
+            }
+        }
+        encode_closing_tag(buffer, 1)?;
+
+        Ok(())
+    }
+
+    /// Decode a result, returning it with the number of bytes consumed
+    pub fn decode(data: &[u8]) -> EncodingResult<(Self, usize)> {
+        let (object_identifier, mut pos) = decode_object_identifier_tag(data)?;
+
+        // List of results - context tag 1
+        expect_tag(data, pos, 0x1E)?;
+        pos += 1;
+        let mut list_of_results = Vec::new();
+        while data.get(pos).is_some_and(|&tag| tag != 0x1F) {
+            let (reference, consumed) = PropertyReference::decode(&data[pos..], 2)?;
+            pos += consumed;
+
+            let read_result = match data.get(pos) {
+                // Property value - context tag 4
+                Some(0x4E) => {
+                    let (value, consumed) = decode_property_value(&data[pos + 1..])?;
+                    pos += 1 + consumed;
+                    expect_tag(data, pos, 0x4F)?;
+                    Ok(value)
+                }
+                // Property access error - context tag 5
+                Some(0x5E) => {
+                    pos += 1;
+                    let (error_class, consumed) = decode_enumerated(&data[pos..])?;
+                    pos += consumed;
+                    let (error_code, consumed) = decode_enumerated(&data[pos..])?;
+                    pos += consumed;
+                    expect_tag(data, pos, 0x5F)?;
+                    Err(PropertyAccessError {
+                        error_class,
+                        error_code,
+                    })
+                }
+                _ => return Err(EncodingError::InvalidTag),
+            };
+            pos += 1;
+
+            list_of_results.push(ReadResult {
+                property_identifier: reference.property_identifier,
+                property_array_index: reference.property_array_index,
+                read_result,
+            });
+        }
+        expect_tag(data, pos, 0x1F)?;
+        pos += 1;
+
+        Ok((
+            Self {
+                object_identifier,
+                list_of_results,
+            },
+            pos,
+        ))
+    }
+}
+
+fn encode_object_identifier_tag(
+    buffer: &mut Vec<u8>,
+    object_identifier: ObjectIdentifier,
+) -> EncodingResult<()> {
+    buffer.extend_from_slice(&encode_context_object_id(
+        u16::from(object_identifier.object_type),
+        object_identifier.instance,
+        0,
+    )?);
+    Ok(())
+}
+
+fn decode_object_identifier_tag(data: &[u8]) -> EncodingResult<(ObjectIdentifier, usize)> {
+    let ((object_type, instance), consumed) = decode_context_object_id(data, 0)?;
+    let object_type =
+        ObjectType::try_from(object_type).map_err(|_| EncodingError::ValueOutOfRange)?;
+    Ok((ObjectIdentifier::new(object_type, instance), consumed))
+}
+
+/// Check that the octet at `pos` is the opening or closing tag `tag`
+pub(super) fn expect_tag(data: &[u8], pos: usize, tag: u8) -> EncodingResult<()> {
+    match data.get(pos) {
+        Some(&found) if found == tag => Ok(()),
+        Some(_) => Err(EncodingError::InvalidTag),
+        None => Err(EncodingError::UnexpectedEndOfData),
+    }
+}
+
+/// Resolve a Read Property Multiple request against an object database
+///
+/// Every property gets a result; those that cannot be read carry their error.
+#[cfg(feature = "std")]
+pub fn read_property_multiple(
+    database: &ObjectDatabase,
+    request: &ReadPropertyMultipleRequest,
+) -> ReadPropertyMultipleAck {
+    ReadPropertyMultipleAck::new(
+        request
+            .read_access_specifications
+            .iter()
+            .map(|spec| database.read_access(spec))
+            .collect(),
+    )
+}
+
+/// Answer a Read Property Multiple request from an object database
+///
+/// Returns the ComplexAck carrying every result, or a Reject PDU if the
+/// request cannot be decoded.
+#[cfg(feature = "std")]
+pub fn handle_read_property_multiple(
+    database: &ObjectDatabase,
+    invoke_id: u8,
+    service_data: &[u8],
+) -> Apdu {
+    let request = match ReadPropertyMultipleRequest::decode(service_data) {
+        Ok(request) => request,
+        Err(error) => return Apdu::reject(invoke_id, RejectReason::for_decode_error(&error)),
+    };
+
+    let mut service_data = Vec::new();
+    match read_property_multiple(database, &request).encode(&mut service_data) {
+        Ok(()) => Apdu::ComplexAck {
+            segmented: false,
+            more_follows: false,
+            invoke_id,
+            sequence_number: None,
+            proposed_window_size: None,
+            service_choice: ConfirmedServiceChoice::ReadPropertyMultiple as u8,
+            service_data,
+        },
+        Err(error) => Apdu::abort(true, invoke_id, AbortReason::for_encode_error(&error)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::PropertyIdentifier;
+
+    #[test]
+    fn test_request_round_trip() {
+        let request = ReadPropertyMultipleRequest::new(vec![
+            ReadAccessSpecification::new(
+                ObjectIdentifier::new(ObjectType::AnalogInput, 1),
+                vec![
+                    PropertyReference::new(u32::from(PropertyIdentifier::PresentValue)),
+                    PropertyReference::with_array_index(
+                        u32::from(PropertyIdentifier::PriorityArray),
+                        0,
+                    ),
+                ],
+            ),
+            ReadAccessSpecification::new(
+                ObjectIdentifier::new(ObjectType::Device, 1234),
+                vec![PropertyReference::new(u32::from(PropertyIdentifier::All))],
+            ),
+        ]);
+        let mut buffer = Vec::new();
+        request.encode(&mut buffer).unwrap();
+        assert_eq!(
+            ReadPropertyMultipleRequest::decode(&buffer).unwrap(),
+            request
+        );
+        assert!(ReadPropertyMultipleRequest::decode(&[]).is_err());
+    }
+
+    #[test]
+    fn test_ack_round_trip() {
+        let ack = ReadPropertyMultipleAck::new(vec![ReadAccessResult {
+            object_identifier: ObjectIdentifier::new(ObjectType::AnalogValue, 3),
+            list_of_results: vec![
+                ReadResult {
+                    property_identifier: u32::from(PropertyIdentifier::PresentValue),
+                    property_array_index: None,
+                    read_result: Ok(PropertyValue::Real(12.5)),
+                },
+                ReadResult {
+                    property_identifier: u32::from(PropertyIdentifier::PriorityArray),
+                    property_array_index: Some(17),
+                    read_result: Err(PropertyAccessError {
+                        error_class: 2,
+                        error_code: 42,
+                    }),
+                },
+            ],
+        }]);
+        let mut buffer = Vec::new();
+        ack.encode(&mut buffer).unwrap();
+        assert_eq!(ReadPropertyMultipleAck::decode(&buffer).unwrap(), ack);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_handle_partial_errors() {
+        use crate::object::{analog::AnalogInput, Device};
+
+        let database = ObjectDatabase::new(Device::new(1234, String::from("Device")));
+        database
+            .add_object(Box::new(AnalogInput::new(1, String::from("AI-1"))))
+            .unwrap();
+        let ai = ObjectIdentifier::new(ObjectType::AnalogInput, 1);
+
+        let mut request = Vec::new();
+        ReadPropertyMultipleRequest::new(vec![
+            ReadAccessSpecification::new(
+                ai,
+                vec![
+                    PropertyReference::new(u32::from(PropertyIdentifier::ObjectName)),
+                    PropertyReference::new(u32::from(PropertyIdentifier::VendorName)),
+                ],
+            ),
+            ReadAccessSpecification::new(
+                ai,
+                vec![PropertyReference::new(u32::from(
+                    PropertyIdentifier::Required,
+                ))],
+            ),
+            ReadAccessSpecification::new(
+                ai,
+                vec![PropertyReference::new(u32::from(PropertyIdentifier::All))],
+            ),
+        ])
+        .encode(&mut request)
+        .unwrap();
+
+        let Apdu::ComplexAck { service_data, .. } =
+            handle_read_property_multiple(&database, 1, &request)
+        else {
+            panic!("Expected ComplexAck");
+        };
+        let ack = ReadPropertyMultipleAck::decode(&service_data).unwrap();
+        let [named, required, all] = ack.list_of_read_access_results.as_slice() else {
+            panic!("Expected three results");
+        };
+        assert_eq!(
+            named.list_of_results[0].read_result,
+            Ok(PropertyValue::CharacterString(String::from("AI-1")))
+        );
+        // The unknown property carries its error without failing the response
+        assert_eq!(
+            named.list_of_results[1].read_result,
+            Err(PropertyAccessError {
+                error_class: 2,
+                error_code: 32,
+            })
+        );
+
+        let required: Vec<u32> = required
+            .list_of_results
+            .iter()
+            .map(|result| result.property_identifier)
+            .collect();
+        assert!(required.contains(&u32::from(PropertyIdentifier::PresentValue)));
+        assert!(!required.contains(&u32::from(PropertyIdentifier::Description)));
+        assert!(!required.contains(&u32::from(PropertyIdentifier::PropertyList)));
+
+        // Property_List is readable but never part of an ALL expansion
+        let all: Vec<u32> = all
+            .list_of_results
+            .iter()
+            .map(|result| result.property_identifier)
+            .collect();
+        assert!(all.contains(&u32::from(PropertyIdentifier::PresentValue)));
+        assert!(!all.contains(&u32::from(PropertyIdentifier::PropertyList)));
+        assert!(matches!(
+            database.get_property(ai, PropertyIdentifier::PropertyList),
+            Ok(PropertyValue::Array(_))
+        ));
+    }
+}
@@ -0,0 +1,233 @@
+//! ReinitializeDevice Service (Clause 16.4)
+//!
+//! A client asks a device to restart, to activate pending configuration
+//! changes, or to move through a backup or restore procedure. The library
+//! checks the password and acknowledges the request; the restart itself, and
+//! saving or loading the configuration, are left to the host application,
+//! which supplies a callback that runs once the request is accepted.
+
+use super::communication_control::{check_password, decode_password, encode_password};
+use super::event_notification::Reader;
+use super::{ConfirmedServiceChoice, PropertyAccessError, RejectReason};
+use crate::app::Apdu;
+use crate::encoding::{encode_context_enumerated, EncodingError, Result as EncodingResult};
+
+#[cfg(feature = "std")]
+use crate::object::database::ObjectDatabase;
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, string::ToString, vec::Vec};
+
+/// Error class services (5), code service-request-denied (29)
+pub(crate) const SERVICE_REQUEST_DENIED: PropertyAccessError = PropertyAccessError {
+    error_class: 5,
+    error_code: 29,
+};
+
+/// State a ReinitializeDevice request moves the device to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u32)]
+pub enum ReinitializedState {
+    /// Restart as after power-up, resetting the configuration to its
+    /// initial state
+    Coldstart = 0,
+    /// Restart keeping the current configuration
+    Warmstart = 1,
+    /// Prepare for a backup
+    StartBackup = 2,
+    /// Finish a backup
+    EndBackup = 3,
+    /// Prepare for a restore
+    StartRestore = 4,
+    /// Finish a restore and use the restored configuration
+    EndRestore = 5,
+    /// Abandon a restore
+    AbortRestore = 6,
+    /// Apply pending configuration changes without restarting
+    ActivateChanges = 7,
+}
+
+impl TryFrom<u32> for ReinitializedState {
+    type Error = EncodingError;
+
+    fn try_from(value: u32) -> EncodingResult<Self> {
+        match value {
+            0 => Ok(Self::Coldstart),
+            1 => Ok(Self::Warmstart),
+            2 => Ok(Self::StartBackup),
+            3 => Ok(Self::EndBackup),
+            4 => Ok(Self::StartRestore),
+            5 => Ok(Self::EndRestore),
+            6 => Ok(Self::AbortRestore),
+            7 => Ok(Self::ActivateChanges),
+            _ => Err(EncodingError::ValueOutOfRange),
+        }
+    }
+}
+
+/// Reinitialize Device request (confirmed service)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReinitializeDeviceRequest {
+    /// Requested state
+    pub reinitialized_state: ReinitializedState,
+    /// Password (optional, at most 20 characters)
+    pub password: Option<String>,
+}
+
+impl ReinitializeDeviceRequest {
+    /// Create a new request without a password
+    pub fn new(reinitialized_state: ReinitializedState) -> Self {
+        Self {
+            reinitialized_state,
+            password: None,
+        }
+    }
+
+    /// Add the device's password
+    pub fn with_password(mut self, password: impl Into<String>) -> Self {
+        self.password = Some(password.into());
+        self
+    }
+
+    /// Encode the request
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        buffer.extend_from_slice(&encode_context_enumerated(
+            self.reinitialized_state as u32,
+            0,
+        )?);
+        if let Some(password) = &self.password {
+            encode_password(buffer, password, 1)?;
+        }
+        Ok(())
+    }
+
+    /// Decode a request
+    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
+        let mut reader = Reader::new(data);
+        let reinitialized_state = ReinitializedState::try_from(reader.enumerated(0)?)?;
+        let password = decode_password(&mut reader, 1)?;
+
+        if !reader.rest().is_empty() {
+            return Err(EncodingError::InvalidFormat(
+                "Unexpected data after Reinitialize Device request".to_string(),
+            ));
+        }
+        Ok(Self {
+            reinitialized_state,
+            password,
+        })
+    }
+}
+
+/// Check a Reinitialize Device request's password, then pass its state to
+/// the host's `reinitialize` callback
+///
+/// The callback returns an error, such as service-request-denied, if it
+/// cannot act on the state.
+pub fn reinitialize_device(
+    request: &ReinitializeDeviceRequest,
+    device_password: Option<&str>,
+    reinitialize: impl FnOnce(ReinitializedState) -> Result<(), PropertyAccessError>,
+) -> Result<(), PropertyAccessError> {
+    check_password(device_password, request.password.as_deref())?;
+    reinitialize(request.reinitialized_state)
+}
+
+/// Apply a Reinitialize Device request to an object database
+///
+/// ACTIVATE_CHANGES activates the pending changes of every object before the
+/// callback runs; every other state is left to the callback.
+#[cfg(feature = "std")]
+pub fn reinitialize_database(
+    database: &ObjectDatabase,
+    request: &ReinitializeDeviceRequest,
+    device_password: Option<&str>,
+    reinitialize: impl FnOnce(ReinitializedState) -> Result<(), PropertyAccessError>,
+) -> Result<(), PropertyAccessError> {
+    reinitialize_device(request, device_password, |state| {
+        if state == ReinitializedState::ActivateChanges {
+            database.activate_changes();
+        }
+        reinitialize(state)
+    })
+}
+
+/// Answer a Reinitialize Device request
+///
+/// Returns a SimpleAck once the callback accepts the state, an Error PDU if
+/// the password is wrong or the callback refuses, or a Reject PDU if the
+/// request cannot be decoded.
+pub fn handle_reinitialize_device(
+    device_password: Option<&str>,
+    invoke_id: u8,
+    service_data: &[u8],
+    reinitialize: impl FnOnce(ReinitializedState) -> Result<(), PropertyAccessError>,
+) -> Apdu {
+    let service_choice = ConfirmedServiceChoice::ReinitializeDevice as u8;
+    let request = match ReinitializeDeviceRequest::decode(service_data) {
+        Ok(request) => request,
+        Err(error) => return Apdu::reject(invoke_id, RejectReason::for_decode_error(&error)),
+    };
+
+    match reinitialize_device(&request, device_password, reinitialize) {
+        Ok(()) => Apdu::SimpleAck {
+            invoke_id,
+            service_choice,
+        },
+        Err(error) => Apdu::error(invoke_id, service_choice, error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::service::communication_control::PASSWORD_FAILURE;
+
+    #[test]
+    fn test_request_codec() {
+        let request =
+            ReinitializeDeviceRequest::new(ReinitializedState::Warmstart).with_password("admin");
+        let mut buffer = Vec::new();
+        request.encode(&mut buffer).unwrap();
+        assert_eq!(
+            buffer,
+            [0x09, 0x01, 0x1D, 0x06, 0x00, b'a', b'd', b'm', b'i', b'n']
+        );
+        assert_eq!(ReinitializeDeviceRequest::decode(&buffer).unwrap(), request);
+
+        assert!(ReinitializeDeviceRequest::decode(&[0x09, 0x08]).is_err());
+    }
+
+    #[test]
+    fn test_handle_reinitialize_device() {
+        let mut service_data = Vec::new();
+        ReinitializeDeviceRequest::new(ReinitializedState::Coldstart)
+            .with_password("admin")
+            .encode(&mut service_data)
+            .unwrap();
+
+        let mut restarted = None;
+        let reply = handle_reinitialize_device(Some("admin"), 3, &service_data, |state| {
+            restarted = Some(state);
+            Ok(())
+        });
+        assert!(matches!(
+            reply,
+            Apdu::SimpleAck {
+                invoke_id: 3,
+                service_choice: 20,
+            }
+        ));
+        assert_eq!(restarted, Some(ReinitializedState::Coldstart));
+
+        let request = ReinitializeDeviceRequest::new(ReinitializedState::StartBackup);
+        assert_eq!(
+            reinitialize_device(&request, Some("admin"), |_| Ok(())),
+            Err(PASSWORD_FAILURE)
+        );
+        assert_eq!(
+            reinitialize_device(&request, None, |_| Err(SERVICE_REQUEST_DENIED)),
+            Err(SERVICE_REQUEST_DENIED)
+        );
+    }
+}
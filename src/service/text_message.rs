@@ -0,0 +1,315 @@
+//! ConfirmedTextMessage and UnconfirmedTextMessage Services (Clauses 16.5 and 16.6)
+//!
+//! Both services carry a line of text from a device or operator to another
+//! device, usually a workstation, with a priority and an optional class that
+//! the receiver can use to decide how to present it. [`TextMessageReceiver`]
+//! passes received messages to a callback and acknowledges the confirmed
+//! ones.
+
+use super::event_notification::{encode_context_value, encode_identifier, Reader};
+use super::{ConfirmedServiceChoice, RejectReason, UnconfirmedServiceChoice};
+use crate::app::{Apdu, MaxApduSize, MaxSegments};
+use crate::encoding::{
+    advanced::context::{encode_closing_tag, encode_opening_tag},
+    encode_context_enumerated, encode_context_unsigned, ApplicationTag, EncodingError,
+    Result as EncodingResult,
+};
+use crate::object::{ObjectIdentifier, PropertyValue};
+
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, string::String, string::ToString, vec::Vec};
+
+/// Class of a text message, chosen by the sender
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MessageClass {
+    /// Numbered class
+    Numeric(u32),
+    /// Named class
+    Character(String),
+}
+
+/// Priority of a text message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[repr(u32)]
+pub enum MessagePriority {
+    /// Normal message
+    #[default]
+    Normal = 0,
+    /// Message to present at once
+    Urgent = 1,
+}
+
+impl TryFrom<u32> for MessagePriority {
+    type Error = EncodingError;
+
+    fn try_from(value: u32) -> EncodingResult<Self> {
+        match value {
+            0 => Ok(Self::Normal),
+            1 => Ok(Self::Urgent),
+            _ => Err(EncodingError::ValueOutOfRange),
+        }
+    }
+}
+
+/// Text Message request (confirmed or unconfirmed service)
+///
+/// Both services carry the same parameters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextMessageRequest {
+    /// Device sending the message
+    pub text_message_source_device: ObjectIdentifier,
+    /// Class of the message (optional)
+    pub message_class: Option<MessageClass>,
+    /// Priority of the message
+    pub message_priority: MessagePriority,
+    /// The text
+    pub message: String,
+}
+
+impl TextMessageRequest {
+    /// Create a new normal-priority message without a class
+    pub fn new(text_message_source_device: ObjectIdentifier, message: impl Into<String>) -> Self {
+        Self {
+            text_message_source_device,
+            message_class: None,
+            message_priority: MessagePriority::Normal,
+            message: message.into(),
+        }
+    }
+
+    /// Set the class of the message
+    pub fn with_class(mut self, message_class: MessageClass) -> Self {
+        self.message_class = Some(message_class);
+        self
+    }
+
+    /// Set the priority of the message
+    pub fn with_priority(mut self, message_priority: MessagePriority) -> Self {
+        self.message_priority = message_priority;
+        self
+    }
+
+    /// Encode the request
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        encode_identifier(buffer, &self.text_message_source_device, 0)?;
+
+        // Message class - context tag 1 (optional)
+        if let Some(message_class) = &self.message_class {
+            encode_opening_tag(buffer, 1)?;
+            match message_class {
+                MessageClass::Numeric(class) => {
+                    buffer.extend_from_slice(&encode_context_unsigned(*class, 0)?);
+                }
+                MessageClass::Character(class) => {
+                    encode_context_value(
+                        buffer,
+                        &PropertyValue::CharacterString(class.clone()),
+                        1,
+                    )?;
+                }
+            }
+            encode_closing_tag(buffer, 1)?;
+        }
+
+        buffer.extend_from_slice(&encode_context_enumerated(self.message_priority as u32, 2)?);
+        encode_context_value(
+            buffer,
+            &PropertyValue::CharacterString(self.message.clone()),
+            3,
+        )
+    }
+
+    /// Decode a request
+    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
+        let mut reader = Reader::new(data);
+        let text_message_source_device = reader.identifier(0)?;
+
+        let message_class = if reader.is_context(1) {
+            reader.open(1)?;
+            let message_class = if reader.is_context(0) {
+                MessageClass::Numeric(reader.unsigned(0)?)
+            } else {
+                MessageClass::Character(character_string(&mut reader, 1)?)
+            };
+            reader.close(1)?;
+            Some(message_class)
+        } else {
+            None
+        };
+
+        let message_priority = MessagePriority::try_from(reader.enumerated(2)?)?;
+        let message = character_string(&mut reader, 3)?;
+
+        if !reader.rest().is_empty() {
+            return Err(EncodingError::InvalidFormat(
+                "Unexpected data after Text Message request".to_string(),
+            ));
+        }
+        Ok(Self {
+            text_message_source_device,
+            message_class,
+            message_priority,
+            message,
+        })
+    }
+
+    /// Build the text message APDU
+    ///
+    /// `invoke_id` is used only for a ConfirmedTextMessage.
+    pub fn to_apdu(&self, confirmed: bool, invoke_id: u8) -> EncodingResult<Apdu> {
+        let mut service_data = Vec::new();
+        self.encode(&mut service_data)?;
+        Ok(if confirmed {
+            Apdu::ConfirmedRequest {
+                segmented: false,
+                more_follows: false,
+                segmented_response_accepted: false,
+                max_segments: MaxSegments::Unspecified,
+                max_response_size: MaxApduSize::Up1476,
+                invoke_id,
+                sequence_number: None,
+                proposed_window_size: None,
+                service_choice: ConfirmedServiceChoice::ConfirmedTextMessage,
+                service_data,
+            }
+        } else {
+            Apdu::UnconfirmedRequest {
+                service_choice: UnconfirmedServiceChoice::UnconfirmedTextMessage,
+                service_data,
+            }
+        })
+    }
+}
+
+fn character_string(reader: &mut Reader<'_>, tag_number: u8) -> EncodingResult<String> {
+    match reader.value(tag_number, ApplicationTag::CharacterString)? {
+        PropertyValue::CharacterString(text) => Ok(text),
+        _ => Err(EncodingError::InvalidTag),
+    }
+}
+
+/// Callback invoked with each received text message and whether it came
+/// confirmed
+pub type TextMessageCallback = Box<dyn FnMut(&TextMessageRequest, bool) + Send>;
+
+/// Delivers received text messages to a callback
+pub struct TextMessageReceiver {
+    callback: TextMessageCallback,
+}
+
+impl TextMessageReceiver {
+    /// Create a receiver that passes every message to `callback`
+    pub fn new<F>(callback: F) -> Self
+    where
+        F: FnMut(&TextMessageRequest, bool) + Send + 'static,
+    {
+        Self {
+            callback: Box::new(callback),
+        }
+    }
+
+    /// Handle a received APDU
+    ///
+    /// Text messages are delivered; other APDUs are ignored. Returns the
+    /// reply to send: a SimpleAck for a confirmed message, or a Reject if it
+    /// cannot be decoded.
+    pub fn handle_apdu(&mut self, apdu: &Apdu) -> Option<Apdu> {
+        match apdu {
+            Apdu::UnconfirmedRequest {
+                service_choice: UnconfirmedServiceChoice::UnconfirmedTextMessage,
+                service_data,
+            } => {
+                if let Ok(message) = TextMessageRequest::decode(service_data) {
+                    (self.callback)(&message, false);
+                }
+                None
+            }
+            Apdu::ConfirmedRequest {
+                invoke_id,
+                service_choice: ConfirmedServiceChoice::ConfirmedTextMessage,
+                service_data,
+                ..
+            } => Some(match TextMessageRequest::decode(service_data) {
+                Ok(message) => {
+                    (self.callback)(&message, true);
+                    Apdu::SimpleAck {
+                        invoke_id: *invoke_id,
+                        service_choice: ConfirmedServiceChoice::ConfirmedTextMessage as u8,
+                    }
+                }
+                Err(error) => Apdu::reject(*invoke_id, RejectReason::for_decode_error(&error)),
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl core::fmt::Debug for TextMessageReceiver {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("TextMessageReceiver")
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::ObjectType;
+
+    #[test]
+    fn test_request_codec() {
+        let source = ObjectIdentifier::new(ObjectType::Device, 5);
+        let request = TextMessageRequest::new(source, "Hi")
+            .with_class(MessageClass::Numeric(3))
+            .with_priority(MessagePriority::Urgent);
+        let mut buffer = Vec::new();
+        request.encode(&mut buffer).unwrap();
+        assert_eq!(
+            buffer,
+            [
+                0x0C, 0x02, 0x00, 0x00, 0x05, 0x1E, 0x09, 0x03, 0x1F, 0x29, 0x01, 0x3B, 0x00, b'H',
+                b'i'
+            ]
+        );
+        assert_eq!(TextMessageRequest::decode(&buffer).unwrap(), request);
+
+        let request = TextMessageRequest::new(source, "Filter change due")
+            .with_class(MessageClass::Character(String::from("maintenance")));
+        let mut buffer = Vec::new();
+        request.encode(&mut buffer).unwrap();
+        assert_eq!(TextMessageRequest::decode(&buffer).unwrap(), request);
+    }
+
+    #[test]
+    fn test_receiver_delivers_messages() {
+        use std::sync::{Arc, Mutex};
+
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let log = Arc::clone(&received);
+        let mut receiver = TextMessageReceiver::new(move |message, confirmed| {
+            log.lock()
+                .unwrap()
+                .push((message.message.clone(), confirmed));
+        });
+        let request =
+            TextMessageRequest::new(ObjectIdentifier::new(ObjectType::Device, 5), "Call me");
+
+        assert!(receiver
+            .handle_apdu(&request.to_apdu(false, 0).unwrap())
+            .is_none());
+        assert!(matches!(
+            receiver.handle_apdu(&request.to_apdu(true, 4).unwrap()),
+            Some(Apdu::SimpleAck {
+                invoke_id: 4,
+                service_choice: 19,
+            })
+        ));
+        assert_eq!(
+            *received.lock().unwrap(),
+            [
+                (String::from("Call me"), false),
+                (String::from("Call me"), true)
+            ]
+        );
+    }
+}
@@ -0,0 +1,545 @@
+//! TimeSynchronization and UTCTimeSynchronization Services (Clauses 16.7 and 16.8)
+//!
+//! A time master sends its local or UTC date and time to other devices,
+//! which set their clocks from it. Devices take part through the [`Clock`]
+//! trait: [`handle_time_synchronization`] and
+//! [`handle_utc_time_synchronization`] pass a received time to the host's
+//! clock, and [`TimeMaster`] reads the clock to send the time to its
+//! recipients on an interval. [`SystemClock`] keeps a correction on top of
+//! the system clock for hosts that cannot set the system time themselves.
+
+use super::BacnetDateTime;
+use crate::app::Apdu;
+use crate::encoding::{EncodingError, Result as EncodingResult};
+use crate::object::Recipient;
+use core::time::Duration;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+/// Source and sink of a device's date and time
+pub trait Clock {
+    /// Current local date and time
+    fn local_date_time(&self) -> BacnetDateTime;
+
+    /// Current UTC date and time
+    fn utc_date_time(&self) -> BacnetDateTime;
+
+    /// Set the clock from a local date and time, as TimeSynchronization does
+    fn set_local_date_time(&mut self, date_time: BacnetDateTime);
+
+    /// Set the clock from a UTC date and time, as UTCTimeSynchronization does
+    fn set_utc_date_time(&mut self, utc_date_time: BacnetDateTime);
+}
+
+/// Time Synchronization request (unconfirmed service)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeSynchronizationRequest {
+    /// Date and time to synchronize to
+    pub date_time: BacnetDateTime,
+}
+
+/// UTC Time Synchronization request (unconfirmed service)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UtcTimeSynchronizationRequest {
+    /// UTC date and time to synchronize to
+    pub utc_date_time: BacnetDateTime,
+}
+
+impl TimeSynchronizationRequest {
+    /// Create a new Time Synchronization request
+    pub fn new(date_time: BacnetDateTime) -> Self {
+        Self { date_time }
+    }
+
+    /// Create Time Synchronization request with current time
+    #[cfg(feature = "std")]
+    pub fn now() -> Self {
+        Self::new(BacnetDateTime::now())
+    }
+
+    /// Encode the Time Synchronization request
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        self.date_time.encode(buffer)
+    }
+
+    /// Decode a Time Synchronization request
+    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
+        let (date_time, _consumed) = BacnetDateTime::decode(data)?;
+        Ok(Self::new(date_time))
+    }
+}
+
+impl UtcTimeSynchronizationRequest {
+    /// Create a new UTC Time Synchronization request
+    pub fn new(utc_date_time: BacnetDateTime) -> Self {
+        Self { utc_date_time }
+    }
+
+    /// Create UTC Time Synchronization request with current UTC time
+    #[cfg(feature = "std")]
+    pub fn now() -> Self {
+        use chrono::{Datelike, Timelike, Utc};
+
+        let now = Utc::now();
+        let year = now.year() as u16;
+        let month = now.month() as u8;
+        let day = now.day() as u8;
+        let weekday = now.weekday().number_from_monday() as u8;
+        let hour = now.hour() as u8;
+        let minute = now.minute() as u8;
+        let second = now.second() as u8;
+        let hundredths = (now.nanosecond() / 10_000_000) as u8;
+
+        let date = crate::object::Date {
+            year,
+            month,
+            day,
+            weekday,
+        };
+        let time = crate::object::Time {
+            hour,
+            minute,
+            second,
+            hundredths,
+        };
+        let utc_date_time = BacnetDateTime::new(date, time);
+        Self::new(utc_date_time)
+    }
+
+    /// Encode the UTC Time Synchronization request
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        self.utc_date_time.encode(buffer)
+    }
+
+    /// Decode a UTC Time Synchronization request
+    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
+        let (utc_date_time, _consumed) = BacnetDateTime::decode(data)?;
+        Ok(Self::new(utc_date_time))
+    }
+}
+
+/// Whether every field of a date and time is given, so a clock can be set
+/// from it
+fn is_specific(date_time: &BacnetDateTime) -> bool {
+    let date = &date_time.date;
+    let time = &date_time.time;
+    (1..=12).contains(&date.month)
+        && (1..=31).contains(&date.day)
+        && date.year != 255
+        && time.hour < 24
+        && time.minute < 60
+        && time.second < 60
+        && time.hundredths < 100
+}
+
+/// Set `clock` from a received Time Synchronization request
+///
+/// A time with unspecified fields is refused and leaves the clock alone.
+pub fn handle_time_synchronization<C: Clock + ?Sized>(
+    clock: &mut C,
+    service_data: &[u8],
+) -> EncodingResult<()> {
+    let request = TimeSynchronizationRequest::decode(service_data)?;
+    if !is_specific(&request.date_time) {
+        return Err(EncodingError::ValueOutOfRange);
+    }
+    clock.set_local_date_time(request.date_time);
+    Ok(())
+}
+
+/// Set `clock` from a received UTC Time Synchronization request
+///
+/// A time with unspecified fields is refused and leaves the clock alone.
+pub fn handle_utc_time_synchronization<C: Clock + ?Sized>(
+    clock: &mut C,
+    service_data: &[u8],
+) -> EncodingResult<()> {
+    let request = UtcTimeSynchronizationRequest::decode(service_data)?;
+    if !is_specific(&request.utc_date_time) {
+        return Err(EncodingError::ValueOutOfRange);
+    }
+    clock.set_utc_date_time(request.utc_date_time);
+    Ok(())
+}
+
+/// The system clock with a correction set by time synchronization
+///
+/// Setting the clock records how far the received time is from the system
+/// time; reading it applies that difference. The system time itself is never
+/// changed.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock {
+    offset: chrono::Duration,
+}
+
+#[cfg(feature = "std")]
+impl SystemClock {
+    /// Create a clock that follows the system time
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Difference between this clock and the system time
+    pub fn offset(&self) -> chrono::Duration {
+        self.offset
+    }
+}
+
+#[cfg(feature = "std")]
+impl Clock for SystemClock {
+    fn local_date_time(&self) -> BacnetDateTime {
+        from_naive(chrono::Local::now().naive_local() + self.offset)
+    }
+
+    fn utc_date_time(&self) -> BacnetDateTime {
+        from_naive(chrono::Utc::now().naive_utc() + self.offset)
+    }
+
+    // A time that is not a real calendar date, such as 30 February, is ignored
+    fn set_local_date_time(&mut self, date_time: BacnetDateTime) {
+        if let Some(target) = to_naive(&date_time) {
+            self.offset = target - chrono::Local::now().naive_local();
+        }
+    }
+
+    fn set_utc_date_time(&mut self, utc_date_time: BacnetDateTime) {
+        if let Some(target) = to_naive(&utc_date_time) {
+            self.offset = target - chrono::Utc::now().naive_utc();
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+fn from_naive(date_time: chrono::NaiveDateTime) -> BacnetDateTime {
+    use chrono::{Datelike, Timelike};
+
+    BacnetDateTime::new(
+        crate::object::Date {
+            year: date_time.year() as u16,
+            month: date_time.month() as u8,
+            day: date_time.day() as u8,
+            weekday: date_time.weekday().number_from_monday() as u8,
+        },
+        crate::object::Time {
+            hour: date_time.hour() as u8,
+            minute: date_time.minute() as u8,
+            second: date_time.second() as u8,
+            hundredths: (date_time.nanosecond() / 10_000_000).min(99) as u8,
+        },
+    )
+}
+
+#[cfg(feature = "std")]
+fn to_naive(date_time: &BacnetDateTime) -> Option<chrono::NaiveDateTime> {
+    let date = &date_time.date;
+    let time = &date_time.time;
+    chrono::NaiveDate::from_ymd_opt(
+        i32::from(date.year),
+        u32::from(date.month),
+        u32::from(date.day),
+    )?
+    .and_hms_milli_opt(
+        u32::from(time.hour),
+        u32::from(time.minute),
+        u32::from(time.second),
+        u32::from(time.hundredths) * 10,
+    )
+}
+
+/// A time synchronization due to one recipient of a [`TimeMaster`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingTimeSynchronization {
+    /// Who receives the time
+    pub recipient: Recipient,
+    /// Send UTCTimeSynchronization rather than TimeSynchronization
+    pub utc: bool,
+    /// The time to send
+    pub date_time: BacnetDateTime,
+}
+
+impl PendingTimeSynchronization {
+    /// Build the time synchronization APDU to send to the recipient
+    pub fn to_apdu(&self) -> EncodingResult<Apdu> {
+        use super::UnconfirmedServiceChoice;
+
+        let mut service_data = Vec::new();
+        let service_choice = if self.utc {
+            UtcTimeSynchronizationRequest::new(self.date_time).encode(&mut service_data)?;
+            UnconfirmedServiceChoice::UtcTimeSynchronization
+        } else {
+            TimeSynchronizationRequest::new(self.date_time).encode(&mut service_data)?;
+            UnconfirmedServiceChoice::TimeSynchronization
+        };
+        Ok(Apdu::UnconfirmedRequest {
+            service_choice,
+            service_data,
+        })
+    }
+}
+
+/// Sends the time to the Time_Synchronization_Recipients and
+/// UTC_Time_Synchronization_Recipients of a device on an interval
+///
+/// A recipient given by address with network 0xFFFF and no MAC address is a
+/// global broadcast.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeMaster {
+    /// Recipients of TimeSynchronization
+    pub time_synchronization_recipients: Vec<Recipient>,
+    /// Recipients of UTCTimeSynchronization
+    pub utc_time_synchronization_recipients: Vec<Recipient>,
+    /// Time between synchronizations; zero disables periodic synchronization
+    pub interval: Duration,
+    remaining: Duration,
+}
+
+impl TimeMaster {
+    /// Create a time master with no recipients that synchronizes every
+    /// `interval`
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            time_synchronization_recipients: Vec::new(),
+            utc_time_synchronization_recipients: Vec::new(),
+            interval,
+            remaining: interval,
+        }
+    }
+
+    /// The time synchronizations due now, read from `clock`
+    pub fn synchronize<C: Clock + ?Sized>(&self, clock: &C) -> Vec<PendingTimeSynchronization> {
+        let to = |recipients: &[Recipient], utc: bool, date_time: BacnetDateTime| {
+            recipients
+                .iter()
+                .map(move |recipient| PendingTimeSynchronization {
+                    recipient: recipient.clone(),
+                    utc,
+                    date_time,
+                })
+                .collect::<Vec<_>>()
+        };
+        let mut pending = Vec::new();
+        if !self.time_synchronization_recipients.is_empty() {
+            pending.extend(to(
+                &self.time_synchronization_recipients,
+                false,
+                clock.local_date_time(),
+            ));
+        }
+        if !self.utc_time_synchronization_recipients.is_empty() {
+            pending.extend(to(
+                &self.utc_time_synchronization_recipients,
+                true,
+                clock.utc_date_time(),
+            ));
+        }
+        pending
+    }
+
+    /// Count down the interval by `elapsed`, returning the time
+    /// synchronizations to send if it has run out
+    pub fn advance_time<C: Clock + ?Sized>(
+        &mut self,
+        elapsed: Duration,
+        clock: &C,
+    ) -> Vec<PendingTimeSynchronization> {
+        if self.interval.is_zero() {
+            return Vec::new();
+        }
+        match self.remaining.checked_sub(elapsed) {
+            Some(remaining) if !remaining.is_zero() => {
+                self.remaining = remaining;
+                Vec::new()
+            }
+            _ => {
+                self.remaining = self.interval;
+                self.synchronize(clock)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_time_synchronization_request() {
+        let date = crate::object::Date {
+            year: 2024,
+            month: 6,
+            day: 20,
+            weekday: 4,
+        };
+        let time = crate::object::Time {
+            hour: 10,
+            minute: 15,
+            second: 30,
+            hundredths: 25,
+        };
+        let datetime = BacnetDateTime::new(date, time);
+        let time_sync = TimeSynchronizationRequest::new(datetime);
+
+        assert_eq!(time_sync.date_time, datetime);
+
+        // Test encoding/decoding
+        let mut buffer = Vec::new();
+        time_sync.encode(&mut buffer).unwrap();
+        assert_eq!(buffer.len(), 10);
+
+        let decoded = TimeSynchronizationRequest::decode(&buffer).unwrap();
+        assert_eq!(decoded.date_time, datetime);
+    }
+
+    #[test]
+    fn test_utc_time_synchronization_request() {
+        let date = crate::object::Date {
+            year: 2024,
+            month: 6,
+            day: 20,
+            weekday: 4,
+        };
+        let time = crate::object::Time {
+            hour: 18,
+            minute: 45,
+            second: 15,
+            hundredths: 75,
+        };
+        let utc_datetime = BacnetDateTime::new(date, time);
+        let utc_sync = UtcTimeSynchronizationRequest::new(utc_datetime);
+
+        assert_eq!(utc_sync.utc_date_time, utc_datetime);
+
+        // Test encoding/decoding
+        let mut buffer = Vec::new();
+        utc_sync.encode(&mut buffer).unwrap();
+        assert_eq!(buffer.len(), 10);
+
+        let decoded = UtcTimeSynchronizationRequest::decode(&buffer).unwrap();
+        assert_eq!(decoded.utc_date_time, utc_datetime);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_time_synchronization_now() {
+        // Test creating time sync with current time
+        let now_sync = TimeSynchronizationRequest::now();
+        assert!(!now_sync.date_time.is_unspecified());
+
+        let utc_now_sync = UtcTimeSynchronizationRequest::now();
+        assert!(!utc_now_sync.utc_date_time.is_unspecified());
+
+        // The current time should be reasonable
+        assert!(now_sync.date_time.date.year >= 2024);
+        assert!(now_sync.date_time.date.month >= 1 && now_sync.date_time.date.month <= 12);
+        assert!(now_sync.date_time.date.day >= 1 && now_sync.date_time.date.day <= 31);
+        assert!(now_sync.date_time.time.hour <= 23);
+        assert!(now_sync.date_time.time.minute <= 59);
+        assert!(now_sync.date_time.time.second <= 59);
+        assert!(now_sync.date_time.time.hundredths <= 99);
+    }
+
+    /// A clock that stands still at the time it was last set
+    struct FixedClock {
+        local: BacnetDateTime,
+        utc: BacnetDateTime,
+    }
+
+    impl Clock for FixedClock {
+        fn local_date_time(&self) -> BacnetDateTime {
+            self.local
+        }
+
+        fn utc_date_time(&self) -> BacnetDateTime {
+            self.utc
+        }
+
+        fn set_local_date_time(&mut self, date_time: BacnetDateTime) {
+            self.local = date_time;
+        }
+
+        fn set_utc_date_time(&mut self, utc_date_time: BacnetDateTime) {
+            self.utc = utc_date_time;
+        }
+    }
+
+    #[test]
+    fn test_handlers_and_time_master() {
+        use crate::object::{ObjectIdentifier, ObjectType};
+        use crate::service::UnconfirmedServiceChoice;
+
+        let mut clock = FixedClock {
+            local: BacnetDateTime::unspecified(),
+            utc: BacnetDateTime::unspecified(),
+        };
+        let date_time = BacnetDateTime::new(
+            crate::object::Date {
+                year: 2024,
+                month: 3,
+                day: 1,
+                weekday: 5,
+            },
+            crate::object::Time {
+                hour: 8,
+                minute: 0,
+                second: 0,
+                hundredths: 0,
+            },
+        );
+        let mut service_data = Vec::new();
+        TimeSynchronizationRequest::new(date_time)
+            .encode(&mut service_data)
+            .unwrap();
+        handle_time_synchronization(&mut clock, &service_data).unwrap();
+        assert_eq!(clock.local_date_time(), date_time);
+
+        let mut service_data = Vec::new();
+        UtcTimeSynchronizationRequest::new(BacnetDateTime::unspecified())
+            .encode(&mut service_data)
+            .unwrap();
+        assert!(handle_utc_time_synchronization(&mut clock, &service_data).is_err());
+        assert!(clock.utc_date_time().is_unspecified());
+
+        let broadcast = Recipient::Address {
+            network: 0xFFFF,
+            mac_address: Vec::new(),
+        };
+        let mut master = TimeMaster::new(Duration::from_secs(3600));
+        master.time_synchronization_recipients.push(broadcast);
+        master
+            .utc_time_synchronization_recipients
+            .push(Recipient::Device(ObjectIdentifier::new(
+                ObjectType::Device,
+                7,
+            )));
+        assert!(master
+            .advance_time(Duration::from_secs(1800), &clock)
+            .is_empty());
+        let pending = master.advance_time(Duration::from_secs(1800), &clock);
+        assert_eq!(pending.len(), 2);
+        assert_eq!(pending[0].date_time, date_time);
+        assert!(pending[1].utc);
+        assert!(matches!(
+            pending[0].to_apdu().unwrap(),
+            Apdu::UnconfirmedRequest {
+                service_choice: UnconfirmedServiceChoice::TimeSynchronization,
+                ..
+            }
+        ));
+        assert!(master
+            .advance_time(Duration::from_secs(1800), &clock)
+            .is_empty());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_system_clock_correction() {
+        let mut clock = SystemClock::new();
+        let mut target = clock.local_date_time();
+        target.date.year += 1;
+        target.date.day = 1;
+        clock.set_local_date_time(target);
+        assert!(clock.offset() > chrono::Duration::days(330));
+        assert_eq!(clock.local_date_time().date.year, target.date.year);
+    }
+}
@@ -0,0 +1,407 @@
+//! Who-Am-I and You-Are Services
+//!
+//! An unconfigured device broadcasts a Who-Am-I carrying its vendor ID, model
+//! name and serial number. A commissioning tool that recognizes the device
+//! answers with a You-Are that repeats those three values and assigns the
+//! device instance, the MAC address, or both. [`DeviceCommissioner`] holds the
+//! tool's assignments; the device applies an answer addressed to it with
+//! [`YouAreRequest::apply_to`].
+
+use crate::app::Apdu;
+use crate::encoding::{
+    decode_character_string, decode_object_identifier, decode_octet_string, decode_unsigned,
+    encode_character_string, encode_object_identifier, encode_octet_string, encode_unsigned,
+    ApplicationTag, EncodingError, Result as EncodingResult,
+};
+use crate::object::{Device, ObjectIdentifier, ObjectType};
+
+use super::UnconfirmedServiceChoice;
+
+#[cfg(feature = "std")]
+use std::collections::BTreeMap;
+
+#[cfg(not(feature = "std"))]
+use alloc::{collections::BTreeMap, string::String, string::ToString, vec::Vec};
+
+/// Who-Am-I request (unconfirmed service)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WhoAmIRequest {
+    /// Vendor of the unconfigured device
+    pub vendor_id: u16,
+    /// Model name of the device
+    pub model_name: String,
+    /// Serial number of the device
+    pub serial_number: String,
+}
+
+impl WhoAmIRequest {
+    /// Create a new Who-Am-I request
+    pub fn new(
+        vendor_id: u16,
+        model_name: impl Into<String>,
+        serial_number: impl Into<String>,
+    ) -> Self {
+        Self {
+            vendor_id,
+            model_name: model_name.into(),
+            serial_number: serial_number.into(),
+        }
+    }
+
+    /// Create the Who-Am-I announcing a device object with `serial_number`
+    pub fn for_device(device: &Device, serial_number: impl Into<String>) -> Self {
+        Self::new(
+            device.vendor_identifier,
+            device.model_name.clone(),
+            serial_number,
+        )
+    }
+
+    /// Encode the Who-Am-I request
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        encode_unsigned(buffer, self.vendor_id as u32)?;
+        encode_character_string(buffer, &self.model_name)?;
+        encode_character_string(buffer, &self.serial_number)?;
+        Ok(())
+    }
+
+    /// Decode a Who-Am-I request
+    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
+        let (request, consumed) = decode_device_key(data)?;
+        if consumed != data.len() {
+            return Err(EncodingError::InvalidFormat(
+                "Unexpected data after Who-Am-I request".to_string(),
+            ));
+        }
+        Ok(request)
+    }
+
+    /// Wrap the request in an unconfirmed request APDU
+    pub fn to_apdu(&self) -> EncodingResult<Apdu> {
+        let mut service_data = Vec::new();
+        self.encode(&mut service_data)?;
+        Ok(Apdu::UnconfirmedRequest {
+            service_choice: UnconfirmedServiceChoice::WhoAmI,
+            service_data,
+        })
+    }
+}
+
+/// You-Are request (unconfirmed service)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct YouAreRequest {
+    /// Vendor of the device addressed
+    pub vendor_id: u16,
+    /// Model name of the device addressed
+    pub model_name: String,
+    /// Serial number of the device addressed
+    pub serial_number: String,
+    /// Device object identifier to take (optional)
+    pub device_identifier: Option<ObjectIdentifier>,
+    /// MAC address to take on the datalink the request arrived on (optional)
+    pub device_mac_address: Option<Vec<u8>>,
+}
+
+impl YouAreRequest {
+    /// Create a You-Are answering `who_am_i` without any assignment
+    pub fn new(who_am_i: &WhoAmIRequest) -> Self {
+        Self {
+            vendor_id: who_am_i.vendor_id,
+            model_name: who_am_i.model_name.clone(),
+            serial_number: who_am_i.serial_number.clone(),
+            device_identifier: None,
+            device_mac_address: None,
+        }
+    }
+
+    /// Assign a device instance
+    pub fn with_device_instance(mut self, instance: u32) -> Self {
+        self.device_identifier = Some(ObjectIdentifier::new(ObjectType::Device, instance));
+        self
+    }
+
+    /// Assign a MAC address
+    pub fn with_mac_address(mut self, mac_address: Vec<u8>) -> Self {
+        self.device_mac_address = Some(mac_address);
+        self
+    }
+
+    /// Check whether this You-Are is addressed to the device that sent
+    /// `who_am_i`
+    pub fn matches(&self, who_am_i: &WhoAmIRequest) -> bool {
+        self.vendor_id == who_am_i.vendor_id
+            && self.model_name == who_am_i.model_name
+            && self.serial_number == who_am_i.serial_number
+    }
+
+    /// Apply the assigned device instance to a device object
+    ///
+    /// Returns whether the request is addressed to the device, identified by
+    /// its vendor, model name and `serial_number`. The MAC address, if any, is
+    /// left for the caller to configure on its datalink.
+    pub fn apply_to(&self, device: &mut Device, serial_number: &str) -> bool {
+        if self.vendor_id != device.vendor_identifier
+            || self.model_name != device.model_name
+            || self.serial_number != serial_number
+        {
+            return false;
+        }
+        if let Some(identifier) = self.device_identifier {
+            let old = device.identifier;
+            for entry in device.object_list.iter_mut().filter(|entry| **entry == old) {
+                *entry = identifier;
+            }
+            device.identifier = identifier;
+        }
+        true
+    }
+
+    /// Encode the You-Are request
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        encode_unsigned(buffer, self.vendor_id as u32)?;
+        encode_character_string(buffer, &self.model_name)?;
+        encode_character_string(buffer, &self.serial_number)?;
+        if let Some(identifier) = &self.device_identifier {
+            encode_object_identifier(
+                buffer,
+                u16::from(identifier.object_type),
+                identifier.instance,
+            )?;
+        }
+        if let Some(mac_address) = &self.device_mac_address {
+            encode_octet_string(buffer, mac_address)?;
+        }
+        Ok(())
+    }
+
+    /// Decode a You-Are request
+    ///
+    /// A device identifier must name a Device object.
+    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
+        let (key, mut pos) = decode_device_key(data)?;
+        let mut request = Self::new(&key);
+
+        if next_tag(&data[pos..]) == Some(ApplicationTag::ObjectIdentifier) {
+            let ((object_type, instance), consumed) = decode_object_identifier(&data[pos..])?;
+            if object_type != u16::from(ObjectType::Device) {
+                return Err(EncodingError::ValueOutOfRange);
+            }
+            request.device_identifier = Some(ObjectIdentifier::new(ObjectType::Device, instance));
+            pos += consumed;
+        }
+        if next_tag(&data[pos..]) == Some(ApplicationTag::OctetString) {
+            let (mac_address, consumed) = decode_octet_string(&data[pos..])?;
+            request.device_mac_address = Some(mac_address);
+            pos += consumed;
+        }
+
+        if pos != data.len() {
+            return Err(EncodingError::InvalidFormat(
+                "Unexpected data after You-Are request".to_string(),
+            ));
+        }
+        Ok(request)
+    }
+
+    /// Wrap the request in an unconfirmed request APDU
+    pub fn to_apdu(&self) -> EncodingResult<Apdu> {
+        let mut service_data = Vec::new();
+        self.encode(&mut service_data)?;
+        Ok(Apdu::UnconfirmedRequest {
+            service_choice: UnconfirmedServiceChoice::YouAre,
+            service_data,
+        })
+    }
+}
+
+/// Decode the vendor ID, model name and serial number both services start with
+fn decode_device_key(data: &[u8]) -> EncodingResult<(WhoAmIRequest, usize)> {
+    let (vendor_id, mut pos) = decode_unsigned(data)?;
+    let vendor_id = u16::try_from(vendor_id).map_err(|_| EncodingError::ValueOutOfRange)?;
+    let (model_name, consumed) = decode_character_string(&data[pos..])?;
+    pos += consumed;
+    let (serial_number, consumed) = decode_character_string(&data[pos..])?;
+    pos += consumed;
+    Ok((
+        WhoAmIRequest {
+            vendor_id,
+            model_name,
+            serial_number,
+        },
+        pos,
+    ))
+}
+
+/// Application tag of the next value, if there is one
+fn next_tag(data: &[u8]) -> Option<ApplicationTag> {
+    let octet = *data.first()?;
+    if octet & 0x08 != 0 {
+        return None;
+    }
+    ApplicationTag::try_from(octet >> 4).ok()
+}
+
+/// Device instance and MAC address a commissioning tool assigns to one device
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct DeviceAssignment {
+    /// Device instance to assign (optional)
+    pub device_instance: Option<u32>,
+    /// MAC address to assign (optional)
+    pub mac_address: Option<Vec<u8>>,
+}
+
+/// Answers Who-Am-I requests from unconfigured devices on behalf of a
+/// commissioning tool
+///
+/// Devices are keyed by vendor ID, model name and serial number; a Who-Am-I
+/// from a device without an assignment goes unanswered.
+#[derive(Debug, Clone, Default)]
+pub struct DeviceCommissioner {
+    assignments: BTreeMap<(u16, String, String), DeviceAssignment>,
+}
+
+impl DeviceCommissioner {
+    /// Create a commissioner without assignments
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the assignment for a device, replacing any previous one
+    pub fn assign(
+        &mut self,
+        vendor_id: u16,
+        model_name: impl Into<String>,
+        serial_number: impl Into<String>,
+        assignment: DeviceAssignment,
+    ) {
+        self.assignments.insert(
+            (vendor_id, model_name.into(), serial_number.into()),
+            assignment,
+        );
+    }
+
+    /// Remove the assignment for a device
+    pub fn unassign(&mut self, vendor_id: u16, model_name: &str, serial_number: &str) -> bool {
+        self.assignments
+            .remove(&(vendor_id, model_name.to_string(), serial_number.to_string()))
+            .is_some()
+    }
+
+    /// Number of devices with an assignment
+    pub fn len(&self) -> usize {
+        self.assignments.len()
+    }
+
+    /// Check whether there are no assignments
+    pub fn is_empty(&self) -> bool {
+        self.assignments.is_empty()
+    }
+
+    /// The You-Are to send in answer to `request`, if the device has an
+    /// assignment
+    pub fn respond(&self, request: &WhoAmIRequest) -> Option<YouAreRequest> {
+        let assignment = self.assignments.get(&(
+            request.vendor_id,
+            request.model_name.clone(),
+            request.serial_number.clone(),
+        ))?;
+        Some(YouAreRequest {
+            device_identifier: assignment
+                .device_instance
+                .map(|instance| ObjectIdentifier::new(ObjectType::Device, instance)),
+            device_mac_address: assignment.mac_address.clone(),
+            ..YouAreRequest::new(request)
+        })
+    }
+
+    /// Answer a Who-Am-I APDU with a You-Are APDU
+    ///
+    /// Returns `None` for other APDUs, requests that cannot be decoded and
+    /// devices without an assignment; unconfirmed services are never rejected.
+    pub fn handle_apdu(&self, apdu: &Apdu) -> Option<Apdu> {
+        let Apdu::UnconfirmedRequest {
+            service_choice: UnconfirmedServiceChoice::WhoAmI,
+            service_data,
+        } = apdu
+        else {
+            return None;
+        };
+        let request = WhoAmIRequest::decode(service_data).ok()?;
+        self.respond(&request)?.to_apdu().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_codec() {
+        let who_am_i = WhoAmIRequest::new(260, "VAV", "S1");
+        let mut buffer = Vec::new();
+        who_am_i.encode(&mut buffer).unwrap();
+        assert_eq!(
+            buffer,
+            [0x22, 0x01, 0x04, 0x74, 0x00, b'V', b'A', b'V', 0x73, 0x00, b'S', b'1']
+        );
+        assert_eq!(WhoAmIRequest::decode(&buffer).unwrap(), who_am_i);
+
+        let you_are = YouAreRequest::new(&who_am_i)
+            .with_device_instance(1001)
+            .with_mac_address(vec![0x0A]);
+        let mut buffer = Vec::new();
+        you_are.encode(&mut buffer).unwrap();
+        assert_eq!(&buffer[12..], [0xC4, 0x02, 0x00, 0x03, 0xE9, 0x61, 0x0A]);
+        assert_eq!(YouAreRequest::decode(&buffer).unwrap(), you_are);
+
+        // Only a MAC address
+        let you_are = YouAreRequest::new(&who_am_i).with_mac_address(vec![0x0B]);
+        let mut buffer = Vec::new();
+        you_are.encode(&mut buffer).unwrap();
+        assert_eq!(YouAreRequest::decode(&buffer).unwrap(), you_are);
+
+        // A device identifier must name a Device object
+        let mut buffer = Vec::new();
+        who_am_i.encode(&mut buffer).unwrap();
+        encode_object_identifier(&mut buffer, u16::from(ObjectType::AnalogInput), 1).unwrap();
+        assert!(YouAreRequest::decode(&buffer).is_err());
+    }
+
+    #[test]
+    fn test_commissioning_round_trip() {
+        let mut device = Device::new(4194303, String::from("Unconfigured"));
+        let who_am_i = WhoAmIRequest::for_device(&device, "SN-7");
+
+        let mut commissioner = DeviceCommissioner::new();
+        assert!(commissioner
+            .handle_apdu(&who_am_i.to_apdu().unwrap())
+            .is_none());
+        commissioner.assign(
+            device.vendor_identifier,
+            device.model_name.clone(),
+            "SN-7",
+            DeviceAssignment {
+                device_instance: Some(77),
+                mac_address: Some(vec![0x12]),
+            },
+        );
+
+        let Some(Apdu::UnconfirmedRequest {
+            service_choice: UnconfirmedServiceChoice::YouAre,
+            service_data,
+        }) = commissioner.handle_apdu(&who_am_i.to_apdu().unwrap())
+        else {
+            panic!("Expected You-Are");
+        };
+        let you_are = YouAreRequest::decode(&service_data).unwrap();
+        assert!(you_are.matches(&who_am_i));
+        assert_eq!(you_are.device_mac_address, Some(vec![0x12]));
+
+        // Another device with the same model ignores the answer
+        assert!(!you_are.apply_to(&mut device.clone(), "SN-8"));
+        assert!(you_are.apply_to(&mut device, "SN-7"));
+        let identifier = ObjectIdentifier::new(ObjectType::Device, 77);
+        assert_eq!(device.identifier, identifier);
+        assert!(device.object_list.contains(&identifier));
+    }
+}
@@ -0,0 +1,320 @@
+//! Who-Has and I-Have Services (Clauses 16.9 and 16.8)
+//!
+//! A Who-Has asks which device hosts an object, named either by identifier or
+//! by Object_Name, optionally only among devices whose instance falls within a
+//! range. Each device that has the object answers with an I-Have carrying its
+//! own identifier and the object's identifier and name.
+//! [`handle_who_has`] searches an
+//! [`ObjectDatabase`](crate::object::database::ObjectDatabase) for the answer.
+
+use crate::encoding::{
+    decode_character_string, decode_context_object_id, decode_context_tag, decode_context_unsigned,
+    decode_object_identifier, encode_character_string, encode_context_object_id,
+    encode_context_tag, encode_context_unsigned, encode_object_identifier, EncodingError,
+    Result as EncodingResult,
+};
+use crate::object::{ObjectIdentifier, ObjectType};
+
+#[cfg(feature = "std")]
+use super::UnconfirmedServiceChoice;
+#[cfg(feature = "std")]
+use crate::{
+    app::Apdu,
+    object::{database::ObjectDatabase, PropertyIdentifier, PropertyValue},
+};
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+
+/// The object a Who-Has searches for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WhoHasObject {
+    /// Search by object identifier
+    Identifier(ObjectIdentifier),
+    /// Search by Object_Name
+    Name(String),
+}
+
+/// Who-Has request (unconfirmed service)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WhoHasRequest {
+    /// Low limit of device instance range (optional)
+    pub device_instance_range_low_limit: Option<u32>,
+    /// High limit of device instance range (optional)
+    pub device_instance_range_high_limit: Option<u32>,
+    /// Object searched for
+    pub object: WhoHasObject,
+}
+
+impl WhoHasRequest {
+    /// Create a Who-Has for an object identifier on any device
+    pub fn for_identifier(object_identifier: ObjectIdentifier) -> Self {
+        Self {
+            device_instance_range_low_limit: None,
+            device_instance_range_high_limit: None,
+            object: WhoHasObject::Identifier(object_identifier),
+        }
+    }
+
+    /// Create a Who-Has for an object name on any device
+    pub fn for_name(object_name: impl Into<String>) -> Self {
+        Self {
+            device_instance_range_low_limit: None,
+            device_instance_range_high_limit: None,
+            object: WhoHasObject::Name(object_name.into()),
+        }
+    }
+
+    /// Limit the search to devices with instances in `low..=high`
+    pub fn with_range(mut self, low: u32, high: u32) -> Self {
+        self.device_instance_range_low_limit = Some(low);
+        self.device_instance_range_high_limit = Some(high);
+        self
+    }
+
+    /// Check if this request addresses a device instance
+    pub fn matches_device(&self, device_instance: u32) -> bool {
+        match (
+            self.device_instance_range_low_limit,
+            self.device_instance_range_high_limit,
+        ) {
+            (Some(low), Some(high)) => (low..=high).contains(&device_instance),
+            _ => true,
+        }
+    }
+
+    /// Encode the Who-Has request
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        // Device instance range - context tags 0 and 1, present together
+        if let (Some(low), Some(high)) = (
+            self.device_instance_range_low_limit,
+            self.device_instance_range_high_limit,
+        ) {
+            buffer.extend_from_slice(&encode_context_unsigned(low, 0)?);
+            buffer.extend_from_slice(&encode_context_unsigned(high, 1)?);
+        }
+
+        match &self.object {
+            // Object identifier - context tag 2
+            WhoHasObject::Identifier(id) => buffer.extend_from_slice(&encode_context_object_id(
+                u16::from(id.object_type),
+                id.instance,
+                2,
+            )?),
+            // Object name - context tag 3
+            WhoHasObject::Name(name) => {
+                encode_context_tag(buffer, 3, name.len() + 1)?;
+                buffer.push(0); // Character set encoding (0 = ANSI X3.4)
+                buffer.extend_from_slice(name.as_bytes());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Decode a Who-Has request
+    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
+        let mut pos = 0;
+        let (mut low, mut high) = (None, None);
+        if let Ok((value, consumed)) = decode_context_unsigned(data, 0) {
+            pos += consumed;
+            let (value_high, consumed) = decode_context_unsigned(&data[pos..], 1)?;
+            pos += consumed;
+            low = Some(value);
+            high = Some(value_high);
+        }
+
+        let (tag_number, length, consumed) = decode_context_tag(&data[pos..])?;
+        let object = match tag_number {
+            2 => {
+                let ((object_type, instance), _) = decode_context_object_id(&data[pos..], 2)?;
+                let object_type = ObjectType::try_from(object_type)
+                    .map_err(|_| EncodingError::ValueOutOfRange)?;
+                WhoHasObject::Identifier(ObjectIdentifier::new(object_type, instance))
+            }
+            3 => {
+                let content = data
+                    .get(pos + consumed..pos + consumed + length)
+                    .filter(|content| !content.is_empty())
+                    .ok_or(EncodingError::BufferUnderflow)?;
+                let name = String::from_utf8(content[1..].to_vec()).map_err(|_| {
+                    EncodingError::InvalidFormat("Invalid UTF-8 string".to_string())
+                })?;
+                WhoHasObject::Name(name)
+            }
+            _ => return Err(EncodingError::InvalidTag),
+        };
+
+        Ok(Self {
+            device_instance_range_low_limit: low,
+            device_instance_range_high_limit: high,
+            object,
+        })
+    }
+}
+
+/// I-Have response (unconfirmed service)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IHaveRequest {
+    /// Identifier of the device hosting the object
+    pub device_identifier: ObjectIdentifier,
+    /// Identifier of the object found
+    pub object_identifier: ObjectIdentifier,
+    /// Name of the object found
+    pub object_name: String,
+}
+
+impl IHaveRequest {
+    /// Create a new I-Have request
+    pub fn new(
+        device_identifier: ObjectIdentifier,
+        object_identifier: ObjectIdentifier,
+        object_name: String,
+    ) -> Self {
+        Self {
+            device_identifier,
+            object_identifier,
+            object_name,
+        }
+    }
+
+    /// Encode the I-Have request
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        encode_object_identifier(
+            buffer,
+            u16::from(self.device_identifier.object_type),
+            self.device_identifier.instance,
+        )?;
+        encode_object_identifier(
+            buffer,
+            u16::from(self.object_identifier.object_type),
+            self.object_identifier.instance,
+        )?;
+        encode_character_string(buffer, &self.object_name)
+    }
+
+    /// Decode an I-Have request
+    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
+        let (device_identifier, mut pos) = decode_identifier(data)?;
+        let (object_identifier, consumed) = decode_identifier(&data[pos..])?;
+        pos += consumed;
+        let (object_name, _) = decode_character_string(&data[pos..])?;
+        Ok(Self::new(device_identifier, object_identifier, object_name))
+    }
+}
+
+fn decode_identifier(data: &[u8]) -> EncodingResult<(ObjectIdentifier, usize)> {
+    let ((object_type, instance), consumed) = decode_object_identifier(data)?;
+    let object_type =
+        ObjectType::try_from(object_type).map_err(|_| EncodingError::ValueOutOfRange)?;
+    Ok((ObjectIdentifier::new(object_type, instance), consumed))
+}
+
+/// Find the object a Who-Has searches for in an object database
+///
+/// Returns the I-Have to send, or `None` if the local device is outside the
+/// requested range or does not have the object.
+#[cfg(feature = "std")]
+pub fn who_has(database: &ObjectDatabase, request: &WhoHasRequest) -> Option<IHaveRequest> {
+    let device_identifier = database.get_device_id();
+    if !request.matches_device(device_identifier.instance) {
+        return None;
+    }
+
+    let object_identifier = match &request.object {
+        WhoHasObject::Identifier(id) => *id,
+        WhoHasObject::Name(name) => database.get_object_by_name(name).ok()?,
+    };
+    let object_name = match database.get_property(object_identifier, PropertyIdentifier::ObjectName)
+    {
+        Ok(PropertyValue::CharacterString(name)) => name,
+        _ => return None,
+    };
+
+    Some(IHaveRequest::new(
+        device_identifier,
+        object_identifier,
+        object_name,
+    ))
+}
+
+/// Answer encoded Who-Has service data with an I-Have APDU
+///
+/// Returns `None` when the local device does not have the object or the
+/// request cannot be decoded; unconfirmed services are never rejected.
+#[cfg(feature = "std")]
+pub fn handle_who_has(database: &ObjectDatabase, service_data: &[u8]) -> Option<Apdu> {
+    let request = WhoHasRequest::decode(service_data).ok()?;
+    let i_have = who_has(database, &request)?;
+    let mut service_data = Vec::new();
+    i_have.encode(&mut service_data).ok()?;
+    Some(Apdu::UnconfirmedRequest {
+        service_choice: UnconfirmedServiceChoice::IHave,
+        service_data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_who_has_round_trip() {
+        let requests = [
+            WhoHasRequest::for_name("Zone Temp").with_range(10, 20),
+            WhoHasRequest::for_identifier(ObjectIdentifier::new(ObjectType::AnalogValue, 3)),
+        ];
+        for request in requests {
+            let mut buffer = Vec::new();
+            request.encode(&mut buffer).unwrap();
+            assert_eq!(WhoHasRequest::decode(&buffer).unwrap(), request);
+        }
+
+        let i_have = IHaveRequest::new(
+            ObjectIdentifier::new(ObjectType::Device, 15),
+            ObjectIdentifier::new(ObjectType::AnalogInput, 1),
+            String::from("Zone Temp"),
+        );
+        let mut buffer = Vec::new();
+        i_have.encode(&mut buffer).unwrap();
+        assert_eq!(IHaveRequest::decode(&buffer).unwrap(), i_have);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_who_has_search() {
+        use crate::object::{analog::AnalogInput, Device};
+
+        let database = ObjectDatabase::new(Device::new(15, String::from("Device")));
+        database
+            .add_object(Box::new(AnalogInput::new(1, String::from("Zone Temp"))))
+            .unwrap();
+        let ai = ObjectIdentifier::new(ObjectType::AnalogInput, 1);
+
+        let by_name = who_has(&database, &WhoHasRequest::for_name("Zone Temp")).unwrap();
+        assert_eq!(by_name.object_identifier, ai);
+        assert_eq!(by_name.device_identifier.instance, 15);
+
+        let by_id = who_has(&database, &WhoHasRequest::for_identifier(ai)).unwrap();
+        assert_eq!(by_id.object_name, "Zone Temp");
+
+        assert!(who_has(&database, &WhoHasRequest::for_name("Missing")).is_none());
+        let out_of_range = WhoHasRequest::for_identifier(ai).with_range(100, 200);
+        assert!(who_has(&database, &out_of_range).is_none());
+
+        let mut service_data = Vec::new();
+        WhoHasRequest::for_name("Zone Temp")
+            .encode(&mut service_data)
+            .unwrap();
+        assert!(matches!(
+            handle_who_has(&database, &service_data),
+            Some(Apdu::UnconfirmedRequest {
+                service_choice: UnconfirmedServiceChoice::IHave,
+                ..
+            })
+        ));
+    }
+}
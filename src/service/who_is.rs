@@ -0,0 +1,308 @@
+//! Who-Is and I-Am Services (Clauses 16.10 and 16.1)
+//!
+//! A Who-Is asks every device, or those whose instance falls within a range,
+//! to announce itself; each matching device answers with an I-Am carrying its
+//! identifier, maximum APDU length, segmentation support and vendor.
+//! [`WhoIsResponder`] produces the local device's answer.
+
+use crate::app::Apdu;
+use crate::encoding::{
+    decode_context_unsigned, decode_enumerated, decode_object_identifier, decode_unsigned,
+    encode_context_unsigned, encode_enumerated, encode_object_identifier, encode_unsigned,
+    Result as EncodingResult,
+};
+use crate::object::{Device, ObjectIdentifier};
+
+use super::UnconfirmedServiceChoice;
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::ToString, vec::Vec};
+
+/// Who-Is request (unconfirmed service)
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct WhoIsRequest {
+    /// Low limit of device instance range (optional)
+    pub device_instance_range_low_limit: Option<u32>,
+    /// High limit of device instance range (optional)
+    pub device_instance_range_high_limit: Option<u32>,
+}
+
+impl WhoIsRequest {
+    /// Create a new Who-Is request for all devices
+    pub fn new() -> Self {
+        Self {
+            device_instance_range_low_limit: None,
+            device_instance_range_high_limit: None,
+        }
+    }
+
+    /// Create a new Who-Is request for a specific device
+    pub fn for_device(device_instance: u32) -> Self {
+        Self {
+            device_instance_range_low_limit: Some(device_instance),
+            device_instance_range_high_limit: Some(device_instance),
+        }
+    }
+
+    /// Create a new Who-Is request for a range of devices
+    pub fn for_range(low: u32, high: u32) -> Self {
+        Self {
+            device_instance_range_low_limit: Some(low),
+            device_instance_range_high_limit: Some(high),
+        }
+    }
+
+    /// Encode the Who-Is request
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        // Both low and high limits must be present together, or both absent
+        // This matches bacnet-stack behavior
+        if let (Some(low), Some(high)) = (
+            self.device_instance_range_low_limit,
+            self.device_instance_range_high_limit,
+        ) {
+            // Context tag 0 - low limit
+            let low_bytes = encode_context_unsigned(low, 0)?;
+            buffer.extend_from_slice(&low_bytes);
+
+            // Context tag 1 - high limit
+            let high_bytes = encode_context_unsigned(high, 1)?;
+            buffer.extend_from_slice(&high_bytes);
+        }
+        // If only one limit is present, encode nothing (broadcast to all)
+
+        Ok(())
+    }
+
+    /// Decode a Who-Is request
+    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
+        let mut request = WhoIsRequest::new();
+        let mut pos = 0;
+
+        // Try to decode context tag 0 (low limit)
+        if pos < data.len() {
+            match decode_context_unsigned(&data[pos..], 0) {
+                Ok((low, consumed)) => {
+                    request.device_instance_range_low_limit = Some(low);
+                    pos += consumed;
+
+                    // If we have low limit, we must have high limit
+                    if pos < data.len() {
+                        match decode_context_unsigned(&data[pos..], 1) {
+                            Ok((high, _consumed)) => {
+                                request.device_instance_range_high_limit = Some(high);
+                            }
+                            Err(_) => {
+                                // Invalid format - low without high
+                                return Err(crate::encoding::EncodingError::InvalidFormat(
+                                    "Who-Is request has low limit without high limit".to_string(),
+                                ));
+                            }
+                        }
+                    }
+                }
+                Err(_) => {
+                    // No device range specified - broadcast to all
+                }
+            }
+        }
+
+        Ok(request)
+    }
+
+    /// Check if this request matches a device instance
+    pub fn matches(&self, device_instance: u32) -> bool {
+        match (
+            self.device_instance_range_low_limit,
+            self.device_instance_range_high_limit,
+        ) {
+            (None, None) => true, // Matches all devices
+            (Some(low), Some(high)) => device_instance >= low && device_instance <= high,
+            (Some(low), None) => device_instance >= low,
+            (None, Some(high)) => device_instance <= high,
+        }
+    }
+}
+
+/// I-Am response (unconfirmed service)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IAmRequest {
+    /// Device object identifier
+    pub device_identifier: ObjectIdentifier,
+    /// Maximum APDU length accepted
+    pub max_apdu_length_accepted: u32,
+    /// Segmentation supported
+    pub segmentation_supported: u32,
+    /// Vendor identifier
+    pub vendor_identifier: u32,
+}
+
+impl IAmRequest {
+    /// Create a new I-Am request
+    pub fn new(
+        device_identifier: ObjectIdentifier,
+        max_apdu_length_accepted: u32,
+        segmentation_supported: u32,
+        vendor_identifier: u32,
+    ) -> Self {
+        Self {
+            device_identifier,
+            max_apdu_length_accepted,
+            segmentation_supported,
+            vendor_identifier,
+        }
+    }
+
+    /// Encode the I-Am request
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        // Device identifier (object identifier) - application tag
+        encode_object_identifier(
+            buffer,
+            u16::from(self.device_identifier.object_type),
+            self.device_identifier.instance,
+        )?;
+
+        // Maximum APDU length accepted - application tag
+        encode_unsigned(buffer, self.max_apdu_length_accepted)?;
+
+        // Segmentation supported - application tag (enumerated)
+        encode_enumerated(buffer, self.segmentation_supported)?;
+
+        // Vendor identifier - application tag
+        encode_unsigned(buffer, self.vendor_identifier)?;
+
+        Ok(())
+    }
+
+    /// Decode an I-Am request
+    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
+        let mut pos = 0;
+
+        // Decode device identifier - application tag
+        let ((object_type, instance), consumed) = decode_object_identifier(&data[pos..])?;
+        let device_identifier = ObjectIdentifier {
+            object_type: crate::object::ObjectType::try_from(object_type)
+                .unwrap_or(crate::object::ObjectType::Device),
+            instance,
+        };
+        pos += consumed;
+
+        // Decode max APDU length accepted - application tag
+        let (max_apdu_length_accepted, consumed) = decode_unsigned(&data[pos..])?;
+        pos += consumed;
+
+        // Decode segmentation supported - application tag (enumerated)
+        let (segmentation_supported, consumed) = decode_enumerated(&data[pos..])?;
+        pos += consumed;
+
+        // Decode vendor identifier - application tag
+        let (vendor_identifier, _consumed) = decode_unsigned(&data[pos..])?;
+
+        Ok(IAmRequest::new(
+            device_identifier,
+            max_apdu_length_accepted,
+            segmentation_supported,
+            vendor_identifier,
+        ))
+    }
+}
+
+impl IAmRequest {
+    /// Create the I-Am announcing a device object
+    pub fn for_device(device: &Device) -> Self {
+        Self::new(
+            device.identifier,
+            u32::from(device.max_apdu_length_accepted),
+            device.segmentation_supported as u32,
+            u32::from(device.vendor_identifier),
+        )
+    }
+}
+
+/// Answers Who-Is requests on behalf of the local device
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WhoIsResponder {
+    i_am: IAmRequest,
+}
+
+impl WhoIsResponder {
+    /// Create a responder that announces `i_am`
+    pub fn new(i_am: IAmRequest) -> Self {
+        Self { i_am }
+    }
+
+    /// Create a responder for a device object
+    pub fn for_device(device: &Device) -> Self {
+        Self::new(IAmRequest::for_device(device))
+    }
+
+    /// The I-Am this responder announces
+    pub fn i_am(&self) -> &IAmRequest {
+        &self.i_am
+    }
+
+    /// The I-Am to send in answer to `request`, if the local device instance
+    /// falls within its range
+    pub fn respond(&self, request: &WhoIsRequest) -> Option<&IAmRequest> {
+        request
+            .matches(self.i_am.device_identifier.instance)
+            .then_some(&self.i_am)
+    }
+
+    /// Answer encoded Who-Is service data with an I-Am APDU
+    ///
+    /// Returns `None` when the request is out of range or cannot be decoded;
+    /// unconfirmed services are never rejected.
+    pub fn handle(&self, service_data: &[u8]) -> Option<Apdu> {
+        let request = WhoIsRequest::decode(service_data).ok()?;
+        let i_am = self.respond(&request)?;
+        let mut service_data = Vec::new();
+        i_am.encode(&mut service_data).ok()?;
+        Some(Apdu::UnconfirmedRequest {
+            service_choice: UnconfirmedServiceChoice::IAm,
+            service_data,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_responder_range() {
+        let responder = WhoIsResponder::for_device(&Device::new(1234, String::from("Device")));
+        assert_eq!(responder.i_am().device_identifier.instance, 1234);
+
+        assert!(responder.respond(&WhoIsRequest::new()).is_some());
+        assert!(responder
+            .respond(&WhoIsRequest::for_range(1000, 2000))
+            .is_some());
+        assert!(responder.respond(&WhoIsRequest::for_device(1234)).is_some());
+        assert!(responder
+            .respond(&WhoIsRequest::for_range(0, 1233))
+            .is_none());
+    }
+
+    #[test]
+    fn test_handle_encoded_who_is() {
+        let responder = WhoIsResponder::for_device(&Device::new(42, String::from("Device")));
+
+        let mut who_is = Vec::new();
+        WhoIsRequest::for_range(40, 50).encode(&mut who_is).unwrap();
+        let Some(Apdu::UnconfirmedRequest {
+            service_choice: UnconfirmedServiceChoice::IAm,
+            service_data,
+        }) = responder.handle(&who_is)
+        else {
+            panic!("Expected I-Am");
+        };
+        assert_eq!(
+            &IAmRequest::decode(&service_data).unwrap(),
+            responder.i_am()
+        );
+
+        let mut who_is = Vec::new();
+        WhoIsRequest::for_device(7).encode(&mut who_is).unwrap();
+        assert!(responder.handle(&who_is).is_none());
+    }
+}
@@ -0,0 +1,243 @@
+//! WriteGroup Service (Clause 15.11)
+//!
+//! WriteGroup is an unconfirmed service that writes values to many Channel
+//! objects at once. Each entry of the change list names a channel number; the
+//! value goes to every local Channel with that Channel_Number whose
+//! Control_Groups includes the request's group number. Writes use the entry's
+//! overriding priority, or the request's write priority without one. Group
+//! number zero addresses no channel, so such requests are ignored.
+//!
+//! Channel values are carried as application-tagged primitives; the
+//! BACnetLightingCommand alternative is not supported.
+
+use super::event_notification::Reader;
+use super::read_property::{decode_property_value, encode_property_value};
+use crate::encoding::{
+    advanced::context::{encode_closing_tag, encode_opening_tag},
+    encode_context_boolean, encode_context_unsigned, EncodingError, Result as EncodingResult,
+};
+use crate::object::PropertyValue;
+
+#[cfg(feature = "std")]
+use crate::object::database::ObjectDatabase;
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::ToString, vec::Vec};
+
+/// One entry of a Write Group change list (BACnetGroupChannelValue)
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupChannelValue {
+    /// Channel_Number of the channels to write
+    pub channel: u16,
+    /// Priority replacing the request's write priority (optional, 1-16)
+    pub overriding_priority: Option<u8>,
+    /// Value to write
+    pub value: PropertyValue,
+}
+
+impl GroupChannelValue {
+    /// Create a new entry written at the request's priority
+    pub fn new(channel: u16, value: PropertyValue) -> Self {
+        Self {
+            channel,
+            overriding_priority: None,
+            value,
+        }
+    }
+
+    /// Write this entry at `priority` instead
+    pub fn with_priority(mut self, priority: u8) -> Self {
+        self.overriding_priority = Some(priority);
+        self
+    }
+}
+
+/// Write Group request (unconfirmed service)
+#[derive(Debug, Clone, PartialEq)]
+pub struct WriteGroupRequest {
+    /// Control group addressed
+    pub group_number: u32,
+    /// Priority for entries without an overriding priority (1-16)
+    pub write_priority: u8,
+    /// Values to write
+    pub change_list: Vec<GroupChannelValue>,
+    /// Whether the channels should skip their Execution_Delay (optional)
+    pub inhibit_delay: Option<bool>,
+}
+
+impl WriteGroupRequest {
+    /// Create a new request
+    pub fn new(group_number: u32, write_priority: u8, change_list: Vec<GroupChannelValue>) -> Self {
+        Self {
+            group_number,
+            write_priority,
+            change_list,
+            inhibit_delay: None,
+        }
+    }
+
+    /// Ask the channels to skip their execution delay
+    pub fn with_inhibit_delay(mut self, inhibit_delay: bool) -> Self {
+        self.inhibit_delay = Some(inhibit_delay);
+        self
+    }
+
+    /// Encode the request
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        buffer.extend_from_slice(&encode_context_unsigned(self.group_number, 0)?);
+        buffer.extend_from_slice(&encode_context_unsigned(self.write_priority as u32, 1)?);
+        encode_opening_tag(buffer, 2)?;
+        for entry in &self.change_list {
+            buffer.extend_from_slice(&encode_context_unsigned(entry.channel as u32, 0)?);
+            if let Some(priority) = entry.overriding_priority {
+                buffer.extend_from_slice(&encode_context_unsigned(priority as u32, 1)?);
+            }
+            encode_property_value(buffer, &entry.value)?;
+        }
+        encode_closing_tag(buffer, 2)?;
+        if let Some(inhibit_delay) = self.inhibit_delay {
+            buffer.extend_from_slice(&encode_context_boolean(inhibit_delay, 3)?);
+        }
+        Ok(())
+    }
+
+    /// Decode a request
+    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
+        let mut reader = Reader::new(data);
+        let group_number = reader.unsigned(0)?;
+        let write_priority = priority(reader.unsigned(1)?)?;
+
+        reader.open(2)?;
+        let mut change_list = Vec::new();
+        while !reader.at_close(2) {
+            let channel =
+                u16::try_from(reader.unsigned(0)?).map_err(|_| EncodingError::ValueOutOfRange)?;
+            let overriding_priority = reader
+                .optional(1, Reader::unsigned)?
+                .map(priority)
+                .transpose()?;
+            let value = reader.advance(decode_property_value(reader.rest())?);
+            change_list.push(GroupChannelValue {
+                channel,
+                overriding_priority,
+                value,
+            });
+        }
+        reader.close(2)?;
+        let inhibit_delay = reader.optional(3, Reader::boolean)?;
+
+        if !reader.rest().is_empty() {
+            return Err(EncodingError::InvalidFormat(
+                "Unexpected data after Write Group request".to_string(),
+            ));
+        }
+        Ok(Self {
+            group_number,
+            write_priority,
+            change_list,
+            inhibit_delay,
+        })
+    }
+}
+
+fn priority(value: u32) -> EncodingResult<u8> {
+    match value {
+        1..=16 => Ok(value as u8),
+        _ => Err(EncodingError::ValueOutOfRange),
+    }
+}
+
+/// Apply a Write Group request to the channels of an object database
+///
+/// Returns the number of channel writes made. Unconfirmed requests have no
+/// reply, so writes a channel refuses are skipped.
+#[cfg(feature = "std")]
+pub fn handle_write_group(database: &ObjectDatabase, service_data: &[u8]) -> EncodingResult<usize> {
+    let request = WriteGroupRequest::decode(service_data)?;
+    Ok(database.write_group(&request))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::{
+        AnalogValue, BacnetObject, Channel, Device, DeviceObjectPropertyReference,
+        ObjectIdentifier, ObjectType, PropertyIdentifier,
+    };
+
+    #[test]
+    fn test_request_codec() {
+        let request = WriteGroupRequest::new(
+            4,
+            10,
+            vec![
+                GroupChannelValue::new(12, PropertyValue::Real(1.0)),
+                GroupChannelValue::new(13, PropertyValue::Null).with_priority(8),
+            ],
+        )
+        .with_inhibit_delay(true);
+        let mut buffer = Vec::new();
+        request.encode(&mut buffer).unwrap();
+        assert_eq!(
+            buffer,
+            [
+                0x09, 0x04, 0x19, 0x0A, 0x2E, 0x09, 0x0C, 0x44, 0x3F, 0x80, 0x00, 0x00, 0x09, 0x0D,
+                0x19, 0x08, 0x00, 0x2F, 0x39, 0x01
+            ]
+        );
+        assert_eq!(WriteGroupRequest::decode(&buffer).unwrap(), request);
+
+        // Priority 17 is out of range
+        buffer[3] = 0x11;
+        assert!(WriteGroupRequest::decode(&buffer).is_err());
+    }
+
+    #[test]
+    fn test_write_group_dispatch() {
+        let database = ObjectDatabase::new(Device::new(1, "Lighting".to_string()));
+        let member = ObjectIdentifier::new(ObjectType::AnalogValue, 1);
+        database
+            .add_object(Box::new(AnalogValue::new(1, "Level".to_string())))
+            .unwrap();
+        let mut channel = Channel::new(1, "Zone A".to_string(), 12);
+        channel.add_member(DeviceObjectPropertyReference::new(
+            member,
+            PropertyIdentifier::PresentValue,
+        ));
+        channel.control_groups = vec![4];
+        let channel_id = channel.identifier();
+        database.add_object(Box::new(channel)).unwrap();
+
+        // Another group, another channel number, and group zero reach nothing
+        for (group, number) in [(5, 12), (4, 13), (0, 12)] {
+            let request = WriteGroupRequest::new(
+                group,
+                10,
+                vec![GroupChannelValue::new(number, PropertyValue::Real(40.0))],
+            );
+            assert_eq!(database.write_group(&request), 0);
+        }
+
+        let mut service_data = Vec::new();
+        WriteGroupRequest::new(
+            4,
+            10,
+            vec![GroupChannelValue::new(12, PropertyValue::Real(55.0)).with_priority(7)],
+        )
+        .encode(&mut service_data)
+        .unwrap();
+        assert_eq!(handle_write_group(&database, &service_data).unwrap(), 1);
+        assert_eq!(
+            database
+                .get_property(member, PropertyIdentifier::PresentValue)
+                .unwrap(),
+            PropertyValue::Real(55.0)
+        );
+        assert_eq!(
+            database
+                .get_property(channel_id, PropertyIdentifier::LastPriority)
+                .unwrap(),
+            PropertyValue::UnsignedInteger(7)
+        );
+    }
+}
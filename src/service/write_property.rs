@@ -0,0 +1,312 @@
+//! WriteProperty Service (Clause 15.9)
+//!
+//! A client names an object, a property, an optional array index and an
+//! optional command priority, and supplies the value to write. The server
+//! answers with a SimpleAck, or with an Error PDU whose class and code explain
+//! why the write was refused. [`handle_write_property`] applies a request to an
+//! [`ObjectDatabase`](crate::object::database::ObjectDatabase).
+
+use super::read_property::{
+    decode_property_reference, decode_property_value, encode_property_reference,
+};
+use crate::encoding::{
+    advanced::context::{encode_closing_tag, encode_opening_tag},
+    decode_context_unsigned, encode_context_unsigned, EncodingError, Result as EncodingResult,
+};
+use crate::object::{ObjectIdentifier, PropertyValue};
+
+#[cfg(feature = "std")]
+use super::{ConfirmedServiceChoice, PropertyAccessError, RejectReason};
+#[cfg(feature = "std")]
+use crate::{
+    app::Apdu,
+    object::{database::ObjectDatabase, ObjectError, PropertyIdentifier},
+};
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::ToString, vec::Vec};
+
+/// Write Property request (confirmed service)
+#[derive(Debug, Clone)]
+pub struct WritePropertyRequest {
+    /// Object identifier to write to
+    pub object_identifier: ObjectIdentifier,
+    /// Property identifier to write
+    pub property_identifier: u32,
+    /// Property array index (optional)
+    pub property_array_index: Option<u32>,
+    /// Property value to write
+    pub property_value: Vec<u8>, // Raw encoded property value
+    /// Priority (optional, 1-16)
+    pub priority: Option<u8>,
+}
+
+impl WritePropertyRequest {
+    /// Create a new Write Property request
+    pub fn new(
+        object_identifier: ObjectIdentifier,
+        property_identifier: u32,
+        property_value: Vec<u8>,
+    ) -> Self {
+        Self {
+            object_identifier,
+            property_identifier,
+            property_array_index: None,
+            property_value,
+            priority: None,
+        }
+    }
+
+    /// Create a new Write Property request with priority
+    pub fn with_priority(
+        object_identifier: ObjectIdentifier,
+        property_identifier: u32,
+        property_value: Vec<u8>,
+        priority: u8,
+    ) -> Self {
+        Self {
+            object_identifier,
+            property_identifier,
+            property_array_index: None,
+            property_value,
+            priority: Some(priority),
+        }
+    }
+
+    /// Create a new Write Property request with array index
+    pub fn with_array_index(
+        object_identifier: ObjectIdentifier,
+        property_identifier: u32,
+        array_index: u32,
+        property_value: Vec<u8>,
+    ) -> Self {
+        Self {
+            object_identifier,
+            property_identifier,
+            property_array_index: Some(array_index),
+            property_value,
+            priority: None,
+        }
+    }
+
+    /// Decode the raw property value
+    pub fn value(&self) -> EncodingResult<PropertyValue> {
+        let (value, consumed) = decode_property_value(&self.property_value)?;
+        if consumed != self.property_value.len() {
+            return Err(EncodingError::InvalidTag);
+        }
+        Ok(value)
+    }
+
+    /// Encode the Write Property request
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        encode_property_reference(
+            buffer,
+            self.object_identifier,
+            self.property_identifier,
+            self.property_array_index,
+        )?;
+
+        // Property value - context tag 3
+        encode_opening_tag(buffer, 3)?;
+        buffer.extend_from_slice(&self.property_value);
+        encode_closing_tag(buffer, 3)?;
+
+        // Priority - context tag 4 (optional)
+        if let Some(priority) = self.priority {
+            buffer.extend_from_slice(&encode_context_unsigned(u32::from(priority), 4)?);
+        }
+
+        Ok(())
+    }
+
+    /// Decode a Write Property request
+    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
+        let (object_identifier, property_identifier, property_array_index, mut pos) =
+            decode_property_reference(data)?;
+
+        // Property value - context tag 3 (opening tag)
+        if data.get(pos) != Some(&0x3E) {
+            return Err(EncodingError::InvalidTag);
+        }
+        pos += 1;
+
+        // The value runs to the closing tag; decoding it finds where that is
+        let (_, consumed) = decode_property_value(&data[pos..])?;
+        let property_value = data[pos..pos + consumed].to_vec();
+        pos += consumed;
+
+        if data.get(pos) != Some(&0x3F) {
+            return Err(EncodingError::InvalidTag);
+        }
+        pos += 1;
+
+        // Priority - context tag 4 (optional)
+        let priority = if pos < data.len() {
+            let (priority, consumed) = decode_context_unsigned(&data[pos..], 4)?;
+            pos += consumed;
+            Some(u8::try_from(priority).map_err(|_| EncodingError::ValueOutOfRange)?)
+        } else {
+            None
+        };
+
+        if pos != data.len() {
+            return Err(EncodingError::InvalidFormat(
+                "Unexpected data after Write Property request".to_string(),
+            ));
+        }
+
+        Ok(WritePropertyRequest {
+            object_identifier,
+            property_identifier,
+            property_array_index,
+            property_value,
+            priority,
+        })
+    }
+}
+
+/// Apply a Write Property request to an object database
+///
+/// A priority routes the write through the object's priority array; without
+/// one, commandable objects use the default priority 16.
+#[cfg(feature = "std")]
+pub fn write_property(
+    database: &ObjectDatabase,
+    request: &WritePropertyRequest,
+) -> core::result::Result<(), PropertyAccessError> {
+    let value = request
+        .value()
+        .map_err(|_| PropertyAccessError::from(&ObjectError::InvalidPropertyType))?;
+    write_value(
+        database,
+        request.object_identifier,
+        request.property_identifier,
+        request.property_array_index,
+        request.priority,
+        value,
+    )
+}
+
+/// Write one property value, routing array element and prioritized writes
+#[cfg(feature = "std")]
+pub(super) fn write_value(
+    database: &ObjectDatabase,
+    identifier: ObjectIdentifier,
+    property_identifier: u32,
+    property_array_index: Option<u32>,
+    priority: Option<u8>,
+    value: PropertyValue,
+) -> core::result::Result<(), PropertyAccessError> {
+    let property = PropertyIdentifier::try_from(property_identifier)
+        .map_err(|_| PropertyAccessError::from(&ObjectError::UnknownProperty))?;
+
+    match (property_array_index, priority) {
+        (Some(index), _) => database.set_property_at(identifier, property, index, value),
+        (None, Some(priority)) => {
+            database.set_property_with_priority(identifier, property, value, priority)
+        }
+        (None, None) => database.set_property(identifier, property, value),
+    }
+    .map_err(|error| PropertyAccessError::from(&error))
+}
+
+/// Answer a Write Property request from an object database
+///
+/// Returns a SimpleAck once the value is written, an Error PDU if the write
+/// is refused, or a Reject PDU if the request cannot be decoded.
+#[cfg(feature = "std")]
+pub fn handle_write_property(
+    database: &ObjectDatabase,
+    invoke_id: u8,
+    service_data: &[u8],
+) -> Apdu {
+    let service_choice = ConfirmedServiceChoice::WriteProperty as u8;
+    let request = match WritePropertyRequest::decode(service_data) {
+        Ok(request) => request,
+        Err(error) => return Apdu::reject(invoke_id, RejectReason::for_decode_error(&error)),
+    };
+
+    match write_property(database, &request) {
+        Ok(()) => Apdu::SimpleAck {
+            invoke_id,
+            service_choice,
+        },
+        Err(error) => Apdu::error(invoke_id, service_choice, error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::ObjectType;
+
+    #[test]
+    fn test_request_round_trip() {
+        let object_id = ObjectIdentifier::new(ObjectType::AnalogOutput, 1);
+        // Real 40.0 followed by a value byte that looks like a closing tag
+        let value = vec![0x44, 0x42, 0x20, 0x00, 0x00, 0x21, 0x3F];
+        let mut request = WritePropertyRequest::with_array_index(object_id, 87, 300, value);
+        request.priority = Some(8);
+
+        let mut buffer = Vec::new();
+        request.encode(&mut buffer).unwrap();
+        let decoded = WritePropertyRequest::decode(&buffer).unwrap();
+        assert_eq!(decoded.property_identifier, 87);
+        assert_eq!(decoded.property_array_index, Some(300));
+        assert_eq!(decoded.property_value, request.property_value);
+        assert_eq!(decoded.priority, Some(8));
+        assert_eq!(
+            decoded.value().unwrap(),
+            PropertyValue::Array(vec![
+                PropertyValue::Real(40.0),
+                PropertyValue::UnsignedInteger(0x3F),
+            ])
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_handle_write_property() {
+        use crate::object::{analog::AnalogOutput, Device};
+
+        let database = ObjectDatabase::new(Device::new(1234, String::from("Device")));
+        database
+            .add_object(Box::new(AnalogOutput::new(1, String::from("AO-1"))))
+            .unwrap();
+        let ao = ObjectIdentifier::new(ObjectType::AnalogOutput, 1);
+        let present_value = u32::from(PropertyIdentifier::PresentValue);
+
+        let mut request = Vec::new();
+        WritePropertyRequest::with_priority(ao, present_value, vec![0x44, 0x42, 0x20, 0, 0], 8)
+            .encode(&mut request)
+            .unwrap();
+        assert!(matches!(
+            handle_write_property(&database, 1, &request),
+            Apdu::SimpleAck {
+                invoke_id: 1,
+                service_choice: 15,
+            }
+        ));
+        assert_eq!(
+            database
+                .get_property_at(ao, PropertyIdentifier::PriorityArray, 8)
+                .unwrap(),
+            PropertyValue::Real(40.0)
+        );
+
+        // Writing a string to Present_Value: property class, invalid-data-type
+        let mut request = Vec::new();
+        WritePropertyRequest::new(ao, present_value, vec![0x71, 0x00])
+            .encode(&mut request)
+            .unwrap();
+        assert!(matches!(
+            handle_write_property(&database, 2, &request),
+            Apdu::Error {
+                error_class: 2,
+                error_code: 9,
+                ..
+            }
+        ));
+    }
+}
@@ -0,0 +1,532 @@
+//! WritePropertyMultiple Service (Clause 15.10)
+//!
+//! A request lists objects and, for each, the property values to write. The
+//! server applies them in order and stops at the first write that fails; the
+//! writes before it stay in effect and the error names the failing property
+//! with a BACnetObjectPropertyReference.
+//!
+//! A failure is a [`WritePropertyMultipleError`], including the first failed
+//! write attempt, which [`handle_write_property_multiple`] sends as the
+//! parameters of its Error PDU.
+
+use super::read_property::{decode_property_value, encode_property_value};
+use super::read_property_multiple::expect_tag;
+use super::{AbortReason, ConfirmedServiceChoice, PropertyAccessError, BACNET_ARRAY_ALL};
+use crate::app::Apdu;
+use crate::encoding::{
+    advanced::context::{encode_closing_tag, encode_opening_tag},
+    decode_context_enumerated, decode_context_object_id, decode_context_unsigned,
+    decode_enumerated, encode_context_enumerated, encode_context_object_id,
+    encode_context_unsigned, encode_enumerated, EncodingError, Result as EncodingResult,
+};
+use crate::object::{ObjectIdentifier, ObjectType, PropertyValue};
+
+#[cfg(feature = "std")]
+use super::{write_property::write_value, RejectReason};
+#[cfg(feature = "std")]
+use crate::object::database::ObjectDatabase;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+/// Write Property Multiple request (confirmed service)
+#[derive(Debug, Clone, PartialEq)]
+pub struct WritePropertyMultipleRequest {
+    /// List of objects and property values to write
+    pub write_access_specifications: Vec<WriteAccessSpecification>,
+}
+
+/// An object and the property values to write to it
+#[derive(Debug, Clone, PartialEq)]
+pub struct WriteAccessSpecification {
+    /// Object identifier
+    pub object_identifier: ObjectIdentifier,
+    /// Property values to write
+    pub list_of_properties: Vec<BacnetPropertyValue>,
+}
+
+/// A property value to write (BACnetPropertyValue)
+#[derive(Debug, Clone, PartialEq)]
+pub struct BacnetPropertyValue {
+    /// Property identifier
+    pub property_identifier: u32,
+    /// Property array index (optional)
+    pub property_array_index: Option<u32>,
+    /// Value to write
+    pub value: PropertyValue,
+    /// Priority (optional, 1-16)
+    pub priority: Option<u8>,
+}
+
+/// Reference to a property of an object (BACnetObjectPropertyReference)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ObjectPropertyReference {
+    /// Object identifier
+    pub object_identifier: ObjectIdentifier,
+    /// Property identifier
+    pub property_identifier: u32,
+    /// Property array index (optional)
+    pub property_array_index: Option<u32>,
+}
+
+/// Error returned when a Write Property Multiple request stops at a failed write
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WritePropertyMultipleError {
+    /// Why the write failed
+    pub error: PropertyAccessError,
+    /// The property whose write failed
+    pub first_failed_write_attempt: ObjectPropertyReference,
+}
+
+impl WritePropertyMultipleRequest {
+    /// Create a new Write Property Multiple request
+    pub fn new(write_access_specifications: Vec<WriteAccessSpecification>) -> Self {
+        Self {
+            write_access_specifications,
+        }
+    }
+
+    /// Encode the Write Property Multiple request
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        self.write_access_specifications
+            .iter()
+            .try_for_each(|spec| spec.encode(buffer))
+    }
+
+    /// Decode a Write Property Multiple request
+    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
+        let mut write_access_specifications = Vec::new();
+        let mut pos = 0;
+        while pos < data.len() {
+            let (spec, consumed) = WriteAccessSpecification::decode(&data[pos..])?;
+            write_access_specifications.push(spec);
+            pos += consumed;
+        }
+        if write_access_specifications.is_empty() {
+            return Err(EncodingError::UnexpectedEndOfData);
+        }
+        Ok(Self::new(write_access_specifications))
+    }
+}
+
+impl WriteAccessSpecification {
+    /// Create a new write access specification
+    pub fn new(
+        object_identifier: ObjectIdentifier,
+        list_of_properties: Vec<BacnetPropertyValue>,
+    ) -> Self {
+        Self {
+            object_identifier,
+            list_of_properties,
+        }
+    }
+
+    /// Encode the specification
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        buffer.extend_from_slice(&encode_context_object_id(
+            u16::from(self.object_identifier.object_type),
+            self.object_identifier.instance,
+            0,
+        )?);
+
+        // List of properties - context tag 1
+        encode_opening_tag(buffer, 1)?;
+        for property in &self.list_of_properties {
+            property.encode(buffer)?;
+        }
+        encode_closing_tag(buffer, 1)?;
+
+        Ok(())
+    }
+
+    /// Decode a specification, returning it with the number of bytes consumed
+    pub fn decode(data: &[u8]) -> EncodingResult<(Self, usize)> {
+        let ((object_type, instance), mut pos) = decode_context_object_id(data, 0)?;
+        let object_type =
+            ObjectType::try_from(object_type).map_err(|_| EncodingError::ValueOutOfRange)?;
+
+        // List of properties - context tag 1
+        expect_tag(data, pos, 0x1E)?;
+        pos += 1;
+        let mut list_of_properties = Vec::new();
+        while data.get(pos).is_some_and(|&tag| tag != 0x1F) {
+            let (property, consumed) = BacnetPropertyValue::decode(&data[pos..])?;
+            list_of_properties.push(property);
+            pos += consumed;
+        }
+        expect_tag(data, pos, 0x1F)?;
+        pos += 1;
+
+        Ok((
+            Self::new(
+                ObjectIdentifier::new(object_type, instance),
+                list_of_properties,
+            ),
+            pos,
+        ))
+    }
+}
+
+impl BacnetPropertyValue {
+    /// Create a property value to write without array index or priority
+    pub fn new(property_identifier: u32, value: PropertyValue) -> Self {
+        Self {
+            property_identifier,
+            property_array_index: None,
+            value,
+            priority: None,
+        }
+    }
+
+    /// Encode the property value
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        buffer.extend_from_slice(&encode_context_enumerated(self.property_identifier, 0)?);
+        if let Some(array_index) = self.property_array_index {
+            buffer.extend_from_slice(&encode_context_unsigned(array_index, 1)?);
+        }
+
+        // Value - context tag 2
+        encode_opening_tag(buffer, 2)?;
+        encode_property_value(buffer, &self.value)?;
+        encode_closing_tag(buffer, 2)?;
+
+        if let Some(priority) = self.priority {
+            buffer.extend_from_slice(&encode_context_unsigned(u32::from(priority), 3)?);
+        }
+
+        Ok(())
+    }
+
+    /// Decode a property value, returning it with the number of bytes consumed
+    pub fn decode(data: &[u8]) -> EncodingResult<(Self, usize)> {
+        let (property_identifier, mut pos) = decode_context_enumerated(data, 0)?;
+        let property_array_index = match decode_context_unsigned(&data[pos..], 1) {
+            Ok((array_index, consumed)) => {
+                pos += consumed;
+                (array_index != BACNET_ARRAY_ALL).then_some(array_index)
+            }
+            Err(_) => None,
+        };
+
+        // Value - context tag 2
+        expect_tag(data, pos, 0x2E)?;
+        pos += 1;
+        let (value, consumed) = decode_property_value(&data[pos..])?;
+        pos += consumed;
+        expect_tag(data, pos, 0x2F)?;
+        pos += 1;
+
+        let priority = match decode_context_unsigned(&data[pos..], 3) {
+            Ok((priority, consumed)) => {
+                pos += consumed;
+                Some(u8::try_from(priority).map_err(|_| EncodingError::ValueOutOfRange)?)
+            }
+            Err(_) => None,
+        };
+
+        Ok((
+            Self {
+                property_identifier,
+                property_array_index,
+                value,
+                priority,
+            },
+            pos,
+        ))
+    }
+}
+
+impl ObjectPropertyReference {
+    /// Encode the reference with context tags 0-2
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        buffer.extend_from_slice(&encode_context_object_id(
+            u16::from(self.object_identifier.object_type),
+            self.object_identifier.instance,
+            0,
+        )?);
+        buffer.extend_from_slice(&encode_context_enumerated(self.property_identifier, 1)?);
+        if let Some(array_index) = self.property_array_index {
+            buffer.extend_from_slice(&encode_context_unsigned(array_index, 2)?);
+        }
+        Ok(())
+    }
+
+    /// Decode a reference, returning it with the number of bytes consumed
+    pub fn decode(data: &[u8]) -> EncodingResult<(Self, usize)> {
+        let ((object_type, instance), mut pos) = decode_context_object_id(data, 0)?;
+        let object_type =
+            ObjectType::try_from(object_type).map_err(|_| EncodingError::ValueOutOfRange)?;
+        let (property_identifier, consumed) = decode_context_enumerated(&data[pos..], 1)?;
+        pos += consumed;
+        let property_array_index = match decode_context_unsigned(&data[pos..], 2) {
+            Ok((array_index, consumed)) => {
+                pos += consumed;
+                Some(array_index)
+            }
+            Err(_) => None,
+        };
+        Ok((
+            Self {
+                object_identifier: ObjectIdentifier::new(object_type, instance),
+                property_identifier,
+                property_array_index,
+            },
+            pos,
+        ))
+    }
+}
+
+impl WritePropertyMultipleError {
+    /// Encode the error (WritePropertyMultiple-Error)
+    pub fn encode(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        // Error type - context tag 0
+        encode_opening_tag(buffer, 0)?;
+        encode_enumerated(buffer, self.error.error_class)?;
+        encode_enumerated(buffer, self.error.error_code)?;
+        encode_closing_tag(buffer, 0)?;
+
+        self.encode_parameters(buffer)
+    }
+
+    /// Decode the error
+    pub fn decode(data: &[u8]) -> EncodingResult<Self> {
+        expect_tag(data, 0, 0x0E)?;
+        let mut pos = 1;
+        let (error_class, consumed) = decode_enumerated(&data[pos..])?;
+        pos += consumed;
+        let (error_code, consumed) = decode_enumerated(&data[pos..])?;
+        pos += consumed;
+        expect_tag(data, pos, 0x0F)?;
+        pos += 1;
+
+        Self::decode_parameters(
+            PropertyAccessError {
+                error_class,
+                error_code,
+            },
+            &data[pos..],
+        )
+    }
+
+    /// An Error PDU reporting this failure for the request `invoke_id`
+    pub fn to_apdu(&self, invoke_id: u8) -> EncodingResult<Apdu> {
+        let mut parameters = Vec::new();
+        self.encode_parameters(&mut parameters)?;
+        Ok(Apdu::error_with_parameters(
+            invoke_id,
+            ConfirmedServiceChoice::WritePropertyMultiple as u8,
+            self.error,
+            parameters,
+        ))
+    }
+
+    /// The failure reported by a WritePropertyMultiple Error PDU, if `apdu`
+    /// is one
+    pub fn from_apdu(apdu: &Apdu) -> Option<Self> {
+        match apdu {
+            Apdu::Error {
+                service_choice,
+                error_class,
+                error_code,
+                error_parameters,
+                ..
+            } if *service_choice == ConfirmedServiceChoice::WritePropertyMultiple as u8 => {
+                let error = PropertyAccessError {
+                    error_class: *error_class,
+                    error_code: *error_code,
+                };
+                Self::decode_parameters(error, error_parameters).ok()
+            }
+            _ => None,
+        }
+    }
+
+    fn encode_parameters(&self, buffer: &mut Vec<u8>) -> EncodingResult<()> {
+        // First failed write attempt - context tag 1
+        encode_opening_tag(buffer, 1)?;
+        self.first_failed_write_attempt.encode(buffer)?;
+        encode_closing_tag(buffer, 1)
+    }
+
+    fn decode_parameters(error: PropertyAccessError, data: &[u8]) -> EncodingResult<Self> {
+        expect_tag(data, 0, 0x1E)?;
+        let (first_failed_write_attempt, consumed) = ObjectPropertyReference::decode(&data[1..])?;
+        expect_tag(data, 1 + consumed, 0x1F)?;
+
+        Ok(Self {
+            error,
+            first_failed_write_attempt,
+        })
+    }
+}
+
+/// Apply a Write Property Multiple request to an object database
+///
+/// Writes are applied in order. The first one that fails stops the request
+/// and is reported with its error; the writes before it are kept.
+#[cfg(feature = "std")]
+pub fn write_property_multiple(
+    database: &ObjectDatabase,
+    request: &WritePropertyMultipleRequest,
+) -> core::result::Result<(), WritePropertyMultipleError> {
+    for spec in &request.write_access_specifications {
+        for property in &spec.list_of_properties {
+            write_value(
+                database,
+                spec.object_identifier,
+                property.property_identifier,
+                property.property_array_index,
+                property.priority,
+                property.value.clone(),
+            )
+            .map_err(|error| WritePropertyMultipleError {
+                error,
+                first_failed_write_attempt: ObjectPropertyReference {
+                    object_identifier: spec.object_identifier,
+                    property_identifier: property.property_identifier,
+                    property_array_index: property.property_array_index,
+                },
+            })?;
+        }
+    }
+    Ok(())
+}
+
+/// Answer a Write Property Multiple request from an object database
+///
+/// Returns a SimpleAck once every value is written, an Error PDU naming the
+/// first failed write and why it failed, or a Reject PDU if the request cannot
+/// be decoded.
+#[cfg(feature = "std")]
+pub fn handle_write_property_multiple(
+    database: &ObjectDatabase,
+    invoke_id: u8,
+    service_data: &[u8],
+) -> Apdu {
+    let service_choice = ConfirmedServiceChoice::WritePropertyMultiple as u8;
+    let request = match WritePropertyMultipleRequest::decode(service_data) {
+        Ok(request) => request,
+        Err(error) => return Apdu::reject(invoke_id, RejectReason::for_decode_error(&error)),
+    };
+
+    match write_property_multiple(database, &request) {
+        Ok(()) => Apdu::SimpleAck {
+            invoke_id,
+            service_choice,
+        },
+        Err(failure) => failure.to_apdu(invoke_id).unwrap_or_else(|error| {
+            Apdu::abort(true, invoke_id, AbortReason::for_encode_error(&error))
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::PropertyIdentifier;
+
+    #[test]
+    fn test_request_round_trip() {
+        let mut setpoint = BacnetPropertyValue::new(
+            u32::from(PropertyIdentifier::PresentValue),
+            PropertyValue::Real(21.0),
+        );
+        setpoint.priority = Some(8);
+        let request = WritePropertyMultipleRequest::new(vec![WriteAccessSpecification::new(
+            ObjectIdentifier::new(ObjectType::AnalogValue, 2),
+            vec![
+                setpoint,
+                BacnetPropertyValue::new(
+                    u32::from(PropertyIdentifier::Description),
+                    PropertyValue::CharacterString(String::from("Setpoint")),
+                ),
+            ],
+        )]);
+
+        let mut buffer = Vec::new();
+        request.encode(&mut buffer).unwrap();
+        assert_eq!(
+            WritePropertyMultipleRequest::decode(&buffer).unwrap(),
+            request
+        );
+    }
+
+    #[test]
+    fn test_error_round_trip() {
+        let error = WritePropertyMultipleError {
+            error: PropertyAccessError {
+                error_class: 2,
+                error_code: 40,
+            },
+            first_failed_write_attempt: ObjectPropertyReference {
+                object_identifier: ObjectIdentifier::new(ObjectType::AnalogInput, 4),
+                property_identifier: u32::from(PropertyIdentifier::PresentValue),
+                property_array_index: None,
+            },
+        };
+        let mut buffer = Vec::new();
+        error.encode(&mut buffer).unwrap();
+        assert_eq!(WritePropertyMultipleError::decode(&buffer).unwrap(), error);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_first_failed_write() {
+        use crate::object::{analog::AnalogInput, Device};
+
+        let database = ObjectDatabase::new(Device::new(1234, String::from("Device")));
+        database
+            .add_object(Box::new(AnalogInput::new(1, String::from("AI-1"))))
+            .unwrap();
+        let ai = ObjectIdentifier::new(ObjectType::AnalogInput, 1);
+        let request = WritePropertyMultipleRequest::new(vec![WriteAccessSpecification::new(
+            ai,
+            vec![
+                BacnetPropertyValue::new(
+                    u32::from(PropertyIdentifier::Description),
+                    PropertyValue::CharacterString(String::from("Outdoor air")),
+                ),
+                BacnetPropertyValue::new(
+                    u32::from(PropertyIdentifier::ObjectType),
+                    PropertyValue::Enumerated(2),
+                ),
+                BacnetPropertyValue::new(
+                    u32::from(PropertyIdentifier::Description),
+                    PropertyValue::CharacterString(String::from("Never written")),
+                ),
+            ],
+        )]);
+
+        let failure = write_property_multiple(&database, &request).unwrap_err();
+        assert_eq!(
+            failure.first_failed_write_attempt.property_identifier,
+            u32::from(PropertyIdentifier::ObjectType)
+        );
+        // The write before the failure stays; the one after is not attempted
+        assert_eq!(
+            database
+                .get_property(ai, PropertyIdentifier::Description)
+                .unwrap(),
+            PropertyValue::CharacterString(String::from("Outdoor air"))
+        );
+
+        let mut service_data = Vec::new();
+        request.encode(&mut service_data).unwrap();
+        let reply = handle_write_property_multiple(&database, 7, &service_data);
+        assert!(matches!(
+            reply,
+            Apdu::Error {
+                invoke_id: 7,
+                service_choice: 16,
+                ..
+            }
+        ));
+        // The failing object and property reach the client
+        let received = Apdu::decode(&reply.encode()).unwrap();
+        assert_eq!(
+            WritePropertyMultipleError::from_apdu(&received),
+            Some(failure)
+        );
+        assert_eq!(failure.first_failed_write_attempt.object_identifier, ai);
+    }
+}
@@ -197,9 +197,31 @@ use std::{
 #[cfg(not(feature = "std"))]
 use alloc::collections::BTreeMap as HashMap;
 
-/// Calculate CRC-16 for MS/TP frames
+/// Calculate the CRC-8 of an MS/TP frame header (Clause 9.5.2, Annex G.1)
 ///
-/// Uses the polynomial x^16 + x^15 + x^2 + 1 (0xA001)
+/// Uses the polynomial x^8 + x^7 + 1, bit-reversed. Returns the ones
+/// complement that is transmitted after the header.
+pub fn crc8_mstp(data: &[u8]) -> u8 {
+    let mut crc = 0xFFu8;
+
+    for byte in data {
+        crc ^= *byte;
+        for _ in 0..8 {
+            if crc & 0x01 != 0 {
+                crc = (crc >> 1) ^ 0x81;
+            } else {
+                crc >>= 1;
+            }
+        }
+    }
+
+    !crc
+}
+
+/// Calculate CRC-16 for MS/TP frame data (Clause 9.5.2, Annex G.2)
+///
+/// Uses the CCITT polynomial x^16 + x^12 + x^5 + 1, bit-reversed (0x8408).
+/// Returns the ones complement that is transmitted, low octet first.
 pub fn crc16_mstp(data: &[u8]) -> u16 {
     let mut crc = 0xFFFF;
 
@@ -207,7 +229,28 @@ pub fn crc16_mstp(data: &[u8]) -> u16 {
         crc ^= *byte as u16;
         for _ in 0..8 {
             if crc & 0x0001 != 0 {
-                crc = (crc >> 1) ^ 0xA001;
+                crc = (crc >> 1) ^ 0x8408;
+            } else {
+                crc >>= 1;
+            }
+        }
+    }
+
+    !crc
+}
+
+/// Calculate CRC-32K (Koopman) for MS/TP extended frames (Clause 9.10)
+///
+/// Uses the polynomial 0x741B8CD7, bit-reversed (0xEB31D82E). Returns the
+/// ones complement that is transmitted, low octet first.
+pub fn crc32k(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFFFFFF;
+
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            if crc & 1 != 0 {
+                crc = (crc >> 1) ^ 0xEB31D82E;
             } else {
                 crc >>= 1;
             }